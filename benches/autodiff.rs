@@ -0,0 +1,80 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Automatic differentiation: recording and accumulating a reverse-mode
+//! tape over input sizes, against forward-mode dual numbers.
+//!
+//! Run with `cargo bench --bench autodiff`.
+
+// Mathematical notation, as in the library.
+#![allow(non_snake_case)]
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use RustQuant::autodiff::{Accumulate, Dual, Graph, Variable};
+use RustQuant::instruments::options::{generalised_black_scholes_merton, TypeFlag};
+
+/// Sum of `x_i * exp(x_i)`, with one tape entry per operation.
+fn objective<'v>(x: &[Variable<'v>]) -> Variable<'v> {
+    x.iter().map(|&x| x * x.exp()).reduce(|a, b| a + b).unwrap()
+}
+
+fn reverse_mode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("autodiff/reverse");
+
+    for n in [10, 100, 1_000, 10_000] {
+        let values: Vec<f64> = (0..n).map(|i| i as f64 / n as f64).collect();
+
+        group.bench_with_input(BenchmarkId::new("record", n), &values, |b, values| {
+            b.iter(|| {
+                let graph = Graph::new();
+                let x = graph.vars(values);
+                black_box(objective(&x).value)
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("accumulate", n), &values, |b, values| {
+            b.iter(|| {
+                let graph = Graph::new();
+                let x = graph.vars(values);
+                black_box(objective(&x).accumulate())
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn black_scholes_delta(c: &mut Criterion) {
+    let mut group = c.benchmark_group("autodiff/black_scholes_delta");
+
+    group.bench_function("reverse", |b| {
+        b.iter(|| {
+            let graph = Graph::new();
+            let S = graph.var(black_box(100.0));
+            let [K, v, r, T] = [110.0, 0.2, 0.05, 0.5].map(|x| graph.var(x));
+
+            let price = generalised_black_scholes_merton(S, K, v, r, r, T, TypeFlag::Call);
+            black_box(price.accumulate())
+        });
+    });
+
+    group.bench_function("forward", |b| {
+        b.iter(|| {
+            let S = Dual::variable(black_box(100.0));
+            let [K, v, r, T] = [110.0, 0.2, 0.05, 0.5].map(Dual::constant);
+
+            black_box(generalised_black_scholes_merton(S, K, v, r, r, T, TypeFlag::Call).derivative)
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, reverse_mode, black_scholes_delta);
+criterion_main!(benches);
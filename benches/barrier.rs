@@ -0,0 +1,97 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Barrier option pricers: the closed form, the batch entry point over
+//! chain sizes, and the finite difference engine over grid sizes.
+//!
+//! Run with `cargo bench --bench barrier`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use RustQuant::instruments::options::{
+    AnalyticBarrierEngine, BarrierOption, BarrierType, FiniteDifferenceBarrierEngine,
+};
+
+const OPTION: BarrierOption = BarrierOption {
+    initial_price: 100.0,
+    strike_price: 100.0,
+    barrier: 95.0,
+    time_to_expiry: 0.5,
+    risk_free_rate: 0.08,
+    volatility: 0.25,
+    rebate: 3.0,
+    dividend_yield: 0.04,
+};
+
+fn analytic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("barrier/analytic");
+
+    for barrier_type in [BarrierType::CDO, BarrierType::CDI, BarrierType::PDO] {
+        group.bench_function(format!("{barrier_type:?}"), |b| {
+            b.iter(|| {
+                black_box(OPTION)
+                    .price_with(barrier_type, &AnalyticBarrierEngine)
+                    .unwrap()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("barrier/batch");
+
+    for n in [1_000, 100_000] {
+        let options: Vec<BarrierOption> = (0..n)
+            .map(|i| BarrierOption {
+                initial_price: 96.0 + 0.5 * (i % 100) as f64,
+                ..OPTION
+            })
+            .collect();
+
+        group.throughput(Throughput::Elements(n as u64));
+
+        for parallel in [false, true] {
+            let id = BenchmarkId::new(if parallel { "parallel" } else { "serial" }, n);
+
+            group.bench_with_input(id, &options, |b, options| {
+                b.iter(|| {
+                    BarrierOption::price_batch(black_box(options), BarrierType::CDO, parallel)
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+fn finite_difference(c: &mut Criterion) {
+    let mut group = c.benchmark_group("barrier/finite_difference");
+    group.sample_size(20);
+
+    for steps in [50, 100, 200, 400] {
+        let engine = FiniteDifferenceBarrierEngine {
+            time_steps: steps,
+            price_steps: 2 * steps,
+        };
+
+        group.bench_with_input(BenchmarkId::from_parameter(steps), &engine, |b, engine| {
+            b.iter(|| {
+                black_box(OPTION)
+                    .price_with(BarrierType::CDO, engine)
+                    .unwrap()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, analytic, batch, finite_difference);
+criterion_main!(benches);
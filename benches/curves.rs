@@ -0,0 +1,76 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Yield curves: building a curve from market tenors and rates, and
+//! reading discount factors off it, over the number of curve points.
+//! Curves are built directly from zero rates, as there is no bootstrapper
+//! from instrument quotes yet.
+//!
+//! Run with `cargo bench --bench curves`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use time::macros::date;
+use time::Date;
+use RustQuant::data::{Curve, YieldCurve};
+use RustQuant::time::Tenor;
+
+const TODAY: Date = date!(2024 - 01 - 02);
+
+/// Monthly tenors and an upward-sloping set of rates.
+fn market(points: usize) -> (Vec<Tenor>, Vec<f64>) {
+    let tenors = (1..=points as i32).map(Tenor::months).collect();
+    let rates = (0..points).map(|i| 0.03 + 1e-4 * i as f64).collect();
+
+    (tenors, rates)
+}
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("curves/build");
+
+    for points in [12, 60, 360] {
+        let (tenors, rates) = market(points);
+
+        group.bench_with_input(BenchmarkId::from_parameter(points), &points, |b, _| {
+            b.iter(|| {
+                YieldCurve::from_initial_date_tenors_and_rates(
+                    TODAY,
+                    black_box(&tenors),
+                    black_box(&rates),
+                )
+                .unwrap()
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn discount_factors(c: &mut Criterion) {
+    let mut group = c.benchmark_group("curves/discount_factors");
+
+    for points in [12, 60, 360] {
+        let (tenors, rates) = market(points);
+        let curve = YieldCurve::from_initial_date_tenors_and_rates(TODAY, &tenors, &rates).unwrap();
+
+        // One date per week across the curve.
+        let dates: Vec<Date> = (0..)
+            .map(|week| curve.initial_date() + time::Duration::weeks(week))
+            .take_while(|date| *date < curve.terminal_date())
+            .collect();
+
+        group.bench_with_input(BenchmarkId::from_parameter(points), &dates, |b, dates| {
+            b.iter(|| curve.discount_factors(black_box(dates)).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, build, discount_factors);
+criterion_main!(benches);
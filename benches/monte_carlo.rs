@@ -0,0 +1,81 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo throughput: the barrier engine over path counts, and
+//! Euler-Maruyama path generation, serial and parallel.
+//!
+//! Run with `cargo bench --bench monte_carlo`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use RustQuant::instruments::options::{BarrierOption, BarrierType, MonteCarloBarrierEngine};
+use RustQuant::models::GeometricBrownianMotion;
+use RustQuant::stochastics::StochasticProcess;
+
+const OPTION: BarrierOption = BarrierOption {
+    initial_price: 100.0,
+    strike_price: 100.0,
+    barrier: 95.0,
+    time_to_expiry: 0.5,
+    risk_free_rate: 0.08,
+    volatility: 0.25,
+    rebate: 3.0,
+    dividend_yield: 0.04,
+};
+
+fn barrier_engine(c: &mut Criterion) {
+    let mut group = c.benchmark_group("monte_carlo/barrier");
+    group.sample_size(10);
+
+    for n_paths in [1_000, 10_000, 100_000] {
+        let engine = MonteCarloBarrierEngine {
+            n_paths,
+            n_steps: 50,
+            seed: 42,
+        };
+
+        group.throughput(Throughput::Elements(n_paths as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_paths),
+            &engine,
+            |b, engine| {
+                b.iter(|| {
+                    black_box(OPTION)
+                        .price_with(BarrierType::CDO, engine)
+                        .unwrap()
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn euler_maruyama(c: &mut Criterion) {
+    let mut group = c.benchmark_group("monte_carlo/euler_maruyama");
+    group.sample_size(10);
+
+    let gbm = GeometricBrownianMotion::new(0.05, 0.2);
+
+    for m_paths in [100, 1_000, 10_000] {
+        group.throughput(Throughput::Elements(m_paths as u64));
+
+        for parallel in [false, true] {
+            let id = if parallel { "parallel" } else { "serial" };
+
+            group.bench_with_input(BenchmarkId::new(id, m_paths), &m_paths, |b, &m_paths| {
+                b.iter(|| gbm.euler_maruyama(100.0, 0.0, 1.0, 252, m_paths, parallel));
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, barrier_engine, euler_maruyama);
+criterion_main!(benches);
@@ -0,0 +1,118 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! The slice kernels in `math::simd` against a scalar loop, and chain
+//! pricing with the batch Black-Scholes-Merton pricer.
+//!
+//! Compare the two builds:
+//!
+//! ```sh
+//! cargo bench --bench simd
+//! RUSTFLAGS="-C target-cpu=native" cargo bench --bench simd --features simd
+//! ```
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use RustQuant::instruments::options::{
+    black_scholes_merton_batch, generalised_black_scholes_merton, TypeFlag,
+};
+use RustQuant::math::{simd, Real};
+
+const N: usize = 100_000;
+
+/// Name, slice kernel, scalar reference, and inputs.
+type Case = (
+    &'static str,
+    fn(&[f64], &mut [f64]),
+    fn(f64) -> f64,
+    Vec<f64>,
+);
+
+fn grid(lo: f64, hi: f64) -> Vec<f64> {
+    (0..N)
+        .map(|i| lo + (hi - lo) * i as f64 / (N - 1) as f64)
+        .collect()
+}
+
+fn kernels(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd/kernels");
+    group.throughput(Throughput::Elements(N as u64));
+
+    let mut out = vec![0.0; N];
+
+    let cases: [Case; 3] = [
+        ("exp", simd::exp, f64::exp, grid(-10.0, 10.0)),
+        ("ln", simd::ln, f64::ln, grid(0.01, 100.0)),
+        ("pnorm", simd::pnorm, f64::norm_cdf, grid(-8.0, 8.0)),
+    ];
+
+    for (name, kernel, scalar, x) in &cases {
+        group.bench_function(format!("{name}/slice"), |b| {
+            b.iter(|| kernel(black_box(x), &mut out));
+        });
+
+        group.bench_function(format!("{name}/scalar"), |b| {
+            b.iter(|| {
+                for (y, &x) in out.iter_mut().zip(black_box(x)) {
+                    *y = scalar(x);
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn chain(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd/black_scholes_merton");
+    group.throughput(Throughput::Elements(N as u64));
+
+    let spot = grid(50.0, 150.0);
+    let strike = vec![100.0; N];
+    let volatility = grid(0.1, 0.6);
+    let rate = vec![0.05; N];
+    let expiry = grid(0.1, 2.0);
+
+    group.bench_function("batch", |b| {
+        b.iter(|| {
+            black_scholes_merton_batch(
+                black_box(&spot),
+                &strike,
+                &volatility,
+                &rate,
+                &rate,
+                &expiry,
+                TypeFlag::Call,
+                false,
+            )
+        });
+    });
+
+    group.bench_function("scalar", |b| {
+        b.iter(|| {
+            (0..N)
+                .map(|i| {
+                    generalised_black_scholes_merton(
+                        black_box(spot[i]),
+                        strike[i],
+                        volatility[i],
+                        rate[i],
+                        rate[i],
+                        expiry[i],
+                        TypeFlag::Call,
+                    )
+                })
+                .collect::<Vec<f64>>()
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, kernels, chain);
+criterion_main!(benches);
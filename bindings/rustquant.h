@@ -0,0 +1,212 @@
+/* RustQuant C interface. Generated by cbindgen, do not edit by hand. */
+
+#ifndef RUSTQUANT_H
+#define RUSTQUANT_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Barrier type codes: call/put, up/down, in/out.
+ */
+typedef enum RqBarrierType {
+  /**
+   * Call, up-and-in.
+   */
+  RQ_BARRIER_TYPE_CUI = 0,
+  /**
+   * Call, down-and-in.
+   */
+  RQ_BARRIER_TYPE_CDI = 1,
+  /**
+   * Call, up-and-out.
+   */
+  RQ_BARRIER_TYPE_CUO = 2,
+  /**
+   * Call, down-and-out.
+   */
+  RQ_BARRIER_TYPE_CDO = 3,
+  /**
+   * Put, up-and-in.
+   */
+  RQ_BARRIER_TYPE_PUI = 4,
+  /**
+   * Put, down-and-in.
+   */
+  RQ_BARRIER_TYPE_PDI = 5,
+  /**
+   * Put, up-and-out.
+   */
+  RQ_BARRIER_TYPE_PUO = 6,
+  /**
+   * Put, down-and-out.
+   */
+  RQ_BARRIER_TYPE_PDO = 7,
+} RqBarrierType;
+
+/**
+ * Option type codes.
+ */
+typedef enum RqOptionType {
+  /**
+   * Call option.
+   */
+  RQ_OPTION_TYPE_CALL = 0,
+  /**
+   * Put option.
+   */
+  RQ_OPTION_TYPE_PUT = 1,
+} RqOptionType;
+
+/**
+ * Result of a call.
+ */
+typedef enum RqStatus {
+  /**
+   * Success; the result was written to `out`.
+   */
+  RQ_STATUS_OK = 0,
+  /**
+   * An input is out of range, or an option or barrier type is unknown.
+   */
+  RQ_STATUS_INVALID_ARGUMENT = 1,
+  /**
+   * The price violates a no-arbitrage bound (implied volatility).
+   */
+  RQ_STATUS_ARBITRAGE_VIOLATION = 2,
+  /**
+   * The solver did not converge (implied volatility).
+   */
+  RQ_STATUS_NON_CONVERGENCE = 3,
+  /**
+   * `out` is null.
+   */
+  RQ_STATUS_NULL_POINTER = 4,
+  /**
+   * Any other error.
+   */
+  RQ_STATUS_INTERNAL_ERROR = 5,
+} RqStatus;
+
+/**
+ * Price and Greeks of a European option. Theta is per year.
+ */
+typedef struct RqGreeks {
+  /**
+   * Price.
+   */
+  double price;
+  /**
+   * Sensitivity to the spot.
+   */
+  double delta;
+  /**
+   * Sensitivity of delta to the spot.
+   */
+  double gamma;
+  /**
+   * Sensitivity to the volatility.
+   */
+  double vega;
+  /**
+   * Sensitivity to the passage of time.
+   */
+  double theta;
+  /**
+   * Sensitivity to the rate.
+   */
+  double rho;
+} RqGreeks;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Analytic price of a barrier option, with `barrier_type` one of
+ * [`RqBarrierType`].
+ *
+ * # Safety
+ *
+ * `out` must be null or valid for writes of a `double`.
+ */
+RqStatus rq_barrier(double spot,
+                    double strike,
+                    double barrier,
+                    double volatility,
+                    double rate,
+                    double time_to_expiry,
+                    double rebate,
+                    double dividend_yield,
+                    int barrier_type,
+                    double *out);
+
+/**
+ * Generalised Black-Scholes-Merton price of a European option.
+ * Pass `cost_of_carry = rate` for a non-dividend-paying underlying.
+ *
+ * # Safety
+ *
+ * `out` must be null or valid for writes of a `double`.
+ */
+RqStatus rq_black_scholes(double spot,
+                          double strike,
+                          double volatility,
+                          double rate,
+                          double cost_of_carry,
+                          double time_to_expiry,
+                          int option_type,
+                          double *out);
+
+/**
+ * Price and Greeks of a European option under generalised
+ * Black-Scholes-Merton.
+ *
+ * # Safety
+ *
+ * `out` must be null or valid for writes of an [`RqGreeks`].
+ */
+RqStatus rq_greeks(double spot,
+                   double strike,
+                   double volatility,
+                   double rate,
+                   double cost_of_carry,
+                   double time_to_expiry,
+                   int option_type,
+                   RqGreeks *out);
+
+/**
+ * Black-Scholes implied volatility of a European option price.
+ *
+ * # Safety
+ *
+ * `out` must be null or valid for writes of a `double`.
+ */
+RqStatus rq_implied_volatility(double price,
+                               double spot,
+                               double strike,
+                               double rate,
+                               double time_to_expiry,
+                               int option_type,
+                               double *out);
+
+/**
+ * Message of the last failed call on this thread (empty if none).
+ *
+ * The string is owned by the library, and is valid until the next call
+ * on this thread.
+ */
+const char *rq_last_error_message(void);
+
+/**
+ * Version of the library, e.g. `"0.1.0"`.
+ */
+const char *rq_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUSTQUANT_H */
@@ -0,0 +1,38 @@
+// To run this example, use:
+//      cargo run --example market_data --features=data
+
+use RustQuant::data::*;
+use time::macros::date;
+
+fn main() -> Result<(), RustQuant::error::RustQuantError> {
+    let downloader = YahooFinanceDownloader::new()?;
+
+    // Daily OHLCV bars, as a time series.
+    let bars = tokio_test::block_on(downloader.price_history(
+        "AAPL",
+        date!(2023 - 01 - 01),
+        date!(2024 - 01 - 01),
+    ))?;
+
+    // Monthly log-returns of the closing price.
+    let monthly = bars
+        .map(|bar| bar.close)
+        .resample(RustQuant::time::Frequency::Monthly, Aggregation::Last)?
+        .returns(ReturnsType::Logarithmic);
+
+    for (date, value) in monthly.iter() {
+        println!("{date}: {value:>8.4}");
+    }
+
+    // Option chain.
+    let chain = tokio_test::block_on(downloader.option_chain("AAPL"))?;
+
+    for quote in chain.iter().take(10) {
+        println!(
+            "{} {:?} {:>8.2} bid = {:?} ask = {:?}",
+            quote.expiry, quote.type_flag, quote.strike, quote.bid, quote.ask
+        );
+    }
+
+    Ok(())
+}
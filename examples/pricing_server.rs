@@ -0,0 +1,24 @@
+// To run this example, use:
+//      cargo run --example pricing_server --features=server
+//
+// Then, in another terminal:
+//
+//      curl -X POST localhost:8080/greeks -H 'Content-Type: application/json' -d '{
+//          "valuation_date": "2024-01-01",
+//          "option": {
+//              "cost_of_carry": 0.05,
+//              "underlying_price": 100.0,
+//              "strike_price": 100.0,
+//              "volatility": 0.2,
+//              "risk_free_rate": 0.05,
+//              "expiration_date": "2025-01-01",
+//              "option_type": "Call"
+//          }
+//      }'
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    println!("Listening on http://localhost:8080");
+
+    RustQuant::server::serve("0.0.0.0:8080").await
+}
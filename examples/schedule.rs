@@ -10,7 +10,7 @@
 use time::macros::date;
 use RustQuant::time::{
     countries::oceania::australia::AustraliaCalendar, DateRollingConvention, DayCountConvention,
-    Scheduler,
+    Frequency, RollConvention, ScheduleGeneratorBuilder, Scheduler, StubRule,
 };
 
 fn main() {
@@ -39,4 +39,23 @@ fn main() {
 
     // You will see that the dates that fall on New Year's Day are rolled to the next business day.
     println!("{}", schedule);
+
+    // Alternatively, generate the schedule from the effective date,
+    // termination date, and frequency, with a short stub at the front.
+    let schedule = ScheduleGeneratorBuilder::default()
+        .effective_date(date!(2024 - 02 - 15))
+        .termination_date(date!(2026 - 01 - 31))
+        .frequency(Frequency::SemiAnnually)
+        .roll_convention(RollConvention::EndOfMonth)
+        .stub_rule(StubRule::ShortFront)
+        .date_rolling_convention(DateRollingConvention::ModifiedFollowing)
+        .day_counting_convention(DayCountConvention::Actual_365_Fixed)
+        .build()
+        .unwrap()
+        .generate(&cal)
+        .unwrap();
+
+    for period in &schedule.periods {
+        println!("{:?}", period);
+    }
 }
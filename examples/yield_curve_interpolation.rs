@@ -22,7 +22,7 @@ fn main() {
         t0 + Duration::days(30 * 365),
     ];
 
-    let yield_curve = YieldCurve::from_dates_and_rates(&date_vec, &rate_vec);
+    let yield_curve = YieldCurve::from_dates_and_rates(&date_vec, &rate_vec).unwrap();
 
     // Create a vector of dates to interpolate the yield curve at.
     let dates_to_plot = (91..(30 * 365))
@@ -31,7 +31,7 @@ fn main() {
         .collect::<Vec<Date>>();
 
     // Compute the discount factors.
-    let discount_factors = yield_curve.discount_factors(&dates_to_plot);
+    let discount_factors = yield_curve.discount_factors(&dates_to_plot).unwrap();
 
     // Plot the interpolated yield curve.
     plot_vector!(discount_factors, "./images/interpolated_yield_curve.png");
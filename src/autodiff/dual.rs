@@ -0,0 +1,177 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Forward-mode automatic differentiation with dual numbers.
+//!
+//! A dual number `a + b ε` (with `ε² = 0`) carries a value and the
+//! derivative of that value with respect to a single seeded input.
+//! Unlike the reverse-mode [`Graph`](crate::autodiff::Graph), no tape is
+//! recorded, so it is cheap when only one sensitivity is needed.
+//!
+//! ```
+//! use RustQuant::autodiff::Dual;
+//! use RustQuant::assert_approx_equal;
+//!
+//! let x = Dual::variable(2.0);
+//! let y = x * x.exp();
+//!
+//! // d/dx (x e^x) = (1 + x) e^x
+//! assert_approx_equal!(y.derivative, 3.0 * 2_f64.exp(), 1e-12);
+//! ```
+
+use std::f64::consts::PI;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Dual number for forward-mode differentiation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Dual {
+    /// Value (real part).
+    pub value: f64,
+    /// Derivative with respect to the seeded input (dual part).
+    pub derivative: f64,
+}
+
+impl Dual {
+    /// New dual number from a value and derivative.
+    #[must_use]
+    pub const fn new(value: f64, derivative: f64) -> Self {
+        Self { value, derivative }
+    }
+
+    /// Constant (derivative zero).
+    #[must_use]
+    pub const fn constant(value: f64) -> Self {
+        Self::new(value, 0.0)
+    }
+
+    /// Input to differentiate with respect to (derivative one).
+    #[must_use]
+    pub const fn variable(value: f64) -> Self {
+        Self::new(value, 1.0)
+    }
+
+    // Chain rule: f(a + b ε) = f(a) + f'(a) b ε.
+    #[inline]
+    fn chain(self, value: f64, derivative: f64) -> Self {
+        Self::new(value, derivative * self.derivative)
+    }
+
+    /// Exponential function.
+    #[must_use]
+    pub fn exp(self) -> Self {
+        let e = self.value.exp();
+        self.chain(e, e)
+    }
+
+    /// Natural logarithm.
+    #[must_use]
+    pub fn ln(self) -> Self {
+        self.chain(self.value.ln(), self.value.recip())
+    }
+
+    /// Square root.
+    #[must_use]
+    pub fn sqrt(self) -> Self {
+        let s = self.value.sqrt();
+        self.chain(s, 0.5 / s)
+    }
+
+    /// Error function.
+    #[must_use]
+    pub fn erf(self) -> Self {
+        self.chain(
+            errorfunctions::RealErrorFunctions::erf(self.value),
+            2.0 * (-self.value * self.value).exp() / PI.sqrt(),
+        )
+    }
+
+    /// Complementary error function.
+    #[must_use]
+    pub fn erfc(self) -> Self {
+        self.chain(
+            errorfunctions::RealErrorFunctions::erfc(self.value),
+            -2.0 * (-self.value * self.value).exp() / PI.sqrt(),
+        )
+    }
+}
+
+impl Add for Dual {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl Div for Dual {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Self::new(
+            self.value / rhs.value,
+            (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value),
+        )
+    }
+}
+
+impl Neg for Dual {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.value, -self.derivative)
+    }
+}
+
+#[cfg(test)]
+mod tests_dual {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_arithmetic() {
+        let x = Dual::variable(3.0);
+        let c = Dual::constant(2.0);
+
+        let y = (x * x - c) / (x + c);
+
+        // y = (x^2 - 2) / (x + 2), y' = (x^2 + 4x + 2) / (x + 2)^2
+        assert_approx_equal!(y.value, 7.0 / 5.0, 1e-15);
+        assert_approx_equal!(y.derivative, 23.0 / 25.0, 1e-15);
+        assert_eq!((-x).derivative, -1.0);
+    }
+
+    #[test]
+    fn test_functions() {
+        let x = Dual::variable(0.7);
+
+        assert_approx_equal!(x.ln().derivative, 1.0 / 0.7, 1e-15);
+        assert_approx_equal!(x.sqrt().derivative, 0.5 / 0.7_f64.sqrt(), 1e-15);
+        assert_approx_equal!(x.exp().derivative, 0.7_f64.exp(), 1e-15);
+        assert_approx_equal!(x.erf().derivative, -x.erfc().derivative, 1e-15);
+    }
+}
@@ -15,7 +15,7 @@
 //!   - Implementation via Operator and Function Overloading.
 //!   - Useful when number of outputs is *smaller* than number of inputs.
 //!     - i.e for functions $f:\mathbb{R}^n \rightarrow \mathbb{R}^m$, where $m \ll n$
-//! - [ ] Forward (Tangent) Mode
+//! - [x] Forward (Tangent) Mode
 //!   - Implementation via Dual Numbers.
 //!   - Useful when number of outputs is *larger* than number of inputs.
 //!     - i.e. for functions $f:\mathbb{R}^n \rightarrow \mathbb{R}^m$, where $m \gg n$
@@ -65,6 +65,9 @@
 pub mod accumulate;
 pub use accumulate::*;
 
+pub mod dual;
+pub use dual::*;
+
 /// Implements the gradient computation.
 pub mod gradient;
 pub use gradient::*;
@@ -0,0 +1,445 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! `rustquant`: command-line pricing tool.
+//!
+//! Build with `cargo install RustQuant --features cli`, then:
+//!
+//! ```bash
+//! rustquant price portfolio.toml                 # values and Greeks (JSON)
+//! rustquant price portfolio.json --format csv    # ... as CSV
+//! rustquant cashflows portfolio.toml             # bond cashflows
+//! rustquant implied-vol --price 12.3 --spot 100 --strike 110 --expiry 0.89 --rate 0.03 --option-type call
+//! rustquant bootstrap quotes.toml                # zero curve from deposits and swaps
+//! ```
+//!
+//! Input files are JSON, or TOML if the extension is `.toml`. A portfolio
+//! file has a valuation date, optional discount curves (given either as
+//! zero rate pillars or as quotes to bootstrap), and a list of trades in
+//! the [`TradeBlotter`](RustQuant::instruments::TradeBlotter) schema:
+//!
+//! ```toml
+//! valuation_date = "2024-01-01"
+//!
+//! [[discount_curves]]
+//! currency = "USD"
+//! dates = ["2024-01-01", "2034-01-01"]
+//! rates = [0.05, 0.045]
+//!
+//! [[trades]]
+//! id = "T1"
+//! quantity = 10
+//! instrument = { type = "EuropeanOption", cost_of_carry = 0.05, underlying_price = 100.0, strike_price = 100.0, volatility = 0.2, risk_free_rate = 0.05, expiration_date = "2025-01-01", option_type = "Call" }
+//! ```
+//!
+//! A quotes file has a valuation date, an optional currency, and a list of
+//! [`BootstrapQuote`]s; `bootstrap` prints the curve in the `discount_curves`
+//! format above.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use time::Date;
+use RustQuant::cashflows::CashflowKind;
+use RustQuant::data::{bootstrap_yield_curve, BootstrapQuote, Curve, YieldCurve};
+use RustQuant::instruments::fx::currency::Currency;
+use RustQuant::instruments::options::{try_implied_volatility, TypeFlag};
+use RustQuant::instruments::{PricingContext, Trade, TradeInstrument};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// COMMAND LINE
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Price instruments, compute implied volatilities, and bootstrap curves.
+#[derive(Parser)]
+#[command(name = "rustquant", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Output format.
+    #[arg(long, value_enum, global = true, default_value_t = Format::Json)]
+    format: Format,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Value each trade in a portfolio file, with Greeks for options.
+    Price {
+        /// Portfolio file (JSON, or TOML if it ends in `.toml`).
+        input: PathBuf,
+    },
+
+    /// List the cashflows of the bonds in a portfolio file.
+    Cashflows {
+        /// Portfolio file (JSON, or TOML if it ends in `.toml`).
+        input: PathBuf,
+    },
+
+    /// Black-Scholes implied volatility of a European option price.
+    ImpliedVol {
+        /// Option price.
+        #[arg(long)]
+        price: f64,
+        /// Underlying price.
+        #[arg(long)]
+        spot: f64,
+        /// Strike price.
+        #[arg(long)]
+        strike: f64,
+        /// Time to expiry in years.
+        #[arg(long)]
+        expiry: f64,
+        /// Continuously compounded risk-free rate.
+        #[arg(long)]
+        rate: f64,
+        /// Call or put.
+        #[arg(long, value_enum)]
+        option_type: OptionType,
+    },
+
+    /// Bootstrap a zero curve from deposit and par swap quotes.
+    Bootstrap {
+        /// Quotes file (JSON, or TOML if it ends in `.toml`).
+        input: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OptionType {
+    Call,
+    Put,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// INPUT AND OUTPUT SCHEMAS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A portfolio: valuation date, discount curves, and trades.
+#[derive(Deserialize)]
+struct Portfolio {
+    valuation_date: Date,
+    #[serde(default)]
+    discount_curves: Vec<CurveInput>,
+    trades: Vec<Trade>,
+}
+
+/// A discount curve, as zero rate pillars or as quotes to bootstrap.
+#[derive(Serialize, Deserialize)]
+struct CurveInput {
+    currency: Currency,
+    #[serde(default)]
+    dates: Vec<Date>,
+    #[serde(default)]
+    rates: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    quotes: Vec<BootstrapQuote>,
+}
+
+/// Quotes to bootstrap a single curve.
+#[derive(Deserialize)]
+struct QuotesInput {
+    valuation_date: Date,
+    #[serde(default = "default_currency")]
+    currency: Currency,
+    quotes: Vec<BootstrapQuote>,
+}
+
+#[derive(Serialize)]
+struct PriceReport {
+    valuation_date: Date,
+    total: f64,
+    trades: Vec<TradeResult>,
+}
+
+#[derive(Serialize)]
+struct TradeResult {
+    id: String,
+    instrument: &'static str,
+    quantity: f64,
+    value: f64,
+    greeks: Option<Greeks>,
+}
+
+/// Black-Scholes-Merton Greeks, scaled by the trade quantity.
+#[derive(Serialize)]
+struct Greeks {
+    delta: f64,
+    gamma: f64,
+    vega: f64,
+    theta: f64,
+    rho: f64,
+}
+
+#[derive(Serialize)]
+struct CashflowRow {
+    id: String,
+    kind: CashflowKind,
+    payment_date: Date,
+    amount: f64,
+    discount_factor: Option<f64>,
+    present_value: Option<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// COMMANDS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+fn main() {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Price { input } => price(&input, cli.format),
+        Command::Cashflows { input } => cashflows(&input, cli.format),
+        Command::ImpliedVol {
+            price,
+            spot,
+            strike,
+            expiry,
+            rate,
+            option_type,
+        } => implied_vol(price, spot, strike, expiry, rate, option_type, cli.format),
+        Command::Bootstrap { input } => bootstrap(&input, cli.format),
+    };
+
+    if let Err(error) = result {
+        eprintln!("error: {error}");
+        std::process::exit(1);
+    }
+}
+
+fn price(input: &Path, format: Format) -> Result<(), Box<dyn Error>> {
+    let portfolio: Portfolio = read(input)?;
+    let ctx = context(&portfolio)?;
+
+    let mut trades = Vec::with_capacity(portfolio.trades.len());
+
+    for trade in &portfolio.trades {
+        let (instrument, greeks) = match &trade.instrument {
+            TradeInstrument::EuropeanOption(option) => {
+                let mut option = *option;
+                option.evaluation_date = option.evaluation_date.or(Some(ctx.valuation_date));
+
+                let greeks = Greeks {
+                    delta: trade.quantity * option.delta(),
+                    gamma: trade.quantity * option.gamma(),
+                    vega: trade.quantity * option.vega(),
+                    theta: trade.quantity * option.theta(),
+                    rho: trade.quantity * option.rho(),
+                };
+
+                ("EuropeanOption", Some(greeks))
+            }
+            TradeInstrument::BarrierOption(_) => ("BarrierOption", None),
+            TradeInstrument::FixedRateBond(_) => ("FixedRateBond", None),
+            TradeInstrument::Cash { .. } => ("Cash", None),
+        };
+
+        trades.push(TradeResult {
+            id: trade.id.clone(),
+            instrument,
+            quantity: trade.quantity,
+            value: trade.value(&ctx)?,
+            greeks,
+        });
+    }
+
+    match format {
+        Format::Json => print_json(&PriceReport {
+            valuation_date: ctx.valuation_date,
+            total: trades.iter().map(|t| t.value).sum(),
+            trades,
+        }),
+        Format::Csv => {
+            println!("id,instrument,quantity,value,delta,gamma,vega,theta,rho");
+
+            for t in &trades {
+                let greeks = t.greeks.as_ref().map_or_else(
+                    || ",,,,".to_string(),
+                    |g| format!("{},{},{},{},{}", g.delta, g.gamma, g.vega, g.theta, g.rho),
+                );
+
+                println!(
+                    "{},{},{},{},{}",
+                    csv_field(&t.id),
+                    t.instrument,
+                    t.quantity,
+                    t.value,
+                    greeks
+                );
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn cashflows(input: &Path, format: Format) -> Result<(), Box<dyn Error>> {
+    let portfolio: Portfolio = read(input)?;
+    let ctx = context(&portfolio)?;
+
+    let mut rows = Vec::new();
+
+    for trade in &portfolio.trades {
+        let TradeInstrument::FixedRateBond(bond) = &trade.instrument else {
+            continue;
+        };
+
+        let curve = ctx.discount_curve(&bond.currency).ok_or_else(|| {
+            format!(
+                "no discount curve for {} (trade {})",
+                bond.currency.code.alphabetic, trade.id
+            )
+        })?;
+
+        let mut bond = bond.to_coupon_bond(ctx.valuation_date);
+        bond.yield_curve = curve.clone();
+
+        for entry in bond.cashflow_report()?.entries {
+            rows.push(CashflowRow {
+                id: trade.id.clone(),
+                kind: entry.kind,
+                payment_date: entry.payment_date,
+                amount: trade.quantity * entry.amount,
+                discount_factor: entry.discount_factor,
+                present_value: entry.present_value().map(|pv| trade.quantity * pv),
+            });
+        }
+    }
+
+    match format {
+        Format::Json => print_json(&rows),
+        Format::Csv => {
+            println!("id,kind,payment_date,amount,discount_factor,present_value");
+
+            for row in &rows {
+                println!(
+                    "{},{:?},{},{},{},{}",
+                    csv_field(&row.id),
+                    row.kind,
+                    row.payment_date,
+                    row.amount,
+                    optional(row.discount_factor),
+                    optional(row.present_value)
+                );
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn implied_vol(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    expiry: f64,
+    rate: f64,
+    option_type: OptionType,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let flag = match option_type {
+        OptionType::Call => TypeFlag::Call,
+        OptionType::Put => TypeFlag::Put,
+    };
+
+    let sigma = try_implied_volatility(price, spot, strike, expiry, rate, flag)?;
+
+    match format {
+        Format::Json => print_json(&serde_json::json!({ "implied_volatility": sigma })),
+        Format::Csv => {
+            println!("implied_volatility\n{sigma}");
+            Ok(())
+        }
+    }
+}
+
+fn bootstrap(input: &Path, format: Format) -> Result<(), Box<dyn Error>> {
+    let input: QuotesInput = read(input)?;
+    let curve = bootstrap_yield_curve(input.valuation_date, &input.quotes)?;
+
+    match format {
+        Format::Json => print_json(&CurveInput {
+            currency: input.currency,
+            dates: curve.rates.keys().copied().collect(),
+            rates: curve.rates.values().copied().collect(),
+            quotes: Vec::new(),
+        }),
+        Format::Csv => {
+            println!("date,zero_rate,discount_factor");
+
+            for (date, rate) in &curve.rates {
+                println!("{date},{rate},{}", curve.discount_factor(*date)?);
+            }
+
+            Ok(())
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// HELPERS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+fn default_currency() -> Currency {
+    Currency::from_code("USD").unwrap()
+}
+
+/// Read a JSON file, or a TOML file if the extension is `.toml`.
+fn read<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, Box<dyn Error>> {
+    let text = std::fs::read_to_string(path)?;
+
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        Ok(toml::from_str(&text)?)
+    } else {
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+/// Pricing context with the portfolio's discount curves.
+fn context(portfolio: &Portfolio) -> Result<PricingContext, Box<dyn Error>> {
+    let mut ctx = PricingContext::new(portfolio.valuation_date);
+
+    for input in &portfolio.discount_curves {
+        let curve = if input.quotes.is_empty() {
+            YieldCurve::from_dates_and_rates(&input.dates, &input.rates)?
+        } else {
+            bootstrap_yield_curve(portfolio.valuation_date, &input.quotes)?
+        };
+
+        ctx = ctx.with_discount_curve(input.currency, curve);
+    }
+
+    Ok(ctx)
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn optional(value: Option<f64>) -> String {
+    value.map_or_else(String::new, |x| x.to_string())
+}
+
+/// Quote a CSV field if it contains a separator, quote, or newline.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,464 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Flat function layer for spreadsheets.
+//!
+//! One snake_case function per pricer, taking only `f64`s and `&str`s, in
+//! the shape spreadsheet add-in frameworks (e.g. `xladd`) register as
+//! worksheet functions:
+//!
+//! ```
+//! use RustQuant::excel;
+//!
+//! let price = excel::black_scholes(60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "call").unwrap();
+//! let delta = excel::black_scholes_greek("delta", 60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "call");
+//!
+//! // Errors are Excel error codes, not panics.
+//! let error = excel::black_scholes(-60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "call").unwrap_err();
+//! assert_eq!(error, excel::XlError::Value);
+//! assert_eq!(error.to_string(), "#VALUE!");
+//! ```
+//!
+//! Option types are `"call"` or `"put"`, barrier types codes such as
+//! `"CDO"` (call, down-and-out), and dates Excel serial numbers (days
+//! since 1899-12-30). A failing call returns an [`XlError`], which the
+//! add-in shows in the cell; [`last_error_message`] explains it. Panics
+//! are caught and reported as [`XlError::Num`].
+//!
+//! Requires the `excel` feature.
+
+use super::{barrier_option, bsm_greeks, parse_barrier_type, parse_type_flag, positive};
+use crate::error::RustQuantError;
+use crate::instruments::options::{
+    bachelier_price, generalised_black_scholes_merton, heston as heston_price,
+    try_implied_volatility, CashOrNothingOption, GapOption, TypeFlag,
+};
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use time::{Date, Duration};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TYPES
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Excel error values, with their `xlerr` codes as discriminants.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XlError {
+    /// `#DIV/0!`: unused by the pricers, included for completeness.
+    Div0 = 7,
+    /// `#VALUE!`: an input is out of range, or a type code is unknown.
+    Value = 15,
+    /// `#NUM!`: no-arbitrage violation, non-convergence, or any other
+    /// numerical failure (including caught panics).
+    Num = 36,
+    /// `#N/A`: a required input is missing.
+    NA = 42,
+}
+
+/// Result of a worksheet function.
+pub type XlResult = Result<f64, XlError>;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl XlError {
+    /// The `xlerr` code.
+    #[must_use]
+    pub const fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// The error as shown in a cell, e.g. `"#NUM!"`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Div0 => "#DIV/0!",
+            Self::Value => "#VALUE!",
+            Self::Num => "#NUM!",
+            Self::NA => "#N/A",
+        }
+    }
+}
+
+impl fmt::Display for XlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&RustQuantError> for XlError {
+    fn from(error: &RustQuantError) -> Self {
+        match error {
+            RustQuantError::InvalidArgument(_) => Self::Value,
+            RustQuantError::MissingInput(_) => Self::NA,
+            _ => Self::Num,
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Generalised Black-Scholes-Merton price of a European option.
+/// Pass `cost_of_carry = rate` for a non-dividend-paying underlying.
+pub fn black_scholes(
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> XlResult {
+    guard(|| {
+        Ok(generalised_black_scholes_merton(
+            positive("spot", spot)?,
+            positive("strike", strike)?,
+            positive("volatility", volatility)?,
+            rate,
+            cost_of_carry,
+            positive("time_to_expiry", time_to_expiry)?,
+            parse_type_flag(option_type)?,
+        ))
+    })
+}
+
+/// A single Greek of a European option under generalised
+/// Black-Scholes-Merton: `greek` is one of `"price"`, `"delta"`,
+/// `"gamma"`, `"vega"`, `"theta"` (per year), or `"rho"`.
+#[allow(clippy::too_many_arguments)]
+pub fn black_scholes_greek(
+    greek: &str,
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> XlResult {
+    guard(|| {
+        let index = ["price", "delta", "gamma", "vega", "theta", "rho"]
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(greek))
+            .ok_or_else(|| {
+                RustQuantError::InvalidArgument(format!(
+                    "unknown Greek {greek:?}, expected price, delta, gamma, vega, theta, or rho"
+                ))
+            })?;
+
+        let greeks = bsm_greeks(
+            positive("spot", spot)?,
+            positive("strike", strike)?,
+            positive("volatility", volatility)?,
+            rate,
+            Some(cost_of_carry),
+            positive("time_to_expiry", time_to_expiry)?,
+            parse_type_flag(option_type)?,
+        );
+
+        Ok(greeks[index])
+    })
+}
+
+/// Bachelier (normal model) price of a European option, with an absolute
+/// (not lognormal) `volatility`.
+pub fn bachelier(
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> XlResult {
+    guard(|| {
+        Ok(bachelier_price(
+            spot,
+            strike,
+            positive("volatility", volatility)?,
+            positive("time_to_expiry", time_to_expiry)?,
+            parse_type_flag(option_type)?,
+        ))
+    })
+}
+
+/// Black-Scholes implied volatility of a European option price.
+pub fn implied_volatility(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> XlResult {
+    guard(|| {
+        try_implied_volatility(
+            price,
+            spot,
+            strike,
+            time_to_expiry,
+            rate,
+            parse_type_flag(option_type)?,
+        )
+    })
+}
+
+/// Analytic price of a barrier option, with `barrier_type` a code such as
+/// `"CDO"` (call, down-and-out).
+#[allow(clippy::too_many_arguments)]
+pub fn barrier(
+    spot: f64,
+    strike: f64,
+    barrier: f64,
+    volatility: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    rebate: f64,
+    dividend_yield: f64,
+    barrier_type: &str,
+) -> XlResult {
+    guard(|| {
+        let barrier_type = parse_barrier_type(barrier_type)?;
+        let inputs = [spot, strike, barrier, volatility, rate, time_to_expiry];
+
+        barrier_option(&inputs, rebate, dividend_yield)?.price(barrier_type)
+    })
+}
+
+/// Price of a cash-or-nothing option paying `payout` if it expires in the
+/// money.
+#[allow(clippy::too_many_arguments)]
+pub fn cash_or_nothing(
+    spot: f64,
+    strike: f64,
+    payout: f64,
+    volatility: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> XlResult {
+    guard(|| {
+        let (call, put) = CashOrNothingOption {
+            initial_price: positive("spot", spot)?,
+            strike_price: positive("strike", strike)?,
+            payout_value: payout,
+            risk_free_rate: rate,
+            volatility: positive("volatility", volatility)?,
+            cost_of_carry,
+            time_to_maturity: positive("time_to_expiry", time_to_expiry)?,
+        }
+        .price();
+
+        Ok(select(parse_type_flag(option_type)?, call, put))
+    })
+}
+
+/// Price of a gap option: triggered at `trigger_strike`, paying off
+/// against `payoff_strike`.
+#[allow(clippy::too_many_arguments)]
+pub fn gap(
+    spot: f64,
+    trigger_strike: f64,
+    payoff_strike: f64,
+    volatility: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> XlResult {
+    guard(|| {
+        let (call, put) = GapOption {
+            initial_price: positive("spot", spot)?,
+            strike_1: positive("trigger_strike", trigger_strike)?,
+            strike_2: payoff_strike,
+            risk_free_rate: rate,
+            volatility: positive("volatility", volatility)?,
+            cost_of_carry,
+            time_to_maturity: positive("time_to_expiry", time_to_expiry)?,
+        }
+        .price();
+
+        Ok(select(parse_type_flag(option_type)?, call, put))
+    })
+}
+
+/// Heston (1993) price of a European option, with the valuation and
+/// expiry dates as Excel serial numbers.
+#[allow(clippy::too_many_arguments)]
+pub fn heston(
+    spot: f64,
+    initial_variance: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    correlation: f64,
+    vol_of_vol: f64,
+    mean_reversion: f64,
+    long_run_variance: f64,
+    valuation_date: f64,
+    expiry_date: f64,
+    option_type: &str,
+) -> XlResult {
+    guard(|| {
+        let flag = parse_type_flag(option_type)?;
+        let valuation_date = excel_date(valuation_date)?;
+        let expiry_date = excel_date(expiry_date)?;
+
+        if expiry_date <= valuation_date {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "expiry date {expiry_date} must be after the valuation date {valuation_date}"
+            )));
+        }
+
+        let (call, put) = heston_price(
+            positive("spot", spot)?,
+            positive("initial_variance", initial_variance)?,
+            positive("strike", strike)?,
+            rate,
+            dividend_yield,
+            correlation,
+            positive("vol_of_vol", vol_of_vol)?,
+            positive("mean_reversion", mean_reversion)?,
+            positive("long_run_variance", long_run_variance)?,
+            Some(valuation_date),
+            expiry_date,
+        );
+
+        Ok(select(flag, call, put))
+    })
+}
+
+/// Message of the last failed call on this thread (empty if none).
+#[must_use]
+pub fn last_error_message() -> String {
+    LAST_ERROR.with(|message| message.borrow().clone())
+}
+
+/// Version of the library, e.g. `"0.1.0"`.
+#[must_use]
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// HELPERS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Run `f`, mapping errors (and panics, which must not unwind into the
+/// host) to Excel error codes and storing the message.
+fn guard<F: FnOnce() -> Result<f64, RustQuantError>>(f: F) -> XlResult {
+    let (error, message) = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) if value.is_finite() => return Ok(value),
+        Ok(Ok(value)) => (XlError::Num, format!("result is not finite: {value}")),
+        Ok(Err(error)) => (XlError::from(&error), error.to_string()),
+        Err(_) => (XlError::Num, "RustQuant panicked".to_string()),
+    };
+
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+
+    Err(error)
+}
+
+/// Date from an Excel serial number (days since 1899-12-30, the 1900
+/// date system with its phantom 1900-02-29 accounted for).
+fn excel_date(serial: f64) -> Result<Date, RustQuantError> {
+    const EPOCH: Date = time::macros::date!(1899 - 12 - 30);
+
+    if !(1.0..=2_958_465.0).contains(&serial) {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "{serial} is not an Excel date serial number"
+        )));
+    }
+
+    Ok(EPOCH + Duration::days(serial.floor() as i64))
+}
+
+fn select(flag: TypeFlag, call: f64, put: f64) -> f64 {
+    match flag {
+        TypeFlag::Call => call,
+        TypeFlag::Put => put,
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_excel {
+    use super::*;
+
+    #[test]
+    fn test_prices() {
+        // Haug (2007), p. 3.
+        let price = black_scholes(60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "call").unwrap();
+        assert_approx_equal!(price, 2.1334, 1e-4);
+
+        let greek = black_scholes_greek("Price", 60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "c").unwrap();
+        assert_approx_equal!(greek, price, 1e-12);
+
+        let vol = implied_volatility(price, 60.0, 65.0, 0.08, 0.25, "call").unwrap();
+        assert_approx_equal!(vol, 0.3, 1e-8);
+
+        // Haug (2007), table 4-13.
+        let price = barrier(100.0, 90.0, 95.0, 0.25, 0.08, 0.5, 3.0, 0.04, "cdo").unwrap();
+        assert_approx_equal!(price, 9.0246, 1e-4);
+
+        // Haug (2007), p. 88.
+        let price = cash_or_nothing(100.0, 80.0, 10.0, 0.35, 0.06, 0.0, 0.75, "put").unwrap();
+        assert_approx_equal!(price, 2.6710, 1e-4);
+
+        // 2024-01-01 and 2025-01-01.
+        let call = heston(
+            100.0, 0.04, 100.0, 0.05, 0.0, -0.5, 0.3, 2.0, 0.04, 45292.0, 45658.0, "call",
+        );
+        assert!(call.unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = black_scholes(-60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "call").unwrap_err();
+        assert_eq!(error, XlError::Value);
+        assert_eq!(error.code(), 15);
+        assert!(last_error_message().contains("spot"));
+
+        let error = black_scholes(60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "straddle").unwrap_err();
+        assert_eq!(error, XlError::Value);
+
+        let error = black_scholes_greek("speed", 60.0, 65.0, 0.3, 0.08, 0.08, 0.25, "call");
+        assert_eq!(error, Err(XlError::Value));
+
+        let error = implied_volatility(70.0, 60.0, 65.0, 0.08, 0.25, "call").unwrap_err();
+        assert_eq!(error, XlError::Num);
+        assert_eq!(error.to_string(), "#NUM!");
+
+        let error = heston(
+            100.0, 0.04, 100.0, 0.05, 0.0, -0.5, 0.3, 2.0, 0.04, 45658.0, 45292.0, "call",
+        );
+        assert_eq!(error, Err(XlError::Value));
+    }
+
+    #[test]
+    fn test_excel_date() {
+        assert_eq!(
+            excel_date(45292.0).unwrap(),
+            time::macros::date!(2024 - 01 - 01)
+        );
+        assert!(excel_date(0.0).is_err());
+        assert!(excel_date(f64::NAN).is_err());
+    }
+}
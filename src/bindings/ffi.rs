@@ -0,0 +1,436 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! C interface.
+//!
+//! `extern "C"` functions for the analytic pricers, for Excel add-ins and
+//! C, C++, or C# systems. The header is `bindings/rustquant.h`, generated
+//! with [cbindgen](https://github.com/mozilla/cbindgen):
+//!
+//! ```sh
+//! cbindgen --config bindings/cbindgen.toml --output bindings/rustquant.h
+//! cargo rustc --release --lib --crate-type cdylib --features ffi
+//! ```
+//!
+//! Every function returns an [`RqStatus`] and writes its result through
+//! the `out` pointer, which is left untouched on failure. The message of
+//! the last failure on the calling thread is returned by
+//! [`rq_last_error_message`]. Option and barrier types are passed as
+//! integers, the values of [`RqOptionType`] and [`RqBarrierType`].
+//!
+//! # Safety
+//!
+//! `out` must be null (which is reported as [`RqStatus::NullPointer`]) or
+//! valid for writes of the result type.
+
+use super::{barrier_option, bsm_greeks, positive};
+use crate::error::RustQuantError;
+use crate::instruments::options::{
+    generalised_black_scholes_merton, try_implied_volatility, BarrierType, TypeFlag,
+};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TYPES
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Result of a call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RqStatus {
+    /// Success; the result was written to `out`.
+    Ok = 0,
+    /// An input is out of range, or an option or barrier type is unknown.
+    InvalidArgument = 1,
+    /// The price violates a no-arbitrage bound (implied volatility).
+    ArbitrageViolation = 2,
+    /// The solver did not converge (implied volatility).
+    NonConvergence = 3,
+    /// `out` is null.
+    NullPointer = 4,
+    /// Any other error.
+    InternalError = 5,
+}
+
+/// Option type codes.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RqOptionType {
+    /// Call option.
+    Call = 0,
+    /// Put option.
+    Put = 1,
+}
+
+/// Barrier type codes: call/put, up/down, in/out.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum RqBarrierType {
+    /// Call, up-and-in.
+    CUI = 0,
+    /// Call, down-and-in.
+    CDI = 1,
+    /// Call, up-and-out.
+    CUO = 2,
+    /// Call, down-and-out.
+    CDO = 3,
+    /// Put, up-and-in.
+    PUI = 4,
+    /// Put, down-and-in.
+    PDI = 5,
+    /// Put, up-and-out.
+    PUO = 6,
+    /// Put, down-and-out.
+    PDO = 7,
+}
+
+/// Price and Greeks of a European option. Theta is per year.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RqGreeks {
+    /// Price.
+    pub price: f64,
+    /// Sensitivity to the spot.
+    pub delta: f64,
+    /// Sensitivity of delta to the spot.
+    pub gamma: f64,
+    /// Sensitivity to the volatility.
+    pub vega: f64,
+    /// Sensitivity to the passage of time.
+    pub theta: f64,
+    /// Sensitivity to the rate.
+    pub rho: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Generalised Black-Scholes-Merton price of a European option.
+/// Pass `cost_of_carry = rate` for a non-dividend-paying underlying.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes of a `double`.
+#[no_mangle]
+pub unsafe extern "C" fn rq_black_scholes(
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    time_to_expiry: f64,
+    option_type: c_int,
+    out: *mut f64,
+) -> RqStatus {
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        write_result(out, || {
+            Ok(generalised_black_scholes_merton(
+                positive("spot", spot)?,
+                positive("strike", strike)?,
+                positive("volatility", volatility)?,
+                rate,
+                cost_of_carry,
+                positive("time_to_expiry", time_to_expiry)?,
+                type_flag(option_type)?,
+            ))
+        })
+    }
+}
+
+/// Analytic price of a barrier option, with `barrier_type` one of
+/// [`RqBarrierType`].
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes of a `double`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn rq_barrier(
+    spot: f64,
+    strike: f64,
+    barrier: f64,
+    volatility: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    rebate: f64,
+    dividend_yield: f64,
+    barrier_type: c_int,
+    out: *mut f64,
+) -> RqStatus {
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        write_result(out, || {
+            let barrier_type = self::barrier_type(barrier_type)?;
+            let inputs = [spot, strike, barrier, volatility, rate, time_to_expiry];
+
+            barrier_option(&inputs, rebate, dividend_yield)?.price(barrier_type)
+        })
+    }
+}
+
+/// Black-Scholes implied volatility of a European option price.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes of a `double`.
+#[no_mangle]
+pub unsafe extern "C" fn rq_implied_volatility(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    option_type: c_int,
+    out: *mut f64,
+) -> RqStatus {
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        write_result(out, || {
+            try_implied_volatility(
+                price,
+                spot,
+                strike,
+                time_to_expiry,
+                rate,
+                type_flag(option_type)?,
+            )
+        })
+    }
+}
+
+/// Price and Greeks of a European option under generalised
+/// Black-Scholes-Merton.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes of an [`RqGreeks`].
+#[no_mangle]
+pub unsafe extern "C" fn rq_greeks(
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    time_to_expiry: f64,
+    option_type: c_int,
+    out: *mut RqGreeks,
+) -> RqStatus {
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        write_result(out, || {
+            let [price, delta, gamma, vega, theta, rho] = bsm_greeks(
+                positive("spot", spot)?,
+                positive("strike", strike)?,
+                positive("volatility", volatility)?,
+                rate,
+                Some(cost_of_carry),
+                positive("time_to_expiry", time_to_expiry)?,
+                type_flag(option_type)?,
+            );
+
+            Ok(RqGreeks {
+                price,
+                delta,
+                gamma,
+                vega,
+                theta,
+                rho,
+            })
+        })
+    }
+}
+
+/// Message of the last failed call on this thread (empty if none).
+///
+/// The string is owned by the library, and is valid until the next call
+/// on this thread.
+#[no_mangle]
+pub extern "C" fn rq_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|message| message.borrow().as_ptr())
+}
+
+/// Version of the library, e.g. `"0.1.0"`.
+#[no_mangle]
+pub extern "C" fn rq_version() -> *const c_char {
+    VERSION.as_ptr()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// HELPERS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+const VERSION: &CStr =
+    match CStr::from_bytes_with_nul(concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes()) {
+        Ok(version) => version,
+        Err(_) => panic!("version contains a nul byte"),
+    };
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+/// Run `f` and write its value to `out`. Errors (and panics, which must
+/// not unwind into the caller) are stored as the last error message.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes of a `T`.
+unsafe fn write_result<T, F>(out: *mut T, f: F) -> RqStatus
+where
+    F: FnOnce() -> Result<T, RustQuantError>,
+{
+    let (status, message) = if out.is_null() {
+        (RqStatus::NullPointer, "`out` is null".to_string())
+    } else {
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(Ok(value)) => {
+                // SAFETY: `out` is not null, and the caller guarantees it is
+                // valid for writes.
+                unsafe { out.write(value) };
+                return RqStatus::Ok;
+            }
+            Ok(Err(error)) => (status(&error), error.to_string()),
+            Err(_) => (RqStatus::InternalError, "RustQuant panicked".to_string()),
+        }
+    };
+
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+
+    status
+}
+
+fn status(error: &RustQuantError) -> RqStatus {
+    match error {
+        RustQuantError::InvalidArgument(_) | RustQuantError::MissingInput(_) => {
+            RqStatus::InvalidArgument
+        }
+        RustQuantError::ArbitrageViolation(_) => RqStatus::ArbitrageViolation,
+        RustQuantError::NonConvergence(_) => RqStatus::NonConvergence,
+        _ => RqStatus::InternalError,
+    }
+}
+
+fn type_flag(code: c_int) -> Result<TypeFlag, RustQuantError> {
+    match code {
+        c if c == RqOptionType::Call as c_int => Ok(TypeFlag::Call),
+        c if c == RqOptionType::Put as c_int => Ok(TypeFlag::Put),
+        _ => Err(RustQuantError::InvalidArgument(format!(
+            "unknown option type {code}"
+        ))),
+    }
+}
+
+fn barrier_type(code: c_int) -> Result<BarrierType, RustQuantError> {
+    use RqBarrierType as Rq;
+
+    [
+        (Rq::CUI, BarrierType::CUI),
+        (Rq::CDI, BarrierType::CDI),
+        (Rq::CUO, BarrierType::CUO),
+        (Rq::CDO, BarrierType::CDO),
+        (Rq::PUI, BarrierType::PUI),
+        (Rq::PDI, BarrierType::PDI),
+        (Rq::PUO, BarrierType::PUO),
+        (Rq::PDO, BarrierType::PDO),
+    ]
+    .into_iter()
+    .find(|(rq, _)| *rq as c_int == code)
+    .map(|(_, barrier_type)| barrier_type)
+    .ok_or_else(|| RustQuantError::InvalidArgument(format!("unknown barrier type {code}")))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_ffi {
+    use super::*;
+    use std::ptr;
+
+    fn last_error() -> String {
+        // SAFETY: the message is a valid C string until the next call.
+        unsafe { CStr::from_ptr(rq_last_error_message()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_prices() {
+        let call = RqOptionType::Call as c_int;
+        let mut out = f64::NAN;
+
+        // SAFETY: `out` is valid for writes.
+        unsafe {
+            // Haug (2007), p. 3.
+            let status = rq_black_scholes(60.0, 65.0, 0.3, 0.08, 0.08, 0.25, call, &mut out);
+            assert_eq!(status, RqStatus::Ok);
+            assert_approx_equal!(out, 2.1334, 1e-4);
+
+            let price = out;
+            let status = rq_implied_volatility(price, 60.0, 65.0, 0.08, 0.25, call, &mut out);
+            assert_eq!(status, RqStatus::Ok);
+            assert_approx_equal!(out, 0.3, 1e-8);
+
+            // Haug (2007), table 4-13.
+            let cdo = RqBarrierType::CDO as c_int;
+            let status = rq_barrier(100.0, 90.0, 95.0, 0.25, 0.08, 0.5, 3.0, 0.04, cdo, &mut out);
+            assert_eq!(status, RqStatus::Ok);
+            assert_approx_equal!(out, 9.0246, 1e-4);
+
+            let mut greeks = RqGreeks::default();
+            let status = rq_greeks(60.0, 65.0, 0.3, 0.08, 0.08, 0.25, call, &mut greeks);
+            assert_eq!(status, RqStatus::Ok);
+            assert_approx_equal!(greeks.price, price, 1e-12);
+            assert!(greeks.delta > 0.0 && greeks.delta < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut out = -1.0;
+
+        // SAFETY: `out` is valid for writes, or null.
+        unsafe {
+            let status = rq_black_scholes(-60.0, 65.0, 0.3, 0.08, 0.08, 0.25, 0, &mut out);
+            assert_eq!(status, RqStatus::InvalidArgument);
+            assert!(last_error().contains("spot"));
+
+            let status = rq_black_scholes(60.0, 65.0, 0.3, 0.08, 0.08, 0.25, 7, &mut out);
+            assert_eq!(status, RqStatus::InvalidArgument);
+            assert!(last_error().contains("option type"));
+
+            let status = rq_barrier(100.0, 90.0, 95.0, 0.25, 0.08, 0.5, 3.0, 0.04, 8, &mut out);
+            assert_eq!(status, RqStatus::InvalidArgument);
+
+            let status = rq_implied_volatility(70.0, 60.0, 65.0, 0.08, 0.25, 0, &mut out);
+            assert_eq!(status, RqStatus::ArbitrageViolation);
+
+            let status = rq_black_scholes(60.0, 65.0, 0.3, 0.08, 0.08, 0.25, 0, ptr::null_mut());
+            assert_eq!(status, RqStatus::NullPointer);
+        }
+
+        // Left untouched on failure.
+        assert_eq!(out, -1.0);
+    }
+
+    #[test]
+    fn test_version() {
+        // SAFETY: the version is a static C string.
+        let version = unsafe { CStr::from_ptr(rq_version()) };
+
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,172 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bindings to other languages.
+//!
+//! Each binding is behind its own cargo feature, and wraps the analytic
+//! pricers in the calling conventions of the target language. The helpers
+//! here (argument parsing, Greeks) are shared between them, so that every
+//! binding accepts the same inputs and returns the same values.
+
+#[cfg(feature = "excel")]
+pub mod excel;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+use crate::autodiff::Dual;
+use crate::error::RustQuantError;
+use crate::instruments::options::{generalised_black_scholes_merton, BarrierOption, TypeFlag};
+use crate::math::Real;
+
+#[cfg(any(feature = "excel", feature = "python", feature = "wasm"))]
+use crate::instruments::options::BarrierType;
+
+/// Price, delta, gamma, vega, theta, and rho of a generalised
+/// Black-Scholes-Merton option. With `b = None` the cost of carry is the
+/// rate, so rho includes the carry sensitivity.
+fn bsm_greeks(S: f64, K: f64, v: f64, r: f64, b: Option<f64>, T: f64, flag: TypeFlag) -> [f64; 6] {
+    let price = |S: Dual, v: Dual, r: Dual, T: Dual| {
+        let b = b.map_or(r, Dual::constant);
+        generalised_black_scholes_merton(S, Dual::constant(K), v, r, b, T, flag)
+    };
+    let (x, c) = (Dual::variable, Dual::constant);
+
+    let delta = price(x(S), c(v), c(r), c(T));
+    let vega = price(c(S), x(v), c(r), c(T)).derivative;
+    let rho = price(c(S), c(v), x(r), c(T)).derivative;
+    let theta = -price(c(S), c(v), c(r), x(T)).derivative;
+
+    // Gamma is the same for calls and puts.
+    let b = b.unwrap_or(r);
+    let d1 = ((S / K).ln() + (b + 0.5 * v * v) * T) / (v * T.sqrt());
+    let gamma = ((b - r) * T).exp() * d1.norm_pdf() / (S * v * T.sqrt());
+
+    [delta.value, delta.derivative, gamma, vega, theta, rho]
+}
+
+/// Check that the argument `name` is positive and finite.
+fn positive(name: &str, x: f64) -> Result<f64, RustQuantError> {
+    if x > 0.0 && x.is_finite() {
+        Ok(x)
+    } else {
+        Err(RustQuantError::InvalidArgument(format!(
+            "`{name}` must be positive and finite, got {x}"
+        )))
+    }
+}
+
+/// Build a barrier option from `[S, X, H, v, r, T]`.
+fn barrier_option(
+    inputs: &[f64],
+    rebate: f64,
+    dividend_yield: f64,
+) -> Result<BarrierOption, RustQuantError> {
+    BarrierOption::builder()
+        .spot(inputs[0])
+        .strike(inputs[1])
+        .barrier(inputs[2])
+        .volatility(inputs[3])
+        .risk_free_rate(inputs[4])
+        .time_to_expiry(inputs[5])
+        .rebate(rebate)
+        .dividend_yield(dividend_yield)
+        .build()
+}
+
+/// `"call"` or `"put"` (or `"c"`, `"p"`), in any case.
+#[cfg(any(feature = "excel", feature = "python", feature = "wasm"))]
+fn parse_type_flag(option_type: &str) -> Result<TypeFlag, RustQuantError> {
+    match option_type.to_ascii_lowercase().as_str() {
+        "call" | "c" => Ok(TypeFlag::Call),
+        "put" | "p" => Ok(TypeFlag::Put),
+        _ => Err(RustQuantError::InvalidArgument(format!(
+            "option type must be \"call\" or \"put\", got {option_type:?}"
+        ))),
+    }
+}
+
+/// Barrier type from its code, e.g. `"CDO"` for a down-and-out call.
+#[cfg(any(feature = "excel", feature = "python", feature = "wasm"))]
+fn parse_barrier_type(code: &str) -> Result<BarrierType, RustQuantError> {
+    match code.to_ascii_uppercase().as_str() {
+        "CUI" => Ok(BarrierType::CUI),
+        "CDI" => Ok(BarrierType::CDI),
+        "CUO" => Ok(BarrierType::CUO),
+        "CDO" => Ok(BarrierType::CDO),
+        "PUI" => Ok(BarrierType::PUI),
+        "PDI" => Ok(BarrierType::PDI),
+        "PUO" => Ok(BarrierType::PUO),
+        "PDO" => Ok(BarrierType::PDO),
+        _ => Err(RustQuantError::InvalidArgument(format!(
+            "unknown barrier type {code:?}, expected e.g. \"CDO\" (call, down-and-out)"
+        ))),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bindings {
+    use super::*;
+    use crate::instruments::options::BlackScholesMerton;
+    use time::macros::date;
+
+    #[test]
+    #[cfg(any(feature = "excel", feature = "python", feature = "wasm"))]
+    fn test_parse_arguments() {
+        assert!(matches!(parse_barrier_type("cdo"), Ok(BarrierType::CDO)));
+        assert!(matches!(parse_barrier_type("PUI"), Ok(BarrierType::PUI)));
+        assert!(parse_barrier_type("CXO").is_err());
+
+        assert_eq!(parse_type_flag("Call").unwrap(), TypeFlag::Call);
+        assert_eq!(parse_type_flag("p").unwrap(), TypeFlag::Put);
+        assert!(parse_type_flag("straddle").is_err());
+    }
+
+    #[test]
+    fn test_positive() {
+        assert!(positive("spot", 1.0).is_ok());
+        assert!(positive("spot", 0.0).is_err());
+        assert!(positive("spot", f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_greeks_match_black_scholes_merton() {
+        let (S, K, v, r) = (60.0, 65.0, 0.3, 0.08);
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let option = BlackScholesMerton::new(
+                r,
+                S,
+                K,
+                v,
+                r,
+                Some(date!(2024 - 01 - 01)),
+                date!(2024 - 04 - 01),
+                flag,
+            );
+            let T = option.year_fraction();
+
+            let [price, delta, gamma, vega, theta, rho] = bsm_greeks(S, K, v, r, None, T, flag);
+
+            assert_approx_equal!(price, option.price(), 1e-10);
+            assert_approx_equal!(delta, option.delta(), 1e-10);
+            assert_approx_equal!(gamma, option.gamma(), 1e-10);
+            assert_approx_equal!(vega, option.vega(), 1e-10);
+            assert_approx_equal!(theta, option.theta(), 1e-10);
+            assert_approx_equal!(rho, option.rho(), 1e-10);
+        }
+    }
+}
@@ -0,0 +1,570 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Python bindings.
+//!
+//! Built by [maturin](https://www.maturin.rs) from the `python` feature
+//! (see `bindings/pyproject.toml`) as the `rustquant` extension module:
+//!
+//! ```python
+//! import numpy as np
+//! import rustquant as rq
+//!
+//! strikes = np.linspace(80.0, 120.0, 41)
+//! prices = rq.black_scholes(100.0, strikes, 0.2, 0.05, 1.0, "call")
+//! vols = rq.implied_volatility(prices, 100.0, strikes, 0.05, 1.0, "call")
+//! ```
+//!
+//! Numeric arguments of the pricing functions take either a float or a 1-D
+//! array (or list). Arrays must all have the same length, and floats are
+//! broadcast against them. The result is a float if every argument is a
+//! float, and a NumPy array otherwise. Invalid inputs raise `ValueError`.
+//!
+//! The loops run without holding the GIL.
+
+use super::{barrier_option, bsm_greeks, parse_barrier_type, parse_type_flag, positive};
+use crate::data::{Curve, YieldCurve};
+use crate::error::RustQuantError;
+use crate::instruments::options::{
+    bachelier_price, generalised_black_scholes_merton, try_implied_volatility, BarrierEngine,
+    MonteCarloBarrierEngine,
+};
+use numpy::{PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyFloat, PyInt, PyList, PyTuple};
+use time::{Date, Month};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// MODULE
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// RustQuant: quantitative finance tools.
+#[pymodule]
+#[pyo3(name = "rustquant")]
+fn rustquant(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(black_scholes, m)?)?;
+    m.add_function(wrap_pyfunction!(bachelier, m)?)?;
+    m.add_function(wrap_pyfunction!(barrier, m)?)?;
+    m.add_function(wrap_pyfunction!(barrier_monte_carlo, m)?)?;
+    m.add_function(wrap_pyfunction!(implied_volatility, m)?)?;
+    m.add_function(wrap_pyfunction!(greeks, m)?)?;
+    m.add_class::<PyYieldCurve>()?;
+
+    Ok(())
+}
+
+impl From<RustQuantError> for PyErr {
+    fn from(error: RustQuantError) -> Self {
+        PyValueError::new_err(error.to_string())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// PRICING FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Generalised Black-Scholes-Merton price of a European option.
+///
+/// `cost_of_carry` defaults to `rate` (no dividends).
+#[pyfunction]
+#[pyo3(signature = (spot, strike, volatility, rate, time_to_expiry, option_type = "call", cost_of_carry = None))]
+#[allow(clippy::too_many_arguments)]
+fn black_scholes(
+    py: Python<'_>,
+    spot: &Bound<'_, PyAny>,
+    strike: &Bound<'_, PyAny>,
+    volatility: &Bound<'_, PyAny>,
+    rate: &Bound<'_, PyAny>,
+    time_to_expiry: &Bound<'_, PyAny>,
+    option_type: &str,
+    cost_of_carry: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let flag = parse_type_flag(option_type)?;
+    let columns = bsm_columns(
+        spot,
+        strike,
+        volatility,
+        rate,
+        time_to_expiry,
+        cost_of_carry,
+    )?;
+
+    let (len, prices) = map_rows(py, &columns, |row| {
+        let [S, K, v, r, T, b] = [row[0], row[1], row[2], row[3], row[4], row[5]];
+        check_positive(&columns, row)?;
+
+        Ok(generalised_black_scholes_merton(S, K, v, r, b, T, flag))
+    })?;
+
+    Ok(output(py, len, prices))
+}
+
+/// Bachelier (normal model) price of a European option.
+#[pyfunction]
+#[pyo3(signature = (spot, strike, volatility, time_to_expiry, option_type = "call"))]
+fn bachelier(
+    py: Python<'_>,
+    spot: &Bound<'_, PyAny>,
+    strike: &Bound<'_, PyAny>,
+    volatility: &Bound<'_, PyAny>,
+    time_to_expiry: &Bound<'_, PyAny>,
+    option_type: &str,
+) -> PyResult<Py<PyAny>> {
+    let flag = parse_type_flag(option_type)?;
+    let columns = [
+        column("spot", spot)?,
+        column("strike", strike)?,
+        column("volatility", volatility)?,
+        column("time_to_expiry", time_to_expiry)?,
+    ];
+
+    let (len, prices) = map_rows(py, &columns, |row| {
+        check_positive(&columns[2..], &row[2..])?;
+
+        Ok(bachelier_price(row[0], row[1], row[2], row[3], flag))
+    })?;
+
+    Ok(output(py, len, prices))
+}
+
+/// Analytic price of a barrier option.
+///
+/// `barrier_type` is one of `"CUI"`, `"CDI"`, `"CUO"`, `"CDO"`, `"PUI"`,
+/// `"PDI"`, `"PUO"`, `"PDO"` (call/put, up/down, in/out).
+#[pyfunction]
+#[pyo3(signature = (spot, strike, barrier, volatility, rate, time_to_expiry, barrier_type, rebate = 0.0, dividend_yield = 0.0))]
+#[allow(clippy::too_many_arguments)]
+fn barrier(
+    py: Python<'_>,
+    spot: &Bound<'_, PyAny>,
+    strike: &Bound<'_, PyAny>,
+    barrier: &Bound<'_, PyAny>,
+    volatility: &Bound<'_, PyAny>,
+    rate: &Bound<'_, PyAny>,
+    time_to_expiry: &Bound<'_, PyAny>,
+    barrier_type: &str,
+    rebate: f64,
+    dividend_yield: f64,
+) -> PyResult<Py<PyAny>> {
+    let barrier_type = parse_barrier_type(barrier_type)?;
+    let columns = [
+        column("spot", spot)?,
+        column("strike", strike)?,
+        column("barrier", barrier)?,
+        column("volatility", volatility)?,
+        column("rate", rate)?,
+        column("time_to_expiry", time_to_expiry)?,
+    ];
+
+    let (len, prices) = map_rows(py, &columns, |row| {
+        barrier_option(row, rebate, dividend_yield)?.price(barrier_type)
+    })?;
+
+    Ok(output(py, len, prices))
+}
+
+/// Monte Carlo price of a barrier option, as `(price, standard_error)`.
+///
+/// Paths are monitored continuously with a Brownian bridge correction.
+#[pyfunction]
+#[pyo3(signature = (spot, strike, barrier, volatility, rate, time_to_expiry, barrier_type, rebate = 0.0, dividend_yield = 0.0, n_paths = 100_000, n_steps = 100, seed = 42))]
+#[allow(clippy::too_many_arguments)]
+fn barrier_monte_carlo(
+    py: Python<'_>,
+    spot: f64,
+    strike: f64,
+    barrier: f64,
+    volatility: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    barrier_type: &str,
+    rebate: f64,
+    dividend_yield: f64,
+    n_paths: usize,
+    n_steps: usize,
+    seed: u64,
+) -> PyResult<(f64, f64)> {
+    let barrier_type = parse_barrier_type(barrier_type)?;
+    let option = barrier_option(
+        &[spot, strike, barrier, volatility, rate, time_to_expiry],
+        rebate,
+        dividend_yield,
+    )?;
+    let engine = MonteCarloBarrierEngine {
+        n_paths,
+        n_steps,
+        seed,
+    };
+
+    let price = py.detach(|| engine.calculate(&option, barrier_type))?;
+
+    Ok((price.price, price.error.unwrap_or(0.0)))
+}
+
+/// Black-Scholes implied volatility of a European option price.
+#[pyfunction]
+#[pyo3(signature = (price, spot, strike, rate, time_to_expiry, option_type = "call"))]
+fn implied_volatility(
+    py: Python<'_>,
+    price: &Bound<'_, PyAny>,
+    spot: &Bound<'_, PyAny>,
+    strike: &Bound<'_, PyAny>,
+    rate: &Bound<'_, PyAny>,
+    time_to_expiry: &Bound<'_, PyAny>,
+    option_type: &str,
+) -> PyResult<Py<PyAny>> {
+    let flag = parse_type_flag(option_type)?;
+    let columns = [
+        column("price", price)?,
+        column("spot", spot)?,
+        column("strike", strike)?,
+        column("rate", rate)?,
+        column("time_to_expiry", time_to_expiry)?,
+    ];
+
+    let (len, vols) = map_rows(py, &columns, |row| {
+        try_implied_volatility(row[0], row[1], row[2], row[4], row[3], flag)
+    })?;
+
+    Ok(output(py, len, vols))
+}
+
+/// Price and Greeks of a European option under generalised
+/// Black-Scholes-Merton, as a dict with keys `price`, `delta`, `gamma`,
+/// `vega`, `theta`, and `rho`.
+///
+/// Delta, vega, theta, and rho are exact derivatives of the price
+/// (forward-mode automatic differentiation); theta is per year.
+#[pyfunction]
+#[pyo3(signature = (spot, strike, volatility, rate, time_to_expiry, option_type = "call", cost_of_carry = None))]
+#[allow(clippy::too_many_arguments)]
+fn greeks<'py>(
+    py: Python<'py>,
+    spot: &Bound<'py, PyAny>,
+    strike: &Bound<'py, PyAny>,
+    volatility: &Bound<'py, PyAny>,
+    rate: &Bound<'py, PyAny>,
+    time_to_expiry: &Bound<'py, PyAny>,
+    option_type: &str,
+    cost_of_carry: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let flag = parse_type_flag(option_type)?;
+    let carry_is_rate = cost_of_carry.is_none();
+    let columns = bsm_columns(
+        spot,
+        strike,
+        volatility,
+        rate,
+        time_to_expiry,
+        cost_of_carry,
+    )?;
+
+    let (len, rows) = map_rows(py, &columns, |row| {
+        check_positive(&columns, row)?;
+
+        let b = (!carry_is_rate).then_some(row[5]);
+        Ok(bsm_greeks(row[0], row[1], row[2], row[3], b, row[4], flag))
+    })?;
+
+    let dict = PyDict::new(py);
+    for (i, key) in GREEKS.iter().enumerate() {
+        let values = rows.iter().map(|greeks| greeks[i]).collect();
+        dict.set_item(key, output(py, len, values))?;
+    }
+
+    Ok(dict)
+}
+
+/// Keys of the dict returned by `greeks`, in the order of [`bsm_greeks`].
+const GREEKS: [&str; 6] = ["price", "delta", "gamma", "vega", "theta", "rho"];
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// CURVES
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Yield curve, linearly interpolated between its points.
+///
+/// Dates are ISO strings, `"YYYY-MM-DD"`.
+#[pyclass(name = "YieldCurve", module = "rustquant", frozen)]
+struct PyYieldCurve(YieldCurve);
+
+#[pymethods]
+impl PyYieldCurve {
+    #[new]
+    fn new(dates: Vec<String>, rates: Vec<f64>) -> PyResult<Self> {
+        let dates = dates
+            .iter()
+            .map(|date| parse_date(date))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self(YieldCurve::from_dates_and_rates(&dates, &rates)?))
+    }
+
+    /// First date of the curve.
+    #[getter]
+    fn initial_date(&self) -> String {
+        self.0.initial_date().to_string()
+    }
+
+    /// Last date of the curve.
+    #[getter]
+    fn terminal_date(&self) -> String {
+        self.0.terminal_date().to_string()
+    }
+
+    /// Interpolated rate at `date`.
+    fn rate(&self, date: &str) -> PyResult<f64> {
+        Ok(self.0.rate(self.date_in_range(date)?)?)
+    }
+
+    /// Discount factor at `date`.
+    fn discount_factor(&self, date: &str) -> PyResult<f64> {
+        Ok(self.0.discount_factor(self.date_in_range(date)?)?)
+    }
+
+    /// Discount factors at each of `dates`, as an array.
+    fn discount_factors<'py>(
+        &self,
+        py: Python<'py>,
+        dates: Vec<String>,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let dates = dates
+            .iter()
+            .map(|date| self.date_in_range(date))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(PyArray1::from_vec(py, self.0.discount_factors(&dates)?))
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.rates.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "YieldCurve({} points, {} to {})",
+            self.0.rates.len(),
+            self.0.initial_date(),
+            self.0.terminal_date()
+        )
+    }
+}
+
+impl PyYieldCurve {
+    /// Parse `date`, which must lie within the curve.
+    fn date_in_range(&self, date: &str) -> PyResult<Date> {
+        let parsed = parse_date(date)?;
+
+        if parsed < self.0.initial_date() || parsed > self.0.terminal_date() {
+            return Err(PyValueError::new_err(format!(
+                "{date} is outside the curve ({} to {})",
+                self.0.initial_date(),
+                self.0.terminal_date()
+            )));
+        }
+
+        Ok(parsed)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// ARGUMENT HANDLING
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A named numeric argument: a float, or one value per row.
+#[derive(Clone)]
+struct Column {
+    name: &'static str,
+    values: Values,
+}
+
+#[derive(Clone)]
+enum Values {
+    Scalar(f64),
+    Vector(Vec<f64>),
+}
+
+impl Column {
+    fn get(&self, row: usize) -> f64 {
+        match &self.values {
+            Values::Scalar(value) => *value,
+            Values::Vector(values) => values[row],
+        }
+    }
+}
+
+/// Extract a float, a 1-D float array, or a sequence of numbers.
+fn column(name: &'static str, arg: &Bound<'_, PyAny>) -> PyResult<Column> {
+    let invalid =
+        || PyTypeError::new_err(format!("`{name}` must be a float or a 1-D array of floats"));
+
+    // Python floats and sequences are handled without touching the NumPy
+    // API. Arrays are tried before other floats (e.g. NumPy scalars), since
+    // a length-one array would also convert to a float.
+    let values = if arg.is_instance_of::<PyFloat>() || arg.is_instance_of::<PyInt>() {
+        Values::Scalar(arg.extract()?)
+    } else if arg.is_instance_of::<PyList>() || arg.is_instance_of::<PyTuple>() {
+        Values::Vector(arg.extract().map_err(|_| invalid())?)
+    } else if let Ok(array) = arg.extract::<PyReadonlyArray1<'_, f64>>() {
+        Values::Vector(array.as_array().to_vec())
+    } else if let Ok(value) = arg.extract::<f64>() {
+        Values::Scalar(value)
+    } else {
+        Values::Vector(arg.extract().map_err(|_| invalid())?)
+    };
+
+    Ok(Column { name, values })
+}
+
+/// Columns of the Black-Scholes-Merton functions, in the order
+/// `S, K, v, r, T, b`.
+fn bsm_columns(
+    spot: &Bound<'_, PyAny>,
+    strike: &Bound<'_, PyAny>,
+    volatility: &Bound<'_, PyAny>,
+    rate: &Bound<'_, PyAny>,
+    time_to_expiry: &Bound<'_, PyAny>,
+    cost_of_carry: Option<&Bound<'_, PyAny>>,
+) -> PyResult<[Column; 6]> {
+    let rate = column("rate", rate)?;
+    let cost_of_carry = match cost_of_carry {
+        Some(cost_of_carry) => column("cost_of_carry", cost_of_carry)?,
+        None => rate.clone(),
+    };
+
+    Ok([
+        column("spot", spot)?,
+        column("strike", strike)?,
+        column("volatility", volatility)?,
+        rate,
+        column("time_to_expiry", time_to_expiry)?,
+        cost_of_carry,
+    ])
+}
+
+/// Evaluate `f` on each row of `columns`, broadcasting scalars.
+///
+/// Returns the number of rows, or `None` if every column is a scalar
+/// (in which case there is a single row).
+fn map_rows<T, F>(py: Python<'_>, columns: &[Column], f: F) -> PyResult<(Option<usize>, Vec<T>)>
+where
+    T: Send,
+    F: Fn(&[f64]) -> Result<T, RustQuantError> + Sync,
+{
+    let mut len: Option<(&str, usize)> = None;
+
+    for column in columns {
+        if let Values::Vector(values) = &column.values {
+            match len {
+                None => len = Some((column.name, values.len())),
+                Some((name, n)) if n != values.len() => {
+                    return Err(PyValueError::new_err(format!(
+                        "`{}` has length {}, but `{name}` has length {n}",
+                        column.name,
+                        values.len()
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    let rows = len.map_or(1, |(_, n)| n);
+
+    let values = py.detach(|| {
+        let mut row = vec![0.0; columns.len()];
+
+        (0..rows)
+            .map(|i| {
+                for (x, column) in row.iter_mut().zip(columns) {
+                    *x = column.get(i);
+                }
+                f(&row).map_err(|e| match len {
+                    Some(_) => format!("row {i}: {e}"),
+                    None => e.to_string(),
+                })
+            })
+            .collect::<Result<Vec<T>, String>>()
+    });
+
+    values
+        .map(|values| (len.map(|(_, n)| n), values))
+        .map_err(PyValueError::new_err)
+}
+
+/// A float if `len` is `None`, otherwise an array.
+fn output(py: Python<'_>, len: Option<usize>, values: Vec<f64>) -> Py<PyAny> {
+    match len {
+        None => PyFloat::new(py, values[0]).into_any().unbind(),
+        Some(_) => PyArray1::from_vec(py, values).into_any().unbind(),
+    }
+}
+
+/// Check that every column of the row is positive, except the rate and
+/// cost of carry.
+fn check_positive(columns: &[Column], row: &[f64]) -> Result<(), RustQuantError> {
+    for (column, &x) in columns.iter().zip(row) {
+        if !matches!(column.name, "rate" | "cost_of_carry") {
+            positive(column.name, x)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse an ISO date, `"YYYY-MM-DD"`.
+fn parse_date(date: &str) -> Result<Date, RustQuantError> {
+    let invalid =
+        || RustQuantError::InvalidArgument(format!("invalid date {date:?}, expected YYYY-MM-DD"));
+
+    let mut parts = date.splitn(3, '-');
+    let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return Err(invalid());
+    }
+
+    let year = year.parse().map_err(|_| invalid())?;
+    let month = month
+        .parse::<u8>()
+        .ok()
+        .and_then(|month| Month::try_from(month).ok())
+        .ok_or_else(invalid)?;
+    let day = day.parse().map_err(|_| invalid())?;
+
+    Date::from_calendar_date(year, month, day).map_err(|_| invalid())
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_python {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("2024-02-29").unwrap(), date!(2024 - 02 - 29));
+
+        for invalid in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-1-01",
+            "24-01-01",
+            "2024/01/01",
+            "",
+        ] {
+            assert!(parse_date(invalid).is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,182 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! WebAssembly bindings.
+//!
+//! The analytic pricers and the implied volatility solver, exported with
+//! `wasm-bindgen` under camelCase names. Nothing here spawns threads or
+//! touches the filesystem, so the module runs in the browser:
+//!
+//! ```sh
+//! cargo rustc --release --lib --crate-type cdylib --target wasm32-unknown-unknown \
+//!     --no-default-features --features wasm
+//! wasm-bindgen --target web --out-dir pkg \
+//!     target/wasm32-unknown-unknown/release/RustQuant.wasm
+//! ```
+//!
+//! ```js
+//! import init, { blackScholes, impliedVolatility, greeks } from "./pkg/RustQuant.js";
+//!
+//! await init();
+//! const price = blackScholes(100, 110, 0.2, 0.05, 1.0, "call");
+//! const vol = impliedVolatility(price, 100, 110, 0.05, 1.0, "call");
+//! const { delta, gamma } = greeks(100, 110, 0.2, 0.05, 1.0, "call");
+//! ```
+//!
+//! Invalid inputs throw an `Error`.
+
+use super::{barrier_option, bsm_greeks, parse_barrier_type, parse_type_flag, positive};
+use crate::instruments::options::{
+    bachelier_price, generalised_black_scholes_merton, try_implied_volatility,
+};
+use wasm_bindgen::prelude::*;
+
+/// Price and Greeks of a European option, returned by `greeks`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct Greeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub theta: f64,
+    pub rho: f64,
+}
+
+/// Generalised Black-Scholes-Merton price of a European option.
+///
+/// `costOfCarry` defaults to `rate` (no dividends).
+#[wasm_bindgen(js_name = blackScholes)]
+pub fn black_scholes(
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+    cost_of_carry: Option<f64>,
+) -> Result<f64, JsError> {
+    let flag = parse_type_flag(option_type)?;
+
+    Ok(generalised_black_scholes_merton(
+        positive("spot", spot)?,
+        positive("strike", strike)?,
+        positive("volatility", volatility)?,
+        rate,
+        cost_of_carry.unwrap_or(rate),
+        positive("timeToExpiry", time_to_expiry)?,
+        flag,
+    ))
+}
+
+/// Bachelier (normal model) price of a European option.
+#[wasm_bindgen]
+pub fn bachelier(
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> Result<f64, JsError> {
+    let flag = parse_type_flag(option_type)?;
+
+    Ok(bachelier_price(
+        spot,
+        strike,
+        positive("volatility", volatility)?,
+        positive("timeToExpiry", time_to_expiry)?,
+        flag,
+    ))
+}
+
+/// Analytic price of a barrier option.
+///
+/// `barrierType` is one of `"CUI"`, `"CDI"`, `"CUO"`, `"CDO"`, `"PUI"`,
+/// `"PDI"`, `"PUO"`, `"PDO"` (call/put, up/down, in/out).
+/// `rebate` and `dividendYield` default to zero.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn barrier(
+    spot: f64,
+    strike: f64,
+    barrier: f64,
+    volatility: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    barrier_type: &str,
+    rebate: Option<f64>,
+    dividend_yield: Option<f64>,
+) -> Result<f64, JsError> {
+    let barrier_type = parse_barrier_type(barrier_type)?;
+    let option = barrier_option(
+        &[spot, strike, barrier, volatility, rate, time_to_expiry],
+        rebate.unwrap_or(0.0),
+        dividend_yield.unwrap_or(0.0),
+    )?;
+
+    Ok(option.price(barrier_type)?)
+}
+
+/// Black-Scholes implied volatility of a European option price.
+#[wasm_bindgen(js_name = impliedVolatility)]
+pub fn implied_volatility(
+    price: f64,
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+) -> Result<f64, JsError> {
+    let flag = parse_type_flag(option_type)?;
+
+    Ok(try_implied_volatility(
+        price,
+        spot,
+        strike,
+        time_to_expiry,
+        rate,
+        flag,
+    )?)
+}
+
+/// Price and Greeks of a European option under generalised
+/// Black-Scholes-Merton. Theta is per year.
+///
+/// `costOfCarry` defaults to `rate` (no dividends).
+#[wasm_bindgen]
+pub fn greeks(
+    spot: f64,
+    strike: f64,
+    volatility: f64,
+    rate: f64,
+    time_to_expiry: f64,
+    option_type: &str,
+    cost_of_carry: Option<f64>,
+) -> Result<Greeks, JsError> {
+    let flag = parse_type_flag(option_type)?;
+
+    let [price, delta, gamma, vega, theta, rho] = bsm_greeks(
+        positive("spot", spot)?,
+        positive("strike", strike)?,
+        positive("volatility", volatility)?,
+        rate,
+        cost_of_carry,
+        positive("timeToExpiry", time_to_expiry)?,
+        flag,
+    );
+
+    Ok(Greeks {
+        price,
+        delta,
+        gamma,
+        vega,
+        theta,
+        rho,
+    })
+}
@@ -31,6 +31,7 @@ pub trait Cashflow {
 
 /// Simple cashflow type.
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleCashflow {
     amount: f64,
     date: OffsetDateTime,
@@ -19,6 +19,7 @@ use time::OffsetDateTime;
 
 /// Leg (sequence of cashflows).
 #[derive(Debug, Clone, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Leg<C: Cashflow> {
     cashflows: Vec<C>,
 }
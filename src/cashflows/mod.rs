@@ -26,3 +26,7 @@ pub use legs::*;
 /// Quotes (price, yield, etc).
 pub mod quotes;
 pub use quotes::*;
+
+/// Cashflow reports (full cashflow tables emitted by pricers).
+pub mod report;
+pub use report::*;
@@ -0,0 +1,611 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Cashflow reports.
+//!
+//! A cashflow report is the full table of cashflows of an instrument:
+//! payment dates, accrual periods, amounts, currencies, fixings,
+//! discount factors and present values.
+//! It is emitted by pricers alongside the NPV, so that results can be
+//! reconciled line by line against other (e.g. back-office) systems.
+
+use super::Cashflow;
+#[cfg(feature = "curves")]
+use crate::data::Curve;
+use crate::error::RustQuantError;
+use crate::instruments::fx::{currency::Currency, decimal_money::DecimalMoney, money::Money};
+use crate::time::AccrualPeriod;
+#[cfg(not(target_arch = "wasm32"))]
+use polars::prelude::*;
+use std::fmt;
+use time::{Date, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Kind of cashflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CashflowKind {
+    /// Fixed rate coupon.
+    FixedCoupon,
+
+    /// Floating rate coupon.
+    FloatingCoupon,
+
+    /// Notional (principal) exchange or redemption.
+    Notional,
+
+    /// Any other payment (fees, premiums, etc).
+    Other,
+}
+
+/// Fixing information of a floating rate cashflow.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixingInfo {
+    /// Name of the floating rate index (e.g. "SOFR", "EURIBOR 6M").
+    pub index: String,
+
+    /// Date the rate is (or will be) fixed.
+    pub fixing_date: Date,
+
+    /// The fixed (or projected) rate, if known.
+    pub rate: Option<f64>,
+
+    /// Spread over the index rate.
+    pub spread: f64,
+}
+
+/// A single line of a cashflow report.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CashflowEntry {
+    /// Kind of cashflow.
+    pub kind: CashflowKind,
+
+    /// Payment date.
+    pub payment_date: Date,
+
+    /// Start of the accrual period (if the cashflow accrues).
+    pub accrual_start_date: Option<Date>,
+
+    /// End of the accrual period (if the cashflow accrues).
+    pub accrual_end_date: Option<Date>,
+
+    /// Accrual year fraction (if the cashflow accrues).
+    pub accrual_factor: Option<f64>,
+
+    /// Notional the cashflow accrues on.
+    pub notional: Option<f64>,
+
+    /// Cashflow amount.
+    pub amount: f64,
+
+    /// Currency of the amount (optional).
+    pub currency: Option<Currency>,
+
+    /// Fixing information for floating cashflows.
+    pub fixing: Option<FixingInfo>,
+
+    /// Discount factor to the payment date (filled in by discounting).
+    pub discount_factor: Option<f64>,
+}
+
+/// Cashflow report: a table of cashflows.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CashflowReport {
+    /// The cashflows, ordered by payment date.
+    pub entries: Vec<CashflowEntry>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CashflowEntry {
+    /// A fixed coupon over an accrual period: `notional * rate * dcf`.
+    #[must_use]
+    pub fn fixed_coupon(
+        period: &AccrualPeriod,
+        notional: f64,
+        rate: f64,
+        currency: Option<Currency>,
+    ) -> Self {
+        Self {
+            kind: CashflowKind::FixedCoupon,
+            payment_date: period.payment_date,
+            accrual_start_date: Some(period.start_date),
+            accrual_end_date: Some(period.end_date),
+            accrual_factor: Some(period.day_count_factor),
+            notional: Some(notional),
+            amount: notional * rate * period.day_count_factor,
+            currency,
+            fixing: None,
+            discount_factor: None,
+        }
+    }
+
+    /// A floating coupon over an accrual period:
+    /// `notional * (rate + spread) * dcf`, where the rate is the fixing's
+    /// rate.
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::MissingInput`] if the fixing's rate is not known;
+    ///   see [`CashflowEntry::projected_floating_coupon`] for coupons not
+    ///   fixed yet.
+    pub fn floating_coupon(
+        period: &AccrualPeriod,
+        notional: f64,
+        fixing: FixingInfo,
+        currency: Option<Currency>,
+    ) -> Result<Self, RustQuantError> {
+        let rate = fixing.rate.ok_or_else(|| {
+            RustQuantError::MissingInput(format!(
+                "{} fixing on {}",
+                fixing.index, fixing.fixing_date
+            ))
+        })? + fixing.spread;
+
+        Ok(Self {
+            kind: CashflowKind::FloatingCoupon,
+            payment_date: period.payment_date,
+            accrual_start_date: Some(period.start_date),
+            accrual_end_date: Some(period.end_date),
+            accrual_factor: Some(period.day_count_factor),
+            notional: Some(notional),
+            amount: notional * rate * period.day_count_factor,
+            currency,
+            fixing: Some(fixing),
+            discount_factor: None,
+        })
+    }
+
+    /// A floating coupon whose rate, if not fixed yet, is projected from
+    /// the forward curve: the simply compounded forward rate over the
+    /// accrual period, `(P(start) / P(end) - 1) / dcf`.
+    /// The projected rate is recorded in the fixing.
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the rate must be projected
+    ///   over an accrual period with a non-positive year fraction.
+    /// * Any error returned by [`Curve::discount_factor`], e.g. for an
+    ///   accrual period outside the curve.
+    #[cfg(feature = "curves")]
+    pub fn projected_floating_coupon<C: Curve>(
+        period: &AccrualPeriod,
+        notional: f64,
+        mut fixing: FixingInfo,
+        forward_curve: &C,
+        currency: Option<Currency>,
+    ) -> Result<Self, RustQuantError> {
+        if fixing.rate.is_none() {
+            if period.day_count_factor <= 0.0 {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "accrual period from {} to {} has a non-positive year fraction",
+                    period.start_date, period.end_date
+                )));
+            }
+
+            let start = forward_curve.discount_factor(period.start_date)?;
+            let end = forward_curve.discount_factor(period.end_date)?;
+
+            fixing.rate = Some((start / end - 1.0) / period.day_count_factor);
+        }
+
+        Self::floating_coupon(period, notional, fixing, currency)
+    }
+
+    /// A notional (principal) payment.
+    #[must_use]
+    pub fn notional(payment_date: Date, amount: f64, currency: Option<Currency>) -> Self {
+        Self {
+            kind: CashflowKind::Notional,
+            payment_date,
+            accrual_start_date: None,
+            accrual_end_date: None,
+            accrual_factor: None,
+            notional: None,
+            amount,
+            currency,
+            fixing: None,
+            discount_factor: None,
+        }
+    }
+
+    /// The amount as `Money`, if the currency is known.
+    #[must_use]
+    pub fn money(&self) -> Option<Money> {
+        self.currency
+            .map(|currency| Money::new(currency, self.amount))
+    }
+
+    /// The settlement amount: the amount as [`DecimalMoney`], rounded to
+    /// the minor unit of its currency.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::MissingInput`] if the currency is unknown,
+    /// [`RustQuantError::InvalidArgument`] if the amount is not finite.
+    pub fn settlement_amount(&self) -> Result<DecimalMoney, RustQuantError> {
+        let currency = self.currency.ok_or_else(|| {
+            RustQuantError::MissingInput(format!(
+                "currency of the cashflow paid on {}",
+                self.payment_date
+            ))
+        })?;
+
+        DecimalMoney::from_f64(currency, self.amount)
+    }
+
+    /// Present value of the cashflow (if it has been discounted).
+    #[must_use]
+    pub fn present_value(&self) -> Option<f64> {
+        self.discount_factor.map(|df| df * self.amount)
+    }
+}
+
+impl Cashflow for CashflowEntry {
+    fn amount(&self) -> f64 {
+        self.amount
+    }
+
+    fn date(&self) -> OffsetDateTime {
+        self.payment_date.midnight().assume_utc()
+    }
+
+    fn npv<F>(&self, df: F) -> f64
+    where
+        F: Fn(OffsetDateTime) -> f64,
+    {
+        self.amount * df(self.date())
+    }
+}
+
+impl CashflowReport {
+    /// Create a new report, sorting the entries by payment date.
+    #[must_use]
+    pub fn new(mut entries: Vec<CashflowEntry>) -> Self {
+        entries.sort_by_key(|entry| entry.payment_date);
+
+        Self { entries }
+    }
+
+    /// Fill in the discount factors of all cashflows paid after the
+    /// curve's initial date from the given curve.
+    /// Cashflows paid before the initial date are considered settled and
+    /// get a discount factor of zero.
+    ///
+    /// # Errors:
+    ///
+    /// * Any error returned by [`Curve::discount_factor`], e.g. for a
+    ///   cashflow paid after the curve's terminal date.
+    #[cfg(feature = "curves")]
+    pub fn discount<C: Curve>(mut self, curve: &C) -> Result<Self, RustQuantError> {
+        let initial_date = curve.initial_date();
+
+        for entry in &mut self.entries {
+            entry.discount_factor = Some(if entry.payment_date < initial_date {
+                0.0
+            } else {
+                curve.discount_factor(entry.payment_date)?
+            });
+        }
+
+        Ok(self)
+    }
+
+    /// Net present value: the sum of the discounted cashflows.
+    /// Cashflows without a discount factor are ignored.
+    #[must_use]
+    pub fn npv(&self) -> f64 {
+        self.entries
+            .iter()
+            .filter_map(CashflowEntry::present_value)
+            .sum()
+    }
+
+    /// Undiscounted sum of the cashflow amounts.
+    #[must_use]
+    pub fn total_amount(&self) -> f64 {
+        self.entries.iter().map(|entry| entry.amount).sum()
+    }
+
+    /// Total of the settlement amounts in the given currency.
+    ///
+    /// Each cashflow is rounded to the currency's minor unit before being
+    /// added in decimal arithmetic, so the total matches the sum of the
+    /// amounts actually paid, without floating-point error.
+    ///
+    /// # Errors
+    ///
+    /// See [`CashflowEntry::settlement_amount`] and
+    /// [`DecimalMoney::checked_add`] (e.g. for mixed currencies).
+    pub fn settlement_total(&self, currency: Currency) -> Result<DecimalMoney, RustQuantError> {
+        self.entries
+            .iter()
+            .map(CashflowEntry::settlement_amount)
+            .try_fold(DecimalMoney::zero(currency), |total, amount| {
+                total.checked_add(amount?)
+            })
+    }
+
+    /// Cashflows paid strictly after the given date.
+    #[must_use]
+    pub fn after(&self, date: Date) -> Self {
+        Self {
+            entries: self
+                .entries
+                .iter()
+                .filter(|entry| entry.payment_date > date)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Convert the report to a Polars `DataFrame`, one row per cashflow.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn to_dataframe(&self) -> Result<DataFrame, RustQuantError> {
+        let epoch = OffsetDateTime::UNIX_EPOCH.date();
+        let days = |date: Option<Date>| date.map(|d| (d - epoch).whole_days() as i32);
+
+        let column = |f: &dyn Fn(&CashflowEntry) -> Option<f64>| {
+            self.entries.iter().map(f).collect::<Vec<Option<f64>>>()
+        };
+        let date_column = |name: &str, f: &dyn Fn(&CashflowEntry) -> Option<Date>| {
+            Series::new(
+                name,
+                self.entries
+                    .iter()
+                    .map(|e| days(f(e)))
+                    .collect::<Vec<Option<i32>>>(),
+            )
+            .cast(&DataType::Date)
+        };
+
+        let df = df!(
+            "kind" => self.entries.iter().map(|e| format!("{:?}", e.kind)).collect::<Vec<String>>(),
+            "payment_date" => date_column("payment_date", &|e| Some(e.payment_date))?,
+            "accrual_start_date" => date_column("accrual_start_date", &|e| e.accrual_start_date)?,
+            "accrual_end_date" => date_column("accrual_end_date", &|e| e.accrual_end_date)?,
+            "accrual_factor" => column(&|e| e.accrual_factor),
+            "notional" => column(&|e| e.notional),
+            "amount" => column(&|e| Some(e.amount)),
+            "currency" => self.entries.iter().map(|e| e.currency.map(|c| c.code.alphabetic.to_string())).collect::<Vec<Option<String>>>(),
+            "fixing_index" => self.entries.iter().map(|e| e.fixing.as_ref().map(|f| f.index.clone())).collect::<Vec<Option<String>>>(),
+            "fixing_date" => date_column("fixing_date", &|e| e.fixing.as_ref().map(|f| f.fixing_date))?,
+            "fixing_rate" => column(&|e| e.fixing.as_ref().and_then(|f| f.rate)),
+            "discount_factor" => column(&|e| e.discount_factor),
+            "present_value" => column(&CashflowEntry::present_value)
+        )?;
+
+        Ok(df)
+    }
+}
+
+impl fmt::Display for CashflowReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let opt = |x: Option<f64>| x.map_or_else(|| "-".to_string(), |x| format!("{x:.6}"));
+        let opt_date = |d: Option<Date>| d.map_or_else(|| "-".to_string(), |d| d.to_string());
+
+        writeln!(
+            f,
+            "{:<15} {:<12} {:<12} {:<12} {:>10} {:>16} {:>4} {:>10} {:>16}",
+            "Kind", "Payment", "Start", "End", "Accrual", "Amount", "Ccy", "DF", "PV"
+        )?;
+
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:<15} {:<12} {:<12} {:<12} {:>10} {:>16.4} {:>4} {:>10} {:>16}",
+                format!("{:?}", entry.kind),
+                entry.payment_date.to_string(),
+                opt_date(entry.accrual_start_date),
+                opt_date(entry.accrual_end_date),
+                opt(entry.accrual_factor),
+                entry.amount,
+                entry.currency.map_or("-", |c| c.code.alphabetic),
+                opt(entry.discount_factor),
+                entry
+                    .present_value()
+                    .map_or_else(|| "-".to_string(), |pv| format!("{pv:.4}")),
+            )?;
+        }
+
+        write!(f, "NPV: {:.4}", self.npv())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cashflow_report {
+    use super::*;
+    #[cfg(feature = "curves")]
+    use crate::data::YieldCurve;
+    use crate::iso::{EUR, USD};
+    use time::macros::date;
+
+    const EPS: f64 = 1e-12;
+
+    fn period(start: Date, end: Date, dcf: f64) -> AccrualPeriod {
+        AccrualPeriod {
+            start_date: start,
+            end_date: end,
+            unadjusted_start_date: start,
+            unadjusted_end_date: end,
+            payment_date: end,
+            day_count_factor: dcf,
+            is_stub: false,
+        }
+    }
+
+    fn report() -> CashflowReport {
+        let p1 = period(date!(2024 - 01 - 01), date!(2024 - 07 - 01), 0.5);
+        let p2 = period(date!(2024 - 07 - 01), date!(2025 - 01 - 01), 0.5);
+
+        let fixing = FixingInfo {
+            index: "SOFR".to_string(),
+            fixing_date: date!(2024 - 07 - 01),
+            rate: Some(0.03),
+            spread: 0.01,
+        };
+
+        CashflowReport::new(vec![
+            CashflowEntry::floating_coupon(&p2, 100.0, fixing, Some(USD)).unwrap(),
+            CashflowEntry::notional(date!(2025 - 01 - 01), 100.0, Some(USD)),
+            CashflowEntry::fixed_coupon(&p1, 100.0, 0.05, Some(USD)),
+        ])
+    }
+
+    #[test]
+    fn test_cashflow_entries() {
+        let report = report();
+
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.entries[0].kind, CashflowKind::FixedCoupon);
+        assert_approx_equal!(report.entries[0].amount, 2.5, EPS);
+        assert_approx_equal!(report.entries[1].amount, 2.0, EPS);
+        assert_approx_equal!(report.total_amount(), 104.5, EPS);
+        assert_eq!(report.entries[2].money(), Some(Money::new(USD, 100.0)));
+        assert_eq!(report.after(date!(2024 - 07 - 01)).entries.len(), 2);
+    }
+
+    #[test]
+    fn test_floating_coupon_fixing() {
+        let p = period(date!(2024 - 07 - 01), date!(2025 - 01 - 01), 0.5);
+        let fixing = FixingInfo {
+            index: "SOFR".to_string(),
+            fixing_date: date!(2024 - 07 - 01),
+            rate: None,
+            spread: 0.01,
+        };
+
+        assert!(matches!(
+            CashflowEntry::floating_coupon(&p, 100.0, fixing, Some(USD)),
+            Err(RustQuantError::MissingInput(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "curves")]
+    fn test_projected_floating_coupon() {
+        let start = date!(2024 - 07 - 01);
+        let end = date!(2025 - 01 - 01);
+        let p = period(start, end, 0.5);
+        let curve = YieldCurve::from_dates_and_rates(
+            &[date!(2024 - 01 - 01), date!(2030 - 01 - 01)],
+            &[0.04, 0.04],
+        )
+        .unwrap();
+
+        let fixing = FixingInfo {
+            index: "SOFR".to_string(),
+            fixing_date: start,
+            rate: None,
+            spread: 0.01,
+        };
+
+        // Not fixed yet: the forward rate over the accrual period.
+        let forward = (curve.discount_factor(start).unwrap() / curve.discount_factor(end).unwrap()
+            - 1.0)
+            / 0.5;
+        let projected =
+            CashflowEntry::projected_floating_coupon(&p, 100.0, fixing.clone(), &curve, Some(USD))
+                .unwrap();
+        assert_approx_equal!(
+            projected.fixing.as_ref().unwrap().rate.unwrap(),
+            forward,
+            EPS
+        );
+        assert_approx_equal!(projected.amount, 100.0 * (forward + 0.01) * 0.5, EPS);
+
+        // Already fixed: the fixing is kept.
+        let fixed = FixingInfo {
+            rate: Some(0.03),
+            ..fixing.clone()
+        };
+        let coupon =
+            CashflowEntry::projected_floating_coupon(&p, 100.0, fixed, &curve, Some(USD)).unwrap();
+        assert_approx_equal!(coupon.amount, 2.0, EPS);
+
+        // Outside the curve.
+        let late = period(date!(2031 - 01 - 01), date!(2031 - 07 - 01), 0.5);
+        assert!(
+            CashflowEntry::projected_floating_coupon(&late, 100.0, fixing, &curve, Some(USD))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cashflow_report_settlement() {
+        let p = period(date!(2024 - 01 - 01), date!(2024 - 02 - 01), 31.0 / 360.0);
+
+        // 1,000,000 * 3.3% * 31/360 = 2841.666...
+        let coupons = CashflowReport::new(vec![
+            CashflowEntry::fixed_coupon(
+                &p,
+                1_000_000.0,
+                0.033,
+                Some(USD)
+            );
+            3
+        ]);
+
+        let total = coupons.settlement_total(USD).unwrap();
+        assert_eq!(total.to_string(), "USD 8525.01");
+        assert_eq!(
+            coupons.entries[0].settlement_amount().unwrap().to_string(),
+            "USD 2841.67"
+        );
+
+        assert!(matches!(
+            coupons.settlement_total(EUR),
+            Err(RustQuantError::CurrencyMismatch(_, _))
+        ));
+
+        let unknown = CashflowEntry::notional(date!(2025 - 01 - 01), 100.0, None);
+        assert!(matches!(
+            unknown.settlement_amount(),
+            Err(RustQuantError::MissingInput(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "curves")]
+    fn test_cashflow_report_discounting() {
+        let curve = YieldCurve::from_dates_and_rates(
+            &[date!(2024 - 01 - 01), date!(2030 - 01 - 01)],
+            &[0.0, 0.0],
+        )
+        .unwrap();
+
+        let report = report();
+        assert_approx_equal!(report.npv(), 0.0, EPS);
+
+        let report = report.discount(&curve).unwrap();
+        assert_approx_equal!(report.npv(), 104.5, EPS);
+        assert!(report.to_string().contains("NPV: 104.5000"));
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_cashflow_report_dataframe() {
+        let df = report().to_dataframe().unwrap();
+
+        assert_eq!(df.shape(), (3, 13));
+        assert_eq!(df.column("payment_date").unwrap().dtype(), &DataType::Date);
+    }
+}
@@ -0,0 +1,550 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Stationarity and cointegration tests.
+//!
+//! - Augmented Dickey-Fuller (ADF) unit root test, with a fixed number of
+//!   lags or lags selected by AIC.
+//! - Engle-Granger two-step cointegration test for a pair of series.
+//! - Half-life of mean reversion from an AR(1) fit.
+//!
+//! Critical values are from the response surfaces of MacKinnon (2010),
+//! "Critical Values for Cointegration Tests".
+//!
+//! ```
+//! use RustQuant::data::*;
+//!
+//! // A mean-reverting spread around a random walk.
+//! let mut x = vec![100.0];
+//! let mut spread = vec![0.0];
+//! for t in 1..500 {
+//!     let noise = |k: usize| ((t * k) % 101) as f64 / 50.0 - 1.0;
+//!     x.push(x[t - 1] + noise(7919));
+//!     spread.push(0.5 * spread[t - 1] + noise(104_729));
+//! }
+//! let y: Vec<f64> = x.iter().zip(&spread).map(|(x, s)| 2.0 * x + s).collect();
+//!
+//! let test = engle_granger(&y, &x, LagSelection::Aic(4)).unwrap();
+//!
+//! assert!((test.hedge_ratio - 2.0).abs() < 0.05);
+//! assert!(test.adf.rejects_unit_root(SignificanceLevel::OnePercent));
+//!
+//! // Half-life of the spread, in periods.
+//! println!("{}", half_life(&test.residuals).unwrap());
+//! ```
+
+use crate::data::TimeSeries;
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Deterministic terms in the Dickey-Fuller regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdfRegression {
+    /// No constant and no trend.
+    NoConstant,
+    /// Constant only.
+    Constant,
+    /// Constant and linear time trend.
+    ConstantTrend,
+}
+
+/// Number of lagged differences in the Dickey-Fuller regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagSelection {
+    /// A fixed number of lags.
+    Fixed(usize),
+    /// The number of lags, up to the given maximum, that minimises the
+    /// Akaike information criterion.
+    Aic(usize),
+}
+
+/// Significance level of a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignificanceLevel {
+    /// 1%.
+    OnePercent,
+    /// 5%.
+    FivePercent,
+    /// 10%.
+    TenPercent,
+}
+
+/// Critical values of a unit root test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CriticalValues {
+    /// 1% critical value.
+    pub one_percent: f64,
+    /// 5% critical value.
+    pub five_percent: f64,
+    /// 10% critical value.
+    pub ten_percent: f64,
+}
+
+/// Result of an augmented Dickey-Fuller test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdfTest {
+    /// The t-statistic of the lagged level.
+    pub statistic: f64,
+    /// The number of lagged differences used.
+    pub lags: usize,
+    /// The number of observations in the regression.
+    pub n_obs: usize,
+    /// The deterministic terms in the regression.
+    pub regression: AdfRegression,
+    /// The critical values for the statistic.
+    pub critical_values: CriticalValues,
+}
+
+/// Result of an Engle-Granger cointegration test of `y` on `x`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngleGrangerTest {
+    /// Intercept of the cointegrating regression.
+    pub intercept: f64,
+    /// Slope of the cointegrating regression, $y_t = a + b x_t + e_t$.
+    pub hedge_ratio: f64,
+    /// Residuals of the cointegrating regression (the spread).
+    pub residuals: Vec<f64>,
+    /// ADF test of the residuals, with Engle-Granger critical values.
+    pub adf: AdfTest,
+}
+
+/// Ordinary least squares fit.
+struct Ols {
+    beta: DVector<f64>,
+    standard_errors: DVector<f64>,
+    rss: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CriticalValues {
+    /// Critical values from the MacKinnon (2010) response surface
+    /// $b_0 + b_1 / T + b_2 / T^2 + b_3 / T^3$.
+    fn mackinnon(coefficients: &[[f64; 4]; 3], n_obs: usize) -> Self {
+        let t = n_obs as f64;
+        let value = |b: &[f64; 4]| b[0] + b[1] / t + b[2] / t.powi(2) + b[3] / t.powi(3);
+
+        Self {
+            one_percent: value(&coefficients[0]),
+            five_percent: value(&coefficients[1]),
+            ten_percent: value(&coefficients[2]),
+        }
+    }
+
+    /// Critical value at the given significance level.
+    #[must_use]
+    pub fn at(&self, level: SignificanceLevel) -> f64 {
+        match level {
+            SignificanceLevel::OnePercent => self.one_percent,
+            SignificanceLevel::FivePercent => self.five_percent,
+            SignificanceLevel::TenPercent => self.ten_percent,
+        }
+    }
+}
+
+impl AdfTest {
+    /// Whether the unit root (non-stationarity) null hypothesis is rejected
+    /// at the given significance level.
+    #[must_use]
+    pub fn rejects_unit_root(&self, level: SignificanceLevel) -> bool {
+        self.statistic < self.critical_values.at(level)
+    }
+}
+
+// MacKinnon (2010) response surface coefficients for one variable
+// (unit root tests), for the 1%, 5% and 10% levels.
+const TAU_NO_CONSTANT: [[f64; 4]; 3] = [
+    [-2.56574, -2.2358, -3.627, 0.0],
+    [-1.94100, -0.2686, -3.365, 31.223],
+    [-1.61682, 0.2656, -2.714, 25.364],
+];
+const TAU_CONSTANT: [[f64; 4]; 3] = [
+    [-3.43035, -6.5393, -16.786, -79.433],
+    [-2.86154, -2.8903, -4.234, -40.040],
+    [-2.56677, -1.5384, -2.809, 0.0],
+];
+const TAU_CONSTANT_TREND: [[f64; 4]; 3] = [
+    [-3.95877, -9.0531, -28.428, -134.155],
+    [-3.41049, -4.3904, -9.036, -45.374],
+    [-3.12705, -2.5856, -3.925, -22.380],
+];
+
+// MacKinnon (2010) coefficients for two variables (Engle-Granger test with a
+// constant in the cointegrating regression).
+const TAU_ENGLE_GRANGER: [[f64; 4]; 3] = [
+    [-3.89644, -10.9519, -33.527, 0.0],
+    [-3.33613, -6.1101, -6.823, 0.0],
+    [-3.04445, -4.2412, -2.720, 0.0],
+];
+
+/// Least squares fit of `y` on the columns of `x`.
+fn ols(x: &DMatrix<f64>, y: &DVector<f64>) -> Result<Ols, RustQuantError> {
+    let (n, k) = x.shape();
+
+    if n <= k {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "need more than {k} observations for the regression, got {n}"
+        )));
+    }
+
+    let x_t_x_inv = (x.transpose() * x)
+        .try_inverse()
+        .ok_or(RustQuantError::MatrixInversionFailed)?;
+    let beta = &x_t_x_inv * x.transpose() * y;
+
+    let rss = (y - x * &beta).norm_squared();
+    let sigma2 = rss / (n - k) as f64;
+    let standard_errors = x_t_x_inv.diagonal().map(|v| (sigma2 * v).sqrt());
+
+    Ok(Ols {
+        beta,
+        standard_errors,
+        rss,
+    })
+}
+
+/// Design matrix and response of the Dickey-Fuller regression with `lags`
+/// lagged differences, on the observations from `start` onwards.
+fn adf_design(
+    series: &[f64],
+    regression: AdfRegression,
+    lags: usize,
+    start: usize,
+) -> (DMatrix<f64>, DVector<f64>) {
+    let deterministic = match regression {
+        AdfRegression::NoConstant => 0,
+        AdfRegression::Constant => 1,
+        AdfRegression::ConstantTrend => 2,
+    };
+    let rows: Vec<usize> = (start..series.len()).collect();
+    let diff = |t: usize| series[t] - series[t - 1];
+
+    let x = DMatrix::from_fn(rows.len(), 1 + lags + deterministic, |row, column| {
+        let t = rows[row];
+
+        match column {
+            0 => series[t - 1],
+            c if c <= lags => diff(t - c),
+            c if c == lags + 1 => 1.0,
+            _ => t as f64,
+        }
+    });
+    let y = DVector::from_iterator(rows.len(), rows.iter().map(|t| diff(*t)));
+
+    (x, y)
+}
+
+/// ADF test with the given critical value coefficients.
+fn adf_with(
+    series: &[f64],
+    regression: AdfRegression,
+    lags: LagSelection,
+    coefficients: &[[f64; 4]; 3],
+) -> Result<AdfTest, RustQuantError> {
+    if series.iter().any(|x| !x.is_finite()) {
+        return Err(RustQuantError::InvalidArgument(
+            "series must be finite".to_string(),
+        ));
+    }
+
+    let lags = match lags {
+        LagSelection::Fixed(lags) => lags,
+        // Compare the criteria on a common sample.
+        LagSelection::Aic(max_lags) => {
+            let mut best = (0, f64::INFINITY);
+
+            for p in 0..=max_lags {
+                let (x, y) = adf_design(series, regression, p, max_lags + 1);
+                let fit = ols(&x, &y)?;
+                let aic = y.len() as f64 * (fit.rss / y.len() as f64).ln() + 2.0 * x.ncols() as f64;
+
+                if aic < best.1 {
+                    best = (p, aic);
+                }
+            }
+
+            best.0
+        }
+    };
+
+    if series.len() < lags + 2 {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "series of length {} is too short for {lags} lags",
+            series.len()
+        )));
+    }
+
+    let (x, y) = adf_design(series, regression, lags, lags + 1);
+    let fit = ols(&x, &y)?;
+
+    Ok(AdfTest {
+        statistic: fit.beta[0] / fit.standard_errors[0],
+        lags,
+        n_obs: y.len(),
+        regression,
+        critical_values: CriticalValues::mackinnon(coefficients, y.len()),
+    })
+}
+
+/// Augmented Dickey-Fuller test of the null hypothesis that the series has
+/// a unit root, based on the regression
+///
+/// $$
+/// \Delta y_t = \alpha + \beta t + \gamma y_{t-1} + \sum_{i=1}^p \delta_i \Delta y_{t-i} + \epsilon_t
+/// $$
+///
+/// # Errors
+///
+/// If the series is not finite or too short for the regression.
+pub fn augmented_dickey_fuller(
+    series: &[f64],
+    regression: AdfRegression,
+    lags: LagSelection,
+) -> Result<AdfTest, RustQuantError> {
+    let coefficients = match regression {
+        AdfRegression::NoConstant => &TAU_NO_CONSTANT,
+        AdfRegression::Constant => &TAU_CONSTANT,
+        AdfRegression::ConstantTrend => &TAU_CONSTANT_TREND,
+    };
+
+    adf_with(series, regression, lags, coefficients)
+}
+
+/// Engle-Granger test of the null hypothesis that `y` and `x` are not
+/// cointegrated: regress $y_t = a + b x_t + e_t$, then test the residuals
+/// for a unit root.
+///
+/// # Errors
+///
+/// If the series have different lengths, are not finite, or are too short
+/// for the regressions.
+pub fn engle_granger(
+    y: &[f64],
+    x: &[f64],
+    lags: LagSelection,
+) -> Result<EngleGrangerTest, RustQuantError> {
+    if y.len() != x.len() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "series have different lengths: {} and {}",
+            y.len(),
+            x.len()
+        )));
+    }
+
+    let design = DMatrix::from_fn(x.len(), 2, |t, column| if column == 0 { 1.0 } else { x[t] });
+    let fit = ols(&design, &DVector::from_column_slice(y))?;
+
+    let (intercept, hedge_ratio) = (fit.beta[0], fit.beta[1]);
+    let residuals: Vec<f64> = y
+        .iter()
+        .zip(x)
+        .map(|(y, x)| y - intercept - hedge_ratio * x)
+        .collect();
+
+    let adf = adf_with(
+        &residuals,
+        AdfRegression::NoConstant,
+        lags,
+        &TAU_ENGLE_GRANGER,
+    )?;
+
+    Ok(EngleGrangerTest {
+        intercept,
+        hedge_ratio,
+        residuals,
+        adf,
+    })
+}
+
+/// Half-life of mean reversion, $-\ln 2 / \ln \phi$, where $\phi$ is the
+/// AR(1) coefficient from the regression $\Delta y_t = a + (\phi - 1) y_{t-1}$.
+///
+/// # Errors
+///
+/// If the series is too short, or is not mean-reverting ($\phi \notin (0, 1)$).
+pub fn half_life(series: &[f64]) -> Result<f64, RustQuantError> {
+    let (x, y) = adf_design(series, AdfRegression::Constant, 0, 1);
+    let phi = 1.0 + ols(&x, &y)?.beta[0];
+
+    if !(phi > 0.0 && phi < 1.0) {
+        return Err(RustQuantError::ConditionViolated(format!(
+            "series is not mean-reverting (AR(1) coefficient {phi})"
+        )));
+    }
+
+    Ok(-std::f64::consts::LN_2 / phi.ln())
+}
+
+impl TimeSeries<f64> {
+    /// Augmented Dickey-Fuller test of the values, see
+    /// [`augmented_dickey_fuller`].
+    ///
+    /// # Errors
+    ///
+    /// If the series is not finite or too short for the regression.
+    pub fn augmented_dickey_fuller(
+        &self,
+        regression: AdfRegression,
+        lags: LagSelection,
+    ) -> Result<AdfTest, RustQuantError> {
+        augmented_dickey_fuller(self.values(), regression, lags)
+    }
+
+    /// Engle-Granger cointegration test of this series on `other`, on their
+    /// common dates, see [`engle_granger`].
+    ///
+    /// # Errors
+    ///
+    /// If the series are not finite or too short for the regressions.
+    pub fn engle_granger(
+        &self,
+        other: &TimeSeries<f64>,
+        lags: LagSelection,
+    ) -> Result<EngleGrangerTest, RustQuantError> {
+        let (y, x): (Vec<f64>, Vec<f64>) = self.inner_join(other).values().iter().copied().unzip();
+
+        engle_granger(&y, &x, lags)
+    }
+
+    /// Half-life of mean reversion in periods, see [`half_life`].
+    ///
+    /// # Errors
+    ///
+    /// If the series is too short or not mean-reverting.
+    pub fn half_life(&self) -> Result<f64, RustQuantError> {
+        half_life(self.values())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cointegration {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, StandardNormal};
+    use time::{macros::date, Duration};
+
+    fn noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n).map(|_| StandardNormal.sample(&mut rng)).collect()
+    }
+
+    /// AR(1) process $y_t = \phi y_{t-1} + \epsilon_t$.
+    fn ar1(phi: f64, n: usize, seed: u64) -> Vec<f64> {
+        noise(n, seed)
+            .into_iter()
+            .scan(0.0, |y, e| {
+                *y = phi * *y + e;
+                Some(*y)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_critical_values() {
+        // Asymptotic values and MacKinnon's T = 100 values for the
+        // constant-only regression.
+        let asymptotic = CriticalValues::mackinnon(&TAU_CONSTANT, usize::MAX);
+        assert_approx_equal!(asymptotic.five_percent, -2.86154, 1e-6);
+
+        let finite = CriticalValues::mackinnon(&TAU_CONSTANT, 100);
+        assert_approx_equal!(finite.one_percent, -3.4981, 1e-3);
+        assert_approx_equal!(finite.five_percent, -2.8912, 1e-3);
+    }
+
+    #[test]
+    fn test_adf() {
+        let random_walk = ar1(1.0, 1000, 1);
+        let stationary = ar1(0.5, 1000, 2);
+
+        for lags in [LagSelection::Fixed(0), LagSelection::Aic(8)] {
+            let walk =
+                augmented_dickey_fuller(&random_walk, AdfRegression::Constant, lags).unwrap();
+            let ar = augmented_dickey_fuller(&stationary, AdfRegression::Constant, lags).unwrap();
+
+            assert!(!walk.rejects_unit_root(SignificanceLevel::TenPercent));
+            assert!(ar.rejects_unit_root(SignificanceLevel::OnePercent));
+        }
+
+        // A trend-stationary series has a unit root without the trend term.
+        let trending: Vec<f64> = stationary
+            .iter()
+            .enumerate()
+            .map(|(t, y)| y + 0.05 * t as f64)
+            .collect();
+        let test = augmented_dickey_fuller(
+            &trending,
+            AdfRegression::ConstantTrend,
+            LagSelection::Aic(4),
+        )
+        .unwrap();
+        assert!(test.rejects_unit_root(SignificanceLevel::OnePercent));
+        assert_eq!(test.n_obs, 1000 - test.lags - 1);
+    }
+
+    #[test]
+    fn test_engle_granger() {
+        let x = ar1(1.0, 1000, 3);
+        let spread = ar1(0.7, 1000, 4);
+        let y: Vec<f64> = x
+            .iter()
+            .zip(&spread)
+            .map(|(x, s)| 1.0 + 1.5 * x + s)
+            .collect();
+
+        let test = engle_granger(&y, &x, LagSelection::Aic(4)).unwrap();
+        assert!((test.hedge_ratio - 1.5).abs() < 0.02);
+        assert!(test.adf.rejects_unit_root(SignificanceLevel::OnePercent));
+
+        // Two independent random walks are not cointegrated.
+        let z = ar1(1.0, 1000, 5);
+        let test = engle_granger(&z, &x, LagSelection::Fixed(1)).unwrap();
+        assert!(!test.adf.rejects_unit_root(SignificanceLevel::FivePercent));
+
+        // Time series are joined on their dates.
+        let start = date!(2024 - 01 - 01);
+        let series = |values: &[f64]| {
+            let dates = (0..values.len() as i64)
+                .map(|i| start + Duration::days(i))
+                .collect();
+            TimeSeries::new(dates, values.to_vec()).unwrap()
+        };
+        let test_series = series(&y)
+            .engle_granger(&series(&x[..900]), LagSelection::Fixed(1))
+            .unwrap();
+        assert_eq!(test_series.residuals.len(), 900);
+    }
+
+    #[test]
+    fn test_half_life() {
+        let series = ar1(0.9, 20_000, 6);
+        let expected = -std::f64::consts::LN_2 / 0.9_f64.ln();
+
+        assert!((half_life(&series).unwrap() - expected).abs() < 0.5);
+        let explosive: Vec<f64> = (0..100).map(|t| 1.01_f64.powi(t)).collect();
+        assert!(half_life(&explosive).is_err());
+        assert!(engle_granger(&[1.0, 2.0], &[1.0], LagSelection::Fixed(0)).is_err());
+        assert!(augmented_dickey_fuller(
+            &[1.0, f64::NAN, 2.0],
+            AdfRegression::Constant,
+            LagSelection::Fixed(0)
+        )
+        .is_err());
+    }
+}
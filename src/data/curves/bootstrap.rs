@@ -0,0 +1,332 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Yield curve bootstrapping from deposit and par swap quotes.
+//!
+//! The bootstrapped curve holds continuously compounded zero rates, with
+//! year fractions measured from the valuation date using the default day
+//! count convention (the same one [`Curve::discount_factor`] uses), so
+//! the curve reprices every input quote.
+//!
+//! [`Curve::discount_factor`]: crate::data::Curve::discount_factor
+//!
+//! ```
+//! use RustQuant::data::{bootstrap_yield_curve, Curve, BootstrapQuote};
+//! use RustQuant::time::Frequency;
+//! use time::macros::date;
+//!
+//! let quotes = [
+//!     BootstrapQuote::Deposit { maturity: date!(2024 - 07 - 01), rate: 0.050 },
+//!     BootstrapQuote::Swap {
+//!         maturity: date!(2029 - 01 - 01),
+//!         rate: 0.045,
+//!         frequency: Frequency::Annually,
+//!     },
+//! ];
+//!
+//! let curve = bootstrap_yield_curve(date!(2024 - 01 - 01), &quotes).unwrap();
+//! let df = curve.discount_factor(date!(2026 - 01 - 01)).unwrap();
+//! ```
+
+use super::YieldCurve;
+use crate::error::RustQuantError;
+use crate::time::{add_months, DayCountConvention, Frequency};
+use std::collections::BTreeMap;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A market quote used to bootstrap a yield curve, tagged by `type`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum BootstrapQuote {
+    /// Deposit with simple interest from the valuation date to maturity.
+    Deposit {
+        /// Maturity date.
+        maturity: Date,
+        /// Simple deposit rate.
+        rate: f64,
+    },
+
+    /// Par swap (or par bond): the fixed leg, paid at `frequency` and
+    /// rolled back from maturity, is worth par.
+    Swap {
+        /// Maturity date.
+        maturity: Date,
+        /// Par fixed rate.
+        rate: f64,
+        /// Fixed leg payment frequency.
+        frequency: Frequency,
+    },
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BootstrapQuote {
+    /// Maturity date of the quote.
+    #[must_use]
+    pub fn maturity(&self) -> Date {
+        match self {
+            Self::Deposit { maturity, .. } | Self::Swap { maturity, .. } => *maturity,
+        }
+    }
+
+    /// Quoted rate.
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        match self {
+            Self::Deposit { rate, .. } | Self::Swap { rate, .. } => *rate,
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bootstrap a [`YieldCurve`] of continuously compounded zero rates.
+///
+/// Quotes are solved in order of maturity. Deposits give the discount
+/// factor directly; each swap is solved (by bisection) for the zero rate
+/// at its maturity, with zero rates linearly interpolated between pillars
+/// for the intermediate fixed leg dates. The rate at the valuation date is
+/// the first pillar's, i.e. the curve is flat at the short end.
+///
+/// # Errors
+///
+/// Returns [`RustQuantError::InvalidArgument`] if there are no quotes, a
+/// quote is not finite or does not mature after the valuation date, two
+/// quotes share a maturity, or a swap frequency does not divide a year
+/// into whole months.
+pub fn bootstrap_yield_curve(
+    valuation_date: Date,
+    quotes: &[BootstrapQuote],
+) -> Result<YieldCurve, RustQuantError> {
+    if quotes.is_empty() {
+        return Err(RustQuantError::InvalidArgument(
+            "No quotes to bootstrap.".to_string(),
+        ));
+    }
+
+    let mut quotes = quotes.to_vec();
+    quotes.sort_by_key(BootstrapQuote::maturity);
+
+    let dcc = DayCountConvention::default();
+    let year_fraction = |date: Date| dcc.day_count_factor(valuation_date, date);
+
+    let mut pillars: BTreeMap<Date, f64> = BTreeMap::new();
+
+    for quote in &quotes {
+        let maturity = quote.maturity();
+
+        if !quote.rate().is_finite() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Quote maturing {maturity} is not finite: {}.",
+                quote.rate()
+            )));
+        }
+        if maturity <= valuation_date {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Quote maturing {maturity} does not mature after {valuation_date}."
+            )));
+        }
+        if pillars.contains_key(&maturity) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Duplicate quote maturity: {maturity}."
+            )));
+        }
+
+        let T = year_fraction(maturity);
+
+        let zero = match *quote {
+            BootstrapQuote::Deposit { rate, .. } => f64::ln(1.0 + rate * T) / T,
+            BootstrapQuote::Swap {
+                rate, frequency, ..
+            } => {
+                let per_year = frequency as i32;
+
+                if per_year > 12 || 12 % per_year != 0 {
+                    return Err(RustQuantError::InvalidArgument(format!(
+                        "Swap frequency {frequency:?} is not a whole number of months."
+                    )));
+                }
+
+                let schedule = fixed_leg_dates(valuation_date, maturity, 12 / per_year);
+
+                // Value of the fixed leg plus the final notional, less par,
+                // for a trial zero rate at maturity. Decreasing in `z`.
+                let residual = |z: f64| {
+                    let discount = |date: Date| {
+                        let r = interpolate(&pillars, maturity, z, date);
+                        f64::exp(-r * year_fraction(date))
+                    };
+
+                    let annuity = schedule
+                        .windows(2)
+                        .map(|w| dcc.day_count_factor(w[0], w[1]) * discount(w[1]))
+                        .sum::<f64>();
+
+                    rate * annuity + discount(maturity) - 1.0
+                };
+
+                bisect(residual, -1.0, 1.0)
+            }
+        };
+
+        pillars.insert(maturity, zero);
+    }
+
+    let short_rate = *pillars.values().next().unwrap();
+    pillars.insert(valuation_date, short_rate);
+
+    Ok(YieldCurve::new(pillars))
+}
+
+/// Fixed leg dates rolled back from `maturity` every `months`, starting
+/// with the valuation date (so the first period may be a short stub).
+fn fixed_leg_dates(valuation_date: Date, maturity: Date, months: i32) -> Vec<Date> {
+    let mut dates = vec![maturity];
+    let mut k = 1;
+
+    loop {
+        let date = add_months(maturity, -months * k);
+
+        if date <= valuation_date {
+            dates.push(valuation_date);
+            break;
+        }
+
+        dates.push(date);
+        k += 1;
+    }
+
+    dates.reverse();
+    dates
+}
+
+/// Zero rate at `date`, linearly interpolated between the pillars solved
+/// so far and a trial rate `z` at `maturity` (flat before the first pillar).
+fn interpolate(pillars: &BTreeMap<Date, f64>, maturity: Date, z: f64, date: Date) -> f64 {
+    if date >= maturity {
+        return z;
+    }
+
+    let (x1, y1) = pillars
+        .range(date..)
+        .next()
+        .map_or((maturity, z), |(d, r)| (*d, *r));
+
+    match pillars.range(..date).next_back() {
+        None => y1,
+        Some((&x0, &y0)) => {
+            let w = (date - x0).whole_days() as f64 / (x1 - x0).whole_days() as f64;
+            y0 + w * (y1 - y0)
+        }
+    }
+}
+
+/// Root of a decreasing function on `[lo, hi]` by bisection.
+fn bisect<F: Fn(f64) -> f64>(f: F, mut lo: f64, mut hi: f64) -> f64 {
+    for _ in 0..200 {
+        let mid = 0.5 * (lo + hi);
+
+        if f(mid) > 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+
+        if hi - lo < 1e-15 {
+            break;
+        }
+    }
+
+    0.5 * (lo + hi)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bootstrap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::Curve;
+    use time::macros::date;
+
+    #[test]
+    fn test_bootstrap_reprices_quotes() {
+        let valuation_date = date!(2024 - 01 - 01);
+        let dcc = DayCountConvention::default();
+
+        let quotes = [
+            BootstrapQuote::Deposit {
+                maturity: date!(2024 - 07 - 01),
+                rate: 0.05,
+            },
+            BootstrapQuote::Swap {
+                maturity: date!(2026 - 01 - 01),
+                rate: 0.047,
+                frequency: Frequency::SemiAnnually,
+            },
+            BootstrapQuote::Swap {
+                maturity: date!(2029 - 01 - 01),
+                rate: 0.045,
+                frequency: Frequency::Annually,
+            },
+        ];
+
+        let curve = bootstrap_yield_curve(valuation_date, &quotes).unwrap();
+
+        assert_eq!(curve.initial_date(), valuation_date);
+
+        let T = dcc.day_count_factor(valuation_date, date!(2024 - 07 - 01));
+        assert_approx_equal!(
+            curve.discount_factor(date!(2024 - 07 - 01)).unwrap(),
+            1.0 / (1.0 + 0.05 * T),
+            1e-12
+        );
+
+        for (maturity, rate, months) in [
+            (date!(2026 - 01 - 01), 0.047, 6),
+            (date!(2029 - 01 - 01), 0.045, 12),
+        ] {
+            let dates = fixed_leg_dates(valuation_date, maturity, months);
+            let annuity = dates
+                .windows(2)
+                .map(|w| dcc.day_count_factor(w[0], w[1]) * curve.discount_factor(w[1]).unwrap())
+                .sum::<f64>();
+
+            assert_approx_equal!(
+                rate * annuity + curve.discount_factor(maturity).unwrap(),
+                1.0,
+                1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn test_bootstrap_rejects_bad_quotes() {
+        let valuation_date = date!(2024 - 01 - 01);
+        let deposit = BootstrapQuote::Deposit {
+            maturity: date!(2024 - 07 - 01),
+            rate: 0.05,
+        };
+
+        assert!(bootstrap_yield_curve(valuation_date, &[]).is_err());
+        assert!(bootstrap_yield_curve(valuation_date, &[deposit, deposit]).is_err());
+        assert!(bootstrap_yield_curve(date!(2025 - 01 - 01), &[deposit]).is_err());
+    }
+}
@@ -11,7 +11,8 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::time::DayCountConvention;
+use crate::error::RustQuantError;
+use crate::time::{DayCountConvention, Tenor};
 use std::{collections::BTreeMap, time::Duration};
 use time::Date;
 
@@ -23,13 +24,25 @@ use time::Date;
 #[allow(clippy::module_name_repetitions)]
 pub trait CurveModel {
     /// Returns the forward rate for a given date.
-    fn forward_rate(&self, date: Date) -> f64;
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the date is not in the future.
+    fn forward_rate(&self, date: Date) -> Result<f64, RustQuantError>;
 
     /// Returns the spot rate for a given date.
-    fn spot_rate(&self, date: Date) -> f64;
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the date is not in the future.
+    fn spot_rate(&self, date: Date) -> Result<f64, RustQuantError>;
 
     /// Returns the discount factor for a given date.
-    fn discount_factor(&self, date: Date) -> f64;
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the date is not in the future.
+    fn discount_factor(&self, date: Date) -> Result<f64, RustQuantError>;
 
     /// Calibrates the model to a set of market rates.
     #[must_use]
@@ -48,7 +61,12 @@ pub trait Curve {
     fn update_rate(&mut self, date: Date, rate: f64);
 
     /// Create a new curve from a set of dates and rates.
-    fn from_dates_and_rates(dates: &[Date], rates: &[f64]) -> Self;
+    ///
+    /// Returns [`RustQuantError::InvalidArgument`] if the inputs are empty,
+    /// have different lengths, contain duplicate dates, or non-finite rates.
+    fn from_dates_and_rates(dates: &[Date], rates: &[f64]) -> Result<Self, RustQuantError>
+    where
+        Self: Sized;
 
     /// Create a new curve from an initial date, a set of rates, and a set of
     /// durations.
@@ -61,7 +79,28 @@ pub trait Curve {
         initial_date: Date,
         rates: &[f64],
         durations: &[Duration],
-    ) -> Self;
+    ) -> Result<Self, RustQuantError>
+    where
+        Self: Sized;
+
+    /// Create a new curve from an initial date, and a set of market tenors
+    /// (e.g. "3M", "1Y", "10Y") with their corresponding rates.
+    /// Each rate is placed at the initial date plus its tenor.
+    fn from_initial_date_tenors_and_rates(
+        initial_date: Date,
+        tenors: &[Tenor],
+        rates: &[f64],
+    ) -> Result<Self, RustQuantError>
+    where
+        Self: Sized,
+    {
+        let dates = tenors
+            .iter()
+            .map(|tenor| initial_date + *tenor)
+            .collect::<Vec<Date>>();
+
+        Self::from_dates_and_rates(&dates, rates)
+    }
 
     /// Function to find the interval of dates that contains the given date.
     /// The interval is defined by the two dates that are closest to the given
@@ -70,7 +109,6 @@ pub trait Curve {
 
     /// Returns the rate for the given date, using linear interpolation for
     /// dates between the curve's initial and terminal dates.
-    /// If the date is outside the curve's range, we panic.
     ///
     /// We use the following formula for the interpolation:
     ///
@@ -81,7 +119,12 @@ pub trait Curve {
     /// Note: there must be at least two points in the curve, otherwise
     /// we consider the curve to be a flat rate, and return the same rate
     /// for all dates.
-    fn rate(&self, date: Date) -> f64;
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the curve has no points, or
+    ///   the date is outside the curve's range.
+    fn rate(&self, date: Date) -> Result<f64, RustQuantError>;
 
     /// Returns the discount factor for the given date.
     /// This is a convenience function that calls [`rate`](Curve::rate) to get the rate for
@@ -90,25 +133,36 @@ pub trait Curve {
     /// $$
     /// p(t) = e^{- r \cdot t}
     /// $$
-    fn discount_factor(&self, date: Date) -> f64 {
+    ///
+    /// # Errors:
+    ///
+    /// * Any error returned by [`rate`](Curve::rate).
+    fn discount_factor(&self, date: Date) -> Result<f64, RustQuantError> {
+        let rate = self.rate(date)?;
         let t = DayCountConvention::default().day_count_factor(self.initial_date(), date);
 
-        f64::exp(-self.rate(date) * t)
+        Ok(f64::exp(-rate * t))
     }
 
     /// Returns multiple discount factors for the given dates.
     /// This is a convenience function that calls [`discount_factor`](Curve::discount_factor) for each
     /// date.
-    fn discount_factors(&self, dates: &[Date]) -> Vec<f64> {
+    ///
+    /// # Errors:
+    ///
+    /// * The first error returned by [`discount_factor`](Curve::discount_factor).
+    fn discount_factors(&self, dates: &[Date]) -> Result<Vec<f64>, RustQuantError> {
         dates
             .iter()
             .map(|date| self.discount_factor(*date))
-            .collect::<Vec<f64>>()
+            .collect()
     }
 }
 
 #[allow(clippy::module_name_repetitions)]
 /// Yield curve struct.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct YieldCurve {
     /// Map of dates and rates.
     /// The dates are the keys and the rates are the values.
@@ -157,14 +211,36 @@ impl Curve for YieldCurve {
     }
 
     #[allow(clippy::similar_names)]
-    fn from_dates_and_rates(dates: &[Date], rates: &[f64]) -> Self {
+    fn from_dates_and_rates(dates: &[Date], rates: &[f64]) -> Result<Self, RustQuantError> {
+        if dates.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "The curve has no points.".to_string(),
+            ));
+        }
+        if dates.len() != rates.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Got {} dates but {} rates.",
+                dates.len(),
+                rates.len()
+            )));
+        }
+
         let mut rates_map = BTreeMap::new();
 
         for (date, rate) in dates.iter().zip(rates.iter()) {
-            rates_map.insert(*date, *rate);
+            if !rate.is_finite() {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "Rate at {date} is not finite: {rate}."
+                )));
+            }
+            if rates_map.insert(*date, *rate).is_some() {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "Duplicate curve date: {date}."
+                )));
+            }
         }
 
-        Self { rates: rates_map }
+        Ok(Self { rates: rates_map })
     }
 
     #[allow(clippy::similar_names)]
@@ -172,7 +248,16 @@ impl Curve for YieldCurve {
         initial_date: Date,
         rates: &[f64],
         durations: &[Duration],
-    ) -> Self {
+    ) -> Result<Self, RustQuantError> {
+        if durations.len() + 1 != rates.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected {} durations for {} rates, got {}.",
+                rates.len().saturating_sub(1),
+                rates.len(),
+                durations.len()
+            )));
+        }
+
         let mut dates = vec![initial_date];
 
         for duration in durations {
@@ -182,17 +267,31 @@ impl Curve for YieldCurve {
         Self::from_dates_and_rates(&dates, rates)
     }
 
-    fn rate(&self, date: Date) -> f64 {
+    fn rate(&self, date: Date) -> Result<f64, RustQuantError> {
         let n = self.rates.len();
 
         match n {
-            0 => panic!("The curve has no points."),
-            1 => *self.rates.values().next().unwrap(),
+            0 => Err(RustQuantError::InvalidArgument(
+                "The curve has no points.".to_string(),
+            )),
+            1 => Ok(*self.rates.values().next().unwrap()),
+            _ if date < self.initial_date() || date > self.terminal_date() => {
+                Err(RustQuantError::InvalidArgument(format!(
+                    "Date {date} is outside the curve's range [{}, {}].",
+                    self.initial_date(),
+                    self.terminal_date()
+                )))
+            }
             _ => {
                 let (x0, x1) = self.find_date_interval(date);
                 let (y0, y1) = (*self.rates.get(&x0).unwrap(), *self.rates.get(&x1).unwrap());
 
-                (y0 * (x1 - date) + y1 * (date - x0)) / (x1 - x0)
+                // On a pillar the interval is degenerate (`x0 == x1`).
+                if x0 == x1 {
+                    return Ok(y0);
+                }
+
+                Ok((y0 * (x1 - date) + y1 * (date - x0)) / (x1 - x0))
             }
         }
     }
@@ -312,7 +411,7 @@ mod tests_curves {
             t0 + Duration::days(360),
         ];
 
-        let yield_curve = YieldCurve::from_dates_and_rates(&date_vec, &rate_vec);
+        let yield_curve = YieldCurve::from_dates_and_rates(&date_vec, &rate_vec).unwrap();
 
         println!("Curve: {:?}", yield_curve.rates);
 
@@ -321,9 +420,9 @@ mod tests_curves {
         let date2 = OffsetDateTime::UNIX_EPOCH.date() + Duration::days(80);
         let date3 = OffsetDateTime::UNIX_EPOCH.date() + Duration::days(250);
 
-        let df1 = yield_curve.discount_factor(date1);
-        let df2 = yield_curve.discount_factor(date2);
-        let df3 = yield_curve.discount_factor(date3);
+        let df1 = yield_curve.discount_factor(date1).unwrap();
+        let df2 = yield_curve.discount_factor(date2).unwrap();
+        let df3 = yield_curve.discount_factor(date3).unwrap();
 
         println!("df1: {:?}", df1);
         println!("df2: {:?}", df2);
@@ -333,4 +432,59 @@ mod tests_curves {
 
         assert!(df1 > df2 && df2 > df3);
     }
+
+    #[test]
+    fn test_yield_curve_invalid_inputs() {
+        let t0 = OffsetDateTime::UNIX_EPOCH.date();
+        let dates = [t0 + Duration::days(30), t0 + Duration::days(60)];
+
+        assert!(YieldCurve::from_dates_and_rates(&[], &[]).is_err());
+        assert!(YieldCurve::from_dates_and_rates(&dates, &[0.03]).is_err());
+        assert!(YieldCurve::from_dates_and_rates(&dates, &[0.03, f64::NAN]).is_err());
+        assert!(YieldCurve::from_dates_and_rates(&[dates[0], dates[0]], &[0.03, 0.04]).is_err());
+        assert!(YieldCurve::from_initial_date_rates_and_durations(
+            t0,
+            &[0.03, 0.04],
+            &[std::time::Duration::from_secs(86_400); 2],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_yield_curve_rate_errors() {
+        let t0 = OffsetDateTime::UNIX_EPOCH.date();
+        let dates = [t0 + Duration::days(30), t0 + Duration::days(60)];
+        let yield_curve = YieldCurve::from_dates_and_rates(&dates, &[0.03, 0.04]).unwrap();
+
+        assert!(YieldCurve::new(BTreeMap::new()).rate(t0).is_err());
+        assert!(YieldCurve::new(BTreeMap::new())
+            .discount_factor(t0)
+            .is_err());
+        assert!(yield_curve.rate(t0).is_err());
+        assert!(yield_curve.rate(t0 + Duration::days(90)).is_err());
+        assert!(yield_curve.discount_factors(&[dates[1], t0]).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests_curves_serde {
+    use super::*;
+    use time::macros::date;
+
+    fn curve(rate: f64) -> YieldCurve {
+        YieldCurve::from_dates_and_rates(
+            &[date!(2024 - 06 - 30), date!(2025 - 06 - 30)],
+            &[rate, rate + 0.01],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_yield_curve_round_trip() {
+        let json = serde_json::to_string(&curve(0.03)).unwrap();
+        assert!(json.contains("2024-06-30"), "{json}");
+
+        let round_trip: YieldCurve = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.rates, curve(0.03).rates);
+    }
 }
@@ -7,6 +7,10 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+/// Yield curve bootstrapping.
+pub mod bootstrap;
+pub use bootstrap::*;
+
 /// Curve data.
 pub mod curve;
 pub use curve::*;
@@ -8,6 +8,7 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::curves::{Curve, CurveModel};
+use crate::error::RustQuantError;
 use crate::time::{today, DayCountConvention};
 use time::Date;
 
@@ -16,6 +17,7 @@ use time::Date;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Nelson-Siegel (1987) model parameters.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NelsonSiegel {
     beta0: f64,
     beta1: f64,
@@ -40,35 +42,42 @@ impl NelsonSiegel {
     }
 }
 
+/// Year fraction from today to `date`, which must be in the future.
+fn time_to_future_date(date: Date) -> Result<f64, RustQuantError> {
+    if date <= today() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "Date must be in the future, got {date}."
+        )));
+    }
+
+    Ok(DayCountConvention::default().day_count_factor(today(), date))
+}
+
 impl CurveModel for NelsonSiegel {
     /// Returns the forward rate for a given date.
-    fn forward_rate(&self, date: Date) -> f64 {
-        assert!(date > today(), "Date must be in the future.");
-
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+    fn forward_rate(&self, date: Date) -> Result<f64, RustQuantError> {
+        let tau = time_to_future_date(date)?;
 
         let term1 = f64::exp(-tau / self.lambda);
         let term2 = (tau / self.lambda) * term1;
 
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2
+        Ok(self.beta0 + self.beta1 * term1 + self.beta2 * term2)
     }
 
     /// Returns the spot rate for a given date.
-    fn spot_rate(&self, date: Date) -> f64 {
-        assert!(date > today(), "Date must be in the future.");
-
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+    fn spot_rate(&self, date: Date) -> Result<f64, RustQuantError> {
+        let tau = time_to_future_date(date)?;
 
         let term1 = self.lambda * (1. - f64::exp(-tau / self.lambda)) / tau;
         let term2 = term1 - f64::exp(-tau / self.lambda);
 
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2
+        Ok(self.beta0 + self.beta1 * term1 + self.beta2 * term2)
     }
 
-    fn discount_factor(&self, date: Date) -> f64 {
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+    fn discount_factor(&self, date: Date) -> Result<f64, RustQuantError> {
+        let tau = time_to_future_date(date)?;
 
-        f64::exp(-self.spot_rate(date) * tau / 100.)
+        Ok(f64::exp(-self.spot_rate(date)? * tau / 100.))
     }
 
     fn calibrate<C: Curve>(&self, _curve: C) -> Self {
@@ -101,14 +110,18 @@ mod tests_nelson_siegel {
 
         let _forward_curve = dates
             .iter()
-            .map(|date| ns.forward_rate(*date))
+            .map(|date| ns.forward_rate(*date).unwrap())
             .collect::<Vec<_>>();
 
         let _discount_curve = dates
             .iter()
-            .map(|date| ns.discount_factor(*date))
+            .map(|date| ns.discount_factor(*date).unwrap())
             .collect::<Vec<_>>();
 
+        assert!(ns.forward_rate(today()).is_err());
+        assert!(ns.spot_rate(today() - Duration::days(1)).is_err());
+        assert!(ns.discount_factor(today()).is_err());
+
         // plot_vector!(forward_curve, "./images/nelson_siegel_forward.png");
         // plot_vector!(discount_curve, "./images/nelson_siegel_discount.png");
     }
@@ -8,6 +8,7 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::curves::{Curve, CurveModel};
+use crate::error::RustQuantError;
 use crate::time::{today, DayCountConvention};
 use time::Date;
 
@@ -16,6 +17,7 @@ use time::Date;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Nelson-Siegel-Svensson (1994) model parameters.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NelsonSiegelSvensson {
     beta0: f64,
     beta1: f64,
@@ -51,38 +53,45 @@ impl NelsonSiegelSvensson {
     }
 }
 
+/// Year fraction from today to `date`, which must be in the future.
+fn time_to_future_date(date: Date) -> Result<f64, RustQuantError> {
+    if date <= today() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "Date must be in the future, got {date}."
+        )));
+    }
+
+    Ok(DayCountConvention::default().day_count_factor(today(), date))
+}
+
 impl CurveModel for NelsonSiegelSvensson {
     /// Returns the forward rate for a given date.
-    fn forward_rate(&self, date: Date) -> f64 {
-        assert!(date > today(), "Date must be in the future.");
-
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+    fn forward_rate(&self, date: Date) -> Result<f64, RustQuantError> {
+        let tau = time_to_future_date(date)?;
 
         let term1 = f64::exp(-tau / self.lambda1);
         let term2 = (tau / self.lambda1) * term1;
         let term3 = (tau / self.lambda2) * f64::exp(-tau / self.lambda2);
 
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3
+        Ok(self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3)
     }
 
     /// Returns the spot rate for a given date.
-    fn spot_rate(&self, date: Date) -> f64 {
-        assert!(date > today(), "Date must be in the future.");
-
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+    fn spot_rate(&self, date: Date) -> Result<f64, RustQuantError> {
+        let tau = time_to_future_date(date)?;
 
         let term1 = self.lambda1 * (1. - f64::exp(-tau / self.lambda1)) / tau;
         let term2 = term1 - f64::exp(-tau / self.lambda1);
         let term3 = self.lambda2 * (1. - f64::exp(-tau / self.lambda2)) / tau
             - f64::exp(-tau / self.lambda2);
 
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3
+        Ok(self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3)
     }
 
-    fn discount_factor(&self, date: Date) -> f64 {
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+    fn discount_factor(&self, date: Date) -> Result<f64, RustQuantError> {
+        let tau = time_to_future_date(date)?;
 
-        f64::exp(-self.spot_rate(date) * tau / 100.)
+        Ok(f64::exp(-self.spot_rate(date)? * tau / 100.))
     }
 
     fn calibrate<C: Curve>(&self, _curve: C) -> Self {
@@ -117,14 +126,18 @@ mod tests_nelson_siegel_svensson {
 
         let _forward_curve = dates
             .iter()
-            .map(|date| nss.forward_rate(*date))
+            .map(|date| nss.forward_rate(*date).unwrap())
             .collect::<Vec<_>>();
 
         let _discount_curve = dates
             .iter()
-            .map(|date| nss.discount_factor(*date))
+            .map(|date| nss.discount_factor(*date).unwrap())
             .collect::<Vec<_>>();
 
+        assert!(nss.forward_rate(today()).is_err());
+        assert!(nss.spot_rate(today() - Duration::days(1)).is_err());
+        assert!(nss.discount_factor(today()).is_err());
+
         // plot_vector!(forward_curve, "./images/nelson_siegel_svensson_forward.png");
         // plot_vector!(
         //     discount_curve,
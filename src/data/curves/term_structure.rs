@@ -13,12 +13,15 @@ use std::fmt::Display;
 use time::Date;
 
 /// Surface data.
+// `f64` keys are not `Ord`, so the surface can be serialized but not deserialized.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Surface {
     /// Nodes of the surface.
     pub nodes: BTreeMap<f64, TermStructure>,
 }
 
 /// Term structure data.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TermStructure {
     /// Nodes of the term structure.
     pub nodes: BTreeMap<Date, f64>,
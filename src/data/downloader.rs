@@ -0,0 +1,282 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Async market data downloader (Yahoo! Finance).
+//!
+//! Unlike [`YahooFinanceData`](crate::data::YahooFinanceData), which stores
+//! Polars `DataFrame`s, the downloader returns the crate's native types:
+//! price histories as a [`TimeSeries`] of [`Bar`]s, and option chains as
+//! [`OptionQuote`]s.
+//!
+//! Requires the `data` feature (enabled by default).
+//!
+//! ```no_run
+//! use RustQuant::data::*;
+//! use time::macros::date;
+//!
+//! let downloader = YahooFinanceDownloader::new().unwrap();
+//!
+//! let history = tokio_test::block_on(downloader.price_history(
+//!     "AAPL",
+//!     date!(2023 - 01 - 01),
+//!     date!(2024 - 01 - 01),
+//! ))
+//! .unwrap();
+//!
+//! let returns = history
+//!     .map(|bar| bar.close)
+//!     .returns(ReturnsType::Logarithmic);
+//!
+//! let chain = tokio_test::block_on(downloader.option_chain("AAPL")).unwrap();
+//! ```
+
+use crate::data::{Bar, OptionQuote, TimeSeries};
+use crate::error::RustQuantError;
+use crate::instruments::options::TypeFlag;
+use time::{Date, Duration, OffsetDateTime, Time};
+use yahoo_finance_api as yahoo;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Async Yahoo! Finance downloader.
+pub struct YahooFinanceDownloader {
+    connector: yahoo::YahooConnector,
+}
+
+/// Option contract identified by an OCC option symbol,
+/// e.g. `AAPL230526C00250000`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionSymbol {
+    /// Underlying root symbol.
+    pub root: String,
+    /// Expiry date.
+    pub expiry: Date,
+    /// Call or put.
+    pub is_call: bool,
+    /// Strike price.
+    pub strike: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl YahooFinanceDownloader {
+    /// Create a new downloader.
+    ///
+    /// # Errors
+    ///
+    /// If the HTTP client cannot be built.
+    pub fn new() -> Result<Self, RustQuantError> {
+        Ok(Self {
+            connector: yahoo::YahooConnector::builder().build()?,
+        })
+    }
+
+    /// Daily OHLCV bars between `start` and `end` (inclusive).
+    ///
+    /// # Errors
+    ///
+    /// If the request fails or the response cannot be parsed.
+    pub async fn price_history(
+        &self,
+        ticker: &str,
+        start: Date,
+        end: Date,
+    ) -> Result<TimeSeries<Bar>, RustQuantError> {
+        let start = OffsetDateTime::new_utc(start, Time::MIDNIGHT);
+        let end = OffsetDateTime::new_utc(end + Duration::days(1), Time::MIDNIGHT);
+
+        let response = self.connector.get_quote_history(ticker, start, end).await?;
+
+        bars_from_quotes(&response.quotes()?)
+    }
+
+    /// Option chain of the given ticker.
+    ///
+    /// # Errors
+    ///
+    /// If the request fails, or an option symbol cannot be parsed.
+    pub async fn option_chain(&self, ticker: &str) -> Result<Vec<OptionQuote>, RustQuantError> {
+        let response = self.connector.search_options(ticker).await?;
+
+        response.options.iter().map(option_quote).collect()
+    }
+}
+
+/// Convert Yahoo! Finance quotes into a time series of bars.
+///
+/// # Errors
+///
+/// If two quotes fall on the same date.
+pub fn bars_from_quotes(quotes: &[yahoo::Quote]) -> Result<TimeSeries<Bar>, RustQuantError> {
+    // Timestamps are in seconds since the UNIX epoch.
+    let dates = quotes
+        .iter()
+        .map(|quote| {
+            OffsetDateTime::from_unix_timestamp(quote.timestamp as i64)
+                .map(OffsetDateTime::date)
+                .map_err(|e| RustQuantError::InvalidArgument(e.to_string()))
+        })
+        .collect::<Result<Vec<Date>, RustQuantError>>()?;
+
+    let bars = quotes
+        .iter()
+        .map(|quote| Bar {
+            open: quote.open,
+            high: quote.high,
+            low: quote.low,
+            close: quote.close,
+            volume: quote.volume as f64,
+        })
+        .collect();
+
+    TimeSeries::new(dates, bars)
+}
+
+/// Convert a Yahoo! Finance option result into an option quote.
+fn option_quote(option: &yahoo::YOptionResult) -> Result<OptionQuote, RustQuantError> {
+    let symbol: OptionSymbol = option.name.parse()?;
+
+    // Yahoo! Finance reports missing quotes as zero.
+    let positive = |value: f64| (value > 0.0).then_some(value);
+
+    Ok(OptionQuote {
+        expiry: symbol.expiry,
+        strike: option.strike,
+        type_flag: if symbol.is_call {
+            TypeFlag::Call
+        } else {
+            TypeFlag::Put
+        },
+        bid: positive(option.bid),
+        ask: positive(option.ask),
+        last: positive(option.last_price),
+        implied_volatility: positive(option.impl_volatility),
+        open_interest: Some(f64::from(option.open_interest)),
+    })
+}
+
+impl std::str::FromStr for OptionSymbol {
+    type Err = RustQuantError;
+
+    /// Parse an OCC option symbol: root, expiry (`YYMMDD`), `C`/`P`, and the
+    /// strike times 1000 (8 digits).
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || RustQuantError::InvalidArgument(format!("invalid option symbol: {symbol}"));
+
+        let symbol = symbol.trim();
+        let split = symbol.len().checked_sub(15).ok_or_else(invalid)?;
+        let (root, code) = (symbol.get(..split).ok_or_else(invalid)?, &symbol[split..]);
+
+        let number = |range: std::ops::Range<usize>| -> Result<u32, RustQuantError> {
+            code.get(range)
+                .and_then(|digits| digits.parse().ok())
+                .ok_or_else(invalid)
+        };
+
+        let year = 2000 + number(0..2)? as i32;
+        let month = time::Month::try_from(number(2..4)? as u8).map_err(|_| invalid())?;
+        let day = number(4..6)? as u8;
+        let expiry = Date::from_calendar_date(year, month, day).map_err(|_| invalid())?;
+
+        let is_call = match &code[6..7] {
+            "C" => true,
+            "P" => false,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            root: root.to_string(),
+            expiry,
+            is_call,
+            strike: f64::from(number(7..15)?) / 1000.0,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_downloader {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn test_option_symbol() {
+        let symbol: OptionSymbol = "AAPL230526C00250000".parse().unwrap();
+
+        assert_eq!(symbol.root, "AAPL");
+        assert_eq!(symbol.expiry, date!(2023 - 05 - 26));
+        assert!(symbol.is_call);
+        assert_eq!(symbol.strike, 250.0);
+
+        let symbol: OptionSymbol = "SPY241220P00432500".parse().unwrap();
+        assert!(!symbol.is_call);
+        assert_eq!(symbol.strike, 432.5);
+
+        assert!("AAPL".parse::<OptionSymbol>().is_err());
+        assert!("AAPL231326C00250000".parse::<OptionSymbol>().is_err());
+        assert!("AAPL230526X00250000".parse::<OptionSymbol>().is_err());
+    }
+
+    #[test]
+    fn test_option_quote() {
+        let option = yahoo::YOptionResult {
+            name: "AAPL230526P00150000".to_string(),
+            strike: 150.0,
+            last_trade_date: String::new(),
+            last_price: 1.25,
+            bid: 1.2,
+            ask: 0.0,
+            change: 0.0,
+            change_pct: 0.0,
+            volume: 10,
+            open_interest: 100,
+            impl_volatility: 0.3,
+        };
+
+        let quote = option_quote(&option).unwrap();
+
+        assert!(matches!(quote.type_flag, TypeFlag::Put));
+        assert_eq!(quote.expiry, date!(2023 - 05 - 26));
+        assert_eq!(quote.bid, Some(1.2));
+        assert_eq!(quote.ask, None);
+        assert_eq!(quote.open_interest, Some(100.0));
+    }
+
+    #[test]
+    fn test_bars_from_quotes() {
+        let quote = |timestamp: u64, close: f64| yahoo::Quote {
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            volume: 1000,
+            close,
+            adjclose: close,
+        };
+
+        // 2024-01-02 and 2024-01-03, 14:30 UTC.
+        let bars =
+            bars_from_quotes(&[quote(1_704_205_800, 100.0), quote(1_704_292_200, 101.0)]).unwrap();
+
+        assert_eq!(
+            bars.dates(),
+            &[date!(2024 - 01 - 02), date!(2024 - 01 - 03)]
+        );
+        assert_eq!(bars.values()[1].close, 101.0);
+        assert_eq!(bars.values()[0].volume, 1000.0);
+    }
+}
@@ -0,0 +1,701 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Market data readers and writers.
+//!
+//! Loads OHLCV bars, option chains, and curve quotes from CSV or Parquet
+//! files (the format is inferred from the file extension) into the
+//! crate's types, and writes them back.
+//!
+//! Column names are matched case-insensitively against a list of common
+//! aliases (e.g. `date`, `timestamp`, or `time` for the date column), and
+//! numeric columns are cast to `f64`, so most vendor files load without
+//! any configuration. Errors report the missing column or the offending
+//! row.
+
+use crate::data::{io::*, TimeSeries};
+use crate::error::RustQuantError;
+#[cfg(feature = "options")]
+use crate::instruments::options::TypeFlag;
+use crate::time::Tenor;
+use polars::prelude::*;
+use time::{macros::date, Date, Duration};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// OHLCV bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar {
+    /// Opening price.
+    pub open: f64,
+    /// Highest price.
+    pub high: f64,
+    /// Lowest price.
+    pub low: f64,
+    /// Closing price.
+    pub close: f64,
+    /// Traded volume.
+    pub volume: f64,
+}
+
+/// Option quote (one row of an option chain).
+#[cfg(feature = "options")]
+#[derive(Debug, Clone, Copy)]
+pub struct OptionQuote {
+    /// Expiry date.
+    pub expiry: Date,
+    /// Strike price.
+    pub strike: f64,
+    /// Call or put.
+    pub type_flag: TypeFlag,
+    /// Bid price (if quoted).
+    pub bid: Option<f64>,
+    /// Ask price (if quoted).
+    pub ask: Option<f64>,
+    /// Last traded price (if any).
+    pub last: Option<f64>,
+    /// Implied volatility (if quoted).
+    pub implied_volatility: Option<f64>,
+    /// Open interest (if quoted).
+    pub open_interest: Option<f64>,
+}
+
+/// Curve quote: a rate for a maturity date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveQuote {
+    /// Maturity date.
+    pub maturity: Date,
+    /// Quoted rate.
+    pub rate: f64,
+}
+
+// Column aliases, matched case-insensitively.
+const DATE: &[&str] = &["date", "timestamp", "time", "datetime"];
+const OPEN: &[&str] = &["open", "o"];
+const HIGH: &[&str] = &["high", "h"];
+const LOW: &[&str] = &["low", "l"];
+const CLOSE: &[&str] = &["close", "c", "adjusted", "adj close", "adj_close"];
+const VOLUME: &[&str] = &["volume", "vol", "v"];
+#[cfg(feature = "options")]
+const EXPIRY: &[&str] = &["expiry", "expiration", "expiration_date", "maturity"];
+#[cfg(feature = "options")]
+const STRIKE: &[&str] = &["strike", "strike_price", "k"];
+#[cfg(feature = "options")]
+const TYPE: &[&str] = &["type", "option_type", "type_flag", "cp", "call_put"];
+#[cfg(feature = "options")]
+const BID: &[&str] = &["bid"];
+#[cfg(feature = "options")]
+const ASK: &[&str] = &["ask", "offer"];
+#[cfg(feature = "options")]
+const LAST: &[&str] = &["last", "last_price", "price"];
+#[cfg(feature = "options")]
+const IMPLIED_VOLATILITY: &[&str] = &["implied_volatility", "iv", "volatility"];
+#[cfg(feature = "options")]
+const OPEN_INTEREST: &[&str] = &["open_interest", "oi"];
+const MATURITY: &[&str] = &["maturity", "maturity_date", "date"];
+const TENOR: &[&str] = &["tenor", "term"];
+const RATE: &[&str] = &["rate", "yield", "zero_rate", "value"];
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl DataFormat {
+    /// Infer the data format from a file extension.
+    ///
+    /// # Errors
+    ///
+    /// If the extension is not `csv`, `json`, or `parquet`.
+    pub fn from_path(path: &str) -> Result<Self, RustQuantError> {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("csv") => Ok(Self::CSV),
+            Some("json") => Ok(Self::JSON),
+            Some("parquet") | Some("pq") => Ok(Self::PARQUET),
+            _ => Err(RustQuantError::FileOperationFailed(format!(
+                "cannot infer data format of '{path}' (expected .csv, .json or .parquet)"
+            ))),
+        }
+    }
+}
+
+/// Read a file into a `DataFrame`, inferring the format from the extension.
+fn read_frame(path: &str) -> Result<DataFrame, RustQuantError> {
+    let mut data = Data::new(DataFormat::from_path(path)?, path.to_string());
+    data.read()?;
+
+    Ok(data.data)
+}
+
+/// Write a `DataFrame` to a file, inferring the format from the extension.
+fn write_frame(path: &str, df: DataFrame) -> Result<(), RustQuantError> {
+    let mut data = Data {
+        format: DataFormat::from_path(path)?,
+        path: path.to_string(),
+        data: df,
+    };
+
+    data.write()
+}
+
+/// Find a column by its aliases (case-insensitive).
+fn find_column<'a>(df: &'a DataFrame, aliases: &[&str]) -> Option<&'a Series> {
+    df.get_columns().iter().find(|series| {
+        aliases
+            .iter()
+            .any(|alias| series.name().trim().eq_ignore_ascii_case(alias))
+    })
+}
+
+/// Find a required column by its aliases.
+fn column<'a>(df: &'a DataFrame, aliases: &[&str]) -> Result<&'a Series, RustQuantError> {
+    find_column(df, aliases).ok_or_else(|| {
+        RustQuantError::MissingInput(format!(
+            "column '{}' not found (accepted names: {})",
+            aliases[0],
+            aliases.join(", ")
+        ))
+    })
+}
+
+/// Numeric column values (cast to `f64`).
+fn floats(series: &Series) -> Result<Vec<Option<f64>>, RustQuantError> {
+    Ok(series
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .collect())
+}
+
+/// Required numeric column values: every row must have a value.
+fn required_floats(df: &DataFrame, aliases: &[&str]) -> Result<Vec<f64>, RustQuantError> {
+    let series = column(df, aliases)?;
+
+    floats(series)?
+        .into_iter()
+        .enumerate()
+        .map(|(row, value)| value.ok_or_else(|| missing_value(series, row)))
+        .collect()
+}
+
+/// Optional numeric column values (all `None` if the column is absent).
+fn optional_floats(df: &DataFrame, aliases: &[&str]) -> Result<Vec<Option<f64>>, RustQuantError> {
+    match find_column(df, aliases) {
+        Some(series) => floats(series),
+        None => Ok(vec![None; df.height()]),
+    }
+}
+
+/// String column values.
+fn strings(series: &Series) -> Result<Vec<Option<String>>, RustQuantError> {
+    Ok(series
+        .cast(&DataType::String)?
+        .str()?
+        .into_iter()
+        .map(|value| value.map(str::to_string))
+        .collect())
+}
+
+/// Date column values.
+///
+/// Accepts native date and datetime columns, as well as strings formatted as
+/// `YYYY-MM-DD` (optionally followed by a time, which is ignored).
+fn dates(df: &DataFrame, aliases: &[&str]) -> Result<Vec<Date>, RustQuantError> {
+    let series = column(df, aliases)?;
+    let epoch = date!(1970 - 01 - 01);
+
+    match series.dtype() {
+        DataType::Date | DataType::Datetime(_, _) => series
+            .cast(&DataType::Date)?
+            .cast(&DataType::Int32)?
+            .i32()?
+            .into_iter()
+            .enumerate()
+            .map(|(row, days)| {
+                days.map(|days| epoch + Duration::days(days.into()))
+                    .ok_or_else(|| missing_value(series, row))
+            })
+            .collect(),
+        _ => strings(series)?
+            .into_iter()
+            .enumerate()
+            .map(|(row, value)| {
+                let value = value.ok_or_else(|| missing_value(series, row))?;
+                parse_date(&value).ok_or_else(|| invalid_value(series, row, &value))
+            })
+            .collect(),
+    }
+}
+
+/// Parse a date formatted as `YYYY-MM-DD`, ignoring anything after the day.
+fn parse_date(value: &str) -> Option<Date> {
+    let mut parts = value.trim().get(..10)?.split('-');
+
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse::<u8>().ok()?.try_into().ok()?;
+    let day = parts.next()?.parse().ok()?;
+
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+fn missing_value(series: &Series, row: usize) -> RustQuantError {
+    RustQuantError::MissingInput(format!(
+        "row {row}: missing value in column '{}'",
+        series.name()
+    ))
+}
+
+fn invalid_value(series: &Series, row: usize, value: &str) -> RustQuantError {
+    RustQuantError::InvalidArgument(format!(
+        "row {row}: invalid value '{value}' in column '{}'",
+        series.name()
+    ))
+}
+
+/// Date column, stored as a native `Date` column.
+fn date_series(name: &str, dates: impl Iterator<Item = Date>) -> Result<Series, RustQuantError> {
+    let epoch = date!(1970 - 01 - 01);
+    let days: Vec<i32> = dates.map(|d| (d - epoch).whole_days() as i32).collect();
+
+    Ok(Series::new(name, days).cast(&DataType::Date)?)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OHLCV BARS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Convert a `DataFrame` with date, open, high, low, close, and volume
+/// columns into a time series of bars.
+///
+/// A missing volume column is treated as zero volume.
+///
+/// # Errors
+///
+/// - If a required column is missing.
+/// - If a value is missing or cannot be parsed.
+/// - If a date appears more than once.
+pub fn bars_from_dataframe(df: &DataFrame) -> Result<TimeSeries<Bar>, RustQuantError> {
+    let dates = dates(df, DATE)?;
+    let open = required_floats(df, OPEN)?;
+    let high = required_floats(df, HIGH)?;
+    let low = required_floats(df, LOW)?;
+    let close = required_floats(df, CLOSE)?;
+    let volume = optional_floats(df, VOLUME)?;
+
+    let bars = (0..df.height())
+        .map(|i| Bar {
+            open: open[i],
+            high: high[i],
+            low: low[i],
+            close: close[i],
+            volume: volume[i].unwrap_or_default(),
+        })
+        .collect();
+
+    TimeSeries::new(dates, bars)
+}
+
+/// Convert a time series of bars into a `DataFrame`.
+///
+/// # Errors
+///
+/// If the `DataFrame` cannot be built.
+pub fn bars_to_dataframe(bars: &TimeSeries<Bar>) -> Result<DataFrame, RustQuantError> {
+    let field = |name: &str, f: fn(&Bar) -> f64| {
+        Series::new(name, bars.values().iter().map(f).collect::<Vec<f64>>())
+    };
+
+    Ok(DataFrame::new(vec![
+        date_series("date", bars.dates().iter().copied())?,
+        field("open", |bar| bar.open),
+        field("high", |bar| bar.high),
+        field("low", |bar| bar.low),
+        field("close", |bar| bar.close),
+        field("volume", |bar| bar.volume),
+    ])?)
+}
+
+/// Read OHLCV bars from a CSV or Parquet file.
+///
+/// # Errors
+///
+/// See [`bars_from_dataframe`].
+pub fn read_bars(path: &str) -> Result<TimeSeries<Bar>, RustQuantError> {
+    bars_from_dataframe(&read_frame(path)?)
+}
+
+/// Write OHLCV bars to a CSV or Parquet file.
+///
+/// # Errors
+///
+/// If the file cannot be written.
+pub fn write_bars(path: &str, bars: &TimeSeries<Bar>) -> Result<(), RustQuantError> {
+    write_frame(path, bars_to_dataframe(bars)?)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// OPTION CHAINS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Parse an option type: `call`/`c` or `put`/`p` (case-insensitive).
+#[cfg(feature = "options")]
+fn parse_type_flag(value: &str) -> Option<TypeFlag> {
+    match value.trim().to_lowercase().as_str() {
+        "call" | "c" => Some(TypeFlag::Call),
+        "put" | "p" => Some(TypeFlag::Put),
+        _ => None,
+    }
+}
+
+/// Convert a `DataFrame` into an option chain.
+///
+/// Required columns: expiry, strike, and type (`call`/`put`).
+/// Optional columns: bid, ask, last, implied volatility, and open interest.
+///
+/// # Errors
+///
+/// - If a required column is missing.
+/// - If a required value is missing or cannot be parsed.
+#[cfg(feature = "options")]
+pub fn option_chain_from_dataframe(df: &DataFrame) -> Result<Vec<OptionQuote>, RustQuantError> {
+    let expiries = dates(df, EXPIRY)?;
+    let strikes = required_floats(df, STRIKE)?;
+
+    let types_column = column(df, TYPE)?;
+    let types = strings(types_column)?
+        .into_iter()
+        .enumerate()
+        .map(|(row, value)| {
+            let value = value.ok_or_else(|| missing_value(types_column, row))?;
+            parse_type_flag(&value).ok_or_else(|| invalid_value(types_column, row, &value))
+        })
+        .collect::<Result<Vec<TypeFlag>, RustQuantError>>()?;
+
+    let bid = optional_floats(df, BID)?;
+    let ask = optional_floats(df, ASK)?;
+    let last = optional_floats(df, LAST)?;
+    let implied_volatility = optional_floats(df, IMPLIED_VOLATILITY)?;
+    let open_interest = optional_floats(df, OPEN_INTEREST)?;
+
+    Ok((0..df.height())
+        .map(|i| OptionQuote {
+            expiry: expiries[i],
+            strike: strikes[i],
+            type_flag: types[i],
+            bid: bid[i],
+            ask: ask[i],
+            last: last[i],
+            implied_volatility: implied_volatility[i],
+            open_interest: open_interest[i],
+        })
+        .collect())
+}
+
+/// Convert an option chain into a `DataFrame`.
+///
+/// # Errors
+///
+/// If the `DataFrame` cannot be built.
+#[cfg(feature = "options")]
+pub fn option_chain_to_dataframe(chain: &[OptionQuote]) -> Result<DataFrame, RustQuantError> {
+    let field = |name: &str, f: fn(&OptionQuote) -> Option<f64>| {
+        Series::new(name, chain.iter().map(f).collect::<Vec<Option<f64>>>())
+    };
+
+    let types: Vec<&str> = chain
+        .iter()
+        .map(|quote| match quote.type_flag {
+            TypeFlag::Call => "call",
+            TypeFlag::Put => "put",
+        })
+        .collect();
+
+    Ok(DataFrame::new(vec![
+        date_series("expiry", chain.iter().map(|quote| quote.expiry))?,
+        field("strike", |quote| Some(quote.strike)),
+        Series::new("type", types),
+        field("bid", |quote| quote.bid),
+        field("ask", |quote| quote.ask),
+        field("last", |quote| quote.last),
+        field("implied_volatility", |quote| quote.implied_volatility),
+        field("open_interest", |quote| quote.open_interest),
+    ])?)
+}
+
+/// Read an option chain from a CSV or Parquet file.
+///
+/// # Errors
+///
+/// See [`option_chain_from_dataframe`].
+#[cfg(feature = "options")]
+pub fn read_option_chain(path: &str) -> Result<Vec<OptionQuote>, RustQuantError> {
+    option_chain_from_dataframe(&read_frame(path)?)
+}
+
+/// Write an option chain to a CSV or Parquet file.
+///
+/// # Errors
+///
+/// If the file cannot be written.
+#[cfg(feature = "options")]
+pub fn write_option_chain(path: &str, chain: &[OptionQuote]) -> Result<(), RustQuantError> {
+    write_frame(path, option_chain_to_dataframe(chain)?)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// CURVE QUOTES
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Convert a `DataFrame` into curve quotes, sorted by maturity.
+///
+/// The maturity is read from a maturity date column if present, otherwise
+/// from a tenor column (e.g. `3M`, `10Y`), relative to `reference_date`.
+///
+/// # Errors
+///
+/// - If the rate column, or both the maturity and tenor columns, are missing.
+/// - If a tenor column is used without a reference date.
+/// - If a value is missing or cannot be parsed.
+pub fn curve_quotes_from_dataframe(
+    df: &DataFrame,
+    reference_date: Option<Date>,
+) -> Result<Vec<CurveQuote>, RustQuantError> {
+    let rates = required_floats(df, RATE)?;
+
+    let maturities = match (find_column(df, MATURITY), find_column(df, TENOR)) {
+        (None, Some(tenors)) => {
+            let reference_date = reference_date.ok_or_else(|| {
+                RustQuantError::MissingInput(
+                    "a reference date is required to read tenor quotes".to_string(),
+                )
+            })?;
+
+            strings(tenors)?
+                .into_iter()
+                .enumerate()
+                .map(|(row, value)| {
+                    let value = value.ok_or_else(|| missing_value(tenors, row))?;
+                    value
+                        .trim()
+                        .parse::<Tenor>()
+                        .map(|tenor| tenor.add_to(reference_date))
+                        .map_err(|_| invalid_value(tenors, row, &value))
+                })
+                .collect::<Result<Vec<Date>, RustQuantError>>()?
+        }
+        _ => dates(df, MATURITY)?,
+    };
+
+    let mut quotes: Vec<CurveQuote> = maturities
+        .into_iter()
+        .zip(rates)
+        .map(|(maturity, rate)| CurveQuote { maturity, rate })
+        .collect();
+    quotes.sort_by_key(|quote| quote.maturity);
+
+    Ok(quotes)
+}
+
+/// Convert curve quotes into a `DataFrame`.
+///
+/// # Errors
+///
+/// If the `DataFrame` cannot be built.
+pub fn curve_quotes_to_dataframe(quotes: &[CurveQuote]) -> Result<DataFrame, RustQuantError> {
+    Ok(DataFrame::new(vec![
+        date_series("maturity", quotes.iter().map(|quote| quote.maturity))?,
+        Series::new(
+            "rate",
+            quotes.iter().map(|quote| quote.rate).collect::<Vec<f64>>(),
+        ),
+    ])?)
+}
+
+/// Read curve quotes from a CSV or Parquet file.
+///
+/// # Errors
+///
+/// See [`curve_quotes_from_dataframe`].
+pub fn read_curve_quotes(
+    path: &str,
+    reference_date: Option<Date>,
+) -> Result<Vec<CurveQuote>, RustQuantError> {
+    curve_quotes_from_dataframe(&read_frame(path)?, reference_date)
+}
+
+/// Write curve quotes to a CSV or Parquet file.
+///
+/// # Errors
+///
+/// If the file cannot be written.
+pub fn write_curve_quotes(path: &str, quotes: &[CurveQuote]) -> Result<(), RustQuantError> {
+    write_frame(path, curve_quotes_to_dataframe(quotes)?)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_market_data {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("rustquant_{}_{name}", std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn write_text(name: &str, contents: &str) -> String {
+        let path = temp_path(name);
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_data_format_from_path() {
+        assert!(matches!(
+            DataFormat::from_path("a/b.CSV"),
+            Ok(DataFormat::CSV)
+        ));
+        assert!(matches!(
+            DataFormat::from_path("b.parquet"),
+            Ok(DataFormat::PARQUET)
+        ));
+        assert!(DataFormat::from_path("b.xlsx").is_err());
+    }
+
+    #[test]
+    fn test_read_bars_csv() {
+        let path = write_text(
+            "bars.csv",
+            "Date,Open,High,Low,Close,Volume\n\
+             2024-01-03,101,103,100,102,2000\n\
+             2024-01-02,100,102,99,101,1000\n",
+        );
+
+        let bars = read_bars(&path).unwrap();
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars.dates()[0], date!(2024 - 01 - 02));
+        assert_eq!(bars.values()[1].close, 102.0);
+        assert_eq!(bars.values()[0].volume, 1000.0);
+
+        let closes = bars.map(|bar| bar.close);
+        assert_eq!(closes.values(), &[101.0, 102.0]);
+    }
+
+    #[test]
+    fn test_bars_round_trip() {
+        let bars = TimeSeries::new(
+            vec![date!(2024 - 01 - 02), date!(2024 - 01 - 03)],
+            vec![
+                Bar {
+                    open: 1.0,
+                    high: 2.0,
+                    low: 0.5,
+                    close: 1.5,
+                    volume: 10.0,
+                },
+                Bar {
+                    open: 1.5,
+                    high: 2.5,
+                    low: 1.0,
+                    close: 2.0,
+                    volume: 20.0,
+                },
+            ],
+        )
+        .unwrap();
+
+        for name in ["round_trip.csv", "round_trip.parquet"] {
+            let path = temp_path(name);
+            write_bars(&path, &bars).unwrap();
+            assert_eq!(read_bars(&path).unwrap(), bars);
+        }
+    }
+
+    #[test]
+    fn test_bars_errors() {
+        let path = write_text("no_close.csv", "date,open,high,low\n2024-01-02,1,2,0.5\n");
+        let error = read_bars(&path).unwrap_err().to_string();
+        assert!(error.contains("close"), "{error}");
+
+        let path = write_text(
+            "bad_date.csv",
+            "date,open,high,low,close\n2024-01-02,1,2,0.5,1\nyesterday,1,2,0.5,1\n",
+        );
+        let error = read_bars(&path).unwrap_err().to_string();
+        assert!(
+            error.contains("row 1") && error.contains("yesterday"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "options")]
+    fn test_read_option_chain() {
+        let path = write_text(
+            "chain.csv",
+            "expiry,strike,type,bid,ask,iv\n\
+             2024-06-21,100,C,5.0,5.2,0.2\n\
+             2024-06-21,100,put,4.1,,0.21\n",
+        );
+
+        let chain = read_option_chain(&path).unwrap();
+
+        assert_eq!(chain.len(), 2);
+        assert!(matches!(chain[0].type_flag, TypeFlag::Call));
+        assert!(matches!(chain[1].type_flag, TypeFlag::Put));
+        assert_eq!(chain[1].ask, None);
+        assert_eq!(chain[1].implied_volatility, Some(0.21));
+        assert_eq!(chain[0].open_interest, None);
+
+        let path = temp_path("chain_out.csv");
+        write_option_chain(&path, &chain).unwrap();
+        let reread = read_option_chain(&path).unwrap();
+        assert_eq!(reread[0].expiry, date!(2024 - 06 - 21));
+        assert_eq!(reread[1].bid, Some(4.1));
+    }
+
+    #[test]
+    fn test_read_curve_quotes() {
+        let path = write_text("curve.csv", "tenor,rate\n1Y,0.04\n3M,0.05\n");
+
+        assert!(read_curve_quotes(&path, None).is_err());
+
+        let quotes = read_curve_quotes(&path, Some(date!(2024 - 01 - 15))).unwrap();
+        assert_eq!(
+            quotes,
+            vec![
+                CurveQuote {
+                    maturity: date!(2024 - 04 - 15),
+                    rate: 0.05
+                },
+                CurveQuote {
+                    maturity: date!(2025 - 01 - 15),
+                    rate: 0.04
+                },
+            ]
+        );
+
+        let path = temp_path("curve_out.parquet");
+        write_curve_quotes(&path, &quotes).unwrap();
+        assert_eq!(read_curve_quotes(&path, None).unwrap(), quotes);
+    }
+}
@@ -13,7 +13,7 @@
 //!
 //!
 //!
-//! Disabled by default, due to Polars increasing compile times.
+//! Downloading data requires the `data` feature (enabled by default).
 //!
 //! You can:
 //!
@@ -106,22 +106,55 @@
 //! ```
 
 /// File reading and writing.
+#[cfg(not(target_arch = "wasm32"))]
 pub mod io;
+#[cfg(not(target_arch = "wasm32"))]
 pub use io::*;
 
+/// Market data (OHLCV bars, option chains, curve quotes) readers and writers.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod market_data;
+#[cfg(not(target_arch = "wasm32"))]
+pub use market_data::*;
+
+/// Time series with returns, resampling, and alignment.
+pub mod time_series;
+pub use time_series::*;
+
+/// Stationarity and cointegration tests.
+pub mod cointegration;
+pub use cointegration::*;
+
+/// Realized volatility estimators.
+pub mod realized_volatility;
+pub use realized_volatility::*;
+
 /// Yahoo! Finance data reader.
+#[cfg(feature = "data")]
 pub mod yahoo;
+#[cfg(feature = "data")]
 pub use yahoo::*;
 
+/// Async market data downloader.
+#[cfg(feature = "data")]
+pub mod downloader;
+#[cfg(feature = "data")]
+pub use downloader::*;
+
 /// Curves module.
 /// Curves (in the financial sense) are functions that map
 /// a time to a value, such as a yield curve or a swap curve.
 /// They may also be known as term structures.
+#[cfg(feature = "curves")]
 pub mod curves;
+#[cfg(feature = "curves")]
 pub use curves::*;
 
 /// Surface implementations.
 /// Surfaces are simply [Curve]s with an additional dimension.
 /// For example, a volatility surface is a function of time and strike/moneyness.
+#[cfg(feature = "curves")]
 pub mod surfaces;
-pub use surfaces::*;
+// Explicit, as `curves` also exports a (term structure) `Surface` struct.
+#[cfg(feature = "curves")]
+pub use surfaces::{Surface, VolatilitySurface};
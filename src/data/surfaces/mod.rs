@@ -12,8 +12,8 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use super::Curve;
+use crate::error::RustQuantError;
 use num::Float;
-use std::collections::BTreeMap;
 use time::Date;
 
 /// Surface trait.
@@ -26,17 +26,96 @@ pub trait Surface {
 /// A volatility surface is a surface of points (volatilities) over a
 /// space dimension (e.g. strike or moneyness) and a time dimension (e.g. dates).
 ///
-/// We represent this as a map from time to a curve of volatilities.
+/// We represent this as a list of curves of volatilities (over dates),
+/// one per strike, sorted by strike.
 #[allow(clippy::module_name_repetitions)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VolatilitySurface<C: Curve> {
-    /// The volatilities of the surface.
-    pub volatilities: BTreeMap<f64, C>,
+    /// The volatilities of the surface, as (strike, curve) pairs sorted by strike.
+    pub volatilities: Vec<(f64, C)>,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl<C: Curve> VolatilitySurface<C> {
+    /// Create a new volatility surface from (strike, curve) pairs.
+    /// The pairs are sorted by strike.
+    #[must_use]
+    pub fn new(mut volatilities: Vec<(f64, C)>) -> Self {
+        volatilities.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self { volatilities }
+    }
+
+    /// Returns the volatility for the given strike and date.
+    ///
+    /// The volatility is read off each strike's curve at the date, and
+    /// interpolated linearly between strikes. Strikes outside the surface
+    /// get the volatility of the nearest strike.
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the surface has no strikes.
+    /// * Any error returned by [`Curve::rate`], e.g. for a date outside the
+    ///   curves' range.
+    pub fn volatility(&self, strike: f64, date: Date) -> Result<f64, RustQuantError> {
+        let upper = self.volatilities.partition_point(|(k, _)| *k < strike);
+
+        match (upper.checked_sub(1), self.volatilities.get(upper)) {
+            (None, None) => Err(RustQuantError::InvalidArgument(
+                "The surface has no strikes.".to_string(),
+            )),
+            (None, Some((_, curve))) => curve.rate(date),
+            (Some(lower), None) => self.volatilities[lower].1.rate(date),
+            (Some(lower), Some((k1, curve1))) => {
+                let (k0, curve0) = &self.volatilities[lower];
+                let (v0, v1) = (curve0.rate(date)?, curve1.rate(date)?);
+
+                Ok(v0 + (v1 - v0) * (strike - k0) / (k1 - k0))
+            }
+        }
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_volatility_surface {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::YieldCurve;
+    use time::macros::date;
+
+    fn flat(volatility: f64) -> YieldCurve {
+        YieldCurve::from_dates_and_rates(
+            &[date!(2024 - 01 - 01), date!(2026 - 01 - 01)],
+            &[volatility, volatility],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_volatility_surface_interpolation() {
+        let surface = VolatilitySurface::new(vec![(110.0, flat(0.18)), (90.0, flat(0.24))]);
+        let date = date!(2025 - 01 - 01);
+
+        assert_eq!(surface.volatilities[0].0, 90.0);
+        assert_approx_equal!(surface.volatility(90.0, date).unwrap(), 0.24, 1e-12);
+        assert_approx_equal!(surface.volatility(100.0, date).unwrap(), 0.21, 1e-12);
+        assert_approx_equal!(surface.volatility(110.0, date).unwrap(), 0.18, 1e-12);
+
+        // Flat extrapolation in strike.
+        assert_approx_equal!(surface.volatility(50.0, date).unwrap(), 0.24, 1e-12);
+        assert_approx_equal!(surface.volatility(150.0, date).unwrap(), 0.18, 1e-12);
+
+        assert!(surface.volatility(100.0, date!(2030 - 01 - 01)).is_err());
+        assert!(VolatilitySurface::<YieldCurve>::new(vec![])
+            .volatility(100.0, date)
+            .is_err());
+    }
+}
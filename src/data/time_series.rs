@@ -0,0 +1,697 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Time series.
+//!
+//! A [`TimeSeries`] is a sequence of observations indexed by strictly
+//! increasing dates. It supports:
+//!
+//! - Returns (simple, logarithmic, absolute).
+//! - Resampling to a lower frequency (weekly, monthly, quarterly, etc).
+//! - Missing-data policies (drop, forward/backward fill, interpolation).
+//! - Alignment and joining of multiple series on their dates.
+//!
+//! ```
+//! use RustQuant::data::*;
+//! use RustQuant::time::Frequency;
+//! use time::macros::date;
+//!
+//! let prices = TimeSeries::new(
+//!     vec![date!(2024 - 01 - 30), date!(2024 - 01 - 31), date!(2024 - 02 - 01)],
+//!     vec![100.0, 101.0, 99.0],
+//! )
+//! .unwrap();
+//!
+//! let returns = prices.returns(ReturnsType::Logarithmic);
+//! assert_eq!(returns.len(), 2);
+//!
+//! let monthly = prices.resample(Frequency::Monthly, Aggregation::Last).unwrap();
+//! assert_eq!(monthly.values(), &[101.0, 99.0]);
+//! ```
+
+use crate::error::RustQuantError;
+use crate::time::Frequency;
+use std::collections::BTreeMap;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Time series: values indexed by strictly increasing dates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeSeries<T> {
+    dates: Vec<Date>,
+    values: Vec<T>,
+}
+
+/// Return type (for the Yahoo! Finance data struct and time series).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReturnsType {
+    /// Arithmetic/simple returns.
+    Arithmetic,
+    /// Logarithmic returns.
+    Logarithmic,
+    /// Absolute returns.
+    Absolute,
+}
+
+/// How observations are aggregated when resampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    /// First observation in the period.
+    First,
+    /// Last observation in the period.
+    Last,
+    /// Arithmetic mean of the observations in the period.
+    Mean,
+    /// Sum of the observations in the period.
+    Sum,
+    /// Minimum observation in the period.
+    Min,
+    /// Maximum observation in the period.
+    Max,
+}
+
+/// Policy for handling missing observations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MissingDataPolicy {
+    /// Drop missing observations.
+    Drop,
+    /// Carry the last available observation forward.
+    /// Leading missing observations are dropped.
+    ForwardFill,
+    /// Carry the next available observation backward.
+    /// Trailing missing observations are dropped.
+    BackwardFill,
+    /// Linear interpolation (in calendar days) between the surrounding
+    /// observations. Leading and trailing missing observations are dropped.
+    Interpolate,
+    /// Replace missing observations with a constant.
+    Constant(f64),
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<T> Default for TimeSeries<T> {
+    fn default() -> Self {
+        Self {
+            dates: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+}
+
+impl<T> TimeSeries<T> {
+    /// Create a new time series.
+    ///
+    /// The observations are sorted by date.
+    ///
+    /// # Errors
+    ///
+    /// - If `dates` and `values` have different lengths.
+    /// - If a date appears more than once.
+    pub fn new(dates: Vec<Date>, values: Vec<T>) -> Result<Self, RustQuantError> {
+        if dates.len() != values.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "{} dates but {} values",
+                dates.len(),
+                values.len()
+            )));
+        }
+
+        let mut pairs: Vec<(Date, T)> = dates.into_iter().zip(values).collect();
+        pairs.sort_by_key(|(date, _)| *date);
+
+        if let Some(pair) = pairs.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "duplicate date in time series: {}",
+                pair[0].0
+            )));
+        }
+
+        Ok(pairs
+            .into_iter()
+            .unzip::<Date, T, Vec<Date>, Vec<T>>()
+            .into())
+    }
+
+    /// Create a time series from `(date, value)` pairs.
+    ///
+    /// # Errors
+    ///
+    /// If a date appears more than once.
+    pub fn from_pairs<I: IntoIterator<Item = (Date, T)>>(pairs: I) -> Result<Self, RustQuantError> {
+        let (dates, values) = pairs.into_iter().unzip();
+        Self::new(dates, values)
+    }
+
+    /// Number of observations.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.dates.len()
+    }
+
+    /// Returns `true` if the series has no observations.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.dates.is_empty()
+    }
+
+    /// Observation dates.
+    #[must_use]
+    pub fn dates(&self) -> &[Date] {
+        &self.dates
+    }
+
+    /// Observation values.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Consume the series, returning the dates and values.
+    #[must_use]
+    pub fn into_parts(self) -> (Vec<Date>, Vec<T>) {
+        (self.dates, self.values)
+    }
+
+    /// Iterator over `(date, value)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (Date, &T)> {
+        self.dates.iter().copied().zip(self.values.iter())
+    }
+
+    /// Value observed on the given date (if any).
+    #[must_use]
+    pub fn get(&self, date: Date) -> Option<&T> {
+        self.dates
+            .binary_search(&date)
+            .ok()
+            .map(|index| &self.values[index])
+    }
+
+    /// Most recent value observed on or before the given date (if any).
+    #[must_use]
+    pub fn as_of(&self, date: Date) -> Option<&T> {
+        match self.dates.partition_point(|d| *d <= date) {
+            0 => None,
+            index => Some(&self.values[index - 1]),
+        }
+    }
+
+    /// First observation.
+    #[must_use]
+    pub fn first(&self) -> Option<(Date, &T)> {
+        self.iter().next()
+    }
+
+    /// Last observation.
+    #[must_use]
+    pub fn last(&self) -> Option<(Date, &T)> {
+        self.iter().last()
+    }
+
+    /// Insert (or replace) an observation.
+    pub fn insert(&mut self, date: Date, value: T) {
+        match self.dates.binary_search(&date) {
+            Ok(index) => self.values[index] = value,
+            Err(index) => {
+                self.dates.insert(index, date);
+                self.values.insert(index, value);
+            }
+        }
+    }
+
+    /// Apply a function to each value.
+    #[must_use]
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> TimeSeries<U> {
+        TimeSeries {
+            dates: self.dates.clone(),
+            values: self.values.iter().map(f).collect(),
+        }
+    }
+
+    /// Keep only the observations satisfying the predicate.
+    #[must_use]
+    pub fn filter<F: FnMut(Date, &T) -> bool>(&self, mut predicate: F) -> Self
+    where
+        T: Clone,
+    {
+        self.iter()
+            .filter(|(date, value)| predicate(*date, value))
+            .map(|(date, value)| (date, value.clone()))
+            .unzip::<Date, T, Vec<Date>, Vec<T>>()
+            .into()
+    }
+
+    /// Observations between `start` and `end` (inclusive).
+    #[must_use]
+    pub fn window(&self, start: Date, end: Date) -> Self
+    where
+        T: Clone,
+    {
+        let lo = self.dates.partition_point(|d| *d < start);
+        let hi = self.dates.partition_point(|d| *d <= end).max(lo);
+
+        Self {
+            dates: self.dates[lo..hi].to_vec(),
+            values: self.values[lo..hi].to_vec(),
+        }
+    }
+
+    /// Resample to a lower frequency, reducing the observations in each
+    /// period with the given function.
+    ///
+    /// Each period is labelled with the date of its last observation.
+    ///
+    /// Supported frequencies: daily, weekly (ISO weeks), monthly, quarterly,
+    /// semi-annually, and annually.
+    ///
+    /// # Errors
+    ///
+    /// If the frequency is not supported.
+    pub fn resample_with<U, F>(
+        &self,
+        frequency: Frequency,
+        mut f: F,
+    ) -> Result<TimeSeries<U>, RustQuantError>
+    where
+        F: FnMut(&[T]) -> U,
+    {
+        let mut periods: BTreeMap<(i32, u32), (usize, usize)> = BTreeMap::new();
+
+        for (index, date) in self.dates.iter().enumerate() {
+            let key = period_key(*date, frequency)?;
+            periods
+                .entry(key)
+                .and_modify(|(_, end)| *end = index + 1)
+                .or_insert((index, index + 1));
+        }
+
+        Ok(periods
+            .into_values()
+            .map(|(start, end)| (self.dates[end - 1], f(&self.values[start..end])))
+            .unzip::<Date, U, Vec<Date>, Vec<U>>()
+            .into())
+    }
+
+    /// Inner join: observations on dates present in both series.
+    #[must_use]
+    pub fn inner_join<U: Clone>(&self, other: &TimeSeries<U>) -> TimeSeries<(T, U)>
+    where
+        T: Clone,
+    {
+        self.iter()
+            .filter_map(|(date, value)| other.get(date).map(|v| (date, (value.clone(), v.clone()))))
+            .unzip::<Date, (T, U), Vec<Date>, Vec<(T, U)>>()
+            .into()
+    }
+
+    /// Left join: all observations of `self`, with the matching observation
+    /// of `other` (if any).
+    #[must_use]
+    pub fn left_join<U: Clone>(&self, other: &TimeSeries<U>) -> TimeSeries<(T, Option<U>)>
+    where
+        T: Clone,
+    {
+        TimeSeries {
+            dates: self.dates.clone(),
+            values: self
+                .iter()
+                .map(|(date, value)| (value.clone(), other.get(date).cloned()))
+                .collect(),
+        }
+    }
+
+    /// Outer join: observations on dates present in either series.
+    #[must_use]
+    pub fn outer_join<U: Clone>(&self, other: &TimeSeries<U>) -> TimeSeries<(Option<T>, Option<U>)>
+    where
+        T: Clone,
+    {
+        let mut dates = [self.dates.as_slice(), other.dates.as_slice()].concat();
+        dates.sort();
+        dates.dedup();
+
+        TimeSeries {
+            values: dates
+                .iter()
+                .map(|date| (self.get(*date).cloned(), other.get(*date).cloned()))
+                .collect(),
+            dates,
+        }
+    }
+
+    /// Align several series on their common dates.
+    ///
+    /// The values of the result hold one entry per input series,
+    /// in the order given.
+    #[must_use]
+    pub fn align(series: &[&TimeSeries<T>]) -> TimeSeries<Vec<T>>
+    where
+        T: Clone,
+    {
+        let Some((first, rest)) = series.split_first() else {
+            return TimeSeries::default();
+        };
+
+        first
+            .iter()
+            .filter_map(|(date, value)| {
+                let mut row = vec![value.clone()];
+                for other in rest {
+                    row.push(other.get(date)?.clone());
+                }
+                Some((date, row))
+            })
+            .unzip::<Date, Vec<T>, Vec<Date>, Vec<Vec<T>>>()
+            .into()
+    }
+}
+
+impl<T> From<(Vec<Date>, Vec<T>)> for TimeSeries<T> {
+    /// Build a series from dates and values that are already sorted and unique.
+    fn from((dates, values): (Vec<Date>, Vec<T>)) -> Self {
+        debug_assert_eq!(dates.len(), values.len());
+        debug_assert!(dates.windows(2).all(|pair| pair[0] < pair[1]));
+
+        Self { dates, values }
+    }
+}
+
+impl TimeSeries<f64> {
+    /// Period-over-period returns.
+    ///
+    /// The returned series starts at the second observation.
+    #[must_use]
+    pub fn returns(&self, returns_type: ReturnsType) -> TimeSeries<f64> {
+        TimeSeries {
+            dates: self.dates.iter().skip(1).copied().collect(),
+            values: self
+                .values
+                .windows(2)
+                .map(|pair| match returns_type {
+                    ReturnsType::Arithmetic => pair[1] / pair[0] - 1.0,
+                    ReturnsType::Logarithmic => (pair[1] / pair[0]).ln(),
+                    ReturnsType::Absolute => pair[1] - pair[0],
+                })
+                .collect(),
+        }
+    }
+
+    /// Resample to a lower frequency, see [`TimeSeries::resample_with`].
+    ///
+    /// # Errors
+    ///
+    /// If the frequency is not supported.
+    pub fn resample(
+        &self,
+        frequency: Frequency,
+        aggregation: Aggregation,
+    ) -> Result<TimeSeries<f64>, RustQuantError> {
+        self.resample_with(frequency, |values| match aggregation {
+            Aggregation::First => values[0],
+            Aggregation::Last => values[values.len() - 1],
+            Aggregation::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregation::Sum => values.iter().sum(),
+            Aggregation::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregation::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+
+    /// Handle missing observations (`NaN` values) with the given policy.
+    #[must_use]
+    pub fn fill_missing(&self, policy: MissingDataPolicy) -> TimeSeries<f64> {
+        self.map(|value| (!value.is_nan()).then_some(*value))
+            .fill_missing(policy)
+    }
+}
+
+impl TimeSeries<Option<f64>> {
+    /// Handle missing observations (`None` values) with the given policy.
+    #[must_use]
+    pub fn fill_missing(&self, policy: MissingDataPolicy) -> TimeSeries<f64> {
+        let n = self.len();
+
+        // Index of the previous/next available observation for each date.
+        let available = |(i, value): (usize, &Option<f64>)| value.map(|_| i);
+        let previous: Vec<Option<usize>> = self
+            .values
+            .iter()
+            .enumerate()
+            .scan(None, |last, entry| {
+                *last = available(entry).or(*last);
+                Some(*last)
+            })
+            .collect();
+        let mut next: Vec<Option<usize>> = self
+            .values
+            .iter()
+            .enumerate()
+            .rev()
+            .scan(None, |last, entry| {
+                *last = available(entry).or(*last);
+                Some(*last)
+            })
+            .collect();
+        next.reverse();
+
+        let value = |i: usize| self.values[i].unwrap_or_default();
+        let days = |i: usize, j: usize| (self.dates[j] - self.dates[i]).whole_days() as f64;
+
+        (0..n)
+            .filter_map(|i| {
+                let filled = match (self.values[i], policy) {
+                    (Some(v), _) => Some(v),
+                    (None, MissingDataPolicy::Drop) => None,
+                    (None, MissingDataPolicy::ForwardFill) => previous[i].map(value),
+                    (None, MissingDataPolicy::BackwardFill) => next[i].map(value),
+                    (None, MissingDataPolicy::Interpolate) => match (previous[i], next[i]) {
+                        (Some(p), Some(q)) => {
+                            let w = days(p, i) / days(p, q);
+                            Some((1.0 - w) * value(p) + w * value(q))
+                        }
+                        _ => None,
+                    },
+                    (None, MissingDataPolicy::Constant(c)) => Some(c),
+                };
+
+                filled.map(|v| (self.dates[i], v))
+            })
+            .unzip::<Date, f64, Vec<Date>, Vec<f64>>()
+            .into()
+    }
+}
+
+/// Key identifying the period a date belongs to, for resampling.
+fn period_key(date: Date, frequency: Frequency) -> Result<(i32, u32), RustQuantError> {
+    let month = date.month() as u32;
+
+    match frequency {
+        Frequency::Daily => Ok((date.year(), date.ordinal().into())),
+        Frequency::Weekly => {
+            let (year, week, _) = date.to_iso_week_date();
+            Ok((year, week.into()))
+        }
+        Frequency::Monthly => Ok((date.year(), month)),
+        Frequency::Quarterly => Ok((date.year(), (month - 1) / 3)),
+        Frequency::SemiAnnually => Ok((date.year(), (month - 1) / 6)),
+        Frequency::Annually => Ok((date.year(), 0)),
+        _ => Err(RustQuantError::InvalidArgument(format!(
+            "resampling to {frequency:?} frequency is not supported"
+        ))),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_time_series {
+    use super::*;
+    use time::macros::date;
+
+    const EPS: f64 = 1e-12;
+
+    fn prices() -> TimeSeries<f64> {
+        TimeSeries::new(
+            vec![
+                date!(2024 - 01 - 29),
+                date!(2024 - 01 - 30),
+                date!(2024 - 01 - 31),
+                date!(2024 - 02 - 01),
+                date!(2024 - 02 - 02),
+            ],
+            vec![100.0, 102.0, 101.0, 105.0, 104.0],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_new_sorts_and_validates() {
+        let series = TimeSeries::new(
+            vec![date!(2024 - 01 - 02), date!(2024 - 01 - 01)],
+            vec![2.0, 1.0],
+        )
+        .unwrap();
+
+        assert_eq!(
+            series.dates(),
+            &[date!(2024 - 01 - 01), date!(2024 - 01 - 02)]
+        );
+        assert_eq!(series.values(), &[1.0, 2.0]);
+
+        assert!(TimeSeries::new(vec![date!(2024 - 01 - 01)], vec![1.0, 2.0]).is_err());
+        assert!(TimeSeries::new(
+            vec![date!(2024 - 01 - 01), date!(2024 - 01 - 01)],
+            vec![1.0, 2.0]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let series = prices();
+
+        assert_eq!(series.get(date!(2024 - 01 - 31)), Some(&101.0));
+        assert_eq!(series.get(date!(2024 - 01 - 28)), None);
+        assert_eq!(series.as_of(date!(2024 - 02 - 10)), Some(&104.0));
+        assert_eq!(series.as_of(date!(2024 - 01 - 01)), None);
+        assert_eq!(
+            series
+                .window(date!(2024 - 01 - 30), date!(2024 - 02 - 01))
+                .values(),
+            &[102.0, 101.0, 105.0]
+        );
+    }
+
+    #[test]
+    fn test_returns() {
+        let series = prices();
+
+        let simple = series.returns(ReturnsType::Arithmetic);
+        let log = series.returns(ReturnsType::Logarithmic);
+        let absolute = series.returns(ReturnsType::Absolute);
+
+        assert_eq!(simple.len(), 4);
+        assert_eq!(simple.dates()[0], date!(2024 - 01 - 30));
+        assert!((simple.values()[0] - 0.02).abs() < EPS);
+        assert!((log.values()[0] - 1.02_f64.ln()).abs() < EPS);
+        assert!((absolute.values()[2] - 4.0).abs() < EPS);
+    }
+
+    #[test]
+    fn test_resample() {
+        let series = prices();
+
+        let last = series
+            .resample(Frequency::Monthly, Aggregation::Last)
+            .unwrap();
+        assert_eq!(
+            last.dates(),
+            &[date!(2024 - 01 - 31), date!(2024 - 02 - 02)]
+        );
+        assert_eq!(last.values(), &[101.0, 104.0]);
+
+        let mean = series
+            .resample(Frequency::Monthly, Aggregation::Mean)
+            .unwrap();
+        assert!((mean.values()[0] - 101.0).abs() < EPS);
+        assert!((mean.values()[1] - 104.5).abs() < EPS);
+
+        let weekly = series
+            .resample(Frequency::Weekly, Aggregation::Max)
+            .unwrap();
+        assert_eq!(weekly.values(), &[105.0]);
+
+        assert!(series
+            .resample(Frequency::BiWeekly, Aggregation::Last)
+            .is_err());
+    }
+
+    #[test]
+    fn test_fill_missing() {
+        let series = TimeSeries::new(
+            vec![
+                date!(2024 - 01 - 01),
+                date!(2024 - 01 - 02),
+                date!(2024 - 01 - 03),
+                date!(2024 - 01 - 05),
+                date!(2024 - 01 - 06),
+            ],
+            vec![None, Some(1.0), None, Some(4.0), None],
+        )
+        .unwrap();
+
+        let drop = series.fill_missing(MissingDataPolicy::Drop);
+        assert_eq!(drop.values(), &[1.0, 4.0]);
+
+        let ffill = series.fill_missing(MissingDataPolicy::ForwardFill);
+        assert_eq!(ffill.values(), &[1.0, 1.0, 4.0, 4.0]);
+        assert_eq!(ffill.dates()[0], date!(2024 - 01 - 02));
+
+        let bfill = series.fill_missing(MissingDataPolicy::BackwardFill);
+        assert_eq!(bfill.values(), &[1.0, 1.0, 4.0, 4.0]);
+        assert_eq!(bfill.dates()[3], date!(2024 - 01 - 05));
+
+        let interpolated = series.fill_missing(MissingDataPolicy::Interpolate);
+        assert_eq!(interpolated.values(), &[1.0, 2.0, 4.0]);
+
+        let constant = series.fill_missing(MissingDataPolicy::Constant(0.0));
+        assert_eq!(constant.values(), &[0.0, 1.0, 0.0, 4.0, 0.0]);
+
+        let nan = series.map(|v| v.unwrap_or(f64::NAN));
+        assert_eq!(nan.fill_missing(MissingDataPolicy::ForwardFill), ffill);
+    }
+
+    #[test]
+    fn test_joins() {
+        let a = TimeSeries::new(
+            vec![
+                date!(2024 - 01 - 01),
+                date!(2024 - 01 - 02),
+                date!(2024 - 01 - 03),
+            ],
+            vec![1.0, 2.0, 3.0],
+        )
+        .unwrap();
+        let b = TimeSeries::new(
+            vec![
+                date!(2024 - 01 - 02),
+                date!(2024 - 01 - 03),
+                date!(2024 - 01 - 04),
+            ],
+            vec![20.0, 30.0, 40.0],
+        )
+        .unwrap();
+
+        let inner = a.inner_join(&b);
+        assert_eq!(inner.values(), &[(2.0, 20.0), (3.0, 30.0)]);
+
+        let left = a.left_join(&b);
+        assert_eq!(
+            left.values(),
+            &[(1.0, None), (2.0, Some(20.0)), (3.0, Some(30.0))]
+        );
+
+        let outer = a.outer_join(&b);
+        assert_eq!(outer.len(), 4);
+        assert_eq!(outer.values()[3], (None, Some(40.0)));
+
+        let aligned = TimeSeries::align(&[&a, &b, &a]);
+        assert_eq!(
+            aligned.dates(),
+            &[date!(2024 - 01 - 02), date!(2024 - 01 - 03)]
+        );
+        assert_eq!(aligned.values()[0], vec![2.0, 20.0, 2.0]);
+    }
+}
@@ -15,6 +15,7 @@ use time::OffsetDateTime;
 use yahoo::YahooError;
 use yahoo_finance_api as yahoo;
 
+use crate::data::ReturnsType;
 use crate::error::RustQuantError;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -39,16 +40,6 @@ pub struct YahooFinanceData {
     pub latest_quote: Option<DataFrame>,
 }
 
-/// Return type for the Yahoo! Finance data struct.
-pub enum ReturnsType {
-    /// Arithmetic/simple returns.
-    Arithmetic,
-    /// Logarithmic returns.
-    Logarithmic,
-    /// Absolute returns.
-    Absolute,
-}
-
 /// Yahoo! Finance data reader trait.
 pub trait YahooFinanceReader {
     /// Retrieves the price history from Yahoo! Finance.
@@ -11,6 +11,7 @@
 //! A custom error type `RustQuantError` is defined, along with a macro to create an error,
 //! that propagates a `RustQuantError` with the text to include in the output.
 
+use alloc::string::String;
 use thiserror::Error;
 
 /// Error type for `RustQuant`.
@@ -33,52 +34,88 @@ pub enum RustQuantError {
     FileOperationFailed(String),
 
     /// Error variant arising from missing inputs.
-    #[error{"An input was missing: {0}"}]
+    #[error("An input was missing: {0}")]
     MissingInput(String),
 
+    /// Error variant arising from an iterative method (root-finder,
+    /// optimiser, calibration) that failed to converge.
+    #[error("Failed to converge: {0}")]
+    NonConvergence(String),
+
+    /// Error variant arising from inputs that admit an arbitrage
+    /// (e.g. an option price outside its no-arbitrage bounds).
+    #[error("Arbitrage violation: {0}")]
+    ArbitrageViolation(String),
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Data related errors
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     /// Error variant arising from the Yahoo! Finance API.
+    #[cfg(feature = "data")]
     #[error("Yahoo! Finance error: {0}")]
     YahooError(#[from] yahoo_finance_api::YahooError),
 
     /// Error variant arising from Polars.
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
     #[error("Polars error: {0}")]
     PolarsError(#[from] polars::error::PolarsError),
 
     /// Error variant arising from [`std::io`].
+    #[cfg(feature = "std")]
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// Error variant arising from JSON (de)serialization.
+    #[cfg(feature = "serde")]
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Currency related errors
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// Error variant arising from operations on amounts in different currencies.
+    #[error("Currency mismatch: {0} and {1}")]
+    CurrencyMismatch(String, String),
+
+    /// Error variant arising from a missing (and non-triangulable) FX rate.
+    #[error("Missing exchange rate: {0}/{1}")]
+    MissingExchangeRate(String, String),
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Statistical distribution related errors
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     /// Error variant from constructing Bernoulli distribution.
+    #[cfg(feature = "std")]
     #[error("{0}")]
     Bernoulli(#[from] rand_distr::BernoulliError),
 
     /// Error variant from constructing Binomial distribution.
+    #[cfg(feature = "std")]
     #[error("{0}")]
     Binomial(#[from] rand_distr::BinomialError),
 
     /// Error variant from constructing ChiSquared distribution.
+    #[cfg(feature = "std")]
     #[error("{0}")]
     ChiSquared(#[from] rand_distr::ChiSquaredError),
 
     /// Error variant from constructing Exponential distribution.
+    #[cfg(feature = "std")]
     #[error("{0}")]
     Exponential(#[from] rand_distr::ExpError),
 
     /// Error variant from constructing Gamma distribution.
+    #[cfg(feature = "std")]
     #[error("{0}")]
     Gamma(#[from] rand_distr::GammaError),
 
     /// Error variant from constructing Gaussian distribution.
+    #[cfg(feature = "std")]
     #[error("{0}")]
     Gaussian(#[from] rand_distr::NormalError),
 
     /// Error variant from constructing Poisson distribution.
+    #[cfg(feature = "std")]
     #[error("{0}")]
     Poisson(#[from] rand_distr::PoissonError),
 
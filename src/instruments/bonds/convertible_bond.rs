@@ -0,0 +1,391 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Convertible bonds in the Tsiveriotis-Fernandes (1998) model.
+//!
+//! A convertible bond can be exchanged for `conversion_ratio` shares at any
+//! time, may be called back by the issuer (forcing the holder to choose
+//! between the call price and conversion) or put back to the issuer by the
+//! holder during given windows, and otherwise pays coupons and its face
+//! value at maturity.
+//!
+//! The payments the holder receives in cash carry the issuer's credit risk,
+//! while the shares received on conversion do not. The TF model therefore
+//! splits the bond's value on a Cox-Ross-Rubinstein tree for the share
+//! price into an equity component, discounted at the risk-free rate, and a
+//! cash-only component, discounted at the risk-free rate plus the issuer's
+//! credit spread.
+//!
+//! Call and put prices are taken as dirty prices (no accrued interest), and
+//! coupons are paid at the first tree node on or after each coupon date.
+
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A period, in years from today, during which a convertible bond can be
+/// called (by the issuer) or put (by the holder) at a fixed price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExerciseWindow {
+    /// Start of the window.
+    pub start: f64,
+    /// End of the window (inclusive).
+    pub end: f64,
+    /// Call or put price.
+    pub price: f64,
+}
+
+/// Convertible bond terms and market data.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConvertibleBond {
+    /// Face value, repaid at maturity.
+    pub face_value: f64,
+    /// Number of shares received on conversion.
+    pub conversion_ratio: f64,
+    /// Time to maturity, in years.
+    pub maturity: f64,
+    /// Coupon payments, as `(time, amount)` pairs.
+    pub coupons: Vec<(f64, f64)>,
+    /// Issuer call windows.
+    pub call_schedule: Vec<ExerciseWindow>,
+    /// Holder put windows.
+    pub put_schedule: Vec<ExerciseWindow>,
+    /// Current share price.
+    pub initial_price: f64,
+    /// Share price volatility.
+    pub volatility: f64,
+    /// Dividend yield of the shares.
+    pub dividend_yield: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Issuer's credit spread over the risk-free rate.
+    pub credit_spread: f64,
+}
+
+/// Value of a [`ConvertibleBond`] and its components.
+#[derive(Debug, Clone, Copy)]
+pub struct ConvertibleValuation {
+    /// Value of the bond.
+    pub price: f64,
+    /// Part of the value received in shares (discounted risk-free).
+    pub equity_component: f64,
+    /// Part of the value received in cash (discounted at the risky rate).
+    pub cash_component: f64,
+    /// Sensitivity to the share price.
+    pub delta: f64,
+    /// Sensitivity of delta to the share price.
+    pub gamma: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ExerciseWindow {
+    fn price_at(windows: &[Self], t: f64) -> Option<f64> {
+        windows
+            .iter()
+            .find(|w| w.start <= t && t <= w.end)
+            .map(|w| w.price)
+    }
+}
+
+impl ConvertibleBond {
+    /// Value the bond on a binomial tree of height `n`.
+    ///
+    /// At each node, working back from maturity:
+    ///
+    /// 1. holding is worth the discounted equity and cash components;
+    /// 2. if callable above the call price, the issuer calls, and the
+    ///    holder converts instead if that is worth more;
+    /// 3. if puttable below the put price, the holder puts;
+    /// 4. the holder converts if the shares are worth more.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the tree height is below 3.
+    pub fn price(&self, n: usize) -> Result<ConvertibleValuation, RustQuantError> {
+        self.validate()?;
+
+        // The Greeks are read off the first three levels of the tree.
+        if n < 3 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Tree height must be at least 3 (got {n})."
+            )));
+        }
+
+        let S = self.initial_price;
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+
+        let dt = self.maturity / n as f64;
+        let u = (self.volatility * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = ((b * dt).exp() - d) / (u - d);
+        let df_equity = (-r * dt).exp();
+        let df_cash = (-(r + self.credit_spread) * dt).exp();
+
+        // Coupons paid at each step, at the first node on or after the
+        // coupon date.
+        let mut coupons = vec![0.0; n + 1];
+        for &(t, amount) in &self.coupons {
+            let step = ((t / dt - 1e-9).ceil().max(0.0) as usize).min(n);
+            coupons[step] += amount;
+        }
+
+        let price_at = |i: usize, j: usize| S * u.powi(i as i32) * d.powi((j - i) as i32);
+
+        // Node values as (equity, cash).
+        let mut values: Vec<(f64, f64)> = (0..=n)
+            .map(|i| {
+                let redemption = self.face_value + coupons[n];
+                let conversion = self.conversion_ratio * price_at(i, n);
+
+                match conversion > redemption {
+                    true => (conversion, 0.0),
+                    false => (0.0, redemption),
+                }
+            })
+            .collect();
+
+        // Values on the first three levels of the tree, for the Greeks.
+        let mut level_1 = [0.0; 2];
+        let mut level_2 = [0.0; 3];
+
+        for j in (0..n).rev() {
+            let t = j as f64 * dt;
+            let call_price = ExerciseWindow::price_at(&self.call_schedule, t);
+            let put_price = ExerciseWindow::price_at(&self.put_schedule, t);
+
+            for i in 0..=j {
+                let conversion = self.conversion_ratio * price_at(i, j);
+
+                let mut equity = df_equity * (p * values[i + 1].0 + (1.0 - p) * values[i].0);
+                let mut cash =
+                    df_cash * (p * values[i + 1].1 + (1.0 - p) * values[i].1) + coupons[j];
+
+                if let Some(call_price) = call_price {
+                    if equity + cash > call_price {
+                        (equity, cash) = match conversion > call_price {
+                            true => (conversion, 0.0),
+                            false => (0.0, call_price),
+                        };
+                    }
+                }
+
+                if let Some(put_price) = put_price {
+                    if put_price > equity + cash {
+                        (equity, cash) = (0.0, put_price);
+                    }
+                }
+
+                if conversion > equity + cash {
+                    (equity, cash) = (conversion, 0.0);
+                }
+
+                values[i] = (equity, cash);
+            }
+
+            match j {
+                2 => level_2 = [0, 1, 2].map(|i| values[i].0 + values[i].1),
+                1 => level_1 = [0, 1].map(|i| values[i].0 + values[i].1),
+                _ => {}
+            }
+        }
+
+        let (equity, cash) = values[0];
+
+        // Finite differences on the first levels of the tree, as in
+        // `BinomialOption::price_CoxRossRubinstein`.
+        let delta = (level_1[1] - level_1[0]) / (S * u - S * d);
+        let gamma = ((level_2[2] - level_2[1]) / (S * u * u - S)
+            - (level_2[1] - level_2[0]) / (S - S * d * d))
+            / (0.5 * (S * u * u - S * d * d));
+
+        Ok(ConvertibleValuation {
+            price: equity + cash,
+            equity_component: equity,
+            cash_component: cash,
+            delta,
+            gamma,
+        })
+    }
+}
+
+impl Validate for ConvertibleBond {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let mut validator = Validator::new()
+            .positive("face_value", self.face_value)
+            .positive("conversion_ratio", self.conversion_ratio)
+            .positive("maturity", self.maturity)
+            .positive("initial_price", self.initial_price)
+            .positive("volatility", self.volatility)
+            .finite("dividend_yield", self.dividend_yield)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .non_negative("credit_spread", self.credit_spread);
+
+        for &(t, amount) in &self.coupons {
+            validator = validator
+                .check(t > 0.0 && t <= self.maturity, || {
+                    format!("coupon times must be in (0, maturity] (got {t})")
+                })
+                .non_negative("coupon amount", amount);
+        }
+
+        for window in self.call_schedule.iter().chain(&self.put_schedule) {
+            validator = validator
+                .check(window.start <= window.end, || {
+                    format!(
+                        "exercise window must not end before it starts (got {} > {})",
+                        window.start, window.end
+                    )
+                })
+                .positive("exercise price", window.price);
+        }
+
+        validator.finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_convertible_bond {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn bond() -> ConvertibleBond {
+        ConvertibleBond {
+            face_value: 100.0,
+            conversion_ratio: 1.0,
+            maturity: 5.0,
+            coupons: (1..=5).map(|t| (t as f64, 4.0)).collect(),
+            call_schedule: vec![],
+            put_schedule: vec![],
+            initial_price: 100.0,
+            volatility: 0.3,
+            dividend_yield: 0.0,
+            risk_free_rate: 0.05,
+            credit_spread: 0.02,
+        }
+    }
+
+    fn bond_floor(bond: &ConvertibleBond) -> f64 {
+        let y = bond.risk_free_rate + bond.credit_spread;
+
+        bond.coupons
+            .iter()
+            .map(|&(t, amount)| amount * (-y * t).exp())
+            .sum::<f64>()
+            + bond.face_value * (-y * bond.maturity).exp()
+    }
+
+    #[test]
+    fn test_no_conversion_is_risky_bond() {
+        // Never converted: all the value is cash, discounted at r + s.
+        let bond = ConvertibleBond {
+            conversion_ratio: 1e-9,
+            ..bond()
+        };
+        let value = bond.price(500).unwrap();
+
+        assert_approx_equal!(value.price, bond_floor(&bond), 1e-8);
+        assert_approx_equal!(value.equity_component, 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_deep_conversion_is_equity() {
+        // Always converted at maturity: worth the shares plus (at most)
+        // the coupons collected on the way.
+        let bond = ConvertibleBond {
+            conversion_ratio: 10.0,
+            ..bond()
+        };
+        let value = bond.price(500).unwrap();
+        let shares = bond.conversion_ratio * bond.initial_price;
+
+        assert!(value.price >= shares);
+        assert!(value.price <= shares + bond_floor(&bond) - 100.0 * (-0.35_f64).exp());
+        assert_approx_equal!(value.delta, bond.conversion_ratio, 1e-2);
+    }
+
+    #[test]
+    fn test_value_above_floors() {
+        let bond = bond();
+        let value = bond.price(500).unwrap();
+
+        assert!(value.price > bond_floor(&bond));
+        assert!(value.price > bond.conversion_ratio * bond.initial_price);
+        assert_approx_equal!(
+            value.price,
+            value.equity_component + value.cash_component,
+            1e-10
+        );
+        assert!(value.delta > 0.0 && value.delta < bond.conversion_ratio);
+        assert!(value.gamma > 0.0);
+    }
+
+    #[test]
+    fn test_call_and_put_features() {
+        let value = bond().price(500).unwrap().price;
+
+        let callable = ConvertibleBond {
+            call_schedule: vec![ExerciseWindow {
+                start: 2.0,
+                end: 5.0,
+                price: 110.0,
+            }],
+            ..bond()
+        };
+        let puttable = ConvertibleBond {
+            put_schedule: vec![ExerciseWindow {
+                start: 2.9,
+                end: 3.1,
+                price: 105.0,
+            }],
+            ..bond()
+        };
+
+        assert!(callable.price(500).unwrap().price < value);
+        assert!(puttable.price(500).unwrap().price > value);
+    }
+
+    #[test]
+    fn test_convertible_bond_validation() {
+        let bond_with_late_coupon = ConvertibleBond {
+            coupons: vec![(6.0, 4.0)],
+            ..bond()
+        };
+        assert!(bond_with_late_coupon.price(100).is_err());
+
+        let bond_with_bad_window = ConvertibleBond {
+            call_schedule: vec![ExerciseWindow {
+                start: 3.0,
+                end: 2.0,
+                price: 110.0,
+            }],
+            ..bond()
+        };
+        assert!(bond_with_bad_window.price(100).is_err());
+
+        let bond_with_negative_spread = ConvertibleBond {
+            credit_spread: -0.01,
+            ..bond()
+        };
+        assert!(bond_with_negative_spread.price(100).is_err());
+        assert!(bond().price(2).is_err());
+    }
+}
@@ -8,10 +8,12 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use super::zero_coupon_bond::ZeroCouponBond;
+use crate::cashflows::{CashflowEntry, CashflowKind, CashflowReport};
 use crate::data::{Curve, YieldCurve};
+use crate::error::RustQuantError;
 use crate::instruments::fx::currency::Currency;
-use crate::instruments::Instrument;
-use crate::time::{DateRollingConvention, Frequency};
+use crate::instruments::{Instrument, PricingContext};
+use crate::time::{DateRollingConvention, Frequency, Schedule};
 use std::collections::BTreeMap;
 use time::{Date, Duration};
 
@@ -32,6 +34,7 @@ use time::{Date, Duration};
 /// - A 12-month zero-coupon bond with a face value of $2.50.
 /// - An 18-month zero-coupon bond with a face value of $102.50.
 #[allow(clippy::module_name_repetitions)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CouponBond {
     /// The date the bond is evaluated (i.e. priced).
     pub evaluation_date: Date,
@@ -65,6 +68,7 @@ pub struct CouponBond {
 }
 
 /// Coupon bond struct.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CouponBond2 {
     /// Portfolio of zero-coupon bonds.
     pub coupons: BTreeMap<Date, ZeroCouponBond>,
@@ -115,26 +119,114 @@ impl CouponBond {
 
         self.coupons = coupons;
     }
+
+    /// Constructs the coupons of the bond from a generated `Schedule`.
+    ///
+    /// Each coupon is paid on the payment date of an accrual period, and
+    /// accrues over the period's day count factor. The face value is
+    /// paid with the final coupon.
+    pub fn construct_coupons_from_schedule(&mut self, schedule: &Schedule) {
+        let mut coupons: BTreeMap<Date, f64> = schedule
+            .periods
+            .iter()
+            .map(|period| {
+                (
+                    period.payment_date,
+                    self.face_value * self.coupon_rate * period.day_count_factor,
+                )
+            })
+            .collect();
+
+        if let Some(last) = schedule.periods.last() {
+            *coupons.entry(last.payment_date).or_insert(0.0) += self.face_value;
+        }
+
+        self.coupons = coupons;
+    }
+
+    /// Returns the price (net present value) of the bond, discounted on
+    /// its own yield curve.
+    ///
+    /// # Errors:
+    ///
+    /// * Any error returned by [`Curve::discount_factors`], e.g. for a
+    ///   coupon paid after the curve's terminal date.
+    pub fn price(&self) -> Result<f64, RustQuantError> {
+        Ok(self
+            .yield_curve
+            .discount_factors(&self.coupons.keys().copied().collect::<Vec<Date>>())?
+            .iter()
+            .zip(self.coupons.values())
+            .map(|(df, coupon)| coupon * df)
+            .sum::<f64>())
+    }
+
+    /// Returns the full cashflow table of the bond, discounted on the
+    /// bond's yield curve.
+    ///
+    /// Each coupon accrues from the previous coupon date (or the
+    /// evaluation date for the first coupon), and the face value is
+    /// reported as a separate notional cashflow on the final date.
+    /// The NPV of the report matches [`price`](Instrument::price).
+    ///
+    /// # Errors:
+    ///
+    /// * Any error returned by [`CashflowReport::discount`].
+    pub fn cashflow_report(&self) -> Result<CashflowReport, RustQuantError> {
+        let mut entries = Vec::with_capacity(self.coupons.len() + 1);
+        let mut accrual_start = self.evaluation_date;
+
+        for (i, (&date, &amount)) in self.coupons.iter().enumerate() {
+            let is_last = i + 1 == self.coupons.len();
+
+            let mut coupon = CashflowEntry::notional(date, amount, self.currency);
+            coupon.kind = CashflowKind::FixedCoupon;
+            coupon.accrual_start_date = Some(accrual_start);
+            coupon.accrual_end_date = Some(date);
+            coupon.notional = Some(self.face_value);
+
+            if is_last {
+                coupon.amount -= self.face_value;
+                entries.push(coupon);
+                entries.push(CashflowEntry::notional(
+                    date,
+                    self.face_value,
+                    self.currency,
+                ));
+            } else {
+                entries.push(coupon);
+            }
+
+            accrual_start = date;
+        }
+
+        CashflowReport::new(entries).discount(&self.yield_curve)
+    }
 }
 
 impl Instrument for CouponBond {
     /// Returns the price (net present value) of the instrument.
-    fn price(&self) -> f64 {
-        // Compute the discount factors for the coupons.
-        let discount_factors = self
-            .yield_curve
-            .discount_factors(&self.coupons.keys().copied().collect::<Vec<Date>>());
-        // .iter()
-        // .enumerate()
-        // .map(|(i, df)| (1. + df / self.coupon_frequency as i32 as f64).powi((i + 1) as i32))
-        // .collect::<Vec<f64>>();
-
-        // Compute the present value of the coupons and face value, and sum them.
+    ///
+    /// The coupons are discounted on the context's discount curve for the
+    /// bond's currency if there is one (using the context's cache),
+    /// otherwise on the bond's own yield curve.
+    ///
+    /// # Errors:
+    ///
+    /// * Any error returned by [`Curve::discount_factor`], e.g. for a
+    ///   coupon paid after the curve's terminal date.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
         self.coupons
-            .values()
-            .zip(discount_factors.iter())
-            .map(|(coupon, df)| coupon * df)
-            .sum::<f64>()
+            .iter()
+            .map(|(date, coupon)| {
+                let df = match self.currency.and_then(|c| ctx.discount_factor(&c, *date)) {
+                    Some(df) => df,
+                    None => self.yield_curve.discount_factor(*date)?,
+                };
+
+                Ok(coupon * df)
+            })
+            .sum()
     }
 
     /// Returns the error on the NPV in case the pricing engine can
@@ -152,6 +244,11 @@ impl Instrument for CouponBond {
     fn instrument_type(&self) -> &'static str {
         "Coupon Bond"
     }
+
+    /// Currency of the bond.
+    fn currency(&self) -> Option<Currency> {
+        self.currency
+    }
 }
 
 impl CouponBond2 {
@@ -198,7 +295,7 @@ mod tests_bond {
             t0 + Duration::days(30 * 365),
         ];
 
-        YieldCurve::from_dates_and_rates(&date_vec, &rate_vec)
+        YieldCurve::from_dates_and_rates(&date_vec, &rate_vec).unwrap()
     }
 
     #[test]
@@ -223,6 +320,107 @@ mod tests_bond {
         // Getting:   $1,198.47
         // Think its close enough for now, down to differences in my computation
         // and the calculator I used. Possibly continuous compounding vs discrete.
-        println!("Price: {}", bond.price());
+        println!("Price: {}", bond.price().unwrap());
+    }
+
+    #[test]
+    fn test_coupon_construction_from_schedule() {
+        use crate::time::{
+            countries::oceania::australia::AustraliaCalendar, DayCountConvention,
+            ScheduleGeneratorBuilder,
+        };
+        use time::macros::date;
+
+        let schedule = ScheduleGeneratorBuilder::default()
+            .effective_date(date!(2024 - 01 - 15))
+            .termination_date(date!(2026 - 01 - 15))
+            .frequency(Frequency::SemiAnnually)
+            .day_counting_convention(DayCountConvention::One_One)
+            .build()
+            .unwrap()
+            .generate(&AustraliaCalendar)
+            .unwrap();
+
+        let mut bond = CouponBond {
+            evaluation_date: date!(2024 - 01 - 15),
+            expiration_date: date!(2026 - 01 - 15),
+            currency: Some(USD),
+            coupon_rate: 0.05,
+            coupon_frequency: Frequency::SemiAnnually,
+            settlement_convention: DateRollingConvention::Actual,
+            yield_curve: create_test_yield_curve(date!(2024 - 01 - 15)),
+            face_value: 100.0,
+            coupons: BTreeMap::new(),
+        };
+
+        bond.construct_coupons_from_schedule(&schedule);
+
+        assert_eq!(bond.coupons.len(), 4);
+        assert_eq!(
+            bond.coupons.values().copied().collect::<Vec<f64>>(),
+            vec![5.0, 5.0, 5.0, 105.0]
+        );
+    }
+
+    #[test]
+    fn test_cashflow_report() {
+        let today = today();
+
+        let mut bond = CouponBond {
+            evaluation_date: today,
+            expiration_date: today + Duration::days(365 * 2),
+            currency: Some(USD),
+            coupon_rate: 0.05,
+            coupon_frequency: Frequency::SemiAnnually,
+            settlement_convention: DateRollingConvention::Actual,
+            yield_curve: create_test_yield_curve(today),
+            face_value: 100.0,
+            coupons: BTreeMap::new(),
+        };
+
+        bond.construct_coupons();
+
+        let report = bond.cashflow_report().unwrap();
+
+        assert_eq!(report.entries.len(), bond.coupons.len() + 1);
+        assert_eq!(report.entries.last().unwrap().kind, CashflowKind::Notional);
+        assert!((report.total_amount() - bond.coupons.values().sum::<f64>()).abs() < 1e-10);
+        assert!((report.npv() - bond.price().unwrap()).abs() < 1e-10);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests_bond_serde {
+    use super::*;
+    use crate::{data::Curve, iso::USD};
+    use time::macros::date;
+
+    #[test]
+    fn test_round_trip() {
+        let t0 = date!(2024 - 01 - 01);
+
+        let mut bond = CouponBond {
+            evaluation_date: t0,
+            expiration_date: t0 + Duration::days(365 * 2),
+            currency: Some(USD),
+            coupon_rate: 0.05,
+            coupon_frequency: Frequency::SemiAnnually,
+            settlement_convention: DateRollingConvention::Actual,
+            yield_curve: YieldCurve::from_dates_and_rates(
+                &[t0 + Duration::days(90), t0 + Duration::days(3 * 365)],
+                &[0.04, 0.045],
+            )
+            .unwrap(),
+            face_value: 100.0,
+            coupons: BTreeMap::new(),
+        };
+        bond.construct_coupons();
+
+        let json = serde_json::to_string(&bond).unwrap();
+        let round_trip: CouponBond = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(round_trip.currency, Some(USD));
+        assert_eq!(round_trip.coupons, bond.coupons);
+        assert_eq!(round_trip.price().unwrap(), bond.price().unwrap());
     }
 }
@@ -24,12 +24,15 @@
 //! the standard deviation increases.
 
 use crate::{
-    instruments::Instrument,
+    error::RustQuantError,
+    instruments::{Instrument, PricingContext},
     time::{today, DayCountConvention},
 };
 use time::Date;
 
 /// Struct containing the Cox-Ingersoll-Ross model parameters.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CoxIngersollRoss {
     a: f64,
     b: f64,
@@ -42,8 +45,10 @@ pub struct CoxIngersollRoss {
     pub expiration_date: Date,
 }
 
-impl Instrument for CoxIngersollRoss {
-    fn price(&self) -> f64 {
+impl CoxIngersollRoss {
+    /// Zero-coupon bond price as of the evaluation date (or today).
+    #[must_use]
+    pub fn price(&self) -> f64 {
         let a = self.a;
         let b = self.b;
         let sigma = self.sigma;
@@ -66,6 +71,16 @@ impl Instrument for CoxIngersollRoss {
         // Price:
         a_t * (-b_t * r).exp()
     }
+}
+
+impl Instrument for CoxIngersollRoss {
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        Ok(Self {
+            evaluation_date: self.evaluation_date.or(Some(ctx.valuation_date)),
+            ..*self
+        }
+        .price())
+    }
 
     fn error(&self) -> Option<f64> {
         None
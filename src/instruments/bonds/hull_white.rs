@@ -24,12 +24,16 @@
 //! - `t`: time to check price at
 //! - `maturity`: time at bond maturity
 
-use crate::instruments::Instrument;
+use crate::error::RustQuantError;
+use crate::instruments::validation::{Validate, Validator};
+use crate::instruments::{Instrument, PricingContext};
 use crate::math::integrate;
 use crate::time::{today, DayCountConvention};
 use time::Date;
 
 /// Struct containing the Hull-White model parameters.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HullWhite {
     a: f64,
     theta_t: fn(f64) -> f64,
@@ -45,14 +49,11 @@ pub struct HullWhite {
 impl HullWhite {
     // TODO make dependenont t,T
     fn B(&self) -> f64 {
-        assert!(self.a > 0.0);
         (1.0 / self.a) * (1.0 - (-self.a).exp())
     }
 
     // TODO make dependenont t,T
     fn A(&self) -> f64 {
-        assert!(self.a > 0.0);
-
         let today = today();
         let t = (self.evaluation_date.unwrap_or(today).year() - today.year()) as f64;
         let T = (self.expiration_date.year() - today.year()) as f64;
@@ -74,12 +75,43 @@ impl HullWhite {
     }
 }
 
-impl Instrument for HullWhite {
-    fn price(&self) -> f64 {
-        assert!(self.a > 0.0);
-        assert!(self.expiration_date >= self.evaluation_date.unwrap_or(today()));
+impl HullWhite {
+    /// Zero-coupon bond price as of the evaluation date (or today).
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`].
+    pub fn price(&self) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        Ok(self.A() * (-1.0 * self.B() * self.r_t).exp())
+    }
+}
 
-        self.A() * (-1.0 * self.B() * self.r_t).exp()
+impl Validate for HullWhite {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let evaluation_date = self.evaluation_date.unwrap_or(today());
+
+        Validator::new()
+            .positive("a", self.a)
+            .check(self.expiration_date >= evaluation_date, || {
+                format!(
+                    "expiration_date ({}) must not be before the evaluation date ({evaluation_date}).",
+                    self.expiration_date
+                )
+            })
+            .finish()
+    }
+}
+
+impl Instrument for HullWhite {
+    /// Returns the bond price (see [`HullWhite::price`]).
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        Self {
+            evaluation_date: self.evaluation_date.or(Some(ctx.valuation_date)),
+            ..*self
+        }
+        .price()
     }
 
     fn error(&self) -> Option<f64> {
@@ -109,8 +141,28 @@ mod tests {
             evaluation_date: None,
             expiration_date: today() + time::Duration::days(365 * 10),
         };
-        let _price = hw_bond.price();
+        let _price = hw_bond.price().unwrap();
         // TODO check price against actual
         // But this implementation is analytic, so should be right
     }
+
+    #[test]
+    fn test_hw_zero_coupon_bond_invalid_inputs() {
+        let hw_bond = HullWhite {
+            a: 2.0,
+            theta_t: |_x| 0.5,
+            sigma: 0.3,
+            r_t: 0.05,
+            evaluation_date: None,
+            expiration_date: today() + time::Duration::days(365 * 10),
+        };
+
+        assert!(HullWhite { a: 0.0, ..hw_bond }.price().is_err());
+        assert!(HullWhite {
+            expiration_date: today() - time::Duration::days(1),
+            ..hw_bond
+        }
+        .price()
+        .is_err());
+    }
 }
@@ -11,8 +11,12 @@
 pub mod zero_coupon_bond;
 
 /// Coupon bond struct.
+#[cfg(feature = "curves")]
 pub mod coupon_bond;
 
+/// Convertible bond (Tsiveriotis-Fernandes) pricer.
+pub mod convertible_bond;
+
 // /// Cox-Ingersoll-Ross bond pricing model.
 // pub mod cox_ingersoll_ross;
 
@@ -20,11 +20,14 @@
 //! - `θ`: is the level to which it gets pulled.
 //! - `σ`: is the diffusion coefficient.
 
-use crate::instruments::Instrument;
+use crate::error::RustQuantError;
+use crate::instruments::{Instrument, PricingContext};
 use crate::time::{today, DayCountConvention};
 use time::Date;
 
 /// Struct containing the Vasicek model parameters.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vasicek {
     r0: f64,
     k: f64,
@@ -37,8 +40,10 @@ pub struct Vasicek {
     pub expiration_date: Date,
 }
 
-impl Instrument for Vasicek {
-    fn price(&self) -> f64 {
+impl Vasicek {
+    /// Zero-coupon bond price as of the evaluation date (or today).
+    #[must_use]
+    pub fn price(&self) -> f64 {
         let k = self.k;
         let theta = self.theta;
         let sigma = self.sigma;
@@ -74,6 +79,16 @@ impl Instrument for Vasicek {
         //     -P_tS * N(-h) + strike * P_tT * N(sigma_p - h),
         // )
     }
+}
+
+impl Instrument for Vasicek {
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        Ok(Self {
+            evaluation_date: self.evaluation_date.or(Some(ctx.valuation_date)),
+            ..*self
+        }
+        .price())
+    }
 
     fn error(&self) -> Option<f64> {
         None
@@ -19,6 +19,7 @@ use time::Date;
 /// debt security that doesn't pay interest (a coupon) periodically but
 /// instead pays the principal in full at maturity.
 #[allow(clippy::module_name_repetitions)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZeroCouponBond {
     /// The date the bond is evaluated (i.e. priced).
     pub evaluation_date: Date,
@@ -13,8 +13,13 @@
 //! Basic arithmetic operations can be performed  on `Money` instances with the
 //! same underlying currency.
 
-use crate::iso::ISO_4217;
-use crate::{instruments::Instrument, time::today};
+use crate::iso::{CURRENCIES, ISO_4217};
+#[cfg(feature = "curves")]
+use crate::{
+    error::RustQuantError,
+    instruments::{Instrument, PricingContext},
+    time::today,
+};
 use std::fmt::{self, Formatter};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -40,9 +45,10 @@ pub struct Currency {
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+#[cfg(feature = "curves")]
 impl Instrument for Currency {
-    fn price(&self) -> f64 {
-        1.0
+    fn price(&self, _ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        Ok(1.0)
     }
 
     fn error(&self) -> Option<f64> {
@@ -56,6 +62,10 @@ impl Instrument for Currency {
     fn instrument_type(&self) -> &'static str {
         self.name
     }
+
+    fn currency(&self) -> Option<Currency> {
+        Some(*self)
+    }
 }
 
 impl Eq for Currency {}
@@ -137,6 +147,56 @@ impl Currency {
     pub fn fractions(&self) -> usize {
         self.fractions
     }
+
+    /// Look up a currency by its ISO 4217 alphabetic (e.g. "USD")
+    /// or numeric (e.g. "840") code.
+    ///
+    /// # Example
+    /// ```
+    /// use RustQuant::instruments::fx::currency::Currency;
+    /// use RustQuant::iso::USD;
+    ///
+    /// assert_eq!(Currency::from_code("usd"), Some(USD));
+    /// assert_eq!(Currency::from_code("840"), Some(USD));
+    /// assert_eq!(Currency::from_code("XYZ"), None);
+    /// ```
+    #[must_use]
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_uppercase();
+
+        CURRENCIES
+            .iter()
+            .find(|c| c.code.alphabetic == code || c.code.numeric == code)
+            .copied()
+    }
+
+    /// Round an amount to the minor unit of the currency
+    /// (e.g. to cents for USD, to whole yen for JPY).
+    #[must_use]
+    pub fn round(&self, amount: f64) -> f64 {
+        let scale = 10_f64.powi(self.minor as i32);
+
+        (amount * scale).round() / scale
+    }
+}
+
+/// Currencies are serialized as their ISO 4217 alphabetic code (e.g. `"USD"`).
+#[cfg(feature = "serde")]
+impl serde::Serialize for Currency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code.alphabetic)
+    }
+}
+
+/// Currencies are deserialized from their ISO 4217 alphabetic or numeric code.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Currency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = <String as serde::Deserialize>::deserialize(deserializer)?;
+
+        Currency::from_code(&code)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown currency code: {code}")))
+    }
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -278,3 +338,29 @@ mod test_currencies {
         let _ = money1 / money2;
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests_currency_serde {
+    use crate::instruments::fx::{currency::Currency, money::Money};
+    use crate::iso::{EUR, JPY};
+
+    #[test]
+    fn test_currency_round_trip() {
+        let json = serde_json::to_string(&EUR).unwrap();
+        assert_eq!(json, "\"EUR\"");
+        assert_eq!(serde_json::from_str::<Currency>(&json).unwrap(), EUR);
+        assert_eq!(serde_json::from_str::<Currency>("\"392\"").unwrap(), JPY);
+        assert!(serde_json::from_str::<Currency>("\"XXY\"").is_err());
+    }
+
+    #[test]
+    fn test_money_round_trip() {
+        let money = Money::new(EUR, 1234.5);
+
+        let json = serde_json::to_string(&money).unwrap();
+        let round_trip: Money = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(round_trip.currency(), EUR);
+        assert_eq!(round_trip.amount(), 1234.5);
+    }
+}
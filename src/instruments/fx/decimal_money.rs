@@ -0,0 +1,335 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Decimal money amounts.
+//!
+//! Model maths is done in `f64`, but summing many `f64` amounts leaves
+//! binary floating-point dust (`0.1 + 0.2 != 0.3`) that does not reconcile
+//! against accounting systems. [`DecimalMoney`] holds the amount as a
+//! base-10 fixed-point [`Decimal`], so that settlement amounts can be
+//! rounded to the currency's minor unit once and then added exactly.
+//!
+//! ```
+//! use RustQuant::instruments::fx::decimal_money::DecimalMoney;
+//! use RustQuant::iso::USD;
+//!
+//! let total = DecimalMoney::try_sum(
+//!     USD,
+//!     [0.1, 0.2].map(|amount| DecimalMoney::from_f64(USD, amount).unwrap()),
+//! )?;
+//!
+//! assert_eq!(total.to_string(), "USD 0.30");
+//! assert_eq!(total.to_f64(), 0.3);
+//! # Ok::<(), RustQuant::error::RustQuantError>(())
+//! ```
+
+use super::{currency::Currency, money::Money};
+use crate::error::RustQuantError;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::fmt::{self, Formatter};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Amount of money in a currency, held as a decimal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecimalMoney {
+    /// The underlying currency.
+    pub currency: Currency,
+    /// The amount.
+    pub amount: Decimal,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl DecimalMoney {
+    /// Create a new decimal money instance.
+    #[must_use]
+    pub fn new(currency: Currency, amount: Decimal) -> Self {
+        Self { currency, amount }
+    }
+
+    /// Zero amount of the given currency.
+    #[must_use]
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(currency, Decimal::ZERO)
+    }
+
+    /// Convert an `f64` amount (e.g. a model output), rounding half away
+    /// from zero to the minor unit of the currency.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::InvalidArgument`] if the amount is not finite or
+    /// is out of the range of a [`Decimal`].
+    pub fn from_f64(currency: Currency, amount: f64) -> Result<Self, RustQuantError> {
+        Decimal::from_f64(amount)
+            .map(|amount| Self::new(currency, amount).round())
+            .ok_or_else(|| {
+                RustQuantError::InvalidArgument(format!(
+                    "cannot represent {amount} {} as a decimal",
+                    currency.code.alphabetic
+                ))
+            })
+    }
+
+    /// Get the currency.
+    #[must_use]
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// Get the amount.
+    #[must_use]
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// The amount as an `f64`, for use in model maths.
+    #[must_use]
+    pub fn to_f64(&self) -> f64 {
+        self.amount.to_f64().unwrap_or(f64::NAN)
+    }
+
+    /// Round the amount to the minor unit of its currency,
+    /// half away from zero.
+    #[must_use]
+    pub fn round(&self) -> Self {
+        self.round_with(RoundingStrategy::MidpointAwayFromZero)
+    }
+
+    /// Round the amount to the minor unit of its currency with the given
+    /// strategy (e.g. [`RoundingStrategy::MidpointNearestEven`] for
+    /// banker's rounding).
+    #[must_use]
+    pub fn round_with(&self, strategy: RoundingStrategy) -> Self {
+        Self::new(
+            self.currency,
+            self.amount.round_dp_with_strategy(self.minor(), strategy),
+        )
+    }
+
+    /// Add two amounts, returning an error if the currencies differ.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::CurrencyMismatch`] if the currencies differ,
+    /// [`RustQuantError::InvalidArgument`] if the sum overflows.
+    pub fn checked_add(self, other: Self) -> Result<Self, RustQuantError> {
+        self.check_currency(&other)?;
+
+        self.amount
+            .checked_add(other.amount)
+            .map(|amount| Self::new(self.currency, amount))
+            .ok_or_else(|| Self::overflow("addition"))
+    }
+
+    /// Subtract two amounts, returning an error if the currencies differ.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::CurrencyMismatch`] if the currencies differ,
+    /// [`RustQuantError::InvalidArgument`] if the difference overflows.
+    pub fn checked_sub(self, other: Self) -> Result<Self, RustQuantError> {
+        self.check_currency(&other)?;
+
+        self.amount
+            .checked_sub(other.amount)
+            .map(|amount| Self::new(self.currency, amount))
+            .ok_or_else(|| Self::overflow("subtraction"))
+    }
+
+    /// Scale the amount by a decimal factor (e.g. a quantity).
+    /// The result is not rounded.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::InvalidArgument`] if the product overflows.
+    pub fn checked_mul(self, factor: Decimal) -> Result<Self, RustQuantError> {
+        self.amount
+            .checked_mul(factor)
+            .map(|amount| Self::new(self.currency, amount))
+            .ok_or_else(|| Self::overflow("multiplication"))
+    }
+
+    /// Sum a collection of amounts in the given currency,
+    /// returning an error if any amount is in a different currency.
+    ///
+    /// # Errors
+    ///
+    /// See [`DecimalMoney::checked_add`].
+    pub fn try_sum<I>(currency: Currency, amounts: I) -> Result<Self, RustQuantError>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Self::zero(currency), Self::checked_add)
+    }
+
+    fn minor(&self) -> u32 {
+        self.currency.minor as u32
+    }
+
+    fn overflow(operation: &str) -> RustQuantError {
+        RustQuantError::InvalidArgument(format!("decimal overflow in {operation}"))
+    }
+
+    fn check_currency(&self, other: &Self) -> Result<(), RustQuantError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(RustQuantError::CurrencyMismatch(
+                self.currency.code.alphabetic.to_string(),
+                other.currency.code.alphabetic.to_string(),
+            ))
+        }
+    }
+}
+
+impl PartialOrd for DecimalMoney {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if self.currency == other.currency {
+            self.amount.partial_cmp(&other.amount)
+        } else {
+            None
+        }
+    }
+}
+
+/// Formats as `"<ISO code> <amount>"`, padded to the minor unit
+/// (e.g. `"USD 12.50"`).
+impl fmt::Display for DecimalMoney {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut amount = self.amount;
+
+        if amount.scale() < self.minor() {
+            amount.rescale(self.minor());
+        }
+
+        write!(f, "{} {}", self.currency.code.alphabetic, amount)
+    }
+}
+
+impl std::ops::Neg for DecimalMoney {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(self.currency, -self.amount)
+    }
+}
+
+impl From<DecimalMoney> for Money {
+    fn from(money: DecimalMoney) -> Self {
+        Money::new(money.currency, money.to_f64())
+    }
+}
+
+impl TryFrom<Money> for DecimalMoney {
+    type Error = RustQuantError;
+
+    /// Convert, rounding to the minor unit of the currency.
+    fn try_from(money: Money) -> Result<Self, Self::Error> {
+        Self::from_f64(money.currency, money.amount)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_decimal_money {
+    use super::*;
+    use crate::iso::{EUR, JPY, USD};
+    use std::str::FromStr;
+
+    fn usd(amount: &str) -> DecimalMoney {
+        DecimalMoney::new(USD, Decimal::from_str(amount).unwrap())
+    }
+
+    #[test]
+    fn test_from_f64_rounds_to_minor_unit() {
+        assert_eq!(DecimalMoney::from_f64(USD, 2.675).unwrap(), usd("2.68"));
+        assert_eq!(DecimalMoney::from_f64(USD, -2.675).unwrap(), usd("-2.68"));
+        assert_eq!(
+            DecimalMoney::from_f64(JPY, 1500.5).unwrap().amount,
+            Decimal::from(1501)
+        );
+
+        assert!(DecimalMoney::from_f64(USD, f64::NAN).is_err());
+        assert!(DecimalMoney::from_f64(USD, f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_sum_has_no_floating_point_dust() {
+        let cents = std::iter::repeat_n(0.01, 1000);
+
+        let float_total: f64 = cents.clone().sum();
+        let decimal_total = DecimalMoney::try_sum(
+            USD,
+            cents.map(|amount| DecimalMoney::from_f64(USD, amount).unwrap()),
+        )
+        .unwrap();
+
+        assert_ne!(float_total, 10.0);
+        assert_eq!(decimal_total, usd("10.00"));
+        assert_eq!(decimal_total.to_f64(), 10.0);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = usd("100.10");
+        let b = usd("0.20");
+
+        assert_eq!(a.checked_add(b).unwrap(), usd("100.30"));
+        assert_eq!(a.checked_sub(b).unwrap(), usd("99.90"));
+        assert_eq!(-b, usd("-0.20"));
+        assert_eq!(
+            b.checked_mul(Decimal::from_str("0.125").unwrap()).unwrap(),
+            usd("0.025")
+        );
+        assert!(a > b);
+
+        let eur = DecimalMoney::from_f64(EUR, 1.0).unwrap();
+        assert!(matches!(
+            a.checked_add(eur),
+            Err(RustQuantError::CurrencyMismatch(_, _))
+        ));
+        assert_eq!(a.partial_cmp(&eur), None);
+    }
+
+    #[test]
+    fn test_rounding_strategies() {
+        let amount = usd("0.125");
+
+        assert_eq!(amount.round(), usd("0.13"));
+        assert_eq!(
+            amount.round_with(RoundingStrategy::MidpointNearestEven),
+            usd("0.12")
+        );
+    }
+
+    #[test]
+    fn test_display_and_conversions() {
+        assert_eq!(usd("12.5").to_string(), "USD 12.50");
+        assert_eq!(usd("0.125").to_string(), "USD 0.125");
+
+        let money = Money::new(USD, 1234.567);
+        let decimal = DecimalMoney::try_from(money).unwrap();
+
+        assert_eq!(decimal, usd("1234.57"));
+        assert_eq!(Money::from(decimal).amount, 1234.57);
+    }
+}
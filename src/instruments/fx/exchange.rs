@@ -9,6 +9,7 @@
 
 //! FX exchange module.
 
+use crate::error::RustQuantError;
 use crate::instruments::fx::currency::Currency;
 use crate::instruments::fx::money::Money;
 use std::collections::HashMap;
@@ -19,6 +20,7 @@ use std::collections::HashMap;
 
 /// Exchange struct to hold exchange rates.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Exchange {
     /// Exchange rates hashmap.
     /// The key is a string of the form e.g. "USD_EUR",
@@ -30,6 +32,7 @@ pub struct Exchange {
 /// `ExchangeRate` struct to hold exchange rate information.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExchangeRate {
     /// From currency
     pub from_currency: Currency,
@@ -41,6 +44,21 @@ pub struct ExchangeRate {
     pub rate: f64,
 }
 
+/// FX matrix holding quoted currency pairs.
+///
+/// Rates for pairs that are not quoted directly are derived by inverting
+/// the opposite quote, or by triangulating through a pivot currency
+/// (e.g. EUR/JPY from EUR/USD and USD/JPY).
+/// If no pivot is set, any currency quoted against both sides is used.
+#[derive(Debug, Clone, Default)]
+pub struct FxMatrix {
+    /// Quoted rates, keyed by (from, to) alphabetic codes.
+    pub quotes: HashMap<(&'static str, &'static str), ExchangeRate>,
+
+    /// Pivot currency used for triangulation (usually USD).
+    pub pivot: Option<Currency>,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -159,6 +177,122 @@ impl Exchange {
     }
 }
 
+impl FxMatrix {
+    /// Create a new empty FX matrix with an optional pivot currency.
+    #[must_use]
+    pub fn new(pivot: Option<Currency>) -> Self {
+        Self {
+            quotes: HashMap::new(),
+            pivot,
+        }
+    }
+
+    /// Adds (or replaces) a quoted rate.
+    pub fn add_quote(&mut self, rate: ExchangeRate) {
+        self.quotes.insert(
+            (
+                rate.from_currency.code.alphabetic,
+                rate.to_currency.code.alphabetic,
+            ),
+            rate,
+        );
+    }
+
+    /// Returns the rate to convert one unit of `from` into `to`.
+    ///
+    /// The rate is looked up in the following order:
+    /// 1. Identity (same currency).
+    /// 2. Direct quote.
+    /// 3. Inverse of the opposite quote.
+    /// 4. Triangulation through the pivot currency (or, if no pivot is set,
+    ///    through any currency quoted against both sides).
+    ///
+    /// # Example
+    /// ```
+    /// use RustQuant::instruments::fx::exchange::{ExchangeRate, FxMatrix};
+    /// use RustQuant::iso::{EUR, JPY, USD};
+    /// use RustQuant::assert_approx_equal;
+    ///
+    /// let mut fx = FxMatrix::new(Some(USD));
+    ///
+    /// fx.add_quote(ExchangeRate::new(EUR, USD, 1.10));
+    /// fx.add_quote(ExchangeRate::new(USD, JPY, 150.0));
+    ///
+    /// assert_approx_equal!(fx.rate(&EUR, &JPY).unwrap(), 165.0, 1e-10);
+    /// assert_approx_equal!(fx.rate(&JPY, &EUR).unwrap(), 1.0 / 165.0, 1e-10);
+    /// ```
+    pub fn rate(&self, from: &Currency, to: &Currency) -> Result<f64, RustQuantError> {
+        if let Some(rate) = self.quoted_rate(from, to) {
+            return Ok(rate);
+        }
+
+        let via = |pivot: &Currency| {
+            Some(self.quoted_rate(from, pivot)? * self.quoted_rate(pivot, to)?)
+        };
+
+        let triangulated = match &self.pivot {
+            Some(pivot) => via(pivot),
+            None => self
+                .currencies()
+                .iter()
+                .filter(|c| *c != from && *c != to)
+                .find_map(via),
+        };
+
+        triangulated.ok_or_else(|| {
+            RustQuantError::MissingExchangeRate(
+                from.code.alphabetic.to_string(),
+                to.code.alphabetic.to_string(),
+            )
+        })
+    }
+
+    /// Returns the (possibly derived) `ExchangeRate` from `from` to `to`.
+    pub fn exchange_rate(
+        &self,
+        from: &Currency,
+        to: &Currency,
+    ) -> Result<ExchangeRate, RustQuantError> {
+        Ok(ExchangeRate::new(*from, *to, self.rate(from, to)?))
+    }
+
+    /// Convert money into another currency.
+    pub fn convert(&self, money: Money, to: Currency) -> Result<Money, RustQuantError> {
+        Ok(Money::new(to, money.amount * self.rate(&money.currency, &to)?))
+    }
+
+    /// All currencies appearing in the quoted pairs.
+    #[must_use]
+    pub fn currencies(&self) -> Vec<Currency> {
+        let mut currencies: Vec<Currency> = Vec::new();
+
+        for rate in self.quotes.values() {
+            for currency in [rate.from_currency, rate.to_currency] {
+                if !currencies.contains(&currency) {
+                    currencies.push(currency);
+                }
+            }
+        }
+
+        currencies.sort_by_key(|c| c.code.alphabetic);
+        currencies
+    }
+
+    /// Direct, inverse or identity rate, without triangulation.
+    fn quoted_rate(&self, from: &Currency, to: &Currency) -> Option<f64> {
+        let (from, to) = (from.code.alphabetic, to.code.alphabetic);
+
+        if from == to {
+            return Some(1.0);
+        }
+
+        self.quotes
+            .get(&(from, to))
+            .map(|q| q.rate)
+            .or_else(|| self.quotes.get(&(to, from)).map(|q| 1.0 / q.rate))
+    }
+}
+
 impl ExchangeRate {
     /// Create a new exchange rate.
     #[must_use]
@@ -170,6 +304,12 @@ impl ExchangeRate {
         }
     }
 
+    /// The inverse exchange rate (`to_currency` into `from_currency`).
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        Self::new(self.to_currency, self.from_currency, 1.0 / self.rate)
+    }
+
     /// Convert money from one currency to another using this exchange rate.
     /// It panics if the money's currency doesn't match with `from_currency`.
     ///
@@ -288,4 +428,56 @@ mod test_exchange_rate {
         assert_eq!(eur_85.currency, EUR);
         assert_approx_equal!(eur_85.amount, 85.0, EPS);
     }
+
+    #[test]
+    fn test_exchange_rate_inverse() {
+        let eur_usd = ExchangeRate::new(EUR, USD, 1.25);
+        let usd_eur = eur_usd.inverse();
+
+        assert_eq!(usd_eur.from_currency, USD);
+        assert_eq!(usd_eur.to_currency, EUR);
+        assert_approx_equal!(usd_eur.rate, 0.8, EPS);
+    }
+
+    #[test]
+    fn test_fx_matrix_direct_and_inverse() {
+        let mut fx = FxMatrix::new(None);
+        fx.add_quote(ExchangeRate::new(EUR, USD, 1.25));
+
+        assert_approx_equal!(fx.rate(&EUR, &USD).unwrap(), 1.25, EPS);
+        assert_approx_equal!(fx.rate(&USD, &EUR).unwrap(), 0.8, EPS);
+        assert_approx_equal!(fx.rate(&GBP, &GBP).unwrap(), 1.0, EPS);
+    }
+
+    #[test]
+    fn test_fx_matrix_triangulation() {
+        let mut fx = FxMatrix::new(Some(USD));
+        fx.add_quote(ExchangeRate::new(EUR, USD, 1.10));
+        fx.add_quote(ExchangeRate::new(GBP, USD, 1.25));
+        fx.add_quote(ExchangeRate::new(USD, JPY, 150.0));
+
+        assert_approx_equal!(fx.rate(&EUR, &GBP).unwrap(), 1.10 / 1.25, 1e-12);
+        assert_approx_equal!(fx.rate(&GBP, &JPY).unwrap(), 187.5, 1e-10);
+
+        let converted = fx.convert(Money::new(EUR, 100.0), JPY).unwrap();
+        assert_eq!(converted.currency, JPY);
+        assert_approx_equal!(converted.amount, 16_500.0, 1e-9);
+
+        // Without an explicit pivot, any common currency is used.
+        fx.pivot = None;
+        assert_approx_equal!(fx.rate(&EUR, &JPY).unwrap(), 165.0, 1e-10);
+    }
+
+    #[test]
+    fn test_fx_matrix_missing_pair() {
+        let mut fx = FxMatrix::new(Some(USD));
+        fx.add_quote(ExchangeRate::new(EUR, USD, 1.10));
+
+        let result = fx.rate(&EUR, &CHF);
+        assert!(matches!(
+            result,
+            Err(RustQuantError::MissingExchangeRate(ref a, ref b)) if a == "EUR" && b == "CHF"
+        ));
+        assert!(fx.convert(Money::new(CHF, 1.0), EUR).is_err());
+    }
 }
@@ -10,3 +10,4 @@
 pub mod currency;
 pub mod exchange;
 pub mod money;
+pub mod decimal_money;
@@ -14,6 +14,7 @@
 //! same underlying currency.
 
 use super::currency::Currency;
+use crate::error::RustQuantError;
 use std::fmt::{self, Formatter};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -22,6 +23,7 @@ use std::fmt::{self, Formatter};
 
 /// Money struct.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Money {
     /// The underlying currency.
     pub currency: Currency,
@@ -79,6 +81,108 @@ impl Money {
     pub fn amount(&self) -> f64 {
         self.amount
     }
+
+    /// Zero amount of the given currency.
+    #[must_use]
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(currency, 0.0)
+    }
+
+    /// Add two amounts, returning an error if the currencies differ.
+    ///
+    /// # Example
+    /// ```
+    /// use RustQuant::instruments::fx::money::Money;
+    /// use RustQuant::iso::{EUR, USD};
+    ///
+    /// let usd = Money::new(USD, 10.0);
+    ///
+    /// assert_eq!(usd.checked_add(usd).unwrap().amount, 20.0);
+    /// assert!(usd.checked_add(Money::new(EUR, 10.0)).is_err());
+    /// ```
+    pub fn checked_add(self, other: Self) -> Result<Self, RustQuantError> {
+        self.check_currency(&other)?;
+
+        Ok(Self::new(self.currency, self.amount + other.amount))
+    }
+
+    /// Subtract two amounts, returning an error if the currencies differ.
+    pub fn checked_sub(self, other: Self) -> Result<Self, RustQuantError> {
+        self.check_currency(&other)?;
+
+        Ok(Self::new(self.currency, self.amount - other.amount))
+    }
+
+    /// Sum a collection of amounts in the given currency,
+    /// returning an error if any amount is in a different currency.
+    pub fn try_sum<I>(currency: Currency, amounts: I) -> Result<Self, RustQuantError>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Self::zero(currency), Self::checked_add)
+    }
+
+    /// Round the amount to the minor unit of its currency.
+    #[must_use]
+    pub fn round(&self) -> Self {
+        Self::new(self.currency, self.currency.round(self.amount))
+    }
+
+    /// Format the amount with its ISO code, rounded to the currency's
+    /// minor unit and with thousands separators.
+    ///
+    /// # Example
+    /// ```
+    /// use RustQuant::instruments::fx::money::Money;
+    /// use RustQuant::iso::{JPY, USD};
+    ///
+    /// assert_eq!(Money::new(USD, -1234567.891).formatted(), "USD -1,234,567.89");
+    /// assert_eq!(Money::new(JPY, 1500.4).formatted(), "JPY 1,500");
+    /// ```
+    #[must_use]
+    pub fn formatted(&self) -> String {
+        let rounded = format!("{:.*}", self.currency.minor, self.currency.round(self.amount));
+
+        let (sign, unsigned) = match rounded.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", rounded.as_str()),
+        };
+
+        let (integer, fraction) = match unsigned.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (unsigned, None),
+        };
+
+        let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+
+        match fraction {
+            Some(fraction) => format!(
+                "{} {}{}.{}",
+                self.currency.code.alphabetic, sign, grouped, fraction
+            ),
+            None => format!("{} {}{}", self.currency.code.alphabetic, sign, grouped),
+        }
+    }
+
+    fn check_currency(&self, other: &Self) -> Result<(), RustQuantError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(RustQuantError::CurrencyMismatch(
+                self.currency.code.alphabetic.to_string(),
+                other.currency.code.alphabetic.to_string(),
+            ))
+        }
+    }
 }
 
 impl std::ops::Add for Money {
@@ -141,6 +245,50 @@ impl std::ops::Div for Money {
     }
 }
 
+impl std::ops::AddAssign for Money {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl std::ops::SubAssign for Money {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl std::ops::Neg for Money {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(self.currency, -self.amount)
+    }
+}
+
+impl std::ops::Mul<f64> for Money {
+    type Output = Self;
+
+    fn mul(self, scalar: f64) -> Self::Output {
+        Self::new(self.currency, self.amount * scalar)
+    }
+}
+
+impl std::ops::Mul<Money> for f64 {
+    type Output = Money;
+
+    fn mul(self, money: Money) -> Self::Output {
+        money * self
+    }
+}
+
+impl std::ops::Div<f64> for Money {
+    type Output = Self;
+
+    fn div(self, scalar: f64) -> Self::Output {
+        Self::new(self.currency, self.amount / scalar)
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -279,4 +427,54 @@ mod test_currencies {
         let money2 = Money::new(EUR, 2.0);
         let _ = money1 / money2;
     }
+
+    #[test]
+    fn test_money_checked_arithmetic() {
+        let money1 = Money::new(USD, 20.5);
+        let money2 = Money::new(USD, 10.5);
+
+        assert_approx_equal!(money1.checked_add(money2).unwrap().amount(), 31.0, EPS);
+        assert_approx_equal!(money1.checked_sub(money2).unwrap().amount(), 10.0, EPS);
+
+        let result = money1.checked_add(Money::new(EUR, 1.0));
+        assert!(matches!(
+            result,
+            Err(RustQuantError::CurrencyMismatch(ref a, ref b)) if a == "USD" && b == "EUR"
+        ));
+    }
+
+    #[test]
+    fn test_money_try_sum() {
+        let amounts = vec![Money::new(USD, 1.0), Money::new(USD, 2.0)];
+        let total = Money::try_sum(USD, amounts).unwrap();
+        assert_approx_equal!(total.amount(), 3.0, EPS);
+
+        let amounts = vec![Money::new(USD, 1.0), Money::new(EUR, 2.0)];
+        assert!(Money::try_sum(USD, amounts).is_err());
+    }
+
+    #[test]
+    fn test_money_scalar_arithmetic() {
+        let money = Money::new(USD, 10.0);
+
+        assert_approx_equal!((money * 2.5).amount(), 25.0, EPS);
+        assert_approx_equal!((2.5 * money).amount(), 25.0, EPS);
+        assert_approx_equal!((money / 4.0).amount(), 2.5, EPS);
+        assert_approx_equal!((-money).amount(), -10.0, EPS);
+
+        let mut total = money;
+        total += money;
+        total -= Money::new(USD, 5.0);
+        assert_approx_equal!(total.amount(), 15.0, EPS);
+    }
+
+    #[test]
+    fn test_money_rounding_and_formatting() {
+        let money = Money::new(USD, 1234.5678);
+
+        assert_approx_equal!(money.round().amount(), 1234.57, EPS);
+        assert_eq!(money.formatted(), "USD 1,234.57");
+        assert_eq!(Money::new(USD, 0.004).formatted(), "USD 0.00");
+        assert_eq!(Money::new(EUR, 999.999).formatted(), "EUR 1,000.00");
+    }
 }
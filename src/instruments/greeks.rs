@@ -0,0 +1,150 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(feature = "options")]
+use crate::instruments::options::BlackScholesMerton;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// First-order sensitivities (and gamma) of an instrument.
+///
+/// Greeks are additive, so the Greeks of a book are the quantity-weighted
+/// sum of the Greeks of its positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Greeks {
+    /// Sensitivity to the underlying price.
+    pub delta: f64,
+    /// Sensitivity of delta to the underlying price.
+    pub gamma: f64,
+    /// Sensitivity to the volatility.
+    pub vega: f64,
+    /// Sensitivity to the passage of time.
+    pub theta: f64,
+    /// Sensitivity to the risk-free rate.
+    pub rho: f64,
+}
+
+/// Instruments that can report their Greeks.
+pub trait Sensitivities {
+    /// Greeks of one unit of the instrument.
+    fn greeks(&self) -> Greeks;
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Add for Greeks {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            delta: self.delta + rhs.delta,
+            gamma: self.gamma + rhs.gamma,
+            vega: self.vega + rhs.vega,
+            theta: self.theta + rhs.theta,
+            rho: self.rho + rhs.rho,
+        }
+    }
+}
+
+impl AddAssign for Greeks {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Mul<f64> for Greeks {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self {
+            delta: self.delta * rhs,
+            gamma: self.gamma * rhs,
+            vega: self.vega * rhs,
+            theta: self.theta * rhs,
+            rho: self.rho * rhs,
+        }
+    }
+}
+
+impl Sum for Greeks {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+#[cfg(feature = "options")]
+impl Sensitivities for BlackScholesMerton {
+    fn greeks(&self) -> Greeks {
+        Greeks {
+            delta: self.delta(),
+            gamma: self.gamma(),
+            vega: self.vega(),
+            theta: self.theta(),
+            rho: self.rho(),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(all(test, feature = "options"))]
+mod tests_greeks {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::TypeFlag;
+    use time::macros::date;
+
+    const EPS: f64 = 1e-12;
+
+    #[test]
+    fn test_greeks_arithmetic() {
+        let a = Greeks {
+            delta: 0.5,
+            gamma: 0.02,
+            vega: 10.0,
+            theta: -3.0,
+            rho: 4.0,
+        };
+
+        let total: Greeks = [a, a * -2.0].into_iter().sum();
+
+        assert_eq!(total, a * -1.0);
+        assert_eq!(Greeks::default() + a, a);
+    }
+
+    #[test]
+    fn test_black_scholes_greeks() {
+        let option = BlackScholesMerton::new(
+            0.05,
+            100.0,
+            100.0,
+            0.2,
+            0.05,
+            Some(date!(2024 - 01 - 01)),
+            date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        );
+
+        let greeks = option.greeks();
+
+        assert_approx_equal!(greeks.delta, option.delta(), EPS);
+        assert_approx_equal!(greeks.gamma, option.gamma(), EPS);
+        assert_approx_equal!(greeks.vega, option.vega(), EPS);
+        assert_approx_equal!(greeks.theta, option.theta(), EPS);
+        assert_approx_equal!(greeks.rho, option.rho(), EPS);
+    }
+}
@@ -7,6 +7,11 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+#[cfg(feature = "curves")]
+use crate::error::RustQuantError;
+#[cfg(feature = "curves")]
+use crate::instruments::{fx::currency::Currency, PricingContext};
+#[cfg(feature = "curves")]
 use time::Date;
 
 /// Instrument trait
@@ -16,9 +21,23 @@ use time::Date;
 /// The valuation date is the date at which the instrument's NPV is
 /// being calculated; for most instruments it is the trade date, for
 /// some exotic products it might be the exercise date.
+///
+/// Instruments are priced against a [`PricingContext`], which carries the
+/// market environment (valuation date, curves, volatility surfaces, FX).
+/// Instruments without their own evaluation date are valued as of the
+/// context's valuation date.
+#[cfg(feature = "curves")]
 pub trait Instrument {
-    /// Returns the price (net present value) of the instrument.
-    fn price(&self) -> f64;
+    /// Returns the price (net present value) of the instrument,
+    /// given the pricing context.
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the instrument's parameters
+    ///   are invalid.
+    /// * Any error from the market data the instrument is priced on, e.g. a
+    ///   cashflow paid after a curve's terminal date.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError>;
 
     /// Returns the error on the NPV in case the pricing engine can
     /// provide it (e.g. Monte Carlo pricing engine).
@@ -29,9 +48,15 @@ pub trait Instrument {
 
     /// Instrument type.
     fn instrument_type(&self) -> &'static str;
+
+    /// Currency the price is denominated in (if known).
+    fn currency(&self) -> Option<Currency> {
+        None
+    }
 }
 
 /// Price structure.
+#[derive(Debug, Clone, Copy)]
 pub struct Price {
     /// Price of the instrument.
     pub price: f64,
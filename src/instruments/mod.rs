@@ -47,7 +47,7 @@
 //!   - [x] Lookback
 //!   - [ ] Asian
 //!   - [ ] Chooser
-//!   - [ ] Barrier
+//!   - [x] Barrier
 //!
 //! ```ignore
 //! use RustQuant::instruments::*;
@@ -70,11 +70,36 @@
 //! ```
 
 /// Base trait for all instruments.
+#[cfg(feature = "std")]
 pub mod instrument;
+#[cfg(feature = "std")]
 pub use instrument::*;
 
+/// Pricing context (market environment) for instruments.
+#[cfg(feature = "curves")]
+pub mod pricing_context;
+#[cfg(feature = "curves")]
+pub use pricing_context::*;
+
+/// Cache of intermediate pricing quantities.
+#[cfg(feature = "curves")]
+pub mod pricing_cache;
+#[cfg(feature = "curves")]
+pub use pricing_cache::*;
+
+/// Greeks (sensitivities) of instruments.
+#[cfg(feature = "std")]
+pub mod greeks;
+#[cfg(feature = "std")]
+pub use greeks::*;
+
+pub mod validation;
+pub use validation::*;
+
 /// Bond pricing models.
+#[cfg(feature = "std")]
 pub mod bonds;
+#[cfg(feature = "std")]
 pub use bonds::*;
 
 /// Option pricers and sensitivity functions.
@@ -82,13 +107,31 @@ pub mod options;
 pub use options::*;
 
 /// FX instruments.
+#[cfg(feature = "std")]
 pub mod fx;
+#[cfg(feature = "std")]
 pub use fx::*;
 
 /// Equity instruments.
+#[cfg(feature = "std")]
 pub mod equities;
+#[cfg(feature = "std")]
 pub use equities::*;
 
 /// Ticker symbol.
+#[cfg(feature = "std")]
 pub mod ticker;
+#[cfg(feature = "std")]
 pub use ticker::*;
+
+/// Structured products (autocallable and cliquet notes).
+#[cfg(all(feature = "options", feature = "autodiff"))]
+pub mod structured;
+#[cfg(all(feature = "options", feature = "autodiff"))]
+pub use structured::*;
+
+/// JSON trade import.
+#[cfg(all(feature = "serde", feature = "options"))]
+pub mod trades;
+#[cfg(all(feature = "serde", feature = "options"))]
+pub use trades::*;
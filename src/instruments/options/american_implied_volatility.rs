@@ -0,0 +1,174 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Implied volatility of American options, and de-Americanization.
+//!
+//! Single-stock options are American, and inverting their prices with a
+//! European formula overstates the volatility of puts (and of calls on
+//! dividend payers) by the early-exercise premium. Here the volatility is
+//! solved against the Cox-Ross-Rubinstein American price instead, and the
+//! quote is "de-Americanized": repriced as a European option at that
+//! volatility, so that European surface construction and calibration can
+//! use it directly.
+
+use super::{generalised_black_scholes_merton, BinomialOption, ExerciseFlag, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::Validator;
+use crate::math::brent::Brent;
+use crate::math::rootfinder::{Rootfinder, RootfinderData};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Implied volatility of an American option quote, and its European
+/// equivalent.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmericanImpliedVolatility {
+    /// Volatility at which the American price matches the quote.
+    pub implied_volatility: f64,
+    /// Black-Scholes price of the European option at that volatility.
+    pub european_price: f64,
+    /// Early-exercise premium: the quote less the European price.
+    pub early_exercise_premium: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Implied volatility of an American option price, using a
+/// Cox-Ross-Rubinstein tree of height `n`, together with the de-Americanized
+/// (European) price.
+///
+/// # Errors:
+/// * [`RustQuantError::InvalidArgument`] if `S`, `K` or `T` are not
+///   positive, the rates are not finite, or the tree height is below 3.
+/// * [`RustQuantError::ArbitrageViolation`] if the price is not above the
+///   intrinsic value, or not below the upper bound (`S` for calls, `K` for
+///   puts).
+/// * [`RustQuantError::NonConvergence`] if the root-finder fails.
+#[allow(clippy::too_many_arguments)]
+pub fn american_implied_volatility(
+    price: f64,
+    S: f64,
+    K: f64,
+    T: f64,
+    r: f64,
+    q: f64,
+    option_type: TypeFlag,
+    n: usize,
+) -> Result<AmericanImpliedVolatility, RustQuantError> {
+    Validator::new()
+        .finite("price", price)
+        .positive("S", S)
+        .positive("K", K)
+        .positive("T", T)
+        .finite("r", r)
+        .finite("q", q)
+        .check(n >= 3, || format!("n must be at least 3 (got {n})"))
+        .finish()?;
+
+    let (intrinsic, upper) = match option_type {
+        TypeFlag::Call => ((S - K).max(0.0), S),
+        TypeFlag::Put => ((K - S).max(0.0), K),
+    };
+
+    if price <= intrinsic || price >= upper {
+        return Err(RustQuantError::ArbitrageViolation(format!(
+            "American option price {price} outside no-arbitrage bounds ({intrinsic}, {upper})"
+        )));
+    }
+
+    let american = |v: f64| {
+        BinomialOption::new(S, K, T, r, q, v)
+            .price_CoxRossRubinstein("p", ExerciseFlag::American, option_type, n)
+            .map_or(f64::NAN, |value| value - price)
+    };
+
+    let data = RootfinderData::new(1e-10, 0.05, 1e-4, 5.0, true);
+    let v = Brent::new(american, 0.25, data).solve()?;
+
+    let european_price = generalised_black_scholes_merton(S, K, v, r, r - q, T, option_type);
+
+    Ok(AmericanImpliedVolatility {
+        implied_volatility: v,
+        european_price,
+        early_exercise_premium: price - european_price,
+    })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_american_implied_volatility {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::try_implied_volatility;
+
+    const N: usize = 200;
+
+    #[test]
+    fn test_american_put_implied_volatility() {
+        let price = BinomialOption::new(100.0, 110.0, 1.0, 0.05, 0.0, 0.3)
+            .price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Put, N)
+            .unwrap();
+        let result =
+            american_implied_volatility(price, 100.0, 110.0, 1.0, 0.05, 0.0, TypeFlag::Put, N)
+                .unwrap();
+
+        assert_approx_equal!(price, 15.625_332_040_296_914, 1e-8);
+        assert_approx_equal!(result.implied_volatility, 0.3, 1e-8);
+        assert_approx_equal!(result.european_price, 14.655_314_315_134_518, 1e-6);
+        assert_approx_equal!(result.early_exercise_premium, 0.970_017_725_162_396, 1e-6);
+
+        // Inverting the American price as if it were European overstates
+        // the volatility.
+        let european_iv = try_implied_volatility(price, 100.0, 110.0, 1.0, 0.05, TypeFlag::Put);
+        assert!(european_iv.unwrap() > 0.3);
+    }
+
+    #[test]
+    fn test_american_call_without_dividends_is_european() {
+        let price =
+            generalised_black_scholes_merton(100.0, 95.0, 0.2, 0.03, 0.03, 0.5, TypeFlag::Call);
+        let result =
+            american_implied_volatility(price, 100.0, 95.0, 0.5, 0.03, 0.0, TypeFlag::Call, N)
+                .unwrap();
+
+        // Only the tree's discretisation error separates the two.
+        assert_approx_equal!(result.implied_volatility, 0.2, 1e-3);
+        assert_approx_equal!(result.early_exercise_premium, 0.0, 1e-2);
+    }
+
+    #[test]
+    fn test_american_implied_volatility_bounds() {
+        let solve = |price| {
+            american_implied_volatility(price, 100.0, 110.0, 1.0, 0.05, 0.0, TypeFlag::Put, N)
+        };
+
+        // At or below the intrinsic value of 10.
+        assert!(matches!(
+            solve(10.0),
+            Err(RustQuantError::ArbitrageViolation(_))
+        ));
+        // At or above the strike.
+        assert!(matches!(
+            solve(110.0),
+            Err(RustQuantError::ArbitrageViolation(_))
+        ));
+        assert!(
+            american_implied_volatility(12.0, 100.0, 110.0, 0.0, 0.05, 0.0, TypeFlag::Put, N)
+                .is_err()
+        );
+    }
+}
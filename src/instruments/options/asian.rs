@@ -8,6 +8,8 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::{
+    error::RustQuantError,
+    instruments::{options::TypeFlag, Instrument, PricingContext},
     math::distributions::{gaussian::Gaussian, Distribution},
     time::{today, DayCountConvention},
 };
@@ -20,6 +22,7 @@ use time::Date;
 /// Type of Asian option (fixed or floating strike).
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AsianStrike {
     /// Floating strike Asian option.
     /// Payoffs:
@@ -35,6 +38,7 @@ pub enum AsianStrike {
 
 /// Method of averaging (arithmetic or geometric, and continuous or discrete).
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AveragingMethod {
     /// Arithmetic Asian option with discrete averaging.
     ArithmeticDiscrete,
@@ -49,6 +53,7 @@ pub enum AveragingMethod {
 /// Asian Option struct.
 #[allow(clippy::module_name_repetitions)]
 #[derive(derive_builder::Builder, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsianOption {
     /// `S` - Initial price of the underlying.
     pub initial_price: f64,
@@ -73,6 +78,51 @@ pub struct AsianOption {
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl Instrument for (AsianOption, TypeFlag) {
+    /// Returns the price (net present value) of the option in the given
+    /// direction, with continuous geometric averaging.
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = self.0.evaluation_date.unwrap_or(ctx.valuation_date);
+        let expiry = self.0.expiration_date;
+
+        let option = AsianOption {
+            risk_free_rate: ctx
+                .risk_free_rate(None, start, expiry)
+                .unwrap_or(self.0.risk_free_rate),
+            volatility: ctx
+                .volatility(self.0.strike_price, expiry)
+                .unwrap_or(self.0.volatility),
+            evaluation_date: Some(start),
+            ..self.0
+        };
+
+        Ok(match self.1 {
+            TypeFlag::Call => option.price_geometric_average().0,
+            TypeFlag::Put => option.price_geometric_average().1,
+        })
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        self.0.evaluation_date.unwrap_or(today())
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Asian Option"
+    }
+}
+
 impl AsianOption {
     /// New Asian Option
     #[must_use]
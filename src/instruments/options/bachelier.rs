@@ -11,7 +11,10 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::error::RustQuantError;
+use crate::instruments::options::closed_form::bachelier_price;
 use crate::instruments::options::TypeFlag;
+use crate::instruments::{Instrument, PricingContext, Validate, Validator};
 use crate::math::distributions::{Distribution, Gaussian};
 use crate::time::{today, DayCountConvention};
 use time::Date;
@@ -21,6 +24,8 @@ use time::Date;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Bachelier European Option pricing model.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bachelier {
     /// The underlying asset price.
     pub underlying_price: f64,
@@ -41,6 +46,7 @@ pub struct Bachelier {
 /// Bachelier European Option pricing model.
 #[allow(clippy::module_name_repetitions)]
 #[derive(derive_builder::Builder, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ModifiedBachelier {
     /// The underlying asset price.
     pub underlying_price: f64,
@@ -67,6 +73,76 @@ pub struct ModifiedBachelier {
 // IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl Instrument for Bachelier {
+    /// Returns the price (net present value) of the instrument.
+    ///
+    /// The model has no rate, and its (normal) volatility is not read off
+    /// the context's (lognormal) volatility surface.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let option = Self {
+            evaluation_date: self.evaluation_date.or(Some(ctx.valuation_date)),
+            ..*self
+        };
+        option.validate()?;
+
+        Ok(option.price())
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        self.evaluation_date.unwrap_or(today())
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Bachelier European Option"
+    }
+}
+
+impl Instrument for ModifiedBachelier {
+    /// Returns the price (net present value) of the instrument.
+    ///
+    /// The risk-free rate is read off the context (see
+    /// [`PricingContext::risk_free_rate`]), keeping the option's own if the
+    /// context has none. The (normal) volatility is the option's own.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = self.evaluation_date.unwrap_or(ctx.valuation_date);
+
+        let option = Self {
+            risk_free_rate: ctx
+                .risk_free_rate(None, start, self.expiration_date)
+                .unwrap_or(self.risk_free_rate),
+            evaluation_date: Some(start),
+            ..*self
+        };
+        option.validate()?;
+
+        Ok(option.price())
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        self.evaluation_date.unwrap_or(today())
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Modified Bachelier European Option"
+    }
+}
+
 impl Bachelier {
     /// New Bachelier European Option
     #[must_use]
@@ -89,26 +165,23 @@ impl Bachelier {
     }
 
     /// Bachelier European Option price.
+    ///
+    /// See [`bachelier_price`] for a version generic over the scalar type.
     #[must_use]
     pub fn price(&self) -> f64 {
-        let S = self.underlying_price;
-        let K = self.strike_price;
-        let v = self.volatility;
-
         // Compute time to maturity.
         let T = DayCountConvention::default().day_count_factor(
             self.evaluation_date.unwrap_or(today()),
             self.expiration_date,
         );
 
-        let d1 = (S - K) / (v * T.sqrt());
-
-        let n = Gaussian::default();
-
-        match self.option_type {
-            TypeFlag::Call => (S - K) * n.cdf(d1) + v * T.sqrt() * n.pdf(d1),
-            TypeFlag::Put => (K - S) * n.cdf(-d1) + v * T.sqrt() * n.pdf(-d1),
-        }
+        bachelier_price(
+            self.underlying_price,
+            self.strike_price,
+            self.volatility,
+            T,
+            self.option_type,
+        )
     }
 }
 
@@ -163,6 +236,42 @@ impl ModifiedBachelier {
     }
 }
 
+// The Bachelier model allows negative prices, so only the volatility and
+// time to expiry are bounded.
+impl Validate for Bachelier {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let T = DayCountConvention::default().day_count_factor(
+            self.evaluation_date.unwrap_or(today()),
+            self.expiration_date,
+        );
+
+        Validator::new()
+            .finite("underlying_price", self.underlying_price)
+            .finite("strike_price", self.strike_price)
+            .positive("volatility", self.volatility)
+            .positive("time_to_expiry", T)
+            .finish()
+    }
+}
+
+impl Validate for ModifiedBachelier {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let T = DayCountConvention::default().day_count_factor(
+            self.evaluation_date.unwrap_or(today()),
+            self.expiration_date,
+        );
+
+        Validator::new()
+            .finite("underlying_price", self.underlying_price)
+            .finite("strike_price", self.strike_price)
+            .positive("volatility", self.volatility)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("dividend_yield", self.dividend_yield)
+            .positive("time_to_expiry", T)
+            .finish()
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -200,4 +309,26 @@ mod tests_bachelier {
         );
         assert_approx_equal!(bachelier.price(), 2.513031723793472, 1e-2);
     }
+
+    #[test]
+    fn bachelier_validate() {
+        let t0 = time::macros::date!(2024 - 01 - 01);
+
+        // Negative prices are admissible in the Bachelier model.
+        let bachelier = Bachelier::new(
+            -0.5,
+            0.25,
+            1.0,
+            Some(t0),
+            t0 + Duration::days(365),
+            TypeFlag::Call,
+        );
+        assert!(bachelier.validate().is_ok());
+
+        let zero_vol = Bachelier {
+            volatility: 0.0,
+            ..bachelier
+        };
+        assert!(zero_vol.validate().is_err());
+    }
 }
@@ -7,7 +7,16 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::math::distributions::{gaussian::Gaussian, Distribution};
+use crate::error::RustQuantError;
+use crate::instruments::validation::{Validate, Validator};
+#[cfg(feature = "options")]
+use crate::instruments::{Instrument, PricingContext};
+use crate::math::Real;
+#[cfg(feature = "options")]
+use crate::time::today;
+use alloc::format;
+#[cfg(feature = "options")]
+use time::Date;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // BARRIER OPTION STRUCT
@@ -16,6 +25,7 @@ use crate::math::distributions::{gaussian::Gaussian, Distribution};
 /// Barrier Option struct for parameters and pricing methods.
 #[derive(Debug, Clone, Copy)]
 #[allow(clippy::module_name_repetitions)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BarrierOption {
     /// * `S` - Initial underlying price.
     pub initial_price: f64,
@@ -38,6 +48,7 @@ pub struct BarrierOption {
 /// Barrier option type enum.
 #[derive(Debug, Clone, Copy)]
 #[allow(clippy::module_name_repetitions)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BarrierType {
     /// Call (up-and-in)
     /// Payoff: `max(S_T - X, 0) * I(max(S_t) > H)`
@@ -69,6 +80,48 @@ pub enum BarrierType {
 // BARRIER OPTION IMPLEMENTATION
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+#[cfg(feature = "options")]
+impl Instrument for (BarrierOption, BarrierType) {
+    /// Returns the price (net present value) of the option with the given
+    /// barrier type (see [`BarrierOption::price`]).
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The time to expiry is counted from the context's valuation date.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = ctx.valuation_date;
+        let expiry = ctx.date_after(self.0.time_to_expiry);
+
+        BarrierOption {
+            risk_free_rate: ctx
+                .risk_free_rate(None, start, expiry)
+                .unwrap_or(self.0.risk_free_rate),
+            volatility: ctx
+                .volatility(self.0.strike_price, expiry)
+                .unwrap_or(self.0.volatility),
+            ..self.0
+        }
+        .price(self.1)
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        today()
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Barrier Option"
+    }
+}
+
 impl BarrierOption {
     /// Closed-form solution for path-dependent barrier options.
     ///
@@ -78,10 +131,31 @@ impl BarrierOption {
     ///
     /// * `type_flag` - One of: `cui`, `cuo`, `pui`, `puo`, `cdi`, `cdo`, `pdi`, `pdo`.
     ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the barrier has already been touched
+    ///   for the given type flag (e.g. a down barrier above spot).
+    ///
     /// # Note:
     /// * `b = r - q` - The cost of carry.
+    pub fn price(&self, type_flag: BarrierType) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        self.price_impl(type_flag)
+            .ok_or_else(|| Self::barrier_touched(type_flag))
+    }
+
+    /// Closed-form price without validating the parameters.
+    ///
+    /// Returns `NaN` if the barrier has already been touched, and propagates
+    /// `NaN`s from invalid inputs. Intended for hot loops over parameters
+    /// that are known to be valid; prefer [`BarrierOption::price`] otherwise.
     #[must_use]
-    pub fn price(&self, type_flag: BarrierType) -> f64 {
+    pub fn price_unchecked(&self, type_flag: BarrierType) -> f64 {
+        self.price_impl(type_flag).unwrap_or(f64::NAN)
+    }
+
+    fn price_impl(&self, type_flag: BarrierType) -> Option<f64> {
         let S = self.initial_price;
         let X = self.strike_price;
         let H = self.barrier;
@@ -104,61 +178,67 @@ impl BarrierOption {
         let y1: f64 = (H * H / (S * X)).ln() / (v * t.sqrt()) + (1. + mu) * v * t.sqrt();
         let y2: f64 = (H / S).ln() / (v * t.sqrt()) + (1. + mu) * v * t.sqrt();
 
-        let norm = Gaussian::default();
-
         // Common functions:
         let A = |phi: f64| -> f64 {
-            let term1: f64 = phi * S * ((b - r) * t).exp() * norm.cdf(phi * x1);
-            let term2: f64 = phi * X * (-r * t).exp() * norm.cdf(phi * x1 - phi * v * (t).sqrt());
+            let term1: f64 = phi * S * ((b - r) * t).exp() * (phi * x1).norm_cdf();
+            let term2: f64 =
+                phi * X * (-r * t).exp() * (phi * x1 - phi * v * (t).sqrt()).norm_cdf();
             term1 - term2
         };
 
         let B = |phi: f64| -> f64 {
-            let term1: f64 = phi * S * ((b - r) * t).exp() * norm.cdf(phi * x2);
-            let term2: f64 = phi * X * (-r * t).exp() * norm.cdf(phi * x2 - phi * v * (t).sqrt());
+            let term1: f64 = phi * S * ((b - r) * t).exp() * (phi * x2).norm_cdf();
+            let term2: f64 =
+                phi * X * (-r * t).exp() * (phi * x2 - phi * v * (t).sqrt()).norm_cdf();
             term1 - term2
         };
 
         let C = |phi: f64, eta: f64| -> f64 {
-            let term1: f64 =
-                phi * S * ((b - r) * t).exp() * (H / S).powf(2. * (mu + 1.)) * norm.cdf(eta * y1);
+            let term1: f64 = phi
+                * S
+                * ((b - r) * t).exp()
+                * (H / S).powf(2. * (mu + 1.))
+                * (eta * y1).norm_cdf();
             let term2: f64 = phi
                 * X
                 * (-r * t).exp()
                 * (H / S).powf(2. * mu)
-                * norm.cdf(eta * y1 - eta * v * t.sqrt());
+                * (eta * y1 - eta * v * t.sqrt()).norm_cdf();
             term1 - term2
         };
 
         let D = |phi: f64, eta: f64| -> f64 {
-            let term1: f64 =
-                phi * S * ((b - r) * t).exp() * (H / S).powf(2. * (mu + 1.)) * norm.cdf(eta * y2);
+            let term1: f64 = phi
+                * S
+                * ((b - r) * t).exp()
+                * (H / S).powf(2. * (mu + 1.))
+                * (eta * y2).norm_cdf();
             let term2: f64 = phi
                 * X
                 * (-r * t).exp()
                 * (H / S).powf(2. * mu)
-                * norm.cdf(eta * y2 - eta * v * (t).sqrt());
+                * (eta * y2 - eta * v * (t).sqrt()).norm_cdf();
 
             term1 - term2
         };
 
         let E = |eta: f64| -> f64 {
-            let term1: f64 = norm.cdf(eta * x2 - eta * v * (t).sqrt());
-            let term2: f64 = (H / S).powf(2. * mu) * norm.cdf(eta * y2 - eta * v * t.sqrt());
+            let term1: f64 = (eta * x2 - eta * v * (t).sqrt()).norm_cdf();
+            let term2: f64 = (H / S).powf(2. * mu) * (eta * y2 - eta * v * t.sqrt()).norm_cdf();
 
             K * (-r * t).exp() * (term1 - term2)
         };
 
         let F = |eta: f64| -> f64 {
-            let term1: f64 = (H / S).powf(mu + lambda) * norm.cdf(eta * z);
+            let term1: f64 = (H / S).powf(mu + lambda) * (eta * z).norm_cdf();
             let term2: f64 =
-                (H / S).powf(mu - lambda) * norm.cdf(eta * z - 2. * eta * lambda * v * t.sqrt());
+                (H / S).powf(mu - lambda) * (eta * z - 2. * eta * lambda * v * t.sqrt()).norm_cdf();
 
             K * (term1 + term2)
         };
 
         // Strike above barrier (X >= H):
-        if X >= H {
+        let price = if X >= H {
             match type_flag {
                 // Knock-In calls:
                 BarrierType::CDI if S >= H => C(1., 1.) + E(1.),
@@ -173,7 +253,7 @@ impl BarrierOption {
                 BarrierType::PDO if S >= H => A(-1.) - B(-1.) + C(-1., 1.) - D(-1., 1.) + F(1.),
                 BarrierType::PUO if S <= H => B(-1.) - D(-1., -1.) + F(-1.),
 
-                _ => panic!("Barrier touched - check barrier and type flag."),
+                _ => return None,
             }
         }
         // Strike below barrier (X < H):
@@ -192,9 +272,33 @@ impl BarrierOption {
                 BarrierType::PDO if S >= H => F(1.),
                 BarrierType::PUO if S <= H => A(-1.) - C(-1., -1.) + F(-1.),
 
-                _ => panic!("Barrier touched - check barrier and type flag."),
+                _ => return None,
             }
-        }
+        };
+
+        Some(price)
+    }
+
+    fn barrier_touched(type_flag: BarrierType) -> RustQuantError {
+        RustQuantError::InvalidArgument(format!(
+            "Barrier touched - check barrier and type flag ({:?}).",
+            type_flag
+        ))
+    }
+}
+
+impl Validate for BarrierOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("barrier", self.barrier)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .positive("volatility", self.volatility)
+            .non_negative("rebate", self.rebate)
+            .finite("dividend_yield", self.dividend_yield)
+            .finish()
     }
 }
 
@@ -232,10 +336,10 @@ mod tests {
     #[allow(clippy::similar_names)]
     #[test]
     fn test_S_above_H() {
-        let cdi = S_ABOVE_H.price(BarrierType::CDI);
-        let cdo = S_ABOVE_H.price(BarrierType::CDO);
-        let pdi = S_ABOVE_H.price(BarrierType::PDI);
-        let pdo = S_ABOVE_H.price(BarrierType::PDO);
+        let cdi = S_ABOVE_H.price(BarrierType::CDI).unwrap();
+        let cdo = S_ABOVE_H.price(BarrierType::CDO).unwrap();
+        let pdi = S_ABOVE_H.price(BarrierType::PDI).unwrap();
+        let pdo = S_ABOVE_H.price(BarrierType::PDO).unwrap();
 
         assert_approx_equal!(cdi, 9.504_815_211_050_698, RUSTQUANT_EPSILON);
         assert_approx_equal!(cdo, 7.295_021_649_666_765, RUSTQUANT_EPSILON);
@@ -244,24 +348,20 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn cui_panic() {
-        let _ = S_ABOVE_H.price(BarrierType::CUI);
+    fn cui_barrier_touched() {
+        assert!(S_ABOVE_H.price(BarrierType::CUI).is_err());
     }
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn cuo_panic() {
-        let _ = S_ABOVE_H.price(BarrierType::CUO);
+    fn cuo_barrier_touched() {
+        assert!(S_ABOVE_H.price(BarrierType::CUO).is_err());
     }
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn pui_panic() {
-        let _ = S_ABOVE_H.price(BarrierType::PUI);
+    fn pui_barrier_touched() {
+        assert!(S_ABOVE_H.price(BarrierType::PUI).is_err());
     }
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn puo_panic() {
-        let _ = S_ABOVE_H.price(BarrierType::PUO);
+    fn puo_barrier_touched() {
+        assert!(S_ABOVE_H.price(BarrierType::PUO).is_err());
     }
 
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -286,10 +386,10 @@ mod tests {
     #[allow(clippy::similar_names)]
     #[test]
     fn test_S_below_H() {
-        let cui = S_BELOW_H.price(BarrierType::CUI);
-        let cuo = S_BELOW_H.price(BarrierType::CUO);
-        let pui = S_BELOW_H.price(BarrierType::PUI);
-        let puo = S_BELOW_H.price(BarrierType::PUO);
+        let cui = S_BELOW_H.price(BarrierType::CUI).unwrap();
+        let cuo = S_BELOW_H.price(BarrierType::CUO).unwrap();
+        let pui = S_BELOW_H.price(BarrierType::PUI).unwrap();
+        let puo = S_BELOW_H.price(BarrierType::PUO).unwrap();
 
         assert_approx_equal!(cui, 4.692_603_355_387_815, RUSTQUANT_EPSILON);
         assert_approx_equal!(cuo, 0.022_448_676_101_445_74, RUSTQUANT_EPSILON);
@@ -298,23 +398,44 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn cdi_panic() {
-        let _ = S_BELOW_H.price(BarrierType::CDI);
+    fn cdi_barrier_touched() {
+        assert!(S_BELOW_H.price(BarrierType::CDI).is_err());
+    }
+    #[test]
+    fn cdo_barrier_touched() {
+        assert!(S_BELOW_H.price(BarrierType::CDO).is_err());
     }
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn cdo_panic() {
-        let _ = S_BELOW_H.price(BarrierType::CDO);
+    fn pdi_barrier_touched() {
+        assert!(S_BELOW_H.price(BarrierType::PDI).is_err());
     }
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn pdi_panic() {
-        let _ = S_BELOW_H.price(BarrierType::PDI);
+    fn pdo_barrier_touched() {
+        assert!(S_BELOW_H.price(BarrierType::PDO).is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let option = BarrierOption {
+            volatility: -0.2,
+            time_to_expiry: f64::NAN,
+            ..S_ABOVE_H
+        };
+
+        match option.price(BarrierType::CDO) {
+            Err(RustQuantError::InvalidArgument(message)) => {
+                assert!(message.contains("volatility must be positive (got -0.2)"));
+                assert!(message.contains("time_to_expiry must be finite (got NaN)"));
+            }
+            other => panic!("expected InvalidArgument, got {other:?}"),
+        }
     }
+
     #[test]
-    #[should_panic(expected = "Barrier touched - check barrier and type flag.")]
-    fn pdo_panic() {
-        let _ = S_BELOW_H.price(BarrierType::PDO);
+    fn test_price_unchecked() {
+        let checked = S_ABOVE_H.price(BarrierType::CDO).unwrap();
+
+        assert_eq!(S_ABOVE_H.price_unchecked(BarrierType::CDO), checked);
+        assert!(S_ABOVE_H.price_unchecked(BarrierType::CUO).is_nan());
     }
 }
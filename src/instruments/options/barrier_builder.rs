@@ -0,0 +1,336 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Fluent builder for [`BarrierOption`].
+//!
+//! The builder tracks which required parameters have been set in its type,
+//! so forgetting one (or setting one twice) is a compile error rather than a
+//! transposed argument or a runtime failure. The rebate and dividend yield
+//! default to zero.
+//!
+//! ```
+//! use RustQuant::instruments::options::*;
+//!
+//! let option = BarrierOption::builder()
+//!     .spot(110.0)
+//!     .strike(100.0)
+//!     .barrier(105.0)
+//!     .time_to_expiry(1.0)
+//!     .risk_free_rate(0.05)
+//!     .volatility(0.2)
+//!     .dividend_yield(0.01)
+//!     .build()?;
+//!
+//! assert_eq!(option.rebate, 0.0);
+//! assert!(option.price(BarrierType::CDO)? > 0.0);
+//! # Ok::<(), RustQuant::error::RustQuantError>(())
+//! ```
+//!
+//! Omitting a required parameter does not compile:
+//!
+//! ```compile_fail
+//! use RustQuant::instruments::options::*;
+//!
+//! let option = BarrierOption::builder()
+//!     .spot(110.0)
+//!     .strike(100.0)
+//!     .time_to_expiry(1.0)
+//!     .risk_free_rate(0.05)
+//!     .volatility(0.2)
+//!     .build(); // no barrier
+//! ```
+//!
+//! Values are checked with [`Validate`] when the option is built.
+
+use crate::error::RustQuantError;
+use crate::instruments::options::barrier::BarrierOption;
+use crate::instruments::Validate;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Marker for a required builder parameter that has not been set yet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Missing;
+
+/// Marker for a required builder parameter that has been set.
+#[derive(Debug, Clone, Copy)]
+pub struct Provided(f64);
+
+/// Builder for [`BarrierOption`], see [`BarrierOption::builder`].
+///
+/// The type parameters record, in order, whether the spot, strike, barrier,
+/// time to expiry, risk-free rate, and volatility have been provided.
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::module_name_repetitions)]
+pub struct BarrierOptionBuilder<
+    S = Missing,
+    X = Missing,
+    H = Missing,
+    T = Missing,
+    R = Missing,
+    V = Missing,
+> {
+    spot: S,
+    strike: X,
+    barrier: H,
+    time_to_expiry: T,
+    risk_free_rate: R,
+    volatility: V,
+    rebate: f64,
+    dividend_yield: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BarrierOption {
+    /// Start building a barrier option.
+    #[must_use]
+    pub fn builder() -> BarrierOptionBuilder {
+        BarrierOptionBuilder::default()
+    }
+}
+
+impl Default for BarrierOptionBuilder {
+    fn default() -> Self {
+        Self {
+            spot: Missing,
+            strike: Missing,
+            barrier: Missing,
+            time_to_expiry: Missing,
+            risk_free_rate: Missing,
+            volatility: Missing,
+            rebate: 0.0,
+            dividend_yield: 0.0,
+        }
+    }
+}
+
+impl<X, H, T, R, V> BarrierOptionBuilder<Missing, X, H, T, R, V> {
+    /// Initial underlying price `S`.
+    #[must_use]
+    pub fn spot(self, spot: f64) -> BarrierOptionBuilder<Provided, X, H, T, R, V> {
+        BarrierOptionBuilder {
+            spot: Provided(spot),
+            strike: self.strike,
+            barrier: self.barrier,
+            time_to_expiry: self.time_to_expiry,
+            risk_free_rate: self.risk_free_rate,
+            volatility: self.volatility,
+            rebate: self.rebate,
+            dividend_yield: self.dividend_yield,
+        }
+    }
+}
+
+impl<S, H, T, R, V> BarrierOptionBuilder<S, Missing, H, T, R, V> {
+    /// Strike price `X`.
+    #[must_use]
+    pub fn strike(self, strike: f64) -> BarrierOptionBuilder<S, Provided, H, T, R, V> {
+        BarrierOptionBuilder {
+            spot: self.spot,
+            strike: Provided(strike),
+            barrier: self.barrier,
+            time_to_expiry: self.time_to_expiry,
+            risk_free_rate: self.risk_free_rate,
+            volatility: self.volatility,
+            rebate: self.rebate,
+            dividend_yield: self.dividend_yield,
+        }
+    }
+}
+
+impl<S, X, T, R, V> BarrierOptionBuilder<S, X, Missing, T, R, V> {
+    /// Barrier level `H`.
+    #[must_use]
+    pub fn barrier(self, barrier: f64) -> BarrierOptionBuilder<S, X, Provided, T, R, V> {
+        BarrierOptionBuilder {
+            spot: self.spot,
+            strike: self.strike,
+            barrier: Provided(barrier),
+            time_to_expiry: self.time_to_expiry,
+            risk_free_rate: self.risk_free_rate,
+            volatility: self.volatility,
+            rebate: self.rebate,
+            dividend_yield: self.dividend_yield,
+        }
+    }
+}
+
+impl<S, X, H, R, V> BarrierOptionBuilder<S, X, H, Missing, R, V> {
+    /// Time to expiry `t`, in years.
+    #[must_use]
+    pub fn time_to_expiry(
+        self,
+        time_to_expiry: f64,
+    ) -> BarrierOptionBuilder<S, X, H, Provided, R, V> {
+        BarrierOptionBuilder {
+            spot: self.spot,
+            strike: self.strike,
+            barrier: self.barrier,
+            time_to_expiry: Provided(time_to_expiry),
+            risk_free_rate: self.risk_free_rate,
+            volatility: self.volatility,
+            rebate: self.rebate,
+            dividend_yield: self.dividend_yield,
+        }
+    }
+}
+
+impl<S, X, H, T, V> BarrierOptionBuilder<S, X, H, T, Missing, V> {
+    /// Risk-free rate `r`.
+    #[must_use]
+    pub fn risk_free_rate(
+        self,
+        risk_free_rate: f64,
+    ) -> BarrierOptionBuilder<S, X, H, T, Provided, V> {
+        BarrierOptionBuilder {
+            spot: self.spot,
+            strike: self.strike,
+            barrier: self.barrier,
+            time_to_expiry: self.time_to_expiry,
+            risk_free_rate: Provided(risk_free_rate),
+            volatility: self.volatility,
+            rebate: self.rebate,
+            dividend_yield: self.dividend_yield,
+        }
+    }
+}
+
+impl<S, X, H, T, R> BarrierOptionBuilder<S, X, H, T, R, Missing> {
+    /// Volatility `v`.
+    #[must_use]
+    pub fn volatility(self, volatility: f64) -> BarrierOptionBuilder<S, X, H, T, R, Provided> {
+        BarrierOptionBuilder {
+            spot: self.spot,
+            strike: self.strike,
+            barrier: self.barrier,
+            time_to_expiry: self.time_to_expiry,
+            risk_free_rate: self.risk_free_rate,
+            volatility: Provided(volatility),
+            rebate: self.rebate,
+            dividend_yield: self.dividend_yield,
+        }
+    }
+}
+
+impl<S, X, H, T, R, V> BarrierOptionBuilder<S, X, H, T, R, V> {
+    /// Rebate `K`, paid if the option cannot be exercised (default `0`).
+    #[must_use]
+    pub fn rebate(mut self, rebate: f64) -> Self {
+        self.rebate = rebate;
+        self
+    }
+
+    /// Continuous dividend yield `q` (default `0`).
+    #[must_use]
+    pub fn dividend_yield(mut self, dividend_yield: f64) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+}
+
+impl BarrierOptionBuilder<Provided, Provided, Provided, Provided, Provided, Provided> {
+    /// Build the option.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::InvalidArgument`] if the parameters fail
+    /// [`Validate::validate`].
+    pub fn build(self) -> Result<BarrierOption, RustQuantError> {
+        BarrierOption {
+            initial_price: self.spot.0,
+            strike_price: self.strike.0,
+            barrier: self.barrier.0,
+            time_to_expiry: self.time_to_expiry.0,
+            risk_free_rate: self.risk_free_rate.0,
+            volatility: self.volatility.0,
+            rebate: self.rebate,
+            dividend_yield: self.dividend_yield,
+        }
+        .validated()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_barrier_builder {
+    use super::*;
+    use crate::instruments::options::BarrierType;
+
+    #[test]
+    fn test_builder_matches_struct_literal() {
+        let literal = BarrierOption {
+            initial_price: 110.0,
+            strike_price: 100.0,
+            barrier: 105.0,
+            time_to_expiry: 1.0,
+            risk_free_rate: 0.05,
+            volatility: 0.2,
+            rebate: 1.0,
+            dividend_yield: 0.01,
+        };
+
+        // Parameters may be given in any order.
+        let built = BarrierOption::builder()
+            .volatility(0.2)
+            .rebate(1.0)
+            .barrier(105.0)
+            .spot(110.0)
+            .risk_free_rate(0.05)
+            .strike(100.0)
+            .dividend_yield(0.01)
+            .time_to_expiry(1.0)
+            .build()
+            .unwrap();
+
+        for barrier_type in [BarrierType::CDI, BarrierType::CDO, BarrierType::PDO] {
+            assert_eq!(
+                built.price(barrier_type).unwrap(),
+                literal.price(barrier_type).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_defaults() {
+        let option = BarrierOption::builder()
+            .spot(100.0)
+            .strike(100.0)
+            .barrier(90.0)
+            .time_to_expiry(0.5)
+            .risk_free_rate(0.03)
+            .volatility(0.25)
+            .build()
+            .unwrap();
+
+        assert_eq!(option.rebate, 0.0);
+        assert_eq!(option.dividend_yield, 0.0);
+    }
+
+    #[test]
+    fn test_builder_validates() {
+        let result = BarrierOption::builder()
+            .spot(100.0)
+            .strike(100.0)
+            .barrier(90.0)
+            .time_to_expiry(0.5)
+            .risk_free_rate(0.03)
+            .volatility(-0.25)
+            .build();
+
+        assert!(matches!(result, Err(RustQuantError::InvalidArgument(_))));
+    }
+}
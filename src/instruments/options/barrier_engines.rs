@@ -0,0 +1,820 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pricing engines for barrier options.
+//!
+//! The engines decouple the barrier contract ([`BarrierOption`] and
+//! [`BarrierType`]) from the numerical method used to value it,
+//! so the same option can be priced analytically, on a PDE grid,
+//! or by Monte Carlo (under Black-Scholes or Heston dynamics) simply by
+//! swapping the engine:
+//!
+//! ```
+//! use RustQuant::instruments::options::*;
+//!
+//! let option = BarrierOption {
+//!     initial_price: 110.0,
+//!     strike_price: 100.0,
+//!     barrier: 105.0,
+//!     time_to_expiry: 1.0,
+//!     risk_free_rate: 0.05,
+//!     volatility: 0.2,
+//!     rebate: 0.0,
+//!     dividend_yield: 0.01,
+//! };
+//!
+//! let analytic = option.price_with(BarrierType::CDO, &AnalyticBarrierEngine)?;
+//! let pde = option.price_with(BarrierType::CDO, &FiniteDifferenceBarrierEngine::default())?;
+//!
+//! assert!((analytic.price - pde.price).abs() < 0.01);
+//! # Ok::<(), RustQuant::error::RustQuantError>(())
+//! ```
+
+use crate::error::RustQuantError;
+use crate::instruments::options::barrier::{BarrierOption, BarrierType};
+use crate::instruments::{Price, Validate, Validator};
+use crate::macros::{trace_event, trace_span};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+use std::time::Instant;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Pricing engine for barrier options.
+pub trait BarrierEngine {
+    /// Price the barrier option with the given barrier type.
+    ///
+    /// Returns an error if the barrier has already been touched.
+    fn calculate(
+        &self,
+        option: &BarrierOption,
+        barrier_type: BarrierType,
+    ) -> Result<Price, RustQuantError>;
+}
+
+/// Closed-form engine (Haug), see [`BarrierOption::price`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnalyticBarrierEngine;
+
+/// Crank-Nicolson finite difference engine, on a grid in log-price.
+///
+/// Knock-out options are solved with the rebate as a Dirichlet condition at
+/// the barrier. Knock-in options are priced via in-out parity.
+/// The first two time steps are fully implicit (Rannacher smoothing) to damp
+/// oscillations from the non-smooth payoff.
+#[derive(Debug, Clone, Copy)]
+pub struct FiniteDifferenceBarrierEngine {
+    /// Number of time steps.
+    pub time_steps: usize,
+    /// Number of log-price steps.
+    pub price_steps: usize,
+}
+
+/// Monte Carlo engine, simulating exact log-normal steps.
+///
+/// The barrier is monitored continuously via a Brownian bridge correction:
+/// for each step, the probability that the path crossed the barrier
+/// between the two monitoring dates is used instead of checking the
+/// endpoints only.
+#[derive(Debug, Clone, Copy)]
+pub struct MonteCarloBarrierEngine {
+    /// Number of simulated paths.
+    pub n_paths: usize,
+    /// Number of time steps per path.
+    pub n_steps: usize,
+    /// Seed for the random number generator.
+    pub seed: u64,
+}
+
+/// Monte Carlo engine under Heston (1993) stochastic volatility.
+///
+/// The initial variance is the square of the option's volatility, and the
+/// variance follows
+///
+/// $$
+/// dv_t = \kappa (\theta - v_t) dt + \sigma \sqrt{v_t} dW^v_t,
+/// \qquad d\langle W^S, W^v \rangle_t = \rho \, dt
+/// $$
+///
+/// discretised with full-truncation Euler steps. Within a step the
+/// log-price is treated as a Brownian bridge with the step's (truncated)
+/// variance, so, as in [`MonteCarloBarrierEngine`], the barrier is
+/// monitored continuously and knock-out prices do not carry the upward
+/// bias of checking the barrier at the time steps only.
+#[derive(Debug, Clone, Copy)]
+pub struct HestonMonteCarloBarrierEngine {
+    /// Mean reversion speed of the variance, `kappa`.
+    pub mean_reversion: f64,
+    /// Long-run variance, `theta`.
+    pub long_run_variance: f64,
+    /// Volatility of the variance, `sigma`.
+    pub vol_of_vol: f64,
+    /// Correlation between the price and variance, `rho`.
+    pub correlation: f64,
+    /// Number of simulated paths.
+    pub n_paths: usize,
+    /// Number of time steps per path.
+    pub n_steps: usize,
+    /// Seed for the random number generator.
+    pub seed: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BarrierOption {
+    /// Price the option with the given pricing engine.
+    pub fn price_with<E: BarrierEngine + ?Sized>(
+        &self,
+        barrier_type: BarrierType,
+        engine: &E,
+    ) -> Result<Price, RustQuantError> {
+        engine.calculate(self, barrier_type)
+    }
+}
+
+impl BarrierType {
+    /// Returns `true` for call options.
+    #[must_use]
+    pub fn is_call(&self) -> bool {
+        matches!(self, Self::CUI | Self::CDI | Self::CUO | Self::CDO)
+    }
+
+    /// Returns `true` for up barriers.
+    #[must_use]
+    pub fn is_up(&self) -> bool {
+        matches!(self, Self::CUI | Self::CUO | Self::PUI | Self::PUO)
+    }
+
+    /// Returns `true` for knock-in options.
+    #[must_use]
+    pub fn is_knock_in(&self) -> bool {
+        matches!(self, Self::CUI | Self::CDI | Self::PUI | Self::PDI)
+    }
+}
+
+/// Validates the option and checks the underlying has not already breached
+/// the barrier.
+fn check_barrier(option: &BarrierOption, barrier_type: BarrierType) -> Result<(), RustQuantError> {
+    option.validate()?;
+
+    let breached = if barrier_type.is_up() {
+        option.initial_price > option.barrier
+    } else {
+        option.initial_price < option.barrier
+    };
+
+    if breached {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "Barrier touched - check barrier and type flag ({:?}).",
+            barrier_type
+        )));
+    }
+
+    Ok(())
+}
+
+impl BarrierEngine for AnalyticBarrierEngine {
+    fn calculate(
+        &self,
+        option: &BarrierOption,
+        barrier_type: BarrierType,
+    ) -> Result<Price, RustQuantError> {
+        Ok(Price {
+            price: option.price(barrier_type)?,
+            error: None,
+        })
+    }
+}
+
+impl Default for FiniteDifferenceBarrierEngine {
+    fn default() -> Self {
+        Self {
+            time_steps: 500,
+            price_steps: 500,
+        }
+    }
+}
+
+impl FiniteDifferenceBarrierEngine {
+    /// Number of standard deviations the grid extends away from the barrier.
+    const STANDARD_DEVIATIONS: f64 = 6.0;
+
+    /// Solve the pricing PDE on `[x_min, x_max]` (in log-price) for the given
+    /// payoff.
+    ///
+    /// A boundary is either a barrier paying a fixed rebate (`Some(rebate)`),
+    /// or a far boundary (`None`), where the value is the discounted payoff
+    /// of the forward.
+    fn solve<P: Fn(f64) -> f64>(
+        &self,
+        option: &BarrierOption,
+        (x_min, x_max): (f64, f64),
+        payoff: P,
+        (lower, upper): (Option<f64>, Option<f64>),
+    ) -> f64 {
+        let n = self.price_steps.max(3);
+        let m = self.time_steps.max(1);
+
+        let r = option.risk_free_rate;
+        let v = option.volatility;
+        let b = r - option.dividend_yield;
+
+        let dx = (x_max - x_min) / n as f64;
+        let dt = option.time_to_expiry / m as f64;
+
+        let boundary = |rebate: Option<f64>, x: f64, tau: f64| match rebate {
+            Some(rebate) => rebate,
+            None => (-r * tau).exp() * payoff(x.exp() * (b * tau).exp()),
+        };
+
+        // Spatial operator coefficients.
+        let a = 0.5 * v * v;
+        let c = b - a;
+        let lo = a / (dx * dx) - c / (2.0 * dx);
+        let di = -2.0 * a / (dx * dx) - r;
+        let up = a / (dx * dx) + c / (2.0 * dx);
+
+        let mut values: Vec<f64> = (0..=n)
+            .map(|i| payoff((x_min + i as f64 * dx).exp()))
+            .collect();
+        values[0] = boundary(lower, x_min, 0.0);
+        values[n] = boundary(upper, x_max, 0.0);
+
+        let mut rhs = vec![0.0; n - 1];
+        let mut c_prime = vec![0.0; n - 1];
+
+        for step in 0..m {
+            let theta = if step < 2 { 1.0 } else { 0.5 };
+            let tau = (step + 1) as f64 * dt;
+
+            let bound_lo = boundary(lower, x_min, tau);
+            let bound_hi = boundary(upper, x_max, tau);
+
+            // Explicit part.
+            for i in 1..n {
+                let lv = lo * values[i - 1] + di * values[i] + up * values[i + 1];
+                rhs[i - 1] = values[i] + (1.0 - theta) * dt * lv;
+            }
+            rhs[0] += theta * dt * lo * bound_lo;
+            rhs[n - 2] += theta * dt * up * bound_hi;
+
+            // Implicit part (Thomas algorithm).
+            let sub = -theta * dt * lo;
+            let diag = 1.0 - theta * dt * di;
+            let sup = -theta * dt * up;
+
+            c_prime[0] = sup / diag;
+            rhs[0] /= diag;
+            for i in 1..(n - 1) {
+                let denom = diag - sub * c_prime[i - 1];
+                c_prime[i] = sup / denom;
+                rhs[i] = (rhs[i] - sub * rhs[i - 1]) / denom;
+            }
+            for i in (0..(n - 2)).rev() {
+                rhs[i] -= c_prime[i] * rhs[i + 1];
+            }
+
+            values[0] = bound_lo;
+            values[1..n].copy_from_slice(&rhs);
+            values[n] = bound_hi;
+        }
+
+        // Linear interpolation at the initial price.
+        let pos = ((option.initial_price.ln() - x_min) / dx).clamp(0.0, n as f64);
+        let i = (pos.floor() as usize).min(n - 1);
+        let w = pos - i as f64;
+
+        (1.0 - w) * values[i] + w * values[i + 1]
+    }
+
+    /// Price an option paying `payoff` at expiry, knocked out at the barrier
+    /// (paying `rebate` when hit). Without a barrier, this is a vanilla option.
+    fn knock_out<P: Fn(f64) -> f64>(
+        &self,
+        option: &BarrierOption,
+        barrier_type: Option<BarrierType>,
+        payoff: P,
+        rebate: f64,
+    ) -> f64 {
+        let width = Self::STANDARD_DEVIATIONS * option.volatility * option.time_to_expiry.sqrt();
+        let x_lo = option.initial_price.min(option.strike_price).ln() - width;
+        let x_hi = option.initial_price.max(option.strike_price).ln() + width;
+        let h = option.barrier.ln();
+
+        match barrier_type {
+            Some(barrier_type) if barrier_type.is_up() => {
+                self.solve(option, (x_lo, h), payoff, (None, Some(rebate)))
+            }
+            Some(_) => self.solve(option, (h, x_hi), payoff, (Some(rebate), None)),
+            None => self.solve(option, (x_lo, x_hi), payoff, (None, None)),
+        }
+    }
+}
+
+impl BarrierEngine for FiniteDifferenceBarrierEngine {
+    fn calculate(
+        &self,
+        option: &BarrierOption,
+        barrier_type: BarrierType,
+    ) -> Result<Price, RustQuantError> {
+        check_barrier(option, barrier_type)?;
+
+        let X = option.strike_price;
+        let payoff = |s: f64| match barrier_type.is_call() {
+            true => (s - X).max(0.0),
+            false => (X - s).max(0.0),
+        };
+
+        let price = if barrier_type.is_knock_in() {
+            // In-out parity: the knock-in pays the vanilla payoff if the barrier
+            // was hit, and the rebate at expiry otherwise.
+            let vanilla = self.knock_out(option, None, payoff, 0.0);
+            let knock_out = self.knock_out(option, Some(barrier_type), payoff, 0.0);
+            let no_touch = self.knock_out(option, Some(barrier_type), |_| 1.0, 0.0);
+
+            vanilla - knock_out + option.rebate * no_touch
+        } else {
+            self.knock_out(option, Some(barrier_type), payoff, option.rebate)
+        };
+
+        Ok(Price { price, error: None })
+    }
+}
+
+impl Default for MonteCarloBarrierEngine {
+    fn default() -> Self {
+        Self {
+            n_paths: 100_000,
+            n_steps: 100,
+            seed: 42,
+        }
+    }
+}
+
+/// Number of paths between `tracing` progress events.
+const TRACE_BATCH_SIZE: usize = 10_000;
+
+/// Probability that a Brownian bridge from `d0` to `d1` (the distances from
+/// the log-barrier at the start and end of a step) with total variance
+/// `variance` crosses the barrier.
+fn crossing_probability(barrier_type: BarrierType, d0: f64, d1: f64, variance: f64) -> f64 {
+    if (barrier_type.is_up() && d1 <= 0.0) || (!barrier_type.is_up() && d1 >= 0.0) {
+        1.0
+    } else if variance <= 0.0 {
+        0.0
+    } else {
+        (-2.0 * d0 * d1 / variance).exp()
+    }
+}
+
+/// Sample mean and its standard error after `n` paths.
+fn estimate(sum: f64, sum_sq: f64, n: usize) -> (f64, f64) {
+    let n = n as f64;
+    let mean = sum / n;
+    let variance = (sum_sq / n - mean * mean).max(0.0) * n / (n - 1.0);
+    (mean, (variance / n).sqrt())
+}
+
+impl BarrierEngine for MonteCarloBarrierEngine {
+    fn calculate(
+        &self,
+        option: &BarrierOption,
+        barrier_type: BarrierType,
+    ) -> Result<Price, RustQuantError> {
+        check_barrier(option, barrier_type)?;
+
+        let S = option.initial_price;
+        let X = option.strike_price;
+        let K = option.rebate;
+        let r = option.risk_free_rate;
+        let v = option.volatility;
+        let b = r - option.dividend_yield;
+
+        let n_paths = self.n_paths.max(2);
+        let n_steps = self.n_steps.max(1);
+        let dt = option.time_to_expiry / n_steps as f64;
+
+        let drift = (b - 0.5 * v * v) * dt;
+        let diffusion = v * dt.sqrt();
+        let log_barrier = option.barrier.ln();
+        let discount = (-r * option.time_to_expiry).exp();
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "monte_carlo_barrier",
+            paths = n_paths,
+            steps = n_steps,
+            seed = self.seed,
+        );
+
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+
+        for path in 1..=n_paths {
+            let mut x = S.ln();
+            let mut survival = 1.0;
+            let mut rebate = 0.0;
+
+            for step in 0..n_steps {
+                let z: f64 = StandardNormal.sample(&mut rng);
+                let x_next = x + drift + diffusion * z;
+
+                let crossed = crossing_probability(
+                    barrier_type,
+                    log_barrier - x,
+                    log_barrier - x_next,
+                    v * v * dt,
+                );
+
+                // Knock-out rebates are paid when the barrier is hit.
+                rebate += survival * crossed * (-r * (step + 1) as f64 * dt).exp();
+                survival *= 1.0 - crossed;
+                x = x_next;
+            }
+
+            let s_t = x.exp();
+            let vanilla = match barrier_type.is_call() {
+                true => (s_t - X).max(0.0),
+                false => (X - s_t).max(0.0),
+            };
+
+            let value = if barrier_type.is_knock_in() {
+                discount * ((1.0 - survival) * vanilla + survival * K)
+            } else {
+                discount * survival * vanilla + K * rebate
+            };
+
+            sum += value;
+            sum_sq += value * value;
+
+            if path % TRACE_BATCH_SIZE == 0 || path == n_paths {
+                trace_event!(
+                    DEBUG,
+                    "batch",
+                    paths = path,
+                    estimate = estimate(sum, sum_sq, path.max(2)).0,
+                    error = estimate(sum, sum_sq, path.max(2)).1,
+                    elapsed = start.elapsed().as_secs_f64(),
+                );
+            }
+        }
+
+        let (price, error) = estimate(sum, sum_sq, n_paths);
+
+        Ok(Price {
+            price,
+            error: Some(error),
+        })
+    }
+}
+
+impl Default for HestonMonteCarloBarrierEngine {
+    fn default() -> Self {
+        Self {
+            mean_reversion: 2.0,
+            long_run_variance: 0.04,
+            vol_of_vol: 0.3,
+            correlation: -0.7,
+            n_paths: 100_000,
+            n_steps: 100,
+            seed: 42,
+        }
+    }
+}
+
+impl Validate for HestonMonteCarloBarrierEngine {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("mean_reversion", self.mean_reversion)
+            .positive("long_run_variance", self.long_run_variance)
+            .non_negative("vol_of_vol", self.vol_of_vol)
+            .check((-1.0..=1.0).contains(&self.correlation), || {
+                format!("`correlation` must be in [-1, 1], got {}", self.correlation)
+            })
+            .finish()
+    }
+}
+
+impl BarrierEngine for HestonMonteCarloBarrierEngine {
+    fn calculate(
+        &self,
+        option: &BarrierOption,
+        barrier_type: BarrierType,
+    ) -> Result<Price, RustQuantError> {
+        check_barrier(option, barrier_type)?;
+        self.validate()?;
+
+        let X = option.strike_price;
+        let K = option.rebate;
+        let r = option.risk_free_rate;
+        let b = r - option.dividend_yield;
+
+        let (kappa, theta, sigma, rho) = (
+            self.mean_reversion,
+            self.long_run_variance,
+            self.vol_of_vol,
+            self.correlation,
+        );
+        let rho_bar = (1.0 - rho * rho).sqrt();
+
+        let n_paths = self.n_paths.max(2);
+        let n_steps = self.n_steps.max(1);
+        let dt = option.time_to_expiry / n_steps as f64;
+        let sqrt_dt = dt.sqrt();
+
+        let log_barrier = option.barrier.ln();
+        let discount = (-r * option.time_to_expiry).exp();
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "heston_monte_carlo_barrier",
+            paths = n_paths,
+            steps = n_steps,
+            seed = self.seed,
+        );
+
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+
+        for path in 1..=n_paths {
+            let mut x = option.initial_price.ln();
+            let mut variance = option.volatility * option.volatility;
+            let mut survival = 1.0;
+            let mut rebate = 0.0;
+
+            for step in 0..n_steps {
+                let z1: f64 = StandardNormal.sample(&mut rng);
+                let z2: f64 = StandardNormal.sample(&mut rng);
+
+                // Full truncation: the drift and diffusion use max(v, 0).
+                let v = variance.max(0.0);
+                let x_next = x + (b - 0.5 * v) * dt + (v * dt).sqrt() * z1;
+                variance += kappa * (theta - v) * dt
+                    + sigma * v.sqrt() * sqrt_dt * (rho * z1 + rho_bar * z2);
+
+                let crossed = crossing_probability(
+                    barrier_type,
+                    log_barrier - x,
+                    log_barrier - x_next,
+                    v * dt,
+                );
+
+                // Knock-out rebates are paid when the barrier is hit.
+                rebate += survival * crossed * (-r * (step + 1) as f64 * dt).exp();
+                survival *= 1.0 - crossed;
+                x = x_next;
+            }
+
+            let s_t = x.exp();
+            let vanilla = match barrier_type.is_call() {
+                true => (s_t - X).max(0.0),
+                false => (X - s_t).max(0.0),
+            };
+
+            let value = if barrier_type.is_knock_in() {
+                discount * ((1.0 - survival) * vanilla + survival * K)
+            } else {
+                discount * survival * vanilla + K * rebate
+            };
+
+            sum += value;
+            sum_sq += value * value;
+
+            if path % TRACE_BATCH_SIZE == 0 || path == n_paths {
+                trace_event!(
+                    DEBUG,
+                    "batch",
+                    paths = path,
+                    estimate = estimate(sum, sum_sq, path.max(2)).0,
+                    error = estimate(sum, sum_sq, path.max(2)).1,
+                    elapsed = start.elapsed().as_secs_f64(),
+                );
+            }
+        }
+
+        let (price, error) = estimate(sum, sum_sq, n_paths);
+
+        Ok(Price {
+            price,
+            error: Some(error),
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_barrier_engines {
+    use super::*;
+
+    const DOWN: BarrierOption = BarrierOption {
+        initial_price: 110.0,
+        strike_price: 100.0,
+        barrier: 105.0,
+        time_to_expiry: 1.0,
+        risk_free_rate: 0.05,
+        volatility: 0.2,
+        rebate: 3.0,
+        dividend_yield: 0.01,
+    };
+
+    const UP: BarrierOption = BarrierOption {
+        initial_price: 90.0,
+        strike_price: 100.0,
+        barrier: 105.0,
+        time_to_expiry: 1.0,
+        risk_free_rate: 0.05,
+        volatility: 0.2,
+        rebate: 3.0,
+        dividend_yield: 0.01,
+    };
+
+    const DOWN_TYPES: [BarrierType; 4] = [
+        BarrierType::CDI,
+        BarrierType::CDO,
+        BarrierType::PDI,
+        BarrierType::PDO,
+    ];
+
+    const UP_TYPES: [BarrierType; 4] = [
+        BarrierType::CUI,
+        BarrierType::CUO,
+        BarrierType::PUI,
+        BarrierType::PUO,
+    ];
+
+    #[test]
+    fn test_analytic_engine() {
+        for barrier_type in DOWN_TYPES {
+            let price = DOWN
+                .price_with(barrier_type, &AnalyticBarrierEngine)
+                .unwrap();
+            assert_eq!(price.price, DOWN.price(barrier_type).unwrap());
+            assert!(price.error.is_none());
+        }
+    }
+
+    #[test]
+    fn test_finite_difference_engine() {
+        let engine = FiniteDifferenceBarrierEngine::default();
+
+        for (option, types) in [(DOWN, DOWN_TYPES), (UP, UP_TYPES)] {
+            for barrier_type in types {
+                let analytic = option
+                    .price_with(barrier_type, &AnalyticBarrierEngine)
+                    .unwrap();
+                let pde = option.price_with(barrier_type, &engine).unwrap();
+
+                assert!(
+                    (analytic.price - pde.price).abs() < 0.02,
+                    "{barrier_type:?}: analytic = {}, pde = {}",
+                    analytic.price,
+                    pde.price
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_monte_carlo_engine() {
+        let engine = MonteCarloBarrierEngine {
+            n_paths: 20_000,
+            n_steps: 50,
+            seed: 1234,
+        };
+
+        for (option, types) in [(DOWN, DOWN_TYPES), (UP, UP_TYPES)] {
+            for barrier_type in types {
+                let analytic = option
+                    .price_with(barrier_type, &AnalyticBarrierEngine)
+                    .unwrap();
+                let mc = option.price_with(barrier_type, &engine).unwrap();
+                let error = mc.error.unwrap();
+
+                assert!(
+                    (analytic.price - mc.price).abs() < 4.0 * error + 0.01,
+                    "{barrier_type:?}: analytic = {}, mc = {} +/- {error}",
+                    analytic.price,
+                    mc.price
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_heston_engine_reduces_to_black_scholes() {
+        // With no vol-of-vol and theta = v0, the variance stays at v0.
+        let engine = HestonMonteCarloBarrierEngine {
+            mean_reversion: 1.0,
+            long_run_variance: 0.04,
+            vol_of_vol: 0.0,
+            correlation: 0.0,
+            n_paths: 20_000,
+            n_steps: 50,
+            seed: 1234,
+        };
+
+        for (option, types) in [(DOWN, DOWN_TYPES), (UP, UP_TYPES)] {
+            for barrier_type in types {
+                let analytic = option
+                    .price_with(barrier_type, &AnalyticBarrierEngine)
+                    .unwrap();
+                let mc = option.price_with(barrier_type, &engine).unwrap();
+                let error = mc.error.unwrap();
+
+                assert!(
+                    (analytic.price - mc.price).abs() < 4.0 * error + 0.01,
+                    "{barrier_type:?}: analytic = {}, mc = {} +/- {error}",
+                    analytic.price,
+                    mc.price
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_heston_engine_bridge_removes_step_bias() {
+        // Without the correction, a knock-out monitored on 10 steps would be
+        // worth noticeably more than on 200; with it, the two agree.
+        let option = BarrierOption {
+            rebate: 0.0,
+            ..DOWN
+        };
+        let coarse = HestonMonteCarloBarrierEngine {
+            n_paths: 20_000,
+            n_steps: 10,
+            ..HestonMonteCarloBarrierEngine::default()
+        };
+        let fine = HestonMonteCarloBarrierEngine {
+            n_steps: 200,
+            ..coarse
+        };
+
+        let coarse = option.price_with(BarrierType::CDO, &coarse).unwrap();
+        let fine = option.price_with(BarrierType::CDO, &fine).unwrap();
+        let error = coarse.error.unwrap().hypot(fine.error.unwrap());
+
+        assert!(
+            (coarse.price - fine.price).abs() < 4.0 * error + 0.05,
+            "coarse = {}, fine = {} +/- {error}",
+            coarse.price,
+            fine.price
+        );
+
+        let invalid = HestonMonteCarloBarrierEngine {
+            correlation: 1.5,
+            ..HestonMonteCarloBarrierEngine::default()
+        };
+        assert!(option.price_with(BarrierType::CDO, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_dyn_engine() {
+        let engines: Vec<Box<dyn BarrierEngine>> = vec![
+            Box::new(AnalyticBarrierEngine),
+            Box::new(FiniteDifferenceBarrierEngine::default()),
+        ];
+
+        let prices: Vec<f64> = engines
+            .iter()
+            .map(|engine| {
+                UP.price_with(BarrierType::CUO, engine.as_ref())
+                    .unwrap()
+                    .price
+            })
+            .collect();
+
+        assert!((prices[0] - prices[1]).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_barrier_touched() {
+        let engines: Vec<Box<dyn BarrierEngine>> = vec![
+            Box::new(AnalyticBarrierEngine),
+            Box::new(FiniteDifferenceBarrierEngine::default()),
+            Box::new(MonteCarloBarrierEngine::default()),
+            Box::new(HestonMonteCarloBarrierEngine::default()),
+        ];
+
+        for engine in &engines {
+            let result = DOWN.price_with(BarrierType::CUO, engine.as_ref());
+            assert!(matches!(result, Err(RustQuantError::InvalidArgument(_))));
+        }
+    }
+}
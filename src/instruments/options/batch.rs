@@ -0,0 +1,395 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Batch entry points for the analytic pricers.
+//!
+//! Pricing an option chain one row at a time through the scalar functions
+//! pays for argument checks, `Result`s and branching on the option type on
+//! every row. The batch functions do that work once per call, then price
+//! fixed-size chunks of struct-of-arrays inputs column by column, with the
+//! `exp`, `ln` and normal CDF evaluations done by the vectorised kernels in
+//! [`math::simd`](crate::math::simd) (see the `simd` feature). With
+//! `parallel = true` the chunks are spread over the rayon thread pool.
+//!
+//! Rows are not validated individually: invalid inputs give `NaN` (or a
+//! meaningless number) in the corresponding output, as with
+//! [`BarrierOption::price_unchecked`].
+//!
+//! ```
+//! use RustQuant::instruments::options::{black_scholes_merton_batch, TypeFlag};
+//!
+//! let spot = [95.0, 100.0, 105.0];
+//! let strike = [100.0; 3];
+//! let volatility = [0.2; 3];
+//! let rate = [0.05; 3];
+//! let time_to_expiry = [0.5; 3];
+//!
+//! let prices = black_scholes_merton_batch(
+//!     &spot, &strike, &volatility, &rate, &rate, &time_to_expiry,
+//!     TypeFlag::Call,
+//!     false,
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(prices.len(), 3);
+//! assert!(prices[0] < prices[1] && prices[1] < prices[2]);
+//! ```
+
+use super::{BarrierOption, BarrierType, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::validation::Validate;
+use crate::math::simd;
+use rayon::prelude::*;
+
+/// Rows per chunk. Large enough to amortise the per-task cost of rayon,
+/// small enough for the chunk's inputs to stay in L1/L2 cache.
+const CHUNK: usize = 1024;
+
+/// `1 / sqrt(2 pi)`.
+const FRAC_1_SQRT_2PI: f64 = 0.398_942_280_401_432_7;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Generalised Black-Scholes-Merton prices of a batch of European options
+/// of the same type.
+///
+/// Row `i` is priced with `spot[i]`, `strike[i]`, and so on; see
+/// [`generalised_black_scholes_merton`](super::generalised_black_scholes_merton)
+/// for the parameters.
+///
+/// # Errors
+/// * [`RustQuantError::InvalidArgument`] if the slices differ in length.
+#[allow(clippy::too_many_arguments)]
+pub fn black_scholes_merton_batch(
+    spot: &[f64],
+    strike: &[f64],
+    volatility: &[f64],
+    rate: &[f64],
+    cost_of_carry: &[f64],
+    time_to_expiry: &[f64],
+    option_type: TypeFlag,
+    parallel: bool,
+) -> Result<Vec<f64>, RustQuantError> {
+    let n = check_lengths(&[
+        ("spot", spot),
+        ("strike", strike),
+        ("volatility", volatility),
+        ("rate", rate),
+        ("cost_of_carry", cost_of_carry),
+        ("time_to_expiry", time_to_expiry),
+    ])?;
+
+    let phi = sign(option_type);
+
+    Ok(run(n, parallel, |start, out| {
+        let rows = start..start + out.len();
+        let (S, K, v, r, b, T) = (
+            &spot[rows.clone()],
+            &strike[rows.clone()],
+            &volatility[rows.clone()],
+            &rate[rows.clone()],
+            &cost_of_carry[rows.clone()],
+            &time_to_expiry[rows],
+        );
+
+        let mut scratch = Vec::new();
+        let [x1, x2, x3, x4, y1, y2, y3, y4] = columns(&mut scratch, out.len());
+
+        for i in 0..out.len() {
+            x1[i] = S[i] / K[i];
+        }
+        simd::ln(x1, y1);
+
+        for i in 0..out.len() {
+            let stdev = v[i] * T[i].sqrt();
+            let d1 = (y1[i] + (b[i] + 0.5 * v[i] * v[i]) * T[i]) / stdev;
+
+            x1[i] = phi * d1;
+            x2[i] = phi * (d1 - stdev);
+            x3[i] = (b[i] - r[i]) * T[i];
+            x4[i] = -r[i] * T[i];
+        }
+        simd::pnorm(x1, y1);
+        simd::pnorm(x2, y2);
+        simd::exp(x3, y3);
+        simd::exp(x4, y4);
+
+        for (i, price) in out.iter_mut().enumerate() {
+            *price = phi * (S[i] * y3[i] * y1[i] - K[i] * y4[i] * y2[i]);
+        }
+    }))
+}
+
+/// Bachelier (normal model) prices of a batch of European options of the
+/// same type.
+///
+/// Row `i` is priced with `spot[i]`, `strike[i]`, and so on; see
+/// [`bachelier_price`](super::bachelier_price) for the parameters.
+///
+/// # Errors
+/// * [`RustQuantError::InvalidArgument`] if the slices differ in length.
+pub fn bachelier_batch(
+    spot: &[f64],
+    strike: &[f64],
+    volatility: &[f64],
+    time_to_expiry: &[f64],
+    option_type: TypeFlag,
+    parallel: bool,
+) -> Result<Vec<f64>, RustQuantError> {
+    let n = check_lengths(&[
+        ("spot", spot),
+        ("strike", strike),
+        ("volatility", volatility),
+        ("time_to_expiry", time_to_expiry),
+    ])?;
+
+    let phi = sign(option_type);
+
+    Ok(run(n, parallel, |start, out| {
+        let rows = start..start + out.len();
+        let (S, K, v, T) = (
+            &spot[rows.clone()],
+            &strike[rows.clone()],
+            &volatility[rows.clone()],
+            &time_to_expiry[rows],
+        );
+
+        let mut scratch = Vec::new();
+        let [x1, x2, y1, y2] = columns(&mut scratch, out.len());
+
+        for i in 0..out.len() {
+            let d = phi * (S[i] - K[i]) / (v[i] * T[i].sqrt());
+
+            x1[i] = d;
+            x2[i] = -0.5 * d * d;
+        }
+        simd::pnorm(x1, y1);
+        simd::exp(x2, y2);
+
+        for (i, price) in out.iter_mut().enumerate() {
+            let stdev = v[i] * T[i].sqrt();
+
+            *price = phi * (S[i] - K[i]) * y1[i] + stdev * y2[i] * FRAC_1_SQRT_2PI;
+        }
+    }))
+}
+
+impl BarrierOption {
+    /// Closed-form prices of a batch of barrier options of the same type.
+    ///
+    /// Each option is validated as in [`BarrierOption::price`], but errors
+    /// are reported as `NaN` in the corresponding output rather than
+    /// failing the whole batch.
+    #[must_use]
+    pub fn price_batch(options: &[Self], type_flag: BarrierType, parallel: bool) -> Vec<f64> {
+        run(options.len(), parallel, |start, out| {
+            for (option, price) in options[start..].iter().zip(out.iter_mut()) {
+                *price = match option.validate() {
+                    Ok(()) => option.price_unchecked(type_flag),
+                    Err(_) => f64::NAN,
+                };
+            }
+        })
+    }
+}
+
+/// `+1` for calls and `-1` for puts, so that one kernel prices both.
+fn sign(option_type: TypeFlag) -> f64 {
+    match option_type {
+        TypeFlag::Call => 1.0,
+        TypeFlag::Put => -1.0,
+    }
+}
+
+/// Common length of the input columns.
+fn check_lengths(columns: &[(&str, &[f64])]) -> Result<usize, RustQuantError> {
+    let (first, n) = columns
+        .first()
+        .map_or(("", 0), |(name, values)| (*name, values.len()));
+
+    let mismatched: Vec<String> = columns
+        .iter()
+        .filter(|(_, values)| values.len() != n)
+        .map(|(name, values)| format!("{name} has {} rows", values.len()))
+        .collect();
+
+    if mismatched.is_empty() {
+        Ok(n)
+    } else {
+        Err(RustQuantError::InvalidArgument(format!(
+            "batch inputs differ in length ({first} has {n} rows, {})",
+            mismatched.join(", ")
+        )))
+    }
+}
+
+/// Split `scratch` into `N` columns of `len` rows each.
+fn columns<const N: usize>(scratch: &mut Vec<f64>, len: usize) -> [&mut [f64]; N] {
+    scratch.resize(N * len, 0.0);
+
+    let mut columns = scratch.chunks_exact_mut(len);
+    std::array::from_fn(|_| columns.next().unwrap_or_default())
+}
+
+/// Allocate `n` outputs and fill them chunk by chunk with
+/// `kernel(first_row, chunk)`, on the rayon pool if `parallel`.
+fn run<F>(n: usize, parallel: bool, kernel: F) -> Vec<f64>
+where
+    F: Fn(usize, &mut [f64]) + Sync,
+{
+    let mut out = vec![0.0; n];
+
+    if parallel {
+        out.par_chunks_mut(CHUNK)
+            .enumerate()
+            .for_each(|(i, chunk)| kernel(i * CHUNK, chunk));
+    } else {
+        out.chunks_mut(CHUNK)
+            .enumerate()
+            .for_each(|(i, chunk)| kernel(i * CHUNK, chunk));
+    }
+
+    out
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_batch {
+    use super::*;
+    use crate::instruments::options::{bachelier_price, generalised_black_scholes_merton};
+
+    /// A chain spanning several chunks, with a partial last chunk.
+    const N: usize = 2 * CHUNK + 17;
+
+    fn column(lo: f64, hi: f64) -> Vec<f64> {
+        (0..N)
+            .map(|i| lo + (hi - lo) * i as f64 / (N - 1) as f64)
+            .collect()
+    }
+
+    #[test]
+    fn test_black_scholes_merton_batch_matches_scalar() {
+        let spot = column(50.0, 150.0);
+        let strike = vec![100.0; N];
+        let volatility = column(0.05, 0.8);
+        let rate = column(-0.01, 0.08);
+        let carry = column(0.05, -0.02);
+        let expiry = column(0.01, 5.0);
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            for parallel in [false, true] {
+                let prices = black_scholes_merton_batch(
+                    &spot,
+                    &strike,
+                    &volatility,
+                    &rate,
+                    &carry,
+                    &expiry,
+                    flag,
+                    parallel,
+                )
+                .unwrap();
+
+                for i in 0..N {
+                    let expected = generalised_black_scholes_merton(
+                        spot[i],
+                        strike[i],
+                        volatility[i],
+                        rate[i],
+                        carry[i],
+                        expiry[i],
+                        flag,
+                    );
+                    assert!((prices[i] - expected).abs() < 1e-10, "row {i}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bachelier_batch_matches_scalar() {
+        let spot = column(80.0, 120.0);
+        let strike = column(110.0, 90.0);
+        let volatility = column(1.0, 30.0);
+        let expiry = column(0.1, 2.0);
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let prices = bachelier_batch(&spot, &strike, &volatility, &expiry, flag, true).unwrap();
+
+            for i in 0..N {
+                let expected = bachelier_price(spot[i], strike[i], volatility[i], expiry[i], flag);
+                assert!((prices[i] - expected).abs() < 1e-10, "row {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_barrier_price_batch() {
+        let options: Vec<BarrierOption> = column(96.0, 130.0)
+            .into_iter()
+            .map(|spot| BarrierOption {
+                initial_price: spot,
+                strike_price: 100.0,
+                barrier: 95.0,
+                time_to_expiry: 0.5,
+                risk_free_rate: 0.08,
+                volatility: 0.25,
+                rebate: 3.0,
+                dividend_yield: 0.04,
+            })
+            .collect();
+
+        let serial = BarrierOption::price_batch(&options, BarrierType::CDO, false);
+        let parallel = BarrierOption::price_batch(&options, BarrierType::CDO, true);
+
+        for (i, option) in options.iter().enumerate() {
+            let expected = option.price(BarrierType::CDO).unwrap();
+            assert_eq!(serial[i], expected, "row {i}");
+            assert_eq!(parallel[i], expected, "row {i}");
+        }
+
+        // Errors become NaN without failing the rest of the batch.
+        let mut invalid = options[..3].to_vec();
+        invalid[0].volatility = -0.25;
+        invalid[1].initial_price = 90.0; // Already below the down barrier.
+
+        let prices = BarrierOption::price_batch(&invalid, BarrierType::CDO, false);
+        assert!(prices[0].is_nan());
+        assert!(prices[1].is_nan());
+        assert!(prices[2].is_finite());
+    }
+
+    #[test]
+    fn test_batch_length_mismatch() {
+        let result = bachelier_batch(
+            &[100.0, 101.0],
+            &[100.0, 100.0],
+            &[10.0],
+            &[1.0, 1.0],
+            TypeFlag::Call,
+            false,
+        );
+
+        match result {
+            Err(RustQuantError::InvalidArgument(message)) => {
+                assert!(message.contains("volatility has 1 rows"), "{message}");
+            }
+            other => panic!("expected InvalidArgument, got {other:?}"),
+        }
+
+        assert!(bachelier_batch(&[], &[], &[], &[], TypeFlag::Put, true)
+            .unwrap()
+            .is_empty());
+    }
+}
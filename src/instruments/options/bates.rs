@@ -0,0 +1,441 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bates (1996) stochastic volatility with jumps (SVJ).
+//!
+//! Heston stochastic variance with Merton normal log-jumps in the price:
+//!
+//! ```text
+//! dS_t / S_t- = (r - q - lambda k) dt + sqrt(v_t) dW^S_t + (exp(J) - 1) dN_t,
+//! dv_t        = kappa (theta - v_t) dt + sigma sqrt(v_t) dW^v_t,
+//! ```
+//!
+//! with `d<W^S, W^v> = rho dt`, `N` a Poisson process of intensity `lambda`,
+//! `J ~ N(mu, delta^2)`, and `k = exp(mu + delta^2 / 2) - 1` the compensator.
+//! The jumps give the short-dated skew and the stochastic variance the
+//! long-dated one.
+//!
+//! European options are priced with the Carr-Madan FFT, paths are simulated
+//! with the quadratic-exponential (QE) scheme of Andersen (2008) for the
+//! variance plus exact compound Poisson jumps, and the model is calibrated
+//! with [`JumpCalibrator::bates`].
+
+use super::{
+    carr_madan_prices, DiffusionParameters, HestonParameters, JumpDiffusionParameters,
+    JumpParameters, TypeFlag,
+};
+use crate::error::RustQuantError;
+use crate::instruments::{PricingContext, Validate, Validator};
+use crate::time::DayCountConvention;
+use num::Complex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::{Distribution, Poisson, StandardNormal};
+use time::Date;
+
+// Switching level of the QE scheme between the quadratic and exponential
+// approximations of the variance (Andersen's `psi_c`).
+const QE_SWITCH: f64 = 1.5;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Parameters of the Bates model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatesParameters {
+    /// Heston stochastic variance.
+    pub heston: HestonParameters,
+    /// Expected number of jumps per year, `lambda`.
+    pub jump_intensity: f64,
+    /// Mean log-jump, `mu`.
+    pub jump_mean: f64,
+    /// Standard deviation of the log-jumps, `delta`.
+    pub jump_volatility: f64,
+}
+
+/// Simulated paths of the Bates model.
+#[derive(Debug, Clone)]
+pub struct BatesPaths {
+    /// Time points, from 0 to the horizon.
+    pub times: Vec<f64>,
+    /// Price paths.
+    pub prices: Vec<Vec<f64>>,
+    /// Variance paths.
+    pub variances: Vec<Vec<f64>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BatesParameters {
+    /// Characteristic function of `ln(S_T / S_0)`, with cost of carry `b`:
+    /// the Heston one times that of the compensated jumps.
+    #[must_use]
+    pub fn characteristic_function(&self, u: Complex<f64>, T: f64, b: f64) -> Complex<f64> {
+        JumpDiffusionParameters::from(*self).characteristic_function(u, T, b)
+    }
+
+    /// European option prices for one expiry via the Carr-Madan FFT.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if a strike is outside the FFT grid.
+    pub fn prices_fft(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        strikes: &[f64],
+        option_type: TypeFlag,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        JumpDiffusionParameters::from(*self).prices_fft(S, r, q, T, strikes, option_type)
+    }
+
+    /// European option prices for one expiry via the Carr-Madan FFT, valued
+    /// as of the context's valuation date.
+    ///
+    /// The risk-free rate is read off the context (see
+    /// [`PricingContext::risk_free_rate`]), keeping `r` if it has none, and
+    /// the characteristic function is memoised in the context's cache, so
+    /// repricing the expiry (e.g. for other strikes) reuses its evaluations.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if [`carr_madan_prices`] fails.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prices_fft_in(
+        &self,
+        ctx: &PricingContext,
+        S: f64,
+        r: f64,
+        q: f64,
+        expiry: Date,
+        strikes: &[f64],
+        option_type: TypeFlag,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        self.validate()?;
+
+        let T = DayCountConvention::default().day_count_factor(ctx.valuation_date, expiry);
+        let r = ctx
+            .risk_free_rate(None, ctx.valuation_date, expiry)
+            .unwrap_or(r);
+        let id = format!("Bates {self:?} {}", r - q);
+
+        // Carr-Madan evaluates the characteristic function on a line of
+        // constant imaginary part, so the real part identifies the argument.
+        carr_madan_prices(
+            |u| {
+                ctx.cache.characteristic_function(&id, expiry, u.re, || {
+                    self.characteristic_function(u, T, r - q)
+                })
+            },
+            S,
+            r,
+            q,
+            T,
+            strikes,
+            option_type,
+        )
+    }
+
+    /// Simulate `n_paths` paths of the price and variance to time `T`, in
+    /// `n_steps` equal steps, with the QE scheme and exact jumps.
+    ///
+    /// The log-price uses Andersen's central discretisation of the
+    /// integrated variance, which keeps its correlation with the variance.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], `S` or `T` are not positive, the rates not
+    ///   finite, or there are no steps or paths.
+    #[allow(clippy::too_many_arguments)]
+    pub fn simulate(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        n_steps: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<BatesPaths, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .positive("S", S)
+            .finite("r", r)
+            .finite("q", q)
+            .positive("T", T)
+            .check(n_steps > 0 && n_paths > 0, || {
+                "at least one step and one path are needed".to_string()
+            })
+            .finish()?;
+
+        let HestonParameters {
+            initial_variance: v0,
+            long_run_variance: theta,
+            mean_reversion_rate: kappa,
+            volatility_of_volatility: sigma,
+            correlation: rho,
+        } = self.heston;
+        let (lambda, mu, delta) = (self.jump_intensity, self.jump_mean, self.jump_volatility);
+
+        let dt = T / n_steps as f64;
+        let decay = (-kappa * dt).exp();
+        let compensator = lambda * ((mu + 0.5 * delta * delta).exp() - 1.0);
+
+        // Coefficients of the log-price step, with equal weights on the
+        // variance at both ends of the step.
+        let k0 = -rho * kappa * theta * dt / sigma;
+        let k1 = 0.5 * dt * (kappa * rho / sigma - 0.5) - rho / sigma;
+        let k2 = 0.5 * dt * (kappa * rho / sigma - 0.5) + rho / sigma;
+        let k3 = 0.5 * dt * (1.0 - rho * rho);
+
+        let jumps = Poisson::new(lambda * dt).map_err(|error| {
+            RustQuantError::InvalidArgument(format!("Invalid jump intensity: {error}"))
+        })?;
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let times = (0..=n_steps).map(|i| dt * i as f64).collect();
+        let mut prices = Vec::with_capacity(n_paths);
+        let mut variances = Vec::with_capacity(n_paths);
+
+        for _ in 0..n_paths {
+            let mut x = vec![S.ln(); n_steps + 1];
+            let mut v = vec![v0; n_steps + 1];
+
+            for i in 0..n_steps {
+                // Moments of the variance at the end of the step.
+                let m = theta + (v[i] - theta) * decay;
+                let s2 = v[i] * sigma * sigma * decay * (1.0 - decay) / kappa
+                    + theta * sigma * sigma * (1.0 - decay).powi(2) / (2.0 * kappa);
+                let psi = s2 / (m * m);
+
+                v[i + 1] = if psi <= QE_SWITCH {
+                    let b2 = 2.0 / psi - 1.0 + (2.0 / psi).sqrt() * (2.0 / psi - 1.0).sqrt();
+                    let z: f64 = StandardNormal.sample(&mut rng);
+
+                    m / (1.0 + b2) * (b2.sqrt() + z).powi(2)
+                } else {
+                    let p = (psi - 1.0) / (psi + 1.0);
+                    let u: f64 = rng.gen();
+
+                    match u <= p {
+                        true => 0.0,
+                        false => ((1.0 - p) / (1.0 - u)).ln() * m / (1.0 - p),
+                    }
+                };
+
+                let n: f64 = jumps.sample(&mut rng);
+                let z: f64 = StandardNormal.sample(&mut rng);
+                let z_jump: f64 = StandardNormal.sample(&mut rng);
+
+                x[i + 1] = x[i]
+                    + (r - q - compensator) * dt
+                    + k0
+                    + k1 * v[i]
+                    + k2 * v[i + 1]
+                    + (k3 * (v[i] + v[i + 1])).sqrt() * z
+                    + n * mu
+                    + n.sqrt() * delta * z_jump;
+            }
+
+            prices.push(x.into_iter().map(f64::exp).collect());
+            variances.push(v);
+        }
+
+        Ok(BatesPaths {
+            times,
+            prices,
+            variances,
+        })
+    }
+}
+
+impl From<BatesParameters> for JumpDiffusionParameters {
+    fn from(parameters: BatesParameters) -> Self {
+        Self {
+            diffusion: DiffusionParameters::Heston(parameters.heston),
+            jumps: JumpParameters::Merton {
+                intensity: parameters.jump_intensity,
+                mean: parameters.jump_mean,
+                volatility: parameters.jump_volatility,
+            },
+        }
+    }
+}
+
+impl TryFrom<JumpDiffusionParameters> for BatesParameters {
+    type Error = RustQuantError;
+
+    /// Bates parameters from a calibrated jump-diffusion, which must have
+    /// Heston variance and Merton jumps.
+    fn try_from(parameters: JumpDiffusionParameters) -> Result<Self, Self::Error> {
+        match parameters {
+            JumpDiffusionParameters {
+                diffusion: DiffusionParameters::Heston(heston),
+                jumps:
+                    JumpParameters::Merton {
+                        intensity,
+                        mean,
+                        volatility,
+                    },
+            } => Ok(Self {
+                heston,
+                jump_intensity: intensity,
+                jump_mean: mean,
+                jump_volatility: volatility,
+            }),
+            _ => Err(RustQuantError::InvalidArgument(
+                "The Bates model has Heston variance and Merton jumps.".to_string(),
+            )),
+        }
+    }
+}
+
+impl Validate for BatesParameters {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        JumpDiffusionParameters::from(*self).validate()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bates {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const BATES: BatesParameters = BatesParameters {
+        heston: HestonParameters {
+            initial_variance: 0.04,
+            long_run_variance: 0.04,
+            mean_reversion_rate: 2.0,
+            volatility_of_volatility: 0.4,
+            correlation: -0.6,
+        },
+        jump_intensity: 0.5,
+        jump_mean: -0.1,
+        jump_volatility: 0.1,
+    };
+
+    const S: f64 = 100.0;
+    const R: f64 = 0.03;
+    const Q: f64 = 0.01;
+    const T: f64 = 0.5;
+
+    #[test]
+    fn test_bates_prices_fft_in_context() {
+        use time::macros::date;
+
+        let ctx = PricingContext::new(date!(2024 - 01 - 01));
+        let expiry = date!(2024 - 07 - 01);
+        let tau = DayCountConvention::default().day_count_factor(ctx.valuation_date, expiry);
+
+        let strikes = [90.0, 100.0, 110.0];
+        let prices = BATES
+            .prices_fft_in(&ctx, S, R, Q, expiry, &strikes, TypeFlag::Call)
+            .unwrap();
+        let expected = BATES
+            .prices_fft(S, R, Q, tau, &strikes, TypeFlag::Call)
+            .unwrap();
+
+        for (price, expected) in prices.iter().zip(expected) {
+            assert_approx_equal!(*price, expected, 1e-12);
+        }
+
+        // Repricing the expiry only reads the cache.
+        let misses = ctx.cache.stats().misses;
+        BATES
+            .prices_fft_in(&ctx, S, R, Q, expiry, &[95.0], TypeFlag::Put)
+            .unwrap();
+        assert_eq!(ctx.cache.stats().misses, misses);
+        assert!(ctx.cache.stats().hits >= misses);
+    }
+
+    #[test]
+    fn test_bates_without_jumps_is_heston() {
+        let heston = BatesParameters {
+            jump_intensity: 1e-12,
+            ..BATES
+        };
+        let strikes = [90.0, 100.0, 110.0];
+
+        let bates = heston
+            .prices_fft(S, R, Q, T, &strikes, TypeFlag::Call)
+            .unwrap();
+        let expected = BATES
+            .heston
+            .prices_fft(S, R, Q, T, &strikes, TypeFlag::Call)
+            .unwrap();
+
+        for (price, expected) in bates.iter().zip(expected) {
+            assert_approx_equal!(price, expected, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_bates_simulation_matches_fft() {
+        let strikes = [90.0, 100.0, 110.0];
+        let calls = BATES
+            .prices_fft(S, R, Q, T, &strikes, TypeFlag::Call)
+            .unwrap();
+
+        let n_paths = 20_000;
+        let paths = BATES.simulate(S, R, Q, T, 50, n_paths, 42).unwrap();
+        let terminal: Vec<f64> = paths.prices.iter().map(|path| path[50]).collect();
+
+        assert_eq!(paths.times.len(), 51);
+        assert!(paths.variances.iter().flatten().all(|&v| v >= 0.0));
+
+        // The jumps are compensated: the forward is preserved.
+        let forward = terminal.iter().sum::<f64>() / n_paths as f64;
+        assert_approx_equal!(forward, S * ((R - Q) * T).exp(), 0.5);
+
+        for (&K, call) in strikes.iter().zip(calls) {
+            let payoffs: Vec<f64> = terminal
+                .iter()
+                .map(|&s| (-R * T).exp() * (s - K).max(0.0))
+                .collect();
+            let mean = payoffs.iter().sum::<f64>() / n_paths as f64;
+            let variance =
+                payoffs.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / (n_paths - 1) as f64;
+            let error = (variance / n_paths as f64).sqrt();
+
+            assert!((mean - call).abs() < 4.0 * error, "{mean} vs {call}");
+        }
+    }
+
+    #[test]
+    fn test_bates_calibration_round_trip() {
+        let parameters = JumpDiffusionParameters::from(BATES);
+
+        assert_eq!(BatesParameters::try_from(parameters).unwrap(), BATES);
+        assert!(BatesParameters::try_from(JumpDiffusionParameters {
+            diffusion: DiffusionParameters::Constant { volatility: 0.2 },
+            ..parameters
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_bates_validation() {
+        let invalid = BatesParameters {
+            jump_volatility: 0.0,
+            ..BATES
+        };
+
+        assert!(invalid.validate().is_err());
+        assert!(BATES.simulate(S, R, Q, T, 0, 10, 42).is_err());
+    }
+}
@@ -9,7 +9,15 @@
 
 //! This module contains various 'binary', or 'digital', option types.
 
-use crate::math::distributions::{gaussian::Gaussian, Distribution};
+#[cfg(feature = "options")]
+use crate::error::RustQuantError;
+#[cfg(feature = "options")]
+use crate::instruments::{options::TypeFlag, Instrument, PricingContext};
+use crate::math::Real;
+#[cfg(feature = "options")]
+use crate::time::today;
+#[cfg(feature = "options")]
+use time::Date;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS
@@ -17,6 +25,7 @@ use crate::math::distributions::{gaussian::Gaussian, Distribution};
 
 /// Gap option parameters.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GapOption {
     /// `S` - Initial price of the underlying.
     pub initial_price: f64,
@@ -36,6 +45,7 @@ pub struct GapOption {
 
 /// Cash-or-Nothing option parameters.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CashOrNothingOption {
     /// `S` - Initial price of the underlying.
     pub initial_price: f64,
@@ -78,10 +88,9 @@ impl GapOption {
         let d1 = ((S / K_1).ln() + (b + 0.5 * v * v) * T) / (v * (T).sqrt());
         let d2 = d1 - v * (T).sqrt();
 
-        let N = Gaussian::default();
-
-        let c = S * ((b - r) * T).exp() * N.cdf(d1) - K_2 * (-r * T).exp() * N.cdf(d2);
-        let p = -S * ((b - r) * T).exp() * N.cdf(-d1) + K_2 * (-r * T).exp() * N.cdf(-d2);
+        let c = S * ((b - r) * T).exp() * d1.norm_cdf() - K_2 * (-r * T).exp() * d2.norm_cdf();
+        let p =
+            -S * ((b - r) * T).exp() * (-d1).norm_cdf() + K_2 * (-r * T).exp() * (-d2).norm_cdf();
 
         (c, p)
     }
@@ -103,15 +112,110 @@ impl CashOrNothingOption {
 
         let d = ((S / X).ln() + (b - 0.5 * v * v) * T) / (v * (T).sqrt());
 
-        let N = Gaussian::default();
-
-        let c = K * (-r * T).exp() * N.cdf(d);
-        let p = K * (-r * T).exp() * N.cdf(-d);
+        let c = K * (-r * T).exp() * d.norm_cdf();
+        let p = K * (-r * T).exp() * (-d).norm_cdf();
 
         (c, p)
     }
 }
 
+#[cfg(feature = "options")]
+impl Instrument for (GapOption, TypeFlag) {
+    /// Returns the price (net present value) of the option in the given
+    /// direction.
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The volatility is read at the trigger strike, and the cost of carry
+    /// moves with the rate. The time to maturity is counted from the
+    /// context's valuation date.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let expiry = ctx.date_after(self.0.time_to_maturity);
+        let r = ctx
+            .risk_free_rate(None, ctx.valuation_date, expiry)
+            .unwrap_or(self.0.risk_free_rate);
+
+        let option = GapOption {
+            cost_of_carry: self.0.cost_of_carry + r - self.0.risk_free_rate,
+            risk_free_rate: r,
+            volatility: ctx
+                .volatility(self.0.strike_1, expiry)
+                .unwrap_or(self.0.volatility),
+            ..self.0
+        };
+
+        Ok(match self.1 {
+            TypeFlag::Call => option.price().0,
+            TypeFlag::Put => option.price().1,
+        })
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        today()
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Gap Option"
+    }
+}
+
+#[cfg(feature = "options")]
+impl Instrument for (CashOrNothingOption, TypeFlag) {
+    /// Returns the price (net present value) of the option in the given
+    /// direction.
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The cost of carry moves with the rate. The time to maturity is
+    /// counted from the context's valuation date.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let expiry = ctx.date_after(self.0.time_to_maturity);
+        let r = ctx
+            .risk_free_rate(None, ctx.valuation_date, expiry)
+            .unwrap_or(self.0.risk_free_rate);
+
+        let option = CashOrNothingOption {
+            cost_of_carry: self.0.cost_of_carry + r - self.0.risk_free_rate,
+            risk_free_rate: r,
+            volatility: ctx
+                .volatility(self.0.strike_price, expiry)
+                .unwrap_or(self.0.volatility),
+            ..self.0
+        };
+
+        Ok(match self.1 {
+            TypeFlag::Call => option.price().0,
+            TypeFlag::Put => option.price().1,
+        })
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        today()
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Cash-or-Nothing Option"
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -11,11 +11,17 @@
 // BINOMIAL OPTION PRICING PARAMETER STRUCT
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use super::{ExerciseFlag, TypeFlag};
+use super::{
+    escrowed_spot, generalised_black_scholes_merton, Dividend, ExerciseBoundary, ExerciseFlag,
+    TypeFlag,
+};
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
 
 /// Struct containing the parameters to price an option via binomial tree method.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BinomialOption {
     initial_price: f64,
     strike_price: f64,
@@ -30,6 +36,26 @@ pub struct BinomialOption {
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 impl BinomialOption {
+    /// New binomial option.
+    #[must_use]
+    pub fn new(
+        initial_price: f64,
+        strike_price: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            strike_price,
+            time_to_expiry,
+            risk_free_rate,
+            dividend_yield,
+            volatility,
+        }
+    }
+
     /// Cox-Ross-Rubinstein binomial option pricing model.
     ///
     /// Adapted from Haug's *Complete Guide to Option Pricing Formulas*.
@@ -41,17 +67,42 @@ impl BinomialOption {
     /// * `call_put_flag` - `TypeFlag`: either `Call` or `Put`.
     /// * `n` - Height of the binomial tree.
     ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], if the tree height is below 3, if the output
+    ///   flag is not one of the above, or if the exercise style is `Bermudan`
+    ///   (not yet supported).
+    ///
     /// # Note:
     ///
     /// * `b = r - q` - The cost of carry.
-    #[must_use]
     pub fn price_CoxRossRubinstein(
         &self,
         output_flag: &str,
         ame_eur_flag: ExerciseFlag,
         call_put_flag: TypeFlag,
         n: usize,
-    ) -> f64 {
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        // The Greeks are read off the first three levels of the tree.
+        if n < 3 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Tree height must be at least 3 (got {n})."
+            )));
+        }
+        if !matches!(output_flag, "p" | "d" | "g" | "t") {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Check OutputFlag. Should be one of: 'p', 'd', 'g', 't' (got '{output_flag}')."
+            )));
+        }
+        if let ExerciseFlag::Bermudan = ame_eur_flag {
+            return Err(RustQuantError::InvalidArgument(
+                "Bermudan option pricing not implemented yet.".to_string(),
+            ));
+        }
+
         let S = self.initial_price;
         let K = self.strike_price;
         let T = self.time_to_expiry;
@@ -95,9 +146,7 @@ impl BinomialOption {
                         option_value[i] =
                             Df * (p * (option_value[i + 1]) + (1.0 - p) * option_value[i]);
                     }
-                    ExerciseFlag::Bermudan => {
-                        panic!("Bermudan option pricing not implemented yet.");
-                    }
+                    ExerciseFlag::Bermudan => unreachable!("Bermudan exercise is rejected above."),
                 }
             }
             if j == 2 {
@@ -115,7 +164,7 @@ impl BinomialOption {
         return_value[3] = (option_value[3] - option_value[0]) / (2.0 * dt) / 365.0;
         return_value[0] = option_value[0];
 
-        match output_flag {
+        Ok(match output_flag {
             // Return the option value.
             "p" => return_value[0],
             // Return the Delta.
@@ -123,10 +172,276 @@ impl BinomialOption {
             // Return the Gamma.
             "g" => return_value[2],
             // Return the Theta.
-            "t" => return_value[3],
-            // Capture edge cases.
-            _ => panic!("Check OutputFlag. Should be one of: 'p', 'd', 'g', 't'."),
+            _ => return_value[3],
+        })
+    }
+
+    /// Cox-Ross-Rubinstein price of an American option, together with its
+    /// early-exercise boundary.
+    ///
+    /// At each level of the tree, the critical price is the highest node at
+    /// which a put (lowest at which a call) is exercised.
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the tree height is zero.
+    pub fn price_with_boundary_CoxRossRubinstein(
+        &self,
+        call_put_flag: TypeFlag,
+        n: usize,
+    ) -> Result<(f64, ExerciseBoundary), RustQuantError> {
+        self.validate()?;
+
+        if n == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Tree height must be positive (got 0).".to_string(),
+            ));
+        }
+
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let T = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let q = self.dividend_yield;
+        let v = self.volatility;
+
+        let dt = T / n as f64;
+        let u = (v * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = (((r - q) * dt).exp() - d) / (u - d);
+        let Df = (-r * dt).exp();
+
+        let mut option_value = vec![0.0; n + 1];
+        let mut critical_prices = vec![None; n + 1];
+
+        for j in (0..=n).rev() {
+            for i in 0..=j {
+                let price = S * u.powi(i as i32) * d.powi((j - i) as i32);
+                let exercise = match call_put_flag {
+                    TypeFlag::Call => price - K,
+                    TypeFlag::Put => K - price,
+                };
+                let continuation = if j == n {
+                    0.0
+                } else {
+                    Df * (p * option_value[i + 1] + (1.0 - p) * option_value[i])
+                };
+
+                // Nodes are in increasing price order.
+                if exercise > 0.0 && exercise >= continuation {
+                    match call_put_flag {
+                        TypeFlag::Call => {
+                            critical_prices[j] = critical_prices[j].or(Some(price));
+                        }
+                        TypeFlag::Put => critical_prices[j] = Some(price),
+                    }
+                }
+
+                option_value[i] = exercise.max(continuation);
+            }
+        }
+
+        let boundary = ExerciseBoundary {
+            times: (0..=n).map(|j| j as f64 * dt).collect(),
+            critical_prices,
+        };
+
+        Ok((option_value[0], boundary))
+    }
+
+    /// Cox-Ross-Rubinstein price of an option on a stock paying discrete
+    /// dividends, on top of the continuous yield.
+    ///
+    /// The tree is built on the escrowed price (the spot net of the present
+    /// value of the cash dividends, see [`escrowed_spot`]), so it still
+    /// recombines. At each node, the stock price is the tree price scaled by
+    /// the proportional dividends already paid, plus the present value of
+    /// the cash dividends still to come, and is used for exercise.
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the parameters or a dividend
+    ///   fail [`Validate::validate`], if the dividends exceed the spot, if
+    ///   the tree height is zero, or if the exercise style is `Bermudan`.
+    pub fn price_with_dividends_CoxRossRubinstein(
+        &self,
+        ame_eur_flag: ExerciseFlag,
+        call_put_flag: TypeFlag,
+        n: usize,
+        dividends: &[Dividend],
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        if n == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Tree height must be positive (got 0).".to_string(),
+            ));
+        }
+        if let ExerciseFlag::Bermudan = ame_eur_flag {
+            return Err(RustQuantError::InvalidArgument(
+                "Bermudan option pricing not implemented yet.".to_string(),
+            ));
+        }
+
+        let K = self.strike_price;
+        let T = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let q = self.dividend_yield;
+        let v = self.volatility;
+
+        dividends.iter().try_for_each(Validate::validate)?;
+
+        let dividends: Vec<Dividend> = dividends
+            .iter()
+            .copied()
+            .filter(|dividend| dividend.is_paid_by(T))
+            .collect();
+
+        // Escrowed price of the cash dividends only; the proportional ones
+        // are applied node by node.
+        let cash_dividends: Vec<Dividend> = dividends
+            .iter()
+            .copied()
+            .filter(|dividend| matches!(dividend, Dividend::Cash { .. }))
+            .collect();
+        let X = escrowed_spot(self.initial_price, r, T, &cash_dividends)?;
+
+        let dt = T / n as f64;
+        let u = (v * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = (((r - q) * dt).exp() - d) / (u - d);
+        let Df = (-r * dt).exp();
+
+        let z = match call_put_flag {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+
+        let stock_price = |j: usize, i: usize| {
+            let t = j as f64 * dt;
+            let (scale, cash) = dividends
+                .iter()
+                .fold((1.0, 0.0), |(scale, cash), dividend| match *dividend {
+                    Dividend::Proportional { time, ratio } if time <= t => {
+                        (scale * (1.0 - ratio), cash)
+                    }
+                    Dividend::Cash { time, amount } if time > t => {
+                        (scale, cash + amount * (-r * (time - t)).exp())
+                    }
+                    _ => (scale, cash),
+                });
+
+            X * u.powi(i as i32) * d.powi((j - i) as i32) * scale + cash
+        };
+
+        let mut option_value: Vec<f64> = (0..=n)
+            .map(|i| (z * (stock_price(n, i) - K)).max(0.0))
+            .collect();
+
+        for j in (0..n).rev() {
+            for i in 0..=j {
+                let continuation = Df * (p * option_value[i + 1] + (1.0 - p) * option_value[i]);
+
+                option_value[i] = match ame_eur_flag {
+                    ExerciseFlag::American => continuation.max(z * (stock_price(j, i) - K)),
+                    _ => continuation,
+                };
+            }
+        }
+
+        Ok(option_value[0])
+    }
+
+    /// Cox-Ross-Rubinstein price of a shout option.
+    ///
+    /// The holder may "shout" once before expiry to lock in the intrinsic
+    /// value at that time, $S_\tau - K$ for a call, and receives at expiry
+    /// the larger of it and the usual payoff. At the shout, the option
+    /// becomes the locked-in amount plus an at-the-money European option on
+    /// the remaining life,
+    ///
+    /// $$
+    /// (S_\tau - K) e^{-r (T - \tau)} + S_\tau \, c(1, 1, T - \tau),
+    /// $$
+    ///
+    /// with $c(1, 1, \cdot)$ the Black-Scholes price of a unit at-the-money
+    /// option (Hull, *Options, Futures, and Other Derivatives*). Going back
+    /// through the tree, the option is worth the larger of this and its
+    /// continuation value at each node where it is in the money.
+    ///
+    /// # Errors:
+    ///
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the tree height is zero.
+    pub fn price_shout_CoxRossRubinstein(
+        &self,
+        call_put_flag: TypeFlag,
+        n: usize,
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        if n == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Tree height must be positive (got 0).".to_string(),
+            ));
         }
+
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let T = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let q = self.dividend_yield;
+        let v = self.volatility;
+
+        let dt = T / n as f64;
+        let u = (v * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = (((r - q) * dt).exp() - d) / (u - d);
+        let Df = (-r * dt).exp();
+
+        let z = match call_put_flag {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+
+        let mut option_value: Vec<f64> = (0..=n)
+            .map(|i| (z * (S * u.powi(i as i32) * d.powi((n - i) as i32) - K)).max(0.0))
+            .collect();
+
+        for j in (0..n).rev() {
+            let remaining = T - j as f64 * dt;
+            let at_the_money =
+                generalised_black_scholes_merton(1.0, 1.0, v, r, r - q, remaining, call_put_flag);
+
+            for i in 0..=j {
+                let price = S * u.powi(i as i32) * d.powi((j - i) as i32);
+                let intrinsic = z * (price - K);
+                let continuation = Df * (p * option_value[i + 1] + (1.0 - p) * option_value[i]);
+
+                option_value[i] = match intrinsic > 0.0 {
+                    true => {
+                        continuation.max(intrinsic * (-r * remaining).exp() + price * at_the_money)
+                    }
+                    false => continuation,
+                };
+            }
+        }
+
+        Ok(option_value[0])
+    }
+}
+
+impl Validate for BinomialOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("dividend_yield", self.dividend_yield)
+            .positive("volatility", self.volatility)
+            .finish()
     }
 }
 
@@ -138,7 +453,7 @@ impl BinomialOption {
 mod tests_binomial {
     use crate::{
         assert_approx_equal,
-        instruments::{BinomialOption, ExerciseFlag, TypeFlag},
+        instruments::{BinomialOption, Dividend, ExerciseFlag, TypeFlag},
     };
 
     #[test]
@@ -152,8 +467,12 @@ mod tests_binomial {
             volatility: 0.3,
         };
 
-        let c = BinOpt.price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Call, 100);
-        let p = BinOpt.price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Put, 100);
+        let c = BinOpt
+            .price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Call, 100)
+            .unwrap();
+        let p = BinOpt
+            .price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Put, 100)
+            .unwrap();
 
         let c_intrinsic = (100_f64 - 95_f64).max(0.0);
         let p_intrinsic = (95_f64 - 100_f64).max(0.0);
@@ -169,4 +488,161 @@ mod tests_binomial {
         // Very weak parity due to discrete time steps.
         assert_approx_equal!(parity, 0.0, 0.5);
     }
+
+    #[test]
+    fn TEST_CRRBinomial_invalid_flags() {
+        let BinOpt = BinomialOption {
+            initial_price: 100.0,
+            strike_price: 95.0,
+            time_to_expiry: 0.5,
+            risk_free_rate: 0.08,
+            dividend_yield: 0.0,
+            volatility: 0.3,
+        };
+
+        assert!(BinOpt
+            .price_CoxRossRubinstein("x", ExerciseFlag::European, TypeFlag::Call, 100)
+            .is_err());
+        assert!(BinOpt
+            .price_CoxRossRubinstein("p", ExerciseFlag::Bermudan, TypeFlag::Call, 100)
+            .is_err());
+        assert!(BinOpt
+            .price_CoxRossRubinstein("p", ExerciseFlag::European, TypeFlag::Call, 2)
+            .is_err());
+
+        let negative_vol = BinomialOption {
+            volatility: -0.3,
+            ..BinOpt
+        };
+        assert!(negative_vol
+            .price_CoxRossRubinstein("p", ExerciseFlag::European, TypeFlag::Call, 100)
+            .is_err());
+    }
+
+    #[test]
+    fn TEST_CRRBinomial_exercise_boundary() {
+        let BinOpt = BinomialOption::new(100.0, 100.0, 1.0, 0.08, 0.0, 0.3);
+
+        let (price, boundary) = BinOpt
+            .price_with_boundary_CoxRossRubinstein(TypeFlag::Put, 200)
+            .unwrap();
+        let expected = BinOpt
+            .price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Put, 200)
+            .unwrap();
+
+        assert_approx_equal!(price, expected, 1e-10);
+        assert_eq!(boundary.times.len(), 201);
+        assert_approx_equal!(boundary.times[200], 1.0, 1e-12);
+
+        // Not exercised at the first few levels, where no node is deep
+        // enough in the money.
+        assert_eq!(boundary.critical_prices[0], None);
+        assert!(boundary
+            .critical_prices
+            .iter()
+            .flatten()
+            .all(|&critical| critical < 100.0));
+
+        // The put boundary rises towards the strike as expiry approaches.
+        let early = boundary.critical_price(0.25).unwrap();
+        let late = boundary.critical_price(0.99).unwrap();
+        assert!(early < late);
+        assert!(early > 70.0 && early < 80.0);
+
+        // Without dividends, an American call is never exercised early.
+        let (_, boundary) = BinOpt
+            .price_with_boundary_CoxRossRubinstein(TypeFlag::Call, 200)
+            .unwrap();
+        assert!(boundary.critical_prices[..200].iter().all(Option::is_none));
+
+        assert!(BinOpt
+            .price_with_boundary_CoxRossRubinstein(TypeFlag::Put, 0)
+            .is_err());
+    }
+
+    #[test]
+    fn TEST_CRRBinomial_discrete_dividends() {
+        let BinOpt = BinomialOption::new(100.0, 100.0, 1.0, 0.05, 0.0, 0.25);
+        let cash = [Dividend::Cash {
+            time: 0.5,
+            amount: 5.0,
+        }];
+        let proportional = [Dividend::Proportional {
+            time: 0.5,
+            ratio: 0.03,
+        }];
+
+        // European prices converge to the escrowed-dividend Black-Scholes.
+        let european = BinOpt
+            .price_with_dividends_CoxRossRubinstein(
+                ExerciseFlag::European,
+                TypeFlag::Call,
+                500,
+                &cash,
+            )
+            .unwrap();
+        assert_approx_equal!(european, 9.462_771_859_718_465, 0.01);
+
+        let european_put = BinOpt
+            .price_with_dividends_CoxRossRubinstein(
+                ExerciseFlag::European,
+                TypeFlag::Put,
+                500,
+                &proportional,
+            )
+            .unwrap();
+        assert_approx_equal!(european_put, 8.646_348_339_063_422, 0.01);
+
+        // A large dividend makes it optimal to exercise the call early.
+        let american = BinOpt
+            .price_with_dividends_CoxRossRubinstein(
+                ExerciseFlag::American,
+                TypeFlag::Call,
+                500,
+                &cash,
+            )
+            .unwrap();
+        assert!(american > european + 0.2);
+
+        // Without dividends, this is the plain CRR tree.
+        let plain = BinOpt
+            .price_with_dividends_CoxRossRubinstein(ExerciseFlag::American, TypeFlag::Put, 200, &[])
+            .unwrap();
+        let expected = BinOpt
+            .price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Put, 200)
+            .unwrap();
+        assert_approx_equal!(plain, expected, 1e-10);
+    }
+
+    #[test]
+    fn TEST_CRRBinomial_shout() {
+        let BinOpt = BinomialOption::new(100.0, 100.0, 1.0, 0.05, 0.0, 0.2);
+
+        let call = BinOpt
+            .price_shout_CoxRossRubinstein(TypeFlag::Call, 200)
+            .unwrap();
+        let put = BinOpt
+            .price_shout_CoxRossRubinstein(TypeFlag::Put, 200)
+            .unwrap();
+
+        // Independent implementation of the same tree.
+        assert_approx_equal!(call, 12.835_336_821, 1e-4);
+        assert_approx_equal!(put, 7.813_890_592, 1e-4);
+
+        // The right to shout is worth more than the European option.
+        let european = BinOpt
+            .price_CoxRossRubinstein("p", ExerciseFlag::European, TypeFlag::Call, 200)
+            .unwrap();
+        assert!(call > european + 2.0);
+
+        // Converged to within a cent.
+        let finer = BinOpt
+            .price_shout_CoxRossRubinstein(TypeFlag::Call, 800)
+            .unwrap();
+        assert_approx_equal!(call, finer, 0.01);
+
+        assert!(BinOpt
+            .price_shout_CoxRossRubinstein(TypeFlag::Call, 0)
+            .is_err());
+    }
 }
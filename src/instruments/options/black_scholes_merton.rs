@@ -25,8 +25,10 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::error::RustQuantError;
+use crate::instruments::options::closed_form::generalised_black_scholes_merton;
 use crate::instruments::options::TypeFlag;
-use crate::instruments::Instrument;
+use crate::instruments::{Instrument, PricingContext, Validate, Validator};
 use crate::math::distributions::{Distribution, Gaussian};
 use crate::time::{today, DayCountConvention};
 
@@ -37,7 +39,8 @@ use time::Date;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Generalised Black-Scholes-Merton European Option pricing model.
-#[derive(derive_builder::Builder)]
+#[derive(derive_builder::Builder, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlackScholesMerton {
     /// The cost of carry factor.
     /// For the generalised Black-Scholes-Merton model there are five options:
@@ -77,8 +80,30 @@ pub struct BlackScholesMerton {
 
 impl Instrument for BlackScholesMerton {
     /// Returns the price (net present value) of the instrument.
-    fn price(&self) -> f64 {
-        self.price()
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The cost of carry moves with the rate, so `r - b` (e.g. the dividend
+    /// yield) is kept.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = self.evaluation_date.unwrap_or(ctx.valuation_date);
+        let r = ctx
+            .risk_free_rate(None, start, self.expiration_date)
+            .unwrap_or(self.risk_free_rate);
+
+        let option = Self {
+            cost_of_carry: self.cost_of_carry + r - self.risk_free_rate,
+            risk_free_rate: r,
+            volatility: ctx
+                .volatility(self.strike_price, self.expiration_date)
+                .unwrap_or(self.volatility),
+            evaluation_date: Some(start),
+            ..*self
+        };
+        option.validate()?;
+
+        Ok(option.price())
     }
 
     /// Returns the error on the NPV in case the pricing engine can
@@ -125,19 +150,14 @@ impl BlackScholesMerton {
     }
 
     /// Generalised Black-Scholes European Option Price.
+    ///
+    /// See [`generalised_black_scholes_merton`] for a version generic over
+    /// the scalar type.
     #[must_use]
     pub fn price(&self) -> f64 {
-        let (S, K, _, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let (d1, d2) = self.d1_d2();
-        let n = Gaussian::default();
+        let (S, K, v, r, b) = self.unpack();
 
-        match self.option_type {
-            TypeFlag::Call => S * ((b - r) * T).exp() * n.cdf(d1) - K * (-r * T).exp() * n.cdf(d2),
-            TypeFlag::Put => {
-                -S * ((b - r) * T).exp() * n.cdf(-d1) + K * (-r * T).exp() * n.cdf(-d2)
-            }
-        }
+        generalised_black_scholes_merton(S, K, v, r, b, self.year_fraction(), self.option_type)
     }
 
     /// Implied volatility.
@@ -438,6 +458,19 @@ impl BlackScholesMerton {
     }
 }
 
+impl Validate for BlackScholesMerton {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .finite("cost_of_carry", self.cost_of_carry)
+            .positive("underlying_price", self.underlying_price)
+            .positive("strike_price", self.strike_price)
+            .positive("volatility", self.volatility)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .positive("time_to_expiry", self.year_fraction())
+            .finish()
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -480,4 +513,135 @@ mod tests_black_scholes_merton {
         );
         assert_approx_equal!(bsm.price(), 2.456571166461579, RUSTQUANT_EPSILON);
     }
+
+    #[test]
+    fn test_validate() {
+        let bsm = BlackScholesMerton::new(
+            0.05,
+            100.0,
+            110.0,
+            0.2,
+            0.05,
+            Some(time::macros::date!(2024 - 01 - 01)),
+            time::macros::date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        );
+
+        assert!(bsm.validated().is_ok());
+
+        let expired = BlackScholesMerton {
+            evaluation_date: Some(time::macros::date!(2026 - 01 - 01)),
+            ..bsm
+        };
+        let negative_vol = BlackScholesMerton {
+            volatility: -0.2,
+            ..bsm
+        };
+        let nan_spot = BlackScholesMerton {
+            underlying_price: f64::NAN,
+            ..bsm
+        };
+
+        for option in [expired, negative_vol, nan_spot] {
+            assert!(matches!(
+                option.validate(),
+                Err(RustQuantError::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "autodiff")]
+    fn test_generic_scalar_types() {
+        use crate::autodiff::{Accumulate, Dual, Gradient, Graph};
+
+        let bsm = BlackScholesMerton::new(
+            0.03,
+            100.0,
+            95.0,
+            0.25,
+            0.05,
+            Some(time::macros::date!(2024 - 01 - 01)),
+            time::macros::date!(2024 - 07 - 01),
+            TypeFlag::Put,
+        );
+        let T = bsm.year_fraction();
+
+        // Reduced precision.
+        let price_f32 = generalised_black_scholes_merton(
+            100_f32,
+            95.0,
+            0.25,
+            0.05,
+            0.03,
+            T as f32,
+            TypeFlag::Put,
+        );
+        assert_approx_equal!(f64::from(price_f32), bsm.price(), 1e-4);
+
+        // Forward mode: vega.
+        let d = Dual::constant;
+        let vega = generalised_black_scholes_merton(
+            d(100.0),
+            d(95.0),
+            Dual::variable(0.25),
+            d(0.05),
+            d(0.03),
+            d(T),
+            TypeFlag::Put,
+        );
+        assert_approx_equal!(vega.value, bsm.price(), 1e-12);
+        assert_approx_equal!(vega.derivative, bsm.vega(), 1e-10);
+
+        // Reverse mode: delta and rho in one sweep.
+        let g = Graph::new();
+        let (S, r) = (g.var(100.0), g.var(0.05));
+        let price = generalised_black_scholes_merton(
+            S,
+            g.var(95.0),
+            g.var(0.25),
+            r,
+            g.var(0.03),
+            g.var(T),
+            TypeFlag::Put,
+        );
+        let grad = price.accumulate();
+
+        assert_approx_equal!(grad.wrt(&S), bsm.delta(), 1e-10);
+
+        // The cost of carry is held fixed, unlike in `BlackScholesMerton::rho`.
+        let h = 1e-6;
+        let bumped =
+            |r: f64| generalised_black_scholes_merton(100.0, 95.0, 0.25, r, 0.03, T, TypeFlag::Put);
+        assert_approx_equal!(
+            grad.wrt(&r),
+            (bumped(0.05 + h) - bumped(0.05 - h)) / (2.0 * h),
+            1e-6
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests_black_scholes_merton_serde {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn test_round_trip() {
+        let option = BlackScholesMerton::new(
+            0.05,
+            100.0,
+            110.0,
+            0.2,
+            0.05,
+            Some(date!(2024 - 01 - 01)),
+            date!(2025 - 01 - 01),
+            TypeFlag::Put,
+        );
+
+        let json = serde_json::to_string(&option).unwrap();
+        let round_trip: BlackScholesMerton = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(round_trip.price(), option.price());
+    }
 }
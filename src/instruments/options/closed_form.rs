@@ -0,0 +1,111 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Closed-form European option prices, generic over the scalar type.
+//!
+//! These only need [`Real`], so they are available without the `std`
+//! feature. The date-based pricers ([`BlackScholesMerton`] and
+//! [`Bachelier`]) are thin wrappers around them.
+//!
+//! [`BlackScholesMerton`]: crate::instruments::options::BlackScholesMerton
+//! [`Bachelier`]: crate::instruments::options::Bachelier
+
+use super::TypeFlag;
+use crate::math::Real;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Generalised Black-Scholes-Merton European option price, generic over the
+/// scalar type.
+///
+/// Evaluating with autodiff [`Variable`](crate::autodiff::Variable)s or
+/// [`Dual`](crate::autodiff::Dual) numbers gives the Greeks by differentiating
+/// the pricing formula directly:
+///
+/// ```
+/// use RustQuant::assert_approx_equal;
+/// use RustQuant::autodiff::*;
+/// use RustQuant::instruments::options::*;
+/// use RustQuant::math::distributions::{Distribution, Gaussian};
+///
+/// let g = Graph::new();
+/// let S = g.var(100.0);
+/// let v = g.var(0.2);
+/// let (K, r, b, T) = (g.var(110.0), g.var(0.05), g.var(0.05), g.var(0.5));
+///
+/// let price = generalised_black_scholes_merton(S, K, v, r, b, T, TypeFlag::Call);
+/// let grad = price.accumulate();
+///
+/// // Delta of a call (with b = r) is N(d1).
+/// let d1 = ((100.0_f64 / 110.0).ln() + (0.05 + 0.02) * 0.5) / (0.2 * 0.5_f64.sqrt());
+/// let delta = Gaussian::default().cdf(d1);
+///
+/// assert_approx_equal!(grad.wrt(&S), delta, 1e-12);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn generalised_black_scholes_merton<R: Real>(
+    S: R,
+    K: R,
+    v: R,
+    r: R,
+    b: R,
+    T: R,
+    option_type: TypeFlag,
+) -> R {
+    let sqrt_T = T.sqrt();
+
+    let d1 = ((S / K).ln() + (b + v * v * v.constant(0.5)) * T) / (v * sqrt_T);
+    let d2 = d1 - v * sqrt_T;
+
+    let forward_discount = ((b - r) * T).exp();
+    let discount = (-r * T).exp();
+
+    match option_type {
+        TypeFlag::Call => S * forward_discount * d1.norm_cdf() - K * discount * d2.norm_cdf(),
+        TypeFlag::Put => K * discount * (-d2).norm_cdf() - S * forward_discount * (-d1).norm_cdf(),
+    }
+}
+
+/// Bachelier (normal model) European option price, generic over the scalar
+/// type (see [`Real`]).
+pub fn bachelier_price<R: Real>(S: R, K: R, v: R, T: R, option_type: TypeFlag) -> R {
+    let stdev = v * T.sqrt();
+    let d1 = (S - K) / stdev;
+
+    match option_type {
+        TypeFlag::Call => (S - K) * d1.norm_cdf() + stdev * d1.norm_pdf(),
+        TypeFlag::Put => (K - S) * (-d1).norm_cdf() + stdev * (-d1).norm_pdf(),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_closed_form {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_put_call_parity() {
+        let (S, K, v, r, b, T) = (100.0, 95.0, 0.25, 0.05, 0.03, 0.75);
+
+        let call = generalised_black_scholes_merton(S, K, v, r, b, T, TypeFlag::Call);
+        let put = generalised_black_scholes_merton(S, K, v, r, b, T, TypeFlag::Put);
+        let forward = S * ((b - r) * T).exp() - K * (-r * T).exp();
+        assert_approx_equal!(call - put, forward, 1e-12);
+
+        let call = bachelier_price(S, K, 20.0, T, TypeFlag::Call);
+        let put = bachelier_price(S, K, 20.0, T, TypeFlag::Put);
+        assert_approx_equal!(call - put, S - K, 1e-12);
+    }
+}
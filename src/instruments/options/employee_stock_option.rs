@@ -0,0 +1,263 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Employee stock options (Hull and White, 2004).
+//!
+//! An employee stock option (ESO) is a long-dated American call that
+//! differs from a traded option in three ways, each an input to the model:
+//!
+//! - **Vesting:** the option cannot be exercised before the end of the
+//!   vesting period, and is forfeited if the employee leaves before then.
+//! - **Exit rate:** employees leave at a constant rate per year. After
+//!   vesting, a leaver exercises immediately if the option is in the
+//!   money, and forfeits it otherwise.
+//! - **Early exercise multiple:** a vested employee exercises as soon as
+//!   the share price reaches a multiple `M` of the strike, rather than at
+//!   the optimal (for a traded option) exercise boundary.
+//!
+//! The option is valued on a Cox-Ross-Rubinstein tree, as used for the
+//! grant-date fair value under IFRS 2 and ASC 718.
+
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Employee stock option parameters.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EmployeeStockOption {
+    /// * `S` - Initial share price.
+    pub initial_price: f64,
+    /// * `K` - Strike price.
+    pub strike_price: f64,
+    /// * `T` - Time to expiry (the contractual life of the option).
+    pub time_to_expiry: f64,
+    /// * `t_v` - Vesting period.
+    pub vesting_period: f64,
+    /// * `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// * `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// * `v` - Volatility.
+    pub volatility: f64,
+    /// * `e` - Employee exit rate, per year.
+    pub exit_rate: f64,
+    /// * `M` - Early exercise multiple of the strike, or `None` if vested
+    ///   options are only exercised optimally (as a traded American call).
+    pub exercise_multiple: Option<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl EmployeeStockOption {
+    /// Hull-White value of the option on a binomial tree of height `n`.
+    ///
+    /// At each node, the employee leaves with probability
+    /// `1 - exp(-e dt)` over the next step. After vesting, the option is
+    /// exercised once `S >= M K`, and otherwise whenever that is worth more
+    /// than holding on.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the tree height is zero.
+    pub fn price(&self, n: usize) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        if n == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Tree height must be at least 1 (got 0).".to_string(),
+            ));
+        }
+
+        let S = self.initial_price;
+        let K = self.strike_price;
+        let r = self.risk_free_rate;
+        let b = r - self.dividend_yield;
+
+        let dt = self.time_to_expiry / n as f64;
+        let u = (self.volatility * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = ((b * dt).exp() - d) / (u - d);
+        let df = (-r * dt).exp();
+        let exit = 1.0 - (-self.exit_rate * dt).exp();
+
+        // Vesting ends at the first node on or after the vesting date.
+        let vesting_step = (self.vesting_period / dt - 1e-9).ceil().max(0.0) as usize;
+
+        let price_at = |i: usize, j: usize| S * u.powi(i as i32) * d.powi((j - i) as i32);
+
+        let mut values: Vec<f64> = (0..=n).map(|i| (price_at(i, n) - K).max(0.0)).collect();
+
+        for j in (0..n).rev() {
+            for i in 0..=j {
+                let hold = df * (p * values[i + 1] + (1.0 - p) * values[i]);
+
+                values[i] = if j < vesting_step {
+                    (1.0 - exit) * hold
+                } else {
+                    let spot = price_at(i, j);
+                    let intrinsic = (spot - K).max(0.0);
+
+                    match self.exercise_multiple {
+                        Some(M) if spot >= M * K => intrinsic,
+                        _ => ((1.0 - exit) * hold + exit * intrinsic).max(intrinsic),
+                    }
+                };
+            }
+        }
+
+        Ok(values[0])
+    }
+}
+
+impl Validate for EmployeeStockOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let mut validator = Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .non_negative("vesting_period", self.vesting_period)
+            .check(self.vesting_period <= self.time_to_expiry, || {
+                format!(
+                    "vesting_period must not exceed time_to_expiry (got {} > {})",
+                    self.vesting_period, self.time_to_expiry
+                )
+            })
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("dividend_yield", self.dividend_yield)
+            .positive("volatility", self.volatility)
+            .non_negative("exit_rate", self.exit_rate);
+
+        if let Some(multiple) = self.exercise_multiple {
+            validator = validator
+                .finite("exercise_multiple", multiple)
+                .check(multiple >= 1.0, || {
+                    format!("exercise_multiple must be at least 1 (got {multiple})")
+                });
+        }
+
+        validator.finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_employee_stock_option {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::{generalised_black_scholes_merton, TypeFlag};
+
+    const ESO: EmployeeStockOption = EmployeeStockOption {
+        initial_price: 50.0,
+        strike_price: 50.0,
+        time_to_expiry: 10.0,
+        vesting_period: 3.0,
+        risk_free_rate: 0.05,
+        dividend_yield: 0.0,
+        volatility: 0.3,
+        exit_rate: 0.03,
+        exercise_multiple: Some(2.0),
+    };
+
+    fn black_scholes(option: &EmployeeStockOption) -> f64 {
+        generalised_black_scholes_merton(
+            option.initial_price,
+            option.strike_price,
+            option.volatility,
+            option.risk_free_rate,
+            option.risk_free_rate - option.dividend_yield,
+            option.time_to_expiry,
+            TypeFlag::Call,
+        )
+    }
+
+    #[test]
+    fn test_reduces_to_black_scholes() {
+        // Without dividends, exits or an exercise multiple, the option is
+        // never exercised early.
+        let option = EmployeeStockOption {
+            exit_rate: 0.0,
+            exercise_multiple: None,
+            ..ESO
+        };
+
+        assert_approx_equal!(option.price(1000).unwrap(), black_scholes(&option), 1e-2);
+    }
+
+    #[test]
+    fn test_forfeited_before_vesting() {
+        // If the option only vests at expiry, it is worth the Black-Scholes
+        // value times the probability the employee stays.
+        let option = EmployeeStockOption {
+            vesting_period: ESO.time_to_expiry,
+            ..ESO
+        };
+
+        assert_approx_equal!(
+            option.price(1000).unwrap(),
+            (-0.03_f64 * 10.0).exp() * black_scholes(&option),
+            1e-2
+        );
+    }
+
+    #[test]
+    fn test_exit_rate_and_multiple_reduce_value() {
+        let value = ESO.price(500).unwrap();
+
+        let no_exits = EmployeeStockOption {
+            exit_rate: 0.0,
+            ..ESO
+        };
+        let higher_multiple = EmployeeStockOption {
+            exercise_multiple: Some(3.0),
+            ..ESO
+        };
+
+        assert!(value < no_exits.price(500).unwrap());
+        assert!(value < higher_multiple.price(500).unwrap());
+        assert!(value < black_scholes(&ESO));
+        assert!(value > 0.0);
+    }
+
+    #[test]
+    fn test_multiple_of_one_exercises_at_the_money() {
+        // Vested and exercised as soon as the option is in the money.
+        let option = EmployeeStockOption {
+            vesting_period: 0.0,
+            exercise_multiple: Some(1.0),
+            ..ESO
+        };
+
+        assert_eq!(option.price(100).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_employee_stock_option_validation() {
+        let option = EmployeeStockOption {
+            vesting_period: 11.0,
+            ..ESO
+        };
+        assert!(option.price(100).is_err());
+
+        let option = EmployeeStockOption {
+            exercise_multiple: Some(0.5),
+            ..ESO
+        };
+        assert!(option.price(100).is_err());
+        assert!(ESO.price(0).is_err());
+    }
+}
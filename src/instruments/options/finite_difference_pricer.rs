@@ -7,9 +7,14 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::instruments::options::option::{ExerciseFlag, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::options::dividends::Dividend;
+use crate::instruments::options::option::{ExerciseBoundary, ExerciseFlag, TypeFlag};
+use crate::instruments::{Validate, Validator};
+use crate::macros::{trace_event, trace_span};
 use crate::time::{today, DayCountConvention};
 use std::cmp::Ordering;
+use std::time::Instant;
 use time::Date;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -17,6 +22,7 @@ use time::Date;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// Finite difference object
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FiniteDifferencePricer {
     /// Spot Price
     pub initial_price: f64,
@@ -41,6 +47,9 @@ pub struct FiniteDifferencePricer {
     pub type_flag: TypeFlag,
     /// Option Style
     pub exercise_flag: ExerciseFlag,
+
+    /// Discrete dividends (times in years from the evaluation date)
+    pub dividends: Vec<Dividend>,
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -49,6 +58,11 @@ pub struct FiniteDifferencePricer {
 
 impl FiniteDifferencePricer {
     /// Constructor for FiniteDifferencePricer
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::InvalidArgument`] if any of the prices, the rate,
+    /// the volatility, the time to expiry, or the grid sizes are not positive.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         initial_price: f64,
@@ -61,14 +75,7 @@ impl FiniteDifferencePricer {
         price_steps: u32,
         type_flag: TypeFlag,
         exercise_flag: ExerciseFlag,
-    ) -> Self {
-        assert!(initial_price > 0.0, "initial_price must be positive!");
-        assert!(strike_price > 0.0, "strike_price must be positive!");
-        assert!(risk_free_rate > 0.0, "risk_free_rate must be positive!");
-        assert!(volatility > 0.0, "volatility must be positive!");
-        assert!(time_steps > 0, "time_steps must be positive!");
-        assert!(price_steps > 0, "price_steps must be positive!");
-
+    ) -> Result<Self, RustQuantError> {
         Self {
             initial_price,
             strike_price,
@@ -80,7 +87,23 @@ impl FiniteDifferencePricer {
             price_steps,
             type_flag,
             exercise_flag,
+            dividends: Vec::new(),
         }
+        .validated()
+    }
+
+    /// Add discrete dividends to the pricer.
+    ///
+    /// At each ex-date, the grid values are shifted to the ex-dividend
+    /// prices (`V(S, t-) = V(S - D, t+)`), which is applied at the time step
+    /// following the ex-date.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::InvalidArgument`] if a dividend fails
+    /// [`Validate::validate`].
+    pub fn with_dividends(self, dividends: Vec<Dividend>) -> Result<Self, RustQuantError> {
+        Self { dividends, ..self }.validated()
     }
 
     fn matrix_multiply_vector(&self, A: &[Vec<f64>], v: Vec<f64>) -> Vec<f64> {
@@ -255,19 +278,60 @@ impl FiniteDifferencePricer {
             .collect()
     }
 
+    // Highest grid price at which a put (lowest at which a call) is
+    // exercised, given the continuation values `u`.
+    fn critical_price(&self, u: &[f64], price_steps: u32) -> Option<f64> {
+        let mut exercised = (0..(price_steps - 1)).filter_map(|i: u32| {
+            let s = (i + 1) as f64 * (2.0 * self.initial_price) / (price_steps as f64);
+            let exercise = self.payoff(s);
+
+            (exercise > 0.0 && exercise >= u[i as usize]).then_some(s)
+        });
+
+        match self.type_flag {
+            TypeFlag::Call => exercised.next(),
+            TypeFlag::Put => exercised.next_back(),
+        }
+    }
+
+    // Shift the values for the dividends going ex between steps `t` and
+    // `t + 1`, interpolating linearly on the grid.
+    fn dividend_step(&self, u: Vec<f64>, t: u32, delta_t: f64, price_steps: u32) -> Vec<f64> {
+        let h = 2.0 * self.initial_price / (price_steps as f64);
+
+        self.dividends
+            .iter()
+            .filter(|dividend| {
+                dividend.time() > t as f64 * delta_t && dividend.time() <= (t + 1) as f64 * delta_t
+            })
+            .fold(u, |u, dividend| {
+                (1..price_steps)
+                    .map(|i| {
+                        let x = dividend.ex_dividend_price(i as f64 * h) / h - 1.0;
+                        let j = (x.floor().max(0.0) as usize).min(u.len() - 2);
+                        let w = x - j as f64;
+
+                        u[j] * (1.0 - w) + u[j + 1] * w
+                    })
+                    .collect()
+            })
+    }
+
     fn boundary_condition_at_time_n(&self, price_steps: u32) -> Vec<f64> {
         (1..(price_steps))
             .map(|i| self.payoff(((i) as f64) * (2.0 * self.initial_price / (price_steps as f64))))
             .collect()
     }
 
+    // Values at the edges of the grid (`S = 2 S_0` for a call, `S = 0` for
+    // a put) at time step `t`, i.e. with `T - t dt` to expiry.
     fn call_boundary(&self, t: u32, T: f64, delta_t: f64) -> f64 {
         2.0 * self.initial_price
-            - self.strike_price * f64::exp(-(self.risk_free_rate * T) - (t as f64 * delta_t))
+            - self.strike_price * f64::exp(-self.risk_free_rate * (T - t as f64 * delta_t))
     }
 
     fn put_boundary(&self, t: u32, T: f64, delta_t: f64) -> f64 {
-        self.strike_price * f64::exp(-(self.risk_free_rate * T) - (t as f64 * delta_t))
+        self.strike_price * f64::exp(-self.risk_free_rate * (T - t as f64 * delta_t))
     }
 
     fn year_fraction(&self) -> f64 {
@@ -295,7 +359,24 @@ impl FiniteDifferencePricer {
 
     /// Explicit method
     pub fn explicit(&self) -> f64 {
+        self.explicit_with_boundary().0
+    }
+
+    /// Explicit method, also returning the early-exercise boundary.
+    ///
+    /// The boundary is empty unless the option is American.
+    pub fn explicit_with_boundary(&self) -> (f64, ExerciseBoundary) {
         let (T, delta_t) = self.time_structure();
+        let mut boundary = ExerciseBoundary::default();
+
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "finite_difference",
+            scheme = "explicit",
+            time_steps = self.time_steps,
+            price_steps = self.price_steps,
+        );
 
         let tridiagonal_matrix = self.create_tridiagonal_matrix(
             self.sub_diagonal(delta_t / 2.0),
@@ -306,32 +387,65 @@ impl FiniteDifferencePricer {
 
         let mut u: Vec<f64> = self.boundary_condition_at_time_n(self.price_steps);
 
-        for t in (1..self.time_steps).rev() {
+        for t in (0..self.time_steps).rev() {
             u = self.matrix_multiply_vector(&tridiagonal_matrix, u);
 
             match self.type_flag {
                 TypeFlag::Call => {
                     u[(self.price_steps - 2) as usize] +=
                         self.super_diagonal(delta_t / 2.0)((self.price_steps - 1) as f64)
-                            * self.call_boundary(t, T, delta_t);
+                            * self.call_boundary(t + 1, T, delta_t);
                 }
                 TypeFlag::Put => {
-                    u[0] +=
-                        self.sub_diagonal(delta_t / 2.0)(1.0) * self.put_boundary(t, T, delta_t);
+                    u[0] += self.sub_diagonal(delta_t / 2.0)(1.0)
+                        * self.put_boundary(t + 1, T, delta_t);
                 }
             }
 
+            u = self.dividend_step(u, t, delta_t, self.price_steps);
+
             if let ExerciseFlag::American = self.exercise_flag {
+                boundary.times.push(t as f64 * delta_t);
+                boundary
+                    .critical_prices
+                    .push(self.critical_price(&u, self.price_steps));
                 u = self.american_time_stop_step(u, self.price_steps);
             }
+
+            trace_event!(
+                TRACE,
+                "time step",
+                time_step = t,
+                elapsed = start.elapsed().as_secs_f64(),
+            );
         }
 
-        self.return_price(u)
+        boundary.times.reverse();
+        boundary.critical_prices.reverse();
+
+        (self.return_price(u), boundary)
     }
 
     /// Implicit method
     pub fn implicit(&self) -> f64 {
+        self.implicit_with_boundary().0
+    }
+
+    /// Implicit method, also returning the early-exercise boundary.
+    ///
+    /// The boundary is empty unless the option is American.
+    pub fn implicit_with_boundary(&self) -> (f64, ExerciseBoundary) {
         let (T, delta_t) = self.time_structure();
+        let mut boundary = ExerciseBoundary::default();
+
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "finite_difference",
+            scheme = "implicit",
+            time_steps = self.time_steps,
+            price_steps = self.price_steps,
+        );
 
         let inverse_matrix = self.invert_tridiagonal_matrix(self.create_tridiagonal_matrix(
             self.sub_diagonal(-delta_t / 2.0),
@@ -342,7 +456,7 @@ impl FiniteDifferencePricer {
 
         let mut u: Vec<f64> = self.boundary_condition_at_time_n(self.price_steps);
 
-        for t in (1..self.time_steps).rev() {
+        for t in (0..self.time_steps).rev() {
             match self.type_flag {
                 TypeFlag::Call => {
                     u[(self.price_steps - 2) as usize] -=
@@ -357,17 +471,50 @@ impl FiniteDifferencePricer {
 
             u = self.matrix_multiply_vector(&inverse_matrix, u);
 
+            u = self.dividend_step(u, t, delta_t, self.price_steps);
+
             if let ExerciseFlag::American = self.exercise_flag {
+                boundary.times.push(t as f64 * delta_t);
+                boundary
+                    .critical_prices
+                    .push(self.critical_price(&u, self.price_steps));
                 u = self.american_time_stop_step(u, self.price_steps);
             }
+
+            trace_event!(
+                TRACE,
+                "time step",
+                time_step = t,
+                elapsed = start.elapsed().as_secs_f64(),
+            );
         }
 
-        self.return_price(u)
+        boundary.times.reverse();
+        boundary.critical_prices.reverse();
+
+        (self.return_price(u), boundary)
     }
 
     /// Crank-Nicolson method
     pub fn crank_nicolson(&self) -> f64 {
+        self.crank_nicolson_with_boundary().0
+    }
+
+    /// Crank-Nicolson method, also returning the early-exercise boundary.
+    ///
+    /// The boundary is empty unless the option is American.
+    pub fn crank_nicolson_with_boundary(&self) -> (f64, ExerciseBoundary) {
         let (T, delta_t) = self.time_structure();
+        let mut boundary = ExerciseBoundary::default();
+
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "finite_difference",
+            scheme = "crank_nicolson",
+            time_steps = self.time_steps,
+            price_steps = self.price_steps,
+        );
 
         let inverse_past_matrix = self.invert_tridiagonal_matrix(self.create_tridiagonal_matrix(
             self.sub_diagonal(-delta_t / 4.0),
@@ -385,7 +532,7 @@ impl FiniteDifferencePricer {
 
         let mut u: Vec<f64> = self.boundary_condition_at_time_n(self.price_steps);
 
-        for t in (1..self.time_steps).rev() {
+        for t in (0..self.time_steps).rev() {
             u = self.matrix_multiply_vector(&tridiagonal_future_matrix, u);
 
             match self.type_flag {
@@ -393,22 +540,58 @@ impl FiniteDifferencePricer {
                     u[(self.price_steps - 2) as usize] +=
                         self.super_diagonal(delta_t / 4.0)((self.price_steps - 1) as f64)
                             * (self.call_boundary(t + 1, T, delta_t)
-                                - self.call_boundary(t, T, delta_t))
+                                + self.call_boundary(t, T, delta_t))
                 }
                 TypeFlag::Put => {
                     u[0] += self.sub_diagonal(delta_t / 4.0)(1.0)
-                        * (self.put_boundary(t + 1, T, delta_t) - self.put_boundary(t, T, delta_t))
+                        * (self.put_boundary(t + 1, T, delta_t) + self.put_boundary(t, T, delta_t))
                 }
             }
 
             u = self.matrix_multiply_vector(&inverse_past_matrix, u);
 
+            u = self.dividend_step(u, t, delta_t, self.price_steps);
+
             if let ExerciseFlag::American = self.exercise_flag {
+                boundary.times.push(t as f64 * delta_t);
+                boundary
+                    .critical_prices
+                    .push(self.critical_price(&u, self.price_steps));
                 u = self.american_time_stop_step(u, self.price_steps);
             }
+
+            trace_event!(
+                TRACE,
+                "time step",
+                time_step = t,
+                elapsed = start.elapsed().as_secs_f64(),
+            );
         }
 
-        self.return_price(u)
+        boundary.times.reverse();
+        boundary.critical_prices.reverse();
+
+        (self.return_price(u), boundary)
+    }
+}
+
+impl Validate for FiniteDifferencePricer {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("risk_free_rate", self.risk_free_rate)
+            .positive("volatility", self.volatility)
+            .positive("time_to_expiry", self.year_fraction())
+            .check(self.time_steps > 0, || {
+                "time_steps must be positive".to_string()
+            })
+            .check(self.price_steps > 0, || {
+                "price_steps must be positive".to_string()
+            })
+            .finish()?;
+
+        self.dividends.iter().try_for_each(Validate::validate)
     }
 }
 
@@ -434,6 +617,7 @@ mod tests_finite_difference_pricer_at_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Call,
         exercise_flag: ExerciseFlag::European,
+        dividends: Vec::new(),
     };
 
     const EUROPEAN_PUT: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -447,6 +631,7 @@ mod tests_finite_difference_pricer_at_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Put,
         exercise_flag: ExerciseFlag::European,
+        dividends: Vec::new(),
     };
 
     const AMERICAN_CALL: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -460,6 +645,7 @@ mod tests_finite_difference_pricer_at_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Call,
         exercise_flag: ExerciseFlag::American,
+        dividends: Vec::new(),
     };
 
     const AMERICAN_PUT: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -473,6 +659,7 @@ mod tests_finite_difference_pricer_at_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Put,
         exercise_flag: ExerciseFlag::American,
+        dividends: Vec::new(),
     };
 
     const EXPECT_A_CALL: f64 = 2.179_260_421_286_684_845;
@@ -539,6 +726,73 @@ mod tests_finite_difference_pricer_at_the_money {
     fn european_put_crank_nicolson() {
         assert_approx_equal!(EUROPEAN_PUT.crank_nicolson(), EXPECT_E_PUT, EPS);
     }
+
+    #[test]
+    fn american_put_exercise_boundary() {
+        let (price, boundary) = AMERICAN_PUT.crank_nicolson_with_boundary();
+
+        assert_approx_equal!(price, AMERICAN_PUT.crank_nicolson(), EPS);
+        assert_eq!(boundary.times.len(), 1000);
+        assert!(boundary.times.windows(2).all(|w| w[0] < w[1]));
+        assert!(boundary
+            .critical_prices
+            .iter()
+            .flatten()
+            .all(|&critical| critical < AMERICAN_PUT.strike_price));
+
+        let (_, boundary) = EUROPEAN_PUT.crank_nicolson_with_boundary();
+        assert!(boundary.times.is_empty());
+        assert_eq!(boundary.critical_price(0.5), None);
+    }
+
+    #[test]
+    fn european_put_discrete_dividends() {
+        // A proportional dividend scales the terminal price, so the European
+        // put is the Black-Scholes put on the scaled spot.
+        let proportional = FiniteDifferencePricer {
+            dividends: vec![Dividend::Proportional {
+                time: 0.5,
+                ratio: 0.05,
+            }],
+            ..EUROPEAN_PUT
+        };
+        assert_approx_equal!(proportional.crank_nicolson(), 1.882_786_978_280_424, 1e-2);
+
+        let cash = FiniteDifferencePricer {
+            dividends: vec![Dividend::Cash {
+                time: 0.5,
+                amount: 0.5,
+            }],
+            ..EUROPEAN_PUT
+        };
+        let american_cash = AMERICAN_PUT.with_dividends(cash.dividends.clone()).unwrap();
+        assert!(cash.crank_nicolson() > EUROPEAN_PUT.crank_nicolson());
+        assert!(american_cash.crank_nicolson() >= cash.crank_nicolson());
+
+        let invalid = EUROPEAN_PUT.with_dividends(vec![Dividend::Proportional {
+            time: 0.5,
+            ratio: 1.5,
+        }]);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn new_rejects_non_positive_inputs() {
+        let pricer = FiniteDifferencePricer::new(
+            10.0,
+            10.0,
+            0.05,
+            0.0,
+            None,
+            date!(2025 - 01 - 01),
+            1000,
+            100,
+            TypeFlag::Call,
+            ExerciseFlag::European,
+        );
+
+        assert!(matches!(pricer, Err(RustQuantError::InvalidArgument(_))));
+    }
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -563,6 +817,7 @@ mod tests_finite_difference_pricer_in_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Call,
         exercise_flag: ExerciseFlag::European,
+        dividends: Vec::new(),
     };
 
     const EUROPEAN_PUT: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -576,6 +831,7 @@ mod tests_finite_difference_pricer_in_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Put,
         exercise_flag: ExerciseFlag::European,
+        dividends: Vec::new(),
     };
 
     const AMERICAN_CALL: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -589,6 +845,7 @@ mod tests_finite_difference_pricer_in_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Call,
         exercise_flag: ExerciseFlag::American,
+        dividends: Vec::new(),
     };
 
     const AMERICAN_PUT: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -602,6 +859,7 @@ mod tests_finite_difference_pricer_in_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Put,
         exercise_flag: ExerciseFlag::American,
+        dividends: Vec::new(),
     };
 
     const EXPECT_A_CALL: f64 = 6.0644265045002292425;
@@ -692,6 +950,7 @@ mod tests_finite_difference_pricer_out_of_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Call,
         exercise_flag: ExerciseFlag::European,
+        dividends: Vec::new(),
     };
 
     const EUROPEAN_PUT: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -705,6 +964,7 @@ mod tests_finite_difference_pricer_out_of_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Put,
         exercise_flag: ExerciseFlag::European,
+        dividends: Vec::new(),
     };
 
     const AMERICAN_CALL: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -718,6 +978,7 @@ mod tests_finite_difference_pricer_out_of_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Call,
         exercise_flag: ExerciseFlag::American,
+        dividends: Vec::new(),
     };
 
     const AMERICAN_PUT: FiniteDifferencePricer = FiniteDifferencePricer {
@@ -731,6 +992,7 @@ mod tests_finite_difference_pricer_out_of_the_money {
         price_steps: 100,
         type_flag: TypeFlag::Put,
         exercise_flag: ExerciseFlag::American,
+        dividends: Vec::new(),
     };
 
     const EXPECT_A_CALL: f64 = 0.0000010140475396182350785;
@@ -14,6 +14,8 @@
 use time::Date;
 
 use crate::{
+    error::RustQuantError,
+    instruments::{options::TypeFlag, Instrument, PricingContext},
     math::distributions::{Distribution, Gaussian},
     time::{today, DayCountConvention},
 };
@@ -21,6 +23,7 @@ use crate::{
 /// Forward Start Option parameters struct
 #[allow(clippy::module_name_repetitions)]
 #[derive(derive_builder::Builder, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ForwardStartOption {
     /// `S` - Initial price of the underlying.
     pub initial_price: f64,
@@ -51,6 +54,53 @@ pub struct ForwardStartOption {
 // FORWARD START OPTION IMPLEMENTATION
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl Instrument for (ForwardStartOption, TypeFlag) {
+    /// Returns the price (net present value) of the option in the given
+    /// direction.
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The volatility is read at the strike set at the start date,
+    /// `alpha` times the initial price.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = self.0.valuation_date.unwrap_or(ctx.valuation_date);
+        let strike = self.0.alpha * self.0.initial_price;
+
+        let option = ForwardStartOption {
+            risk_free_rate: ctx
+                .risk_free_rate(None, start, self.0.end)
+                .unwrap_or(self.0.risk_free_rate),
+            volatility: ctx
+                .volatility(strike, self.0.end)
+                .unwrap_or(self.0.volatility),
+            valuation_date: Some(start),
+            ..self.0
+        };
+
+        Ok(match self.1 {
+            TypeFlag::Call => option.price().0,
+            TypeFlag::Put => option.price().1,
+        })
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        self.0.valuation_date.unwrap_or(today())
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Forward Start Option"
+    }
+}
+
 impl ForwardStartOption {
     /// Rubinstein (1990) Forward Start Option Price formula.
     /// Returns a tuple: `(call_price, put_price)`
@@ -0,0 +1,624 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Experimental GPU Monte Carlo engine (`gpu` feature).
+//!
+//! Paths of a geometric Brownian motion are generated and their payoffs
+//! evaluated in a `wgpu` compute shader, one path per invocation, so that
+//! hundreds of millions of paths can be priced on any Vulkan, Metal, DX12 or
+//! WebGPU device. Each workgroup reduces its payoffs to a sum and a sum of
+//! squares, and the host accumulates those in `f64`.
+//!
+//! Shaders are single precision, so the engine is meant for prices where
+//! the Monte Carlo error dominates `f32` rounding (a relative `1e-6` or so).
+//!
+//! [`GpuMode::Reference`] runs the same algorithm on the CPU (in parallel
+//! with rayon): the same per-path PCG streams, Box-Muller transform, `f32`
+//! arithmetic, and workgroup reduction order. The random numbers are
+//! bit-identical to the device's; prices differ only by the last-bit
+//! differences between the device's and the host's `exp`, `ln`, `sin` and
+//! `cos`, which makes it a check of the GPU results on a machine without one.
+//!
+//! ```
+//! use RustQuant::instruments::options::*;
+//!
+//! let option = GpuMonteCarloOption {
+//!     initial_price: 100.0,
+//!     strike_price: 100.0,
+//!     volatility: 0.2,
+//!     risk_free_rate: 0.05,
+//!     cost_of_carry: 0.05,
+//!     time_to_expiry: 1.0,
+//!     option_type: TypeFlag::Call,
+//!     payoff: GpuPayoff::European,
+//! };
+//!
+//! let engine = GpuMonteCarloEngine {
+//!     n_paths: 100_000,
+//!     n_steps: 1,
+//!     seed: 42,
+//!     mode: GpuMode::Reference,
+//! };
+//!
+//! let price = engine.price(&option)?;
+//!
+//! assert!((price.price - 10.4506).abs() < 4.0 * price.error.unwrap());
+//! # Ok::<(), RustQuant::error::RustQuantError>(())
+//! ```
+
+use super::TypeFlag;
+use crate::error::RustQuantError;
+use crate::instruments::validation::Validator;
+use crate::instruments::{Price, Validate};
+use crate::macros::{trace_event, trace_span};
+use rayon::prelude::*;
+use wgpu::util::DeviceExt;
+
+/// Compute shader, see `gpu_monte_carlo.wgsl`.
+const SHADER: &str = include_str!("gpu_monte_carlo.wgsl");
+
+/// Invocations per workgroup (`@workgroup_size` in the shader).
+const WORKGROUP_SIZE: usize = 256;
+
+/// Workgroups per dispatch, i.e. `2^22` paths. Well below the `65_535`
+/// workgroups per dimension that every backend supports.
+const BATCH_WORKGROUPS: usize = 16_384;
+
+/// `2 pi`, rounded to `f32` as in the shader.
+const TWO_PI: f32 = 6.283_185_5;
+
+/// `2^-24`, maps the top 24 bits of a draw to `(0, 1]`.
+const UNIT: f32 = 1.0 / 16_777_216.0;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Payoffs supported by the GPU engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuPayoff {
+    /// Payoff on the terminal price.
+    European,
+
+    /// Payoff on the arithmetic average of the prices at the end of each
+    /// time step (the initial price is not included).
+    ArithmeticAsian,
+}
+
+/// Where the GPU engine runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuMode {
+    /// On the default high-performance `wgpu` adapter.
+    Device,
+
+    /// The shader's algorithm, run on the CPU, for validating [`GpuMode::Device`].
+    Reference,
+}
+
+/// Option on a geometric Brownian motion, priced by [`GpuMonteCarloEngine`].
+#[derive(Debug, Clone, Copy)]
+pub struct GpuMonteCarloOption {
+    /// Initial price of the underlying.
+    pub initial_price: f64,
+    /// Strike price.
+    pub strike_price: f64,
+    /// Volatility of the underlying.
+    pub volatility: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Cost of carry (`r - q` for a dividend yield `q`).
+    pub cost_of_carry: f64,
+    /// Time to expiry in years.
+    pub time_to_expiry: f64,
+    /// Call or put.
+    pub option_type: TypeFlag,
+    /// Payoff style.
+    pub payoff: GpuPayoff,
+}
+
+/// Monte Carlo engine generating paths and evaluating payoffs on a GPU.
+///
+/// Paths use exact log-normal steps, so European options only need
+/// `n_steps = 1`.
+#[derive(Debug, Clone, Copy)]
+pub struct GpuMonteCarloEngine {
+    /// Number of simulated paths (at most `u32::MAX`).
+    pub n_paths: usize,
+    /// Number of time steps per path (at most `u32::MAX`).
+    pub n_steps: usize,
+    /// Seed for the per-path random number streams.
+    pub seed: u32,
+    /// Device or CPU reference.
+    pub mode: GpuMode,
+}
+
+/// Shader parameters for one dispatch. Field order and layout match
+/// `Params` in the shader.
+#[derive(Debug, Clone, Copy)]
+struct Params {
+    path_offset: u32,
+    n_paths: u32,
+    n_steps: u32,
+    seed: u32,
+    asian: bool,
+    phi: f32,
+    log_spot: f32,
+    strike: f32,
+    drift: f32,
+    diffusion: f32,
+    discount: f32,
+}
+
+/// PCG stream of one path.
+struct Rng {
+    state: u32,
+    increment: u32,
+}
+
+/// Device, queue and pipeline, created once per call to
+/// [`GpuMonteCarloEngine::price`].
+struct Context {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Validate for GpuMonteCarloOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("volatility", self.volatility)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("cost_of_carry", self.cost_of_carry)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finish()
+    }
+}
+
+impl Default for GpuMonteCarloEngine {
+    fn default() -> Self {
+        Self {
+            n_paths: 1 << 24,
+            n_steps: 1,
+            seed: 42,
+            mode: GpuMode::Device,
+        }
+    }
+}
+
+impl GpuMonteCarloEngine {
+    /// Price the option.
+    ///
+    /// # Errors
+    /// * [`RustQuantError::InvalidArgument`] if the option is invalid,
+    ///   `n_paths < 2`, `n_steps == 0`, or either does not fit in a `u32`.
+    /// * [`RustQuantError::ComputationError`] if no GPU adapter is available
+    ///   or the device fails (in [`GpuMode::Device`]).
+    pub fn price(&self, option: &GpuMonteCarloOption) -> Result<Price, RustQuantError> {
+        option.validate()?;
+
+        let n_paths = u32::try_from(self.n_paths).ok().filter(|&n| n >= 2);
+        let n_steps = u32::try_from(self.n_steps).ok().filter(|&n| n >= 1);
+
+        let (Some(n_paths), Some(n_steps)) = (n_paths, n_steps) else {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "n_paths must be in [2, 2^32) and n_steps in [1, 2^32) (got {} and {})",
+                self.n_paths, self.n_steps
+            )));
+        };
+
+        let dt = option.time_to_expiry / f64::from(n_steps);
+        let v = option.volatility;
+
+        let base = Params {
+            path_offset: 0,
+            n_paths: 0,
+            n_steps,
+            seed: self.seed,
+            asian: option.payoff == GpuPayoff::ArithmeticAsian,
+            phi: option.option_type as i32 as f32,
+            log_spot: option.initial_price.ln() as f32,
+            strike: option.strike_price as f32,
+            drift: ((option.cost_of_carry - 0.5 * v * v) * dt) as f32,
+            diffusion: (v * dt.sqrt()) as f32,
+            discount: (-option.risk_free_rate * option.time_to_expiry).exp() as f32,
+        };
+
+        let context = match self.mode {
+            GpuMode::Device => Some(Context::new()?),
+            GpuMode::Reference => None,
+        };
+
+        let _span = trace_span!(
+            DEBUG,
+            "gpu_monte_carlo",
+            paths = n_paths,
+            steps = n_steps,
+            seed = self.seed,
+        );
+
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        let batch_paths = (BATCH_WORKGROUPS * WORKGROUP_SIZE) as u32;
+
+        for path_offset in (0..n_paths).step_by(batch_paths as usize) {
+            let params = Params {
+                path_offset,
+                n_paths: batch_paths.min(n_paths - path_offset),
+                ..base
+            };
+
+            let partials = match &context {
+                Some(context) => context.run(&params)?,
+                None => reference_partials(&params),
+            };
+
+            for [s, s2] in partials {
+                sum += f64::from(s);
+                sum_sq += f64::from(s2);
+            }
+
+            trace_event!(
+                DEBUG,
+                "batch",
+                paths = path_offset + params.n_paths,
+                estimate = sum / f64::from(path_offset + params.n_paths),
+            );
+        }
+
+        let n = f64::from(n_paths);
+        let mean = sum / n;
+        let variance = (sum_sq / n - mean * mean).max(0.0) * n / (n - 1.0);
+
+        Ok(Price {
+            price: mean,
+            error: Some((variance / n).sqrt()),
+        })
+    }
+}
+
+impl Params {
+    /// Number of workgroups to dispatch.
+    fn workgroups(&self) -> usize {
+        (self.n_paths as usize).div_ceil(WORKGROUP_SIZE)
+    }
+
+    /// Uniform buffer contents.
+    fn to_words(self) -> [u32; 12] {
+        [
+            self.path_offset,
+            self.n_paths,
+            self.n_steps,
+            self.seed,
+            u32::from(self.asian),
+            self.phi.to_bits(),
+            self.log_spot.to_bits(),
+            self.strike.to_bits(),
+            self.drift.to_bits(),
+            self.diffusion.to_bits(),
+            self.discount.to_bits(),
+            0,
+        ]
+    }
+
+    /// Discounted payoff of one path, as `discounted_payoff` in the shader.
+    fn discounted_payoff(&self, path: u32) -> f32 {
+        let mut rng = Rng {
+            state: hash(self.seed ^ hash(path)),
+            increment: (path << 1) | 1,
+        };
+
+        let mut x = self.log_spot;
+        let mut sum = 0.0_f32;
+        let mut spare = 0.0_f32;
+
+        for step in 0..self.n_steps {
+            let z = if step & 1 == 0 {
+                let r = (-2.0 * rng.next_uniform().ln()).sqrt();
+                let theta = TWO_PI * rng.next_uniform();
+                spare = r * theta.sin();
+                r * theta.cos()
+            } else {
+                spare
+            };
+
+            x = x + self.drift + self.diffusion * z;
+            sum += x.exp();
+        }
+
+        let s = match self.asian {
+            true => sum / self.n_steps as f32,
+            false => x.exp(),
+        };
+
+        self.discount * (self.phi * (s - self.strike)).max(0.0)
+    }
+}
+
+impl Rng {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(747_796_405).wrapping_add(self.increment);
+        permute(old)
+    }
+
+    fn next_uniform(&mut self) -> f32 {
+        ((self.next_u32() >> 8) + 1) as f32 * UNIT
+    }
+}
+
+impl Context {
+    fn new() -> Result<Self, RustQuantError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .ok_or_else(|| RustQuantError::ComputationError("no GPU adapter available".into()))?;
+
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+                .map_err(|e| RustQuantError::ComputationError(e.to_string()))?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("gpu_monte_carlo"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("gpu_monte_carlo"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        Ok(Self {
+            device,
+            queue,
+            pipeline,
+        })
+    }
+
+    /// Runs one dispatch and reads back the per-workgroup `[sum, sum_sq]`.
+    fn run(&self, params: &Params) -> Result<Vec<[f32; 2]>, RustQuantError> {
+        let size = (params.workgroups() * std::mem::size_of::<[f32; 2]>()) as u64;
+
+        let uniforms = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("params"),
+                contents: bytemuck::cast_slice(&params.to_words()),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let partials = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("partials"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("staging"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniforms.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: partials.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(params.workgroups() as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&partials, 0, &staging, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+
+        receiver
+            .recv()
+            .map_err(|e| RustQuantError::ComputationError(e.to_string()))?
+            .map_err(|e| RustQuantError::ComputationError(e.to_string()))?;
+
+        let view = slice.get_mapped_range();
+        let result = bytemuck::cast_slice::<u8, [f32; 2]>(&view).to_vec();
+        drop(view);
+        staging.unmap();
+
+        Ok(result)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// PCG-RXS-M-XS output permutation of a 32-bit LCG state.
+fn permute(state: u32) -> u32 {
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277_803_737);
+    (word >> 22) ^ word
+}
+
+/// Stateless hash, used to seed each path's stream.
+fn hash(x: u32) -> u32 {
+    permute(x.wrapping_mul(747_796_405).wrapping_add(2_891_336_453))
+}
+
+/// CPU version of one dispatch: per-workgroup `[sum, sum_sq]`, reduced in
+/// the same order as the shader.
+fn reference_partials(params: &Params) -> Vec<[f32; 2]> {
+    (0..params.workgroups())
+        .into_par_iter()
+        .map(|workgroup| {
+            let mut scratch = [[0.0_f32; 2]; WORKGROUP_SIZE];
+
+            for (local, slot) in scratch.iter_mut().enumerate() {
+                let index = (workgroup * WORKGROUP_SIZE + local) as u32;
+
+                if index < params.n_paths {
+                    let value = params.discounted_payoff(params.path_offset + index);
+                    *slot = [value, value * value];
+                }
+            }
+
+            let mut stride = WORKGROUP_SIZE / 2;
+            while stride > 0 {
+                for local in 0..stride {
+                    scratch[local][0] += scratch[local + stride][0];
+                    scratch[local][1] += scratch[local + stride][1];
+                }
+                stride /= 2;
+            }
+
+            scratch[0]
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_gpu_monte_carlo {
+    use super::*;
+
+    const OPTION: GpuMonteCarloOption = GpuMonteCarloOption {
+        initial_price: 100.0,
+        strike_price: 100.0,
+        volatility: 0.2,
+        risk_free_rate: 0.05,
+        cost_of_carry: 0.05,
+        time_to_expiry: 1.0,
+        option_type: TypeFlag::Call,
+        payoff: GpuPayoff::European,
+    };
+
+    const REFERENCE: GpuMonteCarloEngine = GpuMonteCarloEngine {
+        n_paths: 200_000,
+        n_steps: 1,
+        seed: 1234,
+        mode: GpuMode::Reference,
+    };
+
+    #[test]
+    fn test_reference_european() {
+        // Black-Scholes prices.
+        for (option_type, expected) in [(TypeFlag::Call, 10.450_584), (TypeFlag::Put, 5.573_526)] {
+            let option = GpuMonteCarloOption {
+                option_type,
+                ..OPTION
+            };
+            let price = REFERENCE.price(&option).unwrap();
+
+            assert!((price.price - expected).abs() < 4.0 * price.error.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_reference_asian() {
+        let engine = GpuMonteCarloEngine {
+            n_steps: 12,
+            ..REFERENCE
+        };
+        let asian = GpuMonteCarloOption {
+            payoff: GpuPayoff::ArithmeticAsian,
+            ..OPTION
+        };
+
+        let price = engine.price(&asian).unwrap();
+        let european = engine.price(&OPTION).unwrap();
+
+        // Averaging lowers the volatility of the underlying of the payoff.
+        assert!(price.price > 0.0 && price.price < european.price);
+    }
+
+    #[test]
+    fn test_reference_is_deterministic() {
+        let a = REFERENCE.price(&OPTION).unwrap();
+        let b = REFERENCE.price(&OPTION).unwrap();
+        let c = GpuMonteCarloEngine {
+            seed: 4321,
+            ..REFERENCE
+        }
+        .price(&OPTION)
+        .unwrap();
+
+        assert_eq!(a.price.to_bits(), b.price.to_bits());
+        assert_ne!(a.price.to_bits(), c.price.to_bits());
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let engine = GpuMonteCarloEngine {
+            n_steps: 0,
+            ..REFERENCE
+        };
+        let option = GpuMonteCarloOption {
+            volatility: -0.2,
+            ..OPTION
+        };
+
+        assert!(engine.price(&OPTION).is_err());
+        assert!(REFERENCE.price(&option).is_err());
+    }
+
+    #[test]
+    fn test_device_matches_reference() {
+        let device = GpuMonteCarloEngine {
+            mode: GpuMode::Device,
+            n_steps: 12,
+            ..REFERENCE
+        };
+
+        // Skip on machines without a GPU adapter.
+        let Ok(price) = device.price(&OPTION) else {
+            return;
+        };
+        let reference = GpuMonteCarloEngine {
+            mode: GpuMode::Reference,
+            ..device
+        }
+        .price(&OPTION)
+        .unwrap();
+
+        assert!((price.price - reference.price).abs() < 1e-4 * reference.price);
+    }
+}
@@ -0,0 +1,126 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Monte Carlo path generation and payoff evaluation under geometric Brownian
+// motion, one path per invocation.
+//
+// Each workgroup reduces its discounted payoffs to a (sum, sum of squares)
+// pair; the host accumulates the pairs in `f64`.
+//
+// `gpu_monte_carlo.rs` mirrors this file line by line for the reference
+// mode, so any change here must be made there as well.
+
+struct Params {
+    path_offset: u32,
+    n_paths: u32,
+    n_steps: u32,
+    seed: u32,
+    asian: u32,
+    phi: f32,
+    log_spot: f32,
+    strike: f32,
+    drift: f32,
+    diffusion: f32,
+    discount: f32,
+    _padding: u32,
+}
+
+struct Rng {
+    state: u32,
+    increment: u32,
+}
+
+const WORKGROUP_SIZE: u32 = 256u;
+const TWO_PI: f32 = 6.2831855;
+
+// 2^-24: maps the top 24 bits of a draw to (0, 1].
+const UNIT: f32 = 5.9604645e-8;
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> partials: array<vec2<f32>>;
+
+var<workgroup> scratch: array<vec2<f32>, WORKGROUP_SIZE>;
+
+// PCG-RXS-M-XS output permutation of a 32-bit LCG state.
+fn permute(state: u32) -> u32 {
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+// Stateless hash, used to seed each path's stream.
+fn hash(x: u32) -> u32 {
+    return permute(x * 747796405u + 2891336453u);
+}
+
+fn next_u32(rng: ptr<function, Rng>) -> u32 {
+    let old = (*rng).state;
+    (*rng).state = old * 747796405u + (*rng).increment;
+    return permute(old);
+}
+
+fn next_uniform(rng: ptr<function, Rng>) -> f32 {
+    return f32((next_u32(rng) >> 8u) + 1u) * UNIT;
+}
+
+fn discounted_payoff(path: u32) -> f32 {
+    var rng = Rng(hash(params.seed ^ hash(path)), (path << 1u) | 1u);
+
+    var x = params.log_spot;
+    var sum = 0.0;
+    var spare = 0.0;
+
+    for (var step = 0u; step < params.n_steps; step++) {
+        // Box-Muller: one pair of uniforms gives two normals.
+        var z: f32;
+        if (step & 1u) == 0u {
+            let r = sqrt(-2.0 * log(next_uniform(&rng)));
+            let theta = TWO_PI * next_uniform(&rng);
+            z = r * cos(theta);
+            spare = r * sin(theta);
+        } else {
+            z = spare;
+        }
+
+        x = x + params.drift + params.diffusion * z;
+        sum = sum + exp(x);
+    }
+
+    var s = exp(x);
+    if params.asian != 0u {
+        s = sum / f32(params.n_steps);
+    }
+
+    return params.discount * max(params.phi * (s - params.strike), 0.0);
+}
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+    @builtin(local_invocation_index) local_id: u32,
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+) {
+    var value = 0.0;
+    if global_id.x < params.n_paths {
+        value = discounted_payoff(params.path_offset + global_id.x);
+    }
+
+    scratch[local_id] = vec2<f32>(value, value * value);
+    workgroupBarrier();
+
+    for (var stride = WORKGROUP_SIZE / 2u; stride > 0u; stride = stride >> 1u) {
+        if local_id < stride {
+            scratch[local_id] = scratch[local_id] + scratch[local_id + stride];
+        }
+        workgroupBarrier();
+    }
+
+    if local_id == 0u {
+        partials[workgroup_id.x] = scratch[0];
+    }
+}
@@ -8,12 +8,148 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::{
+    error::RustQuantError,
+    instruments::{options::TypeFlag, Instrument, PricingContext},
     math::integrate,
     time::{today, DayCountConvention},
 };
 use num::Complex;
 use time::Date;
 
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// European option under the Heston (1993) model, priced with [`heston`].
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HestonOption {
+    /// `S0` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `V0` - Initial variance.
+    pub initial_variance: f64,
+    /// `K` - Strike price.
+    pub strike_price: f64,
+    /// `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// `rho` - Correlation between the two Brownian motions.
+    pub correlation: f64,
+    /// `sigma` - Volatility-of-volatility.
+    pub volatility_of_volatility: f64,
+    /// `kappa` - Mean reversion rate of the variance.
+    pub mean_reversion_rate: f64,
+    /// `theta` - Long run mean of the variance.
+    pub long_run_variance: f64,
+
+    /// `evaluation_date` - Valuation date.
+    pub evaluation_date: Option<Date>,
+    /// `expiration_date` - Expiry date.
+    pub expiration_date: Date,
+
+    /// Call or put flag.
+    pub type_flag: TypeFlag,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Instrument for HestonOption {
+    /// Returns the price (net present value) of the instrument.
+    ///
+    /// The risk-free rate is read off the context (see
+    /// [`PricingContext::risk_free_rate`]), keeping the option's own if the
+    /// context has none. The variance follows the model's own dynamics.
+    ///
+    /// The characteristic functions are memoised in the context's cache, so
+    /// options that differ only in strike or type share their evaluations.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = self.evaluation_date.unwrap_or(ctx.valuation_date);
+        let r = ctx
+            .risk_free_rate(None, start, self.expiration_date)
+            .unwrap_or(self.risk_free_rate);
+        let tau = DayCountConvention::default().day_count_factor(start, self.expiration_date);
+
+        let Self {
+            initial_price: S0,
+            initial_variance: V0,
+            dividend_yield: q,
+            correlation: rho,
+            volatility_of_volatility: sigma,
+            mean_reversion_rate: kappa,
+            long_run_variance: theta,
+            ..
+        } = *self;
+
+        // The cache key holds the expiry and the argument, so the id holds
+        // the rest of what the characteristic function depends on.
+        let ids = [1, 2].map(|j| {
+            format!("Heston f{j} {start} {S0} {V0} {r} {q} {rho} {sigma} {kappa} {theta}")
+        });
+
+        let (call, put) = heston_with(S0, self.strike_price, r, q, tau, |j, phi| {
+            ctx.cache.characteristic_function(
+                &ids[usize::from(j - 1)],
+                self.expiration_date,
+                phi,
+                || {
+                    heston_characteristic_function(
+                        j, phi, S0, V0, r, q, rho, sigma, kappa, theta, tau,
+                    )
+                },
+            )
+        });
+
+        Ok(match self.type_flag {
+            TypeFlag::Call => call,
+            TypeFlag::Put => put,
+        })
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        self.evaluation_date.unwrap_or(today())
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Heston European Option"
+    }
+}
+
+impl HestonOption {
+    /// Heston (1993) option price.
+    #[must_use]
+    pub fn price(&self) -> f64 {
+        let (call, put) = heston(
+            self.initial_price,
+            self.initial_variance,
+            self.strike_price,
+            self.risk_free_rate,
+            self.dividend_yield,
+            self.correlation,
+            self.volatility_of_volatility,
+            self.mean_reversion_rate,
+            self.long_run_variance,
+            self.evaluation_date,
+            self.expiration_date,
+        );
+
+        match self.type_flag {
+            TypeFlag::Call => call,
+            TypeFlag::Put => put,
+        }
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -39,6 +175,59 @@ pub fn heston(
     let tau = DayCountConvention::default()
         .day_count_factor(evaluation_date.unwrap_or(today()), expiration_date);
 
+    heston_with(S0, K, r, q, tau, |j, phi| {
+        heston_characteristic_function(j, phi, S0, V0, r, q, rho, sigma, kappa, theta, tau)
+    })
+}
+
+/// Heston call and put prices given the characteristic functions `f(j, phi)`
+/// of the two probabilities `P_j`, `j = 1, 2`.
+fn heston_with<F>(S0: f64, K: f64, r: f64, q: f64, tau: f64, f: F) -> (f64, f64)
+where
+    F: Fn(u8, f64) -> Complex<f64>,
+{
+    // i = sqrt(-1). Used frequently, so assign here.
+    let i: Complex<f64> = Complex::i();
+
+    // These functions return the integrand for P1 and P2.
+    let Re1 = |phi: f64| -> f64 {
+        let j = 1;
+
+        (f(j, phi) * (-i * phi * K.ln()).exp() / (i * phi)).re
+    };
+    let Re2 = |phi: f64| -> f64 {
+        let j = 2;
+
+        (f(j, phi) * (-i * phi * K.ln()).exp() / (i * phi)).re
+    };
+
+    // Integration bounds given in Fabrice D. Rouah's book (see tests).
+    // The integral decays rapidly so 50 is probably enough.
+    let P1 = 0.5 + std::f64::consts::FRAC_1_PI * integrate(Re1, 0.00001, 50.0);
+    let P2 = 0.5 + std::f64::consts::FRAC_1_PI * integrate(Re2, 0.00001, 50.0);
+
+    // Price call, then use put-call-parity for the put.
+    let call = S0 * (-q * tau).exp() * P1 - K * (-r * tau).exp() * P2;
+    let put = call + K * (-r * tau).exp() - S0 * (-q * tau).exp();
+
+    (call, put)
+}
+
+/// The Heston characteristic function `f_j(phi)` of the probability `P_j`.
+#[allow(clippy::too_many_arguments)]
+fn heston_characteristic_function(
+    j: u8,
+    phi: f64,
+    S0: f64,
+    V0: f64,
+    r: f64,
+    q: f64,
+    rho: f64,
+    sigma: f64,
+    kappa: f64,
+    theta: f64,
+    tau: f64,
+) -> Complex<f64> {
     // Market price of volatility risk (set to 0 for simplicity).
     // Should probably include, though, since for equity options it has been shown
     // to be non-zero (Lamoureux & Lastrapes, 1993).
@@ -91,35 +280,9 @@ pub fn heston(
             / (sigma.powi(2) * (1.0 - g(j, phi) * (d(j, phi) * tau).exp()))
     };
 
-    // The Heston characteristic functions.
-    let f = |j: u8, phi: f64| -> Complex<f64> {
-        assert!(j == 1 || j == 2);
-
-        (C(j, phi) + D(j, phi) * V0 + i * phi * S0.ln()).exp()
-    };
+    assert!(j == 1 || j == 2);
 
-    // These functions return the integrand for P1 and P2.
-    let Re1 = |phi: f64| -> f64 {
-        let j = 1;
-
-        (f(j, phi) * (-i * phi * K.ln()).exp() / (i * phi)).re
-    };
-    let Re2 = |phi: f64| -> f64 {
-        let j = 2;
-
-        (f(j, phi) * (-i * phi * K.ln()).exp() / (i * phi)).re
-    };
-
-    // Integration bounds given in Fabrice D. Rouah's book (see tests).
-    // The integral decays rapidly so 50 is probably enough.
-    let P1 = 0.5 + std::f64::consts::FRAC_1_PI * integrate(Re1, 0.00001, 50.0);
-    let P2 = 0.5 + std::f64::consts::FRAC_1_PI * integrate(Re2, 0.00001, 50.0);
-
-    // Price call, then use put-call-parity for the put.
-    let call = S0 * (-q * tau).exp() * P1 - K * (-r * tau).exp() * P2;
-    let put = call + K * (-r * tau).exp() - S0 * (-q * tau).exp();
-
-    (call, put)
+    (C(j, phi) + D(j, phi) * V0 + i * phi * S0.ln()).exp()
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -149,6 +312,40 @@ mod tests {
     use crate::{assert_approx_equal, RUSTQUANT_EPSILON};
     use time::Duration;
 
+    #[test]
+    fn test_heston_characteristic_function_cache() {
+        let ctx = PricingContext::new(today());
+        let option = HestonOption {
+            initial_price: 100.0,
+            initial_variance: 0.05,
+            strike_price: 100.0,
+            risk_free_rate: 0.03,
+            dividend_yield: 0.02,
+            correlation: -0.8,
+            volatility_of_volatility: 0.5,
+            mean_reversion_rate: 5.0,
+            long_run_variance: 0.05,
+            evaluation_date: None,
+            expiration_date: today() + Duration::days(183),
+            type_flag: TypeFlag::Put,
+        };
+        let otm = HestonOption {
+            strike_price: 90.0,
+            ..option
+        };
+
+        let price = Instrument::price(&option, &ctx).unwrap();
+        let misses = ctx.cache.stats().misses;
+        assert!(misses > 0);
+
+        // Another strike reuses the characteristic function evaluations.
+        let otm_price = Instrument::price(&otm, &ctx).unwrap();
+        assert_eq!(ctx.cache.stats().misses, misses);
+
+        assert_approx_equal!(price, option.price(), 1e-10);
+        assert_approx_equal!(otm_price, otm.price(), 1e-10);
+    }
+
     #[test]
     fn test_heston_options() {
         // 6 Month expiry.
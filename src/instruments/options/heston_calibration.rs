@@ -0,0 +1,756 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Heston model calibration to an implied volatility surface.
+//!
+//! Prices come from the Carr-Madan FFT (see [`carr_madan_prices`]), with the
+//! characteristic function in the "little Heston trap" form of Albrecher et
+//! al. (2007), which avoids the branch-cut discontinuities of the original.
+//!
+//! The calibration minimises the weighted sum of squared vega-scaled price
+//! errors of the out-of-the-money options (approximately the squared
+//! implied volatility errors), optionally plus a penalty on violations of
+//! the Feller condition `2 kappa theta >= sigma^2`. The parameters are
+//! mapped to an unconstrained space and the objective minimised with
+//! Nelder-Mead from several starting points, keeping the best fit.
+
+use super::{
+    carr_madan_prices, generalised_black_scholes_merton, try_implied_volatility, TypeFlag,
+};
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+use crate::math::Real;
+use num::Complex;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Heston model parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HestonParameters {
+    /// Initial variance (`v0`).
+    pub initial_variance: f64,
+    /// Long-run variance (`theta`).
+    pub long_run_variance: f64,
+    /// Mean reversion rate of the variance (`kappa`).
+    pub mean_reversion_rate: f64,
+    /// Volatility of the variance (`sigma`).
+    pub volatility_of_volatility: f64,
+    /// Correlation between the asset and its variance (`rho`).
+    pub correlation: f64,
+}
+
+/// Implied volatility quote on the surface to calibrate to.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SurfaceQuote {
+    /// Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// Strike price.
+    pub strike: f64,
+    /// Implied volatility.
+    pub volatility: f64,
+    /// Weight of the quote in the objective, e.g. from its bid/ask spread.
+    pub weight: f64,
+}
+
+/// Quality of the fit at one expiry, in implied volatility terms.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpiryFit {
+    /// Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// Number of quotes at the expiry.
+    pub quotes: usize,
+    /// Root mean square implied volatility error.
+    pub rmse: f64,
+    /// Largest absolute implied volatility error.
+    pub max_error: f64,
+}
+
+/// Result of a Heston calibration.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HestonCalibration {
+    /// Calibrated parameters.
+    pub parameters: HestonParameters,
+    /// Objective at the calibrated parameters (including any penalty).
+    pub objective: f64,
+    /// Nelder-Mead iterations taken from the best starting point.
+    pub iterations: usize,
+    /// Fit diagnostics per expiry, by increasing time to expiry.
+    pub expiry_fits: Vec<ExpiryFit>,
+}
+
+/// Calibrates the Heston model to an implied volatility surface.
+#[derive(Debug, Clone)]
+pub struct HestonCalibrator {
+    /// Spot price of the underlying.
+    pub spot: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Dividend yield.
+    pub dividend_yield: f64,
+    /// Quotes to calibrate to.
+    pub quotes: Vec<SurfaceQuote>,
+    /// Weight of the squared Feller violation `max(sigma^2 - 2 kappa theta, 0)^2`
+    /// in the objective. Zero (the default) leaves the condition free.
+    pub feller_penalty: f64,
+    /// Starting points. If empty (the default), a few standard starts are
+    /// built around the average quoted variance.
+    pub starts: Vec<HestonParameters>,
+    /// Maximum Nelder-Mead iterations per start.
+    pub max_iterations: usize,
+}
+
+// Quotes grouped by expiry, as out-of-the-money option prices with their
+// vegas, shared by the transform-based calibrators.
+pub(crate) struct SurfaceTargets {
+    spot: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    expiries: Vec<(f64, Vec<Target>)>,
+}
+
+// Market price and scaling of one quote.
+struct Target {
+    strike: f64,
+    option_type: TypeFlag,
+    volatility: f64,
+    price: f64,
+    vega: f64,
+    weight: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl HestonParameters {
+    /// Feller gap `sigma^2 - 2 kappa theta`: positive when the Feller
+    /// condition is violated and the variance can reach zero.
+    #[must_use]
+    pub fn feller_gap(&self) -> f64 {
+        self.volatility_of_volatility.powi(2)
+            - 2.0 * self.mean_reversion_rate * self.long_run_variance
+    }
+
+    /// Whether the Feller condition `2 kappa theta >= sigma^2` holds.
+    #[must_use]
+    pub fn satisfies_feller(&self) -> bool {
+        self.feller_gap() <= 0.0
+    }
+
+    /// European option prices for one expiry via the Carr-Madan FFT.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if [`carr_madan_prices`] fails.
+    pub fn prices_fft(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        strikes: &[f64],
+        option_type: TypeFlag,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        self.validate()?;
+
+        carr_madan_prices(
+            |u| self.characteristic_function(u, T, r - q),
+            S,
+            r,
+            q,
+            T,
+            strikes,
+            option_type,
+        )
+    }
+
+    // Characteristic function of ln(S_T / S_0), with cost of carry `b`.
+    pub(crate) fn characteristic_function(&self, u: Complex<f64>, T: f64, b: f64) -> Complex<f64> {
+        let v0 = self.initial_variance;
+        let theta = self.long_run_variance;
+        let kappa = self.mean_reversion_rate;
+        let sigma = self.volatility_of_volatility;
+        let rho = self.correlation;
+
+        let iu = Complex::<f64>::i() * u;
+        let beta = kappa - rho * sigma * iu;
+        let d = (beta * beta + sigma * sigma * (iu + u * u)).sqrt();
+        let g = (beta - d) / (beta + d);
+        let e = (-d * T).exp();
+
+        let C = b * iu * T
+            + kappa * theta / (sigma * sigma)
+                * ((beta - d) * T - 2.0 * ((1.0 - g * e) / (1.0 - g)).ln());
+        let D = (beta - d) / (sigma * sigma) * (1.0 - e) / (1.0 - g * e);
+
+        (C + D * v0).exp()
+    }
+
+    // Map to and from the unconstrained space of the optimiser.
+    pub(crate) fn to_unconstrained(self) -> Vec<f64> {
+        vec![
+            self.initial_variance.ln(),
+            self.long_run_variance.ln(),
+            self.mean_reversion_rate.ln(),
+            self.volatility_of_volatility.ln(),
+            self.correlation.atanh(),
+        ]
+    }
+
+    pub(crate) fn from_unconstrained(x: &[f64]) -> Self {
+        Self {
+            initial_variance: x[0].exp(),
+            long_run_variance: x[1].exp(),
+            mean_reversion_rate: x[2].exp(),
+            volatility_of_volatility: x[3].exp(),
+            correlation: x[4].tanh(),
+        }
+    }
+}
+
+impl Validate for HestonParameters {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_variance", self.initial_variance)
+            .positive("long_run_variance", self.long_run_variance)
+            .positive("mean_reversion_rate", self.mean_reversion_rate)
+            .positive("volatility_of_volatility", self.volatility_of_volatility)
+            .finite("correlation", self.correlation)
+            .check(self.correlation.abs() < 1.0, || {
+                format!("correlation must lie in (-1, 1) (got {})", self.correlation)
+            })
+            .finish()
+    }
+}
+
+impl Validate for SurfaceQuote {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("time_to_expiry", self.time_to_expiry)
+            .positive("strike", self.strike)
+            .positive("volatility", self.volatility)
+            .non_negative("weight", self.weight)
+            .finish()
+    }
+}
+
+impl HestonCalibrator {
+    /// New calibrator, with no Feller penalty, the default starting points,
+    /// and at most 2000 iterations per start.
+    #[must_use]
+    pub fn new(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        quotes: &[SurfaceQuote],
+    ) -> Self {
+        Self {
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            quotes: quotes.to_vec(),
+            feller_penalty: 0.0,
+            starts: Vec::new(),
+            max_iterations: 2000,
+        }
+    }
+
+    /// Penalise violations of the Feller condition with the given weight.
+    #[must_use]
+    pub fn with_feller_penalty(self, feller_penalty: f64) -> Self {
+        Self {
+            feller_penalty,
+            ..self
+        }
+    }
+
+    /// Start the optimiser from the given parameters.
+    #[must_use]
+    pub fn with_starts(self, starts: Vec<HestonParameters>) -> Self {
+        Self { starts, ..self }
+    }
+
+    /// Maximum Nelder-Mead iterations per start.
+    #[must_use]
+    pub fn with_max_iterations(self, max_iterations: usize) -> Self {
+        Self {
+            max_iterations,
+            ..self
+        }
+    }
+
+    /// Calibrate the model, keeping the best fit over the starting points.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the calibrator fails
+    ///   [`Validate::validate`], or if a strike is outside the FFT grid.
+    /// * [`RustQuantError::NonConvergence`] if no start reaches a finite
+    ///   objective.
+    pub fn calibrate(&self) -> Result<HestonCalibration, RustQuantError> {
+        self.validate()?;
+
+        let (S, r, q) = (self.spot, self.risk_free_rate, self.dividend_yield);
+        let targets = SurfaceTargets::new(S, r, q, &self.quotes);
+
+        let objective = |x: &[f64]| {
+            let parameters = HestonParameters::from_unconstrained(x);
+            let penalty = self.feller_penalty * parameters.feller_gap().max(0.0).powi(2);
+
+            targets
+                .objective(|T, strikes| parameters.prices_fft(S, r, q, T, strikes, TypeFlag::Call))
+                + penalty
+        };
+
+        let starts = match self.starts.is_empty() {
+            true => self.default_starts(),
+            false => self.starts.clone(),
+        };
+
+        let (x, value, iterations) = starts
+            .into_iter()
+            .map(|start| nelder_mead(objective, &start.to_unconstrained(), self.max_iterations))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("at least one start");
+
+        if !value.is_finite() {
+            return Err(RustQuantError::NonConvergence(
+                "Heston calibration found no parameters with a finite objective.".to_string(),
+            ));
+        }
+
+        let parameters = HestonParameters::from_unconstrained(&x);
+
+        Ok(HestonCalibration {
+            parameters,
+            objective: value,
+            iterations,
+            expiry_fits: targets
+                .fits(|T, strikes| parameters.prices_fft(S, r, q, T, strikes, TypeFlag::Call))?,
+        })
+    }
+
+    // Standard starting points around the average quoted variance.
+    fn default_starts(&self) -> Vec<HestonParameters> {
+        let variance = average_variance(&self.quotes);
+
+        [(1.5, 0.5, -0.5), (4.0, 1.0, -0.8), (0.5, 0.3, 0.0)]
+            .iter()
+            .map(|&(kappa, sigma, rho)| HestonParameters {
+                initial_variance: variance,
+                long_run_variance: variance,
+                mean_reversion_rate: kappa,
+                volatility_of_volatility: sigma,
+                correlation: rho,
+            })
+            .collect()
+    }
+}
+
+impl SurfaceTargets {
+    pub(crate) fn new(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        quotes: &[SurfaceQuote],
+    ) -> Self {
+        let (S, r, q) = (spot, risk_free_rate, dividend_yield);
+
+        let mut quotes = quotes.to_vec();
+        quotes.sort_by(|a, b| a.time_to_expiry.total_cmp(&b.time_to_expiry));
+
+        let expiries = quotes
+            .chunk_by(|a, b| a.time_to_expiry == b.time_to_expiry)
+            .map(|quotes| {
+                let T = quotes[0].time_to_expiry;
+                let forward = S * ((r - q) * T).exp();
+
+                let targets = quotes
+                    .iter()
+                    .map(|quote| {
+                        let (K, v) = (quote.strike, quote.volatility);
+                        let option_type = match K < forward {
+                            true => TypeFlag::Put,
+                            false => TypeFlag::Call,
+                        };
+                        let d1 = ((S / K).ln() + (r - q + 0.5 * v * v) * T) / (v * T.sqrt());
+
+                        Target {
+                            strike: K,
+                            option_type,
+                            volatility: v,
+                            price: generalised_black_scholes_merton(
+                                S,
+                                K,
+                                v,
+                                r,
+                                r - q,
+                                T,
+                                option_type,
+                            ),
+                            vega: S * (-q * T).exp() * d1.norm_pdf() * T.sqrt(),
+                            weight: quote.weight,
+                        }
+                    })
+                    .collect();
+
+                (T, targets)
+            })
+            .collect();
+
+        Self {
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            expiries,
+        }
+    }
+
+    // Weighted sum of squared vega-scaled price errors, given the model call
+    // prices at an expiry and strikes. Infinite if the model cannot price.
+    pub(crate) fn objective<F>(&self, calls: F) -> f64
+    where
+        F: Fn(f64, &[f64]) -> Result<Vec<f64>, RustQuantError>,
+    {
+        let Ok(prices) = self.model_prices(calls) else {
+            return f64::INFINITY;
+        };
+
+        let value = self
+            .expiries
+            .iter()
+            .zip(prices)
+            .flat_map(|((_, targets), prices)| {
+                targets.iter().zip(prices).map(|(target, price)| {
+                    target.weight * ((price - target.price) / target.vega).powi(2)
+                })
+            })
+            .sum::<f64>();
+
+        if value.is_nan() {
+            f64::INFINITY
+        } else {
+            value
+        }
+    }
+
+    // Implied volatility errors of the model prices at each expiry.
+    pub(crate) fn fits<F>(&self, calls: F) -> Result<Vec<ExpiryFit>, RustQuantError>
+    where
+        F: Fn(f64, &[f64]) -> Result<Vec<f64>, RustQuantError>,
+    {
+        let (r, q) = (self.risk_free_rate, self.dividend_yield);
+        let prices = self.model_prices(calls)?;
+
+        Ok(self
+            .expiries
+            .iter()
+            .zip(prices)
+            .map(|((T, targets), prices)| {
+                let S = self.spot * (-q * T).exp();
+
+                let errors: Vec<f64> = targets
+                    .iter()
+                    .zip(prices)
+                    .map(|(target, price)| {
+                        try_implied_volatility(price, S, target.strike, *T, r, target.option_type)
+                            .map_or(f64::NAN, |v| v - target.volatility)
+                    })
+                    .collect();
+
+                ExpiryFit {
+                    time_to_expiry: *T,
+                    quotes: targets.len(),
+                    rmse: (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt(),
+                    max_error: errors.iter().map(|e| e.abs()).fold(0.0, f64::max),
+                }
+            })
+            .collect())
+    }
+
+    // Model prices of the target options at each expiry.
+    fn model_prices<F>(&self, calls: F) -> Result<Vec<Vec<f64>>, RustQuantError>
+    where
+        F: Fn(f64, &[f64]) -> Result<Vec<f64>, RustQuantError>,
+    {
+        let (S, r, q) = (self.spot, self.risk_free_rate, self.dividend_yield);
+
+        self.expiries
+            .iter()
+            .map(|(T, targets)| {
+                let strikes: Vec<f64> = targets.iter().map(|target| target.strike).collect();
+
+                Ok(targets
+                    .iter()
+                    .zip(calls(*T, &strikes)?)
+                    .map(|(target, call)| match target.option_type {
+                        TypeFlag::Call => call,
+                        TypeFlag::Put => call - S * (-q * T).exp() + target.strike * (-r * T).exp(),
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+}
+
+impl Validate for HestonCalibrator {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("spot", self.spot)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("dividend_yield", self.dividend_yield)
+            .non_negative("feller_penalty", self.feller_penalty)
+            .check(!self.quotes.is_empty(), || {
+                "at least one quote is needed".to_string()
+            })
+            .finish()?;
+        self.quotes.iter().try_for_each(Validate::validate)?;
+        self.starts.iter().try_for_each(Validate::validate)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Average quoted variance, to seed the starting points.
+pub(crate) fn average_variance(quotes: &[SurfaceQuote]) -> f64 {
+    quotes
+        .iter()
+        .map(|quote| quote.volatility * quote.volatility)
+        .sum::<f64>()
+        / quotes.len() as f64
+}
+
+/// Minimise a function with the Nelder-Mead simplex method.
+///
+/// Returns the minimiser, the minimum, and the number of iterations.
+pub(crate) fn nelder_mead<F>(f: F, x0: &[f64], max_iterations: usize) -> (Vec<f64>, f64, usize)
+where
+    F: Fn(&[f64]) -> f64,
+{
+    const STEP: f64 = 0.25;
+    const TOLERANCE: f64 = 1e-14;
+
+    let n = x0.len();
+
+    let mut simplex: Vec<(Vec<f64>, f64)> = (0..=n)
+        .map(|i| {
+            let mut x = x0.to_vec();
+            if i > 0 {
+                x[i - 1] += STEP;
+            }
+            let value = f(&x);
+            (x, value)
+        })
+        .collect();
+
+    // Point on the line from the worst vertex through the centroid.
+    let along = |centroid: &[f64], worst: &[f64], t: f64| -> Vec<f64> {
+        centroid
+            .iter()
+            .zip(worst)
+            .map(|(c, w)| c + t * (c - w))
+            .collect()
+    };
+
+    for iteration in 0..max_iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let (best, worst) = (simplex[0].1, simplex[n].1);
+        let size = simplex[1..]
+            .iter()
+            .flat_map(|(x, _)| x.iter().zip(&simplex[0].0).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+
+        if worst - best <= TOLERANCE * (best.abs() + TOLERANCE) || size < 1e-10 {
+            let (x, value) = simplex.swap_remove(0);
+            return (x, value, iteration);
+        }
+
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(x, _)| x[j]).sum::<f64>() / n as f64)
+            .collect();
+        let worst_x = simplex[n].0.clone();
+
+        let reflected = along(&centroid, &worst_x, 1.0);
+        let f_reflected = f(&reflected);
+
+        if f_reflected < best {
+            let expanded = along(&centroid, &worst_x, 2.0);
+            let f_expanded = f(&expanded);
+
+            simplex[n] = match f_expanded < f_reflected {
+                true => (expanded, f_expanded),
+                false => (reflected, f_reflected),
+            };
+        } else if f_reflected < simplex[n - 1].1 {
+            simplex[n] = (reflected, f_reflected);
+        } else {
+            // Contract outside the simplex if the reflection improved on the
+            // worst vertex, inside otherwise.
+            let t = if f_reflected < worst { 0.5 } else { -0.5 };
+            let contracted = along(&centroid, &worst_x, t);
+            let f_contracted = f(&contracted);
+
+            if f_contracted < f_reflected.min(worst) {
+                simplex[n] = (contracted, f_contracted);
+            } else {
+                // Shrink towards the best vertex.
+                let best_x = simplex[0].0.clone();
+
+                for (x, value) in &mut simplex[1..] {
+                    for (a, b) in x.iter_mut().zip(&best_x) {
+                        *a = b + 0.5 * (*a - b);
+                    }
+                    *value = f(x);
+                }
+            }
+        }
+    }
+
+    simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let (x, value) = simplex.swap_remove(0);
+
+    (x, value, max_iterations)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_heston_calibration {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const S: f64 = 100.0;
+    const R: f64 = 0.03;
+    const Q: f64 = 0.01;
+
+    const TRUE: HestonParameters = HestonParameters {
+        initial_variance: 0.04,
+        long_run_variance: 0.06,
+        mean_reversion_rate: 2.0,
+        volatility_of_volatility: 0.6,
+        correlation: -0.7,
+    };
+
+    // Surface generated by the model itself.
+    fn quotes() -> Vec<SurfaceQuote> {
+        let strikes = [80.0, 90.0, 100.0, 110.0, 120.0];
+        let mut quotes = Vec::new();
+
+        for T in [0.25, 1.0] {
+            let calls = TRUE
+                .prices_fft(S, R, Q, T, &strikes, TypeFlag::Call)
+                .unwrap();
+
+            for (&K, call) in strikes.iter().zip(calls) {
+                let volatility =
+                    try_implied_volatility(call, S * (-Q * T).exp(), K, T, R, TypeFlag::Call)
+                        .unwrap();
+
+                quotes.push(SurfaceQuote {
+                    time_to_expiry: T,
+                    strike: K,
+                    volatility,
+                    weight: 1.0,
+                });
+            }
+        }
+
+        quotes
+    }
+
+    #[test]
+    fn test_fft_prices() {
+        // Fabrice D. Rouah's example, priced by direct integration.
+        let parameters = HestonParameters {
+            initial_variance: 0.05,
+            long_run_variance: 0.05,
+            mean_reversion_rate: 5.0,
+            volatility_of_volatility: 0.5,
+            correlation: -0.8,
+        };
+
+        let call = parameters
+            .prices_fft(100.0, 0.03, 0.02, 0.5, &[100.0], TypeFlag::Call)
+            .unwrap()[0];
+        let put = parameters
+            .prices_fft(100.0, 0.03, 0.02, 0.5, &[100.0], TypeFlag::Put)
+            .unwrap()[0];
+
+        assert_approx_equal!(call, 6.252_678_211_219_156, 1e-6);
+        assert_approx_equal!(
+            call - put,
+            100.0 * ((-0.02 * 0.5_f64).exp() - (-0.03 * 0.5_f64).exp()),
+            1e-12
+        );
+        assert!(parameters
+            .prices_fft(100.0, 0.03, 0.02, 0.5, &[1e-12], TypeFlag::Call)
+            .is_err());
+    }
+
+    #[test]
+    fn test_calibration_recovers_parameters() {
+        let calibration = HestonCalibrator::new(S, R, Q, &quotes())
+            .calibrate()
+            .unwrap();
+        let parameters = calibration.parameters;
+
+        assert_approx_equal!(parameters.initial_variance, 0.04, 1e-5);
+        assert_approx_equal!(parameters.long_run_variance, 0.06, 1e-5);
+        assert_approx_equal!(parameters.mean_reversion_rate, 2.0, 1e-3);
+        assert_approx_equal!(parameters.volatility_of_volatility, 0.6, 1e-4);
+        assert_approx_equal!(parameters.correlation, -0.7, 1e-4);
+
+        assert_eq!(calibration.expiry_fits.len(), 2);
+        for fit in &calibration.expiry_fits {
+            assert_eq!(fit.quotes, 5);
+            assert!(fit.rmse < 1e-6);
+            assert!(fit.max_error < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_feller_penalty() {
+        assert!(!TRUE.satisfies_feller());
+
+        let calibration = HestonCalibrator::new(S, R, Q, &quotes())
+            .with_feller_penalty(100.0)
+            .with_starts(vec![HestonParameters {
+                mean_reversion_rate: 1.5,
+                volatility_of_volatility: 0.5,
+                correlation: -0.5,
+                ..TRUE
+            }])
+            .calibrate()
+            .unwrap();
+
+        // The penalty trades fit quality for a (nearly) admissible variance.
+        assert!(calibration.parameters.feller_gap() < 1e-3);
+        assert!(calibration.objective > 1e-6);
+        assert!(calibration.expiry_fits.iter().all(|fit| fit.rmse > 1e-4));
+    }
+
+    #[test]
+    fn test_calibrator_validation() {
+        assert!(HestonCalibrator::new(S, R, Q, &[]).calibrate().is_err());
+        assert!(HestonCalibrator::new(S, R, Q, &quotes())
+            .with_feller_penalty(-1.0)
+            .calibrate()
+            .is_err());
+    }
+}
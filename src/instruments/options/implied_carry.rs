@@ -0,0 +1,252 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Implied forwards, dividends, and borrow costs from put-call parity.
+//!
+//! Across the strikes of one expiry, the synthetic forward `C - P` is linear
+//! in the strike:
+//!
+//! ```text
+//! C - P = D F - D K,
+//! ```
+//!
+//! with `D` the discount factor and `F` the forward. Regressing `C - P` on
+//! `K` gives both, and the forward gives the carry of the underlying,
+//! `q + b = r - ln(F / S) / T`: the dividend yield plus the stock borrow
+//! cost. Quotes are noisy and sometimes stale, so the regression is the
+//! Theil-Sen estimator (the median of the pairwise slopes), which tolerates
+//! outliers in up to about 29% of the strikes.
+
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+use crate::math::Statistic;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Call and put (mid) prices at one strike of an expiry.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyntheticQuote {
+    /// Strike price.
+    pub strike: f64,
+    /// Call price.
+    pub call: f64,
+    /// Put price.
+    pub put: f64,
+}
+
+/// Carry of the underlying implied by put-call parity at one expiry.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImpliedCarry {
+    /// Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// Implied forward.
+    pub forward: f64,
+    /// Implied discount factor to expiry.
+    pub discount_factor: f64,
+    /// Continuously compounded rate implied by the discount factor.
+    pub implied_rate: f64,
+    /// Continuous yield of the underlying implied by the forward: the
+    /// dividend yield plus the borrow cost.
+    pub implied_yield: f64,
+    /// Parity residuals `C - P - D (F - K)`, in the order of the quotes.
+    pub residuals: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ImpliedCarry {
+    /// Borrow cost implied by the carry, given the expected dividend yield.
+    #[must_use]
+    pub fn borrow_cost(&self, expected_dividend_yield: f64) -> f64 {
+        self.implied_yield - expected_dividend_yield
+    }
+
+    /// Indices of the quotes whose parity residual exceeds `tolerance` in
+    /// absolute value, e.g. to drop them before building a surface.
+    #[must_use]
+    pub fn outliers(&self, tolerance: f64) -> Vec<usize> {
+        self.residuals
+            .iter()
+            .enumerate()
+            .filter(|(_, residual)| residual.abs() > tolerance)
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+impl Validate for SyntheticQuote {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("strike", self.strike)
+            .non_negative("call", self.call)
+            .non_negative("put", self.put)
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Estimate the forward, discount factor, and carry of one expiry from the
+/// call and put prices across its strikes.
+///
+/// # Errors:
+/// * [`RustQuantError::InvalidArgument`] if the spot or the time to expiry
+///   are not positive, if a quote fails [`Validate::validate`], or if fewer
+///   than two distinct strikes are quoted.
+/// * [`RustQuantError::ArbitrageViolation`] if the implied discount factor
+///   or forward is not positive.
+pub fn implied_carry(
+    spot: f64,
+    time_to_expiry: f64,
+    quotes: &[SyntheticQuote],
+) -> Result<ImpliedCarry, RustQuantError> {
+    Validator::new()
+        .positive("spot", spot)
+        .positive("time_to_expiry", time_to_expiry)
+        .finish()?;
+    quotes.iter().try_for_each(Validate::validate)?;
+
+    let synthetic: Vec<(f64, f64)> = quotes.iter().map(|q| (q.strike, q.call - q.put)).collect();
+
+    // Theil-Sen slope: the median of the slopes between pairs of strikes.
+    let slopes: Vec<f64> = synthetic
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &(k_i, y_i))| {
+            synthetic[i + 1..]
+                .iter()
+                .filter(move |&&(k_j, _)| k_j != k_i)
+                .map(move |&(k_j, y_j)| (y_j - y_i) / (k_j - k_i))
+        })
+        .collect();
+
+    if slopes.is_empty() {
+        return Err(RustQuantError::InvalidArgument(
+            "At least two distinct strikes are needed to imply the carry.".to_string(),
+        ));
+    }
+
+    let slope = slopes.median();
+    let intercept = synthetic
+        .iter()
+        .map(|&(k, y)| y - slope * k)
+        .collect::<Vec<f64>>()
+        .median();
+
+    let discount_factor = -slope;
+    let forward = intercept / discount_factor;
+
+    if discount_factor <= 0.0 || forward <= 0.0 {
+        return Err(RustQuantError::ArbitrageViolation(format!(
+            "Implied discount factor and forward must be positive (got {discount_factor} and {forward})."
+        )));
+    }
+
+    let implied_rate = -discount_factor.ln() / time_to_expiry;
+
+    Ok(ImpliedCarry {
+        time_to_expiry,
+        forward,
+        discount_factor,
+        implied_rate,
+        implied_yield: implied_rate - (forward / spot).ln() / time_to_expiry,
+        residuals: synthetic
+            .iter()
+            .map(|&(k, y)| y - discount_factor * (forward - k))
+            .collect(),
+    })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_implied_carry {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::{generalised_black_scholes_merton, TypeFlag};
+
+    // Dividend yield of 2% and borrow cost of 1%.
+    const S: f64 = 100.0;
+    const R: f64 = 0.05;
+    const Q: f64 = 0.03;
+    const T: f64 = 0.5;
+
+    fn quotes() -> Vec<SyntheticQuote> {
+        let price = |strike, option_type| {
+            generalised_black_scholes_merton(S, strike, 0.25, R, R - Q, T, option_type)
+        };
+
+        [70.0, 80.0, 90.0, 100.0, 110.0, 120.0, 130.0]
+            .iter()
+            .map(|&strike| SyntheticQuote {
+                strike,
+                call: price(strike, TypeFlag::Call),
+                put: price(strike, TypeFlag::Put),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_implied_carry() {
+        let carry = implied_carry(S, T, &quotes()).unwrap();
+
+        assert_approx_equal!(carry.forward, S * ((R - Q) * T).exp(), 1e-9);
+        assert_approx_equal!(carry.discount_factor, (-R * T).exp(), 1e-12);
+        assert_approx_equal!(carry.implied_rate, R, 1e-10);
+        assert_approx_equal!(carry.implied_yield, Q, 1e-10);
+        assert_approx_equal!(carry.borrow_cost(0.02), 0.01, 1e-10);
+        assert!(carry.outliers(1e-8).is_empty());
+    }
+
+    #[test]
+    fn test_implied_carry_is_robust_to_outliers() {
+        // A stale call quote at one strike.
+        let mut quotes = quotes();
+        quotes[3].call += 1.0;
+
+        let carry = implied_carry(S, T, &quotes).unwrap();
+
+        assert_approx_equal!(carry.forward, S * ((R - Q) * T).exp(), 1e-9);
+        assert_approx_equal!(carry.implied_yield, Q, 1e-10);
+        assert_eq!(carry.outliers(0.5), vec![3]);
+        assert_approx_equal!(carry.residuals[3], 1.0, 1e-9);
+    }
+
+    #[test]
+    fn test_implied_carry_validation() {
+        let quotes = quotes();
+
+        assert!(implied_carry(S, T, &quotes[..1]).is_err());
+        assert!(implied_carry(S, 0.0, &quotes).is_err());
+
+        // Calls and puts swapped: C - P increases with the strike.
+        let inverted: Vec<SyntheticQuote> = quotes
+            .iter()
+            .map(|q| SyntheticQuote {
+                call: q.put,
+                put: q.call,
+                ..*q
+            })
+            .collect();
+        assert!(matches!(
+            implied_carry(S, T, &inverted),
+            Err(RustQuantError::ArbitrageViolation(_))
+        ));
+    }
+}
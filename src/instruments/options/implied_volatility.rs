@@ -28,6 +28,7 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use super::TypeFlag;
+use crate::error::RustQuantError;
 use crate::math::distributions::{gaussian::Gaussian, Distribution};
 use errorfunctions::RealErrorFunctions;
 
@@ -116,6 +117,72 @@ pub fn iv(price: f64, S: f64, K: f64, T: f64, r: f64, flag: TypeFlag) -> f64 {
     implied_volatility(price, S, K, T, r, flag)
 }
 
+/// Implied volatility with the inputs checked against the no-arbitrage bounds.
+///
+/// Unlike [`implied_volatility`], which signals an unattainable price with
+/// `-INF`/`INF`, this returns:
+///
+/// * [`RustQuantError::InvalidArgument`] if `S`, `K` or `T` are not positive,
+///   or any input is not finite;
+/// * [`RustQuantError::ArbitrageViolation`] if the price is below the
+///   discounted intrinsic value or at/above the upper bound (`S` for calls,
+///   `K e^{-rT}` for puts);
+/// * [`RustQuantError::NonConvergence`] if no finite volatility is found.
+///
+/// ```
+/// use RustQuant::instruments::options::implied_volatility::*;
+/// use RustQuant::instruments::options::TypeFlag;
+///
+/// let iv = try_implied_volatility(12.3, 100.0, 110.0, 0.89, 0.03, TypeFlag::Call);
+/// assert!(iv.is_ok());
+///
+/// // A call cannot be worth more than the underlying.
+/// let iv = try_implied_volatility(101.0, 100.0, 110.0, 0.89, 0.03, TypeFlag::Call);
+/// assert!(iv.is_err());
+/// ```
+pub fn try_implied_volatility(
+    price: f64,
+    S: f64,
+    K: f64,
+    T: f64,
+    r: f64,
+    flag: TypeFlag,
+) -> Result<f64, RustQuantError> {
+    if ![price, S, K, T, r].iter().all(|x| x.is_finite()) {
+        return Err(RustQuantError::InvalidArgument(
+            "implied volatility inputs must be finite".to_string(),
+        ));
+    }
+    if S <= 0.0 || K <= 0.0 || T <= 0.0 {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "S, K and T must be positive (S = {S}, K = {K}, T = {T})"
+        )));
+    }
+
+    let discounted_strike = K * (-r * T).exp();
+
+    let (lower, upper) = match flag {
+        TypeFlag::Call => ((S - discounted_strike).max(0.0), S),
+        TypeFlag::Put => ((discounted_strike - S).max(0.0), discounted_strike),
+    };
+
+    if price < lower || price >= upper {
+        return Err(RustQuantError::ArbitrageViolation(format!(
+            "option price {price} outside no-arbitrage bounds [{lower}, {upper})"
+        )));
+    }
+
+    let sigma = implied_volatility(price, S, K, T, r, flag);
+
+    if sigma.is_finite() {
+        Ok(sigma)
+    } else {
+        Err(RustQuantError::NonConvergence(format!(
+            "no finite implied volatility for price {price}"
+        )))
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // INLINED FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -1213,4 +1280,21 @@ mod test_lets_be_rational {
         assert!(!is_zero(-0.1));
         assert!(!is_zero(0.1));
     }
+
+    #[test]
+    fn test_try_implied_volatility() {
+        let iv = try_implied_volatility(12.3, 100.0, 110.0, 0.89, 0.03, TypeFlag::Call).unwrap();
+        assert_approx_equal!(iv, 0.402_699_732_857_872_97, 1e-15);
+
+        // Below intrinsic value.
+        let below = try_implied_volatility(1.0, 100.0, 80.0, 1.0, 0.0, TypeFlag::Call);
+        assert!(matches!(below, Err(RustQuantError::ArbitrageViolation(_))));
+
+        // Put above the discounted strike.
+        let above = try_implied_volatility(100.0, 100.0, 100.0, 1.0, 0.05, TypeFlag::Put);
+        assert!(matches!(above, Err(RustQuantError::ArbitrageViolation(_))));
+
+        let bad = try_implied_volatility(5.0, 100.0, 100.0, -1.0, 0.05, TypeFlag::Put);
+        assert!(matches!(bad, Err(RustQuantError::InvalidArgument(_))));
+    }
 }
@@ -0,0 +1,719 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Jump-diffusion calibration to short-dated smiles.
+//!
+//! Over short horizons a diffusion moves the price too little to produce
+//! the steep skew of short-dated equity options; jumps do. The log-price
+//! here is a diffusion, with either constant volatility or Heston
+//! stochastic variance, plus compound Poisson jumps with intensity `lambda`:
+//!
+//! * Merton (1976): normal log-jumps, `J ~ N(mu, delta^2)`;
+//! * Kou (2002): double-exponential log-jumps, upwards with probability `p`
+//!   and rate `eta_1`, downwards with rate `eta_2`.
+//!
+//! Heston with Merton jumps is the Bates (1996) model. The jumps are
+//! compensated so that the forward is preserved, which multiplies the
+//! diffusion's characteristic function by `exp(T psi(u))` with
+//!
+//! ```text
+//! psi(u) = lambda (E[exp(iuJ)] - 1) - iu lambda (E[exp(J)] - 1).
+//! ```
+//!
+//! The calibration reuses the Carr-Madan pricer, the vega-weighted
+//! objective, and the multi-start Nelder-Mead of the Heston calibration.
+
+use super::carr_madan::DAMPING;
+use super::{
+    average_variance, carr_madan_prices, nelder_mead, ExpiryFit, HestonParameters, SurfaceQuote,
+    SurfaceTargets, TypeFlag,
+};
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+use num::Complex;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Distribution of the jumps in the log-price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JumpParameters {
+    /// Merton (1976) normal log-jumps.
+    Merton {
+        /// Expected number of jumps per year.
+        intensity: f64,
+        /// Mean log-jump.
+        mean: f64,
+        /// Standard deviation of the log-jumps.
+        volatility: f64,
+    },
+
+    /// Kou (2002) double-exponential log-jumps.
+    Kou {
+        /// Expected number of jumps per year.
+        intensity: f64,
+        /// Probability that a jump is upwards.
+        up_probability: f64,
+        /// Rate of the upward jumps (mean size `1 / up_rate`).
+        up_rate: f64,
+        /// Rate of the downward jumps (mean size `1 / down_rate`).
+        down_rate: f64,
+    },
+}
+
+/// Diffusive part of a jump-diffusion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DiffusionParameters {
+    /// Constant volatility.
+    Constant {
+        /// Volatility of the diffusion.
+        volatility: f64,
+    },
+
+    /// Heston stochastic variance (the Bates model, with Merton jumps).
+    Heston(HestonParameters),
+}
+
+/// Parameters of a jump-diffusion model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JumpDiffusionParameters {
+    /// Diffusive part.
+    pub diffusion: DiffusionParameters,
+    /// Jump part.
+    pub jumps: JumpParameters,
+}
+
+/// Jump distribution to calibrate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JumpModel {
+    /// Merton (1976) normal log-jumps.
+    Merton,
+    /// Kou (2002) double-exponential log-jumps.
+    Kou,
+}
+
+/// Result of a jump-diffusion calibration.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JumpCalibration {
+    /// Calibrated parameters.
+    pub parameters: JumpDiffusionParameters,
+    /// Objective at the calibrated parameters.
+    pub objective: f64,
+    /// Nelder-Mead iterations taken from the best starting point.
+    pub iterations: usize,
+    /// Fit diagnostics per expiry, by increasing time to expiry.
+    pub expiry_fits: Vec<ExpiryFit>,
+}
+
+/// Calibrates a jump-diffusion model to an implied volatility surface.
+#[derive(Debug, Clone)]
+pub struct JumpCalibrator {
+    /// Spot price of the underlying.
+    pub spot: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Dividend yield.
+    pub dividend_yield: f64,
+    /// Quotes to calibrate to.
+    pub quotes: Vec<SurfaceQuote>,
+    /// Jump distribution.
+    pub model: JumpModel,
+    /// Whether to calibrate Heston stochastic variance jointly with the
+    /// jumps, rather than a constant volatility.
+    pub stochastic_volatility: bool,
+    /// Starting points. If non-empty, these also fix the model; otherwise
+    /// (the default) a few standard starts of the chosen model are used.
+    pub starts: Vec<JumpDiffusionParameters>,
+    /// Maximum Nelder-Mead iterations per start.
+    pub max_iterations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl JumpParameters {
+    // Characteristic exponent of the compensated jumps, per unit time.
+    fn exponent(&self, u: Complex<f64>) -> Complex<f64> {
+        let iu = Complex::<f64>::i() * u;
+
+        let (intensity, transform, mean) = match *self {
+            Self::Merton {
+                intensity,
+                mean,
+                volatility,
+            } => (
+                intensity,
+                (iu * mean - 0.5 * volatility * volatility * u * u).exp(),
+                (mean + 0.5 * volatility * volatility).exp(),
+            ),
+            Self::Kou {
+                intensity,
+                up_probability: p,
+                up_rate,
+                down_rate,
+            } => (
+                intensity,
+                p * up_rate / (up_rate - iu) + (1.0 - p) * down_rate / (down_rate + iu),
+                p * up_rate / (up_rate - 1.0) + (1.0 - p) * down_rate / (down_rate + 1.0),
+            ),
+        };
+
+        intensity * (transform - 1.0) - iu * intensity * (mean - 1.0)
+    }
+
+    // Map to and from the unconstrained space of the optimiser. The Kou
+    // up-jump rate is kept above `1 + alpha`, where the damped transform of
+    // the FFT exists.
+    fn to_unconstrained(self) -> Vec<f64> {
+        match self {
+            Self::Merton {
+                intensity,
+                mean,
+                volatility,
+            } => vec![intensity.ln(), mean, volatility.ln()],
+            Self::Kou {
+                intensity,
+                up_probability: p,
+                up_rate,
+                down_rate,
+            } => vec![
+                intensity.ln(),
+                (p / (1.0 - p)).ln(),
+                (up_rate - 1.0 - DAMPING).ln(),
+                down_rate.ln(),
+            ],
+        }
+    }
+
+    fn with_unconstrained(self, x: &[f64]) -> Self {
+        match self {
+            Self::Merton { .. } => Self::Merton {
+                intensity: x[0].exp(),
+                mean: x[1],
+                volatility: x[2].exp(),
+            },
+            Self::Kou { .. } => Self::Kou {
+                intensity: x[0].exp(),
+                up_probability: 1.0 / (1.0 + (-x[1]).exp()),
+                up_rate: 1.0 + DAMPING + x[2].exp(),
+                down_rate: x[3].exp(),
+            },
+        }
+    }
+}
+
+impl Validate for JumpParameters {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        match *self {
+            Self::Merton {
+                intensity,
+                mean,
+                volatility,
+            } => Validator::new()
+                .positive("intensity", intensity)
+                .finite("mean", mean)
+                .positive("volatility", volatility)
+                .finish(),
+            Self::Kou {
+                intensity,
+                up_probability,
+                up_rate,
+                down_rate,
+            } => Validator::new()
+                .positive("intensity", intensity)
+                .check(up_probability > 0.0 && up_probability < 1.0, || {
+                    format!("up_probability must lie in (0, 1) (got {up_probability})")
+                })
+                .check(up_rate > 1.0 + DAMPING, || {
+                    format!(
+                        "up_rate must exceed {} for the FFT pricer (got {up_rate})",
+                        1.0 + DAMPING
+                    )
+                })
+                .positive("down_rate", down_rate)
+                .finish(),
+        }
+    }
+}
+
+impl JumpDiffusionParameters {
+    /// European option prices for one expiry via the Carr-Madan FFT.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if [`carr_madan_prices`] fails.
+    pub fn prices_fft(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        strikes: &[f64],
+        option_type: TypeFlag,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        self.validate()?;
+
+        carr_madan_prices(
+            |u| self.characteristic_function(u, T, r - q),
+            S,
+            r,
+            q,
+            T,
+            strikes,
+            option_type,
+        )
+    }
+
+    /// Characteristic function of `ln(S_T / S_0)`, with cost of carry `b`.
+    #[must_use]
+    pub fn characteristic_function(&self, u: Complex<f64>, T: f64, b: f64) -> Complex<f64> {
+        let diffusion = match self.diffusion {
+            DiffusionParameters::Constant { volatility: v } => {
+                (Complex::<f64>::i() * u * (b - 0.5 * v * v) * T - 0.5 * v * v * u * u * T).exp()
+            }
+            DiffusionParameters::Heston(heston) => heston.characteristic_function(u, T, b),
+        };
+
+        diffusion * (T * self.jumps.exponent(u)).exp()
+    }
+
+    fn to_unconstrained(self) -> Vec<f64> {
+        let mut x = match self.diffusion {
+            DiffusionParameters::Constant { volatility } => vec![volatility.ln()],
+            DiffusionParameters::Heston(heston) => heston.to_unconstrained(),
+        };
+        x.extend(self.jumps.to_unconstrained());
+        x
+    }
+
+    // Parameters of the same model as `self` from unconstrained values.
+    fn with_unconstrained(self, x: &[f64]) -> Self {
+        let (diffusion, n) = match self.diffusion {
+            DiffusionParameters::Constant { .. } => (
+                DiffusionParameters::Constant {
+                    volatility: x[0].exp(),
+                },
+                1,
+            ),
+            DiffusionParameters::Heston(_) => (
+                DiffusionParameters::Heston(HestonParameters::from_unconstrained(&x[..5])),
+                5,
+            ),
+        };
+
+        Self {
+            diffusion,
+            jumps: self.jumps.with_unconstrained(&x[n..]),
+        }
+    }
+}
+
+impl Validate for JumpDiffusionParameters {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        match self.diffusion {
+            DiffusionParameters::Constant { volatility } => Validator::new()
+                .positive("volatility", volatility)
+                .finish()?,
+            DiffusionParameters::Heston(heston) => heston.validate()?,
+        }
+        self.jumps.validate()
+    }
+}
+
+impl JumpCalibrator {
+    /// New calibrator of a constant-volatility jump-diffusion, with the
+    /// default starting points and at most 3000 iterations per start.
+    #[must_use]
+    pub fn new(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        quotes: &[SurfaceQuote],
+        model: JumpModel,
+    ) -> Self {
+        Self {
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            quotes: quotes.to_vec(),
+            model,
+            stochastic_volatility: false,
+            starts: Vec::new(),
+            max_iterations: 3000,
+        }
+    }
+
+    /// New calibrator of the Bates model: Heston stochastic variance with
+    /// Merton jumps. See [`BatesParameters`](super::BatesParameters).
+    #[must_use]
+    pub fn bates(
+        spot: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        quotes: &[SurfaceQuote],
+    ) -> Self {
+        Self::new(
+            spot,
+            risk_free_rate,
+            dividend_yield,
+            quotes,
+            JumpModel::Merton,
+        )
+        .with_stochastic_volatility()
+    }
+
+    /// Calibrate Heston stochastic variance jointly with the jumps (the
+    /// Bates model, with Merton jumps).
+    #[must_use]
+    pub fn with_stochastic_volatility(self) -> Self {
+        Self {
+            stochastic_volatility: true,
+            ..self
+        }
+    }
+
+    /// Start the optimiser from the given parameters.
+    #[must_use]
+    pub fn with_starts(self, starts: Vec<JumpDiffusionParameters>) -> Self {
+        Self { starts, ..self }
+    }
+
+    /// Maximum Nelder-Mead iterations per start.
+    #[must_use]
+    pub fn with_max_iterations(self, max_iterations: usize) -> Self {
+        Self {
+            max_iterations,
+            ..self
+        }
+    }
+
+    /// Calibrate the model, keeping the best fit over the starting points.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the calibrator fails
+    ///   [`Validate::validate`], or if a strike is outside the FFT grid.
+    /// * [`RustQuantError::NonConvergence`] if no start reaches a finite
+    ///   objective.
+    pub fn calibrate(&self) -> Result<JumpCalibration, RustQuantError> {
+        self.validate()?;
+
+        let (S, r, q) = (self.spot, self.risk_free_rate, self.dividend_yield);
+        let targets = SurfaceTargets::new(S, r, q, &self.quotes);
+
+        let starts = match self.starts.is_empty() {
+            true => self.default_starts(),
+            false => self.starts.clone(),
+        };
+
+        let (parameters, value, iterations) = starts
+            .into_iter()
+            .map(|start| {
+                let objective = |x: &[f64]| {
+                    let parameters = start.with_unconstrained(x);
+
+                    targets.objective(|T, strikes| {
+                        parameters.prices_fft(S, r, q, T, strikes, TypeFlag::Call)
+                    })
+                };
+
+                let (x, value, iterations) =
+                    nelder_mead(objective, &start.to_unconstrained(), self.max_iterations);
+
+                (start.with_unconstrained(&x), value, iterations)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("at least one start");
+
+        if !value.is_finite() {
+            return Err(RustQuantError::NonConvergence(
+                "Jump-diffusion calibration found no parameters with a finite objective."
+                    .to_string(),
+            ));
+        }
+
+        Ok(JumpCalibration {
+            parameters,
+            objective: value,
+            iterations,
+            expiry_fits: targets
+                .fits(|T, strikes| parameters.prices_fft(S, r, q, T, strikes, TypeFlag::Call))?,
+        })
+    }
+
+    // Standard starting points: a milder diffusion than the quotes imply,
+    // with few large jumps or many small ones.
+    fn default_starts(&self) -> Vec<JumpDiffusionParameters> {
+        let variance = average_variance(&self.quotes);
+
+        let diffusion = match self.stochastic_volatility {
+            true => DiffusionParameters::Heston(HestonParameters {
+                initial_variance: 0.8 * variance,
+                long_run_variance: 0.8 * variance,
+                mean_reversion_rate: 2.0,
+                volatility_of_volatility: 0.5,
+                correlation: -0.5,
+            }),
+            false => DiffusionParameters::Constant {
+                volatility: (0.8 * variance).sqrt(),
+            },
+        };
+
+        let jumps = match self.model {
+            JumpModel::Merton => [
+                JumpParameters::Merton {
+                    intensity: 0.5,
+                    mean: -0.1,
+                    volatility: 0.1,
+                },
+                JumpParameters::Merton {
+                    intensity: 2.0,
+                    mean: -0.05,
+                    volatility: 0.05,
+                },
+            ],
+            JumpModel::Kou => [
+                JumpParameters::Kou {
+                    intensity: 1.0,
+                    up_probability: 0.3,
+                    up_rate: 10.0,
+                    down_rate: 5.0,
+                },
+                JumpParameters::Kou {
+                    intensity: 3.0,
+                    up_probability: 0.5,
+                    up_rate: 20.0,
+                    down_rate: 10.0,
+                },
+            ],
+        };
+
+        jumps
+            .into_iter()
+            .map(|jumps| JumpDiffusionParameters { diffusion, jumps })
+            .collect()
+    }
+}
+
+impl Validate for JumpCalibrator {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("spot", self.spot)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("dividend_yield", self.dividend_yield)
+            .check(!self.quotes.is_empty(), || {
+                "at least one quote is needed".to_string()
+            })
+            .finish()?;
+        self.quotes.iter().try_for_each(Validate::validate)?;
+        self.starts.iter().try_for_each(Validate::validate)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_jump_calibration {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::{generalised_black_scholes_merton, try_implied_volatility};
+
+    const S: f64 = 100.0;
+    const R: f64 = 0.03;
+    const Q: f64 = 0.0;
+
+    // Short-dated surface generated by the model itself.
+    fn quotes(parameters: &JumpDiffusionParameters, expiries: &[f64]) -> Vec<SurfaceQuote> {
+        let strikes = [85.0, 90.0, 95.0, 100.0, 105.0, 110.0, 115.0];
+        let mut quotes = Vec::new();
+
+        for &T in expiries {
+            let calls = parameters
+                .prices_fft(S, R, Q, T, &strikes, TypeFlag::Call)
+                .unwrap();
+
+            for (&K, call) in strikes.iter().zip(calls) {
+                let volatility =
+                    try_implied_volatility(call, S * (-Q * T).exp(), K, T, R, TypeFlag::Call)
+                        .unwrap();
+
+                quotes.push(SurfaceQuote {
+                    time_to_expiry: T,
+                    strike: K,
+                    volatility,
+                    weight: 1.0,
+                });
+            }
+        }
+
+        quotes
+    }
+
+    fn assert_fits(calibration: &JumpCalibration, tolerance: f64) {
+        for fit in &calibration.expiry_fits {
+            assert!(fit.rmse < tolerance);
+        }
+    }
+
+    #[test]
+    fn test_jumps_without_intensity_are_black_scholes() {
+        let parameters = JumpDiffusionParameters {
+            diffusion: DiffusionParameters::Constant { volatility: 0.2 },
+            jumps: JumpParameters::Merton {
+                intensity: 1e-12,
+                mean: -0.1,
+                volatility: 0.1,
+            },
+        };
+
+        let put = parameters
+            .prices_fft(S, R, Q, 0.25, &[95.0], TypeFlag::Put)
+            .unwrap()[0];
+
+        assert_approx_equal!(
+            put,
+            generalised_black_scholes_merton(S, 95.0, 0.2, R, R - Q, 0.25, TypeFlag::Put),
+            1e-3
+        );
+    }
+
+    #[test]
+    fn test_merton_calibration() {
+        let true_parameters = JumpDiffusionParameters {
+            diffusion: DiffusionParameters::Constant { volatility: 0.15 },
+            jumps: JumpParameters::Merton {
+                intensity: 0.8,
+                mean: -0.15,
+                volatility: 0.1,
+            },
+        };
+        let quotes = quotes(&true_parameters, &[0.1, 0.25]);
+
+        // Downside skew.
+        assert!(quotes[0].volatility > quotes[3].volatility);
+
+        let calibration = JumpCalibrator::new(S, R, Q, &quotes, JumpModel::Merton)
+            .calibrate()
+            .unwrap();
+
+        let DiffusionParameters::Constant { volatility } = calibration.parameters.diffusion else {
+            panic!("expected a constant volatility");
+        };
+        let JumpParameters::Merton {
+            intensity,
+            mean,
+            volatility: jump_volatility,
+        } = calibration.parameters.jumps
+        else {
+            panic!("expected Merton jumps");
+        };
+
+        assert_approx_equal!(volatility, 0.15, 1e-5);
+        assert_approx_equal!(intensity, 0.8, 1e-4);
+        assert_approx_equal!(mean, -0.15, 1e-5);
+        assert_approx_equal!(jump_volatility, 0.1, 1e-5);
+        assert_fits(&calibration, 1e-6);
+    }
+
+    #[test]
+    fn test_kou_calibration() {
+        let true_parameters = JumpDiffusionParameters {
+            diffusion: DiffusionParameters::Constant { volatility: 0.15 },
+            jumps: JumpParameters::Kou {
+                intensity: 1.0,
+                up_probability: 0.3,
+                up_rate: 12.0,
+                down_rate: 6.0,
+            },
+        };
+        let quotes = quotes(&true_parameters, &[0.1, 0.25]);
+
+        let calibration = JumpCalibrator::new(S, R, Q, &quotes, JumpModel::Kou)
+            .calibrate()
+            .unwrap();
+
+        assert!(matches!(
+            calibration.parameters.jumps,
+            JumpParameters::Kou { .. }
+        ));
+        assert_fits(&calibration, 1e-6);
+    }
+
+    #[test]
+    fn test_bates_calibration() {
+        let heston = HestonParameters {
+            initial_variance: 0.04,
+            long_run_variance: 0.04,
+            mean_reversion_rate: 2.0,
+            volatility_of_volatility: 0.4,
+            correlation: -0.6,
+        };
+        let jumps = JumpParameters::Merton {
+            intensity: 0.5,
+            mean: -0.1,
+            volatility: 0.1,
+        };
+        let true_parameters = JumpDiffusionParameters {
+            diffusion: DiffusionParameters::Heston(heston),
+            jumps,
+        };
+        let quotes = quotes(&true_parameters, &[0.1, 0.25, 1.0]);
+
+        let start = JumpDiffusionParameters {
+            diffusion: DiffusionParameters::Heston(HestonParameters {
+                initial_variance: 0.045,
+                long_run_variance: 0.045,
+                volatility_of_volatility: 0.5,
+                correlation: -0.5,
+                ..heston
+            }),
+            jumps,
+        };
+
+        let calibration = JumpCalibrator::bates(S, R, Q, &quotes)
+            .with_starts(vec![start])
+            .with_max_iterations(5000)
+            .calibrate()
+            .unwrap();
+
+        assert!(matches!(
+            calibration.parameters.diffusion,
+            DiffusionParameters::Heston(_)
+        ));
+        assert_fits(&calibration, 1e-5);
+    }
+
+    #[test]
+    fn test_jump_parameters_validation() {
+        let kou = JumpDiffusionParameters {
+            diffusion: DiffusionParameters::Constant { volatility: 0.2 },
+            jumps: JumpParameters::Kou {
+                intensity: 1.0,
+                up_probability: 0.3,
+                up_rate: 2.0,
+                down_rate: 5.0,
+            },
+        };
+
+        assert!(kou
+            .prices_fft(S, R, Q, 0.25, &[100.0], TypeFlag::Call)
+            .is_err());
+        assert!(JumpCalibrator::new(S, R, Q, &[], JumpModel::Merton)
+            .calibrate()
+            .is_err());
+    }
+}
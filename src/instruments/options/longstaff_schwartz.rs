@@ -0,0 +1,620 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Least-squares Monte Carlo (Longstaff and Schwartz, 2001) for Bermudan
+//! options on a geometric Brownian motion, with price bounds.
+//!
+//! The regression of the discounted future cashflows on polynomials of the
+//! moneyness gives an exercise policy. Following it on fresh paths is a
+//! lower bound on the price; the Andersen and Broadie (2004) dual
+//! estimator turns the same policy into an upper bound,
+//!
+//! $$
+//! V_0 \leq \mathbb{E} \left[ \max_k \left( h_k - M_k \right) \right],
+//! $$
+//!
+//! with $h_k$ the discounted exercise value and $M$ the martingale part of
+//! the policy's value process, estimated by nested simulation. Together
+//! they give a confidence interval that brackets the true price.
+//!
+//! With the `autodiff` feature, [`LsmcPolicy::greeks`] gives delta, vega
+//! and rho by adjoint algorithmic differentiation of each path with the
+//! exercise policy frozen.
+
+#[cfg(feature = "autodiff")]
+use crate::autodiff::{Accumulate, Gradient, Graph};
+use crate::error::RustQuantError;
+use crate::instruments::options::TypeFlag;
+use crate::instruments::{Price, Validate, Validator};
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bermudan option on a geometric Brownian motion, exercisable at equally
+/// spaced dates $t_k = k T / n$, $k = 1, \dots, n$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BermudanOption {
+    /// Spot price of the underlying.
+    pub spot: f64,
+    /// Strike price.
+    pub strike: f64,
+    /// Continuously compounded risk-free rate.
+    pub risk_free_rate: f64,
+    /// Continuous dividend yield.
+    pub dividend_yield: f64,
+    /// Volatility of the underlying.
+    pub volatility: f64,
+    /// Time to expiry, in years.
+    pub time_to_expiry: f64,
+    /// Number of exercise dates, $n$ (the last at expiry).
+    pub exercises: usize,
+    /// Call or put.
+    pub option_type: TypeFlag,
+}
+
+/// Exercise policy fitted by Longstaff-Schwartz regression.
+#[derive(Debug, Clone)]
+pub struct LsmcPolicy {
+    /// Option the policy exercises.
+    pub option: BermudanOption,
+    /// In-sample price on the regression paths (biased high by the
+    /// foresight of the fit).
+    pub in_sample: Price,
+    // Regression coefficients of the continuation value at t_1..t_{n-1},
+    // in powers of the moneyness S / K.
+    coefficients: Vec<Vec<f64>>,
+}
+
+/// Lower and upper bounds on a Bermudan price.
+#[derive(Debug, Clone, Copy)]
+pub struct DualBound {
+    /// Value of the policy on independent paths.
+    pub lower: Price,
+    /// Estimated duality gap, $\mathbb{E}[\max_k (h_k - M_k)] - L_0$.
+    pub duality_gap: Price,
+    /// Lower bound plus the duality gap.
+    pub upper: Price,
+}
+
+/// Value of an exercise policy and its pathwise Greeks.
+#[cfg(feature = "autodiff")]
+#[derive(Debug, Clone, Copy)]
+pub struct LsmcGreeks {
+    /// Value of the policy on the simulated paths.
+    pub price: Price,
+    /// Sensitivity to the spot price.
+    pub delta: f64,
+    /// Sensitivity to the volatility.
+    pub vega: f64,
+    /// Sensitivity to the risk-free rate.
+    pub rho: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BermudanOption {
+    /// Exercise date $t_k$.
+    #[must_use]
+    pub fn exercise_time(&self, k: usize) -> f64 {
+        k as f64 * self.time_to_expiry / self.exercises as f64
+    }
+
+    /// Undiscounted exercise value at the spot `spot`.
+    #[must_use]
+    pub fn payoff(&self, spot: f64) -> f64 {
+        match self.option_type {
+            TypeFlag::Call => (spot - self.strike).max(0.0),
+            TypeFlag::Put => (self.strike - spot).max(0.0),
+        }
+    }
+
+    /// Fit an exercise policy by regressing the discounted cashflows on
+    /// the in-the-money paths on $(S / K)^j$, $j = 0, \dots,$ `degree`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the option fails
+    ///   [`Validate::validate`], the degree is zero, or there are not more
+    ///   paths than basis functions.
+    pub fn longstaff_schwartz(
+        &self,
+        n_paths: usize,
+        degree: usize,
+        seed: u64,
+    ) -> Result<LsmcPolicy, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .check(degree >= 1, || {
+                "the basis degree must be positive".to_string()
+            })
+            .check(n_paths > degree + 1, || {
+                format!("at least {} paths are needed (got {n_paths})", degree + 2)
+            })
+            .finish()?;
+
+        let n = self.exercises;
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // paths[i][k] is the spot at t_k, k = 0..=n.
+        let paths: Vec<Vec<f64>> = (0..n_paths)
+            .map(|_| {
+                let mut spot = self.spot;
+                std::iter::once(spot)
+                    .chain((0..n).map(|_| {
+                        spot = self.step(spot, &mut rng);
+                        spot
+                    }))
+                    .collect()
+            })
+            .collect();
+
+        // Discounted (to today) cashflows under the policy fitted so far.
+        let mut cashflows: Vec<f64> = paths
+            .iter()
+            .map(|path| self.discount(n) * self.payoff(path[n]))
+            .collect();
+        let mut coefficients = vec![Vec::new(); n.saturating_sub(1)];
+
+        for k in (1..n).rev() {
+            let in_the_money: Vec<usize> = (0..n_paths)
+                .filter(|&i| self.payoff(paths[i][k]) > 0.0)
+                .collect();
+
+            if in_the_money.len() <= degree {
+                continue;
+            }
+
+            let basis = DMatrix::from_fn(in_the_money.len(), degree + 1, |row, column| {
+                (paths[in_the_money[row]][k] / self.strike).powi(column as i32)
+            });
+            let targets = DVector::from_iterator(
+                in_the_money.len(),
+                in_the_money
+                    .iter()
+                    .map(|&i| cashflows[i] / self.discount(k)),
+            );
+            let fitted = basis
+                .svd(true, true)
+                .solve(&targets, 1e-14)
+                .map_err(|error| RustQuantError::ComputationError(error.to_string()))?;
+
+            coefficients[k - 1] = fitted.iter().copied().collect();
+
+            for &i in &in_the_money {
+                let exercise = self.payoff(paths[i][k]);
+
+                if exercise >= polynomial(&coefficients[k - 1], paths[i][k] / self.strike) {
+                    cashflows[i] = self.discount(k) * exercise;
+                }
+            }
+        }
+
+        Ok(LsmcPolicy {
+            option: *self,
+            in_sample: estimate(&cashflows),
+            coefficients,
+        })
+    }
+
+    // Discount factor to t_k.
+    fn discount(&self, k: usize) -> f64 {
+        (-self.risk_free_rate * self.exercise_time(k)).exp()
+    }
+
+    // Exact step of the spot between consecutive exercise dates.
+    fn step(&self, spot: f64, rng: &mut StdRng) -> f64 {
+        let dt = self.time_to_expiry / self.exercises as f64;
+        let v = self.volatility;
+        let z: f64 = StandardNormal.sample(rng);
+
+        spot * ((self.risk_free_rate - self.dividend_yield - 0.5 * v * v) * dt + v * dt.sqrt() * z)
+            .exp()
+    }
+}
+
+impl Validate for BermudanOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("spot", self.spot)
+            .positive("strike", self.strike)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("dividend_yield", self.dividend_yield)
+            .positive("volatility", self.volatility)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .check(self.exercises >= 1, || {
+                "at least one exercise date is needed".to_string()
+            })
+            .finish()
+    }
+}
+
+impl LsmcPolicy {
+    /// Regressed continuation value at $t_k$ (undiscounted), zero at
+    /// expiry or where the fit had too few in-the-money paths.
+    #[must_use]
+    pub fn continuation_value(&self, k: usize, spot: f64) -> f64 {
+        match self.coefficients.get(k.wrapping_sub(1)) {
+            Some(coefficients) if !coefficients.is_empty() => {
+                polynomial(coefficients, spot / self.option.strike)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Whether the policy exercises at $t_k$ with the spot at `spot`.
+    #[must_use]
+    pub fn exercises(&self, k: usize, spot: f64) -> bool {
+        let exercise = self.option.payoff(spot);
+
+        exercise > 0.0 && exercise >= self.continuation_value(k, spot)
+    }
+
+    /// Value of the policy on `n_paths` independent paths: a lower bound on
+    /// the price.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if there are fewer than two
+    ///   paths.
+    pub fn lower_bound(&self, n_paths: usize, seed: u64) -> Result<Price, RustQuantError> {
+        Validator::new()
+            .check(n_paths >= 2, || "at least two paths are needed".to_string())
+            .finish()?;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let values: Vec<f64> = (0..n_paths)
+            .map(|_| self.follow(0, self.option.spot, &mut rng))
+            .collect();
+
+        Ok(estimate(&values))
+    }
+
+    /// Andersen-Broadie bounds: the policy's value on `n_lower` paths, and
+    /// the duality gap on `n_outer` paths, each estimating the policy's
+    /// continuation values with `n_inner` nested paths at every exercise
+    /// date.
+    ///
+    /// The nested estimates add noise to the martingale, which can only
+    /// raise the upper bound: it stays conservative.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if there are fewer than two
+    ///   lower or outer paths, or no inner paths.
+    pub fn dual_bound(
+        &self,
+        n_lower: usize,
+        n_outer: usize,
+        n_inner: usize,
+        seed: u64,
+    ) -> Result<DualBound, RustQuantError> {
+        Validator::new()
+            .check(n_outer >= 2 && n_inner >= 1, || {
+                "at least two outer paths and one inner path are needed".to_string()
+            })
+            .finish()?;
+
+        let lower = self.lower_bound(n_lower, seed)?;
+
+        let option = &self.option;
+        let n = option.exercises;
+        let mut rng = StdRng::seed_from_u64(seed.wrapping_add(1));
+
+        // E_k[L_{k+1}]: the value of following the policy from t_{k+1} on.
+        let continuation = |k: usize, spot: f64, rng: &mut StdRng| match k == n {
+            true => 0.0,
+            false => (0..n_inner).map(|_| self.follow(k, spot, rng)).sum::<f64>() / n_inner as f64,
+        };
+
+        let gaps: Vec<f64> = (0..n_outer)
+            .map(|_| {
+                let mut spot = option.spot;
+                let initial = continuation(0, spot, &mut rng);
+                let (mut previous, mut martingale) = (initial, 0.0);
+                let mut maximum = f64::NEG_INFINITY;
+
+                for k in 1..=n {
+                    spot = option.step(spot, &mut rng);
+
+                    let exercise = option.discount(k) * option.payoff(spot);
+                    let next = continuation(k, spot, &mut rng);
+
+                    // L_k is the exercise value where the policy stops, and
+                    // the continuation value elsewhere.
+                    let value = match self.exercises(k, spot) {
+                        true => exercise,
+                        false => next,
+                    };
+
+                    martingale += value - previous;
+                    maximum = maximum.max(exercise - martingale);
+                    previous = next;
+                }
+
+                maximum - initial
+            })
+            .collect();
+
+        let duality_gap = estimate(&gaps);
+        let error = lower
+            .error
+            .unwrap_or(0.0)
+            .hypot(duality_gap.error.unwrap_or(0.0));
+
+        Ok(DualBound {
+            lower,
+            duality_gap,
+            upper: Price {
+                price: lower.price + duality_gap.price,
+                error: Some(error),
+            },
+        })
+    }
+
+    /// Value of the policy on `n_paths` independent paths, with its delta,
+    /// vega and rho.
+    ///
+    /// Each path is recorded on a tape with the spot, volatility and rate
+    /// as inputs. The policy picks the exercise date from the path's
+    /// values and the date is then held fixed, so one reverse sweep
+    /// differentiates the discounted exercise value. For an optimal policy
+    /// a small shift of the exercise boundary has no first-order effect on
+    /// the value, so there is no need to differentiate through the
+    /// regression; the Greeks are those of the policy's value, which is
+    /// close to the price for a good fit.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if there are fewer than two
+    ///   paths.
+    #[cfg(feature = "autodiff")]
+    pub fn greeks(&self, n_paths: usize, seed: u64) -> Result<LsmcGreeks, RustQuantError> {
+        Validator::new()
+            .check(n_paths >= 2, || "at least two paths are needed".to_string())
+            .finish()?;
+
+        let option = &self.option;
+        let dt = option.time_to_expiry / option.exercises as f64;
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let graph = Graph::new();
+
+        // All the normals of a path are drawn up front, so the paths do not
+        // depend on where earlier ones stopped.
+        let mut normals = vec![0.0; option.exercises];
+        let mut values = Vec::with_capacity(n_paths);
+        let (mut delta, mut vega, mut rho) = (0.0, 0.0, 0.0);
+
+        for _ in 0..n_paths {
+            normals
+                .iter_mut()
+                .for_each(|z| *z = StandardNormal.sample(&mut rng));
+
+            graph.clear();
+            let (initial, volatility, rate) = (
+                graph.var(option.spot),
+                graph.var(option.volatility),
+                graph.var(option.risk_free_rate),
+            );
+            let drift = (rate - option.dividend_yield - volatility * volatility * 0.5) * dt;
+
+            let mut spot = initial;
+            let stopped = normals.iter().enumerate().find_map(|(j, z)| {
+                spot *= (drift + volatility * (dt.sqrt() * z)).exp();
+
+                self.exercises(j + 1, spot.value).then_some((j + 1, spot))
+            });
+
+            let Some((k, spot)) = stopped else {
+                values.push(0.0);
+                continue;
+            };
+
+            let exercise = match option.option_type {
+                TypeFlag::Call => spot - option.strike,
+                TypeFlag::Put => option.strike - spot,
+            };
+            let value = (rate * -option.exercise_time(k)).exp() * exercise;
+            let adjoints = value.accumulate();
+
+            values.push(value.value);
+            delta += adjoints.wrt(&initial);
+            vega += adjoints.wrt(&volatility);
+            rho += adjoints.wrt(&rate);
+        }
+
+        let paths = n_paths as f64;
+
+        Ok(LsmcGreeks {
+            price: estimate(&values),
+            delta: delta / paths,
+            vega: vega / paths,
+            rho: rho / paths,
+        })
+    }
+
+    // Discounted cashflow of following the policy from the spot at t_k,
+    // exercising from t_{k+1} on.
+    fn follow(&self, k: usize, mut spot: f64, rng: &mut StdRng) -> f64 {
+        for j in k + 1..=self.option.exercises {
+            spot = self.option.step(spot, rng);
+
+            if self.exercises(j, spot) {
+                return self.option.discount(j) * self.option.payoff(spot);
+            }
+        }
+
+        0.0
+    }
+}
+
+impl DualBound {
+    /// Confidence interval $[L - z \, \sigma_L, U + z \, \sigma_U]$ for the
+    /// price, e.g. with `z = 1.96` for 95%.
+    #[must_use]
+    pub fn confidence_interval(&self, z: f64) -> (f64, f64) {
+        (
+            self.lower.price - z * self.lower.error.unwrap_or(0.0),
+            self.upper.price + z * self.upper.error.unwrap_or(0.0),
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Polynomial with the given coefficients, in increasing powers.
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |sum, c| sum * x + c)
+}
+
+// Sample mean and its standard error.
+fn estimate(values: &[f64]) -> Price {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    Price {
+        price: mean,
+        error: Some((variance / n).sqrt()),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_longstaff_schwartz {
+    use super::*;
+    use crate::instruments::options::generalised_black_scholes_merton;
+
+    // Longstaff and Schwartz (2001), table 1, with 10 exercise dates.
+    const PUT: BermudanOption = BermudanOption {
+        spot: 36.0,
+        strike: 40.0,
+        risk_free_rate: 0.06,
+        dividend_yield: 0.0,
+        volatility: 0.2,
+        time_to_expiry: 1.0,
+        exercises: 10,
+        option_type: TypeFlag::Put,
+    };
+
+    // Bermudan price from a 2000-step binomial tree.
+    const REFERENCE: f64 = 4.4428;
+
+    #[test]
+    fn test_dual_bounds_bracket_the_price() {
+        let policy = PUT.longstaff_schwartz(20_000, 3, 1).unwrap();
+        let bound = policy.dual_bound(20_000, 500, 100, 2).unwrap();
+        let (low, high) = bound.confidence_interval(3.0);
+
+        assert!(low < REFERENCE && REFERENCE < high, "{bound:?}");
+        assert!(bound.lower.price < bound.upper.price);
+        assert!(bound.duality_gap.price < 0.2, "{bound:?}");
+
+        // The in-sample estimate is close to the out-of-sample one.
+        assert!((policy.in_sample.price - REFERENCE).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_single_exercise_is_european() {
+        let european = BermudanOption {
+            exercises: 1,
+            ..PUT
+        };
+        let exact =
+            generalised_black_scholes_merton(36.0, 40.0, 0.2, 0.06, 0.06, 1.0, TypeFlag::Put);
+
+        let policy = european.longstaff_schwartz(1_000, 2, 1).unwrap();
+        let bound = policy.dual_bound(50_000, 200, 10, 3).unwrap();
+        let lower = bound.lower;
+
+        assert!((lower.price - exact).abs() < 4.0 * lower.error.unwrap());
+
+        // Without early exercise the value process is the policy's own
+        // martingale, and the gap vanishes.
+        assert!(bound.duality_gap.price.abs() < 1e-12, "{bound:?}");
+    }
+
+    #[test]
+    fn test_longstaff_schwartz_validation() {
+        let invalid = BermudanOption {
+            exercises: 0,
+            ..PUT
+        };
+
+        assert!(invalid.longstaff_schwartz(1_000, 3, 1).is_err());
+        assert!(PUT.longstaff_schwartz(4, 3, 1).is_err());
+        assert!(PUT.longstaff_schwartz(1_000, 0, 1).is_err());
+
+        let policy = PUT.longstaff_schwartz(1_000, 3, 1).unwrap();
+        assert!(policy.dual_bound(10, 1, 10, 1).is_err());
+        assert!(policy.lower_bound(1, 1).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "autodiff")]
+    fn test_greeks_of_european() {
+        let european = BermudanOption {
+            exercises: 1,
+            ..PUT
+        };
+        let price = |spot: f64, volatility: f64, rate: f64| {
+            generalised_black_scholes_merton(spot, 40.0, volatility, rate, rate, 1.0, TypeFlag::Put)
+        };
+        let h = 1e-5;
+
+        let policy = european.longstaff_schwartz(1_000, 2, 1).unwrap();
+        let greeks = policy.greeks(50_000, 4).unwrap();
+
+        let delta = (price(36.0 + h, 0.2, 0.06) - price(36.0 - h, 0.2, 0.06)) / (2.0 * h);
+        let vega = (price(36.0, 0.2 + h, 0.06) - price(36.0, 0.2 - h, 0.06)) / (2.0 * h);
+        let rho = (price(36.0, 0.2, 0.06 + h) - price(36.0, 0.2, 0.06 - h)) / (2.0 * h);
+        let error = greeks.price.error.unwrap();
+
+        assert!((greeks.price.price - price(36.0, 0.2, 0.06)).abs() < 4.0 * error);
+        assert!((greeks.delta - delta).abs() < 0.01, "{greeks:?}");
+        assert!((greeks.vega - vega).abs() < 0.5, "{greeks:?}");
+        assert!((greeks.rho - rho).abs() < 0.5, "{greeks:?}");
+    }
+
+    #[test]
+    #[cfg(feature = "autodiff")]
+    fn test_greeks_of_bermudan() {
+        // Central differences on the 2000-step binomial tree.
+        let (delta, vega, rho) = (-0.690, 11.08, -11.20);
+
+        let policy = PUT.longstaff_schwartz(20_000, 3, 1).unwrap();
+        let greeks = policy.greeks(50_000, 2).unwrap();
+
+        assert!((greeks.price.price - REFERENCE).abs() < 0.06, "{greeks:?}");
+        assert!((greeks.delta - delta).abs() < 0.02, "{greeks:?}");
+        assert!((greeks.vega - vega).abs() < 0.6, "{greeks:?}");
+        assert!((greeks.rho - rho).abs() < 0.7, "{greeks:?}");
+        assert!(policy.greeks(1, 1).is_err());
+
+        // Deep out of the money, the put is never exercised.
+        let far = BermudanOption { spot: 400.0, ..PUT };
+        let greeks = LsmcPolicy {
+            option: far,
+            ..policy
+        }
+        .greeks(1_000, 3)
+        .unwrap();
+
+        assert_eq!(greeks.price.price, 0.0);
+        assert_eq!((greeks.delta, greeks.vega, greeks.rho), (0.0, 0.0, 0.0));
+    }
+}
@@ -7,11 +7,15 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::error::RustQuantError;
+use crate::instruments::{options::TypeFlag, Instrument, PricingContext};
+use crate::math::distributions::{Distribution, Gaussian};
+use crate::time::today;
+use time::Date;
+
+#[cfg(feature = "stochastics")]
 use crate::{
-    instruments::options::TypeFlag,
-    math::distributions::{Distribution, Gaussian},
-    math::Statistic,
-    models::geometric_brownian_motion::GeometricBrownianMotion,
+    math::Statistic, models::geometric_brownian_motion::GeometricBrownianMotion,
     stochastics::process::StochasticProcess,
 };
 
@@ -23,6 +27,7 @@ use crate::{
 /// The strike can be either fixed or floating.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LookbackStrike {
     /// Floating strike lookback option.
     /// Payoffs:
@@ -39,6 +44,7 @@ pub enum LookbackStrike {
 /// Struct containing Lookback Option parameters.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LookbackOption {
     /// `S` - Initial price of the underlying.
     pub initial_price: f64,
@@ -69,6 +75,51 @@ pub struct LookbackOption {
 // LOOKBACK OPTION IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl Instrument for (LookbackOption, TypeFlag) {
+    /// Returns the price (net present value) of the option in the given
+    /// direction, in closed form.
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The time to maturity is counted from the context's valuation date,
+    /// and floating strike options read the volatility at the money.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = ctx.valuation_date;
+        let expiry = ctx.date_after(self.0.time_to_maturity);
+        let strike = self.0.strike_price.unwrap_or(self.0.initial_price);
+
+        let option = LookbackOption {
+            risk_free_rate: ctx
+                .risk_free_rate(None, start, expiry)
+                .unwrap_or(self.0.risk_free_rate),
+            volatility: ctx.volatility(strike, expiry).unwrap_or(self.0.volatility),
+            ..self.0
+        };
+
+        Ok(match self.1 {
+            TypeFlag::Call => option.price_analytic().0,
+            TypeFlag::Put => option.price_analytic().1,
+        })
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        today()
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Lookback Option"
+    }
+}
+
 impl LookbackOption {
     /// Closed-form lookback option price.
     #[must_use]
@@ -176,6 +227,7 @@ impl LookbackOption {
         }
     }
 
+    #[cfg(feature = "stochastics")]
     fn payoff(&self, option_type: TypeFlag, strike_type: LookbackStrike, path: &[f64]) -> f64 {
         // let S_min = path.iter().copied().fold(path[0] /*f64::NAN*/, f64::min);
         // let S_max = path.iter().copied().fold(path[0] /*f64::NAN*/, f64::max);
@@ -198,6 +250,7 @@ impl LookbackOption {
     }
 
     /// Monte Carlo simulation of the lookback option price.
+    #[cfg(feature = "stochastics")]
     #[must_use]
     pub fn price_simulated(&self, n_steps: usize, n_sims: usize, parallel: bool) -> (f64, f64) {
         let x_0 = self.initial_price;
@@ -262,7 +315,7 @@ impl LookbackOption {
 // LOOKBACK OPTION TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-#[cfg(test)]
+#[cfg(all(test, feature = "stochastics"))]
 mod tests_lookback {
     use super::*;
     use crate::assert_approx_equal;
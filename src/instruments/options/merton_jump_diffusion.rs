@@ -12,13 +12,15 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use super::TypeFlag;
-use crate::instruments::BlackScholesMerton;
+use crate::error::RustQuantError;
+use crate::instruments::{BlackScholesMerton, Instrument, PricingContext};
 use crate::time::{today, DayCountConvention};
 use time::Date;
 
 /// Merton (1976) jump diffusion model parameters.
 #[allow(clippy::module_name_repetitions)]
-#[derive(derive_builder::Builder, Debug)]
+#[derive(derive_builder::Builder, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Merton1976 {
     /// `underlying_price` - Initial price of the underlying.
     pub underlying_price: f64,
@@ -53,6 +55,46 @@ pub struct Merton1976 {
 // MERTON (1976) JUMP DIFFUSION OPTION PRICING
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl Instrument for Merton1976 {
+    /// Returns the price (net present value) of the instrument.
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The volatility is the diffusion volatility; the jumps are the model's own.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = self.evaluation_date.unwrap_or(ctx.valuation_date);
+
+        Ok(Self {
+            risk_free_rate: ctx
+                .risk_free_rate(None, start, self.expiration_date)
+                .unwrap_or(self.risk_free_rate),
+            volatility: ctx
+                .volatility(self.strike_price, self.expiration_date)
+                .unwrap_or(self.volatility),
+            evaluation_date: Some(start),
+            ..*self
+        }
+        .price())
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        self.evaluation_date.unwrap_or(today())
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Merton (1976) Jump Diffusion European Option"
+    }
+}
+
 impl Merton1976 {
     /// Merton (1976) Jump Diffusion Option Price formula.
     #[must_use]
@@ -75,13 +117,20 @@ impl Merton1976 {
 
         let mut price = 0_f64;
 
-        for i in 0..20 {
+        // Poisson probability of `i` jumps, updated recursively. Past the
+        // mean number of jumps the probabilities fall off geometrically, so
+        // the sum stops once they are negligible.
+        let mut weight = f64::exp(-self.lambda * tau);
+
+        for i in 0.. {
             bsm.volatility = Self::sigma(self, i, tau);
+            price += bsm.price() * weight;
 
-            let factorial: usize = (1..=i).product();
-            let numerator = f64::exp(-self.lambda * tau) * f64::powi(self.lambda * tau, i as i32);
+            if i as f64 > self.lambda * tau && weight < 1e-17 {
+                break;
+            }
 
-            price += bsm.price() * numerator / factorial as f64;
+            weight *= self.lambda * tau / (i + 1) as f64;
         }
 
         price
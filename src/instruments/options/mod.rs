@@ -7,50 +7,192 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+//! Option pricers.
+//!
+//! Without the `options` feature (e.g. in a `no_std` build) only the
+//! closed-form core is built: [`closed_form`], [`barrier`], [`binary`],
+//! [`barrier_builder`], [`soft_barrier`] and the option flags in [`option`].
+
 pub use crate::instruments::options::{
-    asian::*, bachelier::*, barrier::*, binary::*, binomial::*, black_scholes_merton::*,
-    forward_start::*, heston::*, implied_volatility::*, lookback::*, merton_jump_diffusion::*,
-    option::*, power::*,
+    barrier::*, barrier_builder::*, binary::*, closed_form::*, option::*, soft_barrier::*,
 };
 
+#[cfg(feature = "options")]
+pub use crate::instruments::options::{
+    american_implied_volatility::*, asian::*, bachelier::*, barrier_engines::*, batch::*,
+    bates::*, binomial::*, black_scholes_merton::*, carr_madan::*, dividends::*,
+    employee_stock_option::*, forward_start::*, heston::*, heston_calibration::*,
+    implied_carry::*, implied_volatility::*, jump_calibration::*, longstaff_schwartz::*,
+    lookback::*, merton_jump_diffusion::*, option_chain::*, power::*, probabilities::*,
+    rainbow::*, real_options::*, rough_bergomi::*, smile::*, spread::*, static_replication::*,
+    step::*, strategy::*, swing::*, vanna_volga::*, vix::*, warrant::*,
+};
+
+#[cfg(feature = "gpu")]
+pub use crate::instruments::options::gpu_monte_carlo::*;
+
+/// Implied volatility of American options, and de-Americanization.
+#[cfg(feature = "options")]
+pub mod american_implied_volatility;
+
 /// Asian option pricers.
+#[cfg(feature = "options")]
 pub mod asian;
 
 /// Bachelier option pricer.
+#[cfg(feature = "options")]
 pub mod bachelier;
 
 /// Barrier option pricers.
 pub mod barrier;
 
+/// Typestate builder for barrier options.
+pub mod barrier_builder;
+
+/// Barrier option pricing engines (analytic, finite difference, Monte Carlo).
+#[cfg(feature = "options")]
+pub mod barrier_engines;
+
+/// Batch entry points for the analytic pricers.
+#[cfg(feature = "options")]
+pub mod batch;
+
+/// Bates (1996) stochastic volatility with jumps model.
+#[cfg(feature = "options")]
+pub mod bates;
+
 /// Binary option pricers.
 pub mod binary;
 
 /// Binomial option pricers.
+#[cfg(feature = "options")]
 pub mod binomial;
 
 /// Generalised Black-Scholes-Merton option pricer.
+#[cfg(feature = "options")]
 pub mod black_scholes_merton;
 
+/// Carr-Madan FFT pricing of European options.
+#[cfg(feature = "options")]
+pub mod carr_madan;
+
+/// Closed-form pricing formulas, generic over the scalar type.
+pub mod closed_form;
+
+/// Discrete (cash and proportional) dividends.
+#[cfg(feature = "options")]
+pub mod dividends;
+
+/// Employee stock option (Hull-White) pricer.
+#[cfg(feature = "options")]
+pub mod employee_stock_option;
+
 /// Forward start options pricers.
+#[cfg(feature = "options")]
 pub mod forward_start;
 
+/// Experimental GPU Monte Carlo engine.
+#[cfg(feature = "gpu")]
+pub mod gpu_monte_carlo;
+
 /// Heston model option pricer.
+#[cfg(feature = "options")]
 pub mod heston;
 
+/// Heston model calibration to an implied volatility surface.
+#[cfg(feature = "options")]
+pub mod heston_calibration;
+
+/// Implied forwards, dividends, and borrow costs from put-call parity.
+#[cfg(feature = "options")]
+pub mod implied_carry;
+
 /// Implied volatility functions.
+#[cfg(feature = "options")]
 pub mod implied_volatility;
 
+/// Jump-diffusion (Merton, Kou, Bates) calibration to short-dated smiles.
+#[cfg(feature = "options")]
+pub mod jump_calibration;
+
+/// Longstaff-Schwartz Monte Carlo for Bermudan options, with dual bounds.
+#[cfg(feature = "options")]
+pub mod longstaff_schwartz;
+
 /// Lookback option pricers.
+#[cfg(feature = "options")]
 pub mod lookback;
 
 /// Merton (1976) jump diffusion model.
+#[cfg(feature = "options")]
 pub mod merton_jump_diffusion;
 
 /// Base option traits.
 pub mod option;
 
+/// Option chains with per-strike implied volatilities and Greeks.
+#[cfg(feature = "options")]
+pub mod option_chain;
+
 /// Power option pricers.
+#[cfg(feature = "options")]
 pub mod power;
 
+/// Exercise and barrier-touch probabilities under GBM.
+#[cfg(feature = "options")]
+pub mod probabilities;
+
+/// Rainbow (best-of and worst-of) option pricers.
+#[cfg(feature = "options")]
+pub mod rainbow;
+
+/// Real options (to defer, expand, abandon, or stage an investment).
+#[cfg(feature = "options")]
+pub mod real_options;
+
+/// Rough Bergomi model: hybrid-scheme simulation and turbocharged pricing.
+#[cfg(feature = "options")]
+pub mod rough_bergomi;
+
+/// Smile quotation conventions (delta- and strike-quoted volatilities).
+#[cfg(feature = "options")]
+pub mod smile;
+
+/// Soft barrier option pricers.
+pub mod soft_barrier;
+
+/// Spread option pricers (Kirk, Hurd-Zhou).
+#[cfg(feature = "options")]
+pub mod spread;
+
+/// Static (Carr-Madan) replication of European payoffs.
+#[cfg(feature = "options")]
+pub mod static_replication;
+
+/// Option strategies (straddles, spreads, collars) and payoff profiles.
+#[cfg(feature = "options")]
+pub mod strategy;
+
+/// Step (occupation-time) barrier option pricer.
+#[cfg(feature = "options")]
+pub mod step;
+
+/// Swing options on energy and commodities (volume-constrained multiple exercise).
+#[cfg(feature = "options")]
+pub mod swing;
+
+/// Vanna-volga smile adjustment for FX exotics.
+#[cfg(feature = "options")]
+pub mod vanna_volga;
+
+/// Forward variance curves, and VIX futures and options.
+#[cfg(feature = "options")]
+pub mod vix;
+
+/// Warrant pricer, adjusted for dilution.
+#[cfg(feature = "options")]
+pub mod warrant;
+
 /// Finite Difference Pricer
+#[cfg(feature = "options")]
 pub mod finite_difference_pricer;
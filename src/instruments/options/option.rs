@@ -7,7 +7,10 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use alloc::vec::Vec;
+
 /// Option contract data.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OptionContract {
     /// The option's type flag (call or put).
     pub type_flag: TypeFlag,
@@ -23,7 +26,8 @@ pub struct OptionContract {
 }
 
 /// Option type enum.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeFlag {
     /// Call option (right to BUY the underlying asset).
     Call = 1,
@@ -34,6 +38,7 @@ pub enum TypeFlag {
 
 /// American/European option type enum.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExerciseFlag {
     /// European option (can only be exercised at expiry).
     European,
@@ -47,6 +52,7 @@ pub enum ExerciseFlag {
 
 /// Option strike type enum.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StrikeFlag {
     /// Strike is fixed.
     Fixed,
@@ -57,6 +63,7 @@ pub enum StrikeFlag {
 
 /// Instrument settlement flag.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SettlementFlag {
     /// Cash settlement.
     Cash,
@@ -71,6 +78,7 @@ pub enum SettlementFlag {
 /// such as lookback options (S_min, S_max).
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OptionParameters {
     /// `S` - Initial price of the underlying.
     pub S: Vec<f64>,
@@ -108,7 +116,38 @@ impl OptionParameters {
     }
 }
 
-trait Payoff<U, S> {
+/// Early-exercise boundary of an American option, as produced by the
+/// lattice and finite difference pricers.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExerciseBoundary {
+    /// Times from valuation (in years), in increasing order.
+    pub times: Vec<f64>,
+
+    /// Critical underlying price at each time: the highest price at which a
+    /// put is exercised, or the lowest at which a call is exercised.
+    /// `None` if exercise is not optimal anywhere on the grid at that time.
+    pub critical_prices: Vec<Option<f64>>,
+}
+
+impl ExerciseBoundary {
+    /// Critical price at the last observation time at or before `t`.
+    #[must_use]
+    pub fn critical_price(&self, t: f64) -> Option<f64> {
+        let i = self.times.partition_point(|&time| time <= t);
+
+        match i {
+            0 => None,
+            _ => self.critical_prices[i - 1],
+        }
+    }
+}
+
+/// Payoff of an option contract at expiry.
+///
+/// For a path of underlying prices, the payoff is averaged over the path.
+pub trait Payoff<U, S> {
+    /// Payoff given the underlying price(s) and the strike.
     fn payoff(&self, underlying: U, strike: S) -> f64;
 }
 
@@ -0,0 +1,600 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Option chains: market quotes organised by expiry and strike.
+//!
+//! An [`OptionChain`] is built from bid/ask quotes. For each expiry, the
+//! forward is implied from put-call parity,
+//!
+//! ```text
+//! C - P = exp(-r T) (F - K),
+//! ```
+//!
+//! at the strike where the call and put mid prices are closest (as in the
+//! CBOE VIX methodology), together with the dividend yield it implies.
+//! Each quote is then given an implied volatility and Black-Scholes Greeks
+//! from its mid price, and the out-of-the-money volatilities form the smile.
+
+use super::{
+    implied_carry, try_implied_volatility, ImpliedCarry, StrikeVolQuote, SyntheticQuote, TypeFlag,
+};
+use crate::error::RustQuantError;
+use crate::instruments::{Greeks, Validate, Validator};
+use crate::math::Real;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market quote of a European option.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionQuote {
+    /// Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// Strike price.
+    pub strike: f64,
+    /// Call or put.
+    pub option_type: TypeFlag,
+    /// Bid price.
+    pub bid: f64,
+    /// Ask price.
+    pub ask: f64,
+}
+
+/// Quote of one option in a chain, with its analytics.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ChainQuote {
+    /// Bid price.
+    pub bid: f64,
+    /// Ask price.
+    pub ask: f64,
+    /// Implied volatility of the mid price, if attainable.
+    pub implied_volatility: Option<f64>,
+    /// Black-Scholes Greeks at the implied volatility.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub greeks: Option<Greeks>,
+}
+
+/// Call and put quotes of one strike.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ChainStrike {
+    /// Strike price.
+    pub strike: f64,
+    /// Call quote, if any.
+    pub call: Option<ChainQuote>,
+    /// Put quote, if any.
+    pub put: Option<ChainQuote>,
+}
+
+/// Quotes of one expiry, in increasing strike order.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ChainExpiry {
+    /// Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// Discount factor to expiry.
+    pub discount_factor: f64,
+    /// Forward implied from put-call parity, if any strike has both a call
+    /// and a put quote.
+    pub forward: Option<f64>,
+    /// Continuous dividend yield implied by the forward.
+    pub implied_dividend_yield: Option<f64>,
+    /// Quotes by strike.
+    pub strikes: Vec<ChainStrike>,
+}
+
+/// Option chain: quotes on one underlying, by expiry and strike.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OptionChain {
+    /// Spot price of the underlying.
+    pub spot: f64,
+    /// Continuously compounded risk-free rate.
+    pub risk_free_rate: f64,
+    /// Expiries, in increasing order.
+    pub expiries: Vec<ChainExpiry>,
+}
+
+/// Put-call parity check at one strike.
+///
+/// The parity value `exp(-r T) (F - K)` must lie within the band
+/// `[C_bid - P_ask, C_ask - P_bid]`, otherwise a conversion or reversal
+/// locks in a profit against the forward.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParityCheck {
+    /// Time to expiry (in years).
+    pub time_to_expiry: f64,
+    /// Strike price.
+    pub strike: f64,
+    /// Call bid less put ask.
+    pub lower: f64,
+    /// Call ask less put bid.
+    pub upper: f64,
+    /// Discounted forward less strike.
+    pub parity: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ChainQuote {
+    /// Mid price.
+    #[must_use]
+    pub fn mid(&self) -> f64 {
+        0.5 * (self.bid + self.ask)
+    }
+}
+
+impl ChainExpiry {
+    /// Smile of the out-of-the-money options: puts below the forward and
+    /// calls at or above it, falling back to the other side where a quote or
+    /// its implied volatility is missing.
+    #[must_use]
+    pub fn smile(&self) -> Vec<StrikeVolQuote> {
+        let forward = self.forward.unwrap_or(f64::NAN);
+
+        self.strikes
+            .iter()
+            .filter_map(|row| {
+                let vol = |quote: Option<ChainQuote>| quote.and_then(|q| q.implied_volatility);
+                let (otm, itm) = match row.strike < forward {
+                    true => (row.put, row.call),
+                    false => (row.call, row.put),
+                };
+
+                vol(otm).or(vol(itm)).map(|volatility| StrikeVolQuote {
+                    strike: row.strike,
+                    volatility,
+                })
+            })
+            .collect()
+    }
+
+    /// Put-call parity checks at the strikes with both a call and a put
+    /// quote. Empty if the forward could not be implied.
+    #[must_use]
+    pub fn parity_checks(&self) -> Vec<ParityCheck> {
+        let Some(forward) = self.forward else {
+            return Vec::new();
+        };
+
+        self.strikes
+            .iter()
+            .filter_map(|row| {
+                let (call, put) = (row.call?, row.put?);
+
+                Some(ParityCheck {
+                    time_to_expiry: self.time_to_expiry,
+                    strike: row.strike,
+                    lower: call.bid - put.ask,
+                    upper: call.ask - put.bid,
+                    parity: self.discount_factor * (forward - row.strike),
+                })
+            })
+            .collect()
+    }
+
+    /// Mid prices at the strikes with both a call and a put quote, for
+    /// [`implied_carry`].
+    #[must_use]
+    pub fn synthetic_quotes(&self) -> Vec<SyntheticQuote> {
+        self.strikes
+            .iter()
+            .filter_map(|row| {
+                Some(SyntheticQuote {
+                    strike: row.strike,
+                    call: row.call?.mid(),
+                    put: row.put?.mid(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl ParityCheck {
+    /// Whether the parity value lies outside the bid/ask band by more than
+    /// `tolerance`.
+    #[must_use]
+    pub fn is_violated(&self, tolerance: f64) -> bool {
+        self.parity < self.lower - tolerance || self.parity > self.upper + tolerance
+    }
+}
+
+impl OptionChain {
+    /// Build a chain from market quotes.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the spot is not positive or
+    ///   the rate not finite, if a quote fails [`Validate::validate`], or if
+    ///   two quotes have the same expiry, strike, and type.
+    pub fn new(
+        spot: f64,
+        risk_free_rate: f64,
+        quotes: &[OptionQuote],
+    ) -> Result<Self, RustQuantError> {
+        Validator::new()
+            .positive("spot", spot)
+            .finite("risk_free_rate", risk_free_rate)
+            .finish()?;
+        quotes.iter().try_for_each(Validate::validate)?;
+
+        let mut quotes = quotes.to_vec();
+        quotes.sort_by(|a, b| {
+            a.time_to_expiry
+                .total_cmp(&b.time_to_expiry)
+                .then(a.strike.total_cmp(&b.strike))
+        });
+
+        let expiries = quotes
+            .chunk_by(|a, b| a.time_to_expiry == b.time_to_expiry)
+            .map(|quotes| Self::build_expiry(spot, risk_free_rate, quotes))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            spot,
+            risk_free_rate,
+            expiries,
+        })
+    }
+
+    /// Expiry with the given time to expiry, if quoted.
+    #[must_use]
+    pub fn expiry(&self, time_to_expiry: f64) -> Option<&ChainExpiry> {
+        self.expiries
+            .iter()
+            .find(|expiry| expiry.time_to_expiry == time_to_expiry)
+    }
+
+    /// Put-call parity checks over all expiries.
+    #[must_use]
+    pub fn parity_checks(&self) -> Vec<ParityCheck> {
+        self.expiries
+            .iter()
+            .flat_map(ChainExpiry::parity_checks)
+            .collect()
+    }
+
+    /// Forward, discount factor, and carry implied by the call and put mid
+    /// prices of the given expiry. See [`implied_carry`].
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the expiry is not quoted.
+    /// * Any error from [`implied_carry`].
+    pub fn implied_carry(&self, time_to_expiry: f64) -> Result<ImpliedCarry, RustQuantError> {
+        let expiry = self.expiry(time_to_expiry).ok_or_else(|| {
+            RustQuantError::InvalidArgument(format!("No quotes for expiry {time_to_expiry}."))
+        })?;
+
+        implied_carry(self.spot, time_to_expiry, &expiry.synthetic_quotes())
+    }
+
+    // Build one expiry from its quotes, sorted by strike.
+    fn build_expiry(
+        spot: f64,
+        r: f64,
+        quotes: &[OptionQuote],
+    ) -> Result<ChainExpiry, RustQuantError> {
+        let T = quotes[0].time_to_expiry;
+        let discount_factor = (-r * T).exp();
+
+        let mut strikes: Vec<ChainStrike> = Vec::new();
+
+        for quote in quotes {
+            if strikes.last().map(|row| row.strike) != Some(quote.strike) {
+                strikes.push(ChainStrike {
+                    strike: quote.strike,
+                    call: None,
+                    put: None,
+                });
+            }
+
+            let row = strikes.last_mut().expect("a row was just pushed");
+            let slot = match quote.option_type {
+                TypeFlag::Call => &mut row.call,
+                TypeFlag::Put => &mut row.put,
+            };
+
+            if slot.is_some() {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "Duplicate {:?} quote at expiry {T} and strike {}.",
+                    quote.option_type, quote.strike
+                )));
+            }
+
+            *slot = Some(ChainQuote {
+                bid: quote.bid,
+                ask: quote.ask,
+                implied_volatility: None,
+                greeks: None,
+            });
+        }
+
+        // Forward at the strike where the call and put are closest in price.
+        let forward = strikes
+            .iter()
+            .filter_map(|row| Some((row.strike, row.call?.mid() - row.put?.mid())))
+            .min_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map(|(strike, difference)| strike + difference / discount_factor)
+            .filter(|forward| *forward > 0.0);
+
+        let implied_dividend_yield = forward.map(|forward| r - (forward / spot).ln() / T);
+
+        // Without a forward, assume no dividends.
+        let F = forward.unwrap_or(spot / discount_factor);
+        let q = implied_dividend_yield.unwrap_or(0.0);
+
+        for row in &mut strikes {
+            for (quote, option_type) in [
+                (&mut row.call, TypeFlag::Call),
+                (&mut row.put, TypeFlag::Put),
+            ] {
+                if let Some(quote) = quote {
+                    let K = row.strike;
+                    let volatility = try_implied_volatility(
+                        quote.mid(),
+                        F * discount_factor,
+                        K,
+                        T,
+                        r,
+                        option_type,
+                    )
+                    .ok();
+
+                    quote.implied_volatility = volatility;
+                    quote.greeks = volatility.map(|v| greeks(spot, K, v, r, q, T, option_type));
+                }
+            }
+        }
+
+        Ok(ChainExpiry {
+            time_to_expiry: T,
+            discount_factor,
+            forward,
+            implied_dividend_yield,
+            strikes,
+        })
+    }
+}
+
+impl Validate for OptionQuote {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("time_to_expiry", self.time_to_expiry)
+            .positive("strike", self.strike)
+            .non_negative("bid", self.bid)
+            .non_negative("ask", self.ask)
+            .check(self.bid <= self.ask, || {
+                format!("bid must not exceed ask (got {} > {})", self.bid, self.ask)
+            })
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Black-Scholes-Merton Greeks with dividend yield `q` (see Haug, 2007).
+fn greeks(S: f64, K: f64, v: f64, r: f64, q: f64, T: f64, option_type: TypeFlag) -> Greeks {
+    let sqrt_T = T.sqrt();
+    let d1 = ((S / K).ln() + (r - q + 0.5 * v * v) * T) / (v * sqrt_T);
+    let d2 = d1 - v * sqrt_T;
+
+    let carry = (-q * T).exp();
+    let discount = (-r * T).exp();
+    let density = d1.norm_pdf();
+
+    let gamma = carry * density / (S * v * sqrt_T);
+    let vega = S * carry * density * sqrt_T;
+    let decay = -S * carry * density * v / (2.0 * sqrt_T);
+
+    match option_type {
+        TypeFlag::Call => Greeks {
+            delta: carry * d1.norm_cdf(),
+            gamma,
+            vega,
+            theta: decay + q * S * carry * d1.norm_cdf() - r * K * discount * d2.norm_cdf(),
+            rho: K * T * discount * d2.norm_cdf(),
+        },
+        TypeFlag::Put => Greeks {
+            delta: -carry * (-d1).norm_cdf(),
+            gamma,
+            vega,
+            theta: decay - q * S * carry * (-d1).norm_cdf() + r * K * discount * (-d2).norm_cdf(),
+            rho: -K * T * discount * (-d2).norm_cdf(),
+        },
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_option_chain {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::generalised_black_scholes_merton;
+
+    const S: f64 = 100.0;
+    const R: f64 = 0.05;
+    const Q: f64 = 0.02;
+    const SPREAD: f64 = 0.05;
+
+    // Volatility smile used to generate the quotes.
+    fn smile_vol(K: f64, T: f64) -> f64 {
+        let F = S * ((R - Q) * T).exp();
+        0.2 + 0.5 * (K / F).ln().powi(2)
+    }
+
+    fn quote(T: f64, K: f64, option_type: TypeFlag) -> OptionQuote {
+        let price =
+            generalised_black_scholes_merton(S, K, smile_vol(K, T), R, R - Q, T, option_type);
+
+        OptionQuote {
+            time_to_expiry: T,
+            strike: K,
+            option_type,
+            bid: price - SPREAD,
+            ask: price + SPREAD,
+        }
+    }
+
+    fn quotes() -> Vec<OptionQuote> {
+        let mut quotes = Vec::new();
+
+        for T in [0.5, 0.25] {
+            for K in [80.0, 90.0, 100.0, 110.0, 120.0] {
+                quotes.push(quote(T, K, TypeFlag::Call));
+                quotes.push(quote(T, K, TypeFlag::Put));
+            }
+        }
+
+        // An expiry quoted in calls only.
+        quotes.push(quote(1.0, 100.0, TypeFlag::Call));
+
+        quotes
+    }
+
+    #[test]
+    fn test_chain_structure() {
+        let chain = OptionChain::new(S, R, &quotes()).unwrap();
+
+        assert_eq!(chain.expiries.len(), 3);
+        assert_eq!(chain.expiries[0].time_to_expiry, 0.25);
+        assert_eq!(chain.expiry(0.5).unwrap().strikes.len(), 5);
+        assert!(chain.expiry(0.75).is_none());
+    }
+
+    #[test]
+    fn test_forward_and_implied_dividend() {
+        let chain = OptionChain::new(S, R, &quotes()).unwrap();
+
+        for expiry in &chain.expiries[..2] {
+            let T = expiry.time_to_expiry;
+
+            assert_approx_equal!(expiry.forward.unwrap(), S * ((R - Q) * T).exp(), 1e-9);
+            assert_approx_equal!(expiry.implied_dividend_yield.unwrap(), Q, 1e-9);
+        }
+
+        assert!(chain.expiry(1.0).unwrap().forward.is_none());
+    }
+
+    #[test]
+    fn test_implied_carry() {
+        let chain = OptionChain::new(S, R, &quotes()).unwrap();
+        let carry = chain.implied_carry(0.5).unwrap();
+
+        assert_approx_equal!(carry.discount_factor, (-R * 0.5).exp(), 1e-12);
+        assert_approx_equal!(carry.implied_yield, Q, 1e-9);
+        assert!(chain.implied_carry(1.0).is_err());
+        assert!(chain.implied_carry(0.75).is_err());
+    }
+
+    #[test]
+    fn test_implied_volatilities_and_smile() {
+        let chain = OptionChain::new(S, R, &quotes()).unwrap();
+        let expiry = chain.expiry(0.5).unwrap();
+
+        for row in &expiry.strikes {
+            let expected = smile_vol(row.strike, 0.5);
+
+            assert_approx_equal!(
+                row.call.unwrap().implied_volatility.unwrap(),
+                expected,
+                1e-8
+            );
+            assert_approx_equal!(row.put.unwrap().implied_volatility.unwrap(), expected, 1e-8);
+        }
+
+        let smile = expiry.smile();
+        assert_eq!(smile.len(), 5);
+        assert_approx_equal!(smile[0].volatility, smile_vol(80.0, 0.5), 1e-8);
+    }
+
+    #[test]
+    fn test_greeks() {
+        let chain = OptionChain::new(S, R, &quotes()).unwrap();
+        let row = chain.expiry(0.5).unwrap().strikes[3];
+        let v = row.call.unwrap().implied_volatility.unwrap();
+
+        let price = |spot: f64| {
+            generalised_black_scholes_merton(spot, 110.0, v, R, R - Q, 0.5, TypeFlag::Call)
+        };
+        let h = 1e-4;
+
+        let greeks = row.call.unwrap().greeks.unwrap();
+        assert_approx_equal!(
+            greeks.delta,
+            (price(S + h) - price(S - h)) / (2.0 * h),
+            1e-6
+        );
+        assert_approx_equal!(
+            greeks.gamma,
+            (price(S + h) - 2.0 * price(S) + price(S - h)) / (h * h),
+            1e-4
+        );
+
+        // Put-call parity for delta.
+        let put = row.put.unwrap().greeks.unwrap();
+        assert_approx_equal!(greeks.delta - put.delta, (-Q * 0.5_f64).exp(), 1e-6);
+    }
+
+    #[test]
+    fn test_parity_checks() {
+        let chain = OptionChain::new(S, R, &quotes()).unwrap();
+
+        assert_eq!(chain.parity_checks().len(), 10);
+        assert!(chain
+            .parity_checks()
+            .iter()
+            .all(|check| !check.is_violated(0.0)));
+
+        // A stale put quote far below fair value breaks parity.
+        let mut quotes = quotes();
+        let stale = quotes
+            .iter_mut()
+            .find(|q| {
+                q.time_to_expiry == 0.5 && q.strike == 120.0 && q.option_type == TypeFlag::Put
+            })
+            .unwrap();
+        stale.bid -= 1.0;
+        stale.ask -= 1.0;
+
+        let chain = OptionChain::new(S, R, &quotes).unwrap();
+        let violations: Vec<ParityCheck> = chain
+            .parity_checks()
+            .into_iter()
+            .filter(|check| check.is_violated(0.0))
+            .collect();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].strike, 120.0);
+    }
+
+    #[test]
+    fn test_chain_validation() {
+        let mut quotes = quotes();
+        quotes.push(quote(0.5, 100.0, TypeFlag::Call));
+        assert!(OptionChain::new(S, R, &quotes).is_err());
+
+        let crossed = OptionQuote {
+            bid: 2.0,
+            ask: 1.0,
+            ..quote(0.5, 100.0, TypeFlag::Call)
+        };
+        assert!(OptionChain::new(S, R, &[crossed]).is_err());
+        assert!(OptionChain::new(-1.0, R, &[]).is_err());
+    }
+}
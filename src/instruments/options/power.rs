@@ -12,6 +12,8 @@
 //! Power contracts are options with the payoff: (S/K)^i
 //! where i is the (fixed) power of the contract.
 
+use crate::error::RustQuantError;
+use crate::instruments::{Instrument, PricingContext};
 use crate::time::{today, DayCountConvention};
 use time::Date;
 
@@ -22,6 +24,7 @@ use time::Date;
 /// Power Option contract.
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerOption {
     /// `S` - Initial price of the underlying.
     pub initial_price: f64,
@@ -47,6 +50,48 @@ pub struct PowerOption {
 // IMPLEMENTATIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+impl Instrument for PowerOption {
+    /// Returns the price (net present value) of the instrument.
+    ///
+    /// The risk-free rate and volatility are read off the context (see
+    /// [`PricingContext::risk_free_rate`] and [`PricingContext::volatility`]),
+    /// keeping the option's own where the context has none.
+    /// The cost of carry moves with the rate.
+    fn price(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let start = self.evaluation_date.unwrap_or(ctx.valuation_date);
+        let r = ctx
+            .risk_free_rate(None, start, self.expiration_date)
+            .unwrap_or(self.risk_free_rate);
+
+        Ok(Self {
+            cost_of_carry: self.cost_of_carry + r - self.risk_free_rate,
+            risk_free_rate: r,
+            volatility: ctx
+                .volatility(self.strike_price, self.expiration_date)
+                .unwrap_or(self.volatility),
+            evaluation_date: Some(start),
+            ..*self
+        }
+        .price())
+    }
+
+    /// Returns the error on the NPV in case the pricing engine can
+    /// provide it (e.g. Monte Carlo pricing engine).
+    fn error(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the date at which the NPV is calculated.
+    fn valuation_date(&self) -> Date {
+        self.evaluation_date.unwrap_or(today())
+    }
+
+    /// Instrument type.
+    fn instrument_type(&self) -> &'static str {
+        "Power Option"
+    }
+}
+
 impl PowerOption {
     /// New Power Option contract.
     #[allow(clippy::too_many_arguments)]
@@ -0,0 +1,339 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Exercise and barrier-touch probabilities under geometric Brownian motion.
+//!
+//! With `dS = m S dt + v S dW`, the log-price is a Brownian motion with
+//! drift `nu = m - v^2 / 2`, and the first-passage time to a barrier `H` has
+//! a closed-form distribution (the reflection principle with drift):
+//!
+//! ```text
+//! P(touch H by T) = N((-b + nu T) / (v √T)) + exp(2 nu b / v^2) N((-b - nu T) / (v √T))
+//! ```
+//!
+//! for `b = ln(H / S) > 0`, and symmetrically for barriers below the spot.
+//! The drift `m` is `r - q` for risk-neutral probabilities, or `mu - q` for
+//! real-world probabilities given an expected return `mu`.
+
+use super::TypeFlag;
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+use crate::math::{integrate, Real};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Probability analytics for an underlying following geometric Brownian
+/// motion.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GbmProbabilities {
+    /// Spot price of the underlying.
+    pub initial_price: f64,
+    /// Drift of the underlying price (net of the dividend yield).
+    pub drift: f64,
+    /// Volatility of the underlying.
+    pub volatility: f64,
+    /// Time horizon (in years).
+    pub time_to_expiry: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl GbmProbabilities {
+    /// Risk-neutral probabilities, with drift `r - q`.
+    #[must_use]
+    pub fn risk_neutral(
+        initial_price: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        time_to_expiry: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            drift: risk_free_rate - dividend_yield,
+            volatility,
+            time_to_expiry,
+        }
+    }
+
+    /// Real-world probabilities, with drift `mu - q` for an expected total
+    /// return `mu`.
+    #[must_use]
+    pub fn real_world(
+        initial_price: f64,
+        expected_return: f64,
+        dividend_yield: f64,
+        volatility: f64,
+        time_to_expiry: f64,
+    ) -> Self {
+        Self {
+            initial_price,
+            drift: expected_return - dividend_yield,
+            volatility,
+            time_to_expiry,
+        }
+    }
+
+    /// Probability that an option struck at `strike` finishes in the money.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`] or the strike is not positive.
+    pub fn prob_in_the_money(
+        &self,
+        strike: f64,
+        option_type: TypeFlag,
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        Validator::new().positive("strike", strike).finish()?;
+
+        let d2 = ((self.initial_price / strike).ln() + self.log_drift() * self.time_to_expiry)
+            / (self.volatility * self.time_to_expiry.sqrt());
+
+        Ok(match option_type {
+            TypeFlag::Call => d2.norm_cdf(),
+            TypeFlag::Put => (-d2).norm_cdf(),
+        })
+    }
+
+    /// Probability that the underlying touches `barrier` before expiry.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`] or the barrier is not positive.
+    pub fn prob_touch(&self, barrier: f64) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        Validator::new().positive("barrier", barrier).finish()?;
+
+        Ok(self.touch_by(barrier, self.time_to_expiry))
+    }
+
+    /// Expected first-passage time to `barrier`, over an unbounded horizon.
+    ///
+    /// Returns `None` when the drift of the log-price points away from the
+    /// barrier (or is zero), since the barrier is then either never reached
+    /// with positive probability or reached in infinite expected time.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`] or the barrier is not positive.
+    pub fn expected_first_passage_time(&self, barrier: f64) -> Result<Option<f64>, RustQuantError> {
+        self.validate()?;
+        Validator::new().positive("barrier", barrier).finish()?;
+
+        let b = (barrier / self.initial_price).ln();
+        let nu = self.log_drift();
+
+        Ok(if b == 0.0 {
+            Some(0.0)
+        } else if b * nu > 0.0 {
+            Some(b / nu)
+        } else {
+            None
+        })
+    }
+
+    /// Expected time until the underlying touches `barrier` or the option
+    /// expires, whichever comes first: `E[min(tau, T)]`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`] or the barrier is not positive.
+    pub fn expected_time_to_touch(&self, barrier: f64) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        Validator::new().positive("barrier", barrier).finish()?;
+
+        // E[min(tau, T)] = ∫_0^T P(tau > t) dt.
+        Ok(integrate(
+            |t| 1.0 - self.touch_by(barrier, t),
+            0.0,
+            self.time_to_expiry,
+        ))
+    }
+
+    // Drift of the log-price.
+    fn log_drift(&self) -> f64 {
+        self.drift - 0.5 * self.volatility * self.volatility
+    }
+
+    // Probability of touching the barrier by time `t`.
+    fn touch_by(&self, barrier: f64, t: f64) -> f64 {
+        let b = (barrier / self.initial_price).ln();
+
+        if b == 0.0 {
+            return 1.0;
+        }
+        if t <= 0.0 {
+            return 0.0;
+        }
+
+        let v = self.volatility;
+        let nu = self.log_drift();
+        let s = v * t.sqrt();
+        let reflection = (2.0 * nu * b / (v * v)).exp();
+
+        // Reflect an upper barrier into a lower one by flipping the drift.
+        let (b, nu) = match b > 0.0 {
+            true => (-b, -nu),
+            false => (b, nu),
+        };
+
+        ((b - nu * t) / s).norm_cdf() + reflection * ((b + nu * t) / s).norm_cdf()
+    }
+}
+
+impl Validate for GbmProbabilities {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .finite("drift", self.drift)
+            .positive("volatility", self.volatility)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_probabilities {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const PROBABILITIES: GbmProbabilities = GbmProbabilities {
+        initial_price: 100.0,
+        drift: 0.03,
+        volatility: 0.25,
+        time_to_expiry: 1.0,
+    };
+
+    #[test]
+    fn test_prob_in_the_money() {
+        let call = PROBABILITIES
+            .prob_in_the_money(110.0, TypeFlag::Call)
+            .unwrap();
+        let put = PROBABILITIES
+            .prob_in_the_money(110.0, TypeFlag::Put)
+            .unwrap();
+
+        assert_approx_equal!(call, 0.349_659_199_749_719, 1e-10);
+        assert_approx_equal!(call + put, 1.0, 1e-12);
+    }
+
+    #[test]
+    fn test_prob_touch() {
+        let up = PROBABILITIES.prob_touch(120.0).unwrap();
+        let down = PROBABILITIES.prob_touch(85.0).unwrap();
+
+        assert_approx_equal!(up, 0.464_128_614_693_419, 1e-10);
+        assert_approx_equal!(down, 0.517_319_543_004_165, 1e-10);
+        assert_approx_equal!(PROBABILITIES.prob_touch(100.0).unwrap(), 1.0, 1e-12);
+
+        // Touching is more likely than finishing beyond the barrier.
+        let finish = PROBABILITIES
+            .prob_in_the_money(120.0, TypeFlag::Call)
+            .unwrap();
+        assert!(up > finish);
+    }
+
+    #[test]
+    fn test_reflection_principle() {
+        // With zero log-drift, the touch probability is exactly twice the
+        // probability of finishing beyond the barrier.
+        let probabilities = GbmProbabilities {
+            drift: 0.5 * 0.25 * 0.25,
+            ..PROBABILITIES
+        };
+
+        for (barrier, option_type) in [(130.0, TypeFlag::Call), (75.0, TypeFlag::Put)] {
+            assert_approx_equal!(
+                probabilities.prob_touch(barrier).unwrap(),
+                2.0 * probabilities
+                    .prob_in_the_money(barrier, option_type)
+                    .unwrap(),
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_real_world_drift() {
+        let real_world = GbmProbabilities::real_world(100.0, 0.1, 0.02, 0.25, 1.0);
+        let risk_neutral = GbmProbabilities::risk_neutral(100.0, 0.05, 0.02, 0.25, 1.0);
+
+        assert_approx_equal!(
+            real_world.prob_touch(120.0).unwrap(),
+            0.532_692_187_101_102,
+            1e-10
+        );
+        assert!(real_world.prob_touch(120.0).unwrap() > risk_neutral.prob_touch(120.0).unwrap());
+    }
+
+    #[test]
+    fn test_first_passage_times() {
+        assert_approx_equal!(
+            PROBABILITIES.expected_time_to_touch(120.0).unwrap(),
+            0.733_406_508_851_468,
+            1e-8
+        );
+
+        // Positive log-drift: the upper barrier is reached in finite
+        // expected time, the lower one is not.
+        let probabilities = GbmProbabilities {
+            drift: 0.1,
+            ..PROBABILITIES
+        };
+        let nu_up = 0.1 - 0.5 * 0.25 * 0.25;
+
+        assert_approx_equal!(
+            probabilities
+                .expected_first_passage_time(120.0)
+                .unwrap()
+                .unwrap(),
+            1.2_f64.ln() / nu_up,
+            1e-12
+        );
+        assert_eq!(
+            probabilities.expected_first_passage_time(85.0).unwrap(),
+            None
+        );
+
+        // The truncated expectation approaches it over a long horizon.
+        let long = GbmProbabilities {
+            time_to_expiry: 200.0,
+            ..probabilities
+        };
+        assert_approx_equal!(
+            long.expected_time_to_touch(120.0).unwrap(),
+            1.2_f64.ln() / nu_up,
+            1e-3
+        );
+    }
+
+    #[test]
+    fn test_probabilities_validation() {
+        assert!(PROBABILITIES.prob_touch(-1.0).is_err());
+        assert!(PROBABILITIES
+            .prob_in_the_money(0.0, TypeFlag::Call)
+            .is_err());
+
+        let probabilities = GbmProbabilities {
+            volatility: 0.0,
+            ..PROBABILITIES
+        };
+        assert!(probabilities.prob_touch(120.0).is_err());
+    }
+}
@@ -0,0 +1,477 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Rainbow options on the best (maximum) or worst (minimum) of several
+//! correlated assets.
+//!
+//! Two assets are priced with Stulz's (1982) formulas, and three with
+//! Johnson's (1987) extension via the trivariate normal CDF. More assets
+//! can be priced by Monte Carlo.
+//!
+//! ```
+//! use RustQuant::instruments::options::*;
+//!
+//! let option = RainbowOption {
+//!     initial_prices: vec![100.0, 105.0],
+//!     volatilities: vec![0.11, 0.16],
+//!     dividend_yields: vec![0.06, 0.09],
+//!     correlation: vec![vec![1.0, 0.63], vec![0.63, 1.0]],
+//!     strike_price: 98.0,
+//!     time_to_expiry: 0.5,
+//!     risk_free_rate: 0.05,
+//! };
+//!
+//! let best_of = option.price(RainbowType::CallOnMax)?;
+//! let worst_of = option.price_monte_carlo(RainbowType::CallOnMin, 100_000, 42)?;
+//! # Ok::<(), RustQuant::error::RustQuantError>(())
+//! ```
+
+use crate::error::RustQuantError;
+use crate::instruments::{Price, Validate, Validator};
+use crate::math::distributions::{bivariate_normal_cdf, trivariate_normal_cdf};
+use crate::math::Real;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Rainbow option payoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RainbowType {
+    /// Call on the best performer: `max(max(S_1, ..., S_n) - X, 0)`.
+    CallOnMax,
+    /// Call on the worst performer: `max(min(S_1, ..., S_n) - X, 0)`.
+    CallOnMin,
+    /// Put on the best performer: `max(X - max(S_1, ..., S_n), 0)`.
+    PutOnMax,
+    /// Put on the worst performer: `max(X - min(S_1, ..., S_n), 0)`.
+    PutOnMin,
+}
+
+/// Rainbow option parameters.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RainbowOption {
+    /// * `S_i` - Initial prices of the underlying assets.
+    pub initial_prices: Vec<f64>,
+    /// * `v_i` - Volatilities of the underlying assets.
+    pub volatilities: Vec<f64>,
+    /// * `q_i` - Dividend yields of the underlying assets.
+    pub dividend_yields: Vec<f64>,
+    /// * `rho` - Correlation matrix of the assets' log-returns.
+    pub correlation: Vec<Vec<f64>>,
+    /// * `X` - Strike price.
+    pub strike_price: f64,
+    /// * `t` - Time to expiry.
+    pub time_to_expiry: f64,
+    /// * `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RainbowType {
+    fn is_call(self) -> bool {
+        matches!(self, Self::CallOnMax | Self::CallOnMin)
+    }
+
+    fn is_max(self) -> bool {
+        matches!(self, Self::CallOnMax | Self::PutOnMax)
+    }
+}
+
+impl RainbowOption {
+    /// Closed-form price, for two or three assets.
+    ///
+    /// Puts are priced by parity with the call and the zero-strike call
+    /// (the value of receiving the best or worst asset at expiry).
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or for more than three assets (use
+    ///   [`RainbowOption::price_monte_carlo`] instead).
+    pub fn price(&self, rainbow_type: RainbowType) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        let n = self.initial_prices.len();
+
+        if n > 3 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Closed-form rainbow prices are for two or three assets, got {n}."
+            )));
+        }
+
+        let X = self.strike_price;
+        let is_max = rainbow_type.is_max();
+        let call = self.call(X, is_max);
+
+        Ok(match rainbow_type.is_call() {
+            true => call,
+            false => {
+                X * (-self.risk_free_rate * self.time_to_expiry).exp() - self.call(0.0, is_max)
+                    + call
+            }
+        })
+    }
+
+    /// Monte Carlo price, for any number of assets, simulating the
+    /// correlated terminal prices exactly.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`].
+    pub fn price_monte_carlo(
+        &self,
+        rainbow_type: RainbowType,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<Price, RustQuantError> {
+        self.validate()?;
+
+        let n = self.initial_prices.len();
+        let n_paths = n_paths.max(2);
+
+        let T = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let X = self.strike_price;
+
+        let cholesky = self
+            .correlation_matrix()
+            .cholesky()
+            .expect("validated correlation matrix is positive definite")
+            .l();
+
+        let log_forwards: Vec<f64> = (0..n)
+            .map(|i| {
+                let v = self.volatilities[i];
+                self.initial_prices[i].ln() + (r - self.dividend_yields[i] - 0.5 * v * v) * T
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let discount = (-r * T).exp();
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+
+        for _ in 0..n_paths {
+            let z: DVector<f64> = DVector::from_fn(n, |_, _| StandardNormal.sample(&mut rng));
+            let w = &cholesky * z;
+
+            let prices =
+                (0..n).map(|i| (log_forwards[i] + self.volatilities[i] * T.sqrt() * w[i]).exp());
+
+            let extreme = match rainbow_type.is_max() {
+                true => prices.fold(f64::NEG_INFINITY, f64::max),
+                false => prices.fold(f64::INFINITY, f64::min),
+            };
+
+            let value = discount
+                * match rainbow_type.is_call() {
+                    true => (extreme - X).max(0.0),
+                    false => (X - extreme).max(0.0),
+                };
+
+            sum += value;
+            sum_sq += value * value;
+        }
+
+        let mean = sum / n_paths as f64;
+        let variance = (sum_sq / n_paths as f64 - mean * mean).max(0.0) * n_paths as f64
+            / (n_paths as f64 - 1.0);
+
+        Ok(Price {
+            price: mean,
+            error: Some((variance / n_paths as f64).sqrt()),
+        })
+    }
+
+    /// Call on the maximum (or minimum) with the given strike.
+    ///
+    /// Each asset `i` contributes its forward, weighted by the probability
+    /// (under its own measure) that it finishes above the strike and above
+    /// (or below) every other asset. These events are joint conditions on
+    /// `ln(S_i / X)` and the log-ratios `ln(S_i / S_j)`, whose correlations
+    /// follow from the asset covariances. A zero strike drops the first
+    /// condition.
+    fn call(&self, strike: f64, is_max: bool) -> f64 {
+        let n = self.initial_prices.len();
+
+        let S = &self.initial_prices;
+        let v = &self.volatilities;
+        let q = &self.dividend_yields;
+        let rho = &self.correlation;
+        let T = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let sqrt_T = T.sqrt();
+
+        let sign = if is_max { 1.0 } else { -1.0 };
+
+        let asset_legs: f64 = (0..n)
+            .map(|i| {
+                let others: Vec<usize> = (0..n).filter(|&j| j != i).collect();
+
+                // Volatility of ln(S_i / S_j).
+                let spread_vol =
+                    |j: usize| (v[i] * v[i] + v[j] * v[j] - 2.0 * rho[i][j] * v[i] * v[j]).sqrt();
+
+                let mut bounds = vec![match strike > 0.0 {
+                    true => {
+                        ((S[i] / strike).ln() + (r - q[i] + 0.5 * v[i] * v[i]) * T)
+                            / (v[i] * sqrt_T)
+                    }
+                    false => f64::INFINITY,
+                }];
+                bounds.extend(others.iter().map(|&j| {
+                    let s = spread_vol(j);
+                    sign * ((S[i] / S[j]).ln() + (q[j] - q[i] + 0.5 * s * s) * T) / (s * sqrt_T)
+                }));
+
+                let mut corr = vec![vec![1.0; n]; n];
+                for (a, &j) in others.iter().enumerate() {
+                    let c = sign * (v[i] - rho[i][j] * v[j]) / spread_vol(j);
+                    corr[0][a + 1] = c;
+                    corr[a + 1][0] = c;
+
+                    for (b, &k) in others.iter().enumerate().filter(|&(_, &k)| k != j) {
+                        corr[a + 1][b + 1] =
+                            (v[i] * v[i] - rho[i][j] * v[i] * v[j] - rho[i][k] * v[i] * v[k]
+                                + rho[j][k] * v[j] * v[k])
+                                / (spread_vol(j) * spread_vol(k));
+                    }
+                }
+
+                S[i] * (-q[i] * T).exp() * normal_cdf(&bounds, &corr)
+            })
+            .sum();
+
+        if strike <= 0.0 {
+            return asset_legs;
+        }
+
+        // Risk-neutral probability that the maximum (minimum) ends above the strike.
+        let d: Vec<f64> = (0..n)
+            .map(|j| ((S[j] / strike).ln() + (r - q[j] - 0.5 * v[j] * v[j]) * T) / (v[j] * sqrt_T))
+            .collect();
+
+        let exercise = match is_max {
+            true => 1.0 - normal_cdf(&d.iter().map(|x| -x).collect::<Vec<_>>(), rho),
+            false => normal_cdf(&d, rho),
+        };
+
+        asset_legs - strike * (-r * T).exp() * exercise
+    }
+
+    fn correlation_matrix(&self) -> DMatrix<f64> {
+        let n = self.initial_prices.len();
+        DMatrix::from_fn(n, n, |i, j| self.correlation[i][j])
+    }
+}
+
+/// Standard normal CDF in up to three dimensions, ignoring infinite upper
+/// bounds.
+fn normal_cdf(bounds: &[f64], corr: &[Vec<f64>]) -> f64 {
+    let finite: Vec<usize> = (0..bounds.len())
+        .filter(|&i| bounds[i] < f64::INFINITY)
+        .collect();
+
+    match finite[..] {
+        [] => 1.0,
+        [i] => bounds[i].norm_cdf(),
+        [i, j] => bivariate_normal_cdf(bounds[i], bounds[j], corr[i][j]),
+        [i, j, k] => trivariate_normal_cdf(
+            bounds[i], bounds[j], bounds[k], corr[i][j], corr[i][k], corr[j][k],
+        ),
+        _ => unreachable!("closed-form rainbow prices are for at most three assets"),
+    }
+}
+
+impl Validate for RainbowOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let n = self.initial_prices.len();
+
+        let shapes_match = self.volatilities.len() == n
+            && self.dividend_yields.len() == n
+            && self.correlation.len() == n
+            && self.correlation.iter().all(|row| row.len() == n);
+
+        let mut validator = Validator::new()
+            .check(n >= 2, || {
+                format!("a rainbow option needs at least two assets (got {n})")
+            })
+            .check(shapes_match, || {
+                format!("volatilities, dividend_yields and correlation must all be for {n} assets")
+            })
+            .positive("strike_price", self.strike_price)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finite("risk_free_rate", self.risk_free_rate);
+
+        if !shapes_match {
+            return validator.finish();
+        }
+
+        for i in 0..n {
+            validator = validator
+                .positive(&format!("initial_prices[{i}]"), self.initial_prices[i])
+                .positive(&format!("volatilities[{i}]"), self.volatilities[i])
+                .finite(&format!("dividend_yields[{i}]"), self.dividend_yields[i]);
+
+            for j in 0..n {
+                let rho = self.correlation[i][j];
+                validator = match i == j {
+                    true => validator.check(rho == 1.0, || {
+                        format!("correlation[{i}][{i}] must be 1 (got {rho})")
+                    }),
+                    false => validator.check(rho == self.correlation[j][i] && rho.abs() < 1.0, || {
+                        format!("correlation[{i}][{j}] must be symmetric and in (-1, 1) (got {rho})")
+                    }),
+                };
+            }
+        }
+
+        validator
+            .check(self.correlation_matrix().cholesky().is_some(), || {
+                "correlation must be positive definite".to_string()
+            })
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_rainbow {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::{generalised_black_scholes_merton, TypeFlag};
+
+    fn two_assets() -> RainbowOption {
+        // Stulz example from Haug's The Complete Guide to Option Pricing Formulas.
+        RainbowOption {
+            initial_prices: vec![100.0, 105.0],
+            volatilities: vec![0.11, 0.16],
+            dividend_yields: vec![0.06, 0.09],
+            correlation: vec![vec![1.0, 0.63], vec![0.63, 1.0]],
+            strike_price: 98.0,
+            time_to_expiry: 0.5,
+            risk_free_rate: 0.05,
+        }
+    }
+
+    fn three_assets() -> RainbowOption {
+        RainbowOption {
+            initial_prices: vec![100.0, 95.0, 110.0],
+            volatilities: vec![0.2, 0.25, 0.3],
+            dividend_yields: vec![0.0, 0.02, 0.01],
+            correlation: vec![
+                vec![1.0, 0.4, 0.2],
+                vec![0.4, 1.0, -0.3],
+                vec![0.2, -0.3, 1.0],
+            ],
+            strike_price: 100.0,
+            time_to_expiry: 1.0,
+            risk_free_rate: 0.05,
+        }
+    }
+
+    #[test]
+    fn test_stulz_call_on_max() {
+        assert_approx_equal!(
+            two_assets().price(RainbowType::CallOnMax).unwrap(),
+            8.0701,
+            1e-4
+        );
+    }
+
+    #[test]
+    fn test_max_plus_min_is_both_vanillas() {
+        let option = two_assets();
+        let vanilla = |i: usize, type_flag| {
+            generalised_black_scholes_merton(
+                option.initial_prices[i],
+                option.strike_price,
+                option.volatilities[i],
+                option.risk_free_rate,
+                option.risk_free_rate - option.dividend_yields[i],
+                option.time_to_expiry,
+                type_flag,
+            )
+        };
+
+        for (max, min, type_flag) in [
+            (
+                RainbowType::CallOnMax,
+                RainbowType::CallOnMin,
+                TypeFlag::Call,
+            ),
+            (RainbowType::PutOnMax, RainbowType::PutOnMin, TypeFlag::Put),
+        ] {
+            assert_approx_equal!(
+                option.price(max).unwrap() + option.price(min).unwrap(),
+                vanilla(0, type_flag) + vanilla(1, type_flag),
+                1e-10
+            );
+        }
+    }
+
+    #[test]
+    fn test_closed_form_matches_monte_carlo() {
+        for option in [two_assets(), three_assets()] {
+            for rainbow_type in [
+                RainbowType::CallOnMax,
+                RainbowType::CallOnMin,
+                RainbowType::PutOnMax,
+                RainbowType::PutOnMin,
+            ] {
+                let closed_form = option.price(rainbow_type).unwrap();
+                let mc = option.price_monte_carlo(rainbow_type, 200_000, 42).unwrap();
+
+                assert!((closed_form - mc.price).abs() < 4.0 * mc.error.unwrap() + 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rainbow_validation() {
+        let mut option = three_assets();
+        option.correlation[0][1] = 0.9;
+        assert!(option.price(RainbowType::CallOnMax).is_err());
+
+        // Symmetric with unit diagonal, but not positive definite.
+        let mut option = three_assets();
+        option.correlation = vec![
+            vec![1.0, 0.9, -0.9],
+            vec![0.9, 1.0, 0.9],
+            vec![-0.9, 0.9, 1.0],
+        ];
+        assert!(option.price(RainbowType::CallOnMax).is_err());
+
+        let mut option = three_assets();
+        option.volatilities.pop();
+        assert!(option.price(RainbowType::CallOnMax).is_err());
+
+        let mut option = three_assets();
+        option.initial_prices.push(90.0);
+        option.volatilities.push(0.2);
+        option.dividend_yields.push(0.0);
+        option.correlation = (0..4)
+            .map(|i| (0..4).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        assert!(option.price(RainbowType::CallOnMax).is_err());
+        assert!(option
+            .price_monte_carlo(RainbowType::CallOnMax, 1_000, 1)
+            .is_ok());
+    }
+}
@@ -0,0 +1,501 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Real options: flexibility in investment decisions, valued as options on
+//! the present value of a project's cash flows.
+//!
+//! | Decision                     | Option                                 |
+//! |------------------------------|----------------------------------------|
+//! | [`OptionToDefer`]            | American call on the project           |
+//! | [`OptionToExpand`]           | American call on the extra cash flows  |
+//! | [`OptionToAbandon`]          | American put on the project            |
+//! | [`OptionToStage`]            | European call on a call (Geske, 1979)  |
+//!
+//! Each problem is described in corporate-finance terms and returns a
+//! [`RealOptionValue`], comparing the static NPV (committing to the plan
+//! today) with the expanded NPV (keeping the flexibility), as in Trigeorgis
+//! (1996), *Real Options*.
+//!
+//! The cash flow yield plays the role of a dividend yield: it is the value
+//! the project pays out, and a waiting investor gives up, per year.
+
+use super::{BinomialOption, ExerciseFlag, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::options::generalised_black_scholes_merton;
+use crate::instruments::{Validate, Validator};
+use crate::math::distributions::{bivariate_normal_cdf, Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Value of an investment with and without its flexibility.
+#[derive(Debug, Clone, Copy)]
+pub struct RealOptionValue {
+    /// NPV of committing to the investment plan today.
+    pub static_npv: f64,
+    /// NPV when the flexibility is exercised optimally.
+    pub expanded_npv: f64,
+}
+
+/// Option to wait before investing in a project.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionToDefer {
+    /// Present value of the project's cash flows.
+    pub project_value: f64,
+    /// Cost of investing in the project.
+    pub investment_cost: f64,
+    /// How long the investment can be deferred, in years.
+    pub deferral_period: f64,
+    /// Volatility of the project value.
+    pub volatility: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Cash flows lost per year while waiting, as a fraction of the value.
+    pub cash_flow_yield: f64,
+}
+
+/// Option to scale up a project by paying an extra investment.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionToExpand {
+    /// Present value of the project's cash flows.
+    pub project_value: f64,
+    /// Cost of investing in the project.
+    pub investment_cost: f64,
+    /// Expansion as a fraction of the project (`0.3` adds 30%).
+    pub expansion_factor: f64,
+    /// Cost of the expansion.
+    pub expansion_cost: f64,
+    /// How long the expansion remains possible, in years.
+    pub time_horizon: f64,
+    /// Volatility of the project value.
+    pub volatility: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Cash flows paid out per year, as a fraction of the value.
+    pub cash_flow_yield: f64,
+}
+
+/// Option to abandon a project for its salvage value.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionToAbandon {
+    /// Present value of the project's cash flows.
+    pub project_value: f64,
+    /// Cost of investing in the project.
+    pub investment_cost: f64,
+    /// Value recovered on abandoning the project.
+    pub salvage_value: f64,
+    /// How long the project can be abandoned, in years.
+    pub time_horizon: f64,
+    /// Volatility of the project value.
+    pub volatility: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Cash flows paid out per year, as a fraction of the value.
+    pub cash_flow_yield: f64,
+}
+
+/// Investment in two stages, where the second stage need only be paid if
+/// the project still looks worthwhile after the first.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionToStage {
+    /// Present value of the finished project's cash flows.
+    pub project_value: f64,
+    /// Cost of the first stage, paid (if at all) at `first_stage_time`.
+    pub first_stage_cost: f64,
+    /// Time of the first stage decision, in years.
+    pub first_stage_time: f64,
+    /// Cost of the second stage, paid (if at all) at `second_stage_time`.
+    pub second_stage_cost: f64,
+    /// Time of the second stage decision, in years.
+    pub second_stage_time: f64,
+    /// Volatility of the project value.
+    pub volatility: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Cash flows lost per year while waiting, as a fraction of the value.
+    pub cash_flow_yield: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RealOptionValue {
+    /// Value of the flexibility (the option premium): the expanded NPV less
+    /// the static NPV.
+    #[must_use]
+    pub fn flexibility_value(&self) -> f64 {
+        self.expanded_npv - self.static_npv
+    }
+}
+
+/// American option on the project value, on a CRR tree of height `n`.
+#[allow(clippy::too_many_arguments)]
+fn american(
+    value: f64,
+    strike: f64,
+    time: f64,
+    r: f64,
+    q: f64,
+    v: f64,
+    option_type: TypeFlag,
+    n: usize,
+) -> Result<f64, RustQuantError> {
+    BinomialOption::new(value, strike, time, r, q, v).price_CoxRossRubinstein(
+        "p",
+        ExerciseFlag::American,
+        option_type,
+        n,
+    )
+}
+
+impl OptionToDefer {
+    /// Value the project with the option to defer, on a binomial tree of
+    /// height `n`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the tree height is below 3.
+    pub fn value(&self, n: usize) -> Result<RealOptionValue, RustQuantError> {
+        self.validate()?;
+
+        Ok(RealOptionValue {
+            static_npv: self.project_value - self.investment_cost,
+            expanded_npv: american(
+                self.project_value,
+                self.investment_cost,
+                self.deferral_period,
+                self.risk_free_rate,
+                self.cash_flow_yield,
+                self.volatility,
+                TypeFlag::Call,
+                n,
+            )?,
+        })
+    }
+}
+
+impl OptionToExpand {
+    /// Value the project with the option to expand, on a binomial tree of
+    /// height `n`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the tree height is below 3.
+    pub fn value(&self, n: usize) -> Result<RealOptionValue, RustQuantError> {
+        self.validate()?;
+
+        let static_npv = self.project_value - self.investment_cost;
+        let option = american(
+            self.expansion_factor * self.project_value,
+            self.expansion_cost,
+            self.time_horizon,
+            self.risk_free_rate,
+            self.cash_flow_yield,
+            self.volatility,
+            TypeFlag::Call,
+            n,
+        )?;
+
+        Ok(RealOptionValue {
+            static_npv,
+            expanded_npv: static_npv + option,
+        })
+    }
+}
+
+impl OptionToAbandon {
+    /// Value the project with the option to abandon, on a binomial tree of
+    /// height `n`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the tree height is below 3.
+    pub fn value(&self, n: usize) -> Result<RealOptionValue, RustQuantError> {
+        self.validate()?;
+
+        let static_npv = self.project_value - self.investment_cost;
+        let option = american(
+            self.project_value,
+            self.salvage_value,
+            self.time_horizon,
+            self.risk_free_rate,
+            self.cash_flow_yield,
+            self.volatility,
+            TypeFlag::Put,
+            n,
+        )?;
+
+        Ok(RealOptionValue {
+            static_npv,
+            expanded_npv: static_npv + option,
+        })
+    }
+}
+
+impl OptionToStage {
+    /// Value the staged investment as a call on a call (Geske, 1979).
+    ///
+    /// The first stage is only paid if, at `first_stage_time`, the option
+    /// to complete the project is worth more than its cost.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`].
+    pub fn value(&self) -> Result<RealOptionValue, RustQuantError> {
+        self.validate()?;
+
+        let S = self.project_value;
+        let (K1, t1) = (self.first_stage_cost, self.first_stage_time);
+        let (K2, T2) = (self.second_stage_cost, self.second_stage_time);
+        let (r, v) = (self.risk_free_rate, self.volatility);
+        let b = r - self.cash_flow_yield;
+
+        let stage_two = |value: f64| {
+            generalised_black_scholes_merton(value, K2, v, r, b, T2 - t1, TypeFlag::Call)
+        };
+
+        // Critical project value at the first stage, above which the first
+        // stage is paid: the call is increasing in the value, so bisect.
+        let (mut lo, mut hi) = (0.0, S.max(K2));
+        while stage_two(hi) < K1 {
+            hi *= 2.0;
+        }
+        for _ in 0..200 {
+            let mid = 0.5 * (lo + hi);
+            match stage_two(mid) < K1 {
+                true => lo = mid,
+                false => hi = mid,
+            }
+        }
+        let critical = 0.5 * (lo + hi);
+
+        let y1 = ((S / critical).ln() + (b + 0.5 * v * v) * t1) / (v * t1.sqrt());
+        let y2 = y1 - v * t1.sqrt();
+        let z1 = ((S / K2).ln() + (b + 0.5 * v * v) * T2) / (v * T2.sqrt());
+        let z2 = z1 - v * T2.sqrt();
+        let rho = (t1 / T2).sqrt();
+
+        let expanded_npv = S * ((b - r) * T2).exp() * bivariate_normal_cdf(z1, y1, rho)
+            - K2 * (-r * T2).exp() * bivariate_normal_cdf(z2, y2, rho)
+            - K1 * (-r * t1).exp() * Gaussian::default().cdf(y2);
+
+        Ok(RealOptionValue {
+            static_npv: S - K1 * (-r * t1).exp() - K2 * (-r * T2).exp(),
+            expanded_npv,
+        })
+    }
+}
+
+impl Validate for OptionToDefer {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("project_value", self.project_value)
+            .positive("investment_cost", self.investment_cost)
+            .positive("deferral_period", self.deferral_period)
+            .positive("volatility", self.volatility)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("cash_flow_yield", self.cash_flow_yield)
+            .finish()
+    }
+}
+
+impl Validate for OptionToExpand {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("project_value", self.project_value)
+            .non_negative("investment_cost", self.investment_cost)
+            .positive("expansion_factor", self.expansion_factor)
+            .positive("expansion_cost", self.expansion_cost)
+            .positive("time_horizon", self.time_horizon)
+            .positive("volatility", self.volatility)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("cash_flow_yield", self.cash_flow_yield)
+            .finish()
+    }
+}
+
+impl Validate for OptionToAbandon {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("project_value", self.project_value)
+            .non_negative("investment_cost", self.investment_cost)
+            .positive("salvage_value", self.salvage_value)
+            .positive("time_horizon", self.time_horizon)
+            .positive("volatility", self.volatility)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("cash_flow_yield", self.cash_flow_yield)
+            .finish()
+    }
+}
+
+impl Validate for OptionToStage {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("project_value", self.project_value)
+            .positive("first_stage_cost", self.first_stage_cost)
+            .positive("first_stage_time", self.first_stage_time)
+            .positive("second_stage_cost", self.second_stage_cost)
+            .check(self.second_stage_time > self.first_stage_time, || {
+                format!(
+                    "second_stage_time must be after first_stage_time (got {} <= {})",
+                    self.second_stage_time, self.first_stage_time
+                )
+            })
+            .positive("volatility", self.volatility)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("cash_flow_yield", self.cash_flow_yield)
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_real_options {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn black_scholes(S: f64, K: f64, T: f64, r: f64, q: f64, v: f64, flag: TypeFlag) -> f64 {
+        generalised_black_scholes_merton(S, K, v, r, r - q, T, flag)
+    }
+
+    #[test]
+    fn test_option_to_defer() {
+        let defer = OptionToDefer {
+            project_value: 100.0,
+            investment_cost: 105.0,
+            deferral_period: 2.0,
+            volatility: 0.3,
+            risk_free_rate: 0.05,
+            cash_flow_yield: 0.0,
+        };
+        let value = defer.value(500).unwrap();
+
+        // Without lost cash flows, it never pays to invest early.
+        assert_approx_equal!(
+            value.expanded_npv,
+            black_scholes(100.0, 105.0, 2.0, 0.05, 0.0, 0.3, TypeFlag::Call),
+            1e-2
+        );
+        assert_approx_equal!(value.static_npv, -5.0, 1e-12);
+        assert!(value.flexibility_value() > 5.0);
+
+        // Lost cash flows make waiting less valuable.
+        let leaky = OptionToDefer {
+            cash_flow_yield: 0.05,
+            ..defer
+        };
+        assert!(leaky.value(500).unwrap().expanded_npv < value.expanded_npv);
+    }
+
+    #[test]
+    fn test_option_to_expand() {
+        let expand = OptionToExpand {
+            project_value: 100.0,
+            investment_cost: 90.0,
+            expansion_factor: 0.3,
+            expansion_cost: 25.0,
+            time_horizon: 3.0,
+            volatility: 0.3,
+            risk_free_rate: 0.05,
+            cash_flow_yield: 0.0,
+        };
+        let value = expand.value(500).unwrap();
+
+        assert_approx_equal!(value.static_npv, 10.0, 1e-12);
+        assert_approx_equal!(
+            value.flexibility_value(),
+            black_scholes(30.0, 25.0, 3.0, 0.05, 0.0, 0.3, TypeFlag::Call),
+            1e-2
+        );
+    }
+
+    #[test]
+    fn test_option_to_abandon() {
+        let abandon = OptionToAbandon {
+            project_value: 100.0,
+            investment_cost: 110.0,
+            salvage_value: 90.0,
+            time_horizon: 3.0,
+            volatility: 0.3,
+            risk_free_rate: 0.05,
+            cash_flow_yield: 0.03,
+        };
+        let value = abandon.value(500).unwrap();
+        let european = black_scholes(100.0, 90.0, 3.0, 0.05, 0.03, 0.3, TypeFlag::Put);
+
+        // The option to abandon can turn a negative NPV positive, and is
+        // worth more than its European counterpart.
+        assert!(value.static_npv < 0.0);
+        assert!(value.flexibility_value() > european);
+        assert!(value.flexibility_value() > 10.0);
+    }
+
+    #[test]
+    fn test_option_to_stage() {
+        let stage = OptionToStage {
+            project_value: 100.0,
+            first_stage_cost: 10.0,
+            first_stage_time: 1.0,
+            second_stage_cost: 110.0,
+            second_stage_time: 3.0,
+            volatility: 0.35,
+            risk_free_rate: 0.05,
+            cash_flow_yield: 0.02,
+        };
+        let value = stage.value().unwrap();
+
+        // Checked against direct integration over the first stage value.
+        assert_approx_equal!(value.expanded_npv, 14.418_524_102_526_746, 1e-6);
+        assert!(value.expanded_npv > value.static_npv);
+
+        // Staging is worth less than committing to the second stage only.
+        let single = black_scholes(100.0, 110.0, 3.0, 0.05, 0.02, 0.35, TypeFlag::Call);
+        assert!(value.expanded_npv < single);
+    }
+
+    #[test]
+    fn test_real_options_validation() {
+        let stage = OptionToStage {
+            project_value: 100.0,
+            first_stage_cost: 10.0,
+            first_stage_time: 3.0,
+            second_stage_cost: 110.0,
+            second_stage_time: 1.0,
+            volatility: 0.35,
+            risk_free_rate: 0.05,
+            cash_flow_yield: 0.02,
+        };
+        assert!(stage.value().is_err());
+
+        let defer = OptionToDefer {
+            project_value: 100.0,
+            investment_cost: 105.0,
+            deferral_period: 2.0,
+            volatility: -0.3,
+            risk_free_rate: 0.05,
+            cash_flow_yield: 0.0,
+        };
+        assert!(defer.value(100).is_err());
+        assert!(OptionToDefer {
+            volatility: 0.3,
+            ..defer
+        }
+        .value(2)
+        .is_err());
+    }
+}
@@ -0,0 +1,550 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Rough Bergomi (rBergomi) model of Bayer, Friz and Gatheral (2016).
+//!
+//! The variance is driven by a Riemann-Liouville fractional Brownian motion
+//! with Hurst exponent `H < 1/2`:
+//!
+//! ```text
+//! V_t = xi exp(eta Y_t - eta^2 t^(2H) / 2),
+//! Y_t = sqrt(2H) int_0^t (t - s)^(H - 1/2) dW_s,
+//! dS_t / S_t = (r - q) dt + sqrt(V_t) (rho dW_t + sqrt(1 - rho^2) dW'_t),
+//! ```
+//!
+//! with a flat forward variance `xi`. The at-the-money skew then explodes
+//! like `T^(H - 1/2)` at short maturities, as it does in the market.
+//!
+//! `Y` is not Markovian, and is simulated with the hybrid scheme of
+//! Bennedsen, Lunde and Pakkanen (2017): the kernel is integrated exactly
+//! over the last step and approximated by a step function further back.
+//! Vanillas are priced with the "turbocharged" estimator of McCrickerd and
+//! Pakkanen (2018): conditionally on `W` the log-price is normal, so the
+//! payoff is replaced by a Black price, and antithetic paths and a control
+//! variate reduce the remaining noise.
+
+use super::{generalised_black_scholes_merton, try_implied_volatility, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::{Price, Validate, Validator};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Parameters of the rough Bergomi model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoughBergomi {
+    /// Hurst exponent `H`, in `(0, 1/2)`.
+    pub hurst: f64,
+    /// Flat forward variance `xi`.
+    pub forward_variance: f64,
+    /// Volatility of volatility `eta`.
+    pub vol_of_vol: f64,
+    /// Correlation `rho` between the price and the variance.
+    pub correlation: f64,
+}
+
+/// Simulated paths of the rough Bergomi model.
+#[derive(Debug, Clone)]
+pub struct RoughBergomiPaths {
+    /// Time points, from 0 to the horizon.
+    pub times: Vec<f64>,
+    /// Price paths.
+    pub prices: Vec<Vec<f64>>,
+    /// Variance paths.
+    pub variances: Vec<Vec<f64>>,
+}
+
+// Hybrid scheme on a uniform grid: the Cholesky factor of the step
+// increment and the kernel integral over the step, and the step-function
+// kernel weights further back.
+struct HybridScheme {
+    dt: f64,
+    cholesky: [f64; 3],
+    weights: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl HybridScheme {
+    fn new(hurst: f64, T: f64, n_steps: usize) -> Self {
+        let alpha = hurst - 0.5;
+        let dt = T / n_steps as f64;
+
+        // Covariance of (W increment, kernel integral) over one step.
+        let c11 = dt;
+        let c12 = dt.powf(alpha + 1.0) / (alpha + 1.0);
+        let c22 = dt.powf(2.0 * alpha + 1.0) / (2.0 * alpha + 1.0);
+
+        let l11 = c11.sqrt();
+        let l21 = c12 / l11;
+        let l22 = (c22 - l21 * l21).max(0.0).sqrt();
+
+        // Kernel at the optimal points b_k of each earlier step k >= 2.
+        let weights = (0..=n_steps)
+            .map(|k| match k {
+                0 | 1 => 0.0,
+                _ => {
+                    let k = k as f64;
+                    let b = ((k.powf(alpha + 1.0) - (k - 1.0).powf(alpha + 1.0)) / (alpha + 1.0))
+                        .powf(1.0 / alpha);
+
+                    (b * dt).powf(alpha)
+                }
+            })
+            .collect();
+
+        Self {
+            dt,
+            cholesky: [l11, l21, l22],
+            weights,
+        }
+    }
+
+    // Brownian increments, and the Volterra process `Y` on the grid.
+    fn sample(&self, hurst: f64, rng: &mut StdRng) -> (Vec<f64>, Vec<f64>) {
+        let n_steps = self.weights.len() - 1;
+        let [l11, l21, l22] = self.cholesky;
+
+        let (increments, integrals): (Vec<f64>, Vec<f64>) = (0..n_steps)
+            .map(|_| {
+                let z1: f64 = StandardNormal.sample(rng);
+                let z2: f64 = StandardNormal.sample(rng);
+
+                (l11 * z1, l21 * z1 + l22 * z2)
+            })
+            .unzip();
+
+        let scale = (2.0 * hurst).sqrt();
+        let volterra = (0..=n_steps)
+            .map(|i| match i {
+                0 => 0.0,
+                _ => {
+                    let past: f64 = (2..=i).map(|k| self.weights[k] * increments[i - k]).sum();
+
+                    scale * (integrals[i - 1] + past)
+                }
+            })
+            .collect();
+
+        (increments, volterra)
+    }
+
+    // Variance on the grid from `sign * Y`.
+    fn variances(&self, model: &RoughBergomi, volterra: &[f64], sign: f64) -> Vec<f64> {
+        let (xi, eta) = (model.forward_variance, model.vol_of_vol);
+
+        volterra
+            .iter()
+            .enumerate()
+            .map(|(i, y)| {
+                let t = self.dt * i as f64;
+
+                xi * (eta * sign * y - 0.5 * eta * eta * t.powf(2.0 * model.hurst)).exp()
+            })
+            .collect()
+    }
+}
+
+impl RoughBergomi {
+    /// Simulate `n_paths` paths of the price and variance to time `T`, in
+    /// `n_steps` equal steps of the hybrid scheme.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], `S` or `T` are not positive, the rates not
+    ///   finite, or there are no steps or paths.
+    #[allow(clippy::too_many_arguments)]
+    pub fn simulate(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        n_steps: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<RoughBergomiPaths, RustQuantError> {
+        self.check(S, r, q, T, n_steps, n_paths)?;
+
+        let rho = self.correlation;
+        let rho_bar = (1.0 - rho * rho).sqrt();
+        let scheme = HybridScheme::new(self.hurst, T, n_steps);
+        let dt = scheme.dt;
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut prices = Vec::with_capacity(n_paths);
+        let mut variances = Vec::with_capacity(n_paths);
+
+        for _ in 0..n_paths {
+            let (increments, volterra) = scheme.sample(self.hurst, &mut rng);
+            let v = scheme.variances(self, &volterra, 1.0);
+
+            let mut x = vec![S.ln(); n_steps + 1];
+            for i in 0..n_steps {
+                let z: f64 = StandardNormal.sample(&mut rng);
+                let dB = rho * increments[i] + rho_bar * dt.sqrt() * z;
+
+                x[i + 1] = x[i] + (r - q - 0.5 * v[i]) * dt + v[i].sqrt() * dB;
+            }
+
+            prices.push(x.into_iter().map(f64::exp).collect());
+            variances.push(v);
+        }
+
+        Ok(RoughBergomiPaths {
+            times: (0..=n_steps).map(|i| dt * i as f64).collect(),
+            prices,
+            variances,
+        })
+    }
+
+    /// European option prices, with their standard errors, from the
+    /// turbocharged Monte Carlo estimator: conditional Black prices over
+    /// `n_paths` antithetic pairs of variance paths, with the conditional
+    /// forward as a control variate.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], `S` or `T` are not positive, the rates not
+    ///   finite, a strike is not positive, or there are fewer than two paths.
+    #[allow(clippy::too_many_arguments)]
+    pub fn price_turbocharged(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        strikes: &[f64],
+        option_type: TypeFlag,
+        n_steps: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<Vec<Price>, RustQuantError> {
+        self.check(S, r, q, T, n_steps, n_paths)?;
+        Validator::new()
+            .check(n_paths >= 2, || "at least two paths are needed".to_string())
+            .check(strikes.iter().all(|&K| K > 0.0), || {
+                "strikes must be positive".to_string()
+            })
+            .finish()?;
+
+        let rho = self.correlation;
+        let forward = S * ((r - q) * T).exp();
+        let scheme = HybridScheme::new(self.hurst, T, n_steps);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // Per path: the conditional prices and the control variate, each
+        // averaged over the antithetic pair.
+        let mut samples = vec![Vec::with_capacity(n_paths); strikes.len()];
+        let mut controls = Vec::with_capacity(n_paths);
+
+        for _ in 0..n_paths {
+            let (increments, volterra) = scheme.sample(self.hurst, &mut rng);
+            let mut prices = vec![0.0; strikes.len()];
+            let mut control = 0.0;
+
+            for sign in [1.0, -1.0] {
+                let v = scheme.variances(self, &volterra, sign);
+
+                // Stochastic integral against W, and integrated variance.
+                let integral: f64 = (0..n_steps)
+                    .map(|i| v[i].sqrt() * sign * increments[i])
+                    .sum();
+                let integrated: f64 = v[..n_steps].iter().sum::<f64>() * scheme.dt;
+
+                // Given W, the price is lognormal around this forward with
+                // the variance left to the independent Brownian motion.
+                let conditional = forward * (rho * integral - 0.5 * rho * rho * integrated).exp();
+                let volatility = ((1.0 - rho * rho) * integrated / T).sqrt();
+
+                for (price, &K) in prices.iter_mut().zip(strikes) {
+                    *price += 0.5
+                        * generalised_black_scholes_merton(
+                            conditional,
+                            K,
+                            volatility,
+                            r,
+                            0.0,
+                            T,
+                            option_type,
+                        );
+                }
+                control += 0.5 * (conditional - forward);
+            }
+
+            for (sample, price) in samples.iter_mut().zip(prices) {
+                sample.push(price);
+            }
+            controls.push(control);
+        }
+
+        Ok(samples
+            .iter()
+            .map(|sample| control_variate_estimate(sample, &controls))
+            .collect())
+    }
+
+    /// Black-Scholes implied volatilities of the turbocharged prices.
+    ///
+    /// # Errors:
+    /// * As [`RoughBergomi::price_turbocharged`], and
+    ///   [`RustQuantError::NonConvergence`] if a price cannot be inverted.
+    #[allow(clippy::too_many_arguments)]
+    pub fn implied_volatilities(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        strikes: &[f64],
+        n_steps: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        let forward = S * ((r - q) * T).exp();
+
+        // Out-of-the-money options: the inversion is best conditioned.
+        strikes
+            .iter()
+            .map(|&K| {
+                let option_type = match K >= forward {
+                    true => TypeFlag::Call,
+                    false => TypeFlag::Put,
+                };
+                let price =
+                    self.price_turbocharged(S, r, q, T, &[K], option_type, n_steps, n_paths, seed)?;
+
+                try_implied_volatility(price[0].price, S * (-q * T).exp(), K, T, r, option_type)
+            })
+            .collect()
+    }
+
+    /// At-the-money skew `d sigma / d ln K` at the forward, by central
+    /// differences of the implied volatility over `±0.05 sqrt(T)` in
+    /// log-moneyness. Under rBergomi it scales like `T^(H - 1/2)`.
+    ///
+    /// # Errors:
+    /// * As [`RoughBergomi::implied_volatilities`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn atm_skew(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        n_steps: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<f64, RustQuantError> {
+        let forward = S * ((r - q) * T).exp();
+        let h = 0.05 * T.sqrt();
+        let strikes = [forward * (-h).exp(), forward * h.exp()];
+
+        let volatilities =
+            self.implied_volatilities(S, r, q, T, &strikes, n_steps, n_paths, seed)?;
+
+        Ok((volatilities[1] - volatilities[0]) / (2.0 * h))
+    }
+
+    fn check(
+        &self,
+        S: f64,
+        r: f64,
+        q: f64,
+        T: f64,
+        n_steps: usize,
+        n_paths: usize,
+    ) -> Result<(), RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .positive("S", S)
+            .finite("r", r)
+            .finite("q", q)
+            .positive("T", T)
+            .check(n_steps > 0 && n_paths > 0, || {
+                "at least one step and one path are needed".to_string()
+            })
+            .finish()
+    }
+}
+
+impl Validate for RoughBergomi {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .check(self.hurst > 0.0 && self.hurst < 0.5, || {
+                format!("hurst must lie in (0, 1/2) (got {})", self.hurst)
+            })
+            .positive("forward_variance", self.forward_variance)
+            .non_negative("vol_of_vol", self.vol_of_vol)
+            .check(self.correlation.abs() < 1.0, || {
+                format!("correlation must lie in (-1, 1) (got {})", self.correlation)
+            })
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Mean and standard error of `samples - beta controls`, with the
+// variance-minimising `beta`. The controls have mean zero.
+fn control_variate_estimate(samples: &[f64], controls: &[f64]) -> Price {
+    let n = samples.len() as f64;
+    let mean = |x: &[f64]| x.iter().sum::<f64>() / n;
+    let (sample_mean, control_mean) = (mean(samples), mean(controls));
+
+    let (covariance, control_variance) =
+        samples
+            .iter()
+            .zip(controls)
+            .fold((0.0, 0.0), |(covariance, variance), (x, c)| {
+                (
+                    covariance + (x - sample_mean) * (c - control_mean),
+                    variance + (c - control_mean).powi(2),
+                )
+            });
+    let beta = match control_variance > 0.0 {
+        true => covariance / control_variance,
+        false => 0.0,
+    };
+
+    let adjusted: Vec<f64> = samples
+        .iter()
+        .zip(controls)
+        .map(|(x, c)| x - beta * c)
+        .collect();
+    let price = mean(&adjusted);
+    let variance = adjusted.iter().map(|y| (y - price).powi(2)).sum::<f64>() / (n - 1.0);
+
+    Price {
+        price,
+        error: Some((variance / n).sqrt()),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_rough_bergomi {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    // Parameters of Bayer, Friz and Gatheral (2016).
+    const RBERGOMI: RoughBergomi = RoughBergomi {
+        hurst: 0.1,
+        forward_variance: 0.04,
+        vol_of_vol: 1.9,
+        correlation: -0.9,
+    };
+
+    const S: f64 = 100.0;
+
+    #[test]
+    fn test_simulated_variance_is_the_forward_variance() {
+        let model = RoughBergomi {
+            vol_of_vol: 1.0,
+            ..RBERGOMI
+        };
+        let paths = model.simulate(S, 0.02, 0.0, 0.2, 40, 20_000, 42).unwrap();
+
+        let mean = |values: Vec<f64>| values.iter().sum::<f64>() / values.len() as f64;
+        let variance = mean(paths.variances.iter().map(|v| v[40]).collect());
+        let price = mean(paths.prices.iter().map(|s| s[40]).collect());
+
+        assert_eq!(paths.times.len(), 41);
+        assert_approx_equal!(variance, 0.04, 0.04 * 0.04);
+        assert_approx_equal!(price, S * (0.02 * 0.2_f64).exp(), 0.25);
+    }
+
+    #[test]
+    fn test_no_vol_of_vol_is_black_scholes() {
+        let model = RoughBergomi {
+            vol_of_vol: 0.0,
+            correlation: 0.0,
+            ..RBERGOMI
+        };
+        let prices = model
+            .price_turbocharged(
+                S,
+                0.03,
+                0.01,
+                0.5,
+                &[90.0, 110.0],
+                TypeFlag::Put,
+                10,
+                10,
+                42,
+            )
+            .unwrap();
+
+        for (price, K) in prices.iter().zip([90.0, 110.0]) {
+            let expected =
+                generalised_black_scholes_merton(S, K, 0.2, 0.03, 0.02, 0.5, TypeFlag::Put);
+
+            assert_approx_equal!(price.price, expected, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_turbocharged_matches_plain_monte_carlo() {
+        let (T, strikes) = (0.2, [95.0, 100.0, 105.0]);
+
+        let turbocharged = RBERGOMI
+            .price_turbocharged(S, 0.0, 0.0, T, &strikes, TypeFlag::Call, 40, 5_000, 1)
+            .unwrap();
+
+        let n_paths = 20_000;
+        let paths = RBERGOMI.simulate(S, 0.0, 0.0, T, 40, n_paths, 2).unwrap();
+
+        for (&K, turbo) in strikes.iter().zip(turbocharged) {
+            let payoffs: Vec<f64> = paths.prices.iter().map(|s| (s[40] - K).max(0.0)).collect();
+            let mean = payoffs.iter().sum::<f64>() / n_paths as f64;
+            let error = (payoffs.iter().map(|p| (p - mean).powi(2)).sum::<f64>()
+                / ((n_paths - 1) * n_paths) as f64)
+                .sqrt();
+            let turbo_error = turbo.error.unwrap();
+
+            assert!(turbo_error < error);
+            assert!((turbo.price - mean).abs() < 4.0 * error.hypot(turbo_error));
+        }
+    }
+
+    #[test]
+    fn test_atm_skew_power_law() {
+        let short = RBERGOMI.atm_skew(S, 0.0, 0.0, 0.05, 40, 5_000, 7).unwrap();
+        let long = RBERGOMI.atm_skew(S, 0.0, 0.0, 0.2, 40, 5_000, 7).unwrap();
+
+        // Negative skew, steepening like T^(H - 1/2): a ratio of 4^0.4.
+        assert!(short < long && long < 0.0);
+        assert_approx_equal!(short / long, 4_f64.powf(0.4), 0.3);
+    }
+
+    #[test]
+    fn test_rough_bergomi_validation() {
+        let smooth = RoughBergomi {
+            hurst: 0.5,
+            ..RBERGOMI
+        };
+
+        assert!(smooth.simulate(S, 0.0, 0.0, 1.0, 10, 10, 42).is_err());
+        assert!(RBERGOMI.simulate(S, 0.0, 0.0, 0.0, 10, 10, 42).is_err());
+        assert!(RBERGOMI
+            .price_turbocharged(S, 0.0, 0.0, 1.0, &[100.0], TypeFlag::Call, 10, 1, 42)
+            .is_err());
+    }
+}
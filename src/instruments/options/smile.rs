@@ -0,0 +1,555 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Smile quotation conventions: converting between delta-quoted and
+//! strike-quoted volatilities.
+//!
+//! FX volatility smiles are quoted by delta (e.g. the 25-delta call and
+//! put) rather than by strike, under one of several delta conventions:
+//!
+//! | Convention                | Call delta                        |
+//! |---------------------------|-----------------------------------|
+//! | Spot                      | `exp(-r_f T) N(d1)`               |
+//! | Forward                   | `N(d1)`                           |
+//! | Premium-adjusted spot     | `exp(-r_f T) (K / F) N(d2)`       |
+//! | Premium-adjusted forward  | `(K / F) N(d2)`                   |
+//!
+//! Premium-adjusted deltas are used when the premium is paid in the
+//! foreign currency (e.g. USD/JPY quoted in USD). The at-the-money quote
+//! may refer to the spot, the forward, or the delta-neutral straddle (DNS)
+//! strike.
+//!
+//! For equity smiles, use the dividend yield as the foreign rate.
+//!
+//! See Wystup (2006), *FX Options and Structured Products*, and Clark
+//! (2011), *Foreign Exchange Option Pricing*.
+
+use super::TypeFlag;
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+use crate::math::distributions::{Distribution, Gaussian};
+use crate::math::Real;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Delta convention of a smile quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeltaConvention {
+    /// Spot delta.
+    Spot,
+    /// Forward delta.
+    Forward,
+    /// Premium-adjusted spot delta.
+    PremiumAdjustedSpot,
+    /// Premium-adjusted forward delta.
+    PremiumAdjustedForward,
+}
+
+/// At-the-money convention of a smile quote.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AtmConvention {
+    /// At-the-money spot: `K = S`.
+    Spot,
+    /// At-the-money forward: `K = F`.
+    Forward,
+    /// Delta-neutral straddle: the call and put deltas sum to zero.
+    DeltaNeutralStraddle,
+}
+
+/// A volatility quoted by delta.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeltaVolQuote {
+    /// Delta (positive for calls, negative for puts).
+    pub delta: f64,
+    /// Volatility.
+    pub volatility: f64,
+    /// Call or put.
+    pub option_type: TypeFlag,
+}
+
+/// A volatility quoted by strike.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StrikeVolQuote {
+    /// Strike.
+    pub strike: f64,
+    /// Volatility.
+    pub volatility: f64,
+}
+
+/// Market data and conventions for converting smile quotes of one expiry.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SmileConventions {
+    /// * `S` - Spot price.
+    pub spot: f64,
+    /// * `T` - Time to expiry.
+    pub time_to_expiry: f64,
+    /// * `r_d` - Domestic (quote currency) rate.
+    pub domestic_rate: f64,
+    /// * `r_f` - Foreign (base currency) rate, or dividend yield.
+    pub foreign_rate: f64,
+    /// Delta convention of the quotes.
+    pub delta_convention: DeltaConvention,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl DeltaConvention {
+    fn is_premium_adjusted(self) -> bool {
+        matches!(
+            self,
+            Self::PremiumAdjustedSpot | Self::PremiumAdjustedForward
+        )
+    }
+}
+
+impl SmileConventions {
+    const BISECTION_ITERATIONS: usize = 200;
+
+    /// Forward price, `F = S exp((r_d - r_f) T)`.
+    #[must_use]
+    pub fn forward(&self) -> f64 {
+        self.spot * ((self.domestic_rate - self.foreign_rate) * self.time_to_expiry).exp()
+    }
+
+    /// Discount factor applied to spot deltas.
+    fn delta_discount(&self) -> f64 {
+        match self.delta_convention {
+            DeltaConvention::Spot | DeltaConvention::PremiumAdjustedSpot => {
+                (-self.foreign_rate * self.time_to_expiry).exp()
+            }
+            DeltaConvention::Forward | DeltaConvention::PremiumAdjustedForward => 1.0,
+        }
+    }
+
+    fn phi(option_type: TypeFlag) -> f64 {
+        match option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        }
+    }
+
+    /// Delta, under the quote's convention, without validation.
+    fn delta_unchecked(&self, strike: f64, volatility: f64, option_type: TypeFlag) -> f64 {
+        let F = self.forward();
+        let sd = volatility * self.time_to_expiry.sqrt();
+        let d1 = (F / strike).ln() / sd + 0.5 * sd;
+        let d2 = d1 - sd;
+        let phi = Self::phi(option_type);
+
+        let delta = match self.delta_convention.is_premium_adjusted() {
+            false => phi * (phi * d1).norm_cdf(),
+            true => phi * strike / F * (phi * d2).norm_cdf(),
+        };
+
+        self.delta_discount() * delta
+    }
+
+    /// Delta of an option with the given strike and volatility, under the
+    /// quote's delta convention.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the strike or volatility are not
+    ///   positive.
+    pub fn delta(
+        &self,
+        strike: f64,
+        volatility: f64,
+        option_type: TypeFlag,
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .positive("strike", strike)
+            .positive("volatility", volatility)
+            .finish()?;
+
+        Ok(self.delta_unchecked(strike, volatility, option_type))
+    }
+
+    /// Strike of an option with the given delta and volatility, under the
+    /// quote's delta convention.
+    ///
+    /// Unadjusted deltas invert in closed form. Premium-adjusted call deltas
+    /// are not monotonic in the strike, so the strike is taken on the
+    /// (conventional) branch above the strike of maximum delta.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], if the volatility is not positive, or if
+    ///   no strike has the given delta.
+    pub fn strike_from_delta(
+        &self,
+        delta: f64,
+        volatility: f64,
+        option_type: TypeFlag,
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .positive("volatility", volatility)
+            .finish()?;
+
+        let F = self.forward();
+        let sd = volatility * self.time_to_expiry.sqrt();
+        let phi = Self::phi(option_type);
+        let discount = self.delta_discount();
+
+        let out_of_range = || {
+            RustQuantError::InvalidArgument(format!(
+                "No strike has delta {delta} for a {option_type:?} under the {:?} convention.",
+                self.delta_convention
+            ))
+        };
+
+        // Unadjusted deltas lie in (0, discount) for calls and
+        // (-discount, 0) for puts, and give the strike in closed form.
+        let unadjusted_strike = |delta: f64| -> Result<f64, RustQuantError> {
+            let p = phi * delta / discount;
+
+            if !(p > 0.0 && p < 1.0) {
+                return Err(out_of_range());
+            }
+
+            let d1 = phi * Gaussian::default().inv_cdf(p);
+
+            Ok(F * (-d1 * sd + 0.5 * sd * sd).exp())
+        };
+
+        if !self.delta_convention.is_premium_adjusted() {
+            return unadjusted_strike(delta);
+        }
+
+        let pa_delta = |strike: f64| self.delta_unchecked(strike, volatility, option_type);
+
+        let (mut lo, mut hi) = match option_type {
+            TypeFlag::Call => {
+                // The premium-adjusted call delta is below the unadjusted
+                // delta, so the strike is below the unadjusted strike, and
+                // above the strike of maximum delta, where
+                // sd N(d2) = n(d2).
+                let upper = unadjusted_strike(delta)?;
+
+                let (mut lo, mut hi) = (-sd, 10.0);
+                for _ in 0..Self::BISECTION_ITERATIONS {
+                    let d2 = 0.5 * (lo + hi);
+                    match sd * d2.norm_cdf() < d2.norm_pdf() {
+                        true => lo = d2,
+                        false => hi = d2,
+                    }
+                }
+                let d2 = 0.5 * (lo + hi);
+                let lower = F * (-d2 * sd - 0.5 * sd * sd).exp();
+
+                if pa_delta(lower) < delta {
+                    return Err(out_of_range());
+                }
+
+                (lower.ln(), upper.ln())
+            }
+            TypeFlag::Put => {
+                // The premium-adjusted put delta decreases from 0 as the
+                // strike increases.
+                if delta >= 0.0 {
+                    return Err(out_of_range());
+                }
+
+                let lower = F * (-10.0 * sd).exp();
+                let mut upper = F * (10.0 * sd).exp();
+                while pa_delta(upper) > delta {
+                    upper *= 2.0;
+                }
+
+                (lower.ln(), upper.ln())
+            }
+        };
+
+        // On both branches the delta decreases in the strike.
+        for _ in 0..Self::BISECTION_ITERATIONS {
+            let mid = 0.5 * (lo + hi);
+            match pa_delta(mid.exp()) > delta {
+                true => lo = mid,
+                false => hi = mid,
+            }
+        }
+
+        Ok((0.5 * (lo + hi)).exp())
+    }
+
+    /// At-the-money strike under the given convention.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the volatility is not positive.
+    pub fn atm_strike(
+        &self,
+        volatility: f64,
+        atm_convention: AtmConvention,
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .positive("volatility", volatility)
+            .finish()?;
+
+        let F = self.forward();
+        let variance = volatility * volatility * self.time_to_expiry;
+
+        Ok(match atm_convention {
+            AtmConvention::Spot => self.spot,
+            AtmConvention::Forward => F,
+            AtmConvention::DeltaNeutralStraddle => {
+                match self.delta_convention.is_premium_adjusted() {
+                    false => F * (0.5 * variance).exp(),
+                    true => F * (-0.5 * variance).exp(),
+                }
+            }
+        })
+    }
+
+    /// Convert delta-quoted volatilities to strike-quoted volatilities.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if any quote fails
+    ///   [`SmileConventions::strike_from_delta`].
+    pub fn to_strike_quotes(
+        &self,
+        quotes: &[DeltaVolQuote],
+    ) -> Result<Vec<StrikeVolQuote>, RustQuantError> {
+        quotes
+            .iter()
+            .map(|quote| {
+                Ok(StrikeVolQuote {
+                    strike: self.strike_from_delta(
+                        quote.delta,
+                        quote.volatility,
+                        quote.option_type,
+                    )?,
+                    volatility: quote.volatility,
+                })
+            })
+            .collect()
+    }
+
+    /// Convert strike-quoted volatilities to delta-quoted volatilities,
+    /// quoting by calls or puts.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if any quote fails
+    ///   [`SmileConventions::delta`].
+    pub fn to_delta_quotes(
+        &self,
+        quotes: &[StrikeVolQuote],
+        option_type: TypeFlag,
+    ) -> Result<Vec<DeltaVolQuote>, RustQuantError> {
+        quotes
+            .iter()
+            .map(|quote| {
+                Ok(DeltaVolQuote {
+                    delta: self.delta(quote.strike, quote.volatility, option_type)?,
+                    volatility: quote.volatility,
+                    option_type,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Validate for SmileConventions {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("spot", self.spot)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finite("domestic_rate", self.domestic_rate)
+            .finite("foreign_rate", self.foreign_rate)
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Call and put volatilities at a given delta from the market's
+/// at-the-money, risk reversal and (smile) butterfly quotes:
+///
+/// ```text
+/// v_call = v_atm + butterfly + risk_reversal / 2
+/// v_put  = v_atm + butterfly - risk_reversal / 2
+/// ```
+#[must_use]
+pub fn smile_from_risk_reversal(
+    atm_volatility: f64,
+    risk_reversal: f64,
+    butterfly: f64,
+) -> (f64, f64) {
+    (
+        atm_volatility + butterfly + 0.5 * risk_reversal,
+        atm_volatility + butterfly - 0.5 * risk_reversal,
+    )
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_smile {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const EURUSD: SmileConventions = SmileConventions {
+        spot: 1.35,
+        time_to_expiry: 1.0,
+        domestic_rate: 0.03,
+        foreign_rate: 0.025,
+        delta_convention: DeltaConvention::Spot,
+    };
+
+    const CONVENTIONS: [DeltaConvention; 4] = [
+        DeltaConvention::Spot,
+        DeltaConvention::Forward,
+        DeltaConvention::PremiumAdjustedSpot,
+        DeltaConvention::PremiumAdjustedForward,
+    ];
+
+    #[test]
+    fn test_forward_delta_strike() {
+        let market = SmileConventions {
+            delta_convention: DeltaConvention::Forward,
+            ..EURUSD
+        };
+        let strike = market.strike_from_delta(0.5, 0.1, TypeFlag::Call).unwrap();
+
+        // N(d1) = 1/2 at d1 = 0, i.e. the DNS strike.
+        assert_approx_equal!(strike, market.forward() * 0.005_f64.exp(), 1e-12);
+    }
+
+    #[test]
+    fn test_strike_delta_round_trip() {
+        for delta_convention in CONVENTIONS {
+            let market = SmileConventions {
+                delta_convention,
+                ..EURUSD
+            };
+
+            for (delta, option_type) in [(0.25, TypeFlag::Call), (-0.25, TypeFlag::Put)] {
+                let strike = market.strike_from_delta(delta, 0.12, option_type).unwrap();
+
+                assert_approx_equal!(
+                    market.delta(strike, 0.12, option_type).unwrap(),
+                    delta,
+                    1e-10
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_premium_adjusted_strikes_are_lower() {
+        let adjusted = SmileConventions {
+            delta_convention: DeltaConvention::PremiumAdjustedSpot,
+            ..EURUSD
+        };
+
+        for (delta, option_type) in [(0.25, TypeFlag::Call), (-0.25, TypeFlag::Put)] {
+            assert!(
+                adjusted
+                    .strike_from_delta(delta, 0.12, option_type)
+                    .unwrap()
+                    < EURUSD.strike_from_delta(delta, 0.12, option_type).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_delta_neutral_straddle() {
+        for delta_convention in CONVENTIONS {
+            let market = SmileConventions {
+                delta_convention,
+                ..EURUSD
+            };
+            let strike = market
+                .atm_strike(0.1, AtmConvention::DeltaNeutralStraddle)
+                .unwrap();
+
+            let call = market.delta(strike, 0.1, TypeFlag::Call).unwrap();
+            let put = market.delta(strike, 0.1, TypeFlag::Put).unwrap();
+
+            assert_approx_equal!(call + put, 0.0, 1e-12);
+        }
+
+        assert_eq!(EURUSD.atm_strike(0.1, AtmConvention::Spot).unwrap(), 1.35);
+        assert_approx_equal!(
+            EURUSD.atm_strike(0.1, AtmConvention::Forward).unwrap(),
+            1.35 * 0.005_f64.exp(),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_smile_requotation() {
+        let (call_vol, put_vol) = smile_from_risk_reversal(0.10, -0.01, 0.003);
+        assert_approx_equal!(call_vol, 0.098, 1e-12);
+        assert_approx_equal!(put_vol, 0.108, 1e-12);
+
+        let quotes = [
+            DeltaVolQuote {
+                delta: -0.25,
+                volatility: put_vol,
+                option_type: TypeFlag::Put,
+            },
+            DeltaVolQuote {
+                delta: 0.25,
+                volatility: call_vol,
+                option_type: TypeFlag::Call,
+            },
+        ];
+
+        let strikes = EURUSD.to_strike_quotes(&quotes).unwrap();
+        assert!(strikes[0].strike < EURUSD.forward());
+        assert!(strikes[1].strike > EURUSD.forward());
+
+        let calls = EURUSD.to_delta_quotes(&strikes, TypeFlag::Call).unwrap();
+        assert_approx_equal!(calls[1].delta, 0.25, 1e-10);
+        // Put-call delta parity: the call delta is the put delta plus the
+        // discount factor.
+        assert_approx_equal!(calls[0].delta, (-0.025_f64).exp() - 0.25, 1e-10);
+    }
+
+    #[test]
+    fn test_smile_validation() {
+        assert!(EURUSD.strike_from_delta(1.2, 0.1, TypeFlag::Call).is_err());
+        assert!(EURUSD.strike_from_delta(0.25, 0.1, TypeFlag::Put).is_err());
+        assert!(EURUSD
+            .strike_from_delta(0.25, -0.1, TypeFlag::Call)
+            .is_err());
+
+        // Premium-adjusted call deltas are bounded well below one.
+        let adjusted = SmileConventions {
+            delta_convention: DeltaConvention::PremiumAdjustedForward,
+            ..EURUSD
+        };
+        assert!(adjusted
+            .strike_from_delta(0.99, 0.1, TypeFlag::Call)
+            .is_err());
+
+        let market = SmileConventions {
+            spot: -1.0,
+            ..EURUSD
+        };
+        assert!(market.delta(1.0, 0.1, TypeFlag::Call).is_err());
+    }
+}
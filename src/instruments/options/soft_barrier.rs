@@ -0,0 +1,314 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Soft-barrier options (Hart and Ross, 1994).
+//!
+//! Instead of knocking in or out entirely at a single level, a soft barrier
+//! knocks in (or out) a fraction of the notional that grows linearly as the
+//! underlying moves through the range `[L, U]`. For a soft down barrier
+//! with running minimum `m`, the knocked-in fraction is
+//!
+//! $$
+//! \min\left(1, \max\left(0, \frac{U - m}{U - L}\right)\right)
+//! $$
+//!
+//! and for a soft up barrier with running maximum `M` it is
+//! `(M - L) / (U - L)`, clipped the same way. A soft-barrier option is the
+//! average of the standard barrier options with barriers spread uniformly
+//! over `[L, U]`, and tends to the standard option as `L` approaches `U`.
+
+use super::closed_form::generalised_black_scholes_merton;
+use super::{BarrierType, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::validation::{Validate, Validator};
+use crate::math::Real;
+use alloc::format;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Soft-barrier option parameters.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SoftBarrierOption {
+    /// * `S` - Initial underlying price.
+    pub initial_price: f64,
+    /// * `X` - Strike price.
+    pub strike_price: f64,
+    /// * `L` - Lower barrier level.
+    pub lower_barrier: f64,
+    /// * `U` - Upper barrier level.
+    pub upper_barrier: f64,
+    /// * `t` - Time to expiry.
+    pub time_to_expiry: f64,
+    /// * `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// * `v` - Volatility.
+    pub volatility: f64,
+    /// * `q` - Dividend yield.
+    pub dividend_yield: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SoftBarrierOption {
+    /// Closed-form price (Haug's *Complete Guide to Option Pricing
+    /// Formulas*, after Hart and Ross).
+    ///
+    /// # Arguments:
+    ///
+    /// * `type_flag` - One of: `cdi`, `cdo` (soft down barrier) or `pui`,
+    ///   `puo` (soft up barrier).
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], for the other barrier types, if the
+    ///   underlying is already inside the barrier range, or if the barrier
+    ///   range is on the wrong side of the strike (`U <= X` for calls,
+    ///   `L >= X` for puts), where the formula does not hold.
+    pub fn price(&self, type_flag: BarrierType) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        let (S, X) = (self.initial_price, self.strike_price);
+        let (L, U) = (self.lower_barrier, self.upper_barrier);
+
+        let (is_down, option_type) = match type_flag {
+            BarrierType::CDI | BarrierType::CDO => (true, TypeFlag::Call),
+            BarrierType::PUI | BarrierType::PUO => (false, TypeFlag::Put),
+            _ => {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "Soft barriers are priced for CDI, CDO, PUI and PUO only, got {type_flag:?}."
+                )))
+            }
+        };
+
+        if (is_down && S < U) || (!is_down && S > L) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Barrier touched - the underlying {S} is inside the soft barrier [{L}, {U}] ({type_flag:?})."
+            )));
+        }
+        if (is_down && U > X) || (!is_down && L < X) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "The soft barrier [{L}, {U}] must be on the far side of the strike {X} ({type_flag:?})."
+            )));
+        }
+
+        let knock_in = self.knock_in(option_type);
+
+        Ok(match type_flag {
+            BarrierType::CDI | BarrierType::PUI => knock_in,
+            _ => self.vanilla(option_type) - knock_in,
+        })
+    }
+
+    /// Knock-in value. The formula has removable singularities at `b = 0`
+    /// and `b = -v^2`; there it is averaged over a small symmetric
+    /// perturbation of the cost of carry.
+    fn knock_in(&self, option_type: TypeFlag) -> f64 {
+        let v2 = self.volatility * self.volatility;
+        let b = self.risk_free_rate - self.dividend_yield;
+
+        let delta = 1e-6 * v2;
+
+        if b.abs() < delta || (b + v2).abs() < delta {
+            let (lo, hi) = (b - 2.0 * delta, b + 2.0 * delta);
+            0.5 * (self.knock_in_impl(lo, option_type) + self.knock_in_impl(hi, option_type))
+        } else {
+            self.knock_in_impl(b, option_type)
+        }
+    }
+
+    fn knock_in_impl(&self, b: f64, option_type: TypeFlag) -> f64 {
+        let S = self.initial_price;
+        let X = self.strike_price;
+        let L = self.lower_barrier;
+        let U = self.upper_barrier;
+        let t = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+
+        let eta = match option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+
+        let v_sqrt_t = v * t.sqrt();
+
+        let mu = (b + v * v / 2.0) / (v * v);
+        let lambda1 = (-0.5 * v * v * t * (mu + 0.5) * (mu - 0.5)).exp();
+        let lambda2 = (-0.5 * v * v * t * (mu - 0.5) * (mu - 1.5)).exp();
+
+        let d1 = (U * U / (S * X)).ln() / v_sqrt_t + mu * v_sqrt_t;
+        let d2 = d1 - (mu + 0.5) * v_sqrt_t;
+        let d3 = (U * U / (S * X)).ln() / v_sqrt_t + (mu - 1.0) * v_sqrt_t;
+        let d4 = d3 - (mu - 0.5) * v_sqrt_t;
+
+        let e1 = (L * L / (S * X)).ln() / v_sqrt_t + mu * v_sqrt_t;
+        let e2 = e1 - (mu + 0.5) * v_sqrt_t;
+        let e3 = (L * L / (S * X)).ln() / v_sqrt_t + (mu - 1.0) * v_sqrt_t;
+        let e4 = e3 - (mu - 0.5) * v_sqrt_t;
+
+        let asset = S * ((b - r) * t).exp() * S.powf(-2.0 * mu) * (S * X).powf(mu + 0.5)
+            / (2.0 * (mu + 0.5))
+            * ((U * U / (S * X)).powf(mu + 0.5) * (eta * d1).norm_cdf()
+                - lambda1 * (eta * d2).norm_cdf()
+                - (L * L / (S * X)).powf(mu + 0.5) * (eta * e1).norm_cdf()
+                + lambda1 * (eta * e2).norm_cdf());
+
+        let cash = X * (-r * t).exp() * S.powf(-2.0 * (mu - 1.0)) * (S * X).powf(mu - 0.5)
+            / (2.0 * (mu - 0.5))
+            * ((U * U / (S * X)).powf(mu - 0.5) * (eta * d3).norm_cdf()
+                - lambda2 * (eta * d4).norm_cdf()
+                - (L * L / (S * X)).powf(mu - 0.5) * (eta * e3).norm_cdf()
+                + lambda2 * (eta * e4).norm_cdf());
+
+        eta / (U - L) * (asset - cash)
+    }
+
+    fn vanilla(&self, option_type: TypeFlag) -> f64 {
+        let r = self.risk_free_rate;
+
+        generalised_black_scholes_merton(
+            self.initial_price,
+            self.strike_price,
+            self.volatility,
+            r,
+            r - self.dividend_yield,
+            self.time_to_expiry,
+            option_type,
+        )
+    }
+}
+
+impl Validate for SoftBarrierOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("lower_barrier", self.lower_barrier)
+            .positive("upper_barrier", self.upper_barrier)
+            .check(self.lower_barrier < self.upper_barrier, || {
+                format!(
+                    "lower_barrier must be below upper_barrier (got {} >= {})",
+                    self.lower_barrier, self.upper_barrier
+                )
+            })
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .positive("volatility", self.volatility)
+            .finite("dividend_yield", self.dividend_yield)
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_soft_barrier {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::BarrierOption;
+
+    const DOWN: SoftBarrierOption = SoftBarrierOption {
+        initial_price: 100.0,
+        strike_price: 100.0,
+        lower_barrier: 70.0,
+        upper_barrier: 95.0,
+        time_to_expiry: 0.5,
+        risk_free_rate: 0.1,
+        volatility: 0.2,
+        dividend_yield: 0.05,
+    };
+
+    const UP: SoftBarrierOption = SoftBarrierOption {
+        lower_barrier: 105.0,
+        upper_barrier: 130.0,
+        ..DOWN
+    };
+
+    /// Average of the standard barrier prices over barriers in `[L, U]`.
+    fn average_hard_barrier(option: &SoftBarrierOption, type_flag: BarrierType) -> f64 {
+        let n = 2_000;
+        let (L, U) = (option.lower_barrier, option.upper_barrier);
+
+        (0..n)
+            .map(|i| {
+                BarrierOption {
+                    initial_price: option.initial_price,
+                    strike_price: option.strike_price,
+                    barrier: L + (U - L) * (i as f64 + 0.5) / n as f64,
+                    time_to_expiry: option.time_to_expiry,
+                    risk_free_rate: option.risk_free_rate,
+                    volatility: option.volatility,
+                    rebate: 0.0,
+                    dividend_yield: option.dividend_yield,
+                }
+                .price(type_flag)
+                .unwrap()
+            })
+            .sum::<f64>()
+            / n as f64
+    }
+
+    #[test]
+    fn test_soft_barrier_is_average_of_hard_barriers() {
+        for (option, types) in [
+            (DOWN, [BarrierType::CDI, BarrierType::CDO]),
+            (UP, [BarrierType::PUI, BarrierType::PUO]),
+        ] {
+            for type_flag in types {
+                assert_approx_equal!(
+                    option.price(type_flag).unwrap(),
+                    average_hard_barrier(&option, type_flag),
+                    1e-5
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_soft_barrier_zero_cost_of_carry() {
+        let at = SoftBarrierOption {
+            dividend_yield: DOWN.risk_free_rate,
+            ..DOWN
+        };
+        let near = SoftBarrierOption {
+            dividend_yield: DOWN.risk_free_rate - 1e-6,
+            ..DOWN
+        };
+
+        let price = at.price(BarrierType::CDI).unwrap();
+
+        assert!(price.is_finite());
+        assert_approx_equal!(price, near.price(BarrierType::CDI).unwrap(), 1e-4);
+    }
+
+    #[test]
+    fn test_soft_barrier_errors() {
+        assert!(DOWN.price(BarrierType::CUO).is_err());
+        assert!(DOWN.price(BarrierType::PUI).is_err());
+
+        let inside = SoftBarrierOption {
+            initial_price: 90.0,
+            ..DOWN
+        };
+        assert!(inside.price(BarrierType::CDO).is_err());
+
+        let inverted = SoftBarrierOption {
+            lower_barrier: 96.0,
+            ..DOWN
+        };
+        assert!(inverted.price(BarrierType::CDO).is_err());
+    }
+}
@@ -0,0 +1,344 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Step (occupation-time) barrier options (Linetsky, 1999).
+//!
+//! Instead of knocking out the moment the barrier is touched, a
+//! proportional step option loses value at a rate `rho` for as long as the
+//! underlying stays beyond the barrier. A down-and-out step call pays
+//!
+//! $$
+//! e^{-\rho \tau_B} \max(S_T - X, 0),
+//! \qquad \tau_B = \int_0^T \mathbb{1}_{S_t \le B} dt
+//! $$
+//!
+//! (and the up versions use the time spent above the barrier). With
+//! `rho = 0` this is a vanilla option, and as `rho` grows it tends to the
+//! standard knock-out. Knock-in step options pay `1 - e^{-\rho \tau_B}` of
+//! the vanilla payoff.
+//!
+//! The price solves the Black-Scholes PDE with an extra discounting
+//! (killing) term `rho` beyond the barrier, which is solved here on a
+//! Crank-Nicolson grid in log-price, as in the
+//! [`FiniteDifferenceBarrierEngine`](super::FiniteDifferenceBarrierEngine).
+
+use crate::error::RustQuantError;
+use crate::instruments::options::barrier::BarrierType;
+use crate::instruments::{Validate, Validator};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Proportional step option parameters.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepOption {
+    /// * `S` - Initial underlying price.
+    pub initial_price: f64,
+    /// * `X` - Strike price.
+    pub strike_price: f64,
+    /// * `B` - Barrier level.
+    pub barrier: f64,
+    /// * `t` - Time to expiry.
+    pub time_to_expiry: f64,
+    /// * `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// * `v` - Volatility.
+    pub volatility: f64,
+    /// * `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// * `rho` - Knock-out rate per unit of time spent beyond the barrier.
+    pub knock_out_rate: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl StepOption {
+    /// Default number of time steps.
+    const TIME_STEPS: usize = 500;
+    /// Default number of log-price steps.
+    const PRICE_STEPS: usize = 500;
+    /// Number of standard deviations the grid extends beyond the
+    /// initial price, strike and barrier.
+    const STANDARD_DEVIATIONS: f64 = 6.0;
+
+    /// Price the step option on the default (500 x 500) grid.
+    ///
+    /// The barrier type gives the direction (up or down), call or put, and
+    /// whether the option steps out or in. Unlike a standard barrier
+    /// option, the underlying may start beyond the barrier.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`].
+    pub fn price(&self, type_flag: BarrierType) -> Result<f64, RustQuantError> {
+        self.price_on_grid(type_flag, Self::TIME_STEPS, Self::PRICE_STEPS)
+    }
+
+    /// Price the step option with the given number of time and log-price
+    /// steps.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`].
+    pub fn price_on_grid(
+        &self,
+        type_flag: BarrierType,
+        time_steps: usize,
+        price_steps: usize,
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        let step_out = self.solve(type_flag, self.knock_out_rate, time_steps, price_steps);
+
+        Ok(match type_flag.is_knock_in() {
+            true => self.solve(type_flag, 0.0, time_steps, price_steps) - step_out,
+            false => step_out,
+        })
+    }
+
+    /// Solve the pricing PDE with killing rate `rho` beyond the barrier.
+    ///
+    /// The grid is shifted so the barrier falls on a node, which takes half
+    /// the killing rate, and the far boundaries are the discounted forward
+    /// payoff (killed at `rho` if they are beyond the barrier).
+    fn solve(
+        &self,
+        type_flag: BarrierType,
+        rho: f64,
+        time_steps: usize,
+        price_steps: usize,
+    ) -> f64 {
+        let n = price_steps.max(3);
+        let m = time_steps.max(1);
+
+        let S = self.initial_price;
+        let X = self.strike_price;
+        let T = self.time_to_expiry;
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+        let b = r - self.dividend_yield;
+        let h = self.barrier.ln();
+
+        let width = Self::STANDARD_DEVIATIONS * v * T.sqrt();
+        let x_lo = S.min(X).min(self.barrier).ln() - width;
+        let x_hi = S.max(X).max(self.barrier).ln() + width;
+
+        let dx = (x_hi - x_lo) / n as f64;
+        let k = ((h - x_lo) / dx).round() as usize;
+        let x_min = h - k as f64 * dx;
+        let dt = T / m as f64;
+
+        let payoff = |s: f64| match type_flag.is_call() {
+            true => (s - X).max(0.0),
+            false => (X - s).max(0.0),
+        };
+
+        // Fraction of the killing rate applied at each node.
+        let beyond = |i: usize| match (i == k, type_flag.is_up()) {
+            (true, _) => 0.5,
+            (false, true) if i > k => 1.0,
+            (false, false) if i < k => 1.0,
+            _ => 0.0,
+        };
+
+        let boundary = |i: usize, tau: f64| {
+            let x = x_min + i as f64 * dx;
+            (-(r + rho * beyond(i)) * tau).exp() * payoff((x + b * tau).exp())
+        };
+
+        // Spatial operator coefficients.
+        let a = 0.5 * v * v;
+        let c = b - a;
+        let lo = a / (dx * dx) - c / (2.0 * dx);
+        let di = -2.0 * a / (dx * dx) - r;
+        let up = a / (dx * dx) + c / (2.0 * dx);
+
+        let mut values: Vec<f64> = (0..=n)
+            .map(|i| payoff((x_min + i as f64 * dx).exp()))
+            .collect();
+
+        let mut rhs = vec![0.0; n - 1];
+        let mut c_prime = vec![0.0; n - 1];
+
+        for step in 0..m {
+            let theta = if step < 2 { 1.0 } else { 0.5 };
+            let tau = (step + 1) as f64 * dt;
+
+            let bound_lo = boundary(0, tau);
+            let bound_hi = boundary(n, tau);
+
+            // Explicit part.
+            for i in 1..n {
+                let lv =
+                    lo * values[i - 1] + (di - rho * beyond(i)) * values[i] + up * values[i + 1];
+                rhs[i - 1] = values[i] + (1.0 - theta) * dt * lv;
+            }
+            rhs[0] += theta * dt * lo * bound_lo;
+            rhs[n - 2] += theta * dt * up * bound_hi;
+
+            // Implicit part (Thomas algorithm).
+            let sub = -theta * dt * lo;
+            let sup = -theta * dt * up;
+            let diag = |i: usize| 1.0 - theta * dt * (di - rho * beyond(i));
+
+            c_prime[0] = sup / diag(1);
+            rhs[0] /= diag(1);
+            for i in 1..(n - 1) {
+                let denom = diag(i + 1) - sub * c_prime[i - 1];
+                c_prime[i] = sup / denom;
+                rhs[i] = (rhs[i] - sub * rhs[i - 1]) / denom;
+            }
+            for i in (0..(n - 2)).rev() {
+                rhs[i] -= c_prime[i] * rhs[i + 1];
+            }
+
+            values[0] = bound_lo;
+            values[1..n].copy_from_slice(&rhs);
+            values[n] = bound_hi;
+        }
+
+        // Linear interpolation at the initial price.
+        let pos = ((S.ln() - x_min) / dx).clamp(0.0, n as f64);
+        let i = (pos.floor() as usize).min(n - 1);
+        let w = pos - i as f64;
+
+        (1.0 - w) * values[i] + w * values[i + 1]
+    }
+}
+
+impl Validate for StepOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("barrier", self.barrier)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .positive("volatility", self.volatility)
+            .finite("dividend_yield", self.dividend_yield)
+            .non_negative("knock_out_rate", self.knock_out_rate)
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_step {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::{generalised_black_scholes_merton, BarrierOption, TypeFlag};
+
+    const OPTION: StepOption = StepOption {
+        initial_price: 100.0,
+        strike_price: 100.0,
+        barrier: 90.0,
+        time_to_expiry: 1.0,
+        risk_free_rate: 0.05,
+        volatility: 0.2,
+        dividend_yield: 0.01,
+        knock_out_rate: 1.0,
+    };
+
+    fn vanilla(option_type: TypeFlag) -> f64 {
+        generalised_black_scholes_merton(100.0, 100.0, 0.2, 0.05, 0.04, 1.0, option_type)
+    }
+
+    #[test]
+    fn test_step_option_zero_rate_is_vanilla() {
+        let option = StepOption {
+            knock_out_rate: 0.0,
+            ..OPTION
+        };
+
+        assert_approx_equal!(
+            option.price(BarrierType::CDO).unwrap(),
+            vanilla(TypeFlag::Call),
+            1e-2
+        );
+        assert_approx_equal!(option.price(BarrierType::CDI).unwrap(), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_step_option_always_beyond_barrier() {
+        // Practically all the time is spent above a barrier this far below.
+        let option = StepOption {
+            barrier: 1.0,
+            ..OPTION
+        };
+
+        assert_approx_equal!(
+            option.price(BarrierType::CUO).unwrap(),
+            (-1.0_f64).exp() * vanilla(TypeFlag::Call),
+            1e-2
+        );
+    }
+
+    #[test]
+    fn test_step_option_tends_to_knock_out() {
+        let barrier = BarrierOption {
+            initial_price: 100.0,
+            strike_price: 100.0,
+            barrier: 90.0,
+            time_to_expiry: 1.0,
+            risk_free_rate: 0.05,
+            volatility: 0.2,
+            rebate: 0.0,
+            dividend_yield: 0.01,
+        }
+        .price(BarrierType::CDO)
+        .unwrap();
+
+        let prices: Vec<f64> = [1.0, 10.0, 100.0, 1_000.0, 10_000.0]
+            .iter()
+            .map(|&knock_out_rate| {
+                StepOption {
+                    knock_out_rate,
+                    ..OPTION
+                }
+                .price(BarrierType::CDO)
+                .unwrap()
+            })
+            .collect();
+
+        assert!(prices.windows(2).all(|w| w[0] > w[1]));
+        assert!(prices[4] > barrier);
+        assert_approx_equal!(prices[4], barrier, 5e-2);
+    }
+
+    #[test]
+    fn test_step_option_in_out_parity() {
+        let option = StepOption {
+            barrier: 110.0,
+            knock_out_rate: 5.0,
+            ..OPTION
+        };
+
+        let out = option.price(BarrierType::PUO).unwrap();
+        let knock_in = option.price(BarrierType::PUI).unwrap();
+
+        assert_approx_equal!(out + knock_in, vanilla(TypeFlag::Put), 1e-2);
+    }
+
+    #[test]
+    fn test_step_option_validation() {
+        let option = StepOption {
+            knock_out_rate: -1.0,
+            ..OPTION
+        };
+
+        assert!(option.price(BarrierType::CDO).is_err());
+    }
+}
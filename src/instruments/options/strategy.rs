@@ -0,0 +1,458 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Option strategies: portfolios of vanilla European options (and the
+//! underlying) with a common expiry.
+//!
+//! A [`Strategy`] is built leg by leg, or from one of the usual templates
+//! (straddle, strangle, butterfly, iron condor, collar). It is priced and
+//! its Greeks computed with [`BlackScholesMerton`], and its payoff is
+//! piecewise linear in the terminal price, which gives the payoff and
+//! profit profiles at expiry and the breakeven prices in closed form.
+
+use super::{BlackScholesMerton, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::{Greeks, Sensitivities, Validate, Validator};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Leg of an option strategy. Quantities are signed: positive for long
+/// positions, negative for short ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StrategyLeg {
+    /// Vanilla European option.
+    Option {
+        /// Call or put.
+        option_type: TypeFlag,
+        /// Strike price.
+        strike: f64,
+        /// Number of options.
+        quantity: f64,
+    },
+
+    /// Position in the underlying.
+    Underlying {
+        /// Number of units of the underlying.
+        quantity: f64,
+    },
+}
+
+/// Option strategy: a set of legs expiring together.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Strategy {
+    /// Legs of the strategy.
+    pub legs: Vec<StrategyLeg>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl StrategyLeg {
+    /// Payoff of the leg at expiry, for the terminal price `S`.
+    #[must_use]
+    pub fn payoff(&self, S: f64) -> f64 {
+        match *self {
+            Self::Option {
+                option_type,
+                strike,
+                quantity,
+            } => {
+                quantity
+                    * match option_type {
+                        TypeFlag::Call => (S - strike).max(0.0),
+                        TypeFlag::Put => (strike - S).max(0.0),
+                    }
+            }
+            Self::Underlying { quantity } => quantity * S,
+        }
+    }
+
+    // Black-Scholes-Merton model of one option of the leg, in `market`.
+    fn model(&self, market: &BlackScholesMerton) -> Option<BlackScholesMerton> {
+        match *self {
+            Self::Option {
+                option_type,
+                strike,
+                ..
+            } => Some(BlackScholesMerton {
+                strike_price: strike,
+                option_type,
+                ..*market
+            }),
+            Self::Underlying { .. } => None,
+        }
+    }
+
+    fn quantity(&self) -> f64 {
+        match *self {
+            Self::Option { quantity, .. } | Self::Underlying { quantity } => quantity,
+        }
+    }
+}
+
+impl Strategy {
+    /// Empty strategy.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an option leg.
+    #[must_use]
+    pub fn with_option(mut self, option_type: TypeFlag, strike: f64, quantity: f64) -> Self {
+        self.legs.push(StrategyLeg::Option {
+            option_type,
+            strike,
+            quantity,
+        });
+        self
+    }
+
+    /// Add a position in the underlying.
+    #[must_use]
+    pub fn with_underlying(mut self, quantity: f64) -> Self {
+        self.legs.push(StrategyLeg::Underlying { quantity });
+        self
+    }
+
+    /// Long straddle: a long call and a long put struck at `strike`.
+    #[must_use]
+    pub fn straddle(strike: f64) -> Self {
+        Self::new()
+            .with_option(TypeFlag::Call, strike, 1.0)
+            .with_option(TypeFlag::Put, strike, 1.0)
+    }
+
+    /// Long strangle: a long put struck at `put_strike` and a long call
+    /// struck at `call_strike`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] unless `put_strike < call_strike`.
+    pub fn strangle(put_strike: f64, call_strike: f64) -> Result<Self, RustQuantError> {
+        check_increasing(&[put_strike, call_strike])?;
+
+        Ok(Self::new()
+            .with_option(TypeFlag::Put, put_strike, 1.0)
+            .with_option(TypeFlag::Call, call_strike, 1.0))
+    }
+
+    /// Long call butterfly: long one call at each of the wings and short
+    /// two at the body.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] unless the strikes are
+    ///   increasing.
+    pub fn butterfly(low: f64, middle: f64, high: f64) -> Result<Self, RustQuantError> {
+        check_increasing(&[low, middle, high])?;
+
+        Ok(Self::new()
+            .with_option(TypeFlag::Call, low, 1.0)
+            .with_option(TypeFlag::Call, middle, -2.0)
+            .with_option(TypeFlag::Call, high, 1.0))
+    }
+
+    /// Short iron condor: a bull put spread on `k1 < k2` and a bear call
+    /// spread on `k3 < k4`, collecting a credit.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] unless the strikes are
+    ///   increasing.
+    pub fn iron_condor(k1: f64, k2: f64, k3: f64, k4: f64) -> Result<Self, RustQuantError> {
+        check_increasing(&[k1, k2, k3, k4])?;
+
+        Ok(Self::new()
+            .with_option(TypeFlag::Put, k1, 1.0)
+            .with_option(TypeFlag::Put, k2, -1.0)
+            .with_option(TypeFlag::Call, k3, -1.0)
+            .with_option(TypeFlag::Call, k4, 1.0))
+    }
+
+    /// Collar: long the underlying, protected by a long put struck at
+    /// `put_strike` and financed by a short call struck at `call_strike`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] unless `put_strike < call_strike`.
+    pub fn collar(put_strike: f64, call_strike: f64) -> Result<Self, RustQuantError> {
+        check_increasing(&[put_strike, call_strike])?;
+
+        Ok(Self::new()
+            .with_underlying(1.0)
+            .with_option(TypeFlag::Put, put_strike, 1.0)
+            .with_option(TypeFlag::Call, call_strike, -1.0))
+    }
+
+    /// Value of the strategy in `market`, whose underlying price,
+    /// volatility, rates, and dates are used for every leg (its strike and
+    /// option type are ignored).
+    #[must_use]
+    pub fn price(&self, market: &BlackScholesMerton) -> f64 {
+        self.legs
+            .iter()
+            .map(|leg| {
+                leg.quantity()
+                    * leg
+                        .model(market)
+                        .map_or(market.underlying_price, |model| model.price())
+            })
+            .sum()
+    }
+
+    /// Greeks of the strategy in `market` (see [`Strategy::price`]).
+    #[must_use]
+    pub fn greeks(&self, market: &BlackScholesMerton) -> Greeks {
+        self.legs
+            .iter()
+            .map(|leg| {
+                let greeks = leg.model(market).map_or(
+                    Greeks {
+                        delta: 1.0,
+                        ..Greeks::default()
+                    },
+                    |model| model.greeks(),
+                );
+
+                greeks * leg.quantity()
+            })
+            .sum()
+    }
+
+    /// Payoff of the strategy at expiry, for the terminal price `S`.
+    #[must_use]
+    pub fn payoff(&self, S: f64) -> f64 {
+        self.legs.iter().map(|leg| leg.payoff(S)).sum()
+    }
+
+    /// Payoffs at expiry over a range of terminal prices.
+    #[must_use]
+    pub fn payoff_profile(&self, prices: &[f64]) -> Vec<f64> {
+        prices.iter().map(|&S| self.payoff(S)).collect()
+    }
+
+    /// Profit and loss at expiry over a range of terminal prices: the payoff
+    /// less the premium paid today in `market` (ignoring its financing).
+    #[must_use]
+    pub fn profit_profile(&self, market: &BlackScholesMerton, prices: &[f64]) -> Vec<f64> {
+        let premium = self.price(market);
+
+        prices.iter().map(|&S| self.payoff(S) - premium).collect()
+    }
+
+    /// Terminal prices at which the profit at expiry is zero, in increasing
+    /// order, for the premium paid today in `market`.
+    ///
+    /// The payoff is linear between strikes, so the breakevens are exact.
+    #[must_use]
+    pub fn breakevens(&self, market: &BlackScholesMerton) -> Vec<f64> {
+        let premium = self.price(market);
+        let profit = |S: f64| self.payoff(S) - premium;
+
+        let mut kinks: Vec<f64> = self
+            .legs
+            .iter()
+            .filter_map(|leg| match *leg {
+                StrategyLeg::Option { strike, .. } => Some(strike),
+                StrategyLeg::Underlying { .. } => None,
+            })
+            .chain(std::iter::once(0.0))
+            .collect();
+        kinks.sort_by(f64::total_cmp);
+        kinks.dedup();
+
+        let mut breakevens = Vec::new();
+
+        for window in kinks.windows(2) {
+            let (a, b) = (window[0], window[1]);
+            let (fa, fb) = (profit(a), profit(b));
+
+            if fa == 0.0 {
+                breakevens.push(a);
+            } else if fa * fb < 0.0 {
+                breakevens.push(a - fa * (b - a) / (fb - fa));
+            }
+        }
+
+        // Beyond the highest strike, the profit is linear with this slope.
+        let last = kinks[kinks.len() - 1];
+        let f = profit(last);
+        let slope = profit(last + 1.0) - f;
+
+        if f == 0.0 {
+            breakevens.push(last);
+        } else if f * slope < 0.0 {
+            breakevens.push(last - f / slope);
+        }
+
+        breakevens
+    }
+}
+
+impl Validate for Strategy {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        self.legs
+            .iter()
+            .fold(Validator::new(), |validator, leg| match *leg {
+                StrategyLeg::Option {
+                    strike, quantity, ..
+                } => validator
+                    .positive("strike", strike)
+                    .finite("quantity", quantity),
+                StrategyLeg::Underlying { quantity } => validator.finite("quantity", quantity),
+            })
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Strikes of a template must be positive and strictly increasing.
+fn check_increasing(strikes: &[f64]) -> Result<(), RustQuantError> {
+    strikes
+        .iter()
+        .fold(Validator::new(), |validator, &strike| {
+            validator.positive("strike", strike)
+        })
+        .check(strikes.windows(2).all(|w| w[0] < w[1]), || {
+            format!("strikes must be strictly increasing (got {strikes:?})")
+        })
+        .finish()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_strategy {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::date;
+
+    const EPS: f64 = 1e-10;
+
+    fn market() -> BlackScholesMerton {
+        BlackScholesMerton::new(
+            0.05,
+            100.0,
+            100.0,
+            0.2,
+            0.05,
+            Some(date!(2024 - 01 - 01)),
+            date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        )
+    }
+
+    fn option(strike: f64, option_type: TypeFlag) -> BlackScholesMerton {
+        BlackScholesMerton {
+            strike_price: strike,
+            option_type,
+            ..market()
+        }
+    }
+
+    #[test]
+    fn test_straddle() {
+        let straddle = Strategy::straddle(100.0);
+        let premium = straddle.price(&market());
+
+        assert_approx_equal!(
+            premium,
+            option(100.0, TypeFlag::Call).price() + option(100.0, TypeFlag::Put).price(),
+            EPS
+        );
+        assert_eq!(
+            straddle.payoff_profile(&[80.0, 100.0, 130.0]),
+            vec![20.0, 0.0, 30.0]
+        );
+
+        let breakevens = straddle.breakevens(&market());
+        assert_eq!(breakevens.len(), 2);
+        assert_approx_equal!(breakevens[0], 100.0 - premium, EPS);
+        assert_approx_equal!(breakevens[1], 100.0 + premium, EPS);
+
+        let greeks = straddle.greeks(&market());
+        assert_approx_equal!(
+            greeks.delta,
+            option(100.0, TypeFlag::Call).delta() + option(100.0, TypeFlag::Put).delta(),
+            EPS
+        );
+        assert_approx_equal!(
+            greeks.gamma,
+            2.0 * option(100.0, TypeFlag::Call).gamma(),
+            EPS
+        );
+    }
+
+    #[test]
+    fn test_butterfly() {
+        let butterfly = Strategy::butterfly(90.0, 100.0, 110.0).unwrap();
+
+        assert_approx_equal!(butterfly.payoff(100.0), 10.0, EPS);
+        assert_approx_equal!(butterfly.payoff(80.0), 0.0, EPS);
+        assert_approx_equal!(butterfly.payoff(120.0), 0.0, EPS);
+
+        // A long butterfly costs less than its maximum payoff.
+        let premium = butterfly.price(&market());
+        assert!(premium > 0.0 && premium < 10.0);
+
+        let breakevens = butterfly.breakevens(&market());
+        assert_eq!(breakevens.len(), 2);
+        assert_approx_equal!(breakevens[0], 90.0 + premium, EPS);
+        assert_approx_equal!(breakevens[1], 110.0 - premium, EPS);
+    }
+
+    #[test]
+    fn test_iron_condor() {
+        let condor = Strategy::iron_condor(80.0, 90.0, 110.0, 120.0).unwrap();
+
+        // Collects a credit, with the loss capped at the wing width.
+        assert!(condor.price(&market()) < 0.0);
+
+        let profit = condor.profit_profile(&market(), &[50.0, 100.0, 150.0]);
+        assert_approx_equal!(profit[0], profit[2], EPS);
+        assert_approx_equal!(profit[1] - profit[0], 10.0, EPS);
+        assert_eq!(condor.breakevens(&market()).len(), 2);
+    }
+
+    #[test]
+    fn test_collar() {
+        let collar = Strategy::collar(90.0, 110.0).unwrap();
+
+        assert_approx_equal!(collar.payoff(50.0), 90.0, EPS);
+        assert_approx_equal!(collar.payoff(100.0), 100.0, EPS);
+        assert_approx_equal!(collar.payoff(200.0), 110.0, EPS);
+
+        let greeks = collar.greeks(&market());
+        assert_approx_equal!(
+            greeks.delta,
+            1.0 + option(90.0, TypeFlag::Put).delta() - option(110.0, TypeFlag::Call).delta(),
+            EPS
+        );
+        assert_eq!(collar.breakevens(&market()).len(), 1);
+    }
+
+    #[test]
+    fn test_strategy_validation() {
+        assert!(Strategy::strangle(110.0, 90.0).is_err());
+        assert!(Strategy::butterfly(90.0, 90.0, 110.0).is_err());
+        assert!(Strategy::iron_condor(-80.0, 90.0, 110.0, 120.0).is_err());
+        assert!(Strategy::straddle(100.0).validate().is_ok());
+        assert!(Strategy::new()
+            .with_option(TypeFlag::Call, 0.0, 1.0)
+            .validate()
+            .is_err());
+    }
+}
@@ -0,0 +1,456 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Swing options on energy and commodities.
+//!
+//! A swing option gives the holder the right to take (or deliver) a volume
+//! of the commodity at the strike on each of a set of exercise dates,
+//! subject to volume constraints: at most a given number of units per date,
+//! and a total volume over the life of the contract between a minimum
+//! (take-or-pay) and a maximum. With one unit per date and no minimum,
+//! `n` rights on `n` dates are a strip of European options, and one right
+//! is a Bermudan option.
+//!
+//! The spot price follows the one-factor Schwartz (1997) model, a
+//! mean-reverting log-price under the pricing measure,
+//!
+//! $$
+//! d \ln S_t = \kappa \left( \ln \theta - \ln S_t \right) dt + \sigma \, dW_t,
+//! $$
+//!
+//! which is discretised on a Hull-White trinomial lattice. The price is
+//! found by dynamic programming over the lattice nodes and the volume
+//! already taken: at each exercise date, the holder picks the volume that
+//! maximises the exercise value plus the continuation value, among those
+//! that still allow the minimum total volume to be reached.
+//!
+//! ```
+//! use RustQuant::instruments::options::*;
+//!
+//! let swing = SwingOption {
+//!     spot: 20.0,
+//!     strike: 20.0,
+//!     risk_free_rate: 0.05,
+//!     volatility: 0.5,
+//!     mean_reversion: 2.0,
+//!     long_run_price: 22.0,
+//!     time_to_expiry: 1.0,
+//!     exercises: 10,
+//!     max_volume_per_date: 1,
+//!     min_total_volume: 0,
+//!     max_total_volume: 3,
+//!     option_type: TypeFlag::Call,
+//! };
+//!
+//! let price = swing.price_lattice(10)?;
+//!
+//! assert!(price > 13.0 && price < 13.3);
+//! # Ok::<(), RustQuant::error::RustQuantError>(())
+//! ```
+
+use crate::error::RustQuantError;
+use crate::instruments::options::TypeFlag;
+use crate::instruments::{Validate, Validator};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Swing option on a mean-reverting commodity, exercisable at equally
+/// spaced dates $t_k = k T / n$, $k = 1, \dots, n$.
+///
+/// Volumes are in whole units, and prices are per unit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwingOption {
+    /// Spot price of the commodity.
+    pub spot: f64,
+    /// Strike price.
+    pub strike: f64,
+    /// Continuously compounded risk-free rate.
+    pub risk_free_rate: f64,
+    /// Volatility of the log-price, $\sigma$.
+    pub volatility: f64,
+    /// Speed of mean reversion of the log-price, $\kappa$ (zero for none).
+    pub mean_reversion: f64,
+    /// Long-run price level, $\theta$, that the log-price reverts to.
+    pub long_run_price: f64,
+    /// Time to expiry, in years.
+    pub time_to_expiry: f64,
+    /// Number of exercise dates, $n$ (the last at expiry).
+    pub exercises: usize,
+    /// Maximum volume per exercise date.
+    pub max_volume_per_date: usize,
+    /// Minimum total volume over all dates.
+    pub min_total_volume: usize,
+    /// Maximum total volume over all dates.
+    pub max_total_volume: usize,
+    /// Right to buy (call) or to sell (put).
+    pub option_type: TypeFlag,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SwingOption {
+    /// Exercise date $t_k$.
+    #[must_use]
+    pub fn exercise_time(&self, k: usize) -> f64 {
+        k as f64 * self.time_to_expiry / self.exercises as f64
+    }
+
+    /// Forward price of the commodity for delivery at `t`.
+    #[must_use]
+    pub fn forward_price(&self, t: f64) -> f64 {
+        let (mean, variance) = self.log_moments(t);
+
+        (mean + 0.5 * variance).exp()
+    }
+
+    /// Price on a trinomial lattice with `steps_per_date` time steps
+    /// between consecutive exercise dates.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the option fails
+    ///   [`Validate::validate`], `steps_per_date` is zero, or the time
+    ///   step is too coarse for the mean reversion ($\kappa \Delta t \geq 1$,
+    ///   which would give negative branching probabilities).
+    pub fn price_lattice(&self, steps_per_date: usize) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .check(steps_per_date >= 1, || {
+                "at least one step per date is needed".to_string()
+            })
+            .finish()?;
+
+        let n_steps = self.exercises * steps_per_date;
+        let dt = self.time_to_expiry / n_steps as f64;
+
+        Validator::new()
+            .check(self.mean_reversion * dt < 1.0, || {
+                format!("the time step is too coarse (got {steps_per_date} steps per date)")
+            })
+            .finish()?;
+
+        // Hull-White lattice for the deviation of the log-price from its
+        // mean, y = ln S - E[ln S], with dy = -kappa y dt + sigma dW.
+        let m = (-self.mean_reversion * dt).exp() - 1.0;
+        let dx = (3.0 * self.log_moments(dt).1).sqrt();
+        let j_max = match m < 0.0 {
+            true => ((-0.184 / m).ceil() as usize).clamp(1, n_steps),
+            false => n_steps,
+        };
+        let width = |i: usize| i.min(j_max) as isize;
+
+        let z = match self.option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+        let payoffs = |i: usize| -> Vec<f64> {
+            let mean = self.log_moments(i as f64 * dt).0;
+
+            (-width(i)..=width(i))
+                .map(|j| z * ((mean + j as f64 * dx).exp() - self.strike))
+                .collect()
+        };
+
+        let discount = (-self.risk_free_rate * dt).exp();
+        let units = self.max_total_volume;
+
+        // values[node][u]: value with u units already taken.
+        let last = payoffs(n_steps);
+        let mut values = self.exercise(
+            self.exercises,
+            &last,
+            &vec![vec![0.0; units + 1]; last.len()],
+        );
+
+        for i in (0..n_steps).rev() {
+            let next = width(i + 1);
+
+            let continuation: Vec<Vec<f64>> = (-width(i)..=width(i))
+                .map(|j| {
+                    let (targets, probabilities) = branches(j, j_max as isize, m);
+
+                    (0..=units)
+                        .map(|u| {
+                            discount
+                                * targets
+                                    .iter()
+                                    .zip(probabilities)
+                                    .map(|(target, p)| p * values[(target + next) as usize][u])
+                                    .sum::<f64>()
+                        })
+                        .collect()
+                })
+                .collect();
+
+            values = match i > 0 && i % steps_per_date == 0 {
+                true => self.exercise(i / steps_per_date, &payoffs(i), &continuation),
+                false => continuation,
+            };
+        }
+
+        Ok(values[0][0])
+    }
+
+    // Mean and variance of ln S_t.
+    fn log_moments(&self, t: f64) -> (f64, f64) {
+        let kappa = self.mean_reversion;
+        let decay = (-kappa * t).exp();
+
+        let mean = self.spot.ln() * decay + self.long_run_price.ln() * (1.0 - decay);
+        let variance = self.volatility.powi(2)
+            * match kappa > 0.0 {
+                true => (1.0 - decay * decay) / (2.0 * kappa),
+                false => t,
+            };
+
+        (mean, variance)
+    }
+
+    // Values at the exercise date `date` (1-based) for every node and
+    // volume already taken, from the per-unit payoffs at the nodes and the
+    // continuation values (indexed by the volume taken after exercising).
+    // States from which the minimum total volume cannot be reached are
+    // worth minus infinity; they are never entered.
+    fn exercise(&self, date: usize, payoffs: &[f64], continuation: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let units = self.max_total_volume;
+        let remaining = (self.exercises - date) * self.max_volume_per_date;
+
+        payoffs
+            .iter()
+            .zip(continuation)
+            .map(|(payoff, continuation)| {
+                (0..=units)
+                    .map(|used| {
+                        (0..=self.max_volume_per_date.min(units - used))
+                            .filter(|q| used + q + remaining >= self.min_total_volume)
+                            .map(|q| q as f64 * payoff + continuation[used + q])
+                            .fold(f64::NEG_INFINITY, f64::max)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Validate for SwingOption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("spot", self.spot)
+            .positive("strike", self.strike)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .positive("volatility", self.volatility)
+            .non_negative("mean_reversion", self.mean_reversion)
+            .positive("long_run_price", self.long_run_price)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .check(self.exercises >= 1, || {
+                "at least one exercise date is needed".to_string()
+            })
+            .check(self.max_volume_per_date >= 1, || {
+                "max_volume_per_date must be positive".to_string()
+            })
+            .check(self.min_total_volume <= self.max_total_volume, || {
+                format!(
+                    "min_total_volume must not exceed max_total_volume (got {} and {})",
+                    self.min_total_volume, self.max_total_volume
+                )
+            })
+            .check(
+                self.min_total_volume <= self.exercises * self.max_volume_per_date,
+                || "min_total_volume cannot be reached".to_string(),
+            )
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Target nodes and probabilities of the Hull-White trinomial branching from
+// node j, with M = exp(-kappa dt) - 1. The branching turns inwards at the
+// edges of the lattice, |j| = j_max, to keep the probabilities positive.
+fn branches(j: isize, j_max: isize, m: f64) -> ([isize; 3], [f64; 3]) {
+    let jm = j as f64 * m;
+    let a = jm * jm;
+
+    match j {
+        j if j == j_max => (
+            [j - 2, j - 1, j],
+            [
+                1.0 / 6.0 + (a + jm) / 2.0,
+                -1.0 / 3.0 - a - 2.0 * jm,
+                7.0 / 6.0 + (a + 3.0 * jm) / 2.0,
+            ],
+        ),
+        j if j == -j_max => (
+            [j, j + 1, j + 2],
+            [
+                7.0 / 6.0 + (a - 3.0 * jm) / 2.0,
+                -1.0 / 3.0 - a + 2.0 * jm,
+                1.0 / 6.0 + (a - jm) / 2.0,
+            ],
+        ),
+        j => (
+            [j - 1, j, j + 1],
+            [
+                1.0 / 6.0 + (a - jm) / 2.0,
+                2.0 / 3.0 - a,
+                1.0 / 6.0 + (a + jm) / 2.0,
+            ],
+        ),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_swing {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::generalised_black_scholes_merton;
+
+    const SWING: SwingOption = SwingOption {
+        spot: 20.0,
+        strike: 20.0,
+        risk_free_rate: 0.05,
+        volatility: 0.5,
+        mean_reversion: 2.0,
+        long_run_price: 22.0,
+        time_to_expiry: 1.0,
+        exercises: 10,
+        max_volume_per_date: 1,
+        min_total_volume: 0,
+        max_total_volume: 10,
+        option_type: TypeFlag::Call,
+    };
+
+    // Black (1976) price of the European option exercisable at t_k.
+    fn european(swing: &SwingOption, k: usize) -> f64 {
+        let t = swing.exercise_time(k);
+        let volatility = (swing.log_moments(t).1 / t).sqrt();
+
+        generalised_black_scholes_merton(
+            swing.forward_price(t),
+            swing.strike,
+            volatility,
+            swing.risk_free_rate,
+            0.0,
+            t,
+            swing.option_type,
+        )
+    }
+
+    #[test]
+    fn test_full_rights_are_a_strip_of_europeans() {
+        let strip: f64 = (1..=10).map(|k| european(&SWING, k)).sum();
+
+        assert_approx_equal!(SWING.price_lattice(20).unwrap(), strip, 0.01);
+
+        let puts = SwingOption {
+            option_type: TypeFlag::Put,
+            ..SWING
+        };
+        let strip: f64 = (1..=10).map(|k| european(&puts, k)).sum();
+
+        assert_approx_equal!(puts.price_lattice(20).unwrap(), strip, 0.01);
+    }
+
+    #[test]
+    fn test_take_or_pay_is_a_strip_of_forwards() {
+        // The full volume must be taken, at a loss on some dates.
+        for mean_reversion in [2.0, 0.0] {
+            let forced = SwingOption {
+                min_total_volume: 10,
+                mean_reversion,
+                ..SWING
+            };
+            let forwards: f64 = (1..=10)
+                .map(|k| {
+                    let t = forced.exercise_time(k);
+                    (-0.05 * t).exp() * (forced.forward_price(t) - 20.0)
+                })
+                .sum();
+
+            assert_approx_equal!(forced.price_lattice(20).unwrap(), forwards, 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_volume_constraints() {
+        let rights = |max_total_volume: usize| {
+            SwingOption {
+                max_total_volume,
+                ..SWING
+            }
+            .price_lattice(20)
+            .unwrap()
+        };
+        let (one, three, all) = (rights(1), rights(3), rights(10));
+
+        // Independent implementation of the same lattice.
+        assert_approx_equal!(one, 4.910_511_033, 1e-6);
+        assert_approx_equal!(three, 13.163_460_350, 1e-6);
+
+        // A single right is worth more than any of the Europeans.
+        assert!((1..=10).all(|k| one > european(&SWING, k)));
+        assert!(one < three && three < all);
+
+        // Two units per date, between four and six in total.
+        let swing = SwingOption {
+            max_volume_per_date: 2,
+            min_total_volume: 4,
+            max_total_volume: 6,
+            ..SWING
+        };
+        assert_approx_equal!(swing.price_lattice(20).unwrap(), 23.563_234_989, 1e-6);
+    }
+
+    #[test]
+    fn test_swing_validation() {
+        let invalid = [
+            SwingOption {
+                mean_reversion: -1.0,
+                ..SWING
+            },
+            SwingOption {
+                max_volume_per_date: 0,
+                ..SWING
+            },
+            SwingOption {
+                min_total_volume: 11,
+                max_total_volume: 12,
+                ..SWING
+            },
+            SwingOption {
+                min_total_volume: 3,
+                max_total_volume: 2,
+                ..SWING
+            },
+        ];
+
+        for swing in invalid {
+            assert!(swing.price_lattice(10).is_err());
+        }
+
+        assert!(SWING.price_lattice(0).is_err());
+
+        // kappa dt = 1.
+        let fast = SwingOption {
+            mean_reversion: 10.0,
+            ..SWING
+        };
+        assert!(fast.price_lattice(1).is_err());
+    }
+}
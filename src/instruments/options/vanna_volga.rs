@@ -0,0 +1,481 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Vanna-volga pricing of first-generation FX exotics.
+//!
+//! The vanna-volga method (Castagna and Mercurio, 2007) prices an option
+//! off the three most liquid smile quotes: the at-the-money volatility and
+//! the 25-delta risk reversal and butterfly. These give three pillar
+//! vanillas (the 25-delta put, the ATM option and the 25-delta call), and
+//! the exotic is priced as its Black-Scholes value at the ATM volatility,
+//! plus the smile cost of the pillar portfolio that hedges its vega, vanna
+//! and volga:
+//!
+//! ```text
+//! V = V_BS(v_atm) + p * sum_i x_i (C_i(v_i) - C_i(v_atm))
+//! ```
+//!
+//! where the weights `x_i` match the exotic's vega, vanna and volga, and
+//! `p` scales the adjustment down for barrier options (by the probability
+//! the barrier is not touched), as is market practice.
+//!
+//! Vanna-volga is a pragmatic smile adjustment rather than a model: it is
+//! consistent with the pillar quotes, but has no dynamics of its own.
+
+use super::{
+    generalised_black_scholes_merton, smile_from_risk_reversal, AtmConvention, BarrierOption,
+    BarrierType, SmileConventions, StrikeVolQuote, TypeFlag,
+};
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+use crate::math::Real;
+use nalgebra::{Matrix3, Vector3};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market smile of one expiry, from ATM, 25-delta risk reversal and
+/// 25-delta butterfly quotes.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VannaVolgaSmile {
+    /// Market data and delta convention of the quotes.
+    pub market: SmileConventions,
+    /// At-the-money volatility.
+    pub atm_volatility: f64,
+    /// At-the-money convention.
+    pub atm_convention: AtmConvention,
+    /// 25-delta risk reversal (call minus put volatility).
+    pub risk_reversal: f64,
+    /// 25-delta (smile) butterfly.
+    pub butterfly: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl VannaVolgaSmile {
+    /// Bump sizes for the finite difference Greeks, relative to spot for
+    /// the spot bump.
+    const SPOT_BUMP: f64 = 1e-3;
+    const VOL_BUMP: f64 = 1e-3;
+
+    /// The three pillars: the 25-delta put, ATM, and 25-delta call strikes
+    /// and volatilities.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if a 25-delta strike does not exist.
+    pub fn pillars(&self) -> Result<[StrikeVolQuote; 3], RustQuantError> {
+        self.validate()?;
+
+        let (call_vol, put_vol) =
+            smile_from_risk_reversal(self.atm_volatility, self.risk_reversal, self.butterfly);
+
+        Ok([
+            StrikeVolQuote {
+                strike: self
+                    .market
+                    .strike_from_delta(-0.25, put_vol, TypeFlag::Put)?,
+                volatility: put_vol,
+            },
+            StrikeVolQuote {
+                strike: self
+                    .market
+                    .atm_strike(self.atm_volatility, self.atm_convention)?,
+                volatility: self.atm_volatility,
+            },
+            StrikeVolQuote {
+                strike: self
+                    .market
+                    .strike_from_delta(0.25, call_vol, TypeFlag::Call)?,
+                volatility: call_vol,
+            },
+        ])
+    }
+
+    /// First-order vanna-volga implied volatility at the given strike
+    /// (Castagna and Mercurio, 2007), which interpolates the pillars.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the pillars fail, or if the
+    ///   strike is not positive.
+    pub fn volatility(&self, strike: f64) -> Result<f64, RustQuantError> {
+        let [p1, p2, p3] = self.pillars()?;
+        Validator::new().positive("strike", strike).finish()?;
+
+        let (K1, K2, K3) = (p1.strike, p2.strike, p3.strike);
+        let K = strike;
+
+        let y1 = (K2 / K).ln() * (K3 / K).ln() / ((K2 / K1).ln() * (K3 / K1).ln());
+        let y2 = (K / K1).ln() * (K3 / K).ln() / ((K2 / K1).ln() * (K3 / K2).ln());
+        let y3 = (K / K1).ln() * (K / K2).ln() / ((K3 / K1).ln() * (K3 / K2).ln());
+
+        Ok(y1 * p1.volatility + y2 * p2.volatility + y3 * p3.volatility)
+    }
+
+    /// Vanna-volga price of an option whose Black-Scholes value, as a
+    /// function of `(spot, volatility)`, is `black_scholes`.
+    ///
+    /// The adjustment is scaled by `weight`: one for European payoffs, and
+    /// the no-touch probability for barrier options.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the pillars fail.
+    /// * [`RustQuantError::ComputationError`] if the pillar Greeks are
+    ///   singular, or the price is not finite (e.g. a spot bump crosses a
+    ///   barrier).
+    pub fn price<F>(&self, black_scholes: F, weight: f64) -> Result<f64, RustQuantError>
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        let pillars = self.pillars()?;
+        let v_atm = self.atm_volatility;
+
+        let pillar_value = |strike: f64| {
+            move |spot: f64, volatility: f64| self.vanilla(spot, strike, volatility, TypeFlag::Call)
+        };
+
+        let mut greeks = Matrix3::zeros();
+        let mut smile_cost = Vector3::zeros();
+
+        for (i, pillar) in pillars.iter().enumerate() {
+            let value = pillar_value(pillar.strike);
+
+            greeks.set_column(i, &self.greeks(&value));
+            smile_cost[i] =
+                value(self.market.spot, pillar.volatility) - value(self.market.spot, v_atm);
+        }
+
+        let weights = greeks
+            .lu()
+            .solve(&self.greeks(&black_scholes))
+            .ok_or_else(|| {
+                RustQuantError::ComputationError(
+                    "Vanna-volga pillar Greeks are singular.".to_string(),
+                )
+            })?;
+
+        let price = black_scholes(self.market.spot, v_atm) + weight * weights.dot(&smile_cost);
+
+        match price.is_finite() {
+            true => Ok(price),
+            false => Err(RustQuantError::ComputationError(format!(
+                "Vanna-volga price is not finite (got {price})."
+            ))),
+        }
+    }
+
+    /// Vanna-volga price of a vanilla option.
+    ///
+    /// # Errors:
+    /// * See [`VannaVolgaSmile::price`].
+    pub fn price_vanilla(&self, strike: f64, option_type: TypeFlag) -> Result<f64, RustQuantError> {
+        Validator::new().positive("strike", strike).finish()?;
+
+        self.price(|S, v| self.vanilla(S, strike, v, option_type), 1.0)
+    }
+
+    /// Vanna-volga price of a cash-or-nothing digital paying `payout`.
+    ///
+    /// # Errors:
+    /// * See [`VannaVolgaSmile::price`].
+    pub fn price_digital(
+        &self,
+        strike: f64,
+        payout: f64,
+        option_type: TypeFlag,
+    ) -> Result<f64, RustQuantError> {
+        Validator::new()
+            .positive("strike", strike)
+            .finite("payout", payout)
+            .finish()?;
+
+        let market = self.market;
+        let T = market.time_to_expiry;
+        let phi = match option_type {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        };
+
+        let digital = |S: f64, v: f64| {
+            let b = market.domestic_rate - market.foreign_rate;
+            let d2 = ((S / strike).ln() + (b - 0.5 * v * v) * T) / (v * T.sqrt());
+
+            payout * (-market.domestic_rate * T).exp() * (phi * d2).norm_cdf()
+        };
+
+        self.price(digital, 1.0)
+    }
+
+    /// Vanna-volga price of a barrier option.
+    ///
+    /// The option's spot, rates and volatility are taken from the smile.
+    /// Knock-out adjustments are scaled by the no-touch probability, and
+    /// knock-ins are priced by in-out parity with the vanilla.
+    ///
+    /// # Errors:
+    /// * See [`VannaVolgaSmile::price`], and [`BarrierOption::price`] if the
+    ///   barrier has already been touched.
+    pub fn price_barrier(
+        &self,
+        option: &BarrierOption,
+        barrier_type: BarrierType,
+    ) -> Result<f64, RustQuantError> {
+        let market = self.market;
+        let option = BarrierOption {
+            initial_price: market.spot,
+            time_to_expiry: market.time_to_expiry,
+            risk_free_rate: market.domestic_rate,
+            dividend_yield: market.foreign_rate,
+            volatility: self.atm_volatility,
+            ..*option
+        };
+        option.price(barrier_type)?;
+
+        let knock_out = match barrier_type {
+            BarrierType::CUI => BarrierType::CUO,
+            BarrierType::CDI => BarrierType::CDO,
+            BarrierType::PUI => BarrierType::PUO,
+            BarrierType::PDI => BarrierType::PDO,
+            knock_out => knock_out,
+        };
+
+        let knock_out_value = |S: f64, v: f64| {
+            BarrierOption {
+                initial_price: S,
+                volatility: v,
+                ..option
+            }
+            .price_unchecked(knock_out)
+        };
+
+        let no_touch = self.no_touch_probability(option.barrier, barrier_type.is_up());
+        let price = self.price(knock_out_value, no_touch)?;
+
+        if !barrier_type.is_knock_in() {
+            return Ok(price);
+        }
+
+        // In-out parity, with the rebate on knock-in paid at expiry.
+        let option_type = match barrier_type.is_call() {
+            true => TypeFlag::Call,
+            false => TypeFlag::Put,
+        };
+        let vanilla = self.price_vanilla(option.strike_price, option_type)?;
+
+        Ok(vanilla - price)
+    }
+
+    fn vanilla(&self, spot: f64, strike: f64, volatility: f64, option_type: TypeFlag) -> f64 {
+        let market = &self.market;
+
+        generalised_black_scholes_merton(
+            spot,
+            strike,
+            volatility,
+            market.domestic_rate,
+            market.domestic_rate - market.foreign_rate,
+            market.time_to_expiry,
+            option_type,
+        )
+    }
+
+    /// Vega, vanna and volga at the ATM volatility, by finite differences.
+    fn greeks<F: Fn(f64, f64) -> f64>(&self, value: &F) -> Vector3<f64> {
+        let S = self.market.spot;
+        let v = self.atm_volatility;
+        let (h, k) = (Self::SPOT_BUMP * S, Self::VOL_BUMP);
+
+        let vega = (value(S, v + k) - value(S, v - k)) / (2.0 * k);
+        let vanna = (value(S + h, v + k) - value(S + h, v - k) - value(S - h, v + k)
+            + value(S - h, v - k))
+            / (4.0 * h * k);
+        let volga = (value(S, v + k) - 2.0 * value(S, v) + value(S, v - k)) / (k * k);
+
+        Vector3::new(vega, vanna, volga)
+    }
+
+    /// Risk-neutral probability of not touching the barrier before expiry,
+    /// at the ATM volatility.
+    fn no_touch_probability(&self, barrier: f64, is_up: bool) -> f64 {
+        let market = &self.market;
+        let v = self.atm_volatility;
+        let T = market.time_to_expiry;
+        let sd = v * T.sqrt();
+        let mu = market.domestic_rate - market.foreign_rate - 0.5 * v * v;
+
+        // Log-distance to the barrier, positive when not yet touched.
+        let x = match is_up {
+            true => (barrier / market.spot).ln(),
+            false => (market.spot / barrier).ln(),
+        };
+        let m = match is_up {
+            true => mu,
+            false => -mu,
+        };
+
+        ((x - m * T) / sd).norm_cdf()
+            - (2.0 * m * x / (v * v)).exp() * ((-x - m * T) / sd).norm_cdf()
+    }
+}
+
+impl Validate for VannaVolgaSmile {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        self.market.validate()?;
+
+        let (call_vol, put_vol) =
+            smile_from_risk_reversal(self.atm_volatility, self.risk_reversal, self.butterfly);
+
+        Validator::new()
+            .positive("atm_volatility", self.atm_volatility)
+            .finite("risk_reversal", self.risk_reversal)
+            .finite("butterfly", self.butterfly)
+            .check(call_vol > 0.0 && put_vol > 0.0, || {
+                format!("25-delta volatilities must be positive (got {call_vol} and {put_vol})")
+            })
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_vanna_volga {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::DeltaConvention;
+
+    const SMILE: VannaVolgaSmile = VannaVolgaSmile {
+        market: SmileConventions {
+            spot: 1.35,
+            time_to_expiry: 1.0,
+            domestic_rate: 0.03,
+            foreign_rate: 0.025,
+            delta_convention: DeltaConvention::Spot,
+        },
+        atm_volatility: 0.10,
+        atm_convention: AtmConvention::DeltaNeutralStraddle,
+        risk_reversal: -0.01,
+        butterfly: 0.003,
+    };
+
+    const FLAT: VannaVolgaSmile = VannaVolgaSmile {
+        risk_reversal: 0.0,
+        butterfly: 0.0,
+        ..SMILE
+    };
+
+    const BARRIER: BarrierOption = BarrierOption {
+        initial_price: 1.35,
+        strike_price: 1.35,
+        barrier: 1.5,
+        time_to_expiry: 1.0,
+        risk_free_rate: 0.03,
+        volatility: 0.1,
+        rebate: 0.0,
+        dividend_yield: 0.025,
+    };
+
+    #[test]
+    fn test_reprices_pillars() {
+        for pillar in SMILE.pillars().unwrap() {
+            assert_approx_equal!(
+                SMILE.price_vanilla(pillar.strike, TypeFlag::Call).unwrap(),
+                SMILE.vanilla(1.35, pillar.strike, pillar.volatility, TypeFlag::Call),
+                1e-10
+            );
+            assert_approx_equal!(
+                SMILE.volatility(pillar.strike).unwrap(),
+                pillar.volatility,
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_smile_is_black_scholes() {
+        let strike = 1.4;
+        let digital = FLAT.price_digital(strike, 1.0, TypeFlag::Call).unwrap();
+        // The carry b = 0.005 cancels v^2 / 2, so d2 = ln(S / K) / v.
+        let d2 = (1.35_f64 / strike).ln() / 0.1;
+
+        assert_approx_equal!(digital, (-0.03_f64).exp() * d2.norm_cdf(), 1e-10);
+        assert_approx_equal!(
+            FLAT.price_barrier(&BARRIER, BarrierType::CUO).unwrap(),
+            BARRIER.price(BarrierType::CUO).unwrap(),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_digital_is_call_spread() {
+        // The adjustment is linear in the payoff, so the digital is the
+        // limit of a vanna-volga call spread.
+        let (strike, eps) = (1.4, 1e-4);
+        let digital = SMILE.price_digital(strike, 1.0, TypeFlag::Call).unwrap();
+        let spread = (SMILE.price_vanilla(strike - eps, TypeFlag::Call).unwrap()
+            - SMILE.price_vanilla(strike + eps, TypeFlag::Call).unwrap())
+            / (2.0 * eps);
+
+        assert_approx_equal!(digital, spread, 1e-6);
+
+        // With a negative risk reversal, the smile slopes down through the
+        // strike, which makes the digital call dearer than without skew.
+        let flat = FLAT.price_digital(strike, 1.0, TypeFlag::Call).unwrap();
+        assert!(digital > flat);
+    }
+
+    #[test]
+    fn test_barrier_in_out_parity() {
+        let knock_in = SMILE.price_barrier(&BARRIER, BarrierType::CUI).unwrap();
+        let knock_out = SMILE.price_barrier(&BARRIER, BarrierType::CUO).unwrap();
+        let vanilla = SMILE.price_vanilla(1.35, TypeFlag::Call).unwrap();
+
+        assert_approx_equal!(knock_in + knock_out, vanilla, 1e-12);
+        assert!(knock_out > 0.0 && knock_in > 0.0);
+
+        // The knock-out adjustment is damped by the no-touch probability.
+        let undamped = SMILE
+            .price(
+                |S, v| {
+                    BarrierOption {
+                        initial_price: S,
+                        volatility: v,
+                        ..BARRIER
+                    }
+                    .price_unchecked(BarrierType::CUO)
+                },
+                1.0,
+            )
+            .unwrap();
+        let bs = BARRIER.price(BarrierType::CUO).unwrap();
+        assert!((knock_out - bs).abs() < (undamped - bs).abs());
+    }
+
+    #[test]
+    fn test_vanna_volga_validation() {
+        let smile = VannaVolgaSmile {
+            butterfly: -0.2,
+            ..SMILE
+        };
+        assert!(smile.pillars().is_err());
+
+        let touched = BarrierOption {
+            barrier: 1.3,
+            ..BARRIER
+        };
+        assert!(SMILE.price_barrier(&touched, BarrierType::CUO).is_err());
+        assert!(SMILE.volatility(-1.0).is_err());
+    }
+}
@@ -0,0 +1,565 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Forward variance curves, and VIX futures and options.
+//!
+//! The forward variance `xi_t(u) = E_t[V_u]` is the fair instantaneous
+//! variance at a future date `u`; it integrates to the variance swap
+//! strikes, `K_var(T)^2 T = int_0^T xi_0(u) du`. The VIX is the square root
+//! of the 30-day variance swap rate:
+//!
+//! ```text
+//! VIX_T^2 = (1 / Delta) int_T^(T + Delta) xi_T(u) du,    Delta = 30 / 365,
+//! ```
+//!
+//! so a VIX future pays `E[VIX_T]`, which Jensen's inequality keeps below
+//! the square root of the forward 30-day variance. Its distribution, and
+//! so the VIX futures and options prices, are simulated here under:
+//!
+//! * Heston, where `VIX_T^2` is affine in `V_T`, and `V_T` (a scaled
+//!   non-central chi-squared variable) is sampled exactly;
+//! * rough Bergomi, where `xi_T(u)` is lognormal with a log-variance driven
+//!   by `int_0^T (u - s)^(H - 1/2) dW_s`, sampled on a time grid, and
+//!   started from a [`ForwardVarianceCurve`].
+
+use super::{HestonParameters, RoughBergomi, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::{Price, Validate, Validator};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, Gamma, Poisson, StandardNormal};
+
+/// Horizon of the VIX: 30 calendar days.
+pub const VIX_HORIZON: f64 = 30.0 / 365.0;
+
+// Quadrature nodes over the VIX horizon.
+const VIX_NODES: usize = 16;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Piecewise-flat forward variance curve.
+///
+/// The forward variance is `variances[i]` on `(times[i - 1], times[i]]`
+/// (from 0 for the first pillar), and flat beyond the last pillar.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForwardVarianceCurve {
+    /// Pillar times, increasing.
+    pub times: Vec<f64>,
+    /// Forward variance up to each pillar.
+    pub variances: Vec<f64>,
+}
+
+/// Simulated distribution of the VIX at a future date.
+#[derive(Debug, Clone)]
+pub struct VixDistribution {
+    /// Time to the VIX fixing (in years).
+    pub time_to_expiry: f64,
+    /// Simulated VIX values (as volatilities, e.g. 0.2 for a VIX of 20).
+    pub samples: Vec<f64>,
+}
+
+/// Monte Carlo settings for simulating the VIX.
+#[derive(Debug, Clone, Copy)]
+pub struct VixMonteCarlo {
+    /// Number of simulated paths.
+    pub n_paths: usize,
+    /// Number of time steps to the fixing (rough Bergomi only).
+    pub n_steps: usize,
+    /// Seed for the random number generator.
+    pub seed: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ForwardVarianceCurve {
+    /// New curve from its pillars.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the curve fails
+    ///   [`Validate::validate`].
+    pub fn new(times: Vec<f64>, variances: Vec<f64>) -> Result<Self, RustQuantError> {
+        let curve = Self { times, variances };
+        curve.validate()?;
+
+        Ok(curve)
+    }
+
+    /// Flat curve.
+    #[must_use]
+    pub fn flat(variance: f64) -> Self {
+        Self {
+            times: vec![1.0],
+            variances: vec![variance],
+        }
+    }
+
+    /// Bootstrap the curve from variance swap strikes (as volatilities) at
+    /// increasing maturities.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the inputs are empty, of
+    ///   different lengths, or the maturities not positive and increasing.
+    /// * [`RustQuantError::ArbitrageViolation`] if the total variance
+    ///   `K_var^2 T` decreases with the maturity.
+    pub fn from_variance_swaps(
+        maturities: &[f64],
+        strikes: &[f64],
+    ) -> Result<Self, RustQuantError> {
+        Validator::new()
+            .check(maturities.len() == strikes.len(), || {
+                "maturities and strikes must have the same length".to_string()
+            })
+            .finish()?;
+
+        Self {
+            times: maturities.to_vec(),
+            variances: vec![0.0; maturities.len()],
+        }
+        .validate()?;
+
+        let mut previous = (0.0, 0.0);
+        let variances = maturities
+            .iter()
+            .zip(strikes)
+            .map(|(&T, &K)| {
+                let total = K * K * T;
+                let forward = (total - previous.1) / (T - previous.0);
+
+                if forward < 0.0 {
+                    return Err(RustQuantError::ArbitrageViolation(format!(
+                        "Total variance decreases at maturity {T}."
+                    )));
+                }
+
+                previous = (T, total);
+                Ok(forward)
+            })
+            .collect::<Result<Vec<f64>, RustQuantError>>()?;
+
+        Self::new(maturities.to_vec(), variances)
+    }
+
+    /// Forward variance `xi_0(t)`.
+    #[must_use]
+    pub fn forward_variance(&self, t: f64) -> f64 {
+        let i = self.times.partition_point(|&pillar| pillar < t);
+
+        self.variances[i.min(self.variances.len() - 1)]
+    }
+
+    /// Total variance `int_0^T xi_0(u) du`.
+    #[must_use]
+    pub fn total_variance(&self, T: f64) -> f64 {
+        let mut total = 0.0;
+        let mut start = 0.0;
+
+        for (i, &variance) in self.variances.iter().enumerate() {
+            let end = match i + 1 == self.variances.len() {
+                true => f64::INFINITY,
+                false => self.times[i],
+            };
+
+            total += variance * (end.min(T) - start).max(0.0);
+            start = end;
+        }
+
+        total
+    }
+
+    /// Fair variance swap strike (as a volatility) to `T`.
+    #[must_use]
+    pub fn variance_swap_strike(&self, T: f64) -> f64 {
+        (self.total_variance(T) / T).sqrt()
+    }
+
+    /// Square of the VIX implied by the curve at time `t`:
+    /// `(1 / Delta) int_t^(t + Delta) xi_0(u) du`. At `t = 0` it is the
+    /// spot VIX squared, and later the fair strike of a VIX-squared future.
+    #[must_use]
+    pub fn forward_vix_squared(&self, t: f64) -> f64 {
+        (self.total_variance(t + VIX_HORIZON) - self.total_variance(t)) / VIX_HORIZON
+    }
+}
+
+impl Validate for ForwardVarianceCurve {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .check(!self.times.is_empty(), || {
+                "at least one pillar is needed".to_string()
+            })
+            .check(self.times.len() == self.variances.len(), || {
+                "times and variances must have the same length".to_string()
+            })
+            .check(self.times.iter().all(|&t| t > 0.0), || {
+                "pillar times must be positive".to_string()
+            })
+            .check(self.times.windows(2).all(|w| w[0] < w[1]), || {
+                "pillar times must be increasing".to_string()
+            })
+            .check(
+                self.variances.iter().all(|v| v.is_finite() && *v >= 0.0),
+                || "forward variances must be finite and non-negative".to_string(),
+            )
+            .finish()
+    }
+}
+
+impl VixDistribution {
+    /// VIX future price (the expected VIX), with its standard error.
+    #[must_use]
+    pub fn future(&self) -> Price {
+        estimate(self.samples.iter().copied())
+    }
+
+    /// Price of a VIX option struck at `strike` (as a volatility), with
+    /// its standard error.
+    #[must_use]
+    pub fn option(&self, strike: f64, risk_free_rate: f64, option_type: TypeFlag) -> Price {
+        let discount = (-risk_free_rate * self.time_to_expiry).exp();
+
+        estimate(self.samples.iter().map(|&vix| {
+            discount
+                * match option_type {
+                    TypeFlag::Call => (vix - strike).max(0.0),
+                    TypeFlag::Put => (strike - vix).max(0.0),
+                }
+        }))
+    }
+
+    /// Expected VIX squared: the fair strike of a VIX-squared future.
+    #[must_use]
+    pub fn variance_future(&self) -> Price {
+        estimate(self.samples.iter().map(|vix| vix * vix))
+    }
+}
+
+impl Default for VixMonteCarlo {
+    fn default() -> Self {
+        Self {
+            n_paths: 20_000,
+            n_steps: 50,
+            seed: 42,
+        }
+    }
+}
+
+impl VixMonteCarlo {
+    /// VIX at `T` under Heston, from exact samples of the variance.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], `T` is negative, or there are fewer than
+    ///   two paths.
+    pub fn heston(
+        &self,
+        parameters: &HestonParameters,
+        T: f64,
+    ) -> Result<VixDistribution, RustQuantError> {
+        parameters.validate()?;
+        self.check(T)?;
+
+        let HestonParameters {
+            initial_variance: v0,
+            long_run_variance: theta,
+            mean_reversion_rate: kappa,
+            volatility_of_volatility: sigma,
+            ..
+        } = *parameters;
+
+        // VIX_T^2 = a V_T + (1 - a) theta.
+        let a = (1.0 - (-kappa * VIX_HORIZON).exp()) / (kappa * VIX_HORIZON);
+        let vix = |v: f64| (a * v + (1.0 - a) * theta).sqrt();
+
+        if T == 0.0 {
+            return Ok(VixDistribution {
+                time_to_expiry: T,
+                samples: vec![vix(v0); self.n_paths],
+            });
+        }
+
+        // V_T = c X, with X non-central chi-squared with `degrees` degrees
+        // of freedom and non-centrality `centrality`: a chi-squared variable
+        // with a Poisson number of extra degrees of freedom.
+        let decay = (-kappa * T).exp();
+        let c = sigma * sigma * (1.0 - decay) / (4.0 * kappa);
+        let degrees = 4.0 * kappa * theta / (sigma * sigma);
+        let centrality = v0 * decay / c;
+
+        let poisson = Poisson::new(0.5 * centrality).map_err(|error| {
+            RustQuantError::InvalidArgument(format!("Invalid Heston parameters: {error}"))
+        })?;
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let samples = (0..self.n_paths)
+            .map(|_| {
+                let n: f64 = poisson.sample(&mut rng);
+                let chi_squared = Gamma::new(0.5 * degrees + n, 2.0)
+                    .map_err(|error| RustQuantError::ComputationError(error.to_string()))?
+                    .sample(&mut rng);
+
+                Ok(vix(c * chi_squared))
+            })
+            .collect::<Result<Vec<f64>, RustQuantError>>()?;
+
+        Ok(VixDistribution {
+            time_to_expiry: T,
+            samples,
+        })
+    }
+
+    /// VIX at `T` under rough Bergomi, started from `curve`, which replaces
+    /// the model's flat forward variance.
+    ///
+    /// The forward variances on the VIX horizon are
+    ///
+    /// ```text
+    /// xi_T(u) = xi_0(u) exp(eta G(u) - eta^2 Var[G(u)] / 2),
+    /// G(u)    = sqrt(2H) int_0^T (u - s)^(H - 1/2) dW_s,
+    /// ```
+    ///
+    /// with `G(u)` projected on the Brownian increments of `n_steps` steps,
+    /// which keeps `E[xi_T(u)] = xi_0(u)` exactly.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the model or the curve fail
+    ///   [`Validate::validate`], `T` is negative, or there are fewer than
+    ///   two paths or no steps.
+    pub fn rough_bergomi(
+        &self,
+        model: &RoughBergomi,
+        curve: &ForwardVarianceCurve,
+        T: f64,
+    ) -> Result<VixDistribution, RustQuantError> {
+        model.validate()?;
+        curve.validate()?;
+        self.check(T)?;
+        Validator::new()
+            .check(self.n_steps > 0, || {
+                "at least one step is needed".to_string()
+            })
+            .finish()?;
+
+        if T == 0.0 {
+            return Ok(VixDistribution {
+                time_to_expiry: T,
+                samples: vec![curve.forward_vix_squared(0.0).sqrt(); self.n_paths],
+            });
+        }
+
+        let alpha = model.hurst - 0.5;
+        let eta = model.vol_of_vol;
+        let dt = T / self.n_steps as f64;
+        let h = VIX_HORIZON / VIX_NODES as f64;
+
+        // Per quadrature node: the forward variance averaged over its
+        // sub-interval, the weights of the Brownian increments in G(u) at
+        // its midpoint, and the variance of G(u).
+        let nodes: Vec<(f64, Vec<f64>, f64)> = (0..VIX_NODES)
+            .map(|k| {
+                let start = T + h * k as f64;
+                let u = start + 0.5 * h;
+                let xi = (curve.total_variance(start + h) - curve.total_variance(start)) / h;
+
+                let weights: Vec<f64> = (0..self.n_steps)
+                    .map(|j| {
+                        let (s0, s1) = (dt * j as f64, dt * (j + 1) as f64);
+
+                        (2.0 * model.hurst).sqrt()
+                            * ((u - s0).powf(alpha + 1.0) - (u - s1).powf(alpha + 1.0))
+                            / ((alpha + 1.0) * dt)
+                    })
+                    .collect();
+                let variance = weights.iter().map(|w| w * w * dt).sum();
+
+                (xi, weights, variance)
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        let samples = (0..self.n_paths)
+            .map(|_| {
+                let increments: Vec<f64> = (0..self.n_steps)
+                    .map(|_| {
+                        let z: f64 = StandardNormal.sample(&mut rng);
+
+                        dt.sqrt() * z
+                    })
+                    .collect();
+
+                let vix_squared = nodes
+                    .iter()
+                    .map(|(xi, weights, variance)| {
+                        let g: f64 = weights.iter().zip(&increments).map(|(w, dw)| w * dw).sum();
+
+                        xi * (eta * g - 0.5 * eta * eta * variance).exp()
+                    })
+                    .sum::<f64>()
+                    / VIX_NODES as f64;
+
+                vix_squared.sqrt()
+            })
+            .collect();
+
+        Ok(VixDistribution {
+            time_to_expiry: T,
+            samples,
+        })
+    }
+
+    fn check(&self, T: f64) -> Result<(), RustQuantError> {
+        Validator::new()
+            .non_negative("T", T)
+            .check(self.n_paths >= 2, || {
+                "at least two paths are needed".to_string()
+            })
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Sample mean and its standard error.
+fn estimate<I: Iterator<Item = f64>>(values: I) -> Price {
+    let (n, sum, sum_sq) = values.fold((0.0, 0.0, 0.0), |(n, sum, sum_sq), x| {
+        (n + 1.0, sum + x, sum_sq + x * x)
+    });
+    let mean = sum / n;
+    let variance = ((sum_sq - n * mean * mean) / (n - 1.0)).max(0.0);
+
+    Price {
+        price: mean,
+        error: Some((variance / n).sqrt()),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_vix {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const HESTON: HestonParameters = HestonParameters {
+        initial_variance: 0.04,
+        long_run_variance: 0.06,
+        mean_reversion_rate: 2.0,
+        volatility_of_volatility: 0.6,
+        correlation: -0.7,
+    };
+
+    const RBERGOMI: RoughBergomi = RoughBergomi {
+        hurst: 0.1,
+        forward_variance: 0.04,
+        vol_of_vol: 1.9,
+        correlation: -0.9,
+    };
+
+    fn curve() -> ForwardVarianceCurve {
+        ForwardVarianceCurve::from_variance_swaps(&[0.25, 0.5, 1.0], &[0.2, 0.22, 0.23]).unwrap()
+    }
+
+    #[test]
+    fn test_forward_variance_curve() {
+        let curve = curve();
+
+        assert_approx_equal!(curve.forward_variance(0.1), 0.04, 1e-12);
+        assert_approx_equal!(curve.forward_variance(0.3), (0.0242 - 0.01) / 0.25, 1e-12);
+        assert_approx_equal!(curve.forward_variance(2.0), 0.0287 / 0.5, 1e-12);
+        for (&T, K) in [0.25, 0.5, 1.0].iter().zip([0.2, 0.22, 0.23]) {
+            assert_approx_equal!(curve.variance_swap_strike(T), K, 1e-12);
+        }
+        assert_approx_equal!(curve.forward_vix_squared(0.0), 0.04, 1e-12);
+
+        // Total variance falls between the first two maturities.
+        assert!(matches!(
+            ForwardVarianceCurve::from_variance_swaps(&[0.25, 0.5], &[0.3, 0.2]),
+            Err(RustQuantError::ArbitrageViolation(_))
+        ));
+        assert!(ForwardVarianceCurve::from_variance_swaps(&[0.5, 0.25], &[0.2, 0.2]).is_err());
+    }
+
+    #[test]
+    fn test_heston_vix() {
+        let T = 0.5;
+        let vix = VixMonteCarlo::default().heston(&HESTON, T).unwrap();
+
+        // E[VIX_T^2] is affine in E[V_T].
+        let kappa_delta = HESTON.mean_reversion_rate * VIX_HORIZON;
+        let a = (1.0 - (-kappa_delta).exp()) / kappa_delta;
+        let expected_variance = HESTON.long_run_variance
+            + (HESTON.initial_variance - HESTON.long_run_variance)
+                * (-HESTON.mean_reversion_rate * T).exp();
+        let expected = a * expected_variance + (1.0 - a) * HESTON.long_run_variance;
+
+        let variance_future = vix.variance_future();
+        assert!((variance_future.price - expected).abs() < 4.0 * variance_future.error.unwrap());
+
+        // Convexity: the future is below the square root of the VIX-squared
+        // future, and calls and puts satisfy parity.
+        let future = vix.future().price;
+        assert!(future < expected.sqrt());
+
+        let call = vix.option(0.22, 0.03, TypeFlag::Call).price;
+        let put = vix.option(0.22, 0.03, TypeFlag::Put).price;
+        assert_approx_equal!(call - put, (-0.03 * T).exp() * (future - 0.22), 1e-12);
+    }
+
+    #[test]
+    fn test_rough_bergomi_vix_without_vol_of_vol_follows_the_curve() {
+        let model = RoughBergomi {
+            vol_of_vol: 0.0,
+            ..RBERGOMI
+        };
+        let curve = curve();
+        let vix = VixMonteCarlo::default()
+            .rough_bergomi(&model, &curve, 0.2)
+            .unwrap();
+
+        let future = vix.future();
+        assert_approx_equal!(future.price, curve.forward_vix_squared(0.2).sqrt(), 1e-12);
+        assert_approx_equal!(future.error.unwrap(), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_rough_bergomi_vix() {
+        let curve = curve();
+        let vix = VixMonteCarlo::default()
+            .rough_bergomi(&RBERGOMI, &curve, 0.3)
+            .unwrap();
+
+        // The forward variances are martingales.
+        let expected = curve.forward_vix_squared(0.3);
+        let variance_future = vix.variance_future();
+        assert!((variance_future.price - expected).abs() < 4.0 * variance_future.error.unwrap());
+
+        assert!(vix.future().price < expected.sqrt());
+        assert!(vix.samples.iter().all(|&v| v > 0.0));
+    }
+
+    #[test]
+    fn test_vix_validation() {
+        let engine = VixMonteCarlo {
+            n_paths: 1,
+            ..VixMonteCarlo::default()
+        };
+
+        assert!(engine.heston(&HESTON, 0.5).is_err());
+        assert!(VixMonteCarlo::default().heston(&HESTON, -0.5).is_err());
+        assert!(ForwardVarianceCurve::new(vec![1.0], vec![-0.01]).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Warrants, adjusted for dilution.
+//!
+//! A warrant is a call option written by the company on its own shares.
+//! When it is exercised, the company issues new shares, so the payoff is
+//! diluted: with `N` shares and `M` warrants outstanding, each warrant
+//! giving `g` shares for the strike `K`, the payoff is
+//!
+//! ```text
+//! g N / (N + g M) * max(V / N - K / g, 0)
+//! ```
+//!
+//! where `V` is the value of the company's equity, warrants included. Since
+//! `V / N = S + M W / N` depends on the warrant value `W` itself, the value
+//! solves (Haug, *Complete Guide to Option Pricing Formulas*, section 13.1)
+//!
+//! ```text
+//! W = g N / (N + g M) * C(S + M W / N, K / g)
+//! ```
+//!
+//! where `C` is a vanilla call on the equity, priced by one of the
+//! [`WarrantEngine`]s. The volatility is that of `V / N`, not of the share
+//! price alone.
+
+use super::{generalised_black_scholes_merton, BinomialOption, ExerciseFlag, TypeFlag};
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Warrant parameters.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Warrant {
+    /// * `S` - Initial share price.
+    pub initial_price: f64,
+    /// * `K` - Strike price, paid per warrant.
+    pub strike_price: f64,
+    /// * `T` - Time to expiry.
+    pub time_to_expiry: f64,
+    /// * `r` - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// * `q` - Dividend yield.
+    pub dividend_yield: f64,
+    /// * `v` - Volatility of the equity value per share.
+    pub volatility: f64,
+    /// * `N` - Number of shares outstanding.
+    pub shares_outstanding: f64,
+    /// * `M` - Number of warrants outstanding.
+    pub warrants_outstanding: f64,
+    /// * `g` - Number of new shares issued per warrant exercised.
+    pub shares_per_warrant: f64,
+}
+
+/// Engine used to price the vanilla call underlying a [`Warrant`].
+#[derive(Debug, Clone, Copy)]
+pub enum WarrantEngine {
+    /// Generalised Black-Scholes-Merton (European exercise).
+    BlackScholes,
+    /// Cox-Ross-Rubinstein tree of the given height (American exercise).
+    CoxRossRubinstein(usize),
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Warrant {
+    const MAX_ITERATIONS: usize = 100;
+    const TOLERANCE: f64 = 1e-10;
+
+    /// Dilution factor `g N / (N + g M)`.
+    #[must_use]
+    pub fn dilution_factor(&self) -> f64 {
+        let (N, M, g) = (
+            self.shares_outstanding,
+            self.warrants_outstanding,
+            self.shares_per_warrant,
+        );
+
+        g * N / (N + g * M)
+    }
+
+    /// Dilution-adjusted value of the warrant.
+    ///
+    /// The value is found by fixed-point iteration, which converges since
+    /// each step shrinks the error by at least `g M / (N + g M) < 1`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the parameters fail
+    ///   [`Validate::validate`], or if the engine fails.
+    /// * [`RustQuantError::NonConvergence`] if the iteration does not
+    ///   converge.
+    pub fn price(&self, engine: WarrantEngine) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        let dilution = self.dilution_factor();
+        let strike = self.strike_price / self.shares_per_warrant;
+        let per_share = self.warrants_outstanding / self.shares_outstanding;
+
+        let call = |S: f64| -> Result<f64, RustQuantError> {
+            let r = self.risk_free_rate;
+            let q = self.dividend_yield;
+
+            match engine {
+                WarrantEngine::BlackScholes => Ok(generalised_black_scholes_merton(
+                    S,
+                    strike,
+                    self.volatility,
+                    r,
+                    r - q,
+                    self.time_to_expiry,
+                    TypeFlag::Call,
+                )),
+                WarrantEngine::CoxRossRubinstein(n) => {
+                    BinomialOption::new(S, strike, self.time_to_expiry, r, q, self.volatility)
+                        .price_CoxRossRubinstein("p", ExerciseFlag::American, TypeFlag::Call, n)
+                }
+            }
+        };
+
+        let mut value = 0.0;
+
+        for _ in 0..Self::MAX_ITERATIONS {
+            let next = dilution * call(self.initial_price + per_share * value)?;
+
+            if (next - value).abs() < Self::TOLERANCE {
+                return Ok(next);
+            }
+
+            value = next;
+        }
+
+        Err(RustQuantError::NonConvergence(format!(
+            "Warrant value did not converge after {} iterations.",
+            Self::MAX_ITERATIONS
+        )))
+    }
+}
+
+impl Validate for Warrant {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .positive("initial_price", self.initial_price)
+            .positive("strike_price", self.strike_price)
+            .positive("time_to_expiry", self.time_to_expiry)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .finite("dividend_yield", self.dividend_yield)
+            .positive("volatility", self.volatility)
+            .positive("shares_outstanding", self.shares_outstanding)
+            .non_negative("warrants_outstanding", self.warrants_outstanding)
+            .positive("shares_per_warrant", self.shares_per_warrant)
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_warrant {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const WARRANT: Warrant = Warrant {
+        initial_price: 50.0,
+        strike_price: 55.0,
+        time_to_expiry: 2.0,
+        risk_free_rate: 0.05,
+        dividend_yield: 0.0,
+        volatility: 0.3,
+        shares_outstanding: 1_000_000.0,
+        warrants_outstanding: 200_000.0,
+        shares_per_warrant: 1.0,
+    };
+
+    fn black_scholes(warrant: &Warrant) -> f64 {
+        generalised_black_scholes_merton(
+            warrant.initial_price,
+            warrant.strike_price,
+            warrant.volatility,
+            warrant.risk_free_rate,
+            warrant.risk_free_rate - warrant.dividend_yield,
+            warrant.time_to_expiry,
+            TypeFlag::Call,
+        )
+    }
+
+    #[test]
+    fn test_no_dilution_is_vanilla_call() {
+        let warrant = Warrant {
+            warrants_outstanding: 0.0,
+            ..WARRANT
+        };
+
+        assert_approx_equal!(
+            warrant.price(WarrantEngine::BlackScholes).unwrap(),
+            black_scholes(&warrant),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_dilution_fixed_point() {
+        let value = WARRANT.price(WarrantEngine::BlackScholes).unwrap();
+        let equity = Warrant {
+            initial_price: 50.0 + 0.2 * value,
+            ..WARRANT
+        };
+
+        assert_approx_equal!(value, 7.871_830_676_949_706, 1e-8);
+        assert_approx_equal!(value, black_scholes(&equity) / 1.2, 1e-8);
+        assert!(value < black_scholes(&WARRANT));
+    }
+
+    #[test]
+    fn test_shares_per_warrant() {
+        // A warrant on two shares with twice the strike is worth twice as
+        // much when there is no dilution.
+        let single = Warrant {
+            warrants_outstanding: 0.0,
+            ..WARRANT
+        };
+        let double = Warrant {
+            strike_price: 110.0,
+            shares_per_warrant: 2.0,
+            ..single
+        };
+
+        assert_approx_equal!(
+            double.price(WarrantEngine::BlackScholes).unwrap(),
+            2.0 * single.price(WarrantEngine::BlackScholes).unwrap(),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_american_warrant() {
+        // Without dividends, early exercise is never optimal.
+        assert_approx_equal!(
+            WARRANT
+                .price(WarrantEngine::CoxRossRubinstein(500))
+                .unwrap(),
+            WARRANT.price(WarrantEngine::BlackScholes).unwrap(),
+            1e-2
+        );
+
+        let dividends = Warrant {
+            dividend_yield: 0.04,
+            ..WARRANT
+        };
+
+        assert!(
+            dividends
+                .price(WarrantEngine::CoxRossRubinstein(500))
+                .unwrap()
+                >= dividends.price(WarrantEngine::BlackScholes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_warrant_validation() {
+        let warrant = Warrant {
+            warrants_outstanding: -1.0,
+            ..WARRANT
+        };
+        assert!(warrant.price(WarrantEngine::BlackScholes).is_err());
+
+        let warrant = Warrant {
+            shares_outstanding: 0.0,
+            ..WARRANT
+        };
+        assert!(warrant.price(WarrantEngine::BlackScholes).is_err());
+        assert!(WARRANT.price(WarrantEngine::CoxRossRubinstein(2)).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Memoisation of intermediate pricing quantities.
+//!
+//! Pricing a large portfolio against one [`PricingContext`] evaluates the
+//! same discount factors, volatilities, and characteristic functions many
+//! times over (e.g. every bond paying a coupon on the same date).
+//! The [`PricingCache`] stores each value the first time it is computed,
+//! keyed by the curve (or surface, or model) id and the date.
+//!
+//! The cache sits behind a lock so that instruments, which only see
+//! `&PricingContext`, can fill it, including from parallel pricing loops.
+//!
+//! [`PricingContext`]: crate::instruments::PricingContext
+
+use num::Complex;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Cache of discount factors, volatilities, and characteristic-function
+/// values, keyed by (id, date).
+///
+/// Cloning gives an empty cache, since a clone of a context is usually
+/// made in order to change its market data (e.g. to apply a scenario).
+#[derive(Debug, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct PricingCache {
+    /// Discount factors, keyed by (curve id, date).
+    discount_factors: RwLock<HashMap<(String, Date), f64>>,
+
+    /// Volatilities, keyed by (surface id, date, strike bits).
+    volatilities: RwLock<HashMap<(String, Date, u64), f64>>,
+
+    /// Characteristic-function values, keyed by (model id, date, argument bits).
+    characteristic_functions: RwLock<HashMap<(String, Date, u64), Complex<f64>>>,
+
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// Hit and miss counts of a [`PricingCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct CacheStats {
+    /// Number of lookups answered from the cache.
+    pub hits: usize,
+    /// Number of lookups that had to compute the value.
+    pub misses: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl PricingCache {
+    /// Create a new (empty) cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Discount factor of curve `id` at `date`, computed with `compute`
+    /// on the first lookup.
+    pub fn discount_factor<F>(&self, id: &str, date: Date, compute: F) -> f64
+    where
+        F: FnOnce() -> f64,
+    {
+        self.get_or_insert(&self.discount_factors, (id.to_string(), date), compute)
+    }
+
+    /// Volatility of surface `id` at `strike` and `date`, computed with
+    /// `compute` on the first lookup.
+    pub fn volatility<F>(&self, id: &str, strike: f64, date: Date, compute: F) -> f64
+    where
+        F: FnOnce() -> f64,
+    {
+        self.get_or_insert(
+            &self.volatilities,
+            (id.to_string(), date, strike.to_bits()),
+            compute,
+        )
+    }
+
+    /// Characteristic function of model `id` for expiry `date`, evaluated
+    /// at `u`, computed with `compute` on the first lookup.
+    ///
+    /// The id must identify the model parameters as well as the model,
+    /// since they are not part of the key.
+    pub fn characteristic_function<F>(
+        &self,
+        id: &str,
+        date: Date,
+        u: f64,
+        compute: F,
+    ) -> Complex<f64>
+    where
+        F: FnOnce() -> Complex<f64>,
+    {
+        self.get_or_insert(
+            &self.characteristic_functions,
+            (id.to_string(), date, u.to_bits()),
+            compute,
+        )
+    }
+
+    /// Hit and miss counts since the cache was created or last cleared.
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Remove all cached values and reset the counts.
+    ///
+    /// Must be called after changing the market data of a context in place.
+    pub fn clear(&self) {
+        write(&self.discount_factors).clear();
+        write(&self.volatilities).clear();
+        write(&self.characteristic_functions).clear();
+
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    fn get_or_insert<K, V, F>(&self, map: &RwLock<HashMap<K, V>>, key: K, compute: F) -> V
+    where
+        K: Eq + Hash,
+        V: Copy,
+        F: FnOnce() -> V,
+    {
+        if let Some(value) = map.read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return *value;
+        }
+
+        // Computed outside the lock, so `compute` may use the cache itself.
+        let value = compute();
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        *write(map).entry(key).or_insert(value)
+    }
+}
+
+/// Write access to a cache map. A panic while holding the lock cannot leave
+/// a map half-updated, so poisoning is ignored.
+fn write<T>(lock: &RwLock<T>) -> std::sync::RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Clone for PricingCache {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_pricing_cache {
+    use super::*;
+    use std::cell::Cell;
+    use time::macros::date;
+
+    #[test]
+    fn test_values_are_computed_once() {
+        let cache = PricingCache::new();
+        let calls = Cell::new(0);
+        let date = date!(2025 - 01 - 01);
+
+        let df = || {
+            calls.set(calls.get() + 1);
+            0.95
+        };
+
+        for _ in 0..100 {
+            assert_eq!(cache.discount_factor("USD", date, df), 0.95);
+        }
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 99,
+                misses: 1
+            }
+        );
+
+        // Different keys are computed separately.
+        assert_eq!(cache.discount_factor("EUR", date, || 0.97), 0.97);
+        assert_eq!(
+            cache.discount_factor("USD", date!(2026 - 01 - 01), || 0.9),
+            0.9
+        );
+        assert_eq!(cache.volatility("SPX", 100.0, date, || 0.2), 0.2);
+        assert_eq!(cache.volatility("SPX", 105.0, date, || 0.18), 0.18);
+        assert_eq!(cache.volatility("SPX", 100.0, date, || 0.0), 0.2);
+        assert_eq!(
+            cache.characteristic_function("heston", date, 1.5, || Complex::new(0.5, 0.1)),
+            Complex::new(0.5, 0.1)
+        );
+        assert_eq!(cache.stats().misses, 6);
+    }
+
+    #[test]
+    fn test_clear_and_clone_are_empty() {
+        let cache = PricingCache::new();
+        let date = date!(2025 - 01 - 01);
+
+        cache.discount_factor("USD", date, || 0.95);
+
+        let clone = cache.clone();
+        assert_eq!(clone.discount_factor("USD", date, || 0.9), 0.9);
+
+        cache.clear();
+        assert_eq!(cache.stats(), CacheStats::default());
+        assert_eq!(cache.discount_factor("USD", date, || 0.9), 0.9);
+    }
+}
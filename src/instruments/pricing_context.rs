@@ -0,0 +1,597 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pricing context.
+//!
+//! The pricing context carries the market environment an instrument is
+//! valued in: the valuation date, discount curves (by currency),
+//! volatility surfaces (by name), and FX rates.
+//! Any [`Instrument`] can be priced against a context, which allows
+//! portfolios of heterogeneous instruments to be valued in one call.
+//!
+//! Instruments read their risk-free rate off the discount curve for their
+//! currency (or the reporting currency), and their volatility off the
+//! context's default volatility surface, falling back to their own
+//! parameters when the context has no such market data.
+//!
+//! Discount factors looked up through the context, and any volatilities
+//! or characteristic-function values computed through its [`PricingCache`],
+//! are memoised for the lifetime of the context.
+
+use crate::data::{Curve, VolatilitySurface, YieldCurve};
+use crate::error::RustQuantError;
+use crate::instruments::fx::{currency::Currency, exchange::FxMatrix};
+use crate::instruments::{Instrument, PricingCache};
+use crate::time::DayCountConvention;
+use std::collections::HashMap;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market environment used to price instruments.
+#[derive(Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct PricingContext {
+    /// The valuation date.
+    /// Instruments without their own evaluation date are valued as of this date.
+    pub valuation_date: Date,
+
+    /// Discount curves, keyed by ISO 4217 alphabetic currency code.
+    pub discount_curves: HashMap<&'static str, YieldCurve>,
+
+    /// Volatility surfaces, keyed by name (e.g. underlying ticker).
+    pub volatility_surfaces: HashMap<String, VolatilitySurface<YieldCurve>>,
+
+    /// Name of the volatility surface options are priced on (optional).
+    /// The option pricers do not record their underlying, so they all read
+    /// their volatility off this surface.
+    pub default_volatility_surface: Option<String>,
+
+    /// FX rates, used to convert values into the reporting currency.
+    pub fx: FxMatrix,
+
+    /// Currency values are reported in (optional).
+    /// If not set, values are summed without conversion.
+    pub reporting_currency: Option<Currency>,
+
+    /// Memoised discount factors, volatilities, and characteristic-function
+    /// values. Clear it after changing the fields above in place.
+    pub cache: PricingCache,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl PricingContext {
+    /// Create a new (empty) pricing context for the given valuation date.
+    #[must_use]
+    pub fn new(valuation_date: Date) -> Self {
+        Self {
+            valuation_date,
+            discount_curves: HashMap::new(),
+            volatility_surfaces: HashMap::new(),
+            default_volatility_surface: None,
+            fx: FxMatrix::default(),
+            reporting_currency: None,
+            cache: PricingCache::new(),
+        }
+    }
+
+    /// Add a discount curve for a currency.
+    #[must_use]
+    pub fn with_discount_curve(mut self, currency: Currency, curve: YieldCurve) -> Self {
+        self.discount_curves.insert(currency.code.alphabetic, curve);
+        self.cache.clear();
+        self
+    }
+
+    /// Add a volatility surface.
+    #[must_use]
+    pub fn with_volatility_surface(
+        mut self,
+        name: &str,
+        surface: VolatilitySurface<YieldCurve>,
+    ) -> Self {
+        self.volatility_surfaces.insert(name.to_string(), surface);
+        self.cache.clear();
+        self
+    }
+
+    /// Set the volatility surface options are priced on.
+    #[must_use]
+    pub fn with_default_volatility_surface(mut self, name: &str) -> Self {
+        self.default_volatility_surface = Some(name.to_string());
+        self.cache.clear();
+        self
+    }
+
+    /// Set the FX rates.
+    #[must_use]
+    pub fn with_fx(mut self, fx: FxMatrix) -> Self {
+        self.fx = fx;
+        self
+    }
+
+    /// Set the reporting currency.
+    #[must_use]
+    pub fn with_reporting_currency(mut self, currency: Currency) -> Self {
+        self.reporting_currency = Some(currency);
+        self
+    }
+
+    /// Discount curve for the given currency, if any.
+    #[must_use]
+    pub fn discount_curve(&self, currency: &Currency) -> Option<&YieldCurve> {
+        self.discount_curves.get(currency.code.alphabetic)
+    }
+
+    /// Volatility surface with the given name, if any.
+    #[must_use]
+    pub fn volatility_surface(&self, name: &str) -> Option<&VolatilitySurface<YieldCurve>> {
+        self.volatility_surfaces.get(name)
+    }
+
+    /// Discount factor at `date` from the discount curve for the given
+    /// currency, if there is one and it covers `date`.
+    /// Memoised per (currency, date).
+    #[must_use]
+    pub fn discount_factor(&self, currency: &Currency, date: Date) -> Option<f64> {
+        let curve = self.discount_curve(currency)?;
+
+        let df = self
+            .cache
+            .discount_factor(currency.code.alphabetic, date, || {
+                curve.discount_factor(date).unwrap_or(f64::NAN)
+            });
+
+        (!df.is_nan()).then_some(df)
+    }
+
+    /// Continuously compounded risk-free rate from `start` to `end`, implied
+    /// by the discount curve for `currency` (or the reporting currency if
+    /// `None`), if there is one and it covers both dates.
+    #[must_use]
+    pub fn risk_free_rate(
+        &self,
+        currency: Option<Currency>,
+        start: Date,
+        end: Date,
+    ) -> Option<f64> {
+        let currency = currency.or(self.reporting_currency)?;
+        let t = DayCountConvention::default().day_count_factor(start, end);
+
+        if t <= 0.0 {
+            return None;
+        }
+
+        let df_start = self.discount_factor(&currency, start)?;
+        let df_end = self.discount_factor(&currency, end)?;
+
+        Some((df_start / df_end).ln() / t)
+    }
+
+    /// Volatility at `strike` and `date` from the default volatility surface,
+    /// if there is one and it covers `date`.
+    /// Memoised per (surface, strike, date).
+    #[must_use]
+    pub fn volatility(&self, strike: f64, date: Date) -> Option<f64> {
+        let name = self.default_volatility_surface.as_deref()?;
+        let surface = self.volatility_surface(name)?;
+
+        let volatility = self.cache.volatility(name, strike, date, || {
+            surface.volatility(strike, date).unwrap_or(f64::NAN)
+        });
+
+        (!volatility.is_nan()).then_some(volatility)
+    }
+
+    /// Date `years` after the valuation date (counting 365 days per year),
+    /// for instruments whose maturity is given as a year fraction.
+    #[cfg(feature = "options")]
+    pub(crate) fn date_after(&self, years: f64) -> Date {
+        self.valuation_date + time::Duration::days((years * 365.0).round() as i64)
+    }
+
+    /// Value of an instrument in the reporting currency.
+    ///
+    /// If both the reporting currency and the instrument's currency are
+    /// known, the price is converted using the context's FX rates;
+    /// otherwise the price is returned unconverted.
+    pub fn value(&self, instrument: &dyn Instrument) -> Result<f64, RustQuantError> {
+        let price = instrument.price(self)?;
+
+        match (instrument.currency(), self.reporting_currency) {
+            (Some(from), Some(to)) => Ok(price * self.fx.rate(&from, &to)?),
+            _ => Ok(price),
+        }
+    }
+
+    /// Total value of a collection of (possibly heterogeneous) instruments,
+    /// each held in the given quantity, in the reporting currency.
+    ///
+    /// # Example
+    /// ```
+    /// use RustQuant::instruments::{Instrument, PricingContext};
+    /// use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+    /// use time::macros::date;
+    ///
+    /// let ctx = PricingContext::new(date!(2024 - 01 - 01));
+    ///
+    /// let call = BlackScholesMerton::new(
+    ///     0.05, 100.0, 100.0, 0.2, 0.05, None, date!(2025 - 01 - 01), TypeFlag::Call,
+    /// );
+    /// let put = BlackScholesMerton::new(
+    ///     0.05, 100.0, 100.0, 0.2, 0.05, None, date!(2025 - 01 - 01), TypeFlag::Put,
+    /// );
+    ///
+    /// let instruments: Vec<(&dyn Instrument, f64)> = vec![(&call, 10.0), (&put, -5.0)];
+    /// let value = ctx.value_all(&instruments).unwrap();
+    ///
+    /// let expected = 10.0 * Instrument::price(&call, &ctx).unwrap()
+    ///     - 5.0 * Instrument::price(&put, &ctx).unwrap();
+    ///
+    /// assert!((value - expected).abs() < 1e-10);
+    /// ```
+    pub fn value_all(&self, instruments: &[(&dyn Instrument, f64)]) -> Result<f64, RustQuantError> {
+        instruments
+            .iter()
+            .map(|(instrument, quantity)| Ok(quantity * self.value(*instrument)?))
+            .sum()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(all(test, feature = "options"))]
+mod tests_pricing_context {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::fx::exchange::ExchangeRate;
+    use crate::instruments::options::{BlackScholesMerton, TypeFlag};
+    use crate::iso::{EUR, USD};
+    use time::macros::date;
+
+    #[test]
+    fn test_context_valuation_date() {
+        let option = BlackScholesMerton::new(
+            0.05,
+            100.0,
+            100.0,
+            0.2,
+            0.05,
+            None,
+            date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        );
+
+        let ctx_early = PricingContext::new(date!(2024 - 01 - 01));
+        let ctx_late = PricingContext::new(date!(2024 - 07 - 01));
+
+        // Less time value closer to expiry.
+        assert!(
+            Instrument::price(&option, &ctx_early).unwrap()
+                > Instrument::price(&option, &ctx_late).unwrap()
+        );
+
+        let dated = BlackScholesMerton::new(
+            0.05,
+            100.0,
+            100.0,
+            0.2,
+            0.05,
+            Some(date!(2024 - 07 - 01)),
+            date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        );
+
+        // An explicit evaluation date on the instrument takes precedence.
+        assert_approx_equal!(
+            Instrument::price(&dated, &ctx_early).unwrap(),
+            Instrument::price(&option, &ctx_late).unwrap(),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_context_market_data() {
+        use crate::instruments::options::{BarrierOption, BarrierType, HestonOption};
+
+        let valuation_date = date!(2024 - 01 - 01);
+        let expiry = date!(2025 - 01 - 01);
+
+        let flat = |level: f64| {
+            YieldCurve::from_dates_and_rates(&[valuation_date, date!(2030 - 01 - 01)], &[level; 2])
+                .unwrap()
+        };
+        let ctx = |rate: f64, volatility: f64| {
+            PricingContext::new(valuation_date)
+                .with_reporting_currency(USD)
+                .with_discount_curve(USD, flat(rate))
+                .with_volatility_surface(
+                    "SPX",
+                    VolatilitySurface::new(vec![
+                        (80.0, flat(volatility)),
+                        (120.0, flat(volatility)),
+                    ]),
+                )
+                .with_default_volatility_surface("SPX")
+        };
+
+        let option =
+            BlackScholesMerton::new(0.05, 100.0, 100.0, 0.2, 0.05, None, expiry, TypeFlag::Call);
+        let base = Instrument::price(&option, &ctx(0.05, 0.2)).unwrap();
+
+        // Market data matching the option's own parameters gives its own price.
+        assert_approx_equal!(
+            base,
+            Instrument::price(&option, &PricingContext::new(valuation_date)).unwrap(),
+            1e-10
+        );
+        assert!(Instrument::price(&option, &ctx(0.06, 0.2)).unwrap() > base);
+
+        // The rate and volatility lookups are memoised.
+        let market = ctx(0.05, 0.2);
+        Instrument::price(&option, &market).unwrap();
+        let misses = market.cache.stats().misses;
+        assert_approx_equal!(Instrument::price(&option, &market).unwrap(), base, 1e-12);
+        assert_eq!(market.cache.stats().misses, misses);
+        assert!(Instrument::price(&option, &ctx(0.05, 0.3)).unwrap() > base);
+
+        let heston = HestonOption {
+            initial_price: 100.0,
+            initial_variance: 0.04,
+            strike_price: 100.0,
+            risk_free_rate: 0.05,
+            dividend_yield: 0.0,
+            correlation: -0.5,
+            volatility_of_volatility: 0.3,
+            mean_reversion_rate: 2.0,
+            long_run_variance: 0.04,
+            evaluation_date: None,
+            expiration_date: expiry,
+            type_flag: TypeFlag::Put,
+        };
+        assert!(
+            Instrument::price(&heston, &ctx(0.06, 0.2)).unwrap()
+                < Instrument::price(&heston, &ctx(0.05, 0.2)).unwrap()
+        );
+
+        let barrier = (
+            BarrierOption {
+                initial_price: 100.0,
+                strike_price: 100.0,
+                barrier: 90.0,
+                time_to_expiry: 1.0,
+                risk_free_rate: 0.05,
+                volatility: 0.2,
+                rebate: 0.0,
+                dividend_yield: 0.0,
+            },
+            BarrierType::CDO,
+        );
+        assert_approx_equal!(
+            Instrument::price(&barrier, &ctx(0.05, 0.3)).unwrap(),
+            BarrierOption {
+                volatility: 0.3,
+                ..barrier.0
+            }
+            .price(BarrierType::CDO)
+            .unwrap(),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_context_currency_conversion() {
+        let mut fx = FxMatrix::new(None);
+        fx.add_quote(ExchangeRate::new(EUR, USD, 1.25));
+
+        let ctx = PricingContext::new(date!(2024 - 01 - 01))
+            .with_fx(fx)
+            .with_reporting_currency(USD);
+
+        let instruments: Vec<(&dyn Instrument, f64)> = vec![(&EUR, 100.0), (&USD, 50.0)];
+
+        assert_approx_equal!(ctx.value_all(&instruments).unwrap(), 175.0, 1e-12);
+
+        let ctx = ctx.with_reporting_currency(crate::iso::JPY);
+        assert!(ctx.value_all(&instruments).is_err());
+    }
+
+    #[test]
+    fn test_context_discount_curve() {
+        let curve = YieldCurve::from_dates_and_rates(
+            &[date!(2024 - 01 - 01), date!(2030 - 01 - 01)],
+            &[0.05, 0.05],
+        )
+        .unwrap();
+
+        let ctx = PricingContext::new(date!(2024 - 01 - 01)).with_discount_curve(USD, curve);
+
+        assert!(ctx.discount_curve(&USD).is_some());
+        assert!(ctx.discount_curve(&EUR).is_none());
+    }
+
+    #[test]
+    fn test_context_cache() {
+        use crate::instruments::bonds::coupon_bond::CouponBond;
+        use crate::time::{DateRollingConvention, Frequency};
+        use std::collections::BTreeMap;
+
+        let dates = [date!(2024 - 01 - 01), date!(2030 - 01 - 01)];
+        let curve = YieldCurve::from_dates_and_rates(&dates, &[0.05, 0.05]).unwrap();
+
+        let ctx = PricingContext::new(dates[0]).with_discount_curve(USD, curve.clone());
+
+        let coupons =
+            BTreeMap::from([(date!(2024 - 07 - 01), 5.0), (date!(2025 - 01 - 01), 105.0)]);
+        let bond = CouponBond {
+            evaluation_date: dates[0],
+            expiration_date: date!(2025 - 01 - 01),
+            currency: Some(USD),
+            coupon_rate: 0.1,
+            coupon_frequency: Frequency::SemiAnnually,
+            settlement_convention: DateRollingConvention::Actual,
+            yield_curve: curve.clone(),
+            face_value: 100.0,
+            coupons,
+        };
+
+        let portfolio: Vec<(&dyn Instrument, f64)> = vec![(&bond, 1.0); 100];
+        let value = ctx.value_all(&portfolio).unwrap();
+
+        // Two discount factors computed, then reused for every other bond.
+        assert_eq!(ctx.cache.stats().misses, 2);
+        assert_eq!(ctx.cache.stats().hits, 198);
+        assert_approx_equal!(
+            value,
+            100.0
+                * (5.0 * curve.discount_factor(date!(2024 - 07 - 01)).unwrap()
+                    + 105.0 * curve.discount_factor(date!(2025 - 01 - 01)).unwrap()),
+            1e-9
+        );
+
+        // Scenario contexts are clones, which start with an empty cache.
+        let mut shocked = ctx.clone();
+        shocked.discount_curves.insert(
+            "USD",
+            YieldCurve::from_dates_and_rates(&dates, &[0.06, 0.06]).unwrap(),
+        );
+        assert!(shocked.value_all(&portfolio).unwrap() < value);
+        assert_eq!(shocked.cache.stats().misses, 2);
+
+        assert!(ctx.discount_factor(&EUR, dates[1]).is_none());
+    }
+
+    #[test]
+    fn test_context_prices_option_pricers() {
+        use crate::instruments::options::{
+            AsianOption, BarrierOption, BarrierType, CashOrNothingOption, ForwardStartOption,
+            GapOption, HestonOption, LookbackOption, LookbackStrike,
+        };
+
+        let ctx = PricingContext::new(date!(2024 - 01 - 01));
+        let expiry = date!(2024 - 07 - 01);
+
+        let barrier = BarrierOption {
+            initial_price: 100.0,
+            strike_price: 90.0,
+            barrier: 95.0,
+            time_to_expiry: 0.5,
+            risk_free_rate: 0.08,
+            volatility: 0.25,
+            rebate: 3.0,
+            dividend_yield: 0.04,
+        };
+        let gap = GapOption {
+            initial_price: 50.0,
+            strike_1: 50.0,
+            strike_2: 57.0,
+            risk_free_rate: 0.09,
+            volatility: 0.2,
+            time_to_maturity: 0.5,
+            cost_of_carry: 0.09,
+        };
+        let digital = CashOrNothingOption {
+            initial_price: 100.0,
+            strike_price: 80.0,
+            payout_value: 10.0,
+            risk_free_rate: 0.06,
+            volatility: 0.35,
+            cost_of_carry: 0.0,
+            time_to_maturity: 0.75,
+        };
+        let lookback = LookbackOption {
+            initial_price: 50.0,
+            risk_free_rate: 0.1,
+            strike_price: None,
+            volatility: 0.4,
+            time_to_maturity: 0.25,
+            dividend_yield: 0.0,
+            s_min: 50.0,
+            s_max: 50.0,
+            strike_type: LookbackStrike::Floating,
+        };
+        let asian = AsianOption::new(80.0, 85.0, 0.05, 0.2, -0.03, None, expiry);
+        let forward_start = ForwardStartOption {
+            initial_price: 60.0,
+            alpha: 1.1,
+            risk_free_rate: 0.08,
+            volatility: 0.3,
+            dividend_rate: 0.04,
+            valuation_date: None,
+            start: date!(2024 - 04 - 01),
+            end: expiry,
+        };
+        let heston = HestonOption {
+            initial_price: 100.0,
+            initial_variance: 0.05,
+            strike_price: 100.0,
+            risk_free_rate: 0.03,
+            dividend_yield: 0.02,
+            correlation: -0.8,
+            volatility_of_volatility: 0.5,
+            mean_reversion_rate: 5.0,
+            long_run_variance: 0.05,
+            evaluation_date: None,
+            expiration_date: expiry,
+            type_flag: TypeFlag::Put,
+        };
+
+        let barrier = (barrier, BarrierType::CDI);
+        let gap = (gap, TypeFlag::Call);
+        let digital = (digital, TypeFlag::Put);
+        let lookback = (lookback, TypeFlag::Call);
+        let asian = (asian, TypeFlag::Put);
+        let forward_start = (forward_start, TypeFlag::Call);
+
+        let portfolio: Vec<(&dyn Instrument, f64)> = vec![
+            (&barrier, 1.0),
+            (&gap, 2.0),
+            (&digital, -1.0),
+            (&lookback, 1.0),
+            (&asian, 3.0),
+            (&forward_start, 1.0),
+            (&heston, -2.0),
+        ];
+
+        // Dated contracts are valued as of the context's valuation date.
+        let asian_price = AsianOption {
+            evaluation_date: Some(ctx.valuation_date),
+            ..asian.0
+        }
+        .price_geometric_average()
+        .1;
+        let forward_start_price = ForwardStartOption {
+            valuation_date: Some(ctx.valuation_date),
+            ..forward_start.0
+        }
+        .price()
+        .0;
+        let heston_price = HestonOption {
+            evaluation_date: Some(ctx.valuation_date),
+            ..heston
+        }
+        .price();
+
+        let expected = barrier.0.price(BarrierType::CDI).unwrap() + 2.0 * gap.0.price().0
+            - digital.0.price().1
+            + lookback.0.price_analytic().0
+            + 3.0 * asian_price
+            + forward_start_price
+            - 2.0 * heston_price;
+
+        assert_approx_equal!(ctx.value_all(&portfolio).unwrap(), expected, 1e-12);
+    }
+}
@@ -0,0 +1,498 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Autocallable (Phoenix) notes on a single underlying.
+//!
+//! On each observation date, with the underlying's performance
+//! `P = S_t / S_ref` measured against the reference (initial fixing) price:
+//!
+//! - if `P >= coupon_barrier`, the note pays the coupon (and, with the
+//!   memory feature, any coupons missed on earlier dates);
+//! - on every date but the last, if `P >= autocall_barrier`, the note
+//!   redeems early at par.
+//!
+//! If the note survives to maturity it redeems at par, unless the final
+//! performance is below the `knock_in_barrier`, in which case the holder is
+//! short a put struck at the reference price and receives `P` per unit of
+//! notional.
+//!
+//! The Monte Carlo engine prices the note under Black-Scholes dynamics and
+//! computes delta, vega and rho by adjoint algorithmic differentiation
+//! (reverse mode, one tape per path). The pathwise derivative of a
+//! digital payoff is zero almost everywhere, so for the Greeks each barrier
+//! indicator is replaced with a logistic function of width `smoothing`.
+//!
+//! ```
+//! use RustQuant::instruments::structured::*;
+//!
+//! let note = AutocallableNote {
+//!     notional: 100.0,
+//!     initial_price: 100.0,
+//!     reference_price: 100.0,
+//!     observation_times: vec![0.5, 1.0, 1.5, 2.0],
+//!     autocall_barrier: 1.0,
+//!     coupon_barrier: 0.7,
+//!     coupon_rate: 0.04,
+//!     memory: true,
+//!     knock_in_barrier: 0.6,
+//!     risk_free_rate: 0.03,
+//!     volatility: 0.25,
+//!     dividend_yield: 0.01,
+//! };
+//!
+//! let engine = MonteCarloAutocallableEngine {
+//!     n_paths: 10_000,
+//!     ..Default::default()
+//! };
+//! let valuation = engine.calculate(&note)?;
+//!
+//! assert!(valuation.delta > 0.0);
+//! # Ok::<(), RustQuant::error::RustQuantError>(())
+//! ```
+
+use crate::autodiff::{Accumulate, Gradient, Graph};
+use crate::error::RustQuantError;
+use crate::instruments::{Price, Validate, Validator};
+use crate::math::Real;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Autocallable (Phoenix) note terms and market data.
+///
+/// Barriers are fractions of the reference price, and the coupon is a
+/// fraction of the notional paid per observation date.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AutocallableNote {
+    /// Notional (redemption amount at par).
+    pub notional: f64,
+    /// Current price of the underlying.
+    pub initial_price: f64,
+    /// Reference (initial fixing) price the barriers are relative to.
+    pub reference_price: f64,
+    /// Observation times, in years, in increasing order. The last one is
+    /// the maturity.
+    pub observation_times: Vec<f64>,
+    /// Early redemption barrier, e.g. `1.0` for 100% of the reference.
+    pub autocall_barrier: f64,
+    /// Coupon barrier.
+    pub coupon_barrier: f64,
+    /// Coupon per observation date, as a fraction of the notional.
+    pub coupon_rate: f64,
+    /// Whether missed coupons are paid on the next date the coupon
+    /// barrier is met.
+    pub memory: bool,
+    /// Knock-in barrier of the down-and-in put, observed at maturity.
+    pub knock_in_barrier: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Volatility of the underlying.
+    pub volatility: f64,
+    /// Dividend yield of the underlying.
+    pub dividend_yield: f64,
+}
+
+/// Monte Carlo engine for [`AutocallableNote`]s, with AAD Greeks.
+#[derive(Debug, Clone, Copy)]
+pub struct MonteCarloAutocallableEngine {
+    /// Number of simulated paths.
+    pub n_paths: usize,
+    /// Seed for the random number generator.
+    pub seed: u64,
+    /// Width of the logistic functions replacing the barrier indicators
+    /// for the Greeks, in units of performance.
+    pub smoothing: f64,
+}
+
+/// Price, Greeks and autocall profile of an [`AutocallableNote`].
+#[derive(Debug, Clone)]
+pub struct AutocallableValuation {
+    /// Price and its Monte Carlo standard error.
+    pub price: Price,
+    /// Sensitivity to the underlying price.
+    pub delta: f64,
+    /// Sensitivity to the volatility.
+    pub vega: f64,
+    /// Sensitivity to the risk-free rate.
+    pub rho: f64,
+    /// Probability of early redemption on each observation date (zero on
+    /// the last).
+    pub autocall_probabilities: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl AutocallableNote {
+    /// Discounted cashflows of the note along one path, generic over the
+    /// scalar type so the same code gives prices (`f64`) and adjoints
+    /// (`Variable`).
+    ///
+    /// `normals` holds one standard normal draw per observation date.
+    /// Barrier indicators are exact when `smoothing` is `None`, and
+    /// logistic functions of the given width otherwise. The autocall
+    /// indicators are written to `called`, if given.
+    fn path_value<R: Real>(
+        &self,
+        (spot, volatility, rate): (R, R, R),
+        normals: &[f64],
+        smoothing: Option<f64>,
+        mut called: Option<&mut [f64]>,
+    ) -> R {
+        let zero = spot.constant(0.0);
+        let one = spot.constant(1.0);
+
+        let indicator = |x: R| match smoothing {
+            None if x.value() >= 0.0 => one,
+            None => zero,
+            Some(width) => match x.value() / width {
+                z if z > 40.0 => one,
+                z if z < -40.0 => zero,
+                _ => one / (one + (-x / spot.constant(width)).exp()),
+            },
+        };
+
+        let n = self.observation_times.len();
+        let notional = spot.constant(self.notional);
+        let coupon = spot.constant(self.coupon_rate * self.notional);
+        let drift = rate
+            - spot.constant(self.dividend_yield)
+            - volatility * volatility * spot.constant(0.5);
+
+        let mut log_spot = (spot / spot.constant(self.reference_price)).ln();
+        let mut alive = one;
+        let mut coupons_due = zero;
+        let mut value = zero;
+        let mut t_prev = 0.0;
+
+        for (i, (&t, &z)) in self.observation_times.iter().zip(normals).enumerate() {
+            let dt = spot.constant(t - t_prev);
+            log_spot = log_spot + drift * dt + volatility * dt.sqrt() * spot.constant(z);
+            t_prev = t;
+
+            let performance = log_spot.exp();
+            let discount = (-rate * spot.constant(t)).exp();
+
+            // Coupon (with any missed coupons, under the memory feature).
+            coupons_due = match self.memory {
+                true => coupons_due + one,
+                false => one,
+            };
+            let coupon_hit = indicator(performance - spot.constant(self.coupon_barrier));
+            value = value + alive * coupon_hit * coupons_due * coupon * discount;
+            coupons_due = (one - coupon_hit) * coupons_due;
+
+            if i + 1 < n {
+                let autocall = indicator(performance - spot.constant(self.autocall_barrier));
+                value = value + alive * autocall * notional * discount;
+
+                if let Some(called) = called.as_deref_mut() {
+                    called[i] = (alive * autocall).value();
+                }
+                alive = alive * (one - autocall);
+            } else {
+                let knocked_in = indicator(spot.constant(self.knock_in_barrier) - performance);
+                let shortfall = match performance.value() < 1.0 {
+                    true => one - performance,
+                    false => zero,
+                };
+                value = value + alive * notional * (one - knocked_in * shortfall) * discount;
+            }
+        }
+
+        value
+    }
+}
+
+impl Validate for AutocallableNote {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let times = &self.observation_times;
+
+        Validator::new()
+            .positive("notional", self.notional)
+            .positive("initial_price", self.initial_price)
+            .positive("reference_price", self.reference_price)
+            .check(!times.is_empty(), || {
+                "observation_times must not be empty".to_string()
+            })
+            .check(
+                times.iter().all(|t| t.is_finite() && *t > 0.0)
+                    && times.windows(2).all(|w| w[0] < w[1]),
+                || format!("observation_times must be positive and increasing (got {times:?})"),
+            )
+            .non_negative("autocall_barrier", self.autocall_barrier)
+            .non_negative("coupon_barrier", self.coupon_barrier)
+            .non_negative("coupon_rate", self.coupon_rate)
+            .non_negative("knock_in_barrier", self.knock_in_barrier)
+            .finite("risk_free_rate", self.risk_free_rate)
+            .positive("volatility", self.volatility)
+            .finite("dividend_yield", self.dividend_yield)
+            .finish()
+    }
+}
+
+impl Default for MonteCarloAutocallableEngine {
+    fn default() -> Self {
+        Self {
+            n_paths: 100_000,
+            seed: 42,
+            smoothing: 0.01,
+        }
+    }
+}
+
+impl Validate for MonteCarloAutocallableEngine {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .check(self.n_paths >= 2, || {
+                format!("n_paths must be at least 2 (got {})", self.n_paths)
+            })
+            .positive("smoothing", self.smoothing)
+            .finish()
+    }
+}
+
+impl MonteCarloAutocallableEngine {
+    /// Price the note, with its Greeks and autocall probabilities.
+    ///
+    /// The price uses the exact payoff; the Greeks are those of the
+    /// smoothed payoff, on the same paths.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the note or the engine
+    ///   fail [`Validate::validate`].
+    pub fn calculate(
+        &self,
+        note: &AutocallableNote,
+    ) -> Result<AutocallableValuation, RustQuantError> {
+        note.validate()?;
+        self.validate()?;
+
+        let n = note.observation_times.len();
+        let market = (note.initial_price, note.volatility, note.risk_free_rate);
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let graph = Graph::new();
+
+        let mut normals = vec![0.0; n];
+        let mut called = vec![0.0; n];
+        let mut autocalls = vec![0.0; n];
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        let (mut delta, mut vega, mut rho) = (0.0, 0.0, 0.0);
+
+        for _ in 0..self.n_paths {
+            normals
+                .iter_mut()
+                .for_each(|z| *z = StandardNormal.sample(&mut rng));
+
+            let value = note.path_value(market, &normals, None, Some(called.as_mut_slice()));
+            sum += value;
+            sum_sq += value * value;
+            autocalls
+                .iter_mut()
+                .zip(&called)
+                .for_each(|(total, hit)| *total += hit);
+
+            graph.clear();
+            let (S, v, r) = (
+                graph.var(market.0),
+                graph.var(market.1),
+                graph.var(market.2),
+            );
+            let adjoints = note
+                .path_value((S, v, r), &normals, Some(self.smoothing), None)
+                .accumulate();
+
+            delta += adjoints.wrt(&S);
+            vega += adjoints.wrt(&v);
+            rho += adjoints.wrt(&r);
+        }
+
+        let paths = self.n_paths as f64;
+        let mean = sum / paths;
+        let variance = (sum_sq / paths - mean * mean).max(0.0) * paths / (paths - 1.0);
+
+        Ok(AutocallableValuation {
+            price: Price {
+                price: mean,
+                error: Some((variance / paths).sqrt()),
+            },
+            delta: delta / paths,
+            vega: vega / paths,
+            rho: rho / paths,
+            autocall_probabilities: autocalls.iter().map(|total| total / paths).collect(),
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_autocallable {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::{generalised_black_scholes_merton, TypeFlag};
+
+    fn note() -> AutocallableNote {
+        AutocallableNote {
+            notional: 100.0,
+            initial_price: 100.0,
+            reference_price: 100.0,
+            observation_times: vec![0.5, 1.0, 1.5, 2.0],
+            autocall_barrier: 1.0,
+            coupon_barrier: 0.7,
+            coupon_rate: 0.04,
+            memory: true,
+            knock_in_barrier: 0.6,
+            risk_free_rate: 0.03,
+            volatility: 0.25,
+            dividend_yield: 0.01,
+        }
+    }
+
+    const ENGINE: MonteCarloAutocallableEngine = MonteCarloAutocallableEngine {
+        n_paths: 20_000,
+        seed: 42,
+        smoothing: 0.01,
+    };
+
+    #[test]
+    fn test_zero_coupon_bond() {
+        // Never called, never pays a coupon, never knocks in.
+        let note = AutocallableNote {
+            autocall_barrier: 1e6,
+            coupon_barrier: 1e6,
+            knock_in_barrier: 0.0,
+            ..note()
+        };
+
+        let valuation = ENGINE.calculate(&note).unwrap();
+
+        assert_approx_equal!(valuation.price.price, 100.0 * (-0.03_f64 * 2.0).exp(), 1e-8);
+        assert_approx_equal!(valuation.delta, 0.0, 1e-10);
+        assert_approx_equal!(valuation.vega, 0.0, 1e-10);
+        assert_approx_equal!(valuation.rho, -2.0 * valuation.price.price, 1e-8);
+        assert!(valuation.autocall_probabilities.iter().all(|&p| p == 0.0));
+    }
+
+    #[test]
+    fn test_called_on_first_date() {
+        let note = AutocallableNote {
+            autocall_barrier: 0.0,
+            coupon_barrier: 0.0,
+            ..note()
+        };
+
+        let valuation = ENGINE.calculate(&note).unwrap();
+
+        assert_approx_equal!(valuation.price.price, 104.0 * (-0.03_f64 * 0.5).exp(), 1e-8);
+        assert_eq!(valuation.autocall_probabilities, vec![1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_always_knocked_in_matches_black_scholes() {
+        // Without autocalls or coupons, and always knocked in, the note
+        // pays N min(S_T / S_ref, 1): the forward less a call.
+        let note = AutocallableNote {
+            autocall_barrier: 1e6,
+            coupon_barrier: 1e6,
+            knock_in_barrier: 1e6,
+            ..note()
+        };
+        let valuation = MonteCarloAutocallableEngine {
+            n_paths: 100_000,
+            ..ENGINE
+        }
+        .calculate(&note)
+        .unwrap();
+
+        let (T, q) = (2.0, 0.01);
+        let value = |S: f64, v: f64, r: f64| {
+            S * (-q * T).exp()
+                - generalised_black_scholes_merton(S, 100.0, v, r, r - q, T, TypeFlag::Call)
+        };
+        let h = 1e-5;
+
+        let error = valuation.price.error.unwrap();
+        assert!((valuation.price.price - value(100.0, 0.25, 0.03)).abs() < 4.0 * error);
+
+        let delta = (value(100.0 + h, 0.25, 0.03) - value(100.0 - h, 0.25, 0.03)) / (2.0 * h);
+        let vega = (value(100.0, 0.25 + h, 0.03) - value(100.0, 0.25 - h, 0.03)) / (2.0 * h);
+        let rho = (value(100.0, 0.25, 0.03 + h) - value(100.0, 0.25, 0.03 - h)) / (2.0 * h);
+
+        assert_approx_equal!(valuation.delta, delta, 1e-2);
+        assert_approx_equal!(valuation.vega, vega, 1.0);
+        assert_approx_equal!(valuation.rho, rho, 2.0);
+    }
+
+    #[test]
+    fn test_phoenix_note_greeks_match_bumped_prices() {
+        // AAD Greeks of the smoothed payoff against central differences of
+        // the smoothed price, on common random numbers.
+        let note = note();
+        let engine = MonteCarloAutocallableEngine {
+            n_paths: 5_000,
+            smoothing: 0.05,
+            ..ENGINE
+        };
+
+        let smoothed_price = |spot: f64| {
+            let mut rng = StdRng::seed_from_u64(engine.seed);
+            let mut normals = vec![0.0; note.observation_times.len()];
+
+            (0..engine.n_paths)
+                .map(|_| {
+                    normals
+                        .iter_mut()
+                        .for_each(|z| *z = StandardNormal.sample(&mut rng));
+                    note.path_value(
+                        (spot, note.volatility, note.risk_free_rate),
+                        &normals,
+                        Some(engine.smoothing),
+                        None,
+                    )
+                })
+                .sum::<f64>()
+                / engine.n_paths as f64
+        };
+
+        let h = 1e-4;
+        let valuation = engine.calculate(&note).unwrap();
+
+        assert_approx_equal!(
+            valuation.delta,
+            (smoothed_price(100.0 + h) - smoothed_price(100.0 - h)) / (2.0 * h),
+            1e-6
+        );
+        assert!(valuation.delta > 0.0);
+        assert!(valuation.autocall_probabilities[0] > 0.3);
+    }
+
+    #[test]
+    fn test_autocallable_validation() {
+        let unordered = AutocallableNote {
+            observation_times: vec![1.0, 0.5],
+            ..note()
+        };
+        assert!(ENGINE.calculate(&unordered).is_err());
+
+        let engine = MonteCarloAutocallableEngine {
+            smoothing: 0.0,
+            ..ENGINE
+        };
+        assert!(engine.calculate(&note()).is_err());
+    }
+}
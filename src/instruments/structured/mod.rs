@@ -0,0 +1,19 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Structured products: notes whose payoff combines a bond with embedded
+//! (usually exotic) options.
+
+/// Autocallable and Phoenix notes.
+pub mod autocallable;
+pub use autocallable::*;
+
+/// Cliquet notes with locally capped and floored coupons.
+pub mod cliquet;
+pub use cliquet::*;
@@ -0,0 +1,470 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! JSON trade import.
+//!
+//! A trade blotter is a JSON object with a list of trades. Each trade has
+//! an identifier, an optional counterparty and trade date, a quantity
+//! (defaults to `1`), and an instrument tagged by its `type`:
+//!
+//! | `type`           | Fields                                                                                                                              |
+//! |------------------|-------------------------------------------------------------------------------------------------------------------------------------|
+//! | `EuropeanOption` | `cost_of_carry`, `underlying_price`, `strike_price`, `volatility`, `risk_free_rate`, `evaluation_date`?, `expiration_date`, `option_type` |
+//! | `BarrierOption`  | `initial_price`, `strike_price`, `barrier`, `time_to_expiry`, `risk_free_rate`, `volatility`, `rebate`, `dividend_yield`, `barrier_type` |
+//! | `FixedRateBond`  | `maturity_date`, `coupon_rate`, `coupon_frequency`, `currency`, `face_value`? (`100`), `evaluation_date`?                           |
+//! | `Cash`           | `currency`                                                                                                                          |
+//!
+//! Fields marked `?` are optional. Dates are `YYYY-MM-DD` strings,
+//! currencies are ISO 4217 codes, and enums (`option_type`, `barrier_type`,
+//! `coupon_frequency`) are given by their variant names.
+//!
+//! Only the JSON schema is supported; FpML (XML) documents must be
+//! converted to it first.
+//!
+//! Requires the `serde` feature.
+//!
+//! ```
+//! use RustQuant::instruments::{PricingContext, TradeBlotter};
+//! use time::macros::date;
+//!
+//! let json = r#"{
+//!     "trades": [
+//!         {
+//!             "id": "T1",
+//!             "counterparty": "ACME",
+//!             "quantity": 10,
+//!             "instrument": {
+//!                 "type": "EuropeanOption",
+//!                 "cost_of_carry": 0.05,
+//!                 "underlying_price": 100.0,
+//!                 "strike_price": 100.0,
+//!                 "volatility": 0.2,
+//!                 "risk_free_rate": 0.05,
+//!                 "expiration_date": "2025-01-01",
+//!                 "option_type": "Call"
+//!             }
+//!         },
+//!         {
+//!             "id": "T2",
+//!             "instrument": { "type": "Cash", "currency": "USD" }
+//!         }
+//!     ]
+//! }"#;
+//!
+//! let blotter = TradeBlotter::from_json(json).unwrap();
+//! let ctx = PricingContext::new(date!(2024 - 01 - 01));
+//!
+//! let value = blotter.value(&ctx).unwrap();
+//! ```
+
+use crate::data::YieldCurve;
+use crate::error::RustQuantError;
+use crate::instruments::bonds::coupon_bond::CouponBond;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::options::{BarrierOption, BarrierType, BlackScholesMerton};
+use crate::instruments::PricingContext;
+use crate::time::{DateRollingConvention, Frequency};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A collection of trades.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeBlotter {
+    /// The trades.
+    pub trades: Vec<Trade>,
+}
+
+/// A single trade: an instrument held in some quantity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trade {
+    /// Trade identifier.
+    pub id: String,
+
+    /// Counterparty (optional).
+    #[serde(default)]
+    pub counterparty: Option<String>,
+
+    /// Trade date (optional).
+    #[serde(default)]
+    pub trade_date: Option<Date>,
+
+    /// Quantity (notional multiplier), negative for short positions.
+    #[serde(default = "default_quantity")]
+    pub quantity: f64,
+
+    /// The traded instrument.
+    pub instrument: TradeInstrument,
+}
+
+/// Instruments that can be imported, tagged by `type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TradeInstrument {
+    /// European option (generalised Black-Scholes-Merton).
+    EuropeanOption(BlackScholesMerton),
+
+    /// Barrier option (closed-form).
+    BarrierOption(BarrierTrade),
+
+    /// Fixed rate (coupon) bond.
+    FixedRateBond(FixedRateBondTrade),
+
+    /// Cash in a currency.
+    Cash {
+        /// Currency of the cash.
+        currency: Currency,
+    },
+}
+
+/// Barrier option trade: the option and its barrier type.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BarrierTrade {
+    /// The option.
+    #[serde(flatten)]
+    pub option: BarrierOption,
+
+    /// The barrier type.
+    pub barrier_type: BarrierType,
+}
+
+/// Fixed rate bond trade.
+///
+/// Bonds are discounted on the pricing context's curve for their currency.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FixedRateBondTrade {
+    /// Evaluation date (defaults to the context's valuation date).
+    #[serde(default)]
+    pub evaluation_date: Option<Date>,
+
+    /// Maturity date.
+    pub maturity_date: Date,
+
+    /// Annual coupon rate.
+    pub coupon_rate: f64,
+
+    /// Coupon frequency.
+    pub coupon_frequency: Frequency,
+
+    /// Face value.
+    #[serde(default = "default_face_value")]
+    pub face_value: f64,
+
+    /// Currency of the bond.
+    pub currency: Currency,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+const fn default_quantity() -> f64 {
+    1.0
+}
+
+const fn default_face_value() -> f64 {
+    100.0
+}
+
+impl TradeBlotter {
+    /// Parse a blotter from a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// If the JSON does not match the trade schema.
+    pub fn from_json(json: &str) -> Result<Self, RustQuantError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Read a blotter from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or does not match the trade schema.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, RustQuantError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Write the blotter as (pretty-printed) JSON.
+    ///
+    /// # Errors
+    ///
+    /// If the blotter cannot be serialized.
+    pub fn to_json(&self) -> Result<String, RustQuantError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Total value of the blotter in the context's reporting currency.
+    ///
+    /// # Errors
+    ///
+    /// If any trade cannot be valued.
+    pub fn value(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        self.trades.iter().map(|trade| trade.value(ctx)).sum()
+    }
+}
+
+impl Trade {
+    /// Value of the trade (quantity times instrument value).
+    ///
+    /// # Errors
+    ///
+    /// If the instrument cannot be valued in the context, e.g. a bond
+    /// without a discount curve for its currency, or a missing FX rate.
+    pub fn value(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        Ok(self.quantity * self.instrument.value(ctx)?)
+    }
+}
+
+impl TradeInstrument {
+    /// Value of one unit of the instrument.
+    ///
+    /// # Errors
+    ///
+    /// If the instrument cannot be valued in the context.
+    pub fn value(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        match self {
+            Self::EuropeanOption(option) => ctx.value(option),
+            Self::BarrierOption(trade) => trade.option.price(trade.barrier_type),
+            Self::FixedRateBond(bond) => {
+                if ctx.discount_curve(&bond.currency).is_none() {
+                    return Err(RustQuantError::MissingInput(format!(
+                        "discount curve for {}",
+                        bond.currency.code.alphabetic
+                    )));
+                }
+
+                ctx.value(&bond.to_coupon_bond(ctx.valuation_date))
+            }
+            Self::Cash { currency } => ctx.value(currency),
+        }
+    }
+}
+
+impl FixedRateBondTrade {
+    /// Convert to a [`CouponBond`], evaluated at the trade's evaluation
+    /// date or, if it has none, at `valuation_date`.
+    ///
+    /// The bond has an empty yield curve, so it must be priced against a
+    /// context with a discount curve for its currency.
+    #[must_use]
+    pub fn to_coupon_bond(&self, valuation_date: Date) -> CouponBond {
+        let mut bond = CouponBond {
+            evaluation_date: self.evaluation_date.unwrap_or(valuation_date),
+            expiration_date: self.maturity_date,
+            currency: Some(self.currency),
+            coupon_rate: self.coupon_rate,
+            coupon_frequency: self.coupon_frequency,
+            settlement_convention: DateRollingConvention::Actual,
+            yield_curve: YieldCurve::new(BTreeMap::new()),
+            face_value: self.face_value,
+            coupons: BTreeMap::new(),
+        };
+
+        bond.construct_coupons();
+
+        bond
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_trades {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::Curve;
+    use crate::instruments::Instrument;
+    use crate::iso::USD;
+    use time::macros::date;
+
+    const EPS: f64 = 1e-10;
+
+    const BLOTTER: &str = r#"{
+        "trades": [
+            {
+                "id": "OPT-1",
+                "counterparty": "ACME",
+                "trade_date": "2023-12-15",
+                "quantity": -5,
+                "instrument": {
+                    "type": "EuropeanOption",
+                    "cost_of_carry": 0.05,
+                    "underlying_price": 100.0,
+                    "strike_price": 110.0,
+                    "volatility": 0.2,
+                    "risk_free_rate": 0.05,
+                    "expiration_date": "2025-01-01",
+                    "option_type": "Put"
+                }
+            },
+            {
+                "id": "BAR-1",
+                "instrument": {
+                    "type": "BarrierOption",
+                    "initial_price": 100.0,
+                    "strike_price": 100.0,
+                    "barrier": 120,
+                    "time_to_expiry": 1.0,
+                    "risk_free_rate": 0.05,
+                    "volatility": 0.2,
+                    "rebate": 0.0,
+                    "dividend_yield": 0.0,
+                    "barrier_type": "CUO"
+                }
+            },
+            {
+                "id": "BND-1",
+                "quantity": 1000,
+                "instrument": {
+                    "type": "FixedRateBond",
+                    "maturity_date": "2029-01-01",
+                    "coupon_rate": 0.04,
+                    "coupon_frequency": "SemiAnnually",
+                    "currency": "USD"
+                }
+            },
+            {
+                "id": "CASH-1",
+                "quantity": 250.0,
+                "instrument": { "type": "Cash", "currency": "USD" }
+            }
+        ]
+    }"#;
+
+    fn context() -> PricingContext {
+        // The curve does not extrapolate, so it must cover the first coupon.
+        let t0 = date!(2024 - 01 - 01);
+        let dates =
+            [30, 365, 730, 1095, 1460, 1825, 2190].map(|days| t0 + time::Duration::days(days));
+
+        PricingContext::new(t0).with_discount_curve(
+            USD,
+            YieldCurve::from_dates_and_rates(&dates, &[0.04; 7]).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse_blotter() {
+        let blotter = TradeBlotter::from_json(BLOTTER).unwrap();
+
+        assert_eq!(blotter.trades.len(), 4);
+
+        let option = &blotter.trades[0];
+        assert_eq!(option.id, "OPT-1");
+        assert_eq!(option.counterparty.as_deref(), Some("ACME"));
+        assert_eq!(option.trade_date, Some(date!(2023 - 12 - 15)));
+        assert_eq!(option.quantity, -5.0);
+        assert!(matches!(
+            option.instrument,
+            TradeInstrument::EuropeanOption(_)
+        ));
+
+        // Quantity defaults to one.
+        assert_eq!(blotter.trades[1].quantity, 1.0);
+
+        match &blotter.trades[2].instrument {
+            TradeInstrument::FixedRateBond(bond) => {
+                assert_eq!(bond.face_value, 100.0);
+                assert_eq!(bond.currency, USD);
+            }
+            _ => panic!("expected a fixed rate bond"),
+        }
+    }
+
+    #[test]
+    fn test_value_blotter() {
+        let blotter = TradeBlotter::from_json(BLOTTER).unwrap();
+        let ctx = context();
+
+        let values = blotter
+            .trades
+            .iter()
+            .map(|trade| trade.value(&ctx).unwrap())
+            .collect::<Vec<f64>>();
+
+        let TradeInstrument::EuropeanOption(option) = blotter.trades[0].instrument else {
+            panic!("expected a European option");
+        };
+        let option_value = -5.0 * Instrument::price(&option, &ctx).unwrap();
+        assert_approx_equal!(values[0], option_value, EPS);
+
+        let TradeInstrument::BarrierOption(barrier) = blotter.trades[1].instrument else {
+            panic!("expected a barrier option");
+        };
+        let barrier_value = barrier.option.price(BarrierType::CUO).unwrap();
+        assert_approx_equal!(values[1], barrier_value, EPS);
+
+        // 4% coupon bond discounted at roughly 4%: close to par.
+        let bond_value = values[2] / 1000.0;
+        assert!((bond_value - 100.0).abs() < 2.0);
+
+        assert_approx_equal!(values[3], 250.0, EPS);
+
+        let total = blotter.value(&ctx).unwrap();
+        assert_approx_equal!(total, values.iter().sum::<f64>(), EPS);
+    }
+
+    #[test]
+    fn test_bond_requires_discount_curve() {
+        let blotter = TradeBlotter::from_json(BLOTTER).unwrap();
+        let ctx = PricingContext::new(date!(2024 - 01 - 01));
+
+        assert!(matches!(
+            blotter.trades[2].value(&ctx),
+            Err(RustQuantError::MissingInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let missing_type =
+            r#"{ "trades": [ { "id": "X", "instrument": { "currency": "USD" } } ] }"#;
+        let unknown_type = r#"{ "trades": [ { "id": "X", "instrument": { "type": "Swap" } } ] }"#;
+
+        assert!(matches!(
+            TradeBlotter::from_json(missing_type),
+            Err(RustQuantError::JsonError(_))
+        ));
+        assert!(TradeBlotter::from_json(unknown_type).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let blotter = TradeBlotter::from_json(BLOTTER).unwrap();
+        let json = blotter.to_json().unwrap();
+        let parsed = TradeBlotter::from_json(&json).unwrap();
+
+        let ctx = context();
+        let value = blotter.value(&ctx).unwrap();
+        let parsed_value = parsed.value(&ctx).unwrap();
+
+        assert_approx_equal!(value, parsed_value, EPS);
+        assert_eq!(parsed.trades[0].counterparty.as_deref(), Some("ACME"));
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join("rustquant_test_trades.json");
+        std::fs::write(&path, BLOTTER).unwrap();
+
+        let blotter = TradeBlotter::from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(blotter.trades.len(), 4);
+        assert!(TradeBlotter::from_file("does/not/exist.json").is_err());
+    }
+}
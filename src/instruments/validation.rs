@@ -0,0 +1,203 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Validation of pricing parameters.
+//!
+//! Closed-form pricers happily turn a negative volatility or a `NaN` spot
+//! into a `NaN` price. Instruments implementing [`Validate`] check their
+//! parameter domains up front and report every violation at once:
+//!
+//! ```
+//! use RustQuant::instruments::options::*;
+//! use RustQuant::instruments::Validate;
+//!
+//! let option = BarrierOption {
+//!     initial_price: 0.0,
+//!     strike_price: 100.0,
+//!     barrier: 105.0,
+//!     time_to_expiry: -1.0,
+//!     risk_free_rate: 0.05,
+//!     volatility: f64::NAN,
+//!     rebate: 0.0,
+//!     dividend_yield: 0.01,
+//! };
+//!
+//! let error = option.validate().unwrap_err().to_string();
+//!
+//! assert!(error.contains("initial_price must be positive (got 0)"));
+//! assert!(error.contains("time_to_expiry must be positive (got -1)"));
+//! assert!(error.contains("volatility must be finite (got NaN)"));
+//! ```
+//!
+//! Pricers that validate by default (e.g. [`BarrierOption::price`]) have an
+//! `*_unchecked` counterpart that skips the checks, for hot loops where the
+//! inputs are already known to be valid.
+//!
+//! [`BarrierOption::price`]: crate::instruments::options::BarrierOption::price
+
+use crate::error::RustQuantError;
+use alloc::{format, string::String, vec::Vec};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Parameter domain checks for an instrument or model.
+pub trait Validate {
+    /// Check the parameters, returning [`RustQuantError::InvalidArgument`]
+    /// listing every violated constraint.
+    fn validate(&self) -> Result<(), RustQuantError>;
+
+    /// Validate and return `self`, for chaining (`option.validated()?.price()`).
+    fn validated(self) -> Result<Self, RustQuantError>
+    where
+        Self: Sized,
+    {
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+/// Collects parameter constraint violations.
+///
+/// Each check records a diagnostic naming the parameter, the constraint,
+/// and the offending value; [`Validator::finish`] then reports them all.
+#[derive(Debug, Clone, Default)]
+pub struct Validator {
+    violations: Vec<String>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Validator {
+    /// Create a new validator with no violations.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The value must not be `NaN` or infinite.
+    #[must_use]
+    pub fn finite(mut self, name: &str, value: f64) -> Self {
+        if !value.is_finite() {
+            self.violations
+                .push(format!("{name} must be finite (got {value})"));
+        }
+        self
+    }
+
+    /// The value must be finite and strictly positive.
+    #[must_use]
+    pub fn positive(self, name: &str, value: f64) -> Self {
+        if value.is_finite() {
+            self.check(value > 0.0, || {
+                format!("{name} must be positive (got {value})")
+            })
+        } else {
+            self.finite(name, value)
+        }
+    }
+
+    /// The value must be finite and non-negative.
+    #[must_use]
+    pub fn non_negative(self, name: &str, value: f64) -> Self {
+        if value.is_finite() {
+            self.check(value >= 0.0, || {
+                format!("{name} must be non-negative (got {value})")
+            })
+        } else {
+            self.finite(name, value)
+        }
+    }
+
+    /// Records `message` if `condition` does not hold.
+    #[must_use]
+    pub fn check<M: FnOnce() -> String>(mut self, condition: bool, message: M) -> Self {
+        if !condition {
+            self.violations.push(message());
+        }
+        self
+    }
+
+    /// Returns `true` if no constraint has been violated so far.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// The recorded violations.
+    #[must_use]
+    pub fn violations(&self) -> &[String] {
+        &self.violations
+    }
+
+    /// Finish validation, reporting all violations in a single error.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::InvalidArgument`] if any constraint was violated.
+    pub fn finish(self) -> Result<(), RustQuantError> {
+        if self.violations.is_empty() {
+            Ok(())
+        } else {
+            Err(RustQuantError::InvalidArgument(self.violations.join("; ")))
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_validation {
+    use super::*;
+
+    #[test]
+    fn test_valid_parameters() {
+        let result = Validator::new()
+            .positive("spot", 100.0)
+            .non_negative("rebate", 0.0)
+            .finite("rate", -0.01)
+            .finish();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_collects_all_violations() {
+        let validator = Validator::new()
+            .positive("spot", 0.0)
+            .positive("volatility", f64::NAN)
+            .non_negative("rebate", -1.0)
+            .finite("rate", f64::INFINITY)
+            .check(false, || "barrier must differ from spot".to_string());
+
+        assert!(!validator.is_valid());
+        assert_eq!(
+            validator.violations(),
+            [
+                "spot must be positive (got 0)",
+                "volatility must be finite (got NaN)",
+                "rebate must be non-negative (got -1)",
+                "rate must be finite (got inf)",
+                "barrier must differ from spot",
+            ]
+        );
+
+        match validator.finish() {
+            Err(RustQuantError::InvalidArgument(message)) => {
+                assert_eq!(message.matches("; ").count(), 4);
+            }
+            other => panic!("expected InvalidArgument, got {other:?}"),
+        }
+    }
+}
@@ -33,11 +33,61 @@
 //! ```
 //!
 //! replacing `"*"` with the version number you require, such as `"0.0.17"`.
+//!
+//! # Features
+//!
+//! The larger modules are behind cargo features, all enabled by default:
+//!
+//! | Feature       | Enables                                                      |
+//! |---------------|--------------------------------------------------------------|
+//! | `std`         | Everything outside the analytic core (see below).            |
+//! | `options`     | Option pricers (`instruments::options`). Implies `curves`.   |
+//! | `autodiff`    | Automatic differentiation and the optimisers built on it.    |
+//! | `stochastics` | Stochastic processes and path simulation.                    |
+//! | `curves`      | Curves and surfaces, pricing contexts, bonds, and risk.      |
+//! | `data`        | Market data downloaders (Yahoo! Finance). Implies `options`. |
+//! | `ml`          | Machine learning. Implies `autodiff`.                        |
+//! | `serde`       | Serialization and JSON trade import (not default).           |
+//! | `python`      | Python bindings via PyO3, built with maturin (not default).  |
+//! | `wasm`        | WebAssembly bindings for the browser (not default).          |
+//! | `ffi`         | C interface for the analytic pricers (not default).          |
+//! | `excel`       | Flat spreadsheet function layer (`excel`, not default).      |
+//! | `simd`        | SIMD `exp`, `ln`, and normal CDF kernels (not default).      |
+//! | `gpu`         | Experimental `wgpu` Monte Carlo engine (not default).        |
+//! | `tracing`     | Spans for calibration, MC, and PDE loops (not default).      |
+//! | `cli`         | The `rustquant` command-line pricing tool (not default).     |
+//! | `server`      | REST pricing service on `axum` (not default).                |
+//!
+//! To compile only what you need, disable the defaults:
+//!
+//! ```toml
+//! [dependencies]
+//! RustQuant = { version = "*", default-features = false, features = ["autodiff"] }
+//! ```
+//!
+//! # `no_std`
+//!
+//! Without the `std` feature the crate is `no_std + alloc` and contains only
+//! the analytic core: the closed-form pricers in `instruments::options`
+//! (generalised Black-Scholes-Merton, Bachelier, barrier and binary options),
+//! parameter validation, [`math::Real`], and [`error::RustQuantError`].
+//! Elementary functions then come from [`libm`](https://docs.rs/libm).
+//!
+//! ```toml
+//! [dependencies]
+//! RustQuant = { version = "*", default-features = false }
+//! ```
+//!
+//! The most commonly used items can be imported with
+//! `use RustQuant::prelude::*;`.
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // GLOBAL SETTINGS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+// `no_std + alloc` without the `std` feature (see the crate docs).
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+//
 // Strictly enforce documentation.
 #![forbid(missing_docs)]
 //
@@ -59,20 +109,47 @@
 // RUSTQUANT MODULES
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+extern crate alloc;
+
+#[cfg(feature = "autodiff")]
 pub mod autodiff;
+#[cfg(feature = "std")]
 pub mod data;
 pub mod error;
 pub mod instruments;
+#[cfg(feature = "std")]
 pub mod iso;
 #[macro_use]
 pub mod macros;
+#[cfg(any(
+    feature = "excel",
+    feature = "ffi",
+    feature = "python",
+    feature = "wasm"
+))]
+mod bindings;
+#[cfg(feature = "excel")]
+pub use bindings::excel;
+#[cfg(feature = "std")]
 pub mod cashflows;
 pub mod math;
+#[cfg(feature = "ml")]
 pub mod ml;
+#[cfg(feature = "std")]
 pub mod models;
+#[cfg(feature = "std")]
 pub mod portfolio;
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod risk;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "stochastics")]
 pub mod stochastics;
+#[cfg(feature = "std")]
 pub mod time;
+#[cfg(feature = "std")]
 pub mod trading;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -71,6 +71,52 @@ macro_rules! plot_vector {
     }};
 }
 
+/// Enter a `tracing` span at the given level, with `key = value` fields.
+///
+/// Evaluates to a guard that exits the span when dropped. Without the
+/// `tracing` feature the guard is inert and the fields are not evaluated.
+#[cfg(any(feature = "autodiff", feature = "options", feature = "stochastics"))]
+macro_rules! trace_span {
+    ($level:ident, $name:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        let span = ::tracing::span!(::tracing::Level::$level, $name $(, $key = $value)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let span = {
+            if false {
+                $(let _ = &$value;)*
+            }
+            $crate::macros::NoSpan
+        };
+        span
+    }};
+}
+
+/// Stand-in for an entered span when the `tracing` feature is disabled.
+#[cfg(all(
+    not(feature = "tracing"),
+    any(feature = "autodiff", feature = "options", feature = "stochastics")
+))]
+pub(crate) struct NoSpan;
+
+/// Emit a `tracing` event at the given level, with `key = value` fields.
+///
+/// Without the `tracing` feature this expands to nothing and the fields
+/// are not evaluated.
+#[cfg(any(feature = "autodiff", feature = "options", feature = "stochastics"))]
+macro_rules! trace_event {
+    ($level:ident, $message:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "tracing")]
+        ::tracing::event!(::tracing::Level::$level, $($key = $value,)* $message);
+        #[cfg(not(feature = "tracing"))]
+        if false {
+            $(let _ = &$value;)*
+        }
+    }};
+}
+
+#[cfg(feature = "options")]
+pub(crate) use {trace_event, trace_span};
+
 #[cfg(test)]
 mod tests_plotters {
     use std::f64::EPSILON as EPS;
@@ -116,3 +162,66 @@ mod tests_plotters {
         }
     }
 }
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests_tracing {
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Records span names and event fields.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Recorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.lock().unwrap().push(format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.0.lock().unwrap().push(span.metadata().name().to_string());
+            span.record(&mut self.clone());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut self.clone());
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_spans_and_events_are_recorded() {
+        let recorder = Recorder::default();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let _span = trace_span!(DEBUG, "calibration", max_iterations = 10_usize);
+            trace_event!(DEBUG, "iteration", iteration = 1_usize, error = 0.5);
+        });
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "calibration",
+                "max_iterations=10",
+                "message=iteration",
+                "iteration=1",
+                "error=0.5",
+            ]
+        );
+    }
+}
@@ -35,6 +35,10 @@ pub use gamma::*;
 pub mod gaussian;
 pub use gaussian::*;
 
+/// Bivariate and trivariate Gaussian distribution functions.
+pub mod multivariate_gaussian;
+pub use multivariate_gaussian::*;
+
 /// Poisson distribution.
 pub mod poisson;
 pub use poisson::*;
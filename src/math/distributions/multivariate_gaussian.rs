@@ -0,0 +1,336 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bivariate and trivariate standard normal cumulative distribution
+//! functions, as needed by multi-asset option formulas.
+//!
+//! The bivariate CDF uses Genz's (2004) refinement of the Drezner and
+//! Wesolowsky (1990) method, accurate to about 1e-15. The trivariate CDF
+//! integrates the bivariate CDF of the two remaining variables, conditional
+//! on the first, with [`integrate`](crate::math::integrate), which is
+//! accurate to about 1e-7.
+
+use crate::math::{integrate, Real};
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bivariate standard normal CDF, `P(X <= x, Y <= y)` with
+/// `corr(X, Y) = rho`.
+///
+/// # Examples
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// # use RustQuant::math::distributions::*;
+///
+/// // P(X <= 0, Y <= 0) = 1/4 + asin(rho) / (2 pi).
+/// let expected = 0.25 + 0.5_f64.asin() / (2.0 * std::f64::consts::PI);
+///
+/// assert_approx_equal!(bivariate_normal_cdf(0.0, 0.0, 0.5), expected, 1e-12);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `rho` is not in `[-1, 1]`.
+#[must_use]
+pub fn bivariate_normal_cdf(x: f64, y: f64, rho: f64) -> f64 {
+    assert!((-1.0..=1.0).contains(&rho));
+
+    upper_bivariate_normal(-x, -y, rho)
+}
+
+/// Trivariate standard normal CDF, `P(X <= x, Y <= y, Z <= z)` with the
+/// given pairwise correlations.
+///
+/// # Examples
+/// ```
+/// # use RustQuant::assert_approx_equal;
+/// # use RustQuant::math::distributions::*;
+///
+/// // P(X <= 0, Y <= 0, Z <= 0) = 1/8 + sum(asin(rho)) / (4 pi).
+/// let expected = 0.125 + 3.0 * 0.5_f64.asin() / (4.0 * std::f64::consts::PI);
+///
+/// assert_approx_equal!(
+///     trivariate_normal_cdf(0.0, 0.0, 0.0, 0.5, 0.5, 0.5),
+///     expected,
+///     1e-8
+/// );
+/// ```
+///
+/// # Panics
+///
+/// Panics if a correlation is not in `(-1, 1)`.
+#[must_use]
+pub fn trivariate_normal_cdf(x: f64, y: f64, z: f64, rho_xy: f64, rho_xz: f64, rho_yz: f64) -> f64 {
+    assert!([rho_xy, rho_xz, rho_yz].iter().all(|rho| rho.abs() < 1.0));
+
+    // Condition on the variable least correlated with the other two, which
+    // keeps the conditional bivariate CDF smooth in the integration variable.
+    let candidates = [
+        (
+            rho_xy.abs().max(rho_xz.abs()),
+            [x, y, z],
+            [rho_xy, rho_xz, rho_yz],
+        ),
+        (
+            rho_xy.abs().max(rho_yz.abs()),
+            [y, x, z],
+            [rho_xy, rho_yz, rho_xz],
+        ),
+        (
+            rho_xz.abs().max(rho_yz.abs()),
+            [z, x, y],
+            [rho_xz, rho_yz, rho_xy],
+        ),
+    ];
+    let (_, [a, b, c], [rho_ab, rho_ac, rho_bc]) = candidates
+        .into_iter()
+        .min_by(|p, q| p.0.total_cmp(&q.0))
+        .unwrap();
+
+    let s_ab = (1.0 - rho_ab * rho_ab).sqrt();
+    let s_ac = (1.0 - rho_ac * rho_ac).sqrt();
+    let partial = ((rho_bc - rho_ab * rho_ac) / (s_ab * s_ac)).clamp(-1.0, 1.0);
+
+    // The standard normal density is negligible below -10.
+    const LOWER: f64 = -10.0;
+
+    if a <= LOWER {
+        return 0.0;
+    }
+
+    integrate(
+        |t| {
+            t.norm_pdf()
+                * bivariate_normal_cdf((b - rho_ab * t) / s_ab, (c - rho_ac * t) / s_ac, partial)
+        },
+        LOWER,
+        a,
+    )
+}
+
+/// `P(X > h, Y > k)` with `corr(X, Y) = rho` (Genz's BVND).
+fn upper_bivariate_normal(h: f64, k: f64, rho: f64) -> f64 {
+    let (nodes, weights): (&[f64], &[f64]) = match rho.abs() {
+        r if r < 0.3 => (&GL_NODES_6, &GL_WEIGHTS_6),
+        r if r < 0.75 => (&GL_NODES_12, &GL_WEIGHTS_12),
+        _ => (&GL_NODES_20, &GL_WEIGHTS_20),
+    };
+
+    let mut k = k;
+    let mut hk = h * k;
+
+    if rho.abs() < 0.925 {
+        let hs = (h * h + k * k) / 2.0;
+        let asr = rho.asin();
+
+        let sum: f64 = nodes
+            .iter()
+            .zip(weights)
+            .flat_map(|(x, w)| [(-x, w), (*x, w)])
+            .map(|(x, w)| {
+                let sn = (asr * (x + 1.0) / 2.0).sin();
+                w * ((sn * hk - hs) / (1.0 - sn * sn)).exp()
+            })
+            .sum();
+
+        return sum * asr / (4.0 * PI) + (-h).norm_cdf() * (-k).norm_cdf();
+    }
+
+    if rho < 0.0 {
+        k = -k;
+        hk = -hk;
+    }
+
+    let mut bvn = 0.0;
+
+    if rho.abs() < 1.0 {
+        let a_s = (1.0 - rho) * (1.0 + rho);
+        let a = a_s.sqrt();
+        let bs = (h - k) * (h - k);
+        let c = (4.0 - hk) / 8.0;
+        let d = (12.0 - hk) / 16.0;
+
+        bvn = a
+            * (-(bs / a_s + hk) / 2.0).exp()
+            * (1.0 - c * (bs - a_s) * (1.0 - d * bs / 5.0) / 3.0 + c * d * a_s * a_s / 5.0);
+
+        if hk > -160.0 {
+            let b = bs.sqrt();
+            bvn -= (-hk / 2.0).exp()
+                * (2.0 * PI).sqrt()
+                * (-b / a).norm_cdf()
+                * b
+                * (1.0 - c * bs * (1.0 - d * bs / 5.0) / 3.0);
+        }
+
+        let a = a / 2.0;
+
+        for (x, w) in nodes.iter().zip(weights) {
+            for x in [-x, *x] {
+                let xs = (a * (x + 1.0)).powi(2);
+                let rs = (1.0 - xs).sqrt();
+
+                bvn += a
+                    * w
+                    * ((-bs / (2.0 * xs) - hk / (1.0 + rs)).exp() / rs
+                        - (-(bs / xs + hk) / 2.0).exp() * (1.0 + c * xs * (1.0 + d * xs)));
+            }
+        }
+
+        bvn = -bvn / (2.0 * PI);
+    }
+
+    if rho > 0.0 {
+        bvn + (-h.max(k)).norm_cdf()
+    } else if k > h {
+        -bvn + k.norm_cdf() - h.norm_cdf()
+    } else {
+        -bvn
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// NODES & WEIGHTS
+// Half of the symmetric 6, 12 and 20 point Gauss-Legendre rules on [-1, 1].
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+const GL_NODES_6: [f64; 3] = [
+    -0.932_469_514_203_152,
+    -0.661_209_386_466_264_7,
+    -0.238_619_186_083_197,
+];
+const GL_WEIGHTS_6: [f64; 3] = [
+    0.171_324_492_379_170_5,
+    0.360_761_573_048_138_4,
+    0.467_913_934_572_690_4,
+];
+
+const GL_NODES_12: [f64; 6] = [
+    -0.981_560_634_246_719_1,
+    -0.904_117_256_370_475,
+    -0.769_902_674_194_305,
+    -0.587_317_954_286_617_1,
+    -0.367_831_498_998_180_2,
+    -0.125_233_408_511_469_2,
+];
+const GL_WEIGHTS_12: [f64; 6] = [
+    0.047_175_336_386_511_77,
+    0.106_939_325_995_318_3,
+    0.160_078_328_543_346_4,
+    0.203_167_426_723_065_9,
+    0.233_492_536_538_354_7,
+    0.249_147_045_813_402_9,
+];
+
+const GL_NODES_20: [f64; 10] = [
+    -0.993_128_599_185_094_9,
+    -0.963_971_927_277_913_8,
+    -0.912_234_428_251_326,
+    -0.839_116_971_822_218_8,
+    -0.746_331_906_460_150_8,
+    -0.636_053_680_726_515,
+    -0.510_867_001_950_827_1,
+    -0.373_706_088_715_419_6,
+    -0.227_785_851_141_645_1,
+    -0.076_526_521_133_497_32,
+];
+const GL_WEIGHTS_20: [f64; 10] = [
+    0.017_614_007_139_152_12,
+    0.040_601_429_800_386_94,
+    0.062_672_048_334_109_05,
+    0.083_276_741_576_704_75,
+    0.101_930_119_817_240_4,
+    0.118_194_531_961_518_4,
+    0.131_688_638_449_176_6,
+    0.142_096_109_318_382_1,
+    0.149_172_986_472_603_7,
+    0.152_753_387_130_725_9,
+];
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_multivariate_gaussian {
+    use super::*;
+
+    #[test]
+    fn test_bivariate_normal_cdf() {
+        // Orthant probabilities, across all three quadrature regimes.
+        for rho in [-0.99, -0.8, -0.5, 0.0, 0.2, 0.6, 0.9, 0.95, 0.999] {
+            assert_approx_equal!(
+                bivariate_normal_cdf(0.0, 0.0, rho),
+                0.25 + rho.asin() / (2.0 * PI),
+                1e-14
+            );
+        }
+
+        // Independence and the perfectly correlated limits.
+        assert_approx_equal!(
+            bivariate_normal_cdf(0.3, -0.5, 0.0),
+            0.3_f64.norm_cdf() * (-0.5_f64).norm_cdf(),
+            1e-15
+        );
+        assert_approx_equal!(
+            bivariate_normal_cdf(0.3, -0.5, 1.0),
+            (-0.5_f64).norm_cdf(),
+            1e-15
+        );
+        assert_approx_equal!(bivariate_normal_cdf(0.3, -0.5, -1.0), 0.0, 1e-15);
+
+        // Symmetry in the arguments, and P(X <= x, Y <= y) + P(X <= x, Y > y).
+        for (x, y, rho) in [(0.3, -0.5, 0.2), (1.0, 0.5, -0.6), (-1.0, 0.7, -0.97)] {
+            assert_approx_equal!(
+                bivariate_normal_cdf(x, y, rho),
+                bivariate_normal_cdf(y, x, rho),
+                1e-14
+            );
+            assert_approx_equal!(
+                bivariate_normal_cdf(x, y, rho) + bivariate_normal_cdf(x, -y, -rho),
+                x.norm_cdf(),
+                1e-14
+            );
+        }
+    }
+
+    #[test]
+    fn test_trivariate_normal_cdf() {
+        for (r12, r13, r23) in [(0.5, 0.5, 0.5), (-0.3, 0.2, 0.6), (0.9, 0.8, 0.75)] {
+            assert_approx_equal!(
+                trivariate_normal_cdf(0.0, 0.0, 0.0, r12, r13, r23),
+                0.125 + (r12.asin() + r13.asin() + r23.asin()) / (4.0 * PI),
+                1e-7
+            );
+        }
+
+        // Reduces to the bivariate CDF when the third variable is
+        // independent, or far in the upper tail.
+        assert_approx_equal!(
+            trivariate_normal_cdf(0.5, -0.2, 1.0, 0.3, 0.0, 0.0),
+            bivariate_normal_cdf(0.5, -0.2, 0.3) * 1.0_f64.norm_cdf(),
+            1e-7
+        );
+        assert_approx_equal!(
+            trivariate_normal_cdf(0.5, -0.2, 12.0, 0.3, -0.4, 0.5),
+            bivariate_normal_cdf(0.5, -0.2, 0.3),
+            1e-7
+        );
+
+        // Invariant to the order of the variables.
+        assert_approx_equal!(
+            trivariate_normal_cdf(0.5, -0.2, 1.0, 0.3, -0.4, 0.5),
+            trivariate_normal_cdf(1.0, 0.5, -0.2, -0.4, 0.5, 0.3),
+            1e-7
+        );
+    }
+}
@@ -56,6 +56,36 @@ pub fn fft_complex(x: &[Complex<f64>]) -> Vec<Complex<f64>> {
     result
 }
 
+/// 2-D complex FFT inplace, over the rows and then the columns of `x`.
+/// `x` must be square, with side length a power of 2
+#[allow(clippy::needless_range_loop)]
+pub fn fft_complex_2d_inplace(x: &mut [Vec<Complex<f64>>]) {
+    check_vec_length(x);
+    assert!(
+        x.iter().all(|row| row.len() == x.len()),
+        "2-D FFT can only handle square matrices."
+    );
+
+    for row in x.iter_mut() {
+        fft_complex_calculation(row);
+    }
+
+    let n = x.len();
+    let mut column = vec![Complex::new(0.0, 0.0); n];
+
+    for j in 0..n {
+        for (i, value) in column.iter_mut().enumerate() {
+            *value = x[i][j];
+        }
+
+        fft_complex_calculation(&mut column);
+
+        for (i, value) in column.iter().enumerate() {
+            x[i][j] = *value;
+        }
+    }
+}
+
 /// Helper function to check if a vector length is a power of 2
 #[must_use]
 pub fn is_valid_length<T>(x: &[T]) -> bool {
@@ -203,6 +233,22 @@ mod test {
         assert_real_vecs_almost_equal(&test_vec, &REAL_TEST_SEQUENCE.to_vec());
     }
 
+    #[test]
+    fn test_complex_2d_inplace() {
+        // The 2-D transform of an outer product is the outer product of the
+        // 1-D transforms.
+        let mut test_matrix: Vec<Vec<Complex<f64>>> = COMPLEX_TEST_SEQUENCE
+            .iter()
+            .map(|x| COMPLEX_TEST_SEQUENCE.iter().map(|y| x * y).collect())
+            .collect();
+        fft_complex_2d_inplace(&mut test_matrix);
+
+        for (row, x) in test_matrix.iter().zip(COMPLEX_TEST_RESULT.iter()) {
+            let expected = COMPLEX_TEST_RESULT.iter().map(|y| x * y).collect();
+            assert_complex_vecs_almost_equal(row, &expected);
+        }
+    }
+
     #[test]
     #[should_panic(expected = "FFT can only handle vectors which length is a power of 2.")]
     fn test_invalid_vec_length() {
@@ -7,6 +7,7 @@
 //! ### Optimization and Root Finding
 //!
 //! - [x] Gradient Descent
+//! - [x] Constrained optimization (augmented Lagrangian, bounds)
 //! - [x] Newton-Raphson
 //!
 //! Note: the reason you need to specify the lifetimes and use the type `Variable` is because the gradient descent optimiser uses the `RustQuant::autodiff` module to compute the gradients. This is a slight inconvenience, but the speed-up is enormous when working with functions with many inputs (when compared with using finite-difference quotients).
@@ -63,38 +64,63 @@
 //! - [x] Risk-Reward Measures (Sharpe, Treynor, Sortino, etc)
 
 /// Statistical distributions.
+#[cfg(feature = "std")]
 pub mod distributions;
+#[cfg(feature = "std")]
 pub use distributions::*;
 
 /// Numerical integration routines.
 /// The primary (useful) integrator is the Tanh-Sinh (double exponential) implementation.
+#[cfg(feature = "std")]
 pub mod integration;
+#[cfg(feature = "std")]
 pub use integration::*;
 
 /// Numerical optimization and root-finding routines.
+#[cfg(feature = "autodiff")]
 pub mod optimization;
+#[cfg(feature = "autodiff")]
 pub use optimization::*;
 
+pub mod real;
+pub use real::*;
+
 /// Fast fourier transform.
+#[cfg(feature = "std")]
 pub mod fft;
+#[cfg(feature = "std")]
 pub use fft::*;
 
 /// Interpolation routines.
+#[cfg(feature = "std")]
 pub mod interpolation;
+#[cfg(feature = "std")]
 pub use interpolation::*;
 
 /// Simple risk/reward measures.
+#[cfg(feature = "std")]
 pub mod risk_reward;
+#[cfg(feature = "std")]
 pub use risk_reward::*;
 
 /// Root-finding routines.
+#[cfg(feature = "std")]
 pub mod rootfinding;
+#[cfg(feature = "std")]
 pub use rootfinding::*;
 
 /// Sequences of numbers and associated functions.
+#[cfg(feature = "std")]
 pub mod sequences;
+#[cfg(feature = "std")]
 pub use sequences::*;
 
+/// Vectorised `exp`, `ln`, and normal CDF over slices.
+#[cfg(feature = "std")]
+pub mod simd;
+
 /// Statistic trait.
+#[cfg(feature = "std")]
 pub mod statistic;
+#[cfg(feature = "std")]
 pub use statistic::*;
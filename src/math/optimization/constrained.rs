@@ -0,0 +1,496 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Constrained optimization with linear equality constraints and bounds:
+//!
+//! $$
+//! \min_{x \in \mathbb{R}^n} f(x) \quad \text{s.t.} \quad
+//! a_i^\top x = b_i, \quad l \leq x \leq u
+//! $$
+//!
+//! The equality constraints are handled with an augmented Lagrangian:
+//!
+//! $$
+//! \mathcal{L}_\rho(x, \lambda) = f(x)
+//!     + \sum_i \lambda_i (a_i^\top x - b_i)
+//!     + \frac{\rho}{2} \sum_i (a_i^\top x - b_i)^2
+//! $$
+//!
+//! which is minimised over the box $[l, u]$ with a spectral (Barzilai-Borwein)
+//! projected gradient method. The multipliers are then updated,
+//! $\lambda_i \leftarrow \lambda_i + \rho (a_i^\top x - b_i)$, and the
+//! penalty $\rho$ increased until the constraints are satisfied.
+//!
+//! As with [`GradientDescent`](super::GradientDescent), the gradient of the
+//! objective is computed with the `autodiff` module.
+//!
+//! ```
+//! use RustQuant::autodiff::*;
+//! use RustQuant::math::*;
+//!
+//! // min x^2 + y^2  s.t.  x + y = 1,  0 <= x <= 0.2.
+//! fn f<'v>(x: &[Variable<'v>]) -> Variable<'v> {
+//!     x[0] * x[0] + x[1] * x[1]
+//! }
+//!
+//! let result = ConstrainedOptimizer::new(10_000, 1e-10)
+//!     .with_equality(&[1.0, 1.0], 1.0)
+//!     .with_bounds(&[0.0, f64::NEG_INFINITY], &[0.2, f64::INFINITY])
+//!     .optimize(f, &[0.0, 0.0])
+//!     .unwrap();
+//!
+//! assert!((result.minimizer[0] - 0.2).abs() < 1e-6);
+//! assert!((result.minimizer[1] - 0.8).abs() < 1e-6);
+//! ```
+
+use crate::autodiff::{variables::variable::Variable, Accumulate, Gradient, Graph};
+use crate::error::RustQuantError;
+use std::time::Instant;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Linear equality constraint $a^\top x = b$.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearConstraint {
+    /// Coefficients $a$.
+    pub coefficients: Vec<f64>,
+    /// Right-hand side $b$.
+    pub value: f64,
+}
+
+/// Augmented Lagrangian optimizer for smooth objectives subject to linear
+/// equality constraints and bounds.
+#[derive(Debug, Clone)]
+pub struct ConstrainedOptimizer {
+    /// Maximum number of (projected gradient) iterations.
+    pub max_iterations: usize,
+
+    /// Tolerance for the projected gradient and the constraint violation.
+    pub tolerance: f64,
+
+    /// Linear equality constraints.
+    pub equalities: Vec<LinearConstraint>,
+
+    /// Lower bounds (empty for unbounded).
+    pub lower: Vec<f64>,
+
+    /// Upper bounds (empty for unbounded).
+    pub upper: Vec<f64>,
+}
+
+/// Result of the constrained optimization.
+#[derive(Debug, Clone)]
+pub struct ConstrainedOptimizerResult {
+    /// Minimizer of the function.
+    pub minimizer: Vec<f64>,
+
+    /// Value of the function at the minimum.
+    pub minimum: f64,
+
+    /// Number of iterations.
+    pub iterations: usize,
+
+    /// Largest absolute violation of the equality constraints.
+    pub constraint_violation: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ConstrainedOptimizer {
+    /// New optimizer with no constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tolerance is not positive.
+    #[must_use]
+    pub fn new(max_iterations: usize, tolerance: f64) -> Self {
+        assert!(tolerance > 0.0, "tolerance must be positive");
+
+        Self {
+            max_iterations,
+            tolerance,
+            equalities: Vec::new(),
+            lower: Vec::new(),
+            upper: Vec::new(),
+        }
+    }
+
+    /// Add the equality constraint $a^\top x = b$.
+    #[must_use]
+    pub fn with_equality(mut self, coefficients: &[f64], value: f64) -> Self {
+        self.equalities.push(LinearConstraint {
+            coefficients: coefficients.to_vec(),
+            value,
+        });
+        self
+    }
+
+    /// Set the bounds $l \leq x \leq u$ (use infinities for unbounded
+    /// components).
+    #[must_use]
+    pub fn with_bounds(mut self, lower: &[f64], upper: &[f64]) -> Self {
+        self.lower = lower.to_vec();
+        self.upper = upper.to_vec();
+        self
+    }
+
+    /// Minimise the function, starting from `x0`.
+    ///
+    /// # Errors
+    ///
+    /// - If the dimensions of the constraints or bounds do not match `x0`,
+    ///   or a lower bound exceeds an upper bound.
+    /// - If the equality constraints cannot be satisfied (to within the
+    ///   tolerance) inside the bounds.
+    pub fn optimize<F>(
+        &self,
+        f: F,
+        x0: &[f64],
+    ) -> Result<ConstrainedOptimizerResult, RustQuantError>
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    {
+        let n = x0.len();
+        let (lower, upper) = self.bounds(n)?;
+
+        if let Some(constraint) = self.equalities.iter().find(|c| c.coefficients.len() != n) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "constraint has {} coefficients for {n} variables",
+                constraint.coefficients.len()
+            )));
+        }
+
+        let project = |x: &[f64]| -> Vec<f64> {
+            x.iter()
+                .zip(lower.iter().zip(&upper))
+                .map(|(x, (l, u))| x.clamp(*l, *u))
+                .collect()
+        };
+
+        let residuals = |x: &[f64]| -> Vec<f64> {
+            self.equalities
+                .iter()
+                .map(|c| dot(&c.coefficients, x) - c.value)
+                .collect()
+        };
+
+        let max_abs = |v: &[f64]| v.iter().fold(0.0_f64, |m, v| m.max(v.abs()));
+
+        let mut x = project(x0);
+        let mut multipliers = vec![0.0; self.equalities.len()];
+        let mut penalty = 10.0;
+        let mut violation = max_abs(&residuals(&x));
+        let mut iterations = 0;
+
+        // Beyond this the penalty term swamps the objective.
+        const MAX_PENALTY: f64 = 1e12;
+        const MAX_OUTER_ITERATIONS: usize = 100;
+
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "augmented_lagrangian",
+            variables = n,
+            constraints = self.equalities.len(),
+        );
+
+        for outer in 0..MAX_OUTER_ITERATIONS {
+            if iterations >= self.max_iterations || penalty > MAX_PENALTY {
+                break;
+            }
+
+            // Value and gradient of the augmented Lagrangian.
+            let lagrangian = |x: &[f64]| -> (f64, Vec<f64>) {
+                let (mut value, mut gradient) = evaluate(&f, x);
+
+                for ((constraint, residual), multiplier) in
+                    self.equalities.iter().zip(residuals(x)).zip(&multipliers)
+                {
+                    let weight = multiplier + penalty * residual;
+
+                    value += residual * (multiplier + 0.5 * penalty * residual);
+
+                    for (g, a) in gradient.iter_mut().zip(&constraint.coefficients) {
+                        *g += weight * a;
+                    }
+                }
+
+                (value, gradient)
+            };
+
+            let stationary;
+            (x, stationary) = self.projected_gradient(&lagrangian, &project, x, &mut iterations);
+
+            let previous = violation;
+            let h = residuals(&x);
+            violation = max_abs(&h);
+
+            trace_event!(
+                DEBUG,
+                "outer iteration",
+                outer_iteration = outer + 1,
+                iteration = iterations,
+                error = violation,
+                penalty = penalty,
+                stationary = stationary,
+                elapsed = start.elapsed().as_secs_f64(),
+            );
+
+            if violation < self.tolerance && stationary {
+                break;
+            }
+
+            for (multiplier, residual) in multipliers.iter_mut().zip(&h) {
+                *multiplier += penalty * residual;
+            }
+
+            if violation > 0.25 * previous {
+                penalty *= 10.0;
+            }
+        }
+
+        if violation > self.tolerance.sqrt() {
+            return Err(RustQuantError::ComputationError(format!(
+                "equality constraints violated by {violation:e}; \
+                 they may be infeasible within the bounds"
+            )));
+        }
+
+        Ok(ConstrainedOptimizerResult {
+            minimum: evaluate(&f, &x).0,
+            minimizer: x,
+            iterations,
+            constraint_violation: violation,
+        })
+    }
+
+    /// Lower and upper bounds, expanded to `n` components.
+    fn bounds(&self, n: usize) -> Result<(Vec<f64>, Vec<f64>), RustQuantError> {
+        let expand = |bounds: &[f64], default: f64| match bounds.len() {
+            0 => Ok(vec![default; n]),
+            len if len == n => Ok(bounds.to_vec()),
+            len => Err(RustQuantError::InvalidArgument(format!(
+                "{len} bounds for {n} variables"
+            ))),
+        };
+
+        let lower = expand(&self.lower, f64::NEG_INFINITY)?;
+        let upper = expand(&self.upper, f64::INFINITY)?;
+
+        if lower
+            .iter()
+            .zip(&upper)
+            .any(|(l, u)| l > u || l.is_nan() || u.is_nan())
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "lower bounds must not exceed upper bounds".to_string(),
+            ));
+        }
+
+        Ok((lower, upper))
+    }
+
+    /// Spectral projected gradient method with an Armijo line search.
+    ///
+    /// Returns the final point and whether the projected gradient vanished.
+    fn projected_gradient<L, P>(
+        &self,
+        lagrangian: &L,
+        project: &P,
+        mut x: Vec<f64>,
+        iterations: &mut usize,
+    ) -> (Vec<f64>, bool)
+    where
+        L: Fn(&[f64]) -> (f64, Vec<f64>),
+        P: Fn(&[f64]) -> Vec<f64>,
+    {
+        const MIN_STEP: f64 = 1e-12;
+        const MAX_STEP: f64 = 1e12;
+
+        let (mut value, mut gradient) = lagrangian(&x);
+        let mut step = 1.0;
+
+        while *iterations < self.max_iterations {
+            // Projected gradient (with unit step) as the stationarity measure.
+            let stationarity = project(&sub_scaled(&x, &gradient, 1.0))
+                .iter()
+                .zip(&x)
+                .fold(0.0_f64, |m, (p, x)| m.max((p - x).abs()));
+
+            trace_event!(
+                TRACE,
+                "iteration",
+                iteration = *iterations,
+                error = stationarity,
+                value = value,
+            );
+
+            if stationarity < self.tolerance {
+                return (x, true);
+            }
+
+            *iterations += 1;
+
+            let target = project(&sub_scaled(&x, &gradient, step));
+            let direction: Vec<f64> = target.iter().zip(&x).map(|(t, x)| t - x).collect();
+            let slope = dot(&gradient, &direction);
+
+            let mut t = 1.0;
+            let (candidate, candidate_value, candidate_gradient) = loop {
+                let candidate: Vec<f64> =
+                    x.iter().zip(&direction).map(|(x, d)| x + t * d).collect();
+                let (v, g) = lagrangian(&candidate);
+
+                if v.is_finite() && v <= value + 1e-4 * t * slope {
+                    break (candidate, v, g);
+                }
+
+                t *= 0.5;
+
+                if t < MIN_STEP {
+                    // No further progress possible.
+                    return (x, false);
+                }
+            };
+
+            let s: Vec<f64> = candidate.iter().zip(&x).map(|(a, b)| a - b).collect();
+            let y: Vec<f64> = candidate_gradient
+                .iter()
+                .zip(&gradient)
+                .map(|(a, b)| a - b)
+                .collect();
+            let sy = dot(&s, &y);
+
+            // Barzilai-Borwein step size.
+            step = if sy > 0.0 {
+                (dot(&s, &s) / sy).clamp(MIN_STEP, MAX_STEP)
+            } else {
+                MAX_STEP
+            };
+
+            x = candidate;
+            value = candidate_value;
+            gradient = candidate_gradient;
+        }
+
+        (x, false)
+    }
+}
+
+/// Value and gradient of the objective at `x`.
+fn evaluate<F>(f: &F, x: &[f64]) -> (f64, Vec<f64>)
+where
+    F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+{
+    let graph = Graph::new();
+    let location = graph.vars(x);
+    let function = f(&location);
+
+    (function.value, function.accumulate().wrt(&location))
+}
+
+/// Dot product of two vectors.
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+/// $x - \alpha g$.
+fn sub_scaled(x: &[f64], g: &[f64], alpha: f64) -> Vec<f64> {
+    x.iter().zip(g).map(|(x, g)| x - alpha * g).collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_constrained {
+    use super::*;
+
+    const EPS: f64 = 1e-6;
+
+    fn sphere<'v>(x: &[Variable<'v>]) -> Variable<'v> {
+        x.iter().map(|x| *x * *x).sum()
+    }
+
+    #[test]
+    fn test_unconstrained() {
+        fn f<'v>(x: &[Variable<'v>]) -> Variable<'v> {
+            (x[0] - 1.0) * (x[0] - 1.0) + 10.0 * (x[1] + 2.0) * (x[1] + 2.0)
+        }
+
+        let result = ConstrainedOptimizer::new(10_000, 1e-10)
+            .optimize(f, &[5.0, 5.0])
+            .unwrap();
+
+        assert_approx_equal!(result.minimizer[0], 1.0, EPS);
+        assert_approx_equal!(result.minimizer[1], -2.0, EPS);
+        assert_approx_equal!(result.minimum, 0.0, EPS);
+    }
+
+    #[test]
+    fn test_equality_constraints() {
+        // min |x|^2 s.t. x1 + x2 + x3 = 3, x1 - x3 = 1.
+        let result = ConstrainedOptimizer::new(10_000, 1e-10)
+            .with_equality(&[1.0, 1.0, 1.0], 3.0)
+            .with_equality(&[1.0, 0.0, -1.0], 1.0)
+            .optimize(sphere, &[0.0; 3])
+            .unwrap();
+
+        assert_approx_equal!(result.minimizer[0], 1.5, EPS);
+        assert_approx_equal!(result.minimizer[1], 1.0, EPS);
+        assert_approx_equal!(result.minimizer[2], 0.5, EPS);
+        assert!(result.constraint_violation < 1e-10);
+    }
+
+    #[test]
+    fn test_bounds() {
+        // min |x|^2 s.t. sum x = 1, x1 >= 0.6, x2 <= 0.1.
+        let result = ConstrainedOptimizer::new(10_000, 1e-10)
+            .with_equality(&[1.0, 1.0, 1.0], 1.0)
+            .with_bounds(
+                &[0.6, f64::NEG_INFINITY, f64::NEG_INFINITY],
+                &[f64::INFINITY, 0.1, f64::INFINITY],
+            )
+            .optimize(sphere, &[0.0; 3])
+            .unwrap();
+
+        assert_approx_equal!(result.minimizer[0], 0.6, EPS);
+        assert_approx_equal!(result.minimizer[1], 0.1, EPS);
+        assert_approx_equal!(result.minimizer[2], 0.3, EPS);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let optimizer = ConstrainedOptimizer::new(1_000, 1e-10);
+
+        assert!(optimizer
+            .clone()
+            .with_equality(&[1.0], 1.0)
+            .optimize(sphere, &[0.0, 0.0])
+            .is_err());
+
+        assert!(optimizer
+            .clone()
+            .with_bounds(&[1.0, 0.0], &[0.0, 1.0])
+            .optimize(sphere, &[0.0, 0.0])
+            .is_err());
+
+        // Infeasible: sum x = 3 with 0 <= x <= 1.
+        assert!(optimizer
+            .with_equality(&[1.0, 1.0], 3.0)
+            .with_bounds(&[0.0, 0.0], &[1.0, 1.0])
+            .optimize(sphere, &[0.0, 0.0])
+            .is_err());
+    }
+}
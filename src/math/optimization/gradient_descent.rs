@@ -165,6 +165,13 @@ impl GradientDescent {
             elapsed: start.elapsed(),
         };
 
+        let _span = trace_span!(
+            DEBUG,
+            "gradient_descent",
+            learning_rate = self.learning_rate,
+            max_iterations = self.max_iterations,
+        );
+
         for k in 0..self.max_iterations {
             let graph = Graph::new();
 
@@ -174,6 +181,15 @@ impl GradientDescent {
             let function = f(&location);
             let gradient = function.accumulate().wrt(&location);
 
+            trace_event!(
+                TRACE,
+                "iteration",
+                iteration = k + 1,
+                error = Self::norm(&gradient),
+                value = function.value,
+                elapsed = start.elapsed().as_seconds_f64(),
+            );
+
             if Self::is_stationary(&gradient, tolerance) {
                 break;
             }
@@ -200,16 +216,6 @@ impl GradientDescent {
                     location.iter().map(|x| x.value).collect::<Vec<f64>>()
                 );
             }
-
-            // if max_level() == Level::Info {
-            //     info!(
-            //         "Iter: {:?}, Norm: {}, Func: {:.4?}, X: {:.4?}",
-            //         k + 1,
-            //         Self::norm(&gradient),
-            //         function.value,
-            //         location.iter().map(|x| x.value),
-            //     );
-            // }
         }
 
         result.elapsed = start.elapsed();
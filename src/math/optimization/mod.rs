@@ -10,3 +10,7 @@
 /// Gradient descent method.
 pub mod gradient_descent;
 pub use gradient_descent::*;
+
+/// Constrained optimization (equality constraints and bounds).
+pub mod constrained;
+pub use constrained::*;
@@ -0,0 +1,306 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Generic real scalar type.
+//!
+//! Pricing formulas written against [`Real`] work unchanged for `f64`,
+//! `f32`, reverse-mode autodiff [`Variable`]s and forward-mode [`Dual`]
+//! numbers, so Greeks can be obtained by differentiating the pricer itself.
+//!
+//! Without the `std` feature the `f64` and `f32` implementations use
+//! [`libm`](https://docs.rs/libm), and importing [`Real`] also provides
+//! `x.exp()`, `x.ln()`, `x.sqrt()` and `x.powf(n)` on floats, which `core`
+//! lacks.
+
+#[cfg(feature = "autodiff")]
+use crate::autodiff::{Dual, Variable};
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Real scalar type that pricing formulas can be generic over.
+///
+/// Constants are created with [`Real::constant`], which places them in the
+/// same context as `self` (e.g. on the same autodiff graph).
+pub trait Real:
+    Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// A constant in the same context as `self`.
+    #[must_use]
+    fn constant(self, value: f64) -> Self;
+
+    /// The value as an `f64`.
+    fn value(self) -> f64;
+
+    /// Exponential function.
+    #[must_use]
+    fn exp(self) -> Self;
+
+    /// Natural logarithm.
+    #[must_use]
+    fn ln(self) -> Self;
+
+    /// Square root.
+    #[must_use]
+    fn sqrt(self) -> Self;
+
+    /// Complementary error function.
+    #[must_use]
+    fn erfc(self) -> Self;
+
+    /// Power function, `self^n = exp(n ln(self))`.
+    #[must_use]
+    fn powf(self, n: Self) -> Self {
+        (n * self.ln()).exp()
+    }
+
+    /// Standard normal cumulative distribution function.
+    #[must_use]
+    fn norm_cdf(self) -> Self {
+        // Same form as `Gaussian::cdf`, to avoid cancellation in the tails.
+        self.constant(0.5) * (-(self * self.constant(FRAC_1_SQRT_2))).erfc()
+    }
+
+    /// Standard normal probability density function.
+    #[must_use]
+    fn norm_pdf(self) -> Self {
+        (-(self * self) * self.constant(0.5)).exp() * self.constant(1.0 / (2.0 * PI).sqrt())
+    }
+}
+
+impl Real for f64 {
+    fn constant(self, value: f64) -> Self {
+        value
+    }
+
+    fn value(self) -> f64 {
+        self
+    }
+
+    #[cfg(feature = "std")]
+    fn exp(self) -> Self {
+        f64::exp(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn ln(self) -> Self {
+        f64::ln(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn erfc(self) -> Self {
+        errorfunctions::RealErrorFunctions::erfc(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn powf(self, n: Self) -> Self {
+        f64::powf(self, n)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn exp(self) -> Self {
+        libm::exp(self)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn ln(self) -> Self {
+        libm::log(self)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn sqrt(self) -> Self {
+        libm::sqrt(self)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn erfc(self) -> Self {
+        libm::erfc(self)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn powf(self, n: Self) -> Self {
+        libm::pow(self, n)
+    }
+}
+
+impl Real for f32 {
+    fn constant(self, value: f64) -> Self {
+        value as f32
+    }
+
+    fn value(self) -> f64 {
+        f64::from(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn exp(self) -> Self {
+        f32::exp(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn ln(self) -> Self {
+        f32::ln(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn sqrt(self) -> Self {
+        f32::sqrt(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn erfc(self) -> Self {
+        errorfunctions::RealErrorFunctions::erfc(f64::from(self)) as f32
+    }
+
+    #[cfg(feature = "std")]
+    fn powf(self, n: Self) -> Self {
+        f32::powf(self, n)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn exp(self) -> Self {
+        libm::expf(self)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn ln(self) -> Self {
+        libm::logf(self)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn sqrt(self) -> Self {
+        libm::sqrtf(self)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn erfc(self) -> Self {
+        libm::erfc(f64::from(self)) as f32
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn powf(self, n: Self) -> Self {
+        libm::powf(self, n)
+    }
+}
+
+#[cfg(feature = "autodiff")]
+impl<'v> Real for Variable<'v> {
+    fn constant(self, value: f64) -> Self {
+        self.graph.var(value)
+    }
+
+    fn value(self) -> f64 {
+        self.value
+    }
+
+    fn exp(self) -> Self {
+        Variable::exp(self)
+    }
+
+    fn ln(self) -> Self {
+        Variable::ln(self)
+    }
+
+    fn sqrt(self) -> Self {
+        Variable::sqrt(self)
+    }
+
+    fn erfc(self) -> Self {
+        Variable::erfc(self)
+    }
+}
+
+#[cfg(feature = "autodiff")]
+impl Real for Dual {
+    fn constant(self, value: f64) -> Self {
+        Dual::constant(value)
+    }
+
+    fn value(self) -> f64 {
+        self.value
+    }
+
+    fn exp(self) -> Self {
+        Dual::exp(self)
+    }
+
+    fn ln(self) -> Self {
+        Dual::ln(self)
+    }
+
+    fn sqrt(self) -> Self {
+        Dual::sqrt(self)
+    }
+
+    fn erfc(self) -> Self {
+        Dual::erfc(self)
+    }
+}
+
+#[cfg(test)]
+mod tests_real {
+    use super::*;
+    #[cfg(feature = "autodiff")]
+    use crate::autodiff::{Accumulate, Gradient, Graph};
+    use crate::math::distributions::{Distribution, Gaussian};
+
+    fn f<R: Real>(x: R) -> R {
+        x.powf(x.constant(1.5)) * x.norm_cdf() + x.norm_pdf()
+    }
+
+    #[test]
+    fn test_norm_matches_gaussian() {
+        let n = Gaussian::default();
+
+        for x in [-5.0, -1.0, 0.0, 0.3, 2.5] {
+            assert_approx_equal!(Real::norm_cdf(x), n.cdf(x), 1e-15);
+            assert_approx_equal!(Real::norm_pdf(x), n.pdf(x), 1e-15);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "autodiff")]
+    fn test_scalar_types_agree() {
+        let x = 1.3;
+        let expected = f(x);
+
+        assert_approx_equal!(f(x as f32).value(), expected, 1e-6);
+        assert_approx_equal!(f(Dual::variable(x)).value, expected, 1e-15);
+
+        let graph = Graph::new();
+        assert_approx_equal!(f(graph.var(x)).value, expected, 1e-15);
+    }
+
+    #[test]
+    #[cfg(feature = "autodiff")]
+    fn test_forward_and_reverse_derivatives_agree() {
+        let x = 1.3;
+        let h = 1e-6;
+
+        let forward = f(Dual::variable(x)).derivative;
+
+        let graph = Graph::new();
+        let var = graph.var(x);
+        let reverse = f(var).accumulate().wrt(&var);
+
+        let finite_difference = (f(x + h) - f(x - h)) / (2.0 * h);
+
+        assert_approx_equal!(forward, reverse, 1e-12);
+        assert_approx_equal!(forward, finite_difference, 1e-8);
+    }
+}
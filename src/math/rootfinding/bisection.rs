@@ -7,6 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::error::RustQuantError;
 use crate::math::rootfinder::{Rootfinder, RootfinderData};
 
 /// Bisection root-finding algorithm.
@@ -45,7 +46,7 @@ where
         0.0
     }
 
-    fn solve_impl(&mut self) -> f64 {
+    fn solve_impl(&mut self) -> Result<f64, RustQuantError> {
         let mut dx: f64;
         let mut x_mid: f64;
         let mut f_mid: f64;
@@ -69,15 +70,22 @@ where
                 self.data.root = x_mid;
             }
             if dx.abs() < self.data.accuracy || RootfinderData::close(f_mid, 0.0) {
-                return self.data.root;
+                return Ok(self.data.root);
             }
         }
 
-        0.0
+        Err(RustQuantError::NonConvergence(format!(
+            "no root found within {} iterations",
+            Self::MAX_ITERATIONS
+        )))
     }
 
-    fn solve(&mut self) -> f64 {
-        assert!(self.data.accuracy > 0., "accuracy must be positive");
+    fn solve(&mut self) -> Result<f64, RustQuantError> {
+        if self.data.accuracy <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "accuracy must be positive".to_string(),
+            ));
+        }
 
         self.data.accuracy = f64::max(self.data.accuracy, f64::EPSILON);
 
@@ -88,7 +96,7 @@ where
         self.data.y_max = self.value(self.data.root);
 
         if RootfinderData::close(self.data.y_max, 0.0) {
-            return self.data.root;
+            return Ok(self.data.root);
         } else if self.data.y_max > 0.0 {
             self.data.x_min = self
                 .data
@@ -110,10 +118,10 @@ where
             // Check if we can solve.
             if self.data.y_min * self.data.y_max <= 0.0 {
                 if RootfinderData::close(self.data.y_min, 0.0) {
-                    return self.data.x_min;
+                    return Ok(self.data.x_min);
                 }
                 if RootfinderData::close(self.data.y_max, 0.0) {
-                    return self.data.x_max;
+                    return Ok(self.data.x_max);
                 }
                 self.data.root = 0.5 * (self.data.x_max + self.data.x_min);
 
@@ -149,7 +157,10 @@ where
             self.data.increment_evaluation_count();
         }
 
-        0.0
+        Err(RustQuantError::NonConvergence(format!(
+            "unable to bracket a root within {} iterations",
+            Self::MAX_ITERATIONS
+        )))
     }
 }
 
@@ -175,13 +186,13 @@ mod TESTS_bisection_solver {
         //          - Interval enforced: true
         let data = RootfinderData::new(1e-15, 1e-5, 0.0, 2.0, true);
         let mut solver = Bisection::new(f, 1.0, data);
-        let root = solver.solve();
+        let root = solver.solve().unwrap();
         assert!((root - SQRT_2) < 1e-15);
 
         // let n = 1_000_000;
         // let start = std::time::Instant::now();
         // for _ in 0..n {
-        //     solver.solve();
+        //     solver.solve().unwrap();
         // }
         // let duration = start.elapsed();
 
@@ -7,6 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::error::RustQuantError;
 use crate::math::rootfinder::{Rootfinder, RootfinderData};
 
 /// Newton-Raphson root-finding algorithm.
@@ -50,7 +51,7 @@ where
         (self.derivative)(x)
     }
 
-    fn solve_impl(&mut self) -> f64 {
+    fn solve_impl(&mut self) -> Result<f64, RustQuantError> {
         let mut froot: f64;
         let mut dfroot: f64;
         let mut dx: f64;
@@ -92,7 +93,7 @@ where
             }
 
             if dx.abs() < self.data.accuracy {
-                return self.data.root;
+                return Ok(self.data.root);
             }
 
             froot = self.value(self.data.root);
@@ -106,11 +107,18 @@ where
             }
         }
 
-        0.0
+        Err(RustQuantError::NonConvergence(format!(
+            "no root found within {} iterations",
+            Self::MAX_ITERATIONS
+        )))
     }
 
-    fn solve(&mut self) -> f64 {
-        assert!(self.data.accuracy > 0., "accuracy must be positive");
+    fn solve(&mut self) -> Result<f64, RustQuantError> {
+        if self.data.accuracy <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "accuracy must be positive".to_string(),
+            ));
+        }
 
         self.data.accuracy = f64::max(self.data.accuracy, f64::EPSILON);
 
@@ -121,7 +129,7 @@ where
         self.data.y_max = self.value(self.data.root);
 
         if RootfinderData::close(self.data.y_max, 0.0) {
-            return self.data.root;
+            return Ok(self.data.root);
         } else if self.data.y_max > 0.0 {
             self.data.x_min = self
                 .data
@@ -143,10 +151,10 @@ where
             // Check if we can solve.
             if self.data.y_min * self.data.y_max <= 0.0 {
                 if RootfinderData::close(self.data.y_min, 0.0) {
-                    return self.data.x_min;
+                    return Ok(self.data.x_min);
                 }
                 if RootfinderData::close(self.data.y_max, 0.0) {
-                    return self.data.x_max;
+                    return Ok(self.data.x_max);
                 }
                 self.data.root = 0.5 * (self.data.x_max + self.data.x_min);
 
@@ -182,7 +190,10 @@ where
             self.data.increment_evaluation_count();
         }
 
-        0.0
+        Err(RustQuantError::NonConvergence(format!(
+            "unable to bracket a root within {} iterations",
+            Self::MAX_ITERATIONS
+        )))
     }
 }
 
@@ -200,7 +211,7 @@ mod TESTS_newton_raphson_solver {
 
         let data = RootfinderData::new(1e-15, 1e-5, 0.0, 2.0, true);
         let mut solver = NewtonRaphson::new(f, df, 1.0, data);
-        let root = solver.solve();
+        let root = solver.solve().unwrap();
         assert!((root - SQRT_2) < 1e-15);
 
         // // 1 million iterations
@@ -208,7 +219,7 @@ mod TESTS_newton_raphson_solver {
 
         // let start = std::time::Instant::now();
         // for _ in 0..n {
-        //     solver.solve();
+        //     solver.solve().unwrap();
         // }
         // let duration = start.elapsed();
 
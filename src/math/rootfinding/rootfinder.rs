@@ -7,6 +7,8 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::error::RustQuantError;
+
 /// Root-finding base trait.
 pub trait Rootfinder<F>
 where
@@ -25,10 +27,14 @@ where
     fn derivative(&self, x: f64) -> f64;
 
     /// Solve the root-finding problem (back-end implementation).
-    fn solve_impl(&mut self) -> f64;
+    fn solve_impl(&mut self) -> Result<f64, RustQuantError>;
 
     /// Solve the root-finding problem (front-end, performs mostly checks).
-    fn solve(&mut self) -> f64;
+    ///
+    /// Returns [`RustQuantError::InvalidArgument`] if the accuracy is not
+    /// positive, and [`RustQuantError::NonConvergence`] if no root is found
+    /// within [`Rootfinder::MAX_ITERATIONS`] iterations.
+    fn solve(&mut self) -> Result<f64, RustQuantError>;
 }
 
 /// Root-finder data.
@@ -0,0 +1,424 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Regression trees (CART).
+//!
+//! A tree is grown by recursively splitting the samples on the feature and
+//! threshold that most reduce the sum of squared errors, until the maximum
+//! depth is reached or a split would leave fewer than the minimum number of
+//! samples in a leaf. Each leaf predicts the mean of its targets.
+//!
+//! Trees are the building blocks of the [`RandomForest`](crate::ml::RandomForest)
+//! and [`GradientBoosting`](crate::ml::GradientBoosting) ensembles.
+//!
+//! ```
+//! use RustQuant::ml::*;
+//! use nalgebra::{DMatrix, DVector};
+//!
+//! // A step function of the first feature.
+//! let x = DMatrix::from_fn(100, 2, |i, j| if j == 0 { i as f64 } else { (i % 7) as f64 });
+//! let y = DVector::from_fn(100, |i, _| if i < 50 { -1.0 } else { 1.0 });
+//!
+//! let tree = DecisionTree::fit(&x, &y, &TreeConfig::new(2)).unwrap();
+//! let prediction = tree.predict(&DMatrix::from_row_slice(2, 2, &[10.0, 3.0, 80.0, 3.0])).unwrap();
+//!
+//! assert_eq!(prediction.as_slice(), &[-1.0, 1.0]);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, seq::index::sample, SeedableRng};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Tree growing hyper-parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeConfig {
+    /// Maximum depth of the tree (a single leaf has depth zero).
+    pub max_depth: usize,
+    /// Minimum number of samples in each leaf.
+    pub min_samples_leaf: usize,
+    /// Number of features considered at each split (all of them if `None`).
+    pub max_features: Option<usize>,
+    /// Seed for choosing the features considered at each split.
+    pub seed: u64,
+}
+
+/// A node of a regression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeNode {
+    /// Terminal node predicting a constant.
+    Leaf {
+        /// The prediction (mean target of the samples in the leaf).
+        value: f64,
+    },
+    /// Internal node sending samples with `x[feature] <= threshold` left.
+    Split {
+        /// Index of the feature.
+        feature: usize,
+        /// Split threshold.
+        threshold: f64,
+        /// Index of the left child.
+        left: usize,
+        /// Index of the right child.
+        right: usize,
+    },
+}
+
+/// Regression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionTree {
+    /// Nodes of the tree, with the root first.
+    pub nodes: Vec<TreeNode>,
+    /// Number of features the tree was fitted on.
+    pub n_features: usize,
+    /// Total reduction in the sum of squared errors from splits on each
+    /// feature.
+    pub importances: Vec<f64>,
+}
+
+/// Best split found for a node.
+struct BestSplit {
+    feature: usize,
+    threshold: f64,
+    gain: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl TreeConfig {
+    /// New configuration with the given maximum depth, one sample per leaf,
+    /// and all features considered at each split.
+    #[must_use]
+    pub const fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            min_samples_leaf: 1,
+            max_features: None,
+            seed: 0,
+        }
+    }
+
+    /// Set the minimum number of samples in each leaf.
+    #[must_use]
+    pub const fn with_min_samples_leaf(mut self, min_samples_leaf: usize) -> Self {
+        self.min_samples_leaf = min_samples_leaf;
+        self
+    }
+
+    /// Set the number of features considered at each split.
+    #[must_use]
+    pub const fn with_max_features(mut self, max_features: usize) -> Self {
+        self.max_features = Some(max_features);
+        self
+    }
+
+    /// Set the seed for choosing the features considered at each split.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Check that the data has one target per row, at least one feature, and
+/// finite values.
+pub(crate) fn check_data(x: &DMatrix<f64>, y: &DVector<f64>) -> Result<(), RustQuantError> {
+    if x.nrows() == 0 || x.ncols() == 0 || x.nrows() != y.len() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "need a non-empty design matrix with one row per target, got {:?} for {} targets",
+            x.shape(),
+            y.len()
+        )));
+    }
+    if x.iter().chain(y.iter()).any(|v| !v.is_finite()) {
+        return Err(RustQuantError::InvalidArgument(
+            "features and targets must be finite".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+impl DecisionTree {
+    /// Fit a regression tree to the rows of `x` and the targets `y`.
+    ///
+    /// # Errors
+    ///
+    /// If the data is empty, the dimensions do not match, the values are not
+    /// finite, or `min_samples_leaf` or `max_features` is zero.
+    pub fn fit(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        config: &TreeConfig,
+    ) -> Result<Self, RustQuantError> {
+        check_data(x, y)?;
+
+        Self::fit_samples(x, y, &(0..x.nrows()).collect::<Vec<_>>(), config)
+    }
+
+    /// Fit a regression tree to the given rows (with repetition) of the data.
+    pub(crate) fn fit_samples(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        samples: &[usize],
+        config: &TreeConfig,
+    ) -> Result<Self, RustQuantError> {
+        if config.min_samples_leaf == 0 || config.max_features == Some(0) {
+            return Err(RustQuantError::InvalidArgument(
+                "min_samples_leaf and max_features must be positive".to_string(),
+            ));
+        }
+
+        let mut tree = Self {
+            nodes: Vec::new(),
+            n_features: x.ncols(),
+            importances: vec![0.0; x.ncols()],
+        };
+        let mut rng = StdRng::seed_from_u64(config.seed);
+
+        tree.grow(x, y, samples.to_vec(), 0, config, &mut rng);
+
+        Ok(tree)
+    }
+
+    /// Grow the subtree for the samples, returning the index of its root.
+    fn grow(
+        &mut self,
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        samples: Vec<usize>,
+        depth: usize,
+        config: &TreeConfig,
+        rng: &mut StdRng,
+    ) -> usize {
+        let index = self.nodes.len();
+        let mean = samples.iter().map(|i| y[*i]).sum::<f64>() / samples.len() as f64;
+        self.nodes.push(TreeNode::Leaf { value: mean });
+
+        if depth >= config.max_depth || samples.len() < 2 * config.min_samples_leaf {
+            return index;
+        }
+
+        let features: Vec<usize> = match config.max_features {
+            Some(m) if m < x.ncols() => sample(rng, x.ncols(), m).into_vec(),
+            _ => (0..x.ncols()).collect(),
+        };
+
+        let Some(split) = best_split(x, y, &samples, &features, config.min_samples_leaf) else {
+            return index;
+        };
+
+        self.importances[split.feature] += split.gain;
+
+        let (left, right): (Vec<usize>, Vec<usize>) = samples
+            .into_iter()
+            .partition(|i| x[(*i, split.feature)] <= split.threshold);
+
+        let left = self.grow(x, y, left, depth + 1, config, rng);
+        let right = self.grow(x, y, right, depth + 1, config, rng);
+
+        self.nodes[index] = TreeNode::Split {
+            feature: split.feature,
+            threshold: split.threshold,
+            left,
+            right,
+        };
+
+        index
+    }
+
+    /// Depth of the tree.
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        fn depth(nodes: &[TreeNode], node: usize) -> usize {
+            match nodes[node] {
+                TreeNode::Leaf { .. } => 0,
+                TreeNode::Split { left, right, .. } => {
+                    1 + depth(nodes, left).max(depth(nodes, right))
+                }
+            }
+        }
+
+        depth(&self.nodes, 0)
+    }
+
+    /// Number of leaves.
+    #[must_use]
+    pub fn n_leaves(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| matches!(node, TreeNode::Leaf { .. }))
+            .count()
+    }
+
+    /// Prediction for a single sample.
+    fn predict_row<'a>(&self, row: impl Fn(usize) -> f64 + 'a) -> f64 {
+        let mut node = 0;
+
+        loop {
+            match self.nodes[node] {
+                TreeNode::Leaf { value } => return value,
+                TreeNode::Split {
+                    feature,
+                    threshold,
+                    left,
+                    right,
+                } => {
+                    node = if row(feature) <= threshold {
+                        left
+                    } else {
+                        right
+                    }
+                }
+            }
+        }
+    }
+
+    /// Predictions for the rows of `x`.
+    ///
+    /// # Errors
+    ///
+    /// If `x` does not have one column per feature.
+    pub fn predict(&self, x: &DMatrix<f64>) -> Result<DVector<f64>, RustQuantError> {
+        if x.ncols() != self.n_features {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "expected {} features, got {}",
+                self.n_features,
+                x.ncols()
+            )));
+        }
+
+        Ok(DVector::from_fn(x.nrows(), |i, _| {
+            self.predict_row(|j| x[(i, j)])
+        }))
+    }
+}
+
+/// Best split of the samples over the given features, if any split leaves
+/// at least `min_samples_leaf` samples on each side and reduces the error.
+fn best_split(
+    x: &DMatrix<f64>,
+    y: &DVector<f64>,
+    samples: &[usize],
+    features: &[usize],
+    min_samples_leaf: usize,
+) -> Option<BestSplit> {
+    let n = samples.len();
+    let total = samples.iter().map(|i| y[*i]).sum::<f64>();
+    let mut best: Option<BestSplit> = None;
+
+    for &feature in features {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| x[(*a, feature)].total_cmp(&x[(*b, feature)]));
+
+        // The reduction in the sum of squared errors from splitting after
+        // position k is S_L^2 / n_L + S_R^2 / n_R - S^2 / n.
+        let mut left_sum = 0.0;
+
+        for k in 0..n - 1 {
+            left_sum += y[sorted[k]];
+
+            let (n_left, n_right) = (k + 1, n - k - 1);
+            let (here, next) = (x[(sorted[k], feature)], x[(sorted[k + 1], feature)]);
+
+            if n_left < min_samples_leaf || n_right < min_samples_leaf || here == next {
+                continue;
+            }
+
+            let right_sum = total - left_sum;
+            let gain = left_sum * left_sum / n_left as f64 + right_sum * right_sum / n_right as f64
+                - total * total / n as f64;
+
+            if gain
+                > best
+                    .as_ref()
+                    .map_or(1e-12 * total.abs().max(1.0), |b| b.gain)
+            {
+                best = Some(BestSplit {
+                    feature,
+                    threshold: 0.5 * (here + next),
+                    gain,
+                });
+            }
+        }
+    }
+
+    best
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_decision_tree {
+    use super::*;
+
+    #[test]
+    fn test_fits_piecewise_constant() {
+        // y depends on the second feature only, with three levels.
+        let x = DMatrix::from_fn(
+            90,
+            2,
+            |i, j| if j == 0 { (i % 10) as f64 } else { i as f64 },
+        );
+        let y = DVector::from_fn(90, |i, _| (i / 30) as f64);
+
+        let tree = DecisionTree::fit(&x, &y, &TreeConfig::new(5)).unwrap();
+
+        assert_eq!(tree.predict(&x).unwrap(), y);
+        assert_eq!(tree.n_leaves(), 3);
+        assert_eq!(tree.depth(), 2);
+        assert_eq!(tree.importances[0], 0.0);
+        assert!(tree.importances[1] > 0.0);
+    }
+
+    #[test]
+    fn test_stopping_rules() {
+        let x = DMatrix::from_fn(64, 1, |i, _| i as f64);
+        let y = x.column(0).map(|v| v * v);
+
+        let stump = DecisionTree::fit(&x, &y, &TreeConfig::new(1)).unwrap();
+        assert_eq!(stump.n_leaves(), 2);
+
+        let full = DecisionTree::fit(&x, &y, &TreeConfig::new(100)).unwrap();
+        assert_eq!(full.n_leaves(), 64);
+
+        let pruned =
+            DecisionTree::fit(&x, &y, &TreeConfig::new(100).with_min_samples_leaf(8)).unwrap();
+        assert!(pruned.n_leaves() <= 8);
+
+        // A constant target cannot be split.
+        let constant = DecisionTree::fit(&x, &DVector::from_element(64, 1.0), &TreeConfig::new(5));
+        assert_eq!(constant.unwrap().n_leaves(), 1);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let x = DMatrix::from_element(4, 2, 1.0);
+        let y = DVector::from_element(4, 1.0);
+
+        assert!(
+            DecisionTree::fit(&x, &DVector::from_element(3, 1.0), &TreeConfig::new(2)).is_err()
+        );
+        assert!(DecisionTree::fit(&x, &y, &TreeConfig::new(2).with_min_samples_leaf(0)).is_err());
+
+        let tree = DecisionTree::fit(&x, &y, &TreeConfig::new(2)).unwrap();
+        assert!(tree.predict(&DMatrix::from_element(1, 3, 1.0)).is_err());
+    }
+}
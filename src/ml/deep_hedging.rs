@@ -0,0 +1,640 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Deep hedging (Buehler, Gonon, Teichmann and Wood, 2019).
+//!
+//! A [`NeuralNetwork`] maps the state at each rebalancing date, i.e. the
+//! log-moneyness $\ln(S_k / S_{ref})$, the time to maturity, and the
+//! position held so far, to the new position in the underlying. The hedger
+//! sells a claim with payoff $Z$ and trades along each path, paying a
+//! proportional cost $c$ on every trade and on the final unwind, so its
+//! terminal hedging loss is
+//!
+//! $$
+//! L = Z(S_N) - \sum_k \delta_k (S_{k+1} - S_k)
+//!     + c \sum_k |\delta_k - \delta_{k-1}| S_k + c |\delta_{N-1}| S_N.
+//! $$
+//!
+//! The network is trained to minimise a convex risk measure $\rho(L)$ over
+//! simulated paths: the expected shortfall, through the Rockafellar-Uryasev
+//! representation $\min_w \, w + E[(L - w)^+] / (1 - \alpha)$ with $w$
+//! trained alongside the network, or the entropic risk
+//! $\frac{1}{\lambda} \ln E[e^{\lambda L}]$. The whole strategy, one
+//! network call per date and path, is built on an `autodiff` graph per
+//! mini-batch, so one reverse sweep gives the gradient with respect to every
+//! weight, and the weights are updated with Adam. The minimised risk is the
+//! indifference price of the claim, which is the premium the hedger needs
+//! to be indifferent to selling it.
+//!
+//! Rates are zero, so prices are in units of the numeraire.
+//!
+//! ```
+//! use RustQuant::ml::*;
+//! use RustQuant::trading::delta_hedging::geometric_brownian_paths;
+//!
+//! let paths = geometric_brownian_paths(100.0, 0.0, 0.2, 0.25, 5, 100, 42);
+//! let call = |s: f64| (s - 100.0).max(0.0);
+//!
+//! let mut hedger = DeepHedger::new(100.0, 0.25, &[8], 1).with_costs(0.001);
+//! let objective = HedgingObjective::ExpectedShortfall { confidence: 0.9 };
+//! let config = DeepHedgingConfig::new(objective, 0.02, 20);
+//!
+//! let losses = hedger.train(&paths, call, &config).unwrap();
+//! let price = hedger.risk(&paths, call, objective).unwrap();
+//!
+//! assert_eq!(losses.len(), 20);
+//! assert!(price.is_finite());
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::neural_network::{ADAM_EPS, BETA_1, BETA_2};
+use crate::autodiff::{Accumulate, Gradient, Graph, Variable};
+use crate::error::RustQuantError;
+use crate::math::Statistic;
+use crate::ml::{Activation, ActivationFunction, NeuralNetwork};
+use crate::risk::value_at_risk::check_confidence;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Risk measure of the terminal hedging loss minimised by the hedger.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HedgingObjective {
+    /// Expected shortfall (CVaR) of the loss at the given confidence level.
+    ExpectedShortfall {
+        /// Confidence level, in (0, 1).
+        confidence: f64,
+    },
+    /// Entropic risk $\frac{1}{\lambda} \ln E[e^{\lambda L}]$, the
+    /// indifference price under exponential utility.
+    Entropic {
+        /// Absolute risk aversion $\lambda$, per unit of currency.
+        risk_aversion: f64,
+    },
+}
+
+/// Neural network hedging strategy.
+#[derive(Debug, Clone)]
+pub struct DeepHedger {
+    /// Policy network, with inputs (log-moneyness, time to maturity,
+    /// previous position) and the new position as its single output.
+    pub network: NeuralNetwork,
+    /// Reference spot price: prices are divided by it inside the network.
+    pub reference_spot: f64,
+    /// Maturity of the hedged claim, in years.
+    pub maturity: f64,
+    /// Transaction cost as a fraction of the traded notional.
+    pub proportional_cost: f64,
+}
+
+/// Training hyper-parameters of the [`DeepHedger`].
+#[derive(Debug, Clone, Copy)]
+pub struct DeepHedgingConfig {
+    /// Risk measure to minimise.
+    pub objective: HedgingObjective,
+    /// Adam step size.
+    pub learning_rate: f64,
+    /// Number of passes over the paths.
+    pub epochs: usize,
+    /// Mini-batch size, in paths (all paths by default).
+    pub batch_size: usize,
+    /// Seed for shuffling the paths between epochs.
+    pub seed: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl HedgingObjective {
+    fn validate(self) -> Result<(), RustQuantError> {
+        match self {
+            Self::ExpectedShortfall { confidence } => check_confidence(confidence),
+            Self::Entropic { risk_aversion } => match risk_aversion > 0.0 {
+                true => Ok(()),
+                false => Err(RustQuantError::InvalidArgument(
+                    "Risk aversion must be positive.".to_string(),
+                )),
+            },
+        }
+    }
+
+    /// Risk of the empirical distribution of `losses`.
+    ///
+    /// # Errors
+    ///
+    /// If `losses` is empty, or the objective's parameter is invalid.
+    pub fn evaluate(self, losses: &[f64]) -> Result<f64, RustQuantError> {
+        self.validate()?;
+
+        if losses.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "At least one loss is required.".to_string(),
+            ));
+        }
+
+        let n = losses.len() as f64;
+
+        match self {
+            // The Rockafellar-Uryasev objective is minimised at the VaR.
+            Self::ExpectedShortfall { confidence } => {
+                let w = losses.to_vec().quantile(confidence);
+                let excess: f64 = losses.iter().map(|l| (l - w).max(0.0)).sum();
+
+                Ok(w + excess / (n * (1.0 - confidence)))
+            }
+            Self::Entropic { risk_aversion } => {
+                let shift = losses.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                let mean: f64 = losses
+                    .iter()
+                    .map(|l| (risk_aversion * (l - shift)).exp())
+                    .sum::<f64>()
+                    / n;
+
+                Ok(shift + mean.ln() / risk_aversion)
+            }
+        }
+    }
+}
+
+impl DeepHedgingConfig {
+    /// New training configuration using full-batch updates.
+    #[must_use]
+    pub const fn new(objective: HedgingObjective, learning_rate: f64, epochs: usize) -> Self {
+        Self {
+            objective,
+            learning_rate,
+            epochs,
+            batch_size: usize::MAX,
+            seed: 0,
+        }
+    }
+
+    /// Set the mini-batch size.
+    #[must_use]
+    pub const fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the seed used to shuffle the paths.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl DeepHedger {
+    /// New hedger for a claim maturing in `maturity` years, with `tanh`
+    /// hidden layers of the given widths and a linear output.
+    /// The seed drives the initialisation of the network.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reference spot or the maturity is not positive, or if
+    /// a hidden layer has no units.
+    #[must_use]
+    pub fn new(reference_spot: f64, maturity: f64, hidden_layers: &[usize], seed: u64) -> Self {
+        assert!(reference_spot > 0.0, "Reference spot must be positive.");
+        assert!(maturity > 0.0, "Maturity must be positive.");
+
+        let network = hidden_layers
+            .iter()
+            .fold(NeuralNetwork::new(3, seed), |network, &n| {
+                network.with_layer(n, Activation::Tanh)
+            })
+            .with_layer(1, Activation::Identity);
+
+        Self {
+            network,
+            reference_spot,
+            maturity,
+            proportional_cost: 0.0,
+        }
+    }
+
+    /// Set the proportional transaction cost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cost is negative.
+    #[must_use]
+    pub fn with_costs(mut self, proportional_cost: f64) -> Self {
+        assert!(
+            proportional_cost >= 0.0,
+            "Transaction costs must be non-negative."
+        );
+
+        self.proportional_cost = proportional_cost;
+        self
+    }
+
+    /// Positions held over each step of `path`, one fewer than its length.
+    ///
+    /// # Errors
+    ///
+    /// If the path has fewer than two prices or a non-positive price.
+    pub fn positions(&self, path: &[f64]) -> Result<Vec<f64>, RustQuantError> {
+        let n_steps = validate_path(path)?;
+        let dt = self.maturity / n_steps as f64;
+
+        let mut positions = Vec::with_capacity(n_steps);
+        let mut previous = 0.0;
+
+        for (k, spot) in path[..n_steps].iter().enumerate() {
+            let input = [
+                (spot / self.reference_spot).ln(),
+                self.maturity - k as f64 * dt,
+                previous,
+            ];
+
+            previous = self.network.predict(&input)?[0];
+            positions.push(previous);
+        }
+
+        Ok(positions)
+    }
+
+    /// Terminal hedging loss on every path after selling the claim with
+    /// the given payoff (the premium is not included).
+    ///
+    /// # Errors
+    ///
+    /// If the paths are empty, of different lengths, shorter than two
+    /// prices, or contain a non-positive price.
+    pub fn hedging_losses<F>(
+        &self,
+        paths: &[Vec<f64>],
+        payoff: F,
+    ) -> Result<Vec<f64>, RustQuantError>
+    where
+        F: Fn(f64) -> f64,
+    {
+        validate_paths(paths)?;
+
+        paths
+            .iter()
+            .map(|path| {
+                let positions = self.positions(path)?;
+                let n = positions.len();
+                let cost = self.proportional_cost;
+
+                let mut loss = payoff(path[n]);
+                let mut previous = 0.0;
+
+                for (k, &position) in positions.iter().enumerate() {
+                    loss -= position * (path[k + 1] - path[k]);
+                    loss += cost * (position - previous).abs() * path[k];
+                    previous = position;
+                }
+
+                Ok(loss + cost * previous.abs() * path[n])
+            })
+            .collect()
+    }
+
+    /// Risk of the hedging loss on `paths`, i.e. the indifference price of
+    /// the claim under the hedging strategy.
+    ///
+    /// # Errors
+    ///
+    /// If the paths are invalid, or the objective's parameter is invalid.
+    pub fn risk<F>(
+        &self,
+        paths: &[Vec<f64>],
+        payoff: F,
+        objective: HedgingObjective,
+    ) -> Result<f64, RustQuantError>
+    where
+        F: Fn(f64) -> f64,
+    {
+        objective.evaluate(&self.hedging_losses(paths, payoff)?)
+    }
+
+    /// Train the network on `paths` and return the mean objective of
+    /// every epoch, in units of currency.
+    ///
+    /// For the expected shortfall, the VaR level $w$ of the
+    /// Rockafellar-Uryasev objective is an extra parameter trained with
+    /// the network.
+    ///
+    /// # Errors
+    ///
+    /// If the paths are invalid, or the configuration is invalid.
+    pub fn train<F>(
+        &mut self,
+        paths: &[Vec<f64>],
+        payoff: F,
+        config: &DeepHedgingConfig,
+    ) -> Result<Vec<f64>, RustQuantError>
+    where
+        F: Fn(f64) -> f64,
+    {
+        validate_paths(paths)?;
+        config.objective.validate()?;
+
+        if config.learning_rate.is_nan() || config.learning_rate <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Learning rate must be positive.".to_string(),
+            ));
+        }
+        if config.batch_size == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Batch size must be positive.".to_string(),
+            ));
+        }
+
+        let n_paths = paths.len();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut samples: Vec<usize> = (0..n_paths).collect();
+
+        // Network parameters followed by the VaR level of the expected
+        // shortfall objective (unused by the entropic objective).
+        let mut params = self.network.parameters();
+        params.push(0.0);
+
+        let mut m = vec![0.0; params.len()];
+        let mut v = vec![0.0; params.len()];
+        let mut step = 0;
+
+        let mut losses = Vec::with_capacity(config.epochs);
+
+        for _ in 0..config.epochs {
+            if config.batch_size < n_paths {
+                samples.shuffle(&mut rng);
+            }
+
+            let mut epoch_loss = 0.0;
+
+            for batch in samples.chunks(config.batch_size) {
+                let graph = Graph::new();
+                let vars = graph.vars(&params);
+                let loss = self.objective_variable(&graph, &vars, paths, batch, &payoff, config);
+                let gradient = loss.accumulate().wrt(&vars);
+
+                epoch_loss += loss.value * batch.len() as f64;
+
+                step += 1;
+                let bias_1 = 1.0 - BETA_1.powi(step);
+                let bias_2 = 1.0 - BETA_2.powi(step);
+
+                for (k, g) in gradient.iter().enumerate() {
+                    m[k] = BETA_1 * m[k] + (1.0 - BETA_1) * g;
+                    v[k] = BETA_2 * v[k] + (1.0 - BETA_2) * g * g;
+
+                    params[k] -= config.learning_rate * (m[k] / bias_1)
+                        / ((v[k] / bias_2).sqrt() + ADAM_EPS);
+                }
+            }
+
+            self.network.set_parameters(&params[..params.len() - 1])?;
+            losses.push(epoch_loss / n_paths as f64 * self.reference_spot);
+        }
+
+        Ok(losses)
+    }
+
+    /// Objective of the mini-batch, in units of the reference spot, built
+    /// on the graph of `params`.
+    fn objective_variable<'v, F>(
+        &self,
+        graph: &'v Graph,
+        params: &[Variable<'v>],
+        paths: &[Vec<f64>],
+        batch: &[usize],
+        payoff: &F,
+        config: &DeepHedgingConfig,
+    ) -> Variable<'v>
+    where
+        F: Fn(f64) -> f64,
+    {
+        let (network_params, threshold) = params.split_at(params.len() - 1);
+        let n = batch.len() as f64;
+
+        let losses: Vec<Variable> = batch
+            .iter()
+            .map(|&i| self.loss_variable(graph, network_params, &paths[i], payoff))
+            .collect();
+
+        match config.objective {
+            HedgingObjective::ExpectedShortfall { confidence } => {
+                let w = threshold[0];
+
+                losses.iter().map(|&l| (l - w).relu()).sum::<Variable>() / (n * (1.0 - confidence))
+                    + w
+            }
+            // The losses are scaled by the reference spot, so the risk
+            // aversion is scaled up by it; the shift keeps exp() in range.
+            HedgingObjective::Entropic { risk_aversion } => {
+                let lambda = risk_aversion * self.reference_spot;
+                let shift = losses
+                    .iter()
+                    .map(|l| l.value)
+                    .fold(f64::NEG_INFINITY, f64::max);
+
+                (losses
+                    .iter()
+                    .map(|&l| ((l - shift) * lambda).exp())
+                    .sum::<Variable>()
+                    / n)
+                    .ln()
+                    / lambda
+                    + shift
+            }
+        }
+    }
+
+    /// Hedging loss on one path, in units of the reference spot.
+    fn loss_variable<'v, F>(
+        &self,
+        graph: &'v Graph,
+        params: &[Variable<'v>],
+        path: &[f64],
+        payoff: &F,
+    ) -> Variable<'v>
+    where
+        F: Fn(f64) -> f64,
+    {
+        let n = path.len() - 1;
+        let dt = self.maturity / n as f64;
+        let cost = self.proportional_cost;
+
+        let mut loss = graph.var(payoff(path[n]) / self.reference_spot);
+        let mut previous = graph.var(0.0);
+
+        for k in 0..n {
+            let spot = path[k] / self.reference_spot;
+            let next = path[k + 1] / self.reference_spot;
+
+            let input = [
+                graph.var(spot.ln()),
+                graph.var(self.maturity - k as f64 * dt),
+                previous,
+            ];
+            let position = self.network.forward_variables(params, &input)[0];
+
+            loss = loss - position * (next - spot) + (position - previous).abs() * (cost * spot);
+            previous = position;
+        }
+
+        loss + previous.abs() * (cost * path[n] / self.reference_spot)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Check a path and return its number of steps.
+fn validate_path(path: &[f64]) -> Result<usize, RustQuantError> {
+    if path.len() < 2 {
+        return Err(RustQuantError::InvalidArgument(
+            "A path needs at least two prices.".to_string(),
+        ));
+    }
+    if path.iter().any(|s| s.is_nan() || *s <= 0.0) {
+        return Err(RustQuantError::InvalidArgument(
+            "Prices must be positive.".to_string(),
+        ));
+    }
+
+    Ok(path.len() - 1)
+}
+
+/// Check that the paths are non-empty, valid, and of the same length.
+fn validate_paths(paths: &[Vec<f64>]) -> Result<usize, RustQuantError> {
+    let first = paths.first().ok_or_else(|| {
+        RustQuantError::InvalidArgument("At least one path is required.".to_string())
+    })?;
+
+    if paths.iter().any(|path| path.len() != first.len()) {
+        return Err(RustQuantError::InvalidArgument(
+            "All paths must have the same length.".to_string(),
+        ));
+    }
+
+    paths
+        .iter()
+        .try_for_each(|path| validate_path(path).map(|_| ()))?;
+
+    Ok(first.len() - 1)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_deep_hedging {
+    use super::*;
+    use crate::trading::delta_hedging::geometric_brownian_paths;
+
+    fn call(spot: f64) -> f64 {
+        (spot - 100.0).max(0.0)
+    }
+
+    #[test]
+    fn test_expected_shortfall_objective() {
+        let losses: Vec<f64> = (1..=100).map(f64::from).collect();
+        let objective = HedgingObjective::ExpectedShortfall { confidence: 0.9 };
+
+        // Mean of the ten largest losses.
+        assert_approx_equal!(objective.evaluate(&losses).unwrap(), 95.5, 1e-10);
+    }
+
+    #[test]
+    fn test_entropic_objective() {
+        let losses = [1.0, 2.0, 3.0, 6.0];
+
+        // Tends to the mean as the risk aversion vanishes.
+        let neutral = HedgingObjective::Entropic {
+            risk_aversion: 1e-8,
+        };
+        assert_approx_equal!(neutral.evaluate(&losses).unwrap(), 3.0, 1e-6);
+
+        // ... and to the worst loss as it grows.
+        let averse = HedgingObjective::Entropic {
+            risk_aversion: 100.0,
+        };
+        assert_approx_equal!(averse.evaluate(&losses).unwrap(), 6.0, 0.02);
+    }
+
+    #[test]
+    fn test_training_reduces_risk() {
+        let paths = geometric_brownian_paths(100.0, 0.0, 0.2, 0.25, 10, 400, 7);
+        let objective = HedgingObjective::ExpectedShortfall { confidence: 0.9 };
+        let config = DeepHedgingConfig::new(objective, 0.02, 60)
+            .with_batch_size(100)
+            .with_seed(3);
+
+        let mut hedger = DeepHedger::new(100.0, 0.25, &[8], 11).with_costs(0.001);
+        let losses = hedger.train(&paths, call, &config).unwrap();
+
+        assert_eq!(losses.len(), 60);
+        assert!(losses.last().unwrap() < losses.first().unwrap());
+
+        let unhedged: Vec<f64> = paths.iter().map(|path| call(path[10])).collect();
+        let unhedged_risk = objective.evaluate(&unhedged).unwrap();
+        let hedged_risk = hedger.risk(&paths, call, objective).unwrap();
+
+        assert!(hedged_risk < 0.8 * unhedged_risk);
+        assert_eq!(hedger.positions(&paths[0]).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_entropic_training() {
+        let paths = geometric_brownian_paths(100.0, 0.0, 0.2, 0.25, 5, 200, 9);
+        let objective = HedgingObjective::Entropic { risk_aversion: 0.1 };
+        let config = DeepHedgingConfig::new(objective, 0.02, 100);
+
+        let mut hedger = DeepHedger::new(100.0, 0.25, &[8], 5);
+        let losses = hedger.train(&paths, call, &config).unwrap();
+
+        let unhedged: Vec<f64> = paths.iter().map(|path| call(path[5])).collect();
+        let unhedged_risk = objective.evaluate(&unhedged).unwrap();
+
+        assert!(losses.last().unwrap() < losses.first().unwrap());
+        assert!(hedger.risk(&paths, call, objective).unwrap() < unhedged_risk);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let paths = geometric_brownian_paths(100.0, 0.0, 0.2, 0.25, 5, 10, 1);
+        let mut hedger = DeepHedger::new(100.0, 0.25, &[4], 1);
+        let objective = HedgingObjective::ExpectedShortfall { confidence: 0.9 };
+        let config = DeepHedgingConfig::new(objective, 0.01, 1);
+
+        assert!(hedger.train(&[], call, &config).is_err());
+        assert!(hedger.train(&[vec![100.0]], call, &config).is_err());
+        assert!(hedger
+            .train(&[vec![100.0, 101.0], vec![100.0]], call, &config)
+            .is_err());
+        assert!(hedger.train(&[vec![100.0, -1.0]], call, &config).is_err());
+
+        let bad_rate = DeepHedgingConfig::new(objective, 0.0, 1);
+        assert!(hedger.train(&paths, call, &bad_rate).is_err());
+
+        let bad_batch = config.with_batch_size(0);
+        assert!(hedger.train(&paths, call, &bad_batch).is_err());
+
+        let bad_confidence = HedgingObjective::ExpectedShortfall { confidence: 1.0 };
+        assert!(hedger.risk(&paths, call, bad_confidence).is_err());
+
+        let bad_aversion = HedgingObjective::Entropic { risk_aversion: 0.0 };
+        assert!(hedger.risk(&paths, call, bad_aversion).is_err());
+        assert!(bad_aversion.evaluate(&[1.0]).is_err());
+        assert!(objective.evaluate(&[]).is_err());
+    }
+}
@@ -0,0 +1,583 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Gaussian process regression.
+//!
+//! The targets are modelled as $y_i = \bar y + f(x_i) + \epsilon_i$, where
+//! $\bar y$ is the sample mean, $f$ is a zero-mean Gaussian process with a
+//! stationary covariance kernel and $\epsilon_i \sim N(0, \sigma_n^2)$ is
+//! observation noise. The posterior of $f$ gives a smooth interpolant with
+//! pointwise uncertainty bands, e.g. for smoothing implied volatility
+//! surfaces (inputs: moneyness and maturity) or yield curves (input:
+//! maturity).
+//!
+//! Kernels, with one length scale $\ell_d$ per input dimension and
+//! $r^2 = \sum_d (x_d - x'_d)^2 / \ell_d^2$:
+//!
+//! - Squared exponential: $\sigma_f^2 \exp(-r^2 / 2)$.
+//! - Matérn 3/2: $\sigma_f^2 (1 + \sqrt{3} r) \exp(-\sqrt{3} r)$.
+//! - Matérn 5/2: $\sigma_f^2 (1 + \sqrt{5} r + 5 r^2 / 3) \exp(-\sqrt{5} r)$.
+//!
+//! The hyperparameters $(\sigma_f^2, \sigma_n^2, \ell)$ are fitted by
+//! maximising the log marginal likelihood with Adam on their logarithms.
+//! The gradient $\frac{1}{2} \operatorname{tr}((\alpha \alpha^\top - K^{-1})
+//! \partial K / \partial \theta)$ is accumulated in one reverse sweep of an
+//! `autodiff` graph of the kernel matrix.
+//!
+//! ```
+//! use RustQuant::ml::*;
+//! use nalgebra::{DMatrix, DVector};
+//!
+//! // Noisy zero rates on a maturity grid.
+//! let maturities = DMatrix::from_fn(15, 1, |i, _| 0.5 + i as f64);
+//! let rates = DVector::from_fn(15, |i, _| {
+//!     let t = maturities[(i, 0)];
+//!     0.03 + 0.01 * (1.0 - (-t / 3.0).exp()) + if i % 2 == 0 { 5e-4 } else { -5e-4 }
+//! });
+//!
+//! let initial = GpHyperparameters::new(1e-4, 1e-6, vec![3.0]);
+//! let gp = GaussianProcess::optimize(&maturities, &rates, Kernel::Matern52, initial, 500).unwrap();
+//!
+//! let curve = gp.predict(&DMatrix::from_column_slice(3, 1, &[2.0, 7.0, 30.0])).unwrap();
+//! let (lower, upper) = curve.band(1.96);
+//!
+//! // Extrapolation is less certain than interpolation.
+//! assert!(upper[2] - lower[2] > upper[1] - lower[1]);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Accumulate, Gradient, Graph, Variable};
+use crate::error::RustQuantError;
+use nalgebra::{Cholesky, DMatrix, DVector, Dyn};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Stationary covariance kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kernel {
+    /// Squared exponential (radial basis function): infinitely smooth.
+    SquaredExponential,
+    /// Matérn with $\nu = 3/2$: once differentiable.
+    Matern32,
+    /// Matérn with $\nu = 5/2$: twice differentiable.
+    Matern52,
+}
+
+/// Gaussian process hyperparameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpHyperparameters {
+    /// Signal variance $\sigma_f^2$.
+    pub signal_variance: f64,
+    /// Noise variance $\sigma_n^2$.
+    pub noise_variance: f64,
+    /// Length scale of each input dimension.
+    pub length_scales: Vec<f64>,
+}
+
+/// Gaussian process regression model, conditioned on training data.
+#[derive(Debug, Clone)]
+pub struct GaussianProcess {
+    /// The covariance kernel.
+    pub kernel: Kernel,
+    /// The hyperparameters.
+    pub hyperparameters: GpHyperparameters,
+    /// Log marginal likelihood of the training targets.
+    pub log_marginal_likelihood: f64,
+    x: DMatrix<f64>,
+    y_mean: f64,
+    alpha: DVector<f64>,
+    cholesky: Cholesky<f64, Dyn>,
+}
+
+/// Posterior predictions of a Gaussian process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpPrediction {
+    /// Posterior mean.
+    pub mean: DVector<f64>,
+    /// Posterior standard deviation of the latent function (excluding
+    /// observation noise).
+    pub std_dev: DVector<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Adam settings for the hyperparameter search, on the log scale.
+const LEARNING_RATE: f64 = 0.05;
+const BETA_1: f64 = 0.9;
+const BETA_2: f64 = 0.999;
+const ADAM_EPS: f64 = 1e-8;
+const GRADIENT_TOLERANCE: f64 = 1e-6;
+
+// Diagonal jitter, relative to the signal variance, for a stable Cholesky
+// factorisation.
+const JITTER: f64 = 1e-10;
+
+impl Kernel {
+    /// Correlation at scaled distance $r$ (given $r^2$).
+    fn correlation(self, r2: f64) -> f64 {
+        let r = r2.sqrt();
+
+        match self {
+            Self::SquaredExponential => (-0.5 * r2).exp(),
+            Self::Matern32 => {
+                let s = 3.0_f64.sqrt() * r;
+                (1.0 + s) * (-s).exp()
+            }
+            Self::Matern52 => {
+                let s = 5.0_f64.sqrt() * r;
+                (1.0 + s + s * s / 3.0) * (-s).exp()
+            }
+        }
+    }
+
+    /// Correlation at scaled distance $r$ (given $r^2 > 0$), on the graph.
+    fn correlation_variable(self, r2: Variable) -> Variable {
+        match self {
+            Self::SquaredExponential => (r2 * -0.5).exp(),
+            Self::Matern32 => {
+                let s = r2.sqrt() * 3.0_f64.sqrt();
+                (s + 1.0) * (-s).exp()
+            }
+            Self::Matern52 => {
+                let s = r2.sqrt() * 5.0_f64.sqrt();
+                (s + 1.0 + s * s / 3.0) * (-s).exp()
+            }
+        }
+    }
+}
+
+impl GpHyperparameters {
+    /// New hyperparameters.
+    #[must_use]
+    pub fn new(signal_variance: f64, noise_variance: f64, length_scales: Vec<f64>) -> Self {
+        Self {
+            signal_variance,
+            noise_variance,
+            length_scales,
+        }
+    }
+
+    /// Logarithms of the hyperparameters: signal variance, noise variance,
+    /// then the length scales.
+    fn to_log(&self) -> Vec<f64> {
+        [self.signal_variance, self.noise_variance]
+            .iter()
+            .chain(&self.length_scales)
+            .map(|v| v.ln())
+            .collect()
+    }
+
+    fn from_log(theta: &[f64]) -> Self {
+        Self {
+            signal_variance: theta[0].exp(),
+            noise_variance: theta[1].exp(),
+            length_scales: theta[2..].iter().map(|v| v.exp()).collect(),
+        }
+    }
+
+    fn validate(&self, n_features: usize) -> Result<(), RustQuantError> {
+        if self.length_scales.len() != n_features {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "expected {n_features} length scales, got {}",
+                self.length_scales.len()
+            )));
+        }
+
+        let positive = |v: &f64| v.is_finite() && *v > 0.0;
+        let valid = positive(&self.signal_variance)
+            && (self.noise_variance.is_finite() && self.noise_variance >= 0.0)
+            && self.length_scales.iter().all(positive);
+
+        if !valid {
+            return Err(RustQuantError::InvalidArgument(
+                "variances and length scales must be positive".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Squared scaled distance between row `i` of `a` and row `j` of `b`.
+fn scaled_distance(a: &DMatrix<f64>, i: usize, b: &DMatrix<f64>, j: usize, scales: &[f64]) -> f64 {
+    scales
+        .iter()
+        .enumerate()
+        .map(|(d, l)| ((a[(i, d)] - b[(j, d)]) / l).powi(2))
+        .sum()
+}
+
+/// Cross-covariance matrix between the rows of `a` and `b`.
+fn covariance(
+    kernel: Kernel,
+    hyperparameters: &GpHyperparameters,
+    a: &DMatrix<f64>,
+    b: &DMatrix<f64>,
+) -> DMatrix<f64> {
+    DMatrix::from_fn(a.nrows(), b.nrows(), |i, j| {
+        let r2 = scaled_distance(a, i, b, j, &hyperparameters.length_scales);
+        hyperparameters.signal_variance * kernel.correlation(r2)
+    })
+}
+
+impl GaussianProcess {
+    /// Condition a Gaussian process with the given hyperparameters on the
+    /// training data (one input per row of `x`).
+    ///
+    /// # Errors
+    ///
+    /// If the data is empty, the dimensions do not match, the values are
+    /// not finite, the hyperparameters are not positive, or the covariance
+    /// matrix is not positive definite.
+    pub fn fit(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        kernel: Kernel,
+        hyperparameters: GpHyperparameters,
+    ) -> Result<Self, RustQuantError> {
+        if x.nrows() == 0 || x.ncols() == 0 || x.nrows() != y.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need a non-empty input matrix with one row per target, got {:?} for {} targets",
+                x.shape(),
+                y.len()
+            )));
+        }
+        if x.iter().chain(y.iter()).any(|v| !v.is_finite()) {
+            return Err(RustQuantError::InvalidArgument(
+                "inputs and targets must be finite".to_string(),
+            ));
+        }
+        hyperparameters.validate(x.ncols())?;
+
+        let n = x.nrows();
+        let y_mean = y.mean();
+        let centred = y.add_scalar(-y_mean);
+
+        let mut k = covariance(kernel, &hyperparameters, x, x);
+        for i in 0..n {
+            k[(i, i)] += hyperparameters.noise_variance + JITTER * hyperparameters.signal_variance;
+        }
+
+        let cholesky = k.cholesky().ok_or_else(|| {
+            RustQuantError::ComputationError(
+                "covariance matrix is not positive definite".to_string(),
+            )
+        })?;
+        let alpha = cholesky.solve(&centred);
+
+        let log_determinant = 2.0
+            * cholesky
+                .l_dirty()
+                .diagonal()
+                .iter()
+                .map(|d| d.ln())
+                .sum::<f64>();
+        let log_marginal_likelihood = -0.5 * centred.dot(&alpha)
+            - 0.5 * log_determinant
+            - 0.5 * n as f64 * (2.0 * std::f64::consts::PI).ln();
+
+        Ok(Self {
+            kernel,
+            hyperparameters,
+            log_marginal_likelihood,
+            x: x.clone(),
+            y_mean,
+            alpha,
+            cholesky,
+        })
+    }
+
+    /// Fit the hyperparameters by maximising the log marginal likelihood,
+    /// starting from `initial`, then condition on the training data.
+    ///
+    /// Stops after `max_iterations` Adam steps or when the gradient with
+    /// respect to the log-hyperparameters vanishes.
+    ///
+    /// # Errors
+    ///
+    /// As for [`GaussianProcess::fit`], or if the initial noise variance is
+    /// zero (it is optimised on the log scale).
+    pub fn optimize(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        kernel: Kernel,
+        initial: GpHyperparameters,
+        max_iterations: usize,
+    ) -> Result<Self, RustQuantError> {
+        if initial.noise_variance <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "initial noise variance must be positive".to_string(),
+            ));
+        }
+
+        let mut best = Self::fit(x, y, kernel, initial)?;
+        let mut current = best.clone();
+
+        let mut theta = current.hyperparameters.to_log();
+        let mut m = vec![0.0; theta.len()];
+        let mut v = vec![0.0; theta.len()];
+
+        for step in 1..=max_iterations {
+            let gradient = current.log_likelihood_gradient();
+
+            if gradient.iter().map(|g| g * g).sum::<f64>().sqrt() < GRADIENT_TOLERANCE {
+                break;
+            }
+
+            let bias_1 = 1.0 - BETA_1.powi(step as i32);
+            let bias_2 = 1.0 - BETA_2.powi(step as i32);
+
+            for (k, g) in gradient.iter().enumerate() {
+                m[k] = BETA_1 * m[k] + (1.0 - BETA_1) * g;
+                v[k] = BETA_2 * v[k] + (1.0 - BETA_2) * g * g;
+
+                // Ascent on the log-likelihood.
+                theta[k] += LEARNING_RATE * (m[k] / bias_1) / ((v[k] / bias_2).sqrt() + ADAM_EPS);
+            }
+
+            current = Self::fit(x, y, kernel, GpHyperparameters::from_log(&theta))?;
+
+            if current.log_marginal_likelihood > best.log_marginal_likelihood {
+                best = current.clone();
+            }
+        }
+
+        Ok(best)
+    }
+
+    /// Gradient of the log marginal likelihood with respect to the
+    /// log-hyperparameters.
+    fn log_likelihood_gradient(&self) -> Vec<f64> {
+        let n = self.x.nrows();
+
+        // W = alpha alpha^T - K^{-1}, held constant on the graph.
+        let w = &self.alpha * self.alpha.transpose() - self.cholesky.inverse();
+
+        let graph = Graph::new();
+        let theta = graph.vars(&self.hyperparameters.to_log());
+        let signal = theta[0].exp();
+        let noise = theta[1].exp();
+        let inverse_squared_scales: Vec<Variable> =
+            theta[2..].iter().map(|l| (*l * -2.0).exp()).collect();
+
+        // (1/2) tr(W K) = (1/2) sum_ii W_ii K_ii + sum_{i<j} W_ij K_ij.
+        let diagonal = (signal * (1.0 + JITTER) + noise) * (0.5 * w.trace());
+
+        let off_diagonal = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let squared: Vec<f64> = (0..self.x.ncols())
+                    .map(|d| (self.x[(i, d)] - self.x[(j, d)]).powi(2))
+                    .collect();
+
+                let correlation = if squared.iter().all(|s| *s == 0.0) {
+                    graph.var(1.0)
+                } else {
+                    let r2 = inverse_squared_scales
+                        .iter()
+                        .zip(&squared)
+                        .map(|(l, s)| *l * *s)
+                        .sum::<Variable>();
+                    self.kernel.correlation_variable(r2)
+                };
+
+                signal * correlation * w[(i, j)]
+            })
+            .sum::<Variable>();
+
+        let objective = diagonal + off_diagonal;
+
+        objective.accumulate().wrt(&theta)
+    }
+
+    /// Posterior mean and standard deviation at the rows of `x`.
+    ///
+    /// # Errors
+    ///
+    /// If `x` does not have one column per input dimension.
+    pub fn predict(&self, x: &DMatrix<f64>) -> Result<GpPrediction, RustQuantError> {
+        if x.ncols() != self.x.ncols() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "expected {} input columns, got {}",
+                self.x.ncols(),
+                x.ncols()
+            )));
+        }
+
+        let k_star = covariance(self.kernel, &self.hyperparameters, &self.x, x);
+        let mean = k_star.transpose() * &self.alpha;
+
+        let l = self.cholesky.l();
+        let v = l
+            .solve_lower_triangular(&k_star)
+            .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+        let std_dev = DVector::from_fn(x.nrows(), |j, _| {
+            (self.hyperparameters.signal_variance - v.column(j).norm_squared())
+                .max(0.0)
+                .sqrt()
+        });
+
+        Ok(GpPrediction {
+            mean: mean.add_scalar(self.y_mean),
+            std_dev,
+        })
+    }
+}
+
+impl GpPrediction {
+    /// Lower and upper bands, mean $\pm z$ standard deviations (e.g.
+    /// $z = 1.96$ for 95% pointwise bands).
+    #[must_use]
+    pub fn band(&self, z: f64) -> (DVector<f64>, DVector<f64>) {
+        (
+            &self.mean - &self.std_dev * z,
+            &self.mean + &self.std_dev * z,
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_gaussian_process {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, Normal};
+
+    fn grid(n: usize) -> DMatrix<f64> {
+        DMatrix::from_fn(n, 1, |i, _| i as f64 / (n - 1) as f64 * 6.0)
+    }
+
+    #[test]
+    fn test_interpolates_without_noise() {
+        let x = grid(12);
+        let y = x.column(0).map(f64::sin);
+        let hyperparameters = GpHyperparameters::new(1.0, 0.0, vec![1.0]);
+
+        for kernel in [
+            Kernel::SquaredExponential,
+            Kernel::Matern32,
+            Kernel::Matern52,
+        ] {
+            let gp = GaussianProcess::fit(&x, &y, kernel, hyperparameters.clone()).unwrap();
+            let prediction = gp.predict(&x).unwrap();
+
+            assert!((&prediction.mean - &y).amax() < 1e-6);
+            assert!(prediction.std_dev.amax() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let x = DMatrix::from_row_slice(5, 2, &[0.0, 1.0, 0.5, 0.2, 1.0, 0.7, 1.5, 0.1, 2.0, 0.9]);
+        let y = DVector::from_vec(vec![0.1, 0.4, -0.2, 0.3, 0.0]);
+        let hyperparameters = GpHyperparameters::new(0.8, 0.05, vec![0.7, 1.3]);
+        let theta = hyperparameters.to_log();
+
+        for kernel in [
+            Kernel::SquaredExponential,
+            Kernel::Matern32,
+            Kernel::Matern52,
+        ] {
+            let gp = GaussianProcess::fit(&x, &y, kernel, hyperparameters.clone()).unwrap();
+            let gradient = gp.log_likelihood_gradient();
+
+            for k in 0..theta.len() {
+                let h = 1e-6;
+                let mut up = theta.clone();
+                let mut down = theta.clone();
+                up[k] += h;
+                down[k] -= h;
+
+                let likelihood = |t: &[f64]| {
+                    GaussianProcess::fit(&x, &y, kernel, GpHyperparameters::from_log(t))
+                        .unwrap()
+                        .log_marginal_likelihood
+                };
+                let finite_difference = (likelihood(&up) - likelihood(&down)) / (2.0 * h);
+
+                assert!((gradient[k] - finite_difference).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_optimize_recovers_noise() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let noise = Normal::new(0.0, 0.1).unwrap();
+
+        let x = grid(60);
+        let y = x.column(0).map(|t| t.sin() + noise.sample(&mut rng));
+
+        let initial = GpHyperparameters::new(0.1, 0.5, vec![5.0]);
+        let start = GaussianProcess::fit(&x, &y, Kernel::SquaredExponential, initial.clone())
+            .unwrap()
+            .log_marginal_likelihood;
+        let gp =
+            GaussianProcess::optimize(&x, &y, Kernel::SquaredExponential, initial, 1000).unwrap();
+
+        assert!(gp.log_marginal_likelihood > start);
+        assert!((gp.hyperparameters.noise_variance.sqrt() - 0.1).abs() < 0.04);
+
+        // The posterior mean is closer to the signal than the data.
+        let prediction = gp.predict(&x).unwrap();
+        let signal = x.column(0).map(f64::sin);
+        assert!((&prediction.mean - &signal).norm() < (&y - &signal).norm());
+
+        let (lower, upper) = prediction.band(1.96);
+        assert!((0..60).all(|i| lower[i] < prediction.mean[i] && prediction.mean[i] < upper[i]));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let x = grid(5);
+        let y = DVector::from_element(5, 1.0);
+        let good = GpHyperparameters::new(1.0, 0.1, vec![1.0]);
+
+        assert!(GaussianProcess::fit(
+            &x,
+            &y,
+            Kernel::Matern52,
+            GpHyperparameters::new(1.0, 0.1, vec![1.0, 1.0])
+        )
+        .is_err());
+        assert!(GaussianProcess::fit(
+            &x,
+            &y,
+            Kernel::Matern52,
+            GpHyperparameters::new(-1.0, 0.1, vec![1.0])
+        )
+        .is_err());
+        assert!(
+            GaussianProcess::fit(&x, &y.rows(0, 4).into(), Kernel::Matern52, good.clone()).is_err()
+        );
+
+        assert!(GaussianProcess::optimize(
+            &x,
+            &y,
+            Kernel::Matern52,
+            GpHyperparameters::new(1.0, 0.0, vec![1.0]),
+            10
+        )
+        .is_err());
+
+        let gp = GaussianProcess::fit(&x, &y, Kernel::Matern52, good).unwrap();
+        assert!(gp.predict(&DMatrix::zeros(2, 2)).is_err());
+    }
+}
@@ -8,6 +8,10 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 //! Module for linear regression algorithms.
+//!
+//! Ordinary least squares is solved in closed form (see [`Decomposition`]).
+//! Ridge (L2-regularised) regression is fit by gradient descent, with the
+//! gradient of the loss computed on the `autodiff` graph.
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPORTS
@@ -15,7 +19,9 @@
 
 use nalgebra::{DMatrix, DVector};
 
+use crate::autodiff::variables::variable::Variable;
 use crate::error::RustQuantError;
+use crate::math::optimization::gradient_descent::GradientDescent;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS, ENUMS, AND TRAITS
@@ -154,6 +160,88 @@ impl LinearRegressionInput<f64> {
     }
 }
 
+impl LinearRegressionInput<f64> {
+    /// Fits a ridge regression to the input data, minimising
+    ///
+    /// $$
+    /// \frac{1}{n} \| y - X \beta \|^2 + \lambda \sum_{j \geq 1} \beta_j^2
+    /// $$
+    ///
+    /// by gradient descent on the `autodiff` graph. The intercept is not
+    /// penalised. Iterations stop when the norm of the gradient falls below
+    /// `tolerance`.
+    ///
+    /// # Errors
+    ///
+    /// - If `lambda` is negative.
+    /// - If the dimensions of `x` and `y` do not match.
+    pub fn fit_ridge(
+        &self,
+        lambda: f64,
+        tolerance: f64,
+    ) -> Result<LinearRegressionOutput<f64>, RustQuantError> {
+        if lambda.is_nan() || lambda < 0.0 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "ridge penalty must be non-negative, got {lambda}"
+            )));
+        }
+
+        if self.x.nrows() != self.y.len() || self.x.ncols() == 0 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "design matrix is {:?} for {} responses",
+                self.x.shape(),
+                self.y.len()
+            )));
+        }
+
+        let x = self.x.clone().insert_column(0, 1.);
+        let n = x.nrows() as f64;
+
+        // Step size 1/L, with L the Lipschitz constant of the gradient.
+        let lipschitz = 2.0 * (x.transpose() * &x).symmetric_eigenvalues().max() / n + 2.0 * lambda;
+
+        let result = GradientDescent::new(1.0 / lipschitz, MAX_ITERATIONS, Some(tolerance))
+            .optimize(
+                ridge_objective(&x, &self.y, lambda),
+                &vec![0.0; x.ncols()],
+                false,
+            );
+
+        let coefficients = DVector::from_vec(result.minimizer);
+
+        Ok(LinearRegressionOutput {
+            intercept: coefficients[0],
+            coefficients,
+        })
+    }
+}
+
+/// Maximum number of gradient descent iterations for ridge regression.
+const MAX_ITERATIONS: usize = 100_000;
+
+/// Ridge objective in the coefficients (intercept first).
+fn ridge_objective<'a>(
+    x: &'a DMatrix<f64>,
+    y: &'a DVector<f64>,
+    lambda: f64,
+) -> impl for<'v> Fn(&[Variable<'v>]) -> Variable<'v> + 'a {
+    move |beta| {
+        let loss = (0..x.nrows())
+            .map(|i| {
+                let fitted: Variable = beta.iter().zip(x.row(i).iter()).map(|(b, x)| *b * *x).sum();
+                let residual = fitted - y[i];
+
+                residual * residual
+            })
+            .sum::<Variable>()
+            / x.nrows() as f64;
+
+        let penalty: Variable = beta[1..].iter().map(|b| *b * *b).sum();
+
+        loss + penalty * lambda
+    }
+}
+
 impl LinearRegressionOutput<f64> {
     /// Predicts the output for the given input data.
     pub fn predict(&self, input: DMatrix<f64>) -> Result<DVector<f64>, RustQuantError> {
@@ -283,4 +371,50 @@ mod tests_linear_regression {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_ridge_regression() -> Result<(), RustQuantError> {
+        #[rustfmt::skip]
+        let x = DMatrix::from_row_slice(
+            6, // rows
+            2, // columns
+            &[ 0.5, -1.2,
+               1.3,  0.4,
+              -0.7,  0.9,
+               2.1, -0.3,
+              -1.5, -0.8,
+               0.2,  1.7],
+        );
+        let y = DVector::from_row_slice(&[0.3, 2.9, -0.4, 3.8, -2.6, 1.9]);
+        let input = LinearRegressionInput::new(x.clone(), y.clone());
+
+        // No penalty: ordinary least squares.
+        let ols = input.fit(Decomposition::QR)?;
+        let ridge = input.fit_ridge(0.0, 1e-10)?;
+        for (a, b) in ols.coefficients.iter().zip(ridge.coefficients.iter()) {
+            assert_approx_equal!(a, b, 1e-8);
+        }
+
+        // Closed form: (X'X + n lambda D) beta = X'y, D = diag(0, 1, 1).
+        let lambda = 0.3;
+        let x1 = x.insert_column(0, 1.);
+        let penalty = DMatrix::from_diagonal(&DVector::from_row_slice(&[0.0, 1.0, 1.0]));
+        let expected = (x1.transpose() * &x1 + penalty * (6.0 * lambda))
+            .try_inverse()
+            .unwrap()
+            * x1.transpose()
+            * y;
+
+        let ridge = input.fit_ridge(lambda, 1e-10)?;
+        for (a, b) in expected.iter().zip(ridge.coefficients.iter()) {
+            assert_approx_equal!(a, b, 1e-8);
+        }
+        assert_approx_equal!(ridge.intercept, expected[0], 1e-8);
+
+        // Shrinkage.
+        assert!(ridge.coefficients[1].abs() < ols.coefficients[1].abs());
+        assert!(input.fit_ridge(-1.0, 1e-10).is_err());
+
+        Ok(())
+    }
 }
@@ -8,11 +8,17 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 //! Module for logistic regression (classification) algorithms.
+//!
+//! The coefficients can be fit by iteratively reweighted least squares, or
+//! by maximum likelihood: gradient descent on the negative log-likelihood,
+//! with the gradient computed on the `autodiff` graph.
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::autodiff::variables::variable::Variable;
+use crate::math::optimization::gradient_descent::GradientDescent;
 use crate::ml::ActivationFunction;
 use nalgebra::{DMatrix, DVector};
 
@@ -53,6 +59,11 @@ pub struct LogisticRegressionOutput<T> {
 pub enum LogisticRegressionAlgorithm {
     /// Maximum Likelihood Estimation using Algorithmic Adjoint Differentiation
     /// See: <https://en.wikipedia.org/wiki/Logistic_regression#Maximum_likelihood_estimation_(MLE)>
+    ///
+    /// The mean negative log-likelihood is minimised by gradient descent,
+    /// with step size $4n / \lambda_{max}(X^T X)$ (the inverse of the
+    /// Lipschitz constant of its gradient). The tolerance applies to the
+    /// norm of the gradient.
     MLE,
     /// Iterative Reweighted Least Squares
     /// From Wikipedia (<https://en.wikipedia.org/wiki/Logistic_regression#Iteratively_reweighted_least_squares_(IRLS)>):
@@ -179,7 +190,19 @@ impl LogisticRegressionInput<f64> {
             // MAXIMUM LIKELIHOOD ESTIMATION
             // Using Algorithmic Adjoint Differentiation (AAD)
             // from the `autodiff` module.
-            LogisticRegressionAlgorithm::MLE => unimplemented!(),
+            LogisticRegressionAlgorithm::MLE => {
+                let lipschitz = (&X_T * &X).symmetric_eigenvalues().max() / (4.0 * n_rows as f64);
+
+                let result = GradientDescent::new(1.0 / lipschitz, MAX_ITERATIONS, Some(tolerance))
+                    .optimize(
+                        negative_log_likelihood(&X, &y),
+                        output.coefficients.as_slice(),
+                        false,
+                    );
+
+                output.coefficients = DVector::from_vec(result.minimizer);
+                output.iterations = result.iterations;
+            }
 
             // ITERATIVELY RE-WEIGHTED LEAST SQUARES
             // References:
@@ -228,6 +251,26 @@ impl LogisticRegressionInput<f64> {
     }
 }
 
+/// Maximum number of gradient descent iterations for the MLE fit.
+const MAX_ITERATIONS: usize = 100_000;
+
+/// Mean negative log-likelihood of the coefficients (intercept first).
+fn negative_log_likelihood<'a>(
+    x: &'a DMatrix<f64>,
+    y: &'a DVector<f64>,
+) -> impl for<'v> Fn(&[Variable<'v>]) -> Variable<'v> + 'a {
+    move |beta| {
+        (0..x.nrows())
+            .map(|i| {
+                let eta: Variable = beta.iter().zip(x.row(i).iter()).map(|(b, x)| *b * *x).sum();
+
+                (1.0 + eta.exp()).ln() - eta * y[i]
+            })
+            .sum::<Variable>()
+            / x.nrows() as f64
+    }
+}
+
 impl LogisticRegressionOutput<f64> {
     /// Predicts the output for the given input data.
     #[must_use]
@@ -381,6 +424,38 @@ mod tests_logistic_regression {
         // }
     }
 
+    #[test]
+    fn test_logistic_regression_mle() {
+        // Deterministic, non-separable data.
+        let n = 60;
+        let x = DMatrix::from_fn(n, 2, |i, j| {
+            let t = i as f64;
+            if j == 0 {
+                t / 15.0 - 2.0
+            } else {
+                (1.7 * t).sin()
+            }
+        });
+        let y = DVector::from_fn(n, |i, _| {
+            let score = x[(i, 0)] + 0.8 * x[(i, 1)] + (3.1 * i as f64).cos();
+            if score > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        });
+
+        let input = LogisticRegressionInput::new(x, y);
+
+        let irls = input.fit(LogisticRegressionAlgorithm::IRLS, 1e-12).unwrap();
+        let mle = input.fit(LogisticRegressionAlgorithm::MLE, 1e-10).unwrap();
+
+        assert!(mle.iterations > 0);
+        for (a, b) in irls.coefficients.iter().zip(mle.coefficients.iter()) {
+            assert!((a - b).abs() < 1e-6, "IRLS {a} vs MLE {b}");
+        }
+    }
+
     #[test]
     fn test_logistic_regression_stochastic() {
         // cargo test --release   tests_logistic_regression::test_logistic_regression2 -- --nocapture
@@ -12,16 +12,41 @@
 //! ### Regression
 //!
 //! - [x] Linear (using QR or SVD decomposition)
-//! - [x] Logistic (via IRLS, adding MLE in the future).
+//! - [x] Ridge (gradient descent with `autodiff`)
+//! - [x] Logistic (via IRLS, or MLE with `autodiff`).
+//! - [x] Regression trees (CART)
+//! - [x] Random forest and gradient-boosted trees
+//! - [x] Gaussian process (hyperparameters fitted with `autodiff`)
 //!
 //! ### Classification
 //!
 //! - [x] K-Nearest Neighbours
+//!
+//! ### Dimensionality reduction
+//!
+//! - [x] Principal component analysis
+//!
+//! ### Neural networks
+//!
+//! - [x] Feed-forward (multi-layer perceptron), trained with `autodiff`.
+//! - [x] Deep hedging (CVaR or entropic risk of the hedging loss, with costs).
 
 /// Submodule of `ml`: activation functions.
 pub mod activations;
 pub use activations::*;
 
+/// Regression trees.
+pub mod decision_tree;
+pub use decision_tree::*;
+
+/// Deep hedging with neural network policies.
+pub mod deep_hedging;
+pub use deep_hedging::*;
+
+/// Gaussian process regression.
+pub mod gaussian_process;
+pub use gaussian_process::*;
+
 /// K Nearest Neighbor classifier
 pub mod k_nearest_neighbors;
 pub use k_nearest_neighbors::*;
@@ -33,3 +58,15 @@ pub use linear_regression::*;
 /// Logistic regression.
 pub mod logistic_regression;
 pub use logistic_regression::*;
+
+/// Feed-forward neural networks.
+pub mod neural_network;
+pub use neural_network::*;
+
+/// Principal component analysis.
+pub mod principal_component_analysis;
+pub use principal_component_analysis::*;
+
+/// Random forest and gradient-boosted trees.
+pub mod tree_ensemble;
+pub use tree_ensemble::*;
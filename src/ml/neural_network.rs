@@ -0,0 +1,719 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Feed-forward neural network (multi-layer perceptron).
+//!
+//! The network is a stack of dense layers, each with its own activation
+//! function. Training builds the forward pass of every mini-batch on an
+//! `autodiff` graph, so the gradient of the loss with respect to all the
+//! weights and biases comes from a single reverse sweep, and the parameters
+//! are then updated with the Adam optimiser.
+//!
+//! ```
+//! use RustQuant::ml::*;
+//! use nalgebra::DMatrix;
+//!
+//! // Learn y = 2x - 1 on [0, 1].
+//! let x = DMatrix::from_fn(20, 1, |i, _| i as f64 / 19.0);
+//! let y = x.map(|x| 2.0 * x - 1.0);
+//!
+//! let mut network = NeuralNetwork::new(1, 42)
+//!     .with_layer(8, Activation::Tanh)
+//!     .with_layer(1, Activation::Identity);
+//!
+//! let config = TrainingConfig::new(Loss::MeanSquaredError, 0.05, 500);
+//! let losses = network.train(&x, &y, &config).unwrap();
+//!
+//! assert!(losses.last().unwrap() < &1e-3);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{Accumulate, Gradient, Graph, Variable};
+use crate::error::RustQuantError;
+use crate::ml::ActivationFunction;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use std::ops::Mul;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Activation function applied element-wise to the output of a layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activation {
+    /// f(x) = x
+    Identity,
+    /// f(x) = 1 / (1 + exp(-x))
+    Sigmoid,
+    /// f(x) = tanh(x)
+    Tanh,
+    /// f(x) = max(0, x)
+    Relu,
+    /// f(x) = ln(1 + exp(x))
+    Softplus,
+    /// f(x) = x * Phi(x)
+    Gelu,
+}
+
+/// Loss function minimised during training.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Loss {
+    /// Mean squared error over all samples and outputs.
+    MeanSquaredError,
+    /// Binary cross-entropy over all samples and outputs.
+    /// The targets must lie in [0, 1] and the output layer must use the
+    /// [`Activation::Sigmoid`] activation; the loss is evaluated on the
+    /// logits for numerical stability.
+    CrossEntropy,
+}
+
+/// A fully connected layer: `a = f(W x + b)`.
+#[derive(Debug, Clone)]
+pub struct DenseLayer {
+    /// Weight matrix, with one row per output and one column per input.
+    pub weights: DMatrix<f64>,
+    /// Bias vector, one entry per output.
+    pub biases: DVector<f64>,
+    /// Activation function.
+    pub activation: Activation,
+}
+
+/// Feed-forward neural network.
+#[derive(Debug, Clone)]
+pub struct NeuralNetwork {
+    /// Number of inputs.
+    pub n_inputs: usize,
+    /// Layers, from input to output.
+    pub layers: Vec<DenseLayer>,
+    /// Random number generator used to initialise new layers.
+    rng: StdRng,
+}
+
+/// Training hyper-parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct TrainingConfig {
+    /// Loss function.
+    pub loss: Loss,
+    /// Adam step size.
+    pub learning_rate: f64,
+    /// Number of passes over the training data.
+    pub epochs: usize,
+    /// Mini-batch size (the full data set by default).
+    pub batch_size: usize,
+    /// Seed for shuffling the samples between epochs.
+    pub seed: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Adam moment decay rates and denominator offset.
+pub(crate) const BETA_1: f64 = 0.9;
+pub(crate) const BETA_2: f64 = 0.999;
+pub(crate) const ADAM_EPS: f64 = 1e-8;
+
+impl Activation {
+    fn apply(self, z: f64) -> f64 {
+        match self {
+            Self::Identity => z.identity(),
+            Self::Sigmoid => z.sigmoid(),
+            Self::Tanh => ActivationFunction::tanh(&z),
+            Self::Relu => z.relu(),
+            Self::Softplus => z.softplus(),
+            Self::Gelu => z.gelu(),
+        }
+    }
+
+    fn apply_variable(self, z: Variable) -> Variable {
+        match self {
+            Self::Identity => z.identity(),
+            Self::Sigmoid => z.sigmoid(),
+            Self::Tanh => ActivationFunction::tanh(&z),
+            Self::Relu => z.relu(),
+            Self::Softplus => z.softplus(),
+            Self::Gelu => z.gelu(),
+        }
+    }
+}
+
+impl TrainingConfig {
+    /// New training configuration using full-batch updates.
+    #[must_use]
+    pub const fn new(loss: Loss, learning_rate: f64, epochs: usize) -> Self {
+        Self {
+            loss,
+            learning_rate,
+            epochs,
+            batch_size: usize::MAX,
+            seed: 0,
+        }
+    }
+
+    /// Set the mini-batch size.
+    #[must_use]
+    pub const fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the seed used to shuffle the samples.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl DenseLayer {
+    /// Number of inputs to the layer.
+    #[must_use]
+    pub fn n_inputs(&self) -> usize {
+        self.weights.ncols()
+    }
+
+    /// Number of outputs of the layer.
+    #[must_use]
+    pub fn n_outputs(&self) -> usize {
+        self.weights.nrows()
+    }
+
+    /// Number of trainable parameters (weights and biases).
+    #[must_use]
+    pub fn n_parameters(&self) -> usize {
+        self.weights.len() + self.biases.len()
+    }
+
+    /// Forward pass returning the pre-activation values `W x + b`.
+    fn linear(&self, input: &DVector<f64>) -> DVector<f64> {
+        &self.weights * input + &self.biases
+    }
+
+    /// Forward pass on the graph, with the layer's parameters given as
+    /// variables (weights row by row, then biases).
+    fn linear_variables<'v, X>(&self, params: &[Variable<'v>], input: &[X]) -> Vec<Variable<'v>>
+    where
+        X: Copy,
+        Variable<'v>: Mul<X, Output = Variable<'v>>,
+    {
+        let (n_out, n_in) = self.weights.shape();
+        let (weights, biases) = params.split_at(n_out * n_in);
+
+        (0..n_out)
+            .map(|j| {
+                weights[j * n_in..(j + 1) * n_in]
+                    .iter()
+                    .zip(input)
+                    .map(|(w, x)| *w * *x)
+                    .sum::<Variable>()
+                    + biases[j]
+            })
+            .collect()
+    }
+}
+
+impl NeuralNetwork {
+    /// New network with `n_inputs` inputs and no layers.
+    /// The seed drives the initialisation of the layers added afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_inputs` is zero.
+    #[must_use]
+    pub fn new(n_inputs: usize, seed: u64) -> Self {
+        assert!(n_inputs > 0, "The network needs at least one input.");
+
+        Self {
+            n_inputs,
+            layers: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Append a dense layer with `n_outputs` units.
+    /// Weights are drawn from the Glorot (Xavier) normal distribution and
+    /// the biases start at zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n_outputs` is zero.
+    #[must_use]
+    pub fn with_layer(mut self, n_outputs: usize, activation: Activation) -> Self {
+        assert!(n_outputs > 0, "A layer needs at least one unit.");
+
+        let n_inputs = self.n_outputs();
+        let std_dev = (2.0 / (n_inputs + n_outputs) as f64).sqrt();
+        let normal = Normal::new(0.0, std_dev).expect("Standard deviation is positive.");
+
+        let weights = DMatrix::from_fn(n_outputs, n_inputs, |_, _| normal.sample(&mut self.rng));
+
+        self.layers.push(DenseLayer {
+            weights,
+            biases: DVector::zeros(n_outputs),
+            activation,
+        });
+        self
+    }
+
+    /// Number of outputs of the network.
+    #[must_use]
+    pub fn n_outputs(&self) -> usize {
+        self.layers
+            .last()
+            .map_or(self.n_inputs, DenseLayer::n_outputs)
+    }
+
+    /// Number of trainable parameters.
+    #[must_use]
+    pub fn n_parameters(&self) -> usize {
+        self.layers.iter().map(DenseLayer::n_parameters).sum()
+    }
+
+    /// All parameters flattened: for each layer, the weights row by row
+    /// followed by the biases.
+    #[must_use]
+    pub fn parameters(&self) -> Vec<f64> {
+        let mut params = Vec::with_capacity(self.n_parameters());
+
+        for layer in &self.layers {
+            params.extend(layer.weights.transpose().iter());
+            params.extend(layer.biases.iter());
+        }
+
+        params
+    }
+
+    /// Overwrite the parameters from a flat slice laid out as in
+    /// [`NeuralNetwork::parameters`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the slice has the wrong length.
+    pub fn set_parameters(&mut self, params: &[f64]) -> Result<(), RustQuantError> {
+        if params.len() != self.n_parameters() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected {} parameters, got {}.",
+                self.n_parameters(),
+                params.len()
+            )));
+        }
+
+        let mut offset = 0;
+
+        for layer in &mut self.layers {
+            let (n_out, n_in) = layer.weights.shape();
+
+            layer.weights =
+                DMatrix::from_row_slice(n_out, n_in, &params[offset..offset + n_out * n_in]);
+            offset += n_out * n_in;
+
+            layer.biases = DVector::from_column_slice(&params[offset..offset + n_out]);
+            offset += n_out;
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate the network on a single input vector.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input has the wrong dimension.
+    pub fn predict(&self, input: &[f64]) -> Result<Vec<f64>, RustQuantError> {
+        if input.len() != self.n_inputs {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected {} inputs, got {}.",
+                self.n_inputs,
+                input.len()
+            )));
+        }
+
+        let output = self
+            .layers
+            .iter()
+            .fold(DVector::from_column_slice(input), |a, layer| {
+                layer.linear(&a).map(|z| layer.activation.apply(z))
+            });
+
+        Ok(output.as_slice().to_vec())
+    }
+
+    /// Evaluate the network on every row of `x`, one output row per sample.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `x` has the wrong number of columns.
+    pub fn predict_batch(&self, x: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+        let mut output = DMatrix::zeros(x.nrows(), self.n_outputs());
+
+        for i in 0..x.nrows() {
+            let row: Vec<f64> = x.row(i).iter().copied().collect();
+            let prediction = self.predict(&row)?;
+
+            output.row_mut(i).copy_from_slice(&prediction);
+        }
+
+        Ok(output)
+    }
+
+    /// Loss of the network on the data set `(x, y)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is inconsistent with the network.
+    pub fn loss(
+        &self,
+        x: &DMatrix<f64>,
+        y: &DMatrix<f64>,
+        loss: Loss,
+    ) -> Result<f64, RustQuantError> {
+        self.validate(x, y, loss)?;
+
+        let graph = Graph::new();
+        let params = graph.vars(&self.parameters());
+        let samples: Vec<usize> = (0..x.nrows()).collect();
+
+        Ok(self.loss_variable(&params, x, y, &samples, loss).value)
+    }
+
+    /// Train the network on the data set `(x, y)`, one sample per row,
+    /// and return the mean training loss of every epoch.
+    ///
+    /// Each mini-batch is evaluated on a fresh `autodiff` graph; the
+    /// gradient of the batch loss is accumulated in one reverse sweep and
+    /// the parameters are updated with Adam.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is inconsistent with the network, or
+    /// if the configuration is invalid.
+    pub fn train(
+        &mut self,
+        x: &DMatrix<f64>,
+        y: &DMatrix<f64>,
+        config: &TrainingConfig,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        self.validate(x, y, config.loss)?;
+
+        if config.learning_rate.is_nan() || config.learning_rate <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Learning rate must be positive.".to_string(),
+            ));
+        }
+        if config.batch_size == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Batch size must be positive.".to_string(),
+            ));
+        }
+
+        let n_samples = x.nrows();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut samples: Vec<usize> = (0..n_samples).collect();
+
+        let mut params = self.parameters();
+        let mut m = vec![0.0; params.len()];
+        let mut v = vec![0.0; params.len()];
+        let mut step = 0;
+
+        let mut losses = Vec::with_capacity(config.epochs);
+
+        for _ in 0..config.epochs {
+            if config.batch_size < n_samples {
+                samples.shuffle(&mut rng);
+            }
+
+            let mut epoch_loss = 0.0;
+
+            for batch in samples.chunks(config.batch_size) {
+                let graph = Graph::new();
+                let vars = graph.vars(&params);
+                let loss = self.loss_variable(&vars, x, y, batch, config.loss);
+                let gradient = loss.accumulate().wrt(&vars);
+
+                epoch_loss += loss.value * batch.len() as f64;
+
+                step += 1;
+                let bias_1 = 1.0 - BETA_1.powi(step);
+                let bias_2 = 1.0 - BETA_2.powi(step);
+
+                for (k, g) in gradient.iter().enumerate() {
+                    m[k] = BETA_1 * m[k] + (1.0 - BETA_1) * g;
+                    v[k] = BETA_2 * v[k] + (1.0 - BETA_2) * g * g;
+
+                    params[k] -= config.learning_rate * (m[k] / bias_1)
+                        / ((v[k] / bias_2).sqrt() + ADAM_EPS);
+                }
+            }
+
+            self.set_parameters(&params)?;
+            losses.push(epoch_loss / n_samples as f64);
+        }
+
+        Ok(losses)
+    }
+
+    fn validate(
+        &self,
+        x: &DMatrix<f64>,
+        y: &DMatrix<f64>,
+        loss: Loss,
+    ) -> Result<(), RustQuantError> {
+        if self.layers.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "The network has no layers.".to_string(),
+            ));
+        }
+        if x.nrows() == 0 || x.nrows() != y.nrows() {
+            return Err(RustQuantError::InvalidArgument(
+                "Inputs and targets must have the same, non-zero, number of rows.".to_string(),
+            ));
+        }
+        if x.ncols() != self.n_inputs || y.ncols() != self.n_outputs() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected {} input and {} output columns, got {} and {}.",
+                self.n_inputs,
+                self.n_outputs(),
+                x.ncols(),
+                y.ncols()
+            )));
+        }
+        if loss == Loss::CrossEntropy {
+            if self.layers.last().map(|l| l.activation) != Some(Activation::Sigmoid) {
+                return Err(RustQuantError::InvalidArgument(
+                    "Cross-entropy loss requires a sigmoid output layer.".to_string(),
+                ));
+            }
+            if y.iter().any(|t| !(0.0..=1.0).contains(t)) {
+                return Err(RustQuantError::InvalidArgument(
+                    "Cross-entropy targets must lie in [0, 1].".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mean loss over the given samples, built on the graph of `params`.
+    fn loss_variable<'v>(
+        &self,
+        params: &[Variable<'v>],
+        x: &DMatrix<f64>,
+        y: &DMatrix<f64>,
+        samples: &[usize],
+        loss: Loss,
+    ) -> Variable<'v> {
+        let n_terms = (samples.len() * self.n_outputs()) as f64;
+
+        samples
+            .iter()
+            .map(|&i| {
+                let input: Vec<f64> = x.row(i).iter().copied().collect();
+                let logits = self.logits_variables(params, &input);
+                let last = self.layers.last().expect("Validated non-empty.");
+
+                logits
+                    .into_iter()
+                    .zip(y.row(i).iter())
+                    .map(|(z, &t)| match loss {
+                        Loss::MeanSquaredError => {
+                            let error = last.activation.apply_variable(z) - t;
+                            error * error
+                        }
+                        // -t ln(s(z)) - (1 - t) ln(1 - s(z)) = softplus(z) - t z
+                        Loss::CrossEntropy => z.softplus() - z * t,
+                    })
+                    .sum::<Variable>()
+            })
+            .sum::<Variable>()
+            / n_terms
+    }
+
+    /// Output of the network for a single input, built on the graph of
+    /// `params`. The input may itself be made of variables, so networks can
+    /// be chained or fed their own outputs.
+    pub(crate) fn forward_variables<'v, X>(
+        &self,
+        params: &[Variable<'v>],
+        input: &[X],
+    ) -> Vec<Variable<'v>>
+    where
+        X: Copy,
+        Variable<'v>: Mul<X, Output = Variable<'v>>,
+    {
+        let last = self.layers.last().expect("Validated non-empty.");
+
+        self.logits_variables(params, input)
+            .into_iter()
+            .map(|z| last.activation.apply_variable(z))
+            .collect()
+    }
+
+    /// Pre-activation values of the output layer for a single input.
+    fn logits_variables<'v, X>(&self, params: &[Variable<'v>], input: &[X]) -> Vec<Variable<'v>>
+    where
+        X: Copy,
+        Variable<'v>: Mul<X, Output = Variable<'v>>,
+    {
+        let (first, rest) = self.layers.split_first().expect("Validated non-empty.");
+        let (first_params, mut params) = params.split_at(first.n_parameters());
+
+        let mut z = first.linear_variables(first_params, input);
+
+        for (previous, layer) in self.layers.iter().zip(rest) {
+            let (layer_params, remaining) = params.split_at(layer.n_parameters());
+            params = remaining;
+
+            let activations: Vec<Variable> = z
+                .into_iter()
+                .map(|z| previous.activation.apply_variable(z))
+                .collect();
+
+            z = layer.linear_variables::<Variable<'v>>(layer_params, activations.as_slice());
+        }
+
+        z
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_neural_network {
+    use super::*;
+
+    #[test]
+    fn test_parameters_round_trip() {
+        let mut network = NeuralNetwork::new(3, 1)
+            .with_layer(4, Activation::Relu)
+            .with_layer(2, Activation::Identity);
+
+        assert_eq!(network.n_parameters(), 3 * 4 + 4 + 4 * 2 + 2);
+
+        let params: Vec<f64> = (0..network.n_parameters()).map(|i| i as f64).collect();
+        network.set_parameters(&params).unwrap();
+
+        assert_eq!(network.parameters(), params);
+        assert_eq!(network.layers[0].weights[(0, 1)], 1.0);
+        assert_eq!(network.layers[0].weights[(1, 0)], 3.0);
+        assert_eq!(network.layers[0].biases[0], 12.0);
+
+        assert!(network.set_parameters(&params[1..]).is_err());
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let network = NeuralNetwork::new(2, 7)
+            .with_layer(3, Activation::Tanh)
+            .with_layer(1, Activation::Sigmoid);
+
+        let x = DMatrix::from_row_slice(3, 2, &[0.1, -0.4, 0.7, 0.2, -0.5, 0.9]);
+        let y = DMatrix::from_row_slice(3, 1, &[0.0, 1.0, 1.0]);
+        let samples = [0, 1, 2];
+
+        let graph = Graph::new();
+        let params = network.parameters();
+        let vars = graph.vars(&params);
+        let loss = network.loss_variable(&vars, &x, &y, &samples, Loss::CrossEntropy);
+        let gradient = loss.accumulate().wrt(&vars);
+
+        let h = 1e-6;
+        for k in 0..params.len() {
+            let mut bumped = network.clone();
+            let mut p = params.clone();
+
+            p[k] += h;
+            bumped.set_parameters(&p).unwrap();
+            let up = bumped.loss(&x, &y, Loss::CrossEntropy).unwrap();
+
+            p[k] -= 2.0 * h;
+            bumped.set_parameters(&p).unwrap();
+            let down = bumped.loss(&x, &y, Loss::CrossEntropy).unwrap();
+
+            assert!((gradient[k] - (up - down) / (2.0 * h)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_regression_mean_squared_error() {
+        let x = DMatrix::from_fn(40, 1, |i, _| -3.0 + 6.0 * i as f64 / 39.0);
+        let y = x.map(f64::sin);
+
+        let mut network = NeuralNetwork::new(1, 42)
+            .with_layer(16, Activation::Tanh)
+            .with_layer(1, Activation::Identity);
+
+        let config = TrainingConfig::new(Loss::MeanSquaredError, 0.02, 1500)
+            .with_batch_size(10)
+            .with_seed(3);
+        let losses = network.train(&x, &y, &config).unwrap();
+
+        assert_eq!(losses.len(), 1500);
+        assert!(losses[1499] < 1e-3);
+        assert!(losses[1499] < losses[0]);
+
+        let prediction = network.predict(&[1.0]).unwrap();
+        assert!((prediction[0] - 1_f64.sin()).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_classification_cross_entropy() {
+        // XOR is not linearly separable, so it needs the hidden layer.
+        let x = DMatrix::from_row_slice(4, 2, &[0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0]);
+        let y = DMatrix::from_row_slice(4, 1, &[0.0, 1.0, 1.0, 0.0]);
+
+        let mut network = NeuralNetwork::new(2, 5)
+            .with_layer(8, Activation::Tanh)
+            .with_layer(1, Activation::Sigmoid);
+
+        let config = TrainingConfig::new(Loss::CrossEntropy, 0.05, 1000);
+        network.train(&x, &y, &config).unwrap();
+
+        let predictions = network.predict_batch(&x).unwrap();
+        for i in 0..4 {
+            assert_eq!((predictions[(i, 0)] > 0.5) as u8 as f64, y[(i, 0)]);
+        }
+        assert!(network.loss(&x, &y, Loss::CrossEntropy).unwrap() < 0.05);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let x = DMatrix::from_element(4, 2, 0.5);
+        let y = DMatrix::from_element(4, 1, 2.0);
+
+        let mut network = NeuralNetwork::new(2, 0).with_layer(1, Activation::Identity);
+        let config = TrainingConfig::new(Loss::CrossEntropy, 0.1, 10);
+
+        // Identity output layer with cross-entropy.
+        assert!(network.train(&x, &y, &config).is_err());
+        // Wrong number of input columns.
+        assert!(network.predict(&[1.0]).is_err());
+        assert!(network
+            .train(
+                &DMatrix::zeros(4, 3),
+                &y,
+                &TrainingConfig::new(Loss::MeanSquaredError, 0.1, 1)
+            )
+            .is_err());
+        // Non-positive learning rate.
+        assert!(network
+            .train(&x, &y, &TrainingConfig::new(Loss::MeanSquaredError, 0.0, 1))
+            .is_err());
+
+        let mut network = NeuralNetwork::new(2, 0).with_layer(1, Activation::Sigmoid);
+        // Targets outside [0, 1].
+        assert!(network.train(&x, &y, &config).is_err());
+    }
+}
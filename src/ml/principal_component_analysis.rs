@@ -0,0 +1,361 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Principal component analysis (PCA).
+//!
+//! The principal components are the eigenvectors of the covariance matrix
+//! of the data, sorted by decreasing eigenvalue (the variance explained by
+//! each component). Applied to daily changes of a yield curve, the first
+//! three components are the familiar shift (level), twist (slope) and
+//! butterfly (curvature) factors.
+//!
+//! The components can also be used to generate scenarios in a reduced
+//! number of dimensions: independent Gaussian factor scores, with the
+//! variances of the retained components, are mapped back to the original
+//! variables through the loadings.
+//!
+//! ```
+//! use RustQuant::ml::PrincipalComponentAnalysis;
+//! use nalgebra::DMatrix;
+//!
+//! // Two perfectly correlated variables: one component explains everything.
+//! let data = DMatrix::from_row_slice(4, 2, &[1.0, 2.0, 2.0, 4.0, 3.0, 6.0, 4.0, 8.0]);
+//!
+//! let pca = PrincipalComponentAnalysis::fit(&data, None).unwrap();
+//!
+//! assert!((pca.explained_variance_ratio[0] - 1.0).abs() < 1e-12);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Fitted principal component analysis.
+#[derive(Clone, Debug)]
+pub struct PrincipalComponentAnalysis {
+    /// Mean of each variable (zero when fitted from a covariance matrix).
+    pub mean: DVector<f64>,
+    /// Factor loadings: one column per retained component, one row per
+    /// variable. Each column has unit norm, and its sign is chosen so that
+    /// its entries sum to a non-negative number.
+    pub loadings: DMatrix<f64>,
+    /// Variance explained by each retained component (the eigenvalues).
+    pub explained_variance: DVector<f64>,
+    /// Fraction of the total variance explained by each retained component.
+    pub explained_variance_ratio: DVector<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl PrincipalComponentAnalysis {
+    /// Fit the components of a data matrix, one observation per row.
+    /// Keeps `n_components` components, or all of them if `None`.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two observations, or if `n_components` is
+    /// zero or exceeds the number of variables.
+    pub fn fit(data: &DMatrix<f64>, n_components: Option<usize>) -> Result<Self, RustQuantError> {
+        let n_obs = data.nrows();
+
+        if n_obs < 2 || data.ncols() == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "PCA needs at least two observations of one variable.".to_string(),
+            ));
+        }
+
+        let mean = data.row_mean().transpose();
+        let centered = DMatrix::from_fn(n_obs, data.ncols(), |i, j| data[(i, j)] - mean[j]);
+        let covariance = centered.transpose() * &centered / (n_obs - 1) as f64;
+
+        let mut pca = Self::from_covariance(&covariance, n_components)?;
+        pca.mean = mean;
+
+        Ok(pca)
+    }
+
+    /// Fit the components of a covariance matrix.
+    /// Keeps `n_components` components, or all of them if `None`.
+    ///
+    /// # Errors
+    ///
+    /// If the matrix is empty or not square, or if `n_components` is zero
+    /// or exceeds the number of variables.
+    pub fn from_covariance(
+        covariance: &DMatrix<f64>,
+        n_components: Option<usize>,
+    ) -> Result<Self, RustQuantError> {
+        let n = covariance.nrows();
+
+        if n == 0 || !covariance.is_square() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Covariance matrix is {:?}.",
+                covariance.shape()
+            )));
+        }
+
+        let k = n_components.unwrap_or(n);
+
+        if k == 0 || k > n {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Cannot keep {k} components of {n} variables."
+            )));
+        }
+
+        let eigen = covariance.clone().symmetric_eigen();
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| eigen.eigenvalues[*b].total_cmp(&eigen.eigenvalues[*a]));
+
+        // Rounding can leave tiny negative eigenvalues of a singular matrix.
+        let eigenvalues = eigen.eigenvalues.map(|x| x.max(0.0));
+        let total = eigenvalues.sum();
+
+        let mut loadings = DMatrix::zeros(n, k);
+
+        for (c, &i) in order.iter().take(k).enumerate() {
+            let mut column = eigen.eigenvectors.column(i).into_owned();
+
+            if column.sum() < 0.0 {
+                column.neg_mut();
+            }
+            loadings.set_column(c, &column);
+        }
+
+        let explained_variance =
+            DVector::from_iterator(k, order.iter().take(k).map(|i| eigenvalues[*i]));
+        let explained_variance_ratio = if total > 0.0 {
+            &explained_variance / total
+        } else {
+            DVector::zeros(k)
+        };
+
+        Ok(Self {
+            mean: DVector::zeros(n),
+            loadings,
+            explained_variance,
+            explained_variance_ratio,
+        })
+    }
+
+    /// Number of retained components.
+    #[must_use]
+    pub fn n_components(&self) -> usize {
+        self.loadings.ncols()
+    }
+
+    /// Cumulative fraction of the variance explained by the first
+    /// components.
+    #[must_use]
+    pub fn cumulative_explained_variance_ratio(&self) -> DVector<f64> {
+        let mut total = 0.0;
+
+        self.explained_variance_ratio.map(|x| {
+            total += x;
+            total
+        })
+    }
+
+    /// Smallest number of components explaining at least `threshold` of
+    /// the variance (all retained components if none does).
+    #[must_use]
+    pub fn components_for_variance(&self, threshold: f64) -> usize {
+        self.cumulative_explained_variance_ratio()
+            .iter()
+            .position(|x| *x >= threshold)
+            .map_or(self.n_components(), |i| i + 1)
+    }
+
+    /// Factor scores of the observations (rows of `data`).
+    ///
+    /// # Errors
+    ///
+    /// If `data` has the wrong number of columns.
+    pub fn transform(&self, data: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+        if data.ncols() != self.mean.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected {} variables, got {}.",
+                self.mean.len(),
+                data.ncols()
+            )));
+        }
+
+        let centered = DMatrix::from_fn(data.nrows(), data.ncols(), |i, j| {
+            data[(i, j)] - self.mean[j]
+        });
+
+        Ok(centered * &self.loadings)
+    }
+
+    /// Observations reconstructed from their factor scores (rows of
+    /// `scores`).
+    ///
+    /// # Errors
+    ///
+    /// If `scores` has the wrong number of columns.
+    pub fn inverse_transform(&self, scores: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+        if scores.ncols() != self.n_components() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected {} components, got {}.",
+                self.n_components(),
+                scores.ncols()
+            )));
+        }
+
+        let mut data = scores * self.loadings.transpose();
+
+        for mut row in data.row_iter_mut() {
+            row += self.mean.transpose();
+        }
+
+        Ok(data)
+    }
+
+    /// Simulate `n_scenarios` observations (one per row) from independent
+    /// Gaussian factor scores with the variances of the retained
+    /// components.
+    #[must_use]
+    pub fn generate_scenarios(&self, n_scenarios: usize, seed: u64) -> DMatrix<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let std_devs = self.explained_variance.map(f64::sqrt);
+
+        let scores = DMatrix::from_fn(n_scenarios, self.n_components(), |_, c| {
+            let z: f64 = StandardNormal.sample(&mut rng);
+            std_devs[c] * z
+        });
+
+        self.inverse_transform(&scores)
+            .expect("Scores have one column per component.")
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_principal_component_analysis {
+    use super::*;
+
+    const EPS: f64 = 1e-10;
+
+    // Daily curve changes at 5 tenors driven by level, slope and curvature.
+    fn curve_changes() -> DMatrix<f64> {
+        let tenors = [1.0_f64, 2.0, 5.0, 10.0, 30.0];
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut data = DMatrix::zeros(500, 5);
+
+        for mut row in data.row_iter_mut() {
+            let level: f64 = StandardNormal.sample(&mut rng);
+            let slope: f64 = StandardNormal.sample(&mut rng);
+            let curvature: f64 = StandardNormal.sample(&mut rng);
+
+            for (j, t) in tenors.iter().enumerate() {
+                let x = (t / 5.0).ln() / 2.0;
+                row[j] = 10.0 * level + 3.0 * slope * x + curvature * (x * x - 0.3);
+            }
+        }
+
+        data
+    }
+
+    #[test]
+    fn test_two_by_two_covariance() {
+        let covariance = DMatrix::from_row_slice(2, 2, &[2.0, 1.0, 1.0, 2.0]);
+        let pca = PrincipalComponentAnalysis::from_covariance(&covariance, None).unwrap();
+
+        assert!((pca.explained_variance[0] - 3.0).abs() < EPS);
+        assert!((pca.explained_variance[1] - 1.0).abs() < EPS);
+        assert!((pca.explained_variance_ratio[0] - 0.75).abs() < EPS);
+
+        let h = 0.5_f64.sqrt();
+        assert!((pca.loadings[(0, 0)] - h).abs() < EPS);
+        assert!((pca.loadings[(1, 0)] - h).abs() < EPS);
+        assert!((pca.loadings[(0, 1)].abs() - h).abs() < EPS);
+        assert!((pca.loadings[(0, 1)] + pca.loadings[(1, 1)]).abs() < EPS);
+    }
+
+    #[test]
+    fn test_yield_curve_factors() {
+        let data = curve_changes();
+        let pca = PrincipalComponentAnalysis::fit(&data, None).unwrap();
+
+        // Level, slope and curvature explain all the variance.
+        let cumulative = pca.cumulative_explained_variance_ratio();
+        assert!(pca.explained_variance_ratio[0] > 0.9);
+        assert!((cumulative[2] - 1.0).abs() < EPS);
+        assert!((cumulative[4] - 1.0).abs() < EPS);
+        assert_eq!(pca.components_for_variance(0.999_999), 3);
+
+        // Shift: all tenors move together.
+        assert!(pca.loadings.column(0).iter().all(|x| *x > 0.0));
+        // Twist: short and long ends move in opposite directions.
+        assert!(pca.loadings[(0, 1)] * pca.loadings[(4, 1)] < 0.0);
+
+        // Loadings are orthonormal.
+        let gram = pca.loadings.transpose() * &pca.loadings;
+        assert!((gram - DMatrix::identity(5, 5)).norm() < EPS);
+    }
+
+    #[test]
+    fn test_transform_round_trip() {
+        let data = curve_changes();
+
+        let full = PrincipalComponentAnalysis::fit(&data, None).unwrap();
+        let scores = full.transform(&data).unwrap();
+        assert!((full.inverse_transform(&scores).unwrap() - &data).norm() < 1e-8);
+
+        // The scores are uncorrelated, with the component variances.
+        let covariance = scores.transpose() * &scores / (data.nrows() - 1) as f64;
+        assert!((covariance - DMatrix::from_diagonal(&full.explained_variance)).norm() < 1e-8);
+
+        // Three components reconstruct the three-factor data exactly.
+        let reduced = PrincipalComponentAnalysis::fit(&data, Some(3)).unwrap();
+        let scores = reduced.transform(&data).unwrap();
+        assert_eq!(scores.ncols(), 3);
+        assert!((reduced.inverse_transform(&scores).unwrap() - &data).norm() < 1e-8);
+
+        assert!(full.transform(&DMatrix::zeros(1, 4)).is_err());
+        assert!(reduced.inverse_transform(&DMatrix::zeros(1, 5)).is_err());
+    }
+
+    #[test]
+    fn test_generate_scenarios() {
+        let covariance =
+            DMatrix::from_row_slice(3, 3, &[4.0, 1.0, 0.5, 1.0, 2.0, 0.3, 0.5, 0.3, 1.0]);
+        let pca = PrincipalComponentAnalysis::from_covariance(&covariance, None).unwrap();
+
+        let scenarios = pca.generate_scenarios(100_000, 7);
+        let sample = scenarios.transpose() * &scenarios / scenarios.nrows() as f64;
+
+        assert!((sample - covariance).abs().max() < 0.05);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let data = DMatrix::from_element(1, 3, 1.0);
+        assert!(PrincipalComponentAnalysis::fit(&data, None).is_err());
+
+        let covariance = DMatrix::<f64>::identity(3, 3);
+        assert!(PrincipalComponentAnalysis::from_covariance(&covariance, Some(0)).is_err());
+        assert!(PrincipalComponentAnalysis::from_covariance(&covariance, Some(4)).is_err());
+        assert!(PrincipalComponentAnalysis::from_covariance(&DMatrix::zeros(2, 3), None).is_err());
+    }
+}
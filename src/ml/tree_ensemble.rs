@@ -0,0 +1,395 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Ensembles of regression trees.
+//!
+//! - Random forest (Breiman, 2001): the average of deep trees, each fitted
+//!   to a bootstrap sample with a random subset of the features considered
+//!   at each split. The out-of-bag error gives a free estimate of the
+//!   generalisation error.
+//! - Gradient boosting (Friedman, 2001) with squared loss: a sum of shallow
+//!   trees, each fitted to the residuals of the ensemble so far and shrunk
+//!   by the learning rate, optionally on a random subsample of the rows
+//!   (stochastic gradient boosting).
+//!
+//! ```
+//! use RustQuant::ml::*;
+//! use nalgebra::{DMatrix, DVector};
+//!
+//! // y = sin(x0) + x1^2 on a grid.
+//! let x = DMatrix::from_fn(400, 2, |i, j| if j == 0 { (i % 20) as f64 / 4.0 } else { (i / 20) as f64 / 20.0 });
+//! let y = DVector::from_fn(400, |i, _| x[(i, 0)].sin() + x[(i, 1)].powi(2));
+//!
+//! let forest = RandomForest::fit(&x, &y, 50, &TreeConfig::new(10).with_max_features(1)).unwrap();
+//! let boosting = GradientBoosting::fit(&x, &y, &BoostingConfig::new(200, 0.1, TreeConfig::new(3))).unwrap();
+//!
+//! let mse = |p: DVector<f64>| (p - &y).norm_squared() / 400.0;
+//! assert!(mse(forest.predict(&x).unwrap()) < 0.01);
+//! assert!(mse(boosting.predict(&x).unwrap()) < 0.01);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::ml::decision_tree::check_data;
+use crate::ml::{DecisionTree, TreeConfig};
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, seq::index::sample, Rng, SeedableRng};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Random forest regressor.
+#[derive(Debug, Clone)]
+pub struct RandomForest {
+    /// The trees of the forest.
+    pub trees: Vec<DecisionTree>,
+    /// Out-of-bag mean squared error (`NaN` if no sample was ever out of bag).
+    pub oob_error: f64,
+}
+
+/// Gradient boosting hyper-parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoostingConfig {
+    /// Number of boosting rounds (trees).
+    pub n_estimators: usize,
+    /// Shrinkage applied to each tree, in $(0, 1]$.
+    pub learning_rate: f64,
+    /// Fraction of the rows used to fit each tree, in $(0, 1]$.
+    pub subsample: f64,
+    /// Configuration of each tree.
+    pub tree: TreeConfig,
+}
+
+/// Gradient-boosted regression trees with squared loss.
+#[derive(Debug, Clone)]
+pub struct GradientBoosting {
+    /// Initial prediction (the mean target).
+    pub initial: f64,
+    /// Shrinkage applied to each tree.
+    pub learning_rate: f64,
+    /// The boosted trees.
+    pub trees: Vec<DecisionTree>,
+    /// Training mean squared error after each round.
+    pub training_loss: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Normalised sum of the importances of the trees.
+fn feature_importances(trees: &[DecisionTree]) -> Vec<f64> {
+    let n_features = trees.first().map_or(0, |tree| tree.n_features);
+    let mut importances = vec![0.0; n_features];
+
+    for tree in trees {
+        for (total, importance) in importances.iter_mut().zip(&tree.importances) {
+            *total += importance;
+        }
+    }
+
+    let total = importances.iter().sum::<f64>();
+    if total > 0.0 {
+        importances.iter_mut().for_each(|v| *v /= total);
+    }
+
+    importances
+}
+
+impl RandomForest {
+    /// Fit a random forest of `n_trees` trees. Each tree is grown with the
+    /// given configuration on a bootstrap sample; the seed of the
+    /// configuration seeds the bootstrap samples and the feature choices.
+    ///
+    /// A common choice of `max_features` for regression is a third of the
+    /// features.
+    ///
+    /// # Errors
+    ///
+    /// If `n_trees` is zero, the data is empty, the dimensions do not
+    /// match, the values are not finite, or the tree configuration is
+    /// invalid.
+    pub fn fit(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        n_trees: usize,
+        config: &TreeConfig,
+    ) -> Result<Self, RustQuantError> {
+        check_data(x, y)?;
+
+        if n_trees == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "a random forest needs at least one tree".to_string(),
+            ));
+        }
+
+        let n = x.nrows();
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut trees = Vec::with_capacity(n_trees);
+
+        // Sum and count of the out-of-bag predictions of each sample.
+        let mut oob_sum = vec![0.0; n];
+        let mut oob_count = vec![0_usize; n];
+
+        for _ in 0..n_trees {
+            let samples: Vec<usize> = (0..n).map(|_| rng.gen_range(0..n)).collect();
+            let tree_config = config.with_seed(rng.gen());
+            let tree = DecisionTree::fit_samples(x, y, &samples, &tree_config)?;
+
+            let mut in_bag = vec![false; n];
+            samples.iter().for_each(|i| in_bag[*i] = true);
+
+            let out_of_bag: Vec<usize> = (0..n).filter(|i| !in_bag[*i]).collect();
+            let predictions = tree.predict(&x.select_rows(&out_of_bag))?;
+
+            for (i, prediction) in out_of_bag.iter().zip(predictions.iter()) {
+                oob_sum[*i] += prediction;
+                oob_count[*i] += 1;
+            }
+
+            trees.push(tree);
+        }
+
+        let (squared_error, count) =
+            (0..n)
+                .filter(|i| oob_count[*i] > 0)
+                .fold((0.0, 0), |(total, count), i| {
+                    let error = oob_sum[i] / oob_count[i] as f64 - y[i];
+                    (total + error * error, count + 1)
+                });
+
+        Ok(Self {
+            trees,
+            oob_error: if count > 0 {
+                squared_error / count as f64
+            } else {
+                f64::NAN
+            },
+        })
+    }
+
+    /// Predictions for the rows of `x`: the average over the trees.
+    ///
+    /// # Errors
+    ///
+    /// If `x` does not have one column per feature.
+    pub fn predict(&self, x: &DMatrix<f64>) -> Result<DVector<f64>, RustQuantError> {
+        let mut total = DVector::zeros(x.nrows());
+
+        for tree in &self.trees {
+            total += tree.predict(x)?;
+        }
+
+        Ok(total / self.trees.len() as f64)
+    }
+
+    /// Relative importance of each feature (the reduction in squared error
+    /// from splits on the feature, summed over the trees and normalised to
+    /// sum to one).
+    #[must_use]
+    pub fn feature_importances(&self) -> Vec<f64> {
+        feature_importances(&self.trees)
+    }
+}
+
+impl BoostingConfig {
+    /// New configuration using every row to fit each tree.
+    #[must_use]
+    pub const fn new(n_estimators: usize, learning_rate: f64, tree: TreeConfig) -> Self {
+        Self {
+            n_estimators,
+            learning_rate,
+            subsample: 1.0,
+            tree,
+        }
+    }
+
+    /// Set the fraction of rows used to fit each tree.
+    #[must_use]
+    pub const fn with_subsample(mut self, subsample: f64) -> Self {
+        self.subsample = subsample;
+        self
+    }
+}
+
+impl GradientBoosting {
+    /// Fit gradient-boosted trees with squared loss.
+    ///
+    /// # Errors
+    ///
+    /// If the data is empty, the dimensions do not match, the values are
+    /// not finite, the learning rate or subsample fraction is not in
+    /// $(0, 1]$, or the tree configuration is invalid.
+    pub fn fit(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        config: &BoostingConfig,
+    ) -> Result<Self, RustQuantError> {
+        check_data(x, y)?;
+
+        let valid = |v: f64| v > 0.0 && v <= 1.0;
+        if !valid(config.learning_rate) || !valid(config.subsample) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "learning rate ({}) and subsample ({}) must be in (0, 1]",
+                config.learning_rate, config.subsample
+            )));
+        }
+
+        let n = x.nrows();
+        let n_sample = ((config.subsample * n as f64).round() as usize).max(1);
+        let mut rng = StdRng::seed_from_u64(config.tree.seed);
+
+        let initial = y.mean();
+        let mut prediction = DVector::from_element(n, initial);
+        let mut trees = Vec::with_capacity(config.n_estimators);
+        let mut training_loss = Vec::with_capacity(config.n_estimators);
+
+        for _ in 0..config.n_estimators {
+            // The negative gradient of the squared loss is the residual.
+            let residuals = y - &prediction;
+            let samples = if n_sample < n {
+                sample(&mut rng, n, n_sample).into_vec()
+            } else {
+                (0..n).collect()
+            };
+
+            let tree_config = config.tree.with_seed(rng.gen());
+            let tree = DecisionTree::fit_samples(x, &residuals, &samples, &tree_config)?;
+
+            prediction += tree.predict(x)? * config.learning_rate;
+            training_loss.push((y - &prediction).norm_squared() / n as f64);
+            trees.push(tree);
+        }
+
+        Ok(Self {
+            initial,
+            learning_rate: config.learning_rate,
+            trees,
+            training_loss,
+        })
+    }
+
+    /// Predictions for the rows of `x`.
+    ///
+    /// # Errors
+    ///
+    /// If `x` does not have one column per feature.
+    pub fn predict(&self, x: &DMatrix<f64>) -> Result<DVector<f64>, RustQuantError> {
+        let mut prediction = DVector::from_element(x.nrows(), self.initial);
+
+        for tree in &self.trees {
+            prediction += tree.predict(x)? * self.learning_rate;
+        }
+
+        Ok(prediction)
+    }
+
+    /// Relative importance of each feature, as for
+    /// [`RandomForest::feature_importances`].
+    #[must_use]
+    pub fn feature_importances(&self) -> Vec<f64> {
+        feature_importances(&self.trees)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_tree_ensemble {
+    use super::*;
+    use rand_distr::{Distribution, StandardNormal};
+
+    /// Noisy nonlinear target of the first two of four features.
+    fn data(n: usize, seed: u64) -> (DMatrix<f64>, DVector<f64>, DVector<f64>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let x = DMatrix::<f64>::from_fn(n, 4, |_, _| rng.gen_range(-1.0..1.0));
+        let signal = DVector::from_fn(n, |i, _| {
+            (3.0 * x[(i, 0)]).sin() + if x[(i, 1)] > 0.0 { 1.0 } else { -1.0 }
+        });
+        let y = DVector::from_fn(n, |i, _| {
+            let z: f64 = StandardNormal.sample(&mut rng);
+            signal[i] + 0.1 * z
+        });
+
+        (x, y, signal)
+    }
+
+    fn mse(prediction: &DVector<f64>, target: &DVector<f64>) -> f64 {
+        (prediction - target).norm_squared() / target.len() as f64
+    }
+
+    #[test]
+    fn test_random_forest() {
+        let (x, y, _) = data(500, 1);
+        let (x_test, _, signal) = data(500, 2);
+
+        let config = TreeConfig::new(12)
+            .with_max_features(2)
+            .with_min_samples_leaf(3);
+        let forest = RandomForest::fit(&x, &y, 100, &config).unwrap();
+
+        assert!(mse(&forest.predict(&x_test).unwrap(), &signal) < 0.05);
+        assert!(forest.oob_error > 0.01 && forest.oob_error < 0.1);
+
+        // The noise features are unimportant.
+        let importances = forest.feature_importances();
+        assert!((importances.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(importances[0] + importances[1] > 0.9);
+
+        // Same seed, same forest.
+        let again = RandomForest::fit(&x, &y, 100, &config).unwrap();
+        assert_eq!(again.trees, forest.trees);
+    }
+
+    #[test]
+    fn test_gradient_boosting() {
+        let (x, y, _) = data(500, 3);
+        let (x_test, _, signal) = data(500, 4);
+
+        let config = BoostingConfig::new(300, 0.05, TreeConfig::new(3).with_min_samples_leaf(5))
+            .with_subsample(0.7);
+        let boosting = GradientBoosting::fit(&x, &y, &config).unwrap();
+
+        assert!(mse(&boosting.predict(&x_test).unwrap(), &signal) < 0.05);
+        assert!(boosting.training_loss.last() < boosting.training_loss.first());
+
+        let importances = boosting.feature_importances();
+        assert!(importances[0] + importances[1] > 0.9);
+
+        // With a learning rate of one, a single deep tree interpolates.
+        let exact =
+            GradientBoosting::fit(&x, &y, &BoostingConfig::new(1, 1.0, TreeConfig::new(64)))
+                .unwrap();
+        assert!(exact.training_loss[0] < 1e-20);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let (x, y, _) = data(20, 5);
+
+        assert!(RandomForest::fit(&x, &y, 0, &TreeConfig::new(3)).is_err());
+        assert!(
+            GradientBoosting::fit(&x, &y, &BoostingConfig::new(10, 0.0, TreeConfig::new(3)))
+                .is_err()
+        );
+        assert!(GradientBoosting::fit(
+            &x,
+            &y,
+            &BoostingConfig::new(10, 0.1, TreeConfig::new(3)).with_subsample(1.5)
+        )
+        .is_err());
+        assert!(RandomForest::fit(&x, &y.rows(0, 10).into(), 10, &TreeConfig::new(3)).is_err());
+    }
+}
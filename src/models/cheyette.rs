@@ -0,0 +1,618 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Cheyette (quasi-Gaussian) one-factor short rate model.
+//!
+//! The short rate is $r_t = f(0, t) + x_t$, with the Markovian state
+//!
+//! $$
+//! dx_t = (y_t - \kappa x_t) \, dt + \sigma(x_t) \, dW_t, \qquad
+//! dy_t = (\sigma(x_t)^2 - 2 \kappa y_t) \, dt,
+//! $$
+//!
+//! starting at $x_0 = y_0 = 0$, and the bonds are known in closed form:
+//!
+//! $$
+//! P(t, T) = \frac{P(0, T)}{P(0, t)} e^{-G(t, T) x_t - \frac{1}{2} G(t, T)^2 y_t},
+//! \qquad G(t, T) = \frac{1 - e^{-\kappa (T - t)}}{\kappa}.
+//! $$
+//!
+//! With a constant $\sigma$ this is Hull-White; a local volatility
+//! $\sigma(x)$ gives the smile of the swaptions, while the model stays
+//! one-dimensional in the driving noise, which makes it cheaper than a
+//! LIBOR Market Model.
+//!
+//! Bermudan swaptions are priced by:
+//!
+//! - a Crank-Nicolson PDE in $x$, with $y$ frozen at its value along
+//!   $x = 0$ (exact for Hull-White);
+//! - Monte Carlo with the Longstaff-Schwartz regression of the
+//!   continuation value.
+
+use crate::error::RustQuantError;
+use crate::instruments::options::TypeFlag;
+use crate::instruments::{Price, Validate, Validator};
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Local volatility of the Cheyette state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CheyetteVolatility {
+    /// Constant (normal) volatility: the Hull-White model.
+    Constant {
+        /// Volatility of the short rate.
+        sigma: f64,
+    },
+
+    /// Affine volatility $\max(a + b x, 0)$: a skew in the rate.
+    Affine {
+        /// Volatility at $x = 0$, $a$.
+        level: f64,
+        /// Sensitivity of the volatility to the state, $b$.
+        slope: f64,
+    },
+}
+
+/// Cheyette model with a flat initial curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cheyette {
+    /// Continuously compounded rate of the (flat) initial curve.
+    pub initial_rate: f64,
+    /// Mean reversion, $\kappa$.
+    pub mean_reversion: f64,
+    /// Local volatility $\sigma(x)$.
+    pub volatility: CheyetteVolatility,
+}
+
+/// State of the Cheyette model on a simulated path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CheyetteState {
+    /// Short rate state $x$.
+    pub x: f64,
+    /// Accumulated variance $y$.
+    pub y: f64,
+    /// Discount factor from 0 along the path, $e^{-\int_0^t r_s ds}$.
+    pub discount: f64,
+}
+
+/// Simulated paths of the Cheyette model.
+#[derive(Debug, Clone)]
+pub struct CheyettePaths {
+    /// Times at which the states are recorded.
+    pub times: Vec<f64>,
+    /// States: `states[path][i]` at `times[i]`.
+    pub states: Vec<Vec<CheyetteState>>,
+}
+
+/// Bermudan swaption, exercisable into the remainder of a swap.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BermudanSwaption {
+    /// Swap schedule $T_0 < T_1 < \dots < T_n$: fixed payments at
+    /// $T_1, \dots, T_n$.
+    pub tenors: Vec<f64>,
+    /// Fixed rate of the swap.
+    pub strike: f64,
+    /// Payer ([`TypeFlag::Call`]) or receiver ([`TypeFlag::Put`]).
+    pub option_type: TypeFlag,
+    /// Number of exercise dates, the first tenor dates: exercising at
+    /// $T_k$ enters the swap over $[T_k, T_n]$. One gives a European
+    /// swaption.
+    pub exercises: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CheyetteVolatility {
+    /// Volatility at the state `x`.
+    #[must_use]
+    pub fn volatility(&self, x: f64) -> f64 {
+        match *self {
+            Self::Constant { sigma } => sigma,
+            Self::Affine { level, slope } => (level + slope * x).max(0.0),
+        }
+    }
+}
+
+impl Cheyette {
+    /// Initial discount factor $P(0, T)$.
+    #[must_use]
+    pub fn discount_factor(&self, T: f64) -> f64 {
+        (-self.initial_rate * T).exp()
+    }
+
+    /// Bond price $P(t, T)$ in the state `(x, y)` at time `t`.
+    #[must_use]
+    pub fn bond_price(&self, t: f64, T: f64, x: f64, y: f64) -> f64 {
+        let g = (1.0 - (-self.mean_reversion * (T - t)).exp()) / self.mean_reversion;
+
+        self.discount_factor(T) / self.discount_factor(t) * (-g * x - 0.5 * g * g * y).exp()
+    }
+
+    /// Simulate `n_paths` paths with Euler steps, about `steps_per_year` a
+    /// year, recording the state at the increasing `times`.
+    ///
+    /// Consecutive paths `2i` and `2i + 1` are antithetic, driven by opposite
+    /// normal draws.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the model fails
+    ///   [`Validate::validate`], the times are not positive and increasing,
+    ///   or there are no paths or steps.
+    pub fn simulate(
+        &self,
+        times: &[f64],
+        steps_per_year: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<CheyettePaths, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .check(!times.is_empty() && times[0] > 0.0, || {
+                "times must be positive".to_string()
+            })
+            .check(times.windows(2).all(|w| w[0] < w[1]), || {
+                "times must be increasing".to_string()
+            })
+            .check(n_paths > 0 && steps_per_year > 0, || {
+                "at least one path and one step are needed".to_string()
+            })
+            .finish()?;
+
+        let kappa = self.mean_reversion;
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let steps: Vec<(usize, f64)> = times
+            .iter()
+            .scan(0.0, |t, &t_next| {
+                let n_steps = ((t_next - *t) * steps_per_year as f64).ceil().max(1.0);
+                let dt = (t_next - *t) / n_steps;
+                *t = t_next;
+
+                Some((n_steps as usize, dt))
+            })
+            .collect();
+
+        let path = |normals: &[f64], sign: f64| -> Vec<CheyetteState> {
+            let (mut x, mut y, mut integral) = (0.0, 0.0, 0.0);
+            let mut normals = normals.iter();
+
+            times
+                .iter()
+                .zip(&steps)
+                .map(|(&t, &(n_steps, dt))| {
+                    for z in normals.by_ref().take(n_steps) {
+                        let sigma = self.volatility.volatility(x);
+                        let x_next = x + (y - kappa * x) * dt + sigma * dt.sqrt() * sign * z;
+
+                        y += (sigma * sigma - 2.0 * kappa * y) * dt;
+                        integral += 0.5 * (x + x_next) * dt;
+                        x = x_next;
+                    }
+
+                    CheyetteState {
+                        x,
+                        y,
+                        discount: self.discount_factor(t) * (-integral).exp(),
+                    }
+                })
+                .collect()
+        };
+
+        // Antithetic pairs: the discount factor is to first order linear in
+        // the noise, so a pair cancels most of its sampling error.
+        let total_steps: usize = steps.iter().map(|&(n_steps, _)| n_steps).sum();
+        let states = (0..n_paths.div_ceil(2))
+            .flat_map(|_| {
+                let normals: Vec<f64> = (0..total_steps)
+                    .map(|_| StandardNormal.sample(&mut rng))
+                    .collect();
+
+                [path(&normals, 1.0), path(&normals, -1.0)]
+            })
+            .take(n_paths)
+            .collect();
+
+        Ok(CheyettePaths {
+            times: times.to_vec(),
+            states,
+        })
+    }
+
+    /// Price a Bermudan swaption with a Crank-Nicolson PDE in `x`, on
+    /// `x_steps` intervals, with about `steps_per_year` time steps a year.
+    ///
+    /// The variance `y` is frozen at its value along `x = 0`, which is
+    /// exact for Hull-White and an approximation with a local volatility.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the model or the swaption
+    ///   fail [`Validate::validate`], or there are fewer than 3 space
+    ///   steps or no time steps.
+    pub fn price_pde(
+        &self,
+        swaption: &BermudanSwaption,
+        x_steps: usize,
+        steps_per_year: usize,
+    ) -> Result<f64, RustQuantError> {
+        self.validate()?;
+        swaption.validate()?;
+        Validator::new()
+            .check(x_steps >= 3 && steps_per_year > 0, || {
+                "at least 3 space steps and one time step are needed".to_string()
+            })
+            .finish()?;
+
+        let kappa = self.mean_reversion;
+        let sigma_0 = self.volatility.volatility(0.0);
+        let y_frozen =
+            |t: f64| sigma_0 * sigma_0 * (1.0 - (-2.0 * kappa * t).exp()) / (2.0 * kappa);
+
+        // Grid over six standard deviations of x at the last exercise.
+        let last = swaption.tenors[swaption.exercises - 1];
+        let width = 6.0 * y_frozen(last).sqrt();
+        let dx = 2.0 * width / x_steps as f64;
+        let xs: Vec<f64> = (0..=x_steps).map(|i| -width + dx * i as f64).collect();
+        let n = x_steps;
+
+        let mut values = vec![0.0; n + 1];
+        let mut rhs = vec![0.0; n + 1];
+        let (mut lo, mut di, mut up) = (vec![0.0; n + 1], vec![0.0; n + 1], vec![0.0; n + 1]);
+        let (mut c_prime, mut d_prime) = (vec![0.0; n + 1], vec![0.0; n + 1]);
+
+        for k in (0..swaption.exercises).rev() {
+            let t_end = swaption.tenors[k];
+            let t_start = match k {
+                0 => 0.0,
+                _ => swaption.tenors[k - 1],
+            };
+
+            for (value, &x) in values.iter_mut().zip(&xs) {
+                *value = f64::max(*value, swaption.exercise_value(self, k, x, y_frozen(t_end)));
+            }
+
+            let m = ((t_end - t_start) * steps_per_year as f64).ceil().max(1.0) as usize;
+            let dt = (t_end - t_start) / m as f64;
+
+            for step in 0..m {
+                // Fully implicit steps after the exercise kink (Rannacher).
+                let theta = if step < 2 { 1.0 } else { 0.5 };
+                let y = y_frozen(t_end - (step as f64 + 0.5) * dt);
+
+                for i in 1..n {
+                    let a = 0.5 * self.volatility.volatility(xs[i]).powi(2) / (dx * dx);
+                    let c = (y - kappa * xs[i]) / (2.0 * dx);
+
+                    lo[i] = a - c;
+                    di[i] = -2.0 * a - (self.initial_rate + xs[i]);
+                    up[i] = a + c;
+                    rhs[i] = values[i]
+                        + (1.0 - theta)
+                            * dt
+                            * (lo[i] * values[i - 1] + di[i] * values[i] + up[i] * values[i + 1]);
+                }
+
+                // Implicit part, with linear extrapolation at the
+                // boundaries (V_0 = 2 V_1 - V_2, and likewise at the top).
+                let sub = |i: usize| -theta * dt * lo[i];
+                let diag = |i: usize| 1.0 - theta * dt * di[i];
+                let sup = |i: usize| -theta * dt * up[i];
+
+                let (b_1, c_1) = (diag(1) + 2.0 * sub(1), sup(1) - sub(1));
+                c_prime[1] = c_1 / b_1;
+                d_prime[1] = rhs[1] / b_1;
+
+                for i in 2..n {
+                    let (a_i, mut b_i, mut c_i) = (sub(i), diag(i), sup(i));
+                    let a_i = match i == n - 1 {
+                        true => {
+                            b_i += 2.0 * c_i;
+                            let a_i = a_i - c_i;
+                            c_i = 0.0;
+                            a_i
+                        }
+                        false => a_i,
+                    };
+
+                    let denominator = b_i - a_i * c_prime[i - 1];
+                    c_prime[i] = c_i / denominator;
+                    d_prime[i] = (rhs[i] - a_i * d_prime[i - 1]) / denominator;
+                }
+
+                values[n - 1] = d_prime[n - 1];
+                for i in (1..n - 1).rev() {
+                    values[i] = d_prime[i] - c_prime[i] * values[i + 1];
+                }
+                values[0] = 2.0 * values[1] - values[2];
+                values[n] = 2.0 * values[n - 1] - values[n - 2];
+            }
+        }
+
+        // The grid is centred on x = 0.
+        Ok(match n % 2 {
+            0 => values[n / 2],
+            _ => 0.5 * (values[n / 2] + values[n / 2 + 1]),
+        })
+    }
+
+    /// Price a Bermudan swaption by Monte Carlo, exercising where the
+    /// exercise value exceeds the Longstaff-Schwartz regression of the
+    /// continuation value on `(1, x, x^2)` over the in-the-money paths.
+    ///
+    /// # Errors:
+    /// * As [`Cheyette::simulate`], or if the swaption fails
+    ///   [`Validate::validate`] or there are fewer than two paths.
+    pub fn price_monte_carlo(
+        &self,
+        swaption: &BermudanSwaption,
+        n_paths: usize,
+        steps_per_year: usize,
+        seed: u64,
+    ) -> Result<Price, RustQuantError> {
+        swaption.validate()?;
+        Validator::new()
+            .check(n_paths >= 2, || "at least two paths are needed".to_string())
+            .finish()?;
+
+        let exercise_times = &swaption.tenors[..swaption.exercises];
+        let paths = self.simulate(exercise_times, steps_per_year, n_paths, seed)?;
+
+        // Discounted (to today) cashflow of each path under the policy.
+        let mut cashflows = vec![0.0; n_paths];
+
+        for k in (0..swaption.exercises).rev() {
+            let in_the_money: Vec<(usize, CheyetteState, f64)> = paths
+                .states
+                .iter()
+                .enumerate()
+                .map(|(path, states)| {
+                    let state = states[k];
+                    (
+                        path,
+                        state,
+                        swaption.exercise_value(self, k, state.x, state.y),
+                    )
+                })
+                .filter(|&(_, _, exercise)| exercise > 0.0)
+                .collect();
+
+            let continuation = match k + 1 == swaption.exercises || in_the_money.len() < 3 {
+                true => None,
+                false => {
+                    let basis = DMatrix::from_fn(in_the_money.len(), 3, |row, column| {
+                        in_the_money[row].1.x.powi(column as i32)
+                    });
+                    let targets = DVector::from_iterator(
+                        in_the_money.len(),
+                        in_the_money
+                            .iter()
+                            .map(|&(path, state, _)| cashflows[path] / state.discount),
+                    );
+
+                    basis
+                        .clone()
+                        .svd(true, true)
+                        .solve(&targets, 1e-14)
+                        .map(|coefficients| basis * coefficients)
+                        .ok()
+                }
+            };
+
+            for (row, &(path, state, exercise)) in in_the_money.iter().enumerate() {
+                let exercised = match &continuation {
+                    Some(values) => exercise > values[row],
+                    None => true,
+                };
+
+                if exercised {
+                    cashflows[path] = exercise * state.discount;
+                }
+            }
+        }
+
+        // The antithetic pairs are the independent samples.
+        let pairs: Vec<f64> = cashflows
+            .chunks(2)
+            .map(|pair| pair.iter().sum::<f64>() / pair.len() as f64)
+            .collect();
+        let mean = cashflows.iter().sum::<f64>() / n_paths as f64;
+        let variance =
+            pairs.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / (pairs.len() - 1).max(1) as f64;
+
+        Ok(Price {
+            price: mean,
+            error: Some((variance / pairs.len() as f64).sqrt()),
+        })
+    }
+}
+
+impl Validate for Cheyette {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let sigma_0 = self.volatility.volatility(0.0);
+
+        Validator::new()
+            .finite("initial_rate", self.initial_rate)
+            .positive("mean_reversion", self.mean_reversion)
+            .positive("volatility at x = 0", sigma_0)
+            .check(
+                match self.volatility {
+                    CheyetteVolatility::Constant { .. } => true,
+                    CheyetteVolatility::Affine { slope, .. } => slope.is_finite(),
+                },
+                || "volatility slope must be finite".to_string(),
+            )
+            .finish()
+    }
+}
+
+impl BermudanSwaption {
+    /// Value of exercising at $T_k$ into the swap over $[T_k, T_n]$, in the
+    /// state `(x, y)`.
+    #[must_use]
+    pub fn exercise_value(&self, model: &Cheyette, k: usize, x: f64, y: f64) -> f64 {
+        let t = self.tenors[k];
+        let n = self.tenors.len() - 1;
+
+        let annuity: f64 = (k..n)
+            .map(|j| {
+                (self.tenors[j + 1] - self.tenors[j])
+                    * model.bond_price(t, self.tenors[j + 1], x, y)
+            })
+            .sum();
+        let payer = 1.0 - model.bond_price(t, self.tenors[n], x, y) - self.strike * annuity;
+
+        match self.option_type {
+            TypeFlag::Call => payer.max(0.0),
+            TypeFlag::Put => (-payer).max(0.0),
+        }
+    }
+}
+
+impl Validate for BermudanSwaption {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .check(self.tenors.len() >= 2, || {
+                "the swap needs at least one period".to_string()
+            })
+            .check(!self.tenors.is_empty() && self.tenors[0] > 0.0, || {
+                "the first exercise must be after today".to_string()
+            })
+            .check(self.tenors.windows(2).all(|w| w[0] < w[1]), || {
+                "tenor dates must be increasing".to_string()
+            })
+            .finite("strike", self.strike)
+            .check(
+                self.exercises >= 1 && self.exercises < self.tenors.len(),
+                || {
+                    format!(
+                        "exercises must lie in [1, {}] (got {})",
+                        self.tenors.len().saturating_sub(1),
+                        self.exercises
+                    )
+                },
+            )
+            .finish()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cheyette {
+    use super::*;
+
+    const HULL_WHITE: Cheyette = Cheyette {
+        initial_rate: 0.03,
+        mean_reversion: 0.05,
+        volatility: CheyetteVolatility::Constant { sigma: 0.01 },
+    };
+
+    const SKEWED: Cheyette = Cheyette {
+        volatility: CheyetteVolatility::Affine {
+            level: 0.01,
+            slope: 0.3,
+        },
+        ..HULL_WHITE
+    };
+
+    // 1y into 5y, annual payments, exercisable yearly.
+    fn swaption(exercises: usize) -> BermudanSwaption {
+        BermudanSwaption {
+            tenors: vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            strike: 0.03,
+            option_type: TypeFlag::Call,
+            exercises,
+        }
+    }
+
+    fn assert_within(price: Price, expected: f64, slack: f64) {
+        let error = price.error.unwrap();
+
+        assert!(
+            (price.price - expected).abs() < 4.0 * error + slack,
+            "{} vs {expected} (error {error})",
+            price.price
+        );
+    }
+
+    #[test]
+    fn test_cheyette_bonds_match_the_initial_curve() {
+        let times = [1.0, 3.0, 6.0];
+        let paths = SKEWED.simulate(&times, 50, 40_000, 1).unwrap();
+
+        for (i, &T) in times.iter().enumerate() {
+            let discounts: Vec<f64> = paths.states.iter().map(|s| s[i].discount).collect();
+            let mean = discounts.iter().sum::<f64>() / discounts.len() as f64;
+
+            assert_approx_equal!(mean, SKEWED.discount_factor(T), 5e-4);
+        }
+
+        // Bonds at the initial state are the initial curve.
+        assert_approx_equal!(
+            HULL_WHITE.bond_price(1.0, 4.0, 0.0, 0.0),
+            (-0.03 * 3.0_f64).exp(),
+            1e-15
+        );
+    }
+
+    #[test]
+    fn test_hull_white_european_swaption_pde() {
+        // Exact value, by Gaussian quadrature of the Hull-White state
+        // under the 1y forward measure.
+        let price = HULL_WHITE.price_pde(&swaption(1), 400, 100).unwrap();
+
+        assert_approx_equal!(price, 0.016_819_659_976, 2e-5);
+    }
+
+    #[test]
+    fn test_bermudan_swaption_pde_and_monte_carlo_agree() {
+        for (model, slack) in [(HULL_WHITE, 0.0), (SKEWED, 0.03)] {
+            let bermudan = swaption(5);
+            let pde = model.price_pde(&bermudan, 200, 50).unwrap();
+            let monte_carlo = model.price_monte_carlo(&bermudan, 5_000, 20, 1).unwrap();
+
+            // Longstaff-Schwartz is biased low, and the PDE approximates
+            // the skewed model.
+            assert_within(monte_carlo, pde, slack * pde + 2e-4);
+
+            // The Bermudan is worth more than each European it contains.
+            for exercises in 1..5 {
+                let european = BermudanSwaption {
+                    tenors: bermudan.tenors[exercises - 1..].to_vec(),
+                    exercises: 1,
+                    ..bermudan.clone()
+                };
+                assert!(pde > model.price_pde(&european, 200, 50).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cheyette_validation() {
+        let invalid = Cheyette {
+            mean_reversion: 0.0,
+            ..HULL_WHITE
+        };
+
+        assert!(invalid.price_pde(&swaption(1), 100, 10).is_err());
+        assert!(HULL_WHITE.price_pde(&swaption(6), 100, 10).is_err());
+        assert!(HULL_WHITE.simulate(&[2.0, 1.0], 10, 10, 1).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Exponentially weighted moving average (EWMA) volatility model.
+///
+/// The conditional variance is updated as:
+///
+/// $$
+/// \sigma_{t+1}^2 = \lambda \sigma_t^2 + (1 - \lambda) r_t^2
+/// $$
+///
+/// where $r_t$ are (zero-mean) returns. The recursion is seeded with the
+/// mean squared return. RiskMetrics uses $\lambda = 0.94$ for daily returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ewma {
+    /// Decay factor ($\lambda$), in $(0, 1)$.
+    pub lambda: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for Ewma {
+    fn default() -> Self {
+        Self::risk_metrics()
+    }
+}
+
+impl Ewma {
+    /// New EWMA model with the given decay factor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lambda` is not in $(0, 1)$.
+    #[must_use]
+    pub fn new(lambda: f64) -> Self {
+        assert!(lambda > 0.0 && lambda < 1.0, "lambda must be in (0, 1)");
+
+        Self { lambda }
+    }
+
+    /// RiskMetrics (1996) daily model, $\lambda = 0.94$.
+    #[must_use]
+    pub const fn risk_metrics() -> Self {
+        Self { lambda: 0.94 }
+    }
+
+    /// Conditional variances $\sigma_1^2, \dots, \sigma_{n+1}^2$ of the
+    /// given returns $r_1, \dots, r_n$.
+    ///
+    /// The last value is the one-step-ahead forecast.
+    /// Returns an empty vector if there are no returns.
+    #[must_use]
+    pub fn variances(&self, returns: &[f64]) -> Vec<f64> {
+        if returns.is_empty() {
+            return Vec::new();
+        }
+
+        let seed = returns.iter().map(|r| r * r).sum::<f64>() / returns.len() as f64;
+
+        std::iter::once(seed)
+            .chain(returns.iter().scan(seed, |variance, r| {
+                *variance = self.lambda * *variance + (1.0 - self.lambda) * r * r;
+                Some(*variance)
+            }))
+            .collect()
+    }
+
+    /// One-step-ahead (per-period) volatility forecast.
+    #[must_use]
+    pub fn volatility(&self, returns: &[f64]) -> f64 {
+        self.variances(returns)
+            .last()
+            .map_or(f64::NAN, |v| v.sqrt())
+    }
+
+    /// Variance forecasts for the next `horizon` periods.
+    ///
+    /// EWMA has no mean reversion, so the forecast is flat.
+    #[must_use]
+    pub fn forecast(&self, returns: &[f64], horizon: usize) -> Vec<f64> {
+        let variance = self.variances(returns).last().copied().unwrap_or(f64::NAN);
+
+        vec![variance; horizon]
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_ewma {
+    use super::*;
+
+    const EPS: f64 = 1e-12;
+
+    #[test]
+    fn test_ewma_variances() {
+        let returns = [0.01, -0.02, 0.015];
+        let ewma = Ewma::new(0.9);
+
+        let variances = ewma.variances(&returns);
+        assert_eq!(variances.len(), 4);
+
+        let seed = (0.0001 + 0.0004 + 0.000_225) / 3.0;
+        let v1 = 0.9 * seed + 0.1 * 0.0001;
+        let v2 = 0.9 * v1 + 0.1 * 0.0004;
+        let v3 = 0.9 * v2 + 0.1 * 0.000_225;
+
+        for (variance, expected) in variances.iter().zip([seed, v1, v2, v3]) {
+            assert_approx_equal!(*variance, expected, EPS);
+        }
+
+        let volatility = ewma.volatility(&returns);
+        assert_approx_equal!(volatility, v3.sqrt(), EPS);
+
+        let forecast = ewma.forecast(&returns, 3);
+        assert_eq!(forecast.len(), 3);
+        for variance in forecast {
+            assert_approx_equal!(variance, v3, EPS);
+        }
+    }
+
+    #[test]
+    fn test_ewma_defaults() {
+        assert_eq!(Ewma::default().lambda, 0.94);
+        assert!(Ewma::default().variances(&[]).is_empty());
+        assert!(Ewma::default().volatility(&[]).is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "lambda must be in (0, 1)")]
+    fn test_ewma_invalid_lambda() {
+        let _ = Ewma::new(1.0);
+    }
+}
@@ -0,0 +1,665 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! G2++: the two-factor additive Gaussian short rate model.
+//!
+//! The short rate is $r_t = x_t + y_t + \varphi(t)$, with
+//!
+//! $$
+//! dx_t = -a x_t \, dt + \sigma \, dW^1_t, \qquad
+//! dy_t = -b y_t \, dt + \eta \, dW^2_t, \qquad
+//! d\langle W^1, W^2 \rangle_t = \rho \, dt,
+//! $$
+//!
+//! $x_0 = y_0 = 0$, and $\varphi$ fitting the initial curve. Bonds are
+//! (Brigo and Mercurio, 2006, chapter 4)
+//!
+//! $$
+//! P(t, T) = \frac{P(0, T)}{P(0, t)}
+//! e^{\frac{1}{2} \left[ V(t, T) - V(0, T) + V(0, t) \right]
+//! - B_a(t, T) x_t - B_b(t, T) y_t},
+//! \qquad B_a(t, T) = \frac{1 - e^{-a (T - t)}}{a},
+//! $$
+//!
+//! with $V(t, T)$ the variance of $\int_t^T (x_s + y_s) ds$, so zero-bond
+//! options, caplets and caps are closed form. Swaptions use a normal
+//! approximation of the swap rate, whose sensitivities to the factors are
+//! frozen at $x = y = 0$. The two factors decorrelate the rates along the
+//! curve, which a one-factor model cannot.
+
+use crate::error::RustQuantError;
+use crate::instruments::options::{
+    bachelier_price, generalised_black_scholes_merton, nelder_mead, TypeFlag,
+};
+use crate::instruments::{Validate, Validator};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// G2++ model with a flat initial curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct G2PlusPlus {
+    /// Continuously compounded rate of the (flat) initial curve.
+    pub initial_rate: f64,
+    /// Mean reversion of the first factor, $a$.
+    pub mean_reversion_x: f64,
+    /// Volatility of the first factor, $\sigma$.
+    pub volatility_x: f64,
+    /// Mean reversion of the second factor, $b$.
+    pub mean_reversion_y: f64,
+    /// Volatility of the second factor, $\eta$.
+    pub volatility_y: f64,
+    /// Correlation of the factors, $\rho$.
+    pub correlation: f64,
+}
+
+/// State of the G2++ model on a simulated path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct G2PlusPlusState {
+    /// First factor $x$.
+    pub x: f64,
+    /// Second factor $y$.
+    pub y: f64,
+    /// Discount factor from 0 along the path, $e^{-\int_0^t r_s ds}$.
+    pub discount: f64,
+}
+
+/// Simulated paths of the G2++ model.
+#[derive(Debug, Clone)]
+pub struct G2PlusPlusPaths {
+    /// Times at which the states are recorded.
+    pub times: Vec<f64>,
+    /// States: `states[path][i]` at `times[i]`.
+    pub states: Vec<Vec<G2PlusPlusState>>,
+}
+
+/// Market price of a rate option, for calibration.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RateOptionQuote {
+    /// Cap on the periods $[T_j, T_{j + 1}]$ of `tenors`.
+    Cap {
+        /// Schedule $T_0 < \dots < T_n$, the first fixing at $T_0$.
+        tenors: Vec<f64>,
+        /// Cap rate.
+        strike: f64,
+        /// Market price.
+        price: f64,
+    },
+
+    /// European swaption, exercised at $T_0$ into a swap paying at
+    /// $T_1, \dots, T_n$.
+    Swaption {
+        /// Schedule $T_0 < \dots < T_n$.
+        tenors: Vec<f64>,
+        /// Fixed rate.
+        strike: f64,
+        /// Payer ([`TypeFlag::Call`]) or receiver ([`TypeFlag::Put`]).
+        option_type: TypeFlag,
+        /// Market price.
+        price: f64,
+    },
+}
+
+/// Result of a G2++ calibration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct G2PlusPlusCalibration {
+    /// Calibrated model.
+    pub model: G2PlusPlus,
+    /// Root mean square relative price error.
+    pub objective: f64,
+    /// Nelder-Mead iterations used.
+    pub iterations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl G2PlusPlus {
+    /// Initial discount factor $P(0, T)$.
+    #[must_use]
+    pub fn discount_factor(&self, T: f64) -> f64 {
+        (-self.initial_rate * T).exp()
+    }
+
+    /// Variance $V(t, T)$ of $\int_t^T (x_s + y_s) ds$ given the factors
+    /// at $t$.
+    #[must_use]
+    pub fn integrated_variance(&self, t: f64, T: f64) -> f64 {
+        let (a, sigma) = (self.mean_reversion_x, self.volatility_x);
+        let (b, eta) = (self.mean_reversion_y, self.volatility_y);
+        let tau = T - t;
+
+        let single = |k: f64, v: f64| {
+            v * v / (k * k)
+                * (tau + 2.0 / k * (-k * tau).exp()
+                    - 1.0 / (2.0 * k) * (-2.0 * k * tau).exp()
+                    - 3.0 / (2.0 * k))
+        };
+        let cross = 2.0 * self.correlation * sigma * eta / (a * b)
+            * (tau + (-a * tau).exp_m1() / a + (-b * tau).exp_m1() / b
+                - (-(a + b) * tau).exp_m1() / (a + b));
+
+        single(a, sigma) + single(b, eta) + cross
+    }
+
+    /// Bond price $P(t, T)$ given the factors `(x, y)` at time `t`.
+    #[must_use]
+    pub fn bond_price(&self, t: f64, T: f64, x: f64, y: f64) -> f64 {
+        let convexity = 0.5
+            * (self.integrated_variance(t, T) - self.integrated_variance(0.0, T)
+                + self.integrated_variance(0.0, t));
+
+        self.discount_factor(T) / self.discount_factor(t)
+            * (convexity
+                - loading(self.mean_reversion_x, T - t) * x
+                - loading(self.mean_reversion_y, T - t) * y)
+                .exp()
+    }
+
+    /// Price of a European option expiring at `T` on the bond maturing at
+    /// `S`, with strike `strike`.
+    #[must_use]
+    pub fn zero_bond_option(&self, T: f64, S: f64, strike: f64, option_type: TypeFlag) -> f64 {
+        let (a, sigma) = (self.mean_reversion_x, self.volatility_x);
+        let (b, eta) = (self.mean_reversion_y, self.volatility_y);
+
+        let variance = sigma * sigma / (2.0 * a.powi(3))
+            * (-a * (S - T)).exp_m1().powi(2)
+            * -(-2.0 * a * T).exp_m1()
+            + eta * eta / (2.0 * b.powi(3))
+                * (-b * (S - T)).exp_m1().powi(2)
+                * -(-2.0 * b * T).exp_m1()
+            + 2.0 * self.correlation * sigma * eta / (a * b * (a + b))
+                * (-a * (S - T)).exp_m1()
+                * (-b * (S - T)).exp_m1()
+                * -(-(a + b) * T).exp_m1();
+
+        // Black on the forward bond price, lognormal with total variance
+        // `variance` under the T-forward measure.
+        let forward = self.discount_factor(S) / self.discount_factor(T);
+
+        self.discount_factor(T)
+            * generalised_black_scholes_merton(
+                forward,
+                strike,
+                (variance / T).sqrt(),
+                0.0,
+                0.0,
+                T,
+                option_type,
+            )
+    }
+
+    /// Caplet on the rate over `[T, S]`, fixed at `T` and paid at `S`: a
+    /// put on the bond maturing at `S`.
+    #[must_use]
+    pub fn caplet(&self, T: f64, S: f64, strike: f64) -> f64 {
+        let scale = 1.0 + (S - T) * strike;
+
+        scale * self.zero_bond_option(T, S, 1.0 / scale, TypeFlag::Put)
+    }
+
+    /// Cap over the periods of `tenors`, the first fixing at `tenors[0]`.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the tenors are not a
+    ///   positive increasing schedule of at least two dates.
+    pub fn cap(&self, tenors: &[f64], strike: f64) -> Result<f64, RustQuantError> {
+        check_schedule(tenors)?;
+
+        Ok(tenors
+            .windows(2)
+            .map(|period| self.caplet(period[0], period[1], strike))
+            .sum())
+    }
+
+    /// Swap rate and annuity at time `t`, for the factors `(x, y)`, of the
+    /// swap paying at `tenors[1..]`.
+    #[must_use]
+    pub fn swap_rate(&self, t: f64, tenors: &[f64], x: f64, y: f64) -> (f64, f64) {
+        let bonds: Vec<f64> = tenors
+            .iter()
+            .map(|&T| self.bond_price(t, T, x, y))
+            .collect();
+        let annuity: f64 = tenors
+            .windows(2)
+            .zip(&bonds[1..])
+            .map(|(period, bond)| (period[1] - period[0]) * bond)
+            .sum();
+
+        ((bonds[0] - bonds[bonds.len() - 1]) / annuity, annuity)
+    }
+
+    /// European swaption exercised at `tenors[0]`, with the swap rate
+    /// approximated as normal: its sensitivities to the factors are frozen
+    /// at $x = y = 0$ and integrated to a Bachelier variance.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the tenors are not a
+    ///   positive increasing schedule of at least two dates.
+    pub fn swaption(
+        &self,
+        tenors: &[f64],
+        strike: f64,
+        option_type: TypeFlag,
+    ) -> Result<f64, RustQuantError> {
+        check_schedule(tenors)?;
+
+        const STEPS: usize = 100;
+
+        let (a, sigma) = (self.mean_reversion_x, self.volatility_x);
+        let (b, eta) = (self.mean_reversion_y, self.volatility_y);
+        let expiry = tenors[0];
+
+        // Factor sensitivities of the swap rate at (t, 0, 0), from
+        // dP(t, T) / dx = -B_a(t, T) P(t, T).
+        let sensitivities = |t: f64| {
+            let bonds: Vec<f64> = tenors
+                .iter()
+                .map(|&T| self.bond_price(t, T, 0.0, 0.0))
+                .collect();
+            let (rate, annuity) = self.swap_rate(t, tenors, 0.0, 0.0);
+            let n = tenors.len() - 1;
+
+            let derivative = |k: f64| {
+                let d_annuity: f64 = (0..n)
+                    .map(|j| {
+                        -(tenors[j + 1] - tenors[j]) * loading(k, tenors[j + 1] - t) * bonds[j + 1]
+                    })
+                    .sum();
+                let d_floating =
+                    -loading(k, tenors[0] - t) * bonds[0] + loading(k, tenors[n] - t) * bonds[n];
+
+                (d_floating - rate * d_annuity) / annuity
+            };
+
+            (derivative(a), derivative(b))
+        };
+
+        let dt = expiry / STEPS as f64;
+        let variance: f64 = (0..=STEPS)
+            .map(|i| {
+                let (s_x, s_y) = sensitivities(i as f64 * dt);
+                let weight = match i == 0 || i == STEPS {
+                    true => 0.5,
+                    false => 1.0,
+                };
+
+                weight
+                    * dt
+                    * (s_x * s_x * sigma * sigma
+                        + s_y * s_y * eta * eta
+                        + 2.0 * self.correlation * sigma * eta * s_x * s_y)
+            })
+            .sum();
+
+        let (rate, annuity) = self.swap_rate(0.0, tenors, 0.0, 0.0);
+
+        Ok(annuity
+            * bachelier_price(
+                rate,
+                strike,
+                (variance / expiry).sqrt(),
+                expiry,
+                option_type,
+            ))
+    }
+
+    /// Simulate `n_paths` paths, recording the state at the increasing
+    /// `times`. The factors are sampled exactly; the discount factor
+    /// integrates the rate by the trapezoid rule, with about
+    /// `steps_per_year` steps a year.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the model fails
+    ///   [`Validate::validate`], the times are not positive and increasing,
+    ///   or there are no paths or steps.
+    pub fn simulate(
+        &self,
+        times: &[f64],
+        steps_per_year: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<G2PlusPlusPaths, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .check(!times.is_empty() && times[0] > 0.0, || {
+                "times must be positive".to_string()
+            })
+            .check(times.windows(2).all(|w| w[0] < w[1]), || {
+                "times must be increasing".to_string()
+            })
+            .check(n_paths > 0 && steps_per_year > 0, || {
+                "at least one path and one step are needed".to_string()
+            })
+            .finish()?;
+
+        let (a, sigma) = (self.mean_reversion_x, self.volatility_x);
+        let (b, eta) = (self.mean_reversion_y, self.volatility_y);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let states = (0..n_paths)
+            .map(|_| {
+                let (mut x, mut y, mut integral) = (0.0, 0.0, 0.0);
+                let mut t = 0.0;
+
+                times
+                    .iter()
+                    .map(|&t_next| {
+                        let n_steps = ((t_next - t) * steps_per_year as f64).ceil().max(1.0);
+                        let dt = (t_next - t) / n_steps;
+
+                        // Exact Ornstein-Uhlenbeck transition of the pair.
+                        let (decay_x, decay_y) = ((-a * dt).exp(), (-b * dt).exp());
+                        let sd_x = sigma * (-(-2.0 * a * dt).exp_m1() / (2.0 * a)).sqrt();
+                        let sd_y = eta * (-(-2.0 * b * dt).exp_m1() / (2.0 * b)).sqrt();
+                        let rho = self.correlation * sigma * eta * -(-(a + b) * dt).exp_m1()
+                            / ((a + b) * sd_x * sd_y);
+
+                        for _ in 0..n_steps as usize {
+                            let z_1: f64 = StandardNormal.sample(&mut rng);
+                            let z_2: f64 = StandardNormal.sample(&mut rng);
+
+                            let x_next = x * decay_x + sd_x * z_1;
+                            let y_next =
+                                y * decay_y + sd_y * (rho * z_1 + (1.0 - rho * rho).sqrt() * z_2);
+
+                            integral += 0.5 * (x + y + x_next + y_next) * dt;
+                            (x, y) = (x_next, y_next);
+                        }
+                        t = t_next;
+
+                        // exp(-int phi) = P(0, t) exp(-V(0, t) / 2).
+                        G2PlusPlusState {
+                            x,
+                            y,
+                            discount: self.discount_factor(t)
+                                * (-0.5 * self.integrated_variance(0.0, t) - integral).exp(),
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(G2PlusPlusPaths {
+            times: times.to_vec(),
+            states,
+        })
+    }
+
+    /// Model price of a quote.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the quote's tenors are not
+    ///   a positive increasing schedule of at least two dates.
+    pub fn price_quote(&self, quote: &RateOptionQuote) -> Result<f64, RustQuantError> {
+        match quote {
+            RateOptionQuote::Cap { tenors, strike, .. } => self.cap(tenors, *strike),
+            RateOptionQuote::Swaption {
+                tenors,
+                strike,
+                option_type,
+                ..
+            } => self.swaption(tenors, *strike, *option_type),
+        }
+    }
+
+    /// Calibrate the factor parameters to the quotes, starting from this
+    /// model and keeping its initial curve, by minimising the relative
+    /// price errors with Nelder-Mead.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the starting model fails
+    ///   [`Validate::validate`], there are fewer quotes than the five
+    ///   parameters, or a quote has an invalid schedule or a non-positive
+    ///   price.
+    /// * [`RustQuantError::NonConvergence`] if no finite fit is found.
+    pub fn calibrate(
+        &self,
+        quotes: &[RateOptionQuote],
+        max_iterations: usize,
+    ) -> Result<G2PlusPlusCalibration, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .check(quotes.len() >= 5, || {
+                format!("at least 5 quotes are needed (got {})", quotes.len())
+            })
+            .check(quotes.iter().all(|quote| quote.price() > 0.0), || {
+                "quote prices must be positive".to_string()
+            })
+            .finish()?;
+        for quote in quotes {
+            self.price_quote(quote)?;
+        }
+
+        let from_unconstrained = |p: &[f64]| Self {
+            mean_reversion_x: p[0].exp(),
+            volatility_x: p[1].exp(),
+            mean_reversion_y: p[2].exp(),
+            volatility_y: p[3].exp(),
+            correlation: p[4].tanh(),
+            ..*self
+        };
+        let objective = |p: &[f64]| {
+            let model = from_unconstrained(p);
+
+            quotes
+                .iter()
+                .map(|quote| match model.price_quote(quote) {
+                    Ok(price) => ((price - quote.price()) / quote.price()).powi(2),
+                    Err(_) => f64::INFINITY,
+                })
+                .sum::<f64>()
+        };
+
+        let start = [
+            self.mean_reversion_x.ln(),
+            self.volatility_x.ln(),
+            self.mean_reversion_y.ln(),
+            self.volatility_y.ln(),
+            self.correlation.atanh(),
+        ];
+        let (best, value, iterations) = nelder_mead(objective, &start, max_iterations);
+
+        match value.is_finite() {
+            true => Ok(G2PlusPlusCalibration {
+                model: from_unconstrained(&best),
+                objective: (value / quotes.len() as f64).sqrt(),
+                iterations,
+            }),
+            false => Err(RustQuantError::NonConvergence(
+                "G2++ calibration found no finite fit".to_string(),
+            )),
+        }
+    }
+}
+
+impl Validate for G2PlusPlus {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .finite("initial_rate", self.initial_rate)
+            .positive("mean_reversion_x", self.mean_reversion_x)
+            .positive("volatility_x", self.volatility_x)
+            .positive("mean_reversion_y", self.mean_reversion_y)
+            .positive("volatility_y", self.volatility_y)
+            .check(self.correlation.abs() < 1.0, || {
+                format!("correlation must lie in (-1, 1) (got {})", self.correlation)
+            })
+            .finish()
+    }
+}
+
+impl RateOptionQuote {
+    /// Market price of the quote.
+    #[must_use]
+    pub fn price(&self) -> f64 {
+        match *self {
+            Self::Cap { price, .. } | Self::Swaption { price, .. } => price,
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Factor loading B_k(t, t + tau) = (1 - e^{-k tau}) / k.
+fn loading(k: f64, tau: f64) -> f64 {
+    -(-k * tau).exp_m1() / k
+}
+
+// A schedule of at least one period, starting after today.
+fn check_schedule(tenors: &[f64]) -> Result<(), RustQuantError> {
+    Validator::new()
+        .check(tenors.len() >= 2, || {
+            "the schedule needs at least one period".to_string()
+        })
+        .check(!tenors.is_empty() && tenors[0] > 0.0, || {
+            "the first fixing must be after today".to_string()
+        })
+        .check(tenors.windows(2).all(|w| w[0] < w[1]), || {
+            "tenor dates must be increasing".to_string()
+        })
+        .finish()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_g2_plus_plus {
+    use super::*;
+
+    const MODEL: G2PlusPlus = G2PlusPlus {
+        initial_rate: 0.03,
+        mean_reversion_x: 0.5,
+        volatility_x: 0.01,
+        mean_reversion_y: 0.05,
+        volatility_y: 0.008,
+        correlation: -0.7,
+    };
+
+    // Monte Carlo mean and standard error of a discounted payoff at T.
+    fn monte_carlo<F: Fn(G2PlusPlusState) -> f64>(T: f64, payoff: F) -> (f64, f64) {
+        let paths = MODEL.simulate(&[T], 50, 20_000, 1).unwrap();
+        let values: Vec<f64> = paths
+            .states
+            .iter()
+            .map(|states| payoff(states[0]) * states[0].discount)
+            .collect();
+
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        (mean, (variance / n).sqrt())
+    }
+
+    #[test]
+    fn test_g2_plus_plus_fits_the_initial_curve() {
+        for T in [1.0, 3.0] {
+            let (mean, error) = monte_carlo(T, |_| 1.0);
+
+            assert!((mean - MODEL.discount_factor(T)).abs() < 4.0 * error);
+        }
+
+        assert_approx_equal!(
+            MODEL.bond_price(0.0, 5.0, 0.0, 0.0),
+            MODEL.discount_factor(5.0),
+            1e-15
+        );
+    }
+
+    #[test]
+    fn test_g2_plus_plus_caplet_matches_monte_carlo() {
+        let (T, S, K) = (2.0, 2.5, 0.03);
+        let (mean, error) = monte_carlo(T, |state| {
+            let bond = MODEL.bond_price(T, S, state.x, state.y);
+
+            (1.0 - (1.0 + (S - T) * K) * bond).max(0.0)
+        });
+
+        // Reference value from an independent implementation.
+        assert_approx_equal!(MODEL.caplet(T, S, K), 0.001_533_728, 1e-8);
+        assert!((mean - MODEL.caplet(T, S, K)).abs() < 4.0 * error);
+    }
+
+    #[test]
+    fn test_g2_plus_plus_swaption_approximation() {
+        let tenors = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            let (mean, error) = monte_carlo(1.0, |state| {
+                let (rate, annuity) = MODEL.swap_rate(1.0, &tenors, state.x, state.y);
+
+                match option_type {
+                    TypeFlag::Call => annuity * (rate - 0.03).max(0.0),
+                    TypeFlag::Put => annuity * (0.03 - rate).max(0.0),
+                }
+            });
+            let price = MODEL.swaption(&tenors, 0.03, option_type).unwrap();
+
+            assert!((mean - price).abs() < 4.0 * error, "{mean} vs {price}");
+        }
+    }
+
+    #[test]
+    fn test_g2_plus_plus_calibration_reprices_quotes() {
+        let mut quotes = Vec::new();
+        for end in [3.0, 5.0, 7.0] {
+            let tenors: Vec<f64> = (1..=(end as usize)).map(|i| i as f64).collect();
+            quotes.push(RateOptionQuote::Cap {
+                price: MODEL.cap(&tenors, 0.03).unwrap(),
+                tenors,
+                strike: 0.03,
+            });
+        }
+        for (expiry, length) in [(1.0, 5.0), (2.0, 3.0), (5.0, 5.0), (1.0, 1.0)] {
+            let tenors: Vec<f64> = (0..=(length as usize)).map(|i| expiry + i as f64).collect();
+            quotes.push(RateOptionQuote::Swaption {
+                price: MODEL.swaption(&tenors, 0.03, TypeFlag::Call).unwrap(),
+                tenors,
+                strike: 0.03,
+                option_type: TypeFlag::Call,
+            });
+        }
+
+        let start = G2PlusPlus {
+            mean_reversion_x: 0.3,
+            volatility_x: 0.015,
+            mean_reversion_y: 0.1,
+            volatility_y: 0.005,
+            correlation: -0.3,
+            ..MODEL
+        };
+        let calibration = start.calibrate(&quotes, 2_000).unwrap();
+
+        assert!(calibration.objective < 1e-3, "{calibration:?}");
+    }
+
+    #[test]
+    fn test_g2_plus_plus_validation() {
+        let invalid = G2PlusPlus {
+            correlation: 1.0,
+            ..MODEL
+        };
+
+        assert!(invalid.simulate(&[1.0], 10, 10, 1).is_err());
+        assert!(MODEL.cap(&[1.0], 0.03).is_err());
+        assert!(MODEL.swaption(&[2.0, 1.0], 0.03, TypeFlag::Call).is_err());
+        assert!(MODEL.calibrate(&[], 10).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{variables::variable::Variable, Accumulate, Gradient, Graph};
+use crate::error::RustQuantError;
+use std::time::Instant;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// GARCH(1,1) volatility model (Bollerslev, 1986).
+///
+/// The conditional variance is updated as:
+///
+/// $$
+/// \sigma_{t+1}^2 = \omega + \alpha r_t^2 + \beta \sigma_t^2
+/// $$
+///
+/// where $r_t$ are (zero-mean) returns. The recursion is seeded with the
+/// mean squared return.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Garch {
+    /// Constant ($\omega > 0$).
+    pub omega: f64,
+    /// Weight of the last squared return ($\alpha \geq 0$).
+    pub alpha: f64,
+    /// Weight of the last variance ($\beta \geq 0$).
+    pub beta: f64,
+}
+
+/// Result of fitting a GARCH(1,1) model by maximum likelihood.
+#[derive(Debug, Clone)]
+pub struct GarchFit {
+    /// The fitted model.
+    pub model: Garch,
+    /// Gaussian log-likelihood at the fitted parameters.
+    pub log_likelihood: f64,
+    /// Conditional variances under the fitted model, see [`Garch::variances`].
+    pub variances: Vec<f64>,
+    /// Number of optimizer iterations.
+    pub iterations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Garch {
+    /// New GARCH(1,1) model.
+    #[must_use]
+    pub const fn new(omega: f64, alpha: f64, beta: f64) -> Self {
+        Self { omega, alpha, beta }
+    }
+
+    /// Persistence, $\alpha + \beta$.
+    #[must_use]
+    pub fn persistence(&self) -> f64 {
+        self.alpha + self.beta
+    }
+
+    /// Whether the model is covariance stationary ($\alpha + \beta < 1$).
+    #[must_use]
+    pub fn is_stationary(&self) -> bool {
+        self.persistence() < 1.0
+    }
+
+    /// Long-run (unconditional) variance, $\omega / (1 - \alpha - \beta)$.
+    ///
+    /// Infinite if the model is not stationary.
+    #[must_use]
+    pub fn long_run_variance(&self) -> f64 {
+        if self.is_stationary() {
+            self.omega / (1.0 - self.persistence())
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Conditional variances $\sigma_1^2, \dots, \sigma_{n+1}^2$ of the
+    /// given returns $r_1, \dots, r_n$.
+    ///
+    /// The last value is the one-step-ahead forecast.
+    /// Returns an empty vector if there are no returns.
+    #[must_use]
+    pub fn variances(&self, returns: &[f64]) -> Vec<f64> {
+        if returns.is_empty() {
+            return Vec::new();
+        }
+
+        let seed = mean_square(returns);
+
+        std::iter::once(seed)
+            .chain(returns.iter().scan(seed, |variance, r| {
+                *variance = self.omega + self.alpha * r * r + self.beta * *variance;
+                Some(*variance)
+            }))
+            .collect()
+    }
+
+    /// Gaussian log-likelihood of the returns.
+    #[must_use]
+    pub fn log_likelihood(&self, returns: &[f64]) -> f64 {
+        self.variances(returns)
+            .iter()
+            .zip(returns)
+            .map(|(variance, r)| {
+                -0.5 * ((2.0 * std::f64::consts::PI).ln() + variance.ln() + r * r / variance)
+            })
+            .sum()
+    }
+
+    /// Variance forecasts for the next `horizon` periods:
+    ///
+    /// $$
+    /// \sigma_{n+h}^2 = \sigma_L^2 + (\alpha + \beta)^{h-1} (\sigma_{n+1}^2 - \sigma_L^2)
+    /// $$
+    ///
+    /// where $\sigma_L^2$ is the long-run variance.
+    #[must_use]
+    pub fn forecast(&self, returns: &[f64], horizon: usize) -> Vec<f64> {
+        let next = self.variances(returns).last().copied().unwrap_or(f64::NAN);
+
+        (0..horizon)
+            .scan(next, |variance, _| {
+                let current = *variance;
+                *variance = self.omega + self.persistence() * current;
+                Some(current)
+            })
+            .collect()
+    }
+
+    /// Fit a GARCH(1,1) model to (zero-mean) returns by maximum likelihood.
+    ///
+    /// The negative log-likelihood is minimised with BFGS, using gradients
+    /// from the `autodiff` module. The parameters are transformed so that
+    /// $\omega > 0$, $\alpha, \beta > 0$, and $\alpha + \beta < 1$.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than 10 returns, the returns are all zero, or the
+    /// optimizer fails to converge.
+    pub fn fit(returns: &[f64]) -> Result<GarchFit, RustQuantError> {
+        if returns.len() < 10 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "GARCH fitting needs at least 10 returns, got {}",
+                returns.len()
+            )));
+        }
+
+        let scale = mean_square(returns);
+
+        if !(scale.is_finite() && scale > 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "returns must be finite and not all zero".to_string(),
+            ));
+        }
+
+        // Start at alpha = 0.05, beta = 0.90, and the sample variance.
+        let x0 = [0.05_f64.ln(), 0.0, 18.0_f64.ln()];
+
+        let (x, iterations) = minimize(|x| negative_log_likelihood(x, returns, scale), &x0)?;
+
+        let model = Self::from_unconstrained(x[0].exp(), x[1].exp(), x[2].exp(), scale);
+
+        Ok(GarchFit {
+            model,
+            log_likelihood: model.log_likelihood(returns),
+            variances: model.variances(returns),
+            iterations,
+        })
+    }
+
+    /// Parameters from the exponentials of the unconstrained parameters.
+    fn from_unconstrained(w: f64, a: f64, b: f64, scale: f64) -> Self {
+        Self {
+            omega: scale * w,
+            alpha: a / (1.0 + a + b),
+            beta: b / (1.0 + a + b),
+        }
+    }
+}
+
+/// Mean squared return.
+fn mean_square(returns: &[f64]) -> f64 {
+    returns.iter().map(|r| r * r).sum::<f64>() / returns.len() as f64
+}
+
+/// Negative log-likelihood (per observation, up to a constant) and its
+/// gradient with respect to the unconstrained parameters.
+fn negative_log_likelihood(x: &[f64], returns: &[f64], scale: f64) -> (f64, Vec<f64>) {
+    let graph = Graph::with_capacity(10 * returns.len());
+    let params = graph.vars(x);
+
+    let (a, b) = (params[1].exp(), params[2].exp());
+    let omega = scale * params[0].exp();
+    let alpha = a / (1.0 + a + b);
+    let beta = b / (1.0 + a + b);
+
+    // Returns are scaled so that the seed variance is one.
+    let mut variance: Variable = graph.var(1.0);
+    let mut nll: Variable = graph.var(0.0);
+
+    for r in returns {
+        let r2 = r * r / scale;
+        nll = nll + variance.ln() + r2 / variance;
+        variance = omega / scale + alpha * r2 + beta * variance;
+    }
+
+    let nll = 0.5 * nll / returns.len() as f64;
+
+    (nll.value, nll.accumulate().wrt(&params))
+}
+
+/// Minimise a smooth function with BFGS and a backtracking line search.
+///
+/// Returns the minimiser and the number of iterations.
+fn minimize<F>(f: F, x0: &[f64]) -> Result<(Vec<f64>, usize), RustQuantError>
+where
+    F: Fn(&[f64]) -> (f64, Vec<f64>),
+{
+    const MAX_ITERATIONS: usize = 500;
+    const TOLERANCE: f64 = 1e-8;
+
+    let n = x0.len();
+    let dot = |u: &[f64], v: &[f64]| u.iter().zip(v).map(|(a, b)| a * b).sum::<f64>();
+
+    let mut x = x0.to_vec();
+    let (mut fx, mut gx) = f(&x);
+
+    // Inverse Hessian approximation.
+    let identity = |n: usize| {
+        (0..n)
+            .map(|i| (0..n).map(|j| f64::from(u8::from(i == j))).collect())
+            .collect::<Vec<Vec<f64>>>()
+    };
+    let mut h = identity(n);
+
+    let start = Instant::now();
+    let _span = trace_span!(DEBUG, "garch_calibration", parameters = n);
+
+    for iteration in 0..MAX_ITERATIONS {
+        trace_event!(
+            TRACE,
+            "iteration",
+            iteration = iteration,
+            error = dot(&gx, &gx).sqrt(),
+            value = fx,
+            elapsed = start.elapsed().as_secs_f64(),
+        );
+
+        if dot(&gx, &gx).sqrt() < TOLERANCE {
+            return Ok((x, iteration));
+        }
+
+        let mut direction: Vec<f64> = h.iter().map(|row| -dot(row, &gx)).collect();
+
+        // Reset to steepest descent if the direction is not a descent direction.
+        if dot(&direction, &gx) >= 0.0 {
+            h = identity(n);
+            direction = gx.iter().map(|g| -g).collect();
+        }
+
+        // Backtracking (Armijo) line search.
+        let slope = dot(&direction, &gx);
+        let mut step = 1.0;
+        let (x_new, f_new, g_new) = loop {
+            let candidate: Vec<f64> = x
+                .iter()
+                .zip(&direction)
+                .map(|(x, d)| x + step * d)
+                .collect();
+            let (f_candidate, g_candidate) = f(&candidate);
+
+            if f_candidate.is_finite() && f_candidate <= fx + 1e-4 * step * slope {
+                break (candidate, f_candidate, g_candidate);
+            }
+
+            step *= 0.5;
+
+            if step < 1e-12 {
+                // No further progress possible: accept the current point.
+                return Ok((x, iteration));
+            }
+        };
+
+        let s: Vec<f64> = x_new.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let y: Vec<f64> = g_new.iter().zip(&gx).map(|(a, b)| a - b).collect();
+        let sy = dot(&s, &y);
+
+        // BFGS update of the inverse Hessian (skipped if curvature is not positive).
+        if sy > 1e-12 {
+            let hy: Vec<f64> = h.iter().map(|row| dot(row, &y)).collect();
+            let yhy = dot(&y, &hy);
+
+            for i in 0..n {
+                for j in 0..n {
+                    h[i][j] +=
+                        (sy + yhy) * s[i] * s[j] / (sy * sy) - (hy[i] * s[j] + s[i] * hy[j]) / sy;
+                }
+            }
+        }
+
+        x = x_new;
+        fx = f_new;
+        gx = g_new;
+    }
+
+    Err(RustQuantError::ComputationError(format!(
+        "GARCH fit did not converge in {MAX_ITERATIONS} iterations"
+    )))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_garch {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, StandardNormal};
+
+    const EPS: f64 = 1e-12;
+
+    /// Simulate returns from a GARCH(1,1) model.
+    fn simulate(model: &Garch, n: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut variance = model.long_run_variance();
+
+        (0..n)
+            .map(|_| {
+                let z: f64 = StandardNormal.sample(&mut rng);
+                let r = variance.sqrt() * z;
+                variance = model.omega + model.alpha * r * r + model.beta * variance;
+                r
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_garch_variances() {
+        let model = Garch::new(1e-6, 0.1, 0.85);
+        let returns = [0.01, -0.02];
+
+        let variances = model.variances(&returns);
+        let seed = (0.0001 + 0.0004) / 2.0;
+        let v1 = 1e-6 + 0.1 * 0.0001 + 0.85 * seed;
+        let v2 = 1e-6 + 0.1 * 0.0004 + 0.85 * v1;
+
+        for (variance, expected) in variances.iter().zip([seed, v1, v2]) {
+            assert_approx_equal!(*variance, expected, EPS);
+        }
+    }
+
+    #[test]
+    fn test_garch_forecast() {
+        let model = Garch::new(1e-6, 0.1, 0.85);
+        let returns = [0.05, -0.04, 0.03];
+
+        let forecast = model.forecast(&returns, 500);
+        let next = *model.variances(&returns).last().unwrap();
+        let long_run = model.long_run_variance();
+
+        assert_approx_equal!(forecast[0], next, EPS);
+
+        let h = 10;
+        let expected = long_run + 0.95_f64.powi(h - 1) * (next - long_run);
+        assert_approx_equal!(forecast[h as usize - 1], expected, EPS);
+
+        // Forecasts revert to the long-run variance.
+        assert!((forecast[499] - long_run).abs() < 1e-10);
+        assert!(Garch::new(1e-6, 0.2, 0.8).long_run_variance().is_infinite());
+    }
+
+    #[test]
+    fn test_garch_fit() {
+        let model = Garch::new(2e-6, 0.08, 0.9);
+        let returns = simulate(&model, 5000);
+
+        let fit = Garch::fit(&returns).unwrap();
+
+        assert!(fit.model.is_stationary());
+        assert!((fit.model.alpha - model.alpha).abs() < 0.03);
+        assert!((fit.model.beta - model.beta).abs() < 0.05);
+        assert!((fit.model.long_run_variance() / model.long_run_variance() - 1.0).abs() < 0.25);
+
+        // The fit maximises the likelihood.
+        assert!(fit.log_likelihood >= model.log_likelihood(&returns));
+        assert_eq!(fit.variances.len(), returns.len() + 1);
+    }
+
+    #[test]
+    fn test_garch_fit_invalid() {
+        assert!(Garch::fit(&[0.01; 5]).is_err());
+        assert!(Garch::fit(&[0.0; 100]).is_err());
+    }
+}
@@ -0,0 +1,669 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Hidden Markov regime-switching model with Gaussian emissions.
+//!
+//! The market is in one of $K$ unobserved regimes $s_t$, which follows a
+//! Markov chain with transition matrix $A_{ij} = P(s_{t+1} = j | s_t = i)$,
+//! and the return in regime $k$ is $N(\mu_k, \sigma_k^2)$.
+//!
+//! - The parameters are fitted with the Baum-Welch (EM) algorithm.
+//! - The regime probabilities are computed with the scaled
+//!   forward-backward recursions (filtered and smoothed).
+//! - The most likely regime path is decoded with the Viterbi algorithm.
+//!
+//! The one-step-ahead regime probabilities give regime-conditional mean and
+//! volatility forecasts, which can be used to condition strategy signals.
+//!
+//! ```
+//! use RustQuant::models::GaussianHmm;
+//!
+//! // Calm, then turbulent returns.
+//! let returns: Vec<f64> = (0..400)
+//!     .map(|t| {
+//!         let noise = ((t * 7919) % 101) as f64 / 50.0 - 1.0;
+//!         if t < 200 { 0.01 * noise } else { 0.05 * noise }
+//!     })
+//!     .collect();
+//!
+//! let fit = GaussianHmm::fit(&returns, 2).unwrap();
+//! let regimes = fit.model.viterbi(&returns).unwrap();
+//!
+//! // States are ordered by variance: 0 is the calm regime.
+//! assert_eq!(regimes[100], 0);
+//! assert_eq!(regimes[300], 1);
+//! ```
+
+use crate::error::RustQuantError;
+use nalgebra::DMatrix;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Hidden Markov model with univariate Gaussian emissions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianHmm {
+    /// Probabilities of the first regime.
+    pub initial: Vec<f64>,
+    /// Transition matrix, $A_{ij} = P(s_{t+1} = j | s_t = i)$.
+    pub transition: DMatrix<f64>,
+    /// Mean of the returns in each regime.
+    pub means: Vec<f64>,
+    /// Variance of the returns in each regime.
+    pub variances: Vec<f64>,
+}
+
+/// Result of fitting a hidden Markov model with Baum-Welch.
+#[derive(Debug, Clone)]
+pub struct HmmFit {
+    /// The fitted model, with the regimes sorted by increasing variance.
+    pub model: GaussianHmm,
+    /// Log-likelihood at the fitted parameters.
+    pub log_likelihood: f64,
+    /// Number of EM iterations.
+    pub iterations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Baum-Welch settings. The variance floor (relative to the sample
+// variance) stops a regime collapsing onto a single observation.
+const MAX_ITERATIONS: usize = 1000;
+const TOLERANCE: f64 = 1e-8;
+const VARIANCE_FLOOR: f64 = 1e-6;
+
+/// Scaled forward and backward variables.
+struct ForwardBackward {
+    /// Normalised forward variables, $P(s_t | r_1, \dots, r_t)$.
+    alpha: Vec<Vec<f64>>,
+    /// Scaled backward variables.
+    beta: Vec<Vec<f64>>,
+    /// Scaling constants, $P(r_t | r_1, \dots, r_{t-1})$.
+    scale: Vec<f64>,
+}
+
+/// Whether the probabilities lie in [0, 1] and sum to one.
+fn is_distribution(probabilities: impl Iterator<Item = f64>) -> bool {
+    let mut total = 0.0;
+
+    for p in probabilities {
+        if !(0.0..=1.0).contains(&p) {
+            return false;
+        }
+        total += p;
+    }
+
+    (total - 1.0_f64).abs() < 1e-8
+}
+
+impl GaussianHmm {
+    /// New hidden Markov model.
+    ///
+    /// # Errors
+    ///
+    /// If the dimensions are inconsistent, a variance is not positive, or
+    /// the initial or transition probabilities do not sum to one.
+    pub fn new(
+        initial: Vec<f64>,
+        transition: DMatrix<f64>,
+        means: Vec<f64>,
+        variances: Vec<f64>,
+    ) -> Result<Self, RustQuantError> {
+        let k = initial.len();
+
+        if k == 0 || transition.shape() != (k, k) || means.len() != k || variances.len() != k {
+            return Err(RustQuantError::InvalidArgument(
+                "inconsistent hidden Markov model dimensions".to_string(),
+            ));
+        }
+        if variances.iter().any(|v| v.is_nan() || *v <= 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "regime variances must be positive".to_string(),
+            ));
+        }
+
+        if !is_distribution(initial.iter().copied())
+            || !transition
+                .row_iter()
+                .all(|row| is_distribution(row.iter().copied()))
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "initial and transition probabilities must sum to one".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            initial,
+            transition,
+            means,
+            variances,
+        })
+    }
+
+    /// Number of regimes.
+    #[must_use]
+    pub fn n_states(&self) -> usize {
+        self.initial.len()
+    }
+
+    /// Stationary distribution of the regimes, $\pi = \pi A$, found by
+    /// power iteration.
+    #[must_use]
+    pub fn stationary_distribution(&self) -> Vec<f64> {
+        let k = self.n_states();
+        let mut pi = vec![1.0 / k as f64; k];
+
+        for _ in 0..10_000 {
+            let next = self.step(&pi);
+            let change = next
+                .iter()
+                .zip(&pi)
+                .map(|(a, b)| (a - b).abs())
+                .sum::<f64>();
+            pi = next;
+
+            if change < 1e-14 {
+                break;
+            }
+        }
+
+        pi
+    }
+
+    /// Expected duration of each regime, $1 / (1 - A_{kk})$.
+    #[must_use]
+    pub fn expected_durations(&self) -> Vec<f64> {
+        (0..self.n_states())
+            .map(|k| 1.0 / (1.0 - self.transition[(k, k)]))
+            .collect()
+    }
+
+    /// Log-likelihood of the returns.
+    ///
+    /// # Errors
+    ///
+    /// If there are no returns.
+    pub fn log_likelihood(&self, returns: &[f64]) -> Result<f64, RustQuantError> {
+        Ok(self
+            .forward_backward(returns)?
+            .scale
+            .iter()
+            .map(|c| c.ln())
+            .sum())
+    }
+
+    /// Filtered regime probabilities, $P(s_t = k | r_1, \dots, r_t)$.
+    ///
+    /// # Errors
+    ///
+    /// If there are no returns.
+    pub fn filtered_probabilities(&self, returns: &[f64]) -> Result<Vec<Vec<f64>>, RustQuantError> {
+        Ok(self.forward_backward(returns)?.alpha)
+    }
+
+    /// Smoothed regime probabilities, $P(s_t = k | r_1, \dots, r_n)$.
+    ///
+    /// # Errors
+    ///
+    /// If there are no returns.
+    pub fn smoothed_probabilities(&self, returns: &[f64]) -> Result<Vec<Vec<f64>>, RustQuantError> {
+        let fb = self.forward_backward(returns)?;
+
+        Ok(fb
+            .alpha
+            .iter()
+            .zip(&fb.beta)
+            .map(|(a, b)| a.iter().zip(b).map(|(a, b)| a * b).collect())
+            .collect())
+    }
+
+    /// Regime probabilities `horizon` periods after the last return.
+    ///
+    /// # Errors
+    ///
+    /// If there are no returns.
+    pub fn predicted_probabilities(
+        &self,
+        returns: &[f64],
+        horizon: usize,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        let filtered = self.filtered_probabilities(returns)?;
+        let last = filtered.last().cloned().unwrap_or_default();
+
+        Ok((0..horizon).fold(last, |p, _| self.step(&p)))
+    }
+
+    /// Regime-conditional forecast of the next return: its mean and
+    /// volatility under the mixture of the one-step-ahead regime
+    /// probabilities.
+    ///
+    /// # Errors
+    ///
+    /// If there are no returns.
+    pub fn forecast(&self, returns: &[f64]) -> Result<(f64, f64), RustQuantError> {
+        let p = self.predicted_probabilities(returns, 1)?;
+
+        let mean = p.iter().zip(&self.means).map(|(p, m)| p * m).sum::<f64>();
+        let second_moment = (0..self.n_states())
+            .map(|k| p[k] * (self.variances[k] + self.means[k].powi(2)))
+            .sum::<f64>();
+
+        Ok((mean, (second_moment - mean * mean).max(0.0).sqrt()))
+    }
+
+    /// Most likely regime path (Viterbi algorithm).
+    ///
+    /// # Errors
+    ///
+    /// If there are no returns.
+    pub fn viterbi(&self, returns: &[f64]) -> Result<Vec<usize>, RustQuantError> {
+        if returns.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "no returns to decode".to_string(),
+            ));
+        }
+
+        let k = self.n_states();
+        let n = returns.len();
+        let log_a = self.transition.map(f64::ln);
+
+        let mut delta: Vec<f64> = (0..k)
+            .map(|j| self.initial[j].ln() + self.log_density(j, returns[0]))
+            .collect();
+        let mut backpointers = vec![vec![0; k]; n];
+
+        for (t, r) in returns.iter().enumerate().skip(1) {
+            let previous = delta.clone();
+
+            for j in 0..k {
+                let (best, value) = (0..k).map(|i| (i, previous[i] + log_a[(i, j)])).fold(
+                    (0, f64::NEG_INFINITY),
+                    |best, x| if x.1 > best.1 { x } else { best },
+                );
+
+                backpointers[t][j] = best;
+                delta[j] = value + self.log_density(j, *r);
+            }
+        }
+
+        let mut state = (0..k).fold(0, |best, j| if delta[j] > delta[best] { j } else { best });
+        let mut path = vec![state; n];
+
+        for t in (1..n).rev() {
+            state = backpointers[t][state];
+            path[t - 1] = state;
+        }
+
+        Ok(path)
+    }
+
+    /// Fit a model with `n_states` regimes to the returns by Baum-Welch.
+    ///
+    /// The means and variances start from the quantile groups of the
+    /// returns, with persistent regimes ($A_{kk} = 0.9$). The fitted regimes
+    /// are sorted by increasing variance.
+    ///
+    /// # Errors
+    ///
+    /// If `n_states` is zero, there are fewer than `10 * n_states` returns,
+    /// or the returns have zero variance.
+    pub fn fit(returns: &[f64], n_states: usize) -> Result<HmmFit, RustQuantError> {
+        let n = returns.len();
+        let k = n_states;
+
+        if k == 0 || n < 10 * k {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need at least {} returns to fit {k} regimes, got {n}",
+                10 * k.max(1)
+            )));
+        }
+
+        let mean = returns.iter().sum::<f64>() / n as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n as f64;
+
+        let mut sorted = returns.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        if !variance.is_finite() || sorted[0] == sorted[n - 1] {
+            return Err(RustQuantError::InvalidArgument(
+                "returns must be finite and not constant".to_string(),
+            ));
+        }
+
+        let floor = VARIANCE_FLOOR * variance;
+
+        // Initialise from the quantile groups of the returns.
+        let groups: Vec<&[f64]> = (0..k)
+            .map(|j| &sorted[j * n / k..(j + 1) * n / k])
+            .collect();
+        let stay = if k == 1 { 1.0 } else { 0.9 };
+
+        let mut model = Self {
+            initial: vec![1.0 / k as f64; k],
+            transition: DMatrix::from_fn(k, k, |i, j| {
+                if i == j {
+                    stay
+                } else {
+                    (1.0 - stay) / (k - 1) as f64
+                }
+            }),
+            means: groups
+                .iter()
+                .map(|g| g.iter().sum::<f64>() / g.len() as f64)
+                .collect(),
+            variances: groups
+                .iter()
+                .map(|g| {
+                    let m = g.iter().sum::<f64>() / g.len() as f64;
+                    (g.iter().map(|r| (r - m).powi(2)).sum::<f64>() / g.len() as f64).max(floor)
+                })
+                .collect(),
+        };
+
+        let mut log_likelihood = f64::NEG_INFINITY;
+        let mut iterations = 0;
+
+        while iterations < MAX_ITERATIONS {
+            iterations += 1;
+
+            let fb = model.forward_backward(returns)?;
+            let current = fb.scale.iter().map(|c| c.ln()).sum::<f64>();
+
+            // E step: regime and transition probabilities.
+            let gamma: Vec<Vec<f64>> = fb
+                .alpha
+                .iter()
+                .zip(&fb.beta)
+                .map(|(a, b)| a.iter().zip(b).map(|(a, b)| a * b).collect())
+                .collect();
+
+            let mut xi = DMatrix::<f64>::zeros(k, k);
+
+            for t in 0..n - 1 {
+                for i in 0..k {
+                    for j in 0..k {
+                        xi[(i, j)] += fb.alpha[t][i]
+                            * model.transition[(i, j)]
+                            * model.density(j, returns[t + 1])
+                            * fb.beta[t + 1][j]
+                            / fb.scale[t + 1];
+                    }
+                }
+            }
+
+            // M step.
+            model.initial.clone_from(&gamma[0]);
+
+            for i in 0..k {
+                let total = xi.row(i).sum();
+
+                if total > 0.0 {
+                    for j in 0..k {
+                        model.transition[(i, j)] = xi[(i, j)] / total;
+                    }
+                }
+
+                let weight = gamma.iter().map(|g| g[i]).sum::<f64>();
+
+                if weight > 0.0 {
+                    let m = gamma
+                        .iter()
+                        .zip(returns)
+                        .map(|(g, r)| g[i] * r)
+                        .sum::<f64>()
+                        / weight;
+                    let v = gamma
+                        .iter()
+                        .zip(returns)
+                        .map(|(g, r)| g[i] * (r - m).powi(2))
+                        .sum::<f64>()
+                        / weight;
+
+                    model.means[i] = m;
+                    model.variances[i] = v.max(floor);
+                }
+            }
+
+            let converged = (current - log_likelihood).abs() < TOLERANCE * current.abs().max(1.0);
+            log_likelihood = current;
+
+            if converged {
+                break;
+            }
+        }
+
+        let model = model.sorted_by_variance();
+
+        Ok(HmmFit {
+            log_likelihood: model.log_likelihood(returns)?,
+            model,
+            iterations,
+        })
+    }
+
+    /// The same model with the regimes relabelled by increasing variance.
+    fn sorted_by_variance(&self) -> Self {
+        let k = self.n_states();
+        let mut order: Vec<usize> = (0..k).collect();
+        order.sort_by(|a, b| self.variances[*a].total_cmp(&self.variances[*b]));
+
+        Self {
+            initial: order.iter().map(|i| self.initial[*i]).collect(),
+            transition: DMatrix::from_fn(k, k, |i, j| self.transition[(order[i], order[j])]),
+            means: order.iter().map(|i| self.means[*i]).collect(),
+            variances: order.iter().map(|i| self.variances[*i]).collect(),
+        }
+    }
+
+    /// Regime probabilities one period later.
+    fn step(&self, p: &[f64]) -> Vec<f64> {
+        (0..self.n_states())
+            .map(|j| {
+                p.iter()
+                    .enumerate()
+                    .map(|(i, p)| p * self.transition[(i, j)])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Gaussian density of a return in regime `k`.
+    fn density(&self, k: usize, r: f64) -> f64 {
+        self.log_density(k, r).exp()
+    }
+
+    /// Gaussian log-density of a return in regime `k`.
+    fn log_density(&self, k: usize, r: f64) -> f64 {
+        -0.5 * ((2.0 * std::f64::consts::PI * self.variances[k]).ln()
+            + (r - self.means[k]).powi(2) / self.variances[k])
+    }
+
+    /// Scaled forward-backward recursions (Rabiner, 1989).
+    fn forward_backward(&self, returns: &[f64]) -> Result<ForwardBackward, RustQuantError> {
+        if returns.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "no returns to filter".to_string(),
+            ));
+        }
+
+        let k = self.n_states();
+        let n = returns.len();
+
+        let mut alpha = Vec::with_capacity(n);
+        let mut scale = Vec::with_capacity(n);
+        let mut prior = self.initial.clone();
+
+        for r in returns {
+            let mut a: Vec<f64> = (0..k).map(|j| prior[j] * self.density(j, *r)).collect();
+            let c = a.iter().sum::<f64>();
+
+            if !(c.is_finite() && c > 0.0) {
+                return Err(RustQuantError::ComputationError(format!(
+                    "return {r} has zero likelihood under every regime"
+                )));
+            }
+
+            a.iter_mut().for_each(|a| *a /= c);
+            prior = self.step(&a);
+            alpha.push(a);
+            scale.push(c);
+        }
+
+        let mut beta = vec![vec![1.0; k]; n];
+
+        for t in (0..n - 1).rev() {
+            for i in 0..k {
+                beta[t][i] = (0..k)
+                    .map(|j| {
+                        self.transition[(i, j)] * self.density(j, returns[t + 1]) * beta[t + 1][j]
+                    })
+                    .sum::<f64>()
+                    / scale[t + 1];
+            }
+        }
+
+        Ok(ForwardBackward { alpha, beta, scale })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hidden_markov {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::{Distribution, StandardNormal};
+
+    const EPS: f64 = 1e-12;
+
+    fn two_regimes() -> GaussianHmm {
+        GaussianHmm::new(
+            vec![0.5, 0.5],
+            DMatrix::from_row_slice(2, 2, &[0.98, 0.02, 0.05, 0.95]),
+            vec![0.0005, -0.001],
+            vec![0.01_f64.powi(2), 0.03_f64.powi(2)],
+        )
+        .unwrap()
+    }
+
+    /// Simulate regimes and returns.
+    fn simulate(model: &GaussianHmm, n: usize) -> (Vec<usize>, Vec<f64>) {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut state = 0;
+        let mut states = Vec::with_capacity(n);
+        let mut returns = Vec::with_capacity(n);
+
+        for _ in 0..n {
+            let z: f64 = StandardNormal.sample(&mut rng);
+            states.push(state);
+            returns.push(model.means[state] + model.variances[state].sqrt() * z);
+
+            let u: f64 = rng.gen();
+            state = usize::from(u >= model.transition[(state, 0)]);
+        }
+
+        (states, returns)
+    }
+
+    #[test]
+    fn test_likelihood_by_enumeration() {
+        let model = two_regimes();
+        let returns = [0.01, -0.02, 0.005];
+
+        // Sum over all 8 regime paths.
+        let mut likelihood = 0.0;
+        for path in 0..8 {
+            let s: Vec<usize> = (0..3).map(|t| (path >> t) & 1).collect();
+            let mut p = model.initial[s[0]] * model.density(s[0], returns[0]);
+            for t in 1..3 {
+                p *= model.transition[(s[t - 1], s[t])] * model.density(s[t], returns[t]);
+            }
+            likelihood += p;
+        }
+
+        assert_approx_equal!(
+            model.log_likelihood(&returns).unwrap(),
+            likelihood.ln(),
+            1e-10
+        );
+
+        // Smoothed probabilities are distributions.
+        for p in model.smoothed_probabilities(&returns).unwrap() {
+            assert_approx_equal!(p.iter().sum::<f64>(), 1.0, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_stationary_distribution() {
+        let model = two_regimes();
+        let pi = model.stationary_distribution();
+
+        assert_approx_equal!(pi[0], 0.05 / 0.07, 1e-10);
+        assert_approx_equal!(model.expected_durations()[0], 50.0, 1e-9);
+
+        // Forecasts converge to the stationary distribution.
+        let p = model.predicted_probabilities(&[0.05], 2000).unwrap();
+        assert_approx_equal!(p[0], pi[0], 1e-10);
+    }
+
+    #[test]
+    fn test_viterbi_and_forecast() {
+        let model = two_regimes();
+        let (states, returns) = simulate(&model, 2000);
+
+        let path = model.viterbi(&returns).unwrap();
+        let accuracy = path.iter().zip(&states).filter(|(a, b)| a == b).count() as f64 / 2000.0;
+        assert!(accuracy > 0.9);
+
+        // After a large move, the turbulent regime dominates the forecast.
+        let mut shocked = returns.clone();
+        shocked.extend([0.08, -0.09, 0.07]);
+        let (_, calm_vol) = model.forecast(&[0.0; 20]).unwrap();
+        let (_, shocked_vol) = model.forecast(&shocked).unwrap();
+        assert!(calm_vol < 0.015 && shocked_vol > 0.025);
+    }
+
+    #[test]
+    fn test_baum_welch() {
+        let truth = two_regimes();
+        let (_, returns) = simulate(&truth, 5000);
+
+        let fit = GaussianHmm::fit(&returns, 2).unwrap();
+
+        assert!((fit.model.variances[0].sqrt() - 0.01).abs() < 0.002);
+        assert!((fit.model.variances[1].sqrt() - 0.03).abs() < 0.005);
+        assert!((fit.model.transition[(0, 0)] - 0.98).abs() < 0.02);
+        assert!((fit.model.transition[(1, 1)] - 0.95).abs() < 0.04);
+
+        // EM maximises the likelihood.
+        assert!(fit.log_likelihood >= truth.log_likelihood(&returns).unwrap());
+
+        for row in fit.model.transition.row_iter() {
+            assert_approx_equal!(row.sum(), 1.0, EPS * 10.0);
+        }
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let a = DMatrix::from_row_slice(2, 2, &[0.9, 0.1, 0.2, 0.8]);
+
+        assert!(GaussianHmm::new(vec![0.5, 0.5], a.clone(), vec![0.0; 2], vec![1.0, 0.0]).is_err());
+        assert!(GaussianHmm::new(vec![0.6, 0.6], a.clone(), vec![0.0; 2], vec![1.0; 2]).is_err());
+        assert!(GaussianHmm::new(vec![1.0], a, vec![0.0], vec![1.0]).is_err());
+
+        assert!(GaussianHmm::fit(&[0.01; 100], 2).is_err());
+        assert!(GaussianHmm::fit(&[0.01, 0.02], 2).is_err());
+        assert!(two_regimes().viterbi(&[]).is_err());
+    }
+}
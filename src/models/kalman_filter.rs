@@ -0,0 +1,791 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Linear Gaussian state-space models: Kalman filter, Rauch-Tung-Striebel
+//! smoother and maximum likelihood estimation.
+//!
+//! The model is
+//!
+//! $$
+//! \begin{aligned}
+//! y_t &= d + H_t x_t + v_t, & v_t &\sim N(0, R) \\
+//! x_{t+1} &= c + F x_t + w_t, & w_t &\sim N(0, Q)
+//! \end{aligned}
+//! $$
+//!
+//! with $x_1 \sim N(a_1, P_1)$. Observations containing a `NaN` are treated
+//! as missing: the filter only predicts at those dates.
+//!
+//! Two common applications have helpers:
+//!
+//! - the dynamic Nelson-Siegel model, where the state is the (level, slope,
+//!   curvature) factor vector and $H$ the Nelson-Siegel loadings (see
+//!   [`nelson_siegel_loadings`]);
+//! - a dynamic regression $y_t = \beta_t x_t + \alpha_t + v_t$ with random
+//!   walk coefficients, used to estimate the hedge ratio and spread of a
+//!   pair of assets (see [`StateSpaceModel::dynamic_regression`]).
+//!
+//! ```
+//! use RustQuant::models::StateSpaceModel;
+//! use nalgebra::{DMatrix, DVector};
+//!
+//! // Local level model: a random walk observed with noise.
+//! let model = StateSpaceModel::new(
+//!     DMatrix::identity(1, 1),
+//!     DMatrix::from_element(1, 1, 0.1),
+//!     DMatrix::identity(1, 1),
+//!     DMatrix::from_element(1, 1, 1.0),
+//! );
+//!
+//! let observations: Vec<DVector<f64>> =
+//!     [1.0, 1.2, 0.9, 1.1].iter().map(|y| DVector::from_element(1, *y)).collect();
+//!
+//! let filtered = model.filter(&observations).unwrap();
+//! let smoothed = model.smooth(&observations).unwrap();
+//!
+//! assert_eq!(filtered.filtered_states.len(), 4);
+//! assert!(smoothed.covariances[0][(0, 0)] <= filtered.filtered_covariances[0][(0, 0)]);
+//! ```
+
+use crate::autodiff::{variables::variable::Variable, Graph};
+use crate::error::RustQuantError;
+use crate::math::ConstrainedOptimizer;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Linear Gaussian state-space model.
+#[derive(Debug, Clone)]
+pub struct StateSpaceModel {
+    /// State transition matrix $F$ ($m \times m$).
+    pub transition: DMatrix<f64>,
+    /// State intercept $c$ ($m$).
+    pub state_intercept: DVector<f64>,
+    /// State noise covariance $Q$ ($m \times m$).
+    pub process_noise: DMatrix<f64>,
+    /// Observation matrix $H$ ($p \times m$).
+    pub observation: DMatrix<f64>,
+    /// Time-varying observation matrices $H_t$, one per observation.
+    /// Used instead of `observation` when not empty.
+    pub observation_matrices: Vec<DMatrix<f64>>,
+    /// Observation intercept $d$ ($p$).
+    pub observation_intercept: DVector<f64>,
+    /// Observation noise covariance $R$ ($p \times p$).
+    pub observation_noise: DMatrix<f64>,
+    /// Mean of the first state, $a_1$.
+    pub initial_state: DVector<f64>,
+    /// Covariance of the first state, $P_1$.
+    pub initial_covariance: DMatrix<f64>,
+}
+
+/// Output of the Kalman filter.
+#[derive(Debug, Clone)]
+pub struct KalmanFilterOutput {
+    /// One-step-ahead state predictions $a_{t|t-1}$.
+    pub predicted_states: Vec<DVector<f64>>,
+    /// Covariances of the predictions, $P_{t|t-1}$.
+    pub predicted_covariances: Vec<DMatrix<f64>>,
+    /// Filtered states $a_{t|t}$.
+    pub filtered_states: Vec<DVector<f64>>,
+    /// Covariances of the filtered states, $P_{t|t}$.
+    pub filtered_covariances: Vec<DMatrix<f64>>,
+    /// Innovations $y_t - d - H_t a_{t|t-1}$ (`NaN` for missing observations).
+    pub innovations: Vec<DVector<f64>>,
+    /// Gaussian log-likelihood of the observations.
+    pub log_likelihood: f64,
+}
+
+/// Output of the Rauch-Tung-Striebel smoother.
+#[derive(Debug, Clone)]
+pub struct KalmanSmootherOutput {
+    /// Smoothed states $a_{t|n}$.
+    pub states: Vec<DVector<f64>>,
+    /// Covariances of the smoothed states, $P_{t|n}$.
+    pub covariances: Vec<DMatrix<f64>>,
+}
+
+/// State-space model with entries on the `autodiff` graph, built from the
+/// parameters during maximum likelihood estimation.
+///
+/// The observation noise covariance must be diagonal, which lets the
+/// likelihood be computed by processing the observations one at a time
+/// (Durbin and Koopman, 2012, section 6.4), without matrix inverses.
+#[derive(Debug, Clone)]
+pub struct StateSpaceVariables<'v> {
+    /// State transition matrix $F$, row by row.
+    pub transition: Vec<Vec<Variable<'v>>>,
+    /// State intercept $c$.
+    pub state_intercept: Vec<Variable<'v>>,
+    /// State noise covariance $Q$, row by row.
+    pub process_noise: Vec<Vec<Variable<'v>>>,
+    /// Observation matrix $H$, row by row.
+    pub observation: Vec<Vec<Variable<'v>>>,
+    /// Time-varying observation matrices (data, not parameters).
+    /// Used instead of `observation` when not empty.
+    pub observation_matrices: Vec<DMatrix<f64>>,
+    /// Observation intercept $d$.
+    pub observation_intercept: Vec<Variable<'v>>,
+    /// Diagonal of the observation noise covariance $R$.
+    pub observation_noise: Vec<Variable<'v>>,
+    /// Mean of the first state, $a_1$.
+    pub initial_state: Vec<Variable<'v>>,
+    /// Covariance of the first state, $P_1$, row by row.
+    pub initial_covariance: Vec<Vec<Variable<'v>>>,
+}
+
+/// Result of fitting a state-space model by maximum likelihood.
+#[derive(Debug, Clone)]
+pub struct StateSpaceFit {
+    /// The fitted model.
+    pub model: StateSpaceModel,
+    /// The fitted parameters.
+    pub parameters: Vec<f64>,
+    /// Gaussian log-likelihood at the fitted parameters.
+    pub log_likelihood: f64,
+    /// Number of optimizer iterations.
+    pub iterations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Nelson-Siegel factor loadings for the given tenors (in years):
+/// one row per tenor, with columns
+///
+/// $$
+/// 1, \quad \frac{1 - e^{-\lambda \tau}}{\lambda \tau}, \quad
+/// \frac{1 - e^{-\lambda \tau}}{\lambda \tau} - e^{-\lambda \tau}
+/// $$
+///
+/// These are the observation matrix of the dynamic Nelson-Siegel model
+/// (Diebold and Li, 2006).
+#[must_use]
+pub fn nelson_siegel_loadings(tenors: &[f64], lambda: f64) -> DMatrix<f64> {
+    DMatrix::from_fn(tenors.len(), 3, |i, j| {
+        let x = lambda * tenors[i];
+        let slope = (1.0 - (-x).exp()) / x;
+
+        match j {
+            0 => 1.0,
+            1 => slope,
+            _ => slope - (-x).exp(),
+        }
+    })
+}
+
+impl StateSpaceModel {
+    /// New model with zero intercepts and a nearly diffuse first state
+    /// ($a_1 = 0$, $P_1 = 10^6 I$).
+    #[must_use]
+    pub fn new(
+        transition: DMatrix<f64>,
+        process_noise: DMatrix<f64>,
+        observation: DMatrix<f64>,
+        observation_noise: DMatrix<f64>,
+    ) -> Self {
+        let (m, p) = (transition.nrows(), observation.nrows());
+
+        Self {
+            transition,
+            state_intercept: DVector::zeros(m),
+            process_noise,
+            observation,
+            observation_matrices: Vec::new(),
+            observation_intercept: DVector::zeros(p),
+            observation_noise,
+            initial_state: DVector::zeros(m),
+            initial_covariance: DMatrix::identity(m, m) * 1e6,
+        }
+    }
+
+    /// Dynamic regression $y_t = \beta_t x_t + \alpha_t + v_t$, where the
+    /// state $(\beta_t, \alpha_t)$ follows a random walk with covariance
+    /// `state_variance` $\cdot I$, and $v_t$ has variance
+    /// `observation_variance`.
+    ///
+    /// With $y$ and $x$ the prices of two assets, the filtered state is the
+    /// dynamic hedge ratio and the innovations are the spread.
+    #[must_use]
+    pub fn dynamic_regression(x: &[f64], state_variance: f64, observation_variance: f64) -> Self {
+        let mut model = Self::new(
+            DMatrix::identity(2, 2),
+            DMatrix::identity(2, 2) * state_variance,
+            DMatrix::from_row_slice(1, 2, &[0.0, 1.0]),
+            DMatrix::from_element(1, 1, observation_variance),
+        );
+
+        model.observation_matrices = x
+            .iter()
+            .map(|x| DMatrix::from_row_slice(1, 2, &[*x, 1.0]))
+            .collect();
+
+        model
+    }
+
+    /// Set the state and observation intercepts.
+    #[must_use]
+    pub fn with_intercepts(
+        mut self,
+        state_intercept: DVector<f64>,
+        observation_intercept: DVector<f64>,
+    ) -> Self {
+        self.state_intercept = state_intercept;
+        self.observation_intercept = observation_intercept;
+        self
+    }
+
+    /// Set the distribution of the first state.
+    #[must_use]
+    pub fn with_initial_state(mut self, mean: DVector<f64>, covariance: DMatrix<f64>) -> Self {
+        self.initial_state = mean;
+        self.initial_covariance = covariance;
+        self
+    }
+
+    /// Set time-varying observation matrices, one per observation.
+    #[must_use]
+    pub fn with_observation_matrices(mut self, matrices: Vec<DMatrix<f64>>) -> Self {
+        self.observation_matrices = matrices;
+        self
+    }
+
+    /// Number of states.
+    #[must_use]
+    pub fn n_states(&self) -> usize {
+        self.transition.nrows()
+    }
+
+    /// Observation matrix at step `t`.
+    fn observation_matrix(&self, t: usize) -> &DMatrix<f64> {
+        self.observation_matrices
+            .get(t)
+            .unwrap_or(&self.observation)
+    }
+
+    /// Check the dimensions of the model against the observations.
+    fn validate(&self, observations: &[DVector<f64>]) -> Result<(), RustQuantError> {
+        let m = self.n_states();
+        let p = self.observation_intercept.len();
+
+        let square = |a: &DMatrix<f64>, n: usize| a.nrows() == n && a.ncols() == n;
+
+        if m == 0
+            || !square(&self.transition, m)
+            || !square(&self.process_noise, m)
+            || !square(&self.initial_covariance, m)
+            || !square(&self.observation_noise, p)
+            || self.state_intercept.len() != m
+            || self.initial_state.len() != m
+            || self.observation.shape() != (p, m)
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "inconsistent state-space model dimensions".to_string(),
+            ));
+        }
+
+        if !self.observation_matrices.is_empty()
+            && (self.observation_matrices.len() != observations.len()
+                || self
+                    .observation_matrices
+                    .iter()
+                    .any(|h| h.shape() != (p, m)))
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "need one (p x m) observation matrix per observation".to_string(),
+            ));
+        }
+
+        if let Some(y) = observations.iter().find(|y| y.len() != p) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "expected observations of dimension {p}, got {}",
+                y.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Run the Kalman filter over the observations.
+    ///
+    /// # Errors
+    ///
+    /// If the dimensions are inconsistent, or an innovation covariance is
+    /// singular.
+    pub fn filter(
+        &self,
+        observations: &[DVector<f64>],
+    ) -> Result<KalmanFilterOutput, RustQuantError> {
+        self.validate(observations)?;
+
+        let n = observations.len();
+        let p = self.observation_intercept.len();
+
+        let mut output = KalmanFilterOutput {
+            predicted_states: Vec::with_capacity(n),
+            predicted_covariances: Vec::with_capacity(n),
+            filtered_states: Vec::with_capacity(n),
+            filtered_covariances: Vec::with_capacity(n),
+            innovations: Vec::with_capacity(n),
+            log_likelihood: 0.0,
+        };
+
+        let mut a = self.initial_state.clone();
+        let mut p_cov = self.initial_covariance.clone();
+
+        for (t, y) in observations.iter().enumerate() {
+            output.predicted_states.push(a.clone());
+            output.predicted_covariances.push(p_cov.clone());
+
+            if y.iter().any(|y| y.is_nan()) {
+                output.innovations.push(DVector::from_element(p, f64::NAN));
+            } else {
+                let h = self.observation_matrix(t);
+                let v = y - &self.observation_intercept - h * &a;
+                let s = h * &p_cov * h.transpose() + &self.observation_noise;
+
+                let cholesky = s.clone().cholesky().ok_or_else(|| {
+                    RustQuantError::ComputationError(format!(
+                        "innovation covariance is not positive definite at step {t}"
+                    ))
+                })?;
+
+                let s_inv_v = cholesky.solve(&v);
+                let gain = &p_cov * h.transpose() * cholesky.inverse();
+                let log_det = 2.0 * cholesky.l().diagonal().map(f64::ln).sum();
+
+                output.log_likelihood -= 0.5
+                    * (p as f64 * (2.0 * std::f64::consts::PI).ln() + log_det + v.dot(&s_inv_v));
+
+                a += &gain * &v;
+                p_cov -= &gain * h * &p_cov;
+                p_cov = (&p_cov + p_cov.transpose()) * 0.5;
+
+                output.innovations.push(v);
+            }
+
+            output.filtered_states.push(a.clone());
+            output.filtered_covariances.push(p_cov.clone());
+
+            a = &self.state_intercept + &self.transition * a;
+            p_cov = &self.transition * p_cov * self.transition.transpose() + &self.process_noise;
+        }
+
+        Ok(output)
+    }
+
+    /// Run the Rauch-Tung-Striebel smoother: the distribution of every
+    /// state given all the observations.
+    ///
+    /// # Errors
+    ///
+    /// If the filter fails, or a predicted covariance is singular.
+    pub fn smooth(
+        &self,
+        observations: &[DVector<f64>],
+    ) -> Result<KalmanSmootherOutput, RustQuantError> {
+        let filtered = self.filter(observations)?;
+        let n = observations.len();
+
+        let mut states = filtered.filtered_states.clone();
+        let mut covariances = filtered.filtered_covariances.clone();
+
+        for t in (0..n.saturating_sub(1)).rev() {
+            let predicted_inverse = filtered.predicted_covariances[t + 1]
+                .clone()
+                .try_inverse()
+                .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+            let gain =
+                &filtered.filtered_covariances[t] * self.transition.transpose() * predicted_inverse;
+
+            states[t] = &filtered.filtered_states[t]
+                + &gain * (&states[t + 1] - &filtered.predicted_states[t + 1]);
+            covariances[t] = &filtered.filtered_covariances[t]
+                + &gain
+                    * (&covariances[t + 1] - &filtered.predicted_covariances[t + 1])
+                    * gain.transpose();
+        }
+
+        Ok(KalmanSmootherOutput {
+            states,
+            covariances,
+        })
+    }
+
+    /// Fit a parameterised model by maximum likelihood.
+    ///
+    /// `parameterization` builds the model from the (unconstrained)
+    /// parameters on the `autodiff` graph, typically starting from
+    /// [`StateSpaceVariables::from_model`] and replacing the unknown
+    /// entries, e.g. with `exp` of a parameter for a variance. The negative
+    /// log-likelihood is minimised from `x0` using its `autodiff` gradient.
+    ///
+    /// # Errors
+    ///
+    /// If `x0` is empty, there are no observations, or the dimensions are
+    /// inconsistent.
+    pub fn fit<F>(
+        observations: &[DVector<f64>],
+        x0: &[f64],
+        parameterization: F,
+    ) -> Result<StateSpaceFit, RustQuantError>
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> StateSpaceVariables<'v>,
+    {
+        if x0.is_empty() || observations.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "need at least one parameter and one observation".to_string(),
+            ));
+        }
+
+        // Validate the dimensions at the starting point.
+        let graph = Graph::new();
+        let start = parameterization(&graph.vars(x0)).value();
+        start.validate(observations)?;
+
+        let n = observations.len() as f64;
+
+        let result = ConstrainedOptimizer::new(10_000, 1e-8).optimize(
+            |x| negative_log_likelihood(&parameterization(x), observations) / n,
+            x0,
+        )?;
+
+        let graph = Graph::new();
+        let model = parameterization(&graph.vars(&result.minimizer)).value();
+
+        Ok(StateSpaceFit {
+            log_likelihood: model.filter(observations)?.log_likelihood,
+            model,
+            parameters: result.minimizer,
+            iterations: result.iterations,
+        })
+    }
+}
+
+impl<'v> StateSpaceVariables<'v> {
+    /// Put the entries of a model on the graph as constants.
+    /// Only the diagonal of the observation noise covariance is kept.
+    #[must_use]
+    pub fn from_model(graph: &'v Graph, model: &StateSpaceModel) -> Self {
+        let matrix = |a: &DMatrix<f64>| -> Vec<Vec<Variable<'v>>> {
+            a.row_iter()
+                .map(|row| row.iter().map(|x| graph.var(*x)).collect())
+                .collect()
+        };
+        let vector =
+            |a: &DVector<f64>| -> Vec<Variable<'v>> { a.iter().map(|x| graph.var(*x)).collect() };
+
+        Self {
+            transition: matrix(&model.transition),
+            state_intercept: vector(&model.state_intercept),
+            process_noise: matrix(&model.process_noise),
+            observation: matrix(&model.observation),
+            observation_matrices: model.observation_matrices.clone(),
+            observation_intercept: vector(&model.observation_intercept),
+            observation_noise: vector(&model.observation_noise.diagonal()),
+            initial_state: vector(&model.initial_state),
+            initial_covariance: matrix(&model.initial_covariance),
+        }
+    }
+
+    /// The model at the current values of the variables.
+    #[must_use]
+    pub fn value(&self) -> StateSpaceModel {
+        let matrix = |a: &[Vec<Variable<'v>>]| {
+            DMatrix::from_fn(a.len(), a.first().map_or(0, Vec::len), |i, j| a[i][j].value)
+        };
+        let vector =
+            |a: &[Variable<'v>]| DVector::from_iterator(a.len(), a.iter().map(|x| x.value));
+
+        StateSpaceModel {
+            transition: matrix(&self.transition),
+            state_intercept: vector(&self.state_intercept),
+            process_noise: matrix(&self.process_noise),
+            observation: matrix(&self.observation),
+            observation_matrices: self.observation_matrices.clone(),
+            observation_intercept: vector(&self.observation_intercept),
+            observation_noise: DMatrix::from_diagonal(&vector(&self.observation_noise)),
+            initial_state: vector(&self.initial_state),
+            initial_covariance: matrix(&self.initial_covariance),
+        }
+    }
+}
+
+/// Negative log-likelihood on the graph, processing the observations one
+/// at a time (which requires a diagonal observation noise covariance).
+fn negative_log_likelihood<'v>(
+    model: &StateSpaceVariables<'v>,
+    observations: &[DVector<f64>],
+) -> Variable<'v> {
+    let graph = model.transition[0][0].graph;
+    let m = model.transition.len();
+    let ln_2pi = (2.0 * std::f64::consts::PI).ln();
+
+    let mut a = model.initial_state.clone();
+    let mut p = model.initial_covariance.clone();
+    let mut nll = graph.var(0.0);
+
+    for (t, y) in observations.iter().enumerate() {
+        if y.iter().all(|y| !y.is_nan()) {
+            for (i, y) in y.iter().enumerate() {
+                let h: Vec<Variable> = match model.observation_matrices.get(t) {
+                    Some(matrix) => (0..m).map(|j| graph.var(matrix[(i, j)])).collect(),
+                    None => model.observation[i].clone(),
+                };
+
+                // P h', the innovation, and its variance.
+                let ph: Vec<Variable> = (0..m)
+                    .map(|j| (0..m).map(|k| p[j][k] * h[k]).sum())
+                    .collect();
+                let v = *y
+                    - model.observation_intercept[i]
+                    - (0..m).map(|k| h[k] * a[k]).sum::<Variable>();
+                let f = (0..m).map(|k| h[k] * ph[k]).sum::<Variable>() + model.observation_noise[i];
+
+                nll += 0.5 * (ln_2pi + f.ln() + v * v / f);
+
+                for j in 0..m {
+                    a[j] += ph[j] * v / f;
+                    for k in 0..m {
+                        p[j][k] -= ph[j] * ph[k] / f;
+                    }
+                }
+            }
+        }
+
+        // Prediction: a = c + F a, P = F P F' + Q.
+        let fp: Vec<Vec<Variable>> = (0..m)
+            .map(|i| {
+                (0..m)
+                    .map(|j| (0..m).map(|k| model.transition[i][k] * p[k][j]).sum())
+                    .collect()
+            })
+            .collect();
+
+        a = (0..m)
+            .map(|i| {
+                model.state_intercept[i]
+                    + (0..m)
+                        .map(|k| model.transition[i][k] * a[k])
+                        .sum::<Variable>()
+            })
+            .collect();
+        p = (0..m)
+            .map(|i| {
+                (0..m)
+                    .map(|j| {
+                        (0..m)
+                            .map(|k| fp[i][k] * model.transition[j][k])
+                            .sum::<Variable>()
+                            + model.process_noise[i][j]
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    nll
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_kalman_filter {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, StandardNormal};
+
+    const EPS: f64 = 1e-10;
+
+    fn scalar(x: f64) -> DMatrix<f64> {
+        DMatrix::from_element(1, 1, x)
+    }
+
+    fn observations(y: &[f64]) -> Vec<DVector<f64>> {
+        y.iter().map(|y| DVector::from_element(1, *y)).collect()
+    }
+
+    /// Simulate an AR(1) state observed with noise.
+    fn simulate_ar1(phi: f64, q: f64, r: f64, n: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut x = 0.0;
+
+        (0..n)
+            .map(|_| {
+                let (e, u): (f64, f64) = (
+                    StandardNormal.sample(&mut rng),
+                    StandardNormal.sample(&mut rng),
+                );
+                x = phi * x + q.sqrt() * e;
+                x + r.sqrt() * u
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_local_level_filter() {
+        let model = StateSpaceModel::new(scalar(1.0), scalar(0.5), scalar(1.0), scalar(2.0))
+            .with_initial_state(DVector::from_element(1, 0.0), scalar(1.0));
+
+        let output = model.filter(&observations(&[1.0, 3.0])).unwrap();
+
+        // Step 1: gain 1 / 3.
+        assert_approx_equal!(output.filtered_states[0][0], 1.0 / 3.0, EPS);
+        assert_approx_equal!(output.filtered_covariances[0][(0, 0)], 2.0 / 3.0, EPS);
+
+        // Step 2: predicted variance 2/3 + 1/2 = 7/6, gain 7 / 19.
+        assert_approx_equal!(output.predicted_covariances[1][(0, 0)], 7.0 / 6.0, EPS);
+        let expected = 1.0 / 3.0 + 7.0 / 19.0 * (3.0 - 1.0 / 3.0);
+        assert_approx_equal!(output.filtered_states[1][0], expected, EPS);
+
+        let ln_2pi = (2.0 * std::f64::consts::PI).ln();
+        let log_likelihood = -0.5 * (ln_2pi + 3.0_f64.ln() + 1.0 / 3.0)
+            - 0.5 * (ln_2pi + (19.0_f64 / 6.0).ln() + (8.0_f64 / 3.0).powi(2) / (19.0 / 6.0));
+        assert_approx_equal!(output.log_likelihood, log_likelihood, EPS);
+    }
+
+    #[test]
+    fn test_missing_observations() {
+        let model = StateSpaceModel::new(scalar(1.0), scalar(0.5), scalar(1.0), scalar(2.0));
+        let output = model.filter(&observations(&[1.0, f64::NAN, 2.0])).unwrap();
+
+        // No update at the missing date.
+        assert_eq!(output.filtered_states[1], output.predicted_states[1]);
+        assert!(output.innovations[1][0].is_nan());
+        assert!(output.log_likelihood.is_finite());
+    }
+
+    #[test]
+    fn test_smoother() {
+        let y = simulate_ar1(0.8, 0.5, 1.0, 200);
+        let model = StateSpaceModel::new(scalar(0.8), scalar(0.5), scalar(1.0), scalar(1.0));
+
+        let filtered = model.filter(&observations(&y)).unwrap();
+        let smoothed = model.smooth(&observations(&y)).unwrap();
+
+        // The last smoothed state is the last filtered state.
+        assert_eq!(smoothed.states[199], filtered.filtered_states[199]);
+
+        // Smoothing uses more information, so it reduces the variance.
+        for t in 0..199 {
+            assert!(smoothed.covariances[t][(0, 0)] < filtered.filtered_covariances[t][(0, 0)]);
+        }
+    }
+
+    #[test]
+    fn test_graph_likelihood_matches_filter() {
+        let y = simulate_ar1(0.8, 0.5, 1.0, 50);
+        let model = StateSpaceModel::new(
+            DMatrix::from_row_slice(2, 2, &[0.8, 0.1, 0.0, 0.5]),
+            DMatrix::from_row_slice(2, 2, &[0.5, 0.1, 0.1, 0.3]),
+            DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 1.0, 1.0]),
+            DMatrix::from_diagonal(&DVector::from_vec(vec![1.0, 0.5])),
+        )
+        .with_intercepts(
+            DVector::from_vec(vec![0.1, 0.0]),
+            DVector::from_vec(vec![0.0, 0.2]),
+        )
+        .with_initial_state(DVector::zeros(2), DMatrix::identity(2, 2));
+
+        let data: Vec<DVector<f64>> = y
+            .windows(2)
+            .map(|w| DVector::from_vec(vec![w[0], if w[1] > 1.5 { f64::NAN } else { w[1] }]))
+            .collect();
+
+        let graph = Graph::new();
+        let variables = StateSpaceVariables::from_model(&graph, &model);
+        let nll = negative_log_likelihood(&variables, &data).value;
+
+        assert_approx_equal!(-nll, model.filter(&data).unwrap().log_likelihood, 1e-8);
+    }
+
+    #[test]
+    fn test_fit_ar1_plus_noise() {
+        let (phi, q, r) = (0.9, 0.5, 1.0);
+        let y = observations(&simulate_ar1(phi, q, r, 2000));
+
+        let template = StateSpaceModel::new(scalar(0.0), scalar(1.0), scalar(1.0), scalar(1.0))
+            .with_initial_state(DVector::zeros(1), scalar(10.0));
+
+        let fit = StateSpaceModel::fit(&y, &[0.0, 0.0, 0.0], |x| {
+            let mut model = StateSpaceVariables::from_model(x[0].graph, &template);
+            model.transition[0][0] = x[0].tanh();
+            model.process_noise[0][0] = x[1].exp();
+            model.observation_noise[0] = x[2].exp();
+            model
+        })
+        .unwrap();
+
+        assert!((fit.model.transition[(0, 0)] - phi).abs() < 0.05);
+        assert!((fit.model.process_noise[(0, 0)] - q).abs() < 0.2);
+        assert!((fit.model.observation_noise[(0, 0)] - r).abs() < 0.2);
+
+        // The fit maximises the likelihood.
+        let truth = StateSpaceModel::new(scalar(phi), scalar(q), scalar(1.0), scalar(r))
+            .with_initial_state(DVector::zeros(1), scalar(10.0));
+        assert!(fit.log_likelihood >= truth.filter(&y).unwrap().log_likelihood);
+    }
+
+    #[test]
+    fn test_dynamic_regression_hedge_ratio() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let n = 500;
+
+        // The hedge ratio moves from 1 to 2 over the sample.
+        let x: Vec<f64> = (0..n)
+            .map(|t| 50.0 + 10.0 * (t as f64 / 20.0).sin())
+            .collect();
+        let beta: Vec<f64> = (0..n).map(|t| 1.0 + t as f64 / n as f64).collect();
+        let y: Vec<f64> = (0..n)
+            .map(|t| {
+                let e: f64 = StandardNormal.sample(&mut rng);
+                beta[t] * x[t] + 5.0 + 0.1 * e
+            })
+            .collect();
+
+        let model = StateSpaceModel::dynamic_regression(&x, 1e-5, 0.01);
+        let smoothed = model.smooth(&observations(&y)).unwrap();
+
+        for t in (100..n).step_by(50) {
+            assert!((smoothed.states[t][0] - beta[t]).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_nelson_siegel_loadings() {
+        let loadings = nelson_siegel_loadings(&[0.25, 10.0, 100.0], 0.6);
+
+        assert_eq!(loadings.column(0).iter().sum::<f64>(), 3.0);
+        // Short end: slope loading near 1 and curvature near 0.
+        assert!(loadings[(0, 1)] > 0.9 && loadings[(0, 2)] < 0.1);
+        // Long end: both vanish.
+        assert!(loadings[(2, 1)] < 0.02 && loadings[(2, 2)] < 0.02);
+    }
+
+    #[test]
+    fn test_invalid_dimensions() {
+        let model = StateSpaceModel::new(scalar(1.0), scalar(0.5), scalar(1.0), scalar(2.0));
+
+        assert!(model.filter(&[DVector::zeros(2)]).is_err());
+        assert!(model
+            .clone()
+            .with_observation_matrices(vec![scalar(1.0)])
+            .filter(&observations(&[1.0, 2.0]))
+            .is_err());
+        assert!(StateSpaceModel::fit(&observations(&[1.0]), &[], |x| {
+            StateSpaceVariables::from_model(x[0].graph, &model)
+        })
+        .is_err());
+    }
+}
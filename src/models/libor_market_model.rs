@@ -0,0 +1,730 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! LIBOR Market Model (Brace-Gatarek-Musiela, 1997).
+//!
+//! On a tenor structure $0 = T_0 < T_1 < \dots < T_N$ with accruals
+//! $\tau_i = T_{i+1} - T_i$, the forward rates $F_i$ for $[T_i, T_{i+1}]$
+//! are shifted lognormal, each a martingale under its own forward measure:
+//!
+//! $$
+//! d(F_i + s) = (F_i + s) \left( \mu_i \, dt + \sigma_i \, dW_i \right),
+//! \qquad d\langle W_i, W_j \rangle = \rho_{ij} \, dt.
+//! $$
+//!
+//! All forwards are simulated under one measure, which gives the drifts
+//! (with $\delta_j = \tau_j (F_j + s) / (1 + \tau_j F_j)$):
+//!
+//! - spot (rolling bank account): $\mu_i = \sigma_i \sum_{j = q(t)}^{i}
+//!   \delta_j \sigma_j \rho_{ij}$, with $F_{q(t)}$ the first forward
+//!   still alive;
+//! - terminal (the bond maturing at $T_N$): $\mu_i = -\sigma_i
+//!   \sum_{j = i + 1}^{N - 1} \delta_j \sigma_j \rho_{ij}$.
+//!
+//! The correlations come from a parametric form, reduced to a few factors
+//! by principal components, and the paths are log-Euler steps with a
+//! predictor-corrector drift. Caps and swaptions priced on the paths can
+//! be checked against the Black prices (caplets exactly, swaptions with
+//! Rebonato's frozen-weights volatility).
+
+use crate::error::RustQuantError;
+use crate::instruments::options::{generalised_black_scholes_merton, TypeFlag};
+use crate::instruments::{Price, Validate, Validator};
+use nalgebra::{DMatrix, SymmetricEigen};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Parametric correlation between forward rates, as a function of their
+/// fixing times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForwardCorrelation {
+    /// $\rho_{ij} = e^{-\beta |T_i - T_j|}$.
+    Exponential {
+        /// Decay rate, $\beta$.
+        decay: f64,
+    },
+
+    /// $\rho_{ij} = \rho_\infty + (1 - \rho_\infty) e^{-\beta |T_i - T_j|}$
+    /// (Rebonato).
+    TwoParameter {
+        /// Asymptotic correlation, $\rho_\infty$.
+        long_term: f64,
+        /// Decay rate, $\beta$.
+        decay: f64,
+    },
+}
+
+/// Measure under which the forward rates are simulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LmmMeasure {
+    /// Spot LIBOR measure: the numeraire is the discretely rolled bank
+    /// account.
+    Spot,
+    /// Terminal measure: the numeraire is the bond maturing at $T_N$.
+    Terminal,
+}
+
+/// LIBOR Market Model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiborMarketModel {
+    /// Tenor dates $T_0 = 0 < T_1 < \dots < T_N$.
+    pub tenors: Vec<f64>,
+    /// Initial forward rates $F_i(0)$, for $i = 0, \dots, N - 1$.
+    pub initial_forwards: Vec<f64>,
+    /// Volatilities $\sigma_i$ of the shifted forwards.
+    pub volatilities: Vec<f64>,
+    /// Shift $s$ of the forwards (zero for the lognormal model).
+    pub shift: f64,
+    /// Correlation between the forwards.
+    pub correlation: ForwardCorrelation,
+    /// Number of Brownian factors.
+    pub factors: usize,
+}
+
+/// Simulated forward curves of a [`LiborMarketModel`].
+#[derive(Debug, Clone)]
+pub struct LmmPaths {
+    /// Tenor dates of the model.
+    pub tenors: Vec<f64>,
+    /// Measure of the simulation.
+    pub measure: LmmMeasure,
+    /// Forward curves at each tenor date: `forwards[path][k][i]` is
+    /// $F_i(T_k)$, or the fixing $F_i(T_i)$ once $i < k$.
+    pub forwards: Vec<Vec<Vec<f64>>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ForwardCorrelation {
+    /// Correlation between the forwards fixing at `t_i` and `t_j`.
+    #[must_use]
+    pub fn correlation(&self, t_i: f64, t_j: f64) -> f64 {
+        match *self {
+            Self::Exponential { decay } => (-decay * (t_i - t_j).abs()).exp(),
+            Self::TwoParameter { long_term, decay } => {
+                long_term + (1.0 - long_term) * (-decay * (t_i - t_j).abs()).exp()
+            }
+        }
+    }
+}
+
+impl Validate for ForwardCorrelation {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        match *self {
+            Self::Exponential { decay } => Validator::new().non_negative("decay", decay).finish(),
+            Self::TwoParameter { long_term, decay } => Validator::new()
+                .check((0.0..1.0).contains(&long_term), || {
+                    format!("long_term must lie in [0, 1) (got {long_term})")
+                })
+                .non_negative("decay", decay)
+                .finish(),
+        }
+    }
+}
+
+impl LiborMarketModel {
+    /// New lognormal model with one factor per forward.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the model fails
+    ///   [`Validate::validate`].
+    pub fn new(
+        tenors: Vec<f64>,
+        initial_forwards: Vec<f64>,
+        volatilities: Vec<f64>,
+        correlation: ForwardCorrelation,
+    ) -> Result<Self, RustQuantError> {
+        let model = Self {
+            factors: initial_forwards.len(),
+            tenors,
+            initial_forwards,
+            volatilities,
+            shift: 0.0,
+            correlation,
+        };
+        model.validate()?;
+
+        Ok(model)
+    }
+
+    /// Shift the forwards, to allow negative rates.
+    #[must_use]
+    pub fn with_shift(self, shift: f64) -> Self {
+        Self { shift, ..self }
+    }
+
+    /// Reduce the correlation to its first `factors` principal components.
+    #[must_use]
+    pub fn with_factors(self, factors: usize) -> Self {
+        Self { factors, ..self }
+    }
+
+    /// Number of forward rates, $N$.
+    #[must_use]
+    pub fn n_forwards(&self) -> usize {
+        self.initial_forwards.len()
+    }
+
+    /// Initial discount factor $P(0, T_k)$.
+    #[must_use]
+    pub fn discount_factor(&self, k: usize) -> f64 {
+        discount(&self.tenors, &self.initial_forwards, 0, k)
+    }
+
+    /// Correlation matrix of the forwards.
+    #[must_use]
+    pub fn correlation_matrix(&self) -> DMatrix<f64> {
+        let n = self.n_forwards();
+
+        DMatrix::from_fn(n, n, |i, j| {
+            self.correlation.correlation(self.tenors[i], self.tenors[j])
+        })
+    }
+
+    /// Factor loadings ($N \times$ factors): the leading principal
+    /// components of the correlation, with rows rescaled to unit length so
+    /// that each forward keeps its volatility.
+    #[must_use]
+    pub fn factor_loadings(&self) -> DMatrix<f64> {
+        let n = self.n_forwards();
+        let eigen = SymmetricEigen::new(self.correlation_matrix());
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+        let mut loadings = DMatrix::from_fn(n, self.factors, |i, f| {
+            let k = order[f];
+            eigen.eigenvectors[(i, k)] * eigen.eigenvalues[k].max(0.0).sqrt()
+        });
+
+        for mut row in loadings.row_iter_mut() {
+            let norm = row.norm();
+            if norm > 0.0 {
+                row /= norm;
+            }
+        }
+
+        loadings
+    }
+
+    /// Black (shifted) price of the caplet on $F_i$, paid at $T_{i+1}$.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if `i` is not a forward that
+    ///   fixes after today, or the shifted strike is not positive.
+    pub fn caplet_black_price(&self, i: usize, strike: f64) -> Result<f64, RustQuantError> {
+        Validator::new()
+            .check(i >= 1 && i < self.n_forwards(), || {
+                format!(
+                    "caplet index must lie in [1, {}) (got {i})",
+                    self.n_forwards()
+                )
+            })
+            .positive("strike + shift", strike + self.shift)
+            .finish()?;
+
+        let accrual = self.tenors[i + 1] - self.tenors[i];
+
+        Ok(accrual
+            * self.discount_factor(i + 1)
+            * black(
+                self.initial_forwards[i] + self.shift,
+                strike + self.shift,
+                self.volatilities[i],
+                self.tenors[i],
+                TypeFlag::Call,
+            ))
+    }
+
+    /// Forward swap rate and annuity today of the swap over
+    /// $[T_{start}, T_{end}]$.
+    #[must_use]
+    pub fn swap_rate(&self, start: usize, end: usize) -> (f64, f64) {
+        swap_rate(&self.tenors, &self.initial_forwards, 0, start, end)
+    }
+
+    /// Rebonato's approximation of the Black (shifted) volatility of the
+    /// swap rate over $[T_{start}, T_{end}]$, freezing the weights of the
+    /// forwards in the swap rate at their initial values.
+    #[must_use]
+    pub fn swaption_black_volatility(&self, start: usize, end: usize) -> f64 {
+        let (rate, annuity) = self.swap_rate(start, end);
+        let s = self.shift;
+
+        let weights: Vec<f64> = (start..end)
+            .map(|j| {
+                (self.tenors[j + 1] - self.tenors[j]) * self.discount_factor(j + 1) / annuity
+                    * (self.initial_forwards[j] + s)
+                    * self.volatilities[j]
+            })
+            .collect();
+
+        let variance: f64 = (start..end)
+            .flat_map(|i| (start..end).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                weights[i - start]
+                    * weights[j - start]
+                    * self.correlation.correlation(self.tenors[i], self.tenors[j])
+            })
+            .sum();
+
+        variance.sqrt() / (rate + s)
+    }
+
+    /// Black (shifted) price of the European swaption over
+    /// $[T_{start}, T_{end}]$ with Rebonato's volatility: payer for
+    /// [`TypeFlag::Call`], receiver for [`TypeFlag::Put`].
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the swap does not start
+    ///   after today and end by $T_N$, or the shifted strike is not
+    ///   positive.
+    pub fn swaption_black_price(
+        &self,
+        start: usize,
+        end: usize,
+        strike: f64,
+        option_type: TypeFlag,
+    ) -> Result<f64, RustQuantError> {
+        self.check_swap(start, end)?;
+        Validator::new()
+            .positive("strike + shift", strike + self.shift)
+            .finish()?;
+
+        let (rate, annuity) = self.swap_rate(start, end);
+
+        Ok(annuity
+            * black(
+                rate + self.shift,
+                strike + self.shift,
+                self.swaption_black_volatility(start, end),
+                self.tenors[start],
+                option_type,
+            ))
+    }
+
+    /// Simulate `n_paths` forward curves to each tenor date under
+    /// `measure`, with `steps_per_period` steps between tenor dates.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the model fails
+    ///   [`Validate::validate`], or there are no paths or steps.
+    pub fn simulate(
+        &self,
+        measure: LmmMeasure,
+        n_paths: usize,
+        steps_per_period: usize,
+        seed: u64,
+    ) -> Result<LmmPaths, RustQuantError> {
+        self.validate()?;
+        Validator::new()
+            .check(n_paths > 0 && steps_per_period > 0, || {
+                "at least one path and one step are needed".to_string()
+            })
+            .finish()?;
+
+        let n = self.n_forwards();
+        let loadings = self.factor_loadings();
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let forwards = (0..n_paths)
+            .map(|_| {
+                let mut forward = self.initial_forwards.clone();
+                let mut curves = Vec::with_capacity(n + 1);
+                curves.push(forward.clone());
+
+                for k in 0..n {
+                    // Forwards fixing after T_k are still alive.
+                    let alive = k + 1;
+                    let h = (self.tenors[k + 1] - self.tenors[k]) / steps_per_period as f64;
+
+                    for _ in 0..steps_per_period {
+                        let z: Vec<f64> = (0..self.factors)
+                            .map(|_| StandardNormal.sample(&mut rng))
+                            .collect();
+
+                        let shocks: Vec<f64> = (0..n)
+                            .map(|i| {
+                                h.sqrt()
+                                    * (0..self.factors)
+                                        .map(|f| loadings[(i, f)] * z[f])
+                                        .sum::<f64>()
+                            })
+                            .collect();
+
+                        let step = |forward: &[f64], drift: &[f64], i: usize| {
+                            let sigma = self.volatilities[i];
+
+                            (forward[i] + self.shift)
+                                * ((drift[i] - 0.5 * sigma * sigma) * h + sigma * shocks[i]).exp()
+                                - self.shift
+                        };
+
+                        // Predictor-corrector: average the drifts at both
+                        // ends of the step.
+                        let evolve = |drift: &[f64]| -> Vec<f64> {
+                            (0..n)
+                                .map(|i| match i < alive {
+                                    true => forward[i],
+                                    false => step(&forward, drift, i),
+                                })
+                                .collect()
+                        };
+
+                        let drift = self.drift(&forward, alive, measure);
+                        let predicted = evolve(&drift);
+                        let corrected: Vec<f64> = self
+                            .drift(&predicted, alive, measure)
+                            .iter()
+                            .zip(&drift)
+                            .map(|(a, b)| 0.5 * (a + b))
+                            .collect();
+
+                        forward = evolve(&corrected);
+                    }
+
+                    curves.push(forward.clone());
+                }
+
+                curves
+            })
+            .collect();
+
+        Ok(LmmPaths {
+            tenors: self.tenors.clone(),
+            measure,
+            forwards,
+        })
+    }
+
+    // Drifts of the log shifted forwards under `measure`.
+    fn drift(&self, forward: &[f64], alive: usize, measure: LmmMeasure) -> Vec<f64> {
+        let n = self.n_forwards();
+        let rho = |i: usize, j: usize| self.correlation.correlation(self.tenors[i], self.tenors[j]);
+
+        let delta: Vec<f64> = (0..n)
+            .map(|j| {
+                let accrual = self.tenors[j + 1] - self.tenors[j];
+
+                accrual * (forward[j] + self.shift) * self.volatilities[j]
+                    / (1.0 + accrual * forward[j])
+            })
+            .collect();
+
+        (0..n)
+            .map(|i| {
+                let terms = match measure {
+                    LmmMeasure::Spot => alive.min(i + 1)..i + 1,
+                    LmmMeasure::Terminal => i + 1..n,
+                };
+                let sum: f64 = terms.map(|j| delta[j] * rho(i, j)).sum();
+
+                match (i < alive, measure) {
+                    (true, _) => 0.0,
+                    (false, LmmMeasure::Spot) => self.volatilities[i] * sum,
+                    (false, LmmMeasure::Terminal) => -self.volatilities[i] * sum,
+                }
+            })
+            .collect()
+    }
+
+    fn check_swap(&self, start: usize, end: usize) -> Result<(), RustQuantError> {
+        Validator::new()
+            .check(
+                start >= 1 && start < end && end <= self.n_forwards(),
+                || {
+                    format!(
+                        "swap must satisfy 1 <= start < end <= {} (got {start} and {end})",
+                        self.n_forwards()
+                    )
+                },
+            )
+            .finish()
+    }
+}
+
+impl Validate for LiborMarketModel {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let n = self.initial_forwards.len();
+
+        Validator::new()
+            .check(n > 0, || "at least one forward rate is needed".to_string())
+            .check(self.tenors.len() == n + 1, || {
+                format!("{} tenor dates are needed for {n} forwards", n + 1)
+            })
+            .check(self.volatilities.len() == n, || {
+                format!("{n} volatilities are needed for {n} forwards")
+            })
+            .check(self.tenors.first() == Some(&0.0), || {
+                "the first tenor date must be 0".to_string()
+            })
+            .check(self.tenors.windows(2).all(|w| w[0] < w[1]), || {
+                "tenor dates must be increasing".to_string()
+            })
+            .non_negative("shift", self.shift)
+            .check(
+                self.initial_forwards.iter().all(|&f| f + self.shift > 0.0),
+                || "shifted forwards must be positive".to_string(),
+            )
+            .check(self.volatilities.iter().all(|&v| v > 0.0), || {
+                "volatilities must be positive".to_string()
+            })
+            .check(self.factors >= 1 && self.factors <= n, || {
+                format!("factors must lie in [1, {n}] (got {})", self.factors)
+            })
+            .finish()?;
+
+        self.correlation.validate()
+    }
+}
+
+impl LmmPaths {
+    /// Number of simulated paths.
+    #[must_use]
+    pub fn n_paths(&self) -> usize {
+        self.forwards.len()
+    }
+
+    /// Price of the zero-coupon bond maturing at $T_k$, which checks the
+    /// drifts: it must match the initial discount factor.
+    #[must_use]
+    pub fn zero_coupon_bond(&self, k: usize) -> Price {
+        self.price(k, |_| 1.0)
+    }
+
+    /// Price of the caplet on $F_i$, paid at $T_{i+1}$.
+    #[must_use]
+    pub fn caplet(&self, i: usize, strike: f64) -> Price {
+        let accrual = self.tenors[i + 1] - self.tenors[i];
+
+        self.price(i + 1, |curves| accrual * (curves[i][i] - strike).max(0.0))
+    }
+
+    /// Price of the cap on all the forwards fixing after today.
+    #[must_use]
+    pub fn cap(&self, strike: f64) -> Price {
+        let n = self.tenors.len() - 1;
+
+        estimate((0..self.n_paths()).map(|path| {
+            (1..n)
+                .map(|i| {
+                    let accrual = self.tenors[i + 1] - self.tenors[i];
+                    let curves = &self.forwards[path];
+
+                    accrual * (curves[i][i] - strike).max(0.0) * self.deflator(path, i + 1)
+                })
+                .sum()
+        }))
+    }
+
+    /// Price of the European swaption over $[T_{start}, T_{end}]$, exercised
+    /// at $T_{start}$: payer for [`TypeFlag::Call`], receiver for
+    /// [`TypeFlag::Put`].
+    #[must_use]
+    pub fn swaption(&self, start: usize, end: usize, strike: f64, option_type: TypeFlag) -> Price {
+        self.price(start, |curves| {
+            let (rate, annuity) = swap_rate(&self.tenors, &curves[start], start, start, end);
+
+            annuity
+                * match option_type {
+                    TypeFlag::Call => (rate - strike).max(0.0),
+                    TypeFlag::Put => (strike - rate).max(0.0),
+                }
+        })
+    }
+
+    // Price of a payoff at T_k, a function of the forward curves up to T_k.
+    fn price<P: Fn(&[Vec<f64>]) -> f64>(&self, k: usize, payoff: P) -> Price {
+        estimate(
+            (0..self.n_paths())
+                .map(|path| payoff(&self.forwards[path][..=k]) * self.deflator(path, k)),
+        )
+    }
+
+    // Value today of a unit paid at T_k on a path: N(0) / N(T_k).
+    fn deflator(&self, path: usize, k: usize) -> f64 {
+        let curves = &self.forwards[path];
+        let n = self.tenors.len() - 1;
+
+        match self.measure {
+            LmmMeasure::Spot => 1.0 / spot_numeraire(&self.tenors, curves, k),
+            LmmMeasure::Terminal => {
+                discount(&self.tenors, &curves[0], 0, n) / discount(&self.tenors, &curves[k], k, n)
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Undiscounted Black price.
+fn black(forward: f64, strike: f64, volatility: f64, T: f64, option_type: TypeFlag) -> f64 {
+    generalised_black_scholes_merton(forward, strike, volatility, 0.0, 0.0, T, option_type)
+}
+
+// P(T_k, T_e) from the forwards at T_k.
+fn discount(tenors: &[f64], forwards: &[f64], k: usize, e: usize) -> f64 {
+    (k..e)
+        .map(|l| 1.0 / (1.0 + (tenors[l + 1] - tenors[l]) * forwards[l]))
+        .product()
+}
+
+// Spot numeraire at T_k: the bank account rolled over the fixings.
+fn spot_numeraire(tenors: &[f64], curves: &[Vec<f64>], k: usize) -> f64 {
+    (0..k)
+        .map(|j| 1.0 + (tenors[j + 1] - tenors[j]) * curves[j][j])
+        .product()
+}
+
+// Swap rate and annuity at T_k of the swap over [T_start, T_end].
+fn swap_rate(tenors: &[f64], forwards: &[f64], k: usize, start: usize, end: usize) -> (f64, f64) {
+    let annuity: f64 = (start..end)
+        .map(|j| (tenors[j + 1] - tenors[j]) * discount(tenors, forwards, k, j + 1))
+        .sum();
+    let rate =
+        (discount(tenors, forwards, k, start) - discount(tenors, forwards, k, end)) / annuity;
+
+    (rate, annuity)
+}
+
+// Sample mean and its standard error.
+fn estimate<I: Iterator<Item = f64>>(values: I) -> Price {
+    let (n, sum, sum_sq) = values.fold((0.0, 0.0, 0.0), |(n, sum, sum_sq), x| {
+        (n + 1.0, sum + x, sum_sq + x * x)
+    });
+    let mean = sum / n;
+    let variance = ((sum_sq - n * mean * mean) / (n - 1.0)).max(0.0);
+
+    Price {
+        price: mean,
+        error: Some((variance / n).sqrt()),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_libor_market_model {
+    use super::*;
+
+    fn model() -> LiborMarketModel {
+        LiborMarketModel::new(
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            vec![0.05, 0.052, 0.054, 0.055, 0.056],
+            vec![0.2, 0.19, 0.18, 0.17, 0.16],
+            ForwardCorrelation::Exponential { decay: 0.1 },
+        )
+        .unwrap()
+        .with_shift(0.01)
+    }
+
+    fn assert_within(price: Price, expected: f64, slack: f64) {
+        let error = price.error.unwrap();
+
+        // The floor covers rounding when the price is deterministic (e.g.
+        // the numeraire bond under the terminal measure, with no error).
+        assert!(
+            (price.price - expected).abs() < 4.0 * error + slack + 1e-12,
+            "{} vs {expected} (error {error})",
+            price.price
+        );
+    }
+
+    #[test]
+    fn test_factor_loadings() {
+        let model = model();
+        let loadings = model.factor_loadings();
+
+        // All factors reproduce the correlation.
+        let correlation = &loadings * loadings.transpose();
+        assert!((correlation - model.correlation_matrix()).abs().max() < 1e-10);
+
+        // Fewer factors keep the volatilities (unit rows), and the highly
+        // correlated forwards stay highly correlated.
+        let reduced = model.with_factors(2).factor_loadings();
+        assert_eq!(reduced.ncols(), 2);
+        for row in reduced.row_iter() {
+            assert_approx_equal!(row.norm(), 1.0, 1e-12);
+        }
+        assert!(reduced.row(0).dot(&reduced.row(1)) > 0.9);
+    }
+
+    #[test]
+    fn test_lmm_caplets_and_bonds_match_the_initial_curve() {
+        let model = model();
+
+        for measure in [LmmMeasure::Spot, LmmMeasure::Terminal] {
+            let paths = model.simulate(measure, 4000, 4, 1).unwrap();
+
+            for k in [2, 4, 5] {
+                assert_within(paths.zero_coupon_bond(k), model.discount_factor(k), 0.0);
+            }
+            for i in [1, 3] {
+                assert_within(
+                    paths.caplet(i, 0.055),
+                    model.caplet_black_price(i, 0.055).unwrap(),
+                    0.0,
+                );
+            }
+
+            let cap: f64 = (1..5)
+                .map(|i| model.caplet_black_price(i, 0.055).unwrap())
+                .sum();
+            assert_within(paths.cap(0.055), cap, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_lmm_swaption_matches_rebonato() {
+        let model = model();
+        let (rate, _) = model.swap_rate(2, 5);
+
+        for measure in [LmmMeasure::Spot, LmmMeasure::Terminal] {
+            let paths = model.simulate(measure, 4000, 4, 7).unwrap();
+
+            for option_type in [TypeFlag::Call, TypeFlag::Put] {
+                let black = model.swaption_black_price(2, 5, rate, option_type).unwrap();
+
+                // Rebonato's formula is an approximation.
+                assert_within(paths.swaption(2, 5, rate, option_type), black, 0.01 * black);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lmm_validation() {
+        let model = model();
+
+        assert!(model.clone().with_factors(0).validate().is_err());
+        assert!(LiborMarketModel::new(
+            vec![0.0, 1.0],
+            vec![0.05, 0.05],
+            vec![0.2, 0.2],
+            ForwardCorrelation::Exponential { decay: 0.1 },
+        )
+        .is_err());
+        assert!(model.caplet_black_price(0, 0.05).is_err());
+        assert!(model
+            .swaption_black_price(3, 2, 0.05, TypeFlag::Call)
+            .is_err());
+        assert!(model.simulate(LmmMeasure::Spot, 0, 4, 1).is_err());
+    }
+}
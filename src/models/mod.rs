@@ -30,6 +30,10 @@ pub use black_derman_toy::*;
 pub mod brownian_motion;
 pub use brownian_motion::*;
 
+/// Cheyette (quasi-Gaussian) short rate model.
+pub mod cheyette;
+pub use cheyette::*;
+
 /// Constant Elasticity of Variance.
 pub mod constant_elasticity_of_variance;
 pub use constant_elasticity_of_variance::*;
@@ -38,22 +42,44 @@ pub use constant_elasticity_of_variance::*;
 pub mod cox_ingersoll_ross;
 pub use cox_ingersoll_ross::*;
 
+/// Exponentially Weighted Moving Average (EWMA) volatility.
+pub mod ewma;
+pub use ewma::*;
+
 /// Extended Vasicek.
 pub mod extended_vasicek;
 pub use extended_vasicek::*;
 
 /// Fractional Brownian Motion.
+#[cfg(feature = "stochastics")]
 pub mod fractional_brownian_motion;
+#[cfg(feature = "stochastics")]
 pub use fractional_brownian_motion::*;
 
 /// Fractional Cox-Ingersoll-Ross.
+#[cfg(feature = "stochastics")]
 pub mod fractional_cox_ingersoll_ross;
+#[cfg(feature = "stochastics")]
 pub use fractional_cox_ingersoll_ross::*;
 
 /// Fractional Ornstein-Uhlenbeck.
+#[cfg(feature = "stochastics")]
 pub mod fractional_ornstein_uhlenbeck;
+#[cfg(feature = "stochastics")]
 pub use fractional_ornstein_uhlenbeck::*;
 
+/// G2++ two-factor Gaussian short rate model.
+#[cfg(feature = "options")]
+pub mod g2_plus_plus;
+#[cfg(feature = "options")]
+pub use g2_plus_plus::*;
+
+/// GARCH(1,1) volatility.
+#[cfg(feature = "autodiff")]
+pub mod garch;
+#[cfg(feature = "autodiff")]
+pub use garch::*;
+
 /// Geometric Brownian Bridge.
 pub mod geometric_brownian_bridge;
 pub use geometric_brownian_bridge::*;
@@ -66,6 +92,10 @@ pub use geometric_brownian_motion::*;
 pub mod heston;
 pub use heston::*;
 
+/// Hidden Markov regime-switching model.
+pub mod hidden_markov;
+pub use hidden_markov::*;
+
 /// Ho-Lee.
 pub mod ho_lee;
 pub use ho_lee::*;
@@ -74,6 +104,16 @@ pub use ho_lee::*;
 pub mod hull_white;
 pub use hull_white::*;
 
+/// Kalman filter and linear Gaussian state-space models.
+#[cfg(feature = "autodiff")]
+pub mod kalman_filter;
+#[cfg(feature = "autodiff")]
+pub use kalman_filter::*;
+
+/// LIBOR Market Model (BGM).
+pub mod libor_market_model;
+pub use libor_market_model::*;
+
 /// Merton Jump Diffusion.
 pub mod merton_jump_diffusion;
 pub use merton_jump_diffusion::*;
@@ -97,3 +137,9 @@ pub use ornstein_uhlenbeck::*;
 /// SABR: Stochastic Alpha, Beta, Rho.
 pub mod sabr;
 pub use sabr::*;
+
+/// Short rate models shifted to fit the initial curve (Hull-White, CIR++).
+#[cfg(feature = "curves")]
+pub mod shifted_short_rate;
+#[cfg(feature = "curves")]
+pub use shifted_short_rate::*;
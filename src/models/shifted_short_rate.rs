@@ -0,0 +1,602 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Short rate models shifted to fit today's discount curve.
+//!
+//! The short rate is $r_t = x_t + \varphi(t)$, with $x$ a time-homogeneous
+//! model and the deterministic shift $\varphi$ chosen so that the model
+//! bonds $P(0, T)$ are the market ones (Brigo and Mercurio, 2001):
+//!
+//! $$
+//! \varphi(t) = f^M(0, t) - f^x(0, t),
+//! $$
+//!
+//! with $f^M$ the market instantaneous forward and $f^x$ the forward of the
+//! unshifted model. Two dynamics are supported:
+//!
+//! - Gaussian, $dx = -a x \, dt + \sigma \, dW$ from $x_0 = 0$: the
+//!   Hull-White model, whose drift $\theta(t)$ is also given;
+//! - Cox-Ingersoll-Ross, $dx = \kappa (\theta - x) dt + \sigma \sqrt{x} \, dW$:
+//!   the CIR++ model.
+//!
+//! The market curve is read from a [`YieldCurve`] at its pillar dates,
+//! with the zero rates linearly interpolated in time between them and
+//! held flat beyond the last pillar.
+
+use crate::data::YieldCurve;
+use crate::error::RustQuantError;
+use crate::instruments::{Validate, Validator};
+use crate::time::DayCountConvention;
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, Gamma, Poisson, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Time-homogeneous dynamics of the unshifted state $x$.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShortRateDynamics {
+    /// Ornstein-Uhlenbeck from zero: the shifted model is Hull-White.
+    Gaussian {
+        /// Mean reversion, $a$.
+        mean_reversion: f64,
+        /// Volatility, $\sigma$.
+        volatility: f64,
+    },
+
+    /// Cox-Ingersoll-Ross: the shifted model is CIR++.
+    CoxIngersollRoss {
+        /// Mean reversion, $\kappa$.
+        mean_reversion: f64,
+        /// Long run mean, $\theta$.
+        long_run_mean: f64,
+        /// Volatility, $\sigma$.
+        volatility: f64,
+        /// Initial state, $x_0$.
+        initial_value: f64,
+    },
+}
+
+/// Short rate model fitted to an initial discount curve by a deterministic
+/// shift.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShiftedShortRate {
+    /// Dynamics of the unshifted state.
+    pub dynamics: ShortRateDynamics,
+    // Pillar times (from the curve's initial date) and zero rates.
+    times: Vec<f64>,
+    rates: Vec<f64>,
+}
+
+/// Short rate and discount factor on a simulated path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShortRateState {
+    /// Short rate $r_t$.
+    pub rate: f64,
+    /// Discount factor from 0 along the path, $e^{-\int_0^t r_s ds}$.
+    pub discount: f64,
+}
+
+/// Simulated paths of a shifted short rate model.
+#[derive(Debug, Clone)]
+pub struct ShortRatePaths {
+    /// Times at which the states are recorded.
+    pub times: Vec<f64>,
+    /// States: `states[path][i]` at `times[i]`.
+    pub states: Vec<Vec<ShortRateState>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ShortRateDynamics {
+    /// Initial state $x_0$.
+    #[must_use]
+    pub fn initial_value(&self) -> f64 {
+        match *self {
+            Self::Gaussian { .. } => 0.0,
+            Self::CoxIngersollRoss { initial_value, .. } => initial_value,
+        }
+    }
+
+    /// Unshifted bond $P^x(t, t + \tau)$ given the state `x` at $t$.
+    #[must_use]
+    pub fn bond_price(&self, tau: f64, x: f64) -> f64 {
+        match *self {
+            Self::Gaussian {
+                mean_reversion: a,
+                volatility: sigma,
+            } => {
+                let b = -(-a * tau).exp_m1() / a;
+                let variance = sigma * sigma / (a * a) * (tau - b - 0.5 * a * b * b);
+
+                (0.5 * variance - b * x).exp()
+            }
+            Self::CoxIngersollRoss { .. } => {
+                let (a, b) = self.cir_coefficients(tau);
+
+                a * (-b * x).exp()
+            }
+        }
+    }
+
+    /// Unshifted instantaneous forward $f^x(0, t)$ from $x_0$.
+    #[must_use]
+    pub fn forward_rate(&self, t: f64) -> f64 {
+        match *self {
+            Self::Gaussian {
+                mean_reversion: a,
+                volatility: sigma,
+            } => -sigma * sigma / (2.0 * a * a) * (-a * t).exp_m1().powi(2),
+            Self::CoxIngersollRoss {
+                mean_reversion: kappa,
+                long_run_mean: theta,
+                volatility: sigma,
+                initial_value: x_0,
+            } => {
+                let h = (kappa * kappa + 2.0 * sigma * sigma).sqrt();
+                let growth = (h * t).exp_m1();
+                let denominator = 2.0 * h + (kappa + h) * growth;
+
+                2.0 * kappa * theta * growth / denominator
+                    + x_0 * 4.0 * h * h * (h * t).exp() / (denominator * denominator)
+            }
+        }
+    }
+
+    // CIR bond coefficients: P(t, t + tau) = A e^{-B x}.
+    fn cir_coefficients(&self, tau: f64) -> (f64, f64) {
+        let Self::CoxIngersollRoss {
+            mean_reversion: kappa,
+            long_run_mean: theta,
+            volatility: sigma,
+            ..
+        } = *self
+        else {
+            unreachable!("only called for CIR dynamics")
+        };
+
+        let h = (kappa * kappa + 2.0 * sigma * sigma).sqrt();
+        let growth = (h * tau).exp_m1();
+        let denominator = 2.0 * h + (kappa + h) * growth;
+        let a = (2.0 * h * (0.5 * (kappa + h) * tau).exp() / denominator)
+            .powf(2.0 * kappa * theta / (sigma * sigma));
+
+        (a, 2.0 * growth / denominator)
+    }
+}
+
+impl Validate for ShortRateDynamics {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        match *self {
+            Self::Gaussian {
+                mean_reversion,
+                volatility,
+            } => Validator::new()
+                .positive("mean_reversion", mean_reversion)
+                .positive("volatility", volatility)
+                .finish(),
+            Self::CoxIngersollRoss {
+                mean_reversion,
+                long_run_mean,
+                volatility,
+                initial_value,
+            } => Validator::new()
+                .positive("mean_reversion", mean_reversion)
+                .positive("long_run_mean", long_run_mean)
+                .positive("volatility", volatility)
+                .non_negative("initial_value", initial_value)
+                .finish(),
+        }
+    }
+}
+
+impl ShiftedShortRate {
+    /// Fit the dynamics to a yield curve, read at its pillar dates from its
+    /// initial date.
+    ///
+    /// # Errors:
+    /// * As [`ShiftedShortRate::from_zero_rates`].
+    pub fn new(dynamics: ShortRateDynamics, curve: &YieldCurve) -> Result<Self, RustQuantError> {
+        let Some(&initial_date) = curve.rates.keys().next() else {
+            return Err(RustQuantError::InvalidArgument(
+                "The curve has no points.".to_string(),
+            ));
+        };
+        let times: Vec<f64> = curve
+            .rates
+            .keys()
+            .map(|&date| DayCountConvention::default().day_count_factor(initial_date, date))
+            .collect();
+        let rates: Vec<f64> = curve.rates.values().copied().collect();
+
+        Self::from_zero_rates(dynamics, &times, &rates)
+    }
+
+    /// Fit the dynamics to continuously compounded zero rates at increasing
+    /// times (in years).
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the dynamics fail
+    ///   [`Validate::validate`], the times and rates differ in length or
+    ///   are empty, the times are negative or not increasing, or a rate is
+    ///   not finite.
+    pub fn from_zero_rates(
+        dynamics: ShortRateDynamics,
+        times: &[f64],
+        rates: &[f64],
+    ) -> Result<Self, RustQuantError> {
+        dynamics.validate()?;
+        Validator::new()
+            .check(!times.is_empty() && times.len() == rates.len(), || {
+                format!(
+                    "times and rates must be non-empty and of equal length (got {} and {})",
+                    times.len(),
+                    rates.len()
+                )
+            })
+            .check(!times.is_empty() && times[0] >= 0.0, || {
+                "times must be non-negative".to_string()
+            })
+            .check(times.windows(2).all(|w| w[0] < w[1]), || {
+                "times must be increasing".to_string()
+            })
+            .check(rates.iter().all(|rate| rate.is_finite()), || {
+                "rates must be finite".to_string()
+            })
+            .finish()?;
+
+        Ok(Self {
+            dynamics,
+            times: times.to_vec(),
+            rates: rates.to_vec(),
+        })
+    }
+
+    /// Market discount factor $P^M(0, T)$.
+    #[must_use]
+    pub fn discount_factor(&self, T: f64) -> f64 {
+        let (rate, _) = self.zero_rate(T);
+
+        (-rate * T).exp()
+    }
+
+    /// Market instantaneous forward $f^M(0, t) = R(t) + t R'(t)$, with $R$
+    /// the interpolated zero rate.
+    #[must_use]
+    pub fn forward_rate(&self, t: f64) -> f64 {
+        let (rate, slope) = self.zero_rate(t);
+
+        rate + t * slope
+    }
+
+    /// Deterministic shift $\varphi(t)$.
+    #[must_use]
+    pub fn shift(&self, t: f64) -> f64 {
+        self.forward_rate(t) - self.dynamics.forward_rate(t)
+    }
+
+    /// Initial short rate $r_0 = x_0 + \varphi(0)$.
+    #[must_use]
+    pub fn initial_short_rate(&self) -> f64 {
+        self.dynamics.initial_value() + self.shift(0.0)
+    }
+
+    /// Drift $\theta(t)$ of the Hull-White form $dr = (\theta(t) - a r) dt
+    /// + \sigma dW$, for Gaussian dynamics: $\theta(t) = \partial_t f^M(0, t)
+    /// + a f^M(0, t) + \frac{\sigma^2}{2 a} (1 - e^{-2 a t})$.
+    ///
+    /// Returns `None` for CIR dynamics, which have no such form.
+    #[must_use]
+    pub fn theta(&self, t: f64) -> Option<f64> {
+        match self.dynamics {
+            ShortRateDynamics::Gaussian {
+                mean_reversion: a,
+                volatility: sigma,
+            } => {
+                // f = R + t R' with R linear between pillars, so f' = 2 R'.
+                let (_, slope) = self.zero_rate(t);
+
+                Some(
+                    2.0 * slope + a * self.forward_rate(t)
+                        - sigma * sigma / (2.0 * a) * (-2.0 * a * t).exp_m1(),
+                )
+            }
+            ShortRateDynamics::CoxIngersollRoss { .. } => None,
+        }
+    }
+
+    /// Bond price $P(t, T)$ given the short rate `rate` at `t`:
+    ///
+    /// $$
+    /// P(t, T) = \frac{P^M(0, T) \, P^x(0, t)}{P^M(0, t) \, P^x(0, T)}
+    /// P^x(t, T; r_t - \varphi(t)).
+    /// $$
+    #[must_use]
+    pub fn bond_price(&self, t: f64, T: f64, rate: f64) -> f64 {
+        let x_0 = self.dynamics.initial_value();
+
+        self.discount_factor(T) * self.dynamics.bond_price(t, x_0)
+            / (self.discount_factor(t) * self.dynamics.bond_price(T, x_0))
+            * self.dynamics.bond_price(T - t, rate - self.shift(t))
+    }
+
+    /// Simulate `n_paths` short rate paths, recording the state at the
+    /// increasing `times`. The state is sampled exactly (non-central
+    /// chi-squared for CIR); the discount factor integrates the rate by
+    /// the trapezoid rule, with about `steps_per_year` steps a year.
+    ///
+    /// # Errors:
+    /// * [`RustQuantError::InvalidArgument`] if the times are not positive
+    ///   and increasing, or there are no paths or steps.
+    /// * [`RustQuantError::ComputationError`] if a CIR transition cannot be
+    ///   sampled.
+    pub fn simulate(
+        &self,
+        times: &[f64],
+        steps_per_year: usize,
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<ShortRatePaths, RustQuantError> {
+        Validator::new()
+            .check(!times.is_empty() && times[0] > 0.0, || {
+                "times must be positive".to_string()
+            })
+            .check(times.windows(2).all(|w| w[0] < w[1]), || {
+                "times must be increasing".to_string()
+            })
+            .check(n_paths > 0 && steps_per_year > 0, || {
+                "at least one path and one step are needed".to_string()
+            })
+            .finish()?;
+
+        // Steps between the recording times, with the shift at both ends.
+        let mut t = 0.0;
+        let grid: Vec<Vec<(f64, f64, f64)>> = times
+            .iter()
+            .map(|&t_next| {
+                let n_steps = ((t_next - t) * steps_per_year as f64).ceil().max(1.0);
+                let dt = (t_next - t) / n_steps;
+                let start = t;
+                t = t_next;
+
+                (0..n_steps as usize)
+                    .map(|j| {
+                        let s = start + j as f64 * dt;
+                        (dt, self.shift(s), self.shift(s + dt))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let states = (0..n_paths)
+            .map(|_| {
+                let mut x = self.dynamics.initial_value();
+                let mut integral = 0.0;
+
+                grid.iter()
+                    .map(|steps| {
+                        for &(dt, shift, shift_next) in steps {
+                            let x_next = self.transition(x, dt, &mut rng)?;
+
+                            integral += 0.5 * (x + shift + x_next + shift_next) * dt;
+                            x = x_next;
+                        }
+
+                        Ok(ShortRateState {
+                            rate: x + steps[steps.len() - 1].2,
+                            discount: (-integral).exp(),
+                        })
+                    })
+                    .collect::<Result<Vec<ShortRateState>, RustQuantError>>()
+            })
+            .collect::<Result<Vec<Vec<ShortRateState>>, RustQuantError>>()?;
+
+        Ok(ShortRatePaths {
+            times: times.to_vec(),
+            states,
+        })
+    }
+
+    // Zero rate at t and its slope, interpolated linearly between pillars
+    // and held flat outside them.
+    fn zero_rate(&self, t: f64) -> (f64, f64) {
+        let n = self.times.len();
+        let i = self.times.partition_point(|&pillar| pillar <= t);
+
+        match i {
+            0 => (self.rates[0], 0.0),
+            _ if i == n => (self.rates[n - 1], 0.0),
+            _ => {
+                let slope =
+                    (self.rates[i] - self.rates[i - 1]) / (self.times[i] - self.times[i - 1]);
+
+                (self.rates[i - 1] + slope * (t - self.times[i - 1]), slope)
+            }
+        }
+    }
+
+    // Exact transition of the unshifted state over dt.
+    fn transition(&self, x: f64, dt: f64, rng: &mut StdRng) -> Result<f64, RustQuantError> {
+        match self.dynamics {
+            ShortRateDynamics::Gaussian {
+                mean_reversion: a,
+                volatility: sigma,
+            } => {
+                let z: f64 = StandardNormal.sample(rng);
+
+                Ok(
+                    x * (-a * dt).exp()
+                        + sigma * (-(-2.0 * a * dt).exp_m1() / (2.0 * a)).sqrt() * z,
+                )
+            }
+            ShortRateDynamics::CoxIngersollRoss {
+                mean_reversion: kappa,
+                long_run_mean: theta,
+                volatility: sigma,
+                ..
+            } => {
+                // x' = c X, with X non-central chi-squared: a chi-squared
+                // variable with a Poisson number of extra degrees of freedom.
+                let decay = (-kappa * dt).exp();
+                let c = sigma * sigma * (1.0 - decay) / (4.0 * kappa);
+                let degrees = 4.0 * kappa * theta / (sigma * sigma);
+                let centrality = x * decay / c;
+
+                let n: f64 = match centrality > 0.0 {
+                    true => Poisson::new(0.5 * centrality)
+                        .map_err(|error| RustQuantError::ComputationError(error.to_string()))?
+                        .sample(rng),
+                    false => 0.0,
+                };
+                let chi_squared: f64 = Gamma::new(0.5 * degrees + n, 2.0)
+                    .map_err(|error| RustQuantError::ComputationError(error.to_string()))?
+                    .sample(rng);
+
+                Ok(c * chi_squared)
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_shifted_short_rate {
+    use super::*;
+    use crate::data::Curve;
+    use time::{Duration, OffsetDateTime};
+
+    const HULL_WHITE: ShortRateDynamics = ShortRateDynamics::Gaussian {
+        mean_reversion: 0.1,
+        volatility: 0.01,
+    };
+
+    const CIR: ShortRateDynamics = ShortRateDynamics::CoxIngersollRoss {
+        mean_reversion: 0.3,
+        long_run_mean: 0.03,
+        volatility: 0.1,
+        initial_value: 0.02,
+    };
+
+    // Upward sloping curve, with pillars out to 10 years.
+    fn curve() -> YieldCurve {
+        let t0 = OffsetDateTime::UNIX_EPOCH.date();
+        let dates: Vec<_> = [0, 182, 365, 730, 1826, 3652]
+            .iter()
+            .map(|&days| t0 + Duration::days(days))
+            .collect();
+
+        YieldCurve::from_dates_and_rates(&dates, &[0.02, 0.022, 0.025, 0.03, 0.035, 0.04]).unwrap()
+    }
+
+    #[test]
+    fn test_shifted_models_reprice_the_curve() {
+        let curve = curve();
+        let t0 = curve.initial_date();
+
+        for dynamics in [HULL_WHITE, CIR] {
+            let model = ShiftedShortRate::new(dynamics, &curve).unwrap();
+            let r_0 = model.initial_short_rate();
+
+            // Today's model bonds are the curve at each pillar.
+            for (&date, &rate) in curve.rates.iter().skip(1) {
+                let T = DayCountConvention::default().day_count_factor(t0, date);
+
+                assert_approx_equal!(model.bond_price(0.0, T, r_0), (-rate * T).exp(), 1e-12);
+            }
+
+            // And so are the simulated discount factors.
+            let times = [1.0, 3.0, 7.0];
+            let paths = model.simulate(&times, 100, 20_000, 1).unwrap();
+
+            for (i, &T) in times.iter().enumerate() {
+                let discounts: Vec<f64> = paths.states.iter().map(|s| s[i].discount).collect();
+                let n = discounts.len() as f64;
+                let mean = discounts.iter().sum::<f64>() / n;
+                let variance =
+                    discounts.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+                assert!(
+                    (mean - model.discount_factor(T)).abs() < 4.0 * (variance / n).sqrt() + 1e-4,
+                    "{mean} vs {}",
+                    model.discount_factor(T)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_shifted_bonds_are_martingales() {
+        // E[D(t) P(t, T)] = P(0, T) for the simulated rates at t.
+        for dynamics in [HULL_WHITE, CIR] {
+            let model = ShiftedShortRate::new(dynamics, &curve()).unwrap();
+            let paths = model.simulate(&[2.0], 100, 20_000, 2).unwrap();
+
+            let values: Vec<f64> = paths
+                .states
+                .iter()
+                .map(|s| s[0].discount * model.bond_price(2.0, 6.0, s[0].rate))
+                .collect();
+            let n = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / n;
+            let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+            assert!((mean - model.discount_factor(6.0)).abs() < 4.0 * (variance / n).sqrt() + 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_hull_white_theta() {
+        let model = ShiftedShortRate::new(HULL_WHITE, &curve()).unwrap();
+        let ShortRateDynamics::Gaussian { mean_reversion, .. } = HULL_WHITE else {
+            unreachable!()
+        };
+
+        // theta = phi' + a phi, away from the pillars.
+        for t in [0.7, 3.0, 12.0] {
+            let h = 1e-5;
+            let derivative = (model.shift(t + h) - model.shift(t - h)) / (2.0 * h);
+
+            assert_approx_equal!(
+                model.theta(t).unwrap(),
+                derivative + mean_reversion * model.shift(t),
+                1e-7
+            );
+        }
+
+        assert!(ShiftedShortRate::new(CIR, &curve())
+            .unwrap()
+            .theta(1.0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_shifted_short_rate_validation() {
+        let invalid = ShortRateDynamics::Gaussian {
+            mean_reversion: 0.0,
+            volatility: 0.01,
+        };
+
+        assert!(ShiftedShortRate::new(invalid, &curve()).is_err());
+        assert!(ShiftedShortRate::from_zero_rates(CIR, &[1.0, 0.5], &[0.02, 0.03]).is_err());
+        assert!(ShiftedShortRate::from_zero_rates(CIR, &[1.0], &[]).is_err());
+
+        let model = ShiftedShortRate::from_zero_rates(CIR, &[0.0], &[0.02]).unwrap();
+        assert!(model.simulate(&[0.0], 10, 10, 1).is_err());
+    }
+}
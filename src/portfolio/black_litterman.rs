@@ -0,0 +1,377 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Black-Litterman expected returns.
+//!
+//! The prior is the equilibrium (CAPM-implied) expected return
+//! $\pi = \delta \Sigma w_{mkt}$, with uncertainty $\tau \Sigma$.
+//! Views $P \mu = q + \varepsilon$, $\varepsilon \sim N(0, \Omega)$, are
+//! blended in to give the posterior:
+//!
+//! $$
+//! \mu_{BL} = M \left[ (\tau \Sigma)^{-1} \pi + P^\top \Omega^{-1} q \right],
+//! \qquad M = \left[ (\tau \Sigma)^{-1} + P^\top \Omega^{-1} P \right]^{-1}
+//! $$
+//!
+//! with posterior covariance of returns $\Sigma + M$.
+//! By default the view uncertainty is $\Omega_{kk} = \tau \, p_k^\top \Sigma p_k$
+//! (He and Litterman, 1999).
+//!
+//! The posterior can be fed to [`MeanVariance`](super::MeanVariance).
+//!
+//! ```
+//! use RustQuant::portfolio::{BlackLitterman, View};
+//! use nalgebra::DMatrix;
+//!
+//! let covariance = DMatrix::from_row_slice(3, 3, &[
+//!     0.010, 0.002, 0.001,
+//!     0.002, 0.040, 0.006,
+//!     0.001, 0.006, 0.090,
+//! ]);
+//!
+//! // Asset 2 will outperform asset 1 by 3%.
+//! let posterior = BlackLitterman::new(covariance, &[0.5, 0.3, 0.2], 2.5, 0.05)
+//!     .unwrap()
+//!     .with_view(View::relative(3, 1, 0, 0.03))
+//!     .posterior()
+//!     .unwrap();
+//!
+//! assert_eq!(posterior.expected_returns.len(), 3);
+//! ```
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A view on the expected returns: $p^\top \mu = q$.
+#[derive(Debug, Clone, PartialEq)]
+pub struct View {
+    /// Pick vector $p$ (portfolio the view is about).
+    pub picks: Vec<f64>,
+
+    /// Expected return of the pick portfolio, $q$.
+    pub expected_return: f64,
+
+    /// Variance of the view (the diagonal element of $\Omega$).
+    /// If `None`, the He-Litterman default $\tau p^\top \Sigma p$ is used.
+    pub uncertainty: Option<f64>,
+}
+
+/// Black-Litterman model.
+#[derive(Debug, Clone)]
+pub struct BlackLitterman {
+    /// Covariance matrix of the asset returns.
+    pub covariance: DMatrix<f64>,
+
+    /// Equilibrium (prior) expected returns.
+    pub equilibrium_returns: Vec<f64>,
+
+    /// Scaling of the prior uncertainty, $\tau$.
+    pub tau: f64,
+
+    /// Views.
+    pub views: Vec<View>,
+}
+
+/// Posterior distribution of returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlackLittermanPosterior {
+    /// Posterior expected returns.
+    pub expected_returns: Vec<f64>,
+
+    /// Posterior covariance matrix of returns.
+    pub covariance: DMatrix<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl View {
+    /// Absolute view: asset `asset` (out of `n_assets`) returns `expected_return`.
+    #[must_use]
+    pub fn absolute(n_assets: usize, asset: usize, expected_return: f64) -> Self {
+        let mut picks = vec![0.0; n_assets];
+        picks[asset] = 1.0;
+
+        Self {
+            picks,
+            expected_return,
+            uncertainty: None,
+        }
+    }
+
+    /// Relative view: asset `long` outperforms asset `short` by
+    /// `expected_return`.
+    #[must_use]
+    pub fn relative(n_assets: usize, long: usize, short: usize, expected_return: f64) -> Self {
+        let mut picks = vec![0.0; n_assets];
+        picks[long] = 1.0;
+        picks[short] = -1.0;
+
+        Self {
+            picks,
+            expected_return,
+            uncertainty: None,
+        }
+    }
+
+    /// Set the variance of the view.
+    #[must_use]
+    pub const fn with_uncertainty(mut self, variance: f64) -> Self {
+        self.uncertainty = Some(variance);
+        self
+    }
+}
+
+/// Equilibrium (CAPM-implied) expected returns $\pi = \delta \Sigma w_{mkt}$.
+#[must_use]
+pub fn implied_returns(
+    covariance: &DMatrix<f64>,
+    market_weights: &[f64],
+    risk_aversion: f64,
+) -> Vec<f64> {
+    (risk_aversion * covariance * DVector::from_column_slice(market_weights))
+        .iter()
+        .copied()
+        .collect()
+}
+
+impl BlackLitterman {
+    /// New model with the prior implied by the market weights and the
+    /// risk aversion $\delta$.
+    ///
+    /// # Errors
+    ///
+    /// If the dimensions do not match or $\tau$ is not positive.
+    pub fn new(
+        covariance: DMatrix<f64>,
+        market_weights: &[f64],
+        risk_aversion: f64,
+        tau: f64,
+    ) -> Result<Self, RustQuantError> {
+        if covariance.shape() != (market_weights.len(), market_weights.len()) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "covariance matrix is {:?} for {} assets",
+                covariance.shape(),
+                market_weights.len()
+            )));
+        }
+
+        let equilibrium_returns = implied_returns(&covariance, market_weights, risk_aversion);
+
+        Self::from_equilibrium_returns(covariance, &equilibrium_returns, tau)
+    }
+
+    /// New model with the given prior expected returns.
+    ///
+    /// # Errors
+    ///
+    /// If the dimensions do not match or $\tau$ is not positive.
+    pub fn from_equilibrium_returns(
+        covariance: DMatrix<f64>,
+        equilibrium_returns: &[f64],
+        tau: f64,
+    ) -> Result<Self, RustQuantError> {
+        let n = equilibrium_returns.len();
+
+        if covariance.shape() != (n, n) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "covariance matrix is {:?} for {n} assets",
+                covariance.shape()
+            )));
+        }
+
+        if tau.is_nan() || tau <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "tau must be positive".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            covariance,
+            equilibrium_returns: equilibrium_returns.to_vec(),
+            tau,
+            views: Vec::new(),
+        })
+    }
+
+    /// Add a view.
+    #[must_use]
+    pub fn with_view(mut self, view: View) -> Self {
+        self.views.push(view);
+        self
+    }
+
+    /// Posterior expected returns and covariance.
+    ///
+    /// # Errors
+    ///
+    /// - If a view does not have one pick per asset, or a non-positive
+    ///   uncertainty.
+    /// - If the covariance matrix (or the posterior precision) cannot be
+    ///   inverted.
+    pub fn posterior(&self) -> Result<BlackLittermanPosterior, RustQuantError> {
+        let n = self.equilibrium_returns.len();
+        let k = self.views.len();
+
+        if self.views.iter().any(|view| view.picks.len() != n) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "views must have {n} picks"
+            )));
+        }
+
+        let prior = self.tau * &self.covariance;
+        let prior_precision = prior
+            .clone()
+            .try_inverse()
+            .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+        let p = DMatrix::from_fn(k, n, |i, j| self.views[i].picks[j]);
+        let q = DVector::from_iterator(k, self.views.iter().map(|view| view.expected_return));
+
+        let omega = DVector::from_iterator(
+            k,
+            self.views.iter().enumerate().map(|(i, view)| {
+                view.uncertainty
+                    .unwrap_or_else(|| (p.row(i) * &prior * p.row(i).transpose())[(0, 0)])
+            }),
+        );
+
+        if omega.iter().any(|omega| omega.is_nan() || *omega <= 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "view uncertainties must be positive".to_string(),
+            ));
+        }
+
+        let omega_inverse = DMatrix::from_diagonal(&omega.map(|omega| 1.0 / omega));
+        let pi = DVector::from_column_slice(&self.equilibrium_returns);
+
+        let m = (&prior_precision + p.transpose() * &omega_inverse * &p)
+            .try_inverse()
+            .ok_or(RustQuantError::MatrixInversionFailed)?;
+        let mu = &m * (&prior_precision * pi + p.transpose() * &omega_inverse * q);
+
+        Ok(BlackLittermanPosterior {
+            expected_returns: mu.iter().copied().collect(),
+            covariance: &self.covariance + m,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_black_litterman {
+    use super::*;
+
+    const EPS: f64 = 1e-10;
+
+    const WEIGHTS: [f64; 3] = [0.5, 0.3, 0.2];
+
+    fn covariance() -> DMatrix<f64> {
+        DMatrix::from_row_slice(
+            3,
+            3,
+            &[
+                0.010, 0.002, 0.001, //
+                0.002, 0.040, 0.006, //
+                0.001, 0.006, 0.090,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_no_views() {
+        let model = BlackLitterman::new(covariance(), &WEIGHTS, 2.5, 0.05).unwrap();
+        let posterior = model.posterior().unwrap();
+
+        // pi_1 = 2.5 * (0.010 * 0.5 + 0.002 * 0.3 + 0.001 * 0.2)
+        assert_approx_equal!(model.equilibrium_returns[0], 0.0145, EPS);
+
+        for (mu, pi) in posterior
+            .expected_returns
+            .iter()
+            .zip(&model.equilibrium_returns)
+        {
+            assert_approx_equal!(*mu, *pi, EPS);
+        }
+        assert!((posterior.covariance - 1.05 * covariance()).amax() < EPS);
+    }
+
+    #[test]
+    fn test_single_absolute_view() {
+        // With one absolute view on an asset, the posterior return of that
+        // asset is a precision-weighted average of the prior and the view.
+        let tau = 0.05;
+        let omega = 0.001;
+        let model = BlackLitterman::new(covariance(), &WEIGHTS, 2.5, tau).unwrap();
+        let pi = model.equilibrium_returns.clone();
+
+        let posterior = model
+            .with_view(View::absolute(3, 2, 0.10).with_uncertainty(omega))
+            .posterior()
+            .unwrap();
+
+        let prior_variance = tau * 0.090;
+        let expected =
+            (pi[2] / prior_variance + 0.10 / omega) / (1.0 / prior_variance + 1.0 / omega);
+
+        assert_approx_equal!(posterior.expected_returns[2], expected, EPS);
+    }
+
+    #[test]
+    fn test_confident_relative_view() {
+        let posterior = BlackLitterman::new(covariance(), &WEIGHTS, 2.5, 0.05)
+            .unwrap()
+            .with_view(View::relative(3, 1, 0, 0.03).with_uncertainty(1e-14))
+            .posterior()
+            .unwrap();
+
+        let spread = posterior.expected_returns[1] - posterior.expected_returns[0];
+        assert_approx_equal!(spread, 0.03, 1e-6);
+    }
+
+    #[test]
+    fn test_default_uncertainty_moves_towards_view() {
+        let model = BlackLitterman::new(covariance(), &WEIGHTS, 2.5, 0.05).unwrap();
+        let pi = model.equilibrium_returns.clone();
+
+        let posterior = model
+            .with_view(View::absolute(3, 0, 0.05))
+            .posterior()
+            .unwrap();
+
+        // Equal prior and view uncertainty: half-way for that asset.
+        assert_approx_equal!(posterior.expected_returns[0], 0.5 * (pi[0] + 0.05), EPS);
+        assert!(posterior.expected_returns[1] > pi[1]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(BlackLitterman::new(covariance(), &[0.5, 0.5], 2.5, 0.05).is_err());
+        assert!(BlackLitterman::new(covariance(), &WEIGHTS, 2.5, 0.0).is_err());
+
+        let model = BlackLitterman::new(covariance(), &WEIGHTS, 2.5, 0.05).unwrap();
+        assert!(model
+            .clone()
+            .with_view(View::absolute(2, 0, 0.1))
+            .posterior()
+            .is_err());
+        assert!(model
+            .with_view(View::absolute(3, 0, 0.1).with_uncertainty(0.0))
+            .posterior()
+            .is_err());
+    }
+}
@@ -0,0 +1,762 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Clustering of assets and hierarchical risk parity.
+//!
+//! Assets are compared through the correlation distance
+//!
+//! $$
+//! d_{ij} = \sqrt{\frac{1 - \rho_{ij}}{2}}
+//! $$
+//!
+//! which is a proper metric on the correlation matrix. Assets can then be
+//! grouped by agglomerative (hierarchical) clustering, or by k-means on the
+//! rows of the distance matrix.
+//!
+//! Hierarchical risk parity (López de Prado, 2016) orders the assets by the
+//! leaves of the dendrogram, so that similar assets sit next to each other,
+//! and splits the capital by recursive bisection of that order, each half
+//! receiving a share inversely proportional to its (inverse-variance
+//! portfolio) variance.
+//!
+//! ```
+//! use RustQuant::portfolio::HierarchicalRiskParity;
+//! use nalgebra::DMatrix;
+//!
+//! let covariance = DMatrix::from_row_slice(3, 3, &[
+//!     0.010, 0.002, 0.001,
+//!     0.002, 0.040, 0.006,
+//!     0.001, 0.006, 0.090,
+//! ]);
+//!
+//! let weights = HierarchicalRiskParity::new(covariance).unwrap().weights().unwrap();
+//!
+//! assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+//! assert!(weights[0] > weights[1] && weights[1] > weights[2]);
+//! ```
+
+use crate::error::RustQuantError;
+use nalgebra::DMatrix;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Linkage criterion: the distance between two clusters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Linkage {
+    /// Minimum distance between members (the HRP default).
+    #[default]
+    Single,
+    /// Maximum distance between members.
+    Complete,
+    /// Mean distance between members (UPGMA).
+    Average,
+    /// Ward's minimum variance criterion.
+    Ward,
+}
+
+/// One agglomeration step of a hierarchical clustering.
+///
+/// Clusters are numbered as in `scipy`: the leaves are `0..n` and the
+/// cluster created by the `k`-th merge is `n + k`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Merge {
+    /// First merged cluster.
+    pub left: usize,
+    /// Second merged cluster.
+    pub right: usize,
+    /// Linkage distance between the two clusters.
+    pub distance: f64,
+    /// Number of leaves in the new cluster.
+    pub size: usize,
+}
+
+/// Result of a hierarchical clustering: the `n - 1` merges, in order.
+#[derive(Debug, Clone)]
+pub struct Dendrogram {
+    /// Number of leaves (observations).
+    pub n_leaves: usize,
+    /// Merges, by increasing step.
+    pub merges: Vec<Merge>,
+}
+
+/// Agglomerative clustering of a distance matrix.
+#[derive(Debug, Clone)]
+pub struct HierarchicalClustering {
+    /// Symmetric matrix of pairwise distances.
+    pub distance: DMatrix<f64>,
+    /// Linkage criterion.
+    pub linkage: Linkage,
+}
+
+/// K-means clustering (Lloyd's algorithm with k-means++ seeding).
+#[derive(Debug, Clone, Copy)]
+pub struct KMeans {
+    /// Number of clusters.
+    pub k: usize,
+    /// Maximum number of iterations.
+    pub max_iterations: usize,
+    /// Seed for the k-means++ initialisation.
+    pub seed: u64,
+}
+
+/// Result of a k-means clustering.
+#[derive(Debug, Clone)]
+pub struct KMeansResult {
+    /// Cluster of each observation.
+    pub labels: Vec<usize>,
+    /// Cluster centroids, one per row.
+    pub centroids: DMatrix<f64>,
+    /// Sum of squared distances of the observations to their centroid.
+    pub inertia: f64,
+    /// Number of iterations performed.
+    pub iterations: usize,
+}
+
+/// Hierarchical risk parity portfolio.
+#[derive(Debug, Clone)]
+pub struct HierarchicalRiskParity {
+    /// Covariance matrix of the asset returns.
+    pub covariance: DMatrix<f64>,
+    /// Linkage used to build the asset tree.
+    pub linkage: Linkage,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Correlation matrix of a covariance matrix.
+///
+/// # Errors
+///
+/// If the matrix is not square or has a non-positive variance.
+pub fn covariance_to_correlation(
+    covariance: &DMatrix<f64>,
+) -> Result<DMatrix<f64>, RustQuantError> {
+    if !covariance.is_square() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "covariance matrix is {:?}",
+            covariance.shape()
+        )));
+    }
+
+    let vols = covariance.diagonal().map(f64::sqrt);
+
+    if vols.iter().any(|v| v.is_nan() || *v <= 0.0) {
+        return Err(RustQuantError::InvalidArgument(
+            "variances must be positive".to_string(),
+        ));
+    }
+
+    Ok(DMatrix::from_fn(
+        covariance.nrows(),
+        covariance.ncols(),
+        |i, j| covariance[(i, j)] / (vols[i] * vols[j]),
+    ))
+}
+
+/// Correlation distance matrix, $d_{ij} = \sqrt{(1 - \rho_{ij}) / 2}$.
+///
+/// # Errors
+///
+/// If the matrix is not square or has entries outside $[-1, 1]$.
+pub fn correlation_distance(correlation: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+    if !correlation.is_square() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "correlation matrix is {:?}",
+            correlation.shape()
+        )));
+    }
+    if correlation.iter().any(|rho| !(-1.0..=1.0).contains(rho)) {
+        return Err(RustQuantError::InvalidArgument(
+            "correlations must lie in [-1, 1]".to_string(),
+        ));
+    }
+
+    Ok(correlation.map(|rho| ((1.0 - rho) / 2.0).sqrt()))
+}
+
+impl HierarchicalClustering {
+    /// New hierarchical clustering of a distance matrix (single linkage).
+    ///
+    /// # Errors
+    ///
+    /// If the matrix is empty, not square, or has negative entries.
+    pub fn new(distance: DMatrix<f64>) -> Result<Self, RustQuantError> {
+        if distance.nrows() == 0 || !distance.is_square() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "distance matrix is {:?}",
+                distance.shape()
+            )));
+        }
+        if distance.iter().any(|d| d.is_nan() || *d < 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "distances must be non-negative".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            distance,
+            linkage: Linkage::default(),
+        })
+    }
+
+    /// Set the linkage criterion.
+    #[must_use]
+    pub fn with_linkage(mut self, linkage: Linkage) -> Self {
+        self.linkage = linkage;
+        self
+    }
+
+    /// Build the dendrogram by repeatedly merging the two closest clusters,
+    /// updating the cluster distances with the Lance-Williams formula.
+    #[must_use]
+    pub fn fit(&self) -> Dendrogram {
+        let n = self.distance.nrows();
+        let mut d = self.distance.clone();
+
+        // Active clusters: (cluster id, size), indexed by row of `d`.
+        let mut active: Vec<Option<(usize, usize)>> = (0..n).map(|i| Some((i, 1))).collect();
+        let mut merges = Vec::with_capacity(n.saturating_sub(1));
+
+        for step in 0..n.saturating_sub(1) {
+            let (mut a, mut b, mut closest) = (0, 0, f64::INFINITY);
+
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    if active[i].is_some() && active[j].is_some() && d[(i, j)] < closest {
+                        (a, b, closest) = (i, j, d[(i, j)]);
+                    }
+                }
+            }
+
+            let (id_a, n_a) = active[a].expect("Active cluster.");
+            let (id_b, n_b) = active[b].expect("Active cluster.");
+
+            for k in 0..n {
+                if k == a || k == b {
+                    continue;
+                }
+                if let Some((_, n_k)) = active[k] {
+                    let updated = self.update(d[(a, k)], d[(b, k)], closest, n_a, n_b, n_k);
+                    d[(a, k)] = updated;
+                    d[(k, a)] = updated;
+                }
+            }
+
+            merges.push(Merge {
+                left: id_a.min(id_b),
+                right: id_a.max(id_b),
+                distance: closest,
+                size: n_a + n_b,
+            });
+
+            active[a] = Some((n + step, n_a + n_b));
+            active[b] = None;
+        }
+
+        Dendrogram {
+            n_leaves: n,
+            merges,
+        }
+    }
+
+    fn update(&self, d_ak: f64, d_bk: f64, d_ab: f64, n_a: usize, n_b: usize, n_k: usize) -> f64 {
+        let (n_a, n_b, n_k) = (n_a as f64, n_b as f64, n_k as f64);
+
+        match self.linkage {
+            Linkage::Single => d_ak.min(d_bk),
+            Linkage::Complete => d_ak.max(d_bk),
+            Linkage::Average => (n_a * d_ak + n_b * d_bk) / (n_a + n_b),
+            Linkage::Ward => (((n_a + n_k) * d_ak.powi(2) + (n_b + n_k) * d_bk.powi(2)
+                - n_k * d_ab.powi(2))
+                / (n_a + n_b + n_k))
+                .max(0.0)
+                .sqrt(),
+        }
+    }
+}
+
+impl Dendrogram {
+    /// Leaves ordered so that the members of every cluster are contiguous
+    /// (the quasi-diagonalisation step of HRP).
+    #[must_use]
+    pub fn order(&self) -> Vec<usize> {
+        let n = self.n_leaves;
+
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut order = Vec::with_capacity(n);
+        let mut stack = vec![n + self.merges.len() - 1];
+
+        while let Some(cluster) = stack.pop() {
+            if cluster < n {
+                order.push(cluster);
+            } else {
+                let merge = &self.merges[cluster - n];
+                stack.push(merge.right);
+                stack.push(merge.left);
+            }
+        }
+
+        order
+    }
+
+    /// Cut the tree into `n_clusters` flat clusters and return the label of
+    /// every leaf. Labels are numbered by order of first appearance.
+    ///
+    /// # Errors
+    ///
+    /// If `n_clusters` is zero or larger than the number of leaves.
+    pub fn cut(&self, n_clusters: usize) -> Result<Vec<usize>, RustQuantError> {
+        let n = self.n_leaves;
+
+        if n_clusters == 0 || n_clusters > n {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "cannot cut {n} leaves into {n_clusters} clusters"
+            )));
+        }
+
+        // Root of every leaf after the first `n - n_clusters` merges.
+        let mut root: Vec<usize> = (0..n).collect();
+        let mut members: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+
+        for (step, merge) in self.merges.iter().take(n - n_clusters).enumerate() {
+            let mut merged = std::mem::take(&mut members[merge.left]);
+            merged.append(&mut std::mem::take(&mut members[merge.right]));
+
+            for &leaf in &merged {
+                root[leaf] = n + step;
+            }
+            members.push(merged);
+        }
+
+        let mut labels = vec![0; n];
+        let mut seen: Vec<usize> = Vec::with_capacity(n_clusters);
+
+        for leaf in 0..n {
+            labels[leaf] = match seen.iter().position(|r| *r == root[leaf]) {
+                Some(label) => label,
+                None => {
+                    seen.push(root[leaf]);
+                    seen.len() - 1
+                }
+            };
+        }
+
+        Ok(labels)
+    }
+}
+
+impl KMeans {
+    /// New k-means clustering with `k` clusters.
+    #[must_use]
+    pub const fn new(k: usize) -> Self {
+        Self {
+            k,
+            max_iterations: 300,
+            seed: 0,
+        }
+    }
+
+    /// Set the maximum number of iterations.
+    #[must_use]
+    pub const fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Set the seed of the initialisation.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Cluster the rows of `points`.
+    ///
+    /// To group assets, pass the rows of their correlation distance matrix
+    /// (see [`correlation_distance`]), so that assets with similar
+    /// correlation profiles end up in the same cluster.
+    ///
+    /// # Errors
+    ///
+    /// If `k` is zero or exceeds the number of observations.
+    pub fn fit(&self, points: &DMatrix<f64>) -> Result<KMeansResult, RustQuantError> {
+        let (n, dim) = points.shape();
+
+        if self.k == 0 || self.k > n {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "cannot form {} clusters from {n} observations",
+                self.k
+            )));
+        }
+
+        let squared_distance = |i: usize, centroids: &DMatrix<f64>, c: usize| {
+            (points.row(i) - centroids.row(c)).norm_squared()
+        };
+
+        // k-means++: each new centroid is drawn with probability
+        // proportional to the squared distance to the nearest existing one.
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut centroids = DMatrix::zeros(self.k, dim);
+        centroids.set_row(0, &points.row(rng.gen_range(0..n)));
+
+        for c in 1..self.k {
+            let weights: Vec<f64> = (0..n)
+                .map(|i| {
+                    (0..c)
+                        .map(|j| squared_distance(i, &centroids, j))
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .collect();
+            let total = weights.iter().sum::<f64>();
+
+            let chosen = if total > 0.0 {
+                let mut u = rng.gen::<f64>() * total;
+                weights
+                    .iter()
+                    .position(|w| {
+                        u -= w;
+                        u <= 0.0
+                    })
+                    .unwrap_or(n - 1)
+            } else {
+                rng.gen_range(0..n)
+            };
+
+            centroids.set_row(c, &points.row(chosen));
+        }
+
+        let mut labels = vec![usize::MAX; n];
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            iterations += 1;
+
+            let assignment: Vec<usize> = (0..n)
+                .map(|i| {
+                    (0..self.k)
+                        .map(|c| (c, squared_distance(i, &centroids, c)))
+                        .fold(
+                            (0, f64::INFINITY),
+                            |best, x| if x.1 < best.1 { x } else { best },
+                        )
+                        .0
+                })
+                .collect();
+
+            if assignment == labels {
+                break;
+            }
+            labels = assignment;
+
+            for c in 0..self.k {
+                let members: Vec<usize> = (0..n).filter(|i| labels[*i] == c).collect();
+
+                // Empty clusters keep their previous centroid.
+                if !members.is_empty() {
+                    let mean = members
+                        .iter()
+                        .fold(nalgebra::RowDVector::zeros(dim), |acc, i| {
+                            acc + points.row(*i)
+                        })
+                        / members.len() as f64;
+                    centroids.set_row(c, &mean);
+                }
+            }
+        }
+
+        let inertia = (0..n)
+            .map(|i| squared_distance(i, &centroids, labels[i]))
+            .sum();
+
+        Ok(KMeansResult {
+            labels,
+            centroids,
+            inertia,
+            iterations,
+        })
+    }
+}
+
+impl HierarchicalRiskParity {
+    /// New hierarchical risk parity solver (single linkage).
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix is empty, not square, or has a non-positive
+    /// variance.
+    pub fn new(covariance: DMatrix<f64>) -> Result<Self, RustQuantError> {
+        if covariance.nrows() == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "covariance matrix is empty".to_string(),
+            ));
+        }
+
+        covariance_to_correlation(&covariance)?;
+
+        Ok(Self {
+            covariance,
+            linkage: Linkage::default(),
+        })
+    }
+
+    /// Set the linkage used to build the asset tree.
+    #[must_use]
+    pub fn with_linkage(mut self, linkage: Linkage) -> Self {
+        self.linkage = linkage;
+        self
+    }
+
+    /// The dendrogram of the assets, from their correlation distances.
+    ///
+    /// # Errors
+    ///
+    /// If the implied correlations lie outside $[-1, 1]$.
+    pub fn dendrogram(&self) -> Result<Dendrogram, RustQuantError> {
+        // Clamp rounding noise so that the diagonal has zero distance.
+        let correlation =
+            covariance_to_correlation(&self.covariance)?.map(|rho| rho.clamp(-1.0, 1.0));
+
+        Ok(
+            HierarchicalClustering::new(correlation_distance(&correlation)?)?
+                .with_linkage(self.linkage)
+                .fit(),
+        )
+    }
+
+    /// Long-only weights, summing to one.
+    ///
+    /// # Errors
+    ///
+    /// If the implied correlations lie outside $[-1, 1]$.
+    pub fn weights(&self) -> Result<Vec<f64>, RustQuantError> {
+        let order = self.dendrogram()?.order();
+        let mut weights = vec![1.0; order.len()];
+        let mut clusters = vec![order.as_slice()];
+
+        while let Some(cluster) = clusters.pop() {
+            if cluster.len() < 2 {
+                continue;
+            }
+
+            let (left, right) = cluster.split_at(cluster.len() / 2);
+            let (v_left, v_right) = (self.cluster_variance(left), self.cluster_variance(right));
+            let alpha = 1.0 - v_left / (v_left + v_right);
+
+            left.iter().for_each(|i| weights[*i] *= alpha);
+            right.iter().for_each(|i| weights[*i] *= 1.0 - alpha);
+
+            clusters.push(left);
+            clusters.push(right);
+        }
+
+        Ok(weights)
+    }
+
+    /// Variance of the inverse-variance portfolio of a cluster.
+    fn cluster_variance(&self, cluster: &[usize]) -> f64 {
+        let inverse: Vec<f64> = cluster
+            .iter()
+            .map(|i| 1.0 / self.covariance[(*i, *i)])
+            .collect();
+        let total = inverse.iter().sum::<f64>();
+        let w: Vec<f64> = inverse.iter().map(|x| x / total).collect();
+
+        cluster
+            .iter()
+            .enumerate()
+            .map(|(a, i)| {
+                cluster
+                    .iter()
+                    .enumerate()
+                    .map(|(b, j)| w[a] * w[b] * self.covariance[(*i, *j)])
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_clustering {
+    use super::*;
+
+    // Two blocks of highly correlated assets, {0, 2} and {1, 3, 4}.
+    fn block_correlation() -> DMatrix<f64> {
+        DMatrix::from_row_slice(
+            5,
+            5,
+            &[
+                1.0, 0.1, 0.9, 0.0, 0.1, //
+                0.1, 1.0, 0.2, 0.8, 0.7, //
+                0.9, 0.2, 1.0, 0.1, 0.0, //
+                0.0, 0.8, 0.1, 1.0, 0.9, //
+                0.1, 0.7, 0.0, 0.9, 1.0,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_correlation_distance() {
+        let distance = correlation_distance(&block_correlation()).unwrap();
+
+        assert_eq!(distance[(0, 0)], 0.0);
+        assert!((distance[(0, 2)] - 0.05_f64.sqrt()).abs() < 1e-12);
+        assert!((distance[(1, 3)] - 0.1_f64.sqrt()).abs() < 1e-12);
+
+        assert!(correlation_distance(&DMatrix::from_element(2, 2, 1.5)).is_err());
+    }
+
+    #[test]
+    fn test_single_linkage_merges() {
+        // Points on a line at 0, 1, 3 and 7.
+        let x = [0.0_f64, 1.0, 3.0, 7.0];
+        let distance = DMatrix::from_fn(4, 4, |i, j| (x[i] - x[j]).abs());
+
+        let dendrogram = HierarchicalClustering::new(distance).unwrap().fit();
+
+        let merges: Vec<(usize, usize, f64, usize)> = dendrogram
+            .merges
+            .iter()
+            .map(|m| (m.left, m.right, m.distance, m.size))
+            .collect();
+
+        assert_eq!(merges, vec![(0, 1, 1.0, 2), (2, 4, 2.0, 3), (3, 5, 4.0, 4)]);
+    }
+
+    #[test]
+    fn test_linkage_distances() {
+        let x = [0.0_f64, 1.0, 3.0, 7.0];
+        let distance = DMatrix::from_fn(4, 4, |i, j| (x[i] - x[j]).abs());
+        let clustering = HierarchicalClustering::new(distance).unwrap();
+
+        let last = |linkage| {
+            clustering
+                .clone()
+                .with_linkage(linkage)
+                .fit()
+                .merges
+                .last()
+                .unwrap()
+                .distance
+        };
+
+        assert_eq!(last(Linkage::Complete), 7.0);
+        // Mean of 7, 6 and 4.
+        assert!((last(Linkage::Average) - 17.0 / 3.0).abs() < 1e-12);
+        // Ward distance between {0, 1, 3} and {7}: sqrt(2 n_a n_b / (n_a + n_b)) * |mean_a - mean_b|.
+        assert!((last(Linkage::Ward) - (1.5_f64).sqrt() * (7.0 - 4.0 / 3.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cut_and_order() {
+        for linkage in [
+            Linkage::Single,
+            Linkage::Complete,
+            Linkage::Average,
+            Linkage::Ward,
+        ] {
+            let distance = correlation_distance(&block_correlation()).unwrap();
+            let dendrogram = HierarchicalClustering::new(distance)
+                .unwrap()
+                .with_linkage(linkage)
+                .fit();
+
+            assert_eq!(dendrogram.cut(2).unwrap(), vec![0, 1, 0, 1, 1]);
+            assert_eq!(dendrogram.cut(5).unwrap(), vec![0, 1, 2, 3, 4]);
+            assert_eq!(dendrogram.cut(1).unwrap(), vec![0; 5]);
+            assert!(dendrogram.cut(6).is_err());
+
+            // Each block is contiguous in the leaf order.
+            let order = dendrogram.order();
+            let position = |leaf| order.iter().position(|i| *i == leaf).unwrap();
+            assert_eq!(position(0).abs_diff(position(2)), 1);
+            assert_eq!(order.len(), 5);
+        }
+    }
+
+    #[test]
+    fn test_kmeans() {
+        let points = DMatrix::from_row_slice(
+            6,
+            2,
+            &[0.0, 0.0, 0.1, 0.2, -0.1, 0.1, 5.0, 5.0, 5.2, 4.9, 4.8, 5.1],
+        );
+
+        let result = KMeans::new(2).with_seed(1).fit(&points).unwrap();
+
+        assert_eq!(result.labels[0], result.labels[1]);
+        assert_eq!(result.labels[0], result.labels[2]);
+        assert_eq!(result.labels[3], result.labels[4]);
+        assert_eq!(result.labels[3], result.labels[5]);
+        assert_ne!(result.labels[0], result.labels[3]);
+
+        let c = result.labels[3];
+        assert!((result.centroids[(c, 0)] - 5.0).abs() < 1e-12);
+        assert!((result.centroids[(c, 1)] - 5.0).abs() < 1e-12);
+
+        assert!(KMeans::new(7).fit(&points).is_err());
+
+        // Asset grouping on the rows of the distance matrix.
+        let distance = correlation_distance(&block_correlation()).unwrap();
+        let labels = KMeans::new(2).fit(&distance).unwrap().labels;
+        assert_eq!(labels[0], labels[2]);
+        assert_eq!(labels[1], labels[3]);
+        assert_eq!(labels[1], labels[4]);
+        assert_ne!(labels[0], labels[1]);
+    }
+
+    #[test]
+    fn test_hrp_diagonal_is_inverse_variance() {
+        let variances = [0.01, 0.04, 0.09, 0.16];
+        let covariance = DMatrix::from_fn(4, 4, |i, j| if i == j { variances[i] } else { 0.0 });
+
+        let weights = HierarchicalRiskParity::new(covariance)
+            .unwrap()
+            .weights()
+            .unwrap();
+        let total = variances.iter().map(|v| 1.0 / v).sum::<f64>();
+
+        for (w, v) in weights.iter().zip(variances) {
+            assert!((w - 1.0 / v / total).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_hrp_weights() {
+        let vols = DMatrix::from_diagonal(&nalgebra::DVector::from_vec(vec![
+            0.1, 0.2, 0.15, 0.3, 0.25,
+        ]));
+        let covariance = &vols * block_correlation() * &vols;
+
+        for linkage in [Linkage::Single, Linkage::Average, Linkage::Ward] {
+            let weights = HierarchicalRiskParity::new(covariance.clone())
+                .unwrap()
+                .with_linkage(linkage)
+                .weights()
+                .unwrap();
+
+            assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            assert!(weights.iter().all(|w| *w > 0.0));
+        }
+
+        assert!(HierarchicalRiskParity::new(DMatrix::zeros(0, 0)).is_err());
+        assert!(HierarchicalRiskParity::new(DMatrix::from_element(2, 2, -1.0)).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Covariance matrix estimation.
+//!
+//! The sample covariance matrix is noisy when the number of assets is not
+//! small relative to the number of observations, and is singular when there
+//! are more assets than observations. The estimators here are better
+//! conditioned inputs for the portfolio optimizers:
+//!
+//! - Ledoit-Wolf (2004) shrinkage towards the constant-correlation matrix,
+//!   $\hat\Sigma = \delta F + (1 - \delta) S$, with the optimal intensity
+//!   $\delta$ estimated from the data.
+//! - Exponentially weighted (RiskMetrics) covariance, which puts more
+//!   weight on recent observations.
+//!
+//! Returns are passed as a matrix with one row per observation and one
+//! column per asset.
+//!
+//! ```
+//! use RustQuant::portfolio::*;
+//! use nalgebra::DMatrix;
+//!
+//! // 20 observations of 30 assets: the sample covariance is singular.
+//! let returns = DMatrix::from_fn(20, 30, |t, i| {
+//!     (((t * 31 + i * 17) * 7919) % 1009) as f64 / 1009.0 - 0.5
+//! }) * 0.02;
+//!
+//! let sample = CovarianceEstimator::Sample.estimate(&returns).unwrap();
+//! let shrunk = CovarianceEstimator::LedoitWolf.estimate(&returns).unwrap();
+//!
+//! assert!(sample.clone().cholesky().is_none());
+//! assert!(shrunk.cholesky().is_some());
+//! ```
+
+use super::FactorModel;
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Covariance matrix estimator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CovarianceEstimator {
+    /// Unbiased sample covariance.
+    Sample,
+    /// Ledoit-Wolf shrinkage towards the constant-correlation matrix.
+    LedoitWolf,
+    /// Exponentially weighted covariance with decay factor `lambda`.
+    ExponentiallyWeighted {
+        /// Decay factor, in $(0, 1)$. RiskMetrics uses 0.94 for daily returns.
+        lambda: f64,
+    },
+    /// Covariance of a statistical factor model with `n_factors` principal
+    /// component factors, see [`FactorModel::statistical`].
+    StatisticalFactor {
+        /// Number of factors, less than the number of assets.
+        n_factors: usize,
+    },
+}
+
+/// Result of a shrinkage covariance estimation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShrinkageEstimate {
+    /// The shrunk covariance matrix.
+    pub covariance: DMatrix<f64>,
+    /// The shrinkage target (constant-correlation matrix).
+    pub target: DMatrix<f64>,
+    /// The shrinkage intensity $\delta \in [0, 1]$.
+    pub shrinkage: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CovarianceEstimator {
+    /// Estimate the covariance matrix of the returns (one row per
+    /// observation, one column per asset).
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two observations, no assets, non-finite
+    /// returns, (for the exponentially weighted estimator) `lambda` is not
+    /// in $(0, 1)$, or (for the factor model) `n_factors` is zero or not
+    /// less than the number of assets.
+    pub fn estimate(&self, returns: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+        match self {
+            Self::Sample => sample_covariance(returns),
+            Self::LedoitWolf => Ok(ledoit_wolf(returns)?.covariance),
+            Self::ExponentiallyWeighted { lambda } => {
+                exponentially_weighted_covariance(returns, *lambda)
+            }
+            Self::StatisticalFactor { n_factors } => {
+                Ok(FactorModel::statistical(returns, *n_factors)?.covariance())
+            }
+        }
+    }
+}
+
+/// Check the returns and subtract the column means.
+fn demeaned(returns: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+    if returns.nrows() < 2 || returns.ncols() == 0 {
+        return Err(RustQuantError::InvalidArgument(
+            "covariance estimation needs at least two observations of one asset".to_string(),
+        ));
+    }
+    if returns.iter().any(|r| !r.is_finite()) {
+        return Err(RustQuantError::InvalidArgument(
+            "returns must be finite".to_string(),
+        ));
+    }
+
+    let mean = returns.row_mean();
+
+    Ok(DMatrix::from_fn(
+        returns.nrows(),
+        returns.ncols(),
+        |t, i| returns[(t, i)] - mean[i],
+    ))
+}
+
+/// Unbiased sample covariance matrix, $S = X^\top X / (T - 1)$ for the
+/// demeaned returns $X$.
+///
+/// # Errors
+///
+/// If there are fewer than two observations, no assets, or non-finite
+/// returns.
+pub fn sample_covariance(returns: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+    let x = demeaned(returns)?;
+
+    Ok(x.transpose() * &x / (x.nrows() - 1) as f64)
+}
+
+/// Ledoit-Wolf (2004) shrinkage of the sample covariance towards the
+/// constant-correlation matrix, from "Honey, I Shrunk the Sample Covariance
+/// Matrix".
+///
+/// The target keeps the sample variances and sets every correlation to the
+/// average sample correlation $\bar r$. As in the paper, the sample
+/// covariance is normalised by $T$ rather than $T - 1$.
+///
+/// # Errors
+///
+/// If there are fewer than two observations, no assets, non-finite returns,
+/// or an asset has zero variance.
+pub fn ledoit_wolf(returns: &DMatrix<f64>) -> Result<ShrinkageEstimate, RustQuantError> {
+    let x = demeaned(returns)?;
+    let (t, n) = x.shape();
+    let t_f = t as f64;
+
+    let sample = x.transpose() * &x / t_f;
+    let variances: DVector<f64> = sample.diagonal();
+
+    if variances.iter().any(|v| *v <= 0.0) {
+        return Err(RustQuantError::InvalidArgument(
+            "every asset must have positive variance".to_string(),
+        ));
+    }
+
+    let volatilities = variances.map(f64::sqrt);
+
+    // Average correlation and the constant-correlation target.
+    let r_bar = if n > 1 {
+        let total = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .filter(|(i, j)| i != j)
+            .map(|(i, j)| sample[(i, j)] / (volatilities[i] * volatilities[j]))
+            .sum::<f64>();
+
+        total / (n * (n - 1)) as f64
+    } else {
+        0.0
+    };
+
+    let target = DMatrix::from_fn(n, n, |i, j| {
+        if i == j {
+            variances[i]
+        } else {
+            r_bar * volatilities[i] * volatilities[j]
+        }
+    });
+
+    // pi: sum of the asymptotic variances of the sample covariances.
+    let y = x.map(|v| v * v);
+    let pi_matrix = DMatrix::from_fn(n, n, |i, j| {
+        (y.column(i).dot(&y.column(j))) / t_f - sample[(i, j)].powi(2)
+    });
+    let pi = pi_matrix.sum();
+
+    // rho: sum of the asymptotic covariances of the target and sample.
+    let x3 = x.map(|v| v.powi(3));
+    let theta = DMatrix::from_fn(n, n, |i, j| {
+        x3.column(i).dot(&x.column(j)) / t_f - variances[i] * sample[(i, j)]
+    });
+    let off_diagonal = (0..n)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .filter(|(i, j)| i != j)
+        .map(|(i, j)| {
+            volatilities[j] / volatilities[i] * theta[(i, j)]
+                + volatilities[i] / volatilities[j] * theta[(j, i)]
+        })
+        .sum::<f64>();
+    let rho = pi_matrix.diagonal().sum() + 0.5 * r_bar * off_diagonal;
+
+    // gamma: misspecification of the target.
+    let gamma = (&sample - &target).norm_squared();
+
+    let shrinkage = if gamma > 0.0 {
+        ((pi - rho) / gamma / t_f).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    Ok(ShrinkageEstimate {
+        covariance: &target * shrinkage + &sample * (1.0 - shrinkage),
+        target,
+        shrinkage,
+    })
+}
+
+/// Exponentially weighted covariance matrix,
+///
+/// $$
+/// \Sigma = \frac{1 - \lambda}{1 - \lambda^T} \sum_{t=1}^T \lambda^{T-t} r_t r_t^\top
+/// $$
+///
+/// As in RiskMetrics, the returns are assumed to have zero mean. The last
+/// row of `returns` is the most recent observation.
+///
+/// # Errors
+///
+/// If `lambda` is not in $(0, 1)$, there are no observations or assets, or
+/// the returns are not finite.
+pub fn exponentially_weighted_covariance(
+    returns: &DMatrix<f64>,
+    lambda: f64,
+) -> Result<DMatrix<f64>, RustQuantError> {
+    let (t, n) = returns.shape();
+
+    if !(lambda > 0.0 && lambda < 1.0) {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "decay factor must be in (0, 1), got {lambda}"
+        )));
+    }
+    if t == 0 || n == 0 {
+        return Err(RustQuantError::InvalidArgument(
+            "covariance estimation needs at least one observation of one asset".to_string(),
+        ));
+    }
+    if returns.iter().any(|r| !r.is_finite()) {
+        return Err(RustQuantError::InvalidArgument(
+            "returns must be finite".to_string(),
+        ));
+    }
+
+    let normalisation = (1.0 - lambda) / (1.0 - lambda.powi(t as i32));
+    let weights = DVector::from_fn(t, |s, _| normalisation * lambda.powi((t - 1 - s) as i32));
+    let weighted = DMatrix::from_fn(t, n, |s, i| weights[s] * returns[(s, i)]);
+
+    Ok(weighted.transpose() * returns)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_covariance {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, StandardNormal};
+
+    const EPS: f64 = 1e-12;
+
+    /// Returns with a common factor: every pair has correlation 0.5.
+    fn factor_returns(t: usize, n: usize, seed: u64) -> DMatrix<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut z = || -> f64 { StandardNormal.sample(&mut rng) };
+
+        let mut returns = DMatrix::zeros(t, n);
+        for s in 0..t {
+            let market = z();
+            for i in 0..n {
+                let volatility = 0.01 * (1.0 + i as f64 / n as f64);
+                returns[(s, i)] = volatility * (0.5_f64.sqrt() * market + 0.5_f64.sqrt() * z());
+            }
+        }
+        returns
+    }
+
+    #[test]
+    fn test_sample_covariance() {
+        let returns = DMatrix::from_row_slice(3, 2, &[1.0, 2.0, 2.0, 4.0, 3.0, 9.0]);
+        let covariance = sample_covariance(&returns).unwrap();
+
+        assert_approx_equal!(covariance[(0, 0)], 1.0, EPS);
+        assert_approx_equal!(covariance[(0, 1)], 3.5, EPS);
+        assert_approx_equal!(covariance[(1, 1)], 13.0, EPS);
+    }
+
+    #[test]
+    fn test_ledoit_wolf() {
+        // Few observations of many assets: heavy shrinkage, and the
+        // estimate is positive definite although the sample is singular.
+        let short = factor_returns(20, 50, 1);
+        let estimate = ledoit_wolf(&short).unwrap();
+
+        assert!(estimate.shrinkage > 0.3 && estimate.shrinkage <= 1.0);
+        assert!(estimate.covariance.clone().cholesky().is_some());
+        assert!(sample_covariance(&short).unwrap().cholesky().is_none());
+
+        // The target has the sample variances and a common correlation.
+        let target = &estimate.target;
+        let r01 = target[(0, 1)] / (target[(0, 0)] * target[(1, 1)]).sqrt();
+        let r23 = target[(2, 3)] / (target[(2, 2)] * target[(3, 3)]).sqrt();
+        assert_approx_equal!(r01, r23, EPS);
+        assert!((r01 - 0.5).abs() < 0.1);
+
+        // With many observations and a misspecified target, the intensity
+        // vanishes.
+        let mut long = factor_returns(5000, 5, 2);
+        long.column_mut(4).neg_mut();
+        assert!(ledoit_wolf(&long).unwrap().shrinkage < 0.1);
+
+        // Closer to the true covariance than the sample covariance.
+        let truth = DMatrix::from_fn(50, 50, |i, j| {
+            let vi = 0.01 * (1.0 + i as f64 / 50.0);
+            let vj = 0.01 * (1.0 + j as f64 / 50.0);
+            if i == j {
+                vi * vi
+            } else {
+                0.5 * vi * vj
+            }
+        });
+        let sample = sample_covariance(&short).unwrap();
+        assert!((&estimate.covariance - &truth).norm() < (&sample - &truth).norm());
+    }
+
+    #[test]
+    fn test_exponentially_weighted() {
+        let returns = DMatrix::from_row_slice(3, 2, &[0.01, 0.02, -0.02, 0.01, 0.03, -0.01]);
+        let lambda = 0.9;
+        let covariance = exponentially_weighted_covariance(&returns, lambda).unwrap();
+
+        // Same as the EWMA variance recursion, started from the first return.
+        let normalisation = (1.0 - lambda) / (1.0 - lambda.powi(3));
+        let expected =
+            normalisation * (lambda * lambda * 0.01 * 0.02 + lambda * -0.02 * 0.01 + 0.03 * -0.01);
+        assert_approx_equal!(covariance[(0, 1)], expected, EPS);
+        assert_approx_equal!(covariance[(0, 1)], covariance[(1, 0)], EPS);
+
+        // Weights sum to one: constant returns give their square.
+        let constant = DMatrix::from_element(50, 1, 0.02);
+        let covariance = CovarianceEstimator::ExponentiallyWeighted { lambda: 0.94 }
+            .estimate(&constant)
+            .unwrap();
+        assert_approx_equal!(covariance[(0, 0)], 0.0004, EPS);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let returns = DMatrix::from_element(1, 3, 0.01);
+
+        assert!(sample_covariance(&returns).is_err());
+        assert!(ledoit_wolf(&DMatrix::from_element(10, 2, 0.5)).is_err());
+        assert!(exponentially_weighted_covariance(&returns, 1.0).is_err());
+        assert!(CovarianceEstimator::LedoitWolf
+            .estimate(&DMatrix::from_element(10, 2, f64::NAN))
+            .is_err());
+    }
+}
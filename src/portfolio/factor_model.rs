@@ -0,0 +1,450 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Factor risk models.
+//!
+//! Asset returns are explained by a few common factors plus independent
+//! asset-specific noise,
+//!
+//! $$
+//! r = \alpha + B f + \epsilon, \qquad \Sigma = B F B^\top + D
+//! $$
+//!
+//! where $B$ are the exposures (loadings) of the assets to the factors, $F$
+//! the factor covariance matrix, and $D$ the diagonal matrix of specific
+//! variances. The model covariance has far fewer parameters than the sample
+//! covariance, and is positive definite whenever the specific variances are
+//! positive. The factors are either:
+//!
+//! - given: the exposures come from time-series regressions of the asset
+//!   returns on user-provided factor returns (e.g. market, sector, or style
+//!   factor returns);
+//! - statistical: the leading principal components of the sample
+//!   covariance matrix.
+//!
+//! The model covariance can be passed to the portfolio optimizers (see
+//! [`CovarianceEstimator::StatisticalFactor`]) and to the delta-normal VaR
+//! engine, and the risk of a portfolio splits into factor and specific
+//! parts.
+//!
+//! ```
+//! use RustQuant::portfolio::*;
+//! use nalgebra::DMatrix;
+//!
+//! let returns = DMatrix::from_fn(250, 10, |t, i| {
+//!     let market = (((t * 7919) % 1009) as f64 / 1009.0 - 0.5) * 0.02;
+//!     let noise = (((t * 31 + i * 17) * 7919 % 1013) as f64 / 1013.0 - 0.5) * 0.01;
+//!     (1.0 + 0.1 * i as f64) * market + noise
+//! });
+//!
+//! let model = FactorModel::statistical(&returns, 1).unwrap();
+//! let risk = model.risk(&[0.1; 10]).unwrap();
+//!
+//! assert!(risk.factor_variance > risk.specific_variance);
+//! ```
+
+use super::covariance::sample_covariance;
+use crate::error::RustQuantError;
+use crate::risk::{delta_normal, RiskEstimate};
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Factor risk model of a set of assets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorModel {
+    /// Exposures $B$: one row per asset, one column per factor.
+    pub loadings: DMatrix<f64>,
+    /// Factor covariance matrix $F$.
+    pub factor_covariance: DMatrix<f64>,
+    /// Specific (residual) variance of each asset, the diagonal of $D$.
+    pub specific_variance: DVector<f64>,
+    /// Mean return not explained by the factors, $\alpha$.
+    pub intercepts: DVector<f64>,
+    /// Fraction of each asset's variance explained by the factors.
+    pub r_squared: DVector<f64>,
+}
+
+/// Factor decomposition of the variance of a portfolio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactorRisk {
+    /// Exposures of the portfolio to the factors, $B^\top w$.
+    pub exposures: DVector<f64>,
+    /// Total variance, $w^\top \Sigma w$.
+    pub total_variance: f64,
+    /// Variance from the factors, $w^\top B F B^\top w$.
+    pub factor_variance: f64,
+    /// Variance from the specific risks, $w^\top D w$.
+    pub specific_variance: f64,
+    /// Contribution of each factor to the factor variance; they sum to it.
+    pub factor_contributions: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FactorModel {
+    /// New factor model from its parts, with zero intercepts.
+    ///
+    /// # Errors
+    ///
+    /// If the dimensions do not match, or a specific variance is negative.
+    pub fn new(
+        loadings: DMatrix<f64>,
+        factor_covariance: DMatrix<f64>,
+        specific_variance: DVector<f64>,
+    ) -> Result<Self, RustQuantError> {
+        let (n, k) = loadings.shape();
+
+        if factor_covariance.shape() != (k, k) || specific_variance.len() != n {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "loadings are {:?}, factor covariance {:?}, and {} specific variances",
+                loadings.shape(),
+                factor_covariance.shape(),
+                specific_variance.len()
+            )));
+        }
+        if specific_variance
+            .iter()
+            .any(|v| !(v.is_finite() && *v >= 0.0))
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "specific variances must be finite and non-negative".to_string(),
+            ));
+        }
+
+        let factor_variance = (&loadings * &factor_covariance * loadings.transpose()).diagonal();
+        let r_squared = DVector::from_fn(n, |i, _| {
+            let total = factor_variance[i] + specific_variance[i];
+
+            match total > 0.0 {
+                true => factor_variance[i] / total,
+                false => 0.0,
+            }
+        });
+
+        Ok(Self {
+            loadings,
+            factor_covariance,
+            specific_variance,
+            intercepts: DVector::zeros(n),
+            r_squared,
+        })
+    }
+
+    /// Fit the exposures to given factors by regressing the returns of each
+    /// asset on the factor returns (both one row per observation).
+    ///
+    /// # Errors
+    ///
+    /// If the returns and factor returns have different numbers of
+    /// observations, there are no more observations than factors plus one,
+    /// or the factor returns are collinear.
+    pub fn fit(
+        returns: &DMatrix<f64>,
+        factor_returns: &DMatrix<f64>,
+    ) -> Result<Self, RustQuantError> {
+        let (t, n) = returns.shape();
+        let k = factor_returns.ncols();
+
+        if factor_returns.nrows() != t || n == 0 || k == 0 || t <= k + 1 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "{t} observations of {n} assets and {} observations of {k} factors",
+                factor_returns.nrows()
+            )));
+        }
+
+        // Regressors: a constant and the factor returns.
+        let x = DMatrix::from_fn(t, k + 1, |s, j| match j {
+            0 => 1.0,
+            _ => factor_returns[(s, j - 1)],
+        });
+        let gram = x.transpose() * &x;
+        let scale = gram.diagonal().max();
+        let coefficients = gram
+            .cholesky()
+            .filter(|cholesky| {
+                cholesky
+                    .l()
+                    .diagonal()
+                    .iter()
+                    .all(|l| l * l > 1e-12 * scale)
+            })
+            .ok_or_else(|| {
+                RustQuantError::ComputationError("factor returns are collinear".to_string())
+            })?
+            .solve(&(x.transpose() * returns));
+
+        let residuals = returns - &x * &coefficients;
+        let specific_variance = DVector::from_fn(n, |i, _| {
+            residuals.column(i).norm_squared() / (t - k - 1) as f64
+        });
+
+        let mut model = Self::new(
+            coefficients.rows(1, k).transpose(),
+            sample_covariance(factor_returns)?,
+            specific_variance,
+        )?;
+        model.intercepts = coefficients.row(0).transpose();
+
+        Ok(model)
+    }
+
+    /// Fit a statistical model: the factors are the `n_factors` leading
+    /// principal components of the sample covariance matrix, and the
+    /// specific variances what they leave of the sample variances.
+    ///
+    /// # Errors
+    ///
+    /// If the sample covariance cannot be estimated, or `n_factors` is zero
+    /// or not less than the number of assets.
+    pub fn statistical(returns: &DMatrix<f64>, n_factors: usize) -> Result<Self, RustQuantError> {
+        let covariance = sample_covariance(returns)?;
+        let n = covariance.nrows();
+
+        if n_factors == 0 || n_factors >= n {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "cannot fit {n_factors} factors to {n} assets"
+            )));
+        }
+
+        let eigen = covariance.clone().symmetric_eigen();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+        let mut loadings = DMatrix::zeros(n, n_factors);
+        for (c, &i) in order.iter().take(n_factors).enumerate() {
+            let mut column = eigen.eigenvectors.column(i).into_owned();
+
+            if column.sum() < 0.0 {
+                column.neg_mut();
+            }
+            loadings.set_column(c, &column);
+        }
+
+        let factor_covariance = DMatrix::from_diagonal(&DVector::from_iterator(
+            n_factors,
+            order
+                .iter()
+                .take(n_factors)
+                .map(|&i| eigen.eigenvalues[i].max(0.0)),
+        ));
+
+        let explained = (&loadings * &factor_covariance * loadings.transpose()).diagonal();
+        let specific_variance =
+            DVector::from_fn(n, |i, _| (covariance[(i, i)] - explained[i]).max(0.0));
+
+        let mut model = Self::new(loadings, factor_covariance, specific_variance)?;
+        model.intercepts = returns.row_mean().transpose();
+
+        Ok(model)
+    }
+
+    /// Number of assets.
+    #[must_use]
+    pub fn n_assets(&self) -> usize {
+        self.loadings.nrows()
+    }
+
+    /// Number of factors.
+    #[must_use]
+    pub fn n_factors(&self) -> usize {
+        self.loadings.ncols()
+    }
+
+    /// Model covariance matrix of the assets, $B F B^\top + D$.
+    #[must_use]
+    pub fn covariance(&self) -> DMatrix<f64> {
+        &self.loadings * &self.factor_covariance * self.loadings.transpose()
+            + DMatrix::from_diagonal(&self.specific_variance)
+    }
+
+    /// Factor and specific variance of a portfolio with weights (or
+    /// exposures) `weights`.
+    ///
+    /// # Errors
+    ///
+    /// If there is not one weight per asset.
+    pub fn risk(&self, weights: &[f64]) -> Result<FactorRisk, RustQuantError> {
+        self.check_weights(weights)?;
+
+        let w = DVector::from_column_slice(weights);
+        let exposures = self.loadings.transpose() * &w;
+        let covariance_exposures = &self.factor_covariance * &exposures;
+
+        let factor_contributions: Vec<f64> = exposures
+            .iter()
+            .zip(covariance_exposures.iter())
+            .map(|(e, c)| e * c)
+            .collect();
+        let factor_variance = factor_contributions.iter().sum::<f64>();
+        let specific_variance = w
+            .iter()
+            .zip(self.specific_variance.iter())
+            .map(|(w, v)| w * w * v)
+            .sum::<f64>();
+
+        Ok(FactorRisk {
+            exposures,
+            total_variance: factor_variance + specific_variance,
+            factor_variance,
+            specific_variance,
+            factor_contributions,
+        })
+    }
+
+    /// Delta-normal VaR and ES of a portfolio with exposures `weights`,
+    /// from the model covariance.
+    ///
+    /// # Errors
+    ///
+    /// If there is not one weight per asset, or the confidence level is not
+    /// in $(0, 1)$.
+    pub fn value_at_risk(
+        &self,
+        weights: &[f64],
+        confidence: f64,
+    ) -> Result<RiskEstimate, RustQuantError> {
+        self.check_weights(weights)?;
+
+        delta_normal(weights, &self.covariance(), confidence)
+    }
+
+    /// Check there is one weight per asset.
+    fn check_weights(&self, weights: &[f64]) -> Result<(), RustQuantError> {
+        match weights.len() == self.n_assets() {
+            true => Ok(()),
+            false => Err(RustQuantError::InvalidArgument(format!(
+                "{} weights for {} assets",
+                weights.len(),
+                self.n_assets()
+            ))),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_factor_model {
+    use super::*;
+    use crate::portfolio::CovarianceEstimator;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, StandardNormal};
+
+    const EPS: f64 = 1e-12;
+
+    /// Returns driven by two factors with known betas, and the factor
+    /// returns.
+    fn two_factor_returns(t: usize, n: usize) -> (DMatrix<f64>, DMatrix<f64>) {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut z = || -> f64 { StandardNormal.sample(&mut rng) };
+
+        let factors = DMatrix::from_fn(t, 2, |_, j| match j {
+            0 => 0.01 * z(),
+            _ => 0.005 * z(),
+        });
+        let returns = DMatrix::from_fn(t, n, |s, i| {
+            let (market, style) = (1.0 + 0.1 * i as f64, i as f64 / n as f64 - 0.5);
+            0.0001 + market * factors[(s, 0)] + style * factors[(s, 1)] + 0.002 * z()
+        });
+
+        (returns, factors)
+    }
+
+    #[test]
+    fn test_given_factors() {
+        let (returns, factors) = two_factor_returns(2_000, 8);
+        let model = FactorModel::fit(&returns, &factors).unwrap();
+
+        assert_eq!((model.n_assets(), model.n_factors()), (8, 2));
+        for i in 0..8 {
+            assert_approx_equal!(model.loadings[(i, 0)], 1.0 + 0.1 * i as f64, 0.03);
+            assert_approx_equal!(model.loadings[(i, 1)], i as f64 / 8.0 - 0.5, 0.05);
+            assert_approx_equal!(model.specific_variance[i].sqrt(), 0.002, 1e-4);
+            assert!(model.r_squared[i] > 0.9);
+        }
+
+        // The model covariance is close to the sample covariance.
+        let sample = sample_covariance(&returns).unwrap();
+        assert!((model.covariance() - &sample).norm() < 0.05 * sample.norm());
+
+        assert!(FactorModel::fit(&returns, &factors.rows(0, 100).into_owned()).is_err());
+        let collinear = DMatrix::from_fn(2_000, 2, |s, _| factors[(s, 0)]);
+        assert!(FactorModel::fit(&returns, &collinear).is_err());
+    }
+
+    #[test]
+    fn test_statistical_factors() {
+        let (returns, _) = two_factor_returns(2_000, 8);
+        let model = FactorModel::statistical(&returns, 2).unwrap();
+        let sample = sample_covariance(&returns).unwrap();
+
+        // The model matches the sample variances, and is positive definite.
+        let covariance = model.covariance();
+        for i in 0..8 {
+            assert_approx_equal!(covariance[(i, i)], sample[(i, i)], EPS);
+        }
+        assert!((&covariance - &sample).norm() < 0.05 * sample.norm());
+        assert!(covariance.cholesky().is_some());
+
+        let optimizer = CovarianceEstimator::StatisticalFactor { n_factors: 2 };
+        assert_eq!(optimizer.estimate(&returns).unwrap(), model.covariance());
+
+        assert!(FactorModel::statistical(&returns, 0).is_err());
+        assert!(FactorModel::statistical(&returns, 8).is_err());
+    }
+
+    #[test]
+    fn test_portfolio_risk() {
+        let model = FactorModel::new(
+            DMatrix::from_row_slice(3, 2, &[1.0, 0.5, 1.2, -0.5, 0.8, 0.0]),
+            DMatrix::from_row_slice(2, 2, &[0.04, 0.01, 0.01, 0.02]),
+            DVector::from_column_slice(&[0.01, 0.02, 0.03]),
+        )
+        .unwrap();
+
+        let weights = [0.5, 0.3, 0.2];
+        let risk = model.risk(&weights).unwrap();
+
+        let w = DVector::from_column_slice(&weights);
+        assert_approx_equal!(risk.total_variance, w.dot(&(model.covariance() * &w)), EPS);
+        assert_approx_equal!(
+            risk.factor_contributions.iter().sum::<f64>(),
+            risk.factor_variance,
+            EPS
+        );
+        assert_approx_equal!(
+            risk.specific_variance,
+            0.25 * 0.01 + 0.09 * 0.02 + 0.04 * 0.03,
+            EPS
+        );
+        assert_approx_equal!(risk.exposures[0], 0.5 + 0.36 + 0.16, EPS);
+
+        let var = model.value_at_risk(&weights, 0.99).unwrap();
+        assert_approx_equal!(
+            var.value_at_risk,
+            2.326_347_874 * risk.total_variance.sqrt(),
+            1e-8
+        );
+
+        assert!(model.risk(&[1.0]).is_err());
+        assert!(FactorModel::new(
+            DMatrix::zeros(3, 2),
+            DMatrix::zeros(2, 2),
+            DVector::from_column_slice(&[0.01, -0.01, 0.0]),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,625 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Markowitz mean-variance portfolio optimization.
+//!
+//! Given expected returns $\mu$ and a covariance matrix $\Sigma$, find the
+//! weights $w$ (summing to one, within optional bounds $l \leq w \leq u$)
+//! that:
+//!
+//! - minimise the variance $w^\top \Sigma w$ (minimum variance portfolio),
+//! - minimise the variance for a target return $\mu^\top w = \mu^*$
+//!   (a point on the efficient frontier),
+//! - maximise the Sharpe ratio $(\mu^\top w - r_f) / \sqrt{w^\top \Sigma w}$
+//!   (the tangency portfolio).
+//!
+//! The problems are solved with the [`ConstrainedOptimizer`].
+//!
+//! ```
+//! use RustQuant::portfolio::MeanVariance;
+//! use nalgebra::DMatrix;
+//!
+//! let covariance = DMatrix::from_row_slice(3, 3, &[
+//!     0.010, 0.002, 0.001,
+//!     0.002, 0.040, 0.006,
+//!     0.001, 0.006, 0.090,
+//! ]);
+//!
+//! let model = MeanVariance::new(&[0.04, 0.08, 0.12], covariance)
+//!     .unwrap()
+//!     .with_risk_free_rate(0.02)
+//!     .long_only();
+//!
+//! let frontier = model.efficient_frontier(10).unwrap();
+//!
+//! assert_eq!(frontier.points.len(), 10);
+//! assert!(frontier.tangency.sharpe_ratio >= frontier.min_variance.sharpe_ratio);
+//! ```
+
+use crate::autodiff::variables::variable::Variable;
+use crate::error::RustQuantError;
+use crate::math::ConstrainedOptimizer;
+use crate::portfolio::CovarianceEstimator;
+use nalgebra::DMatrix;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Mean-variance (Markowitz) portfolio optimizer.
+#[derive(Debug, Clone)]
+pub struct MeanVariance {
+    /// Expected returns of the assets.
+    pub expected_returns: Vec<f64>,
+
+    /// Covariance matrix of the asset returns.
+    pub covariance: DMatrix<f64>,
+
+    /// Risk-free rate (for the Sharpe ratio).
+    pub risk_free_rate: f64,
+
+    /// Lower bounds on the weights (empty for unbounded).
+    pub lower: Vec<f64>,
+
+    /// Upper bounds on the weights (empty for unbounded).
+    pub upper: Vec<f64>,
+}
+
+/// Portfolio weights and their risk/return characteristics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Allocation {
+    /// Weights of the assets (summing to one).
+    pub weights: Vec<f64>,
+
+    /// Expected return of the portfolio.
+    pub expected_return: f64,
+
+    /// Volatility of the portfolio.
+    pub volatility: f64,
+
+    /// Sharpe ratio of the portfolio.
+    pub sharpe_ratio: f64,
+}
+
+/// Efficient frontier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfficientFrontier {
+    /// Frontier portfolios, by increasing expected return.
+    pub points: Vec<Allocation>,
+
+    /// Minimum variance portfolio.
+    pub min_variance: Allocation,
+
+    /// Tangency (maximum Sharpe ratio) portfolio.
+    pub tangency: Allocation,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+const MAX_ITERATIONS: usize = 100_000;
+const TOLERANCE: f64 = 1e-10;
+
+impl MeanVariance {
+    /// New mean-variance optimizer, with unbounded weights (short sales
+    /// allowed) and a zero risk-free rate.
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix is not square and symmetric with one row per
+    /// asset.
+    pub fn new(expected_returns: &[f64], covariance: DMatrix<f64>) -> Result<Self, RustQuantError> {
+        let n = expected_returns.len();
+
+        if n == 0 || covariance.shape() != (n, n) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "covariance matrix is {:?} for {n} assets",
+                covariance.shape()
+            )));
+        }
+
+        if (&covariance - covariance.transpose()).amax() > 1e-12 * covariance.amax() {
+            return Err(RustQuantError::InvalidArgument(
+                "covariance matrix is not symmetric".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            expected_returns: expected_returns.to_vec(),
+            covariance,
+            risk_free_rate: 0.0,
+            lower: Vec::new(),
+            upper: Vec::new(),
+        })
+    }
+
+    /// New mean-variance optimizer from historical returns (one row per
+    /// observation, one column per asset): the expected returns are the
+    /// sample means, and the covariance matrix is given by the estimator.
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix cannot be estimated from the returns.
+    pub fn from_returns(
+        returns: &DMatrix<f64>,
+        estimator: CovarianceEstimator,
+    ) -> Result<Self, RustQuantError> {
+        let covariance = estimator.estimate(returns)?;
+        let expected_returns: Vec<f64> = returns.row_mean().iter().copied().collect();
+
+        Self::new(&expected_returns, covariance)
+    }
+
+    /// Set the risk-free rate.
+    #[must_use]
+    pub const fn with_risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+
+    /// Set bounds on the weights.
+    #[must_use]
+    pub fn with_bounds(mut self, lower: &[f64], upper: &[f64]) -> Self {
+        self.lower = lower.to_vec();
+        self.upper = upper.to_vec();
+        self
+    }
+
+    /// Restrict to long-only portfolios (no short sales).
+    #[must_use]
+    pub fn long_only(self) -> Self {
+        let n = self.expected_returns.len();
+
+        self.with_bounds(&vec![0.0; n], &vec![f64::INFINITY; n])
+    }
+
+    /// Expected return, volatility and Sharpe ratio of the given weights.
+    #[must_use]
+    pub fn allocation(&self, weights: &[f64]) -> Allocation {
+        let expected_return = dot(&self.expected_returns, weights);
+        let volatility = self.variance(weights).sqrt();
+
+        Allocation {
+            weights: weights.to_vec(),
+            expected_return,
+            volatility,
+            sharpe_ratio: (expected_return - self.risk_free_rate) / volatility,
+        }
+    }
+
+    /// Minimum variance portfolio.
+    ///
+    /// # Errors
+    ///
+    /// If the bounds are invalid or infeasible, or the optimizer fails.
+    pub fn min_variance(&self) -> Result<Allocation, RustQuantError> {
+        self.bounds()?;
+
+        let result = self.optimizer().optimize(
+            variance_objective(&self.covariance),
+            &self.initial_weights(),
+        )?;
+
+        Ok(self.allocation(&result.minimizer))
+    }
+
+    /// Minimum variance portfolio with the given expected return.
+    ///
+    /// # Errors
+    ///
+    /// If the bounds are invalid or infeasible, the target return cannot be
+    /// attained within the bounds, or the optimizer fails.
+    pub fn target_return(&self, target: f64) -> Result<Allocation, RustQuantError> {
+        let (min_return, max_return) = self.return_range()?;
+
+        if target < min_return - TOLERANCE || target > max_return + TOLERANCE {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "target return {target} outside the attainable range [{min_return}, {max_return}]"
+            )));
+        }
+
+        let result = self
+            .optimizer()
+            .with_equality(&self.expected_returns, target)
+            .optimize(
+                variance_objective(&self.covariance),
+                &self.initial_weights(),
+            )?;
+
+        Ok(self.allocation(&result.minimizer))
+    }
+
+    /// Tangency portfolio: the portfolio with the maximum Sharpe ratio.
+    ///
+    /// # Errors
+    ///
+    /// If the bounds are invalid or infeasible, no portfolio has an expected
+    /// return above the risk-free rate, or the optimizer fails.
+    pub fn max_sharpe(&self) -> Result<Allocation, RustQuantError> {
+        let (_, max_return) = self.return_range()?;
+
+        if max_return <= self.risk_free_rate {
+            return Err(RustQuantError::InvalidArgument(
+                "no portfolio has an expected return above the risk-free rate".to_string(),
+            ));
+        }
+
+        // Start from the minimum variance portfolio if it earns more than
+        // the risk-free rate, otherwise from a portfolio half-way up the
+        // attainable returns.
+        let min_variance = self.min_variance()?;
+        let start = if min_variance.expected_return > self.risk_free_rate {
+            min_variance
+        } else {
+            let upper = if max_return.is_finite() {
+                max_return
+            } else {
+                self.risk_free_rate.max(min_variance.expected_return) + 1.0
+            };
+            self.target_return(0.5 * (self.risk_free_rate + upper))?
+        };
+
+        let result = self.optimizer().optimize(
+            sharpe_objective(
+                &self.covariance,
+                &self.expected_returns,
+                self.risk_free_rate,
+            ),
+            &start.weights,
+        )?;
+
+        Ok(self.allocation(&result.minimizer))
+    }
+
+    /// Efficient frontier with `n_points` portfolios, with expected returns
+    /// evenly spaced from the minimum variance portfolio up to the maximum
+    /// attainable return (or the highest asset return if short sales are
+    /// unbounded).
+    ///
+    /// # Errors
+    ///
+    /// If `n_points < 2`, the bounds are invalid or infeasible, or the
+    /// optimizer fails.
+    pub fn efficient_frontier(&self, n_points: usize) -> Result<EfficientFrontier, RustQuantError> {
+        if n_points < 2 {
+            return Err(RustQuantError::InvalidArgument(
+                "the frontier needs at least two points".to_string(),
+            ));
+        }
+
+        let (_, max_return) = self.return_range()?;
+        let min_variance = self.min_variance()?;
+
+        let start = min_variance.expected_return;
+        let end = if max_return.is_finite() {
+            max_return
+        } else {
+            self.expected_returns
+                .iter()
+                .fold(start, |max, &mu| max.max(mu))
+        };
+
+        let points = (0..n_points)
+            .map(|i| {
+                let target = start + (end - start) * i as f64 / (n_points - 1) as f64;
+
+                if i == 0 {
+                    Ok(min_variance.clone())
+                } else {
+                    self.target_return(target)
+                }
+            })
+            .collect::<Result<Vec<Allocation>, RustQuantError>>()?;
+
+        Ok(EfficientFrontier {
+            points,
+            tangency: self.max_sharpe()?,
+            min_variance,
+        })
+    }
+
+    /// Portfolio variance $w^\top \Sigma w$.
+    fn variance(&self, weights: &[f64]) -> f64 {
+        let n = weights.len();
+
+        (0..n)
+            .map(|i| {
+                weights[i]
+                    * (0..n)
+                        .map(|j| self.covariance[(i, j)] * weights[j])
+                        .sum::<f64>()
+            })
+            .sum()
+    }
+
+    /// Optimizer with the budget constraint and the bounds.
+    fn optimizer(&self) -> ConstrainedOptimizer {
+        let n = self.expected_returns.len();
+
+        ConstrainedOptimizer::new(MAX_ITERATIONS, TOLERANCE)
+            .with_equality(&vec![1.0; n], 1.0)
+            .with_bounds(&self.lower, &self.upper)
+    }
+
+    /// Equal weights.
+    fn initial_weights(&self) -> Vec<f64> {
+        let n = self.expected_returns.len();
+
+        vec![1.0 / n as f64; n]
+    }
+
+    /// Lower and upper bounds, expanded to one per asset and checked for
+    /// feasibility.
+    fn bounds(&self) -> Result<(Vec<f64>, Vec<f64>), RustQuantError> {
+        let n = self.expected_returns.len();
+
+        let expand = |bounds: &[f64], default: f64| match bounds.len() {
+            0 => Ok(vec![default; n]),
+            len if len == n => Ok(bounds.to_vec()),
+            len => Err(RustQuantError::InvalidArgument(format!(
+                "{len} bounds for {n} assets"
+            ))),
+        };
+
+        let lower = expand(&self.lower, f64::NEG_INFINITY)?;
+        let upper = expand(&self.upper, f64::INFINITY)?;
+
+        if lower
+            .iter()
+            .zip(&upper)
+            .any(|(l, u)| l > u || l.is_nan() || u.is_nan())
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "lower bounds must not exceed upper bounds".to_string(),
+            ));
+        }
+
+        if lower.iter().sum::<f64>() > 1.0 || upper.iter().sum::<f64>() < 1.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "the bounds do not allow fully invested portfolios".to_string(),
+            ));
+        }
+
+        Ok((lower, upper))
+    }
+
+    /// Lowest and highest attainable expected returns.
+    ///
+    /// With finite lower bounds, the extremes are found greedily by filling
+    /// the assets in order of expected return. Otherwise the range is
+    /// taken to be unbounded.
+    fn return_range(&self) -> Result<(f64, f64), RustQuantError> {
+        let (lower, upper) = self.bounds()?;
+
+        if lower.iter().any(|l| l.is_infinite()) {
+            return Ok((f64::NEG_INFINITY, f64::INFINITY));
+        }
+
+        let mut order: Vec<usize> = (0..lower.len()).collect();
+        order.sort_by(|&i, &j| self.expected_returns[i].total_cmp(&self.expected_returns[j]));
+
+        let extreme = |order: &mut dyn Iterator<Item = &usize>| {
+            let mut weights = lower.clone();
+            let mut remaining = 1.0 - lower.iter().sum::<f64>();
+
+            for &i in order {
+                let w = remaining.min(upper[i] - lower[i]);
+                weights[i] += w;
+                remaining -= w;
+            }
+
+            dot(&self.expected_returns, &weights)
+        };
+
+        Ok((extreme(&mut order.iter()), extreme(&mut order.iter().rev())))
+    }
+}
+
+/// Objective $\frac{1}{2} w^\top \Sigma w$.
+fn variance_objective(
+    covariance: &DMatrix<f64>,
+) -> impl for<'v> Fn(&[Variable<'v>]) -> Variable<'v> + '_ {
+    move |w| 0.5 * quadratic_form(covariance, w)
+}
+
+/// Objective $-(\mu^\top w - r_f) / \sqrt{w^\top \Sigma w}$ (negative Sharpe ratio).
+fn sharpe_objective<'a>(
+    covariance: &'a DMatrix<f64>,
+    expected_returns: &'a [f64],
+    risk_free_rate: f64,
+) -> impl for<'v> Fn(&[Variable<'v>]) -> Variable<'v> + 'a {
+    move |w| {
+        let excess = w
+            .iter()
+            .zip(expected_returns)
+            .map(|(w, mu)| *w * *mu)
+            .sum::<Variable>()
+            - risk_free_rate;
+
+        -1.0 * excess / quadratic_form(covariance, w).sqrt()
+    }
+}
+
+/// $w^\top \Sigma w$.
+fn quadratic_form<'v>(covariance: &DMatrix<f64>, w: &[Variable<'v>]) -> Variable<'v> {
+    let n = w.len();
+
+    (0..n)
+        .map(|i| w[i] * (0..n).map(|j| w[j] * covariance[(i, j)]).sum::<Variable>())
+        .sum()
+}
+
+/// Dot product of two vectors.
+fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(x, y)| x * y).sum()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_markowitz {
+    use super::*;
+    use nalgebra::DVector;
+
+    const EPS: f64 = 1e-6;
+
+    const MU: [f64; 4] = [0.05, 0.08, 0.12, 0.03];
+
+    fn covariance() -> DMatrix<f64> {
+        let vols = DMatrix::from_diagonal(&DVector::from_vec(vec![0.10, 0.15, 0.25, 0.08]));
+        let correlation = DMatrix::from_row_slice(
+            4,
+            4,
+            &[
+                1.0, 0.3, 0.2, 0.8, //
+                0.3, 1.0, 0.5, 0.2, //
+                0.2, 0.5, 1.0, 0.1, //
+                0.8, 0.2, 0.1, 1.0,
+            ],
+        );
+
+        &vols * correlation * &vols
+    }
+
+    fn model() -> MeanVariance {
+        MeanVariance::new(&MU, covariance())
+            .unwrap()
+            .with_risk_free_rate(0.02)
+    }
+
+    /// Closed-form weights proportional to $\Sigma^{-1} v$.
+    fn closed_form(v: &[f64]) -> Vec<f64> {
+        let x = covariance().try_inverse().unwrap() * DVector::from_column_slice(v);
+        let sum = x.sum();
+
+        x.iter().map(|x| x / sum).collect()
+    }
+
+    #[test]
+    fn test_min_variance_unconstrained() {
+        let allocation = model().min_variance().unwrap();
+        let expected = closed_form(&[1.0; 4]);
+
+        for (w, e) in allocation.weights.iter().zip(&expected) {
+            assert_approx_equal!(*w, *e, EPS);
+        }
+    }
+
+    #[test]
+    fn test_max_sharpe_unconstrained() {
+        let excess: Vec<f64> = MU.iter().map(|mu| mu - 0.02).collect();
+        let allocation = model().max_sharpe().unwrap();
+        let expected = closed_form(&excess);
+
+        for (w, e) in allocation.weights.iter().zip(&expected) {
+            assert_approx_equal!(*w, *e, 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_target_return_unconstrained() {
+        // Frontier variance: (A r^2 - 2 B r + C) / (A C - B^2).
+        let inverse = covariance().try_inverse().unwrap();
+        let ones = DVector::from_element(4, 1.0);
+        let mu = DVector::from_column_slice(&MU);
+        let a = ones.dot(&(&inverse * &ones));
+        let b = ones.dot(&(&inverse * &mu));
+        let c = mu.dot(&(&inverse * &mu));
+
+        for target in [0.06, 0.1, 0.15] {
+            let allocation = model().target_return(target).unwrap();
+            let variance = (a * target * target - 2.0 * b * target + c) / (a * c - b * b);
+
+            assert_approx_equal!(allocation.expected_return, target, EPS);
+            assert_approx_equal!(allocation.volatility, variance.sqrt(), EPS);
+        }
+    }
+
+    #[test]
+    fn test_long_only() {
+        let model = model().long_only();
+
+        // The unconstrained minimum variance portfolio shorts the first asset.
+        assert!(closed_form(&[1.0; 4])[0] < 0.0);
+
+        let allocation = model.min_variance().unwrap();
+        assert_approx_equal!(allocation.weights.iter().sum::<f64>(), 1.0, EPS);
+        assert!(allocation.weights.iter().all(|w| *w >= 0.0));
+        assert_approx_equal!(allocation.weights[0], 0.0, EPS);
+
+        // KKT: marginal variances are equal for held assets, and no lower
+        // for assets at the bound.
+        let marginal = covariance() * DVector::from_column_slice(&allocation.weights);
+        let held: Vec<f64> = (0..4)
+            .filter(|&i| allocation.weights[i] > 1e-6)
+            .map(|i| marginal[i])
+            .collect();
+        for m in &held {
+            assert_approx_equal!(*m, held[0], EPS);
+        }
+        assert!(marginal[0] >= held[0] - EPS);
+
+        let frontier = model.efficient_frontier(6).unwrap();
+        assert_approx_equal!(frontier.points[5].expected_return, 0.12, EPS);
+        assert_approx_equal!(frontier.points[5].weights[2], 1.0, EPS);
+
+        for pair in frontier.points.windows(2) {
+            assert!(pair[1].expected_return > pair[0].expected_return);
+            assert!(pair[1].volatility > pair[0].volatility);
+        }
+        for point in &frontier.points {
+            assert!(frontier.tangency.sharpe_ratio >= point.sharpe_ratio - EPS);
+            assert!(point.weights.iter().all(|w| *w >= -EPS));
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        let allocation = model()
+            .with_bounds(&[0.1; 4], &[0.4; 4])
+            .max_sharpe()
+            .unwrap();
+
+        assert_approx_equal!(allocation.weights.iter().sum::<f64>(), 1.0, EPS);
+        assert!(allocation
+            .weights
+            .iter()
+            .all(|w| (0.1 - EPS..=0.4 + EPS).contains(w)));
+    }
+
+    #[test]
+    fn test_from_returns() {
+        let returns =
+            DMatrix::from_row_slice(4, 2, &[0.01, 0.02, -0.01, 0.03, 0.02, -0.01, 0.00, 0.04]);
+        let model = MeanVariance::from_returns(&returns, CovarianceEstimator::LedoitWolf).unwrap();
+
+        assert_approx_equal!(model.expected_returns[0], 0.005, EPS);
+        assert_approx_equal!(model.expected_returns[1], 0.02, EPS);
+        assert!(model.min_variance().is_ok());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(MeanVariance::new(&MU, DMatrix::identity(3, 3)).is_err());
+        assert!(model().long_only().target_return(0.2).is_err());
+        assert!(model()
+            .with_bounds(&[0.3; 4], &[1.0; 4])
+            .min_variance()
+            .is_err());
+        assert!(model()
+            .with_risk_free_rate(0.2)
+            .long_only()
+            .max_sharpe()
+            .is_err());
+        assert!(model().efficient_frontier(1).is_err());
+    }
+}
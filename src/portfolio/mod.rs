@@ -0,0 +1,92 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Portfolio module.
+//!
+//! ### Positions and portfolios
+//!
+//! - [x] Mark-to-market value, cost and profit
+//! - [x] NPV against a pricing context
+//! - [x] Greeks bucketed by underlying and currency
+//! - [x] Netting
+//!
+//! ### Performance metrics
+//!
+//! - [x] Sharpe, Sortino and Calmar ratios
+//! - [x] Maximum drawdown
+//! - [x] Information ratio, beta and alpha against a benchmark
+//! - [x] Rolling metrics
+//!
+//! ### Covariance estimation
+//!
+//! - [x] Sample covariance
+//! - [x] Ledoit-Wolf shrinkage (constant-correlation target)
+//! - [x] Exponentially weighted covariance
+//! - [x] Factor models (given or statistical factors)
+//!
+//! ### Portfolio optimization
+//!
+//! - [x] Markowitz mean-variance (minimum variance, target return, maximum Sharpe)
+//! - [x] Risk parity and risk budgeting
+//! - [x] Black-Litterman
+//! - [x] Hierarchical risk parity
+//!
+//! ### Clustering
+//!
+//! - [x] Correlation distance
+//! - [x] Hierarchical clustering (single, complete, average and Ward linkage)
+//! - [x] K-means
+//!
+//! ### Position sizing
+//!
+//! - [x] (Fractional) Kelly criterion
+//! - [x] Volatility targeting
+//! - [x] Fixed-risk sizing
+
+/// Positions and portfolios.
+#[cfg(feature = "curves")]
+pub mod position;
+#[cfg(feature = "curves")]
+pub use position::*;
+
+/// Covariance matrix estimation.
+pub mod covariance;
+pub use covariance::*;
+
+/// Factor risk models.
+pub mod factor_model;
+pub use factor_model::*;
+
+/// Markowitz mean-variance optimization.
+#[cfg(feature = "autodiff")]
+pub mod markowitz;
+#[cfg(feature = "autodiff")]
+pub use markowitz::*;
+
+/// Risk parity and risk budgeting.
+#[cfg(feature = "autodiff")]
+pub mod risk_parity;
+#[cfg(feature = "autodiff")]
+pub use risk_parity::*;
+
+/// Black-Litterman expected returns.
+pub mod black_litterman;
+pub use black_litterman::*;
+
+/// Asset clustering and hierarchical risk parity.
+pub mod clustering;
+pub use clustering::*;
+
+/// Performance and risk metrics of return series.
+pub mod performance;
+pub use performance::*;
+
+/// Position sizing: Kelly criterion, volatility targeting and fixed risk.
+pub mod sizing;
+pub use sizing::*;
@@ -0,0 +1,621 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Performance and risk metrics on a series of (simple) periodic returns.
+//!
+//! - Annualised return (geometric) and volatility.
+//! - Sharpe ratio: mean excess return over its standard deviation.
+//! - Sortino ratio: mean excess return over the downside deviation.
+//! - Maximum drawdown and drawdown series.
+//! - Calmar ratio: annualised return over maximum drawdown.
+//! - Against a benchmark: information ratio, beta, and (Jensen's) alpha.
+//!
+//! Ratios are annualised with the number of periods per year (252 for daily
+//! returns by default). The risk-free rate is an annual rate, converted to a
+//! per-period rate by dividing by the number of periods per year.
+//! Every metric also has a rolling version.
+//!
+//! ```
+//! use RustQuant::data::TimeSeries;
+//! use RustQuant::portfolio::{PerformanceMetric, PerformanceMetrics};
+//! use time::{macros::date, Duration};
+//!
+//! let start = date!(2024 - 01 - 01);
+//! let returns = TimeSeries::from_pairs(
+//!     [0.01, -0.02, 0.015, 0.003, -0.007, 0.012]
+//!         .iter()
+//!         .enumerate()
+//!         .map(|(i, r)| (start + Duration::days(i as i64), *r)),
+//! )
+//! .unwrap();
+//!
+//! let metrics = PerformanceMetrics::new().with_risk_free_rate(0.02);
+//!
+//! let sharpe = metrics.sharpe_ratio(&returns).unwrap();
+//! let drawdown = metrics.max_drawdown(&returns).unwrap();
+//! let rolling = metrics.rolling(&returns, 3, PerformanceMetric::SharpeRatio).unwrap();
+//!
+//! assert!((drawdown - 0.02).abs() < 1e-12);
+//! assert_eq!(rolling.len(), 4);
+//! ```
+
+use crate::data::TimeSeries;
+use crate::error::RustQuantError;
+use crate::time::Frequency;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Performance metrics of a return series on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PerformanceMetric {
+    /// Annualised (geometric) return.
+    AnnualizedReturn,
+    /// Annualised volatility.
+    AnnualizedVolatility,
+    /// Sharpe ratio.
+    SharpeRatio,
+    /// Sortino ratio.
+    SortinoRatio,
+    /// Calmar ratio.
+    CalmarRatio,
+    /// Maximum drawdown.
+    MaxDrawdown,
+}
+
+/// Performance metrics of a return series relative to a benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelativeMetric {
+    /// Information ratio.
+    InformationRatio,
+    /// Beta.
+    Beta,
+    /// Jensen's alpha (annualised).
+    Alpha,
+}
+
+/// Summary of the performance of a return series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerformanceSummary {
+    /// Annualised (geometric) return.
+    pub annualized_return: f64,
+    /// Annualised volatility.
+    pub annualized_volatility: f64,
+    /// Sharpe ratio.
+    pub sharpe_ratio: f64,
+    /// Sortino ratio.
+    pub sortino_ratio: f64,
+    /// Calmar ratio.
+    pub calmar_ratio: f64,
+    /// Maximum drawdown.
+    pub max_drawdown: f64,
+}
+
+/// Performance metrics: an annualisation convention and a risk-free rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PerformanceMetrics {
+    /// Number of return periods per year, used to annualise.
+    pub periods_per_year: f64,
+    /// Annual risk-free rate.
+    pub risk_free_rate: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for PerformanceMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerformanceMetrics {
+    /// Metrics for daily returns (252 per year) and a zero risk-free rate.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            periods_per_year: 252.0,
+            risk_free_rate: 0.0,
+        }
+    }
+
+    /// Metrics for returns of the given frequency.
+    #[must_use]
+    pub fn with_frequency(mut self, frequency: Frequency) -> Self {
+        self.periods_per_year = frequency.times_in_year() as f64;
+        self
+    }
+
+    /// Set the (annual) risk-free rate.
+    #[must_use]
+    pub const fn with_risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+
+    /// Annualised (geometric) return.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two returns.
+    pub fn annualized_return(&self, returns: &TimeSeries<f64>) -> Result<f64, RustQuantError> {
+        self.metric(returns.values(), PerformanceMetric::AnnualizedReturn)
+    }
+
+    /// Annualised volatility (sample standard deviation).
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two returns.
+    pub fn annualized_volatility(&self, returns: &TimeSeries<f64>) -> Result<f64, RustQuantError> {
+        self.metric(returns.values(), PerformanceMetric::AnnualizedVolatility)
+    }
+
+    /// Annualised Sharpe ratio.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two returns.
+    pub fn sharpe_ratio(&self, returns: &TimeSeries<f64>) -> Result<f64, RustQuantError> {
+        self.metric(returns.values(), PerformanceMetric::SharpeRatio)
+    }
+
+    /// Annualised Sortino ratio.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two returns.
+    pub fn sortino_ratio(&self, returns: &TimeSeries<f64>) -> Result<f64, RustQuantError> {
+        self.metric(returns.values(), PerformanceMetric::SortinoRatio)
+    }
+
+    /// Calmar ratio.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two returns.
+    pub fn calmar_ratio(&self, returns: &TimeSeries<f64>) -> Result<f64, RustQuantError> {
+        self.metric(returns.values(), PerformanceMetric::CalmarRatio)
+    }
+
+    /// Maximum drawdown, as a positive fraction of the running peak.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two returns.
+    pub fn max_drawdown(&self, returns: &TimeSeries<f64>) -> Result<f64, RustQuantError> {
+        self.metric(returns.values(), PerformanceMetric::MaxDrawdown)
+    }
+
+    /// All the stand-alone metrics of a return series.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two returns.
+    pub fn summary(&self, returns: &TimeSeries<f64>) -> Result<PerformanceSummary, RustQuantError> {
+        let metric = |metric| self.metric(returns.values(), metric);
+
+        Ok(PerformanceSummary {
+            annualized_return: metric(PerformanceMetric::AnnualizedReturn)?,
+            annualized_volatility: metric(PerformanceMetric::AnnualizedVolatility)?,
+            sharpe_ratio: metric(PerformanceMetric::SharpeRatio)?,
+            sortino_ratio: metric(PerformanceMetric::SortinoRatio)?,
+            calmar_ratio: metric(PerformanceMetric::CalmarRatio)?,
+            max_drawdown: metric(PerformanceMetric::MaxDrawdown)?,
+        })
+    }
+
+    /// Drawdown from the running peak of cumulative wealth, at each date.
+    #[must_use]
+    pub fn drawdowns(&self, returns: &TimeSeries<f64>) -> TimeSeries<f64> {
+        TimeSeries::from((returns.dates().to_vec(), drawdowns(returns.values())))
+    }
+
+    /// Annualised information ratio against a benchmark: mean active return
+    /// over the tracking error. Only dates present in both series are used.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two common dates.
+    pub fn information_ratio(
+        &self,
+        returns: &TimeSeries<f64>,
+        benchmark: &TimeSeries<f64>,
+    ) -> Result<f64, RustQuantError> {
+        self.relative(returns, benchmark, RelativeMetric::InformationRatio)
+    }
+
+    /// Beta against a benchmark. Only dates present in both series are used.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two common dates.
+    pub fn beta(
+        &self,
+        returns: &TimeSeries<f64>,
+        benchmark: &TimeSeries<f64>,
+    ) -> Result<f64, RustQuantError> {
+        self.relative(returns, benchmark, RelativeMetric::Beta)
+    }
+
+    /// Annualised Jensen's alpha against a benchmark. Only dates present in
+    /// both series are used.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than two common dates.
+    pub fn alpha(
+        &self,
+        returns: &TimeSeries<f64>,
+        benchmark: &TimeSeries<f64>,
+    ) -> Result<f64, RustQuantError> {
+        self.relative(returns, benchmark, RelativeMetric::Alpha)
+    }
+
+    /// Rolling metric over windows of `window` returns.
+    ///
+    /// Each value is dated at the last return of its window.
+    ///
+    /// # Errors
+    ///
+    /// If the window is shorter than two returns.
+    pub fn rolling(
+        &self,
+        returns: &TimeSeries<f64>,
+        window: usize,
+        metric: PerformanceMetric,
+    ) -> Result<TimeSeries<f64>, RustQuantError> {
+        check_window(window)?;
+
+        let values = returns
+            .values()
+            .windows(window)
+            .map(|returns| self.metric(returns, metric))
+            .collect::<Result<Vec<f64>, RustQuantError>>()?;
+        let dates = returns.dates().iter().skip(window - 1).copied().collect();
+
+        Ok(TimeSeries::from((dates, values)))
+    }
+
+    /// Rolling metric against a benchmark over windows of `window` common
+    /// dates.
+    ///
+    /// # Errors
+    ///
+    /// If the window is shorter than two returns.
+    pub fn rolling_relative(
+        &self,
+        returns: &TimeSeries<f64>,
+        benchmark: &TimeSeries<f64>,
+        window: usize,
+        metric: RelativeMetric,
+    ) -> Result<TimeSeries<f64>, RustQuantError> {
+        check_window(window)?;
+
+        let joined = returns.inner_join(benchmark);
+        let (portfolio, benchmark): (Vec<f64>, Vec<f64>) = joined.values().iter().copied().unzip();
+
+        let values = portfolio
+            .windows(window)
+            .zip(benchmark.windows(window))
+            .map(|(portfolio, benchmark)| self.relative_metric(portfolio, benchmark, metric))
+            .collect::<Result<Vec<f64>, RustQuantError>>()?;
+        let dates = joined.dates().iter().skip(window - 1).copied().collect();
+
+        Ok(TimeSeries::from((dates, values)))
+    }
+
+    /// Per-period risk-free rate.
+    fn periodic_risk_free_rate(&self) -> f64 {
+        self.risk_free_rate / self.periods_per_year
+    }
+
+    /// Metric of a slice of returns.
+    fn metric(&self, returns: &[f64], metric: PerformanceMetric) -> Result<f64, RustQuantError> {
+        if returns.len() < 2 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need at least two returns, got {}",
+                returns.len()
+            )));
+        }
+
+        let n = returns.len() as f64;
+        let rf = self.periodic_risk_free_rate();
+        let excess: Vec<f64> = returns.iter().map(|r| r - rf).collect();
+
+        let annualized_return = || {
+            let growth = returns.iter().map(|r| 1.0 + r).product::<f64>();
+            growth.powf(self.periods_per_year / n) - 1.0
+        };
+        let max_drawdown = || drawdowns(returns).into_iter().fold(0.0, f64::max);
+
+        Ok(match metric {
+            PerformanceMetric::AnnualizedReturn => annualized_return(),
+            PerformanceMetric::AnnualizedVolatility => {
+                mean_and_std_dev(returns).1 * self.periods_per_year.sqrt()
+            }
+            PerformanceMetric::SharpeRatio => {
+                let (mean, std_dev) = mean_and_std_dev(&excess);
+                mean / std_dev * self.periods_per_year.sqrt()
+            }
+            PerformanceMetric::SortinoRatio => {
+                let downside = (excess.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+                mean_and_std_dev(&excess).0 / downside * self.periods_per_year.sqrt()
+            }
+            PerformanceMetric::CalmarRatio => annualized_return() / max_drawdown(),
+            PerformanceMetric::MaxDrawdown => max_drawdown(),
+        })
+    }
+
+    /// Relative metric of two time series, on their common dates.
+    fn relative(
+        &self,
+        returns: &TimeSeries<f64>,
+        benchmark: &TimeSeries<f64>,
+        metric: RelativeMetric,
+    ) -> Result<f64, RustQuantError> {
+        let (portfolio, benchmark): (Vec<f64>, Vec<f64>) = returns
+            .inner_join(benchmark)
+            .values()
+            .iter()
+            .copied()
+            .unzip();
+
+        self.relative_metric(&portfolio, &benchmark, metric)
+    }
+
+    /// Relative metric of two aligned slices of returns.
+    fn relative_metric(
+        &self,
+        returns: &[f64],
+        benchmark: &[f64],
+        metric: RelativeMetric,
+    ) -> Result<f64, RustQuantError> {
+        if returns.len() < 2 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need at least two common returns, got {}",
+                returns.len()
+            )));
+        }
+
+        let rf = self.periodic_risk_free_rate();
+        let n = returns.len() as f64;
+
+        let beta = || {
+            let (mean_p, _) = mean_and_std_dev(returns);
+            let (mean_b, std_b) = mean_and_std_dev(benchmark);
+            let covariance = returns
+                .iter()
+                .zip(benchmark)
+                .map(|(p, b)| (p - mean_p) * (b - mean_b))
+                .sum::<f64>()
+                / (n - 1.0);
+
+            covariance / (std_b * std_b)
+        };
+
+        Ok(match metric {
+            RelativeMetric::InformationRatio => {
+                let active: Vec<f64> = returns.iter().zip(benchmark).map(|(p, b)| p - b).collect();
+                let (mean, tracking_error) = mean_and_std_dev(&active);
+
+                mean / tracking_error * self.periods_per_year.sqrt()
+            }
+            RelativeMetric::Beta => beta(),
+            RelativeMetric::Alpha => {
+                let (mean_p, _) = mean_and_std_dev(returns);
+                let (mean_b, _) = mean_and_std_dev(benchmark);
+
+                ((mean_p - rf) - beta() * (mean_b - rf)) * self.periods_per_year
+            }
+        })
+    }
+}
+
+/// Drawdowns of cumulative wealth from its running peak (starting at 1).
+fn drawdowns(returns: &[f64]) -> Vec<f64> {
+    let mut wealth = 1.0;
+    let mut peak: f64 = 1.0;
+
+    returns
+        .iter()
+        .map(|r| {
+            wealth *= 1.0 + r;
+            peak = peak.max(wealth);
+            1.0 - wealth / peak
+        })
+        .collect()
+}
+
+/// Mean and sample standard deviation.
+fn mean_and_std_dev(x: &[f64]) -> (f64, f64) {
+    let n = x.len() as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    let variance = x.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    (mean, variance.sqrt())
+}
+
+/// Rolling windows need at least two returns.
+fn check_window(window: usize) -> Result<(), RustQuantError> {
+    if window < 2 {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "rolling window must be at least 2, got {window}"
+        )));
+    }
+
+    Ok(())
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_performance {
+    use super::*;
+    use time::{macros::date, Duration};
+
+    const EPS: f64 = 1e-12;
+
+    fn series(returns: &[f64]) -> TimeSeries<f64> {
+        let start = date!(2024 - 01 - 01);
+
+        TimeSeries::from_pairs(
+            returns
+                .iter()
+                .enumerate()
+                .map(|(i, r)| (start + Duration::days(i as i64), *r)),
+        )
+        .unwrap()
+    }
+
+    const RETURNS: [f64; 6] = [0.10, -0.05, 0.02, -0.10, 0.05, 0.03];
+
+    #[test]
+    fn test_return_and_volatility() {
+        let metrics = PerformanceMetrics::new().with_frequency(Frequency::Annually);
+        let returns = series(&RETURNS);
+
+        let growth: f64 = RETURNS.iter().map(|r| 1.0 + r).product();
+        assert_approx_equal!(
+            metrics.annualized_return(&returns).unwrap(),
+            growth.powf(1.0 / 6.0) - 1.0,
+            EPS
+        );
+
+        let mean = RETURNS.iter().sum::<f64>() / 6.0;
+        let variance = RETURNS.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 5.0;
+        assert_approx_equal!(
+            metrics.annualized_volatility(&returns).unwrap(),
+            variance.sqrt(),
+            EPS
+        );
+
+        // Semi-quarterly returns: eight a year.
+        let semi_quarterly = PerformanceMetrics::new().with_frequency(Frequency::SemiQuarterly);
+        assert_approx_equal!(
+            semi_quarterly.annualized_volatility(&returns).unwrap(),
+            (8.0 * variance).sqrt(),
+            EPS
+        );
+
+        // Sharpe with a 1% risk-free rate.
+        let metrics = metrics.with_risk_free_rate(0.01);
+        assert_approx_equal!(
+            metrics.sharpe_ratio(&returns).unwrap(),
+            (mean - 0.01) / variance.sqrt(),
+            EPS
+        );
+    }
+
+    #[test]
+    fn test_sortino() {
+        let metrics = PerformanceMetrics::new().with_frequency(Frequency::Annually);
+        let returns = series(&RETURNS);
+
+        let mean = RETURNS.iter().sum::<f64>() / 6.0;
+        let downside = ((0.05_f64.powi(2) + 0.10_f64.powi(2)) / 6.0).sqrt();
+
+        assert_approx_equal!(
+            metrics.sortino_ratio(&returns).unwrap(),
+            mean / downside,
+            EPS
+        );
+    }
+
+    #[test]
+    fn test_drawdowns() {
+        let metrics = PerformanceMetrics::new().with_frequency(Frequency::Annually);
+        let returns = series(&RETURNS);
+
+        // Peak 1.1, trough 1.1 * 0.95 * 1.02 * 0.9.
+        let max_drawdown = 1.0 - 0.95 * 1.02 * 0.9;
+        assert_approx_equal!(metrics.max_drawdown(&returns).unwrap(), max_drawdown, EPS);
+
+        let drawdowns = metrics.drawdowns(&returns);
+        assert_eq!(drawdowns.len(), 6);
+        assert_eq!(drawdowns.values()[0], 0.0);
+        assert_approx_equal!(drawdowns.values()[1], 0.05, EPS);
+
+        assert_approx_equal!(
+            metrics.calmar_ratio(&returns).unwrap(),
+            metrics.annualized_return(&returns).unwrap() / max_drawdown,
+            EPS
+        );
+
+        let summary = metrics.summary(&returns).unwrap();
+        assert_eq!(
+            summary.max_drawdown,
+            metrics.max_drawdown(&returns).unwrap()
+        );
+        assert_eq!(
+            summary.sortino_ratio,
+            metrics.sortino_ratio(&returns).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_relative_metrics() {
+        let metrics = PerformanceMetrics::new().with_frequency(Frequency::Annually);
+        let benchmark = series(&RETURNS);
+
+        // Portfolio = 1% + 1.5 x benchmark.
+        let portfolio = series(&RETURNS.map(|r| 0.01 + 1.5 * r));
+
+        assert_approx_equal!(metrics.beta(&portfolio, &benchmark).unwrap(), 1.5, EPS);
+        assert_approx_equal!(metrics.alpha(&portfolio, &benchmark).unwrap(), 0.01, EPS);
+
+        let active: Vec<f64> = RETURNS.iter().map(|r| 0.01 + 0.5 * r).collect();
+        let mean = active.iter().sum::<f64>() / 6.0;
+        let tracking_error = (active.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / 5.0).sqrt();
+        assert_approx_equal!(
+            metrics.information_ratio(&portfolio, &benchmark).unwrap(),
+            mean / tracking_error,
+            EPS
+        );
+
+        // Only common dates are used.
+        let shorter = series(&RETURNS[..4]);
+        assert_approx_equal!(metrics.beta(&portfolio, &shorter).unwrap(), 1.5, EPS);
+    }
+
+    #[test]
+    fn test_rolling() {
+        let metrics = PerformanceMetrics::new();
+        let returns = series(&RETURNS);
+
+        let rolling = metrics
+            .rolling(&returns, 3, PerformanceMetric::MaxDrawdown)
+            .unwrap();
+        assert_eq!(rolling.len(), 4);
+        assert_eq!(rolling.dates()[0], date!(2024 - 01 - 03));
+        assert_approx_equal!(
+            rolling.values()[0],
+            metrics.max_drawdown(&series(&RETURNS[..3])).unwrap(),
+            EPS
+        );
+
+        let benchmark = series(&RETURNS.map(|r| 0.5 * r));
+        let rolling_beta = metrics
+            .rolling_relative(&returns, &benchmark, 4, RelativeMetric::Beta)
+            .unwrap();
+        assert_eq!(rolling_beta.len(), 3);
+        for beta in rolling_beta.values() {
+            assert_approx_equal!(*beta, 2.0, EPS);
+        }
+
+        assert!(metrics
+            .rolling(&returns, 1, PerformanceMetric::SharpeRatio)
+            .is_err());
+        assert!(metrics.sharpe_ratio(&series(&[0.01])).is_err());
+    }
+}
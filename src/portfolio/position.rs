@@ -0,0 +1,578 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! A portfolio is a collection of [`Position`]s, which are simply a combination
+//! of an [`Instrument`], a quantity, a purchase price, and a current price.
+//! You may also specify the [`Currency`] and the underlying of the instrument.
+//!
+//! Quantities are signed: short positions have a negative quantity.
+//! Besides the mark-to-market value (from the current prices), a portfolio
+//! can be valued against a [`PricingContext`], its Greeks can be aggregated
+//! in total or bucketed by underlying or currency, and positions in the same
+//! instrument can be netted.
+//!
+//! # Example
+//!
+//! ```
+//! # use RustQuant::portfolio::{Portfolio, Position};
+//! # use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+//! # use RustQuant::iso::USD;
+//! # use time::{Duration, OffsetDateTime};
+//! # use std::collections::HashMap;
+//! # use RustQuant::assert_approx_equal;
+//! # use RustQuant::time::today;
+//!
+//! // Create a position of 100 call options.
+//! let position_1 = Position {
+//!    instrument: BlackScholesMerton::new(
+//!         0.08,
+//!         60.0,
+//!         65.0,
+//!         0.3,
+//!         0.08,
+//!         None,
+//!         today() + Duration::days(91),
+//!         TypeFlag::Call,
+//!     ),
+//!     quantity: 100,
+//!     purchase_price: 2.1045,
+//!     current_price: 3.5,
+//!     currency: Some(USD),
+//!     underlying: Some("ABC".to_string()),
+//! };
+//!
+//! // Create a position of 100 put options.
+//! let position_2 = Position {
+//!    instrument: BlackScholesMerton::new(
+//!         0.1 - 0.05,
+//!         100.0,
+//!         95.0,
+//!         0.2,
+//!         0.1,
+//!         None,
+//!         today() + Duration::days(182),
+//!         TypeFlag::Put,
+//!     ),
+//!     quantity: 100,
+//!     purchase_price: 2.4524,
+//!     current_price: 2.0,
+//!     currency: Some(USD),
+//!     underlying: Some("XYZ".to_string()),
+//! };
+//!
+//! let positions = HashMap::from([
+//!     ("Call Options".to_string(), position_1),
+//!     ("Put Options".to_string(), position_2),
+//! ]);
+//!
+//! // Create a portfolio.
+//! let portfolio = Portfolio::new(positions);
+//!     
+//! // Check the value of the portfolio.
+//! assert_approx_equal!(portfolio.value(), 100.0 * 3.5 + 100.0 * 2.0, 1e-10);
+//!     
+//! // Check the profit of the portfolio.
+//! assert_approx_equal!(portfolio.profit(), 550.0 - portfolio.cost(), 1e-10);
+//!
+//! // Delta of the options on each underlying.
+//! let deltas = portfolio.greeks_by_underlying();
+//! assert!(deltas["ABC"].delta > 0.0);
+//! assert!(deltas["XYZ"].delta < 0.0);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::{Greeks, Instrument, PricingContext, Sensitivities};
+use std::collections::HashMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Portfolio type. Simply a collection of positions.
+#[derive(Debug, Clone)]
+pub struct Portfolio<I: Instrument> {
+    /// HashMap of positions.
+    pub positions: HashMap<String, Position<I>>,
+}
+
+/// Position type.
+#[derive(Debug, Clone)]
+pub struct Position<I: Instrument> {
+    /// Instrument.
+    pub instrument: I,
+
+    /// Quantity (negative for short positions).
+    pub quantity: i64,
+
+    /// Purchase price of the instrument (per unit).
+    pub purchase_price: f64,
+
+    /// Current price of the instrument (per unit).
+    pub current_price: f64,
+
+    /// Currency of the instrument.
+    pub currency: Option<Currency>,
+
+    /// Name of the underlying (e.g. a ticker), used to bucket Greeks.
+    pub underlying: Option<String>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<I> Position<I>
+where
+    I: Instrument,
+{
+    /// Create a new position.
+    pub fn new(
+        instrument: I,
+        quantity: i64,
+        purchase_price: f64,
+        current_price: f64,
+        currency: Option<Currency>,
+    ) -> Self {
+        Self {
+            instrument,
+            quantity,
+            purchase_price,
+            current_price,
+            currency,
+            underlying: None,
+        }
+    }
+
+    /// Set the underlying of the position.
+    #[must_use]
+    pub fn with_underlying(mut self, underlying: &str) -> Self {
+        self.underlying = Some(underlying.to_string());
+        self
+    }
+
+    /// Currency of the position: the position's own currency if set,
+    /// otherwise the instrument's.
+    pub fn currency(&self) -> Option<Currency> {
+        self.currency.or_else(|| self.instrument.currency())
+    }
+
+    /// Net present value of the position, priced against the context and
+    /// converted to its reporting currency.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot be converted to the reporting currency.
+    pub fn npv(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        Ok(self.quantity as f64 * ctx.value(&self.instrument)?)
+    }
+
+    /// Returns the value of the position.
+    pub fn value(&self) -> f64 {
+        self.quantity as f64 * self.current_price
+    }
+
+    /// Returns the profit (or loss) of the position.
+    pub fn profit(&self) -> f64 {
+        self.value() - self.quantity as f64 * self.purchase_price
+    }
+
+    /// Update the price of the position.
+    pub fn update_price(&mut self, new_price: f64) {
+        self.current_price = new_price;
+    }
+
+    /// Update the quantity of the position.
+    pub fn update_quantity(&mut self, new_quantity: i64) {
+        self.quantity = new_quantity;
+    }
+}
+
+impl<I> Position<I>
+where
+    I: Instrument + Sensitivities,
+{
+    /// Greeks of the position (the instrument's Greeks times the quantity).
+    pub fn greeks(&self) -> Greeks {
+        self.instrument.greeks() * self.quantity as f64
+    }
+}
+
+impl<I> Portfolio<I>
+where
+    I: Instrument,
+{
+    /// Create a new portfolio.
+    #[must_use]
+    pub const fn new(positions: HashMap<String, Position<I>>) -> Self {
+        Self { positions }
+    }
+
+    /// Returns the value of the portfolio.
+    #[must_use]
+    pub fn value(&self) -> f64 {
+        self.positions.values().map(Position::value).sum()
+    }
+
+    /// Returns the cost of the portfolio.
+    #[must_use]
+    pub fn cost(&self) -> f64 {
+        self.positions
+            .values()
+            .map(|position| position.quantity as f64 * position.purchase_price)
+            .sum()
+    }
+
+    /// Returns the profit (or loss) of the portfolio.
+    #[must_use]
+    pub fn profit(&self) -> f64 {
+        self.positions.values().map(Position::profit).sum()
+    }
+
+    /// Update the price of a position in the portfolio.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instrument_name` not found in the portfolio
+    pub fn update_price(&mut self, instrument_name: &str, new_price: f64) {
+        self.positions
+            .get_mut(instrument_name)
+            .unwrap()
+            .update_price(new_price);
+    }
+
+    /// Update the quantity of a position in the portfolio.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `instrument_name` not found in the portfolio
+    pub fn update_quantity(&mut self, instrument_name: &str, new_quantity: i64) {
+        self.positions
+            .get_mut(instrument_name)
+            .unwrap()
+            .update_quantity(new_quantity);
+    }
+
+    /// Returns the current weights of all positions
+    pub fn position_weights(&self) -> HashMap<String, f32> {
+        let current_value = self.value();
+
+        self.positions
+            .iter()
+            .map(|(instrument_name, position)| {
+                (
+                    instrument_name.to_string(),
+                    position.value() as f32 / current_value as f32,
+                )
+            })
+            .collect()
+    }
+
+    /// Net present value of the portfolio, with every position priced
+    /// against the context and converted to its reporting currency.
+    ///
+    /// # Errors
+    ///
+    /// If a position cannot be converted to the reporting currency.
+    pub fn npv(&self, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        self.positions
+            .values()
+            .map(|position| position.npv(ctx))
+            .sum()
+    }
+
+    /// Net the positions held in the same instrument.
+    ///
+    /// Each group of positions with equal instruments is replaced by a single
+    /// position, under the (alphabetically) first name of the group, holding
+    /// the net quantity. Its purchase price is the net cost divided by the
+    /// net quantity. Positions that net to zero are removed.
+    #[must_use]
+    pub fn netted(&self) -> Self
+    where
+        I: PartialEq + Clone,
+    {
+        let mut names: Vec<&String> = self.positions.keys().collect();
+        names.sort();
+
+        let mut netted: Vec<(String, Position<I>, f64)> = Vec::new();
+
+        for name in names {
+            let position = &self.positions[name];
+            let cost = position.quantity as f64 * position.purchase_price;
+
+            match netted
+                .iter_mut()
+                .find(|(_, net, _)| net.instrument == position.instrument)
+            {
+                Some((_, net, net_cost)) => {
+                    net.quantity += position.quantity;
+                    *net_cost += cost;
+                }
+                None => netted.push((name.clone(), position.clone(), cost)),
+            }
+        }
+
+        Self::new(
+            netted
+                .into_iter()
+                .filter(|(_, net, _)| net.quantity != 0)
+                .map(|(name, mut net, cost)| {
+                    net.purchase_price = cost / net.quantity as f64;
+                    (name, net)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<I> Portfolio<I>
+where
+    I: Instrument + Sensitivities,
+{
+    /// Aggregate Greeks of the portfolio.
+    ///
+    /// Note that Greeks on different underlyings are simply added, so the
+    /// totals are mostly useful for books on a single underlying.
+    pub fn greeks(&self) -> Greeks {
+        self.positions.values().map(Position::greeks).sum()
+    }
+
+    /// Greeks bucketed by underlying.
+    ///
+    /// Positions without an underlying are bucketed under their own name.
+    pub fn greeks_by_underlying(&self) -> HashMap<String, Greeks> {
+        let mut buckets: HashMap<String, Greeks> = HashMap::new();
+
+        for (name, position) in &self.positions {
+            let underlying = position.underlying.as_ref().unwrap_or(name);
+
+            *buckets.entry(underlying.clone()).or_default() += position.greeks();
+        }
+
+        buckets
+    }
+
+    /// Greeks bucketed by currency (ISO 4217 alphabetic code).
+    ///
+    /// Positions without a known currency are bucketed under `None`.
+    pub fn greeks_by_currency(&self) -> HashMap<Option<&'static str>, Greeks> {
+        let mut buckets: HashMap<Option<&'static str>, Greeks> = HashMap::new();
+
+        for position in self.positions.values() {
+            let code = position.currency().map(|currency| currency.code.alphabetic);
+
+            *buckets.entry(code).or_default() += position.greeks();
+        }
+
+        buckets
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(all(test, feature = "options"))]
+mod tests_portfolio {
+    use super::*;
+    use crate::{
+        instruments::options::{BlackScholesMerton, TypeFlag},
+        iso::{EUR, USD},
+        time::today,
+    };
+    use time::macros::date;
+    use time::Duration;
+
+    fn setup_test_portfolio() -> Portfolio<BlackScholesMerton> {
+        // Create a position of 100 call options.
+        let position_1 = Position {
+            instrument: BlackScholesMerton::new(
+                0.08,
+                60.0,
+                65.0,
+                0.3,
+                0.08,
+                None,
+                today() + Duration::days(91),
+                TypeFlag::Call,
+            ),
+            quantity: 100,
+            purchase_price: 2.1045,
+            current_price: 3.5,
+            currency: Some(USD),
+            underlying: Some("ABC".to_string()),
+        };
+
+        // Create a position of 100 put options.
+        let position_2 = Position {
+            instrument: BlackScholesMerton::new(
+                0.1 - 0.05,
+                100.0,
+                95.0,
+                0.2,
+                0.1,
+                None,
+                today() + Duration::days(182),
+                TypeFlag::Put,
+            ),
+            quantity: 100,
+            purchase_price: 2.4524,
+            current_price: 2.0,
+            currency: Some(USD),
+            underlying: Some("XYZ".to_string()),
+        };
+
+        let positions = HashMap::from([
+            ("Call Options".to_string(), position_1),
+            ("Put Options".to_string(), position_2),
+        ]);
+
+        // Create a portfolio.
+        Portfolio::new(positions)
+    }
+
+    #[test]
+    fn test_portfolio() {
+        // Create a portfolio.
+        let portfolio = setup_test_portfolio();
+
+        // Check the value of the portfolio.
+        assert_approx_equal!(portfolio.value(), 100.0 * 3.5 + 100.0 * 2.0, 1e-10);
+
+        // Check the profit of the portfolio.
+        assert_approx_equal!(portfolio.profit(), 550.0 - portfolio.cost(), 1e-10);
+    }
+
+    #[test]
+    fn test_portfolio_weights() {
+        // Create a portfolio.
+        let portfolio = setup_test_portfolio();
+
+        // Grab out the weights
+        let weights = portfolio.position_weights();
+
+        // Assert the weights are correct
+        assert_eq!(weights.get("Put Options"), Some(&0.36363637));
+        assert_eq!(weights.get("Call Options"), Some(&0.6363636));
+    }
+
+    fn call(spot: f64) -> BlackScholesMerton {
+        BlackScholesMerton::new(
+            0.05,
+            spot,
+            100.0,
+            0.2,
+            0.05,
+            Some(date!(2024 - 01 - 01)),
+            date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        )
+    }
+
+    #[test]
+    fn test_portfolio_npv() {
+        let portfolio = Portfolio::new(HashMap::from([
+            (
+                "long".to_string(),
+                Position::new(call(100.0), 10, 10.0, 10.0, None),
+            ),
+            (
+                "short".to_string(),
+                Position::new(call(110.0), -4, 16.0, 16.0, None),
+            ),
+        ]));
+
+        let ctx = PricingContext::new(date!(2024 - 01 - 01));
+        let expected = 10.0 * call(100.0).price() - 4.0 * call(110.0).price();
+
+        assert_approx_equal!(portfolio.npv(&ctx).unwrap(), expected, 1e-10);
+    }
+
+    #[test]
+    fn test_portfolio_greeks() {
+        let portfolio = Portfolio::new(HashMap::from([
+            (
+                "A".to_string(),
+                Position::new(call(100.0), 10, 10.0, 10.0, Some(USD)).with_underlying("SPX"),
+            ),
+            (
+                "B".to_string(),
+                Position::new(call(110.0), -4, 16.0, 16.0, Some(USD)).with_underlying("SPX"),
+            ),
+            (
+                "C".to_string(),
+                Position::new(call(90.0), 3, 5.0, 5.0, Some(EUR)).with_underlying("SX5E"),
+            ),
+            (
+                "D".to_string(),
+                Position::new(call(95.0), 2, 7.0, 7.0, None),
+            ),
+        ]));
+
+        let spx = call(100.0).greeks() * 10.0 + call(110.0).greeks() * -4.0;
+        let sx5e = call(90.0).greeks() * 3.0;
+        let d = call(95.0).greeks() * 2.0;
+
+        let by_underlying = portfolio.greeks_by_underlying();
+        assert_eq!(by_underlying.len(), 3);
+        assert_approx_equal!(by_underlying["SPX"].delta, spx.delta, 1e-10);
+        assert_approx_equal!(by_underlying["SX5E"].vega, sx5e.vega, 1e-10);
+        assert_approx_equal!(by_underlying["D"].gamma, d.gamma, 1e-10);
+
+        let by_currency = portfolio.greeks_by_currency();
+        assert_eq!(by_currency.len(), 3);
+        assert_approx_equal!(by_currency[&Some("USD")].theta, spx.theta, 1e-10);
+        assert_approx_equal!(by_currency[&Some("EUR")].rho, sx5e.rho, 1e-10);
+        assert_approx_equal!(by_currency[&None].delta, d.delta, 1e-10);
+
+        assert_approx_equal!(
+            portfolio.greeks().delta,
+            spx.delta + sx5e.delta + d.delta,
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_portfolio_netting() {
+        let portfolio = Portfolio::new(HashMap::from([
+            (
+                "b".to_string(),
+                Position::new(call(100.0), 10, 10.0, 12.0, None),
+            ),
+            (
+                "a".to_string(),
+                Position::new(call(100.0), -4, 13.0, 12.0, None),
+            ),
+            (
+                "c".to_string(),
+                Position::new(call(110.0), 5, 6.0, 7.0, None),
+            ),
+            (
+                "d".to_string(),
+                Position::new(call(110.0), -5, 6.5, 7.0, None),
+            ),
+        ]));
+
+        let netted = portfolio.netted();
+
+        assert_eq!(netted.positions.len(), 1);
+        let net = &netted.positions["a"];
+        assert_eq!(net.quantity, 6);
+        assert_approx_equal!(net.purchase_price, (100.0 - 52.0) / 6.0, 1e-12);
+
+        // Netting preserves the value and the cost of the remaining positions.
+        assert_approx_equal!(netted.value(), 6.0 * 12.0, 1e-12);
+        assert_approx_equal!(netted.cost(), 48.0, 1e-12);
+    }
+}
@@ -0,0 +1,248 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Risk parity (risk budgeting) portfolios.
+//!
+//! The risk contribution of asset $i$ to the portfolio volatility
+//! $\sigma(w) = \sqrt{w^\top \Sigma w}$ is
+//!
+//! $$
+//! RC_i = \frac{w_i (\Sigma w)_i}{\sigma(w)}, \qquad \sum_i RC_i = \sigma(w)
+//! $$
+//!
+//! A risk budgeting portfolio has $RC_i / \sigma(w) = b_i$ for given budgets
+//! $b_i > 0$ summing to one; risk parity is the special case $b_i = 1/n$.
+//! The long-only solution is found from the convex problem (Spinu, 2013)
+//!
+//! $$
+//! \min_{y > 0} \frac{1}{2} y^\top \Sigma y - \sum_i b_i \ln y_i
+//! $$
+//!
+//! whose minimiser, normalised to sum to one, gives the weights.
+//!
+//! ```
+//! use RustQuant::portfolio::{risk_contributions, RiskParity};
+//! use nalgebra::DMatrix;
+//!
+//! let covariance = DMatrix::from_row_slice(3, 3, &[
+//!     0.010, 0.002, 0.001,
+//!     0.002, 0.040, 0.006,
+//!     0.001, 0.006, 0.090,
+//! ]);
+//!
+//! let weights = RiskParity::new(covariance.clone()).unwrap().weights().unwrap();
+//!
+//! for contribution in risk_contributions(&weights, &covariance) {
+//!     assert!((contribution - 1.0 / 3.0).abs() < 1e-6);
+//! }
+//! ```
+
+use crate::autodiff::variables::variable::Variable;
+use crate::error::RustQuantError;
+use crate::math::ConstrainedOptimizer;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Risk budgeting portfolio solver.
+#[derive(Debug, Clone)]
+pub struct RiskParity {
+    /// Covariance matrix of the asset returns.
+    pub covariance: DMatrix<f64>,
+
+    /// Risk budgets (fractions of the portfolio risk), summing to one.
+    pub budgets: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RiskParity {
+    /// New risk parity solver (equal risk budgets).
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix is empty or not square.
+    pub fn new(covariance: DMatrix<f64>) -> Result<Self, RustQuantError> {
+        let n = covariance.nrows();
+
+        if n == 0 || !covariance.is_square() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "covariance matrix is {:?}",
+                covariance.shape()
+            )));
+        }
+
+        Ok(Self {
+            covariance,
+            budgets: vec![1.0 / n as f64; n],
+        })
+    }
+
+    /// Set the risk budgets. They are normalised to sum to one.
+    ///
+    /// # Errors
+    ///
+    /// If there is not one positive budget per asset.
+    pub fn with_budgets(mut self, budgets: &[f64]) -> Result<Self, RustQuantError> {
+        if budgets.len() != self.covariance.nrows()
+            || budgets.iter().any(|b| b.is_nan() || *b <= 0.0)
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "risk budgets must be positive, one per asset".to_string(),
+            ));
+        }
+
+        let total = budgets.iter().sum::<f64>();
+        self.budgets = budgets.iter().map(|b| b / total).collect();
+
+        Ok(self)
+    }
+
+    /// Long-only weights (summing to one) with the given risk budgets.
+    ///
+    /// # Errors
+    ///
+    /// If the optimizer fails (e.g. the covariance matrix is not positive
+    /// definite).
+    pub fn weights(&self) -> Result<Vec<f64>, RustQuantError> {
+        let n = self.budgets.len();
+
+        // Start from inverse-volatility weights.
+        let x0: Vec<f64> = (0..n)
+            .map(|i| 1.0 / self.covariance[(i, i)].sqrt())
+            .collect();
+
+        let result = ConstrainedOptimizer::new(100_000, 1e-12)
+            .with_bounds(&vec![1e-12; n], &vec![f64::INFINITY; n])
+            .optimize(spinu_objective(&self.covariance, &self.budgets), &x0)?;
+
+        let total = result.minimizer.iter().sum::<f64>();
+
+        if !total.is_finite() || total <= 0.0 {
+            return Err(RustQuantError::ComputationError(
+                "risk budgeting problem did not converge".to_string(),
+            ));
+        }
+
+        Ok(result.minimizer.iter().map(|y| y / total).collect())
+    }
+}
+
+/// Risk contributions of the assets, as fractions of the portfolio
+/// volatility (summing to one).
+#[must_use]
+pub fn risk_contributions(weights: &[f64], covariance: &DMatrix<f64>) -> Vec<f64> {
+    let w = DVector::from_column_slice(weights);
+    let marginal = covariance * &w;
+    let variance = w.dot(&marginal);
+
+    w.iter()
+        .zip(marginal.iter())
+        .map(|(w, m)| w * m / variance)
+        .collect()
+}
+
+/// Objective $\frac{1}{2} y^\top \Sigma y - \sum_i b_i \ln y_i$.
+fn spinu_objective<'a>(
+    covariance: &'a DMatrix<f64>,
+    budgets: &'a [f64],
+) -> impl for<'v> Fn(&[Variable<'v>]) -> Variable<'v> + 'a {
+    move |y| {
+        let n = y.len();
+
+        let quadratic: Variable = (0..n)
+            .map(|i| y[i] * (0..n).map(|j| y[j] * covariance[(i, j)]).sum::<Variable>())
+            .sum();
+        let barrier: Variable = y.iter().zip(budgets).map(|(y, b)| y.ln() * *b).sum();
+
+        0.5 * quadratic - barrier
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_risk_parity {
+    use super::*;
+
+    const EPS: f64 = 1e-6;
+
+    fn covariance() -> DMatrix<f64> {
+        let vols = DMatrix::from_diagonal(&DVector::from_vec(vec![0.10, 0.15, 0.25, 0.08]));
+        let correlation = DMatrix::from_row_slice(
+            4,
+            4,
+            &[
+                1.0, 0.3, 0.2, 0.8, //
+                0.3, 1.0, 0.5, 0.2, //
+                0.2, 0.5, 1.0, 0.1, //
+                0.8, 0.2, 0.1, 1.0,
+            ],
+        );
+
+        &vols * correlation * &vols
+    }
+
+    #[test]
+    fn test_uncorrelated_is_inverse_volatility() {
+        let vols = [0.1, 0.2, 0.4];
+        let covariance =
+            DMatrix::from_diagonal(&DVector::from_iterator(3, vols.iter().map(|v| v * v)));
+
+        let weights = RiskParity::new(covariance).unwrap().weights().unwrap();
+        let total = vols.iter().map(|v| 1.0 / v).sum::<f64>();
+
+        for (w, v) in weights.iter().zip(vols) {
+            assert_approx_equal!(*w, 1.0 / v / total, EPS);
+        }
+    }
+
+    #[test]
+    fn test_equal_risk_contributions() {
+        let weights = RiskParity::new(covariance()).unwrap().weights().unwrap();
+
+        assert_approx_equal!(weights.iter().sum::<f64>(), 1.0, EPS);
+        for contribution in risk_contributions(&weights, &covariance()) {
+            assert_approx_equal!(contribution, 0.25, EPS);
+        }
+    }
+
+    #[test]
+    fn test_risk_budgets() {
+        let budgets = [0.1, 0.2, 0.3, 0.4];
+        let weights = RiskParity::new(covariance())
+            .unwrap()
+            .with_budgets(&budgets)
+            .unwrap()
+            .weights()
+            .unwrap();
+
+        for (contribution, budget) in risk_contributions(&weights, &covariance())
+            .iter()
+            .zip(budgets)
+        {
+            assert_approx_equal!(*contribution, budget, EPS);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(RiskParity::new(DMatrix::zeros(2, 3)).is_err());
+        assert!(RiskParity::new(covariance())
+            .unwrap()
+            .with_budgets(&[0.5, 0.5, 0.0, 0.0])
+            .is_err());
+    }
+}
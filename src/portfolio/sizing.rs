@@ -0,0 +1,329 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Position sizing.
+//!
+//! - Kelly criterion: the fraction of capital that maximises the expected
+//!   log growth. For a bet won with probability $p$ paying $b$ per unit
+//!   staked, $f^* = p - (1 - p) / b$. For assets with expected excess
+//!   returns $\mu$ and covariance $\Sigma$, $w^* = \Sigma^{-1} \mu$.
+//!   Full Kelly is very aggressive, so a fraction of it is usually used.
+//! - Volatility targeting: leverage $\sigma_{target} / \hat\sigma$, capped.
+//! - Fixed-risk sizing: the quantity that loses a fixed fraction of equity
+//!   if the stop-loss is hit.
+//!
+//! [`VolatilityTargeting`] wraps a backtester
+//! [`Strategy`](crate::trading::backtest::Strategy) and scales its weights
+//! to a target volatility.
+//!
+//! ```
+//! use RustQuant::portfolio::*;
+//!
+//! // 55% chance to win 1:1, half Kelly.
+//! let f = kelly_fraction(0.55, 1.0, 0.5).unwrap();
+//! assert!((f - 0.05).abs() < 1e-12);
+//!
+//! // Risk 1% of 100,000 with a stop 5 below a 100 entry.
+//! let units = fixed_risk_quantity(100_000.0, 0.01, 100.0, 95.0).unwrap();
+//! assert!((units - 200.0).abs() < 1e-9);
+//! ```
+
+use crate::error::RustQuantError;
+use crate::trading::backtest::Strategy;
+use nalgebra::{DMatrix, DVector};
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Strategy adapter that scales the weights of another strategy to a
+/// target (annualised) volatility.
+///
+/// The volatility is estimated from the returns the inner strategy's current
+/// weights would have earned over the last `lookback` bars. Until there are
+/// enough bars, the weights are left unscaled.
+#[derive(Debug, Clone)]
+pub struct VolatilityTargeting<S: Strategy> {
+    /// Strategy whose weights are scaled.
+    pub strategy: S,
+    /// Target annualised volatility.
+    pub target: f64,
+    /// Number of returns used to estimate the volatility.
+    pub lookback: usize,
+    /// Number of bars per year, used to annualise.
+    pub periods_per_year: f64,
+    /// Maximum leverage.
+    pub max_leverage: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Fractional Kelly stake for a binary bet.
+///
+/// `win_loss_ratio` is the amount won per unit staked, and `fraction` the
+/// fraction of full Kelly to use (e.g. `0.5` for half Kelly). The stake is
+/// zero when the bet has no edge.
+///
+/// # Errors
+///
+/// If the probability is not in $[0, 1]$ or the ratio is not positive.
+pub fn kelly_fraction(
+    win_probability: f64,
+    win_loss_ratio: f64,
+    fraction: f64,
+) -> Result<f64, RustQuantError> {
+    if !(0.0..=1.0).contains(&win_probability) || win_loss_ratio.is_nan() || win_loss_ratio <= 0.0 {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "invalid bet: win probability {win_probability}, win/loss ratio {win_loss_ratio}"
+        )));
+    }
+
+    let kelly = win_probability - (1.0 - win_probability) / win_loss_ratio;
+
+    Ok(fraction * kelly.max(0.0))
+}
+
+/// Fractional Kelly weights $f \Sigma^{-1} \mu$ for assets with expected
+/// excess returns $\mu$ and covariance $\Sigma$ (over the same period).
+///
+/// # Errors
+///
+/// - If the dimensions do not match.
+/// - If the covariance matrix is singular.
+pub fn kelly_weights(
+    expected_excess_returns: &[f64],
+    covariance: &DMatrix<f64>,
+    fraction: f64,
+) -> Result<Vec<f64>, RustQuantError> {
+    let n = expected_excess_returns.len();
+
+    if covariance.shape() != (n, n) {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "covariance matrix is {:?}, expected ({n}, {n})",
+            covariance.shape()
+        )));
+    }
+
+    let inverse = covariance
+        .clone()
+        .try_inverse()
+        .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+    Ok(
+        (inverse * DVector::from_column_slice(expected_excess_returns) * fraction)
+            .iter()
+            .copied()
+            .collect(),
+    )
+}
+
+/// Leverage that scales a realised volatility to the target, capped at
+/// `max_leverage`.
+#[must_use]
+pub fn volatility_target_leverage(target: f64, realized: f64, max_leverage: f64) -> f64 {
+    if realized > 0.0 {
+        (target / realized).min(max_leverage)
+    } else {
+        max_leverage
+    }
+}
+
+/// Quantity such that hitting the stop-loss loses `risk_fraction` of equity.
+///
+/// Positive for longs (stop below entry), negative for shorts.
+///
+/// # Errors
+///
+/// If the stop equals the entry price.
+pub fn fixed_risk_quantity(
+    equity: f64,
+    risk_fraction: f64,
+    entry_price: f64,
+    stop_price: f64,
+) -> Result<f64, RustQuantError> {
+    let risk_per_unit = entry_price - stop_price;
+
+    if risk_per_unit == 0.0 || !risk_per_unit.is_finite() {
+        return Err(RustQuantError::InvalidArgument(
+            "stop price must differ from the entry price".to_string(),
+        ));
+    }
+
+    Ok(equity * risk_fraction / risk_per_unit)
+}
+
+impl<S: Strategy> VolatilityTargeting<S> {
+    /// Scale a strategy to a target volatility, with daily bars (252 per
+    /// year) and no leverage cap.
+    #[must_use]
+    pub const fn new(strategy: S, target: f64, lookback: usize) -> Self {
+        Self {
+            strategy,
+            target,
+            lookback,
+            periods_per_year: 252.0,
+            max_leverage: f64::INFINITY,
+        }
+    }
+
+    /// Set the number of bars per year.
+    #[must_use]
+    pub const fn with_periods_per_year(mut self, periods_per_year: f64) -> Self {
+        self.periods_per_year = periods_per_year;
+        self
+    }
+
+    /// Set the maximum leverage.
+    #[must_use]
+    pub const fn with_max_leverage(mut self, max_leverage: f64) -> Self {
+        self.max_leverage = max_leverage;
+        self
+    }
+}
+
+impl<S: Strategy> Strategy for VolatilityTargeting<S> {
+    fn target_weights(&mut self, date: Date, history: &[Vec<f64>]) -> Vec<f64> {
+        let weights = self.strategy.target_weights(date, history);
+
+        if self.lookback < 2 || history.len() <= self.lookback {
+            return weights;
+        }
+
+        let returns: Vec<f64> = history[history.len() - self.lookback - 1..]
+            .windows(2)
+            .map(|bars| {
+                weights
+                    .iter()
+                    .zip(bars[0].iter().zip(&bars[1]))
+                    .map(|(w, (p0, p1))| w * (p1 / p0 - 1.0))
+                    .sum()
+            })
+            .collect();
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let realized = (variance * self.periods_per_year).sqrt();
+
+        let leverage = volatility_target_leverage(self.target, realized, self.max_leverage);
+
+        weights.iter().map(|w| w * leverage).collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_sizing {
+    use super::*;
+    use crate::data::TimeSeries;
+    use crate::trading::backtest::Backtester;
+    use time::{macros::date, Duration};
+
+    const EPS: f64 = 1e-12;
+
+    #[test]
+    fn test_kelly_fraction() {
+        assert_approx_equal!(kelly_fraction(0.6, 1.0, 1.0).unwrap(), 0.2, EPS);
+        assert_approx_equal!(kelly_fraction(0.4, 2.0, 1.0).unwrap(), 0.1, EPS);
+        assert_approx_equal!(kelly_fraction(0.6, 1.0, 0.25).unwrap(), 0.05, EPS);
+
+        // No edge, no bet.
+        assert_eq!(kelly_fraction(0.4, 1.0, 1.0).unwrap(), 0.0);
+
+        assert!(kelly_fraction(1.1, 1.0, 1.0).is_err());
+        assert!(kelly_fraction(0.5, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_kelly_weights() {
+        // Uncorrelated assets: w_i = mu_i / sigma_i^2.
+        let covariance = DMatrix::from_diagonal(&DVector::from_vec(vec![0.04, 0.09]));
+        let weights = kelly_weights(&[0.02, 0.03], &covariance, 0.5).unwrap();
+
+        assert_approx_equal!(weights[0], 0.5 * 0.02 / 0.04, EPS);
+        assert_approx_equal!(weights[1], 0.5 * 0.03 / 0.09, EPS);
+
+        assert!(kelly_weights(&[0.02], &covariance, 1.0).is_err());
+        assert!(kelly_weights(&[0.02, 0.03], &DMatrix::zeros(2, 2), 1.0).is_err());
+    }
+
+    #[test]
+    fn test_volatility_target_and_fixed_risk() {
+        assert_approx_equal!(volatility_target_leverage(0.1, 0.2, 3.0), 0.5, EPS);
+        assert_approx_equal!(volatility_target_leverage(0.1, 0.02, 3.0), 3.0, EPS);
+
+        assert_approx_equal!(
+            fixed_risk_quantity(50_000.0, 0.02, 100.0, 90.0).unwrap(),
+            100.0,
+            EPS
+        );
+        assert_approx_equal!(
+            fixed_risk_quantity(50_000.0, 0.02, 100.0, 110.0).unwrap(),
+            -100.0,
+            EPS
+        );
+        assert!(fixed_risk_quantity(50_000.0, 0.02, 100.0, 100.0).is_err());
+    }
+
+    struct Long;
+
+    impl Strategy for Long {
+        fn target_weights(&mut self, _date: Date, _history: &[Vec<f64>]) -> Vec<f64> {
+            vec![1.0]
+        }
+    }
+
+    #[test]
+    fn test_volatility_targeting() {
+        // Alternating +1% / -1% moves: 1% daily volatility.
+        let history: Vec<Vec<f64>> = (0..21)
+            .map(|i| vec![if i % 2 == 0 { 100.0 } else { 101.0 }])
+            .collect();
+
+        let mut strategy = VolatilityTargeting::new(Long, 0.05, 10).with_periods_per_year(1.0);
+        let weights = strategy.target_weights(date!(2024 - 01 - 01), &history);
+
+        let returns: Vec<f64> = history[10..]
+            .windows(2)
+            .map(|b| b[1][0] / b[0][0] - 1.0)
+            .collect();
+        let mean = returns.iter().sum::<f64>() / 10.0;
+        let sd = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 9.0).sqrt();
+        assert_approx_equal!(weights[0], 0.05 / sd, EPS);
+
+        // Too little history: unscaled.
+        assert_eq!(
+            strategy.target_weights(date!(2024 - 01 - 01), &history[..5]),
+            vec![1.0]
+        );
+
+        // Runs in the backtester, capped at 2x leverage.
+        let start = date!(2024 - 01 - 01);
+        let prices = TimeSeries::from_pairs(
+            history
+                .iter()
+                .enumerate()
+                .map(|(i, bar)| (start + Duration::days(i as i64), bar.clone())),
+        )
+        .unwrap();
+
+        let mut capped = VolatilityTargeting::new(Long, 1.0, 10).with_max_leverage(2.0);
+        let result = Backtester::new(1_000.0).run(&prices, &mut capped).unwrap();
+        let last = result.holdings.values().last().unwrap()[0];
+        let price = prices.values().last().unwrap()[0];
+
+        assert_approx_equal!(last * price / result.final_equity(), 2.0, 0.05);
+    }
+}
@@ -0,0 +1,59 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! The RustQuant prelude.
+//!
+//! Glob-importing the prelude brings the error type, the core traits, and
+//! the most commonly used types into scope. Items from optional modules
+//! are only included when their cargo feature is enabled.
+//!
+//! ```
+//! use RustQuant::prelude::*;
+//! use time::macros::date;
+//!
+//! let option = BlackScholesMerton::new(
+//!     0.08,
+//!     60.0,
+//!     65.0,
+//!     0.3,
+//!     0.08,
+//!     Some(date!(2024 - 01 - 01)),
+//!     date!(2024 - 04 - 01),
+//!     TypeFlag::Call,
+//! );
+//!
+//! assert!(option.validate().is_ok());
+//! assert!(option.price() > 0.0);
+//! ```
+
+pub use crate::error::RustQuantError;
+pub use crate::instruments::fx::{currency::Currency, decimal_money::DecimalMoney, money::Money};
+pub use crate::instruments::{Greeks, Sensitivities, Validate};
+pub use crate::math::distributions::{Distribution, Gaussian};
+pub use crate::math::{Real, Statistic};
+pub use crate::time::{
+    Calendar, DateRoller, DateRollingConvention, DayCountConvention, DayCounter, Frequency,
+    Schedule, Scheduler, Tenor, YearFraction,
+};
+
+#[cfg(feature = "curves")]
+pub use crate::data::{Curve, YieldCurve};
+#[cfg(feature = "curves")]
+pub use crate::instruments::{Instrument, PricingContext};
+
+#[cfg(feature = "options")]
+pub use crate::instruments::options::{
+    Bachelier, BarrierOption, BarrierType, BlackScholesMerton, TypeFlag,
+};
+
+#[cfg(feature = "autodiff")]
+pub use crate::autodiff::{Accumulate, Dual, Gradient, Graph, Variable};
+
+#[cfg(feature = "stochastics")]
+pub use crate::stochastics::{StochasticProcess, Trajectories};
@@ -0,0 +1,397 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! VaR backtesting and stressed VaR.
+//!
+//! A VaR forecast is breached when the realised loss exceeds it. Over $n$
+//! days with $x$ exceptions at confidence $c$ (exception probability
+//! $p = 1 - c$):
+//!
+//! - Kupiec's proportion of failures test compares $x / n$ with $p$,
+//!   $LR_{pof} = -2 \ln \frac{(1 - p)^{n - x} p^x}{(1 - x/n)^{n - x} (x/n)^x} \sim \chi^2_1$;
+//! - Christoffersen's independence test compares the probability of an
+//!   exception after an exception, $\pi_1$, and after none, $\pi_0$,
+//!   $LR_{ind} \sim \chi^2_1$, and the conditional coverage test adds both,
+//!   $LR_{cc} = LR_{pof} + LR_{ind} \sim \chi^2_2$;
+//! - the Basel traffic light puts the number of exceptions in the green
+//!   zone if its cumulative binomial probability is below 95%, the yellow
+//!   zone below 99.99%, and the red zone otherwise.
+//!
+//! Stressed VaR is the VaR over the historical window (e.g. 250 days) in
+//! which the current portfolio would have had the largest VaR.
+//!
+//! ```
+//! use RustQuant::risk::*;
+//!
+//! let pnl = [1.0, -2.5, 0.3, -0.1, -3.0, 0.8, 0.2, -0.4, 1.1, -0.9];
+//! let var = [2.0; 10];
+//!
+//! let backtest = VarBacktest::new(&pnl, &var, 0.99).unwrap();
+//!
+//! assert_eq!(backtest.exceptions, 2);
+//! ```
+
+use crate::error::RustQuantError;
+use crate::math::distributions::{Binomial, ChiSquared, Distribution};
+use crate::risk::ValueAtRisk;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Basel traffic light zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrafficLight {
+    /// No evidence against the model.
+    Green,
+    /// The model may be inaccurate.
+    Yellow,
+    /// The model is almost certainly inaccurate.
+    Red,
+}
+
+/// Likelihood ratio test statistic and p-value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LikelihoodRatioTest {
+    /// Test statistic.
+    pub statistic: f64,
+    /// Probability of a statistic at least as large under the null.
+    pub p_value: f64,
+}
+
+/// Backtest of VaR forecasts against realised P&L.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarBacktest {
+    /// Confidence level of the VaR.
+    pub confidence: f64,
+    /// Number of observations.
+    pub observations: usize,
+    /// Number of exceptions (losses beyond VaR).
+    pub exceptions: usize,
+    /// Whether each day was an exception.
+    pub hits: Vec<bool>,
+    /// Kupiec's proportion of failures test.
+    pub kupiec: LikelihoodRatioTest,
+    /// Christoffersen's independence test.
+    pub independence: LikelihoodRatioTest,
+    /// Christoffersen's conditional coverage test.
+    pub conditional_coverage: LikelihoodRatioTest,
+    /// Basel traffic light zone.
+    pub zone: TrafficLight,
+}
+
+/// Window of largest VaR in a P&L history.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StressedWindow {
+    /// Index of the first observation of the window.
+    pub start: usize,
+    /// Index one past the last observation of the window.
+    pub end: usize,
+    /// VaR over the window.
+    pub value_at_risk: f64,
+    /// ES over the window.
+    pub expected_shortfall: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl TrafficLight {
+    /// Zone of a number of exceptions over `observations` days.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the confidence level is not in $[0, 1]$.
+    #[must_use]
+    pub fn new(exceptions: usize, observations: usize, confidence: f64) -> Self {
+        let cumulative = match exceptions >= observations {
+            true => 1.0,
+            false => Binomial::new(observations, 1.0 - confidence).cdf(exceptions as f64),
+        };
+
+        match cumulative {
+            c if c < 0.95 => TrafficLight::Green,
+            c if c < 0.9999 => TrafficLight::Yellow,
+            _ => TrafficLight::Red,
+        }
+    }
+}
+
+impl VarBacktest {
+    /// Backtest daily VaR forecasts (as positive losses) against the
+    /// realised P&L of the same days.
+    ///
+    /// # Errors
+    ///
+    /// If the series are empty or of different lengths, or the confidence
+    /// level is not in $(0, 1)$.
+    pub fn new(pnl: &[f64], var: &[f64], confidence: f64) -> Result<Self, RustQuantError> {
+        if pnl.is_empty() || pnl.len() != var.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "P&L and VaR must be non-empty and of the same length, got {} and {}",
+                pnl.len(),
+                var.len()
+            )));
+        }
+        if !(confidence > 0.0 && confidence < 1.0) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "confidence level must be in (0, 1), got {confidence}"
+            )));
+        }
+
+        let hits: Vec<bool> = pnl.iter().zip(var).map(|(p, v)| -p > *v).collect();
+        let n = hits.len();
+        let x = hits.iter().filter(|&&hit| hit).count();
+        let p = 1.0 - confidence;
+
+        // Proportion of failures.
+        let kupiec_statistic = -2.0
+            * (bernoulli_log_likelihood(n - x, x, p)
+                - bernoulli_log_likelihood(n - x, x, x as f64 / n as f64));
+
+        // Transitions between days with and without exceptions.
+        let mut transitions = [[0_usize; 2]; 2];
+        for pair in hits.windows(2) {
+            transitions[usize::from(pair[0])][usize::from(pair[1])] += 1;
+        }
+        let [[n00, n01], [n10, n11]] = transitions;
+
+        let rate = |misses: usize, hits: usize| match misses + hits {
+            0 => 0.0,
+            total => hits as f64 / total as f64,
+        };
+        let (pi_0, pi_1, pi) = (rate(n00, n01), rate(n10, n11), rate(n00 + n10, n01 + n11));
+
+        let independence_statistic = -2.0
+            * (bernoulli_log_likelihood(n00 + n10, n01 + n11, pi)
+                - bernoulli_log_likelihood(n00, n01, pi_0)
+                - bernoulli_log_likelihood(n10, n11, pi_1));
+
+        Ok(Self {
+            confidence,
+            observations: n,
+            exceptions: x,
+            kupiec: LikelihoodRatioTest::new(kupiec_statistic, 1),
+            independence: LikelihoodRatioTest::new(independence_statistic, 1),
+            conditional_coverage: LikelihoodRatioTest::new(
+                kupiec_statistic + independence_statistic,
+                2,
+            ),
+            zone: TrafficLight::new(x, n, confidence),
+            hits,
+        })
+    }
+
+    /// Observed exception rate.
+    #[must_use]
+    pub fn exception_rate(&self) -> f64 {
+        self.exceptions as f64 / self.observations as f64
+    }
+}
+
+impl LikelihoodRatioTest {
+    /// Test of a statistic, chi-squared with `degrees_of_freedom` under the
+    /// null.
+    #[must_use]
+    pub fn new(statistic: f64, degrees_of_freedom: usize) -> Self {
+        let statistic = statistic.max(0.0);
+        let p_value = match statistic > 0.0 {
+            true => 1.0 - ChiSquared::new(degrees_of_freedom).cdf(statistic),
+            false => 1.0,
+        };
+
+        Self { statistic, p_value }
+    }
+
+    /// Whether the null is rejected at a significance level, e.g. `0.05`.
+    #[must_use]
+    pub fn rejects(&self, significance: f64) -> bool {
+        self.p_value < significance
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Basel multiplier of the market risk capital for a number of exceptions
+/// over 250 days of 99% VaR: 3 in the green zone, plus 0.4 to 0.85 in the
+/// yellow zone, and 4 in the red zone.
+#[must_use]
+pub fn basel_multiplier(exceptions: usize) -> f64 {
+    match exceptions {
+        0..=4 => 3.0,
+        5 => 3.4,
+        6 => 3.5,
+        7 => 3.65,
+        8 => 3.75,
+        9 => 3.85,
+        _ => 4.0,
+    }
+}
+
+/// Window of `window` consecutive observations of a P&L history (of the
+/// current portfolio) with the largest VaR, for stressed VaR.
+///
+/// # Errors
+///
+/// If the window is empty or longer than the history, or the VaR of a
+/// window cannot be computed.
+pub fn stressed_window(
+    pnl: &[f64],
+    window: usize,
+    estimator: &ValueAtRisk,
+) -> Result<StressedWindow, RustQuantError> {
+    if window == 0 || window > pnl.len() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "window of {window} observations for a history of {}",
+            pnl.len()
+        )));
+    }
+
+    let mut stressed: Option<StressedWindow> = None;
+
+    for (start, observations) in pnl.windows(window).enumerate() {
+        let risk = estimator.compute(observations)?;
+
+        let larger = match stressed {
+            Some(current) => risk.value_at_risk > current.value_at_risk,
+            None => true,
+        };
+        if larger {
+            stressed = Some(StressedWindow {
+                start,
+                end: start + window,
+                value_at_risk: risk.value_at_risk,
+                expected_shortfall: risk.expected_shortfall,
+            });
+        }
+    }
+
+    stressed.ok_or_else(|| RustQuantError::ComputationError("no window".to_string()))
+}
+
+// Log-likelihood of `misses` and `hits` Bernoulli outcomes with hit
+// probability `p`, with 0 ln 0 = 0.
+fn bernoulli_log_likelihood(misses: usize, hits: usize, p: f64) -> f64 {
+    let term = |count: usize, probability: f64| match count {
+        0 => 0.0,
+        _ => count as f64 * probability.ln(),
+    };
+
+    term(misses, 1.0 - p) + term(hits, p)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_backtesting {
+    use super::*;
+    use crate::risk::VarMethod;
+
+    // 250 days of P&L with exceptions on the given days, against a VaR of 1.
+    fn backtest(exception_days: &[usize]) -> VarBacktest {
+        let pnl: Vec<f64> = (0..250)
+            .map(|day| match exception_days.contains(&day) {
+                true => -2.0,
+                false => 0.5,
+            })
+            .collect();
+
+        VarBacktest::new(&pnl, &[1.0; 250], 0.99).unwrap()
+    }
+
+    #[test]
+    fn test_traffic_light_zones() {
+        let zone = |exceptions: usize| TrafficLight::new(exceptions, 250, 0.99);
+
+        assert_eq!(zone(0), TrafficLight::Green);
+        assert_eq!(zone(4), TrafficLight::Green);
+        assert_eq!(zone(5), TrafficLight::Yellow);
+        assert_eq!(zone(9), TrafficLight::Yellow);
+        assert_eq!(zone(10), TrafficLight::Red);
+        assert_eq!(zone(250), TrafficLight::Red);
+
+        assert_approx_equal!(basel_multiplier(3), 3.0, 1e-15);
+        assert_approx_equal!(basel_multiplier(7), 3.65, 1e-15);
+        assert_approx_equal!(basel_multiplier(12), 4.0, 1e-15);
+    }
+
+    #[test]
+    fn test_kupiec() {
+        // 2.5 expected exceptions: 2 is consistent, 10 is not.
+        let few = backtest(&[40, 180]);
+        assert_eq!(few.exceptions, 2);
+        assert_approx_equal!(few.exception_rate(), 0.008, 1e-15);
+
+        let expected = -2.0
+            * (248.0 * 0.99_f64.ln() + 2.0 * 0.01_f64.ln()
+                - 248.0 * 0.992_f64.ln()
+                - 2.0 * 0.008_f64.ln());
+        assert_approx_equal!(few.kupiec.statistic, expected, 1e-9);
+        assert!(!few.kupiec.rejects(0.05));
+        assert_eq!(few.zone, TrafficLight::Green);
+
+        let many = backtest(&[10, 30, 50, 70, 90, 110, 130, 150, 170, 190]);
+        assert!(many.kupiec.rejects(0.01));
+        assert_eq!(many.zone, TrafficLight::Red);
+
+        // No exceptions at all.
+        let none = backtest(&[]);
+        assert_approx_equal!(none.kupiec.statistic, -2.0 * 250.0 * 0.99_f64.ln(), 1e-9);
+        assert_approx_equal!(none.independence.statistic, 0.0, 1e-12);
+        assert_approx_equal!(none.independence.p_value, 1.0, 1e-12);
+    }
+
+    #[test]
+    fn test_christoffersen_independence() {
+        // The same number of exceptions, spread out or clustered.
+        let spread = backtest(&[20, 70, 120, 170, 220]);
+        let clustered = backtest(&[100, 101, 102, 103, 104]);
+
+        assert_approx_equal!(spread.kupiec.statistic, clustered.kupiec.statistic, 1e-12);
+        assert!(!spread.independence.rejects(0.05));
+        assert!(clustered.independence.rejects(0.01));
+        assert!(clustered.conditional_coverage.rejects(0.01));
+        assert_approx_equal!(
+            clustered.conditional_coverage.statistic,
+            clustered.kupiec.statistic + clustered.independence.statistic,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_stressed_window() {
+        // Calm, then a volatile period, then calm.
+        let pnl: Vec<f64> = (0..300)
+            .map(|day| {
+                let scale = match (100..160).contains(&day) {
+                    true => 5.0,
+                    false => 1.0,
+                };
+                scale * ((day * 7919 % 101) as f64 / 50.0 - 1.0)
+            })
+            .collect();
+
+        let estimator = ValueAtRisk::new(0.99, VarMethod::Historical);
+        let stressed = stressed_window(&pnl, 60, &estimator).unwrap();
+
+        // The window overlaps the volatile period.
+        assert!(stressed.start < 160 && stressed.end > 100);
+        assert!(stressed.value_at_risk > 3.0);
+        assert!(stressed.expected_shortfall >= stressed.value_at_risk);
+
+        assert!(stressed_window(&pnl, 0, &estimator).is_err());
+        assert!(stressed_window(&pnl, 301, &estimator).is_err());
+        assert!(VarBacktest::new(&pnl, &[1.0; 10], 0.99).is_err());
+    }
+}
@@ -0,0 +1,372 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Risk contributions: marginal, component, and incremental VaR and ES.
+//!
+//! VaR and ES are homogeneous of degree one in the position sizes $w_i$, so
+//! by Euler's theorem they split into component contributions that sum to
+//! the portfolio figure:
+//!
+//! $$
+//! VaR = \sum_i w_i \frac{\partial VaR}{\partial w_i}
+//! $$
+//!
+//! where $\partial VaR / \partial w_i$ is the marginal VaR. For the
+//! delta-normal method the marginal VaR is $-z (\Sigma w)_i / \sigma_p$. For
+//! P&L series (historical or simulated), the component VaR is the position's
+//! loss in the scenario at the VaR quantile, and the component ES its mean
+//! loss in the scenarios at or beyond VaR.
+//!
+//! The incremental VaR is the change in VaR from removing the position
+//! altogether, $VaR - VaR_{-i}$; unlike the components, the increments do
+//! not sum to the portfolio VaR.
+//!
+//! ```
+//! use RustQuant::risk::*;
+//! use nalgebra::DMatrix;
+//!
+//! let covariance = DMatrix::from_row_slice(2, 2, &[0.04, 0.01, 0.01, 0.09]);
+//! let decomposition =
+//!     delta_normal_contributions(&["equity", "rates"], &[100.0, 50.0], &covariance, 0.99)
+//!         .unwrap();
+//!
+//! let total: f64 = decomposition
+//!     .contributions
+//!     .iter()
+//!     .map(|c| c.component_var)
+//!     .sum();
+//!
+//! assert!((total - decomposition.risk.value_at_risk).abs() < 1e-9);
+//! ```
+
+use super::value_at_risk::{check_confidence, standard_normal_tail};
+use crate::error::RustQuantError;
+use crate::risk::{delta_normal, RiskEstimate, ValueAtRisk, VarMethod};
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Contribution of a position to the portfolio VaR and ES.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskContribution {
+    /// Name of the position.
+    pub name: String,
+    /// Sensitivity of the VaR to the position size (per unit of exposure
+    /// for the delta-normal method, per unit of scaling for P&L series).
+    pub marginal_var: f64,
+    /// Euler contribution to the VaR.
+    pub component_var: f64,
+    /// VaR less the VaR without the position.
+    pub incremental_var: f64,
+    /// Sensitivity of the ES to the position size.
+    pub marginal_es: f64,
+    /// Euler contribution to the ES.
+    pub component_es: f64,
+    /// ES less the ES without the position.
+    pub incremental_es: f64,
+}
+
+/// Portfolio VaR and ES with their per-position contributions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskDecomposition {
+    /// Portfolio VaR and ES.
+    pub risk: RiskEstimate,
+    /// Per-position contributions.
+    pub contributions: Vec<RiskContribution>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RiskDecomposition {
+    /// Contribution of a position, by name.
+    #[must_use]
+    pub fn contribution(&self, name: &str) -> Option<&RiskContribution> {
+        self.contributions.iter().find(|c| c.name == name)
+    }
+
+    /// Component VaR as fractions of the portfolio VaR.
+    #[must_use]
+    pub fn var_shares(&self) -> Vec<f64> {
+        self.contributions
+            .iter()
+            .map(|c| c.component_var / self.risk.value_at_risk)
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Delta-normal VaR and ES of a portfolio, see [`delta_normal`], with the
+/// contributions of each exposure.
+///
+/// # Errors
+///
+/// If the confidence level is not in $(0, 1)$, the dimensions of the names,
+/// exposures, and covariance matrix do not match, or the portfolio has no
+/// variance.
+pub fn delta_normal_contributions(
+    names: &[&str],
+    exposures: &[f64],
+    covariance: &DMatrix<f64>,
+    confidence: f64,
+) -> Result<RiskDecomposition, RustQuantError> {
+    check_names(names, exposures.len())?;
+
+    let risk = delta_normal(exposures, covariance, confidence)?;
+    let (z, tail) = standard_normal_tail(confidence);
+
+    let w = DVector::from_column_slice(exposures);
+    let covariance_w = covariance * &w;
+    let std_dev = w.dot(&covariance_w).max(0.0).sqrt();
+
+    if std_dev <= 0.0 {
+        return Err(RustQuantError::InvalidArgument(
+            "portfolio has no variance".to_string(),
+        ));
+    }
+
+    let contributions = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let without: Vec<f64> = (0..exposures.len())
+                .map(|j| match i == j {
+                    true => 0.0,
+                    false => exposures[j],
+                })
+                .collect();
+            let remaining = delta_normal(&without, covariance, confidence)?;
+
+            let marginal_var = -z * covariance_w[i] / std_dev;
+            let marginal_es = -tail * covariance_w[i] / std_dev;
+
+            Ok(RiskContribution {
+                name: (*name).to_string(),
+                marginal_var,
+                component_var: exposures[i] * marginal_var,
+                incremental_var: risk.value_at_risk - remaining.value_at_risk,
+                marginal_es,
+                component_es: exposures[i] * marginal_es,
+                incremental_es: risk.expected_shortfall - remaining.expected_shortfall,
+            })
+        })
+        .collect::<Result<Vec<RiskContribution>, RustQuantError>>()?;
+
+    Ok(RiskDecomposition {
+        risk,
+        contributions,
+    })
+}
+
+/// Historical VaR and ES of a portfolio from the P&L series of its
+/// positions (one series per position, over the same scenarios), with the
+/// contributions of each position.
+///
+/// # Errors
+///
+/// If the confidence level is not in $(0, 1)$, there are no positions, or
+/// the names and P&L series do not match.
+pub fn historical_contributions(
+    names: &[&str],
+    pnl: &[Vec<f64>],
+    confidence: f64,
+) -> Result<RiskDecomposition, RustQuantError> {
+    check_confidence(confidence)?;
+    check_names(names, pnl.len())?;
+
+    let n = pnl.first().map(Vec::len).unwrap_or_default();
+
+    if n == 0 || pnl.iter().any(|series| series.len() != n) {
+        return Err(RustQuantError::InvalidArgument(
+            "P&L series must be non-empty and of the same length".to_string(),
+        ));
+    }
+
+    let estimator = ValueAtRisk::new(confidence, VarMethod::Historical);
+    let portfolio = |skip: Option<usize>| -> Vec<f64> {
+        (0..n)
+            .map(|j| {
+                (0..pnl.len())
+                    .filter(|&i| Some(i) != skip)
+                    .map(|i| pnl[i][j])
+                    .sum()
+            })
+            .collect()
+    };
+
+    let total = portfolio(None);
+    let risk = estimator.compute(&total)?;
+
+    // The VaR interpolates the losses of the scenarios either side of the
+    // quantile.
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| (-total[a]).total_cmp(&-total[b]));
+
+    let index = confidence * (n - 1) as f64;
+    let fraction = index - index.floor();
+    let (lower, upper) = (order[index.floor() as usize], order[index.ceil() as usize]);
+
+    let tail: Vec<usize> = (0..n)
+        .filter(|&j| -total[j] >= risk.value_at_risk)
+        .collect();
+
+    let contributions = names
+        .iter()
+        .zip(pnl)
+        .enumerate()
+        .map(|(i, (name, series))| {
+            let remaining = match pnl.len() {
+                1 => RiskEstimate {
+                    confidence,
+                    value_at_risk: 0.0,
+                    expected_shortfall: 0.0,
+                },
+                _ => estimator.compute(&portfolio(Some(i)))?,
+            };
+
+            let component_var = -(series[lower] * (1.0 - fraction) + series[upper] * fraction);
+            let component_es = -tail.iter().map(|&j| series[j]).sum::<f64>() / tail.len() as f64;
+
+            Ok(RiskContribution {
+                name: (*name).to_string(),
+                marginal_var: component_var,
+                component_var,
+                incremental_var: risk.value_at_risk - remaining.value_at_risk,
+                marginal_es: component_es,
+                component_es,
+                incremental_es: risk.expected_shortfall - remaining.expected_shortfall,
+            })
+        })
+        .collect::<Result<Vec<RiskContribution>, RustQuantError>>()?;
+
+    Ok(RiskDecomposition {
+        risk,
+        contributions,
+    })
+}
+
+// One name per position.
+fn check_names(names: &[&str], positions: usize) -> Result<(), RustQuantError> {
+    match names.len() == positions && positions > 0 {
+        true => Ok(()),
+        false => Err(RustQuantError::InvalidArgument(format!(
+            "{} names for {positions} positions",
+            names.len()
+        ))),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_contributions {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn sums(decomposition: &RiskDecomposition) -> (f64, f64) {
+        decomposition
+            .contributions
+            .iter()
+            .fold((0.0, 0.0), |(var, es), c| {
+                (var + c.component_var, es + c.component_es)
+            })
+    }
+
+    #[test]
+    fn test_delta_normal_contributions() {
+        let covariance = DMatrix::from_row_slice(
+            3,
+            3,
+            &[0.04, 0.006, -0.01, 0.006, 0.09, 0.0, -0.01, 0.0, 0.01],
+        );
+        let exposures = [100.0, 50.0, 80.0];
+        let decomposition =
+            delta_normal_contributions(&["a", "b", "c"], &exposures, &covariance, 0.99).unwrap();
+
+        let (var, es) = sums(&decomposition);
+        assert_approx_equal!(var, decomposition.risk.value_at_risk, EPS);
+        assert_approx_equal!(es, decomposition.risk.expected_shortfall, EPS);
+        assert_approx_equal!(decomposition.var_shares().iter().sum::<f64>(), 1.0, EPS);
+
+        // The marginal VaR is the derivative of the VaR.
+        let bump = 1e-4;
+        let bumped = delta_normal(&[100.0, 50.0 + bump, 80.0], &covariance, 0.99).unwrap();
+        let b = decomposition.contribution("b").unwrap();
+        assert_approx_equal!(
+            b.marginal_var,
+            (bumped.value_at_risk - decomposition.risk.value_at_risk) / bump,
+            1e-5
+        );
+
+        // The negatively correlated position is a hedge: removing it adds risk.
+        let c = decomposition.contribution("c").unwrap();
+        let without = delta_normal(&[100.0, 50.0, 0.0], &covariance, 0.99).unwrap();
+        assert_approx_equal!(
+            c.incremental_var,
+            decomposition.risk.value_at_risk - without.value_at_risk,
+            EPS
+        );
+        assert!(c.component_var < 0.0);
+        assert!(c.incremental_var < 0.0);
+
+        assert!(delta_normal_contributions(&["a"], &exposures, &covariance, 0.99).is_err());
+        assert!(
+            delta_normal_contributions(&["a", "b", "c"], &[0.0; 3], &covariance, 0.99).is_err()
+        );
+    }
+
+    #[test]
+    fn test_historical_contributions() {
+        let a: Vec<f64> = (0..500).map(|j| (j as f64 * 0.61).sin()).collect();
+        let b: Vec<f64> = (0..500).map(|j| 0.5 * (j as f64 * 1.37).cos()).collect();
+        let hedge: Vec<f64> = a.iter().map(|x| -0.5 * x).collect();
+
+        let decomposition =
+            historical_contributions(&["a", "b", "hedge"], &[a.clone(), b.clone(), hedge], 0.95)
+                .unwrap();
+
+        let (var, es) = sums(&decomposition);
+        assert_approx_equal!(var, decomposition.risk.value_at_risk, EPS);
+        assert_approx_equal!(es, decomposition.risk.expected_shortfall, EPS);
+
+        let hedge = decomposition.contribution("hedge").unwrap();
+        assert!(hedge.component_es < 0.0);
+        assert!(hedge.incremental_es < 0.0);
+
+        // A single position carries all of the risk.
+        let single = historical_contributions(&["a"], std::slice::from_ref(&a), 0.95).unwrap();
+        let estimate = ValueAtRisk::new(0.95, VarMethod::Historical)
+            .compute(&a)
+            .unwrap();
+        assert_approx_equal!(
+            single.contributions[0].component_var,
+            estimate.value_at_risk,
+            EPS
+        );
+        assert_approx_equal!(
+            single.contributions[0].incremental_es,
+            estimate.expected_shortfall,
+            EPS
+        );
+
+        assert!(
+            historical_contributions(&["a", "b"], &[a.clone(), b[..10].to_vec()], 0.95).is_err()
+        );
+        assert!(historical_contributions(&["a"], &[a], 1.0).is_err());
+    }
+}
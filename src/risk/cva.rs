@@ -0,0 +1,478 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Credit valuation adjustment from an exposure profile.
+//!
+//! With a constant default intensity $\lambda$, independent of the
+//! exposure, the CVA is the discounted expected exposure weighted by the
+//! probability of default in each period:
+//!
+//! $$
+//! CVA = (1 - R) \sum_i P(t_i) \, EE(t_i) \left( e^{-\lambda t_{i-1}} - e^{-\lambda t_i} \right)
+//! $$
+//!
+//! Independence understates the CVA when the counterparty is more likely to
+//! default as the exposure grows (wrong-way risk). [`WrongWayRisk`] ties the
+//! default to one of the simulated factors, through its shock standardised
+//! across the paths at each date, $z_p(t)$, and the CVA is computed path by
+//! path from the exposure and the conditional default probabilities:
+//!
+//! - Gaussian copula: the default time is driven by a normal variable with
+//!   correlation $\rho$ to $z$, so that
+//!   $P(\tau \leq t \mid z) = \Phi\left( (\Phi^{-1}(1 - e^{-\lambda t}) + \rho z) / \sqrt{1 - \rho^2} \right)$;
+//! - stochastic intensity: $\lambda_p(t) = \lambda e^{\beta z_p(t) - \beta^2 / 2}$,
+//!   whose mean is $\lambda$.
+
+use super::ExposureProfile;
+use crate::error::RustQuantError;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::{PricingContext, Validate, Validator};
+use crate::math::distributions::{Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Dependence between the counterparty's default and the exposure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WrongWayRisk {
+    /// Default independent of the exposure.
+    Independent,
+    /// Gaussian copula between the default time and a factor shock.
+    GaussianCopula {
+        /// Index of the factor in the exposure engine.
+        factor: usize,
+        /// Correlation, positive for wrong-way risk.
+        correlation: f64,
+    },
+    /// Default intensity lognormal in a factor shock.
+    Intensity {
+        /// Index of the factor in the exposure engine.
+        factor: usize,
+        /// Sensitivity of the log-intensity to the standardised shock,
+        /// positive for wrong-way risk.
+        sensitivity: f64,
+    },
+}
+
+/// Credit of a counterparty: a flat default intensity and a recovery rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CounterpartyCredit {
+    /// Default intensity (hazard rate).
+    pub hazard_rate: f64,
+    /// Fraction of the exposure recovered on default.
+    pub recovery_rate: f64,
+    /// Dependence of the default on the exposure.
+    pub wrong_way_risk: WrongWayRisk,
+}
+
+/// Credit valuation adjustment of a netting set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreditValuationAdjustment {
+    /// Total CVA.
+    pub cva: f64,
+    /// Contribution of each period of the exposure profile.
+    pub contributions: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Validate for CounterpartyCredit {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .non_negative("hazard_rate", self.hazard_rate)
+            .finite("hazard_rate", self.hazard_rate)
+            .check((0.0..=1.0).contains(&self.recovery_rate), || {
+                format!(
+                    "recovery_rate must lie in [0, 1] (got {})",
+                    self.recovery_rate
+                )
+            })
+            .check(
+                match self.wrong_way_risk {
+                    WrongWayRisk::GaussianCopula { correlation, .. } => correlation.abs() < 1.0,
+                    WrongWayRisk::Intensity { sensitivity, .. } => sensitivity.is_finite(),
+                    WrongWayRisk::Independent => true,
+                },
+                || format!("invalid wrong-way risk {:?}", self.wrong_way_risk),
+            )
+            .finish()
+    }
+}
+
+impl CounterpartyCredit {
+    /// New counterparty credit, with default independent of the exposure.
+    #[must_use]
+    pub const fn new(hazard_rate: f64, recovery_rate: f64) -> Self {
+        Self {
+            hazard_rate,
+            recovery_rate,
+            wrong_way_risk: WrongWayRisk::Independent,
+        }
+    }
+
+    /// Set the dependence of the default on the exposure.
+    #[must_use]
+    pub const fn with_wrong_way_risk(self, wrong_way_risk: WrongWayRisk) -> Self {
+        Self {
+            wrong_way_risk,
+            ..self
+        }
+    }
+
+    /// Probability of surviving to time `t` (in years).
+    #[must_use]
+    pub fn survival_probability(&self, t: f64) -> f64 {
+        (-self.hazard_rate * t).exp()
+    }
+
+    /// CVA of an exposure profile, discounted on the context's curve for
+    /// `currency`.
+    ///
+    /// # Errors
+    ///
+    /// If the credit parameters are invalid, the context has no discount
+    /// curve for the currency, or the wrong-way risk refers to a factor the
+    /// profile has no paths of.
+    pub fn credit_valuation_adjustment(
+        &self,
+        profile: &ExposureProfile,
+        ctx: &PricingContext,
+        currency: &Currency,
+    ) -> Result<CreditValuationAdjustment, RustQuantError> {
+        self.validate()?;
+
+        let discount_factors = discount_factors(profile, ctx, currency)?;
+        let lgd = 1.0 - self.recovery_rate;
+
+        // Expected exposure weighted by the default probability in each
+        // period, averaged over the paths if they are not independent.
+        let weighted_exposure = match self.wrong_way_risk {
+            WrongWayRisk::Independent => {
+                let mut previous = 1.0;
+
+                profile
+                    .times
+                    .iter()
+                    .zip(&profile.expected_exposure)
+                    .map(|(&t, &ee)| {
+                        let survival = self.survival_probability(t);
+                        let weighted = ee * (previous - survival);
+                        previous = survival;
+                        weighted
+                    })
+                    .collect()
+            }
+            WrongWayRisk::GaussianCopula {
+                factor,
+                correlation,
+            } => {
+                let z = standardised_shocks(profile, factor)?;
+                let gaussian = Gaussian::default();
+                let scale = (1.0 - correlation * correlation).sqrt();
+
+                // Default probability by `t`, conditional on the shock.
+                let conditional = |t: f64, z: f64| match t > 0.0 && self.hazard_rate > 0.0 {
+                    true => {
+                        let threshold = gaussian.inv_cdf(1.0 - self.survival_probability(t));
+                        gaussian.cdf((threshold + correlation * z) / scale)
+                    }
+                    false => 0.0,
+                };
+
+                pathwise(profile, |p, i| {
+                    let previous = match i {
+                        0 => 0.0,
+                        _ => profile.times[i - 1],
+                    };
+
+                    conditional(profile.times[i], z[p][i]) - conditional(previous, z[p][i])
+                })
+            }
+            WrongWayRisk::Intensity {
+                factor,
+                sensitivity,
+            } => {
+                let z = standardised_shocks(profile, factor)?;
+
+                // Integrated intensity of each path at each date.
+                let integrated: Vec<Vec<f64>> = z
+                    .iter()
+                    .map(|path| {
+                        let mut previous = 0.0;
+
+                        path.iter()
+                            .zip(&profile.times)
+                            .scan(0.0, |total, (z, &t)| {
+                                let intensity = self.hazard_rate
+                                    * (sensitivity * z - 0.5 * sensitivity * sensitivity).exp();
+                                *total += intensity * (t - previous);
+                                previous = t;
+                                Some(*total)
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                pathwise(profile, |p, i| {
+                    let previous = match i {
+                        0 => 0.0,
+                        _ => integrated[p][i - 1],
+                    };
+
+                    (-previous).exp() - (-integrated[p][i]).exp()
+                })
+            }
+        };
+
+        let contributions: Vec<f64> = weighted_exposure
+            .iter()
+            .zip(&discount_factors)
+            .map(|(weighted, df)| lgd * df * weighted)
+            .collect();
+
+        Ok(CreditValuationAdjustment {
+            cva: contributions.iter().sum(),
+            contributions,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Discount factors to the dates of an exposure profile.
+pub(crate) fn discount_factors(
+    profile: &ExposureProfile,
+    ctx: &PricingContext,
+    currency: &Currency,
+) -> Result<Vec<f64>, RustQuantError> {
+    profile
+        .dates
+        .iter()
+        .map(|&date| {
+            ctx.discount_factor(currency, date).ok_or_else(|| {
+                RustQuantError::MissingInput(format!(
+                    "no discount curve for {}",
+                    currency.code.alphabetic
+                ))
+            })
+        })
+        .collect()
+}
+
+// Shocks of a factor standardised across the paths at each date,
+// `z[path][date]`.
+fn standardised_shocks(
+    profile: &ExposureProfile,
+    factor: usize,
+) -> Result<Vec<Vec<f64>>, RustQuantError> {
+    let n_factors = profile
+        .shocks
+        .first()
+        .and_then(|path| path.first())
+        .map(Vec::len);
+
+    if profile.shocks.len() < 2 || n_factors.unwrap_or_default() <= factor {
+        return Err(RustQuantError::MissingInput(format!(
+            "no simulated paths of factor {factor} in the exposure profile"
+        )));
+    }
+
+    let n = profile.shocks.len() as f64;
+    let mut z: Vec<Vec<f64>> = profile
+        .shocks
+        .iter()
+        .map(|path| path.iter().map(|shocks| shocks[factor]).collect())
+        .collect();
+
+    for i in 0..profile.dates.len() {
+        let mean = z.iter().map(|path| path[i]).sum::<f64>() / n;
+        let variance = z.iter().map(|path| (path[i] - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let std_dev = variance.sqrt();
+
+        for path in &mut z {
+            path[i] = match std_dev > 0.0 {
+                true => (path[i] - mean) / std_dev,
+                false => 0.0,
+            };
+        }
+    }
+
+    Ok(z)
+}
+
+// Positive exposure weighted by a pathwise default probability
+// `default(path, date)`, averaged over the paths.
+fn pathwise<F>(profile: &ExposureProfile, default: F) -> Vec<f64>
+where
+    F: Fn(usize, usize) -> f64,
+{
+    let n = profile.values.len() as f64;
+
+    (0..profile.dates.len())
+        .map(|i| {
+            profile
+                .values
+                .iter()
+                .enumerate()
+                .map(|(p, path)| path[i].max(0.0) * default(p, i))
+                .sum::<f64>()
+                / n
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cva {
+    use super::*;
+    use crate::data::{Curve, YieldCurve};
+    use crate::iso::USD;
+    use time::macros::date;
+    use time::Duration;
+
+    #[test]
+    fn test_flat_exposure_cva() {
+        let t0 = date!(2024 - 01 - 01);
+        let pillars = [0, 365, 730, 1095].map(|days| t0 + Duration::days(days));
+        let ctx = PricingContext::new(t0).with_discount_curve(
+            USD,
+            YieldCurve::from_dates_and_rates(&pillars, &[0.0; 4]).unwrap(),
+        );
+
+        let dates: Vec<_> = (1..=8).map(|i| t0 + Duration::days(91 * i)).collect();
+        let times: Vec<f64> = (1..=8).map(|i| 91.0 * i as f64 / 365.0).collect();
+        let profile = ExposureProfile {
+            dates,
+            times: times.clone(),
+            expected_exposure: vec![100.0; 8],
+            expected_negative_exposure: vec![0.0; 8],
+            potential_future_exposure: vec![150.0; 8],
+            values: vec![],
+            shocks: vec![],
+            initial_margin: vec![0.0; 8],
+        };
+
+        // Flat exposure, no discounting: LGD x EE x P(default before the end).
+        let credit = CounterpartyCredit::new(0.02, 0.4);
+        let cva = credit
+            .credit_valuation_adjustment(&profile, &ctx, &USD)
+            .unwrap();
+
+        let expected = 0.6 * 100.0 * (1.0 - (-0.02 * times[7]).exp());
+        assert_approx_equal!(cva.cva, expected, 1e-9);
+        assert_eq!(cva.contributions.len(), 8);
+        assert!(cva.contributions.windows(2).all(|w| w[0] > w[1]));
+
+        // No curve, or an invalid recovery rate.
+        let empty = PricingContext::new(t0);
+        assert!(credit
+            .credit_valuation_adjustment(&profile, &empty, &USD)
+            .is_err());
+        assert!(CounterpartyCredit::new(0.02, 1.5)
+            .credit_valuation_adjustment(&profile, &ctx, &USD)
+            .is_err());
+    }
+
+    #[test]
+    fn test_wrong_way_risk() {
+        let t0 = date!(2024 - 01 - 01);
+        let pillars = [0, 365, 730, 1095].map(|days| t0 + Duration::days(days));
+        let ctx = PricingContext::new(t0).with_discount_curve(
+            USD,
+            YieldCurve::from_dates_and_rates(&pillars, &[0.03; 4]).unwrap(),
+        );
+
+        // Exposure growing with the (one) factor shock, on evenly spread
+        // normal quantiles.
+        let gaussian = Gaussian::default();
+        let n_paths = 1_000;
+        let times: Vec<f64> = (1..=8).map(|i| 91.0 * i as f64 / 365.0).collect();
+        let shocks: Vec<Vec<Vec<f64>>> = (0..n_paths)
+            .map(|p| {
+                let z = gaussian.inv_cdf((p as f64 + 0.5) / n_paths as f64);
+                times.iter().map(|t| vec![0.2 * t.sqrt() * z]).collect()
+            })
+            .collect();
+        let values: Vec<Vec<f64>> = shocks
+            .iter()
+            .map(|path| path.iter().map(|shock| 100.0 * shock[0]).collect())
+            .collect();
+        let expected_exposure = (0..8)
+            .map(|i| values.iter().map(|v| v[i].max(0.0)).sum::<f64>() / n_paths as f64)
+            .collect();
+
+        let profile = ExposureProfile {
+            dates: (1..=8).map(|i| t0 + Duration::days(91 * i)).collect(),
+            times,
+            expected_exposure,
+            expected_negative_exposure: vec![0.0; 8],
+            potential_future_exposure: vec![0.0; 8],
+            values,
+            shocks,
+            initial_margin: vec![0.0; 8],
+        };
+
+        let credit = CounterpartyCredit::new(0.05, 0.4);
+        let cva = |wrong_way_risk: WrongWayRisk| {
+            credit
+                .with_wrong_way_risk(wrong_way_risk)
+                .credit_valuation_adjustment(&profile, &ctx, &USD)
+                .unwrap()
+                .cva
+        };
+        let copula = |correlation: f64| {
+            cva(WrongWayRisk::GaussianCopula {
+                factor: 0,
+                correlation,
+            })
+        };
+        let intensity = |sensitivity: f64| {
+            cva(WrongWayRisk::Intensity {
+                factor: 0,
+                sensitivity,
+            })
+        };
+
+        // Without dependence, the pathwise CVA is the independent one.
+        let independent = cva(WrongWayRisk::Independent);
+        assert_approx_equal!(copula(0.0), independent, 1e-6 * independent);
+        assert_approx_equal!(intensity(0.0), independent, 1e-9 * independent);
+
+        // Wrong-way risk raises the CVA, right-way risk lowers it.
+        assert!(copula(0.5) > 1.5 * independent);
+        assert!(copula(-0.5) < 0.5 * independent);
+        assert!(copula(0.5) > copula(0.2));
+        assert!(intensity(0.5) > 1.2 * independent);
+        assert!(intensity(-0.5) < 0.8 * independent);
+
+        // Unknown factor, or perfect correlation.
+        let invalid = |wrong_way_risk: WrongWayRisk| {
+            credit
+                .with_wrong_way_risk(wrong_way_risk)
+                .credit_valuation_adjustment(&profile, &ctx, &USD)
+                .is_err()
+        };
+        assert!(invalid(WrongWayRisk::Intensity {
+            factor: 1,
+            sensitivity: 0.5
+        }));
+        assert!(invalid(WrongWayRisk::GaussianCopula {
+            factor: 0,
+            correlation: 1.0
+        }));
+    }
+}
@@ -0,0 +1,794 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Exposure simulation for counterparty risk.
+//!
+//! The [`ExposureEngine`] simulates the [`RiskFactor`]s forward as
+//! correlated Brownian shocks (with an annual covariance and drift):
+//! prices and volatilities are lognormal, rate shifts are normal. At each
+//! exposure date the [`NettingSet`] is revalued under the simulated
+//! [`Scenario`], with the pricing context moved to that date, and the
+//! [`Csa`] collateral is taken off:
+//!
+//! - the collateral called is the netting set value beyond the threshold,
+//!   on either side, observed one margin period of risk earlier;
+//! - the balance only moves when the call is at least the minimum transfer
+//!   amount.
+//!
+//! The [`ExposureProfile`] holds the expected exposure (EE), expected
+//! negative exposure (ENE), and potential future exposure (PFE) at each
+//! date, the Basel EPE and effective EPE, and the pathwise values for the
+//! CVA. With a [`SimmApproximation`], it also holds the expected initial
+//! margin at each date, from the delta sensitivities of the netting set on
+//! each path, for the MVA.
+//!
+//! ```
+//! use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+//! use RustQuant::instruments::{Instrument, PricingContext};
+//! use RustQuant::risk::*;
+//! use nalgebra::DMatrix;
+//! use time::macros::date;
+//!
+//! let ctx = PricingContext::new(date!(2024 - 01 - 01));
+//!
+//! let factors = vec![RiskFactor::Price { name: "SPX".to_string(), level: 100.0 }];
+//!
+//! let call = ScenarioPosition::new("SPX call", 100.0, |scenario: &Scenario| {
+//!     Box::new(BlackScholesMerton::new(
+//!         0.05,
+//!         scenario.level("SPX").unwrap(),
+//!         100.0,
+//!         0.2,
+//!         0.05,
+//!         None,
+//!         date!(2025 - 01 - 01),
+//!         TypeFlag::Call,
+//!     )) as Box<dyn Instrument>
+//! });
+//! let netting_set = NettingSet::new(vec![ExposurePosition::new(call, date!(2025 - 01 - 01))]);
+//!
+//! let dates = [date!(2024 - 04 - 01), date!(2024 - 07 - 01), date!(2024 - 10 - 01)];
+//! let engine = ExposureEngine::new(factors, DMatrix::from_element(1, 1, 0.04), &dates, 2_000, 42)
+//!     .unwrap()
+//!     .with_drifts(&[0.05]);
+//!
+//! let profile = engine.run(&ctx, &netting_set).unwrap();
+//!
+//! assert!(profile.potential_future_exposure[0] > profile.expected_exposure[0]);
+//! ```
+
+use super::{RiskFactor, Scenario, ScenarioPosition};
+use crate::error::RustQuantError;
+use crate::instruments::PricingContext;
+use crate::time::DayCountConvention;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+use time::{Date, Duration};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A position of a netting set, worth nothing from its maturity on.
+pub struct ExposurePosition<'a> {
+    /// The position, revalued under each scenario.
+    pub position: ScenarioPosition<'a>,
+    /// Date of the last cashflow.
+    pub maturity: Date,
+}
+
+/// Credit support annex: the collateral terms of a netting set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Csa {
+    /// Uncollateralised amount allowed on either side.
+    pub threshold: f64,
+    /// Smallest change of the collateral balance that is called.
+    pub minimum_transfer_amount: f64,
+    /// Time between the last margin call met and the close-out.
+    pub margin_period_of_risk: Duration,
+}
+
+/// Positions whose values offset on default, with optional collateral.
+pub struct NettingSet<'a> {
+    /// Positions.
+    pub positions: Vec<ExposurePosition<'a>>,
+    /// Collateral agreement, if any.
+    pub csa: Option<Csa>,
+}
+
+/// Delta-only approximation of ISDA SIMM, for initial margin profiles.
+///
+/// The sensitivity of the netting set to each factor is its change in value
+/// for a 1% relative shift of a price or volatility, or a 1bp shift of a
+/// rate. The initial margin is
+///
+/// $$
+/// IM = \sqrt{\sum_{k,l} \rho_{kl} WS_k WS_l}, \quad WS_k = RW_k \delta_k
+/// $$
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimmApproximation {
+    /// Risk weight of each factor, in units of its shift (e.g. `20.0` for
+    /// a 20% equity risk weight, `50.0` for a 50bp rate risk weight).
+    pub risk_weights: Vec<f64>,
+    /// Correlation of the weighted sensitivities.
+    pub correlation: DMatrix<f64>,
+}
+
+/// Monte Carlo exposure engine.
+#[derive(Debug, Clone)]
+pub struct ExposureEngine {
+    /// Risk factors, with their current levels.
+    pub factors: Vec<RiskFactor>,
+    /// Annual covariance of the factor shocks.
+    pub covariance: DMatrix<f64>,
+    /// Annual drift of each factor: the expected log-return of prices and
+    /// volatilities, the expected change of rates.
+    pub drifts: Vec<f64>,
+    /// Exposure dates.
+    pub dates: Vec<Date>,
+    /// Number of paths.
+    pub n_paths: usize,
+    /// Seed of the random number generator.
+    pub seed: u64,
+    /// Confidence level of the PFE, e.g. `0.95`.
+    pub confidence: f64,
+    /// Initial margin model, if any.
+    pub initial_margin: Option<SimmApproximation>,
+}
+
+/// Exposure profile of a netting set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExposureProfile {
+    /// Exposure dates.
+    pub dates: Vec<Date>,
+    /// Year fractions of the dates from the valuation date.
+    pub times: Vec<f64>,
+    /// Expected exposure, $\mathbb{E}[\max(V - C, 0)]$, at each date.
+    pub expected_exposure: Vec<f64>,
+    /// Expected negative exposure, $\mathbb{E}[\max(C - V, 0)]$, at each date.
+    pub expected_negative_exposure: Vec<f64>,
+    /// Quantile of the exposure at the engine's confidence, at each date.
+    pub potential_future_exposure: Vec<f64>,
+    /// Collateralised value $V - C$, `values[path][date]`.
+    pub values: Vec<Vec<f64>>,
+    /// Cumulative factor shocks, `shocks[path][date][factor]`.
+    pub shocks: Vec<Vec<Vec<f64>>>,
+    /// Expected initial margin at each date (zero without an initial margin
+    /// model). The exposures above are before initial margin.
+    pub initial_margin: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<'a> ExposurePosition<'a> {
+    /// New position maturing at `maturity`.
+    #[must_use]
+    pub const fn new(position: ScenarioPosition<'a>, maturity: Date) -> Self {
+        Self { position, maturity }
+    }
+}
+
+impl Csa {
+    /// Collateral balance called for a netting set value.
+    #[must_use]
+    pub fn target(&self, value: f64) -> f64 {
+        (value - self.threshold).max(0.0) - (-value - self.threshold).max(0.0)
+    }
+}
+
+impl<'a> NettingSet<'a> {
+    /// Uncollateralised netting set.
+    #[must_use]
+    pub const fn new(positions: Vec<ExposurePosition<'a>>) -> Self {
+        Self {
+            positions,
+            csa: None,
+        }
+    }
+
+    /// Set the collateral agreement.
+    #[must_use]
+    pub fn with_csa(self, csa: Csa) -> Self {
+        Self {
+            csa: Some(csa),
+            ..self
+        }
+    }
+
+    /// Value of the positions alive at `ctx`'s valuation date, under a
+    /// scenario.
+    ///
+    /// # Errors
+    ///
+    /// If a position cannot be valued.
+    pub fn value(&self, scenario: &Scenario, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        self.positions
+            .iter()
+            .filter(|position| position.maturity > ctx.valuation_date)
+            .map(|position| position.position.value(scenario, ctx))
+            .sum()
+    }
+}
+
+impl SimmApproximation {
+    /// New SIMM approximation.
+    #[must_use]
+    pub const fn new(risk_weights: Vec<f64>, correlation: DMatrix<f64>) -> Self {
+        Self {
+            risk_weights,
+            correlation,
+        }
+    }
+
+    /// Initial margin from the delta sensitivities.
+    #[must_use]
+    pub fn initial_margin(&self, sensitivities: &[f64]) -> f64 {
+        let weighted = DVector::from_iterator(
+            sensitivities.len(),
+            sensitivities
+                .iter()
+                .zip(&self.risk_weights)
+                .map(|(delta, weight)| delta * weight),
+        );
+
+        weighted
+            .dot(&(&self.correlation * &weighted))
+            .max(0.0)
+            .sqrt()
+    }
+
+    // Shift of each factor's shock for its SIMM sensitivity.
+    fn shift(factor: &RiskFactor) -> f64 {
+        match factor {
+            RiskFactor::Rate { .. } => 1e-4,
+            _ => 0.01_f64.ln_1p(),
+        }
+    }
+}
+
+impl ExposureEngine {
+    /// New engine with zero drifts and a 95% PFE.
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix is not square with one row per factor, the
+    /// dates are empty or not increasing, or there are fewer than two paths.
+    pub fn new(
+        factors: Vec<RiskFactor>,
+        covariance: DMatrix<f64>,
+        dates: &[Date],
+        n_paths: usize,
+        seed: u64,
+    ) -> Result<Self, RustQuantError> {
+        let n = factors.len();
+
+        if covariance.shape() != (n, n) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "covariance matrix is {:?}, expected ({n}, {n})",
+                covariance.shape()
+            )));
+        }
+        if dates.is_empty() || dates.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RustQuantError::InvalidArgument(
+                "exposure dates must be non-empty and increasing".to_string(),
+            ));
+        }
+        if n_paths < 2 {
+            return Err(RustQuantError::InvalidArgument(
+                "at least two paths are needed".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            factors,
+            covariance,
+            drifts: vec![0.0; n],
+            dates: dates.to_vec(),
+            n_paths,
+            seed,
+            confidence: 0.95,
+            initial_margin: None,
+        })
+    }
+
+    /// Set the annual drifts of the factors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of drifts differs from the number of factors.
+    #[must_use]
+    pub fn with_drifts(self, drifts: &[f64]) -> Self {
+        assert_eq!(self.factors.len(), drifts.len(), "one drift per factor");
+
+        Self {
+            drifts: drifts.to_vec(),
+            ..self
+        }
+    }
+
+    /// Set the confidence level of the PFE.
+    #[must_use]
+    pub fn with_confidence(self, confidence: f64) -> Self {
+        Self { confidence, ..self }
+    }
+
+    /// Compute initial margin profiles with a SIMM approximation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the risk weights or correlation matrix do not have one
+    /// entry or row per factor.
+    #[must_use]
+    pub fn with_initial_margin(self, simm: SimmApproximation) -> Self {
+        let n = self.factors.len();
+
+        assert_eq!(simm.risk_weights.len(), n, "one risk weight per factor");
+        assert_eq!(
+            simm.correlation.shape(),
+            (n, n),
+            "one correlation row per factor"
+        );
+
+        Self {
+            initial_margin: Some(simm),
+            ..self
+        }
+    }
+
+    /// Simulate the factors and revalue the netting set at each date.
+    ///
+    /// # Errors
+    ///
+    /// If the first date is not after the valuation date, the confidence
+    /// is not in $(0, 1)$, the covariance matrix is not positive definite,
+    /// or a position cannot be valued.
+    pub fn run(
+        &self,
+        ctx: &PricingContext,
+        netting_set: &NettingSet,
+    ) -> Result<ExposureProfile, RustQuantError> {
+        if self.dates[0] <= ctx.valuation_date {
+            return Err(RustQuantError::InvalidArgument(
+                "exposure dates must be after the valuation date".to_string(),
+            ));
+        }
+        if !(self.confidence > 0.0 && self.confidence < 1.0) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "confidence must lie in (0, 1) (got {})",
+                self.confidence
+            )));
+        }
+
+        let cholesky = self.covariance.clone().cholesky().ok_or_else(|| {
+            RustQuantError::ComputationError(
+                "covariance matrix is not positive definite".to_string(),
+            )
+        })?;
+        let lower = cholesky.l();
+
+        // Simulation dates: the exposure dates, and the margin dates one
+        // margin period of risk before them.
+        let mpor = netting_set
+            .csa
+            .map_or(Duration::ZERO, |csa| csa.margin_period_of_risk);
+        let margin_dates: Vec<Date> = self
+            .dates
+            .iter()
+            .map(|&date| (date - mpor).max(ctx.valuation_date))
+            .collect();
+        let mut grid: Vec<Date> = self.dates.iter().chain(&margin_dates).copied().collect();
+        grid.sort();
+        grid.dedup();
+
+        let year_fraction =
+            |date: Date| DayCountConvention::default().day_count_factor(ctx.valuation_date, date);
+        let times: Vec<f64> = self.dates.iter().map(|&date| year_fraction(date)).collect();
+
+        // Ito correction, so that lognormal factors grow at their drift.
+        let corrections: Vec<f64> = self
+            .factors
+            .iter()
+            .enumerate()
+            .map(|(j, factor)| match factor {
+                RiskFactor::Rate { .. } => 0.0,
+                _ => 0.5 * self.covariance[(j, j)],
+            })
+            .collect();
+
+        // Pricing contexts moved to each simulation date.
+        let contexts: Vec<PricingContext> = grid
+            .iter()
+            .map(|&date| {
+                let mut moved = ctx.clone();
+                moved.valuation_date = date;
+                moved
+            })
+            .collect();
+        let base = netting_set.value(&Scenario::base(&self.factors), ctx)?;
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let n = self.factors.len();
+
+        let mut values = Vec::with_capacity(self.n_paths);
+        let mut shocks = Vec::with_capacity(self.n_paths);
+        let mut initial_margin = vec![0.0; self.dates.len()];
+
+        for _ in 0..self.n_paths {
+            let mut shock = vec![0.0; n];
+            let mut t = 0.0;
+
+            // Netting set value, shocks, and initial margin at each
+            // simulation date.
+            let mut grid_values = Vec::with_capacity(grid.len());
+            let mut grid_shocks = Vec::with_capacity(grid.len());
+            let mut grid_margins = Vec::with_capacity(grid.len());
+
+            for (date, moved) in grid.iter().zip(&contexts) {
+                let t_next = year_fraction(*date);
+                let dt = t_next - t;
+                let z: DVector<f64> = DVector::from_fn(n, |_, _| StandardNormal.sample(&mut rng));
+                let increment = &lower * z * dt.sqrt();
+
+                for j in 0..n {
+                    shock[j] += (self.drifts[j] - corrections[j]) * dt + increment[j];
+                }
+                t = t_next;
+
+                let scenario = Scenario::from_shocks(&self.factors, &shock);
+                let value = netting_set.value(&scenario, moved)?;
+
+                let margin = match &self.initial_margin {
+                    Some(simm) if self.dates.binary_search(date).is_ok() => {
+                        let sensitivities = (0..n)
+                            .map(|j| {
+                                let mut shifted = shock.clone();
+                                shifted[j] += SimmApproximation::shift(&self.factors[j]);
+
+                                let scenario = Scenario::from_shocks(&self.factors, &shifted);
+                                Ok(netting_set.value(&scenario, moved)? - value)
+                            })
+                            .collect::<Result<Vec<f64>, RustQuantError>>()?;
+
+                        simm.initial_margin(&sensitivities)
+                    }
+                    _ => 0.0,
+                };
+
+                grid_values.push(value);
+                grid_shocks.push(shock.clone());
+                grid_margins.push(margin);
+            }
+
+            let at = |date: &Date| grid.binary_search(date).unwrap_or_default();
+            let mut balance = netting_set.csa.map_or(0.0, |csa| called(&csa, 0.0, base));
+
+            let path_values: Vec<f64> = self
+                .dates
+                .iter()
+                .zip(&margin_dates)
+                .map(|(date, margin_date)| {
+                    let value = grid_values[at(date)];
+
+                    match netting_set.csa {
+                        Some(csa) => {
+                            balance = called(&csa, balance, grid_values[at(margin_date)]);
+                            value - balance
+                        }
+                        None => value,
+                    }
+                })
+                .collect();
+
+            for (margin, date) in initial_margin.iter_mut().zip(&self.dates) {
+                *margin += grid_margins[at(date)] / self.n_paths as f64;
+            }
+
+            values.push(path_values);
+            shocks.push(
+                self.dates
+                    .iter()
+                    .map(|date| grid_shocks[at(date)].clone())
+                    .collect(),
+            );
+        }
+
+        let column = |i: usize| values.iter().map(|path| path[i]).collect::<Vec<f64>>();
+        let n_paths = self.n_paths as f64;
+
+        let expected_exposure = (0..self.dates.len())
+            .map(|i| column(i).iter().map(|v| v.max(0.0)).sum::<f64>() / n_paths)
+            .collect();
+        let expected_negative_exposure = (0..self.dates.len())
+            .map(|i| column(i).iter().map(|v| (-v).max(0.0)).sum::<f64>() / n_paths)
+            .collect();
+        let potential_future_exposure = (0..self.dates.len())
+            .map(|i| {
+                let mut exposures: Vec<f64> = column(i).iter().map(|v| v.max(0.0)).collect();
+                exposures.sort_by(f64::total_cmp);
+
+                let index = (self.confidence * n_paths).ceil() as usize;
+                exposures[index.clamp(1, exposures.len()) - 1]
+            })
+            .collect();
+
+        Ok(ExposureProfile {
+            dates: self.dates.clone(),
+            times,
+            expected_exposure,
+            expected_negative_exposure,
+            potential_future_exposure,
+            values,
+            shocks,
+            initial_margin,
+        })
+    }
+}
+
+impl ExposureProfile {
+    /// Expected positive exposure: the time average of the EE,
+    /// $\sum_i EE_i \Delta t_i / t_n$.
+    #[must_use]
+    pub fn expected_positive_exposure(&self) -> f64 {
+        time_average(&self.times, &self.expected_exposure, f64::INFINITY)
+    }
+
+    /// Effective EE: the running maximum of the EE.
+    #[must_use]
+    pub fn effective_expected_exposure(&self) -> Vec<f64> {
+        self.expected_exposure
+            .iter()
+            .scan(0.0, |maximum: &mut f64, &ee| {
+                *maximum = maximum.max(ee);
+                Some(*maximum)
+            })
+            .collect()
+    }
+
+    /// Effective EPE (Basel): the time average of the effective EE over the
+    /// first year.
+    #[must_use]
+    pub fn effective_expected_positive_exposure(&self) -> f64 {
+        time_average(&self.times, &self.effective_expected_exposure(), 1.0)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Collateral balance after a margin call on the value, if it moves the
+// balance by at least the minimum transfer amount.
+fn called(csa: &Csa, balance: f64, value: f64) -> f64 {
+    let target = csa.target(value);
+
+    match (target - balance).abs() >= csa.minimum_transfer_amount {
+        true => target,
+        false => balance,
+    }
+}
+
+// Average of a step profile over the dates up to the horizon (and at least
+// the first date).
+fn time_average(times: &[f64], profile: &[f64], horizon: f64) -> f64 {
+    let mut previous = 0.0;
+    let mut total = 0.0;
+
+    for (&t, &value) in times.iter().zip(profile) {
+        if previous >= horizon {
+            break;
+        }
+        total += value * (t - previous);
+        previous = t;
+    }
+
+    total / previous
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(all(test, feature = "options"))]
+mod tests_exposure {
+    use super::*;
+    use crate::instruments::options::{BlackScholesMerton, TypeFlag};
+    use crate::instruments::Instrument;
+    use time::macros::date;
+
+    const VALUATION: Date = date!(2024 - 01 - 01);
+    const EXPIRY: Date = date!(2025 - 01 - 01);
+
+    fn call(quantity: f64) -> ExposurePosition<'static> {
+        let position = ScenarioPosition::new("SPX call", quantity, |scenario: &Scenario| {
+            Box::new(BlackScholesMerton::new(
+                0.05,
+                scenario.level("SPX").unwrap(),
+                100.0,
+                0.2,
+                0.05,
+                None,
+                EXPIRY,
+                TypeFlag::Call,
+            )) as Box<dyn Instrument>
+        });
+
+        ExposurePosition::new(position, EXPIRY)
+    }
+
+    fn engine(dates: &[Date]) -> ExposureEngine {
+        let factors = vec![RiskFactor::Price {
+            name: "SPX".to_string(),
+            level: 100.0,
+        }];
+
+        ExposureEngine::new(factors, DMatrix::from_element(1, 1, 0.04), dates, 4_000, 7)
+            .unwrap()
+            .with_drifts(&[0.05])
+    }
+
+    #[test]
+    fn test_uncollateralised_option_exposure() {
+        let ctx = PricingContext::new(VALUATION);
+        let dates = [
+            date!(2024 - 04 - 01),
+            date!(2024 - 10 - 01),
+            date!(2025 - 06 - 01),
+        ];
+        let netting_set = NettingSet::new(vec![call(1.0)]);
+
+        let profile = engine(&dates).run(&ctx, &netting_set).unwrap();
+        let today = netting_set
+            .value(&Scenario::base(&engine(&dates).factors), &ctx)
+            .unwrap();
+
+        // A long option is always an exposure, growing at the risk-free rate
+        // under the risk-neutral drift.
+        for i in 0..2 {
+            let errors = profile
+                .values
+                .iter()
+                .map(|v| (v[i] - profile.expected_exposure[i]).powi(2));
+            let error = (errors.sum::<f64>() / 4_000.0 / 4_000.0).sqrt();
+
+            assert!(
+                (profile.expected_exposure[i] - today * (0.05 * profile.times[i]).exp()).abs()
+                    < 4.0 * error
+            );
+            assert_approx_equal!(profile.expected_negative_exposure[i], 0.0, 1e-15);
+            assert!(profile.potential_future_exposure[i] > profile.expected_exposure[i]);
+        }
+
+        // Nothing is left after the expiry.
+        assert_approx_equal!(profile.expected_exposure[2], 0.0, 1e-15);
+
+        let effective = profile.effective_expected_exposure();
+        assert!(effective.windows(2).all(|w| w[0] <= w[1]));
+        assert_approx_equal!(effective[2], profile.expected_exposure[1], 1e-15);
+        assert!(
+            profile.effective_expected_positive_exposure() >= profile.expected_positive_exposure()
+        );
+    }
+
+    #[test]
+    fn test_collateral_reduces_exposure() {
+        let ctx = PricingContext::new(VALUATION);
+        let dates = [
+            date!(2024 - 04 - 01),
+            date!(2024 - 07 - 01),
+            date!(2024 - 10 - 01),
+        ];
+        let engine = engine(&dates);
+
+        let uncollateralised = engine
+            .run(&ctx, &NettingSet::new(vec![call(100.0)]))
+            .unwrap();
+
+        let csa = |threshold: f64, days: i64| Csa {
+            threshold,
+            minimum_transfer_amount: 0.0,
+            margin_period_of_risk: Duration::days(days),
+        };
+        let run = |csa: Csa| {
+            engine
+                .run(&ctx, &NettingSet::new(vec![call(100.0)]).with_csa(csa))
+                .unwrap()
+        };
+
+        // Margin called on the day: the exposure is capped at the threshold.
+        let capped = run(csa(200.0, 0));
+        for path in &capped.values {
+            assert!(path.iter().all(|&v| v <= 200.0 + 1e-9));
+        }
+
+        // With a margin period of risk, some exposure remains, less than
+        // without collateral.
+        let lagged = run(csa(0.0, 10));
+        for i in 0..dates.len() {
+            assert!(lagged.expected_exposure[i] > 0.0);
+            assert!(lagged.expected_exposure[i] < 0.2 * uncollateralised.expected_exposure[i]);
+        }
+
+        // Without a margin period of risk nor threshold, nothing is exposed.
+        let full = run(csa(0.0, 0));
+        assert!(full.expected_exposure.iter().all(|&ee| ee.abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_initial_margin_profile() {
+        let ctx = PricingContext::new(VALUATION);
+        let dates = [date!(2024 - 04 - 01), date!(2024 - 10 - 01)];
+        let simm = SimmApproximation::new(vec![25.0], DMatrix::identity(1, 1));
+        let margined = engine(&dates).with_initial_margin(simm);
+
+        let run = |positions| margined.run(&ctx, &NettingSet::new(positions)).unwrap();
+
+        // About the risk weight times the delta of a 1% move.
+        let single = run(vec![call(1.0)]);
+        assert!(single.initial_margin[0] > 10.0 && single.initial_margin[0] < 20.0);
+
+        // Initial margin scales with the position, and offsetting positions
+        // need none.
+        let double = run(vec![call(2.0)]);
+        assert_approx_equal!(
+            double.initial_margin[1],
+            2.0 * single.initial_margin[1],
+            1e-9
+        );
+
+        let flat = run(vec![call(1.0), call(-1.0)]);
+        assert!(flat.initial_margin.iter().all(|&im| im.abs() < 1e-9));
+
+        // No initial margin without a model.
+        let plain = engine(&dates).run(&ctx, &NettingSet::new(vec![call(1.0)]));
+        assert!(plain.unwrap().initial_margin.iter().all(|&im| im.abs() < 1e-15));
+    }
+
+    #[test]
+    fn test_minimum_transfer_amount() {
+        let csa = Csa {
+            threshold: 10.0,
+            minimum_transfer_amount: 5.0,
+            margin_period_of_risk: Duration::ZERO,
+        };
+
+        assert_approx_equal!(csa.target(25.0), 15.0, 1e-15);
+        assert_approx_equal!(csa.target(-25.0), -15.0, 1e-15);
+        assert_approx_equal!(csa.target(5.0), 0.0, 1e-15);
+
+        // Calls below the minimum transfer amount are not made.
+        assert_approx_equal!(called(&csa, 0.0, 13.0), 0.0, 1e-15);
+        assert_approx_equal!(called(&csa, 0.0, 16.0), 6.0, 1e-15);
+        assert_approx_equal!(called(&csa, 6.0, 14.0), 6.0, 1e-15);
+    }
+
+    #[test]
+    fn test_exposure_engine_validation() {
+        let factors = vec![RiskFactor::Price {
+            name: "SPX".to_string(),
+            level: 100.0,
+        }];
+        let dates = [date!(2024 - 04 - 01)];
+
+        assert!(
+            ExposureEngine::new(factors.clone(), DMatrix::zeros(2, 2), &dates, 100, 1).is_err()
+        );
+        assert!(
+            ExposureEngine::new(factors.clone(), DMatrix::identity(1, 1), &[], 100, 1).is_err()
+        );
+        assert!(ExposureEngine::new(factors, DMatrix::identity(1, 1), &dates, 1, 1).is_err());
+
+        let late = PricingContext::new(date!(2024 - 05 - 01));
+        assert!(engine(&dates)
+            .run(&late, &NettingSet::new(vec![call(1.0)]))
+            .is_err());
+    }
+}
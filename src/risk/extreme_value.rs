@@ -0,0 +1,481 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Extreme value theory (EVT) tail risk.
+//!
+//! Empirical VaR runs out of data far in the tail, and the normal
+//! distribution understates fat tails. EVT fits the tail alone:
+//!
+//! - peaks over threshold: the excesses $y = L - u$ of the losses over a
+//!   high threshold $u$ follow a generalised Pareto distribution (GPD),
+//!   $F(y) = 1 - (1 + \xi y / \sigma)^{-1 / \xi}$, fitted by maximum
+//!   likelihood. With $N_u$ of $n$ losses above $u$,
+//!   $VaR_c = u + \frac{\sigma}{\xi} \left[ \left( \frac{n}{N_u} (1 - c) \right)^{-\xi} - 1 \right]$
+//!   and $ES_c = \frac{VaR_c + \sigma - \xi u}{1 - \xi}$;
+//! - Hill: for a Pareto-type tail, the tail index from the $k$ largest
+//!   losses is $\xi = \frac{1}{k} \sum_{i=1}^{k} \ln L_{(i)} - \ln L_{(k+1)}$,
+//!   and $VaR_c = L_{(k+1)} \left( \frac{n}{k} (1 - c) \right)^{-\xi}$,
+//!   $ES_c = VaR_c / (1 - \xi)$.
+//!
+//! The estimates are only meaningful beyond the threshold, i.e. for
+//! $1 - c < N_u / n$.
+//!
+//! ```
+//! use RustQuant::risk::*;
+//!
+//! // Losses with a Pareto tail.
+//! let pnl: Vec<f64> = (0..1_000)
+//!     .map(|i| -(1.0 - (i as f64 + 0.5) / 1_000.0).powf(-0.25))
+//!     .collect();
+//!
+//! let tail = PeaksOverThreshold::fit(&pnl, 0.9).unwrap();
+//! let risk = tail.compute(0.999).unwrap();
+//!
+//! assert!((tail.distribution.shape - 0.25).abs() < 0.05);
+//! assert!(risk.expected_shortfall > risk.value_at_risk);
+//! ```
+
+use super::value_at_risk::check_confidence;
+use crate::error::RustQuantError;
+use crate::math::Statistic;
+use crate::risk::RiskEstimate;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Generalised Pareto distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneralisedPareto {
+    /// Shape $\xi$ (the tail index; positive for fat tails).
+    pub shape: f64,
+    /// Scale $\sigma$.
+    pub scale: f64,
+}
+
+/// Peaks-over-threshold fit of the loss tail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeaksOverThreshold {
+    /// Loss threshold $u$.
+    pub threshold: f64,
+    /// Distribution of the excesses over the threshold.
+    pub distribution: GeneralisedPareto,
+    /// Number of observations $n$.
+    pub observations: usize,
+    /// Number of losses above the threshold $N_u$.
+    pub exceedances: usize,
+}
+
+/// Hill estimator of the loss tail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HillEstimator {
+    /// Tail index $\xi$.
+    pub shape: f64,
+    /// The $(k + 1)$-th largest loss $L_{(k+1)}$.
+    pub threshold: f64,
+    /// Number of order statistics $k$ used.
+    pub order_statistics: usize,
+    /// Number of observations $n$.
+    pub observations: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl GeneralisedPareto {
+    /// New distribution.
+    #[must_use]
+    pub const fn new(shape: f64, scale: f64) -> Self {
+        Self { shape, scale }
+    }
+
+    /// Maximum likelihood fit to excesses, with the shape above $-1$ (where
+    /// the likelihood is bounded).
+    ///
+    /// The likelihood is profiled on $\theta = \xi / \sigma$, for which
+    /// $\xi(\theta) = \frac{1}{n} \sum_i \ln(1 + \theta y_i)$, and maximised
+    /// over a grid refined by golden-section search.
+    ///
+    /// # Errors
+    ///
+    /// If there are fewer than three excesses, or an excess is negative or
+    /// not finite, or all excesses are zero.
+    pub fn fit(excesses: &[f64]) -> Result<Self, RustQuantError> {
+        if excesses.len() < 3 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need at least three excesses, got {}",
+                excesses.len()
+            )));
+        }
+        if excesses.iter().any(|y| !(y.is_finite() && *y >= 0.0)) {
+            return Err(RustQuantError::InvalidArgument(
+                "excesses must be finite and non-negative".to_string(),
+            ));
+        }
+
+        let n = excesses.len() as f64;
+        let mean = excesses.iter().sum::<f64>() / n;
+        let max = excesses.iter().copied().fold(0.0, f64::max);
+
+        if mean <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(
+                "excesses are all zero".to_string(),
+            ));
+        }
+
+        // Shape and scale at theta.
+        let parameters = |theta: f64| -> (f64, f64) {
+            match theta.abs() < 1e-12 / max {
+                true => (0.0, mean),
+                false => {
+                    let shape = excesses.iter().map(|y| (theta * y).ln_1p()).sum::<f64>() / n;
+                    (shape, shape / theta)
+                }
+            }
+        };
+
+        // Profile log-likelihood per observation.
+        let likelihood = |theta: f64| -> f64 {
+            let (shape, scale) = parameters(theta);
+
+            match shape > -1.0 && scale > 0.0 {
+                true => -scale.ln() - 1.0 - shape,
+                false => f64::NEG_INFINITY,
+            }
+        };
+
+        // Negative thetas approach -1 / max, positive ones span decades.
+        let mut grid: Vec<f64> = (1..=40).map(|j| -(1.0 - 0.5_f64.powi(j)) / max).collect();
+        grid.reverse();
+        grid.push(0.0);
+        grid.extend((-40..=40).map(|k| 10_f64.powf(f64::from(k) / 10.0) / mean));
+
+        let values: Vec<f64> = grid.iter().map(|&theta| likelihood(theta)).collect();
+        let best = (0..grid.len())
+            .max_by(|&i, &j| values[i].total_cmp(&values[j]))
+            .unwrap_or_default();
+
+        let mut lower = grid[best.saturating_sub(1)];
+        let mut upper = grid[(best + 1).min(grid.len() - 1)];
+
+        // Golden-section search between the neighbours of the best point.
+        let ratio = (5_f64.sqrt() - 1.0) / 2.0;
+        for _ in 0..100 {
+            let left = upper - ratio * (upper - lower);
+            let right = lower + ratio * (upper - lower);
+
+            match likelihood(left) > likelihood(right) {
+                true => upper = right,
+                false => lower = left,
+            }
+        }
+
+        let theta = match likelihood(0.5 * (lower + upper)) > values[best] {
+            true => 0.5 * (lower + upper),
+            false => grid[best],
+        };
+        let (shape, scale) = parameters(theta);
+
+        Ok(Self { shape, scale })
+    }
+
+    /// Cumulative distribution function of an excess.
+    #[must_use]
+    pub fn cdf(&self, excess: f64) -> f64 {
+        let excess = excess.max(0.0);
+
+        match self.shape.abs() < 1e-12 {
+            true => 1.0 - (-excess / self.scale).exp(),
+            false => {
+                1.0 - (1.0 + self.shape * excess / self.scale)
+                    .max(0.0)
+                    .powf(-1.0 / self.shape)
+            }
+        }
+    }
+
+    /// Excess exceeded with probability `tail`.
+    #[must_use]
+    pub fn tail_quantile(&self, tail: f64) -> f64 {
+        match self.shape.abs() < 1e-12 {
+            true => -self.scale * tail.ln(),
+            false => self.scale / self.shape * (tail.powf(-self.shape) - 1.0),
+        }
+    }
+
+    /// Log-likelihood of excesses.
+    #[must_use]
+    pub fn log_likelihood(&self, excesses: &[f64]) -> f64 {
+        excesses
+            .iter()
+            .map(|y| {
+                let z = 1.0 + self.shape * y / self.scale;
+
+                match (z > 0.0, self.shape.abs() < 1e-12) {
+                    (false, _) => f64::NEG_INFINITY,
+                    (true, true) => -self.scale.ln() - y / self.scale,
+                    (true, false) => -self.scale.ln() - (1.0 + 1.0 / self.shape) * z.ln(),
+                }
+            })
+            .sum()
+    }
+}
+
+impl PeaksOverThreshold {
+    /// Fit the losses (negative P&L) above their empirical
+    /// `threshold_quantile`, e.g. `0.95`.
+    ///
+    /// # Errors
+    ///
+    /// If the threshold quantile is not in $(0, 1)$, or the excesses cannot
+    /// be fitted.
+    pub fn fit(pnl: &[f64], threshold_quantile: f64) -> Result<Self, RustQuantError> {
+        check_confidence(threshold_quantile)?;
+
+        if pnl.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "no observations".to_string(),
+            ));
+        }
+
+        let losses: Vec<f64> = pnl.iter().map(|x| -x).collect();
+
+        Self::fit_threshold(pnl, losses.quantile(threshold_quantile))
+    }
+
+    /// Fit the losses (negative P&L) above a loss threshold.
+    ///
+    /// # Errors
+    ///
+    /// If fewer than three losses exceed the threshold, or the excesses
+    /// cannot be fitted.
+    pub fn fit_threshold(pnl: &[f64], threshold: f64) -> Result<Self, RustQuantError> {
+        let excesses: Vec<f64> = pnl
+            .iter()
+            .map(|x| -x - threshold)
+            .filter(|excess| *excess > 0.0)
+            .collect();
+
+        Ok(Self {
+            threshold,
+            distribution: GeneralisedPareto::fit(&excesses)?,
+            observations: pnl.len(),
+            exceedances: excesses.len(),
+        })
+    }
+
+    /// Tail VaR and ES at a confidence level beyond the threshold.
+    ///
+    /// # Errors
+    ///
+    /// If the confidence level is not in $(0, 1)$ or not beyond the
+    /// threshold, or the shape is at least one (infinite ES).
+    pub fn compute(&self, confidence: f64) -> Result<RiskEstimate, RustQuantError> {
+        let tail = tail_probability(confidence, self.exceedances, self.observations)?;
+        let GeneralisedPareto { shape, scale } = self.distribution;
+
+        check_finite_mean(shape)?;
+
+        let value_at_risk = self.threshold + self.distribution.tail_quantile(tail);
+
+        Ok(RiskEstimate {
+            confidence,
+            value_at_risk,
+            expected_shortfall: (value_at_risk + scale - shape * self.threshold) / (1.0 - shape),
+        })
+    }
+}
+
+impl HillEstimator {
+    /// Fit the `order_statistics` largest losses (negative P&L).
+    ///
+    /// # Errors
+    ///
+    /// If `order_statistics` is zero or not less than the number of
+    /// observations, or the $(k + 1)$-th largest loss is not positive.
+    pub fn fit(pnl: &[f64], order_statistics: usize) -> Result<Self, RustQuantError> {
+        if order_statistics == 0 || order_statistics >= pnl.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need between 1 and {} order statistics, got {order_statistics}",
+                pnl.len().saturating_sub(1)
+            )));
+        }
+
+        let mut losses: Vec<f64> = pnl.iter().map(|x| -x).collect();
+        losses.sort_by(|a, b| b.total_cmp(a));
+
+        let threshold = losses[order_statistics];
+        if threshold.is_nan() || threshold <= 0.0 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "the {}-th largest loss must be positive, got {threshold}",
+                order_statistics + 1
+            )));
+        }
+
+        let shape = losses[..order_statistics]
+            .iter()
+            .map(|loss| (loss / threshold).ln())
+            .sum::<f64>()
+            / order_statistics as f64;
+
+        Ok(Self {
+            shape,
+            threshold,
+            order_statistics,
+            observations: pnl.len(),
+        })
+    }
+
+    /// Tail VaR and ES at a confidence level beyond the threshold.
+    ///
+    /// # Errors
+    ///
+    /// If the confidence level is not in $(0, 1)$ or not beyond the
+    /// threshold, or the tail index is at least one (infinite ES).
+    pub fn compute(&self, confidence: f64) -> Result<RiskEstimate, RustQuantError> {
+        let tail = tail_probability(confidence, self.order_statistics, self.observations)?;
+
+        check_finite_mean(self.shape)?;
+
+        let value_at_risk = self.threshold * tail.powf(-self.shape);
+
+        Ok(RiskEstimate {
+            confidence,
+            value_at_risk,
+            expected_shortfall: value_at_risk / (1.0 - self.shape),
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Probability of exceeding the confidence level's VaR given a tail beyond
+// the threshold, $\frac{n}{N_u} (1 - c)$.
+fn tail_probability(
+    confidence: f64,
+    exceedances: usize,
+    observations: usize,
+) -> Result<f64, RustQuantError> {
+    check_confidence(confidence)?;
+
+    let tail = (1.0 - confidence) * observations as f64 / exceedances as f64;
+
+    match tail < 1.0 {
+        true => Ok(tail),
+        false => Err(RustQuantError::InvalidArgument(format!(
+            "confidence level {confidence} is not beyond the threshold"
+        ))),
+    }
+}
+
+// ES is finite only for a shape below one.
+fn check_finite_mean(shape: f64) -> Result<(), RustQuantError> {
+    match shape < 1.0 {
+        true => Ok(()),
+        false => Err(RustQuantError::ComputationError(format!(
+            "tail index {shape} is at least one, so ES is infinite"
+        ))),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_extreme_value {
+    use super::*;
+
+    // Stratified sample of a Pareto loss distribution with tail index
+    // `shape`, P(L > x) = x^{-1 / shape} for x >= 1, as P&L.
+    fn pareto_pnl(n: usize, shape: f64) -> Vec<f64> {
+        (0..n)
+            .map(|i| -(1.0 - (i as f64 + 0.5) / n as f64).powf(-shape))
+            .collect()
+    }
+
+    #[test]
+    fn test_generalised_pareto_fit() {
+        for (shape, scale) in [(0.3, 2.0), (0.0, 1.5), (-0.2, 1.0)] {
+            let gpd = GeneralisedPareto::new(shape, scale);
+            let excesses: Vec<f64> = (0..5_000)
+                .map(|i| gpd.tail_quantile(1.0 - (i as f64 + 0.5) / 5_000.0))
+                .collect();
+
+            let fitted = GeneralisedPareto::fit(&excesses).unwrap();
+            assert_approx_equal!(fitted.shape, shape, 0.02);
+            assert_approx_equal!(fitted.scale, scale, 0.03 * scale);
+
+            // The fit is at least as likely as the true parameters.
+            assert!(fitted.log_likelihood(&excesses) >= gpd.log_likelihood(&excesses) - 1e-6);
+            assert_approx_equal!(gpd.cdf(gpd.tail_quantile(0.01)), 0.99, 1e-12);
+        }
+
+        assert!(GeneralisedPareto::fit(&[1.0, 2.0]).is_err());
+        assert!(GeneralisedPareto::fit(&[1.0, -2.0, 3.0]).is_err());
+        assert!(GeneralisedPareto::fit(&[0.0; 5]).is_err());
+    }
+
+    #[test]
+    fn test_peaks_over_threshold() {
+        let shape = 0.25;
+        let pnl = pareto_pnl(10_000, shape);
+        let tail = PeaksOverThreshold::fit(&pnl, 0.9).unwrap();
+
+        // Excesses of a Pareto over u are GPD with scale shape * u.
+        assert_eq!(tail.observations, 10_000);
+        assert!((990..=1_000).contains(&tail.exceedances));
+        assert_approx_equal!(tail.distribution.shape, shape, 0.02);
+        assert_approx_equal!(tail.distribution.scale, shape * tail.threshold, 0.02);
+
+        let risk = tail.compute(0.999).unwrap();
+        let value_at_risk = 0.001_f64.powf(-shape);
+        assert_approx_equal!(risk.value_at_risk, value_at_risk, 0.03 * value_at_risk);
+        assert_approx_equal!(
+            risk.expected_shortfall,
+            value_at_risk / (1.0 - shape),
+            0.05 * value_at_risk
+        );
+
+        // Not beyond the threshold.
+        assert!(tail.compute(0.8).is_err());
+        assert!(tail.compute(1.0).is_err());
+        assert!(PeaksOverThreshold::fit(&pnl, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_hill_estimator() {
+        let shape = 0.4;
+        let pnl = pareto_pnl(10_000, shape);
+        let hill = HillEstimator::fit(&pnl, 500).unwrap();
+
+        assert_approx_equal!(hill.shape, shape, 0.02);
+
+        let risk = hill.compute(0.9999).unwrap();
+        let value_at_risk = 0.0001_f64.powf(-shape);
+        assert_approx_equal!(risk.value_at_risk, value_at_risk, 0.05 * value_at_risk);
+        assert_approx_equal!(
+            risk.expected_shortfall,
+            risk.value_at_risk / (1.0 - hill.shape),
+            1e-9
+        );
+
+        assert!(hill.compute(0.9).is_err());
+        assert!(HillEstimator::fit(&pnl, 0).is_err());
+        assert!(HillEstimator::fit(&pnl, 10_000).is_err());
+        assert!(HillEstimator::fit(&[1.0, 2.0, 3.0], 1).is_err());
+
+        // Infinite mean tail.
+        let heavy = HillEstimator::fit(&pareto_pnl(10_000, 1.5), 500).unwrap();
+        assert!(heavy.compute(0.999).is_err());
+    }
+}
@@ -0,0 +1,776 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! FRTB standardised approach: the sensitivities-based method (Basel MAR21).
+//!
+//! The capital is computed for the general interest rate (GIRR), equity,
+//! and FX risk classes from [`FrtbSensitivity`] records:
+//!
+//! - delta: the sensitivity to a rate (per unit, for GIRR) or to a relative
+//!   move of the spot ($\partial V / \partial S \cdot S$);
+//! - vega: the sensitivity to the implied volatility times the volatility
+//!   ($\partial V / \partial \sigma \cdot \sigma$);
+//! - curvature: the losses beyond delta under the up and down risk weight
+//!   shocks, $CVR^\pm$.
+//!
+//! Delta and vega are risk weighted, $WS_k = RW_k s_k$, aggregated within
+//! each bucket, $K_b = \sqrt{\max(0, \sum_{k,l} \rho_{kl} WS_k WS_l)}$, and
+//! across buckets, $\sqrt{\sum_b K_b^2 + \sum_{b \neq c} \gamma_{bc} S_b S_c}$
+//! with $S_b = \sum_k WS_k$ (capped at $\pm K_b$ if the sum under the root is
+//! negative). Curvature uses the squared correlations, and only counts pairs
+//! of losses. Every charge is computed under the low, medium, and high
+//! correlation scenarios, and the capital is the largest scenario total.
+//!
+//! ```
+//! use RustQuant::instruments::Greeks;
+//! use RustQuant::risk::*;
+//! use std::collections::HashMap;
+//!
+//! // Greeks bucketed by underlying, e.g. from `Portfolio::greeks_by_underlying`.
+//! let greeks = HashMap::from([
+//!     (
+//!         "AAPL".to_string(),
+//!         Greeks { delta: 800.0, gamma: 12.0, vega: 2_500.0, ..Default::default() },
+//!     ),
+//!     (
+//!         "MSFT".to_string(),
+//!         Greeks { delta: -300.0, gamma: 4.0, vega: 900.0, ..Default::default() },
+//!     ),
+//! ]);
+//! let spots = HashMap::from([("AAPL", 190.0), ("MSFT", 410.0)]);
+//!
+//! let sensitivities: Vec<FrtbSensitivity> = greeks
+//!     .iter()
+//!     .flat_map(|(name, greeks)| {
+//!         let spot = spots[name.as_str()];
+//!         FrtbSensitivity::from_greeks(name, 6, greeks, spot, 0.25, 1.0).unwrap()
+//!     })
+//!     .collect();
+//!
+//! let capital = FrtbCalculator::compute(&sensitivities).unwrap();
+//!
+//! assert!(capital.capital > 0.0);
+//! ```
+
+use crate::error::RustQuantError;
+use crate::instruments::Greeks;
+use std::collections::BTreeMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// FRTB risk class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FrtbRiskClass {
+    /// General interest rate risk: one bucket per currency.
+    Girr,
+    /// Equity risk: buckets 1 to 13 by size, region, and sector.
+    Equity,
+    /// FX risk: one bucket per currency.
+    Fx,
+}
+
+/// FRTB risk measure, with its sensitivity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrtbMeasure {
+    /// Delta sensitivity.
+    Delta(f64),
+    /// Vega sensitivity.
+    Vega(f64),
+    /// Curvature risk under the upward and downward shocks.
+    Curvature {
+        /// $CVR^+$.
+        up: f64,
+        /// $CVR^-$.
+        down: f64,
+    },
+}
+
+/// Sensitivity to one FRTB risk factor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrtbSensitivity {
+    /// Risk class.
+    pub risk_class: FrtbRiskClass,
+    /// Risk measure and sensitivity.
+    pub measure: FrtbMeasure,
+    /// Equity bucket (1 to 13), ignored for GIRR and FX.
+    pub bucket: usize,
+    /// Issuer (equity) or currency (GIRR, FX).
+    pub qualifier: String,
+    /// Tenor (GIRR delta) or option maturity (vega), in years.
+    pub tenor: f64,
+}
+
+/// Correlation scenario.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CorrelationScenario {
+    /// Correlations $\max(2\rho - 1, 0.75\rho)$.
+    Low,
+    /// The prescribed correlations.
+    Medium,
+    /// Correlations $\min(1.25\rho, 1)$.
+    High,
+}
+
+/// FRTB sensitivities-based method calculator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrtbCalculator;
+
+/// Charges of a risk class.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskClassCharge {
+    /// Risk class.
+    pub risk_class: FrtbRiskClass,
+    /// Delta charge.
+    pub delta: f64,
+    /// Vega charge.
+    pub vega: f64,
+    /// Curvature charge.
+    pub curvature: f64,
+}
+
+/// Capital under one correlation scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioCapital {
+    /// Correlation scenario.
+    pub scenario: CorrelationScenario,
+    /// Sum of the charges.
+    pub capital: f64,
+    /// Charges of each risk class.
+    pub charges: Vec<RiskClassCharge>,
+}
+
+/// FRTB SA capital.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrtbCapital {
+    /// Capital: the largest scenario capital.
+    pub capital: f64,
+    /// Scenario giving the capital.
+    pub binding_scenario: CorrelationScenario,
+    /// Capital under each scenario.
+    pub scenarios: Vec<ScenarioCapital>,
+}
+
+// Netted risk factor of a bucket: (issuer, tenor) and its sensitivity.
+type Factors<'a> = BTreeMap<(&'a str, u64), f64>;
+
+// Curvature risks of a bucket: bucket number, and the netted up and down
+// risks of each issuer.
+type CurvatureFactors<'a> = (usize, BTreeMap<&'a str, (f64, f64)>);
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// GIRR delta tenors, in years.
+pub const GIRR_TENORS: [f64; 10] = [0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0, 15.0, 20.0, 30.0];
+
+// GIRR delta risk weights of the tenors.
+const GIRR_RISK_WEIGHTS: [f64; 10] = [
+    0.017, 0.017, 0.016, 0.013, 0.012, 0.011, 0.011, 0.011, 0.011, 0.011,
+];
+
+// Equity spot delta risk weights of buckets 1 to 13.
+const EQUITY_RISK_WEIGHTS: [f64; 13] = [
+    0.55, 0.60, 0.45, 0.55, 0.30, 0.35, 0.40, 0.50, 0.70, 0.50, 0.70, 0.15, 0.25,
+];
+
+// Equity correlation between issuers of buckets 1 to 13.
+const EQUITY_CORRELATIONS: [f64; 13] = [
+    0.15, 0.15, 0.15, 0.15, 0.25, 0.25, 0.25, 0.25, 0.075, 0.125, 0.0, 0.80, 0.80,
+];
+
+// Equity "other sector" bucket, whose risks are added up.
+const EQUITY_OTHER: usize = 11;
+
+impl CorrelationScenario {
+    /// All scenarios.
+    pub const ALL: [CorrelationScenario; 3] = [
+        CorrelationScenario::Low,
+        CorrelationScenario::Medium,
+        CorrelationScenario::High,
+    ];
+
+    /// Correlation under the scenario.
+    #[must_use]
+    pub fn apply(&self, correlation: f64) -> f64 {
+        match self {
+            CorrelationScenario::Low => (2.0 * correlation - 1.0).max(0.75 * correlation),
+            CorrelationScenario::Medium => correlation,
+            CorrelationScenario::High => (1.25 * correlation).min(1.0),
+        }
+    }
+}
+
+impl FrtbSensitivity {
+    /// Delta sensitivity.
+    #[must_use]
+    pub fn delta(
+        risk_class: FrtbRiskClass,
+        bucket: usize,
+        qualifier: &str,
+        tenor: f64,
+        amount: f64,
+    ) -> Self {
+        Self {
+            risk_class,
+            measure: FrtbMeasure::Delta(amount),
+            bucket,
+            qualifier: qualifier.to_string(),
+            tenor,
+        }
+    }
+
+    /// Vega sensitivity for an option maturity.
+    #[must_use]
+    pub fn vega(
+        risk_class: FrtbRiskClass,
+        bucket: usize,
+        qualifier: &str,
+        maturity: f64,
+        amount: f64,
+    ) -> Self {
+        Self {
+            risk_class,
+            measure: FrtbMeasure::Vega(amount),
+            bucket,
+            qualifier: qualifier.to_string(),
+            tenor: maturity,
+        }
+    }
+
+    /// Curvature risk.
+    #[must_use]
+    pub fn curvature(
+        risk_class: FrtbRiskClass,
+        bucket: usize,
+        qualifier: &str,
+        up: f64,
+        down: f64,
+    ) -> Self {
+        Self {
+            risk_class,
+            measure: FrtbMeasure::Curvature { up, down },
+            bucket,
+            qualifier: qualifier.to_string(),
+            tenor: 0.0,
+        }
+    }
+
+    /// Delta, vega, and curvature of an equity position from its Greeks.
+    ///
+    /// The curvature is the gamma loss under the bucket's risk weight shock,
+    /// $CVR^\pm = -\frac{1}{2} \Gamma (RW \cdot S)^2$.
+    ///
+    /// # Errors
+    ///
+    /// If the bucket is not between 1 and 13.
+    pub fn from_greeks(
+        issuer: &str,
+        bucket: usize,
+        greeks: &Greeks,
+        spot: f64,
+        volatility: f64,
+        maturity: f64,
+    ) -> Result<[Self; 3], RustQuantError> {
+        let risk_weight = equity_risk_weight(bucket)?;
+        let loss = -0.5 * greeks.gamma * (risk_weight * spot).powi(2);
+        let equity = FrtbRiskClass::Equity;
+
+        Ok([
+            Self::delta(equity, bucket, issuer, 0.0, greeks.delta * spot),
+            Self::vega(equity, bucket, issuer, maturity, greeks.vega * volatility),
+            Self::curvature(equity, bucket, issuer, loss, loss),
+        ])
+    }
+}
+
+impl FrtbCalculator {
+    /// Capital of a portfolio's sensitivities.
+    ///
+    /// # Errors
+    ///
+    /// If an equity bucket is not between 1 and 13, a GIRR delta tenor is
+    /// not one of [`GIRR_TENORS`], or a maturity is not positive.
+    pub fn compute(sensitivities: &[FrtbSensitivity]) -> Result<FrtbCapital, RustQuantError> {
+        for sensitivity in sensitivities {
+            if sensitivity.risk_class == FrtbRiskClass::Equity {
+                equity_risk_weight(sensitivity.bucket)?;
+            }
+            match sensitivity.measure {
+                FrtbMeasure::Delta(_) if sensitivity.risk_class == FrtbRiskClass::Girr => {
+                    girr_tenor(sensitivity.tenor)?;
+                }
+                FrtbMeasure::Vega(_) if sensitivity.tenor <= 0.0 => {
+                    return Err(RustQuantError::InvalidArgument(format!(
+                        "vega maturity must be positive (got {})",
+                        sensitivity.tenor
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        let mut classes: BTreeMap<FrtbRiskClass, Vec<&FrtbSensitivity>> = BTreeMap::new();
+        for sensitivity in sensitivities {
+            classes
+                .entry(sensitivity.risk_class)
+                .or_default()
+                .push(sensitivity);
+        }
+
+        let scenarios: Vec<ScenarioCapital> = CorrelationScenario::ALL
+            .iter()
+            .map(|&scenario| {
+                let charges: Vec<RiskClassCharge> = classes
+                    .iter()
+                    .map(|(&risk_class, sensitivities)| RiskClassCharge {
+                        risk_class,
+                        delta: linear_charge(risk_class, sensitivities, false, scenario),
+                        vega: linear_charge(risk_class, sensitivities, true, scenario),
+                        curvature: curvature_charge(risk_class, sensitivities, scenario),
+                    })
+                    .collect();
+
+                ScenarioCapital {
+                    scenario,
+                    capital: charges.iter().map(|c| c.delta + c.vega + c.curvature).sum(),
+                    charges,
+                }
+            })
+            .collect();
+
+        let binding = scenarios
+            .iter()
+            .max_by(|a, b| a.capital.total_cmp(&b.capital))
+            .map_or(CorrelationScenario::Medium, |s| s.scenario);
+
+        Ok(FrtbCapital {
+            capital: scenarios.iter().map(|s| s.capital).fold(0.0, f64::max),
+            binding_scenario: binding,
+            scenarios,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Equity spot risk weight of a bucket.
+fn equity_risk_weight(bucket: usize) -> Result<f64, RustQuantError> {
+    match bucket {
+        1..=13 => Ok(EQUITY_RISK_WEIGHTS[bucket - 1]),
+        _ => Err(RustQuantError::InvalidArgument(format!(
+            "equity bucket must be between 1 and 13 (got {bucket})"
+        ))),
+    }
+}
+
+// Index of a GIRR delta tenor.
+fn girr_tenor(tenor: f64) -> Result<usize, RustQuantError> {
+    GIRR_TENORS
+        .iter()
+        .position(|&t| (t - tenor).abs() < 1e-9)
+        .ok_or_else(|| {
+            RustQuantError::InvalidArgument(format!("{tenor} is not a GIRR delta tenor"))
+        })
+}
+
+// Bucket of a sensitivity: the equity bucket, or the currency.
+fn bucket_key(sensitivity: &FrtbSensitivity) -> String {
+    match sensitivity.risk_class {
+        FrtbRiskClass::Equity => sensitivity.bucket.to_string(),
+        _ => sensitivity.qualifier.clone(),
+    }
+}
+
+// Risk factor of a sensitivity within its bucket: the issuer for equity,
+// and the tenor for GIRR delta and all vegas.
+fn factor_key(sensitivity: &FrtbSensitivity, vega: bool) -> (&str, u64) {
+    let issuer = match sensitivity.risk_class {
+        FrtbRiskClass::Equity => sensitivity.qualifier.as_str(),
+        _ => "",
+    };
+    let tenor = match (sensitivity.risk_class, vega) {
+        (FrtbRiskClass::Girr, _) | (_, true) => sensitivity.tenor.to_bits(),
+        _ => 0,
+    };
+
+    (issuer, tenor)
+}
+
+// Risk weight of a delta or vega sensitivity.
+fn risk_weight(risk_class: FrtbRiskClass, bucket: usize, tenor: f64, vega: bool) -> f64 {
+    match (risk_class, vega) {
+        (FrtbRiskClass::Girr, false) => girr_tenor(tenor).map_or(0.0, |i| GIRR_RISK_WEIGHTS[i]),
+        (FrtbRiskClass::Equity, false) => equity_risk_weight(bucket).unwrap_or_default(),
+        (FrtbRiskClass::Fx, false) => 0.15,
+        // Large-cap equity and index buckets.
+        (FrtbRiskClass::Equity, true) if matches!(bucket, 1..=8 | 12 | 13) => 0.7778,
+        (_, true) => 1.0,
+    }
+}
+
+// Correlation between two risk factors of a bucket, before the scenario.
+fn factor_correlation(
+    risk_class: FrtbRiskClass,
+    bucket: usize,
+    (issuer_k, tenor_k): (&str, u64),
+    (issuer_l, tenor_l): (&str, u64),
+    vega: bool,
+) -> f64 {
+    let (t_k, t_l) = (f64::from_bits(tenor_k), f64::from_bits(tenor_l));
+    let ratio = (t_k - t_l).abs() / t_k.min(t_l);
+
+    let name = match (risk_class, issuer_k == issuer_l) {
+        (FrtbRiskClass::Equity, false) => EQUITY_CORRELATIONS[bucket - 1],
+        _ => 1.0,
+    };
+
+    match (risk_class, vega) {
+        (FrtbRiskClass::Girr, false) => (-0.03 * ratio).exp().max(0.4),
+        (_, true) => (name * (-0.01 * ratio).exp()).min(1.0),
+        _ => name,
+    }
+}
+
+// Correlation between two buckets, before the scenario.
+fn bucket_correlation(risk_class: FrtbRiskClass, b: &str, c: &str) -> f64 {
+    match risk_class {
+        FrtbRiskClass::Girr => 0.5,
+        FrtbRiskClass::Fx => 0.6,
+        FrtbRiskClass::Equity => {
+            let (b, c) = (b.parse().unwrap_or(0), c.parse().unwrap_or(0));
+
+            match (b.min(c), b.max(c)) {
+                (_, EQUITY_OTHER) | (EQUITY_OTHER, _) => 0.0,
+                (12, 13) => 0.75,
+                (_, 12 | 13) => 0.45,
+                _ => 0.15,
+            }
+        }
+    }
+}
+
+// Delta or vega charge of a risk class.
+fn linear_charge(
+    risk_class: FrtbRiskClass,
+    sensitivities: &[&FrtbSensitivity],
+    vega: bool,
+    scenario: CorrelationScenario,
+) -> f64 {
+    // Weighted sensitivities, netted by bucket and risk factor.
+    let mut buckets: BTreeMap<String, (usize, Factors)> = BTreeMap::new();
+
+    for sensitivity in sensitivities {
+        let amount = match (sensitivity.measure, vega) {
+            (FrtbMeasure::Delta(amount), false) | (FrtbMeasure::Vega(amount), true) => amount,
+            _ => continue,
+        };
+        let weight = risk_weight(risk_class, sensitivity.bucket, sensitivity.tenor, vega);
+
+        let (_, factors) = buckets
+            .entry(bucket_key(sensitivity))
+            .or_insert((sensitivity.bucket, BTreeMap::new()));
+        *factors.entry(factor_key(sensitivity, vega)).or_default() += weight * amount;
+    }
+
+    let margins: Vec<(String, f64, f64)> = buckets
+        .iter()
+        .map(|(key, (bucket, factors))| {
+            let weighted: Vec<f64> = factors.values().copied().collect();
+            let sum = weighted.iter().sum();
+
+            if risk_class == FrtbRiskClass::Equity && *bucket == EQUITY_OTHER {
+                return (key.clone(), weighted.iter().map(|ws| ws.abs()).sum(), sum);
+            }
+
+            let keys: Vec<(&str, u64)> = factors.keys().copied().collect();
+            let mut variance = 0.0;
+
+            for (k, ws_k) in weighted.iter().enumerate() {
+                for (l, ws_l) in weighted.iter().enumerate() {
+                    let rho = match k == l {
+                        true => 1.0,
+                        false => scenario.apply(factor_correlation(
+                            risk_class, *bucket, keys[k], keys[l], vega,
+                        )),
+                    };
+                    variance += rho * ws_k * ws_l;
+                }
+            }
+
+            (key.clone(), variance.max(0.0).sqrt(), sum)
+        })
+        .collect();
+
+    let gamma = |b: usize, c: usize| {
+        scenario.apply(bucket_correlation(risk_class, &margins[b].0, &margins[c].0))
+    };
+    let radicand = |capped: bool| {
+        let s: Vec<f64> = margins
+            .iter()
+            .map(|(_, k, s)| match capped {
+                true => s.clamp(-k, *k),
+                false => *s,
+            })
+            .collect();
+
+        let mut total: f64 = margins.iter().map(|(_, k, _)| k * k).sum();
+        for b in 0..margins.len() {
+            for c in 0..margins.len() {
+                if b != c {
+                    total += gamma(b, c) * s[b] * s[c];
+                }
+            }
+        }
+        total
+    };
+
+    let total = radicand(false);
+    match total < 0.0 {
+        true => radicand(true).max(0.0).sqrt(),
+        false => total.sqrt(),
+    }
+}
+
+// Curvature charge of a risk class.
+fn curvature_charge(
+    risk_class: FrtbRiskClass,
+    sensitivities: &[&FrtbSensitivity],
+    scenario: CorrelationScenario,
+) -> f64 {
+    // Up and down curvature risks, netted by bucket and risk factor.
+    let mut buckets: BTreeMap<String, CurvatureFactors> = BTreeMap::new();
+
+    for sensitivity in sensitivities {
+        let FrtbMeasure::Curvature { up, down } = sensitivity.measure else {
+            continue;
+        };
+
+        let (issuer, _) = factor_key(sensitivity, false);
+        let (_, factors) = buckets
+            .entry(bucket_key(sensitivity))
+            .or_insert((sensitivity.bucket, BTreeMap::new()));
+        let cvr = factors.entry(issuer).or_default();
+        cvr.0 += up;
+        cvr.1 += down;
+    }
+
+    // Psi: no correlation benefit between two gains.
+    let psi = |a: f64, b: f64| match a < 0.0 && b < 0.0 {
+        true => 0.0,
+        false => 1.0,
+    };
+
+    // Bucket margin and the sum of the risks of the binding direction.
+    let margins: Vec<(String, f64, f64)> = buckets
+        .iter()
+        .map(|(key, (bucket, factors))| {
+            let side = |cvr: Vec<f64>| {
+                let sum: f64 = cvr.iter().sum();
+
+                if risk_class == FrtbRiskClass::Equity && *bucket == EQUITY_OTHER {
+                    return (cvr.iter().map(|x| x.max(0.0)).sum::<f64>(), sum);
+                }
+
+                let issuers: Vec<&str> = factors.keys().copied().collect();
+                let mut variance: f64 = cvr.iter().map(|x| x.max(0.0).powi(2)).sum();
+
+                for (k, x_k) in cvr.iter().enumerate() {
+                    for (l, x_l) in cvr.iter().enumerate() {
+                        if k != l {
+                            let rho = factor_correlation(
+                                risk_class,
+                                *bucket,
+                                (issuers[k], 0),
+                                (issuers[l], 0),
+                                false,
+                            );
+                            variance += scenario.apply(rho * rho) * x_k * x_l * psi(*x_k, *x_l);
+                        }
+                    }
+                }
+
+                (variance.max(0.0).sqrt(), sum)
+            };
+
+            let up = side(factors.values().map(|cvr| cvr.0).collect());
+            let down = side(factors.values().map(|cvr| cvr.1).collect());
+
+            // The direction with the larger margin binds.
+            let (margin, sum) = match up.0 >= down.0 {
+                true => up,
+                false => down,
+            };
+
+            (key.clone(), margin, sum)
+        })
+        .collect();
+
+    let mut total: f64 = margins.iter().map(|(_, k, _)| k * k).sum();
+
+    for (b, (key_b, _, s_b)) in margins.iter().enumerate() {
+        for (c, (key_c, _, s_c)) in margins.iter().enumerate() {
+            if b != c {
+                let gamma = bucket_correlation(risk_class, key_b, key_c);
+                total += scenario.apply(gamma * gamma) * s_b * s_c * psi(*s_b, *s_c);
+            }
+        }
+    }
+
+    total.max(0.0).sqrt()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_frtb {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn equity_delta(issuer: &str, bucket: usize, amount: f64) -> FrtbSensitivity {
+        FrtbSensitivity::delta(FrtbRiskClass::Equity, bucket, issuer, 0.0, amount)
+    }
+
+    fn capital(sensitivities: &[FrtbSensitivity]) -> FrtbCapital {
+        FrtbCalculator::compute(sensitivities).unwrap()
+    }
+
+    #[test]
+    fn test_equity_delta_within_bucket() {
+        // One issuer: the risk weight times the sensitivity, in every scenario.
+        let single = capital(&[equity_delta("A", 1, 1_000.0)]);
+        assert_approx_equal!(single.capital, 550.0, EPS);
+        assert!(single
+            .scenarios
+            .iter()
+            .all(|s| (s.capital - 550.0).abs() < EPS));
+
+        // Two long issuers in bucket 1: the high scenario binds.
+        let (a, b) = (0.55 * 1_000.0, 0.55 * 500.0);
+        let pair = capital(&[equity_delta("A", 1, 1_000.0), equity_delta("B", 1, 500.0)]);
+        let expected = |rho: f64| (a * a + b * b + 2.0 * rho * a * b).sqrt();
+
+        assert_eq!(pair.binding_scenario, CorrelationScenario::High);
+        assert_approx_equal!(pair.capital, expected(0.1875), EPS);
+        assert_approx_equal!(pair.scenarios[0].capital, expected(0.1125), EPS);
+        assert_approx_equal!(pair.scenarios[1].capital, expected(0.15), EPS);
+
+        // A long-short pair: the low scenario binds.
+        let hedge = capital(&[equity_delta("A", 1, 1_000.0), equity_delta("B", 1, -500.0)]);
+        assert_eq!(hedge.binding_scenario, CorrelationScenario::Low);
+        assert_approx_equal!(hedge.capital, expected(-0.1125), EPS);
+    }
+
+    #[test]
+    fn test_equity_delta_across_buckets() {
+        let sensitivities = [equity_delta("A", 1, 1_000.0), equity_delta("B", 5, 2_000.0)];
+        let (k_1, k_5) = (550.0, 600.0);
+
+        let expected = |gamma: f64| (k_1 * k_1 + k_5 * k_5 + 2.0 * gamma * k_1 * k_5).sqrt();
+        assert_approx_equal!(capital(&sensitivities).capital, expected(0.1875), EPS);
+
+        // The other sector bucket adds up absolute risks, uncorrelated with
+        // the other buckets.
+        let other = capital(&[
+            equity_delta("A", 11, 1_000.0),
+            equity_delta("B", 11, -1_000.0),
+            equity_delta("C", 1, 1_000.0),
+        ]);
+        assert_approx_equal!(
+            other.capital,
+            (1_400.0_f64.powi(2) + 550.0_f64.powi(2)).sqrt(),
+            EPS
+        );
+
+        assert!(FrtbCalculator::compute(&[equity_delta("A", 14, 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_girr_delta() {
+        let girr = |currency: &str, tenor: f64, amount: f64| {
+            FrtbSensitivity::delta(FrtbRiskClass::Girr, 0, currency, tenor, amount)
+        };
+
+        let (ws_1, ws_5) = (0.016 * 10_000.0, 0.011 * 10_000.0);
+        let rho = (-0.03_f64 * 4.0).exp();
+        let expected = (ws_1 * ws_1 + ws_5 * ws_5 + 2.0 * rho * ws_1 * ws_5).sqrt();
+
+        let curve = capital(&[girr("USD", 1.0, 10_000.0), girr("USD", 5.0, 10_000.0)]);
+        assert_approx_equal!(curve.scenarios[1].capital, expected, EPS);
+
+        // Two currencies.
+        let two = capital(&[girr("USD", 1.0, 10_000.0), girr("EUR", 1.0, 10_000.0)]);
+        assert_approx_equal!(two.scenarios[1].capital, ws_1 * 3.0_f64.sqrt(), EPS);
+
+        assert!(FrtbCalculator::compute(&[girr("USD", 7.0, 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_curvature() {
+        let curvature = |issuer: &str, up: f64, down: f64| {
+            FrtbSensitivity::curvature(FrtbRiskClass::Equity, 1, issuer, up, down)
+        };
+
+        // The larger direction binds.
+        let single = capital(&[curvature("A", 100.0, -50.0)]);
+        assert_approx_equal!(single.capital, 100.0, EPS);
+
+        // Two gains give no capital.
+        let gains = capital(&[curvature("A", -100.0, -50.0), curvature("B", -20.0, -10.0)]);
+        assert_approx_equal!(gains.capital, 0.0, EPS);
+
+        // Two losses, with the squared correlation.
+        let losses = capital(&[curvature("A", 100.0, 0.0), curvature("B", 50.0, 0.0)]);
+        let rho = 1.25 * 0.15 * 0.15;
+        let expected = (100.0_f64.powi(2) + 50.0_f64.powi(2) + 2.0 * rho * 5_000.0).sqrt();
+        assert_approx_equal!(losses.capital, expected, EPS);
+    }
+
+    #[test]
+    fn test_from_greeks() {
+        let greeks = Greeks {
+            delta: 0.6,
+            gamma: 0.02,
+            vega: 40.0,
+            theta: -5.0,
+            rho: 30.0,
+        };
+
+        let [delta, vega, curvature] =
+            FrtbSensitivity::from_greeks("A", 5, &greeks, 100.0, 0.2, 1.0).unwrap();
+
+        let amount = |measure: FrtbMeasure| match measure {
+            FrtbMeasure::Delta(amount) | FrtbMeasure::Vega(amount) => amount,
+            FrtbMeasure::Curvature { up, down } => up + down,
+        };
+
+        assert_approx_equal!(amount(delta.measure), 60.0, EPS);
+        assert_approx_equal!(amount(vega.measure), 8.0, EPS);
+        assert_approx_equal!(vega.tenor, 1.0, EPS);
+        assert_approx_equal!(amount(curvature.measure), -0.02 * 30.0 * 30.0, EPS);
+
+        // Delta 0.3 x 60, vega 0.7778 x 8, and no curvature for a long
+        // gamma position.
+        let total = capital(&[delta, vega, curvature]);
+        assert_approx_equal!(total.capital, 18.0 + 0.7778 * 8.0, EPS);
+
+        let charge = total.scenarios[1].charges[0];
+        assert_eq!(charge.risk_class, FrtbRiskClass::Equity);
+        assert_approx_equal!(charge.curvature, 0.0, EPS);
+
+        assert!(FrtbSensitivity::from_greeks("A", 0, &greeks, 100.0, 0.2, 1.0).is_err());
+    }
+}
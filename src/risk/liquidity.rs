@@ -0,0 +1,415 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Liquidity-adjusted VaR.
+//!
+//! Positions cannot all be closed in a day, nor at the mid price. Each
+//! position has:
+//!
+//! - a liquidation horizon of $h$ days, over which its daily P&L is scaled
+//!   by $\sqrt{h}$ before the portfolio VaR is computed;
+//! - an exogenous cost of crossing half the bid-ask spread, with the
+//!   relative spread having mean $\mu_s$ and volatility $\sigma_s$
+//!   (Bangia et al.), $\frac{1}{2} |V| (\mu_s + k \sigma_s)$;
+//! - optionally, an endogenous market impact cost from the square-root
+//!   model, where trading $Q$ against a daily volume $ADV$ moves the price
+//!   by a fraction $Y \sigma \sqrt{Q / ADV}$ of itself.
+//!
+//! The liquidity-adjusted VaR is the VaR plus the costs. The
+//! [`SquareRootImpact`] model is also available to the trading backtester
+//! as a slippage model.
+//!
+//! ```
+//! use RustQuant::risk::*;
+//!
+//! let pnl = vec![1.2, -0.8, 0.3, -2.5, 0.9, -1.1, 0.4, 1.7, -0.2, -3.1];
+//!
+//! let position = LiquidityPosition::new("bond", 100.0, pnl)
+//!     .with_spread(BidAskSpread::new(0.01, 0.002))
+//!     .with_liquidation_days(4.0);
+//!
+//! let risk = LiquidityAdjustedVar::new(0.95, VarMethod::Historical)
+//!     .compute(&[position])
+//!     .unwrap();
+//!
+//! assert!(risk.liquidity_adjusted_var > risk.value_at_risk);
+//! ```
+
+use crate::error::RustQuantError;
+use crate::math::distributions::{Distribution, Gaussian};
+use crate::risk::{ValueAtRisk, VarMethod};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Relative bid-ask spread, $(ask - bid) / mid$.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BidAskSpread {
+    /// Mean relative spread.
+    pub mean: f64,
+    /// Volatility of the relative spread.
+    pub volatility: f64,
+}
+
+/// Square-root market impact model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SquareRootImpact {
+    /// Impact coefficient $Y$, of order one.
+    pub coefficient: f64,
+    /// Daily volatility of the price.
+    pub volatility: f64,
+    /// Average daily volume, in the same units as the traded quantities.
+    pub daily_volume: f64,
+}
+
+/// Position with its liquidity characteristics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityPosition {
+    /// Name of the position.
+    pub name: String,
+    /// Market value of the position.
+    pub value: f64,
+    /// Daily P&L history of the position.
+    pub pnl: Vec<f64>,
+    /// Relative bid-ask spread.
+    pub spread: BidAskSpread,
+    /// Days needed to close the position.
+    pub liquidation_days: f64,
+    /// Market impact of closing the position (volume in value terms).
+    pub impact: Option<SquareRootImpact>,
+}
+
+/// Liquidity-adjusted VaR estimator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidityAdjustedVar {
+    /// Estimator of the market VaR.
+    pub estimator: ValueAtRisk,
+    /// Number of spread volatilities $k$ added to the mean spread.
+    pub spread_multiplier: f64,
+}
+
+/// Liquidity costs of a position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionLiquidityCost {
+    /// Name of the position.
+    pub name: String,
+    /// Cost of crossing half the spread.
+    pub spread_cost: f64,
+    /// Market impact cost.
+    pub impact_cost: f64,
+}
+
+/// Liquidity-adjusted VaR of a portfolio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidityAdjustedRisk {
+    /// Market VaR over the liquidation horizons.
+    pub value_at_risk: f64,
+    /// Total spread cost.
+    pub spread_cost: f64,
+    /// Total market impact cost.
+    pub impact_cost: f64,
+    /// VaR plus the liquidity costs.
+    pub liquidity_adjusted_var: f64,
+    /// Costs per position.
+    pub positions: Vec<PositionLiquidityCost>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BidAskSpread {
+    /// New spread.
+    #[must_use]
+    pub const fn new(mean: f64, volatility: f64) -> Self {
+        Self { mean, volatility }
+    }
+
+    /// Mean and volatility of the relative spread of quotes.
+    ///
+    /// # Errors
+    ///
+    /// If there are no quotes, the series have different lengths, or a
+    /// quote is not positive or crossed.
+    pub fn from_quotes(bids: &[f64], asks: &[f64]) -> Result<Self, RustQuantError> {
+        if bids.is_empty() || bids.len() != asks.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "bids and asks must be non-empty and of the same length, got {} and {}",
+                bids.len(),
+                asks.len()
+            )));
+        }
+        if bids.iter().zip(asks).any(|(b, a)| !(*b > 0.0 && a >= b)) {
+            return Err(RustQuantError::InvalidArgument(
+                "quotes must be positive and not crossed".to_string(),
+            ));
+        }
+
+        let spreads: Vec<f64> = bids
+            .iter()
+            .zip(asks)
+            .map(|(b, a)| 2.0 * (a - b) / (a + b))
+            .collect();
+
+        let n = spreads.len() as f64;
+        let mean = spreads.iter().sum::<f64>() / n;
+        let volatility = match spreads.len() {
+            1 => 0.0,
+            _ => (spreads.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt(),
+        };
+
+        Ok(Self { mean, volatility })
+    }
+
+    /// Cost of closing a position of value `value` at $k$ spread
+    /// volatilities above the mean spread.
+    #[must_use]
+    pub fn cost(&self, value: f64, multiplier: f64) -> f64 {
+        0.5 * value.abs() * (self.mean + multiplier * self.volatility).max(0.0)
+    }
+}
+
+impl SquareRootImpact {
+    /// New impact model.
+    #[must_use]
+    pub const fn new(coefficient: f64, volatility: f64, daily_volume: f64) -> Self {
+        Self {
+            coefficient,
+            volatility,
+            daily_volume,
+        }
+    }
+
+    /// Relative price move from trading `quantity` in a day.
+    #[must_use]
+    pub fn impact(&self, quantity: f64) -> f64 {
+        match self.daily_volume > 0.0 {
+            true => {
+                self.coefficient * self.volatility * (quantity.abs() / self.daily_volume).sqrt()
+            }
+            false => 0.0,
+        }
+    }
+
+    /// Execution price of `quantity` units quoted at `price`, moved against
+    /// the trader.
+    #[must_use]
+    pub fn execution_price(&self, quantity: f64, price: f64) -> f64 {
+        price * (1.0 + quantity.signum() * self.impact(quantity))
+    }
+
+    /// Cost of trading `quantity` evenly over `days` days.
+    #[must_use]
+    pub fn cost(&self, quantity: f64, days: f64) -> f64 {
+        let days = days.max(1.0);
+
+        quantity.abs() * self.impact(quantity / days)
+    }
+}
+
+impl LiquidityPosition {
+    /// New position, liquid in a day at no cost.
+    #[must_use]
+    pub fn new(name: &str, value: f64, pnl: Vec<f64>) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+            pnl,
+            spread: BidAskSpread::default(),
+            liquidation_days: 1.0,
+            impact: None,
+        }
+    }
+
+    /// Set the relative bid-ask spread.
+    #[must_use]
+    pub fn with_spread(self, spread: BidAskSpread) -> Self {
+        Self { spread, ..self }
+    }
+
+    /// Set the days needed to close the position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of days is less than one.
+    #[must_use]
+    pub fn with_liquidation_days(self, liquidation_days: f64) -> Self {
+        assert!(liquidation_days >= 1.0, "liquidation horizon below one day");
+
+        Self {
+            liquidation_days,
+            ..self
+        }
+    }
+
+    /// Set the market impact model (with the daily volume in value terms).
+    #[must_use]
+    pub fn with_impact(self, impact: SquareRootImpact) -> Self {
+        Self {
+            impact: Some(impact),
+            ..self
+        }
+    }
+}
+
+impl LiquidityAdjustedVar {
+    /// New estimator, with the spread multiplier $k$ the normal quantile of
+    /// the confidence level.
+    #[must_use]
+    pub fn new(confidence: f64, method: VarMethod) -> Self {
+        let spread_multiplier = match confidence > 0.0 && confidence < 1.0 {
+            true => Gaussian::default().inv_cdf(confidence),
+            false => 0.0,
+        };
+
+        Self {
+            estimator: ValueAtRisk::new(confidence, method),
+            spread_multiplier,
+        }
+    }
+
+    /// Set the spread multiplier $k$.
+    #[must_use]
+    pub const fn with_spread_multiplier(self, spread_multiplier: f64) -> Self {
+        Self {
+            spread_multiplier,
+            ..self
+        }
+    }
+
+    /// Liquidity-adjusted VaR of a portfolio.
+    ///
+    /// # Errors
+    ///
+    /// If there are no positions, their P&L histories have different
+    /// lengths, or the VaR cannot be computed.
+    pub fn compute(
+        &self,
+        positions: &[LiquidityPosition],
+    ) -> Result<LiquidityAdjustedRisk, RustQuantError> {
+        let n = positions.first().map(|p| p.pnl.len()).unwrap_or_default();
+
+        if positions.is_empty() || positions.iter().any(|p| p.pnl.len() != n) {
+            return Err(RustQuantError::InvalidArgument(
+                "positions must have P&L histories of the same length".to_string(),
+            ));
+        }
+
+        // Portfolio P&L with each position held over its liquidation horizon.
+        let mut pnl = vec![0.0; n];
+        for position in positions {
+            let scale = position.liquidation_days.sqrt();
+
+            for (total, p) in pnl.iter_mut().zip(&position.pnl) {
+                *total += scale * p;
+            }
+        }
+
+        let value_at_risk = self.estimator.value_at_risk(&pnl)?;
+
+        let costs: Vec<PositionLiquidityCost> = positions
+            .iter()
+            .map(|position| PositionLiquidityCost {
+                name: position.name.clone(),
+                spread_cost: position.spread.cost(position.value, self.spread_multiplier),
+                impact_cost: position
+                    .impact
+                    .map(|impact| impact.cost(position.value, position.liquidation_days))
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        let spread_cost = costs.iter().map(|c| c.spread_cost).sum::<f64>();
+        let impact_cost = costs.iter().map(|c| c.impact_cost).sum::<f64>();
+
+        Ok(LiquidityAdjustedRisk {
+            value_at_risk,
+            spread_cost,
+            impact_cost,
+            liquidity_adjusted_var: value_at_risk + spread_cost + impact_cost,
+            positions: costs,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_liquidity {
+    use super::*;
+
+    #[test]
+    fn test_spread_and_impact() {
+        let spread = BidAskSpread::from_quotes(&[99.0, 98.0], &[101.0, 102.0]).unwrap();
+        assert_approx_equal!(spread.mean, 0.03, 1e-12);
+        assert_approx_equal!(spread.volatility, 0.01 * 2_f64.sqrt(), 1e-12);
+        assert_approx_equal!(
+            spread.cost(-1_000.0, 1.0),
+            0.5 * 1_000.0 * (0.03 + spread.volatility),
+            1e-12
+        );
+
+        assert!(BidAskSpread::from_quotes(&[101.0], &[99.0]).is_err());
+        assert!(BidAskSpread::from_quotes(&[99.0], &[]).is_err());
+
+        // Trading a quarter of the daily volume moves the price half a
+        // volatility.
+        let impact = SquareRootImpact::new(1.0, 0.02, 400.0);
+        assert_approx_equal!(impact.impact(100.0), 0.01, 1e-12);
+        assert_approx_equal!(impact.execution_price(100.0, 50.0), 50.5, 1e-12);
+        assert_approx_equal!(impact.execution_price(-100.0, 50.0), 49.5, 1e-12);
+
+        // Spreading the trade over four days halves the cost.
+        assert_approx_equal!(impact.cost(100.0, 1.0), 1.0, 1e-12);
+        assert_approx_equal!(impact.cost(100.0, 4.0), 0.5, 1e-12);
+    }
+
+    #[test]
+    fn test_liquidity_adjusted_var() {
+        let pnl: Vec<f64> = (0..100).map(|i| (i as f64 * 0.37).sin()).collect();
+        let estimator = LiquidityAdjustedVar::new(0.99, VarMethod::Historical);
+        assert_approx_equal!(estimator.spread_multiplier, 2.326_347_874, 1e-6);
+
+        let liquid = estimator
+            .compute(&[LiquidityPosition::new("liquid", 100.0, pnl.clone())])
+            .unwrap();
+        assert_approx_equal!(liquid.liquidity_adjusted_var, liquid.value_at_risk, 1e-12);
+
+        let illiquid = LiquidityPosition::new("illiquid", 100.0, pnl.clone())
+            .with_liquidation_days(4.0)
+            .with_spread(BidAskSpread::new(0.01, 0.005))
+            .with_impact(SquareRootImpact::new(1.0, 0.02, 100.0));
+        let risk = estimator.compute(&[illiquid]).unwrap();
+
+        // VaR over four days, plus half the stressed spread, plus impact of
+        // a quarter of the daily volume per day.
+        assert_approx_equal!(risk.value_at_risk, 2.0 * liquid.value_at_risk, 1e-12);
+        assert_approx_equal!(
+            risk.spread_cost,
+            50.0 * (0.01 + 2.326_347_874 * 0.005),
+            1e-6
+        );
+        assert_approx_equal!(risk.impact_cost, 1.0, 1e-12);
+        assert_approx_equal!(
+            risk.liquidity_adjusted_var,
+            risk.value_at_risk + risk.spread_cost + risk.impact_cost,
+            1e-12
+        );
+        assert_eq!(risk.positions[0].name, "illiquid");
+
+        let short = LiquidityPosition::new("short", 100.0, pnl[..10].to_vec());
+        assert!(estimator
+            .compute(&[LiquidityPosition::new("liquid", 100.0, pnl), short])
+            .is_err());
+        assert!(estimator.compute(&[]).is_err());
+    }
+}
@@ -0,0 +1,124 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Risk module.
+//!
+//! ### Value-at-Risk and Expected Shortfall
+//!
+//! - [x] Historical simulation
+//! - [x] Variance-covariance (delta-normal)
+//! - [x] Cornish-Fisher
+//! - [x] Monte Carlo (full revaluation)
+//! - [x] Marginal, component, and incremental VaR and ES
+//! - [x] Extreme value theory (peaks over threshold, Hill)
+//! - [x] Backtesting (Kupiec, Christoffersen, traffic light) and stressed VaR
+//! - [x] Liquidity-adjusted VaR (bid-ask spreads and market impact)
+//!
+//! ### Stress testing
+//!
+//! - [x] Parallel curve shifts, volatility bumps, and spot shocks
+//! - [x] Historical replay
+//! - [x] P&L per scenario and per position
+//!
+//! ### P&L explain
+//!
+//! - [x] Delta, gamma, vega, theta, rates, and residual
+//!
+//! ### Counterparty risk
+//!
+//! - [x] Exposure simulation with collateral (EE, EPE, PFE)
+//! - [x] Credit valuation adjustment, with wrong-way risk
+//! - [x] Funding, margin, and capital valuation adjustments
+//! - [x] ISDA SIMM initial margin from CRIF sensitivities
+//!
+//! ### Regulatory capital
+//!
+//! - [x] FRTB standardised approach (sensitivities-based method)
+//!
+//! ```
+//! use RustQuant::risk::*;
+//!
+//! // Daily P&L of a portfolio.
+//! let pnl = vec![1.2, -0.8, 0.3, -2.5, 0.9, -1.1, 0.4, 1.7, -0.2, -3.1];
+//!
+//! let var = ValueAtRisk::new(0.95, VarMethod::Historical);
+//! let risk = var.compute(&pnl).unwrap();
+//!
+//! assert!(risk.expected_shortfall >= risk.value_at_risk);
+//! ```
+
+/// Value-at-Risk and Expected Shortfall.
+pub mod value_at_risk;
+pub use value_at_risk::*;
+
+/// VaR backtesting and stressed VaR.
+pub mod backtesting;
+pub use backtesting::*;
+
+/// Marginal, component, and incremental risk contributions.
+pub mod contributions;
+pub use contributions::*;
+
+/// Extreme value theory tail risk.
+pub mod extreme_value;
+pub use extreme_value::*;
+
+/// Liquidity-adjusted VaR and market impact.
+pub mod liquidity;
+pub use liquidity::*;
+
+/// ISDA SIMM initial margin.
+pub mod simm;
+pub use simm::*;
+
+/// FRTB standardised approach capital.
+pub mod frtb;
+pub use frtb::*;
+
+/// Market risk factors and scenario generation.
+#[cfg(feature = "curves")]
+pub mod scenarios;
+#[cfg(feature = "curves")]
+pub use scenarios::*;
+
+/// Monte Carlo VaR with full revaluation.
+#[cfg(feature = "curves")]
+pub mod monte_carlo_var;
+#[cfg(feature = "curves")]
+pub use monte_carlo_var::*;
+
+/// Stress scenarios and stress testing.
+#[cfg(feature = "curves")]
+pub mod stress_testing;
+#[cfg(feature = "curves")]
+pub use stress_testing::*;
+
+/// P&L explain between two market snapshots.
+#[cfg(feature = "curves")]
+pub mod pnl_explain;
+#[cfg(feature = "curves")]
+pub use pnl_explain::*;
+
+/// Exposure simulation for counterparty risk.
+#[cfg(feature = "curves")]
+pub mod exposure;
+#[cfg(feature = "curves")]
+pub use exposure::*;
+
+/// Credit valuation adjustment.
+#[cfg(feature = "curves")]
+pub mod cva;
+#[cfg(feature = "curves")]
+pub use cva::*;
+
+/// Funding, margin, and capital valuation adjustments.
+#[cfg(feature = "curves")]
+pub mod xva;
+#[cfg(feature = "curves")]
+pub use xva::*;
@@ -0,0 +1,387 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo VaR with full revaluation.
+//!
+//! Each position builds its instrument from a [`Scenario`], so any
+//! [`Instrument`] can be revalued under shocked market factors. The
+//! portfolio is priced under the base scenario and under each simulated
+//! scenario, and VaR and ES are read off the simulated loss distribution.
+//!
+//! ```
+//! use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+//! use RustQuant::instruments::{Instrument, PricingContext};
+//! use RustQuant::risk::*;
+//! use nalgebra::DMatrix;
+//! use time::macros::date;
+//!
+//! let ctx = PricingContext::new(date!(2024 - 01 - 01));
+//!
+//! let factors = vec![RiskFactor::Price { name: "SPX".to_string(), level: 100.0 }];
+//!
+//! // 1-day shocks with 20% annual volatility.
+//! let generator = ScenarioGenerator::new(
+//!     factors,
+//!     DMatrix::from_element(1, 1, 0.2_f64.powi(2) / 252.0),
+//!     10_000,
+//!     42,
+//! )
+//! .unwrap();
+//!
+//! let call = ScenarioPosition::new("SPX call", 100.0, |scenario: &Scenario| {
+//!     Box::new(BlackScholesMerton::new(
+//!         0.0,
+//!         scenario.level("SPX").unwrap(),
+//!         100.0,
+//!         0.2,
+//!         0.05,
+//!         None,
+//!         date!(2025 - 01 - 01),
+//!         TypeFlag::Call,
+//!     )) as Box<dyn Instrument>
+//! });
+//!
+//! let result = MonteCarloVar::new(generator, 0.99).run(&ctx, &[call]).unwrap();
+//!
+//! assert!(result.risk.value_at_risk > 0.0);
+//! ```
+
+use super::{RiskEstimate, Scenario, ScenarioGenerator, ValueAtRisk, VarMethod};
+use crate::error::RustQuantError;
+use crate::instruments::{Instrument, PricingContext};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Builds an instrument from the market factors of a scenario.
+pub type InstrumentBuilder<'a> = Box<dyn Fn(&Scenario) -> Box<dyn Instrument> + 'a>;
+
+/// A position that can be revalued under a scenario.
+pub struct ScenarioPosition<'a> {
+    /// Name of the position.
+    pub name: String,
+    /// Quantity held.
+    pub quantity: f64,
+    /// Builds the instrument under a scenario.
+    pub instrument: InstrumentBuilder<'a>,
+}
+
+/// Monte Carlo VaR engine.
+#[derive(Debug, Clone)]
+pub struct MonteCarloVar {
+    /// Scenario generator.
+    pub generator: ScenarioGenerator,
+    /// Confidence level, e.g. `0.99`.
+    pub confidence: f64,
+}
+
+/// Contribution of a position to the portfolio risk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarContribution {
+    /// Name of the position.
+    pub name: String,
+    /// Value of the position in the base scenario.
+    pub base_value: f64,
+    /// VaR and ES of the position on its own.
+    pub standalone: RiskEstimate,
+    /// Contribution to the portfolio ES: the position's mean loss in the
+    /// scenarios where the portfolio loss is at or beyond VaR.
+    /// The contributions sum to the portfolio ES.
+    pub expected_shortfall_contribution: f64,
+}
+
+/// Result of a Monte Carlo VaR run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarloVarResult {
+    /// Portfolio value in the base scenario.
+    pub base_value: f64,
+    /// Portfolio loss in each scenario (positive values are losses).
+    pub losses: Vec<f64>,
+    /// Portfolio VaR and ES.
+    pub risk: RiskEstimate,
+    /// Per-position contributions.
+    pub contributions: Vec<VarContribution>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<'a> ScenarioPosition<'a> {
+    /// New position.
+    pub fn new<F>(name: &str, quantity: f64, instrument: F) -> Self
+    where
+        F: Fn(&Scenario) -> Box<dyn Instrument> + 'a,
+    {
+        Self {
+            name: name.to_string(),
+            quantity,
+            instrument: Box::new(instrument),
+        }
+    }
+
+    /// Value of the position under a scenario, in the reporting currency.
+    ///
+    /// # Errors
+    ///
+    /// If the value cannot be converted to the reporting currency.
+    pub fn value(&self, scenario: &Scenario, ctx: &PricingContext) -> Result<f64, RustQuantError> {
+        let instrument = (self.instrument)(scenario);
+
+        Ok(self.quantity * scenario.apply(ctx).value(instrument.as_ref())?)
+    }
+}
+
+impl MonteCarloVar {
+    /// New Monte Carlo VaR engine.
+    #[must_use]
+    pub const fn new(generator: ScenarioGenerator, confidence: f64) -> Self {
+        Self {
+            generator,
+            confidence,
+        }
+    }
+
+    /// Revalue the positions under each scenario and compute VaR, ES, and
+    /// per-position contributions.
+    ///
+    /// # Errors
+    ///
+    /// If the scenarios cannot be generated, a position cannot be valued, or
+    /// the confidence level is not in $(0, 1)$.
+    pub fn run(
+        &self,
+        ctx: &PricingContext,
+        positions: &[ScenarioPosition],
+    ) -> Result<MonteCarloVarResult, RustQuantError> {
+        let scenarios = self.generator.generate()?;
+        let base = Scenario::base(&self.generator.factors);
+
+        let base_values = positions
+            .iter()
+            .map(|position| position.value(&base, ctx))
+            .collect::<Result<Vec<f64>, RustQuantError>>()?;
+
+        // Loss of each position (rows) in each scenario (columns).
+        let position_losses = positions
+            .iter()
+            .zip(&base_values)
+            .map(|(position, base_value)| {
+                scenarios
+                    .iter()
+                    .map(|scenario| Ok(base_value - position.value(scenario, ctx)?))
+                    .collect::<Result<Vec<f64>, RustQuantError>>()
+            })
+            .collect::<Result<Vec<Vec<f64>>, RustQuantError>>()?;
+
+        let losses: Vec<f64> = (0..scenarios.len())
+            .map(|j| position_losses.iter().map(|losses| losses[j]).sum())
+            .collect();
+
+        let estimator = ValueAtRisk::new(self.confidence, VarMethod::Historical);
+        let pnl = |losses: &[f64]| losses.iter().map(|loss| -loss).collect::<Vec<f64>>();
+
+        let risk = estimator.compute(&pnl(&losses))?;
+
+        let tail: Vec<usize> = (0..losses.len())
+            .filter(|&j| losses[j] >= risk.value_at_risk)
+            .collect();
+
+        let contributions = positions
+            .iter()
+            .zip(&base_values)
+            .zip(&position_losses)
+            .map(|((position, base_value), losses)| {
+                Ok(VarContribution {
+                    name: position.name.clone(),
+                    base_value: *base_value,
+                    standalone: estimator.compute(&pnl(losses))?,
+                    expected_shortfall_contribution: tail.iter().map(|&j| losses[j]).sum::<f64>()
+                        / tail.len() as f64,
+                })
+            })
+            .collect::<Result<Vec<VarContribution>, RustQuantError>>()?;
+
+        Ok(MonteCarloVarResult {
+            base_value: base_values.iter().sum(),
+            losses,
+            risk,
+            contributions,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(all(test, feature = "options"))]
+mod tests_monte_carlo_var {
+    use super::*;
+    use crate::data::{Curve, YieldCurve};
+    use crate::instruments::bonds::coupon_bond::CouponBond;
+    use crate::instruments::options::{BlackScholesMerton, TypeFlag};
+    use crate::iso::USD;
+    use crate::risk::RiskFactor;
+    use crate::time::{DateRollingConvention, Frequency};
+    use nalgebra::DMatrix;
+    use std::collections::BTreeMap;
+    use time::macros::date;
+    use time::Duration;
+
+    const EPS: f64 = 1e-9;
+
+    fn call(spot: f64, volatility: f64) -> Box<dyn Instrument> {
+        Box::new(BlackScholesMerton::new(
+            0.0,
+            spot,
+            100.0,
+            volatility,
+            0.05,
+            None,
+            date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        ))
+    }
+
+    fn spot_generator(n_scenarios: usize) -> ScenarioGenerator {
+        let factors = vec![
+            RiskFactor::Price {
+                name: "SPX".to_string(),
+                level: 100.0,
+            },
+            RiskFactor::Volatility {
+                name: "VOL".to_string(),
+                level: 0.2,
+            },
+        ];
+
+        let correlation = DMatrix::from_row_slice(2, 2, &[1.0, -0.7, -0.7, 1.0]);
+
+        ScenarioGenerator::from_correlation(factors, &[0.01, 0.05], &correlation, n_scenarios, 7)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_linear_position_matches_parametric() {
+        let ctx = PricingContext::new(date!(2024 - 01 - 01));
+
+        // A deep in-the-money call with zero volatility behaves like a forward.
+        let forward = ScenarioPosition::new("forward", 10.0, |scenario: &Scenario| {
+            Box::new(BlackScholesMerton::new(
+                0.0,
+                scenario.level("SPX").unwrap(),
+                1.0,
+                1e-8,
+                0.0,
+                None,
+                date!(2025 - 01 - 01),
+                TypeFlag::Call,
+            )) as Box<dyn Instrument>
+        });
+
+        let result = MonteCarloVar::new(spot_generator(50_000), 0.99)
+            .run(&ctx, &[forward])
+            .unwrap();
+
+        // Loss ~ 10 * 100 * (1 - exp(shock)), shock ~ N(0, 0.01^2).
+        let expected = 10.0 * 100.0 * (1.0 - (-0.01_f64 * 2.326_347_874_040_841).exp());
+
+        assert_approx_equal!(result.base_value, 10.0 * 99.0, 1e-6);
+        assert!((result.risk.value_at_risk / expected - 1.0).abs() < 0.03);
+        assert!(result.risk.expected_shortfall > result.risk.value_at_risk);
+    }
+
+    #[test]
+    fn test_contributions() {
+        let ctx = PricingContext::new(date!(2024 - 01 - 01));
+
+        let long_call = ScenarioPosition::new("long call", 100.0, |scenario: &Scenario| {
+            call(
+                scenario.level("SPX").unwrap(),
+                scenario.level("VOL").unwrap(),
+            )
+        });
+        let short_call = ScenarioPosition::new("short call", -40.0, |scenario: &Scenario| {
+            call(
+                scenario.level("SPX").unwrap(),
+                scenario.level("VOL").unwrap(),
+            )
+        });
+
+        let result = MonteCarloVar::new(spot_generator(2_000), 0.95)
+            .run(&ctx, &[long_call, short_call])
+            .unwrap();
+
+        assert_eq!(result.losses.len(), 2_000);
+        assert_eq!(result.contributions.len(), 2);
+
+        let total = result
+            .contributions
+            .iter()
+            .map(|c| c.expected_shortfall_contribution)
+            .sum::<f64>();
+        assert_approx_equal!(total, result.risk.expected_shortfall, EPS);
+
+        // The short position hedges part of the long one.
+        let long = &result.contributions[0];
+        let short = &result.contributions[1];
+        assert!(short.expected_shortfall_contribution < 0.0);
+        assert_approx_equal!(
+            result.risk.value_at_risk,
+            0.6 * long.standalone.value_at_risk,
+            1e-6
+        );
+        assert_approx_equal!(result.base_value, long.base_value + short.base_value, EPS);
+    }
+
+    #[test]
+    fn test_rate_factor() {
+        let t0 = date!(2024 - 01 - 01);
+        let dates = [30, 365, 730, 1095, 1460, 1825, 2190].map(|days| t0 + Duration::days(days));
+        let ctx = PricingContext::new(t0).with_discount_curve(
+            USD,
+            YieldCurve::from_dates_and_rates(&dates, &[0.04; 7]).unwrap(),
+        );
+
+        let generator = ScenarioGenerator::new(
+            vec![RiskFactor::Rate { currency: USD }],
+            DMatrix::from_element(1, 1, 0.001_f64.powi(2)),
+            1_000,
+            3,
+        )
+        .unwrap();
+
+        let bond = ScenarioPosition::new("bond", 1.0, move |_: &Scenario| {
+            let mut bond = CouponBond {
+                evaluation_date: t0,
+                expiration_date: t0 + Duration::days(5 * 365),
+                currency: Some(USD),
+                coupon_rate: 0.04,
+                coupon_frequency: Frequency::Annually,
+                settlement_convention: DateRollingConvention::Actual,
+                yield_curve: YieldCurve::new(BTreeMap::new()),
+                face_value: 100.0,
+                coupons: BTreeMap::new(),
+            };
+            bond.construct_coupons();
+            Box::new(bond) as Box<dyn Instrument>
+        });
+
+        let result = MonteCarloVar::new(generator, 0.99)
+            .run(&ctx, &[bond])
+            .unwrap();
+
+        // Roughly duration (~4.5) * 2.33 * 10bp * price.
+        let approx = 4.5 * 2.326 * 0.001 * result.base_value;
+        assert!(result.risk.value_at_risk > 0.5 * approx);
+        assert!(result.risk.value_at_risk < 1.5 * approx);
+    }
+}
@@ -0,0 +1,483 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! P&L explain (attribution) between two market snapshots.
+//!
+//! A [`MarketSnapshot`] pairs a [`PricingContext`] (valuation date and
+//! curves) with a [`Scenario`] (levels of the risk factors). The value
+//! change of a position between two snapshots is split with a second-order
+//! Taylor expansion in the [`Greeks`] at the first snapshot:
+//!
+//! $$
+//! \Delta V \approx \delta \Delta S + \frac{1}{2} \gamma (\Delta S)^2
+//!     + \nu \Delta \sigma + \theta \Delta t + \rho \Delta r
+//! $$
+//!
+//! and the residual is what the expansion does not explain (cross effects,
+//! higher orders, and moves of the curves that are not rate shifts).
+//!
+//! The Greeks are computed by central finite differences on the position's
+//! instrument builder, so any [`Instrument`](crate::instruments::Instrument)
+//! can be explained.
+//!
+//! ```
+//! use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+//! use RustQuant::instruments::{Instrument, PricingContext};
+//! use RustQuant::risk::*;
+//! use time::macros::date;
+//!
+//! let call = ScenarioPosition::new("call", 10.0, |scenario: &Scenario| {
+//!     Box::new(BlackScholesMerton::new(
+//!         0.0,
+//!         scenario.level("SPX").unwrap(),
+//!         100.0,
+//!         scenario.level("SPX vol").unwrap(),
+//!         0.05,
+//!         None,
+//!         date!(2025 - 01 - 01),
+//!         TypeFlag::Call,
+//!     )) as Box<dyn Instrument>
+//! });
+//!
+//! let position = PnlExplainPosition::new(call)
+//!     .with_spot("SPX")
+//!     .with_volatility("SPX vol");
+//!
+//! let start = MarketSnapshot::new(
+//!     PricingContext::new(date!(2024 - 01 - 01)),
+//!     Scenario::default().with_level("SPX", 100.0).with_level("SPX vol", 0.2),
+//! );
+//! let end = MarketSnapshot::new(
+//!     PricingContext::new(date!(2024 - 01 - 02)),
+//!     Scenario::default().with_level("SPX", 101.0).with_level("SPX vol", 0.21),
+//! );
+//!
+//! let report = pnl_explain(&[position], &start, &end).unwrap();
+//!
+//! assert!(report.total.delta > 0.0);
+//! assert!(report.total.theta < 0.0);
+//! assert!(report.total.residual.abs() < 0.01 * report.total.total.abs());
+//! ```
+
+use super::{Scenario, ScenarioPosition};
+use crate::error::RustQuantError;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::{Greeks, PricingContext};
+use std::iter::Sum;
+use std::ops::Add;
+use time::Duration;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// State of the market: pricing context and risk factor levels.
+#[derive(Clone)]
+pub struct MarketSnapshot {
+    /// Pricing context (valuation date, curves, surfaces, FX).
+    pub context: PricingContext,
+    /// Levels of the risk factors.
+    pub scenario: Scenario,
+}
+
+/// A position together with the risk factors its P&L is explained by.
+pub struct PnlExplainPosition<'a> {
+    /// The position.
+    pub position: ScenarioPosition<'a>,
+    /// Name of the spot price factor.
+    pub spot: Option<String>,
+    /// Name of the volatility factor.
+    pub volatility: Option<String>,
+    /// Currency of the rate shift.
+    pub currency: Option<Currency>,
+}
+
+/// P&L of a position split by risk factor.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PnlAttribution {
+    /// Delta P&L: $\delta \Delta S$.
+    pub delta: f64,
+    /// Gamma P&L: $\frac{1}{2} \gamma (\Delta S)^2$.
+    pub gamma: f64,
+    /// Vega P&L: $\nu \Delta \sigma$.
+    pub vega: f64,
+    /// Theta P&L: $\theta \Delta t$.
+    pub theta: f64,
+    /// Rates P&L: $\rho \Delta r$.
+    pub rates: f64,
+    /// Unexplained P&L.
+    pub residual: f64,
+    /// Actual P&L (full revaluation).
+    pub total: f64,
+}
+
+/// P&L explain of a set of positions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PnlExplainReport {
+    /// Attribution of each position, by name.
+    pub positions: Vec<(String, PnlAttribution)>,
+    /// Attribution of the whole set.
+    pub total: PnlAttribution,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Relative bump of the spot price.
+const SPOT_BUMP: f64 = 1e-4;
+/// Absolute bump of the volatility and the rate.
+const BUMP: f64 = 1e-4;
+
+impl MarketSnapshot {
+    /// New market snapshot.
+    #[must_use]
+    pub const fn new(context: PricingContext, scenario: Scenario) -> Self {
+        Self { context, scenario }
+    }
+}
+
+impl<'a> PnlExplainPosition<'a> {
+    /// New position, with no risk factors.
+    #[must_use]
+    pub const fn new(position: ScenarioPosition<'a>) -> Self {
+        Self {
+            position,
+            spot: None,
+            volatility: None,
+            currency: None,
+        }
+    }
+
+    /// Set the spot price factor.
+    #[must_use]
+    pub fn with_spot(mut self, name: &str) -> Self {
+        self.spot = Some(name.to_string());
+        self
+    }
+
+    /// Set the volatility factor.
+    #[must_use]
+    pub fn with_volatility(mut self, name: &str) -> Self {
+        self.volatility = Some(name.to_string());
+        self
+    }
+
+    /// Set the currency whose rate shift drives the rates P&L.
+    #[must_use]
+    pub const fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Greeks of the position at a snapshot, by central finite differences.
+    ///
+    /// Theta is per year, from a one-day forward difference.
+    ///
+    /// # Errors
+    ///
+    /// - If the snapshot has no level for the spot or volatility factor.
+    /// - If the position cannot be valued.
+    pub fn greeks(&self, snapshot: &MarketSnapshot) -> Result<Greeks, RustQuantError> {
+        let ctx = &snapshot.context;
+        let value = |scenario: &Scenario| self.position.value(scenario, ctx);
+        let base = value(&snapshot.scenario)?;
+
+        let mut greeks = Greeks::default();
+
+        if let Some(spot) = &self.spot {
+            let level = level(&snapshot.scenario, spot)?;
+            let h = SPOT_BUMP * level;
+
+            let up = value(&snapshot.scenario.clone().with_level(spot, level + h))?;
+            let down = value(&snapshot.scenario.clone().with_level(spot, level - h))?;
+
+            greeks.delta = (up - down) / (2.0 * h);
+            greeks.gamma = (up - 2.0 * base + down) / (h * h);
+        }
+
+        if let Some(volatility) = &self.volatility {
+            let level = level(&snapshot.scenario, volatility)?;
+
+            let up = value(
+                &snapshot
+                    .scenario
+                    .clone()
+                    .with_level(volatility, level + BUMP),
+            )?;
+            let down = value(
+                &snapshot
+                    .scenario
+                    .clone()
+                    .with_level(volatility, level - BUMP),
+            )?;
+
+            greeks.vega = (up - down) / (2.0 * BUMP);
+        }
+
+        if let Some(currency) = &self.currency {
+            let shift = snapshot.scenario.rate_shift(currency);
+
+            let up = value(
+                &snapshot
+                    .scenario
+                    .clone()
+                    .with_rate_shift(currency, shift + BUMP),
+            )?;
+            let down = value(
+                &snapshot
+                    .scenario
+                    .clone()
+                    .with_rate_shift(currency, shift - BUMP),
+            )?;
+
+            greeks.rho = (up - down) / (2.0 * BUMP);
+        }
+
+        let mut tomorrow = ctx.clone();
+        tomorrow.valuation_date += Duration::days(1);
+        greeks.theta = (self.position.value(&snapshot.scenario, &tomorrow)? - base) * 365.0;
+
+        Ok(greeks)
+    }
+
+    /// Explain the P&L of the position between two snapshots.
+    ///
+    /// # Errors
+    ///
+    /// - If a snapshot has no level for the spot or volatility factor.
+    /// - If the position cannot be valued.
+    pub fn explain(
+        &self,
+        start: &MarketSnapshot,
+        end: &MarketSnapshot,
+    ) -> Result<PnlAttribution, RustQuantError> {
+        let change = |factor: &Option<String>| -> Result<f64, RustQuantError> {
+            match factor {
+                Some(name) => Ok(level(&end.scenario, name)? - level(&start.scenario, name)?),
+                None => Ok(0.0),
+            }
+        };
+
+        let d_spot = change(&self.spot)?;
+        let d_volatility = change(&self.volatility)?;
+        let d_rate = self.currency.map_or(0.0, |currency| {
+            end.scenario.rate_shift(&currency) - start.scenario.rate_shift(&currency)
+        });
+        let d_time =
+            (end.context.valuation_date - start.context.valuation_date).whole_days() as f64 / 365.0;
+
+        let greeks = self.greeks(start)?;
+
+        let total = self.position.value(&end.scenario, &end.context)?
+            - self.position.value(&start.scenario, &start.context)?;
+
+        let mut attribution = PnlAttribution {
+            delta: greeks.delta * d_spot,
+            gamma: 0.5 * greeks.gamma * d_spot * d_spot,
+            vega: greeks.vega * d_volatility,
+            theta: greeks.theta * d_time,
+            rates: greeks.rho * d_rate,
+            residual: 0.0,
+            total,
+        };
+        attribution.residual = total - attribution.explained();
+
+        Ok(attribution)
+    }
+}
+
+impl PnlAttribution {
+    /// P&L explained by the Greeks (everything but the residual).
+    #[must_use]
+    pub fn explained(&self) -> f64 {
+        self.delta + self.gamma + self.vega + self.theta + self.rates
+    }
+}
+
+impl Add for PnlAttribution {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            delta: self.delta + rhs.delta,
+            gamma: self.gamma + rhs.gamma,
+            vega: self.vega + rhs.vega,
+            theta: self.theta + rhs.theta,
+            rates: self.rates + rhs.rates,
+            residual: self.residual + rhs.residual,
+            total: self.total + rhs.total,
+        }
+    }
+}
+
+impl Sum for PnlAttribution {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Explain the P&L of a set of positions between two snapshots.
+///
+/// # Errors
+///
+/// If the P&L of a position cannot be explained.
+pub fn pnl_explain(
+    positions: &[PnlExplainPosition],
+    start: &MarketSnapshot,
+    end: &MarketSnapshot,
+) -> Result<PnlExplainReport, RustQuantError> {
+    let positions = positions
+        .iter()
+        .map(|position| {
+            Ok((
+                position.position.name.clone(),
+                position.explain(start, end)?,
+            ))
+        })
+        .collect::<Result<Vec<(String, PnlAttribution)>, RustQuantError>>()?;
+
+    Ok(PnlExplainReport {
+        total: positions.iter().map(|(_, attribution)| *attribution).sum(),
+        positions,
+    })
+}
+
+/// Level of a factor in a scenario.
+fn level(scenario: &Scenario, name: &str) -> Result<f64, RustQuantError> {
+    scenario
+        .level(name)
+        .ok_or_else(|| RustQuantError::MissingInput(format!("no level for factor {name}")))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(all(test, feature = "options"))]
+mod tests_pnl_explain {
+    use super::*;
+    use crate::instruments::options::{BlackScholesMerton, TypeFlag};
+    use crate::instruments::Instrument;
+    use crate::iso::USD;
+    use time::macros::date;
+
+    fn call_position(quantity: f64) -> PnlExplainPosition<'static> {
+        let call = ScenarioPosition::new("call", quantity, |s: &Scenario| {
+            Box::new(BlackScholesMerton::new(
+                0.05 + s.rate_shift(&USD),
+                s.level("SPX").unwrap(),
+                100.0,
+                s.level("VOL").unwrap(),
+                0.05 + s.rate_shift(&USD),
+                None,
+                date!(2025 - 01 - 01),
+                TypeFlag::Call,
+            )) as Box<dyn Instrument>
+        });
+
+        PnlExplainPosition::new(call)
+            .with_spot("SPX")
+            .with_volatility("VOL")
+            .with_currency(USD)
+    }
+
+    fn snapshot(date: time::Date, spot: f64, volatility: f64, shift: f64) -> MarketSnapshot {
+        MarketSnapshot::new(
+            PricingContext::new(date),
+            Scenario::default()
+                .with_level("SPX", spot)
+                .with_level("VOL", volatility)
+                .with_rate_shift(&USD, shift),
+        )
+    }
+
+    #[test]
+    fn test_greeks_match_analytic() {
+        let start = snapshot(date!(2024 - 01 - 01), 100.0, 0.2, 0.0);
+        let greeks = call_position(1.0).greeks(&start).unwrap();
+
+        let option = BlackScholesMerton::new(
+            0.05,
+            100.0,
+            100.0,
+            0.2,
+            0.05,
+            Some(date!(2024 - 01 - 01)),
+            date!(2025 - 01 - 01),
+            TypeFlag::Call,
+        );
+
+        assert_approx_equal!(greeks.delta, option.delta(), 1e-6);
+        assert_approx_equal!(greeks.gamma, option.gamma(), 1e-4);
+        assert_approx_equal!(greeks.vega, option.vega(), 1e-4);
+        // The rate shift moves the cost of carry too, which for b = r gives
+        // the same rho.
+        assert_approx_equal!(greeks.rho, option.rho(), 1e-4);
+        assert_approx_equal!(greeks.theta, option.theta(), 0.05);
+    }
+
+    #[test]
+    fn test_single_factor_moves() {
+        let position = call_position(1.0);
+        let start = snapshot(date!(2024 - 01 - 01), 100.0, 0.2, 0.0);
+
+        // Spot only: delta and gamma explain almost everything.
+        let spot = position
+            .explain(&start, &snapshot(date!(2024 - 01 - 01), 101.0, 0.2, 0.0))
+            .unwrap();
+        assert_eq!(spot.vega, 0.0);
+        assert_eq!(spot.theta, 0.0);
+        assert!(spot.residual.abs() < 1e-3);
+
+        // Time only.
+        let time = position
+            .explain(&start, &snapshot(date!(2024 - 01 - 02), 100.0, 0.2, 0.0))
+            .unwrap();
+        assert_eq!(time.delta, 0.0);
+        assert_approx_equal!(time.theta, time.total, 1e-10);
+
+        // Rates only.
+        let rates = position
+            .explain(&start, &snapshot(date!(2024 - 01 - 01), 100.0, 0.2, 0.001))
+            .unwrap();
+        assert!(rates.rates > 0.0);
+        assert!(rates.residual.abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_report_totals() {
+        let positions = [call_position(10.0), call_position(-4.0)];
+        let start = snapshot(date!(2024 - 01 - 01), 100.0, 0.2, 0.0);
+        let end = snapshot(date!(2024 - 01 - 08), 97.0, 0.23, 0.002);
+
+        let report = pnl_explain(&positions, &start, &end).unwrap();
+
+        assert_eq!(report.positions.len(), 2);
+        assert_approx_equal!(
+            report.total.total,
+            report.positions[0].1.total + report.positions[1].1.total,
+            1e-10
+        );
+        assert_approx_equal!(
+            report.total.explained() + report.total.residual,
+            report.total.total,
+            1e-10
+        );
+        // Net long 6 calls.
+        assert_approx_equal!(report.total.delta, 0.6 * report.positions[0].1.delta, 1e-8);
+        assert!(report.total.residual.abs() < 0.05 * report.total.total.abs());
+
+        let missing = MarketSnapshot::new(
+            PricingContext::new(date!(2024 - 01 - 01)),
+            Scenario::default(),
+        );
+        assert!(pnl_explain(&positions, &missing, &end).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Market risk factors and scenarios.
+//!
+//! A [`Scenario`] assigns a shocked level to each [`RiskFactor`]:
+//!
+//! - Prices and volatilities are shocked multiplicatively,
+//!   $x \mapsto x e^{\epsilon}$ (the shock is a log-return).
+//! - Rates are shifted additively, $r \mapsto r + \epsilon$, which moves the
+//!   whole discount curve of the currency in parallel.
+//!
+//! A scenario can also bump the volatility surfaces of the pricing context
+//! additively (see [`Scenario::with_volatility_bump`]).
+//!
+//! The [`ScenarioGenerator`] draws correlated normal shocks from a
+//! covariance matrix (of the shocks over the risk horizon).
+
+use crate::data::YieldCurve;
+use crate::error::RustQuantError;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::PricingContext;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+use std::collections::HashMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market risk factor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskFactor {
+    /// Price of an underlying (equity, commodity, ...), shocked multiplicatively.
+    Price {
+        /// Name of the underlying.
+        name: String,
+        /// Current level.
+        level: f64,
+    },
+    /// Volatility of an underlying, shocked multiplicatively.
+    Volatility {
+        /// Name of the underlying.
+        name: String,
+        /// Current level.
+        level: f64,
+    },
+    /// Parallel shift of a currency's discount curve, shocked additively.
+    Rate {
+        /// Currency of the discount curve.
+        currency: Currency,
+    },
+}
+
+/// Shocked levels of a set of risk factors.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scenario {
+    /// Shocked price and volatility levels, by factor name.
+    levels: HashMap<String, f64>,
+    /// Parallel rate shifts, by ISO 4217 alphabetic currency code.
+    rate_shifts: HashMap<&'static str, f64>,
+    /// Additive bumps to volatility surfaces, by surface name.
+    volatility_bumps: HashMap<String, f64>,
+}
+
+/// Monte Carlo scenario generator: correlated normal shocks to risk factors.
+#[derive(Debug, Clone)]
+pub struct ScenarioGenerator {
+    /// Risk factors.
+    pub factors: Vec<RiskFactor>,
+    /// Covariance matrix of the factor shocks over the risk horizon.
+    pub covariance: DMatrix<f64>,
+    /// Number of scenarios.
+    pub n_scenarios: usize,
+    /// Seed of the random number generator.
+    pub seed: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RiskFactor {
+    /// Name of the factor: the underlying's name, or the currency code for
+    /// rate factors.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Price { name, .. } | Self::Volatility { name, .. } => name,
+            Self::Rate { currency } => currency.code.alphabetic,
+        }
+    }
+}
+
+impl Scenario {
+    /// The base (unshocked) scenario.
+    #[must_use]
+    pub fn base(factors: &[RiskFactor]) -> Self {
+        Self::from_shocks(factors, &vec![0.0; factors.len()])
+    }
+
+    /// Scenario from one shock per factor.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of shocks differs from the number of factors.
+    #[must_use]
+    pub fn from_shocks(factors: &[RiskFactor], shocks: &[f64]) -> Self {
+        assert_eq!(factors.len(), shocks.len(), "one shock per factor");
+
+        let mut scenario = Self::default();
+
+        for (factor, shock) in factors.iter().zip(shocks) {
+            match factor {
+                RiskFactor::Price { name, level } | RiskFactor::Volatility { name, level } => {
+                    scenario.levels.insert(name.clone(), level * shock.exp());
+                }
+                RiskFactor::Rate { currency } => {
+                    scenario
+                        .rate_shifts
+                        .insert(currency.code.alphabetic, *shock);
+                }
+            }
+        }
+
+        scenario
+    }
+
+    /// Set the level of a price or volatility factor.
+    #[must_use]
+    pub fn with_level(mut self, name: &str, level: f64) -> Self {
+        self.levels.insert(name.to_string(), level);
+        self
+    }
+
+    /// Set the parallel shift of a currency's discount curve.
+    #[must_use]
+    pub fn with_rate_shift(mut self, currency: &Currency, shift: f64) -> Self {
+        self.rate_shifts.insert(currency.code.alphabetic, shift);
+        self
+    }
+
+    /// Set the additive bump of a volatility surface of the pricing context.
+    #[must_use]
+    pub fn with_volatility_bump(mut self, surface: &str, bump: f64) -> Self {
+        self.volatility_bumps.insert(surface.to_string(), bump);
+        self
+    }
+
+    /// Shocked level of a price or volatility factor.
+    #[must_use]
+    pub fn level(&self, name: &str) -> Option<f64> {
+        self.levels.get(name).copied()
+    }
+
+    /// Parallel shift of the currency's discount curve (zero if not a factor).
+    #[must_use]
+    pub fn rate_shift(&self, currency: &Currency) -> f64 {
+        self.rate_shifts
+            .get(currency.code.alphabetic)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Additive bump of the named volatility surface (zero if not bumped).
+    #[must_use]
+    pub fn volatility_bump(&self, surface: &str) -> f64 {
+        self.volatility_bumps
+            .get(surface)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Pricing context with the discount curves shifted and the volatility
+    /// surfaces bumped by the scenario.
+    #[must_use]
+    pub fn apply(&self, ctx: &PricingContext) -> PricingContext {
+        let mut shocked = ctx.clone();
+
+        for (code, curve) in &mut shocked.discount_curves {
+            if let Some(shift) = self.rate_shifts.get(code) {
+                *curve = YieldCurve::new(
+                    curve
+                        .rates
+                        .iter()
+                        .map(|(date, rate)| (*date, rate + shift))
+                        .collect(),
+                );
+            }
+        }
+
+        for (name, surface) in &mut shocked.volatility_surfaces {
+            if let Some(bump) = self.volatility_bumps.get(name) {
+                for (_, curve) in &mut surface.volatilities {
+                    for volatility in curve.rates.values_mut() {
+                        *volatility += bump;
+                    }
+                }
+            }
+        }
+
+        shocked
+    }
+}
+
+impl ScenarioGenerator {
+    /// New scenario generator.
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix is not square with one row per factor.
+    pub fn new(
+        factors: Vec<RiskFactor>,
+        covariance: DMatrix<f64>,
+        n_scenarios: usize,
+        seed: u64,
+    ) -> Result<Self, RustQuantError> {
+        let n = factors.len();
+
+        if covariance.shape() != (n, n) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "covariance matrix is {:?}, expected ({n}, {n})",
+                covariance.shape()
+            )));
+        }
+
+        Ok(Self {
+            factors,
+            covariance,
+            n_scenarios,
+            seed,
+        })
+    }
+
+    /// New scenario generator from factor volatilities and a correlation
+    /// matrix, both over the risk horizon.
+    ///
+    /// # Errors
+    ///
+    /// If the dimensions do not match the number of factors.
+    pub fn from_correlation(
+        factors: Vec<RiskFactor>,
+        volatilities: &[f64],
+        correlation: &DMatrix<f64>,
+        n_scenarios: usize,
+        seed: u64,
+    ) -> Result<Self, RustQuantError> {
+        if volatilities.len() != factors.len() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "{} volatilities for {} factors",
+                volatilities.len(),
+                factors.len()
+            )));
+        }
+
+        let vols = DMatrix::from_diagonal(&DVector::from_column_slice(volatilities));
+
+        if correlation.shape() != vols.shape() {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "correlation matrix is {:?}, expected {:?}",
+                correlation.shape(),
+                vols.shape()
+            )));
+        }
+
+        Self::new(factors, &vols * correlation * &vols, n_scenarios, seed)
+    }
+
+    /// Factor shocks, one row per scenario.
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix is not positive definite.
+    pub fn shocks(&self) -> Result<Vec<Vec<f64>>, RustQuantError> {
+        let n = self.factors.len();
+        let cholesky = self.covariance.clone().cholesky().ok_or_else(|| {
+            RustQuantError::ComputationError(
+                "covariance matrix is not positive definite".to_string(),
+            )
+        })?;
+        let lower = cholesky.l();
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+
+        Ok((0..self.n_scenarios)
+            .map(|_| {
+                let z = DVector::from_fn(n, |_, _| StandardNormal.sample(&mut rng));
+                (&lower * z).iter().copied().collect()
+            })
+            .collect())
+    }
+
+    /// Generate the scenarios.
+    ///
+    /// # Errors
+    ///
+    /// If the covariance matrix is not positive definite.
+    pub fn generate(&self) -> Result<Vec<Scenario>, RustQuantError> {
+        Ok(self
+            .shocks()?
+            .iter()
+            .map(|shocks| Scenario::from_shocks(&self.factors, shocks))
+            .collect())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_scenarios {
+    use super::*;
+    use crate::data::Curve;
+    use crate::iso::{EUR, USD};
+    use time::macros::date;
+    use time::Duration;
+
+    const EPS: f64 = 1e-12;
+
+    fn factors() -> Vec<RiskFactor> {
+        vec![
+            RiskFactor::Price {
+                name: "SPX".to_string(),
+                level: 100.0,
+            },
+            RiskFactor::Volatility {
+                name: "SPX".to_string() + " vol",
+                level: 0.2,
+            },
+            RiskFactor::Rate { currency: USD },
+        ]
+    }
+
+    #[test]
+    fn test_scenario_from_shocks() {
+        let scenario = Scenario::from_shocks(&factors(), &[0.1, -0.5, 0.01]);
+
+        assert_approx_equal!(scenario.level("SPX").unwrap(), 100.0 * 0.1_f64.exp(), EPS);
+        assert_approx_equal!(
+            scenario.level("SPX vol").unwrap(),
+            0.2 * (-0.5_f64).exp(),
+            EPS
+        );
+        assert_eq!(scenario.rate_shift(&USD), 0.01);
+        assert_eq!(scenario.rate_shift(&EUR), 0.0);
+        assert_eq!(scenario.level("NDX"), None);
+
+        let base = Scenario::base(&factors());
+        assert_eq!(base.level("SPX"), Some(100.0));
+    }
+
+    #[test]
+    fn test_scenario_apply() {
+        let t0 = date!(2024 - 01 - 01);
+        let dates = [t0, t0 + Duration::days(365)];
+        let ctx = PricingContext::new(t0)
+            .with_discount_curve(
+                USD,
+                YieldCurve::from_dates_and_rates(&dates, &[0.03, 0.04]).unwrap(),
+            )
+            .with_discount_curve(
+                EUR,
+                YieldCurve::from_dates_and_rates(&dates, &[0.02, 0.02]).unwrap(),
+            );
+
+        let scenario = Scenario::from_shocks(&factors(), &[0.0, 0.0, 0.01]);
+        let shocked = scenario.apply(&ctx);
+
+        let usd = shocked.discount_curve(&USD).unwrap();
+        assert_approx_equal!(usd.rates[&dates[1]], 0.05, EPS);
+
+        let eur = shocked.discount_curve(&EUR).unwrap();
+        assert_approx_equal!(eur.rates[&dates[1]], 0.02, EPS);
+    }
+
+    #[test]
+    fn test_scenario_setters() {
+        let scenario = Scenario::default()
+            .with_volatility_bump("SPX", 0.05)
+            .with_level("SPX", 90.0)
+            .with_rate_shift(&USD, -0.01);
+
+        assert_eq!(scenario.volatility_bump("SPX"), 0.05);
+        assert_eq!(scenario.volatility_bump("NDX"), 0.0);
+        assert_eq!(scenario.level("SPX"), Some(90.0));
+        assert_eq!(scenario.rate_shift(&USD), -0.01);
+    }
+
+    #[test]
+    fn test_generator_covariance() {
+        let correlation =
+            DMatrix::from_row_slice(3, 3, &[1.0, -0.5, 0.0, -0.5, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        let generator = ScenarioGenerator::from_correlation(
+            factors(),
+            &[0.02, 0.1, 0.001],
+            &correlation,
+            50_000,
+            1,
+        )
+        .unwrap();
+
+        let shocks = generator.shocks().unwrap();
+        assert_eq!(shocks.len(), 50_000);
+
+        let n = shocks.len() as f64;
+        let var = |i: usize| shocks.iter().map(|s| s[i] * s[i]).sum::<f64>() / n;
+        let cov = shocks.iter().map(|s| s[0] * s[1]).sum::<f64>() / n;
+
+        assert!((var(0).sqrt() / 0.02 - 1.0).abs() < 0.02);
+        assert!((var(2).sqrt() / 0.001 - 1.0).abs() < 0.02);
+        assert!((cov / (0.02 * 0.1) + 0.5).abs() < 0.02);
+
+        // Same seed, same scenarios.
+        assert_eq!(generator.generate().unwrap(), generator.generate().unwrap());
+    }
+
+    #[test]
+    fn test_generator_invalid() {
+        assert!(ScenarioGenerator::new(factors(), DMatrix::identity(2, 2), 10, 1).is_err());
+
+        let singular = DMatrix::from_element(3, 3, 1.0) - DMatrix::identity(3, 3);
+        let generator = ScenarioGenerator::new(factors(), singular, 10, 1).unwrap();
+        assert!(generator.generate().is_err());
+    }
+}
@@ -0,0 +1,1484 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! ISDA SIMM initial margin from CRIF sensitivities.
+//!
+//! Each [`CrifRecord`] is a sensitivity to one risk factor, as in the ISDA
+//! Common Risk Interchange Format: interest rate deltas per currency, tenor,
+//! and sub-curve (per 1bp), credit spread deltas per issuer and tenor (per
+//! 1bp), equity, commodity, and FX deltas per name (per 1% relative shift),
+//! and vegas per option expiry (per volatility point, volatility-weighted
+//! for rates and credit).
+//!
+//! The sensitivities are risk weighted, $WS_k = RW_k s_k$, and aggregated
+//! in the SIMM steps:
+//!
+//! - within each bucket (a currency for rates),
+//!   $K_b = \sqrt{\sum_{k,l} \rho_{kl} WS_k WS_l}$;
+//! - across buckets, with $S_b = \max(\min(\sum_k WS_k, K_b), -K_b)$,
+//!   $\sqrt{\sum_b K_b^2 + \sum_{b \neq c} \gamma_{bc} S_b S_c}$, plus the
+//!   residual bucket's $K$ for credit, equity, and commodity;
+//! - the curvature sensitivities are the vegas scaled by the option expiry,
+//!   $CVR_k = SF(t_k) \sigma_k VR_k$ with $SF(t) = \min(1, 14d / t) / 2$,
+//!   aggregated with the squared correlations into
+//!   $\max(\sum_k CVR_k + \lambda \sqrt{\sum_b K_b^2 + \sum_{b \neq c}
+//!   \gamma_{bc}^2 S_b S_c}, 0)$;
+//! - the delta, vega, and curvature margins are added in each risk class;
+//! - across risk classes, $\sqrt{\sum_{r,s} \psi_{rs} IM_r IM_s}$ for each
+//!   product class;
+//! - the product class margins are added.
+//!
+//! The default [`SimmParameters`] are close to the ISDA SIMM v2.6
+//! calibration, including its interest rate tenor correlations, simplified
+//! to uniform inter-bucket correlations, without base correlation risk or
+//! concentration thresholds. Production use should load the currently
+//! published parameters.
+//!
+//! ```
+//! use RustQuant::risk::*;
+//!
+//! let records = [
+//!     "RatesFX\tRisk_IRCurve\tUSD\t\t5y\tOIS\t1000",
+//!     "RatesFX\tRisk_IRCurve\tUSD\t\t10y\tOIS\t-800",
+//!     "Equity\tRisk_Equity\tSPX\t11\t\t\t50000",
+//! ]
+//! .iter()
+//! .map(|line| line.parse::<CrifRecord>())
+//! .collect::<Result<Vec<_>, _>>()
+//! .unwrap();
+//!
+//! let simm = SimmCalculator::default().compute(&records).unwrap();
+//!
+//! assert!(simm.total > 0.0);
+//! ```
+
+use crate::error::RustQuantError;
+use crate::instruments::Greeks;
+use crate::math::distributions::{Distribution, Gaussian};
+use nalgebra::DMatrix;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// SIMM product class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProductClass {
+    /// Interest rate and FX products.
+    RatesFx,
+    /// Credit products.
+    Credit,
+    /// Equity products.
+    Equity,
+    /// Commodity products.
+    Commodity,
+}
+
+/// SIMM risk class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskClass {
+    /// Interest rate risk.
+    InterestRate,
+    /// Qualifying credit risk.
+    CreditQualifying,
+    /// Non-qualifying credit risk.
+    CreditNonQualifying,
+    /// Equity risk.
+    Equity,
+    /// Commodity risk.
+    Commodity,
+    /// Foreign exchange risk.
+    Fx,
+}
+
+/// CRIF risk type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskType {
+    /// Interest rate delta (`Risk_IRCurve`).
+    IrCurve,
+    /// Interest rate vega (`Risk_IRVol`).
+    IrVol,
+    /// Qualifying credit delta (`Risk_CreditQ`).
+    CreditQ,
+    /// Qualifying credit vega (`Risk_CreditVol`).
+    CreditVol,
+    /// Non-qualifying credit delta (`Risk_CreditNonQ`).
+    CreditNonQ,
+    /// Non-qualifying credit vega (`Risk_CreditVolNonQ`).
+    CreditVolNonQ,
+    /// Equity delta (`Risk_Equity`).
+    Equity,
+    /// Equity vega (`Risk_EquityVol`).
+    EquityVol,
+    /// Commodity delta (`Risk_Commodity`).
+    Commodity,
+    /// Commodity vega (`Risk_CommodityVol`).
+    CommodityVol,
+    /// FX delta (`Risk_FX`).
+    Fx,
+    /// FX vega (`Risk_FXVol`).
+    FxVol,
+}
+
+/// Sensitivity record in the Common Risk Interchange Format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrifRecord {
+    /// Product class of the trade.
+    pub product_class: ProductClass,
+    /// Risk type.
+    pub risk_type: RiskType,
+    /// Currency (rates, FX), issuer (credit), name (equity, commodity), or
+    /// currency pair (FX vega).
+    pub qualifier: String,
+    /// Credit, equity, or commodity bucket, `None` for the residual bucket.
+    pub bucket: Option<usize>,
+    /// Tenor (rates and credit delta) or option expiry (vega).
+    pub label1: String,
+    /// Sub-curve (rates delta), e.g. `OIS` or `Libor3m`.
+    pub label2: String,
+    /// Sensitivity in the calculation currency.
+    pub amount: f64,
+}
+
+/// Risk weights and correlations of the SIMM.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimmParameters {
+    /// Labels of the rates tenors.
+    pub ir_tenors: Vec<String>,
+    /// Rates delta risk weight of each tenor (bp).
+    pub ir_risk_weights: Vec<f64>,
+    /// Correlation between the rates tenors.
+    pub ir_tenor_correlation: DMatrix<f64>,
+    /// Correlation between sub-curves of a currency.
+    pub ir_sub_curve_correlation: f64,
+    /// Correlation between currencies.
+    pub ir_currency_correlation: f64,
+    /// Rates vega risk weight.
+    pub ir_vega_risk_weight: f64,
+    /// Labels of the credit tenors.
+    pub credit_tenors: Vec<String>,
+    /// Qualifying credit delta risk weight of buckets 1 to 12 (bp).
+    pub credit_q_risk_weights: Vec<f64>,
+    /// Qualifying credit delta risk weight of the residual bucket (bp).
+    pub credit_q_residual_risk_weight: f64,
+    /// Correlation between tenors of a qualifying credit issuer.
+    pub credit_q_issuer_correlation: f64,
+    /// Correlation between qualifying credit issuers within a bucket.
+    pub credit_q_correlation: f64,
+    /// Correlation between qualifying credit buckets.
+    pub credit_q_bucket_correlation: f64,
+    /// Qualifying credit vega risk weight.
+    pub credit_q_vega_risk_weight: f64,
+    /// Non-qualifying credit delta risk weight of buckets 1 and 2 (bp).
+    pub credit_non_q_risk_weights: Vec<f64>,
+    /// Non-qualifying credit delta risk weight of the residual bucket (bp).
+    pub credit_non_q_residual_risk_weight: f64,
+    /// Correlation between tenors of a non-qualifying credit issuer.
+    pub credit_non_q_issuer_correlation: f64,
+    /// Correlation between non-qualifying credit issuers within a bucket.
+    pub credit_non_q_correlation: f64,
+    /// Correlation between non-qualifying credit buckets.
+    pub credit_non_q_bucket_correlation: f64,
+    /// Non-qualifying credit vega risk weight.
+    pub credit_non_q_vega_risk_weight: f64,
+    /// Equity delta risk weight of buckets 1 to 12 (%).
+    pub equity_risk_weights: Vec<f64>,
+    /// Correlation between names within equity buckets 1 to 12.
+    pub equity_correlations: Vec<f64>,
+    /// Equity delta risk weight of the residual bucket (%).
+    pub equity_residual_risk_weight: f64,
+    /// Correlation between equity buckets.
+    pub equity_bucket_correlation: f64,
+    /// Equity vega risk weight.
+    pub equity_vega_risk_weight: f64,
+    /// Commodity delta risk weight of buckets 1 to 17 (%).
+    pub commodity_risk_weights: Vec<f64>,
+    /// Correlation between names within commodity buckets 1 to 17.
+    pub commodity_correlations: Vec<f64>,
+    /// Correlation between commodity buckets.
+    pub commodity_bucket_correlation: f64,
+    /// Commodity vega risk weight.
+    pub commodity_vega_risk_weight: f64,
+    /// FX delta risk weight (%).
+    pub fx_risk_weight: f64,
+    /// Correlation between currencies.
+    pub fx_correlation: f64,
+    /// FX vega risk weight.
+    pub fx_vega_risk_weight: f64,
+    /// Correlation between the risk classes, in the order of [`RiskClass`].
+    pub risk_class_correlation: DMatrix<f64>,
+}
+
+/// SIMM calculator.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SimmCalculator {
+    /// Risk weights and correlations.
+    pub parameters: SimmParameters,
+}
+
+/// Margin of a risk class within a product class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskClassMargin {
+    /// Risk class.
+    pub risk_class: RiskClass,
+    /// Delta margin.
+    pub delta: f64,
+    /// Vega margin.
+    pub vega: f64,
+    /// Curvature margin.
+    pub curvature: f64,
+    /// Delta margin of each bucket (currency for rates and FX).
+    pub buckets: BTreeMap<String, f64>,
+}
+
+/// Margin of a product class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductClassMargin {
+    /// Product class.
+    pub product_class: ProductClass,
+    /// Margin, aggregated across the risk classes.
+    pub margin: f64,
+    /// Margin of each risk class.
+    pub risk_classes: Vec<RiskClassMargin>,
+}
+
+/// SIMM initial margin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimmMargin {
+    /// Total initial margin, the sum over the product classes.
+    pub total: f64,
+    /// Margin of each product class.
+    pub product_classes: Vec<ProductClassMargin>,
+}
+
+// Net curvature sensitivities by bucket (and currency, for rates), then risk
+// factor: the tenor for rates, the name otherwise.
+type CurvatureBuckets<'a> = BTreeMap<(Option<usize>, &'a str), BTreeMap<(usize, &'a str), f64>>;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RiskClass {
+    /// All risk classes, in order.
+    pub const ALL: [RiskClass; 6] = [
+        RiskClass::InterestRate,
+        RiskClass::CreditQualifying,
+        RiskClass::CreditNonQualifying,
+        RiskClass::Equity,
+        RiskClass::Commodity,
+        RiskClass::Fx,
+    ];
+}
+
+impl RiskType {
+    /// Risk class of the risk type.
+    #[must_use]
+    pub const fn risk_class(&self) -> RiskClass {
+        match self {
+            RiskType::IrCurve | RiskType::IrVol => RiskClass::InterestRate,
+            RiskType::CreditQ | RiskType::CreditVol => RiskClass::CreditQualifying,
+            RiskType::CreditNonQ | RiskType::CreditVolNonQ => RiskClass::CreditNonQualifying,
+            RiskType::Equity | RiskType::EquityVol => RiskClass::Equity,
+            RiskType::Commodity | RiskType::CommodityVol => RiskClass::Commodity,
+            RiskType::Fx | RiskType::FxVol => RiskClass::Fx,
+        }
+    }
+
+    /// Whether the risk type is a vega.
+    #[must_use]
+    pub const fn is_vega(&self) -> bool {
+        matches!(
+            self,
+            RiskType::IrVol
+                | RiskType::CreditVol
+                | RiskType::CreditVolNonQ
+                | RiskType::EquityVol
+                | RiskType::CommodityVol
+                | RiskType::FxVol
+        )
+    }
+}
+
+impl FromStr for ProductClass {
+    type Err = RustQuantError;
+
+    /// Parse a CRIF product class, e.g. "RatesFX".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "RatesFX" => Ok(ProductClass::RatesFx),
+            "Credit" => Ok(ProductClass::Credit),
+            "Equity" => Ok(ProductClass::Equity),
+            "Commodity" => Ok(ProductClass::Commodity),
+            other => Err(RustQuantError::InvalidArgument(format!(
+                "Invalid product class: {other:?}"
+            ))),
+        }
+    }
+}
+
+impl FromStr for RiskType {
+    type Err = RustQuantError;
+
+    /// Parse a CRIF risk type, e.g. "Risk_IRCurve".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "Risk_IRCurve" => Ok(RiskType::IrCurve),
+            "Risk_IRVol" => Ok(RiskType::IrVol),
+            "Risk_CreditQ" => Ok(RiskType::CreditQ),
+            "Risk_CreditVol" => Ok(RiskType::CreditVol),
+            "Risk_CreditNonQ" => Ok(RiskType::CreditNonQ),
+            "Risk_CreditVolNonQ" => Ok(RiskType::CreditVolNonQ),
+            "Risk_Equity" => Ok(RiskType::Equity),
+            "Risk_EquityVol" => Ok(RiskType::EquityVol),
+            "Risk_Commodity" => Ok(RiskType::Commodity),
+            "Risk_CommodityVol" => Ok(RiskType::CommodityVol),
+            "Risk_FX" => Ok(RiskType::Fx),
+            "Risk_FXVol" => Ok(RiskType::FxVol),
+            other => Err(RustQuantError::InvalidArgument(format!(
+                "Invalid or unsupported risk type: {other:?}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for RiskType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RiskType::IrCurve => "Risk_IRCurve",
+            RiskType::IrVol => "Risk_IRVol",
+            RiskType::CreditQ => "Risk_CreditQ",
+            RiskType::CreditVol => "Risk_CreditVol",
+            RiskType::CreditNonQ => "Risk_CreditNonQ",
+            RiskType::CreditVolNonQ => "Risk_CreditVolNonQ",
+            RiskType::Equity => "Risk_Equity",
+            RiskType::EquityVol => "Risk_EquityVol",
+            RiskType::Commodity => "Risk_Commodity",
+            RiskType::CommodityVol => "Risk_CommodityVol",
+            RiskType::Fx => "Risk_FX",
+            RiskType::FxVol => "Risk_FXVol",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for CrifRecord {
+    type Err = RustQuantError;
+
+    /// Parse a tab- or comma-separated CRIF line with the columns
+    /// `ProductClass, RiskType, Qualifier, Bucket, Label1, Label2, Amount`.
+    /// An empty or "Residual" bucket is the residual bucket.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let separator = match s.contains('\t') {
+            true => '\t',
+            false => ',',
+        };
+        let columns: Vec<&str> = s.split(separator).map(str::trim).collect();
+
+        if columns.len() != 7 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "CRIF line has {} columns, expected 7: {s:?}",
+                columns.len()
+            )));
+        }
+
+        let bucket = match columns[3] {
+            "" | "Residual" => None,
+            bucket => Some(bucket.parse::<usize>().map_err(|_| {
+                RustQuantError::InvalidArgument(format!("Invalid bucket: {bucket:?}"))
+            })?),
+        };
+        let amount = columns[6].parse::<f64>().map_err(|_| {
+            RustQuantError::InvalidArgument(format!("Invalid amount: {:?}", columns[6]))
+        })?;
+
+        Ok(Self {
+            product_class: columns[0].parse()?,
+            risk_type: columns[1].parse()?,
+            qualifier: columns[2].to_string(),
+            bucket,
+            label1: columns[4].to_string(),
+            label2: columns[5].to_string(),
+            amount,
+        })
+    }
+}
+
+impl CrifRecord {
+    /// New record, without labels.
+    #[must_use]
+    pub fn new(
+        product_class: ProductClass,
+        risk_type: RiskType,
+        qualifier: &str,
+        bucket: Option<usize>,
+        amount: f64,
+    ) -> Self {
+        Self {
+            product_class,
+            risk_type,
+            qualifier: qualifier.to_string(),
+            bucket,
+            label1: String::new(),
+            label2: String::new(),
+            amount,
+        }
+    }
+
+    /// Set the labels (tenor or expiry, and sub-curve).
+    #[must_use]
+    pub fn with_labels(self, label1: &str, label2: &str) -> Self {
+        Self {
+            label1: label1.to_string(),
+            label2: label2.to_string(),
+            ..self
+        }
+    }
+
+    /// Delta and vega records of an equity, commodity, or FX position from
+    /// its Greeks (per unit of spot and of volatility): the change in value
+    /// for a 1% move of the spot, and for a volatility point. The vega is
+    /// labelled with the option expiry, e.g. "1y", for its curvature.
+    ///
+    /// # Errors
+    ///
+    /// If the risk class is interest rates or credit, whose sensitivities
+    /// are per tenor rather than Greeks.
+    pub fn from_greeks(
+        risk_class: RiskClass,
+        qualifier: &str,
+        bucket: Option<usize>,
+        greeks: &Greeks,
+        spot: f64,
+        expiry: &str,
+    ) -> Result<[Self; 2], RustQuantError> {
+        let (product_class, delta, vega) = match risk_class {
+            RiskClass::Equity => (ProductClass::Equity, RiskType::Equity, RiskType::EquityVol),
+            RiskClass::Commodity => (
+                ProductClass::Commodity,
+                RiskType::Commodity,
+                RiskType::CommodityVol,
+            ),
+            RiskClass::Fx => (ProductClass::RatesFx, RiskType::Fx, RiskType::FxVol),
+            RiskClass::InterestRate
+            | RiskClass::CreditQualifying
+            | RiskClass::CreditNonQualifying => {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "{risk_class:?} sensitivities are per tenor, not Greeks"
+                )))
+            }
+        };
+
+        Ok([
+            Self::new(
+                product_class,
+                delta,
+                qualifier,
+                bucket,
+                0.01 * greeks.delta * spot,
+            ),
+            Self::new(product_class, vega, qualifier, bucket, 0.01 * greeks.vega)
+                .with_labels(expiry, ""),
+        ])
+    }
+}
+
+impl Default for SimmParameters {
+    fn default() -> Self {
+        let tenors = [
+            "2w", "1m", "3m", "6m", "1y", "2y", "3y", "5y", "10y", "15y", "20y", "30y",
+        ];
+
+        // ISDA SIMM v2.6 interest rate tenor correlations.
+        #[rustfmt::skip]
+        let rho = [
+            [1.00, 0.77, 0.67, 0.59, 0.48, 0.39, 0.34, 0.30, 0.25, 0.23, 0.21, 0.20],
+            [0.77, 1.00, 0.84, 0.74, 0.56, 0.43, 0.36, 0.31, 0.26, 0.21, 0.19, 0.19],
+            [0.67, 0.84, 1.00, 0.88, 0.69, 0.55, 0.47, 0.40, 0.34, 0.27, 0.25, 0.25],
+            [0.59, 0.74, 0.88, 1.00, 0.86, 0.73, 0.65, 0.57, 0.49, 0.40, 0.38, 0.37],
+            [0.48, 0.56, 0.69, 0.86, 1.00, 0.94, 0.87, 0.79, 0.68, 0.60, 0.57, 0.55],
+            [0.39, 0.43, 0.55, 0.73, 0.94, 1.00, 0.96, 0.91, 0.80, 0.74, 0.70, 0.69],
+            [0.34, 0.36, 0.47, 0.65, 0.87, 0.96, 1.00, 0.97, 0.88, 0.81, 0.77, 0.76],
+            [0.30, 0.31, 0.40, 0.57, 0.79, 0.91, 0.97, 1.00, 0.95, 0.90, 0.86, 0.85],
+            [0.25, 0.26, 0.34, 0.49, 0.68, 0.80, 0.88, 0.95, 1.00, 0.97, 0.94, 0.94],
+            [0.23, 0.21, 0.27, 0.40, 0.60, 0.74, 0.81, 0.90, 0.97, 1.00, 0.98, 0.97],
+            [0.21, 0.19, 0.25, 0.38, 0.57, 0.70, 0.77, 0.86, 0.94, 0.98, 1.00, 0.99],
+            [0.20, 0.19, 0.25, 0.37, 0.55, 0.69, 0.76, 0.85, 0.94, 0.97, 0.99, 1.00],
+        ];
+
+        let psi = [
+            [1.0, 0.04, 0.04, 0.29, 0.13, 0.28],
+            [0.04, 1.0, 0.54, 0.70, 0.27, 0.37],
+            [0.04, 0.54, 1.0, 0.46, 0.24, 0.15],
+            [0.29, 0.70, 0.46, 1.0, 0.37, 0.35],
+            [0.13, 0.27, 0.24, 0.37, 1.0, 0.39],
+            [0.28, 0.37, 0.15, 0.35, 0.39, 1.0],
+        ];
+
+        Self {
+            ir_tenors: tenors.iter().map(|label| label.to_string()).collect(),
+            ir_risk_weights: vec![
+                109.0, 105.0, 90.0, 71.0, 66.0, 66.0, 64.0, 60.0, 60.0, 61.0, 61.0, 67.0,
+            ],
+            ir_tenor_correlation: DMatrix::from_fn(12, 12, |i, j| rho[i][j]),
+            ir_sub_curve_correlation: 0.993,
+            ir_currency_correlation: 0.32,
+            ir_vega_risk_weight: 0.18,
+            credit_tenors: ["1y", "2y", "3y", "5y", "10y"]
+                .iter()
+                .map(|label| label.to_string())
+                .collect(),
+            credit_q_risk_weights: vec![
+                75.0, 90.0, 84.0, 54.0, 62.0, 48.0, 185.0, 343.0, 255.0, 250.0, 214.0, 173.0,
+            ],
+            credit_q_residual_risk_weight: 343.0,
+            credit_q_issuer_correlation: 0.93,
+            credit_q_correlation: 0.46,
+            credit_q_bucket_correlation: 0.4,
+            credit_q_vega_risk_weight: 0.74,
+            credit_non_q_risk_weights: vec![280.0, 1_300.0],
+            credit_non_q_residual_risk_weight: 1_300.0,
+            credit_non_q_issuer_correlation: 0.83,
+            credit_non_q_correlation: 0.32,
+            credit_non_q_bucket_correlation: 0.43,
+            credit_non_q_vega_risk_weight: 0.74,
+            equity_risk_weights: vec![
+                30.0, 33.0, 36.0, 29.0, 26.0, 25.0, 34.0, 28.0, 36.0, 50.0, 19.0, 19.0,
+            ],
+            equity_correlations: vec![
+                0.18, 0.20, 0.28, 0.24, 0.25, 0.36, 0.35, 0.37, 0.23, 0.27, 0.45, 0.45,
+            ],
+            equity_residual_risk_weight: 50.0,
+            equity_bucket_correlation: 0.2,
+            equity_vega_risk_weight: 0.45,
+            commodity_risk_weights: vec![
+                48.0, 29.0, 33.0, 25.0, 35.0, 30.0, 60.0, 52.0, 68.0, 63.0, 21.0, 21.0, 15.0, 16.0,
+                13.0, 58.0, 17.0,
+            ],
+            commodity_correlations: vec![
+                0.84, 0.98, 0.95, 0.96, 0.94, 0.87, 0.96, 0.65, 0.96, 0.22, 0.70, 0.61, 0.51, 0.21,
+                0.30, 0.00, 0.69,
+            ],
+            commodity_bucket_correlation: 0.2,
+            commodity_vega_risk_weight: 0.74,
+            fx_risk_weight: 7.4,
+            fx_correlation: 0.5,
+            fx_vega_risk_weight: 0.47,
+            risk_class_correlation: DMatrix::from_fn(6, 6, |i, j| psi[i][j]),
+        }
+    }
+}
+
+impl SimmCalculator {
+    /// New calculator with the given parameters.
+    #[must_use]
+    pub const fn new(parameters: SimmParameters) -> Self {
+        Self { parameters }
+    }
+
+    /// Initial margin of a portfolio's sensitivities.
+    ///
+    /// # Errors
+    ///
+    /// If a record has an unknown rates or credit tenor, a credit, equity, or
+    /// commodity bucket out of range, or a vega has no valid option expiry.
+    pub fn compute(&self, records: &[CrifRecord]) -> Result<SimmMargin, RustQuantError> {
+        let mut products: BTreeMap<ProductClass, Vec<&CrifRecord>> = BTreeMap::new();
+
+        for record in records {
+            products
+                .entry(record.product_class)
+                .or_default()
+                .push(record);
+        }
+
+        let product_classes = products
+            .into_iter()
+            .map(|(product_class, records)| {
+                let risk_classes = RiskClass::ALL
+                    .iter()
+                    .filter_map(|&risk_class| {
+                        let records: Vec<&CrifRecord> = records
+                            .iter()
+                            .copied()
+                            .filter(|record| record.risk_type.risk_class() == risk_class)
+                            .collect();
+
+                        match records.is_empty() {
+                            true => None,
+                            false => Some(self.risk_class_margin(risk_class, &records)),
+                        }
+                    })
+                    .collect::<Result<Vec<RiskClassMargin>, RustQuantError>>()?;
+
+                let psi = &self.parameters.risk_class_correlation;
+
+                let mut variance = 0.0;
+                for r in &risk_classes {
+                    for s in &risk_classes {
+                        let correlation = psi[(r.risk_class as usize, s.risk_class as usize)];
+                        variance += correlation * r.margin() * s.margin();
+                    }
+                }
+
+                Ok(ProductClassMargin {
+                    product_class,
+                    margin: variance.max(0.0).sqrt(),
+                    risk_classes,
+                })
+            })
+            .collect::<Result<Vec<ProductClassMargin>, RustQuantError>>()?;
+
+        Ok(SimmMargin {
+            total: product_classes.iter().map(|product| product.margin).sum(),
+            product_classes,
+        })
+    }
+
+    // Delta, vega, and curvature margins of the records of one risk class.
+    fn risk_class_margin(
+        &self,
+        risk_class: RiskClass,
+        records: &[&CrifRecord],
+    ) -> Result<RiskClassMargin, RustQuantError> {
+        let (deltas, vegas): (Vec<&CrifRecord>, Vec<&CrifRecord>) = records
+            .iter()
+            .copied()
+            .partition(|record| !record.risk_type.is_vega());
+
+        let (delta, buckets) = match risk_class {
+            RiskClass::InterestRate => self.interest_rate(&deltas, false)?,
+            RiskClass::CreditQualifying | RiskClass::CreditNonQualifying => {
+                self.credit(risk_class, &deltas, false)?
+            }
+            RiskClass::Equity | RiskClass::Commodity => {
+                self.bucketed(risk_class, &deltas, false)?
+            }
+            RiskClass::Fx => self.fx(&deltas, false),
+        };
+        let (vega, _) = match risk_class {
+            RiskClass::InterestRate => self.interest_rate(&vegas, true)?,
+            RiskClass::CreditQualifying | RiskClass::CreditNonQualifying => {
+                self.credit(risk_class, &vegas, true)?
+            }
+            RiskClass::Equity | RiskClass::Commodity => self.bucketed(risk_class, &vegas, true)?,
+            RiskClass::Fx => self.fx(&vegas, true),
+        };
+        let curvature = self.curvature(risk_class, &vegas)?;
+
+        Ok(RiskClassMargin {
+            risk_class,
+            delta,
+            vega,
+            curvature,
+            buckets,
+        })
+    }
+
+    // Rates margin: currencies are the buckets, tenors (and sub-curves for
+    // delta) the risk factors.
+    fn interest_rate(
+        &self,
+        records: &[&CrifRecord],
+        vega: bool,
+    ) -> Result<(f64, BTreeMap<String, f64>), RustQuantError> {
+        let p = &self.parameters;
+
+        // Net sensitivity by currency, then (tenor, sub-curve).
+        let mut currencies: BTreeMap<&str, BTreeMap<(usize, &str), f64>> = BTreeMap::new();
+
+        for record in records {
+            let tenor = p
+                .ir_tenors
+                .iter()
+                .position(|tenor| tenor.eq_ignore_ascii_case(&record.label1))
+                .ok_or_else(|| {
+                    RustQuantError::InvalidArgument(format!(
+                        "unknown rates tenor {:?}",
+                        record.label1
+                    ))
+                })?;
+            let sub_curve = match vega {
+                true => "",
+                false => record.label2.as_str(),
+            };
+
+            *currencies
+                .entry(record.qualifier.as_str())
+                .or_default()
+                .entry((tenor, sub_curve))
+                .or_default() += record.amount;
+        }
+
+        let buckets: Vec<(String, (f64, f64))> = currencies
+            .into_iter()
+            .map(|(currency, sensitivities)| {
+                let factors: Vec<(usize, &str)> = sensitivities.keys().copied().collect();
+                let weighted: Vec<f64> = sensitivities
+                    .iter()
+                    .map(|(&(tenor, _), amount)| match vega {
+                        true => p.ir_vega_risk_weight * amount,
+                        false => p.ir_risk_weights[tenor] * amount,
+                    })
+                    .collect();
+
+                let margin = bucket_margin(&weighted, |k, l| {
+                    let (tenor_k, curve_k) = factors[k];
+                    let (tenor_l, curve_l) = factors[l];
+
+                    let sub_curve = match curve_k == curve_l {
+                        true => 1.0,
+                        false => p.ir_sub_curve_correlation,
+                    };
+                    p.ir_tenor_correlation[(tenor_k, tenor_l)] * sub_curve
+                });
+
+                (currency.to_string(), margin)
+            })
+            .collect();
+
+        let margins: Vec<(f64, f64)> = buckets.iter().map(|(_, margin)| *margin).collect();
+        let total = across_buckets(&margins, |_, _| p.ir_currency_correlation);
+
+        Ok((
+            total,
+            buckets.into_iter().map(|(b, (k, _))| (b, k)).collect(),
+        ))
+    }
+
+    // Credit margin: issuers in numbered buckets, with a sensitivity per
+    // tenor (or expiry, for vega), and a residual bucket added on top.
+    fn credit(
+        &self,
+        risk_class: RiskClass,
+        records: &[&CrifRecord],
+        vega: bool,
+    ) -> Result<(f64, BTreeMap<String, f64>), RustQuantError> {
+        let p = &self.parameters;
+
+        let (
+            weights,
+            residual_weight,
+            issuer_correlation,
+            correlation,
+            bucket_correlation,
+            vega_weight,
+        ) = match risk_class {
+            RiskClass::CreditQualifying => (
+                &p.credit_q_risk_weights,
+                p.credit_q_residual_risk_weight,
+                p.credit_q_issuer_correlation,
+                p.credit_q_correlation,
+                p.credit_q_bucket_correlation,
+                p.credit_q_vega_risk_weight,
+            ),
+            _ => (
+                &p.credit_non_q_risk_weights,
+                p.credit_non_q_residual_risk_weight,
+                p.credit_non_q_issuer_correlation,
+                p.credit_non_q_correlation,
+                p.credit_non_q_bucket_correlation,
+                p.credit_non_q_vega_risk_weight,
+            ),
+        };
+
+        // Net sensitivity by bucket, then (issuer, tenor).
+        let mut buckets: BTreeMap<Option<usize>, BTreeMap<(&str, usize), f64>> = BTreeMap::new();
+
+        for record in records {
+            if let Some(bucket) = record.bucket {
+                if bucket == 0 || bucket > weights.len() {
+                    return Err(RustQuantError::InvalidArgument(format!(
+                        "{risk_class:?} bucket {bucket} out of range"
+                    )));
+                }
+            }
+            let tenor = p
+                .credit_tenors
+                .iter()
+                .position(|tenor| tenor.eq_ignore_ascii_case(&record.label1))
+                .ok_or_else(|| {
+                    RustQuantError::InvalidArgument(format!(
+                        "unknown credit tenor {:?}",
+                        record.label1
+                    ))
+                })?;
+
+            *buckets
+                .entry(record.bucket)
+                .or_default()
+                .entry((record.qualifier.as_str(), tenor))
+                .or_default() += record.amount;
+        }
+
+        let mut margins = BTreeMap::new();
+        let mut numbered = Vec::new();
+        let mut residual = 0.0;
+
+        for (bucket, sensitivities) in &buckets {
+            let risk_weight = match (vega, bucket) {
+                (true, _) => vega_weight,
+                (false, Some(b)) => weights[b - 1],
+                (false, None) => residual_weight,
+            };
+            let issuers: Vec<&str> = sensitivities.keys().map(|(issuer, _)| *issuer).collect();
+            let weighted: Vec<f64> = sensitivities
+                .values()
+                .map(|amount| risk_weight * amount)
+                .collect();
+
+            let margin = bucket_margin(&weighted, |k, l| match issuers[k] == issuers[l] {
+                true => issuer_correlation,
+                false => correlation,
+            });
+
+            match bucket {
+                Some(b) => {
+                    numbered.push(margin);
+                    margins.insert(b.to_string(), margin.0);
+                }
+                None => {
+                    residual = margin.0;
+                    margins.insert("Residual".to_string(), margin.0);
+                }
+            }
+        }
+
+        let total = across_buckets(&numbered, |_, _| bucket_correlation) + residual;
+
+        Ok((total, margins))
+    }
+
+    // Equity or commodity margin: names in numbered buckets, and a residual
+    // bucket added on top.
+    fn bucketed(
+        &self,
+        risk_class: RiskClass,
+        records: &[&CrifRecord],
+        vega: bool,
+    ) -> Result<(f64, BTreeMap<String, f64>), RustQuantError> {
+        let p = &self.parameters;
+
+        let (weights, correlations, residual_weight, bucket_correlation, vega_weight) =
+            match risk_class {
+                RiskClass::Equity => (
+                    &p.equity_risk_weights,
+                    &p.equity_correlations,
+                    p.equity_residual_risk_weight,
+                    p.equity_bucket_correlation,
+                    p.equity_vega_risk_weight,
+                ),
+                _ => (
+                    &p.commodity_risk_weights,
+                    &p.commodity_correlations,
+                    // Commodities have no residual bucket: use the largest
+                    // risk weight.
+                    p.commodity_risk_weights.iter().copied().fold(0.0, f64::max),
+                    p.commodity_bucket_correlation,
+                    p.commodity_vega_risk_weight,
+                ),
+            };
+
+        // Net sensitivity by bucket, then name (summed across expiries for
+        // vega).
+        let mut buckets: BTreeMap<Option<usize>, BTreeMap<&str, f64>> = BTreeMap::new();
+
+        for record in records {
+            if let Some(bucket) = record.bucket {
+                if bucket == 0 || bucket > weights.len() {
+                    return Err(RustQuantError::InvalidArgument(format!(
+                        "{risk_class:?} bucket {bucket} out of range"
+                    )));
+                }
+            }
+
+            *buckets
+                .entry(record.bucket)
+                .or_default()
+                .entry(record.qualifier.as_str())
+                .or_default() += record.amount;
+        }
+
+        // Volatility implied by a delta risk weight, for vega.
+        let vega_scale = |risk_weight: f64| match vega {
+            true => vega_weight * risk_weight * (365.0_f64 / 14.0).sqrt() / alpha(),
+            false => risk_weight,
+        };
+
+        let mut margins = BTreeMap::new();
+        let mut numbered = Vec::new();
+        let mut residual = 0.0;
+
+        for (bucket, names) in &buckets {
+            let (risk_weight, correlation) = match bucket {
+                Some(b) => (weights[b - 1], correlations[b - 1]),
+                None => (residual_weight, 0.0),
+            };
+            let weighted: Vec<f64> = names
+                .values()
+                .map(|amount| vega_scale(risk_weight) * amount)
+                .collect();
+
+            let margin = bucket_margin(&weighted, |_, _| correlation);
+
+            match bucket {
+                Some(b) => {
+                    numbered.push(margin);
+                    margins.insert(b.to_string(), margin.0);
+                }
+                None => {
+                    residual = margin.0;
+                    margins.insert("Residual".to_string(), margin.0);
+                }
+            }
+        }
+
+        let total = across_buckets(&numbered, |_, _| bucket_correlation) + residual;
+
+        Ok((total, margins))
+    }
+
+    // Curvature margin of the vega records of one risk class: the vegas,
+    // times the volatility for the classes whose vegas are not
+    // volatility-weighted, scaled by the option expiry, and aggregated with
+    // the squared delta correlations (the names netted across expiries,
+    // except for rates).
+    fn curvature(
+        &self,
+        risk_class: RiskClass,
+        records: &[&CrifRecord],
+    ) -> Result<f64, RustQuantError> {
+        let p = &self.parameters;
+
+        let volatility = |risk_weight: f64| risk_weight * (365.0_f64 / 14.0).sqrt() / alpha();
+        let commodity_residual = p.commodity_risk_weights.iter().copied().fold(0.0, f64::max);
+
+        let mut buckets: CurvatureBuckets = BTreeMap::new();
+
+        for record in records {
+            let sigma = match risk_class {
+                RiskClass::InterestRate
+                | RiskClass::CreditQualifying
+                | RiskClass::CreditNonQualifying => 1.0,
+                RiskClass::Equity => {
+                    volatility(record.bucket.map_or(p.equity_residual_risk_weight, |b| {
+                        p.equity_risk_weights[b - 1]
+                    }))
+                }
+                RiskClass::Commodity => volatility(
+                    record
+                        .bucket
+                        .map_or(commodity_residual, |b| p.commodity_risk_weights[b - 1]),
+                ),
+                RiskClass::Fx => volatility(p.fx_risk_weight),
+            };
+            let (bucket, factor) = match risk_class {
+                RiskClass::InterestRate => {
+                    let tenor = p
+                        .ir_tenors
+                        .iter()
+                        .position(|tenor| tenor.eq_ignore_ascii_case(&record.label1))
+                        // Checked by the vega margin.
+                        .unwrap_or_default();
+                    ((None, record.qualifier.as_str()), (tenor, ""))
+                }
+                RiskClass::Fx => ((None, ""), (0, record.qualifier.as_str())),
+                _ => ((record.bucket, ""), (0, record.qualifier.as_str())),
+            };
+
+            *buckets
+                .entry(bucket)
+                .or_default()
+                .entry(factor)
+                .or_default() += scaling_function(&record.label1)? * sigma * record.amount;
+        }
+
+        let correlation =
+            |bucket: Option<usize>, k: (usize, &str), l: (usize, &str)| match risk_class {
+                RiskClass::InterestRate => p.ir_tenor_correlation[(k.0, l.0)],
+                RiskClass::CreditQualifying => p.credit_q_correlation,
+                RiskClass::CreditNonQualifying => p.credit_non_q_correlation,
+                RiskClass::Equity => bucket.map_or(0.0, |b| p.equity_correlations[b - 1]),
+                RiskClass::Commodity => bucket.map_or(0.0, |b| p.commodity_correlations[b - 1]),
+                RiskClass::Fx => p.fx_correlation,
+            };
+        let gamma = match risk_class {
+            RiskClass::InterestRate => p.ir_currency_correlation,
+            RiskClass::CreditQualifying => p.credit_q_bucket_correlation,
+            RiskClass::CreditNonQualifying => p.credit_non_q_bucket_correlation,
+            RiskClass::Equity => p.equity_bucket_correlation,
+            RiskClass::Commodity => p.commodity_bucket_correlation,
+            RiskClass::Fx => 0.0,
+        };
+        let has_residual = !matches!(risk_class, RiskClass::InterestRate | RiskClass::Fx);
+
+        let mut numbered = Vec::new();
+        let mut residual = Vec::new();
+
+        for (&(bucket, _), sensitivities) in &buckets {
+            let factors: Vec<(usize, &str)> = sensitivities.keys().copied().collect();
+            let cvr: Vec<f64> = sensitivities.values().copied().collect();
+
+            let margin = bucket_margin(&cvr, |k, l| {
+                correlation(bucket, factors[k], factors[l]).powi(2)
+            });
+            let absolute: f64 = cvr.iter().map(|c| c.abs()).sum();
+
+            match has_residual && bucket.is_none() {
+                true => residual.push((margin, absolute)),
+                false => numbered.push((margin, absolute)),
+            }
+        }
+
+        Ok(curvature_margin(&numbered, gamma) + curvature_margin(&residual, 0.0))
+    }
+
+    // FX margin: one bucket, with currencies (or pairs, for vega) as the
+    // risk factors.
+    fn fx(&self, records: &[&CrifRecord], vega: bool) -> (f64, BTreeMap<String, f64>) {
+        let p = &self.parameters;
+
+        let mut currencies: BTreeMap<&str, f64> = BTreeMap::new();
+        for record in records {
+            *currencies.entry(record.qualifier.as_str()).or_default() += record.amount;
+        }
+
+        let risk_weight = match vega {
+            true => p.fx_vega_risk_weight * p.fx_risk_weight * (365.0_f64 / 14.0).sqrt() / alpha(),
+            false => p.fx_risk_weight,
+        };
+        let weighted: Vec<f64> = currencies
+            .values()
+            .map(|amount| risk_weight * amount)
+            .collect();
+
+        let (margin, _) = bucket_margin(&weighted, |_, _| p.fx_correlation);
+
+        let buckets = currencies
+            .iter()
+            .map(|(currency, amount)| (currency.to_string(), (risk_weight * amount).abs()))
+            .collect();
+
+        (margin, buckets)
+    }
+}
+
+impl RiskClassMargin {
+    /// Margin of the risk class: the sum of the delta, vega, and curvature
+    /// margins.
+    #[must_use]
+    pub fn margin(&self) -> f64 {
+        self.delta + self.vega + self.curvature
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Margin of a bucket's weighted sensitivities, with `correlation(k, l)`
+// between distinct risk factors, and their sum.
+fn bucket_margin<F>(weighted: &[f64], correlation: F) -> (f64, f64)
+where
+    F: Fn(usize, usize) -> f64,
+{
+    let mut variance = 0.0;
+
+    for (k, ws_k) in weighted.iter().enumerate() {
+        for (l, ws_l) in weighted.iter().enumerate() {
+            let rho = match k == l {
+                true => 1.0,
+                false => correlation(k, l),
+            };
+            variance += rho * ws_k * ws_l;
+        }
+    }
+
+    (variance.max(0.0).sqrt(), weighted.iter().sum())
+}
+
+// Aggregation of bucket margins `(K_b, sum of WS)` with correlation
+// `gamma(b, c)` between distinct buckets.
+fn across_buckets<F>(buckets: &[(f64, f64)], gamma: F) -> f64
+where
+    F: Fn(usize, usize) -> f64,
+{
+    let capped: Vec<f64> = buckets
+        .iter()
+        .map(|&(margin, sum)| sum.clamp(-margin, margin))
+        .collect();
+
+    let mut variance: f64 = buckets.iter().map(|(margin, _)| margin * margin).sum();
+
+    for (b, s_b) in capped.iter().enumerate() {
+        for (c, s_c) in capped.iter().enumerate() {
+            if b != c {
+                variance += gamma(b, c) * s_b * s_c;
+            }
+        }
+    }
+
+    variance.max(0.0).sqrt()
+}
+
+// Curvature margin of bucket margins `((K_b, sum of CVR), sum of |CVR|)`,
+// with correlation `gamma` between distinct buckets: the net curvature plus
+// the aggregated margin, scaled by `lambda` which falls to one as the
+// curvature becomes net short.
+fn curvature_margin(buckets: &[((f64, f64), f64)], gamma: f64) -> f64 {
+    let sum: f64 = buckets.iter().map(|((_, sum), _)| sum).sum();
+    let absolute: f64 = buckets.iter().map(|(_, absolute)| absolute).sum();
+
+    if absolute == 0.0 {
+        return 0.0;
+    }
+
+    let theta = (sum / absolute).min(0.0);
+    let quantile = Gaussian::default().inv_cdf(0.995);
+    let lambda = (quantile * quantile - 1.0) * (1.0 + theta) - theta;
+
+    let margins: Vec<(f64, f64)> = buckets.iter().map(|(margin, _)| *margin).collect();
+
+    (sum + lambda * across_buckets(&margins, |_, _| gamma * gamma)).max(0.0)
+}
+
+// Curvature scaling of an option expiry label such as "2w" or "1y": half of
+// two weeks over the expiry, at most one half.
+fn scaling_function(expiry: &str) -> Result<f64, RustQuantError> {
+    let label = expiry.trim().to_ascii_lowercase();
+
+    let days = [("d", 1.0), ("w", 7.0), ("m", 365.0 / 12.0), ("y", 365.0)]
+        .iter()
+        .find_map(|(unit, days)| Some(label.strip_suffix(unit)?.parse::<f64>().ok()? * days))
+        .filter(|days| *days > 0.0)
+        .ok_or_else(|| {
+            RustQuantError::InvalidArgument(format!("invalid option expiry {expiry:?}"))
+        })?;
+
+    Ok(0.5 * (14.0 / days).min(1.0))
+}
+
+// Normal quantile relating risk weights to volatilities.
+fn alpha() -> f64 {
+    Gaussian::default().inv_cdf(0.99)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_simm {
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn ir(currency: &str, tenor: &str, curve: &str, amount: f64) -> CrifRecord {
+        CrifRecord::new(
+            ProductClass::RatesFx,
+            RiskType::IrCurve,
+            currency,
+            None,
+            amount,
+        )
+        .with_labels(tenor, curve)
+    }
+
+    fn equity(name: &str, bucket: Option<usize>, amount: f64) -> CrifRecord {
+        CrifRecord::new(ProductClass::Equity, RiskType::Equity, name, bucket, amount)
+    }
+
+    #[test]
+    fn test_interest_rate_delta() {
+        let simm = SimmCalculator::default();
+        let p = &simm.parameters;
+
+        // One sensitivity: the risk weight times the amount.
+        let single = simm.compute(&[ir("USD", "5y", "OIS", 1_000.0)]).unwrap();
+        assert_approx_equal!(single.total, 60.0 * 1_000.0, EPS);
+
+        // Two tenors, one curve, partly offsetting.
+        let (ws_5, ws_10) = (60.0 * 1_000.0, -60.0 * 800.0);
+        let rho = p.ir_tenor_correlation[(7, 8)];
+        assert_approx_equal!(rho, 0.95, EPS);
+        let expected = (ws_5 * ws_5 + ws_10 * ws_10 + 2.0 * rho * ws_5 * ws_10).sqrt();
+
+        let curve = simm
+            .compute(&[
+                ir("USD", "5y", "OIS", 1_000.0),
+                ir("USD", "10Y", "OIS", -800.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(curve.total, expected, EPS);
+        assert_approx_equal!(
+            curve.product_classes[0].risk_classes[0].buckets["USD"],
+            expected,
+            EPS
+        );
+
+        // Equal and opposite on two sub-curves: basis risk only.
+        let basis = simm
+            .compute(&[
+                ir("USD", "5y", "OIS", 1_000.0),
+                ir("USD", "5y", "Libor3m", -1_000.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(basis.total, ws_5 * (2.0 * (1.0 - 0.993_f64)).sqrt(), EPS);
+
+        // Two currencies, same sign.
+        let two = simm
+            .compute(&[
+                ir("USD", "5y", "OIS", 1_000.0),
+                ir("EUR", "5y", "OIS", 1_000.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(two.total, ws_5 * (2.0 * (1.0 + 0.32_f64)).sqrt(), EPS);
+
+        assert!(simm.compute(&[ir("USD", "7y", "OIS", 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_equity_buckets() {
+        let simm = SimmCalculator::default();
+
+        // Two names in bucket 11 (risk weight 19, correlation 0.45).
+        let (a, b) = (19.0_f64 * 1_000.0, 19.0 * 500.0);
+        let same = simm
+            .compute(&[
+                equity("SPX", Some(11), 1_000.0),
+                equity("SX5E", Some(11), 500.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(same.total, (a * a + b * b + 2.0 * 0.45 * a * b).sqrt(), EPS);
+
+        // Netting of the same name.
+        let flat = simm
+            .compute(&[
+                equity("SPX", Some(11), 1_000.0),
+                equity("SPX", Some(11), -1_000.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(flat.total, 0.0, EPS);
+
+        // The residual bucket is added on top of the others.
+        let residual = simm
+            .compute(&[equity("SPX", Some(11), 1_000.0), equity("XYZ", None, 100.0)])
+            .unwrap();
+        assert_approx_equal!(residual.total, a + 50.0 * 100.0, EPS);
+
+        assert!(simm.compute(&[equity("SPX", Some(13), 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_aggregation_across_classes() {
+        let simm = SimmCalculator::default();
+
+        let fx = CrifRecord::new(ProductClass::RatesFx, RiskType::Fx, "EUR", None, 10_000.0);
+        let rates = ir("USD", "5y", "OIS", 1_000.0);
+        let stock = equity("SPX", Some(11), 1_000.0);
+
+        let margin = simm
+            .compute(&[fx.clone(), rates.clone(), stock.clone()])
+            .unwrap();
+        let (im_fx, im_ir, im_eq) = (7.4_f64 * 10_000.0, 60.0_f64 * 1_000.0, 19.0 * 1_000.0);
+
+        // Rates and FX are aggregated with their correlation, and added to
+        // the equity product class.
+        let rates_fx = (im_fx * im_fx + im_ir * im_ir + 2.0 * 0.28 * im_fx * im_ir).sqrt();
+        assert_approx_equal!(margin.total, rates_fx + im_eq, EPS);
+        assert_eq!(margin.product_classes.len(), 2);
+        assert_approx_equal!(margin.product_classes[0].margin, rates_fx, EPS);
+    }
+
+    #[test]
+    fn test_vega_and_greeks() {
+        let simm = SimmCalculator::default();
+        let greeks = Greeks {
+            delta: 0.5,
+            gamma: 0.02,
+            vega: 40.0,
+            theta: -5.0,
+            rho: 30.0,
+        };
+
+        let [delta, vega] =
+            CrifRecord::from_greeks(RiskClass::Equity, "SPX", Some(11), &greeks, 4_000.0, "1y")
+                .unwrap();
+        assert_approx_equal!(delta.amount, 20.0, EPS);
+        assert_approx_equal!(vega.amount, 0.4, EPS);
+        assert_eq!(vega.risk_type, RiskType::EquityVol);
+        assert_eq!(vega.label1, "1y");
+
+        // Vega risk weight times the volatility implied by the delta risk
+        // weight.
+        let margin = simm.compute(std::slice::from_ref(&vega)).unwrap();
+        let sigma = 19.0 * (365.0_f64 / 14.0).sqrt() / 2.326_347_874;
+
+        let class = &margin.product_classes[0].risk_classes[0];
+        assert_approx_equal!(class.delta, 0.0, EPS);
+        assert_approx_equal!(class.vega, 0.45 * sigma * 0.4, 1e-6);
+
+        // A single long curvature sensitivity, scaled by the expiry, is
+        // multiplied by the squared 99.5% normal quantile.
+        let cvr = 0.5 * 14.0 / 365.0 * sigma * 0.4;
+        assert_approx_equal!(class.curvature, cvr * 2.575_829_304_f64.powi(2), 1e-6);
+        assert_approx_equal!(margin.total, class.vega + class.curvature, EPS);
+
+        // Short curvature has no margin.
+        let short = CrifRecord {
+            amount: -0.4,
+            ..vega.clone()
+        };
+        let margin = simm.compute(&[short]).unwrap();
+        assert_approx_equal!(
+            margin.product_classes[0].risk_classes[0].curvature,
+            0.0,
+            EPS
+        );
+
+        // Curvature needs the option expiry.
+        assert!(simm.compute(&[vega.with_labels("", "")]).is_err());
+
+        assert!(
+            CrifRecord::from_greeks(RiskClass::InterestRate, "USD", None, &greeks, 1.0, "1y")
+                .is_err()
+        );
+        assert!(CrifRecord::from_greeks(
+            RiskClass::CreditQualifying,
+            "ABC",
+            Some(1),
+            &greeks,
+            1.0,
+            "1y"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_credit() {
+        let simm = SimmCalculator::default();
+
+        let credit = |issuer: &str, bucket: Option<usize>, tenor: &str, amount: f64| {
+            CrifRecord::new(
+                ProductClass::Credit,
+                RiskType::CreditQ,
+                issuer,
+                bucket,
+                amount,
+            )
+            .with_labels(tenor, "")
+        };
+
+        // One issuer on two tenors in bucket 4 (risk weight 54).
+        let (a, b) = (54.0_f64 * 100.0, 54.0 * 50.0);
+        let issuer = simm
+            .compute(&[
+                credit("ABC", Some(4), "5y", 100.0),
+                credit("ABC", Some(4), "10y", 50.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(
+            issuer.total,
+            (a * a + b * b + 2.0 * 0.93 * a * b).sqrt(),
+            EPS
+        );
+
+        // Two issuers in the same bucket.
+        let issuers = simm
+            .compute(&[
+                credit("ABC", Some(4), "5y", 100.0),
+                credit("XYZ", Some(4), "5y", 50.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(
+            issuers.total,
+            (a * a + b * b + 2.0 * 0.46 * a * b).sqrt(),
+            EPS
+        );
+
+        // Two buckets, and the residual bucket on top.
+        let c = 185.0 * 100.0;
+        let buckets = simm
+            .compute(&[
+                credit("ABC", Some(4), "5y", 100.0),
+                credit("DEF", Some(7), "5y", 100.0),
+                credit("GHI", None, "5y", 10.0),
+            ])
+            .unwrap();
+        assert_approx_equal!(
+            buckets.total,
+            (a * a + c * c + 2.0 * 0.4 * a * c).sqrt() + 343.0 * 10.0,
+            EPS
+        );
+
+        // Qualifying and non-qualifying credit in one product class.
+        let non_q = CrifRecord::new(
+            ProductClass::Credit,
+            RiskType::CreditNonQ,
+            "RMBS",
+            Some(1),
+            100.0,
+        )
+        .with_labels("5y", "");
+        let d = 280.0 * 100.0;
+        let both = simm
+            .compute(&[credit("ABC", Some(4), "5y", 100.0), non_q])
+            .unwrap();
+        assert_eq!(both.product_classes[0].risk_classes.len(), 2);
+        assert_approx_equal!(both.total, (a * a + d * d + 2.0 * 0.54 * a * d).sqrt(), EPS);
+
+        // Volatility-weighted vega, and its curvature at a one year expiry.
+        let vega = CrifRecord::new(
+            ProductClass::Credit,
+            RiskType::CreditVol,
+            "ABC",
+            Some(4),
+            1_000.0,
+        )
+        .with_labels("1y", "");
+        let margin = simm.compute(&[vega]).unwrap();
+        let class = &margin.product_classes[0].risk_classes[0];
+        assert_approx_equal!(class.vega, 0.74 * 1_000.0, EPS);
+        let cvr = 0.5 * 14.0 / 365.0 * 1_000.0;
+        assert_approx_equal!(class.curvature, cvr * 2.575_829_304_f64.powi(2), 1e-6);
+
+        assert!(simm.compute(&[credit("ABC", Some(13), "5y", 1.0)]).is_err());
+        assert!(simm.compute(&[credit("ABC", Some(4), "7y", 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_parse_crif() {
+        let record: CrifRecord = "RatesFX\tRisk_IRCurve\tUSD\t\t5y\tOIS\t1000.5"
+            .parse()
+            .unwrap();
+        assert_eq!(record, ir("USD", "5y", "OIS", 1_000.5));
+
+        let record: CrifRecord = "Equity,Risk_Equity,SPX,11,,,-250".parse().unwrap();
+        assert_eq!(record, equity("SPX", Some(11), -250.0));
+
+        let record: CrifRecord = "Equity,Risk_EquityVol,XYZ,Residual,1y,,3".parse().unwrap();
+        assert_eq!(record.bucket, None);
+        assert_eq!(record.risk_type.to_string(), "Risk_EquityVol");
+
+        let record: CrifRecord = "Credit,Risk_CreditQ,ABC,1,5y,,1".parse().unwrap();
+        assert_eq!(record.risk_type.risk_class(), RiskClass::CreditQualifying);
+
+        assert!("Credit,Risk_BaseCorr,CDX,,,,1"
+            .parse::<CrifRecord>()
+            .is_err());
+        assert!("Equity,Risk_Equity,SPX,11,,".parse::<CrifRecord>().is_err());
+        assert!("Equity,Risk_Equity,SPX,x,,,1"
+            .parse::<CrifRecord>()
+            .is_err());
+    }
+}
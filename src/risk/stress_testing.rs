@@ -0,0 +1,570 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Stress testing.
+//!
+//! A [`StressScenario`] is a named list of deterministic [`Shock`]s:
+//!
+//! - Parallel shifts of a currency's discount curve.
+//! - Additive bumps to a volatility surface (and volatility factor).
+//! - Relative shocks to a spot price, e.g. `-0.2` for a 20% fall.
+//!
+//! Scenarios can be written by hand or replayed from history: the change of
+//! each risk factor between two dates becomes a shock.
+//!
+//! A [`StressTest`] revalues a set of [`ScenarioPosition`]s under each
+//! scenario and reports the P&L per scenario and per position.
+//!
+//! Curve shifts and volatility bumps are applied to the pricing context, so
+//! instruments priced off its curves and surfaces pick them up; spot shocks
+//! are passed to the positions through the [`Scenario`].
+//!
+//! ```
+//! use RustQuant::data::{Curve, VolatilitySurface, YieldCurve};
+//! use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+//! use RustQuant::instruments::{Instrument, PricingContext};
+//! use RustQuant::iso::USD;
+//! use RustQuant::risk::*;
+//! use time::macros::date;
+//!
+//! let flat = |level: f64| {
+//!     YieldCurve::from_dates_and_rates(&[date!(2024 - 01 - 01), date!(2026 - 01 - 01)], &[level; 2])
+//!         .unwrap()
+//! };
+//!
+//! let ctx = PricingContext::new(date!(2024 - 01 - 01))
+//!     .with_reporting_currency(USD)
+//!     .with_discount_curve(USD, flat(0.05))
+//!     .with_volatility_surface("SPX", VolatilitySurface::new(vec![(100.0, flat(0.2))]))
+//!     .with_default_volatility_surface("SPX");
+//!
+//! let factors = vec![RiskFactor::Price { name: "SPX".to_string(), level: 100.0 }];
+//!
+//! // The rate and volatility are read off the (shocked) context.
+//! let call = ScenarioPosition::new("SPX call", 100.0, |scenario: &Scenario| {
+//!     Box::new(BlackScholesMerton::new(
+//!         0.05,
+//!         scenario.level("SPX").unwrap(),
+//!         100.0,
+//!         0.2,
+//!         0.05,
+//!         None,
+//!         date!(2025 - 01 - 01),
+//!         TypeFlag::Call,
+//!     )) as Box<dyn Instrument>
+//! });
+//!
+//! let report = StressTest::new(factors)
+//!     .with_scenario(StressScenario::new("crash").with_spot_shock("SPX", -0.3))
+//!     .with_scenario(StressScenario::new("vol spike").with_volatility_bump("SPX", 0.1))
+//!     .with_scenario(StressScenario::new("rates up").with_curve_shift(USD, 0.01))
+//!     .run(&ctx, &[call])
+//!     .unwrap();
+//!
+//! assert!(report.total("crash").unwrap() < 0.0);
+//! assert!(report.total("vol spike").unwrap() > 0.0);
+//! assert!(report.total("rates up").unwrap() > 0.0);
+//! assert_eq!(report.worst().unwrap().0, "crash");
+//! ```
+
+use super::{RiskFactor, Scenario, ScenarioPosition};
+use crate::data::TimeSeries;
+use crate::error::RustQuantError;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::PricingContext;
+use std::collections::HashMap;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Deterministic shock to the market.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shock {
+    /// Parallel shift of a currency's discount curve.
+    CurveShift {
+        /// Currency of the discount curve.
+        currency: Currency,
+        /// Additive shift, e.g. `0.01` for +100bp.
+        shift: f64,
+    },
+    /// Additive bump to a volatility surface of the pricing context, and to
+    /// the volatility factor with the same name.
+    VolatilityBump {
+        /// Name of the surface (and factor).
+        name: String,
+        /// Additive bump, e.g. `0.05` for +5 vol points.
+        bump: f64,
+    },
+    /// Relative shock to a price factor.
+    SpotShock {
+        /// Name of the underlying.
+        name: String,
+        /// Relative change, e.g. `-0.2` for a 20% fall.
+        shock: f64,
+    },
+}
+
+/// Named set of shocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StressScenario {
+    /// Name of the scenario.
+    pub name: String,
+    /// Shocks applied together.
+    pub shocks: Vec<Shock>,
+}
+
+/// Stress test: stress scenarios applied to a set of risk factors.
+#[derive(Debug, Clone)]
+pub struct StressTest {
+    /// Risk factors (with their current levels).
+    pub factors: Vec<RiskFactor>,
+    /// Stress scenarios.
+    pub scenarios: Vec<StressScenario>,
+}
+
+/// P&L of each position under each stress scenario.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StressTestReport {
+    /// Names of the scenarios.
+    pub scenarios: Vec<String>,
+    /// Names of the positions.
+    pub positions: Vec<String>,
+    /// P&L, one row per scenario and one column per position.
+    pub pnl: Vec<Vec<f64>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl StressScenario {
+    /// New scenario with no shocks.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            shocks: Vec::new(),
+        }
+    }
+
+    /// Add a shock.
+    #[must_use]
+    pub fn with_shock(mut self, shock: Shock) -> Self {
+        self.shocks.push(shock);
+        self
+    }
+
+    /// Add a parallel shift of a currency's discount curve.
+    #[must_use]
+    pub fn with_curve_shift(self, currency: Currency, shift: f64) -> Self {
+        self.with_shock(Shock::CurveShift { currency, shift })
+    }
+
+    /// Add a bump to a volatility surface.
+    #[must_use]
+    pub fn with_volatility_bump(self, name: &str, bump: f64) -> Self {
+        self.with_shock(Shock::VolatilityBump {
+            name: name.to_string(),
+            bump,
+        })
+    }
+
+    /// Add a relative shock to a spot price.
+    #[must_use]
+    pub fn with_spot_shock(self, name: &str, shock: f64) -> Self {
+        self.with_shock(Shock::SpotShock {
+            name: name.to_string(),
+            shock,
+        })
+    }
+
+    /// Replay the historical move of the factors between two dates.
+    ///
+    /// `history` holds the level of each factor, keyed by factor name
+    /// (the currency code for rate factors). Prices give relative shocks;
+    /// volatilities and rates give additive ones. Factors without a history
+    /// are not shocked. The level at a date is the last one on or before it.
+    ///
+    /// # Errors
+    ///
+    /// If a history has no level on or before one of the dates.
+    pub fn historical(
+        name: &str,
+        factors: &[RiskFactor],
+        history: &HashMap<String, TimeSeries<f64>>,
+        start: Date,
+        end: Date,
+    ) -> Result<Self, RustQuantError> {
+        let mut scenario = Self::new(name);
+
+        for factor in factors {
+            let Some(series) = history.get(factor.name()) else {
+                continue;
+            };
+
+            let level = |date: Date| {
+                series.as_of(date).copied().ok_or_else(|| {
+                    RustQuantError::MissingInput(format!(
+                        "no level of {} on or before {date}",
+                        factor.name()
+                    ))
+                })
+            };
+            let (from, to) = (level(start)?, level(end)?);
+
+            scenario = match factor {
+                RiskFactor::Price { name, .. } => scenario.with_spot_shock(name, to / from - 1.0),
+                RiskFactor::Volatility { name, .. } => {
+                    scenario.with_volatility_bump(name, to - from)
+                }
+                RiskFactor::Rate { currency } => scenario.with_curve_shift(*currency, to - from),
+            };
+        }
+
+        Ok(scenario)
+    }
+
+    /// Market scenario for the given risk factors.
+    ///
+    /// Spot shocks and volatility bumps move the matching factors from their
+    /// current levels; factors that are not shocked keep their levels.
+    #[must_use]
+    pub fn scenario(&self, factors: &[RiskFactor]) -> Scenario {
+        let mut scenario = Scenario::base(factors);
+
+        let level = |name: &str, price: bool| {
+            factors.iter().find_map(|factor| match factor {
+                RiskFactor::Price { name: n, level } if price && n == name => Some(*level),
+                RiskFactor::Volatility { name: n, level } if !price && n == name => Some(*level),
+                _ => None,
+            })
+        };
+
+        for shock in &self.shocks {
+            scenario = match shock {
+                Shock::CurveShift { currency, shift } => scenario.with_rate_shift(currency, *shift),
+                Shock::VolatilityBump { name, bump } => {
+                    let scenario = scenario.with_volatility_bump(name, *bump);
+
+                    match level(name, false) {
+                        Some(level) => scenario.with_level(name, level + bump),
+                        None => scenario,
+                    }
+                }
+                Shock::SpotShock { name, shock } => match level(name, true) {
+                    Some(level) => scenario.with_level(name, level * (1.0 + shock)),
+                    None => scenario,
+                },
+            };
+        }
+
+        scenario
+    }
+}
+
+impl StressTest {
+    /// New stress test with no scenarios.
+    #[must_use]
+    pub const fn new(factors: Vec<RiskFactor>) -> Self {
+        Self {
+            factors,
+            scenarios: Vec::new(),
+        }
+    }
+
+    /// Add a scenario.
+    #[must_use]
+    pub fn with_scenario(mut self, scenario: StressScenario) -> Self {
+        self.scenarios.push(scenario);
+        self
+    }
+
+    /// Revalue the positions under each scenario.
+    ///
+    /// # Errors
+    ///
+    /// If a position cannot be valued.
+    pub fn run(
+        &self,
+        ctx: &PricingContext,
+        positions: &[ScenarioPosition],
+    ) -> Result<StressTestReport, RustQuantError> {
+        let base = Scenario::base(&self.factors);
+
+        let base_values = positions
+            .iter()
+            .map(|position| position.value(&base, ctx))
+            .collect::<Result<Vec<f64>, RustQuantError>>()?;
+
+        let pnl = self
+            .scenarios
+            .iter()
+            .map(|stress| {
+                let scenario = stress.scenario(&self.factors);
+
+                positions
+                    .iter()
+                    .zip(&base_values)
+                    .map(|(position, base_value)| Ok(position.value(&scenario, ctx)? - base_value))
+                    .collect::<Result<Vec<f64>, RustQuantError>>()
+            })
+            .collect::<Result<Vec<Vec<f64>>, RustQuantError>>()?;
+
+        Ok(StressTestReport {
+            scenarios: self.scenarios.iter().map(|s| s.name.clone()).collect(),
+            positions: positions.iter().map(|p| p.name.clone()).collect(),
+            pnl,
+        })
+    }
+}
+
+impl StressTestReport {
+    /// P&L of a position under a scenario.
+    #[must_use]
+    pub fn pnl(&self, scenario: &str, position: &str) -> Option<f64> {
+        let i = self.scenarios.iter().position(|s| s == scenario)?;
+        let j = self.positions.iter().position(|p| p == position)?;
+
+        Some(self.pnl[i][j])
+    }
+
+    /// Total P&L under a scenario.
+    #[must_use]
+    pub fn total(&self, scenario: &str) -> Option<f64> {
+        let i = self.scenarios.iter().position(|s| s == scenario)?;
+
+        Some(self.pnl[i].iter().sum())
+    }
+
+    /// Total P&L under each scenario.
+    #[must_use]
+    pub fn totals(&self) -> Vec<f64> {
+        self.pnl.iter().map(|row| row.iter().sum()).collect()
+    }
+
+    /// Scenario with the lowest total P&L.
+    #[must_use]
+    pub fn worst(&self) -> Option<(&str, f64)> {
+        self.scenarios
+            .iter()
+            .zip(self.totals())
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(name, total)| (name.as_str(), total))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_stress_testing {
+    use super::*;
+    use crate::instruments::Instrument;
+    use crate::iso::{EUR, USD};
+    use time::macros::date;
+
+    const EPS: f64 = 1e-12;
+
+    /// Instrument with a fixed price.
+    struct Linear(f64);
+
+    impl Instrument for Linear {
+        fn price(&self, _ctx: &PricingContext) -> Result<f64, RustQuantError> {
+            Ok(self.0)
+        }
+
+        fn error(&self) -> Option<f64> {
+            None
+        }
+
+        fn valuation_date(&self) -> Date {
+            date!(2024 - 01 - 01)
+        }
+
+        fn instrument_type(&self) -> &'static str {
+            "Linear"
+        }
+    }
+
+    fn factors() -> Vec<RiskFactor> {
+        vec![
+            RiskFactor::Price {
+                name: "SPX".to_string(),
+                level: 100.0,
+            },
+            RiskFactor::Volatility {
+                name: "SPX vol".to_string(),
+                level: 0.2,
+            },
+            RiskFactor::Rate { currency: USD },
+        ]
+    }
+
+    fn positions() -> Vec<ScenarioPosition<'static>> {
+        vec![
+            ScenarioPosition::new("spot", 2.0, |s: &Scenario| {
+                Box::new(Linear(s.level("SPX").unwrap())) as Box<dyn Instrument>
+            }),
+            ScenarioPosition::new("vega", 1.0, |s: &Scenario| {
+                Box::new(Linear(
+                    100.0 * (s.level("SPX vol").unwrap() + s.rate_shift(&USD)),
+                )) as Box<dyn Instrument>
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_scenario() {
+        let scenario = StressScenario::new("combined")
+            .with_spot_shock("SPX", -0.2)
+            .with_volatility_bump("SPX vol", 0.1)
+            .with_curve_shift(USD, 0.01)
+            .scenario(&factors());
+
+        assert_approx_equal!(scenario.level("SPX").unwrap(), 80.0, EPS);
+        assert_approx_equal!(scenario.level("SPX vol").unwrap(), 0.3, EPS);
+        assert_eq!(scenario.volatility_bump("SPX vol"), 0.1);
+        assert_eq!(scenario.rate_shift(&USD), 0.01);
+        assert_eq!(scenario.rate_shift(&EUR), 0.0);
+    }
+
+    #[test]
+    fn test_report() {
+        let ctx = PricingContext::new(date!(2024 - 01 - 01));
+
+        let report = StressTest::new(factors())
+            .with_scenario(StressScenario::new("crash").with_spot_shock("SPX", -0.2))
+            .with_scenario(
+                StressScenario::new("vol and rates")
+                    .with_volatility_bump("SPX vol", 0.1)
+                    .with_curve_shift(USD, 0.01),
+            )
+            .run(&ctx, &positions())
+            .unwrap();
+
+        assert_approx_equal!(report.pnl("crash", "spot").unwrap(), -40.0, EPS);
+        assert_approx_equal!(report.pnl("crash", "vega").unwrap(), 0.0, EPS);
+        assert_approx_equal!(report.pnl("vol and rates", "spot").unwrap(), 0.0, EPS);
+        assert_approx_equal!(report.pnl("vol and rates", "vega").unwrap(), 11.0, 1e-9);
+        assert_approx_equal!(report.total("crash").unwrap(), -40.0, EPS);
+        assert_eq!(report.worst().unwrap().0, "crash");
+        assert_eq!(report.pnl("crash", "missing"), None);
+    }
+
+    #[test]
+    fn test_report_context_shocks() {
+        use crate::data::{Curve, VolatilitySurface, YieldCurve};
+        use crate::instruments::options::{BlackScholesMerton, TypeFlag};
+
+        let start = date!(2024 - 01 - 01);
+        let flat = |level: f64| {
+            YieldCurve::from_dates_and_rates(&[start, date!(2026 - 01 - 01)], &[level; 2]).unwrap()
+        };
+
+        let ctx = PricingContext::new(start)
+            .with_reporting_currency(USD)
+            .with_discount_curve(USD, flat(0.05))
+            .with_volatility_surface("SPX", VolatilitySurface::new(vec![(100.0, flat(0.2))]))
+            .with_default_volatility_surface("SPX");
+
+        let option = |rate: f64, volatility: f64| {
+            BlackScholesMerton::new(
+                rate,
+                100.0,
+                100.0,
+                volatility,
+                rate,
+                None,
+                date!(2025 - 01 - 01),
+                TypeFlag::Call,
+            )
+        };
+        let price = |rate: f64, volatility: f64| {
+            Instrument::price(&option(rate, volatility), &PricingContext::new(start)).unwrap()
+        };
+
+        // The position ignores the scenario: the shocks reach it through
+        // the context's curve and surface.
+        let positions = [ScenarioPosition::new("call", 1.0, move |_: &Scenario| {
+            Box::new(option(0.05, 0.2)) as Box<dyn Instrument>
+        })];
+
+        let report = StressTest::new(vec![])
+            .with_scenario(StressScenario::new("rates").with_curve_shift(USD, 0.01))
+            .with_scenario(StressScenario::new("vol").with_volatility_bump("SPX", 0.05))
+            .with_scenario(StressScenario::new("other currency").with_curve_shift(EUR, 0.01))
+            .run(&ctx, &positions)
+            .unwrap();
+
+        assert_approx_equal!(
+            report.pnl("rates", "call").unwrap(),
+            price(0.06, 0.2) - price(0.05, 0.2),
+            1e-9
+        );
+        assert_approx_equal!(
+            report.pnl("vol", "call").unwrap(),
+            price(0.05, 0.25) - price(0.05, 0.2),
+            1e-9
+        );
+        assert_approx_equal!(report.pnl("other currency", "call").unwrap(), 0.0, EPS);
+    }
+
+    #[test]
+    fn test_historical_replay() {
+        let history: HashMap<String, TimeSeries<f64>> = [
+            (
+                "SPX".to_string(),
+                TimeSeries::from_pairs([
+                    (date!(2020 - 02 - 19), 3386.0),
+                    (date!(2020 - 03 - 23), 2237.0),
+                ])
+                .unwrap(),
+            ),
+            (
+                "USD".to_string(),
+                TimeSeries::from_pairs([
+                    (date!(2020 - 02 - 19), 0.0158),
+                    (date!(2020 - 03 - 20), 0.0025),
+                ])
+                .unwrap(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let replay = StressScenario::historical(
+            "covid",
+            &factors(),
+            &history,
+            date!(2020 - 02 - 19),
+            date!(2020 - 03 - 23),
+        )
+        .unwrap();
+
+        // No history for the volatility factor.
+        assert_eq!(replay.shocks.len(), 2);
+
+        let scenario = replay.scenario(&factors());
+        assert_approx_equal!(scenario.level("SPX").unwrap(), 100.0 * 2237.0 / 3386.0, EPS);
+        assert_approx_equal!(scenario.rate_shift(&USD), 0.0025 - 0.0158, EPS);
+        assert_eq!(scenario.level("SPX vol"), Some(0.2));
+
+        assert!(StressScenario::historical(
+            "too early",
+            &factors(),
+            &history,
+            date!(2019 - 01 - 01),
+            date!(2020 - 03 - 23),
+        )
+        .is_err());
+    }
+}
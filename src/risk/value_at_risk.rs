@@ -0,0 +1,413 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Value-at-Risk (VaR) and Expected Shortfall (ES).
+//!
+//! Both measures are reported as positive losses, in the units of the
+//! input: a return series gives VaR as a fraction of the portfolio value,
+//! a P&L vector gives VaR in currency units.
+//!
+//! At confidence level $c$, VaR is the loss that is exceeded with
+//! probability $1 - c$, and ES is the expected loss given that VaR is
+//! exceeded.
+
+use crate::error::RustQuantError;
+use crate::math::distributions::{Distribution, Gaussian};
+use crate::math::Statistic;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Method used to estimate VaR and ES.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarMethod {
+    /// Historical simulation: empirical quantile of the losses.
+    Historical,
+    /// Parametric (variance-covariance): normally distributed P&L.
+    Parametric,
+    /// Parametric with the Cornish-Fisher expansion, which adjusts the
+    /// normal quantile for the skewness and excess kurtosis of the P&L.
+    CornishFisher,
+}
+
+/// Value-at-Risk estimator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueAtRisk {
+    /// Confidence level, e.g. `0.99`.
+    pub confidence: f64,
+    /// Estimation method.
+    pub method: VarMethod,
+}
+
+/// VaR and ES at a confidence level (both as positive losses).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskEstimate {
+    /// Confidence level.
+    pub confidence: f64,
+    /// Value-at-Risk.
+    pub value_at_risk: f64,
+    /// Expected Shortfall (Conditional VaR).
+    pub expected_shortfall: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ValueAtRisk {
+    /// New VaR estimator.
+    #[must_use]
+    pub const fn new(confidence: f64, method: VarMethod) -> Self {
+        Self { confidence, method }
+    }
+
+    /// VaR and ES of a P&L (or return) series, where profits are positive.
+    ///
+    /// # Errors
+    ///
+    /// If the confidence level is not in $(0, 1)$, or there are too few
+    /// observations (two for the parametric methods, four for
+    /// Cornish-Fisher).
+    pub fn compute(&self, pnl: &[f64]) -> Result<RiskEstimate, RustQuantError> {
+        check_confidence(self.confidence)?;
+
+        let min_observations = match self.method {
+            VarMethod::Historical => 1,
+            VarMethod::Parametric => 2,
+            VarMethod::CornishFisher => 4,
+        };
+
+        if pnl.len() < min_observations {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "{:?} VaR needs at least {min_observations} observations, got {}",
+                self.method,
+                pnl.len()
+            )));
+        }
+
+        let (value_at_risk, expected_shortfall) = match self.method {
+            VarMethod::Historical => historical(pnl, self.confidence),
+            VarMethod::Parametric => {
+                let (mean, std_dev) = mean_and_std_dev(pnl);
+                let (z, tail) = standard_normal_tail(self.confidence);
+
+                (-(mean + z * std_dev), -(mean + tail * std_dev))
+            }
+            VarMethod::CornishFisher => cornish_fisher(pnl, self.confidence),
+        };
+
+        Ok(RiskEstimate {
+            confidence: self.confidence,
+            value_at_risk,
+            expected_shortfall,
+        })
+    }
+
+    /// Value-at-Risk of a P&L (or return) series, see [`ValueAtRisk::compute`].
+    ///
+    /// # Errors
+    ///
+    /// See [`ValueAtRisk::compute`].
+    pub fn value_at_risk(&self, pnl: &[f64]) -> Result<f64, RustQuantError> {
+        Ok(self.compute(pnl)?.value_at_risk)
+    }
+
+    /// Expected Shortfall of a P&L (or return) series, see [`ValueAtRisk::compute`].
+    ///
+    /// # Errors
+    ///
+    /// See [`ValueAtRisk::compute`].
+    pub fn expected_shortfall(&self, pnl: &[f64]) -> Result<f64, RustQuantError> {
+        Ok(self.compute(pnl)?.expected_shortfall)
+    }
+}
+
+impl RiskEstimate {
+    /// Scale a one-period estimate to a horizon of `periods` periods with the
+    /// square-root-of-time rule (assumes i.i.d. zero-mean P&L).
+    #[must_use]
+    pub fn scale(&self, periods: f64) -> Self {
+        Self {
+            value_at_risk: self.value_at_risk * periods.sqrt(),
+            expected_shortfall: self.expected_shortfall * periods.sqrt(),
+            ..*self
+        }
+    }
+}
+
+/// Delta-normal (variance-covariance) VaR and ES of a portfolio.
+///
+/// The portfolio P&L is $w^\top r$, where $w$ are the exposures (deltas) to
+/// the risk factors and $r \sim N(0, \Sigma)$ are the factor returns.
+///
+/// # Errors
+///
+/// If the confidence level is not in $(0, 1)$, or the dimensions of the
+/// exposures and the covariance matrix do not match.
+pub fn delta_normal(
+    exposures: &[f64],
+    covariance: &DMatrix<f64>,
+    confidence: f64,
+) -> Result<RiskEstimate, RustQuantError> {
+    check_confidence(confidence)?;
+
+    let n = exposures.len();
+
+    if covariance.shape() != (n, n) {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "covariance matrix is {:?}, expected ({n}, {n})",
+            covariance.shape()
+        )));
+    }
+
+    let w = DVector::from_column_slice(exposures);
+    let std_dev = w.dot(&(covariance * &w)).max(0.0).sqrt();
+    let (z, tail) = standard_normal_tail(confidence);
+
+    Ok(RiskEstimate {
+        confidence,
+        value_at_risk: -z * std_dev,
+        expected_shortfall: -tail * std_dev,
+    })
+}
+
+pub(crate) fn check_confidence(confidence: f64) -> Result<(), RustQuantError> {
+    if confidence > 0.0 && confidence < 1.0 {
+        Ok(())
+    } else {
+        Err(RustQuantError::InvalidArgument(format!(
+            "confidence level must be in (0, 1), got {confidence}"
+        )))
+    }
+}
+
+/// Historical VaR (interpolated empirical quantile of the losses) and ES
+/// (mean of the losses at or beyond VaR).
+fn historical(pnl: &[f64], confidence: f64) -> (f64, f64) {
+    let losses: Vec<f64> = pnl.iter().map(|x| -x).collect();
+    let value_at_risk = losses.quantile(confidence);
+
+    let tail: Vec<f64> = losses
+        .iter()
+        .copied()
+        .filter(|loss| *loss >= value_at_risk)
+        .collect();
+
+    let expected_shortfall = tail.iter().sum::<f64>() / tail.len() as f64;
+
+    (value_at_risk, expected_shortfall)
+}
+
+/// Sample mean and standard deviation.
+fn mean_and_std_dev(pnl: &[f64]) -> (f64, f64) {
+    let pnl = pnl.to_vec();
+
+    (pnl.mean(), pnl.sample_standard_deviation())
+}
+
+/// Left-tail quantile $q = \Phi^{-1}(1 - c)$ of the standard normal and the
+/// tail mean $E[Z \mid Z \leq q] = -\phi(q) / (1 - c)$.
+pub(crate) fn standard_normal_tail(confidence: f64) -> (f64, f64) {
+    let gaussian = Gaussian::default();
+    let alpha = 1.0 - confidence;
+    let q = gaussian.inv_cdf(alpha);
+
+    (q, -gaussian.pdf(q) / alpha)
+}
+
+/// Cornish-Fisher VaR and ES.
+///
+/// The standardised P&L quantile is
+///
+/// $$
+/// z_{cf} = z + \frac{(z^2 - 1) S}{6} + \frac{(z^3 - 3z) K}{24} - \frac{(2z^3 - 5z) S^2}{36}
+/// $$
+///
+/// where $S$ is the skewness and $K$ the excess kurtosis. ES is the mean of
+/// $z_{cf}(Z)$ over the tail $Z \leq q$, computed in closed form from the
+/// partial moments of the standard normal.
+fn cornish_fisher(pnl: &[f64], confidence: f64) -> (f64, f64) {
+    let (mean, std_dev) = mean_and_std_dev(pnl);
+
+    let n = pnl.len() as f64;
+    let m2 = pnl.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    let m3 = pnl.iter().map(|x| (x - mean).powi(3)).sum::<f64>() / n;
+    let m4 = pnl.iter().map(|x| (x - mean).powi(4)).sum::<f64>() / n;
+
+    let (s, k) = if m2 > 0.0 {
+        (m3 / m2.powf(1.5), m4 / (m2 * m2) - 3.0)
+    } else {
+        (0.0, 0.0)
+    };
+
+    let z_cf = |z: f64| {
+        z + (z * z - 1.0) * s / 6.0 + (z.powi(3) - 3.0 * z) * k / 24.0
+            - (2.0 * z.powi(3) - 5.0 * z) * s * s / 36.0
+    };
+
+    let gaussian = Gaussian::default();
+    let alpha = 1.0 - confidence;
+    let q = gaussian.inv_cdf(alpha);
+    let phi = gaussian.pdf(q);
+
+    // Partial moments E[Z^k; Z <= q].
+    let p0 = alpha;
+    let p1 = -phi;
+    let p2 = alpha - q * phi;
+    let p3 = -(q * q + 2.0) * phi;
+
+    let tail = (p1 + (p2 - p0) * s / 6.0 + (p3 - 3.0 * p1) * k / 24.0
+        - (2.0 * p3 - 5.0 * p1) * s * s / 36.0)
+        / alpha;
+
+    (-(mean + z_cf(q) * std_dev), -(mean + tail * std_dev))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_value_at_risk {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    const EPS: f64 = 1e-10;
+
+    fn normal_sample(mean: f64, std_dev: f64, n: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        (0..n)
+            .map(|_| {
+                let z: f64 = rand_distr::Distribution::sample(&StandardNormal, &mut rng);
+                mean + std_dev * z
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_historical() {
+        // Losses 1, 2, ..., 100.
+        let pnl: Vec<f64> = (1..=100).map(|i| -f64::from(i)).collect();
+
+        let risk = ValueAtRisk::new(0.95, VarMethod::Historical)
+            .compute(&pnl)
+            .unwrap();
+
+        // Interpolated 95% quantile of 1..=100.
+        assert_approx_equal!(risk.value_at_risk, 95.05, EPS);
+        // Mean of 96..=100.
+        assert_approx_equal!(risk.expected_shortfall, 98.0, EPS);
+    }
+
+    #[test]
+    fn test_parametric() {
+        let pnl = [-1.0, 1.0, -1.0, 1.0];
+        let (mean, std_dev) = mean_and_std_dev(&pnl);
+
+        let risk = ValueAtRisk::new(0.99, VarMethod::Parametric)
+            .compute(&pnl)
+            .unwrap();
+
+        let z: f64 = 2.326_347_874_040_841;
+        let phi = (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt();
+
+        assert_approx_equal!(risk.value_at_risk, z * std_dev - mean, 1e-8);
+        assert_approx_equal!(risk.expected_shortfall, std_dev * phi / 0.01, 1e-8);
+    }
+
+    #[test]
+    fn test_methods_agree_for_normal_pnl() {
+        let pnl = normal_sample(0.001, 0.02, 50_000);
+        let confidence = 0.99;
+
+        let historical = ValueAtRisk::new(confidence, VarMethod::Historical)
+            .compute(&pnl)
+            .unwrap();
+        let parametric = ValueAtRisk::new(confidence, VarMethod::Parametric)
+            .compute(&pnl)
+            .unwrap();
+        let cornish_fisher = ValueAtRisk::new(confidence, VarMethod::CornishFisher)
+            .compute(&pnl)
+            .unwrap();
+
+        let exact = 0.02 * 2.326_347_874_040_841 - 0.001;
+
+        for risk in [historical, parametric, cornish_fisher] {
+            assert!((risk.value_at_risk / exact - 1.0).abs() < 0.03);
+            assert!(risk.expected_shortfall > risk.value_at_risk);
+        }
+
+        assert!(
+            (cornish_fisher.expected_shortfall / parametric.expected_shortfall - 1.0).abs() < 0.03
+        );
+    }
+
+    #[test]
+    fn test_cornish_fisher_fat_tails() {
+        // Mixture with occasional large losses: negatively skewed, fat-tailed.
+        let mut pnl = normal_sample(0.0, 1.0, 10_000);
+        pnl.iter_mut().step_by(50).for_each(|x| *x -= 5.0);
+
+        let parametric = ValueAtRisk::new(0.99, VarMethod::Parametric)
+            .compute(&pnl)
+            .unwrap();
+        let cornish_fisher = ValueAtRisk::new(0.99, VarMethod::CornishFisher)
+            .compute(&pnl)
+            .unwrap();
+
+        assert!(cornish_fisher.value_at_risk > parametric.value_at_risk);
+        assert!(cornish_fisher.expected_shortfall > parametric.expected_shortfall);
+    }
+
+    #[test]
+    fn test_delta_normal() {
+        let covariance = DMatrix::from_row_slice(2, 2, &[0.04, 0.006, 0.006, 0.09]);
+        let exposures = [100.0, 50.0];
+
+        let risk = delta_normal(&exposures, &covariance, 0.95).unwrap();
+
+        let variance: f64 = 100.0 * 100.0 * 0.04 + 2.0 * 100.0 * 50.0 * 0.006 + 50.0 * 50.0 * 0.09;
+        let z = 1.644_853_626_951_472_2;
+
+        assert_approx_equal!(risk.value_at_risk, z * variance.sqrt(), 1e-8);
+        assert!(risk.expected_shortfall > risk.value_at_risk);
+
+        // Square-root-of-time scaling.
+        let ten_day = risk.scale(10.0);
+        assert_approx_equal!(
+            ten_day.value_at_risk,
+            risk.value_at_risk * 10.0_f64.sqrt(),
+            EPS
+        );
+
+        assert!(delta_normal(&[1.0], &covariance, 0.95).is_err());
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        let pnl = [1.0, -1.0, 2.0];
+
+        assert!(ValueAtRisk::new(1.0, VarMethod::Historical)
+            .compute(&pnl)
+            .is_err());
+        assert!(ValueAtRisk::new(0.0, VarMethod::Parametric)
+            .compute(&pnl)
+            .is_err());
+        assert!(ValueAtRisk::new(0.99, VarMethod::CornishFisher)
+            .compute(&pnl)
+            .is_err());
+        assert!(ValueAtRisk::new(0.99, VarMethod::Historical)
+            .compute(&[])
+            .is_err());
+    }
+}
@@ -0,0 +1,253 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Funding, margin, and capital valuation adjustments.
+//!
+//! On top of the CVA, the costs of a netting set over its life are computed
+//! from its exposure profile, discounted and weighted by the counterparty's
+//! survival $S(t)$ over each period $\Delta t_i$:
+//!
+//! - FVA: the uncollateralised positive exposure is funded at the borrowing
+//!   spread (FCA), the negative exposure earns the lending spread (FBA),
+//!   $FVA = \sum_i P(t_i) S(t_i) (s_b EE_i - s_l ENE_i) \Delta t_i$;
+//! - MVA: the initial margin posted is funded at the borrowing spread,
+//!   $MVA = \sum_i P(t_i) S(t_i) s_b IM_i \Delta t_i$;
+//! - KVA: the regulatory capital, $K_i = c \cdot RW \cdot \alpha \cdot EEE_i$
+//!   from the effective EE, costs the hurdle rate $h$,
+//!   $KVA = \sum_i P(t_i) S(t_i) h K_i \Delta t_i$.
+//!
+//! The initial margin profile comes from the exposure engine's
+//! [`SimmApproximation`](super::SimmApproximation).
+
+use super::cva::discount_factors;
+use super::{CounterpartyCredit, ExposureProfile};
+use crate::error::RustQuantError;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::{PricingContext, Validate, Validator};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// XVA calculator: credit, funding, and capital terms of a netting set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XvaCalculator {
+    /// Credit of the counterparty.
+    pub counterparty: CounterpartyCredit,
+    /// Spread paid over the discount curve to borrow.
+    pub borrowing_spread: f64,
+    /// Spread earned over the discount curve on surplus cash.
+    pub lending_spread: f64,
+    /// Hurdle rate on the regulatory capital.
+    pub cost_of_capital: f64,
+    /// Capital ratio, e.g. `0.08`.
+    pub capital_ratio: f64,
+    /// Risk weight of the counterparty.
+    pub risk_weight: f64,
+    /// Multiplier of the effective EE giving the exposure at default.
+    pub alpha: f64,
+}
+
+/// Valuation adjustments of a netting set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValuationAdjustments {
+    /// Credit valuation adjustment.
+    pub cva: f64,
+    /// Funding cost adjustment.
+    pub funding_cost: f64,
+    /// Funding benefit adjustment.
+    pub funding_benefit: f64,
+    /// Funding valuation adjustment, the cost less the benefit.
+    pub fva: f64,
+    /// Margin valuation adjustment.
+    pub mva: f64,
+    /// Capital valuation adjustment.
+    pub kva: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Validate for XvaCalculator {
+    fn validate(&self) -> Result<(), RustQuantError> {
+        Validator::new()
+            .finite("borrowing_spread", self.borrowing_spread)
+            .finite("lending_spread", self.lending_spread)
+            .non_negative("cost_of_capital", self.cost_of_capital)
+            .non_negative("capital_ratio", self.capital_ratio)
+            .non_negative("risk_weight", self.risk_weight)
+            .positive("alpha", self.alpha)
+            .finish()
+    }
+}
+
+impl XvaCalculator {
+    /// New calculator, without capital costs (a zero hurdle rate, an 8%
+    /// capital ratio, a 100% risk weight, and $\alpha = 1.4$).
+    #[must_use]
+    pub const fn new(
+        counterparty: CounterpartyCredit,
+        borrowing_spread: f64,
+        lending_spread: f64,
+    ) -> Self {
+        Self {
+            counterparty,
+            borrowing_spread,
+            lending_spread,
+            cost_of_capital: 0.0,
+            capital_ratio: 0.08,
+            risk_weight: 1.0,
+            alpha: 1.4,
+        }
+    }
+
+    /// Set the hurdle rate on the capital and the counterparty risk weight.
+    #[must_use]
+    pub const fn with_capital(self, cost_of_capital: f64, risk_weight: f64) -> Self {
+        Self {
+            cost_of_capital,
+            risk_weight,
+            ..self
+        }
+    }
+
+    /// Valuation adjustments of an exposure profile, discounted on the
+    /// context's curve for `currency`.
+    ///
+    /// # Errors
+    ///
+    /// If the parameters are invalid, or the CVA cannot be computed.
+    pub fn compute(
+        &self,
+        profile: &ExposureProfile,
+        ctx: &PricingContext,
+        currency: &Currency,
+    ) -> Result<ValuationAdjustments, RustQuantError> {
+        self.validate()?;
+
+        let cva = self
+            .counterparty
+            .credit_valuation_adjustment(profile, ctx, currency)?
+            .cva;
+
+        // Discounted survival over each period.
+        let mut previous = 0.0;
+        let weights: Vec<f64> = profile
+            .times
+            .iter()
+            .zip(discount_factors(profile, ctx, currency)?)
+            .map(|(&t, df)| {
+                let weight = df * self.counterparty.survival_probability(t) * (t - previous);
+                previous = t;
+                weight
+            })
+            .collect();
+
+        let integrate = |profile: &[f64]| -> f64 {
+            profile
+                .iter()
+                .zip(&weights)
+                .map(|(value, weight)| value * weight)
+                .sum()
+        };
+
+        let funding_cost = self.borrowing_spread * integrate(&profile.expected_exposure);
+        let funding_benefit = self.lending_spread * integrate(&profile.expected_negative_exposure);
+        let mva = self.borrowing_spread * integrate(&profile.initial_margin);
+        let kva = self.cost_of_capital
+            * self.capital_ratio
+            * self.risk_weight
+            * self.alpha
+            * integrate(&profile.effective_expected_exposure());
+
+        Ok(ValuationAdjustments {
+            cva,
+            funding_cost,
+            funding_benefit,
+            fva: funding_cost - funding_benefit,
+            mva,
+            kva,
+        })
+    }
+}
+
+impl ValuationAdjustments {
+    /// Sum of the adjustments.
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.cva + self.fva + self.mva + self.kva
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_xva {
+    use super::*;
+    use crate::data::{Curve, YieldCurve};
+    use crate::iso::USD;
+    use time::macros::date;
+    use time::Duration;
+
+    #[test]
+    fn test_flat_profile_adjustments() {
+        let t0 = date!(2024 - 01 - 01);
+        let pillars = [0, 365, 730, 1095].map(|days| t0 + Duration::days(days));
+        let ctx = PricingContext::new(t0).with_discount_curve(
+            USD,
+            YieldCurve::from_dates_and_rates(&pillars, &[0.0; 4]).unwrap(),
+        );
+
+        let times: Vec<f64> = (1..=8).map(|i| 91.0 * i as f64 / 365.0).collect();
+        let profile = ExposureProfile {
+            dates: (1..=8).map(|i| t0 + Duration::days(91 * i)).collect(),
+            times: times.clone(),
+            expected_exposure: vec![100.0; 8],
+            expected_negative_exposure: vec![50.0; 8],
+            potential_future_exposure: vec![150.0; 8],
+            values: vec![],
+            shocks: vec![],
+            initial_margin: vec![30.0; 8],
+        };
+
+        let counterparty = CounterpartyCredit::new(0.02, 0.4);
+        let xva = XvaCalculator::new(counterparty, 0.01, 0.005)
+            .with_capital(0.1, 1.0)
+            .compute(&profile, &ctx, &USD)
+            .unwrap();
+
+        // Survival-weighted life of the netting set.
+        let dt = 91.0 / 365.0;
+        let life: f64 = times.iter().map(|&t| (-0.02 * t).exp() * dt).sum();
+
+        assert_approx_equal!(xva.funding_cost, 0.01 * 100.0 * life, 1e-9);
+        assert_approx_equal!(xva.funding_benefit, 0.005 * 50.0 * life, 1e-9);
+        assert_approx_equal!(xva.fva, 0.75 * life, 1e-9);
+        assert_approx_equal!(xva.mva, 0.01 * 30.0 * life, 1e-9);
+        assert_approx_equal!(xva.kva, 0.1 * 0.08 * 1.4 * 100.0 * life, 1e-9);
+        assert_approx_equal!(
+            xva.cva,
+            0.6 * 100.0 * (1.0 - (-0.02 * times[7]).exp()),
+            1e-9
+        );
+        assert_approx_equal!(xva.total(), xva.cva + xva.fva + xva.mva + xva.kva, 1e-12);
+
+        // No capital costs by default.
+        let without_capital = XvaCalculator::new(counterparty, 0.01, 0.005)
+            .compute(&profile, &ctx, &USD)
+            .unwrap();
+        assert_approx_equal!(without_capital.kva, 0.0, 1e-15);
+
+        let invalid = XvaCalculator::new(counterparty, 0.01, 0.005).with_capital(-0.1, 1.0);
+        assert!(invalid.compute(&profile, &ctx, &USD).is_err());
+    }
+}
@@ -0,0 +1,393 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! REST pricing service.
+//!
+//! An [`axum`] router exposing the pricers over JSON, so the crate can be
+//! deployed as a pricing service. Requests and responses reuse the serde
+//! models of the library ([`Trade`], [`BlackScholesMerton`],
+//! [`BootstrapQuote`], [`YieldCurve`]):
+//!
+//! | Endpoint        | Request           | Response           |
+//! |-----------------|-------------------|--------------------|
+//! | `GET /health`   |                   | `"ok"`             |
+//! | `POST /price`   | [`PriceRequest`]  | [`PriceResponse`]  |
+//! | `POST /greeks`  | [`GreeksRequest`] | [`GreeksResponse`] |
+//! | `POST /curve`   | [`CurveRequest`]  | [`CurveResponse`]  |
+//!
+//! Invalid requests are answered with `422 Unprocessable Entity` and a
+//! JSON body `{ "error": "..." }`.
+//!
+//! Requires the `server` feature. See `examples/pricing_server.rs`:
+//!
+//! ```no_run
+//! # async fn run() -> std::io::Result<()> {
+//! RustQuant::server::serve("0.0.0.0:8080").await
+//! # }
+//! ```
+
+use crate::data::{bootstrap_yield_curve, BootstrapQuote, Curve, YieldCurve};
+use crate::error::RustQuantError;
+use crate::instruments::fx::currency::Currency;
+use crate::instruments::options::BlackScholesMerton;
+use crate::instruments::{PricingContext, Trade, Validate};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use time::Date;
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// `POST /price`: value a list of trades.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PriceRequest {
+    /// Valuation date.
+    pub valuation_date: Date,
+
+    /// Discount curves, by currency.
+    #[serde(default)]
+    pub discount_curves: Vec<DiscountCurve>,
+
+    /// The trades, in the [`TradeBlotter`](crate::instruments::TradeBlotter) schema.
+    pub trades: Vec<Trade>,
+}
+
+/// A discount curve for a currency.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DiscountCurve {
+    /// Currency the curve discounts.
+    pub currency: Currency,
+
+    /// The curve.
+    pub curve: YieldCurve,
+}
+
+/// Response to [`PriceRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceResponse {
+    /// Sum of the trade values.
+    pub total: f64,
+
+    /// Value of each trade, in request order.
+    pub trades: Vec<TradeValue>,
+}
+
+/// Value of a single trade.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeValue {
+    /// Trade identifier.
+    pub id: String,
+
+    /// Trade value (quantity times instrument value).
+    pub value: f64,
+}
+
+/// `POST /greeks`: price and Greeks of a European option.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GreeksRequest {
+    /// Valuation date, used if the option has no evaluation date.
+    pub valuation_date: Date,
+
+    /// The option.
+    pub option: BlackScholesMerton,
+}
+
+/// Response to [`GreeksRequest`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GreeksResponse {
+    /// Option price.
+    pub price: f64,
+    /// dV/dS.
+    pub delta: f64,
+    /// d^2V/dS^2.
+    pub gamma: f64,
+    /// dV/dsigma.
+    pub vega: f64,
+    /// -dV/dT.
+    pub theta: f64,
+    /// dV/dr.
+    pub rho: f64,
+    /// d^2V/dS dsigma.
+    pub vanna: f64,
+    /// d^2V/dsigma^2.
+    pub vomma: f64,
+    /// -d^2V/dS dT.
+    pub charm: f64,
+}
+
+/// `POST /curve`: bootstrap a curve and (optionally) read discount
+/// factors off it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveRequest {
+    /// Valuation date.
+    pub valuation_date: Date,
+
+    /// Deposit and par swap quotes.
+    pub quotes: Vec<BootstrapQuote>,
+
+    /// Dates to return discount factors for (optional).
+    #[serde(default)]
+    pub dates: Vec<Date>,
+}
+
+/// Response to [`CurveRequest`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CurveResponse {
+    /// The bootstrapped zero curve.
+    pub curve: YieldCurve,
+
+    /// Discount factors for the requested dates, in request order.
+    pub discount_factors: Vec<f64>,
+}
+
+/// An error, returned as `422 Unprocessable Entity`.
+#[derive(Debug)]
+pub struct ServerError(pub RustQuantError);
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl From<RustQuantError> for ServerError {
+    fn from(error: RustQuantError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            error: self.0.to_string(),
+        };
+
+        (StatusCode::UNPROCESSABLE_ENTITY, Json(body)).into_response()
+    }
+}
+
+impl PriceRequest {
+    /// Value the trades.
+    ///
+    /// # Errors
+    ///
+    /// If a trade cannot be valued, e.g. a bond without a discount curve
+    /// for its currency.
+    pub fn evaluate(&self) -> Result<PriceResponse, RustQuantError> {
+        let ctx = self
+            .discount_curves
+            .iter()
+            .fold(PricingContext::new(self.valuation_date), |ctx, c| {
+                ctx.with_discount_curve(c.currency, c.curve.clone())
+            });
+
+        let trades = self
+            .trades
+            .iter()
+            .map(|trade| {
+                Ok(TradeValue {
+                    id: trade.id.clone(),
+                    value: trade.value(&ctx)?,
+                })
+            })
+            .collect::<Result<Vec<_>, RustQuantError>>()?;
+
+        Ok(PriceResponse {
+            total: trades.iter().map(|t| t.value).sum(),
+            trades,
+        })
+    }
+}
+
+impl GreeksRequest {
+    /// Price and Greeks of the option.
+    ///
+    /// # Errors
+    ///
+    /// If the option's parameters are invalid (see [`Validate`]), e.g. it
+    /// expires on or before its evaluation date.
+    pub fn evaluate(&self) -> Result<GreeksResponse, RustQuantError> {
+        let option = BlackScholesMerton {
+            evaluation_date: self.option.evaluation_date.or(Some(self.valuation_date)),
+            ..self.option
+        }
+        .validated()?;
+
+        Ok(GreeksResponse {
+            price: option.price(),
+            delta: option.delta(),
+            gamma: option.gamma(),
+            vega: option.vega(),
+            theta: option.theta(),
+            rho: option.rho(),
+            vanna: option.vanna(),
+            vomma: option.vomma(),
+            charm: option.charm(),
+        })
+    }
+}
+
+impl CurveRequest {
+    /// Bootstrap the curve.
+    ///
+    /// # Errors
+    ///
+    /// If the quotes cannot be bootstrapped (see [`bootstrap_yield_curve`]),
+    /// or a requested date is outside the curve.
+    pub fn evaluate(&self) -> Result<CurveResponse, RustQuantError> {
+        let curve = bootstrap_yield_curve(self.valuation_date, &self.quotes)?;
+
+        Ok(CurveResponse {
+            discount_factors: curve.discount_factors(&self.dates)?,
+            curve,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// The pricing service's routes.
+pub fn router() -> Router {
+    Router::new()
+        .route("/health", get(|| async { "ok" }))
+        .route("/price", post(price))
+        .route("/greeks", post(greeks))
+        .route("/curve", post(curve))
+}
+
+/// Serve [`router`] on `addr` until the process is stopped.
+///
+/// # Errors
+///
+/// If the address cannot be bound.
+pub async fn serve<A: ToSocketAddrs>(addr: A) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+
+    axum::serve(listener, router()).await
+}
+
+async fn price(Json(request): Json<PriceRequest>) -> Result<Json<PriceResponse>, ServerError> {
+    Ok(Json(request.evaluate()?))
+}
+
+async fn greeks(Json(request): Json<GreeksRequest>) -> Result<Json<GreeksResponse>, ServerError> {
+    Ok(Json(request.evaluate()?))
+}
+
+async fn curve(Json(request): Json<CurveRequest>) -> Result<Json<CurveResponse>, ServerError> {
+    Ok(Json(request.evaluate()?))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_server {
+    use super::*;
+
+    #[test]
+    fn test_price_request() {
+        let request: PriceRequest = serde_json::from_str(
+            r#"{
+                "valuation_date": "2024-01-01",
+                "trades": [
+                    {
+                        "id": "T1",
+                        "quantity": 10,
+                        "instrument": {
+                            "type": "EuropeanOption",
+                            "cost_of_carry": 0.05,
+                            "underlying_price": 100.0,
+                            "strike_price": 100.0,
+                            "volatility": 0.2,
+                            "risk_free_rate": 0.05,
+                            "expiration_date": "2025-01-01",
+                            "option_type": "Call"
+                        }
+                    },
+                    { "id": "T2", "instrument": { "type": "Cash", "currency": "USD" } }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let response = request.evaluate().unwrap();
+
+        assert_eq!(response.trades.len(), 2);
+        assert_approx_equal!(
+            response.total,
+            response.trades[0].value + response.trades[1].value,
+            1e-12
+        );
+        assert!(response.trades[0].value > 0.0);
+    }
+
+    #[test]
+    fn test_greeks_request() {
+        let request: GreeksRequest = serde_json::from_str(
+            r#"{
+                "valuation_date": "2024-01-01",
+                "option": {
+                    "cost_of_carry": 0.05,
+                    "underlying_price": 100.0,
+                    "strike_price": 100.0,
+                    "volatility": 0.2,
+                    "risk_free_rate": 0.05,
+                    "expiration_date": "2025-01-01",
+                    "option_type": "Call"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let response = request.evaluate().unwrap();
+
+        assert!(response.delta > 0.0 && response.delta < 1.0);
+        assert!(response.gamma > 0.0);
+
+        let mut expired = request;
+        expired.valuation_date = time::macros::date!(2026 - 01 - 01);
+        assert!(expired.evaluate().is_err());
+    }
+
+    #[test]
+    fn test_curve_request() {
+        let request: CurveRequest = serde_json::from_str(
+            r#"{
+                "valuation_date": "2024-01-01",
+                "quotes": [
+                    { "type": "Deposit", "maturity": "2024-07-01", "rate": 0.05 },
+                    { "type": "Swap", "maturity": "2029-01-01", "rate": 0.045, "frequency": "Annually" }
+                ],
+                "dates": ["2026-01-01"]
+            }"#,
+        )
+        .unwrap();
+
+        let response = request.evaluate().unwrap();
+
+        assert_eq!(response.discount_factors.len(), 1);
+        assert!(response.discount_factors[0] < 1.0);
+
+        let mut outside = request;
+        outside.dates = vec![time::macros::date!(2030 - 01 - 01)];
+        assert!(outside.evaluate().is_err());
+    }
+}
@@ -230,7 +230,7 @@ impl StochasticProcess for FractionalBrownianMotion {
 // TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-#[cfg(test)]
+#[cfg(all(test, feature = "ml"))]
 mod test_fractional_brownian_motion {
     // use std::time::Instant;
 
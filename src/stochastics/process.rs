@@ -17,6 +17,7 @@
 use rand::prelude::Distribution;
 use rand::{rngs::StdRng, SeedableRng};
 use rayon::prelude::*;
+use std::time::Instant;
 // use statrs::distribution::Normal;
 
 /// Struct to contain the time points and path values of the process.
@@ -57,6 +58,15 @@ pub trait StochasticVolatilityProcess: Sync {
 
         let dt: f64 = (t_n - t_0) / (n_steps as f64);
 
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "euler_maruyama",
+            paths = m_paths,
+            steps = n_steps,
+            parallel = parallel,
+        );
+
         // Initialise empty paths and fill in the time points.
         let mut x_paths = vec![vec![x_0; n_steps + 1]; m_paths];
         let mut y_paths = vec![vec![y_0; n_steps + 1]; m_paths];
@@ -94,6 +104,13 @@ pub trait StochasticVolatilityProcess: Sync {
                 .for_each(path_generator);
         }
 
+        trace_event!(
+            DEBUG,
+            "batch",
+            paths = m_paths,
+            elapsed = start.elapsed().as_secs_f64(),
+        );
+
         Trajectories {
             times: times.clone(),
             paths: x_paths,
@@ -135,6 +152,15 @@ pub trait StochasticProcess: Sync {
 
         let dt: f64 = (t_n - t_0) / (n_steps as f64);
 
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "euler_maruyama",
+            paths = m_paths,
+            steps = n_steps,
+            parallel = parallel,
+        );
+
         // Initialise empty paths and fill in the time points.
         let mut paths = vec![vec![x_0; n_steps + 1]; m_paths];
         let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
@@ -162,6 +188,13 @@ pub trait StochasticProcess: Sync {
             paths.iter_mut().for_each(path_generator);
         }
 
+        trace_event!(
+            DEBUG,
+            "batch",
+            paths = m_paths,
+            elapsed = start.elapsed().as_secs_f64(),
+        );
+
         Trajectories { times, paths }
     }
 
@@ -189,6 +222,15 @@ pub trait StochasticProcess: Sync {
 
         let dt: f64 = (t_n - t_0) / (n_steps as f64);
 
+        let start = Instant::now();
+        let _span = trace_span!(
+            DEBUG,
+            "euler_maruyama",
+            paths = m_paths,
+            steps = n_steps,
+            parallel = parallel,
+        );
+
         // Initialise empty paths and fill in the time points.
         let mut paths = vec![vec![x_0; n_steps + 1]; m_paths];
         let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
@@ -216,6 +258,13 @@ pub trait StochasticProcess: Sync {
             paths.iter_mut().for_each(path_generator);
         }
 
+        trace_event!(
+            DEBUG,
+            "batch",
+            paths = m_paths,
+            elapsed = start.elapsed().as_secs_f64(),
+        );
+
         Trajectories { times, paths }
     }
 }
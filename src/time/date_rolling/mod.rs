@@ -43,6 +43,8 @@ use time::Date;
 /// time such that it falls in a business day, according with the
 /// same business calendar.
 /// """
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DateRollingConvention {
     /// Actual: paid on the actual day, even if it is a non-business day.
     Actual,
@@ -51,6 +51,8 @@ use time::{Date, Duration};
 /// payment dates, the seller is eligible to some fraction of the coupon amount.
 /// """
 #[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DayCountConvention {
     /// The '1/1' day count, which always returns a day count of 1.
     One_One,
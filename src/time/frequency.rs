@@ -18,7 +18,8 @@ use crate::time::constants::{
 /// This is important in finance, as it determines the number of times
 /// a cash flow is paid in a year, and thus affects the present value
 /// of the cash flows.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Frequency {
     /// Daily (252 per year).
     Daily = DAILY,
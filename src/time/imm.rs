@@ -0,0 +1,432 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! IMM, CDS and futures date utilities.
+//!
+//! - IMM dates are the third Wednesday of March, June, September and
+//!   December (the "main cycle"), or of any month (the "serial" months).
+//! - CDS standard roll dates are the 20th of March, June, September
+//!   and December, with the on-the-run series rolling semi-annually on
+//!   the 20th of March and September.
+//! - Futures contracts are identified by a root, a month code and a year
+//!   digit, e.g. "EDZ5" is the December 2025 Eurodollar contract.
+
+use crate::error::RustQuantError;
+use crate::time::{add_months, get_third_wednesday_of_month, Tenor};
+use std::fmt;
+use std::str::FromStr;
+use time::{Date, Month};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Futures month codes, January to December.
+pub const FUTURES_MONTH_CODES: [char; 12] =
+    ['F', 'G', 'H', 'J', 'K', 'M', 'N', 'Q', 'U', 'V', 'X', 'Z'];
+
+/// A futures contract code, such as "EDZ5" or "SFRH25".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuturesCode {
+    /// Contract root (e.g. "ED", "SFR").
+    pub root: String,
+
+    /// Contract (delivery) month.
+    pub month: Month,
+
+    /// Contract year.
+    pub year: i32,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Function to check if the month is in the main IMM cycle
+/// (March, June, September, December).
+pub fn is_imm_month(month: Month) -> bool {
+    matches!(
+        month,
+        Month::March | Month::June | Month::September | Month::December
+    )
+}
+
+/// Function to check if the date is an IMM date (third Wednesday of the month).
+/// If `main_cycle` is true, only March, June, September and December count.
+pub fn is_imm_date(date: Date, main_cycle: bool) -> bool {
+    if main_cycle && !is_imm_month(date.month()) {
+        return false;
+    }
+
+    get_third_wednesday_of_month(date.year(), date.month()).ok() == Some(date)
+}
+
+/// Function to get the first IMM date strictly after the given date.
+///
+/// ```
+/// use time::macros::date;
+/// use RustQuant::time::imm::next_imm_date;
+///
+/// assert_eq!(next_imm_date(date!(2024 - 03 - 20), true), date!(2024 - 06 - 19));
+/// assert_eq!(next_imm_date(date!(2024 - 03 - 20), false), date!(2024 - 04 - 17));
+/// ```
+pub fn next_imm_date(date: Date, main_cycle: bool) -> Date {
+    let mut month_start = date.replace_day(1).unwrap();
+
+    loop {
+        if !main_cycle || is_imm_month(month_start.month()) {
+            let imm =
+                get_third_wednesday_of_month(month_start.year(), month_start.month()).unwrap();
+
+            if imm > date {
+                return imm;
+            }
+        }
+
+        month_start = add_months(month_start, 1);
+    }
+}
+
+/// Function to get the last IMM date strictly before the given date.
+pub fn previous_imm_date(date: Date, main_cycle: bool) -> Date {
+    let mut month_start = date.replace_day(1).unwrap();
+
+    loop {
+        if !main_cycle || is_imm_month(month_start.month()) {
+            let imm =
+                get_third_wednesday_of_month(month_start.year(), month_start.month()).unwrap();
+
+            if imm < date {
+                return imm;
+            }
+        }
+
+        month_start = add_months(month_start, -1);
+    }
+}
+
+/// Function to get the next `n` IMM dates strictly after the given date.
+pub fn next_imm_dates(date: Date, n: usize, main_cycle: bool) -> Vec<Date> {
+    let mut dates = Vec::with_capacity(n);
+    let mut current = date;
+
+    for _ in 0..n {
+        current = next_imm_date(current, main_cycle);
+        dates.push(current);
+    }
+
+    dates
+}
+
+/// Function to get the IMM code of a date (e.g. "H4" for March 2024).
+pub fn imm_code(date: Date) -> String {
+    format!(
+        "{}{}",
+        futures_month_code(date.month()),
+        date.year().rem_euclid(10)
+    )
+}
+
+/// Function to get the IMM date for an IMM code (e.g. "H4").
+/// The year digit is resolved to the first matching year on or after
+/// the year of the `reference_date`.
+pub fn imm_date_from_code(code: &str, reference_date: Date) -> Result<Date, RustQuantError> {
+    let code = code.trim().to_uppercase();
+    let mut chars = code.chars();
+
+    let (Some(month_code), Some(year_digit), None) = (chars.next(), chars.next(), chars.next())
+    else {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "Invalid IMM code: {code:?}"
+        )));
+    };
+
+    let month = month_from_futures_code(month_code)?;
+    let year = resolve_year(&year_digit.to_string(), reference_date.year())?;
+
+    Ok(get_third_wednesday_of_month(year, month).unwrap())
+}
+
+/// Function to check if the date is a CDS standard roll date
+/// (20th of March, June, September or December).
+pub fn is_cds_date(date: Date) -> bool {
+    date.day() == 20 && is_imm_month(date.month())
+}
+
+/// Function to get the first CDS standard date strictly after the given date.
+///
+/// ```
+/// use time::macros::date;
+/// use RustQuant::time::imm::next_cds_date;
+///
+/// assert_eq!(next_cds_date(date!(2024 - 03 - 20)), date!(2024 - 06 - 20));
+/// assert_eq!(next_cds_date(date!(2024 - 11 - 30)), date!(2024 - 12 - 20));
+/// ```
+pub fn next_cds_date(date: Date) -> Date {
+    let mut candidate = date.replace_day(20).unwrap();
+
+    while !is_imm_month(candidate.month()) || candidate <= date {
+        candidate = add_months(candidate, 1);
+    }
+
+    candidate
+}
+
+/// Function to get the last CDS standard date strictly before the given date.
+pub fn previous_cds_date(date: Date) -> Date {
+    let mut candidate = date.replace_day(20).unwrap();
+
+    while !is_imm_month(candidate.month()) || candidate >= date {
+        candidate = add_months(candidate, -1);
+    }
+
+    candidate
+}
+
+/// Function to get the maturity date of a standard CDS contract traded on
+/// `trade_date` with the given tenor (e.g. "5Y").
+///
+/// Under the semi-annual roll convention (since December 2015), contracts
+/// traded from 20 March (inclusive) to 20 September (exclusive) mature on
+/// 20 June, and those traded from 20 September to 20 March mature on
+/// 20 December, plus the tenor.
+///
+/// ```
+/// use time::macros::date;
+/// use RustQuant::time::{imm::cds_maturity_date, Tenor};
+///
+/// let maturity = cds_maturity_date(date!(2024 - 05 - 10), Tenor::years(5));
+///
+/// assert_eq!(maturity, date!(2029 - 06 - 20));
+/// ```
+pub fn cds_maturity_date(trade_date: Date, tenor: Tenor) -> Date {
+    let year = trade_date.year();
+    let march_roll = Date::from_calendar_date(year, Month::March, 20).unwrap();
+    let september_roll = Date::from_calendar_date(year, Month::September, 20).unwrap();
+
+    let base = if trade_date < march_roll {
+        Date::from_calendar_date(year - 1, Month::December, 20).unwrap()
+    } else if trade_date < september_roll {
+        Date::from_calendar_date(year, Month::June, 20).unwrap()
+    } else {
+        Date::from_calendar_date(year, Month::December, 20).unwrap()
+    };
+
+    base + tenor
+}
+
+/// Function to get the futures month code of a month (e.g. 'Z' for December).
+pub fn futures_month_code(month: Month) -> char {
+    FUTURES_MONTH_CODES[month as usize - 1]
+}
+
+/// Function to get the month of a futures month code (e.g. December for 'Z').
+pub fn month_from_futures_code(code: char) -> Result<Month, RustQuantError> {
+    let index = FUTURES_MONTH_CODES
+        .iter()
+        .position(|&c| c == code.to_ascii_uppercase())
+        .ok_or_else(|| {
+            RustQuantError::InvalidArgument(format!("Invalid futures month code: {code:?}"))
+        })?;
+
+    Ok(Month::try_from(index as u8 + 1).unwrap())
+}
+
+/// Resolve a one or two digit year to the first matching year on or after
+/// the reference year.
+fn resolve_year(digits: &str, reference_year: i32) -> Result<i32, RustQuantError> {
+    let invalid = || RustQuantError::InvalidArgument(format!("Invalid futures year: {digits:?}"));
+
+    if digits.is_empty() || digits.len() > 2 {
+        return Err(invalid());
+    }
+
+    let value = digits.parse::<i32>().map_err(|_| invalid())?;
+    let modulus = 10_i32.pow(digits.len() as u32);
+
+    let mut year = reference_year - reference_year.rem_euclid(modulus) + value;
+
+    if year < reference_year {
+        year += modulus;
+    }
+
+    Ok(year)
+}
+
+impl FuturesCode {
+    /// Parse a futures code such as "EDZ5" or "SFRH25".
+    /// The year digit(s) are resolved to the first matching year on or
+    /// after the year of the `reference_date`.
+    ///
+    /// ```
+    /// use time::{macros::date, Month};
+    /// use RustQuant::time::imm::FuturesCode;
+    ///
+    /// let code = FuturesCode::parse("EDZ5", date!(2024 - 01 - 01)).unwrap();
+    ///
+    /// assert_eq!(code.root, "ED");
+    /// assert_eq!(code.month, Month::December);
+    /// assert_eq!(code.year, 2025);
+    /// ```
+    pub fn parse(code: &str, reference_date: Date) -> Result<Self, RustQuantError> {
+        let code = code.trim().to_uppercase();
+
+        let digits_start = code.find(|c: char| c.is_ascii_digit()).ok_or_else(|| {
+            RustQuantError::InvalidArgument(format!("Invalid futures code: {code:?}"))
+        })?;
+
+        if digits_start < 2 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Invalid futures code: {code:?}"
+            )));
+        }
+
+        let (prefix, digits) = code.split_at(digits_start);
+        let (root_end, month_code) = prefix.char_indices().last().unwrap();
+
+        Ok(Self {
+            root: prefix[..root_end].to_string(),
+            month: month_from_futures_code(month_code)?,
+            year: resolve_year(digits, reference_date.year())?,
+        })
+    }
+
+    /// IMM date (third Wednesday) of the contract month.
+    /// This is the last trading date convention for IMM money market futures.
+    pub fn imm_date(&self) -> Date {
+        get_third_wednesday_of_month(self.year, self.month).unwrap()
+    }
+}
+
+impl FromStr for FuturesCode {
+    type Err = RustQuantError;
+
+    /// Parse a futures code relative to today's date.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, crate::time::today())
+    }
+}
+
+impl fmt::Display for FuturesCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.root,
+            futures_month_code(self.month),
+            self.year.rem_euclid(10)
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_imm {
+    use super::*;
+    use time::macros::date;
+
+    #[test]
+    fn test_imm_dates() {
+        assert!(is_imm_date(date!(2024 - 03 - 20), true));
+        assert!(!is_imm_date(date!(2024 - 05 - 15), true));
+        assert!(is_imm_date(date!(2024 - 05 - 15), false));
+
+        assert_eq!(
+            next_imm_date(date!(2024 - 12 - 20), true),
+            date!(2025 - 03 - 19)
+        );
+        assert_eq!(
+            previous_imm_date(date!(2024 - 03 - 20), true),
+            date!(2023 - 12 - 20)
+        );
+
+        assert_eq!(
+            next_imm_dates(date!(2024 - 01 - 01), 4, true),
+            vec![
+                date!(2024 - 03 - 20),
+                date!(2024 - 06 - 19),
+                date!(2024 - 09 - 18),
+                date!(2024 - 12 - 18)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_imm_codes() {
+        assert_eq!(imm_code(date!(2024 - 03 - 20)), "H4");
+        assert_eq!(
+            imm_date_from_code("Z4", date!(2024 - 01 - 01)).unwrap(),
+            date!(2024 - 12 - 18)
+        );
+        assert_eq!(
+            imm_date_from_code("H3", date!(2024 - 01 - 01)).unwrap(),
+            date!(2033 - 03 - 16)
+        );
+        assert!(imm_date_from_code("A4", date!(2024 - 01 - 01)).is_err());
+        assert!(imm_date_from_code("H45", date!(2024 - 01 - 01)).is_err());
+    }
+
+    #[test]
+    fn test_cds_dates() {
+        assert!(is_cds_date(date!(2024 - 09 - 20)));
+        assert!(!is_cds_date(date!(2024 - 10 - 20)));
+
+        assert_eq!(next_cds_date(date!(2024 - 12 - 20)), date!(2025 - 03 - 20));
+        assert_eq!(
+            previous_cds_date(date!(2024 - 03 - 20)),
+            date!(2023 - 12 - 20)
+        );
+        assert_eq!(
+            previous_cds_date(date!(2024 - 03 - 21)),
+            date!(2024 - 03 - 20)
+        );
+    }
+
+    #[test]
+    fn test_cds_maturity_date() {
+        let five_years = Tenor::years(5);
+
+        assert_eq!(
+            cds_maturity_date(date!(2024 - 03 - 19), five_years),
+            date!(2028 - 12 - 20)
+        );
+        assert_eq!(
+            cds_maturity_date(date!(2024 - 03 - 20), five_years),
+            date!(2029 - 06 - 20)
+        );
+        assert_eq!(
+            cds_maturity_date(date!(2024 - 09 - 20), five_years),
+            date!(2029 - 12 - 20)
+        );
+    }
+
+    #[test]
+    fn test_futures_codes() {
+        let reference = date!(2024 - 06 - 01);
+
+        let code = FuturesCode::parse("SFRH25", reference).unwrap();
+        assert_eq!(code.root, "SFR");
+        assert_eq!(code.month, Month::March);
+        assert_eq!(code.year, 2025);
+        assert_eq!(code.imm_date(), date!(2025 - 03 - 19));
+        assert_eq!(code.to_string(), "SFRH5");
+
+        let code = FuturesCode::parse("edu4", reference).unwrap();
+        assert_eq!(code.year, 2024);
+        assert_eq!(code.imm_date(), date!(2024 - 09 - 18));
+
+        assert!(FuturesCode::parse("EDZ", reference).is_err());
+        assert!(FuturesCode::parse("Z5", reference).is_err());
+        assert!(FuturesCode::parse("EDA5", reference).is_err());
+        assert!(FuturesCode::parse("ESÉ5", reference).is_err());
+    }
+}
@@ -46,3 +46,10 @@ pub use utilities::*;
 /// The `Schedule` type.
 pub mod schedule;
 pub use schedule::*;
+
+/// Tenors ("3M", "10Y", "ON") and tenor/date arithmetic.
+pub mod tenor;
+pub use tenor::*;
+
+/// IMM, CDS and futures date utilities.
+pub mod imm;
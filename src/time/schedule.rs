@@ -7,9 +7,11 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::error::RustQuantError;
 use crate::time::date_rolling::{DateRoller, DateRollingConvention};
 use crate::time::day_counting::{DayCountConvention, DayCounter};
-use crate::time::Calendar;
+use crate::time::utilities::{end_of_month, get_third_wednesday_of_month, is_end_of_month};
+use crate::time::{Calendar, Frequency, TenorUnit};
 use std::fmt;
 use time::Date;
 
@@ -26,6 +28,7 @@ use time::Date;
 ///
 /// The Schedule struct is used to represent these schedules,
 /// and pricing methods should be implemented using date/time functionality.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Schedule {
     /// The dates of the schedule.
     pub dates: Vec<Date>,
@@ -38,6 +41,141 @@ pub struct Schedule {
 
     /// The business day convention of the schedule.
     pub date_rolling_convention: DateRollingConvention,
+
+    /// The accrual periods of the schedule.
+    pub periods: Vec<AccrualPeriod>,
+}
+
+/// Accrual period struct.
+///
+/// An accrual period is the interval between two consecutive schedule dates,
+/// over which a coupon (or a reset, caplet, etc.) accrues.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccrualPeriod {
+    /// The (adjusted) start date of the accrual period.
+    pub start_date: Date,
+
+    /// The (adjusted) end date of the accrual period.
+    pub end_date: Date,
+
+    /// The unadjusted start date of the accrual period.
+    pub unadjusted_start_date: Date,
+
+    /// The unadjusted end date of the accrual period.
+    pub unadjusted_end_date: Date,
+
+    /// The payment date of the accrual period.
+    /// This is the adjusted end date of the period.
+    pub payment_date: Date,
+
+    /// The day count factor (year fraction) of the accrual period.
+    pub day_count_factor: f64,
+
+    /// Whether the accrual period is an irregular (stub) period.
+    pub is_stub: bool,
+}
+
+/// Roll conventions.
+///
+/// The roll convention determines the day of the month on which the
+/// (unadjusted) schedule dates fall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RollConvention {
+    /// No special rule: dates roll on the day of month of the anchor date
+    /// (clamped to the end of shorter months).
+    #[default]
+    None,
+
+    /// End of month: if the anchor date is the last day of its month,
+    /// all schedule dates fall on the last day of their month.
+    EndOfMonth,
+
+    /// IMM: all schedule dates fall on the third Wednesday of their month.
+    IMM,
+}
+
+/// Stub rules.
+///
+/// When the period between the effective date and the termination date is
+/// not an integer multiple of the frequency, an irregular period (stub) is
+/// required at either the front or the back of the schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StubRule {
+    /// Short stub at the front of the schedule.
+    /// Dates are generated backwards from the termination date.
+    #[default]
+    ShortFront,
+
+    /// Long stub at the front of the schedule.
+    /// Dates are generated backwards from the termination date,
+    /// and a short front stub is merged into the following period.
+    LongFront,
+
+    /// Short stub at the back of the schedule.
+    /// Dates are generated forwards from the effective date.
+    ShortBack,
+
+    /// Long stub at the back of the schedule.
+    /// Dates are generated forwards from the effective date,
+    /// and a short back stub is merged into the preceding period.
+    LongBack,
+}
+
+/// Schedule generator.
+///
+/// Generates the accrual periods of an instrument (e.g. the coupon periods of
+/// a bond or swap leg, the caplets of a cap, or the reset dates of a cliquet)
+/// from its effective date, termination date, and frequency, according to a
+/// roll convention and a stub rule.
+///
+/// ```
+/// use time::macros::date;
+/// use RustQuant::time::countries::oceania::australia::AustraliaCalendar;
+/// use RustQuant::time::{Frequency, ScheduleGeneratorBuilder, StubRule};
+///
+/// let schedule = ScheduleGeneratorBuilder::default()
+///     .effective_date(date!(2024 - 01 - 15))
+///     .termination_date(date!(2025 - 03 - 15))
+///     .frequency(Frequency::SemiAnnually)
+///     .stub_rule(StubRule::ShortFront)
+///     .build()
+///     .unwrap()
+///     .generate(&AustraliaCalendar)
+///     .unwrap();
+///
+/// assert_eq!(schedule.periods.len(), 3);
+/// assert!(schedule.periods[0].is_stub);
+/// ```
+#[derive(derive_builder::Builder, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduleGenerator {
+    /// The effective (start) date of the schedule.
+    pub effective_date: Date,
+
+    /// The termination (end) date of the schedule.
+    pub termination_date: Date,
+
+    /// The frequency of the schedule dates.
+    pub frequency: Frequency,
+
+    /// The roll convention (defaults to `RollConvention::None`).
+    #[builder(default)]
+    pub roll_convention: RollConvention,
+
+    /// The stub rule (defaults to `StubRule::ShortFront`).
+    #[builder(default)]
+    pub stub_rule: StubRule,
+
+    /// The business day convention used to adjust the schedule dates.
+    #[builder(default)]
+    pub date_rolling_convention: DateRollingConvention,
+
+    /// The day count convention used for the accrual factors.
+    #[builder(default)]
+    pub day_counting_convention: DayCountConvention,
 }
 
 /// The `Scheduler` trait.
@@ -83,11 +221,162 @@ where
             self.day_count_factor(today, rolled_dates[0], &day_counting_convention),
         );
 
+        let mut period_dates = rolled_dates.clone();
+        period_dates.insert(0, today);
+
+        let periods = period_dates
+            .windows(2)
+            .zip(dates.iter())
+            .zip(day_count_factors.iter())
+            .map(
+                |((window, &unadjusted_end_date), &day_count_factor)| AccrualPeriod {
+                    start_date: window[0],
+                    end_date: window[1],
+                    unadjusted_start_date: window[0],
+                    unadjusted_end_date,
+                    payment_date: window[1],
+                    day_count_factor,
+                    is_stub: false,
+                },
+            )
+            .collect();
+
         Schedule {
             dates: rolled_dates,
             day_count_factors,
             day_counting_convention,
             date_rolling_convention,
+            periods,
+        }
+    }
+}
+
+impl ScheduleGenerator {
+    /// Generate the schedule, adjusting the dates with the given calendar.
+    ///
+    /// The resulting `Schedule` contains the adjusted dates (including the
+    /// effective and termination dates), the day count factor of each
+    /// accrual period, and the accrual periods themselves.
+    ///
+    /// # Errors
+    ///
+    /// [`RustQuantError::InvalidArgument`] if the effective date is not
+    /// strictly before the termination date.
+    pub fn generate<C: Calendar>(&self, calendar: &C) -> Result<Schedule, RustQuantError> {
+        if self.effective_date >= self.termination_date {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Effective date ({}) must be before the termination date ({}).",
+                self.effective_date, self.termination_date
+            )));
+        }
+
+        let unadjusted_dates = self.unadjusted_dates();
+
+        let dates = calendar.roll_dates(&unadjusted_dates, &self.date_rolling_convention);
+        let day_count_factors = calendar.day_count_factors(&dates, &self.day_counting_convention);
+
+        let periods = (0..dates.len() - 1)
+            .map(|i| AccrualPeriod {
+                start_date: dates[i],
+                end_date: dates[i + 1],
+                unadjusted_start_date: unadjusted_dates[i],
+                unadjusted_end_date: unadjusted_dates[i + 1],
+                payment_date: dates[i + 1],
+                day_count_factor: day_count_factors[i],
+                is_stub: self.is_stub(unadjusted_dates[i], unadjusted_dates[i + 1]),
+            })
+            .collect();
+
+        Ok(Schedule {
+            dates,
+            day_count_factors,
+            day_counting_convention: self.day_counting_convention,
+            date_rolling_convention: self.date_rolling_convention,
+            periods,
+        })
+    }
+
+    /// Generate the unadjusted schedule dates, including the effective
+    /// and termination dates, in chronological order.
+    pub fn unadjusted_dates(&self) -> Vec<Date> {
+        let (start, end) = (self.effective_date, self.termination_date);
+
+        let mut dates = match self.stub_rule {
+            StubRule::ShortFront | StubRule::LongFront => {
+                let mut dates = vec![end];
+                let mut i = 1;
+
+                loop {
+                    let date = self.step(end, -i);
+                    if date <= start {
+                        break;
+                    }
+                    dates.push(date);
+                    i += 1;
+                }
+
+                dates.push(start);
+                dates.reverse();
+                dates
+            }
+            StubRule::ShortBack | StubRule::LongBack => {
+                let mut dates = vec![start];
+                let mut i = 1;
+
+                loop {
+                    let date = self.step(start, i);
+                    if date >= end {
+                        break;
+                    }
+                    dates.push(date);
+                    i += 1;
+                }
+
+                dates.push(end);
+                dates
+            }
+        };
+
+        // Merge a short stub into its neighbouring period for long stubs.
+        let n = dates.len();
+        match self.stub_rule {
+            StubRule::LongFront if n > 2 && self.is_stub(dates[0], dates[1]) => {
+                dates.remove(1);
+            }
+            StubRule::LongBack if n > 2 && self.is_stub(dates[n - 2], dates[n - 1]) => {
+                dates.remove(n - 2);
+            }
+            _ => {}
+        }
+
+        dates
+    }
+
+    /// Checks if the period between two unadjusted dates is irregular,
+    /// i.e. it is not exactly one frequency step long.
+    fn is_stub(&self, start: Date, end: Date) -> bool {
+        match self.stub_rule {
+            StubRule::ShortFront | StubRule::LongFront => self.step(end, -1) != start,
+            StubRule::ShortBack | StubRule::LongBack => self.step(start, 1) != end,
+        }
+    }
+
+    /// Move `n` frequency steps from the anchor date,
+    /// applying the roll convention to the resulting date.
+    fn step(&self, anchor: Date, n: i32) -> Date {
+        let tenor = self.frequency.tenor();
+        let date = anchor + tenor.times(n);
+
+        match tenor.unit {
+            TenorUnit::Days | TenorUnit::Weeks => date,
+            TenorUnit::Months | TenorUnit::Years => match self.roll_convention {
+                RollConvention::None => date,
+                RollConvention::EndOfMonth if is_end_of_month(anchor) => end_of_month(date),
+                RollConvention::EndOfMonth => date,
+                RollConvention::IMM => {
+                    get_third_wednesday_of_month(date.year(), date.month()).unwrap()
+                }
+            },
         }
     }
 }
@@ -291,3 +580,187 @@ impl fmt::Display for Schedule {
 //         );
 //     }
 // }
+
+#[cfg(test)]
+mod tests_schedule_generator {
+    use super::*;
+    use crate::time::countries::oceania::australia::AustraliaCalendar;
+    use time::macros::date;
+
+    fn generator(start: Date, end: Date, stub_rule: StubRule) -> ScheduleGenerator {
+        ScheduleGeneratorBuilder::default()
+            .effective_date(start)
+            .termination_date(end)
+            .frequency(Frequency::Quarterly)
+            .stub_rule(stub_rule)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_regular_schedule() {
+        let schedule = generator(
+            date!(2024 - 01 - 15),
+            date!(2025 - 01 - 15),
+            StubRule::ShortFront,
+        )
+        .unadjusted_dates();
+
+        assert_eq!(
+            schedule,
+            vec![
+                date!(2024 - 01 - 15),
+                date!(2024 - 04 - 15),
+                date!(2024 - 07 - 15),
+                date!(2024 - 10 - 15),
+                date!(2025 - 01 - 15),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stub_rules() {
+        let (start, end) = (date!(2024 - 02 - 01), date!(2025 - 01 - 15));
+
+        assert_eq!(
+            generator(start, end, StubRule::ShortFront).unadjusted_dates()[..2],
+            [start, date!(2024 - 04 - 15)]
+        );
+        assert_eq!(
+            generator(start, end, StubRule::LongFront).unadjusted_dates()[..2],
+            [start, date!(2024 - 07 - 15)]
+        );
+        assert_eq!(
+            generator(start, end, StubRule::ShortBack).unadjusted_dates()[3..],
+            [date!(2024 - 11 - 01), end]
+        );
+        assert_eq!(
+            generator(start, end, StubRule::LongBack).unadjusted_dates()[2..],
+            [date!(2024 - 08 - 01), end]
+        );
+    }
+
+    #[test]
+    fn test_end_of_month_roll() {
+        let schedule = ScheduleGeneratorBuilder::default()
+            .effective_date(date!(2024 - 02 - 29))
+            .termination_date(date!(2025 - 02 - 28))
+            .frequency(Frequency::Quarterly)
+            .roll_convention(RollConvention::EndOfMonth)
+            .stub_rule(StubRule::ShortBack)
+            .build()
+            .unwrap()
+            .unadjusted_dates();
+
+        assert_eq!(
+            schedule,
+            vec![
+                date!(2024 - 02 - 29),
+                date!(2024 - 05 - 31),
+                date!(2024 - 08 - 31),
+                date!(2024 - 11 - 30),
+                date!(2025 - 02 - 28),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_imm_roll() {
+        let schedule = ScheduleGeneratorBuilder::default()
+            .effective_date(date!(2024 - 03 - 20))
+            .termination_date(date!(2024 - 12 - 18))
+            .frequency(Frequency::Quarterly)
+            .roll_convention(RollConvention::IMM)
+            .stub_rule(StubRule::ShortBack)
+            .build()
+            .unwrap()
+            .unadjusted_dates();
+
+        assert_eq!(
+            schedule,
+            vec![
+                date!(2024 - 03 - 20),
+                date!(2024 - 06 - 19),
+                date!(2024 - 09 - 18),
+                date!(2024 - 12 - 18),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generate_accrual_periods() {
+        let schedule = ScheduleGeneratorBuilder::default()
+            .effective_date(date!(2024 - 06 - 30))
+            .termination_date(date!(2025 - 06 - 30))
+            .frequency(Frequency::SemiAnnually)
+            .date_rolling_convention(DateRollingConvention::ModifiedFollowing)
+            .day_counting_convention(DayCountConvention::Actual_365_Fixed)
+            .build()
+            .unwrap()
+            .generate(&AustraliaCalendar)
+            .unwrap();
+
+        // 2024-06-30 is a Sunday, rolled back to Friday 2024-06-28
+        // since rolling forward would change the month.
+        assert_eq!(schedule.dates[0], date!(2024 - 06 - 28));
+        assert_eq!(schedule.periods.len(), 2);
+        assert_eq!(
+            schedule.periods[0].unadjusted_start_date,
+            date!(2024 - 06 - 30)
+        );
+        assert_eq!(schedule.periods[0].payment_date, date!(2024 - 12 - 30));
+        assert!(schedule.periods.iter().all(|period| !period.is_stub));
+        assert_eq!(
+            schedule.periods[1].day_count_factor,
+            DayCountConvention::Actual_365_Fixed
+                .day_count_factor(date!(2024 - 12 - 30), date!(2025 - 06 - 30))
+        );
+    }
+
+    #[test]
+    fn test_generate_invalid_dates() {
+        let generator = ScheduleGeneratorBuilder::default()
+            .effective_date(date!(2025 - 06 - 30))
+            .termination_date(date!(2024 - 06 - 30))
+            .frequency(Frequency::SemiAnnually)
+            .build()
+            .unwrap();
+
+        assert!(generator.generate(&AustraliaCalendar).is_err());
+        assert!(ScheduleGenerator {
+            termination_date: generator.effective_date,
+            ..generator
+        }
+        .generate(&AustraliaCalendar)
+        .is_err());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests_schedule_serde {
+    use super::*;
+    use crate::time::countries::oceania::australia::AustraliaCalendar;
+    use time::macros::date;
+
+    #[test]
+    fn test_schedule_round_trip() {
+        let generator = ScheduleGeneratorBuilder::default()
+            .effective_date(date!(2024 - 01 - 15))
+            .termination_date(date!(2025 - 03 - 15))
+            .frequency(Frequency::Quarterly)
+            .day_counting_convention(DayCountConvention::Actual_360)
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&generator).unwrap();
+        let round_trip: ScheduleGenerator = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_trip.unadjusted_dates(), generator.unadjusted_dates());
+
+        let schedule = generator.generate(&AustraliaCalendar).unwrap();
+        let json = serde_json::to_string(&schedule).unwrap();
+        let round_trip: Schedule = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(round_trip.dates, schedule.dates);
+        assert_eq!(round_trip.periods, schedule.periods);
+    }
+}
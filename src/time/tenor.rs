@@ -0,0 +1,366 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::time::{
+    add_months, Calendar, DateRoller, DateRollingConvention, DayCountConvention, Frequency,
+};
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+use time::{Date, Duration};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Unit of a tenor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TenorUnit {
+    /// Calendar days ("D").
+    Days,
+
+    /// Weeks ("W").
+    Weeks,
+
+    /// Calendar months ("M").
+    Months,
+
+    /// Years ("Y").
+    Years,
+}
+
+/// A tenor is a length of time expressed in market terms,
+/// such as "3M", "10Y" or "ON" (overnight).
+///
+/// Tenors are added to dates using calendar arithmetic: month and year
+/// tenors are clamped to the end of the month (e.g. 31 Jan + 1M = 29 Feb
+/// in a leap year), while day and week tenors add calendar days.
+///
+/// ```
+/// use RustQuant::time::Tenor;
+/// use time::macros::date;
+///
+/// let tenor: Tenor = "3M".parse().unwrap();
+///
+/// assert_eq!(date!(2024 - 01 - 31) + tenor, date!(2024 - 04 - 30));
+/// assert_eq!(tenor.to_string(), "3M");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tenor {
+    /// Number of units (may be negative).
+    pub length: i32,
+
+    /// Unit of the tenor.
+    pub unit: TenorUnit,
+}
+
+/// Compatibility layer between bare `f64` times (year fractions),
+/// `Date`s, and `Tenor`s.
+///
+/// Only the date-based instruments (those priced through `Instrument`)
+/// work in dates: most pricers still take a time to expiry as an `f64`,
+/// which callers convert with this trait, e.g.
+/// `expiry_date.year_fraction(today)` or `Tenor::years(2).year_fraction(today)`.
+/// Dates and tenors use the default day count convention, the same one
+/// used by the curves and the date-based option pricers.
+pub trait YearFraction {
+    /// Year fraction between the `valuation_date` and `self`.
+    fn year_fraction(&self, valuation_date: Date) -> f64;
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Tenor {
+    /// Create a new tenor.
+    #[must_use]
+    pub const fn new(length: i32, unit: TenorUnit) -> Self {
+        Self { length, unit }
+    }
+
+    /// Tenor of `n` days.
+    #[must_use]
+    pub const fn days(n: i32) -> Self {
+        Self::new(n, TenorUnit::Days)
+    }
+
+    /// Tenor of `n` weeks.
+    #[must_use]
+    pub const fn weeks(n: i32) -> Self {
+        Self::new(n, TenorUnit::Weeks)
+    }
+
+    /// Tenor of `n` months.
+    #[must_use]
+    pub const fn months(n: i32) -> Self {
+        Self::new(n, TenorUnit::Months)
+    }
+
+    /// Tenor of `n` years.
+    #[must_use]
+    pub const fn years(n: i32) -> Self {
+        Self::new(n, TenorUnit::Years)
+    }
+
+    /// Adds the tenor to a date (unadjusted).
+    #[must_use]
+    pub fn add_to(&self, date: Date) -> Date {
+        match self.unit {
+            TenorUnit::Days => date + Duration::days(self.length as i64),
+            TenorUnit::Weeks => date + Duration::weeks(self.length as i64),
+            TenorUnit::Months => add_months(date, self.length),
+            TenorUnit::Years => add_months(date, 12 * self.length),
+        }
+    }
+
+    /// Adds the tenor to a date and rolls the result to a business day
+    /// of the given calendar.
+    #[must_use]
+    pub fn add_to_adjusted<C: Calendar>(
+        &self,
+        date: Date,
+        calendar: &C,
+        convention: &DateRollingConvention,
+    ) -> Date {
+        calendar.roll_date(self.add_to(date), convention)
+    }
+
+    /// Multiplies the tenor length by `n` (e.g. 3M * 4 = 12M).
+    #[must_use]
+    pub const fn times(&self, n: i32) -> Self {
+        Self::new(self.length * n, self.unit)
+    }
+
+    /// Approximate length of the tenor in years, independent of any date
+    /// (365 days, 52 weeks, or 12 months per year).
+    #[must_use]
+    pub fn approximate_years(&self) -> f64 {
+        match self.unit {
+            TenorUnit::Days => self.length as f64 / 365.0,
+            TenorUnit::Weeks => self.length as f64 / 52.0,
+            TenorUnit::Months => self.length as f64 / 12.0,
+            TenorUnit::Years => self.length as f64,
+        }
+    }
+
+    /// Year fraction of the tenor starting at `date`,
+    /// under the given day count convention.
+    #[must_use]
+    pub fn day_count_factor(&self, date: Date, convention: &DayCountConvention) -> f64 {
+        convention.day_count_factor(date, self.add_to(date))
+    }
+}
+
+impl Frequency {
+    /// The tenor between two consecutive payments at this frequency.
+    ///
+    /// Note: `Daily` is one calendar day (the 252 per year refers to
+    /// business days), and the frequencies that are not a whole number of
+    /// months apart are approximated in days: `SemiMonthly` by 15 days and
+    /// `SemiQuarterly` by 46 days (365 / 8).
+    #[must_use]
+    pub fn tenor(&self) -> Tenor {
+        match self {
+            Frequency::Daily => Tenor::days(1),
+            Frequency::Weekly => Tenor::weeks(1),
+            Frequency::BiWeekly => Tenor::weeks(2),
+            Frequency::SemiMonthly => Tenor::days(15),
+            Frequency::Monthly => Tenor::months(1),
+            Frequency::SemiQuarterly => Tenor::days(46),
+            Frequency::Quarterly => Tenor::months(3),
+            Frequency::TriAnnually => Tenor::months(4),
+            Frequency::SemiAnnually => Tenor::months(6),
+            Frequency::Annually => Tenor::months(12),
+        }
+    }
+}
+
+impl FromStr for Tenor {
+    type Err = RustQuantError;
+
+    /// Parse a tenor from a market string such as "3M", "10Y", "1W",
+    /// "2D", "ON" (overnight, 1D), "TN" (tom-next, 2D), or "SN" (spot-next, 3D).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_uppercase();
+
+        match s.as_str() {
+            "ON" => return Ok(Tenor::days(1)),
+            "TN" => return Ok(Tenor::days(2)),
+            "SN" => return Ok(Tenor::days(3)),
+            _ => {}
+        }
+
+        let invalid = || RustQuantError::InvalidArgument(format!("Invalid tenor: {s:?}"));
+
+        let unit = match s.chars().last().ok_or_else(invalid)? {
+            'D' => TenorUnit::Days,
+            'W' => TenorUnit::Weeks,
+            'M' => TenorUnit::Months,
+            'Y' => TenorUnit::Years,
+            _ => return Err(invalid()),
+        };
+
+        let length = s[..s.len() - 1].parse::<i32>().map_err(|_| invalid())?;
+
+        Ok(Tenor::new(length, unit))
+    }
+}
+
+impl fmt::Display for Tenor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.unit {
+            TenorUnit::Days => "D",
+            TenorUnit::Weeks => "W",
+            TenorUnit::Months => "M",
+            TenorUnit::Years => "Y",
+        };
+
+        write!(f, "{}{}", self.length, unit)
+    }
+}
+
+impl Neg for Tenor {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.length, self.unit)
+    }
+}
+
+impl Add<Tenor> for Date {
+    type Output = Date;
+
+    fn add(self, tenor: Tenor) -> Self::Output {
+        tenor.add_to(self)
+    }
+}
+
+impl Sub<Tenor> for Date {
+    type Output = Date;
+
+    fn sub(self, tenor: Tenor) -> Self::Output {
+        (-tenor).add_to(self)
+    }
+}
+
+impl YearFraction for f64 {
+    fn year_fraction(&self, _valuation_date: Date) -> f64 {
+        *self
+    }
+}
+
+impl YearFraction for Date {
+    fn year_fraction(&self, valuation_date: Date) -> f64 {
+        DayCountConvention::default().day_count_factor(valuation_date, *self)
+    }
+}
+
+impl YearFraction for Tenor {
+    fn year_fraction(&self, valuation_date: Date) -> f64 {
+        self.add_to(valuation_date).year_fraction(valuation_date)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_tenor {
+    use super::*;
+    use crate::time::countries::oceania::australia::AustraliaCalendar;
+    use time::macros::date;
+
+    const EPS: f64 = f64::EPSILON;
+
+    #[test]
+    fn test_tenor_parsing() {
+        assert_eq!("3M".parse::<Tenor>().unwrap(), Tenor::months(3));
+        assert_eq!("10Y".parse::<Tenor>().unwrap(), Tenor::years(10));
+        assert_eq!("1w".parse::<Tenor>().unwrap(), Tenor::weeks(1));
+        assert_eq!("ON".parse::<Tenor>().unwrap(), Tenor::days(1));
+        assert_eq!("TN".parse::<Tenor>().unwrap(), Tenor::days(2));
+
+        assert!("".parse::<Tenor>().is_err());
+        assert!("M".parse::<Tenor>().is_err());
+        assert!("3X".parse::<Tenor>().is_err());
+    }
+
+    #[test]
+    fn test_tenor_display() {
+        for s in ["1D", "2W", "6M", "30Y"] {
+            assert_eq!(s.parse::<Tenor>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_tenor_date_arithmetic() {
+        let date = date!(2024 - 01 - 31);
+
+        assert_eq!(date + Tenor::days(1), date!(2024 - 02 - 01));
+        assert_eq!(date + Tenor::weeks(2), date!(2024 - 02 - 14));
+        assert_eq!(date + Tenor::months(1), date!(2024 - 02 - 29));
+        assert_eq!(date + Tenor::years(1), date!(2025 - 01 - 31));
+        assert_eq!(date - Tenor::months(2), date!(2023 - 11 - 30));
+    }
+
+    #[test]
+    fn test_tenor_adjusted() {
+        // 2024-03-30 is a Saturday.
+        let adjusted = Tenor::months(2).add_to_adjusted(
+            date!(2024 - 01 - 30),
+            &AustraliaCalendar,
+            &DateRollingConvention::Following,
+        );
+
+        assert!(AustraliaCalendar.is_business_day(adjusted));
+        assert!(adjusted > date!(2024 - 03 - 30));
+    }
+
+    #[test]
+    fn test_frequency_tenor() {
+        assert_eq!(Frequency::Quarterly.tenor(), Tenor::months(3));
+        assert_eq!(Frequency::Annually.tenor(), Tenor::months(12));
+        assert_eq!(Frequency::BiWeekly.tenor(), Tenor::weeks(2));
+        assert_eq!(Frequency::SemiMonthly.tenor(), Tenor::days(15));
+        assert_eq!(Frequency::SemiQuarterly.tenor(), Tenor::days(46));
+        assert_eq!(Frequency::TriAnnually.tenor(), Tenor::months(4));
+
+        // Semi-quarterly is eight times a year.
+        assert_eq!(Frequency::SemiQuarterly.times_in_year(), 8);
+
+        // Whole-month tenors add up to a year.
+        for frequency in [
+            Frequency::Monthly,
+            Frequency::Quarterly,
+            Frequency::TriAnnually,
+            Frequency::SemiAnnually,
+            Frequency::Annually,
+        ] {
+            assert_eq!(
+                frequency.tenor(),
+                Tenor::months(12 / frequency.times_in_year() as i32)
+            );
+        }
+    }
+
+    #[test]
+    fn test_year_fraction_compatibility() {
+        let today = date!(2023 - 01 - 01);
+
+        assert_approx_equal!(0.5_f64.year_fraction(today), 0.5, EPS);
+        assert_approx_equal!(date!(2024 - 01 - 01).year_fraction(today), 1.0, EPS);
+        assert_approx_equal!(Tenor::years(1).year_fraction(today), 1.0, EPS);
+        assert_approx_equal!(Tenor::months(6).approximate_years(), 0.5, EPS);
+    }
+}
@@ -335,6 +335,46 @@ pub fn get_last_sunday_of_month(year: i32, month: Month) -> Result<Date, Error>
     }
 }
 
+/// Function to get the date of the third Wednesday of the month.
+/// This is the standard IMM date for the month.
+pub fn get_third_wednesday_of_month(year: i32, month: Month) -> Result<Date, Error> {
+    Ok(get_first_wednesday_of_month(year, month)? + Duration::weeks(2))
+}
+
+/// Function to check if the date is the last day of its month.
+pub fn is_end_of_month(date: Date) -> bool {
+    date.day() == days_in_year_month(date.year(), date.month())
+}
+
+/// Function to get the last day of the month of the given date.
+pub fn end_of_month(date: Date) -> Date {
+    let days_in_month = days_in_year_month(date.year(), date.month());
+
+    Date::from_calendar_date(date.year(), date.month(), days_in_month).unwrap()
+}
+
+/// Function to add a (possibly negative) number of months to a date.
+///
+/// If the day of the month does not exist in the target month,
+/// the date is clamped to the last day of the target month.
+///
+/// ```
+/// use time::macros::date;
+/// use RustQuant::time::utilities::add_months;
+///
+/// assert_eq!(add_months(date!(2024 - 01 - 31), 1), date!(2024 - 02 - 29));
+/// assert_eq!(add_months(date!(2024 - 03 - 15), -3), date!(2023 - 12 - 15));
+/// ```
+pub fn add_months(date: Date, months: i32) -> Date {
+    let total_months = date.year() * 12 + (date.month() as i32 - 1) + months;
+
+    let year = total_months.div_euclid(12);
+    let month = Month::try_from((total_months.rem_euclid(12) + 1) as u8).unwrap();
+    let day = date.day().min(days_in_year_month(year, month));
+
+    Date::from_calendar_date(year, month, day).unwrap()
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // UNIT TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -516,4 +556,43 @@ mod test_utilities {
             Date::from_calendar_date(2024, Month::December, 2).unwrap()
         );
     }
+
+    #[test]
+    fn test_get_third_wednesday_of_month() {
+        assert_eq!(
+            get_third_wednesday_of_month(2024, Month::March).unwrap(),
+            Date::from_calendar_date(2024, Month::March, 20).unwrap()
+        );
+        assert_eq!(
+            get_third_wednesday_of_month(2024, Month::May).unwrap(),
+            Date::from_calendar_date(2024, Month::May, 15).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_end_of_month() {
+        let date = Date::from_calendar_date(2024, Month::February, 10).unwrap();
+
+        assert!(!is_end_of_month(date));
+        assert!(is_end_of_month(end_of_month(date)));
+        assert_eq!(end_of_month(date).day(), 29);
+    }
+
+    #[test]
+    fn test_add_months() {
+        let date = Date::from_calendar_date(2023, Month::August, 31).unwrap();
+
+        assert_eq!(
+            add_months(date, 6),
+            Date::from_calendar_date(2024, Month::February, 29).unwrap()
+        );
+        assert_eq!(
+            add_months(date, -12),
+            Date::from_calendar_date(2022, Month::August, 31).unwrap()
+        );
+        assert_eq!(
+            add_months(date, -9),
+            Date::from_calendar_date(2022, Month::November, 30).unwrap()
+        );
+    }
 }
@@ -0,0 +1,459 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Backtesting of trading strategies.
+//!
+//! The [`Backtester`] walks a time series of asset prices. After each bar,
+//! the [`Strategy`] sees the price history so far and returns its target
+//! weights (fractions of equity per asset, negative for short). The
+//! rebalancing trades are executed at the prices of the *next* bar, so a
+//! strategy cannot trade on prices it has not seen yet.
+//!
+//! Each trade pays [`TransactionCosts`] (a fixed fee plus a fraction of the
+//! traded notional) and is executed at a price moved against it by the
+//! [`SlippageModel`]. The result is an equity curve whose returns can be fed
+//! to the [`PerformanceMetrics`](crate::portfolio::PerformanceMetrics) suite.
+//!
+//! ```
+//! use RustQuant::data::TimeSeries;
+//! use RustQuant::portfolio::PerformanceMetrics;
+//! use RustQuant::trading::backtest::*;
+//! use time::{macros::date, Date, Duration};
+//!
+//! // Long when the price is above its 3-bar moving average, flat otherwise.
+//! struct MovingAverage;
+//!
+//! impl Strategy for MovingAverage {
+//!     fn target_weights(&mut self, _date: Date, history: &[Vec<f64>]) -> Vec<f64> {
+//!         if history.len() < 3 {
+//!             return vec![0.0];
+//!         }
+//!         let recent = &history[history.len() - 3..];
+//!         let average = recent.iter().map(|p| p[0]).sum::<f64>() / 3.0;
+//!
+//!         vec![if history[history.len() - 1][0] > average { 1.0 } else { 0.0 }]
+//!     }
+//! }
+//!
+//! let start = date!(2024 - 01 - 01);
+//! let prices = TimeSeries::from_pairs(
+//!     [100.0, 101.0, 103.0, 102.0, 105.0, 107.0, 104.0, 108.0]
+//!         .iter()
+//!         .enumerate()
+//!         .map(|(i, p)| (start + Duration::days(i as i64), vec![*p])),
+//! )
+//! .unwrap();
+//!
+//! let result = Backtester::new(10_000.0)
+//!     .with_costs(TransactionCosts::new(1.0, 0.0005))
+//!     .with_slippage(SlippageModel::Proportional(0.0002))
+//!     .run(&prices, &mut MovingAverage)
+//!     .unwrap();
+//!
+//! assert_eq!(result.equity.len(), 8);
+//! assert!(result.total_costs > 0.0);
+//!
+//! let summary = PerformanceMetrics::new().summary(&result.returns()).unwrap();
+//! assert!(summary.max_drawdown >= 0.0);
+//! ```
+
+use crate::data::{ReturnsType, TimeSeries};
+use crate::error::RustQuantError;
+use crate::risk::SquareRootImpact;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Trading strategy: maps the price history to target portfolio weights.
+pub trait Strategy {
+    /// Target weights (fractions of equity, one per asset) after observing
+    /// the bar at `date`.
+    ///
+    /// `history` holds the prices of all bars so far, the last one being
+    /// the bar at `date`.
+    fn target_weights(&mut self, date: Date, history: &[Vec<f64>]) -> Vec<f64>;
+}
+
+/// Transaction costs: a fixed fee per trade plus a fraction of the traded
+/// notional.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransactionCosts {
+    /// Fee per trade.
+    pub fixed: f64,
+    /// Fee as a fraction of the traded notional.
+    pub proportional: f64,
+}
+
+/// Slippage model: how far from the quoted price trades are executed.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SlippageModel {
+    /// Trades are executed at the quoted price.
+    #[default]
+    None,
+    /// Trades are executed a fixed amount per unit against the trader.
+    FixedSpread(f64),
+    /// Trades are executed a fraction of the price against the trader.
+    Proportional(f64),
+    /// Trades move the price with the square-root market impact model (the
+    /// daily volume in units).
+    SquareRoot(SquareRootImpact),
+}
+
+/// Backtesting engine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backtester {
+    /// Initial capital (cash).
+    pub initial_capital: f64,
+    /// Transaction costs.
+    pub costs: TransactionCosts,
+    /// Slippage model.
+    pub slippage: SlippageModel,
+}
+
+/// Executed trade.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trade {
+    /// Execution date.
+    pub date: Date,
+    /// Index of the asset.
+    pub asset: usize,
+    /// Units traded (negative for sales).
+    pub quantity: f64,
+    /// Execution price (including slippage).
+    pub price: f64,
+    /// Transaction costs paid.
+    pub cost: f64,
+}
+
+/// Result of a backtest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestResult {
+    /// Portfolio value at each bar.
+    pub equity: TimeSeries<f64>,
+    /// Units held in each asset at each bar (after trading).
+    pub holdings: TimeSeries<Vec<f64>>,
+    /// Executed trades.
+    pub trades: Vec<Trade>,
+    /// Total transaction costs paid.
+    pub total_costs: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl TransactionCosts {
+    /// New transaction costs.
+    #[must_use]
+    pub const fn new(fixed: f64, proportional: f64) -> Self {
+        Self {
+            fixed,
+            proportional,
+        }
+    }
+
+    /// Cost of trading `quantity` units at `price`.
+    #[must_use]
+    pub fn cost(&self, quantity: f64, price: f64) -> f64 {
+        if quantity == 0.0 {
+            0.0
+        } else {
+            self.fixed + self.proportional * (quantity * price).abs()
+        }
+    }
+}
+
+impl SlippageModel {
+    /// Execution price of `quantity` units quoted at `price`.
+    #[must_use]
+    pub fn execution_price(&self, quantity: f64, price: f64) -> f64 {
+        let direction = quantity.signum();
+
+        match self {
+            Self::None => price,
+            Self::FixedSpread(spread) => price + direction * spread,
+            Self::Proportional(fraction) => price * (1.0 + direction * fraction),
+            Self::SquareRoot(impact) => impact.execution_price(quantity, price),
+        }
+    }
+}
+
+impl Backtester {
+    /// New backtester without costs or slippage.
+    #[must_use]
+    pub fn new(initial_capital: f64) -> Self {
+        Self {
+            initial_capital,
+            costs: TransactionCosts::default(),
+            slippage: SlippageModel::default(),
+        }
+    }
+
+    /// Set the transaction costs.
+    #[must_use]
+    pub const fn with_costs(mut self, costs: TransactionCosts) -> Self {
+        self.costs = costs;
+        self
+    }
+
+    /// Set the slippage model.
+    #[must_use]
+    pub const fn with_slippage(mut self, slippage: SlippageModel) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    /// Run the strategy over the prices (one value per asset at each date).
+    ///
+    /// # Errors
+    ///
+    /// - If there are fewer than two bars, or the bars do not all have the
+    ///   same (non-zero) number of positive prices.
+    /// - If the strategy returns the wrong number of weights.
+    pub fn run<S: Strategy>(
+        &self,
+        prices: &TimeSeries<Vec<f64>>,
+        strategy: &mut S,
+    ) -> Result<BacktestResult, RustQuantError> {
+        let n_assets = self.validate(prices)?;
+
+        let mut cash = self.initial_capital;
+        let mut holdings = vec![0.0; n_assets];
+        let mut pending: Option<Vec<f64>> = None;
+
+        let mut equity = Vec::with_capacity(prices.len());
+        let mut history = Vec::with_capacity(prices.len());
+        let mut trades = Vec::new();
+        let mut total_costs = 0.0;
+
+        for (t, (date, bar)) in prices.iter().enumerate() {
+            let value = |cash: f64, holdings: &[f64]| {
+                cash + holdings.iter().zip(bar).map(|(h, p)| h * p).sum::<f64>()
+            };
+
+            if let Some(weights) = pending.take() {
+                let before = value(cash, &holdings);
+
+                for (asset, (weight, price)) in weights.iter().zip(bar).enumerate() {
+                    let quantity = weight * before / price - holdings[asset];
+
+                    if quantity == 0.0 {
+                        continue;
+                    }
+
+                    let execution = self.slippage.execution_price(quantity, *price);
+                    let cost = self.costs.cost(quantity, execution);
+
+                    cash -= quantity * execution + cost;
+                    holdings[asset] += quantity;
+                    total_costs += cost;
+
+                    trades.push(Trade {
+                        date,
+                        asset,
+                        quantity,
+                        price: execution,
+                        cost,
+                    });
+                }
+            }
+
+            equity.push(value(cash, &holdings));
+            history.push(holdings.clone());
+
+            if t + 1 < prices.len() {
+                let weights = strategy.target_weights(date, &prices.values()[..=t]);
+
+                if weights.len() != n_assets {
+                    return Err(RustQuantError::InvalidArgument(format!(
+                        "strategy returned {} weights for {n_assets} assets",
+                        weights.len()
+                    )));
+                }
+
+                pending = Some(weights);
+            }
+        }
+
+        let dates = prices.dates().to_vec();
+
+        Ok(BacktestResult {
+            equity: TimeSeries::from((dates.clone(), equity)),
+            holdings: TimeSeries::from((dates, history)),
+            trades,
+            total_costs,
+        })
+    }
+
+    /// Number of assets, after checking the prices.
+    fn validate(&self, prices: &TimeSeries<Vec<f64>>) -> Result<usize, RustQuantError> {
+        if prices.len() < 2 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need at least two bars, got {}",
+                prices.len()
+            )));
+        }
+
+        let n_assets = prices.values()[0].len();
+
+        let valid = n_assets > 0
+            && prices
+                .values()
+                .iter()
+                .all(|bar| bar.len() == n_assets && bar.iter().all(|p| p.is_finite() && *p > 0.0));
+
+        if !valid {
+            return Err(RustQuantError::InvalidArgument(
+                "every bar needs one positive price per asset".to_string(),
+            ));
+        }
+
+        Ok(n_assets)
+    }
+}
+
+impl BacktestResult {
+    /// Simple returns of the equity curve (starting at the second bar).
+    #[must_use]
+    pub fn returns(&self) -> TimeSeries<f64> {
+        self.equity.returns(ReturnsType::Arithmetic)
+    }
+
+    /// Final portfolio value.
+    #[must_use]
+    pub fn final_equity(&self) -> f64 {
+        self.equity.values().last().copied().unwrap_or_default()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_backtest {
+    use super::*;
+    use time::{macros::date, Duration};
+
+    const EPS: f64 = 1e-9;
+
+    struct Constant(Vec<f64>);
+
+    impl Strategy for Constant {
+        fn target_weights(&mut self, _date: Date, _history: &[Vec<f64>]) -> Vec<f64> {
+            self.0.clone()
+        }
+    }
+
+    fn prices(bars: &[&[f64]]) -> TimeSeries<Vec<f64>> {
+        let start = date!(2024 - 01 - 01);
+
+        TimeSeries::from_pairs(
+            bars.iter()
+                .enumerate()
+                .map(|(i, bar)| (start + Duration::days(i as i64), bar.to_vec())),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_buy_and_hold() {
+        let prices = prices(&[&[100.0], &[110.0], &[99.0], &[121.0]]);
+        let result = Backtester::new(1_000.0)
+            .run(&prices, &mut Constant(vec![1.0]))
+            .unwrap();
+
+        // Bought 10 units at the second bar; nothing traded afterwards.
+        assert_eq!(result.trades.len(), 1);
+        assert_approx_equal!(result.trades[0].quantity, 1_000.0 / 110.0, EPS);
+        assert_eq!(result.trades[0].date, date!(2024 - 01 - 02));
+
+        let equity = result.equity.values();
+        assert_approx_equal!(equity[0], 1_000.0, EPS);
+        assert_approx_equal!(equity[1], 1_000.0, EPS);
+        assert_approx_equal!(equity[3], 1_100.0, EPS);
+        assert_approx_equal!(result.final_equity(), 1_100.0, EPS);
+
+        let returns = result.returns();
+        assert_eq!(returns.len(), 3);
+        assert_approx_equal!(returns.values()[1], -0.1, EPS);
+    }
+
+    #[test]
+    fn test_costs_and_slippage() {
+        let prices = prices(&[&[100.0, 50.0], &[100.0, 50.0], &[100.0, 50.0]]);
+        let result = Backtester::new(1_000.0)
+            .with_costs(TransactionCosts::new(1.0, 0.001))
+            .with_slippage(SlippageModel::Proportional(0.01))
+            .run(&prices, &mut Constant(vec![0.5, -0.5]))
+            .unwrap();
+
+        // Buy 5 at 101, sell 10 at 49.5.
+        assert_approx_equal!(result.trades[0].price, 101.0, EPS);
+        assert_approx_equal!(result.trades[1].price, 49.5, EPS);
+
+        let first_costs = 2.0 + 0.001 * (5.0 * 101.0 + 10.0 * 49.5);
+        let slippage = 5.0 + 5.0;
+        assert_approx_equal!(
+            result.equity.values()[1],
+            1_000.0 - first_costs - slippage,
+            EPS
+        );
+
+        // Rebalancing at the third bar trades a little and pays again.
+        assert_eq!(result.trades.len(), 4);
+        assert!(result.total_costs > first_costs);
+    }
+
+    #[test]
+    fn test_fixed_spread() {
+        assert_eq!(
+            SlippageModel::FixedSpread(0.05).execution_price(10.0, 100.0),
+            100.05
+        );
+        assert_eq!(
+            SlippageModel::FixedSpread(0.05).execution_price(-10.0, 100.0),
+            99.95
+        );
+        assert_eq!(SlippageModel::None.execution_price(-10.0, 100.0), 100.0);
+        assert_approx_equal!(
+            SlippageModel::SquareRoot(SquareRootImpact::new(0.5, 0.02, 1_000.0))
+                .execution_price(-10.0, 100.0),
+            99.9,
+            EPS
+        );
+        assert_eq!(TransactionCosts::new(1.0, 0.1).cost(0.0, 100.0), 0.0);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let backtester = Backtester::new(1_000.0);
+
+        assert!(backtester
+            .run(&prices(&[&[100.0]]), &mut Constant(vec![1.0]))
+            .is_err());
+        assert!(backtester
+            .run(
+                &prices(&[&[100.0], &[100.0, 1.0]]),
+                &mut Constant(vec![1.0])
+            )
+            .is_err());
+        assert!(backtester
+            .run(&prices(&[&[100.0], &[-1.0]]), &mut Constant(vec![1.0]))
+            .is_err());
+        assert!(backtester
+            .run(
+                &prices(&[&[100.0], &[100.0]]),
+                &mut Constant(vec![1.0, 0.0])
+            )
+            .is_err());
+    }
+}
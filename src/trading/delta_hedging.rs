@@ -0,0 +1,452 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Delta hedging simulation.
+//!
+//! The [`DeltaHedger`] sells an option at its model price and hedges it
+//! along each path of the underlying: it buys the model delta in the
+//! underlying, rebalances every few steps of the path, finances the hedge
+//! in a cash account at the risk-free rate, and unwinds it at maturity when
+//! the option pays off. Every trade pays [`TransactionCosts`].
+//!
+//! With continuous rebalancing, no costs, and the model volatility equal to
+//! the realised one, the P&L at maturity would be zero. Discrete
+//! rebalancing leaves a hedging error whose standard deviation shrinks as
+//! $1 / \sqrt{N}$ in the number of rebalances $N$, approximately
+//! $\sqrt{\pi / 4} \, \sigma \, \mathcal{V} / \sqrt{N}$ for an option with
+//! vega $\mathcal{V}$ (Derman and Kamal), while the costs grow with $N$.
+//!
+//! The paths can be real prices or simulated, e.g. with
+//! [`geometric_brownian_paths`].
+//!
+//! ```
+//! use RustQuant::instruments::options::TypeFlag;
+//! use RustQuant::trading::delta_hedging::*;
+//!
+//! let model = BlackScholesHedge::new(100.0, 0.2, 0.0, 0.0, TypeFlag::Call);
+//! let paths = geometric_brownian_paths(100.0, 0.05, 0.2, 1.0, 52, 200, 42);
+//!
+//! let report = DeltaHedger::new(1.0, 0.0)
+//!     .run(&model, &paths)
+//!     .unwrap();
+//!
+//! assert_eq!(report.pnl.len(), 200);
+//! assert!(report.mean_pnl().abs() < 0.5);
+//! ```
+
+use super::backtest::TransactionCosts;
+use crate::error::RustQuantError;
+use crate::instruments::options::{generalised_black_scholes_merton, TypeFlag};
+use crate::math::Real;
+use crate::risk::{RiskEstimate, ValueAtRisk, VarMethod};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Model of the hedged option: its price, delta, and payoff.
+pub trait HedgeModel {
+    /// Price of the option at `spot` with `time_to_maturity` years left.
+    fn price(&self, spot: f64, time_to_maturity: f64) -> f64;
+
+    /// Delta of the option at `spot` with `time_to_maturity` years left.
+    fn delta(&self, spot: f64, time_to_maturity: f64) -> f64;
+
+    /// Payoff of the option at maturity.
+    fn payoff(&self, spot: f64) -> f64;
+
+    /// Dividend yield (or foreign rate) earned on the hedge.
+    fn dividend_yield(&self) -> f64 {
+        0.0
+    }
+}
+
+/// European option hedged with Black-Scholes-Merton deltas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlackScholesHedge {
+    /// Strike price.
+    pub strike: f64,
+    /// Volatility used to price and hedge.
+    pub volatility: f64,
+    /// Risk-free rate.
+    pub risk_free_rate: f64,
+    /// Cost of carry $b$ (the dividend yield is $r - b$).
+    pub cost_of_carry: f64,
+    /// Call or put.
+    pub option_type: TypeFlag,
+}
+
+/// Delta hedging simulator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeltaHedger {
+    /// Maturity of the option, in years, spanned by each path.
+    pub maturity: f64,
+    /// Rate earned (or paid) on the cash account.
+    pub risk_free_rate: f64,
+    /// Number of path steps between rebalances.
+    pub rebalance_every: usize,
+    /// Costs of trading the underlying.
+    pub costs: TransactionCosts,
+}
+
+/// Result of a hedging simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgingReport {
+    /// P&L at maturity of the hedged short option on each path.
+    pub pnl: Vec<f64>,
+    /// Transaction costs paid on each path.
+    pub costs: Vec<f64>,
+    /// Number of rebalances per path (excluding the initial trade and the
+    /// unwind).
+    pub rebalances: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BlackScholesHedge {
+    /// New Black-Scholes-Merton hedge model.
+    #[must_use]
+    pub const fn new(
+        strike: f64,
+        volatility: f64,
+        risk_free_rate: f64,
+        cost_of_carry: f64,
+        option_type: TypeFlag,
+    ) -> Self {
+        Self {
+            strike,
+            volatility,
+            risk_free_rate,
+            cost_of_carry,
+            option_type,
+        }
+    }
+}
+
+impl HedgeModel for BlackScholesHedge {
+    fn price(&self, spot: f64, time_to_maturity: f64) -> f64 {
+        match time_to_maturity > 0.0 {
+            true => generalised_black_scholes_merton(
+                spot,
+                self.strike,
+                self.volatility,
+                self.risk_free_rate,
+                self.cost_of_carry,
+                time_to_maturity,
+                self.option_type,
+            ),
+            false => self.payoff(spot),
+        }
+    }
+
+    fn delta(&self, spot: f64, time_to_maturity: f64) -> f64 {
+        let tau = time_to_maturity.max(f64::EPSILON);
+        let d1 = ((spot / self.strike).ln()
+            + (self.cost_of_carry + 0.5 * self.volatility * self.volatility) * tau)
+            / (self.volatility * tau.sqrt());
+        let forward_discount = ((self.cost_of_carry - self.risk_free_rate) * tau).exp();
+
+        match self.option_type {
+            TypeFlag::Call => forward_discount * d1.norm_cdf(),
+            TypeFlag::Put => forward_discount * (d1.norm_cdf() - 1.0),
+        }
+    }
+
+    fn payoff(&self, spot: f64) -> f64 {
+        match self.option_type {
+            TypeFlag::Call => (spot - self.strike).max(0.0),
+            TypeFlag::Put => (self.strike - spot).max(0.0),
+        }
+    }
+
+    fn dividend_yield(&self) -> f64 {
+        self.risk_free_rate - self.cost_of_carry
+    }
+}
+
+impl DeltaHedger {
+    /// New hedger rebalancing at every step of the paths, without costs.
+    #[must_use]
+    pub fn new(maturity: f64, risk_free_rate: f64) -> Self {
+        Self {
+            maturity,
+            risk_free_rate,
+            rebalance_every: 1,
+            costs: TransactionCosts::default(),
+        }
+    }
+
+    /// Rebalance every `steps` steps of the paths.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `steps` is zero.
+    #[must_use]
+    pub fn with_rebalancing(mut self, steps: usize) -> Self {
+        assert!(steps > 0, "rebalancing interval must be positive");
+
+        self.rebalance_every = steps;
+        self
+    }
+
+    /// Set the transaction costs.
+    #[must_use]
+    pub const fn with_costs(mut self, costs: TransactionCosts) -> Self {
+        self.costs = costs;
+        self
+    }
+
+    /// Hedge a short option along each path of the underlying. Each path
+    /// runs from today to maturity on a uniform grid.
+    ///
+    /// # Errors
+    ///
+    /// If the maturity is not positive, there are no paths, the paths have
+    /// different lengths or fewer than two points, or a price is not
+    /// positive.
+    pub fn run<M: HedgeModel>(
+        &self,
+        model: &M,
+        paths: &[Vec<f64>],
+    ) -> Result<HedgingReport, RustQuantError> {
+        let n_points = paths.first().map(Vec::len).unwrap_or_default();
+
+        if self.maturity.is_nan() || self.maturity <= 0.0 || n_points < 2 {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "need a positive maturity and paths of at least two points, got {} and {n_points}",
+                self.maturity
+            )));
+        }
+        if paths
+            .iter()
+            .any(|path| path.len() != n_points || path.iter().any(|s| !(s.is_finite() && *s > 0.0)))
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "paths must have the same length and positive prices".to_string(),
+            ));
+        }
+
+        let n_steps = n_points - 1;
+        let dt = self.maturity / n_steps as f64;
+        let growth = (self.risk_free_rate * dt).exp();
+        let dividend = (model.dividend_yield() * dt).exp() - 1.0;
+
+        let (pnl, costs): (Vec<f64>, Vec<f64>) = paths
+            .iter()
+            .map(|path| {
+                let mut paid = 0.0;
+                let mut trade = |quantity: f64, spot: f64| {
+                    let cost = self.costs.cost(quantity, spot);
+                    paid += cost;
+                    quantity * spot + cost
+                };
+
+                // Sell the option and buy the initial hedge.
+                let mut delta = model.delta(path[0], self.maturity);
+                let mut cash = model.price(path[0], self.maturity) - trade(delta, path[0]);
+
+                for step in 1..=n_steps {
+                    cash = cash * growth + delta * path[step - 1] * dividend;
+
+                    if step < n_steps && step % self.rebalance_every == 0 {
+                        let target = model.delta(path[step], self.maturity - step as f64 * dt);
+                        cash -= trade(target - delta, path[step]);
+                        delta = target;
+                    }
+                }
+
+                // Unwind the hedge and pay the option.
+                let spot = path[n_steps];
+                cash -= trade(-delta, spot);
+
+                (cash - model.payoff(spot), paid)
+            })
+            .unzip();
+
+        Ok(HedgingReport {
+            pnl,
+            costs,
+            rebalances: (n_steps - 1) / self.rebalance_every,
+        })
+    }
+}
+
+impl HedgingReport {
+    /// Mean P&L.
+    #[must_use]
+    pub fn mean_pnl(&self) -> f64 {
+        mean(&self.pnl)
+    }
+
+    /// Standard deviation of the P&L (the hedging error).
+    #[must_use]
+    pub fn std_dev(&self) -> f64 {
+        let mean = self.mean_pnl();
+        let n = self.pnl.len() as f64;
+
+        match self.pnl.len() {
+            0 | 1 => 0.0,
+            _ => (self.pnl.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt(),
+        }
+    }
+
+    /// Mean transaction costs.
+    #[must_use]
+    pub fn mean_cost(&self) -> f64 {
+        mean(&self.costs)
+    }
+
+    /// VaR and ES of the hedged P&L.
+    ///
+    /// # Errors
+    ///
+    /// If the confidence level is not in $(0, 1)$.
+    pub fn risk(&self, confidence: f64) -> Result<RiskEstimate, RustQuantError> {
+        ValueAtRisk::new(confidence, VarMethod::Historical).compute(&self.pnl)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Paths of geometric Brownian motion with `n_steps` steps to `maturity`
+/// (each path has `n_steps + 1` points, starting at `spot`).
+#[must_use]
+pub fn geometric_brownian_paths(
+    spot: f64,
+    drift: f64,
+    volatility: f64,
+    maturity: f64,
+    n_steps: usize,
+    n_paths: usize,
+    seed: u64,
+) -> Vec<Vec<f64>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let dt = maturity / n_steps.max(1) as f64;
+    let (mean, std_dev) = (
+        (drift - 0.5 * volatility * volatility) * dt,
+        volatility * dt.sqrt(),
+    );
+
+    (0..n_paths)
+        .map(|_| {
+            let mut path = Vec::with_capacity(n_steps + 1);
+            let mut s = spot;
+            path.push(s);
+
+            for _ in 0..n_steps {
+                let z: f64 = StandardNormal.sample(&mut rng);
+                s *= (mean + std_dev * z).exp();
+                path.push(s);
+            }
+            path
+        })
+        .collect()
+}
+
+/// Mean of a vector (zero if empty).
+fn mean(values: &[f64]) -> f64 {
+    match values.len() {
+        0 => 0.0,
+        n => values.iter().sum::<f64>() / n as f64,
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_delta_hedging {
+    use super::*;
+
+    const CALL: BlackScholesHedge = BlackScholesHedge::new(100.0, 0.2, 0.0, 0.0, TypeFlag::Call);
+
+    #[test]
+    fn test_hedging_error_shrinks_with_frequency() {
+        let paths = geometric_brownian_paths(100.0, 0.05, 0.2, 1.0, 252, 2_000, 1);
+
+        let daily = DeltaHedger::new(1.0, 0.0).run(&CALL, &paths).unwrap();
+        let weekly = DeltaHedger::new(1.0, 0.0)
+            .with_rebalancing(5)
+            .run(&CALL, &paths)
+            .unwrap();
+
+        assert_eq!(daily.rebalances, 251);
+        assert_eq!(weekly.rebalances, 50);
+
+        // Derman-Kamal: sqrt(pi / 4) * sigma * vega / sqrt(N).
+        let vega = 100.0 * (-0.5 * 0.01_f64).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let expected = |n: f64| (std::f64::consts::PI / 4.0).sqrt() * 0.2 * vega / n.sqrt();
+
+        assert_approx_equal!(daily.std_dev(), expected(252.0), 0.15 * expected(252.0));
+        assert_approx_equal!(weekly.std_dev(), expected(51.0), 0.15 * expected(51.0));
+        assert!(daily.mean_pnl().abs() < 0.05);
+        assert!(weekly.mean_pnl().abs() < 0.1);
+
+        let risk = daily.risk(0.99).unwrap();
+        assert!(risk.value_at_risk > 0.0 && risk.value_at_risk < 4.0 * daily.std_dev());
+    }
+
+    #[test]
+    fn test_transaction_costs() {
+        let paths = geometric_brownian_paths(100.0, 0.0, 0.2, 0.5, 126, 500, 2);
+        let costs = TransactionCosts::new(0.0, 0.001);
+        let hedger = DeltaHedger::new(0.5, 0.0).with_costs(costs);
+
+        let free = DeltaHedger::new(0.5, 0.0).run(&CALL, &paths).unwrap();
+        let daily = hedger.run(&CALL, &paths).unwrap();
+        let weekly = hedger.with_rebalancing(5).run(&CALL, &paths).unwrap();
+
+        // Costs come straight out of the P&L, and grow with the frequency.
+        assert!(daily.mean_cost() > weekly.mean_cost());
+        assert!(weekly.mean_cost() > 0.0);
+        assert_approx_equal!(daily.mean_pnl(), free.mean_pnl() - daily.mean_cost(), 1e-9);
+        assert_eq!(free.mean_cost(), 0.0);
+    }
+
+    #[test]
+    fn test_deterministic_paths() {
+        // A put hedged along a flat path: the option expires worthless after
+        // the premium decays, with rates and dividends in the carry.
+        let put = BlackScholesHedge::new(100.0, 0.2, 0.03, 0.01, TypeFlag::Put);
+        assert_approx_equal!(put.dividend_yield(), 0.02, 1e-15);
+        assert_approx_equal!(
+            put.delta(100.0, 1.0)
+                - BlackScholesHedge {
+                    option_type: TypeFlag::Call,
+                    ..put
+                }
+                .delta(100.0, 1.0),
+            -(-0.02_f64).exp(),
+            1e-12
+        );
+
+        let report = DeltaHedger::new(1.0, 0.03)
+            .run(&put, &[vec![100.0; 3], vec![100.0; 3]])
+            .unwrap();
+        assert_eq!(report.pnl.len(), 2);
+        assert_approx_equal!(report.pnl[0], report.pnl[1], 1e-12);
+        assert_approx_equal!(report.std_dev(), 0.0, 1e-12);
+
+        let hedger = DeltaHedger::new(1.0, 0.0);
+        assert!(hedger.run(&put, &[]).is_err());
+        assert!(hedger.run(&put, &[vec![100.0]]).is_err());
+        assert!(hedger.run(&put, &[vec![100.0, 90.0], vec![100.0]]).is_err());
+        assert!(hedger.run(&put, &[vec![100.0, -1.0]]).is_err());
+        assert!(DeltaHedger::new(0.0, 0.0)
+            .run(&put, &[vec![100.0, 90.0]])
+            .is_err());
+    }
+}
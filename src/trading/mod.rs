@@ -9,6 +9,12 @@
 
 //! Trading related items.
 
+/// Backtesting engine for trading strategies.
+pub mod backtest;
+
+/// Delta hedging simulation.
+pub mod delta_hedging;
+
 /// Contains limit order book implementation
 pub mod limit_order_book;
 
@@ -0,0 +1,421 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Golden-value regression tests.
+//!
+//! Reference prices, from published tables or evaluated independently, live
+//! in `tests/data/golden/*.json`:
+//!
+//! ```json
+//! {
+//!   "source": "where the values come from",
+//!   "tolerance": 1e-4,
+//!   "cases": [
+//!     { "name": "...", "expected": 9.0246, "tolerance": 5e-3, ...inputs }
+//!   ]
+//! }
+//! ```
+//!
+//! `tolerance` is an absolute tolerance; a case may override the file's
+//! default (e.g. when the source only quotes two decimals). Every case is
+//! priced, and all mismatches are reported together.
+//!
+//! Pricers that work in dates take `evaluation_date` and `expiration_date`
+//! fields (`YYYY-MM-DD`), so the cases do not depend on today's date.
+
+use serde_json::Value;
+use std::path::Path;
+use time::{Date, Month};
+use RustQuant::error::RustQuantError;
+use RustQuant::instruments::options::finite_difference_pricer::FiniteDifferencePricer;
+use RustQuant::instruments::options::{
+    bachelier_price, generalised_black_scholes_merton, AsianOption, BarrierOption, BarrierType,
+    CashOrNothingOption, ExerciseFlag, GapOption, HestonOption, LookbackOption, LookbackStrike,
+    Merton1976, TypeFlag,
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// HARNESS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A single reference case.
+struct Case {
+    name: String,
+    expected: f64,
+    tolerance: f64,
+    fields: Value,
+}
+
+impl Case {
+    /// Numeric input field.
+    fn num(&self, key: &str) -> Result<f64, RustQuantError> {
+        self.fields[key]
+            .as_f64()
+            .ok_or_else(|| RustQuantError::MissingInput(format!("{}: `{key}`", self.name)))
+    }
+
+    /// String input field.
+    fn str(&self, key: &str) -> Result<&str, RustQuantError> {
+        self.fields[key]
+            .as_str()
+            .ok_or_else(|| RustQuantError::MissingInput(format!("{}: `{key}`", self.name)))
+    }
+
+    /// Integer input field.
+    fn int(&self, key: &str) -> Result<u32, RustQuantError> {
+        self.fields[key]
+            .as_u64()
+            .and_then(|value| u32::try_from(value).ok())
+            .ok_or_else(|| RustQuantError::MissingInput(format!("{}: `{key}`", self.name)))
+    }
+
+    /// Date input field, `YYYY-MM-DD`.
+    fn date(&self, key: &str) -> Result<Date, RustQuantError> {
+        let value = self.str(key)?;
+        let invalid = || {
+            RustQuantError::InvalidArgument(format!(
+                "{}: `{key}` is not a YYYY-MM-DD date: {value:?}",
+                self.name
+            ))
+        };
+
+        let mut parts = value.splitn(3, '-').map(str::parse::<i32>);
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        let month = u8::try_from(month)
+            .ok()
+            .and_then(|month| Month::try_from(month).ok())
+            .ok_or_else(invalid)?;
+        let day = u8::try_from(day).map_err(|_| invalid())?;
+
+        Date::from_calendar_date(year, month, day).map_err(|_| invalid())
+    }
+
+    /// `"call"` or `"put"` from the `option_type` field.
+    fn type_flag(&self) -> Result<TypeFlag, RustQuantError> {
+        match self.str("option_type")? {
+            "call" => Ok(TypeFlag::Call),
+            "put" => Ok(TypeFlag::Put),
+            other => Err(RustQuantError::InvalidArgument(format!(
+                "{}: unknown option type `{other}`",
+                self.name
+            ))),
+        }
+    }
+}
+
+/// Load the cases of a golden file, applying the file's default tolerance.
+fn load(file: &str) -> Vec<Case> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/data/golden")
+        .join(file);
+
+    let text = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()));
+    let doc: Value = serde_json::from_str(&text)
+        .unwrap_or_else(|e| panic!("cannot parse {}: {e}", path.display()));
+
+    let default_tolerance = doc["tolerance"]
+        .as_f64()
+        .unwrap_or_else(|| panic!("{file}: missing default `tolerance`"));
+
+    let cases = doc["cases"]
+        .as_array()
+        .unwrap_or_else(|| panic!("{file}: missing `cases`"));
+
+    assert!(!cases.is_empty(), "{file}: no cases");
+
+    cases
+        .iter()
+        .enumerate()
+        .map(|(i, case)| {
+            let name = case["name"]
+                .as_str()
+                .map_or_else(|| format!("#{i}"), str::to_string);
+
+            Case {
+                expected: case["expected"]
+                    .as_f64()
+                    .unwrap_or_else(|| panic!("{file}: {name}: missing `expected`")),
+                tolerance: case["tolerance"].as_f64().unwrap_or(default_tolerance),
+                fields: case.clone(),
+                name,
+            }
+        })
+        .collect()
+}
+
+/// Price every case in `file` with `pricer` and panic with a list of all
+/// cases that error or miss their expected value.
+fn check<F>(file: &str, pricer: F)
+where
+    F: Fn(&Case) -> Result<f64, RustQuantError>,
+{
+    let cases = load(file);
+
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|case| match pricer(case) {
+            Ok(price) if (price - case.expected).abs() <= case.tolerance => None,
+            Ok(price) => Some(format!(
+                "{}: expected {}, got {price} (diff {:e}, tolerance {:e})",
+                case.name,
+                case.expected,
+                (price - case.expected).abs(),
+                case.tolerance
+            )),
+            Err(e) => Some(format!("{}: {e}", case.name)),
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{file}: {} of {} cases failed:\n  {}",
+        failures.len(),
+        cases.len(),
+        failures.join("\n  ")
+    );
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// PRICERS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[test]
+fn golden_barrier() {
+    check("barrier.json", |case| {
+        let barrier_type = match case.str("barrier_type")? {
+            "CUI" => BarrierType::CUI,
+            "CDI" => BarrierType::CDI,
+            "CUO" => BarrierType::CUO,
+            "CDO" => BarrierType::CDO,
+            "PUI" => BarrierType::PUI,
+            "PDI" => BarrierType::PDI,
+            "PUO" => BarrierType::PUO,
+            "PDO" => BarrierType::PDO,
+            other => {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "unknown barrier type `{other}`"
+                )))
+            }
+        };
+
+        BarrierOption::builder()
+            .spot(case.num("spot")?)
+            .strike(case.num("strike")?)
+            .barrier(case.num("barrier")?)
+            .time_to_expiry(case.num("time_to_expiry")?)
+            .risk_free_rate(case.num("risk_free_rate")?)
+            .volatility(case.num("volatility")?)
+            .dividend_yield(case.num("dividend_yield")?)
+            .rebate(case.num("rebate")?)
+            .build()?
+            .price(barrier_type)
+    });
+}
+
+#[test]
+fn golden_black_scholes_merton() {
+    check("black_scholes_merton.json", |case| {
+        Ok(generalised_black_scholes_merton(
+            case.num("spot")?,
+            case.num("strike")?,
+            case.num("volatility")?,
+            case.num("risk_free_rate")?,
+            case.num("cost_of_carry")?,
+            case.num("time_to_expiry")?,
+            case.type_flag()?,
+        ))
+    });
+}
+
+#[test]
+fn golden_bachelier() {
+    check("bachelier.json", |case| {
+        Ok(bachelier_price(
+            case.num("spot")?,
+            case.num("strike")?,
+            case.num("volatility")?,
+            case.num("time_to_expiry")?,
+            case.type_flag()?,
+        ))
+    });
+}
+
+#[test]
+fn golden_binary() {
+    check("binary.json", |case| {
+        let (call, put) = match case.str("kind")? {
+            "gap" => GapOption {
+                initial_price: case.num("spot")?,
+                strike_1: case.num("strike_1")?,
+                strike_2: case.num("strike_2")?,
+                risk_free_rate: case.num("risk_free_rate")?,
+                volatility: case.num("volatility")?,
+                cost_of_carry: case.num("cost_of_carry")?,
+                time_to_maturity: case.num("time_to_expiry")?,
+            }
+            .price(),
+            "cash_or_nothing" => CashOrNothingOption {
+                initial_price: case.num("spot")?,
+                strike_price: case.num("strike")?,
+                payout_value: case.num("payout")?,
+                risk_free_rate: case.num("risk_free_rate")?,
+                volatility: case.num("volatility")?,
+                cost_of_carry: case.num("cost_of_carry")?,
+                time_to_maturity: case.num("time_to_expiry")?,
+            }
+            .price(),
+            other => {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "unknown binary option `{other}`"
+                )))
+            }
+        };
+
+        match case.type_flag()? {
+            TypeFlag::Call => Ok(call),
+            TypeFlag::Put => Ok(put),
+        }
+    });
+}
+
+#[test]
+fn golden_asian() {
+    check("asian.json", |case| {
+        let (call, put) = AsianOption {
+            initial_price: case.num("spot")?,
+            strike_price: case.num("strike")?,
+            risk_free_rate: case.num("risk_free_rate")?,
+            volatility: case.num("volatility")?,
+            dividend_rate: case.num("dividend_yield")?,
+            evaluation_date: Some(case.date("evaluation_date")?),
+            expiration_date: case.date("expiration_date")?,
+        }
+        .price_geometric_average();
+
+        match case.type_flag()? {
+            TypeFlag::Call => Ok(call),
+            TypeFlag::Put => Ok(put),
+        }
+    });
+}
+
+#[test]
+fn golden_lookback() {
+    check("lookback.json", |case| {
+        let (strike_price, strike_type) = match case.str("strike_type")? {
+            "fixed" => (Some(case.num("strike")?), LookbackStrike::Fixed),
+            "floating" => (None, LookbackStrike::Floating),
+            other => {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "unknown strike type `{other}`"
+                )))
+            }
+        };
+
+        let (call, put) = LookbackOption {
+            initial_price: case.num("spot")?,
+            risk_free_rate: case.num("risk_free_rate")?,
+            strike_price,
+            volatility: case.num("volatility")?,
+            time_to_maturity: case.num("time_to_expiry")?,
+            dividend_yield: case.num("dividend_yield")?,
+            s_min: case.num("s_min")?,
+            s_max: case.num("s_max")?,
+            strike_type,
+        }
+        .price_analytic();
+
+        match case.type_flag()? {
+            TypeFlag::Call => Ok(call),
+            TypeFlag::Put => Ok(put),
+        }
+    });
+}
+
+#[test]
+fn golden_heston() {
+    check("heston.json", |case| {
+        Ok(HestonOption {
+            initial_price: case.num("spot")?,
+            initial_variance: case.num("initial_variance")?,
+            strike_price: case.num("strike")?,
+            risk_free_rate: case.num("risk_free_rate")?,
+            dividend_yield: case.num("dividend_yield")?,
+            correlation: case.num("correlation")?,
+            volatility_of_volatility: case.num("volatility_of_volatility")?,
+            mean_reversion_rate: case.num("mean_reversion_rate")?,
+            long_run_variance: case.num("long_run_variance")?,
+            evaluation_date: Some(case.date("evaluation_date")?),
+            expiration_date: case.date("expiration_date")?,
+            type_flag: case.type_flag()?,
+        }
+        .price())
+    });
+}
+
+#[test]
+fn golden_merton_jump_diffusion() {
+    check("merton_jump_diffusion.json", |case| {
+        Ok(Merton1976 {
+            underlying_price: case.num("spot")?,
+            strike_price: case.num("strike")?,
+            risk_free_rate: case.num("risk_free_rate")?,
+            volatility: case.num("volatility")?,
+            lambda: case.num("lambda")?,
+            gamma: case.num("gamma")?,
+            type_flag: case.type_flag()?,
+            evaluation_date: Some(case.date("evaluation_date")?),
+            expiration_date: case.date("expiration_date")?,
+        }
+        .price())
+    });
+}
+
+#[test]
+fn golden_finite_difference() {
+    check("finite_difference.json", |case| {
+        let exercise_flag = match case.str("exercise")? {
+            "european" => ExerciseFlag::European,
+            "american" => ExerciseFlag::American,
+            other => {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "unknown exercise style `{other}`"
+                )))
+            }
+        };
+
+        let pricer = FiniteDifferencePricer::new(
+            case.num("spot")?,
+            case.num("strike")?,
+            case.num("risk_free_rate")?,
+            case.num("volatility")?,
+            Some(case.date("evaluation_date")?),
+            case.date("expiration_date")?,
+            case.int("time_steps")?,
+            case.int("price_steps")?,
+            case.type_flag()?,
+            exercise_flag,
+        )?;
+
+        match case.str("method")? {
+            "explicit" => Ok(pricer.explicit()),
+            "implicit" => Ok(pricer.implicit()),
+            "crank_nicolson" => Ok(pricer.crank_nicolson()),
+            other => Err(RustQuantError::InvalidArgument(format!(
+                "unknown finite difference method `{other}`"
+            ))),
+        }
+    });
+}